@@ -4,11 +4,14 @@
 
 use std::env;
 use std::path::PathBuf;
+use std::sync::mpsc;
+use std::thread;
 use std::time::Duration;
 
+use bytesize::ByteSize;
 use remotefs_ssh::SshKeyStorage as SshKeyStorageTrait;
 
-use crate::cli::{Remote, RemoteArgs};
+use crate::cli::{ExternalPassword, Remote, RemoteArgs, TransferTask};
 use crate::filetransfer::{
     FileTransferParams, FileTransferProtocol, HostBridgeParams, ProtocolParams,
 };
@@ -16,11 +19,17 @@ use crate::host::HostError;
 use crate::system::bookmarks_client::BookmarksClient;
 use crate::system::config_client::ConfigClient;
 use crate::system::environment;
+use crate::system::netrc::{self, Netrc};
+#[cfg(posix)]
+use crate::system::signals::{self, SuspendListener};
 use crate::system::sshkey_storage::SshKeyStorage;
-use crate::system::theme_provider::ThemeProvider;
+use crate::system::theme_provider::{Palette, ThemeProvider};
 use crate::ui::activities::auth::AuthActivity;
-use crate::ui::activities::filetransfer::FileTransferActivity;
-use crate::ui::activities::setup::SetupActivity;
+use crate::ui::activities::filetransfer::{
+    remote_modes, FileTransferActivity, QueueEvent, QueueWorker, RemoteSession, TransferDirection,
+    TransferQueue,
+};
+use crate::ui::activities::setup::{SetupActivity, ViewLayout};
 use crate::ui::activities::{Activity, ExitReason};
 use crate::ui::context::Context;
 use crate::utils::{fmt, tty};
@@ -29,7 +38,8 @@ use crate::utils::{fmt, tty};
 pub enum NextActivity {
     Authentication,
     FileTransfer,
-    SetupActivity,
+    /// Setup activity, opened on the provided tab
+    SetupActivity(ViewLayout),
 }
 
 pub enum Host {
@@ -42,18 +52,34 @@ pub enum HostParams {
     Remote(FileTransferParams),
 }
 
+/// Outcome of a transfer run without the user interface
+#[derive(Debug, Default)]
+pub struct TransferReport {
+    pub transferred: usize,
+    pub unchanged: usize,
+    /// Errors of the files which couldn't be transferred
+    pub errors: Vec<String>,
+}
+
+/// Interval between the polls of the transfer queue, when transferring without the user interface
+const TRANSFER_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
 /// The activity manager takes care of running activities and handling them until the application has ended
 pub struct ActivityManager {
     context: Option<Context>,
     ticks: Duration,
+    /// password of the remotes without a password in the arguments
+    external_password: ExternalPassword,
+    #[cfg(posix)]
+    suspend_listener: Option<SuspendListener>,
 }
 
 impl ActivityManager {
     /// Initializes a new Activity Manager
-    pub fn new(ticks: Duration) -> Result<ActivityManager, HostError> {
+    pub fn new(ticks: Duration, palette: Palette) -> Result<ActivityManager, HostError> {
         // Prepare Context
         // Initialize configuration client
-        let (config_client, error_config): (ConfigClient, Option<String>) =
+        let (mut config_client, error_config): (ConfigClient, Option<String>) =
             match Self::init_config_client() {
                 Ok(cli) => (cli, None),
                 Err(err) => {
@@ -61,21 +87,31 @@ impl ActivityManager {
                     (ConfigClient::degraded(), Some(err))
                 }
             };
-        let (bookmarks_client, error_bookmark) = match Self::init_bookmarks_client() {
-            Ok(cli) => (cli, None),
-            Err(err) => (None, Some(err)),
-        };
-        let error = error_config.or(error_bookmark);
-        let theme_provider: ThemeProvider = Self::init_theme_provider();
+        let (bookmarks_client, error_bookmark) =
+            match Self::init_bookmarks_client(config_client.get_recents_size()) {
+                Ok(cli) => (cli, None),
+                Err(err) => (None, Some(err)),
+            };
+        let error_keymap = Self::init_keymap(&mut config_client).err();
+        let error = error_config.or(error_bookmark).or(error_keymap);
+        let mut theme_provider: ThemeProvider = Self::init_theme_provider();
+        theme_provider.set_palette(palette);
         let ctx: Context = Context::new(bookmarks_client, config_client, theme_provider, error);
         Ok(ActivityManager {
             context: Some(ctx),
             ticks,
+            external_password: ExternalPassword::default(),
+            #[cfg(posix)]
+            suspend_listener: Self::init_suspend_listener(),
         })
     }
 
     /// Configure remote args
     pub fn configure_remote_args(&mut self, remote_args: RemoteArgs) -> Result<(), String> {
+        self.external_password = ExternalPassword::new(
+            remote_args.password_command,
+            remote_args.password_from_stdin,
+        );
         // Set for host bridge
         match remote_args.host_bridge {
             Remote::Bookmark(params) => self.resolve_bookmark_name(
@@ -118,9 +154,36 @@ impl ActivityManager {
         host: HostParams,
         password: Option<&str>,
     ) -> Result<(), String> {
-        let (remote_local_path, remote_remote_path) = match &host {
-            HostParams::Remote(params) => (params.local_path.clone(), params.remote_path.clone()),
-            _ => (None, None),
+        let (
+            remote_local_path,
+            remote_remote_path,
+            remote_shell,
+            quick_commands,
+            url_mappings,
+            explorer,
+            ssh,
+            modes,
+        ) = match &host {
+            HostParams::Remote(params) => (
+                params.local_path.clone(),
+                params.remote_path.clone(),
+                params.remote_shell.clone(),
+                params.quick_commands.clone(),
+                params.url_mappings.clone(),
+                params.explorer.clone(),
+                params.ssh.clone(),
+                params.modes,
+            ),
+            _ => (
+                None,
+                None,
+                None,
+                Vec::new(),
+                Vec::new(),
+                Default::default(),
+                Default::default(),
+                Default::default(),
+            ),
         };
 
         let mut remote_params = match &host {
@@ -155,6 +218,12 @@ impl ActivityManager {
                 let params = FileTransferParams {
                     local_path: remote_local_path,
                     remote_path: remote_remote_path,
+                    remote_shell,
+                    quick_commands,
+                    url_mappings,
+                    explorer,
+                    ssh,
+                    modes,
                     protocol,
                     params,
                 };
@@ -170,18 +239,40 @@ impl ActivityManager {
         params: &mut ProtocolParams,
         password: Option<&str>,
     ) -> Result<(), String> {
+        // Localhost doesn't authenticate
+        if protocol == FileTransferProtocol::Localhost {
+            return Ok(());
+        }
         // Set password if provided
         if params.password_missing() {
             if let Some(password) = password {
                 params.set_default_secret(password.to_string());
-            } else if matches!(
+            } else if !Self::resolve_stored_credentials(
                 protocol,
-                FileTransferProtocol::Scp | FileTransferProtocol::Sftp,
-            ) && params.generic_params().is_some()
-            {
+                params,
+                self.context.as_ref().unwrap().config(),
+            ) {
+                self.read_external_password_or_prompt(params)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns whether the password of params is not required, since credentials are stored for the host:
+    /// a SSH key is registered for SCP and SFTP hosts, while the credentials of FTP hosts are read from the netrc file
+    pub(crate) fn resolve_stored_credentials(
+        protocol: FileTransferProtocol,
+        params: &mut ProtocolParams,
+        config: &ConfigClient,
+    ) -> bool {
+        match protocol {
+            FileTransferProtocol::Scp | FileTransferProtocol::Sftp => {
+                let Some(generic_params) = params.generic_params() else {
+                    return false;
+                };
                 // * if protocol is SCP or SFTP check whether a SSH key is registered for this remote, in case not ask password
-                let storage = SshKeyStorage::from(self.context.as_ref().unwrap().config());
-                let generic_params = params.generic_params().unwrap();
+                let storage = SshKeyStorage::from(config);
                 if storage
                     .resolve(
                         &generic_params.address,
@@ -196,27 +287,101 @@ impl ActivityManager {
                         "storage could not find any suitable key for {}... prompting for password",
                         generic_params.address
                     );
-                    self.prompt_password(params)?;
+                    false
                 } else {
                     debug!(
                         "a key is already set for {}; password is not required",
                         generic_params.address
                     );
+                    true
                 }
-            } else {
-                self.prompt_password(params)?;
             }
+            // * if protocol is FTP check whether credentials are stored in netrc, in case not ask password
+            FileTransferProtocol::Ftp(_) if params.generic_params().is_some() => {
+                Self::resolve_netrc_credentials(params)
+            }
+            _ => false,
         }
+    }
 
-        Ok(())
+    /// Fill username and password for generic params from the netrc file, if it has an entry for the host.
+    /// Returns whether the password has been set
+    fn resolve_netrc_credentials(params: &mut ProtocolParams) -> bool {
+        let Some(path) = netrc::netrc_path().filter(|p| p.exists()) else {
+            return false;
+        };
+        let netrc = match Netrc::from_file(&path) {
+            Ok(netrc) => netrc,
+            Err(err) => {
+                warn!("could not parse netrc file {}: {err}", path.display());
+                return false;
+            }
+        };
+        let Some(params) = params.mut_generic_params() else {
+            return false;
+        };
+        let Some(entry) = netrc.find(&params.address) else {
+            return false;
+        };
+        // if username is provided, it must match the netrc login
+        if let (Some(username), Some(login)) = (params.username.as_deref(), entry.login.as_deref())
+        {
+            if username != login {
+                return false;
+            }
+        }
+        let Some(password) = entry.password.clone() else {
+            return false;
+        };
+        debug!(
+            "using credentials from netrc file {} for {}",
+            path.display(),
+            params.address
+        );
+        if params.username.is_none() {
+            params.username = entry.login.clone();
+        }
+        params.password = Some(password);
+        true
+    }
+
+    /// Set the password from `--password-command`, `--password-from-stdin` or `TERMSCP_PASSWORD` into params,
+    /// or prompt user for it if neither is provided.
+    /// NOTE: the password must never be logged
+    fn read_external_password_or_prompt(
+        &mut self,
+        params: &mut ProtocolParams,
+    ) -> Result<(), String> {
+        // give the terminal to the command, which may prompt the user as well
+        let run_command = self.external_password.is_command_pending();
+        if run_command {
+            tty::leave_terminal(self.context.as_mut().unwrap().terminal());
+        }
+        let password = self.external_password.get();
+        if run_command {
+            tty::enter_terminal(self.context.as_mut().unwrap().terminal());
+        }
+        match password? {
+            Some(password) => {
+                debug!(
+                    "Using the password provided by {} for {}",
+                    self.external_password.source(),
+                    params.host_name()
+                );
+                params.set_default_secret(password);
+                Ok(())
+            }
+            None => self.prompt_password(params),
+        }
     }
 
     /// Prompt user for password to set into params.
     fn prompt_password(&mut self, params: &mut ProtocolParams) -> Result<(), String> {
         let ctx = self.context.as_mut().unwrap();
         let prompt = format!("Password for {}: ", params.host_name());
+        let asterisks = ctx.config().get_password_prompt_asterisks();
 
-        match tty::read_secret_from_tty(ctx.terminal(), prompt) {
+        match tty::read_secret_from_tty(ctx.terminal(), prompt, asterisks) {
             Err(err) => Err(format!("Could not read password: {err}")),
             Ok(Some(secret)) => {
                 debug!(
@@ -248,6 +413,7 @@ impl ActivityManager {
                 Some(params) => params,
             };
 
+            let is_remote = matches!(host, Host::Remote);
             let params = match host {
                 Host::Remote => HostParams::Remote(params),
                 Host::HostBridge => {
@@ -255,7 +421,14 @@ impl ActivityManager {
                 }
             };
 
-            self.set_host_params(params, password)
+            self.set_host_params(params, password)?;
+            if is_remote {
+                self.context
+                    .as_mut()
+                    .unwrap()
+                    .set_remote_bookmark(Some(bookmark_name.to_string()));
+            }
+            Ok(())
         } else {
             Err(String::from(
                 "Could not resolve bookmark name: bookmarks client not initialized",
@@ -273,7 +446,7 @@ impl ActivityManager {
                 Some(activity) => match activity {
                     NextActivity::Authentication => self.run_authentication(),
                     NextActivity::FileTransfer => self.run_filetransfer(),
-                    NextActivity::SetupActivity => self.run_setup(),
+                    NextActivity::SetupActivity(layout) => self.run_setup(layout),
                 },
                 None => break, // Exit
             }
@@ -306,6 +479,8 @@ impl ActivityManager {
         loop {
             // Draw activity
             activity.on_draw();
+            // Handle suspension
+            self.handle_job_control(&mut activity);
             // Check if has to be terminated
             if let Some(exit_reason) = activity.will_umount() {
                 match exit_reason {
@@ -317,7 +492,7 @@ impl ActivityManager {
                     ExitReason::EnterSetup => {
                         // User requested activity
                         info!("AuthActivity terminated due to 'EnterSetup'");
-                        result = Some(NextActivity::SetupActivity);
+                        result = Some(NextActivity::SetupActivity(ViewLayout::SetupForm));
                         break;
                     }
                     ExitReason::Connect => {
@@ -376,6 +551,8 @@ impl ActivityManager {
         loop {
             // Draw activity
             activity.on_draw();
+            // Handle suspension
+            self.handle_job_control(&mut activity);
             // Check if has to be terminated
             if let Some(exit_reason) = activity.will_umount() {
                 match exit_reason {
@@ -402,9 +579,9 @@ impl ActivityManager {
     /// `SetupActivity` run loop.
     /// Returns when activity terminates.
     /// Returns the next activity to run
-    fn run_setup(&mut self) -> Option<NextActivity> {
+    fn run_setup(&mut self, layout: ViewLayout) -> Option<NextActivity> {
         // Prepare activity
-        let mut activity: SetupActivity = SetupActivity::new(self.ticks);
+        let mut activity: SetupActivity = SetupActivity::new(self.ticks, layout);
         // Get context
         let ctx: Context = match self.context.take() {
             Some(ctx) => ctx,
@@ -418,6 +595,8 @@ impl ActivityManager {
         loop {
             // Draw activity
             activity.on_draw();
+            // Handle suspension
+            self.handle_job_control(&mut activity);
             // Check if activity has terminated
             if let Some(ExitReason::Quit) = activity.will_umount() {
                 info!("SetupActivity terminated due to 'Quit'");
@@ -430,9 +609,232 @@ impl ActivityManager {
         Some(NextActivity::Authentication)
     }
 
+    /// Transfer files between the working directory and the remote host of `task` without the user interface.
+    /// The user is never prompted: the password is read from the arguments, the bookmark, the stored credentials,
+    /// the password command, the standard input or `TERMSCP_PASSWORD`, and the transfer fails if none provides it.
+    /// Since the user can't accept unknown host keys, the connection is refused, unless any host key is accepted
+    pub fn run_transfer(task: TransferTask) -> Result<TransferReport, String> {
+        let mut config = Self::init_config_client().unwrap_or_else(|err| {
+            warn!("{err}; using default configuration");
+            ConfigClient::degraded()
+        });
+        if task.accept_any_host_key {
+            config.set_ssh_accept_any_host_key(true);
+        }
+        let mut external_password =
+            ExternalPassword::new(task.password_command, task.password_from_stdin);
+        let remote_params =
+            Self::resolve_transfer_remote(task.remote, &config, &mut external_password)?;
+        let wrkdir =
+            env::current_dir().map_err(|e| format!("Could not get current directory: {e}"))?;
+        let local = wrkdir.join(task.local);
+        let (direction, source, dest) = match (task.recv, remote_params.remote_path.clone()) {
+            (true, Some(remote)) => (TransferDirection::Download, remote, local),
+            (true, None) => {
+                return Err(String::from(
+                    "The remote address doesn't provide the path to download",
+                ))
+            }
+            (false, remote) => (
+                TransferDirection::Upload,
+                local,
+                remote.unwrap_or_else(|| PathBuf::from(".")),
+            ),
+        };
+        // downloads are written to the local host, which always sets times
+        let preserves_mtime =
+            direction == TransferDirection::Download || remote_params.protocol.preserves_mtime();
+        if !preserves_mtime {
+            warn!(
+                "{} doesn't preserve modification times: files are compared by size only",
+                remote_params.protocol
+            );
+        }
+        let modes = remote_modes(&remote_params, &config);
+        let preserve_symlinks = config.get_preserve_symlinks();
+        let preserve_metadata = config.get_preserve_metadata() && preserves_mtime;
+        Self::run_transfer_queue(
+            move || {
+                RemoteSession::connect(
+                    direction,
+                    HostBridgeParams::Localhost(wrkdir),
+                    remote_params,
+                    &config,
+                )
+                .map(|session| {
+                    session
+                        .compare_mtime(preserves_mtime)
+                        .preserve_symlinks(preserve_symlinks)
+                        .preserve_metadata(preserve_metadata)
+                        .modes(modes)
+                })
+            },
+            direction,
+            source,
+            dest,
+            task.quiet,
+        )
+    }
+
+    /// Resolve the params of the remote host of a transfer, setting its password if missing.
+    /// Returns error if no password is available, since the user can't be prompted for it
+    fn resolve_transfer_remote(
+        remote: Remote,
+        config: &ConfigClient,
+        external_password: &mut ExternalPassword,
+    ) -> Result<FileTransferParams, String> {
+        let (mut params, password) = match remote {
+            Remote::Host(host) => (host.file_transfer_params, host.password),
+            Remote::Bookmark(bookmark) => (
+                Self::init_bookmarks_client(config.get_recents_size())?
+                    .ok_or_else(|| {
+                        String::from(
+                            "Could not resolve bookmark name: bookmarks client not initialized",
+                        )
+                    })?
+                    .get_bookmark(&bookmark.name)
+                    .ok_or_else(|| {
+                        format!(
+                            r#"Could not resolve bookmark name: "{}" no such bookmark"#,
+                            bookmark.name
+                        )
+                    })?,
+                bookmark.password,
+            ),
+            Remote::None => return Err(String::from("No remote host provided")),
+        };
+        if params.protocol == FileTransferProtocol::Localhost || !params.params.password_missing() {
+            return Ok(params);
+        }
+        if let Some(password) = password {
+            params.params.set_default_secret(password);
+        } else if !Self::resolve_stored_credentials(params.protocol, &mut params.params, config) {
+            match external_password.get()? {
+                Some(password) => params.params.set_default_secret(password),
+                None => {
+                    return Err(format!(
+                        "no password available for {}: provide it with -P, --password-command, --password-from-stdin or TERMSCP_PASSWORD",
+                        params.params.host_name()
+                    ))
+                }
+            }
+        }
+        Ok(params)
+    }
+
+    /// Queue the transfer of `source` to `dest`, or into `dest` if it's a directory, and wait for the queue worker
+    /// to transfer it with the session opened by `connect`; the outcome of each file is printed unless `quiet` is set
+    fn run_transfer_queue<C>(
+        connect: C,
+        direction: TransferDirection,
+        source: PathBuf,
+        dest: PathBuf,
+        quiet: bool,
+    ) -> Result<TransferReport, String>
+    where
+        C: FnOnce() -> Result<RemoteSession, String> + Send + 'static,
+    {
+        let mut queue = TransferQueue::default();
+        // the session lives in the worker, which resolves the entries to queue once connected
+        let (sender, entries) = mpsc::channel();
+        queue.set_worker(QueueWorker::spawn(queue.transfer.progress(), move || {
+            let resolved = connect().and_then(|mut session| {
+                let source = session.stat_source(direction, &source)?;
+                let dest = match session.stat_dest(direction, &dest) {
+                    Some(dir) if dir.is_dir() => dest.join(source.name()),
+                    _ => dest,
+                };
+                Ok((session, source, dest))
+            });
+            match resolved {
+                Ok((session, source, dest)) => {
+                    let _ = sender.send(Ok((source, dest)));
+                    Ok(session)
+                }
+                Err(err) => {
+                    let _ = sender.send(Err(err.clone()));
+                    Err(err)
+                }
+            }
+        }));
+        let (source, dest) = entries
+            .recv()
+            .map_err(|_| String::from("transfer queue worker terminated"))??;
+        queue.push(direction, source, dest);
+        let mut report = TransferReport::default();
+        // file being transferred, with its size
+        let mut active = None;
+        while queue.unfinished() > 0 {
+            queue.dispatch();
+            for event in queue.poll() {
+                match event {
+                    QueueEvent::ConnectionFailed(err) => return Err(err),
+                    QueueEvent::FileStarted { path, size, .. } => active = Some((path, size)),
+                    QueueEvent::FileTransferred { .. } => {
+                        report.transferred += 1;
+                        if let Some((path, size)) = active.as_ref().filter(|_| !quiet) {
+                            println!("{} ({})", path.display(), ByteSize(*size as u64));
+                        }
+                    }
+                    QueueEvent::FileSkipped { .. } => {
+                        report.unchanged += 1;
+                        if let Some((path, _)) = active.as_ref().filter(|_| !quiet) {
+                            println!("{} is unchanged", path.display());
+                        }
+                    }
+                    QueueEvent::FileFailed { failure, .. } => report.errors.push(format!(
+                        "Could not transfer {}: {}",
+                        failure.source.path().display(),
+                        failure.error
+                    )),
+                    QueueEvent::Planned { .. } | QueueEvent::Finished { .. } => {}
+                }
+            }
+            thread::sleep(TRANSFER_POLL_INTERVAL);
+        }
+        // wait for the worker to disconnect
+        queue.stop();
+        Ok(report)
+    }
+
+    /// Suspend the process if requested (`SIGTSTP`), giving the terminal back to the shell.
+    /// Once resumed (`SIGCONT`), the activity takes the terminal back
+    #[cfg(posix)]
+    fn handle_job_control(&self, activity: &mut dyn Activity) {
+        let Some(listener) = self.suspend_listener.as_ref() else {
+            return;
+        };
+        if listener.take_suspend() {
+            info!("Suspending termscp");
+            activity.on_suspend();
+            signals::stop();
+            info!("termscp resumed");
+            listener.take_resume();
+            activity.on_resume();
+        } else if listener.take_resume() {
+            // stopped by a signal which can't be handled (e.g. `SIGSTOP`)
+            info!("termscp resumed");
+            activity.on_resume();
+        }
+    }
+
+    #[cfg(win)]
+    fn handle_job_control(&self, _activity: &mut dyn Activity) {}
+
     // -- misc
 
-    fn init_bookmarks_client() -> Result<Option<BookmarksClient>, String> {
+    #[cfg(posix)]
+    fn init_suspend_listener() -> Option<SuspendListener> {
+        match SuspendListener::register() {
+            Ok(listener) => Some(listener),
+            Err(err) => {
+                error!("Failed to register job control signals handlers: {}", err);
+                None
+            }
+        }
+    }
+
+    fn init_bookmarks_client(recents_size: usize) -> Result<Option<BookmarksClient>, String> {
         // Get config dir
         match environment::init_config_dir() {
             Ok(path) => {
@@ -441,16 +843,20 @@ impl ActivityManager {
                     let bookmarks_file: PathBuf =
                         environment::get_bookmarks_paths(config_dir_path.as_path());
                     // Initialize client
-                    BookmarksClient::new(bookmarks_file.as_path(), config_dir_path.as_path(), 16)
-                        .map(Option::Some)
-                        .map_err(|e| {
-                            format!(
-                                "Could not initialize bookmarks (at \"{}\", \"{}\"): {}",
-                                bookmarks_file.display(),
-                                config_dir_path.display(),
-                                e
-                            )
-                        })
+                    BookmarksClient::new(
+                        bookmarks_file.as_path(),
+                        config_dir_path.as_path(),
+                        recents_size,
+                    )
+                    .map(Option::Some)
+                    .map_err(|e| {
+                        format!(
+                            "Could not initialize bookmarks (at \"{}\", \"{}\"): {}",
+                            bookmarks_file.display(),
+                            config_dir_path.display(),
+                            e
+                        )
+                    })
                 } else {
                     Ok(None)
                 }
@@ -485,6 +891,26 @@ impl ActivityManager {
         }
     }
 
+    /// Read the keymap of the configuration directory into the config client
+    fn init_keymap(config_client: &mut ConfigClient) -> Result<(), String> {
+        match environment::init_config_dir() {
+            Ok(Some(config_dir)) => {
+                let keymap_path: PathBuf = environment::get_keymap_path(config_dir.as_path());
+                config_client
+                    .read_keymap(keymap_path.as_path())
+                    .map_err(|err| {
+                        error!("Could not read keymap '{}': {}", keymap_path.display(), err);
+                        format!(
+                            "Could not read keymap \"{}\": {err}; using default keybindings",
+                            keymap_path.display()
+                        )
+                    })
+            }
+            // the error is already reported by the config client
+            Ok(None) | Err(_) => Ok(()),
+        }
+    }
+
     fn init_theme_provider() -> ThemeProvider {
         match environment::init_config_dir() {
             Ok(config_dir) => {
@@ -513,3 +939,93 @@ impl ActivityManager {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+
+    use std::fs;
+    use std::path::Path;
+
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    use super::*;
+    use crate::filetransfer::params::GenericProtocolParams;
+
+    fn localhost_session(
+        wrkdir: &Path,
+    ) -> impl FnOnce() -> Result<RemoteSession, String> + Send + 'static {
+        let wrkdir = wrkdir.to_path_buf();
+        move || {
+            RemoteSession::connect(
+                TransferDirection::Upload,
+                HostBridgeParams::Localhost(wrkdir),
+                FileTransferParams::new(
+                    FileTransferProtocol::Localhost,
+                    ProtocolParams::Generic(GenericProtocolParams::default()),
+                ),
+                &ConfigClient::degraded(),
+            )
+            .map(|session| session.preserve_metadata(true))
+        }
+    }
+
+    #[test]
+    fn should_transfer_directory_into_destination() {
+        let source = TempDir::new().unwrap();
+        let dest = TempDir::new().unwrap();
+        fs::create_dir(source.path().join("docs")).unwrap();
+        fs::write(source.path().join("docs/readme.md"), "hello").unwrap();
+        fs::write(source.path().join("notes.txt"), "world").unwrap();
+        let report = ActivityManager::run_transfer_queue(
+            localhost_session(source.path()),
+            TransferDirection::Upload,
+            source.path().to_path_buf(),
+            dest.path().to_path_buf(),
+            true,
+        )
+        .unwrap();
+        assert_eq!(report.transferred, 2);
+        assert!(report.errors.is_empty());
+        let copy = dest.path().join(source.path().file_name().unwrap());
+        assert_eq!(
+            fs::read_to_string(copy.join("docs/readme.md")).unwrap(),
+            "hello"
+        );
+        assert_eq!(fs::read_to_string(copy.join("notes.txt")).unwrap(), "world");
+        // unchanged files are not transferred again
+        let report = ActivityManager::run_transfer_queue(
+            localhost_session(source.path()),
+            TransferDirection::Upload,
+            source.path().to_path_buf(),
+            dest.path().to_path_buf(),
+            true,
+        )
+        .unwrap();
+        assert_eq!(report.transferred, 0);
+        assert_eq!(report.unchanged, 2);
+    }
+
+    #[test]
+    fn should_fail_transfer_without_password() {
+        let params = FileTransferParams::new(
+            FileTransferProtocol::Ftp(false),
+            ProtocolParams::Generic(
+                GenericProtocolParams::default()
+                    .address("127.0.0.1")
+                    .username(Some("omar")),
+            ),
+        );
+        let remote = Remote::Host(crate::cli::HostParams {
+            file_transfer_params: params,
+            password: None,
+        });
+        let err = ActivityManager::resolve_transfer_remote(
+            remote,
+            &ConfigClient::degraded(),
+            &mut ExternalPassword::new(None, false),
+        )
+        .unwrap_err();
+        assert!(err.starts_with("no password available for 127.0.0.1"));
+    }
+}
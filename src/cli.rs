@@ -2,21 +2,50 @@
 //!
 //! defines the types for main.rs types
 
+mod password;
 mod remote;
 
 use std::path::PathBuf;
 use std::time::Duration;
 
 use argh::FromArgs;
-pub use remote::{Remote, RemoteArgs};
+pub use password::ExternalPassword;
+pub use remote::{BookmarkParams, HostParams, Remote, RemoteArgs};
 
 use crate::activity_manager::NextActivity;
 use crate::system::logging::LogLevel;
+use crate::system::theme_provider::Palette;
+use crate::ui::activities::setup::ViewLayout;
+use crate::utils;
 
 pub enum Task {
     Activity(NextActivity),
+    CheckConfig,
+    ExportBookmarks {
+        file: PathBuf,
+        include_passwords: bool,
+    },
+    ImportBookmarks {
+        file: PathBuf,
+        overwrite: bool,
+    },
     ImportTheme(PathBuf),
     InstallUpdate,
+    Transfer(Box<TransferTask>),
+}
+
+/// Transfer run without the user interface
+pub struct TransferTask {
+    pub remote: Remote,
+    pub local: PathBuf,
+    /// download the remote path into the local path, instead of uploading the local path
+    pub recv: bool,
+    /// don't print the progress of the transfer
+    pub quiet: bool,
+    pub password_command: Option<String>,
+    pub password_from_stdin: bool,
+    /// accept the host key of the remote without verifying it
+    pub accept_any_host_key: bool,
 }
 
 #[derive(Default, FromArgs)]
@@ -31,7 +60,7 @@ where positional can be:
 Address syntax can be:
 
     - `protocol://user@address:port:wrkdir` for protocols such as Sftp, Scp, Ftp
-    - `s3://bucket-name@region:profile:/wrkdir` for Aws S3 protocol
+    - `s3://bucket-name@region:profile:/wrkdir[?endpoint=url&new_path_style=true]` for Aws S3 protocol
     - `\\\\<server>[:port]\\<share>[\\path]` for SMB (on Windows)
     - `smb://[user@]<server>[:port]</share>[/path]` for SMB (on other systems)
 
@@ -46,10 +75,20 @@ pub struct Args {
     /// enable TRACE log level
     #[argh(switch, short = 'D')]
     pub debug: bool,
+    /// disable colors; they're also disabled if the NO_COLOR environment variable is set or on terminals without colors
+    #[argh(switch)]
+    pub no_color: bool,
     /// provide password from CLI; if you need to provide multiple passwords, use multiple -P flags.
     /// In case just respect the order of the addresses
     #[argh(option, short = 'P')]
     pub password: Vec<String>,
+    /// run this command to get the password of the addresses without -P, instead of being prompted for it;
+    /// the first line of its output is the password. Otherwise it's read from TERMSCP_PASSWORD, if set
+    #[argh(option)]
+    pub password_command: Option<String>,
+    /// read the password of the addresses without -P from the first line of the standard input, which must be piped
+    #[argh(switch)]
+    pub password_from_stdin: bool,
     /// disable logging
     #[argh(switch, short = 'q')]
     pub quiet: bool,
@@ -67,15 +106,114 @@ pub struct Args {
 #[derive(FromArgs)]
 #[argh(subcommand)]
 pub enum ArgsSubcommands {
+    Bookmarks(BookmarksArgs),
     Config(ConfigArgs),
     LoadTheme(LoadThemeArgs),
+    Transfer(TransferArgs),
     Update(UpdateArgs),
 }
 
+#[derive(FromArgs)]
+/// export or import bookmarks
+#[argh(subcommand, name = "bookmarks")]
+pub struct BookmarksArgs {
+    #[argh(subcommand)]
+    pub nested: BookmarksSubcommands,
+}
+
+#[derive(FromArgs)]
+#[argh(subcommand)]
+pub enum BookmarksSubcommands {
+    Export(BookmarksExportArgs),
+    Import(BookmarksImportArgs),
+}
+
+#[derive(FromArgs)]
+/// export bookmarks to a plain TOML file; passwords are left out unless --include-passwords is given
+#[argh(subcommand, name = "export")]
+pub struct BookmarksExportArgs {
+    #[argh(positional)]
+    /// file to write bookmarks to
+    pub file: PathBuf,
+    /// write passwords and secrets in plain text into the exported file
+    #[argh(switch)]
+    pub include_passwords: bool,
+}
+
+#[derive(FromArgs)]
+/// import bookmarks from a file exported with `bookmarks export`
+#[argh(subcommand, name = "import")]
+pub struct BookmarksImportArgs {
+    #[argh(positional)]
+    /// file to read bookmarks from
+    pub file: PathBuf,
+    /// replace existing bookmarks with the same name, instead of skipping them
+    #[argh(switch)]
+    pub overwrite: bool,
+}
+
 #[derive(FromArgs)]
 /// open termscp configuration
 #[argh(subcommand, name = "config")]
-pub struct ConfigArgs {}
+pub struct ConfigArgs {
+    /// check configuration, theme and bookmarks files and print a report, without opening the configuration
+    #[argh(switch, short = 'c')]
+    pub check: bool,
+    #[argh(subcommand)]
+    pub nested: Option<ConfigSubcommands>,
+}
+
+#[derive(FromArgs)]
+#[argh(subcommand)]
+pub enum ConfigSubcommands {
+    SshKeys(ConfigSshKeysArgs),
+    Theme(ConfigThemeArgs),
+}
+
+#[derive(FromArgs)]
+/// open termscp configuration on the ssh keys tab
+#[argh(subcommand, name = "ssh-keys")]
+pub struct ConfigSshKeysArgs {}
+
+#[derive(FromArgs)]
+/// open termscp configuration on the theme tab
+#[argh(subcommand, name = "theme")]
+pub struct ConfigThemeArgs {}
+
+#[derive(FromArgs)]
+/// transfer files without the user interface, e.g. `termscp transfer sftp://user@host:/remote/dir ./local/dir --recv`;
+/// the source is copied into the destination, if it's a directory
+#[argh(subcommand, name = "transfer")]
+pub struct TransferArgs {
+    #[argh(positional)]
+    /// address of the remote host, including the remote path
+    pub remote: String,
+    #[argh(positional)]
+    /// local path
+    pub local: PathBuf,
+    /// download the remote path into the local path, instead of uploading the local path to the remote path
+    #[argh(switch)]
+    pub recv: bool,
+    /// resolve the remote argument as a bookmark name
+    #[argh(switch, short = 'b')]
+    pub bookmark: bool,
+    /// password of the remote host
+    #[argh(option, short = 'P')]
+    pub password: Option<String>,
+    /// run this command to get the password, if not provided with -P; otherwise it's read from TERMSCP_PASSWORD, if set
+    #[argh(option)]
+    pub password_command: Option<String>,
+    /// read the password, if not provided with -P, from the first line of the standard input, which must be piped
+    #[argh(switch)]
+    pub password_from_stdin: bool,
+    /// don't print the progress of the transfer
+    #[argh(switch, short = 'q')]
+    pub quiet: bool,
+    /// accept the host key of the remote without verifying it against the known hosts file (SCP and SFTP only);
+    /// use it only on trusted networks
+    #[argh(switch)]
+    pub accept_any_host_key: bool,
+}
 
 #[derive(FromArgs)]
 /// update termscp to the latest version
@@ -94,14 +232,22 @@ pub struct LoadThemeArgs {
 pub struct RunOpts {
     pub remote: RemoteArgs,
     pub ticks: Duration,
+    pub palette: Palette,
     pub log_level: LogLevel,
     pub task: Task,
 }
 
 impl RunOpts {
-    pub fn config() -> Self {
+    pub fn config(layout: ViewLayout) -> Self {
+        Self {
+            task: Task::Activity(NextActivity::SetupActivity(layout)),
+            ..Default::default()
+        }
+    }
+
+    pub fn check_config() -> Self {
         Self {
-            task: Task::Activity(NextActivity::SetupActivity),
+            task: Task::CheckConfig,
             ..Default::default()
         }
     }
@@ -113,12 +259,57 @@ impl RunOpts {
         }
     }
 
+    pub fn export_bookmarks(file: PathBuf, include_passwords: bool) -> Self {
+        Self {
+            task: Task::ExportBookmarks {
+                file,
+                include_passwords,
+            },
+            ..Default::default()
+        }
+    }
+
+    pub fn import_bookmarks(file: PathBuf, overwrite: bool) -> Self {
+        Self {
+            task: Task::ImportBookmarks { file, overwrite },
+            ..Default::default()
+        }
+    }
+
     pub fn import_theme(theme: PathBuf) -> Self {
         Self {
             task: Task::ImportTheme(theme),
             ..Default::default()
         }
     }
+
+    /// Run the transfer described by `args` without the user interface
+    pub fn transfer(args: TransferArgs) -> Result<Self, String> {
+        password::check_password_from_stdin(
+            args.password_from_stdin,
+            args.password_command.as_deref(),
+        )?;
+        let remote = match args.bookmark {
+            true => Remote::Bookmark(BookmarkParams::new(args.remote, args.password)),
+            false => Remote::Host(HostParams::new(
+                utils::parser::parse_remote_opt(&args.remote)
+                    .map_err(|e| format!("Bad address option: {e}"))?,
+                args.password,
+            )),
+        };
+        Ok(Self {
+            task: Task::Transfer(Box::new(TransferTask {
+                remote,
+                local: args.local,
+                recv: args.recv,
+                quiet: args.quiet,
+                password_command: args.password_command,
+                password_from_stdin: args.password_from_stdin,
+                accept_any_host_key: args.accept_any_host_key,
+            })),
+            ..Default::default()
+        })
+    }
 }
 
 impl Default for RunOpts {
@@ -126,6 +317,7 @@ impl Default for RunOpts {
         Self {
             remote: RemoteArgs::default(),
             ticks: Duration::from_millis(10),
+            palette: Palette::default(),
             log_level: LogLevel::Info,
             task: Task::Activity(NextActivity::Authentication),
         }
@@ -0,0 +1,184 @@
+//! ## Password
+//!
+//! resolves the password of the remotes which have not been provided with one in the arguments
+
+use std::io::{BufRead, IsTerminal};
+use std::process::Command;
+
+/// Environment variable providing the password of the remotes
+pub const PASSWORD_ENV_VAR: &str = "TERMSCP_PASSWORD";
+
+/// Password of the remotes which have not been provided with `-P`: the output of `--password-command`, if given,
+/// the first line of the standard input with `--password-from-stdin`, or the value of `TERMSCP_PASSWORD`.
+/// The command is run (or the standard input read) once, the first time the password is required.
+///
+/// NOTE: doesn't implement `Debug`, so that the password can't be written to the log
+#[derive(Default)]
+pub struct ExternalPassword {
+    command: Option<String>,
+    from_stdin: bool,
+    resolved: Option<Option<String>>,
+}
+
+impl ExternalPassword {
+    /// Instantiate a new `ExternalPassword`, reading the password from the output of `command`, if any,
+    /// or from the standard input if `from_stdin`
+    pub fn new(command: Option<String>, from_stdin: bool) -> Self {
+        Self {
+            command,
+            from_stdin,
+            resolved: None,
+        }
+    }
+
+    /// Returns whether the password command has still to be run
+    pub fn is_command_pending(&self) -> bool {
+        self.command.is_some() && self.resolved.is_none()
+    }
+
+    /// Describe where the password comes from, to report it without revealing the password
+    pub fn source(&self) -> &'static str {
+        match (&self.command, self.from_stdin) {
+            (Some(_), _) => "the password command",
+            (None, true) => "the standard input",
+            (None, false) => PASSWORD_ENV_VAR,
+        }
+    }
+
+    /// Get the password, running the password command if it hasn't run yet
+    pub fn get(&mut self) -> Result<Option<String>, String> {
+        if let Some(password) = self.resolved.as_ref() {
+            return Ok(password.clone());
+        }
+        let password = match (self.command.as_deref(), self.from_stdin) {
+            (None, true) => {
+                Some(read_password_line(std::io::stdin().lock())?).filter(|x| !x.is_empty())
+            }
+            (command, _) => resolve_password(command, std::env::var(PASSWORD_ENV_VAR).ok())?,
+        };
+        self.resolved = Some(password.clone());
+        Ok(password)
+    }
+}
+
+/// Check that the password can be read from the standard input, if `from_stdin`: it must be piped, rather than
+/// typed into the terminal, and it can't be combined with the password `command`
+pub fn check_password_from_stdin(from_stdin: bool, command: Option<&str>) -> Result<(), String> {
+    if !from_stdin {
+        return Ok(());
+    }
+    if command.is_some() {
+        return Err(String::from(
+            "--password-from-stdin can't be used along with --password-command",
+        ));
+    }
+    if std::io::stdin().is_terminal() {
+        return Err(String::from(
+            "--password-from-stdin requires the password to be piped into the standard input",
+        ));
+    }
+    Ok(())
+}
+
+/// Read the first line of `reader`, without the trailing newline
+fn read_password_line(mut reader: impl BufRead) -> Result<String, String> {
+    let mut line = String::new();
+    reader
+        .read_line(&mut line)
+        .map_err(|e| format!("Could not read password from the standard input: {e}"))?;
+    Ok(line.trim_end_matches(['\n', '\r']).to_string())
+}
+
+/// Resolve the password from the output of `command`, if any, or from the `env` value; empty passwords are discarded
+fn resolve_password(command: Option<&str>, env: Option<String>) -> Result<Option<String>, String> {
+    let password = match command {
+        Some(command) => Some(run_password_command(command)?),
+        None => env,
+    };
+    Ok(password.filter(|x| !x.is_empty()))
+}
+
+/// Run `command` in the shell, returning the first line of its output
+fn run_password_command(command: &str) -> Result<String, String> {
+    #[cfg(posix)]
+    let output = Command::new("sh").arg("-c").arg(command).output();
+    #[cfg(win)]
+    let output = Command::new("cmd").arg("/C").arg(command).output();
+    let output = output.map_err(|e| format!("Could not run password command: {e}"))?;
+    if !output.status.success() {
+        return Err(format!("Password command failed: {}", output.status));
+    }
+    let stdout = String::from_utf8(output.stdout)
+        .map_err(|_| String::from("Password command output is not valid UTF-8"))?;
+    Ok(stdout
+        .lines()
+        .next()
+        .unwrap_or_default()
+        .trim_end_matches('\r')
+        .to_string())
+}
+
+#[cfg(test)]
+mod test {
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn should_resolve_password_from_env() {
+        assert_eq!(
+            resolve_password(None, Some(String::from("secret"))).unwrap(),
+            Some(String::from("secret"))
+        );
+        assert_eq!(resolve_password(None, Some(String::new())).unwrap(), None);
+        assert_eq!(resolve_password(None, None).unwrap(), None);
+    }
+
+    #[test]
+    #[cfg(posix)]
+    fn should_resolve_password_from_command() {
+        // the command wins over the environment
+        assert_eq!(
+            resolve_password(
+                Some("printf 'hunter2\\nignored\\n'"),
+                Some(String::from("secret"))
+            )
+            .unwrap(),
+            Some(String::from("hunter2"))
+        );
+        assert!(resolve_password(Some("exit 1"), None).is_err());
+        let mut password = ExternalPassword::new(Some(String::from("echo hunter2")), false);
+        assert!(password.is_command_pending());
+        assert_eq!(password.get().unwrap().as_deref(), Some("hunter2"));
+        assert!(!password.is_command_pending());
+        assert_eq!(password.source(), "the password command");
+    }
+
+    #[test]
+    fn should_read_password_line() {
+        assert_eq!(
+            read_password_line("hunter2\nignored\n".as_bytes()).unwrap(),
+            "hunter2"
+        );
+        assert_eq!(
+            read_password_line("hunter2\r\n".as_bytes()).unwrap(),
+            "hunter2"
+        );
+        assert_eq!(
+            read_password_line("  spaced  ".as_bytes()).unwrap(),
+            "  spaced  "
+        );
+        assert_eq!(read_password_line("".as_bytes()).unwrap(), "");
+        assert_eq!(
+            ExternalPassword::new(None, true).source(),
+            "the standard input"
+        );
+    }
+
+    #[test]
+    fn should_check_password_from_stdin() {
+        assert!(check_password_from_stdin(false, Some("pass show sftp")).is_ok());
+        assert!(check_password_from_stdin(true, Some("pass show sftp")).is_err());
+    }
+}
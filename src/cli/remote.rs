@@ -1,5 +1,6 @@
 use std::path::{Path, PathBuf};
 
+use super::password::check_password_from_stdin;
 use super::Args;
 use crate::filetransfer::FileTransferParams;
 use crate::utils;
@@ -16,6 +17,10 @@ pub struct RemoteArgs {
     pub host_bridge: Remote,
     pub remote: Remote,
     pub local_dir: Option<PathBuf>,
+    /// command printing the password of the remotes without a password in the arguments
+    pub password_command: Option<String>,
+    /// read the password of the remotes without a password in the arguments from the standard input
+    pub password_from_stdin: bool,
 }
 
 impl Default for RemoteArgs {
@@ -24,6 +29,8 @@ impl Default for RemoteArgs {
             host_bridge: Remote::None,
             remote: Remote::None,
             local_dir: None,
+            password_command: None,
+            password_from_stdin: false,
         }
     }
 }
@@ -32,7 +39,12 @@ impl TryFrom<&Args> for RemoteArgs {
     type Error = String;
 
     fn try_from(args: &Args) -> Result<Self, Self::Error> {
-        let mut remote_args = RemoteArgs::default();
+        check_password_from_stdin(args.password_from_stdin, args.password_command.as_deref())?;
+        let mut remote_args = RemoteArgs {
+            password_command: args.password_command.clone(),
+            password_from_stdin: args.password_from_stdin,
+            ..Default::default()
+        };
         // validate arguments
         match (args.bookmark.len(), args.positional.len()) {
             (0, positional) if positional < 4 => Ok(()),
@@ -166,6 +178,30 @@ mod test {
         assert_eq!(remote_args.local_dir, None);
     }
 
+    #[test]
+    fn test_should_make_remote_args_with_password_command() {
+        let args = Args {
+            positional: vec!["scp://host1".to_string()],
+            password_command: Some("pass show work/sftp".to_string()),
+            ..Default::default()
+        };
+
+        let remote_args = RemoteArgs::try_from(&args).unwrap();
+        assert_eq!(
+            remote_args.password_command.as_deref(),
+            Some("pass show work/sftp")
+        );
+        assert_eq!(remote_args.password_from_stdin, false);
+        // can't read the password from both
+        let args = Args {
+            positional: vec!["scp://host1".to_string()],
+            password_command: Some("pass show work/sftp".to_string()),
+            password_from_stdin: true,
+            ..Default::default()
+        };
+        assert!(RemoteArgs::try_from(&args).is_err());
+    }
+
     #[test]
     fn test_should_make_remote_args_from_args_two_remotes() {
         let args = Args {
@@ -9,6 +9,7 @@ mod smb;
 use std::collections::HashMap;
 use std::path::PathBuf;
 use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 use serde::de::Error as DeError;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
@@ -16,17 +17,22 @@ use serde::{Deserialize, Deserializer, Serialize, Serializer};
 pub use self::aws_s3::S3Params;
 pub use self::kube::KubeParams;
 pub use self::smb::SmbParams;
+use crate::explorer::{FileSorting, GroupDirs};
 use crate::filetransfer::params::{
-    AwsS3Params, GenericProtocolParams, KubeProtocolParams, ProtocolParams,
-    SmbParams as TransferSmbParams, WebDAVProtocolParams,
+    AwsS3Params, ExplorerPrefs, GenericProtocolParams, KubeProtocolParams, ModePrefs,
+    ProtocolParams, QuickCommand, SmbParams as TransferSmbParams, SshPrefs, UrlMapping,
+    WebDAVProtocolParams,
 };
 use crate::filetransfer::{FileTransferParams, FileTransferProtocol};
+use crate::utils::parser;
 
 /// UserHosts contains all the hosts saved by the user in the data storage
 /// It contains both `Bookmark`
 #[derive(Deserialize, Serialize, Debug, Default)]
 pub struct UserHosts {
     pub bookmarks: HashMap<String, Bookmark>,
+    /// Recents; missing in exported bookmarks
+    #[serde(default)]
     pub recents: HashMap<String, Bookmark>,
 }
 
@@ -51,6 +57,32 @@ pub struct Bookmark {
     pub remote_path: Option<PathBuf>,
     /// local folder to open at startup
     pub local_path: Option<PathBuf>,
+    /// Shell used to run commands on the remote host (e.g. `/bin/sh -c`)
+    pub remote_shell: Option<String>,
+    /// Commands which can be run on the remote host from the quick commands menu
+    pub quick_commands: Option<Vec<QuickCommand>>,
+    /// Remote directories served by a web server, mapped to the URLs to open them in the browser
+    pub url_mappings: Option<Vec<UrlMapping>>,
+    /// Criteria to sort files in the explorers (e.g. `by_mtime`)
+    pub file_sorting: Option<String>,
+    /// How to group directories in the explorers (`first`, `last` or `none`)
+    pub group_dirs: Option<String>,
+    /// Whether to show hidden files in the explorers
+    pub show_hidden_files: Option<bool>,
+    /// Whether the ssh session is compressed
+    pub ssh_compression: Option<bool>,
+    /// Preferred ciphers of the ssh session, as a comma separated list
+    pub ssh_ciphers: Option<String>,
+    /// Mode, in octal, applied to the files uploaded to the remote host (e.g. `0664`)
+    pub default_file_mode: Option<String>,
+    /// Mode, in octal, applied to the directories created on the remote host (e.g. `2775`)
+    pub default_dir_mode: Option<String>,
+    /// Whether FTP data connections are opened in active mode
+    pub ftp_active_mode: Option<bool>,
+    /// Whether FTPS data connections reuse the TLS session of the control connection; reused if `None`
+    pub ftp_tls_session_reuse: Option<bool>,
+    /// Group the bookmark is listed under in the auth activity; ungrouped if `None`
+    pub group: Option<String>,
     /// Kube params; optional. When used other fields are empty for sure
     pub kube: Option<KubeParams>,
     /// S3 params; optional. When used other fields are empty for sure
@@ -59,13 +91,92 @@ pub struct Bookmark {
     pub smb: Option<SmbParams>,
 }
 
+/// Maximum length of the error summary of a failed connection
+const CONNECTION_ERROR_SUMMARY_LEN: usize = 128;
+
+/// BookmarksState contains the state associated to the bookmarks.
+/// It is stored in a sidecar file, so the bookmarks file is never rewritten by connecting to hosts
+#[derive(Clone, Deserialize, Serialize, Debug, Default)]
+pub struct BookmarksState {
+    /// Last failed connection for each bookmark
+    pub failures: HashMap<String, ConnectionFailure>,
+    /// Remote files pinned as favorites for each bookmark
+    #[serde(default)]
+    pub favorites: HashMap<String, Vec<PathBuf>>,
+    /// Directories bookmarked during the sessions, for each host
+    #[serde(default)]
+    pub directories: HashMap<String, Vec<DirectoryBookmark>>,
+}
+
+/// DirectoryBookmark describes a directory bookmarked under a short name, to jump to it within a session
+#[derive(Clone, Deserialize, Serialize, Debug, PartialEq, Eq)]
+pub struct DirectoryBookmark {
+    pub name: String,
+    pub path: PathBuf,
+}
+
+/// ConnectionFailure describes the last failed connection to a bookmark
+#[derive(Clone, Deserialize, Serialize, Debug, PartialEq, Eq)]
+pub struct ConnectionFailure {
+    /// Time of the connection attempt, as seconds since the unix epoch
+    pub time: u64,
+    /// Summary of the connection error
+    pub error: String,
+}
+
 // -- impls
 
+impl ConnectionFailure {
+    /// Instantiates a new `ConnectionFailure` occurred now.
+    /// Only the first line of `error` is kept, truncated to `CONNECTION_ERROR_SUMMARY_LEN` characters
+    pub fn new(error: &str) -> Self {
+        let time = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|x| x.as_secs())
+            .unwrap_or_default();
+        Self {
+            time,
+            error: Self::summarize(error),
+        }
+    }
+
+    /// Get the time of the connection attempt
+    pub fn time(&self) -> SystemTime {
+        UNIX_EPOCH + std::time::Duration::from_secs(self.time)
+    }
+
+    fn summarize(error: &str) -> String {
+        let line = error.lines().next().unwrap_or_default().trim();
+        match line.chars().count() > CONNECTION_ERROR_SUMMARY_LEN {
+            true => format!(
+                "{}…",
+                line.chars()
+                    .take(CONNECTION_ERROR_SUMMARY_LEN - 1)
+                    .collect::<String>()
+            ),
+            false => line.to_string(),
+        }
+    }
+}
+
 impl From<FileTransferParams> for Bookmark {
     fn from(params: FileTransferParams) -> Self {
         let protocol = params.protocol;
         let remote_path = params.remote_path;
         let local_path = params.local_path;
+        let remote_shell = params.remote_shell;
+        let quick_commands = Some(params.quick_commands).filter(|x| !x.is_empty());
+        let url_mappings = Some(params.url_mappings).filter(|x| !x.is_empty());
+        let file_sorting = params.explorer.file_sorting.map(|x| x.to_string());
+        let group_dirs = params.explorer.group_dirs.map(|x| match x {
+            Some(group_dirs) => group_dirs.to_string(),
+            None => String::from("none"),
+        });
+        let show_hidden_files = params.explorer.show_hidden_files;
+        let ssh_compression = params.ssh.compression;
+        let ssh_ciphers = params.ssh.ciphers;
+        let default_file_mode = params.modes.file.map(|x| format!("{x:04o}"));
+        let default_dir_mode = params.modes.dir.map(|x| format!("{x:04o}"));
         // Create generic or others
         match params.params {
             ProtocolParams::Generic(params) => Self {
@@ -76,6 +187,19 @@ impl From<FileTransferParams> for Bookmark {
                 password: params.password,
                 remote_path,
                 local_path,
+                remote_shell,
+                quick_commands,
+                url_mappings,
+                file_sorting,
+                group_dirs,
+                show_hidden_files,
+                ssh_compression,
+                ssh_ciphers,
+                default_file_mode,
+                default_dir_mode,
+                ftp_active_mode: params.active_mode.then_some(true),
+                ftp_tls_session_reuse: (!params.tls_session_reuse).then_some(false),
+                group: None,
                 kube: None,
                 s3: None,
                 smb: None,
@@ -88,6 +212,19 @@ impl From<FileTransferParams> for Bookmark {
                 password: None,
                 remote_path,
                 local_path,
+                remote_shell,
+                quick_commands,
+                url_mappings,
+                file_sorting,
+                group_dirs,
+                show_hidden_files,
+                ssh_compression,
+                ssh_ciphers,
+                default_file_mode,
+                default_dir_mode,
+                ftp_active_mode: None,
+                ftp_tls_session_reuse: None,
+                group: None,
                 kube: None,
                 s3: Some(S3Params::from(params)),
                 smb: None,
@@ -100,6 +237,19 @@ impl From<FileTransferParams> for Bookmark {
                 password: None,
                 remote_path,
                 local_path,
+                remote_shell,
+                quick_commands,
+                url_mappings,
+                file_sorting,
+                group_dirs,
+                show_hidden_files,
+                ssh_compression,
+                ssh_ciphers,
+                default_file_mode,
+                default_dir_mode,
+                ftp_active_mode: None,
+                ftp_tls_session_reuse: None,
+                group: None,
                 kube: Some(KubeParams::from(params)),
                 s3: None,
                 smb: None,
@@ -116,6 +266,19 @@ impl From<FileTransferParams> for Bookmark {
                 password: params.password,
                 remote_path,
                 local_path,
+                remote_shell,
+                quick_commands,
+                url_mappings,
+                file_sorting,
+                group_dirs,
+                show_hidden_files,
+                ssh_compression,
+                ssh_ciphers,
+                default_file_mode,
+                default_dir_mode,
+                ftp_active_mode: None,
+                ftp_tls_session_reuse: None,
+                group: None,
                 kube: None,
                 s3: None,
             },
@@ -127,6 +290,19 @@ impl From<FileTransferParams> for Bookmark {
                 password: Some(parms.password),
                 remote_path,
                 local_path,
+                remote_shell,
+                quick_commands,
+                url_mappings,
+                file_sorting,
+                group_dirs,
+                show_hidden_files,
+                ssh_compression,
+                ssh_ciphers,
+                default_file_mode,
+                default_dir_mode,
+                ftp_active_mode: None,
+                ftp_tls_session_reuse: None,
+                group: None,
                 kube: None,
                 s3: None,
                 smb: None,
@@ -151,7 +327,9 @@ impl From<Bookmark> for FileTransferParams {
                     .address(bookmark.address.unwrap_or_default())
                     .port(bookmark.port.unwrap_or(22))
                     .username(bookmark.username)
-                    .password(bookmark.password);
+                    .password(bookmark.password)
+                    .active_mode(bookmark.ftp_active_mode.unwrap_or(false))
+                    .tls_session_reuse(bookmark.ftp_tls_session_reuse.unwrap_or(true));
                 Self::new(bookmark.protocol, ProtocolParams::Generic(params))
             }
             FileTransferProtocol::Kube => {
@@ -159,6 +337,9 @@ impl From<Bookmark> for FileTransferParams {
                 let params = KubeProtocolParams::from(params);
                 Self::new(bookmark.protocol, ProtocolParams::Kube(params))
             }
+            FileTransferProtocol::Localhost => {
+                Self::new(FileTransferProtocol::Localhost, ProtocolParams::default())
+            }
             #[cfg(posix)]
             FileTransferProtocol::Smb => {
                 let params = TransferSmbParams::new(
@@ -194,6 +375,34 @@ impl From<Bookmark> for FileTransferParams {
         }
         .remote_path(bookmark.remote_path) // Set entry remote_path
         .local_path(bookmark.local_path) // Set entry local path
+        .remote_shell(bookmark.remote_shell) // Set entry remote shell
+        .quick_commands(bookmark.quick_commands.unwrap_or_default()) // Set entry quick commands
+        .url_mappings(bookmark.url_mappings.unwrap_or_default()) // Set entry url mappings
+        .explorer(ExplorerPrefs {
+            file_sorting: bookmark
+                .file_sorting
+                .as_deref()
+                .and_then(|x| FileSorting::from_str(x).ok()),
+            group_dirs: bookmark
+                .group_dirs
+                .as_deref()
+                .map(|x| GroupDirs::from_str(x).ok()),
+            show_hidden_files: bookmark.show_hidden_files,
+        })
+        .ssh(SshPrefs {
+            compression: bookmark.ssh_compression,
+            ciphers: bookmark.ssh_ciphers,
+        })
+        .modes(ModePrefs {
+            file: bookmark
+                .default_file_mode
+                .as_deref()
+                .and_then(parser::parse_octal_mode),
+            dir: bookmark
+                .default_dir_mode
+                .as_deref()
+                .and_then(parser::parse_octal_mode),
+        })
     }
 }
 
@@ -232,6 +441,30 @@ mod tests {
         assert_eq!(bookmarks.recents.len(), 0);
     }
 
+    #[test]
+    fn should_make_connection_failure() {
+        let failure = ConnectionFailure::new("Connection refused");
+        assert_eq!(failure.error.as_str(), "Connection refused");
+        assert!(failure.time().elapsed().unwrap().as_secs() < 60);
+        assert_eq!(
+            ConnectionFailure::new("  Authentication failed\ncaused by: bad key\n")
+                .error
+                .as_str(),
+            "Authentication failed"
+        );
+        assert_eq!(ConnectionFailure::new("").error.as_str(), "");
+    }
+
+    #[test]
+    fn should_truncate_connection_failure_error() {
+        let error = "é".repeat(200);
+        let failure = ConnectionFailure::new(&error);
+        assert_eq!(failure.error.chars().count(), CONNECTION_ERROR_SUMMARY_LEN);
+        assert!(failure.error.ends_with('…'));
+        let error = "a".repeat(CONNECTION_ERROR_SUMMARY_LEN);
+        assert_eq!(ConnectionFailure::new(&error).error, error);
+    }
+
     #[test]
     fn test_bookmarks_bookmark_new() {
         let bookmark: Bookmark = Bookmark {
@@ -242,6 +475,19 @@ mod tests {
             password: Some(String::from("password")),
             remote_path: Some(PathBuf::from("/tmp")),
             local_path: Some(PathBuf::from("/usr")),
+            remote_shell: None,
+            quick_commands: None,
+            url_mappings: None,
+            file_sorting: None,
+            group_dirs: None,
+            show_hidden_files: None,
+            ssh_compression: None,
+            ssh_ciphers: None,
+            default_file_mode: None,
+            default_dir_mode: None,
+            ftp_active_mode: None,
+            ftp_tls_session_reuse: None,
+            group: None,
             kube: None,
             s3: None,
             smb: None,
@@ -254,6 +500,19 @@ mod tests {
             password: Some(String::from("password")),
             remote_path: Some(PathBuf::from("/home")),
             local_path: Some(PathBuf::from("/usr")),
+            remote_shell: None,
+            quick_commands: None,
+            url_mappings: None,
+            file_sorting: None,
+            group_dirs: None,
+            show_hidden_files: None,
+            ssh_compression: None,
+            ssh_ciphers: None,
+            default_file_mode: None,
+            default_dir_mode: None,
+            ftp_active_mode: None,
+            ftp_tls_session_reuse: None,
+            group: None,
             kube: None,
             s3: None,
             smb: None,
@@ -304,6 +563,9 @@ mod tests {
             port: 10222,
             username: Some(String::from("root")),
             password: Some(String::from("omar")),
+            active_mode: false,
+            tls_session_reuse: true,
+            jump_credentials: HashMap::new(),
         });
         let params: FileTransferParams = FileTransferParams::new(FileTransferProtocol::Scp, params)
             .remote_path(Some(PathBuf::from("/home")))
@@ -323,6 +585,170 @@ mod tests {
             std::path::Path::new("/tmp")
         );
         assert!(bookmark.s3.is_none());
+        assert!(bookmark.quick_commands.is_none());
+    }
+
+    #[test]
+    fn should_keep_quick_commands_between_bookmark_and_ftparams() {
+        let commands = vec![
+            QuickCommand::new("Tail", "tail -n 100 {selected_path}"),
+            QuickCommand::new("Uptime", "uptime"),
+        ];
+        let params = FileTransferParams::new(
+            FileTransferProtocol::Sftp,
+            ProtocolParams::Generic(GenericProtocolParams::default()),
+        )
+        .quick_commands(commands.clone());
+        let bookmark = Bookmark::from(params);
+        assert_eq!(bookmark.quick_commands.as_ref().unwrap(), &commands);
+        let params = FileTransferParams::from(bookmark);
+        assert_eq!(params.quick_commands, commands);
+    }
+
+    #[test]
+    fn should_keep_url_mappings_between_bookmark_and_ftparams() {
+        let mappings = vec![UrlMapping::new("/var/www/html", "https://example.com")];
+        let params = FileTransferParams::new(
+            FileTransferProtocol::Sftp,
+            ProtocolParams::Generic(GenericProtocolParams::default()),
+        )
+        .url_mappings(mappings.clone());
+        let bookmark = Bookmark::from(params);
+        assert_eq!(bookmark.url_mappings.as_ref().unwrap(), &mappings);
+        let params = FileTransferParams::from(bookmark);
+        assert_eq!(params.url_mappings, mappings);
+        let bookmark = Bookmark::from(FileTransferParams::new(
+            FileTransferProtocol::Sftp,
+            ProtocolParams::Generic(GenericProtocolParams::default()),
+        ));
+        assert!(bookmark.url_mappings.is_none());
+    }
+
+    #[test]
+    fn should_keep_ftp_active_mode_between_bookmark_and_ftparams() {
+        let params = FileTransferParams::new(
+            FileTransferProtocol::Ftp(false),
+            ProtocolParams::Generic(GenericProtocolParams::default().active_mode(true)),
+        );
+        let bookmark = Bookmark::from(params);
+        assert_eq!(bookmark.ftp_active_mode, Some(true));
+        let params = FileTransferParams::from(bookmark);
+        assert_eq!(params.params.generic_params().unwrap().active_mode, true);
+        // Passive mode is the default, so it's not stored
+        let bookmark = Bookmark::from(FileTransferParams::new(
+            FileTransferProtocol::Ftp(false),
+            ProtocolParams::Generic(GenericProtocolParams::default()),
+        ));
+        assert!(bookmark.ftp_active_mode.is_none());
+        assert_eq!(
+            FileTransferParams::from(bookmark)
+                .params
+                .generic_params()
+                .unwrap()
+                .active_mode,
+            false
+        );
+    }
+
+    #[test]
+    fn should_keep_ftp_tls_session_reuse_between_bookmark_and_ftparams() {
+        let params = FileTransferParams::new(
+            FileTransferProtocol::Ftp(true),
+            ProtocolParams::Generic(GenericProtocolParams::default().tls_session_reuse(false)),
+        );
+        let bookmark = Bookmark::from(params);
+        assert_eq!(bookmark.ftp_tls_session_reuse, Some(false));
+        let params = FileTransferParams::from(bookmark);
+        assert_eq!(
+            params.params.generic_params().unwrap().tls_session_reuse,
+            false
+        );
+        // Reuse is the default, so it's not stored
+        let bookmark = Bookmark::from(FileTransferParams::new(
+            FileTransferProtocol::Ftp(true),
+            ProtocolParams::Generic(GenericProtocolParams::default()),
+        ));
+        assert!(bookmark.ftp_tls_session_reuse.is_none());
+        assert_eq!(
+            FileTransferParams::from(bookmark)
+                .params
+                .generic_params()
+                .unwrap()
+                .tls_session_reuse,
+            true
+        );
+    }
+
+    #[test]
+    fn should_keep_explorer_prefs_between_bookmark_and_ftparams() {
+        let prefs = ExplorerPrefs {
+            file_sorting: Some(FileSorting::ModifyTime),
+            group_dirs: Some(None),
+            show_hidden_files: Some(true),
+        };
+        let params = FileTransferParams::new(
+            FileTransferProtocol::Sftp,
+            ProtocolParams::Generic(GenericProtocolParams::default()),
+        )
+        .explorer(prefs.clone());
+        let bookmark = Bookmark::from(params);
+        assert_eq!(bookmark.file_sorting.as_deref(), Some("by_mtime"));
+        assert_eq!(bookmark.group_dirs.as_deref(), Some("none"));
+        assert_eq!(bookmark.show_hidden_files, Some(true));
+        let params = FileTransferParams::from(bookmark);
+        assert_eq!(params.explorer, prefs);
+        // Bookmarks without preferences fall back to the configuration
+        let bookmark = Bookmark::from(FileTransferParams::new(
+            FileTransferProtocol::Sftp,
+            ProtocolParams::Generic(GenericProtocolParams::default()),
+        ));
+        assert!(bookmark.file_sorting.is_none());
+        assert!(bookmark.group_dirs.is_none());
+        assert!(bookmark.show_hidden_files.is_none());
+        assert_eq!(
+            FileTransferParams::from(bookmark).explorer,
+            ExplorerPrefs::default()
+        );
+    }
+
+    #[test]
+    fn should_keep_ssh_prefs_between_bookmark_and_ftparams() {
+        let prefs = SshPrefs {
+            compression: Some(true),
+            ciphers: Some(String::from("aes256-ctr,aes128-ctr")),
+        };
+        let params = FileTransferParams::new(
+            FileTransferProtocol::Sftp,
+            ProtocolParams::Generic(GenericProtocolParams::default()),
+        )
+        .ssh(prefs.clone());
+        let bookmark = Bookmark::from(params);
+        assert_eq!(bookmark.ssh_compression, Some(true));
+        assert_eq!(
+            bookmark.ssh_ciphers.as_deref(),
+            Some("aes256-ctr,aes128-ctr")
+        );
+        assert_eq!(FileTransferParams::from(bookmark).ssh, prefs);
+    }
+
+    #[test]
+    fn should_keep_mode_prefs_between_bookmark_and_ftparams() {
+        let prefs = ModePrefs {
+            file: Some(0o664),
+            dir: None,
+        };
+        let params = FileTransferParams::new(
+            FileTransferProtocol::Sftp,
+            ProtocolParams::Generic(GenericProtocolParams::default()),
+        )
+        .modes(prefs);
+        let mut bookmark = Bookmark::from(params);
+        assert_eq!(bookmark.default_file_mode.as_deref(), Some("0664"));
+        assert!(bookmark.default_dir_mode.is_none());
+        assert_eq!(FileTransferParams::from(bookmark.clone()).modes, prefs);
+        // invalid modes are ignored
+        bookmark.default_dir_mode = Some(String::from("0999"));
+        assert_eq!(FileTransferParams::from(bookmark).modes, prefs);
     }
 
     #[test]
@@ -330,7 +756,9 @@ mod tests {
         let params = ProtocolParams::AwsS3(
             AwsS3Params::new("omar", Some("eu-west-1"), Some("test"))
                 .access_key(Some("pippo"))
-                .secret_access_key(Some("pluto")),
+                .secret_access_key(Some("pluto"))
+                .storage_class(Some("STANDARD_IA"))
+                .ca_bundle(Some("/etc/ssl/minio.pem")),
         );
         let params: FileTransferParams =
             FileTransferParams::new(FileTransferProtocol::AwsS3, params);
@@ -346,6 +774,13 @@ mod tests {
         assert_eq!(s3.profile.as_deref().unwrap(), "test");
         assert_eq!(s3.access_key.as_deref().unwrap(), "pippo");
         assert_eq!(s3.secret_access_key.as_deref().unwrap(), "pluto");
+        assert_eq!(s3.requester_pays, Some(false));
+        assert_eq!(s3.storage_class.as_deref().unwrap(), "STANDARD_IA");
+        assert_eq!(s3.accept_invalid_certs, Some(false));
+        assert_eq!(
+            s3.ca_bundle.as_deref().unwrap(),
+            std::path::Path::new("/etc/ssl/minio.pem")
+        );
     }
 
     #[test]
@@ -386,6 +821,19 @@ mod tests {
             password: Some(String::from("password")),
             remote_path: Some(PathBuf::from("/tmp")),
             local_path: Some(PathBuf::from("/usr")),
+            remote_shell: None,
+            quick_commands: None,
+            url_mappings: None,
+            file_sorting: None,
+            group_dirs: None,
+            show_hidden_files: None,
+            ssh_compression: None,
+            ssh_ciphers: None,
+            default_file_mode: None,
+            default_dir_mode: None,
+            ftp_active_mode: None,
+            ftp_tls_session_reuse: None,
+            group: None,
             kube: None,
             s3: None,
             smb: None,
@@ -417,6 +865,19 @@ mod tests {
             password: Some(String::from("password")),
             remote_path: Some(PathBuf::from("/tmp")),
             local_path: Some(PathBuf::from("/usr")),
+            remote_shell: None,
+            quick_commands: None,
+            url_mappings: None,
+            file_sorting: None,
+            group_dirs: None,
+            show_hidden_files: None,
+            ssh_compression: None,
+            ssh_ciphers: None,
+            default_file_mode: None,
+            default_dir_mode: None,
+            ftp_active_mode: None,
+            ftp_tls_session_reuse: None,
+            group: None,
             kube: None,
             s3: None,
             smb: None,
@@ -447,6 +908,19 @@ mod tests {
             password: None,
             remote_path: Some(PathBuf::from("/tmp")),
             local_path: Some(PathBuf::from("/usr")),
+            remote_shell: None,
+            quick_commands: None,
+            url_mappings: None,
+            file_sorting: None,
+            group_dirs: None,
+            show_hidden_files: None,
+            ssh_compression: None,
+            ssh_ciphers: None,
+            default_file_mode: None,
+            default_dir_mode: None,
+            ftp_active_mode: None,
+            ftp_tls_session_reuse: None,
+            group: None,
             kube: None,
             s3: Some(S3Params {
                 bucket: String::from("veeso"),
@@ -456,6 +930,10 @@ mod tests {
                 access_key: Some(String::from("pippo")),
                 secret_access_key: Some(String::from("pluto")),
                 new_path_style: Some(true),
+                requester_pays: Some(true),
+                storage_class: Some(String::from("STANDARD_IA")),
+                accept_invalid_certs: Some(true),
+                ca_bundle: Some(PathBuf::from("/etc/ssl/minio.pem")),
             }),
             smb: None,
         };
@@ -477,6 +955,13 @@ mod tests {
         assert_eq!(gparams.access_key.as_deref().unwrap(), "pippo");
         assert_eq!(gparams.secret_access_key.as_deref().unwrap(), "pluto");
         assert_eq!(gparams.new_path_style, true);
+        assert_eq!(gparams.requester_pays, true);
+        assert_eq!(gparams.storage_class.as_deref().unwrap(), "STANDARD_IA");
+        assert_eq!(gparams.accept_invalid_certs, true);
+        assert_eq!(
+            gparams.ca_bundle.as_deref().unwrap(),
+            std::path::Path::new("/etc/ssl/minio.pem")
+        );
     }
 
     #[test]
@@ -489,6 +974,19 @@ mod tests {
             password: None,
             remote_path: Some(PathBuf::from("/tmp")),
             local_path: Some(PathBuf::from("/usr")),
+            remote_shell: None,
+            quick_commands: None,
+            url_mappings: None,
+            file_sorting: None,
+            group_dirs: None,
+            show_hidden_files: None,
+            ssh_compression: None,
+            ssh_ciphers: None,
+            default_file_mode: None,
+            default_dir_mode: None,
+            ftp_active_mode: None,
+            ftp_tls_session_reuse: None,
+            group: None,
             kube: Some(KubeParams {
                 namespace: Some(String::from("default")),
                 cluster_url: Some(String::from("https://localhost:6443")),
@@ -531,6 +1029,19 @@ mod tests {
             password: Some("bar".to_string()),
             remote_path: Some(PathBuf::from("/tmp")),
             local_path: Some(PathBuf::from("/usr")),
+            remote_shell: None,
+            quick_commands: None,
+            url_mappings: None,
+            file_sorting: None,
+            group_dirs: None,
+            show_hidden_files: None,
+            ssh_compression: None,
+            ssh_ciphers: None,
+            default_file_mode: None,
+            default_dir_mode: None,
+            ftp_active_mode: None,
+            ftp_tls_session_reuse: None,
+            group: None,
             kube: None,
             s3: None,
             smb: Some(SmbParams {
@@ -569,7 +1080,20 @@ mod tests {
             password: None,
             remote_path: Some(PathBuf::from("/tmp")),
             local_path: Some(PathBuf::from("/usr")),
+            remote_shell: None,
+            quick_commands: None,
+            url_mappings: None,
+            file_sorting: None,
+            group_dirs: None,
+            show_hidden_files: None,
+            ssh_compression: None,
+            ssh_ciphers: None,
+            default_file_mode: None,
+            default_dir_mode: None,
+            ftp_active_mode: None,
+            ftp_tls_session_reuse: None,
             s3: None,
+            group: None,
             kube: None,
             smb: Some(SmbParams {
                 share: "test".to_string(),
@@ -1,3 +1,5 @@
+use std::path::PathBuf;
+
 use serde::{Deserialize, Serialize};
 
 use crate::filetransfer::params::AwsS3Params;
@@ -13,6 +15,10 @@ pub struct S3Params {
     pub secret_access_key: Option<String>,
     /// NOTE: there are no session token and security token since they are always temporary
     pub new_path_style: Option<bool>,
+    pub requester_pays: Option<bool>,
+    pub storage_class: Option<String>,
+    pub accept_invalid_certs: Option<bool>,
+    pub ca_bundle: Option<PathBuf>,
 }
 
 impl From<AwsS3Params> for S3Params {
@@ -25,6 +31,10 @@ impl From<AwsS3Params> for S3Params {
             access_key: params.access_key,
             secret_access_key: params.secret_access_key,
             new_path_style: Some(params.new_path_style),
+            requester_pays: Some(params.requester_pays),
+            storage_class: params.storage_class,
+            accept_invalid_certs: Some(params.accept_invalid_certs),
+            ca_bundle: params.ca_bundle,
         }
     }
 }
@@ -36,5 +46,9 @@ impl From<S3Params> for AwsS3Params {
             .access_key(params.access_key)
             .secret_access_key(params.secret_access_key)
             .new_path_style(params.new_path_style.unwrap_or(false))
+            .requester_pays(params.requester_pays.unwrap_or(false))
+            .storage_class(params.storage_class)
+            .accept_invalid_certs(params.accept_invalid_certs.unwrap_or(false))
+            .ca_bundle(params.ca_bundle)
     }
 }
@@ -0,0 +1,652 @@
+//! ## Keymap
+//!
+//! `keymap` is the module which provides the keybindings of the file explorers, which can be remapped in `keys.toml`
+
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+use serde::Deserialize;
+use thiserror::Error;
+use tuirealm::event::{Key, KeyEvent, KeyModifiers};
+
+/// Action of the file explorers which can be bound to keys
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum KeyAction {
+    Transfer,
+    ToggleHidden,
+    FileSorting,
+    Copy,
+    CompareChecksums,
+    Mirror,
+    Sync,
+    Mkdir,
+    ShowDelete,
+    FuzzySearch,
+    Favorites,
+    Goto,
+    GotoClipboard,
+    FileInfo,
+    QuickCommands,
+    Symlink,
+    Reload,
+    Mark,
+    NewFile,
+    OpenTextFile,
+    LogPanel,
+    Preview,
+    Rename,
+    SaveAs,
+    Watch,
+    WatchedPaths,
+    Undo,
+    ParentDirectory,
+    Open,
+    OpenWith,
+    Exec,
+    SwapPanels,
+    SyncBrowsing,
+    Chmod,
+    Filter,
+    ToggleFavorite,
+    SelectAll,
+    DeselectAll,
+    OpenUrl,
+    CopyUrl,
+    Duplicate,
+    Cut,
+    Paste,
+    Compress,
+    Extract,
+    BookmarkDirectory,
+    DirectoryBookmarks,
+    Enqueue,
+}
+
+impl KeyAction {
+    pub const ALL: [KeyAction; 48] = [
+        Self::Transfer,
+        Self::ToggleHidden,
+        Self::FileSorting,
+        Self::Copy,
+        Self::CompareChecksums,
+        Self::Mirror,
+        Self::Sync,
+        Self::Mkdir,
+        Self::ShowDelete,
+        Self::FuzzySearch,
+        Self::Favorites,
+        Self::Goto,
+        Self::GotoClipboard,
+        Self::FileInfo,
+        Self::QuickCommands,
+        Self::Symlink,
+        Self::Reload,
+        Self::Mark,
+        Self::NewFile,
+        Self::OpenTextFile,
+        Self::LogPanel,
+        Self::Preview,
+        Self::Rename,
+        Self::SaveAs,
+        Self::Watch,
+        Self::WatchedPaths,
+        Self::Undo,
+        Self::ParentDirectory,
+        Self::Open,
+        Self::OpenWith,
+        Self::Exec,
+        Self::SwapPanels,
+        Self::SyncBrowsing,
+        Self::Chmod,
+        Self::Filter,
+        Self::ToggleFavorite,
+        Self::SelectAll,
+        Self::DeselectAll,
+        Self::OpenUrl,
+        Self::CopyUrl,
+        Self::Duplicate,
+        Self::Cut,
+        Self::Paste,
+        Self::Compress,
+        Self::Extract,
+        Self::BookmarkDirectory,
+        Self::DirectoryBookmarks,
+        Self::Enqueue,
+    ];
+
+    /// Name of the action in the keymap file
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Transfer => "transfer",
+            Self::ToggleHidden => "toggle_hidden",
+            Self::FileSorting => "file_sorting",
+            Self::Copy => "copy",
+            Self::CompareChecksums => "compare_checksums",
+            Self::Mirror => "mirror",
+            Self::Sync => "sync",
+            Self::Mkdir => "mkdir",
+            Self::ShowDelete => "show_delete",
+            Self::FuzzySearch => "fuzzy_search",
+            Self::Favorites => "favorites",
+            Self::Goto => "goto",
+            Self::GotoClipboard => "goto_clipboard",
+            Self::FileInfo => "file_info",
+            Self::QuickCommands => "quick_commands",
+            Self::Symlink => "symlink",
+            Self::Reload => "reload",
+            Self::Mark => "mark",
+            Self::NewFile => "new_file",
+            Self::OpenTextFile => "open_text_file",
+            Self::LogPanel => "log_panel",
+            Self::Preview => "preview",
+            Self::Rename => "rename",
+            Self::SaveAs => "save_as",
+            Self::Watch => "watch",
+            Self::WatchedPaths => "watched_paths",
+            Self::Undo => "undo",
+            Self::ParentDirectory => "parent_directory",
+            Self::Open => "open",
+            Self::OpenWith => "open_with",
+            Self::Exec => "exec",
+            Self::SwapPanels => "swap_panels",
+            Self::SyncBrowsing => "sync_browsing",
+            Self::Chmod => "chmod",
+            Self::Filter => "filter",
+            Self::ToggleFavorite => "toggle_favorite",
+            Self::SelectAll => "select_all",
+            Self::DeselectAll => "deselect_all",
+            Self::OpenUrl => "open_url",
+            Self::CopyUrl => "copy_url",
+            Self::Duplicate => "duplicate",
+            Self::Cut => "cut",
+            Self::Paste => "paste",
+            Self::Compress => "compress",
+            Self::Extract => "extract",
+            Self::BookmarkDirectory => "bookmark_directory",
+            Self::DirectoryBookmarks => "directory_bookmarks",
+            Self::Enqueue => "enqueue",
+        }
+    }
+
+    /// Get the action called `name` in the keymap file
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|x| x.name() == name)
+    }
+
+    /// Keys bound to the action when it is not remapped
+    pub fn default_bindings(&self) -> Vec<KeyBinding> {
+        match self {
+            Self::Transfer => vec![KeyBinding::char(' ')],
+            Self::ToggleHidden => vec![KeyBinding::char('a')],
+            Self::FileSorting => vec![KeyBinding::char('b')],
+            Self::Copy => vec![KeyBinding::char('c'), KeyBinding::function(5)],
+            Self::CompareChecksums => vec![KeyBinding::shift('C')],
+            Self::Mirror => vec![KeyBinding::shift('M')],
+            Self::Sync => vec![KeyBinding::shift('S')],
+            Self::Mkdir => vec![KeyBinding::char('d'), KeyBinding::function(7)],
+            Self::ShowDelete => vec![
+                KeyBinding::new(Key::Delete, KeyModifiers::NONE),
+                KeyBinding::function(8),
+                KeyBinding::char('e'),
+            ],
+            Self::FuzzySearch => vec![KeyBinding::char('f')],
+            Self::Favorites => vec![KeyBinding::shift('F')],
+            Self::Goto => vec![KeyBinding::char('g')],
+            Self::GotoClipboard => vec![KeyBinding::ctrl('g')],
+            Self::FileInfo => vec![KeyBinding::char('i')],
+            Self::QuickCommands => vec![KeyBinding::char('j')],
+            Self::Symlink => vec![KeyBinding::char('k')],
+            Self::Reload => vec![KeyBinding::char('l')],
+            Self::Mark => vec![KeyBinding::char('m')],
+            Self::NewFile => vec![KeyBinding::char('n')],
+            Self::OpenTextFile => vec![KeyBinding::char('o'), KeyBinding::function(4)],
+            Self::LogPanel => vec![KeyBinding::char('p')],
+            Self::Preview => vec![KeyBinding::shift('P')],
+            Self::Rename => vec![KeyBinding::char('r'), KeyBinding::function(6)],
+            Self::SaveAs => vec![KeyBinding::char('s'), KeyBinding::function(2)],
+            Self::Watch => vec![KeyBinding::char('t')],
+            Self::WatchedPaths => vec![KeyBinding::ctrl('t')],
+            Self::Undo => vec![KeyBinding::ctrl('u')],
+            Self::ParentDirectory => vec![KeyBinding::char('u')],
+            Self::Open => vec![KeyBinding::char('v'), KeyBinding::function(3)],
+            Self::OpenWith => vec![KeyBinding::char('w')],
+            Self::Exec => vec![KeyBinding::char('x')],
+            Self::SwapPanels => vec![KeyBinding::ctrl('x')],
+            Self::SyncBrowsing => vec![KeyBinding::char('y')],
+            Self::Chmod => vec![KeyBinding::char('z')],
+            Self::Filter => vec![KeyBinding::char('/')],
+            Self::ToggleFavorite => vec![KeyBinding::char('*')],
+            Self::SelectAll => vec![KeyBinding::ctrl('a')],
+            Self::DeselectAll => vec![KeyBinding::new(Key::Char('a'), KeyModifiers::ALT)],
+            Self::OpenUrl => vec![KeyBinding::shift('O')],
+            Self::CopyUrl => vec![KeyBinding::shift('U')],
+            Self::Duplicate => vec![KeyBinding::shift('D')],
+            Self::Cut => vec![KeyBinding::shift('X')],
+            Self::Paste => vec![KeyBinding::shift('V')],
+            Self::Compress => vec![KeyBinding::shift('Z')],
+            Self::Extract => vec![KeyBinding::shift('E')],
+            Self::BookmarkDirectory => vec![KeyBinding::shift('B')],
+            Self::DirectoryBookmarks => vec![KeyBinding::shift('G')],
+            Self::Enqueue => vec![KeyBinding::shift('Q')],
+        }
+    }
+}
+
+/// Keys which are handled by termscp regardless of the keymap, thus can't be bound to actions
+const RESERVED_KEYS: &[KeyBinding] = &[
+    KeyBinding::new(Key::Char('h'), KeyModifiers::NONE),
+    KeyBinding::new(Key::Char('q'), KeyModifiers::NONE),
+    KeyBinding::new(Key::Function(1), KeyModifiers::NONE),
+    KeyBinding::new(Key::Function(10), KeyModifiers::NONE),
+    KeyBinding::new(Key::Char('c'), KeyModifiers::CONTROL),
+    KeyBinding::new(Key::Char('q'), KeyModifiers::CONTROL),
+    KeyBinding::new(Key::Char('z'), KeyModifiers::CONTROL),
+];
+
+/// A key combination, such as `CTRL+U`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyBinding {
+    pub code: Key,
+    pub modifiers: KeyModifiers,
+}
+
+impl KeyBinding {
+    pub const fn new(code: Key, modifiers: KeyModifiers) -> Self {
+        Self { code, modifiers }
+    }
+
+    fn char(c: char) -> Self {
+        Self::new(Key::Char(c), KeyModifiers::NONE)
+    }
+
+    fn shift(c: char) -> Self {
+        Self::new(Key::Char(c), KeyModifiers::SHIFT)
+    }
+
+    fn ctrl(c: char) -> Self {
+        Self::new(Key::Char(c), KeyModifiers::CONTROL)
+    }
+
+    fn function(n: u8) -> Self {
+        Self::new(Key::Function(n), KeyModifiers::NONE)
+    }
+
+    /// Whether the key event is this key combination
+    pub fn matches(&self, ev: &KeyEvent) -> bool {
+        if self.code != ev.code {
+            return false;
+        }
+        match self.code {
+            // NOTE: symbols may be reported with shift, depending on the keyboard layout
+            Key::Char(c) if !c.is_alphabetic() => {
+                self.modifiers.difference(KeyModifiers::SHIFT)
+                    == ev.modifiers.difference(KeyModifiers::SHIFT)
+            }
+            _ => self.modifiers == ev.modifiers,
+        }
+    }
+
+    /// Whether the key is used by termscp for navigation or by the global keybindings
+    fn is_reserved(&self) -> bool {
+        matches!(
+            self.code,
+            Key::Up
+                | Key::Down
+                | Key::Left
+                | Key::Right
+                | Key::PageUp
+                | Key::PageDown
+                | Key::Home
+                | Key::End
+                | Key::Tab
+                | Key::BackTab
+                | Key::Enter
+                | Key::Esc
+                | Key::Backspace
+        ) || RESERVED_KEYS.iter().any(|x| x.matches(&self.into()))
+    }
+}
+
+impl From<&KeyBinding> for KeyEvent {
+    fn from(binding: &KeyBinding) -> Self {
+        Self {
+            code: binding.code,
+            modifiers: binding.modifiers,
+        }
+    }
+}
+
+impl FromStr for KeyBinding {
+    type Err = KeymapError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (modifiers_str, key) = match s.rsplit_once('+') {
+            // the key itself is `+`
+            Some((modifiers, "")) => (modifiers.strip_suffix('+').unwrap_or(modifiers), "+"),
+            Some((modifiers, key)) => (modifiers, key),
+            None => ("", s),
+        };
+        let mut modifiers = KeyModifiers::NONE;
+        for modifier in modifiers_str.split('+').filter(|x| !x.is_empty()) {
+            modifiers |= match modifier.to_ascii_uppercase().as_str() {
+                "CTRL" | "CONTROL" => KeyModifiers::CONTROL,
+                "ALT" => KeyModifiers::ALT,
+                "SHIFT" => KeyModifiers::SHIFT,
+                _ => return Err(KeymapError::InvalidKey(s.to_string())),
+            };
+        }
+        let code = match key.to_ascii_uppercase().as_str() {
+            "SPACE" => Key::Char(' '),
+            "DEL" | "DELETE" => Key::Delete,
+            "INS" | "INSERT" => Key::Insert,
+            "BACKSPACE" => Key::Backspace,
+            "ENTER" => Key::Enter,
+            "ESC" => Key::Esc,
+            "TAB" => Key::Tab,
+            "UP" => Key::Up,
+            "DOWN" => Key::Down,
+            "LEFT" => Key::Left,
+            "RIGHT" => Key::Right,
+            "HOME" => Key::Home,
+            "END" => Key::End,
+            "PGUP" => Key::PageUp,
+            "PGDOWN" => Key::PageDown,
+            f if f.len() > 1 && f.starts_with('F') => match f[1..].parse::<u8>() {
+                Ok(n) if (1..=12).contains(&n) => Key::Function(n),
+                _ => return Err(KeymapError::InvalidKey(s.to_string())),
+            },
+            _ => {
+                let mut chars = key.chars();
+                match (chars.next(), chars.next()) {
+                    // letters are uppercase only with shift
+                    (Some(c), None)
+                        if c.is_alphabetic() && modifiers.contains(KeyModifiers::SHIFT) =>
+                    {
+                        Key::Char(c.to_ascii_uppercase())
+                    }
+                    (Some(c), None) => Key::Char(c.to_ascii_lowercase()),
+                    _ => return Err(KeymapError::InvalidKey(s.to_string())),
+                }
+            }
+        };
+        Ok(Self::new(code, modifiers))
+    }
+}
+
+impl fmt::Display for KeyBinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            write!(f, "CTRL+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            write!(f, "ALT+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            write!(f, "SHIFT+")?;
+        }
+        match self.code {
+            Key::Char(' ') => write!(f, "SPACE"),
+            Key::Char(c) => write!(f, "{}", c.to_ascii_uppercase()),
+            Key::Delete => write!(f, "DEL"),
+            Key::Insert => write!(f, "INS"),
+            Key::Backspace => write!(f, "BACKSPACE"),
+            Key::Enter => write!(f, "ENTER"),
+            Key::Esc => write!(f, "ESC"),
+            Key::Tab => write!(f, "TAB"),
+            Key::Up => write!(f, "UP"),
+            Key::Down => write!(f, "DOWN"),
+            Key::Left => write!(f, "LEFT"),
+            Key::Right => write!(f, "RIGHT"),
+            Key::Home => write!(f, "HOME"),
+            Key::End => write!(f, "END"),
+            Key::PageUp => write!(f, "PGUP"),
+            Key::PageDown => write!(f, "PGDOWN"),
+            Key::Function(n) => write!(f, "F{n}"),
+            other => write!(f, "{other:?}"),
+        }
+    }
+}
+
+/// Error in the keymap file
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum KeymapError {
+    #[error("invalid key \"{0}\"")]
+    InvalidKey(String),
+    #[error("key <{0}> is bound to both \"{1}\" and \"{2}\"")]
+    DuplicateBinding(KeyBinding, &'static str, &'static str),
+    #[error("key <{0}> is reserved and can't be bound to \"{1}\"")]
+    ReservedKey(KeyBinding, &'static str),
+}
+
+/// Keys of an action in the keymap file; either a key or a list of keys
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum KeysConfig {
+    One(String),
+    Many(Vec<String>),
+}
+
+/// Content of the keymap file, which maps the action names to their keys
+pub type KeymapConfig = HashMap<String, KeysConfig>;
+
+/// Keys bound to each action of the file explorers
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Keymap {
+    bindings: HashMap<KeyAction, Vec<KeyBinding>>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self {
+            bindings: KeyAction::ALL
+                .into_iter()
+                .map(|x| (x, x.default_bindings()))
+                .collect(),
+        }
+    }
+}
+
+impl Keymap {
+    /// Make the keymap out of the keymap file. Unknown actions are ignored and missing actions keep their default keys.
+    /// Fails if a key is invalid, reserved or bound to more than one action
+    pub fn from_config(config: KeymapConfig) -> Result<Self, KeymapError> {
+        let mut keymap = Self::default();
+        for (name, keys) in config {
+            let Some(action) = KeyAction::from_name(name.as_str()) else {
+                warn!("keymap: ignoring unknown action \"{name}\"");
+                continue;
+            };
+            let keys = match keys {
+                KeysConfig::One(key) => vec![key],
+                KeysConfig::Many(keys) => keys,
+            };
+            let bindings = keys
+                .iter()
+                .map(|x| KeyBinding::from_str(x))
+                .collect::<Result<Vec<KeyBinding>, KeymapError>>()?;
+            if let Some(binding) = bindings.iter().find(|x| x.is_reserved()) {
+                return Err(KeymapError::ReservedKey(*binding, action.name()));
+            }
+            keymap.bindings.insert(action, bindings);
+        }
+        keymap.check_duplicates()?;
+        Ok(keymap)
+    }
+
+    /// Keys bound to `action`
+    pub fn bindings(&self, action: KeyAction) -> &[KeyBinding] {
+        self.bindings.get(&action).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// Get the action bound to the key event
+    pub fn action(&self, ev: &KeyEvent) -> Option<KeyAction> {
+        KeyAction::ALL
+            .into_iter()
+            .find(|x| self.bindings(*x).iter().any(|b| b.matches(ev)))
+    }
+
+    /// Format the keys bound to `action` as displayed in the keybindings popup (e.g. `<C|F5>`)
+    pub fn fmt_bindings(&self, action: KeyAction) -> String {
+        let keys: Vec<String> = self
+            .bindings(action)
+            .iter()
+            .map(KeyBinding::to_string)
+            .collect();
+        match keys.is_empty() {
+            true => String::from("<NONE>"),
+            false => format!("<{}>", keys.join("|")),
+        }
+    }
+
+    fn check_duplicates(&self) -> Result<(), KeymapError> {
+        for (i, action) in KeyAction::ALL.iter().enumerate() {
+            for binding in self.bindings(*action) {
+                let event: KeyEvent = binding.into();
+                if let Some(other) = KeyAction::ALL[i + 1..]
+                    .iter()
+                    .find(|x| self.bindings(**x).iter().any(|b| b.matches(&event)))
+                {
+                    return Err(KeymapError::DuplicateBinding(
+                        *binding,
+                        action.name(),
+                        other.name(),
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn config(entries: &[(&str, &[&str])]) -> KeymapConfig {
+        entries
+            .iter()
+            .map(|(name, keys)| {
+                (
+                    name.to_string(),
+                    KeysConfig::Many(keys.iter().map(|x| x.to_string()).collect()),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn should_parse_and_format_key_bindings() {
+        for (s, code, modifiers) in [
+            ("a", Key::Char('a'), KeyModifiers::NONE),
+            ("CTRL+U", Key::Char('u'), KeyModifiers::CONTROL),
+            ("shift+p", Key::Char('P'), KeyModifiers::SHIFT),
+            ("ALT+A", Key::Char('a'), KeyModifiers::ALT),
+            ("SPACE", Key::Char(' '), KeyModifiers::NONE),
+            ("DEL", Key::Delete, KeyModifiers::NONE),
+            ("F5", Key::Function(5), KeyModifiers::NONE),
+            ("CTRL++", Key::Char('+'), KeyModifiers::CONTROL),
+            ("/", Key::Char('/'), KeyModifiers::NONE),
+        ] {
+            let binding = KeyBinding::from_str(s).unwrap();
+            assert_eq!(binding, KeyBinding::new(code, modifiers));
+            assert_eq!(KeyBinding::from_str(&binding.to_string()).unwrap(), binding);
+        }
+        assert_eq!(
+            KeyBinding::from_str("CTRL+U").unwrap().to_string().as_str(),
+            "CTRL+U"
+        );
+        assert!(KeyBinding::from_str("HYPER+A").is_err());
+        assert!(KeyBinding::from_str("F13").is_err());
+        assert!(KeyBinding::from_str("ab").is_err());
+    }
+
+    #[test]
+    fn should_match_key_events() {
+        let keymap = Keymap::default();
+        assert_eq!(
+            keymap.action(&KeyEvent::new(Key::Char('a'), KeyModifiers::NONE)),
+            Some(KeyAction::ToggleHidden)
+        );
+        assert_eq!(
+            keymap.action(&KeyEvent::new(Key::Char('a'), KeyModifiers::CONTROL)),
+            Some(KeyAction::SelectAll)
+        );
+        assert_eq!(
+            keymap.action(&KeyEvent::new(Key::Function(5), KeyModifiers::NONE)),
+            Some(KeyAction::Copy)
+        );
+        assert_eq!(
+            keymap.action(&KeyEvent::new(Key::Char('*'), KeyModifiers::SHIFT)),
+            Some(KeyAction::ToggleFavorite)
+        );
+        assert_eq!(
+            keymap.action(&KeyEvent::new(Key::Char('q'), KeyModifiers::NONE)),
+            None
+        );
+        assert_eq!(keymap.fmt_bindings(KeyAction::Copy).as_str(), "<C|F5>");
+        assert_eq!(
+            keymap.fmt_bindings(KeyAction::ShowDelete).as_str(),
+            "<DEL|F8|E>"
+        );
+    }
+
+    #[test]
+    fn default_keymap_should_be_valid() {
+        assert!(Keymap::default().check_duplicates().is_ok());
+        assert!(KeyAction::ALL
+            .iter()
+            .flat_map(|x| x.default_bindings())
+            .all(|x| !x.is_reserved()));
+        for action in KeyAction::ALL {
+            assert_eq!(KeyAction::from_name(action.name()), Some(action));
+        }
+    }
+
+    #[test]
+    fn should_remap_keys() {
+        let keymap = Keymap::from_config(config(&[
+            ("toggle_hidden", &["CTRL+H"]),
+            ("select_all", &["ALT+S"]),
+            ("frobnicate", &["F9"]),
+        ]))
+        .unwrap();
+        assert_eq!(
+            keymap.action(&KeyEvent::new(Key::Char('h'), KeyModifiers::CONTROL)),
+            Some(KeyAction::ToggleHidden)
+        );
+        assert_eq!(
+            keymap.action(&KeyEvent::new(Key::Char('a'), KeyModifiers::NONE)),
+            None
+        );
+        assert_eq!(
+            keymap.fmt_bindings(KeyAction::SelectAll).as_str(),
+            "<ALT+S>"
+        );
+        // missing entries keep the default keys
+        assert_eq!(keymap.fmt_bindings(KeyAction::Copy).as_str(), "<C|F5>");
+    }
+
+    #[test]
+    fn should_reject_duplicate_and_reserved_keys() {
+        assert_eq!(
+            Keymap::from_config(config(&[("toggle_hidden", &["c"])])).unwrap_err(),
+            KeymapError::DuplicateBinding(KeyBinding::char('c'), "toggle_hidden", "copy")
+        );
+        assert_eq!(
+            Keymap::from_config(config(&[("reload", &["ENTER"])])).unwrap_err(),
+            KeymapError::ReservedKey(KeyBinding::new(Key::Enter, KeyModifiers::NONE), "reload")
+        );
+        assert_eq!(
+            Keymap::from_config(config(&[("reload", &["q"])]))
+                .unwrap_err()
+                .to_string()
+                .as_str(),
+            r#"key <Q> is reserved and can't be bound to "reload""#
+        );
+        assert!(Keymap::from_config(config(&[("reload", &["CTRL+ALT"])])).is_err());
+    }
+}
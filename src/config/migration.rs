@@ -0,0 +1,325 @@
+//! ## Migration
+//!
+//! `migration` converts the configuration and bookmarks files written by older versions of termscp, or edited by hand
+//! after older docs, to the current format.
+//!
+//! The known legacy shapes are:
+//!
+//! - bookmarks and recents tables missing from the bookmarks file
+//! - bookmark keys with their old names (`wrkdir` and `remote_path` for `directory`, `local_wrkdir` for `local_path`)
+//! - ports written as strings
+//! - protocol aliases (e.g. `AWS-S3` for `S3`)
+//! - mandatory configuration keys missing from the configuration file
+//!
+//! Bookmarks which still can't be read after the migration are left out, so they don't prevent the others from
+//! being loaded.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use toml::{Table, Value};
+
+use super::bookmarks::{Bookmark, UserHosts};
+use super::params::{UserConfig, UserInterfaceConfig};
+use super::serialization::{SerializerError, SerializerErrorKind};
+use crate::filetransfer::FileTransferProtocol;
+
+/// Old names of the bookmark keys, associated to their current name
+const LEGACY_BOOKMARK_KEYS: &[(&str, &str)] = &[
+    ("wrkdir", "directory"),
+    ("remote_path", "directory"),
+    ("local_wrkdir", "local_path"),
+];
+
+/// Protocol aliases, associated to the current protocol name
+const LEGACY_PROTOCOLS: &[(&str, &str)] = &[
+    ("AWS-S3", "S3"),
+    ("AWS_S3", "S3"),
+    ("AWSS3", "S3"),
+    ("SSH", "SFTP"),
+];
+
+/// Mandatory keys of the user interface configuration
+const UI_MANDATORY_KEYS: &[&str] = &["text_editor", "default_protocol", "show_hidden_files"];
+
+/// Result of a migration
+#[derive(Debug)]
+pub struct Migrated<T> {
+    pub value: T,
+    /// Description of the changes applied to the file; empty if nothing has been migrated
+    pub changes: Vec<String>,
+}
+
+/// Migrate the bookmarks file `data` to the current format
+pub fn migrate_bookmarks(data: &str) -> Result<Migrated<UserHosts>, SerializerError> {
+    let mut table = parse(data)?;
+    let mut changes = Vec::new();
+    for section in ["bookmarks", "recents"] {
+        let entries = match table
+            .entry(section)
+            .or_insert_with(|| {
+                changes.push(format!("added the missing `{section}` table"));
+                Value::Table(Table::new())
+            })
+            .as_table_mut()
+        {
+            Some(entries) => entries,
+            None => return Err(syntax_error(format!("`{section}` is not a table"))),
+        };
+        let mut left_out = Vec::new();
+        for (name, entry) in entries.iter_mut() {
+            if let Some(entry) = entry.as_table_mut() {
+                migrate_bookmark(name, entry, &mut changes);
+            }
+            if let Err(err) = Bookmark::deserialize(entry.clone()) {
+                left_out.push(name.clone());
+                changes.push(format!(
+                    "left out `{name}`, which can't be read: {}",
+                    err.message()
+                ));
+            }
+        }
+        for name in left_out {
+            entries.remove(&name);
+        }
+    }
+    Ok(Migrated {
+        value: try_into(table)?,
+        changes,
+    })
+}
+
+/// Migrate the configuration file `data` to the current format
+pub fn migrate_config(data: &str) -> Result<Migrated<UserConfig>, SerializerError> {
+    let mut table = parse(data)?;
+    let mut changes = Vec::new();
+    let defaults = Value::try_from(UserInterfaceConfig::default())
+        .map_err(|e| SerializerError::new_ex(SerializerErrorKind::Serialization, e.to_string()))?;
+    let ui = section_mut(&mut table, "user_interface", &mut changes)?;
+    for key in UI_MANDATORY_KEYS {
+        if !ui.contains_key(*key) {
+            if let Some(default) = defaults.get(*key) {
+                ui.insert(key.to_string(), default.clone());
+                changes.push(format!("set the missing `{key}` to its default"));
+            }
+        }
+    }
+    if let Some(Value::String(protocol)) = ui.get_mut("default_protocol") {
+        if let Some(current) = migrate_protocol(protocol) {
+            changes.push(format!(
+                "replaced the default protocol `{protocol}` with `{current}`"
+            ));
+            *protocol = current;
+        }
+    }
+    let remote = section_mut(&mut table, "remote", &mut changes)?;
+    if !remote.contains_key("ssh_keys") {
+        remote.insert(String::from("ssh_keys"), Value::Table(Table::new()));
+        changes.push(String::from("added the missing `ssh_keys` table"));
+    }
+    Ok(Migrated {
+        value: try_into(table)?,
+        changes,
+    })
+}
+
+/// Copy the file at `p` next to it, before overwriting it with the migrated content.
+/// The backup is named after the file with the `.bak` suffix, followed by a number if it already exists.
+/// Returns the path of the backup
+pub fn backup(p: &Path) -> io::Result<PathBuf> {
+    let name = p.file_name().unwrap_or_default().to_string_lossy();
+    let mut backup = p.with_file_name(format!("{name}.bak"));
+    let mut n = 1;
+    while backup.exists() {
+        backup = p.with_file_name(format!("{name}.bak.{n}"));
+        n += 1;
+    }
+    fs::copy(p, backup.as_path())?;
+    Ok(backup)
+}
+
+/// Migrate the bookmark `name`
+fn migrate_bookmark(name: &str, entry: &mut Table, changes: &mut Vec<String>) {
+    for (old, new) in LEGACY_BOOKMARK_KEYS {
+        if entry.contains_key(*new) {
+            continue;
+        }
+        if let Some(value) = entry.remove(*old) {
+            entry.insert(new.to_string(), value);
+            changes.push(format!("renamed `{old}` of `{name}` to `{new}`"));
+        }
+    }
+    if let Some(Value::String(port)) = entry.get("port") {
+        if let Ok(port) = port.trim().parse::<u16>() {
+            entry.insert(String::from("port"), Value::Integer(port.into()));
+            changes.push(format!("converted the port of `{name}` to a number"));
+        }
+    }
+    if let Some(Value::String(protocol)) = entry.get_mut("protocol") {
+        if let Some(current) = migrate_protocol(protocol) {
+            changes.push(format!(
+                "replaced the protocol `{protocol}` of `{name}` with `{current}`"
+            ));
+            *protocol = current;
+        }
+    }
+}
+
+/// Get the current name of `protocol`, if it's a legacy alias
+fn migrate_protocol(protocol: &str) -> Option<String> {
+    if FileTransferProtocol::from_str(protocol).is_ok() {
+        return None;
+    }
+    LEGACY_PROTOCOLS
+        .iter()
+        .find(|(alias, _)| alias.eq_ignore_ascii_case(protocol.trim()))
+        .map(|(_, current)| current.to_string())
+}
+
+/// Get the table `name` of `table`, adding it if missing
+fn section_mut<'a>(
+    table: &'a mut Table,
+    name: &str,
+    changes: &mut Vec<String>,
+) -> Result<&'a mut Table, SerializerError> {
+    table
+        .entry(name)
+        .or_insert_with(|| {
+            changes.push(format!("added the missing `{name}` table"));
+            Value::Table(Table::new())
+        })
+        .as_table_mut()
+        .ok_or_else(|| syntax_error(format!("`{name}` is not a table")))
+}
+
+fn parse(data: &str) -> Result<Table, SerializerError> {
+    data.parse::<Table>()
+        .map_err(|e| syntax_error(e.to_string()))
+}
+
+fn try_into<T: DeserializeOwned>(table: Table) -> Result<T, SerializerError> {
+    table.try_into().map_err(|e| syntax_error(e.to_string()))
+}
+
+fn syntax_error(msg: String) -> SerializerError {
+    SerializerError::new_ex(SerializerErrorKind::Syntax, msg)
+}
+
+#[cfg(test)]
+mod test {
+
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn should_load_bookmarks_v0_2() {
+        let migrated = migrate_bookmarks(include_str!("fixtures/bookmarks_v0_2.toml")).unwrap();
+        assert!(migrated.changes.is_empty());
+        let hosts = migrated.value;
+        assert_eq!(hosts.bookmarks.len(), 2);
+        assert_eq!(hosts.recents.len(), 1);
+        let raspberry = hosts.bookmarks.get("raspberry").unwrap();
+        assert_eq!(raspberry.protocol, FileTransferProtocol::Sftp);
+        assert_eq!(raspberry.address.as_deref(), Some("192.168.1.31"));
+        assert_eq!(raspberry.port, Some(22));
+        assert_eq!(raspberry.username.as_deref(), Some("pi"));
+        assert!(raspberry.remote_path.is_none());
+        assert_eq!(
+            hosts.bookmarks.get("nas").unwrap().protocol,
+            FileTransferProtocol::Ftp(false)
+        );
+    }
+
+    #[test]
+    fn should_migrate_hand_edited_bookmarks() {
+        let migrated =
+            migrate_bookmarks(include_str!("fixtures/bookmarks_hand_edited.toml")).unwrap();
+        let hosts = migrated.value;
+        assert!(hosts.recents.is_empty());
+        assert_eq!(hosts.bookmarks.len(), 2);
+        let raspberry = hosts.bookmarks.get("raspberry").unwrap();
+        assert_eq!(raspberry.protocol, FileTransferProtocol::Sftp);
+        assert_eq!(raspberry.port, Some(22));
+        assert_eq!(raspberry.remote_path, Some(PathBuf::from("/home/pi")));
+        assert_eq!(raspberry.local_path, Some(PathBuf::from("/home/omar/pi")));
+        let bucket = hosts.bookmarks.get("bucket").unwrap();
+        assert_eq!(bucket.protocol, FileTransferProtocol::AwsS3);
+        assert_eq!(bucket.s3.as_ref().unwrap().bucket, "omar-docs");
+        assert!(!hosts.bookmarks.contains_key("broken"));
+        assert_eq!(
+            migrated.changes,
+            vec![
+                "left out `broken`, which can't be read: gopher".to_string(),
+                "replaced the protocol `AWS-S3` of `bucket` with `S3`".to_string(),
+                "renamed `wrkdir` of `raspberry` to `directory`".to_string(),
+                "renamed `local_wrkdir` of `raspberry` to `local_path`".to_string(),
+                "converted the port of `raspberry` to a number".to_string(),
+                "added the missing `recents` table".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn should_load_config_v0_3() {
+        let migrated = migrate_config(include_str!("fixtures/config_v0_3.toml")).unwrap();
+        assert!(migrated.changes.is_empty());
+        let config = migrated.value;
+        assert_eq!(config.user_interface.text_editor, PathBuf::from("vim"));
+        assert_eq!(config.user_interface.default_protocol, "SFTP");
+        assert_eq!(config.user_interface.group_dirs.as_deref(), Some("first"));
+        assert!(config.user_interface.check_for_updates.is_none());
+        assert_eq!(
+            config.remote.ssh_keys.get("pi@192.168.1.31"),
+            Some(&PathBuf::from(
+                "/home/omar/.config/termscp/.ssh/pi@192.168.1.31.key"
+            ))
+        );
+    }
+
+    #[test]
+    fn should_migrate_config_missing_tables() {
+        let migrated = migrate_config(include_str!("fixtures/config_missing_tables.toml")).unwrap();
+        let config = migrated.value;
+        assert_eq!(config.user_interface.default_protocol, "S3");
+        assert_eq!(config.user_interface.show_hidden_files, false);
+        assert_eq!(config.user_interface.group_dirs.as_deref(), Some("last"));
+        assert!(config.remote.ssh_keys.is_empty());
+        assert_eq!(
+            migrated.changes,
+            vec![
+                "set the missing `text_editor` to its default".to_string(),
+                "set the missing `show_hidden_files` to its default".to_string(),
+                "replaced the default protocol `aws_s3` with `S3`".to_string(),
+                "added the missing `remote` table".to_string(),
+                "added the missing `ssh_keys` table".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn should_not_migrate_bad_syntax() {
+        assert!(migrate_bookmarks("bookmarks = [").is_err());
+        assert!(migrate_bookmarks("bookmarks = 1").is_err());
+        assert!(migrate_config("remote = \"none\"").is_err());
+    }
+
+    #[test]
+    fn should_backup_file() {
+        let tmp_dir = TempDir::new().unwrap();
+        let file = tmp_dir.path().join("bookmarks.toml");
+        fs::write(file.as_path(), "[bookmarks]").unwrap();
+        let first = backup(file.as_path()).unwrap();
+        assert_eq!(first, tmp_dir.path().join("bookmarks.toml.bak"));
+        assert_eq!(fs::read_to_string(first).unwrap(), "[bookmarks]");
+        assert_eq!(
+            backup(file.as_path()).unwrap(),
+            tmp_dir.path().join("bookmarks.toml.bak.1")
+        );
+    }
+}
@@ -5,6 +5,9 @@
 // export
 
 pub mod bookmarks;
+pub mod keymap;
+pub mod migration;
 pub mod params;
 pub mod serialization;
 pub mod themes;
+pub mod validation;
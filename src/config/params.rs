@@ -12,8 +12,26 @@ use serde::{Deserialize, Serialize};
 use crate::filetransfer::FileTransferProtocol;
 
 pub const DEFAULT_NOTIFICATION_TRANSFER_THRESHOLD: u64 = 536870912; // 512MB
+pub const DEFAULT_TRANSFER_PROMPT_FILES_THRESHOLD: u64 = 10000;
+pub const DEFAULT_TRANSFER_PROMPT_SIZE_THRESHOLD: u64 = 10737418240; // 10GB
+pub const DEFAULT_DELETE_CONFIRM_NAME_THRESHOLD: u64 = 5000;
+pub const DEFAULT_TRANSFER_CONCURRENCY: u64 = 1;
+pub const MAX_TRANSFER_CONCURRENCY: u64 = 16;
+pub const DEFAULT_TRANSFER_BUFFER_KB: u64 = 256;
+pub const MAX_TRANSFER_BUFFER_KB: u64 = 16384;
+pub const DEFAULT_DIRSTACK_SIZE: u64 = 16;
+pub const MAX_DIRSTACK_SIZE: u64 = 256;
+pub const DEFAULT_RECONNECT_RETRIES: u64 = 3;
+pub const MAX_RECONNECT_RETRIES: u64 = 10;
+pub const DEFAULT_PARKED_SESSION_TIMEOUT: u64 = 300;
+pub const DEFAULT_LOG_MESSAGE_MAX_LEN: u64 = 4096;
+pub const DEFAULT_LOG_MAX_BYTES: u64 = 1048576; // 1MB
+pub const DEFAULT_FIND_SPILL_THRESHOLD: u64 = 50000;
+pub const DEFAULT_REMOTE_WATCHER_INTERVAL: u64 = 30;
+pub const DEFAULT_RECENTS_SIZE: u64 = 16;
+pub const DEFAULT_SSH_KNOWN_HOSTS: &str = "~/.ssh/known_hosts";
 
-#[derive(Deserialize, Serialize, Debug, Default)]
+#[derive(Clone, Deserialize, Serialize, Debug, Default)]
 /// UserConfig contains all the configurations for the user,
 /// supported by termscp
 pub struct UserConfig {
@@ -21,7 +39,7 @@ pub struct UserConfig {
     pub remote: RemoteConfig,
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Clone, Deserialize, Serialize, Debug)]
 /// UserInterfaceConfig provides all the keys to configure the user interface
 pub struct UserInterfaceConfig {
     pub text_editor: PathBuf,
@@ -35,13 +53,85 @@ pub struct UserInterfaceConfig {
     pub remote_file_fmt: Option<String>,     // @! Since 0.5.0
     pub notifications: Option<bool>,         // @! Since 0.7.0; Default true
     pub notification_threshold: Option<u64>, // @! Since 0.7.0; Default 512MB
+    /// if set, transfers lasting at least this amount of seconds are notified whatever their size
+    pub notification_min_duration_secs: Option<u64>, // @! Since 0.17.0; Default None
+    /// if true, transfers are notified only while the terminal is not focused
+    pub notification_unfocused_only: Option<bool>, // @! Since 0.17.0; Default false
+    /// if true, remote files are never staged into a local temporary directory
+    pub disable_cache: Option<bool>, // @! Since 0.17.0; Default false
+    /// if true, bookmarks are probed for reachability when the auth activity starts
+    pub check_bookmarks_health: Option<bool>, // @! Since 0.17.0; Default false
+    /// events on which the terminal bell is rung (off, on_completion, on_error, both)
+    pub terminal_bell: Option<String>, // @! Since 0.17.0; Default off
+    /// amount of files above which the user is asked to confirm a recursive transfer (0 to disable)
+    pub transfer_prompt_files_threshold: Option<u64>, // @! Since 0.17.0; Default 10000
+    /// size in bytes above which the user is asked to confirm a recursive transfer (0 to disable)
+    pub transfer_prompt_size_threshold: Option<u64>, // @! Since 0.17.0; Default 10GB
+    /// amount of entries above which the user has to type the directory name to confirm its deletion (0 to disable)
+    pub delete_confirm_name_threshold: Option<u64>, // @! Since 0.17.0; Default 5000
+    /// amount of files transferred in parallel by recursive and multiple transfers, each one on its own connection
+    pub transfer_concurrency: Option<u64>, // @! Since 0.17.0; Default 1
+    /// size in KiB of the buffer used to copy the streams of transfers
+    pub transfer_buffer_kb: Option<u64>, // @! Since 0.17.0; Default 256
+    /// component focused when the auth activity starts (form, bookmarks, recents)
+    pub startup_focus: Option<String>, // @! Since 0.17.0; Default form
+    /// amount of previous directories remembered by each explorer
+    pub dirstack_size: Option<u64>, // @! Since 0.17.0; Default 16
+    /// if true, local files are moved to the trash instead of being deleted permanently
+    pub delete_to_trash: Option<bool>, // @! Since 0.17.0; Default false
+    /// if true, symlinks are re-created as links on the destination of transfers, instead of being followed
+    pub preserve_symlinks: Option<bool>, // @! Since 0.17.0; Default false
+    /// if true, the modification time, access time and mode of the source are applied to the transferred entries
+    pub preserve_metadata: Option<bool>, // @! Since 0.17.0; Default true
+    /// if true, hidden files and sorting toggles are applied to both explorer panels
+    pub lock_panels_view: Option<bool>, // @! Since 0.17.0; Default false
+    /// length in characters above which the messages of the log panel are truncated (0 to disable)
+    pub log_message_max_len: Option<u64>, // @! Since 0.17.0; Default 4096
+    /// total size in bytes of the messages kept by the log panel (0 to disable)
+    pub log_max_bytes: Option<u64>, // @! Since 0.17.0; Default 1MB
+    /// amount of find results kept in memory; the next ones are written to the cache directory (0 to disable)
+    pub find_spill_threshold: Option<u64>, // @! Since 0.17.0; Default 50000
+    /// how the filter popup matches file names when no `re:` or `glob:` prefix is given (glob, regex)
+    pub filter_mode: Option<String>, // @! Since 0.17.0; Default glob
+    /// if true, the filter is applied to the panel and kept across directory changes, until it's cleared
+    pub keep_filter: Option<bool>, // @! Since 0.17.0; Default false
+    /// if true, the diff of an edited remote file is shown, to be confirmed before uploading it
+    pub confirm_edit_upload_with_diff: Option<bool>, // @! Since 0.17.0; Default false
+    /// patterns of the files whose changes aren't synched by the file watcher
+    pub watcher_ignore: Option<Vec<String>>, // @! Since 0.17.0; Default .git/, *.swp, *~, .DS_Store
+    /// seconds between the polls of the remote directories whose changes are downloaded by the file watcher
+    pub remote_watcher_interval: Option<u64>, // @! Since 0.17.0; Default 30
+    /// maximum amount of recent connections kept; the least recent ones are removed first
+    pub recents_size: Option<u64>, // @! Since 0.17.0; Default 16
+    /// if true, an asterisk is echoed for each character typed in the password prompt
+    pub password_prompt_asterisks: Option<bool>, // @! Since 0.17.0; Default false
+    /// if true, the mouse is captured by the file transfer activity, instead of selecting the terminal text
+    pub mouse_capture: Option<bool>, // @! Since 0.17.0; Default false
 }
 
-#[derive(Deserialize, Serialize, Debug)]
+#[derive(Clone, Deserialize, Serialize, Debug)]
 /// Contains configuratio related to remote hosts
 pub struct RemoteConfig {
     /// Ssh configuration path. If NONE, won't be read
     pub ssh_config: Option<String>,
+    /// if true, ssh sessions are compressed
+    pub ssh_compression: Option<bool>, // @! Since 0.17.0; Default false
+    /// preferred ciphers of ssh sessions, as a comma separated list
+    pub ssh_ciphers: Option<String>, // @! Since 0.17.0
+    /// attempts to reconnect to the remote when the connection is lost; 0 disables the reconnection
+    pub reconnect_retries: Option<u64>, // @! Since 0.17.0; Default 3
+    /// if true, WebDAV uploads lock the destination file and don't overwrite files changed on the server
+    pub webdav_lock_uploads: Option<bool>, // @! Since 0.17.0; Default false
+    /// seconds a session kept connected, when returning to the authentication form, stays open; 0 disables it
+    pub parked_session_timeout: Option<u64>, // @! Since 0.17.0; Default 300
+    /// known hosts file the host keys of ssh servers are verified against, and where the accepted ones are saved
+    pub ssh_known_hosts: Option<String>, // @! Since 0.17.0; Default ~/.ssh/known_hosts
+    /// if true, the host keys of ssh servers are accepted without being verified
+    pub ssh_accept_any_host_key: Option<bool>, // @! Since 0.17.0; Default false
+    /// mode, in octal, applied to the files uploaded to the remote host; if unset, the mode isn't changed
+    pub default_file_mode: Option<String>, // @! Since 0.17.0
+    /// mode, in octal, applied to the directories created on the remote host; if unset, the mode isn't changed
+    pub default_dir_mode: Option<String>, // @! Since 0.17.0
     /// Association between host name and path to private key
     /// NOTE: this parameter must stay as last: <https://github.com/alexcrichton/toml-rs/issues/142>
     pub ssh_keys: HashMap<String, PathBuf>,
@@ -62,6 +152,15 @@ impl Default for RemoteConfig {
 
         Self {
             ssh_config: ssh_config_path,
+            ssh_compression: None,
+            ssh_ciphers: None,
+            reconnect_retries: Some(DEFAULT_RECONNECT_RETRIES),
+            webdav_lock_uploads: Some(false),
+            parked_session_timeout: Some(DEFAULT_PARKED_SESSION_TIMEOUT),
+            ssh_known_hosts: Some(DEFAULT_SSH_KNOWN_HOSTS.to_string()),
+            ssh_accept_any_host_key: Some(false),
+            default_file_mode: None,
+            default_dir_mode: None,
             ssh_keys: HashMap::default(),
         }
     }
@@ -83,6 +182,33 @@ impl Default for UserInterfaceConfig {
             remote_file_fmt: None,
             notifications: Some(true),
             notification_threshold: Some(DEFAULT_NOTIFICATION_TRANSFER_THRESHOLD),
+            notification_min_duration_secs: None,
+            notification_unfocused_only: Some(false),
+            disable_cache: Some(false),
+            check_bookmarks_health: Some(false),
+            terminal_bell: None,
+            transfer_prompt_files_threshold: Some(DEFAULT_TRANSFER_PROMPT_FILES_THRESHOLD),
+            transfer_prompt_size_threshold: Some(DEFAULT_TRANSFER_PROMPT_SIZE_THRESHOLD),
+            delete_confirm_name_threshold: Some(DEFAULT_DELETE_CONFIRM_NAME_THRESHOLD),
+            transfer_concurrency: Some(DEFAULT_TRANSFER_CONCURRENCY),
+            transfer_buffer_kb: Some(DEFAULT_TRANSFER_BUFFER_KB),
+            startup_focus: None,
+            dirstack_size: Some(DEFAULT_DIRSTACK_SIZE),
+            delete_to_trash: Some(false),
+            preserve_symlinks: Some(false),
+            preserve_metadata: Some(true),
+            lock_panels_view: Some(false),
+            log_message_max_len: Some(DEFAULT_LOG_MESSAGE_MAX_LEN),
+            log_max_bytes: Some(DEFAULT_LOG_MAX_BYTES),
+            find_spill_threshold: Some(DEFAULT_FIND_SPILL_THRESHOLD),
+            filter_mode: None,
+            keep_filter: Some(false),
+            confirm_edit_upload_with_diff: Some(false),
+            watcher_ignore: None,
+            remote_watcher_interval: Some(DEFAULT_REMOTE_WATCHER_INTERVAL),
+            recents_size: Some(DEFAULT_RECENTS_SIZE),
+            password_prompt_asterisks: Some(false),
+            mouse_capture: Some(false),
         }
     }
 }
@@ -106,6 +232,15 @@ mod tests {
         let remote: RemoteConfig = RemoteConfig {
             ssh_keys: keys,
             ssh_config: Some(String::from("~/.ssh/config")),
+            ssh_compression: Some(true),
+            ssh_ciphers: None,
+            reconnect_retries: Some(DEFAULT_RECONNECT_RETRIES),
+            webdav_lock_uploads: Some(false),
+            parked_session_timeout: Some(DEFAULT_PARKED_SESSION_TIMEOUT),
+            ssh_known_hosts: Some(String::from("~/.ssh/known_hosts")),
+            ssh_accept_any_host_key: Some(false),
+            default_file_mode: None,
+            default_dir_mode: None,
         };
         let ui: UserInterfaceConfig = UserInterfaceConfig {
             default_protocol: String::from("SFTP"),
@@ -118,6 +253,33 @@ mod tests {
             remote_file_fmt: Some(String::from("{USER}")),
             notifications: Some(true),
             notification_threshold: Some(DEFAULT_NOTIFICATION_TRANSFER_THRESHOLD),
+            notification_min_duration_secs: None,
+            notification_unfocused_only: Some(false),
+            disable_cache: Some(false),
+            check_bookmarks_health: Some(false),
+            terminal_bell: None,
+            transfer_prompt_files_threshold: Some(DEFAULT_TRANSFER_PROMPT_FILES_THRESHOLD),
+            transfer_prompt_size_threshold: Some(DEFAULT_TRANSFER_PROMPT_SIZE_THRESHOLD),
+            delete_confirm_name_threshold: Some(DEFAULT_DELETE_CONFIRM_NAME_THRESHOLD),
+            transfer_concurrency: Some(DEFAULT_TRANSFER_CONCURRENCY),
+            transfer_buffer_kb: Some(DEFAULT_TRANSFER_BUFFER_KB),
+            startup_focus: None,
+            dirstack_size: Some(DEFAULT_DIRSTACK_SIZE),
+            delete_to_trash: Some(false),
+            preserve_symlinks: Some(false),
+            preserve_metadata: Some(true),
+            lock_panels_view: Some(false),
+            log_message_max_len: Some(DEFAULT_LOG_MESSAGE_MAX_LEN),
+            log_max_bytes: Some(DEFAULT_LOG_MAX_BYTES),
+            find_spill_threshold: Some(DEFAULT_FIND_SPILL_THRESHOLD),
+            filter_mode: None,
+            keep_filter: Some(false),
+            confirm_edit_upload_with_diff: Some(false),
+            watcher_ignore: None,
+            remote_watcher_interval: Some(DEFAULT_REMOTE_WATCHER_INTERVAL),
+            recents_size: Some(DEFAULT_RECENTS_SIZE),
+            password_prompt_asterisks: Some(false),
+            mouse_capture: Some(false),
         };
         assert_eq!(ui.default_protocol, String::from("SFTP"));
         assert_eq!(ui.text_editor, PathBuf::from("nano"));
@@ -157,5 +319,51 @@ mod tests {
             cfg.user_interface.notification_threshold,
             Some(DEFAULT_NOTIFICATION_TRANSFER_THRESHOLD)
         );
+        assert!(cfg.user_interface.notification_min_duration_secs.is_none());
+        assert_eq!(cfg.user_interface.notification_unfocused_only, Some(false));
+        assert_eq!(cfg.user_interface.disable_cache, Some(false));
+        assert_eq!(cfg.user_interface.check_bookmarks_health, Some(false));
+        assert!(cfg.user_interface.terminal_bell.is_none());
+        assert_eq!(
+            cfg.user_interface.transfer_prompt_files_threshold,
+            Some(DEFAULT_TRANSFER_PROMPT_FILES_THRESHOLD)
+        );
+        assert_eq!(
+            cfg.user_interface.transfer_prompt_size_threshold,
+            Some(DEFAULT_TRANSFER_PROMPT_SIZE_THRESHOLD)
+        );
+        assert_eq!(
+            cfg.user_interface.delete_confirm_name_threshold,
+            Some(DEFAULT_DELETE_CONFIRM_NAME_THRESHOLD)
+        );
+        assert_eq!(
+            cfg.user_interface.transfer_concurrency,
+            Some(DEFAULT_TRANSFER_CONCURRENCY)
+        );
+        assert_eq!(
+            cfg.user_interface.transfer_buffer_kb,
+            Some(DEFAULT_TRANSFER_BUFFER_KB)
+        );
+        assert!(cfg.user_interface.startup_focus.is_none());
+        assert_eq!(
+            cfg.user_interface.dirstack_size,
+            Some(DEFAULT_DIRSTACK_SIZE)
+        );
+        assert_eq!(cfg.user_interface.delete_to_trash, Some(false));
+        assert_eq!(cfg.user_interface.preserve_symlinks, Some(false));
+        assert_eq!(cfg.user_interface.preserve_metadata, Some(true));
+        assert_eq!(cfg.user_interface.lock_panels_view, Some(false));
+        assert_eq!(
+            cfg.user_interface.log_message_max_len,
+            Some(DEFAULT_LOG_MESSAGE_MAX_LEN)
+        );
+        assert_eq!(
+            cfg.user_interface.log_max_bytes,
+            Some(DEFAULT_LOG_MAX_BYTES)
+        );
+        assert_eq!(
+            cfg.user_interface.find_spill_threshold,
+            Some(DEFAULT_FIND_SPILL_THRESHOLD)
+        );
     }
 }
@@ -115,9 +115,13 @@ mod tests {
     use tuirealm::ratatui::style::Color;
 
     use super::*;
-    use crate::config::bookmarks::{Bookmark, KubeParams, S3Params, SmbParams, UserHosts};
+    use crate::config::bookmarks::{
+        Bookmark, BookmarksState, ConnectionFailure, DirectoryBookmark, KubeParams, S3Params,
+        SmbParams, UserHosts,
+    };
     use crate::config::params::UserConfig;
     use crate::config::themes::Theme;
+    use crate::filetransfer::params::{QuickCommand, UrlMapping};
     use crate::filetransfer::FileTransferProtocol;
     use crate::utils::test_helpers::create_file_ioers;
 
@@ -171,12 +175,81 @@ mod tests {
         assert_eq!(cfg.user_interface.prompt_on_file_replace.unwrap(), false);
         assert_eq!(cfg.user_interface.notifications.unwrap(), false);
         assert_eq!(cfg.user_interface.notification_threshold.unwrap(), 1024);
+        assert_eq!(
+            cfg.user_interface.notification_min_duration_secs.unwrap(),
+            120
+        );
+        assert_eq!(
+            cfg.user_interface.notification_unfocused_only.unwrap(),
+            true
+        );
+        assert_eq!(cfg.user_interface.disable_cache.unwrap(), true);
+        assert_eq!(cfg.user_interface.check_bookmarks_health.unwrap(), true);
+        assert_eq!(
+            cfg.user_interface.terminal_bell,
+            Some(String::from("on_error"))
+        );
+        assert_eq!(
+            cfg.user_interface.transfer_prompt_files_threshold.unwrap(),
+            5000
+        );
+        assert_eq!(
+            cfg.user_interface.transfer_prompt_size_threshold.unwrap(),
+            0
+        );
+        assert_eq!(
+            cfg.user_interface.delete_confirm_name_threshold.unwrap(),
+            100
+        );
+        assert_eq!(cfg.user_interface.transfer_concurrency.unwrap(), 4);
+        assert_eq!(cfg.user_interface.transfer_buffer_kb.unwrap(), 1024);
+        assert_eq!(cfg.user_interface.dirstack_size.unwrap(), 32);
+        assert_eq!(cfg.user_interface.delete_to_trash.unwrap(), true);
+        assert_eq!(cfg.user_interface.preserve_symlinks.unwrap(), true);
+        assert_eq!(cfg.user_interface.preserve_metadata.unwrap(), false);
+        assert_eq!(cfg.user_interface.lock_panels_view.unwrap(), true);
+        assert_eq!(cfg.user_interface.log_message_max_len.unwrap(), 256);
+        assert_eq!(cfg.user_interface.log_max_bytes.unwrap(), 65536);
+        assert_eq!(cfg.user_interface.find_spill_threshold.unwrap(), 0);
+        assert_eq!(cfg.user_interface.filter_mode.as_deref(), Some("regex"));
+        assert_eq!(cfg.user_interface.keep_filter.unwrap(), true);
+        assert_eq!(
+            cfg.user_interface.confirm_edit_upload_with_diff.unwrap(),
+            true
+        );
+        assert_eq!(
+            cfg.user_interface.watcher_ignore.as_deref().unwrap(),
+            &[".git/", "node_modules/", "*.swp"]
+        );
+        assert_eq!(cfg.user_interface.remote_watcher_interval, Some(10));
+        assert_eq!(cfg.user_interface.recents_size, Some(32));
+        assert_eq!(cfg.user_interface.password_prompt_asterisks, Some(true));
+        assert_eq!(cfg.user_interface.mouse_capture, Some(true));
+        assert_eq!(
+            cfg.user_interface.startup_focus,
+            Some(String::from("bookmarks"))
+        );
         assert_eq!(cfg.user_interface.group_dirs, Some(String::from("last")));
         // Remote
         assert_eq!(
             cfg.remote.ssh_config.as_deref(),
             Some("/home/omar/.ssh/config")
         );
+        assert_eq!(cfg.remote.ssh_compression, Some(true));
+        assert_eq!(
+            cfg.remote.ssh_ciphers.as_deref(),
+            Some("aes256-gcm@openssh.com,aes256-ctr")
+        );
+        assert_eq!(cfg.remote.reconnect_retries, Some(5));
+        assert_eq!(cfg.remote.webdav_lock_uploads, Some(true));
+        assert_eq!(cfg.remote.parked_session_timeout, Some(60));
+        assert_eq!(
+            cfg.remote.ssh_known_hosts.as_deref(),
+            Some("/home/omar/.ssh/known_hosts")
+        );
+        assert_eq!(cfg.remote.ssh_accept_any_host_key, Some(true));
+        assert_eq!(cfg.remote.default_file_mode.as_deref(), Some("0664"));
+        assert_eq!(cfg.remote.default_dir_mode.as_deref(), Some("2775"));
         assert_eq!(
             cfg.user_interface.file_fmt,
             Some(String::from("{NAME} {PEX}"))
@@ -224,7 +297,43 @@ mod tests {
         assert!(cfg.user_interface.remote_file_fmt.is_none());
         assert!(cfg.user_interface.notifications.is_none());
         assert!(cfg.user_interface.notification_threshold.is_none());
+        assert!(cfg.user_interface.notification_min_duration_secs.is_none());
+        assert!(cfg.user_interface.notification_unfocused_only.is_none());
+        assert!(cfg.user_interface.disable_cache.is_none());
+        assert!(cfg.user_interface.check_bookmarks_health.is_none());
+        assert!(cfg.user_interface.terminal_bell.is_none());
+        assert!(cfg.user_interface.transfer_prompt_files_threshold.is_none());
+        assert!(cfg.user_interface.transfer_prompt_size_threshold.is_none());
+        assert!(cfg.user_interface.delete_confirm_name_threshold.is_none());
+        assert!(cfg.user_interface.transfer_concurrency.is_none());
+        assert!(cfg.user_interface.transfer_buffer_kb.is_none());
+        assert!(cfg.user_interface.startup_focus.is_none());
+        assert!(cfg.user_interface.dirstack_size.is_none());
+        assert!(cfg.user_interface.delete_to_trash.is_none());
+        assert!(cfg.user_interface.preserve_symlinks.is_none());
+        assert!(cfg.user_interface.preserve_metadata.is_none());
+        assert!(cfg.user_interface.lock_panels_view.is_none());
+        assert!(cfg.user_interface.log_message_max_len.is_none());
+        assert!(cfg.user_interface.log_max_bytes.is_none());
+        assert!(cfg.user_interface.find_spill_threshold.is_none());
+        assert!(cfg.user_interface.filter_mode.is_none());
+        assert!(cfg.user_interface.keep_filter.is_none());
+        assert!(cfg.user_interface.confirm_edit_upload_with_diff.is_none());
+        assert!(cfg.user_interface.watcher_ignore.is_none());
+        assert!(cfg.user_interface.remote_watcher_interval.is_none());
+        assert!(cfg.user_interface.recents_size.is_none());
+        assert!(cfg.user_interface.password_prompt_asterisks.is_none());
+        assert!(cfg.user_interface.mouse_capture.is_none());
         assert!(cfg.remote.ssh_config.is_none());
+        assert!(cfg.remote.ssh_compression.is_none());
+        assert!(cfg.remote.ssh_ciphers.is_none());
+        assert!(cfg.remote.reconnect_retries.is_none());
+        assert!(cfg.remote.webdav_lock_uploads.is_none());
+        assert!(cfg.remote.parked_session_timeout.is_none());
+        assert!(cfg.remote.ssh_known_hosts.is_none());
+        assert!(cfg.remote.ssh_accept_any_host_key.is_none());
+        assert!(cfg.remote.default_file_mode.is_none());
+        assert!(cfg.remote.default_dir_mode.is_none());
         // Verify keys
         assert_eq!(
             *cfg.remote
@@ -302,9 +411,45 @@ mod tests {
         remote_file_fmt = "{NAME} {USER}"
         notifications = false
         notification_threshold = 1024
+        notification_min_duration_secs = 120
+        notification_unfocused_only = true
+        disable_cache = true
+        check_bookmarks_health = true
+        terminal_bell = "on_error"
+        transfer_prompt_files_threshold = 5000
+        transfer_prompt_size_threshold = 0
+        delete_confirm_name_threshold = 100
+        transfer_concurrency = 4
+        transfer_buffer_kb = 1024
+        startup_focus = "bookmarks"
+        dirstack_size = 32
+        delete_to_trash = true
+        preserve_symlinks = true
+        preserve_metadata = false
+        lock_panels_view = true
+        log_message_max_len = 256
+        log_max_bytes = 65536
+        find_spill_threshold = 0
+        filter_mode = "regex"
+        keep_filter = true
+        confirm_edit_upload_with_diff = true
+        watcher_ignore = [".git/", "node_modules/", "*.swp"]
+        remote_watcher_interval = 10
+        recents_size = 32
+        password_prompt_asterisks = true
+        mouse_capture = true
 
         [remote]
         ssh_config = "/home/omar/.ssh/config"
+        ssh_compression = true
+        ssh_ciphers = "aes256-gcm@openssh.com,aes256-ctr"
+        reconnect_retries = 5
+        webdav_lock_uploads = true
+        parked_session_timeout = 60
+        ssh_known_hosts = "/home/omar/.ssh/known_hosts"
+        ssh_accept_any_host_key = true
+        default_file_mode = "0664"
+        default_dir_mode = "2775"
 
         [remote.ssh_keys]
         "192.168.1.31" = "/home/omar/.ssh/raspberry.key"
@@ -383,7 +528,28 @@ mod tests {
             host.remote_path.as_deref().unwrap(),
             std::path::Path::new("/tmp")
         );
+        assert_eq!(host.remote_shell.as_deref().unwrap(), "/bin/bash -lc");
+        assert_eq!(
+            host.quick_commands.as_deref().unwrap(),
+            &[
+                QuickCommand::new("Tail", "tail -n 100 {selected_path}"),
+                QuickCommand::new("Uptime", "uptime"),
+            ]
+        );
+        assert_eq!(
+            host.url_mappings.as_deref().unwrap(),
+            &[UrlMapping::new("/var/www/html", "https://example.com")]
+        );
+        assert_eq!(host.file_sorting.as_deref().unwrap(), "by_mtime");
+        assert_eq!(host.group_dirs.as_deref().unwrap(), "first");
+        assert_eq!(host.show_hidden_files, Some(true));
         let host: &Bookmark = hosts.bookmarks.get("aws-server-prod1").unwrap();
+        assert!(host.remote_shell.is_none());
+        assert!(host.quick_commands.is_none());
+        assert!(host.url_mappings.is_none());
+        assert!(host.file_sorting.is_none());
+        assert!(host.group_dirs.is_none());
+        assert!(host.show_hidden_files.is_none());
         assert_eq!(host.address.as_deref().unwrap(), "51.23.67.12");
         assert_eq!(host.port.unwrap(), 21);
         assert_eq!(host.protocol, FileTransferProtocol::Ftp(true));
@@ -404,6 +570,13 @@ mod tests {
         assert_eq!(s3.access_key.as_deref().unwrap(), "pippo");
         assert_eq!(s3.secret_access_key.as_deref().unwrap(), "pluto");
         assert_eq!(s3.new_path_style.unwrap(), true);
+        assert_eq!(s3.requester_pays.unwrap(), true);
+        assert_eq!(s3.storage_class.as_deref().unwrap(), "STANDARD_IA");
+        assert_eq!(s3.accept_invalid_certs.unwrap(), true);
+        assert_eq!(
+            s3.ca_bundle.as_deref().unwrap(),
+            std::path::Path::new("/etc/ssl/minio.pem")
+        );
         // Kube pod
         let host: &Bookmark = hosts.bookmarks.get("pod").unwrap();
         assert_eq!(host.address, None);
@@ -456,6 +629,19 @@ mod tests {
                 password: None,
                 remote_path: None,
                 local_path: None,
+                remote_shell: None,
+                quick_commands: None,
+                url_mappings: None,
+                file_sorting: None,
+                group_dirs: None,
+                show_hidden_files: None,
+                ssh_compression: None,
+                ssh_ciphers: None,
+                default_file_mode: None,
+                default_dir_mode: None,
+                ftp_active_mode: None,
+                ftp_tls_session_reuse: None,
+                group: None,
                 kube: None,
                 s3: None,
                 smb: None,
@@ -471,6 +657,19 @@ mod tests {
                 password: Some(String::from("password")),
                 remote_path: Some(PathBuf::from("/tmp")),
                 local_path: Some(PathBuf::from("/usr")),
+                remote_shell: None,
+                quick_commands: None,
+                url_mappings: None,
+                file_sorting: None,
+                group_dirs: None,
+                show_hidden_files: None,
+                ssh_compression: None,
+                ssh_ciphers: None,
+                default_file_mode: None,
+                default_dir_mode: None,
+                ftp_active_mode: None,
+                ftp_tls_session_reuse: None,
+                group: None,
                 kube: None,
                 s3: None,
                 smb: None,
@@ -486,6 +685,18 @@ mod tests {
                 password: None,
                 remote_path: None,
                 local_path: None,
+                remote_shell: None,
+                quick_commands: None,
+                url_mappings: None,
+                file_sorting: None,
+                group_dirs: None,
+                show_hidden_files: None,
+                ssh_compression: None,
+                ssh_ciphers: None,
+                default_file_mode: None,
+                default_dir_mode: None,
+                ftp_active_mode: None,
+                ftp_tls_session_reuse: None,
                 s3: Some(S3Params {
                     bucket: "veeso".to_string(),
                     region: Some("eu-west-1".to_string()),
@@ -494,7 +705,12 @@ mod tests {
                     access_key: None,
                     secret_access_key: None,
                     new_path_style: None,
+                    requester_pays: None,
+                    storage_class: None,
+                    accept_invalid_certs: None,
+                    ca_bundle: None,
                 }),
+                group: None,
                 kube: None,
                 smb: None,
             },
@@ -510,8 +726,21 @@ mod tests {
                 password: None,
                 remote_path: None,
                 local_path: None,
+                remote_shell: None,
+                quick_commands: None,
+                url_mappings: None,
+                file_sorting: None,
+                group_dirs: None,
+                show_hidden_files: None,
+                ssh_compression: None,
+                ssh_ciphers: None,
+                default_file_mode: None,
+                default_dir_mode: None,
+                ftp_active_mode: None,
+                ftp_tls_session_reuse: None,
                 s3: None,
                 smb: None,
+                group: None,
                 kube: Some(KubeParams {
                     namespace: Some("my-namespace".to_string()),
                     cluster_url: Some("https://my-cluster".to_string()),
@@ -536,7 +765,20 @@ mod tests {
                 password: None,
                 remote_path: None,
                 local_path: None,
+                remote_shell: None,
+                quick_commands: None,
+                url_mappings: None,
+                file_sorting: None,
+                group_dirs: None,
+                show_hidden_files: None,
+                ssh_compression: None,
+                ssh_ciphers: None,
+                default_file_mode: None,
+                default_dir_mode: None,
+                ftp_active_mode: None,
+                ftp_tls_session_reuse: None,
                 s3: None,
+                group: None,
                 kube: None,
                 smb: smb_params,
             },
@@ -552,7 +794,20 @@ mod tests {
                 password: Some(String::from("aaa")),
                 remote_path: Some(PathBuf::from("/tmp")),
                 local_path: Some(PathBuf::from("/usr")),
+                remote_shell: None,
+                quick_commands: None,
+                url_mappings: None,
+                file_sorting: None,
+                group_dirs: None,
+                show_hidden_files: None,
+                ssh_compression: None,
+                ssh_ciphers: None,
+                default_file_mode: None,
+                default_dir_mode: None,
+                ftp_active_mode: None,
+                ftp_tls_session_reuse: None,
                 s3: None,
+                group: None,
                 kube: None,
                 smb: None,
             },
@@ -567,6 +822,9 @@ mod tests {
     fn test_config_serialization_theme_serialize() {
         let theme: Theme = Theme {
             auth_address: Color::Rgb(240, 240, 240),
+            explorer_inactive_fg: Some(Color::DarkGray),
+            explorer_dir_fg: Some(Color::Indexed(33)),
+            explorer_code_fg: Some(Color::Rgb(255, 128, 0)),
             ..Default::default()
         };
         let tmpfile: tempfile::NamedTempFile = tempfile::NamedTempFile::new().unwrap();
@@ -582,7 +840,11 @@ mod tests {
         let toml_file = create_good_toml_theme();
         toml_file.as_file().sync_all().unwrap();
         toml_file.as_file().rewind().unwrap();
-        assert!(deserialize::<Theme>(Box::new(toml_file)).is_ok());
+        let theme = deserialize::<Theme>(Box::new(toml_file)).unwrap();
+        assert_eq!(theme.explorer_inactive_fg, None);
+        // missing file type colors fall back to the explorer foreground
+        assert_eq!(theme.explorer_dir_fg, None);
+        assert_eq!(theme.explorer_exec_fg, None);
         let toml_file = create_bad_toml_theme();
         toml_file.as_file().sync_all().unwrap();
         toml_file.as_file().rewind().unwrap();
@@ -605,13 +867,50 @@ mod tests {
         assert_eq!(kube.client_key.as_deref().unwrap(), "my-key");
     }
 
+    #[test]
+    fn test_config_serialization_bookmarks_state() {
+        let mut state = BookmarksState::default();
+        state
+            .failures
+            .insert(String::from("raspberry"), ConnectionFailure::new("Timeout"));
+        state.favorites.insert(
+            String::from("raspberry"),
+            vec![PathBuf::from("/etc/hosts"), PathBuf::from("/var/log")],
+        );
+        state.directories.insert(
+            String::from("sftp://192.168.1.31"),
+            vec![DirectoryBookmark {
+                name: String::from("logs"),
+                path: PathBuf::from("/var/log/nginx"),
+            }],
+        );
+        let tmpfile: tempfile::NamedTempFile = tempfile::NamedTempFile::new().unwrap();
+        let (reader, writer) = create_file_ioers(tmpfile.path());
+        assert!(serialize(&state, Box::new(writer)).is_ok());
+        let deserialized: BookmarksState = deserialize(Box::new(reader)).unwrap();
+        assert_eq!(deserialized.failures, state.failures);
+        assert_eq!(deserialized.favorites, state.favorites);
+        assert_eq!(deserialized.directories, state.directories);
+        // a state without favorites is still valid
+        let mut toml_file: tempfile::NamedTempFile = tempfile::NamedTempFile::new().unwrap();
+        toml_file
+            .write_all(b"[failures.raspberry]\ntime = 1700000000\nerror = \"Timeout\"\n")
+            .unwrap();
+        toml_file.as_file().sync_all().unwrap();
+        toml_file.as_file().rewind().unwrap();
+        let deserialized: BookmarksState = deserialize(Box::new(toml_file)).unwrap();
+        assert_eq!(deserialized.failures.len(), 1);
+        assert!(deserialized.favorites.is_empty());
+        assert!(deserialized.directories.is_empty());
+    }
+
     fn create_good_toml_bookmarks() -> tempfile::NamedTempFile {
         // Write
         let mut tmpfile: tempfile::NamedTempFile = tempfile::NamedTempFile::new().unwrap();
         let file_content: &str = r#"
         [bookmarks]
         raspberrypi2 = { address = "192.168.1.31", port = 22, protocol = "SFTP", username = "root", password = "mypassword" }
-        msi-estrem = { address = "192.168.1.30", port = 22, protocol = "SFTP", username = "cvisintin", password = "mysecret", directory = "/tmp", local_path = "/usr" }
+        msi-estrem = { address = "192.168.1.30", port = 22, protocol = "SFTP", username = "cvisintin", password = "mysecret", directory = "/tmp", local_path = "/usr", remote_shell = "/bin/bash -lc", quick_commands = [{ label = "Tail", command = "tail -n 100 {selected_path}" }, { label = "Uptime", command = "uptime" }], url_mappings = [{ path = "/var/www/html", url = "https://example.com" }], file_sorting = "by_mtime", group_dirs = "first", show_hidden_files = true }
         aws-server-prod1 = { address = "51.23.67.12", port = 21, protocol = "FTPS", username = "aws001" }
         
         [bookmarks.my-bucket]
@@ -625,6 +924,10 @@ mod tests {
         access_key = "pippo"
         secret_access_key = "pluto"
         new_path_style = true
+        requester_pays = true
+        storage_class = "STANDARD_IA"
+        accept_invalid_certs = true
+        ca_bundle = "/etc/ssl/minio.pem"
 
         [bookmarks.pod]
         protocol = "KUBE"
@@ -8,9 +8,27 @@ use serde::de::Error as DeError;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use tuirealm::ratatui::style::Color;
 
+use remotefs::File;
+
 use crate::utils::fmt::fmt_color;
 use crate::utils::parser::parse_color;
 
+/// Extensions of the files colored as archives in the explorers
+const ARCHIVE_EXTENSIONS: &[&str] = &[
+    "7z", "bz2", "deb", "gz", "iso", "jar", "lz", "lzma", "rar", "rpm", "tar", "tbz2", "tgz",
+    "txz", "xz", "zip", "zst",
+];
+/// Extensions of the files colored as images in the explorers
+const IMAGE_EXTENSIONS: &[&str] = &[
+    "bmp", "gif", "heic", "ico", "jpeg", "jpg", "png", "psd", "svg", "tif", "tiff", "webp",
+];
+/// Extensions of the files colored as source code in the explorers
+const CODE_EXTENSIONS: &[&str] = &[
+    "bash", "c", "cc", "cpp", "cs", "css", "go", "h", "hpp", "html", "java", "js", "json", "jsx",
+    "kt", "lua", "php", "py", "rb", "rs", "scss", "sh", "sql", "swift", "toml", "ts", "tsx", "xml",
+    "yaml", "yml", "zsh",
+];
+
 /// Theme contains all the colors lookup table for termscp
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
 pub struct Theme {
@@ -152,6 +170,69 @@ pub struct Theme {
         serialize_with = "serialize_color"
     )]
     pub transfer_status_sync_browsing: Color,
+    /// Border and title color of the explorer panel which is not focused.
+    /// When unset, it is derived from the panel foreground
+    #[serde(
+        default,
+        deserialize_with = "deserialize_option_color",
+        serialize_with = "serialize_option_color",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub explorer_inactive_fg: Option<Color>,
+    /// Foreground of the directories in the explorers.
+    /// When unset, the explorer foreground is used
+    #[serde(
+        default,
+        deserialize_with = "deserialize_option_color",
+        serialize_with = "serialize_option_color",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub explorer_dir_fg: Option<Color>,
+    /// Foreground of the symbolic links in the explorers.
+    /// When unset, the explorer foreground is used
+    #[serde(
+        default,
+        deserialize_with = "deserialize_option_color",
+        serialize_with = "serialize_option_color",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub explorer_symlink_fg: Option<Color>,
+    /// Foreground of the executable files in the explorers.
+    /// When unset, the explorer foreground is used
+    #[serde(
+        default,
+        deserialize_with = "deserialize_option_color",
+        serialize_with = "serialize_option_color",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub explorer_exec_fg: Option<Color>,
+    /// Foreground of the archives in the explorers.
+    /// When unset, the explorer foreground is used
+    #[serde(
+        default,
+        deserialize_with = "deserialize_option_color",
+        serialize_with = "serialize_option_color",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub explorer_archive_fg: Option<Color>,
+    /// Foreground of the images in the explorers.
+    /// When unset, the explorer foreground is used
+    #[serde(
+        default,
+        deserialize_with = "deserialize_option_color",
+        serialize_with = "serialize_option_color",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub explorer_image_fg: Option<Color>,
+    /// Foreground of the source code files in the explorers.
+    /// When unset, the explorer foreground is used
+    #[serde(
+        default,
+        deserialize_with = "deserialize_option_color",
+        serialize_with = "serialize_option_color",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub explorer_code_fg: Option<Color>,
 }
 
 impl Default for Theme {
@@ -184,10 +265,64 @@ impl Default for Theme {
             transfer_status_hidden: Color::LightBlue,
             transfer_status_sorting: Color::LightYellow,
             transfer_status_sync_browsing: Color::LightGreen,
+            explorer_inactive_fg: None,
+            explorer_dir_fg: None,
+            explorer_symlink_fg: None,
+            explorer_exec_fg: None,
+            explorer_archive_fg: None,
+            explorer_image_fg: None,
+            explorer_code_fg: None,
         }
     }
 }
 
+impl Theme {
+    /// Get the border and title color for an inactive explorer panel with `foreground`.
+    /// If `explorer_inactive_fg` is not set, a dimmed color is derived from the foreground
+    pub fn explorer_inactive_fg(&self, foreground: Color) -> Color {
+        self.explorer_inactive_fg.unwrap_or(match foreground {
+            Color::LightRed => Color::Red,
+            Color::LightGreen => Color::Green,
+            Color::LightYellow => Color::Yellow,
+            Color::LightMagenta => Color::Magenta,
+            Color::LightCyan => Color::Cyan,
+            Color::LightBlue => Color::Blue,
+            _ => Color::DarkGray,
+        })
+    }
+
+    /// Get the foreground of `file` in the explorers, by its type.
+    /// Returns `Color::Reset`, which renders the explorer foreground, if no color is set for its type
+    pub fn explorer_file_fg(&self, file: &File) -> Color {
+        let extension = file.extension().map(|x| x.to_lowercase());
+        let is_extension_of = |group: &[&str]| {
+            extension
+                .as_deref()
+                .is_some_and(|extension| group.contains(&extension))
+        };
+        let color = if file.is_symlink() {
+            self.explorer_symlink_fg
+        } else if file.is_dir() {
+            self.explorer_dir_fg
+        } else if file
+            .metadata()
+            .mode
+            .is_some_and(|mode| u32::from(mode) & 0o111 != 0)
+        {
+            self.explorer_exec_fg
+        } else if is_extension_of(ARCHIVE_EXTENSIONS) {
+            self.explorer_archive_fg
+        } else if is_extension_of(IMAGE_EXTENSIONS) {
+            self.explorer_image_fg
+        } else if is_extension_of(CODE_EXTENSIONS) {
+            self.explorer_code_fg
+        } else {
+            None
+        };
+        color.unwrap_or(Color::Reset)
+    }
+}
+
 // -- deserializer
 
 fn deserialize_color<'de, D>(deserializer: D) -> Result<Color, D::Error>
@@ -211,9 +346,29 @@ where
     serializer.serialize_str(s.as_str())
 }
 
+fn deserialize_option_color<'de, D>(deserializer: D) -> Result<Option<Color>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deserialize_color(deserializer).map(Some)
+}
+
+fn serialize_option_color<S>(color: &Option<Color>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    match color {
+        Some(color) => serialize_color(color, serializer),
+        None => serializer.serialize_none(),
+    }
+}
+
 #[cfg(test)]
 mod test {
+    use std::path::PathBuf;
+
     use pretty_assertions::assert_eq;
+    use remotefs::fs::{FileType, Metadata, UnixPex};
 
     use super::*;
 
@@ -247,5 +402,71 @@ mod test {
         assert_eq!(theme.transfer_status_hidden, Color::LightBlue);
         assert_eq!(theme.transfer_status_sorting, Color::LightYellow);
         assert_eq!(theme.transfer_status_sync_browsing, Color::LightGreen);
+        assert_eq!(theme.explorer_inactive_fg, None);
+        assert_eq!(theme.explorer_dir_fg, None);
+        assert_eq!(theme.explorer_symlink_fg, None);
+        assert_eq!(theme.explorer_exec_fg, None);
+        assert_eq!(theme.explorer_archive_fg, None);
+        assert_eq!(theme.explorer_image_fg, None);
+        assert_eq!(theme.explorer_code_fg, None);
+    }
+
+    #[test]
+    fn should_get_explorer_file_color_by_type() {
+        fn file(path: &str, file_type: FileType, mode: u32) -> File {
+            File {
+                path: PathBuf::from(path),
+                metadata: Metadata::default()
+                    .file_type(file_type)
+                    .mode(UnixPex::from(mode)),
+            }
+        }
+        let mut theme = Theme::default();
+        let dir = file("/home/omar/docs", FileType::Directory, 0o755);
+        let archive = file("/home/omar/backup.TAR", FileType::File, 0o644);
+        // unset colors fall back to the explorer foreground
+        assert_eq!(theme.explorer_file_fg(&dir), Color::Reset);
+        assert_eq!(theme.explorer_file_fg(&archive), Color::Reset);
+        theme.explorer_dir_fg = Some(Color::Blue);
+        theme.explorer_symlink_fg = Some(Color::Cyan);
+        theme.explorer_exec_fg = Some(Color::Green);
+        theme.explorer_archive_fg = Some(Color::Red);
+        theme.explorer_image_fg = Some(Color::Magenta);
+        theme.explorer_code_fg = Some(Color::Indexed(208));
+        assert_eq!(theme.explorer_file_fg(&dir), Color::Blue);
+        assert_eq!(
+            theme.explorer_file_fg(&file("/home/omar/link", FileType::Symlink, 0o777)),
+            Color::Cyan
+        );
+        assert_eq!(
+            theme.explorer_file_fg(&file("/home/omar/build.sh", FileType::File, 0o755)),
+            Color::Green
+        );
+        assert_eq!(theme.explorer_file_fg(&archive), Color::Red);
+        assert_eq!(
+            theme.explorer_file_fg(&file("/home/omar/cat.png", FileType::File, 0o644)),
+            Color::Magenta
+        );
+        assert_eq!(
+            theme.explorer_file_fg(&file("/home/omar/main.rs", FileType::File, 0o644)),
+            Color::Indexed(208)
+        );
+        assert_eq!(
+            theme.explorer_file_fg(&file("/home/omar/notes.txt", FileType::File, 0o644)),
+            Color::Reset
+        );
+    }
+
+    #[test]
+    fn should_derive_inactive_explorer_color_from_foreground() {
+        let mut theme = Theme::default();
+        assert_eq!(theme.explorer_inactive_fg(Color::Reset), Color::DarkGray);
+        assert_eq!(theme.explorer_inactive_fg(Color::LightGreen), Color::Green);
+        assert_eq!(theme.explorer_inactive_fg(Color::Red), Color::DarkGray);
+        theme.explorer_inactive_fg = Some(Color::Rgb(64, 64, 64));
+        assert_eq!(
+            theme.explorer_inactive_fg(Color::LightGreen),
+            Color::Rgb(64, 64, 64)
+        );
     }
 }
@@ -0,0 +1,520 @@
+//! ## Validation
+//!
+//! `validation` is the module which checks the termscp configuration files and the constraints between their values
+
+use std::fmt;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use serde::de::DeserializeOwned;
+
+use super::bookmarks::UserHosts;
+use super::params::UserConfig;
+use super::themes::Theme;
+use crate::explorer::formatter::Formatter;
+use crate::explorer::GroupDirs;
+use crate::filetransfer::FileTransferProtocol;
+use crate::system::notifications::TerminalBell;
+use crate::ui::activities::auth::StartupFocus;
+use crate::utils::parser;
+
+/// Describes how bad a configuration issue is
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum IssueSeverity {
+    Error,
+    Warning,
+}
+
+impl fmt::Display for IssueSeverity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Error => write!(f, "error"),
+            Self::Warning => write!(f, "warning"),
+        }
+    }
+}
+
+/// An issue found in a configuration file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigIssue {
+    pub severity: IssueSeverity,
+    /// File the issue has been found in
+    pub file: PathBuf,
+    /// Line of the file, starting from 1
+    pub line: Option<usize>,
+    /// Configuration field the issue refers to
+    pub field: Option<String>,
+    pub message: String,
+}
+
+impl ConfigIssue {
+    fn new(severity: IssueSeverity, file: &Path, field: Option<&str>, message: String) -> Self {
+        Self {
+            severity,
+            file: file.to_path_buf(),
+            line: None,
+            field: field.map(String::from),
+            message,
+        }
+    }
+}
+
+impl fmt::Display for ConfigIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.file.display())?;
+        if let Some(line) = self.line {
+            write!(f, ":{line}")?;
+        }
+        write!(f, ": {}: ", self.severity)?;
+        if let Some(field) = self.field.as_deref() {
+            write!(f, "`{field}`: ")?;
+        }
+        write!(f, "{}", self.message)
+    }
+}
+
+/// Report of the issues found checking the configuration
+#[derive(Debug, Default)]
+pub struct ConfigReport {
+    issues: Vec<ConfigIssue>,
+}
+
+impl ConfigReport {
+    pub fn issues(&self) -> &[ConfigIssue] {
+        &self.issues
+    }
+
+    /// Returns the amount of issues with the provided severity
+    pub fn count(&self, severity: IssueSeverity) -> usize {
+        self.issues
+            .iter()
+            .filter(|x| x.severity == severity)
+            .count()
+    }
+
+    pub fn has_errors(&self) -> bool {
+        self.count(IssueSeverity::Error) > 0
+    }
+
+    /// Check the TOML file at `path`, parsing it as `T`.
+    /// Returns the parsed value and its source if the file could be parsed.
+    /// If the file doesn't exist and it's not `required`, no issue is reported
+    fn check_file<T>(&mut self, path: &Path, required: bool) -> Option<(T, String)>
+    where
+        T: DeserializeOwned,
+    {
+        if !path.exists() {
+            if required {
+                self.issues.push(ConfigIssue::new(
+                    IssueSeverity::Error,
+                    path,
+                    None,
+                    String::from("no such file"),
+                ));
+            }
+            return None;
+        }
+        let source = match std::fs::read_to_string(path) {
+            Ok(source) => source,
+            Err(err) => {
+                self.issues.push(ConfigIssue::new(
+                    IssueSeverity::Error,
+                    path,
+                    None,
+                    format!("could not read file: {err}"),
+                ));
+                return None;
+            }
+        };
+        match toml::de::from_str(&source) {
+            Ok(value) => Some((value, source)),
+            Err(err) => {
+                let mut issue =
+                    ConfigIssue::new(IssueSeverity::Error, path, None, err.message().to_string());
+                issue.line = err.span().map(|span| line_at(&source, span.start));
+                self.issues.push(issue);
+                None
+            }
+        }
+    }
+}
+
+impl fmt::Display for ConfigReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for issue in self.issues() {
+            writeln!(f, "{issue}")?;
+        }
+        write!(
+            f,
+            "{} error(s), {} warning(s)",
+            self.count(IssueSeverity::Error),
+            self.count(IssueSeverity::Warning)
+        )
+    }
+}
+
+/// Check the configuration, the theme and the bookmarks files
+pub fn check_files(config_path: &Path, theme_path: &Path, bookmarks_path: &Path) -> ConfigReport {
+    let mut report = ConfigReport::default();
+    if let Some((mut config, source)) = report.check_file::<UserConfig>(config_path, true) {
+        report.issues.extend(validate_user_config(
+            &mut config,
+            config_path,
+            Some(&source),
+            false,
+        ));
+    }
+    report.check_file::<Theme>(theme_path, false);
+    report.check_file::<UserHosts>(bookmarks_path, false);
+    report
+}
+
+/// Validate the constraints of the values in `config`, read from the file at `path`.
+/// If `source` is provided, it's used to resolve the line of the fields with issues.
+/// If `repair` is `true`, bad optional values are replaced with their default
+pub fn validate_user_config(
+    config: &mut UserConfig,
+    path: &Path,
+    source: Option<&str>,
+    repair: bool,
+) -> Vec<ConfigIssue> {
+    let mut issues = Vec::new();
+    let mut push = |severity, field: &str, message: String, repaired: bool| {
+        let message = match repaired {
+            true => format!("{message}; the default value will be used"),
+            false => message,
+        };
+        let mut issue = ConfigIssue::new(severity, path, Some(field), message);
+        issue.line = source.and_then(|source| field_line(source, field));
+        issues.push(issue);
+    };
+    let ui = &mut config.user_interface;
+    if !is_executable_on_path(&ui.text_editor) {
+        push(
+            IssueSeverity::Error,
+            "text_editor",
+            format!("could not find `{}`", ui.text_editor.display()),
+            false,
+        );
+    }
+    if FileTransferProtocol::from_str(&ui.default_protocol).is_err() {
+        push(
+            IssueSeverity::Error,
+            "default_protocol",
+            format!("unknown protocol \"{}\"", ui.default_protocol),
+            repair,
+        );
+        if repair {
+            ui.default_protocol = FileTransferProtocol::Sftp.to_string();
+        }
+    }
+    if let Some(group_dirs) = ui.group_dirs.as_deref() {
+        if GroupDirs::from_str(group_dirs).is_err() {
+            push(
+                IssueSeverity::Error,
+                "group_dirs",
+                format!("expected `first`, `last` or `no`, found \"{group_dirs}\""),
+                repair,
+            );
+            if repair {
+                ui.group_dirs = None;
+            }
+        }
+    }
+    for (field, fmt) in [
+        ("file_fmt", &mut ui.file_fmt),
+        ("remote_file_fmt", &mut ui.remote_file_fmt),
+    ] {
+        let unknown_keys = fmt
+            .as_deref()
+            .map(Formatter::unknown_keys)
+            .unwrap_or_default();
+        if !unknown_keys.is_empty() {
+            push(
+                IssueSeverity::Error,
+                field,
+                format!(
+                    "unknown placeholder(s) {}",
+                    unknown_keys
+                        .iter()
+                        .map(|key| format!("{{{key}}}"))
+                        .collect::<Vec<String>>()
+                        .join(", ")
+                ),
+                repair,
+            );
+            if repair {
+                *fmt = None;
+            }
+        }
+    }
+    if let Some(bell) = ui.terminal_bell.as_deref() {
+        if TerminalBell::from_str(bell).is_err() {
+            push(
+                IssueSeverity::Error,
+                "terminal_bell",
+                format!("expected `off`, `on_completion`, `on_error` or `both`, found \"{bell}\""),
+                repair,
+            );
+            if repair {
+                ui.terminal_bell = None;
+            }
+        }
+    }
+    if let Some(focus) = ui.startup_focus.as_deref() {
+        if StartupFocus::from_str(focus).is_err() {
+            push(
+                IssueSeverity::Error,
+                "startup_focus",
+                format!("expected `form`, `bookmarks` or `recents`, found \"{focus}\""),
+                repair,
+            );
+            if repair {
+                ui.startup_focus = None;
+            }
+        }
+    }
+    let remote = &mut config.remote;
+    if let Some(ssh_config) = remote.ssh_config.as_deref() {
+        if let Err(err) = File::open(ssh_config) {
+            push(
+                IssueSeverity::Error,
+                "ssh_config",
+                format!("could not read \"{ssh_config}\": {err}"),
+                repair,
+            );
+            if repair {
+                remote.ssh_config = None;
+            }
+        }
+    }
+    for (field, mode) in [
+        ("default_file_mode", &mut remote.default_file_mode),
+        ("default_dir_mode", &mut remote.default_dir_mode),
+    ] {
+        if let Some(value) = mode.as_deref() {
+            if parser::parse_octal_mode(value).is_none() {
+                push(
+                    IssueSeverity::Error,
+                    field,
+                    format!("expected an octal mode (e.g. \"0664\"), found \"{value}\""),
+                    repair,
+                );
+                if repair {
+                    *mode = None;
+                }
+            }
+        }
+    }
+    let mut hosts: Vec<&String> = remote.ssh_keys.keys().collect();
+    hosts.sort();
+    for host in hosts {
+        let key = &remote.ssh_keys[host];
+        if !key.exists() {
+            push(
+                IssueSeverity::Warning,
+                "ssh_keys",
+                format!("key file \"{}\" for {host} doesn't exist", key.display()),
+                false,
+            );
+        }
+    }
+    issues
+}
+
+/// Returns whether `program` is an existing path or can be found in one of the `PATH` directories
+fn is_executable_on_path(program: &Path) -> bool {
+    if program.components().count() > 1 || program.is_absolute() {
+        return program.exists();
+    }
+    let Some(paths) = std::env::var_os("PATH") else {
+        return false;
+    };
+    std::env::split_paths(&paths).any(|dir| {
+        let candidate = dir.join(program);
+        candidate.is_file() || (cfg!(windows) && candidate.with_extension("exe").is_file())
+    })
+}
+
+/// Get the 1-based line of the byte at `offset` in `source`
+fn line_at(source: &str, offset: usize) -> usize {
+    source[..offset.min(source.len())]
+        .bytes()
+        .filter(|b| *b == b'\n')
+        .count()
+        + 1
+}
+
+/// Get the 1-based line where `field` is assigned in `source`
+fn field_line(source: &str, field: &str) -> Option<usize> {
+    source
+        .lines()
+        .position(|line| {
+            line.trim_start()
+                .strip_prefix(field)
+                .is_some_and(|rest| rest.trim_start().starts_with('='))
+        })
+        .map(|idx| idx + 1)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use std::io::Write;
+
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    use super::*;
+
+    const CONFIG: &str = r#"[user_interface]
+default_protocol = "FOO"
+text_editor = "/this/editor/does/not/exist"
+show_hidden_files = false
+group_dirs = "first"
+file_fmt = "{NAME} {OWNER}"
+terminal_bell = "loud"
+startup_focus = "nowhere"
+
+[remote]
+ssh_config = "/this/file/does/not/exist"
+
+[remote.ssh_keys]
+"omar@192.168.1.31" = "/this/key/does/not/exist"
+"#;
+
+    fn write_file(dir: &TempDir, name: &str, content: &str) -> PathBuf {
+        let path = dir.path().join(name);
+        let mut file = File::create(&path).unwrap();
+        write!(file, "{content}").unwrap();
+        path
+    }
+
+    #[test]
+    fn should_report_config_issues_with_lines() {
+        let dir = TempDir::new().unwrap();
+        let config = write_file(&dir, "config.toml", CONFIG);
+        let report = check_files(
+            &config,
+            &dir.path().join("theme.toml"),
+            &dir.path().join("bookmarks.toml"),
+        );
+        let fields: Vec<(Option<&str>, Option<usize>, IssueSeverity)> = report
+            .issues()
+            .iter()
+            .map(|x| (x.field.as_deref(), x.line, x.severity))
+            .collect();
+        assert_eq!(
+            fields,
+            vec![
+                (Some("text_editor"), Some(3), IssueSeverity::Error),
+                (Some("default_protocol"), Some(2), IssueSeverity::Error),
+                (Some("file_fmt"), Some(6), IssueSeverity::Error),
+                (Some("terminal_bell"), Some(7), IssueSeverity::Error),
+                (Some("startup_focus"), Some(8), IssueSeverity::Error),
+                (Some("ssh_config"), Some(11), IssueSeverity::Error),
+                (Some("ssh_keys"), None, IssueSeverity::Warning),
+            ]
+        );
+        assert_eq!(report.count(IssueSeverity::Error), 6);
+        assert_eq!(report.count(IssueSeverity::Warning), 1);
+        assert!(report.has_errors());
+        assert_eq!(
+            report.issues()[1].to_string(),
+            format!(
+                "{}:2: error: `default_protocol`: unknown protocol \"FOO\"",
+                config.display()
+            )
+        );
+        assert!(report.to_string().ends_with("6 error(s), 1 warning(s)"));
+    }
+
+    #[test]
+    fn should_report_syntax_errors_with_lines() {
+        let dir = TempDir::new().unwrap();
+        let config = write_file(&dir, "config.toml", CONFIG);
+        let theme = write_file(&dir, "theme.toml", "auth_address = \"Yellow\"\n");
+        let bookmarks = write_file(
+            &dir,
+            "bookmarks.toml",
+            "[bookmarks]\nraspberry = { address = \"192.168.1.31\", port = 22, protocol = \"MAGIC\" }\n\n[recents]\n",
+        );
+        let report = check_files(&config, &theme, &bookmarks);
+        let theme_issue = report.issues().iter().find(|x| x.file == theme).unwrap();
+        assert_eq!(theme_issue.severity, IssueSeverity::Error);
+        assert_eq!(theme_issue.field, None);
+        let bookmarks_issue = report
+            .issues()
+            .iter()
+            .find(|x| x.file == bookmarks)
+            .unwrap();
+        assert_eq!(bookmarks_issue.line, Some(2));
+    }
+
+    #[test]
+    fn should_report_missing_config_file() {
+        let dir = TempDir::new().unwrap();
+        let report = check_files(
+            &dir.path().join("config.toml"),
+            &dir.path().join("theme.toml"),
+            &dir.path().join("bookmarks.toml"),
+        );
+        assert_eq!(report.issues().len(), 1);
+        assert!(report.has_errors());
+    }
+
+    #[test]
+    fn should_repair_bad_optional_values() {
+        let mut config: UserConfig = toml::de::from_str(CONFIG).unwrap();
+        let issues = validate_user_config(&mut config, Path::new("config.toml"), None, true);
+        assert_eq!(issues.len(), 7);
+        assert!(issues.iter().all(|x| x.line.is_none()));
+        assert!(issues[1]
+            .message
+            .ends_with("the default value will be used"));
+        assert_eq!(config.user_interface.default_protocol, "SFTP");
+        assert_eq!(config.user_interface.group_dirs.as_deref(), Some("first"));
+        assert!(config.user_interface.file_fmt.is_none());
+        assert!(config.user_interface.terminal_bell.is_none());
+        assert!(config.user_interface.startup_focus.is_none());
+        assert!(config.remote.ssh_config.is_none());
+        // non optional values are kept
+        assert_eq!(
+            config.user_interface.text_editor,
+            PathBuf::from("/this/editor/does/not/exist")
+        );
+        // once repaired, only the non optional values are reported
+        assert_eq!(
+            validate_user_config(&mut config, Path::new("config.toml"), None, true).len(),
+            2
+        );
+    }
+
+    #[test]
+    fn should_repair_bad_default_modes() {
+        let source = CONFIG.replace(
+            "[remote]\n",
+            "[remote]\ndefault_file_mode = \"rw-rw-r--\"\ndefault_dir_mode = \"0775\"\n",
+        );
+        let mut config: UserConfig = toml::de::from_str(&source).unwrap();
+        let issues = validate_user_config(&mut config, Path::new("config.toml"), None, true);
+        let modes: Vec<&ConfigIssue> = issues
+            .iter()
+            .filter(|x| x.field.as_deref().is_some_and(|x| x.ends_with("_mode")))
+            .collect();
+        assert_eq!(modes.len(), 1);
+        assert_eq!(modes[0].field.as_deref(), Some("default_file_mode"));
+        assert!(config.remote.default_file_mode.is_none());
+        assert_eq!(config.remote.default_dir_mode.as_deref(), Some("0775"));
+    }
+
+    #[test]
+    fn should_get_field_line() {
+        assert_eq!(field_line(CONFIG, "default_protocol"), Some(2));
+        assert_eq!(field_line(CONFIG, "show_hidden"), None);
+        assert_eq!(field_line(CONFIG, "ssh_keys"), None);
+        assert_eq!(line_at("a\nb\nc", 0), 1);
+        assert_eq!(line_at("a\nb\nc", 4), 3);
+    }
+}
@@ -0,0 +1,176 @@
+//! ## Filter
+//!
+//! `filter` provides the filters which match the files of the explorer by name
+
+use std::fmt;
+use std::str::FromStr;
+
+use regex::Regex;
+use wildmatch::WildMatch;
+
+/// Prefix of the filters to match as regex, whatever the default mode
+const REGEX_PREFIX: &str = "re:";
+/// Prefix of the filters to match as glob, whatever the default mode
+const GLOB_PREFIX: &str = "glob:";
+
+/// How a filter matches the file names
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum FilterMode {
+    /// Wildmatch pattern, such as `*.log`
+    #[default]
+    Glob,
+    /// Regular expression, such as `^access\.log\.[0-9]+$`
+    Regex,
+}
+
+impl fmt::Display for FilterMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Glob => "glob",
+                Self::Regex => "regex",
+            }
+        )
+    }
+}
+
+impl FromStr for FilterMode {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "glob" | "wildmatch" => Ok(Self::Glob),
+            "regex" => Ok(Self::Regex),
+            _ => Err(()),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+enum Matcher {
+    Glob(WildMatch),
+    Regex(Regex),
+}
+
+/// Filter matching the files by name
+#[derive(Clone, Debug)]
+pub struct Filter {
+    pattern: String,
+    mode: FilterMode,
+    matcher: Matcher,
+}
+
+impl Filter {
+    /// Parse the `input` of the user.
+    /// The `re:` and `glob:` prefixes choose the mode, otherwise `default_mode` is used.
+    /// Fails with a one-line description of the error if the regex is invalid
+    pub fn parse(input: &str, default_mode: FilterMode) -> Result<Self, String> {
+        let (mode, pattern) = if let Some(pattern) = input.strip_prefix(REGEX_PREFIX) {
+            (FilterMode::Regex, pattern)
+        } else if let Some(pattern) = input.strip_prefix(GLOB_PREFIX) {
+            (FilterMode::Glob, pattern)
+        } else {
+            (default_mode, input)
+        };
+        let matcher = match mode {
+            FilterMode::Glob => Matcher::Glob(WildMatch::new(pattern)),
+            FilterMode::Regex => Matcher::Regex(Regex::new(pattern).map_err(|err| {
+                // the error of the regex crate is drawn over more lines
+                let err = err.to_string();
+                let reason = err
+                    .lines()
+                    .rev()
+                    .find(|x| !x.trim().is_empty())
+                    .unwrap_or_default()
+                    .trim()
+                    .trim_start_matches("error: ")
+                    .to_string();
+                format!("invalid regex: {reason}")
+            })?),
+        };
+        Ok(Self {
+            pattern: pattern.to_string(),
+            mode,
+            matcher,
+        })
+    }
+
+    /// Returns whether the file called `name` matches the filter
+    pub fn matches(&self, name: &str) -> bool {
+        match &self.matcher {
+            Matcher::Glob(wm) => wm.matches(name),
+            Matcher::Regex(re) => re.is_match(name),
+        }
+    }
+
+    #[cfg(test)]
+    /// Pattern of the filter, without the mode prefix
+    pub fn pattern(&self) -> &str {
+        &self.pattern
+    }
+
+    #[cfg(test)]
+    /// Mode of the filter
+    pub fn mode(&self) -> FilterMode {
+        self.mode
+    }
+}
+
+impl fmt::Display for Filter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({})", self.pattern, self.mode)
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn should_parse_filter_mode() {
+        assert_eq!(FilterMode::from_str("glob"), Ok(FilterMode::Glob));
+        assert_eq!(FilterMode::from_str("Regex"), Ok(FilterMode::Regex));
+        assert!(FilterMode::from_str("fuzzy").is_err());
+        assert_eq!(FilterMode::Regex.to_string().as_str(), "regex");
+        assert_eq!(FilterMode::default(), FilterMode::Glob);
+    }
+
+    #[test]
+    fn should_match_glob_filter() {
+        let filter = Filter::parse("*.log", FilterMode::Glob).unwrap();
+        assert_eq!(filter.mode(), FilterMode::Glob);
+        assert!(filter.matches("access.log"));
+        assert!(!filter.matches("access.log.1"));
+        // not a valid regex, but a valid glob
+        let filter = Filter::parse("glob:*.log", FilterMode::Regex).unwrap();
+        assert_eq!(filter.mode(), FilterMode::Glob);
+        assert_eq!(filter.pattern(), "*.log");
+        assert!(filter.matches("error.log"));
+    }
+
+    #[test]
+    fn should_match_regex_filter() {
+        let filter = Filter::parse(r"re:^access\.log\.[0-9]+$", FilterMode::Glob).unwrap();
+        assert_eq!(filter.mode(), FilterMode::Regex);
+        assert_eq!(filter.pattern(), r"^access\.log\.[0-9]+$");
+        assert!(filter.matches("access.log.12"));
+        assert!(!filter.matches("access.log"));
+        let filter = Filter::parse("[0-9]{4}", FilterMode::Regex).unwrap();
+        assert!(filter.matches("backup-2024.tar"));
+        assert_eq!(filter.to_string().as_str(), "[0-9]{4} (regex)");
+    }
+
+    #[test]
+    fn should_report_invalid_regex() {
+        let err = Filter::parse("re:*.log", FilterMode::Glob).unwrap_err();
+        assert!(err.starts_with("invalid regex: "));
+        assert!(!err.contains('\n'));
+        assert!(Filter::parse("(unclosed", FilterMode::Regex).is_err());
+        assert!(Filter::parse("(unclosed", FilterMode::Glob).is_ok());
+    }
+}
@@ -3,7 +3,8 @@
 //! `formatter` is the module which provides formatting utilities for `FileExplorer`
 
 // Locals
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::time::UNIX_EPOCH;
 
 // Ext
@@ -14,9 +15,9 @@ use unicode_width::UnicodeWidthStr;
 #[cfg(posix)]
 use uzers::{get_group_by_gid, get_user_by_uid};
 
-use crate::utils::fmt::{fmt_path_elide, fmt_pex, fmt_time};
+use super::special::SpecialFileType;
+use crate::utils::fmt::{fmt_name_elide, fmt_pad, fmt_path_elide, fmt_pex, fmt_time};
 use crate::utils::path::diff_paths;
-use crate::utils::string::secure_substring;
 // Types
 // FmtCallback: Formatter, fsentry: &File, cur_str, prefix, length, extra
 type FmtCallback = fn(&Formatter, &File, &str, &str, Option<&usize>, Option<&String>) -> String;
@@ -34,6 +35,14 @@ const FMT_KEY_SYMLINK: &str = "SYMLINK";
 const FMT_KEY_USER: &str = "USER";
 // Default
 const FMT_DEFAULT_STX: &str = "{NAME} {PEX} {USER} {SIZE} {MTIME}";
+/// Length of the name when not specified and not fitted to the available width
+const FMT_NAME_DEFAULT_LEN: usize = 24;
+/// Minimum length of the name, when fitted to the available width
+const FMT_NAME_MIN_LEN: usize = 12;
+/// Separator between the name of a symlink and its target, when the format has no `SYMLINK` key
+const FMT_SYMLINK_ARROW: &str = " -> ";
+/// Minimum length of the symlink target displayed after the name, before the name itself is elided
+const FMT_SYMLINK_MIN_LEN: usize = 6;
 /**
  * Regex matches:
  *  - group 0: KEY NAME
@@ -56,6 +65,8 @@ struct CallChainBlock {
     fmt_len: Option<usize>,
     /// The extra argument for formatting, specified for key as `{KEY:LEN:EXTRA}`
     fmt_extra: Option<String>,
+    /// Whether the fmt len can be fitted to the available width (`{NAME}` without length)
+    fit: bool,
     /// The next block to format
     next_block: Option<Box<CallChainBlock>>,
 }
@@ -67,34 +78,52 @@ impl CallChainBlock {
         prefix: String,
         fmt_len: Option<usize>,
         fmt_extra: Option<String>,
+        fit: bool,
     ) -> Self {
         CallChainBlock {
             func,
             prefix,
             fmt_len,
             fmt_extra,
+            fit,
             next_block: None,
         }
     }
 
-    /// Call next callback in the CallChain
-    pub fn next(&self, fmt: &Formatter, fsentry: &File, cur_str: &str) -> String {
+    /// Call next callback in the CallChain.
+    /// `fit_len` is used as fmt len by the blocks which can be fitted to the available width
+    pub fn next(
+        &self,
+        fmt: &Formatter,
+        fsentry: &File,
+        cur_str: &str,
+        fit_len: Option<usize>,
+    ) -> String {
+        let fmt_len = match self.fit {
+            true => fit_len.or(self.fmt_len),
+            false => self.fmt_len,
+        };
         // Call func
         let new_str: String = (self.func)(
             fmt,
             fsentry,
             cur_str,
             self.prefix.as_str(),
-            self.fmt_len.as_ref(),
+            fmt_len.as_ref(),
             self.fmt_extra.as_ref(),
         );
         // If next is some, call next, otherwise (END OF CHAIN) return new_str
         match &self.next_block {
-            Some(block) => block.next(fmt, fsentry, new_str.as_str()),
+            Some(block) => block.next(fmt, fsentry, new_str.as_str(), fit_len),
             None => new_str,
         }
     }
 
+    /// Returns whether any block in the chain can be fitted to the available width
+    pub fn can_fit(&self) -> bool {
+        self.fit || self.next_block.as_ref().is_some_and(|x| x.can_fit())
+    }
+
     /// Push func to the last element in the Call chain
     pub fn push(
         &mut self,
@@ -102,15 +131,16 @@ impl CallChainBlock {
         prefix: String,
         fmt_len: Option<usize>,
         fmt_extra: Option<String>,
+        fit: bool,
     ) {
         // Call recursively until an element with next_block equal to None is found
         match &mut self.next_block {
             None => {
                 self.next_block = Some(Box::new(CallChainBlock::new(
-                    func, prefix, fmt_len, fmt_extra,
+                    func, prefix, fmt_len, fmt_extra, fit,
                 )))
             }
-            Some(block) => block.push(func, prefix, fmt_len, fmt_extra),
+            Some(block) => block.push(func, prefix, fmt_len, fmt_extra, fit),
         }
     }
 }
@@ -120,14 +150,16 @@ impl CallChainBlock {
 /// at each fmt call.
 pub struct Formatter {
     call_chain: CallChainBlock,
+    /// Whether the format has the `SYMLINK` key; otherwise the symlink target is displayed after the name
+    symlink_key: bool,
+    /// Special files of the directory, which are reported as regular files by the file system
+    special: HashMap<PathBuf, SpecialFileType>,
 }
 
 impl Default for Formatter {
     /// Instantiates a Formatter with the default fmt syntax
     fn default() -> Self {
-        Formatter {
-            call_chain: Self::make_callchain(FMT_DEFAULT_STX),
-        }
+        Self::new(FMT_DEFAULT_STX)
     }
 }
 
@@ -136,13 +168,100 @@ impl Formatter {
     pub fn new(fmt_str: &str) -> Self {
         Formatter {
             call_chain: Self::make_callchain(fmt_str),
+            symlink_key: Self::keys(fmt_str).any(|key| key == Some(FMT_KEY_SYMLINK)),
+            special: HashMap::new(),
         }
     }
 
+    /// Set the special files of the directory, which are formatted with their type
+    pub fn set_special_files(&mut self, special: HashMap<PathBuf, SpecialFileType>) {
+        self.special = special;
+    }
+
+    /// Get the type of the special file at `path`, if it has been classified as such
+    pub fn special_file(&self, path: &Path) -> Option<SpecialFileType> {
+        self.special.get(path).copied()
+    }
+
+    /// Get the keys in the format string which are not supported by the formatter.
+    /// Unknown keys are still accepted by `new`, but they're formatted as empty strings
+    pub fn unknown_keys(fmt_str: &str) -> Vec<String> {
+        FMT_KEY_REGEX
+            .captures_iter(fmt_str)
+            .zip(Self::keys(fmt_str))
+            .filter_map(|(regex_match, key)| match key {
+                Some(
+                    FMT_KEY_ATIME | FMT_KEY_CTIME | FMT_KEY_GROUP | FMT_KEY_MTIME | FMT_KEY_NAME
+                    | FMT_KEY_PATH | FMT_KEY_PEX | FMT_KEY_SIZE | FMT_KEY_SYMLINK | FMT_KEY_USER,
+                ) => None,
+                _ => Some(regex_match[1].to_string()),
+            })
+            .collect()
+    }
+
+    /// Get the name of each key in the format string, if it has one
+    fn keys(fmt_str: &str) -> impl Iterator<Item = Option<&str>> {
+        FMT_KEY_REGEX.captures_iter(fmt_str).map(|regex_match| {
+            FMT_ATTR_REGEX
+                .captures(regex_match.get(1).unwrap().as_str())
+                .and_then(|attrs| attrs.get(1))
+                .map(|key| key.as_str())
+        })
+    }
+
     /// Format fsentry
     pub fn fmt(&self, fsentry: &File) -> String {
         // Execute callchain blocks
-        self.call_chain.next(self, fsentry, "")
+        self.call_chain.next(self, fsentry, "", None)
+    }
+
+    /// Format fsentry to fit `width` columns.
+    /// If the name has no length in the format string, it takes all the width left by the other keys
+    pub fn fmt_fit(&self, fsentry: &File, width: usize) -> String {
+        self.call_chain
+            .next(self, fsentry, "", self.fit_name_len(fsentry, width))
+    }
+
+    /// Returns whether the name of `fsentry` is elided, when formatted to fit `width` columns
+    pub fn is_name_elided(&self, fsentry: &File, width: usize) -> bool {
+        let name_len = self
+            .fit_name_len(fsentry, width)
+            .unwrap_or(FMT_NAME_DEFAULT_LEN);
+        self.name_width(fsentry) >= name_len
+    }
+
+    /// Get the length of the name to fit `width` columns, if the name can be fitted
+    fn fit_name_len(&self, fsentry: &File, width: usize) -> Option<usize> {
+        if !self.call_chain.can_fit() {
+            return None;
+        }
+        let other_keys_width = self
+            .fmt(fsentry)
+            .width()
+            .saturating_sub(FMT_NAME_DEFAULT_LEN);
+        Some(width.saturating_sub(other_keys_width).max(FMT_NAME_MIN_LEN))
+    }
+
+    /// Width of the formatted name (with the trailing `/` for directories and the symlink target, if displayed)
+    fn name_width(&self, fsentry: &File) -> usize {
+        fsentry.name().width()
+            + usize::from(fsentry.is_dir())
+            + self
+                .inline_symlink(fsentry)
+                .map(|target| FMT_SYMLINK_ARROW.len() + target.width())
+                .unwrap_or_default()
+    }
+
+    /// Get the target of the symlink `fsentry`, if it must be displayed after the name
+    fn inline_symlink(&self, fsentry: &File) -> Option<String> {
+        match self.symlink_key {
+            true => None,
+            false => fsentry
+                .metadata()
+                .symlink
+                .as_deref()
+                .map(|target| target.to_string_lossy().to_string()),
+        }
     }
 
     // Fmt methods
@@ -226,11 +345,8 @@ impl Formatter {
         };
         // Add to cur str, prefix and the key value
         format!(
-            "{}{}{:0width$}",
-            cur_str,
-            prefix,
-            group,
-            width = fmt_len.unwrap_or(&12)
+            "{cur_str}{prefix}{}",
+            fmt_pad(&group, *fmt_len.unwrap_or(&12))
         )
     }
 
@@ -273,23 +389,35 @@ impl Formatter {
         // Get file name (or elide if too long)
         let file_len: usize = match fmt_len {
             Some(l) => *l,
-            None => 24,
+            None => FMT_NAME_DEFAULT_LEN,
         };
         let name = fsentry.name();
-        let last_idx: usize = match fsentry.is_dir() {
+        let dir_len = usize::from(fsentry.is_dir());
+        let mut name: String = match (
+            self.name_width(fsentry) >= file_len,
+            self.inline_symlink(fsentry),
+        ) {
+            (false, None) => name,
+            (false, Some(target)) => format!("{name}{FMT_SYMLINK_ARROW}{target}"),
+            // The target is elided first, as long as a few columns of it can be displayed
+            (true, Some(target))
+                if name.width() + dir_len + FMT_SYMLINK_ARROW.len() + FMT_SYMLINK_MIN_LEN
+                    < file_len =>
+            {
+                let target_len = file_len - 1 - dir_len - name.width() - FMT_SYMLINK_ARROW.len();
+                format!(
+                    "{name}{FMT_SYMLINK_ARROW}{}",
+                    fmt_name_elide(&target, target_len)
+                )
+            }
             // NOTE: For directories is l - 2, since we push '/' to name
-            true => file_len - 2,
-            false => file_len - 1,
-        };
-        let mut name: String = match name.width() >= file_len {
-            false => name,
-            true => format!("{}…", secure_substring(&name, 0, last_idx)),
+            (true, _) => fmt_name_elide(&name, file_len.saturating_sub(1 + dir_len)),
         };
         if fsentry.is_dir() {
             name.push('/');
         }
-        // Add to cur str, prefix and the key value
-        format!("{cur_str}{prefix}{name:0file_len$}")
+        // Add to cur str, prefix and the key value, padded by width (names may contain wide chars)
+        format!("{cur_str}{prefix}{}", fmt_pad(&name, file_len))
     }
 
     /// Format path
@@ -332,7 +460,10 @@ impl Formatter {
             true => 'l',
             false => match fsentry.is_dir() {
                 true => 'd',
-                false => '-',
+                false => self
+                    .special_file(fsentry.path())
+                    .map(|x| x.as_char())
+                    .unwrap_or('-'),
             },
         };
         pex.push(file_type);
@@ -361,7 +492,10 @@ impl Formatter {
         _fmt_len: Option<&usize>,
         _fmt_extra: Option<&String>,
     ) -> String {
-        if fsentry.is_file() {
+        if let Some(special) = self.special_file(fsentry.path()) {
+            // Special files have no size
+            format!("{cur_str}{prefix}{:10}", special.label())
+        } else if fsentry.is_file() {
             // Get byte size
             let size: ByteSize = ByteSize(fsentry.metadata().size);
             // Add to cur str, prefix and the key value
@@ -401,11 +535,8 @@ impl Formatter {
         match fsentry.metadata().symlink.as_deref() {
             None => format!("{cur_str}{prefix}                        "),
             Some(p) => format!(
-                "{}{}-> {:0width$}",
-                cur_str,
-                prefix,
-                fmt_path_elide(p, file_len - 1),
-                width = file_len
+                "{cur_str}{prefix}-> {}",
+                fmt_pad(&fmt_path_elide(p, file_len - 1), file_len)
             ),
         }
     }
@@ -434,7 +565,7 @@ impl Formatter {
             None => 0.to_string(),
         };
         // Add to cur str, prefix and the key value
-        format!("{cur_str}{prefix}{username:12}")
+        format!("{cur_str}{prefix}{}", fmt_pad(&username, 12))
     }
 
     /// Fallback function in case the format key is unknown
@@ -500,13 +631,19 @@ impl Formatter {
                         .get(5)
                         .as_ref()
                         .map(|extra| extra.as_str().to_string());
+                    // Only the name without length can be fitted to the available width
+                    let fit = regex_match.get(1).map(|x| x.as_str()) == Some(FMT_KEY_NAME)
+                        && fmt_len.is_none();
                     // Create a callchain or push new element to its back
                     match callchain.as_mut() {
                         None => {
-                            callchain =
-                                Some(CallChainBlock::new(callback, prefix, fmt_len, fmt_extra))
+                            callchain = Some(CallChainBlock::new(
+                                callback, prefix, fmt_len, fmt_extra, fit,
+                            ))
+                        }
+                        Some(chain_block) => {
+                            chain_block.push(callback, prefix, fmt_len, fmt_extra, fit)
                         }
-                        Some(chain_block) => chain_block.push(callback, prefix, fmt_len, fmt_extra),
                     }
                 }
                 None => continue,
@@ -515,7 +652,7 @@ impl Formatter {
         // Finalize and return
         match callchain {
             Some(callchain) => callchain,
-            None => CallChainBlock::new(Self::fmt_fallback, String::new(), None, None),
+            None => CallChainBlock::new(Self::fmt_fallback, String::new(), None, None, false),
         }
     }
 }
@@ -531,6 +668,19 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn should_get_unknown_format_keys() {
+        assert!(
+            Formatter::unknown_keys("{NAME:24} {PEX} {USER} {SIZE} {MTIME:17:%Y-%m-%d %H:%M}")
+                .is_empty()
+        );
+        assert!(Formatter::unknown_keys("no keys at all").is_empty());
+        assert_eq!(
+            Formatter::unknown_keys("{NAME} {FOO:12} {pex} {}"),
+            vec![String::from("FOO:12"), String::from("pex"), String::new()]
+        );
+    }
+
     #[test]
     fn test_fs_explorer_formatter_callchain() {
         // Make a dummy formatter
@@ -552,22 +702,23 @@ mod tests {
             },
         };
         let prefix: String = String::from("h");
-        let mut callchain: CallChainBlock = CallChainBlock::new(dummy_fmt, prefix, None, None);
+        let mut callchain: CallChainBlock =
+            CallChainBlock::new(dummy_fmt, prefix, None, None, false);
         assert!(callchain.next_block.is_none());
         assert_eq!(callchain.prefix, String::from("h"));
         // Execute
         assert_eq!(
-            callchain.next(&dummy_formatter, &dummy_entry, ""),
+            callchain.next(&dummy_formatter, &dummy_entry, "", None),
             String::from("hA")
         );
         // Push 4 new blocks
-        callchain.push(dummy_fmt, String::from("h"), None, None);
-        callchain.push(dummy_fmt, String::from("h"), None, None);
-        callchain.push(dummy_fmt, String::from("h"), None, None);
-        callchain.push(dummy_fmt, String::from("h"), None, None);
+        callchain.push(dummy_fmt, String::from("h"), None, None, false);
+        callchain.push(dummy_fmt, String::from("h"), None, None, false);
+        callchain.push(dummy_fmt, String::from("h"), None, None, false);
+        callchain.push(dummy_fmt, String::from("h"), None, None, false);
         // Verify
         assert_eq!(
-            callchain.next(&dummy_formatter, &dummy_entry, ""),
+            callchain.next(&dummy_formatter, &dummy_entry, "", None),
             String::from("hAhAhAhAhA")
         );
     }
@@ -627,7 +778,7 @@ mod tests {
         assert_eq!(
             formatter.fmt(&entry),
             format!(
-                "piroparoporoperoperupup… -rw-r--r-- root         8.2 KB     {}",
+                "piroparoporoper…upu.txt  -rw-r--r-- root         8.2 KB     {}",
                 fmt_time(t, "%b %d %Y %H:%M")
             )
         );
@@ -635,7 +786,7 @@ mod tests {
         assert_eq!(
             formatter.fmt(&entry),
             format!(
-                "piroparoporoperoperupup… -rw-r--r-- 0            8.2 KB     {}",
+                "piroparoporoper…upu.txt  -rw-r--r-- 0            8.2 KB     {}",
                 fmt_time(t, "%b %d %Y %H:%M")
             )
         );
@@ -959,7 +1110,7 @@ mod tests {
             },
         };
         let formatter: Formatter = Formatter::new("{NAME:8}");
-        assert_eq!(formatter.fmt(&entry).as_str(), "christi…");
+        assert_eq!(formatter.fmt(&entry).as_str(), "chris…t ");
     }
 
     #[test]
@@ -999,7 +1150,146 @@ mod tests {
             },
         };
         let formatter: Formatter = Formatter::new("{NAME:8}");
-        assert_eq!(formatter.fmt(&entry).as_str(), "喵喵喵喵喵喵喵…");
+        assert_eq!(formatter.fmt(&entry).as_str(), "喵喵喵… ");
+    }
+
+    #[test]
+    fn should_fit_name_to_width() {
+        let entry = File {
+            path: PathBuf::from("/tmp/verylongname-2024-backup.tar.gz"),
+            metadata: Metadata {
+                accessed: None,
+                created: None,
+                modified: None,
+                file_type: FileType::File,
+                size: 8192,
+                symlink: None,
+                uid: None,
+                gid: None,
+                mode: None,
+            },
+        };
+        let formatter: Formatter = Formatter::new("{NAME} {SIZE}");
+        assert_eq!(
+            formatter.fmt_fit(&entry, 30).as_str(),
+            "verylong…up.tar.gz  8.2 KB    "
+        );
+        assert_eq!(formatter.is_name_elided(&entry, 30), true);
+        // wide enough for the whole name
+        assert_eq!(
+            formatter.fmt_fit(&entry, 45).as_str(),
+            "verylongname-2024-backup.tar.gz    8.2 KB    "
+        );
+        assert_eq!(formatter.is_name_elided(&entry, 45), false);
+        // names with a length are not fitted
+        let formatter: Formatter = Formatter::new("{NAME:16} {SIZE}");
+        assert_eq!(
+            formatter.fmt_fit(&entry, 45).as_str(),
+            "verylo…p.tar.gz  8.2 KB    "
+        );
+    }
+
+    #[test]
+    fn should_align_columns_of_mixed_width_names() {
+        let make_entry = |path: &str, symlink: Option<&str>| File {
+            path: PathBuf::from(path),
+            metadata: Metadata {
+                accessed: None,
+                created: None,
+                modified: None,
+                file_type: FileType::File,
+                size: 8192,
+                symlink: symlink.map(PathBuf::from),
+                uid: None,
+                gid: None,
+                mode: None,
+            },
+        };
+        let entries = [
+            make_entry("/tmp/report.txt", None),
+            make_entry("/tmp/喵喵喵.txt", None),
+            make_entry("/tmp/🦀crab.rs", None),
+            make_entry("/tmp/喵喵喵喵喵喵喵喵喵喵.txt", None),
+        ];
+        let formatter: Formatter = Formatter::new("{NAME:16}|{SIZE}");
+        for entry in entries.iter() {
+            let line = formatter.fmt(entry);
+            let (name, size) = line.split_once('|').unwrap();
+            assert_eq!(name.width(), 16);
+            assert_eq!(size, "8.2 KB    ");
+        }
+        // the symlink target is padded by width too
+        let formatter: Formatter = Formatter::new("{NAME:8}|{SYMLINK:12}|{SIZE}");
+        let line = formatter.fmt(&make_entry("/tmp/link", Some("/喵喵")));
+        assert_eq!(line.as_str(), "link    |-> /喵喵       |8.2 KB    ");
+        assert_eq!(line.split('|').nth(1).unwrap().width(), 15);
+    }
+
+    #[test]
+    fn should_fmt_symlink_target_after_name() {
+        let entry = File {
+            path: PathBuf::from("/var/www/current"),
+            metadata: Metadata {
+                accessed: None,
+                created: None,
+                modified: None,
+                file_type: FileType::Symlink,
+                size: 20,
+                symlink: Some(PathBuf::from("/srv/releases/20240101")),
+                uid: None,
+                gid: None,
+                mode: None,
+            },
+        };
+        let formatter: Formatter = Formatter::new("{NAME:40}");
+        assert_eq!(
+            formatter.fmt(&entry).as_str(),
+            "current -> /srv/releases/20240101       "
+        );
+        assert_eq!(Formatter::new("{NAME}").is_name_elided(&entry, 80), false);
+        // the target is elided first
+        let formatter: Formatter = Formatter::new("{NAME:24}");
+        assert_eq!(formatter.fmt(&entry).as_str(), "current -> /srv/rele…01 ");
+        assert_eq!(Formatter::new("{NAME}").is_name_elided(&entry, 24), true);
+        // then the name
+        let formatter: Formatter = Formatter::new("{NAME:12}");
+        assert_eq!(formatter.fmt(&entry).as_str(), "current     ");
+        // the target is not repeated if the format has the symlink key
+        let formatter: Formatter = Formatter::new("{NAME:12}|{SYMLINK:24}");
+        assert_eq!(
+            formatter.fmt(&entry).as_str(),
+            "current     |-> /srv/releases/20240101  "
+        );
+    }
+
+    #[test]
+    fn should_fmt_special_files() {
+        let entry = File {
+            path: PathBuf::from("/dev/ttyS0"),
+            metadata: Metadata {
+                accessed: None,
+                created: None,
+                modified: None,
+                file_type: FileType::File,
+                size: 0,
+                symlink: None,
+                uid: None,
+                gid: None,
+                mode: Some(UnixPex::from(0o660)),
+            },
+        };
+        let mut formatter: Formatter = Formatter::new("{PEX}|{SIZE}");
+        assert_eq!(formatter.fmt(&entry).as_str(), "-rw-rw----|0 B       ");
+        formatter.set_special_files(HashMap::from([(
+            PathBuf::from("/dev/ttyS0"),
+            SpecialFileType::CharDevice,
+        )]));
+        assert_eq!(formatter.fmt(&entry).as_str(), "crw-rw----|<char>    ");
+        assert_eq!(
+            formatter.special_file(Path::new("/dev/ttyS0")),
+            Some(SpecialFileType::CharDevice)
+        );
+        assert_eq!(formatter.special_file(Path::new("/dev/sda")), None);
     }
 
     /// Dummy formatter, just yelds an 'A' at the end of the current string
@@ -4,14 +4,18 @@
 
 // Mods
 pub(crate) mod builder;
-mod formatter;
+pub(crate) mod filter;
+pub(crate) mod formatter;
+pub(crate) mod special;
 // Locals
 use std::cmp::Reverse;
-use std::collections::VecDeque;
-use std::path::{Path, PathBuf};
+use std::collections::{HashMap, VecDeque};
+use std::path::{Component, Path, PathBuf};
 use std::str::FromStr;
 
+use filter::Filter;
 use formatter::Formatter;
+use special::SpecialFileType;
 // Ext
 use remotefs::fs::File;
 
@@ -23,7 +27,9 @@ bitflags! {
     }
 }
 
-/// FileSorting defines the criteria for sorting files
+/// FileSorting defines the criteria for sorting files.
+/// Entries which are equal for the chosen criteria are sorted by name, so the order is deterministic
+/// whatever order the entries were returned in by the backend (except for `None`, which keeps the backend order)
 #[derive(Copy, Clone, PartialEq, Eq, std::fmt::Debug)]
 pub enum FileSorting {
     Name,
@@ -34,7 +40,7 @@ pub enum FileSorting {
 }
 
 /// GroupDirs defines how directories should be grouped in sorting files
-#[derive(PartialEq, Eq, std::fmt::Debug)]
+#[derive(Copy, Clone, PartialEq, Eq, std::fmt::Debug)]
 pub enum GroupDirs {
     First,
     Last,
@@ -43,12 +49,13 @@ pub enum GroupDirs {
 /// File explorer states
 pub struct FileExplorer {
     pub wrkdir: PathBuf,                      // Current directory
-    pub(crate) dirstack: VecDeque<PathBuf>,   // Stack of visited directory (max 16)
+    pub(crate) dirstack: VecDeque<PathBuf>,   // Stack of visited directory (max `stack_size`)
     pub(crate) stack_size: usize,             // Directory stack size
     pub(crate) file_sorting: FileSorting,     // File sorting criteria
     pub(crate) group_dirs: Option<GroupDirs>, // If Some, defines how to group directories
     pub(crate) opts: ExplorerOpts,            // Explorer options
     pub(crate) fmt: Formatter,                // File formatter
+    filter: Option<Filter>,                   // Filter kept across directory changes
     files: Vec<File>,                         // Files in directory
 }
 
@@ -62,6 +69,7 @@ impl Default for FileExplorer {
             group_dirs: None,
             opts: ExplorerOpts::empty(),
             fmt: Formatter::default(),
+            filter: None,
             files: Vec::new(),
         }
     }
@@ -83,15 +91,44 @@ impl FileExplorer {
         self.dirstack.pop_back()
     }
 
+    /// Iterate over the directory stack, from the oldest to the most recent directory
+    pub fn iter_dirstack(&self) -> impl DoubleEndedIterator<Item = &Path> + '_ {
+        self.dirstack.iter().map(PathBuf::as_path)
+    }
+
+    /// Get the amount of directories in the stack
+    pub fn dirstack_len(&self) -> usize {
+        self.dirstack.len()
+    }
+
+    /// Jump to the directory at `idx` in the stack, as indexed by `iter_dirstack`.
+    /// The directory is returned and removed from the stack along with all the directories pushed after it
+    pub fn jump_to(&mut self, idx: usize) -> Option<PathBuf> {
+        let dir = self.dirstack.get(idx).cloned()?;
+        self.dirstack.truncate(idx);
+        Some(dir)
+    }
+
     /// Set Explorer files
     /// This method will also sort entries based on current options
     /// Once all sorting have been performed, index is moved to first valid entry.
     pub fn set_files(&mut self, files: Vec<File>) {
         self.files = files;
+        self.fmt.set_special_files(HashMap::new());
         // Sort
         self.sort();
     }
 
+    /// Set the special files among the explorer files, once they have been classified
+    pub fn set_special_files(&mut self, special: HashMap<PathBuf, SpecialFileType>) {
+        self.fmt.set_special_files(special);
+    }
+
+    /// Get the type of the file at `path`, if it is a special file among the explorer files
+    pub fn special_file(&self, path: &Path) -> Option<SpecialFileType> {
+        self.fmt.special_file(path)
+    }
+
     /// Delete file at provided index
     pub fn del_entry(&mut self, idx: usize) {
         if self.files.len() > idx {
@@ -104,6 +141,7 @@ impl FileExplorer {
     pub fn iter_files(&self) -> impl Iterator<Item = &File> + '_ {
         // Filter
         let opts: ExplorerOpts = self.opts;
+        let filter = self.filter.as_ref();
         Box::new(self.files.iter().filter(move |x| {
             // If true, element IS NOT filtered
             let mut pass: bool = true;
@@ -111,10 +149,21 @@ impl FileExplorer {
             if !opts.intersects(ExplorerOpts::SHOW_HIDDEN_FILES) {
                 pass &= !x.is_hidden();
             }
+            // Directories are kept, so the filter can be kept while browsing
+            if let Some(filter) = filter {
+                pass &= x.is_dir() || filter.matches(&x.name());
+            }
             pass
         }))
     }
 
+    /// Returns whether `file` matches the filter; every file does if no filter is set.
+    /// The directories which don't match it are listed by [`Self::iter_files`] to browse them, but they're not selected
+    /// and transferred along with the matching files
+    pub fn matches_filter(&self, file: &File) -> bool {
+        self.filter.as_ref().is_none_or(|x| x.matches(&file.name()))
+    }
+
     /// Iterate all files; doesn't care about options
     pub fn iter_files_all(&self) -> impl Iterator<Item = &File> + '_ {
         Box::new(self.files.iter())
@@ -122,28 +171,19 @@ impl FileExplorer {
 
     /// Get file at relative index
     pub fn get(&self, idx: usize) -> Option<&File> {
-        let opts: ExplorerOpts = self.opts;
-        let filtered = self
-            .files
-            .iter()
-            .filter(move |x| {
-                // If true, element IS NOT filtered
-                let mut pass: bool = true;
-                // If hidden files SHOULDN'T be shown, AND pass with not hidden
-                if !opts.intersects(ExplorerOpts::SHOW_HIDDEN_FILES) {
-                    pass &= !x.is_hidden();
-                }
-                pass
-            })
-            .collect::<Vec<_>>();
-        filtered.get(idx).copied()
+        self.iter_files().nth(idx)
     }
 
     // Formatting
 
-    /// Format a file entry
-    pub fn fmt_file(&self, entry: &File) -> String {
-        self.fmt.fmt(entry)
+    /// Format a file entry to fit `width` columns
+    pub fn fmt_file(&self, entry: &File, width: usize) -> String {
+        self.fmt.fmt_fit(entry, width)
+    }
+
+    /// Returns whether the name of `entry` is elided, when formatted to fit `width` columns
+    pub fn is_name_elided(&self, entry: &File, width: usize) -> bool {
+        self.fmt.is_name_elided(entry, width)
     }
 
     // Sorting
@@ -191,27 +231,34 @@ impl FileExplorer {
         }
     }
 
-    /// Sort explorer files by their name. All names are converted to lowercase
+    /// Sort explorer files by their name. All names are converted to lowercase;
+    /// names differing only by case are sorted by their actual name
     fn sort_files_by_name(&mut self) {
-        self.files.sort_by_key(|x: &File| x.name().to_lowercase());
+        self.files.sort_by_cached_key(Self::name_key);
     }
 
     /// Sort files by mtime; the newest comes first
     fn sort_files_by_mtime(&mut self) {
         self.files
-            .sort_by_key(|b: &File| Reverse(b.metadata().modified));
+            .sort_by_cached_key(|b: &File| (Reverse(b.metadata().modified), Self::name_key(b)));
     }
 
     /// Sort files by creation time; the newest comes first
     fn sort_files_by_creation_time(&mut self) {
         self.files
-            .sort_by_key(|b: &File| Reverse(b.metadata().created));
+            .sort_by_cached_key(|b: &File| (Reverse(b.metadata().created), Self::name_key(b)));
     }
 
     /// Sort files by size
     fn sort_files_by_size(&mut self) {
         self.files
-            .sort_by_key(|b: &File| Reverse(b.metadata().size));
+            .sort_by_cached_key(|b: &File| (Reverse(b.metadata().size), Self::name_key(b)));
+    }
+
+    /// Key used to sort files by name and to break ties between files equal for the other criteria
+    fn name_key(file: &File) -> (String, String) {
+        let name = file.name();
+        (name.to_lowercase(), name)
     }
 
     /// Sort files; directories come first
@@ -229,10 +276,47 @@ impl FileExplorer {
         self.opts.toggle(ExplorerOpts::SHOW_HIDDEN_FILES);
     }
 
+    /// Show or hide hidden files
+    pub fn set_hidden_files(&mut self, visible: bool) {
+        self.opts.set(ExplorerOpts::SHOW_HIDDEN_FILES, visible);
+    }
+
     /// Returns whether hidden files are visible
     pub fn hidden_files_visible(&self) -> bool {
         self.opts.intersects(ExplorerOpts::SHOW_HIDDEN_FILES)
     }
+
+    /// Set the filter which files must match to be listed, or remove it.
+    /// The filter is kept when the files change, until it's removed
+    pub fn set_filter(&mut self, filter: Option<Filter>) {
+        self.filter = filter;
+    }
+
+    /// Get the amount of listed files and of the files which would be listed without the filter
+    pub fn filter_counts(&self) -> (usize, usize) {
+        let show_hidden = self.hidden_files_visible();
+        let total = self
+            .files
+            .iter()
+            .filter(|x| show_hidden || !x.is_hidden())
+            .count();
+        (self.iter_files().count(), total)
+    }
+
+    /// Get the filter applied to files, if any
+    pub fn filter(&self) -> Option<&Filter> {
+        self.filter.as_ref()
+    }
+
+    /// Returns whether the entry at the relative path `p` would be filtered out by the current options.
+    /// Only the first component of `p` is checked, since it's the one listed in the working directory
+    pub fn hides(&self, p: &Path) -> bool {
+        !self.hidden_files_visible()
+            && matches!(
+                p.components().next(),
+                Some(Component::Normal(name)) if name.to_string_lossy().starts_with('.')
+            )
+    }
 }
 
 // Traits
@@ -346,6 +430,37 @@ mod tests {
         );
     }
 
+    #[test]
+    fn should_jump_to_dirstack_entry() {
+        let mut explorer = FileExplorer::default();
+        explorer.pushd(Path::new("/tmp"));
+        explorer.pushd(Path::new("/home/omar"));
+        explorer.pushd(Path::new("/dev"));
+        explorer.pushd(Path::new("/usr"));
+        assert_eq!(explorer.dirstack_len(), 4);
+        assert_eq!(
+            explorer.iter_dirstack().collect::<Vec<&Path>>(),
+            vec![
+                Path::new("/tmp"),
+                Path::new("/home/omar"),
+                Path::new("/dev"),
+                Path::new("/usr")
+            ]
+        );
+        // Out of range
+        assert!(explorer.jump_to(4).is_none());
+        assert_eq!(explorer.dirstack_len(), 4);
+        // Jump truncates the stack above the entry
+        assert_eq!(explorer.jump_to(1).unwrap(), PathBuf::from("/home/omar"));
+        assert_eq!(
+            explorer.iter_dirstack().collect::<Vec<&Path>>(),
+            vec![Path::new("/tmp")]
+        );
+        assert_eq!(explorer.jump_to(0).unwrap(), PathBuf::from("/tmp"));
+        assert_eq!(explorer.dirstack_len(), 0);
+        assert!(explorer.jump_to(0).is_none());
+    }
+
     #[test]
     fn test_fs_explorer_files() {
         let mut explorer: FileExplorer = FileExplorer::default();
@@ -376,6 +491,49 @@ mod tests {
         assert_eq!(explorer.iter_files().count(), 6); // All files are returned now
     }
 
+    #[test]
+    fn should_keep_filter_across_set_files() {
+        let mut explorer: FileExplorer = FileExplorer::default();
+        explorer.set_filter(Some(
+            Filter::parse("*.md", filter::FilterMode::Glob).unwrap(),
+        ));
+        explorer.set_files(vec![
+            make_fs_entry("README.md", false),
+            make_fs_entry("src", true),
+            make_fs_entry("codecov.yml", false),
+        ]);
+        // directories are kept, but they don't match
+        assert_eq!(explorer.iter_files().count(), 2);
+        assert_eq!(explorer.get(1).unwrap().name(), "src");
+        assert!(explorer.get(2).is_none());
+        assert!(explorer.matches_filter(explorer.get(0).unwrap()));
+        assert!(!explorer.matches_filter(explorer.get(1).unwrap()));
+        explorer.set_files(vec![
+            make_fs_entry("lib.rs", false),
+            make_fs_entry("CHANGELOG.md", false),
+        ]);
+        assert_eq!(explorer.iter_files().count(), 1);
+        assert_eq!(explorer.filter_counts(), (1, 2));
+        assert_eq!(explorer.filter().unwrap().pattern(), "*.md");
+        explorer.set_filter(None);
+        assert_eq!(explorer.iter_files().count(), 2);
+    }
+
+    #[test]
+    fn test_fs_explorer_hides() {
+        let mut explorer: FileExplorer = FileExplorer::default();
+        explorer.opts.remove(ExplorerOpts::SHOW_HIDDEN_FILES);
+        assert_eq!(explorer.hides(Path::new(".env")), true);
+        assert_eq!(explorer.hides(Path::new(".config/termscp")), true);
+        assert_eq!(explorer.hides(Path::new("README.md")), false);
+        assert_eq!(explorer.hides(Path::new("src/.gitignore")), false);
+        assert_eq!(explorer.hides(Path::new("./README.md")), false);
+        assert_eq!(explorer.hides(Path::new("/tmp/.env")), false);
+        // Show hidden files
+        explorer.toggle_hidden_files();
+        assert_eq!(explorer.hides(Path::new(".env")), false);
+    }
+
     #[test]
     fn test_fs_explorer_sort_by_name() {
         let mut explorer: FileExplorer = FileExplorer::default();
@@ -446,6 +604,72 @@ mod tests {
         assert_eq!(explorer.files.get(2).unwrap().name(), "CONTRIBUTING.md");
     }
 
+    #[test]
+    fn should_break_sorting_ties_by_name() {
+        let t = SystemTime::now();
+        let make_entries = || {
+            ["b.txt", "C.txt", "a.txt", "c.txt", "B.txt", "d", "A.txt"]
+                .into_iter()
+                .map(|name| {
+                    let mut entry = make_fs_entry_with_size(name, name == "d", 128);
+                    entry.metadata.modified = Some(t);
+                    entry.metadata.created = Some(t);
+                    entry
+                })
+                .collect::<Vec<File>>()
+        };
+        let expected = vec!["A.txt", "a.txt", "B.txt", "b.txt", "C.txt", "c.txt", "d"];
+        for sorting in [
+            FileSorting::Name,
+            FileSorting::ModifyTime,
+            FileSorting::CreationTime,
+            FileSorting::Size,
+        ] {
+            let mut explorer = FileExplorer::default();
+            explorer.sort_by(sorting);
+            // the output must not depend on the order of the entries
+            let mut entries = make_entries();
+            for _ in 0..entries.len() {
+                entries.rotate_left(1);
+                explorer.set_files(entries.clone());
+                let names: Vec<String> = explorer.iter_files().map(|x| x.name()).collect();
+                assert_eq!(names, expected, "sorting: {sorting}");
+                entries.reverse();
+                explorer.set_files(entries.clone());
+                let names: Vec<String> = explorer.iter_files().map(|x| x.name()).collect();
+                assert_eq!(names, expected, "sorting: {sorting}");
+            }
+            // ties are broken by name also when grouping directories
+            explorer.group_dirs = Some(GroupDirs::First);
+            explorer.set_files(make_entries());
+            assert_eq!(explorer.get(0).unwrap().name(), "d");
+            assert_eq!(explorer.get(1).unwrap().name(), "A.txt");
+            assert_eq!(explorer.get(6).unwrap().name(), "c.txt");
+        }
+    }
+
+    #[test]
+    fn should_sort_by_criteria_then_by_name() {
+        let t = SystemTime::now();
+        let older = t - Duration::from_secs(60);
+        let mut explorer = FileExplorer::default();
+        explorer.sort_by(FileSorting::ModifyTime);
+        let entries: Vec<File> = [("b", t), ("old-b", older), ("a", t), ("old-a", older)]
+            .into_iter()
+            .map(|(name, modified)| {
+                let mut entry = make_fs_entry(name, false);
+                entry.metadata.modified = Some(modified);
+                entry
+            })
+            .collect();
+        explorer.set_files(entries);
+        let names: Vec<String> = explorer.iter_files().map(|x| x.name()).collect();
+        assert_eq!(names, vec!["a", "b", "old-a", "old-b"]);
+        explorer.sort_by(FileSorting::Size);
+        let names: Vec<String> = explorer.iter_files().map(|x| x.name()).collect();
+        assert_eq!(names, vec!["a", "b", "old-a", "old-b"]);
+    }
+
     #[test]
     fn test_fs_explorer_sort_by_name_and_dirs_first() {
         let mut explorer: FileExplorer = FileExplorer::default();
@@ -520,21 +744,24 @@ mod tests {
             },
         };
         #[cfg(posix)]
-        assert_eq!(
-            explorer.fmt_file(&entry),
-            format!(
-                "bar.txt                  -rw-r--r-- root         8.2 KB     {}",
-                fmt_time(t, "%b %d %Y %H:%M")
-            )
+        let expected = format!(
+            "bar.txt                  -rw-r--r-- root         8.2 KB     {}",
+            fmt_time(t, "%b %d %Y %H:%M")
         );
         #[cfg(win)]
+        let expected = format!(
+            "bar.txt                  -rw-r--r-- 0            8.2 KB     {}",
+            fmt_time(t, "%b %d %Y %H:%M")
+        );
+        assert_eq!(explorer.fmt_file(&entry, expected.len()), expected);
+        assert_eq!(explorer.is_name_elided(&entry, expected.len()), false);
+        // the name column is fitted to the width
         assert_eq!(
-            explorer.fmt_file(&entry),
-            format!(
-                "bar.txt                  -rw-r--r-- 0            8.2 KB     {}",
-                fmt_time(t, "%b %d %Y %H:%M")
-            )
+            explorer.fmt_file(&entry, expected.len() + 4),
+            expected.replacen("bar.txt ", "bar.txt     ", 1)
         );
+        // the name column is never narrower than its minimum length
+        assert_eq!(explorer.is_name_elided(&entry, expected.len() - 20), false);
     }
 
     #[test]
@@ -586,6 +813,23 @@ mod tests {
         assert_eq!(explorer.files.len(), 3);
     }
 
+    #[test]
+    fn should_reset_special_files_on_set_files() {
+        let mut explorer: FileExplorer = FileExplorer::default();
+        explorer.set_files(vec![make_fs_entry("fifo", false)]);
+        explorer.set_special_files(HashMap::from([(
+            PathBuf::from("fifo"),
+            SpecialFileType::Fifo,
+        )]));
+        assert_eq!(
+            explorer.special_file(Path::new("fifo")),
+            Some(SpecialFileType::Fifo)
+        );
+        assert_eq!(explorer.special_file(Path::new("README.md")), None);
+        explorer.set_files(vec![make_fs_entry("fifo", false)]);
+        assert_eq!(explorer.special_file(Path::new("fifo")), None);
+    }
+
     fn make_fs_entry(name: &str, is_dir: bool) -> File {
         let t: SystemTime = SystemTime::now();
         let metadata = Metadata {
@@ -0,0 +1,224 @@
+//! ## Special
+//!
+//! `special` classifies the files which are neither directories, nor regular files, nor symlinks
+
+use std::fmt;
+use std::path::Path;
+
+use remotefs::fs::File;
+
+/// Mask of the file type bits in `st_mode`
+const S_IFMT: u32 = 0o170000;
+const S_IFSOCK: u32 = 0o140000;
+const S_IFBLK: u32 = 0o060000;
+const S_IFCHR: u32 = 0o020000;
+const S_IFIFO: u32 = 0o010000;
+
+/// Type of a special file, such as a device or a named pipe.
+/// Special files are reported as regular files by the remote file systems, but reading them may block forever
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SpecialFileType {
+    BlockDevice,
+    CharDevice,
+    Fifo,
+    Socket,
+}
+
+impl SpecialFileType {
+    /// Get the special file type from the `st_mode` of a file, if it is a special file
+    pub fn from_mode(mode: u32) -> Option<Self> {
+        match mode & S_IFMT {
+            S_IFBLK => Some(Self::BlockDevice),
+            S_IFCHR => Some(Self::CharDevice),
+            S_IFIFO => Some(Self::Fifo),
+            S_IFSOCK => Some(Self::Socket),
+            _ => None,
+        }
+    }
+
+    /// Get the special file type from its character in `ls -l`
+    pub fn from_char(c: char) -> Option<Self> {
+        match c {
+            'b' => Some(Self::BlockDevice),
+            'c' => Some(Self::CharDevice),
+            'p' => Some(Self::Fifo),
+            's' => Some(Self::Socket),
+            _ => None,
+        }
+    }
+
+    /// Character of the file type in `ls -l`
+    pub fn as_char(&self) -> char {
+        match self {
+            Self::BlockDevice => 'b',
+            Self::CharDevice => 'c',
+            Self::Fifo => 'p',
+            Self::Socket => 's',
+        }
+    }
+
+    /// Short label to display in place of the file size
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::BlockDevice => "<block>",
+            Self::CharDevice => "<char>",
+            Self::Fifo => "<fifo>",
+            Self::Socket => "<socket>",
+        }
+    }
+
+    /// Returns whether `file` may be a special file reported as a regular file.
+    /// Special files have no size, so regular files with some content are never special
+    pub fn is_candidate(file: &File) -> bool {
+        file.is_file() && file.metadata().symlink.is_none() && file.metadata().size == 0
+    }
+
+    /// Classify the file at `path` on the local host, without following symlinks
+    #[cfg(posix)]
+    pub fn classify_local(path: &Path) -> Option<Self> {
+        use std::os::unix::fs::MetadataExt;
+
+        std::fs::symlink_metadata(path)
+            .ok()
+            .and_then(|metadata| Self::from_mode(metadata.mode()))
+    }
+
+    /// Special files are not reported by the file system on Windows
+    #[cfg(win)]
+    pub fn classify_local(_path: &Path) -> Option<Self> {
+        None
+    }
+}
+
+impl fmt::Display for SpecialFileType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::BlockDevice => "block device",
+                Self::CharDevice => "character device",
+                Self::Fifo => "named pipe",
+                Self::Socket => "socket",
+            }
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use std::path::PathBuf;
+    use std::time::SystemTime;
+
+    use pretty_assertions::assert_eq;
+    use remotefs::fs::{FileType, Metadata};
+
+    use super::*;
+
+    #[test]
+    fn should_classify_special_files_from_mode() {
+        assert_eq!(
+            SpecialFileType::from_mode(0o060660),
+            Some(SpecialFileType::BlockDevice)
+        );
+        assert_eq!(
+            SpecialFileType::from_mode(0o020666),
+            Some(SpecialFileType::CharDevice)
+        );
+        assert_eq!(
+            SpecialFileType::from_mode(0o010644),
+            Some(SpecialFileType::Fifo)
+        );
+        assert_eq!(
+            SpecialFileType::from_mode(0o140755),
+            Some(SpecialFileType::Socket)
+        );
+        assert_eq!(SpecialFileType::from_mode(0o100644), None);
+        assert_eq!(SpecialFileType::from_mode(0o040755), None);
+        assert_eq!(SpecialFileType::from_mode(0o120777), None);
+        assert_eq!(SpecialFileType::from_mode(0o644), None);
+    }
+
+    #[test]
+    fn should_convert_special_file_type_chars() {
+        for kind in [
+            SpecialFileType::BlockDevice,
+            SpecialFileType::CharDevice,
+            SpecialFileType::Fifo,
+            SpecialFileType::Socket,
+        ] {
+            assert_eq!(SpecialFileType::from_char(kind.as_char()), Some(kind));
+        }
+        assert_eq!(SpecialFileType::from_char('-'), None);
+        assert_eq!(SpecialFileType::from_char('d'), None);
+        assert_eq!(SpecialFileType::CharDevice.to_string(), "character device");
+        assert_eq!(SpecialFileType::Fifo.label(), "<fifo>");
+    }
+
+    #[test]
+    fn should_tell_special_file_candidates() {
+        let file = |file_type: FileType, size: u64, symlink: Option<PathBuf>| File {
+            path: PathBuf::from("/dev/null"),
+            metadata: Metadata {
+                accessed: None,
+                created: None,
+                file_type,
+                gid: None,
+                mode: None,
+                modified: Some(SystemTime::UNIX_EPOCH),
+                size,
+                symlink,
+                uid: None,
+            },
+        };
+        assert!(SpecialFileType::is_candidate(&file(
+            FileType::File,
+            0,
+            None
+        )));
+        assert!(!SpecialFileType::is_candidate(&file(
+            FileType::File,
+            1024,
+            None
+        )));
+        assert!(!SpecialFileType::is_candidate(&file(
+            FileType::Directory,
+            0,
+            None
+        )));
+        assert!(!SpecialFileType::is_candidate(&file(
+            FileType::Symlink,
+            0,
+            Some(PathBuf::from("/dev/zero"))
+        )));
+    }
+
+    #[test]
+    #[cfg(posix)]
+    fn should_classify_local_special_files() {
+        let tmpdir = tempfile::TempDir::new().unwrap();
+        let fifo = tmpdir.path().join("fifo");
+        assert!(std::process::Command::new("mkfifo")
+            .arg(fifo.as_path())
+            .status()
+            .unwrap()
+            .success());
+        assert_eq!(
+            SpecialFileType::classify_local(fifo.as_path()),
+            Some(SpecialFileType::Fifo)
+        );
+        assert_eq!(
+            SpecialFileType::classify_local(Path::new("/dev/null")),
+            Some(SpecialFileType::CharDevice)
+        );
+        let regular = tmpdir.path().join("regular");
+        std::fs::write(regular.as_path(), b"").unwrap();
+        assert_eq!(SpecialFileType::classify_local(regular.as_path()), None);
+        assert_eq!(SpecialFileType::classify_local(tmpdir.path()), None);
+        assert_eq!(
+            SpecialFileType::classify_local(tmpdir.path().join("missing").as_path()),
+            None
+        );
+    }
+}
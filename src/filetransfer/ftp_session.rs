@@ -0,0 +1,151 @@
+//! ## FtpSession
+//!
+//! Ftp client which applies the data connection mode of the connection
+
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+use remotefs::fs::{File, Metadata, ReadStream, UnixPex, Welcome, WriteStream};
+use remotefs::{RemoteFs, RemoteResult};
+use remotefs_ftp::FtpFs;
+use suppaftp::Mode;
+
+/// Ftp client opening the data connections in active or passive mode.
+///
+/// The mode is applied to the ftp stream once connected, since the mode set on the [`FtpFs`] builder is not
+pub struct FtpModeFs {
+    client: FtpFs,
+    active: bool,
+}
+
+impl FtpModeFs {
+    /// Wrap `client`; data connections are opened in active mode if `active` is true, in passive mode otherwise
+    pub fn new(client: FtpFs, active: bool) -> Self {
+        Self { client, active }
+    }
+}
+
+impl RemoteFs for FtpModeFs {
+    fn connect(&mut self) -> RemoteResult<Welcome> {
+        let welcome = self.client.connect()?;
+        if let Some(stream) = self.client.stream() {
+            let mode = match self.active {
+                true => Mode::Active,
+                false => Mode::Passive,
+            };
+            debug!("ftp data connections mode: {mode:?}");
+            stream.set_mode(mode);
+        }
+        Ok(welcome)
+    }
+
+    fn disconnect(&mut self) -> RemoteResult<()> {
+        self.client.disconnect()
+    }
+
+    fn is_connected(&mut self) -> bool {
+        self.client.is_connected()
+    }
+
+    fn pwd(&mut self) -> RemoteResult<PathBuf> {
+        self.client.pwd()
+    }
+
+    fn change_dir(&mut self, dir: &Path) -> RemoteResult<PathBuf> {
+        self.client.change_dir(dir)
+    }
+
+    fn list_dir(&mut self, path: &Path) -> RemoteResult<Vec<File>> {
+        self.client.list_dir(path)
+    }
+
+    fn stat(&mut self, path: &Path) -> RemoteResult<File> {
+        self.client.stat(path)
+    }
+
+    fn setstat(&mut self, path: &Path, metadata: Metadata) -> RemoteResult<()> {
+        self.client.setstat(path, metadata)
+    }
+
+    fn exists(&mut self, path: &Path) -> RemoteResult<bool> {
+        self.client.exists(path)
+    }
+
+    fn remove_file(&mut self, path: &Path) -> RemoteResult<()> {
+        self.client.remove_file(path)
+    }
+
+    fn remove_dir(&mut self, path: &Path) -> RemoteResult<()> {
+        self.client.remove_dir(path)
+    }
+
+    fn remove_dir_all(&mut self, path: &Path) -> RemoteResult<()> {
+        self.client.remove_dir_all(path)
+    }
+
+    fn create_dir(&mut self, path: &Path, mode: UnixPex) -> RemoteResult<()> {
+        self.client.create_dir(path, mode)
+    }
+
+    fn symlink(&mut self, path: &Path, target: &Path) -> RemoteResult<()> {
+        self.client.symlink(path, target)
+    }
+
+    fn copy(&mut self, src: &Path, dest: &Path) -> RemoteResult<()> {
+        self.client.copy(src, dest)
+    }
+
+    fn mov(&mut self, src: &Path, dest: &Path) -> RemoteResult<()> {
+        self.client.mov(src, dest)
+    }
+
+    fn exec(&mut self, cmd: &str) -> RemoteResult<(u32, String)> {
+        self.client.exec(cmd)
+    }
+
+    fn append(&mut self, path: &Path, metadata: &Metadata) -> RemoteResult<WriteStream> {
+        self.client.append(path, metadata)
+    }
+
+    fn create(&mut self, path: &Path, metadata: &Metadata) -> RemoteResult<WriteStream> {
+        self.client.create(path, metadata)
+    }
+
+    fn open(&mut self, path: &Path) -> RemoteResult<ReadStream> {
+        self.client.open(path)
+    }
+
+    fn on_written(&mut self, writable: WriteStream) -> RemoteResult<()> {
+        self.client.on_written(writable)
+    }
+
+    fn on_read(&mut self, readable: ReadStream) -> RemoteResult<()> {
+        self.client.on_read(readable)
+    }
+
+    fn append_file(
+        &mut self,
+        path: &Path,
+        metadata: &Metadata,
+        reader: Box<dyn Read + Send>,
+    ) -> RemoteResult<u64> {
+        self.client.append_file(path, metadata, reader)
+    }
+
+    fn create_file(
+        &mut self,
+        path: &Path,
+        metadata: &Metadata,
+        reader: Box<dyn Read + Send>,
+    ) -> RemoteResult<u64> {
+        self.client.create_file(path, metadata, reader)
+    }
+
+    fn open_file(&mut self, src: &Path, dest: Box<dyn Write + Send>) -> RemoteResult<u64> {
+        self.client.open_file(src, dest)
+    }
+
+    fn find(&mut self, search: &str) -> RemoteResult<Vec<File>> {
+        self.client.find(search)
+    }
+}
@@ -0,0 +1,394 @@
+//! ## FtpsSession
+//!
+//! Ftps client which reuses the TLS session of the control connection for the data connections
+
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use remotefs::fs::{
+    File, FileType, Metadata, ReadStream, UnixPex, UnixPexClass, Welcome, WriteStream,
+};
+use remotefs::{RemoteError, RemoteErrorType, RemoteFs, RemoteResult};
+use rustls::client::{Resumption, ServerCertVerified, ServerCertVerifier};
+use rustls::{Certificate, ClientConfig, ServerName};
+use suppaftp::list::{File as FtpFile, PosixPexQuery};
+use suppaftp::types::{FileType as FtpFileType, Response};
+use suppaftp::{FtpError, Mode, RustlsConnector, RustlsFtpStream, Status};
+
+use crate::utils::path::absolutize;
+
+/// Number of TLS sessions kept to be resumed by the data connections
+const TLS_SESSIONS: usize = 32;
+
+/// Ftps client resuming the TLS session of the control connection when opening the data connections, as required by
+/// servers such as vsftpd with `require_ssl_reuse=YES`.
+///
+/// The [`remotefs_ftp::FtpFs`] connects through native-tls, which can't resume sessions, so the TLS stream is set up
+/// here with rustls, which keeps the sessions of the connector and resumes them for the same server.
+/// As for the [`remotefs_ftp::FtpFs`] of termscp, the certificate of the server is not verified
+pub struct FtpsSessionFs {
+    stream: Option<RustlsFtpStream>,
+    hostname: String,
+    port: u16,
+    username: String,
+    password: Option<String>,
+    active: bool,
+}
+
+impl FtpsSessionFs {
+    /// Instantiates a new client connecting to `hostname`:`port`; data connections are opened in active mode if
+    /// `active` is true, in passive mode otherwise
+    pub fn new<S: AsRef<str>>(hostname: S, port: u16, active: bool) -> Self {
+        Self {
+            stream: None,
+            hostname: hostname.as_ref().to_string(),
+            port,
+            username: String::from("anonymous"),
+            password: None,
+            active,
+        }
+    }
+
+    /// Set username
+    pub fn username<S: AsRef<str>>(mut self, username: S) -> Self {
+        self.username = username.as_ref().to_string();
+        self
+    }
+
+    /// Set password
+    pub fn password<S: AsRef<str>>(mut self, password: S) -> Self {
+        self.password = Some(password.as_ref().to_string());
+        self
+    }
+
+    /// Make the TLS configuration of the control and the data connections
+    fn tls_config() -> ClientConfig {
+        let mut config = ClientConfig::builder()
+            .with_safe_defaults()
+            .with_custom_certificate_verifier(Arc::new(NoCertificateVerification))
+            .with_no_client_auth();
+        config.resumption = Resumption::in_memory_sessions(TLS_SESSIONS);
+        config
+    }
+
+    /// Get the stream, if connected
+    fn stream(&mut self) -> RemoteResult<&mut RustlsFtpStream> {
+        self.stream
+            .as_mut()
+            .ok_or_else(|| RemoteError::new(RemoteErrorType::NotConnected))
+    }
+
+    /// Fix `p`; on Windows the backslashes are converted to slashes
+    fn resolve(p: &Path) -> String {
+        let p = p.to_string_lossy().to_string();
+        #[cfg(win)]
+        let p = p.replace('\\', "/");
+        p
+    }
+
+    /// Parse the lines of the LIST command output for the entries of `path`
+    fn parse_list_lines(path: &Path, lines: Vec<String>) -> Vec<File> {
+        lines
+            .into_iter()
+            .flat_map(FtpFile::try_from)
+            .map(|f| {
+                let file_type = if f.is_symlink() {
+                    FileType::Symlink
+                } else if f.is_directory() {
+                    FileType::Directory
+                } else {
+                    FileType::File
+                };
+                let metadata = Metadata {
+                    file_type,
+                    gid: f.gid(),
+                    mode: Some(Self::query_unix_pex(&f)),
+                    modified: Some(f.modified()),
+                    size: f.size() as u64,
+                    symlink: f.symlink().map(|x| absolutize(path, x)),
+                    ..Default::default()
+                };
+                File {
+                    path: path.join(f.name()),
+                    metadata,
+                }
+            })
+            .collect()
+    }
+
+    /// Get the unix pex of `f`
+    fn query_unix_pex(f: &FtpFile) -> UnixPex {
+        let class =
+            |query| UnixPexClass::new(f.can_read(query), f.can_write(query), f.can_execute(query));
+        UnixPex::new(
+            class(PosixPexQuery::Owner),
+            class(PosixPexQuery::Group),
+            class(PosixPexQuery::Others),
+        )
+    }
+
+    /// Make the protocol error reported when `what` fails with `err`
+    fn protocol_error(what: &str, err: FtpError) -> RemoteError {
+        error!("{what}: {err}");
+        RemoteError::new_ex(RemoteErrorType::ProtocolError, err)
+    }
+}
+
+impl RemoteFs for FtpsSessionFs {
+    fn connect(&mut self) -> RemoteResult<Welcome> {
+        info!("Connecting to {}:{}", self.hostname, self.port);
+        let stream =
+            RustlsFtpStream::connect(format!("{}:{}", self.hostname, self.port)).map_err(|e| {
+                error!("Failed to connect to remote server: {e}");
+                RemoteError::new_ex(RemoteErrorType::ConnectionError, e)
+            })?;
+        debug!("Setting up TLS stream...");
+        let connector = RustlsConnector::from(Arc::new(Self::tls_config()));
+        let mut stream = stream
+            .into_secure(connector, self.hostname.as_str())
+            .map_err(|e| {
+                error!("Failed to negotiate TLS with server: {e}");
+                RemoteError::new_ex(RemoteErrorType::SslError, e)
+            })?;
+        debug!("Signin in as {}", self.username);
+        stream
+            .login(
+                self.username.as_str(),
+                self.password.as_deref().unwrap_or(""),
+            )
+            .map_err(|e| {
+                error!("Authentication failed: {e}");
+                RemoteError::new_ex(RemoteErrorType::AuthenticationFailed, e)
+            })?;
+        stream
+            .transfer_type(FtpFileType::Binary)
+            .map_err(|e| Self::protocol_error("Failed to set transfer type to Binary", e))?;
+        let mode = match self.active {
+            true => Mode::Active,
+            false => Mode::Passive,
+        };
+        debug!("ftp data connections mode: {mode:?}");
+        stream.set_mode(mode);
+        let welcome = Welcome::default().banner(stream.get_welcome_msg().map(|x| x.to_string()));
+        self.stream = Some(stream);
+        Ok(welcome)
+    }
+
+    fn disconnect(&mut self) -> RemoteResult<()> {
+        info!("Disconnecting from FTP server...");
+        self.stream()?.quit().map_err(|e| {
+            error!("Failed to disconnect from remote: {e}");
+            RemoteError::new_ex(RemoteErrorType::ConnectionError, e)
+        })?;
+        self.stream = None;
+        Ok(())
+    }
+
+    fn is_connected(&mut self) -> bool {
+        self.stream.is_some()
+    }
+
+    fn pwd(&mut self) -> RemoteResult<PathBuf> {
+        self.stream()?
+            .pwd()
+            .map(PathBuf::from)
+            .map_err(|e| Self::protocol_error("Pwd failed", e))
+    }
+
+    fn change_dir(&mut self, dir: &Path) -> RemoteResult<PathBuf> {
+        let dir = Self::resolve(dir);
+        self.stream()?
+            .cwd(dir.as_str())
+            .map(|_| PathBuf::from(dir))
+            .map_err(|e| {
+                error!("Failed to change directory: {e}");
+                RemoteError::new_ex(RemoteErrorType::NoSuchFileOrDirectory, e)
+            })
+    }
+
+    fn list_dir(&mut self, path: &Path) -> RemoteResult<Vec<File>> {
+        let dir = Self::resolve(path);
+        self.stream()?
+            .list(Some(dir.as_str()))
+            .map(|lines| Self::parse_list_lines(Path::new(&dir), lines))
+            .map_err(|e| Self::protocol_error("Failed to list directory", e))
+    }
+
+    fn stat(&mut self, path: &Path) -> RemoteResult<File> {
+        let wrkdir = self.pwd()?;
+        let path = absolutize(&wrkdir, Path::new(&Self::resolve(path)));
+        let Some(parent) = path.parent() else {
+            warn!("{} has no parent: returning root", path.display());
+            return Ok(File {
+                path: PathBuf::from("/"),
+                metadata: Metadata::default().file_type(FileType::Directory),
+            });
+        };
+        self.list_dir(parent)?
+            .into_iter()
+            .find(|x| x.path() == path.as_path())
+            .ok_or_else(|| RemoteError::new(RemoteErrorType::NoSuchFileOrDirectory))
+    }
+
+    fn setstat(&mut self, _path: &Path, _metadata: Metadata) -> RemoteResult<()> {
+        Err(RemoteError::new(RemoteErrorType::UnsupportedFeature))
+    }
+
+    fn exists(&mut self, path: &Path) -> RemoteResult<bool> {
+        match self.stat(path) {
+            Ok(_) => Ok(true),
+            Err(RemoteError {
+                kind: RemoteErrorType::NoSuchFileOrDirectory,
+                ..
+            }) => Ok(false),
+            Err(err) => Err(err),
+        }
+    }
+
+    fn remove_file(&mut self, path: &Path) -> RemoteResult<()> {
+        let path = Self::resolve(path);
+        self.stream()?
+            .rm(path.as_str())
+            .map_err(|e| Self::protocol_error("Failed to remove file", e))
+    }
+
+    fn remove_dir(&mut self, path: &Path) -> RemoteResult<()> {
+        let path = Self::resolve(path);
+        self.stream()?
+            .rmdir(path.as_str())
+            .map_err(|e| Self::protocol_error("Failed to remove directory", e))
+    }
+
+    fn create_dir(&mut self, path: &Path, _mode: UnixPex) -> RemoteResult<()> {
+        let path = Self::resolve(path);
+        match self.stream()?.mkdir(path.as_str()) {
+            Ok(_) => Ok(()),
+            Err(FtpError::UnexpectedResponse(Response {
+                status: Status::FileUnavailable,
+                ..
+            })) => {
+                error!("Failed to create directory: directory already exists");
+                Err(RemoteError::new(RemoteErrorType::DirectoryAlreadyExists))
+            }
+            Err(e) => Err(Self::protocol_error("Failed to create directory", e)),
+        }
+    }
+
+    fn symlink(&mut self, _path: &Path, _target: &Path) -> RemoteResult<()> {
+        Err(RemoteError::new(RemoteErrorType::UnsupportedFeature))
+    }
+
+    fn copy(&mut self, _src: &Path, _dest: &Path) -> RemoteResult<()> {
+        Err(RemoteError::new(RemoteErrorType::UnsupportedFeature))
+    }
+
+    fn mov(&mut self, src: &Path, dest: &Path) -> RemoteResult<()> {
+        let src = Self::resolve(src);
+        let dest = Self::resolve(dest);
+        self.stream()?
+            .rename(src.as_str(), dest.as_str())
+            .map_err(|e| Self::protocol_error("Failed to rename file", e))
+    }
+
+    fn exec(&mut self, _cmd: &str) -> RemoteResult<(u32, String)> {
+        Err(RemoteError::new(RemoteErrorType::UnsupportedFeature))
+    }
+
+    fn append(&mut self, path: &Path, _metadata: &Metadata) -> RemoteResult<WriteStream> {
+        let path = Self::resolve(path);
+        self.stream()?
+            .append_with_stream(path.as_str())
+            .map(|x| WriteStream::from(Box::new(x) as Box<dyn Write + Send>))
+            .map_err(|e| Self::protocol_error("Failed to open file", e))
+    }
+
+    fn create(&mut self, path: &Path, _metadata: &Metadata) -> RemoteResult<WriteStream> {
+        let path = Self::resolve(path);
+        self.stream()?
+            .put_with_stream(path.as_str())
+            .map(|x| WriteStream::from(Box::new(x) as Box<dyn Write + Send>))
+            .map_err(|e| Self::protocol_error("Failed to open file", e))
+    }
+
+    fn open(&mut self, path: &Path) -> RemoteResult<ReadStream> {
+        let path = Self::resolve(path);
+        self.stream()?
+            .retr_as_stream(path.as_str())
+            .map(|x| ReadStream::from(Box::new(x) as Box<dyn Read + Send>))
+            .map_err(|e| Self::protocol_error("Failed to open file", e))
+    }
+
+    fn on_written(&mut self, writable: WriteStream) -> RemoteResult<()> {
+        self.stream()?
+            .finalize_put_stream(writable)
+            .map_err(|e| Self::protocol_error("Failed to finalize write stream", e))
+    }
+
+    fn on_read(&mut self, readable: ReadStream) -> RemoteResult<()> {
+        self.stream()?
+            .finalize_retr_stream(readable)
+            .map_err(|e| Self::protocol_error("Failed to finalize read stream", e))
+    }
+}
+
+/// Certificate verifier accepting any certificate of the server
+struct NoCertificateVerification;
+
+impl ServerCertVerifier for NoCertificateVerification {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &Certificate,
+        _intermediates: &[Certificate],
+        _server_name: &ServerName,
+        _scts: &mut dyn Iterator<Item = &[u8]>,
+        _ocsp_response: &[u8],
+        _now: SystemTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        Ok(ServerCertVerified::assertion())
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn should_parse_list_lines() {
+        let files = FtpsSessionFs::parse_list_lines(
+            Path::new("/home/omar"),
+            vec![
+                String::from("-rw-r--r-- 1 omar omar 8192 Nov 5 2018 readme.txt"),
+                String::from("drwxr-xr-x 1 omar omar 4096 Nov 5 2018 docs"),
+                String::from("total 2"),
+            ],
+        );
+        assert_eq!(files.len(), 2);
+        assert_eq!(files[0].path(), Path::new("/home/omar/readme.txt"));
+        assert!(files[0].is_file());
+        assert_eq!(files[0].metadata().size, 8192);
+        assert_eq!(files[0].metadata().mode, Some(UnixPex::from(0o644)));
+        assert_eq!(files[1].path(), Path::new("/home/omar/docs"));
+        assert!(files[1].is_dir());
+    }
+
+    #[test]
+    fn should_not_operate_when_disconnected() {
+        let mut client = FtpsSessionFs::new("127.0.0.1", 21, false)
+            .username("omar")
+            .password("secret");
+        assert_eq!(client.is_connected(), false);
+        assert_eq!(
+            client.pwd().unwrap_err().kind,
+            RemoteErrorType::NotConnected
+        );
+        assert_eq!(
+            client.disconnect().unwrap_err().kind,
+            RemoteErrorType::NotConnected
+        );
+    }
+}
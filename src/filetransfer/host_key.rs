@@ -0,0 +1,357 @@
+//! ## HostKey
+//!
+//! Verification of the host keys of ssh servers against the known hosts file
+
+use std::fs::{self, OpenOptions};
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+
+use base64::engine::general_purpose::{STANDARD, STANDARD_NO_PAD};
+use base64::Engine as _;
+use sha2::{Digest, Sha256};
+use ssh2::{CheckResult, KnownHostFileKind, Session};
+
+/// Key of an ssh server, sent during the handshake
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HostKey {
+    host: String,
+    port: u16,
+    /// Public key, in the ssh wire format
+    key: Vec<u8>,
+}
+
+impl HostKey {
+    pub fn new(host: impl ToString, port: u16, key: Vec<u8>) -> Self {
+        Self {
+            host: host.to_string(),
+            port,
+            key,
+        }
+    }
+
+    /// Name of the host in the known hosts file, which includes the port if it's not the default one
+    pub fn host_name(&self) -> String {
+        match self.port {
+            22 => self.host.clone(),
+            port => format!("[{}]:{port}", self.host),
+        }
+    }
+
+    /// Type of the key, such as `ssh-ed25519`, read from its first field
+    pub fn key_type(&self) -> String {
+        self.key
+            .get(..4)
+            .map(|len| u32::from_be_bytes([len[0], len[1], len[2], len[3]]) as usize)
+            .and_then(|len| self.key.get(4..4 + len))
+            .map(|name| String::from_utf8_lossy(name).to_string())
+            .unwrap_or_else(|| String::from("unknown"))
+    }
+
+    /// SHA256 fingerprint of the key, formatted as OpenSSH does, e.g. `SHA256:ZkAslGjFiUHdGf/WUL8r...`
+    pub fn fingerprint(&self) -> String {
+        format!(
+            "SHA256:{}",
+            STANDARD_NO_PAD.encode(Sha256::digest(&self.key))
+        )
+    }
+
+    /// Line of the known hosts file for this key
+    fn known_hosts_line(&self) -> String {
+        format!(
+            "{} {} {}",
+            self.host_name(),
+            self.key_type(),
+            STANDARD.encode(&self.key)
+        )
+    }
+}
+
+/// Outcome of the verification of a host key
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HostKeyStatus {
+    /// The key is in the known hosts file
+    Known,
+    /// The host has no key of this type in the known hosts file
+    Unknown,
+    /// The host has a different key of this type in the known hosts file
+    Changed,
+}
+
+/// Known hosts file, in the OpenSSH format
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KnownHosts {
+    path: PathBuf,
+}
+
+impl KnownHosts {
+    /// Known hosts file at `path`; a leading `~` is the home directory
+    pub fn new(path: &str) -> Self {
+        let path = match (path.strip_prefix("~/"), dirs::home_dir()) {
+            (Some(path), Some(home)) => home.join(path),
+            _ => PathBuf::from(path),
+        };
+        Self { path }
+    }
+
+    pub fn path(&self) -> &Path {
+        self.path.as_path()
+    }
+
+    /// Verify `key` against the keys of its host. A missing file knows no host
+    pub fn check(&self, key: &HostKey) -> io::Result<HostKeyStatus> {
+        let key_type = key.key_type();
+        let mut status = HostKeyStatus::Unknown;
+        for (line_type, result) in self.check_lines(&key.host_name(), &key.key)? {
+            match result {
+                CheckResult::Match => return Ok(HostKeyStatus::Known),
+                CheckResult::Mismatch if line_type == key_type => status = HostKeyStatus::Changed,
+                _ => {}
+            }
+        }
+        Ok(status)
+    }
+
+    /// Get the types of the keys of `host` at `port`
+    pub fn key_types(&self, host: &str, port: u16) -> io::Result<Vec<String>> {
+        let host = HostKey::new(host, port, Vec::new()).host_name();
+        let mut key_types: Vec<String> = Vec::new();
+        // no key matches a null byte, so the lines of the host mismatch
+        for (line_type, result) in self.check_lines(&host, &[0])? {
+            if !matches!(result, CheckResult::NotFound) && !key_types.contains(&line_type) {
+                key_types.push(line_type);
+            }
+        }
+        Ok(key_types)
+    }
+
+    /// Append `key` to the file, creating it if it doesn't exist
+    pub fn add(&self, key: &HostKey) -> io::Result<()> {
+        if let Some(dir) = self.path.parent().filter(|x| !x.exists()) {
+            fs::create_dir_all(dir)?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.path.as_path())?;
+        // the last line of the file may not be terminated
+        if file.metadata()?.len() > 0 && !self.ends_with_newline()? {
+            writeln!(file)?;
+        }
+        writeln!(file, "{}", key.known_hosts_line())?;
+        info!(
+            "added the {} key of {} to {}",
+            key.key_type(),
+            key.host_name(),
+            self.path.display()
+        );
+        Ok(())
+    }
+
+    /// Check `key` of `host`, as named in the known hosts file, against each line of the file,
+    /// returning the key type of the line and the outcome.
+    ///
+    /// Lines are checked one by one, since libssh2 fails on the whole file if it can't parse a line,
+    /// e.g. because of a key type it doesn't support.
+    /// The port is part of the host name, since libssh2 would match the entries without a port for any port
+    fn check_lines(&self, host: &str, key: &[u8]) -> io::Result<Vec<(String, CheckResult)>> {
+        let content = match fs::read_to_string(self.path.as_path()) {
+            Ok(content) => content,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(err) => return Err(err),
+        };
+        let session = Session::new().map_err(io::Error::other)?;
+        let mut results = Vec::new();
+        for line in content.lines().map(str::trim) {
+            // markers, such as `@cert-authority` and `@revoked`, are not supported
+            if line.is_empty() || line.starts_with('#') || line.starts_with('@') {
+                continue;
+            }
+            let Some(line_type) = line.split_whitespace().nth(1) else {
+                continue;
+            };
+            let mut known_hosts = session.known_hosts().map_err(io::Error::other)?;
+            if let Err(err) = known_hosts.read_str(line, KnownHostFileKind::OpenSSH) {
+                debug!("skipping known hosts line \"{line}\": {err}");
+                continue;
+            }
+            results.push((line_type.to_string(), known_hosts.check(host, key)));
+        }
+        Ok(results)
+    }
+
+    fn ends_with_newline(&self) -> io::Result<bool> {
+        let mut content = Vec::new();
+        fs::File::open(self.path.as_path())?.read_to_end(&mut content)?;
+        Ok(content.ends_with(b"\n"))
+    }
+}
+
+/// Get the host key algorithms to negotiate the key types `key_types` with the server.
+/// Rsa keys are negotiated with the sha2 signatures too
+pub fn host_key_algorithms(key_types: &[String]) -> Vec<String> {
+    let mut algorithms: Vec<String> = Vec::new();
+    for key_type in key_types {
+        let names: &[&str] = match key_type.as_str() {
+            "ssh-rsa" => &["rsa-sha2-512", "rsa-sha2-256", "ssh-rsa"],
+            name => &[name],
+        };
+        for name in names {
+            if !algorithms.iter().any(|x| x == name) {
+                algorithms.push(name.to_string());
+            }
+        }
+    }
+    algorithms
+}
+
+#[cfg(test)]
+mod test {
+
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    use super::*;
+
+    /// Make an ed25519 key from `seed`
+    fn ed25519_key(host: &str, port: u16, seed: u8) -> HostKey {
+        let mut key = Vec::new();
+        key.extend_from_slice(&11u32.to_be_bytes());
+        key.extend_from_slice(b"ssh-ed25519");
+        key.extend_from_slice(&32u32.to_be_bytes());
+        key.extend((0..32).map(|x| x + seed));
+        HostKey::new(host, port, key)
+    }
+
+    #[test]
+    fn should_format_host_key() {
+        let key = ed25519_key("example.com", 22, 0);
+        assert_eq!(key.key_type(), "ssh-ed25519");
+        assert_eq!(
+            key.fingerprint(),
+            "SHA256:ZkAslGjFiUHdGf/WUL8rQvkib4PTvQatUV0OUQSncCA"
+        );
+        assert_eq!(key.host_name(), "example.com");
+        assert_eq!(
+            key.known_hosts_line(),
+            "example.com ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIAABAgMEBQYHCAkKCwwNDg8QERITFBUWFxgZGhscHR4f"
+        );
+        assert_eq!(
+            ed25519_key("example.com", 2022, 0).host_name(),
+            "[example.com]:2022"
+        );
+        assert_eq!(
+            HostKey::new("example.com", 22, vec![0]).key_type(),
+            "unknown"
+        );
+    }
+
+    #[test]
+    fn should_accept_and_persist_host_key() {
+        let tmpdir = TempDir::new().unwrap();
+        let known_hosts = KnownHosts::new(
+            tmpdir
+                .path()
+                .join(".ssh")
+                .join("known_hosts")
+                .to_str()
+                .unwrap(),
+        );
+        let key = ed25519_key("example.com", 22, 0);
+        // the file doesn't exist yet
+        assert_eq!(known_hosts.check(&key).unwrap(), HostKeyStatus::Unknown);
+        known_hosts.add(&key).unwrap();
+        assert_eq!(known_hosts.check(&key).unwrap(), HostKeyStatus::Known);
+        assert_eq!(
+            known_hosts.key_types("example.com", 22).unwrap(),
+            vec![String::from("ssh-ed25519")]
+        );
+        // a different key of the same type has changed
+        assert_eq!(
+            known_hosts
+                .check(&ed25519_key("example.com", 22, 1))
+                .unwrap(),
+            HostKeyStatus::Changed
+        );
+        // other hosts and ports are unknown
+        assert_eq!(
+            known_hosts.check(&ed25519_key("other.com", 22, 0)).unwrap(),
+            HostKeyStatus::Unknown
+        );
+        let key_2022 = ed25519_key("example.com", 2022, 1);
+        assert_eq!(
+            known_hosts.check(&key_2022).unwrap(),
+            HostKeyStatus::Unknown
+        );
+        known_hosts.add(&key_2022).unwrap();
+        assert_eq!(known_hosts.check(&key_2022).unwrap(), HostKeyStatus::Known);
+        assert_eq!(known_hosts.check(&key).unwrap(), HostKeyStatus::Known);
+        assert!(known_hosts.key_types("other.com", 22).unwrap().is_empty());
+    }
+
+    #[test]
+    fn should_skip_unsupported_known_hosts_lines() {
+        let tmpdir = TempDir::new().unwrap();
+        let path = tmpdir.path().join("known_hosts");
+        fs::write(
+            path.as_path(),
+            "# comment\n@cert-authority *.example.com ssh-ed25519 AAAA\nbroken line\nexample.com sk-ssh-ed25519@openssh.com AAAA",
+        )
+        .unwrap();
+        let known_hosts = KnownHosts::new(path.to_str().unwrap());
+        let key = ed25519_key("example.com", 22, 0);
+        assert_eq!(known_hosts.check(&key).unwrap(), HostKeyStatus::Unknown);
+        // the key is written on a new line
+        known_hosts.add(&key).unwrap();
+        assert_eq!(known_hosts.check(&key).unwrap(), HostKeyStatus::Known);
+    }
+
+    #[test]
+    fn should_check_hashed_host_names() {
+        let tmpdir = TempDir::new().unwrap();
+        let path = tmpdir.path().join("known_hosts");
+        fs::write(
+            path.as_path(),
+            "|1|AAECAwQFBgcICQoLDA0ODxAREhM=|ns/zeZYVTLaRf/qtfP/8g0h9tt4= ssh-ed25519 AAAAC3NzaC1lZDI1NTE5AAAAIAABAgMEBQYHCAkKCwwNDg8QERITFBUWFxgZGhscHR4f\n",
+        )
+        .unwrap();
+        let known_hosts = KnownHosts::new(path.to_str().unwrap());
+        assert_eq!(
+            known_hosts
+                .check(&ed25519_key("hashed.com", 22, 0))
+                .unwrap(),
+            HostKeyStatus::Known
+        );
+        assert_eq!(
+            known_hosts
+                .check(&ed25519_key("hashed.com", 22, 1))
+                .unwrap(),
+            HostKeyStatus::Changed
+        );
+    }
+
+    #[test]
+    fn should_get_host_key_algorithms() {
+        assert_eq!(
+            host_key_algorithms(&[
+                String::from("ssh-ed25519"),
+                String::from("ssh-rsa"),
+                String::from("ssh-ed25519")
+            ]),
+            vec![
+                String::from("ssh-ed25519"),
+                String::from("rsa-sha2-512"),
+                String::from("rsa-sha2-256"),
+                String::from("ssh-rsa"),
+            ]
+        );
+        assert!(host_key_algorithms(&[]).is_empty());
+    }
+
+    #[test]
+    fn should_expand_known_hosts_home() {
+        let known_hosts = KnownHosts::new("~/.ssh/known_hosts");
+        if let Some(home) = dirs::home_dir() {
+            assert_eq!(known_hosts.path(), home.join(".ssh/known_hosts").as_path());
+        }
+    }
+}
@@ -0,0 +1,701 @@
+//! ## HostKeyGuard
+//!
+//! Relay between the ssh clients and the server, which lets the handshake complete only once the host key is verified
+
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{Shutdown, SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use remotefs::{RemoteError, RemoteErrorType, RemoteResult};
+
+use super::ssh_session::{HostKeyCheck, SshNegotiationHandle};
+
+/// Interval between the polls of the listener, while waiting for the client to connect
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+/// Maximum length of the packets exchanged before the key exchange reply
+const MAX_PACKET_LEN: usize = 256 * 1024;
+/// Maximum length of the lines sent by the server before the packets, the version line included
+const MAX_LINE_LEN: usize = 8192;
+/// `SSH_MSG_KEXINIT`
+const MSG_KEXINIT: u8 = 20;
+/// `SSH_MSG_KEXDH_REPLY`, which is `SSH_MSG_KEX_ECDH_REPLY` too, or `SSH_MSG_KEX_DH_GEX_GROUP` in the group exchange
+const MSG_KEXDH_REPLY: u8 = 31;
+/// `SSH_MSG_KEX_DH_GEX_REPLY`
+const MSG_KEX_DH_GEX_REPLY: u8 = 33;
+
+/// Relay the ssh client connects to, instead of the server.
+///
+/// The host key is read from the key exchange reply of the server, which is held back until the key is verified:
+/// without the reply the client can't complete the handshake, so no credentials are sent to a server whose key is not known.
+/// The reply is the message of the key exchange negotiated in the key exchange init messages of the client and the server.
+/// Since the client checks that the reply is signed with the key, its session is the one with the verified server
+pub struct HostKeyGuard {
+    check: HostKeyCheck,
+    /// Listener on localhost the client connects to; `None` if it couldn't be bound, so connections are refused
+    listener: Option<TcpListener>,
+}
+
+impl HostKeyGuard {
+    /// Bind the listener of the relay, which connects to the server of `check`
+    pub fn new(check: HostKeyCheck) -> Self {
+        let listener = match TcpListener::bind("127.0.0.1:0") {
+            Ok(listener) => Some(listener),
+            Err(err) => {
+                error!("could not bind the host key guard: {err}");
+                None
+            }
+        };
+        Self { check, listener }
+    }
+
+    pub fn check(&self) -> &HostKeyCheck {
+        &self.check
+    }
+
+    /// Address the client must connect to
+    pub fn local_addr(&self) -> Option<SocketAddr> {
+        self.listener.as_ref().and_then(|x| x.local_addr().ok())
+    }
+
+    /// Stop listening, so connections are refused; to call if the client couldn't be made to connect to the relay
+    pub fn close(&mut self) {
+        self.listener = None;
+    }
+
+    /// Relay the next connection of the client to the server, in a background thread
+    pub fn relay(&self, negotiation: &SshNegotiationHandle) -> RemoteResult<Relay> {
+        let listener = self
+            .listener
+            .as_ref()
+            .and_then(|x| x.try_clone().ok())
+            .ok_or_else(|| {
+                RemoteError::new_ex(
+                    RemoteErrorType::ConnectionError,
+                    "the host key can't be verified: the host key guard is not listening on localhost",
+                )
+            })?;
+        let relay = Relay {
+            cancelled: Arc::new(AtomicBool::new(false)),
+            error: Arc::new(Mutex::new(None)),
+        };
+        let check = self.check.clone();
+        let negotiation = negotiation.clone();
+        let cancelled = relay.cancelled.clone();
+        let error = relay.error.clone();
+        thread::spawn(move || {
+            relay_connection(&listener, &check, &negotiation, &cancelled, &error)
+        });
+        Ok(relay)
+    }
+}
+
+/// Connection relayed by the [`HostKeyGuard`]
+pub struct Relay {
+    /// Stops waiting for the client, once the relay is dropped
+    cancelled: Arc<AtomicBool>,
+    error: Arc<Mutex<Option<RemoteError>>>,
+}
+
+impl Relay {
+    /// Get the error the connection has been closed for, if the host key has been refused or the server is unreachable.
+    /// It's set before the connection is closed, so it's available once the client has failed
+    pub fn error(&self) -> Option<RemoteError> {
+        self.error.lock().ok().and_then(|mut x| x.take())
+    }
+}
+
+impl Drop for Relay {
+    fn drop(&mut self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Accept the connection of the client, connect to the server and relay the data between them,
+/// holding back the key exchange reply of the server until its host key is verified
+fn relay_connection(
+    listener: &TcpListener,
+    check: &HostKeyCheck,
+    negotiation: &SshNegotiationHandle,
+    cancelled: &AtomicBool,
+    error: &Mutex<Option<RemoteError>>,
+) {
+    let client = match accept(listener, check.timeout, cancelled) {
+        Ok(Some(client)) => client,
+        Ok(None) => return,
+        Err(err) => {
+            error!("host key guard could not accept the connection: {err}");
+            return;
+        }
+    };
+    let result = tcp_connect(&check.address.0, check.address.1, check.timeout)
+        .and_then(|server| relay_handshake(&client, &server, check, negotiation).map(|_| server));
+    let server = match result {
+        Ok(server) => server,
+        Err(err) => {
+            if let Ok(mut x) = error.lock() {
+                *x = Some(err);
+            }
+            let _ = client.shutdown(Shutdown::Both);
+            return;
+        }
+    };
+    // the handshake goes on with the verified server: the following packets are encrypted with the keys of the session,
+    // which only the client and the verified server have
+    let _ = server.set_read_timeout(None);
+    if let (Ok(mut reader), Ok(mut writer)) = (server.try_clone(), client.try_clone()) {
+        let _ = io::copy(&mut reader, &mut writer);
+    }
+    let _ = client.shutdown(Shutdown::Both);
+    let _ = server.shutdown(Shutdown::Both);
+}
+
+/// Wait for the client to connect, until `timeout` or until the relay is cancelled
+fn accept(
+    listener: &TcpListener,
+    timeout: Duration,
+    cancelled: &AtomicBool,
+) -> io::Result<Option<TcpStream>> {
+    listener.set_nonblocking(true)?;
+    let started_at = Instant::now();
+    while !cancelled.load(Ordering::Relaxed) && started_at.elapsed() < timeout {
+        match listener.accept() {
+            Ok((stream, _)) => {
+                stream.set_nonblocking(false)?;
+                return Ok(Some(stream));
+            }
+            Err(err) if err.kind() == io::ErrorKind::WouldBlock => thread::sleep(POLL_INTERVAL),
+            Err(err) => return Err(err),
+        }
+    }
+    Ok(None)
+}
+
+fn tcp_connect(address: &str, port: u16, timeout: Duration) -> RemoteResult<TcpStream> {
+    let addresses: Vec<SocketAddr> = (address, port)
+        .to_socket_addrs()
+        .map_err(|e| RemoteError::new_ex(RemoteErrorType::BadAddress, e))?
+        .collect();
+    addresses
+        .iter()
+        .find_map(|address| TcpStream::connect_timeout(address, timeout).ok())
+        .ok_or_else(|| {
+            RemoteError::new_ex(
+                RemoteErrorType::ConnectionError,
+                format!("could not connect to {address}:{port}"),
+            )
+        })
+}
+
+/// Forward the data of the client to the server in a background thread, and the data of the server to the client
+/// up to the key exchange reply, which is forwarded only once the host key it carries is verified.
+/// The key exchange init message of the client is passed to the handshake, to know the negotiated key exchange.
+///
+/// Returns an error if the key is refused, if the server doesn't send it within the timeout or if the handshake can't be
+/// read; the data of the server is never forwarded past the reply if the key is not verified
+fn relay_handshake(
+    client: &TcpStream,
+    server: &TcpStream,
+    check: &HostKeyCheck,
+    negotiation: &SshNegotiationHandle,
+) -> RemoteResult<()> {
+    let io_error = |err: io::Error| RemoteError::new_ex(RemoteErrorType::IoError, err);
+    let mut upstream = server.try_clone().map_err(io_error)?;
+    let downstream = client.try_clone().map_err(io_error)?;
+    let (kexinit_tx, kexinit_rx) = mpsc::channel();
+    thread::spawn(move || {
+        let mut reader = BufReader::new(downstream);
+        if let Ok(kexinit) = read_kexinit(&mut reader, &mut upstream) {
+            let _ = kexinit_tx.send(kexinit);
+            let _ = io::copy(&mut reader, &mut upstream);
+        }
+        let _ = upstream.shutdown(Shutdown::Write);
+    });
+    server
+        .set_read_timeout(Some(check.timeout))
+        .map_err(io_error)?;
+    let mut reader = BufReader::new(server);
+    let mut writer = client;
+    let client_kexinit = || {
+        kexinit_rx
+            .recv_timeout(check.timeout)
+            .map_err(|err| match err {
+                mpsc::RecvTimeoutError::Timeout => io::ErrorKind::TimedOut.into(),
+                mpsc::RecvTimeoutError::Disconnected => io::ErrorKind::UnexpectedEof.into(),
+            })
+    };
+    let key = match read_host_key(&mut reader, &mut writer, client_kexinit) {
+        Ok(key) => key,
+        Err(err)
+            if err.kind() == io::ErrorKind::WouldBlock || err.kind() == io::ErrorKind::TimedOut =>
+        {
+            return Err(RemoteError::new_ex(
+                RemoteErrorType::ConnectionError,
+                format!(
+                    "the server didn't send its host key within {} seconds",
+                    check.timeout.as_secs()
+                ),
+            ))
+        }
+        Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => {
+            return Err(RemoteError::new_ex(
+                RemoteErrorType::ConnectionError,
+                "the connection has been closed before the host key was sent",
+            ))
+        }
+        Err(err) => {
+            return Err(RemoteError::new_ex(
+                RemoteErrorType::ProtocolError,
+                format!("could not read the host key in the ssh handshake: {err}"),
+            ))
+        }
+    };
+    check.verify(&key.key, negotiation)?;
+    // data read past the reply is buffered
+    writer
+        .write_all(&key.packet)
+        .and_then(|_| writer.write_all(reader.buffer()))
+        .map_err(io_error)
+}
+
+/// Key exchange reply of the server
+struct KexReply {
+    packet: Vec<u8>,
+    /// Host key carried by the reply, in the ssh wire format
+    key: Vec<u8>,
+}
+
+/// Forward the version line, and the lines before it, from `reader` to `writer`
+fn forward_version(reader: &mut impl BufRead, writer: &mut impl Write) -> io::Result<()> {
+    loop {
+        let mut line = Vec::new();
+        reader
+            .by_ref()
+            .take(MAX_LINE_LEN as u64)
+            .read_until(b'\n', &mut line)?;
+        if !line.ends_with(b"\n") {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+        writer.write_all(&line)?;
+        if line.starts_with(b"SSH-") {
+            return Ok(());
+        }
+    }
+}
+
+/// Forward the version exchange and the key exchange init message of the client, from `reader` to `writer`, returning
+/// the payload of the key exchange init
+fn read_kexinit(reader: &mut impl BufRead, writer: &mut impl Write) -> io::Result<Vec<u8>> {
+    forward_version(reader, writer)?;
+    let packet = read_packet(reader)?;
+    writer.write_all(&packet)?;
+    match packet_payload(&packet) {
+        Some(payload) if payload.first() == Some(&MSG_KEXINIT) => Ok(payload.to_vec()),
+        _ => Err(invalid_data(
+            "the first packet is not the key exchange init",
+        )),
+    }
+}
+
+/// Forward the version exchange and the packets of the server to the client, up to the key exchange reply,
+/// which is returned instead.
+///
+/// The reply is looked for only after the key exchange init of the server, once the key exchange is negotiated with
+/// the key exchange init of the client, got with `client_kexinit`
+fn read_host_key(
+    reader: &mut impl BufRead,
+    writer: &mut impl Write,
+    client_kexinit: impl FnOnce() -> io::Result<Vec<u8>>,
+) -> io::Result<KexReply> {
+    // the server can send other lines before the version line
+    forward_version(reader, writer)?;
+    let mut client_kexinit = Some(client_kexinit);
+    let mut reply_msg = None;
+    loop {
+        let packet = read_packet(reader)?;
+        let payload =
+            packet_payload(&packet).ok_or_else(|| invalid_data("invalid packet padding"))?;
+        match (payload.first().copied(), reply_msg) {
+            (Some(msg), Some(reply_msg)) if msg == reply_msg => {
+                let key = kex_reply_host_key(payload)
+                    .ok_or_else(|| invalid_data("the key exchange reply has no host key"))?;
+                return Ok(KexReply { packet, key });
+            }
+            (Some(MSG_KEXINIT), None) => {
+                writer.write_all(&packet)?;
+                let client_kexinit = client_kexinit.take().map(|x| x()).transpose()?;
+                let kex = client_kexinit
+                    .as_deref()
+                    .and_then(|client| negotiated_kex(client, payload))
+                    .ok_or_else(|| invalid_data("no key exchange algorithm in common"))?;
+                debug!("host key guard: negotiated key exchange {kex}");
+                reply_msg = Some(kex_reply_msg(&kex));
+            }
+            _ => writer.write_all(&packet)?,
+        }
+    }
+}
+
+/// Read an unencrypted packet, its length included
+fn read_packet(reader: &mut impl Read) -> io::Result<Vec<u8>> {
+    let mut packet = vec![0; 4];
+    reader.read_exact(&mut packet)?;
+    let len = u32::from_be_bytes([packet[0], packet[1], packet[2], packet[3]]) as usize;
+    if len == 0 || len > MAX_PACKET_LEN {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("invalid packet length {len}"),
+        ));
+    }
+    packet.resize(4 + len, 0);
+    reader.read_exact(&mut packet[4..])?;
+    Ok(packet)
+}
+
+fn invalid_data(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+/// Get the payload of an unencrypted packet, without the length and the padding
+fn packet_payload(packet: &[u8]) -> Option<&[u8]> {
+    let padding = *packet.get(4)? as usize;
+    packet.get(5..packet.len().checked_sub(padding)?)
+}
+
+/// Get the key exchange negotiated by the key exchange init payloads of the client and the server:
+/// the first algorithm of the client the server supports
+fn negotiated_kex(client: &[u8], server: &[u8]) -> Option<String> {
+    let client = kex_algorithms(client)?;
+    let server = kex_algorithms(server)?;
+    client
+        .split(',')
+        .find(|x| !x.is_empty() && server.split(',').any(|y| y == *x))
+        .map(str::to_string)
+}
+
+/// Get the key exchange algorithms of a key exchange init payload, which follow the message number and the cookie
+fn kex_algorithms(payload: &[u8]) -> Option<&str> {
+    let fields = payload.get(17..)?;
+    std::str::from_utf8(read_string(fields)?).ok()
+}
+
+/// Get the number of the message carrying the host key in the key exchange `kex`
+fn kex_reply_msg(kex: &str) -> u8 {
+    if kex.starts_with("diffie-hellman-group-exchange-") {
+        MSG_KEX_DH_GEX_REPLY
+    } else {
+        MSG_KEXDH_REPLY
+    }
+}
+
+/// Get the host key of the key exchange reply `payload`
+fn kex_reply_host_key(payload: &[u8]) -> Option<Vec<u8>> {
+    let key = read_string(payload.get(1..)?)?;
+    // the key starts with its type, such as `ssh-ed25519`
+    let key_type = read_string(key)?;
+    let is_key = !key_type.is_empty()
+        && key_type.len() <= 64
+        && key_type.iter().all(|x| x.is_ascii_graphic());
+    is_key.then(|| key.to_vec())
+}
+
+/// Read the string at the start of `data`, which is prefixed by its length
+fn read_string(data: &[u8]) -> Option<&[u8]> {
+    let len = data.get(..4)?;
+    let len = u32::from_be_bytes([len[0], len[1], len[2], len[3]]) as usize;
+    data.get(4..4usize.checked_add(len)?)
+}
+
+#[cfg(test)]
+mod test {
+
+    use std::path::Path;
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::filetransfer::host_key::{HostKey, KnownHosts};
+
+    fn ed25519_key(seed: u8) -> Vec<u8> {
+        let mut key = Vec::new();
+        key.extend_from_slice(&11u32.to_be_bytes());
+        key.extend_from_slice(b"ssh-ed25519");
+        key.extend_from_slice(&32u32.to_be_bytes());
+        key.extend((0..32).map(|x| x + seed));
+        key
+    }
+
+    /// Make an unencrypted packet with `payload`
+    fn packet(payload: &[u8]) -> Vec<u8> {
+        let padding = 8 - (payload.len() + 5) % 8 + 4;
+        let mut packet = Vec::new();
+        packet.extend_from_slice(&((payload.len() + padding + 1) as u32).to_be_bytes());
+        packet.push(padding as u8);
+        packet.extend_from_slice(payload);
+        packet.extend(std::iter::repeat_n(0, padding));
+        packet
+    }
+
+    fn string(data: &[u8]) -> Vec<u8> {
+        let mut string = (data.len() as u32).to_be_bytes().to_vec();
+        string.extend_from_slice(data);
+        string
+    }
+
+    /// Make a key exchange init payload with the key exchange algorithms `kex`
+    fn kexinit(kex: &str) -> Vec<u8> {
+        let mut payload = vec![MSG_KEXINIT];
+        payload.extend([7; 16]);
+        payload.extend(string(kex.as_bytes()));
+        for _ in 0..9 {
+            payload.extend(string(b"none"));
+        }
+        payload.extend([0; 5]);
+        payload
+    }
+
+    /// Make the group message of the group exchange, whose modulus is `modulus`
+    fn gex_group(modulus: &[u8]) -> Vec<u8> {
+        let mut payload = vec![MSG_KEXDH_REPLY];
+        payload.extend(string(modulus));
+        payload.extend(string(&[2]));
+        packet(&payload)
+    }
+
+    /// Make a key exchange reply carrying `key`
+    fn kex_reply(msg: u8, key: &[u8]) -> Vec<u8> {
+        let mut payload = vec![msg];
+        payload.extend(string(key));
+        payload.extend(string(&[1; 32]));
+        payload.extend(string(b"signature"));
+        packet(&payload)
+    }
+
+    fn check(known_hosts: &Path, address: SocketAddr) -> HostKeyCheck {
+        HostKeyCheck {
+            address: (address.ip().to_string(), address.port()),
+            host: (String::from("example.com"), 22),
+            known_hosts: KnownHosts::new(known_hosts.to_str().unwrap()),
+            algorithms: Vec::new(),
+            timeout: Duration::from_secs(5),
+        }
+    }
+
+    /// Serve the handshake of a server with `key` on a listener, returning its address
+    fn serve_handshake(key: Vec<u8>) -> SocketAddr {
+        let mut packets = packet(&kexinit("curve25519-sha256"));
+        packets.extend(kex_reply(MSG_KEXDH_REPLY, &key));
+        serve_packets(packets)
+    }
+
+    /// Serve the version line and `packets` on a listener, up to the new keys message, returning its address
+    fn serve_packets(packets: Vec<u8>) -> SocketAddr {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut data = b"SSH-2.0-OpenSSH_9.6\r\n".to_vec();
+            data.extend(packets);
+            data.extend(packet(&[21]));
+            let _ = stream.write_all(&data);
+            let _ = stream.read(&mut [0; 64]);
+        });
+        address
+    }
+
+    /// Connect to the guard, with the key exchange init of a client supporting `kex`, and read what the relay forwards,
+    /// until it's closed
+    fn read_relayed(address: SocketAddr, kex: &str) -> Vec<u8> {
+        let mut stream = TcpStream::connect(address).unwrap();
+        stream
+            .set_read_timeout(Some(Duration::from_secs(5)))
+            .unwrap();
+        let mut data = b"SSH-2.0-termscp\r\n".to_vec();
+        data.extend(packet(&kexinit(kex)));
+        let _ = stream.write_all(&data);
+        let mut data = Vec::new();
+        let mut buffer = [0; 1024];
+        // the server keeps the connection open after the handshake
+        while !data.ends_with(&packet(&[21])) {
+            match stream.read(&mut buffer) {
+                Ok(0) | Err(_) => break,
+                Ok(bytes) => data.extend_from_slice(&buffer[..bytes]),
+            }
+        }
+        data
+    }
+
+    #[test]
+    fn should_get_host_key_of_kex_reply() {
+        let reply = kex_reply(MSG_KEXDH_REPLY, &ed25519_key(0));
+        assert_eq!(
+            kex_reply_host_key(packet_payload(&reply).unwrap()),
+            Some(ed25519_key(0))
+        );
+        assert_eq!(kex_reply_host_key(&[MSG_KEXDH_REPLY, 0, 0]), None);
+        assert!(packet_payload(&[0, 0, 0, 1]).is_none());
+    }
+
+    #[test]
+    fn should_negotiate_kex() {
+        let client = kexinit("ext-info-c,diffie-hellman-group-exchange-sha256,curve25519-sha256");
+        let server = kexinit("curve25519-sha256,diffie-hellman-group-exchange-sha256,ext-info-s");
+        assert_eq!(
+            negotiated_kex(&client, &server).as_deref(),
+            Some("diffie-hellman-group-exchange-sha256")
+        );
+        assert_eq!(
+            negotiated_kex(
+                &kexinit("curve25519-sha256"),
+                &kexinit("ecdh-sha2-nistp256")
+            ),
+            None
+        );
+        assert_eq!(negotiated_kex(&[MSG_KEXINIT], &server), None);
+        assert_eq!(
+            kex_reply_msg("diffie-hellman-group-exchange-sha256"),
+            MSG_KEX_DH_GEX_REPLY
+        );
+        assert_eq!(kex_reply_msg("curve25519-sha256"), MSG_KEXDH_REPLY);
+        assert_eq!(
+            kex_reply_msg("diffie-hellman-group14-sha256"),
+            MSG_KEXDH_REPLY
+        );
+    }
+
+    #[test]
+    fn should_read_host_key() {
+        let mut data = b"banner\r\nSSH-2.0-OpenSSH_9.6\r\n".to_vec();
+        data.extend(packet(&kexinit("curve25519-sha256")));
+        data.extend(kex_reply(MSG_KEXDH_REPLY, &ed25519_key(0)));
+        data.extend(packet(&[21]));
+        let mut reader = io::Cursor::new(data);
+        let mut forwarded = Vec::new();
+        let reply = read_host_key(&mut reader, &mut forwarded, || {
+            Ok(kexinit("curve25519-sha256"))
+        })
+        .unwrap();
+        assert_eq!(reply.key, ed25519_key(0));
+        assert_eq!(reply.packet, kex_reply(MSG_KEXDH_REPLY, &ed25519_key(0)));
+        // the reply is held back
+        let mut expected = b"banner\r\nSSH-2.0-OpenSSH_9.6\r\n".to_vec();
+        expected.extend(packet(&kexinit("curve25519-sha256")));
+        assert_eq!(forwarded, expected);
+        // the connection is closed before the reply
+        let mut reader = io::Cursor::new(b"SSH-2.0-OpenSSH_9.6\r\n".to_vec());
+        assert!(read_host_key(&mut reader, &mut Vec::new(), || Ok(kexinit(""))).is_err());
+        // no key exchange in common
+        let mut data = b"SSH-2.0-OpenSSH_9.6\r\n".to_vec();
+        data.extend(packet(&kexinit("curve25519-sha256")));
+        data.extend(kex_reply(MSG_KEXDH_REPLY, &ed25519_key(0)));
+        let mut reader = io::Cursor::new(data);
+        let err = read_host_key(&mut reader, &mut Vec::new(), || {
+            Ok(kexinit("ecdh-sha2-nistp256"))
+        })
+        .err()
+        .unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn should_read_host_key_of_group_exchange() {
+        // the group message can't be taken for the reply, even if its modulus is a known key
+        let group = gex_group(&ed25519_key(0));
+        let mut data = b"SSH-2.0-OpenSSH_9.6\r\n".to_vec();
+        data.extend(packet(&kexinit("diffie-hellman-group-exchange-sha256")));
+        data.extend(&group);
+        data.extend(kex_reply(MSG_KEX_DH_GEX_REPLY, &ed25519_key(1)));
+        let mut reader = io::Cursor::new(data);
+        let mut forwarded = Vec::new();
+        let reply = read_host_key(&mut reader, &mut forwarded, || {
+            Ok(kexinit("diffie-hellman-group-exchange-sha256"))
+        })
+        .unwrap();
+        assert_eq!(reply.key, ed25519_key(1));
+        assert!(forwarded.ends_with(&group));
+    }
+
+    #[test]
+    fn should_relay_verified_host_key() {
+        let tmpdir = tempfile::TempDir::new().unwrap();
+        let known_hosts = tmpdir.path().join("known_hosts");
+        let check = check(&known_hosts, serve_handshake(ed25519_key(0)));
+        check
+            .known_hosts
+            .add(&HostKey::new("example.com", 22, ed25519_key(0)))
+            .unwrap();
+        let guard = HostKeyGuard::new(check);
+        let negotiation = SshNegotiationHandle::default();
+        let relay = guard.relay(&negotiation).unwrap();
+        let data = read_relayed(guard.local_addr().unwrap(), "curve25519-sha256");
+        assert!(data.ends_with(&packet(&[21])));
+        assert!(relay.error().is_none());
+        assert!(negotiation.rejected_host_key().is_none());
+    }
+
+    #[test]
+    fn should_hold_back_unknown_host_key() {
+        let tmpdir = tempfile::TempDir::new().unwrap();
+        let known_hosts = tmpdir.path().join("known_hosts");
+        let guard = HostKeyGuard::new(check(&known_hosts, serve_handshake(ed25519_key(0))));
+        let negotiation = SshNegotiationHandle::default();
+        let relay = guard.relay(&negotiation).unwrap();
+        let data = read_relayed(guard.local_addr().unwrap(), "curve25519-sha256");
+        // the connection is closed before the reply
+        let mut expected = b"SSH-2.0-OpenSSH_9.6\r\n".to_vec();
+        expected.extend(packet(&kexinit("curve25519-sha256")));
+        assert_eq!(data, expected);
+        let err = relay.error().unwrap();
+        assert_eq!(err.kind, RemoteErrorType::ConnectionError);
+        assert!(err.to_string().contains("unknown"));
+        assert_eq!(
+            negotiation.rejected_host_key().unwrap().key.key_type(),
+            "ssh-ed25519"
+        );
+    }
+
+    #[test]
+    fn should_hold_back_unknown_host_key_of_group_exchange() {
+        let tmpdir = tempfile::TempDir::new().unwrap();
+        let known_hosts = tmpdir.path().join("known_hosts");
+        // the modulus of the group is the known key, the reply carries another key
+        let kex = "diffie-hellman-group-exchange-sha256";
+        let mut packets = packet(&kexinit(kex));
+        packets.extend(gex_group(&ed25519_key(0)));
+        packets.extend(kex_reply(MSG_KEX_DH_GEX_REPLY, &ed25519_key(1)));
+        let check = check(&known_hosts, serve_packets(packets));
+        check
+            .known_hosts
+            .add(&HostKey::new("example.com", 22, ed25519_key(0)))
+            .unwrap();
+        let guard = HostKeyGuard::new(check);
+        let negotiation = SshNegotiationHandle::default();
+        let relay = guard.relay(&negotiation).unwrap();
+        let data = read_relayed(guard.local_addr().unwrap(), kex);
+        let mut expected = b"SSH-2.0-OpenSSH_9.6\r\n".to_vec();
+        expected.extend(packet(&kexinit(kex)));
+        expected.extend(gex_group(&ed25519_key(0)));
+        assert_eq!(data, expected);
+        let err = relay.error().unwrap();
+        assert!(err.to_string().contains("has changed"));
+        assert!(negotiation.rejected_host_key().is_some());
+    }
+
+    #[test]
+    fn should_report_unreachable_server() {
+        let tmpdir = tempfile::TempDir::new().unwrap();
+        let address = TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap();
+        let guard = HostKeyGuard::new(check(&tmpdir.path().join("known_hosts"), address));
+        let relay = guard.relay(&SshNegotiationHandle::default()).unwrap();
+        assert!(read_relayed(guard.local_addr().unwrap(), "curve25519-sha256").is_empty());
+        assert_eq!(
+            relay.error().unwrap().kind,
+            RemoteErrorType::ConnectionError
+        );
+    }
+}
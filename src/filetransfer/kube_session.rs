@@ -0,0 +1,503 @@
+//! ## KubeSession
+//!
+//! Kube client which lists the namespaces, when no namespace is set, along with the pods and their containers
+
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+
+use k8s_openapi::api::core::v1::Namespace;
+use kube::{Api, Client, Config};
+use remotefs::fs::{
+    File, FileType, Metadata, ReadStream, RemoteError, RemoteErrorType, UnixPex, Welcome,
+    WriteStream,
+};
+use remotefs::{RemoteFs, RemoteResult};
+use remotefs_kube::KubeMultiPodFs;
+use tokio::runtime::Runtime;
+
+/// Kube client browsing the pods of a namespace and the file systems of their containers.
+///
+/// If the namespace is set, paths have the format `/pod-name/container-name/path/to/file`; otherwise the namespaces
+/// are listed in the root directory, and paths have the format `/namespace/pod-name/container-name/path/to/file`.
+/// The namespaces, the pods and the containers are listed as directories, but files can be handled only within the
+/// file system of a container
+pub struct KubeNamespacedFs {
+    runtime: Arc<Runtime>,
+    config: Option<Config>,
+    /// Namespace set in the connection parameters
+    namespace: Option<String>,
+    /// Api of the namespaces, once connected; used only if the namespace is not set
+    namespaces: Option<Api<Namespace>>,
+    /// Client of the pods of each namespace, connected on demand
+    pods: HashMap<String, KubeMultiPodFs>,
+    wrkdir: PathBuf,
+}
+
+impl KubeNamespacedFs {
+    /// Instantiates a new client. If `namespace` is not set, the namespaces are listed in the root directory
+    pub fn new(runtime: &Arc<Runtime>, config: Option<Config>, namespace: Option<String>) -> Self {
+        Self {
+            runtime: runtime.clone(),
+            config,
+            namespace,
+            namespaces: None,
+            pods: HashMap::new(),
+            wrkdir: PathBuf::from("/"),
+        }
+    }
+
+    /// Get the absolute path of `path`, resolving it from the working directory
+    fn absolute(&self, path: &Path) -> PathBuf {
+        let mut abs = PathBuf::from("/");
+        for component in self.wrkdir.join(path).components() {
+            match component {
+                Component::Normal(name) => abs.push(name),
+                Component::ParentDir => {
+                    abs.pop();
+                }
+                _ => {}
+            }
+        }
+        abs
+    }
+
+    /// Split `path` into its namespace and the path in the namespace, made of the pod, the container and
+    /// the path in the container file system
+    fn split(&self, path: &Path) -> (Option<String>, PathBuf) {
+        let abs = self.absolute(path);
+        if let Some(namespace) = self.namespace.as_ref() {
+            return (Some(namespace.clone()), abs);
+        }
+        let mut components = abs.components();
+        components.next();
+        match components.next() {
+            Some(namespace) => (
+                Some(namespace.as_os_str().to_string_lossy().to_string()),
+                Path::new("/").join(components.as_path()),
+            ),
+            None => (None, abs),
+        }
+    }
+
+    /// Make the path of the namespace client `path` absolute
+    fn join(&self, namespace: &str, path: &Path) -> PathBuf {
+        match self.namespace.is_some() {
+            true => path.to_path_buf(),
+            false => Path::new("/")
+                .join(namespace)
+                .join(path.strip_prefix("/").unwrap_or(path)),
+        }
+    }
+
+    /// Fix the path of `file`, got from the client of `namespace`
+    fn fix_path(&self, namespace: &str, mut file: File) -> File {
+        file.path = self.join(namespace, file.path.as_path());
+        file
+    }
+
+    /// Check whether `path` is in the file system of a container, so that files can be handled there
+    fn check_container_path(&self, path: &Path, kind: RemoteErrorType) -> RemoteResult<()> {
+        let (levels, depth) = match self.namespace.is_some() {
+            true => ("a pod and a container", 3),
+            false => ("a namespace, a pod and a container", 4),
+        };
+        match self.absolute(path).components().count() > depth {
+            true => Ok(()),
+            false => Err(RemoteError::new_ex(
+                kind,
+                format!(
+                    "\"{}\" is not in the file system of a container: enter {levels} first",
+                    path.display()
+                ),
+            )),
+        }
+    }
+
+    /// Get the client of the pods of `namespace`, connecting it if necessary
+    fn pods(&mut self, namespace: &str) -> RemoteResult<&mut KubeMultiPodFs> {
+        if !self.pods.contains_key(namespace) {
+            let mut config = self.config.clone().ok_or_else(|| {
+                RemoteError::new_ex(
+                    RemoteErrorType::NotConnected,
+                    "Not connected to a Kubernetes cluster",
+                )
+            })?;
+            config.default_namespace = namespace.to_string();
+            let mut client = KubeMultiPodFs::new(&self.runtime).config(config);
+            client.connect()?;
+            self.pods.insert(namespace.to_string(), client);
+        }
+        Ok(self.pods.get_mut(namespace).unwrap())
+    }
+
+    fn namespaces_api(&self) -> RemoteResult<&Api<Namespace>> {
+        self.namespaces.as_ref().ok_or_else(|| {
+            RemoteError::new_ex(
+                RemoteErrorType::NotConnected,
+                "Not connected to a Kubernetes cluster",
+            )
+        })
+    }
+
+    /// List the namespaces of the cluster
+    fn list_namespaces(&self) -> RemoteResult<Vec<File>> {
+        let api = self.namespaces_api()?;
+        let namespaces = self
+            .runtime
+            .block_on(async { api.list(&Default::default()).await })
+            .map_err(|err| {
+                RemoteError::new_ex(
+                    RemoteErrorType::ProtocolError,
+                    format!("could not list the namespaces (set the namespace to connect to it directly): {err}"),
+                )
+            })?;
+        Ok(namespaces
+            .into_iter()
+            .filter_map(|x| x.metadata.name)
+            .map(|name| Self::directory(Path::new("/").join(name)))
+            .collect())
+    }
+
+    /// Check whether `namespace` exists
+    fn check_namespace(&self, namespace: &str) -> RemoteResult<()> {
+        if self.namespace.is_some() {
+            return Ok(());
+        }
+        let api = self.namespaces_api()?;
+        self.runtime
+            .block_on(async { api.get(namespace).await })
+            .map(|_| ())
+            .map_err(|err| {
+                RemoteError::new_ex(
+                    RemoteErrorType::NoSuchFileOrDirectory,
+                    format!("namespace {namespace} not found: {err}"),
+                )
+            })
+    }
+
+    fn directory(path: PathBuf) -> File {
+        File {
+            path,
+            metadata: Metadata::default().file_type(FileType::Directory),
+        }
+    }
+}
+
+impl RemoteFs for KubeNamespacedFs {
+    fn connect(&mut self) -> RemoteResult<Welcome> {
+        debug!("Initializing Kube connection...");
+        if self.config.is_none() {
+            let config = self
+                .runtime
+                .block_on(Config::infer())
+                .map_err(|err| RemoteError::new_ex(RemoteErrorType::ConnectionError, err))?;
+            self.config = Some(config);
+        }
+        match self.namespace.clone() {
+            Some(namespace) => {
+                self.pods(&namespace)?;
+            }
+            None => {
+                let config = self.config.clone().unwrap();
+                let api = self.runtime.block_on(async {
+                    Client::try_from(config)
+                        .map(Api::all)
+                        .map_err(|err| RemoteError::new_ex(RemoteErrorType::ConnectionError, err))
+                })?;
+                self.namespaces = Some(api);
+            }
+        }
+        self.wrkdir = PathBuf::from("/");
+        Ok(Welcome::default())
+    }
+
+    fn disconnect(&mut self) -> RemoteResult<()> {
+        for (_, mut client) in self.pods.drain() {
+            if let Err(err) = client.disconnect() {
+                debug!("Failed to disconnect kube client: {err}");
+            }
+        }
+        self.namespaces = None;
+        Ok(())
+    }
+
+    fn is_connected(&mut self) -> bool {
+        match self.namespace.clone() {
+            Some(namespace) => self
+                .pods
+                .get_mut(&namespace)
+                .is_some_and(|x| x.is_connected()),
+            None => self.namespaces.is_some(),
+        }
+    }
+
+    fn pwd(&mut self) -> RemoteResult<PathBuf> {
+        Ok(self.wrkdir.clone())
+    }
+
+    fn change_dir(&mut self, dir: &Path) -> RemoteResult<PathBuf> {
+        let (namespace, path) = self.split(dir);
+        self.wrkdir = match namespace {
+            None => PathBuf::from("/"),
+            Some(namespace) => {
+                self.check_namespace(&namespace)?;
+                let wrkdir = self.pods(&namespace)?.change_dir(path.as_path())?;
+                self.join(&namespace, wrkdir.as_path())
+            }
+        };
+        Ok(self.wrkdir.clone())
+    }
+
+    fn list_dir(&mut self, path: &Path) -> RemoteResult<Vec<File>> {
+        let (namespace, path) = self.split(path);
+        let Some(namespace) = namespace else {
+            return self.list_namespaces();
+        };
+        let files = self.pods(&namespace)?.list_dir(path.as_path())?;
+        Ok(files
+            .into_iter()
+            .map(|x| self.fix_path(&namespace, x))
+            .collect())
+    }
+
+    fn stat(&mut self, path: &Path) -> RemoteResult<File> {
+        let (namespace, rest) = self.split(path);
+        let Some(namespace) = namespace else {
+            return Ok(Self::directory(PathBuf::from("/")));
+        };
+        if self.namespace.is_none() && rest == Path::new("/") {
+            self.check_namespace(&namespace)?;
+            return Ok(Self::directory(self.join(&namespace, rest.as_path())));
+        }
+        let file = self.pods(&namespace)?.stat(rest.as_path())?;
+        Ok(self.fix_path(&namespace, file))
+    }
+
+    fn setstat(&mut self, path: &Path, metadata: Metadata) -> RemoteResult<()> {
+        match self.split(path) {
+            (Some(namespace), path) => self.pods(&namespace)?.setstat(path.as_path(), metadata),
+            (None, _) => Ok(()),
+        }
+    }
+
+    fn exists(&mut self, path: &Path) -> RemoteResult<bool> {
+        let (namespace, rest) = self.split(path);
+        let Some(namespace) = namespace else {
+            return Ok(true);
+        };
+        if self.namespace.is_none() && rest == Path::new("/") {
+            return Ok(self.check_namespace(&namespace).is_ok());
+        }
+        self.pods(&namespace)?.exists(rest.as_path())
+    }
+
+    fn remove_file(&mut self, path: &Path) -> RemoteResult<()> {
+        self.check_container_path(path, RemoteErrorType::CouldNotRemoveFile)?;
+        let (namespace, path) = self.split(path);
+        self.pods(&namespace.unwrap_or_default())?
+            .remove_file(path.as_path())
+    }
+
+    fn remove_dir(&mut self, path: &Path) -> RemoteResult<()> {
+        self.check_container_path(path, RemoteErrorType::CouldNotRemoveFile)?;
+        let (namespace, path) = self.split(path);
+        self.pods(&namespace.unwrap_or_default())?
+            .remove_dir(path.as_path())
+    }
+
+    fn remove_dir_all(&mut self, path: &Path) -> RemoteResult<()> {
+        self.check_container_path(path, RemoteErrorType::CouldNotRemoveFile)?;
+        let (namespace, path) = self.split(path);
+        self.pods(&namespace.unwrap_or_default())?
+            .remove_dir_all(path.as_path())
+    }
+
+    fn create_dir(&mut self, path: &Path, mode: UnixPex) -> RemoteResult<()> {
+        self.check_container_path(path, RemoteErrorType::CouldNotOpenFile)?;
+        let (namespace, path) = self.split(path);
+        self.pods(&namespace.unwrap_or_default())?
+            .create_dir(path.as_path(), mode)
+    }
+
+    fn symlink(&mut self, path: &Path, target: &Path) -> RemoteResult<()> {
+        self.check_container_path(path, RemoteErrorType::CouldNotOpenFile)?;
+        let (namespace, path) = self.split(path);
+        self.pods(&namespace.unwrap_or_default())?
+            .symlink(path.as_path(), target)
+    }
+
+    fn copy(&mut self, src: &Path, dest: &Path) -> RemoteResult<()> {
+        let (namespace, src, dest) = self.split_pair(src, dest)?;
+        self.pods(&namespace)?.copy(src.as_path(), dest.as_path())
+    }
+
+    fn mov(&mut self, src: &Path, dest: &Path) -> RemoteResult<()> {
+        let (namespace, src, dest) = self.split_pair(src, dest)?;
+        self.pods(&namespace)?.mov(src.as_path(), dest.as_path())
+    }
+
+    fn exec(&mut self, cmd: &str) -> RemoteResult<(u32, String)> {
+        let wrkdir = self.wrkdir.clone();
+        self.check_container_path(wrkdir.as_path(), RemoteErrorType::ProtocolError)?;
+        let (namespace, _) = self.split(wrkdir.as_path());
+        self.pods(&namespace.unwrap_or_default())?.exec(cmd)
+    }
+
+    fn append(&mut self, _path: &Path, _metadata: &Metadata) -> RemoteResult<WriteStream> {
+        Err(RemoteError::new(RemoteErrorType::UnsupportedFeature))
+    }
+
+    fn create(&mut self, _path: &Path, _metadata: &Metadata) -> RemoteResult<WriteStream> {
+        Err(RemoteError::new(RemoteErrorType::UnsupportedFeature))
+    }
+
+    fn open(&mut self, _path: &Path) -> RemoteResult<ReadStream> {
+        Err(RemoteError::new(RemoteErrorType::UnsupportedFeature))
+    }
+
+    fn append_file(
+        &mut self,
+        path: &Path,
+        metadata: &Metadata,
+        reader: Box<dyn Read + Send>,
+    ) -> RemoteResult<u64> {
+        self.check_container_path(path, RemoteErrorType::CouldNotOpenFile)?;
+        let (namespace, path) = self.split(path);
+        self.pods(&namespace.unwrap_or_default())?
+            .append_file(path.as_path(), metadata, reader)
+    }
+
+    fn create_file(
+        &mut self,
+        path: &Path,
+        metadata: &Metadata,
+        reader: Box<dyn Read + Send>,
+    ) -> RemoteResult<u64> {
+        self.check_container_path(path, RemoteErrorType::CouldNotOpenFile)?;
+        let (namespace, path) = self.split(path);
+        self.pods(&namespace.unwrap_or_default())?
+            .create_file(path.as_path(), metadata, reader)
+    }
+
+    fn open_file(&mut self, src: &Path, dest: Box<dyn Write + Send>) -> RemoteResult<u64> {
+        self.check_container_path(src, RemoteErrorType::CouldNotOpenFile)?;
+        let (namespace, src) = self.split(src);
+        self.pods(&namespace.unwrap_or_default())?
+            .open_file(src.as_path(), dest)
+    }
+}
+
+impl KubeNamespacedFs {
+    /// Split `src` and `dest` of a copy or a move, which must be in the file systems of containers of the same namespace
+    fn split_pair(&self, src: &Path, dest: &Path) -> RemoteResult<(String, PathBuf, PathBuf)> {
+        self.check_container_path(src, RemoteErrorType::CouldNotOpenFile)?;
+        self.check_container_path(dest, RemoteErrorType::CouldNotOpenFile)?;
+        let (src_namespace, src) = self.split(src);
+        let (dest_namespace, dest) = self.split(dest);
+        match src_namespace == dest_namespace {
+            true => Ok((src_namespace.unwrap_or_default(), src, dest)),
+            false => Err(RemoteError::new_ex(
+                RemoteErrorType::UnsupportedFeature,
+                "files can't be copied or moved to a different namespace",
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn should_split_namespaced_paths() {
+        let mut fs = client(None);
+        assert_eq!(fs.split(Path::new("/")), (None, PathBuf::from("/")));
+        assert_eq!(
+            fs.split(Path::new("/default")),
+            (Some(String::from("default")), PathBuf::from("/"))
+        );
+        assert_eq!(
+            fs.split(Path::new("/default/nginx/web/etc/nginx/../hosts")),
+            (
+                Some(String::from("default")),
+                PathBuf::from("/nginx/web/etc/hosts")
+            )
+        );
+        // relative paths are resolved from the working directory
+        fs.wrkdir = PathBuf::from("/default/nginx");
+        assert_eq!(
+            fs.split(Path::new("web/tmp")),
+            (
+                Some(String::from("default")),
+                PathBuf::from("/nginx/web/tmp")
+            )
+        );
+        assert_eq!(
+            fs.join("default", Path::new("/nginx/web/tmp")),
+            PathBuf::from("/default/nginx/web/tmp")
+        );
+        // with the namespace set, paths start from the pods
+        let fs = client(Some("default"));
+        assert_eq!(
+            fs.split(Path::new("/nginx/web/tmp")),
+            (
+                Some(String::from("default")),
+                PathBuf::from("/nginx/web/tmp")
+            )
+        );
+        assert_eq!(
+            fs.join("default", Path::new("/nginx/web/tmp")),
+            PathBuf::from("/nginx/web/tmp")
+        );
+    }
+
+    #[test]
+    fn should_handle_files_only_in_containers() {
+        let mut fs = client(None);
+        for path in ["/", "/default", "/default/nginx", "/default/nginx/web"] {
+            let err = fs.remove_file(Path::new(path)).unwrap_err();
+            assert_eq!(err.kind, RemoteErrorType::CouldNotRemoveFile);
+            assert!(fs
+                .create_dir(Path::new(path), UnixPex::from(0o755))
+                .is_err());
+        }
+        assert!(fs
+            .check_container_path(
+                Path::new("/default/nginx/web/tmp"),
+                RemoteErrorType::CouldNotOpenFile
+            )
+            .is_ok());
+        assert!(fs.exec("ls").is_err());
+        assert!(fs
+            .copy(
+                Path::new("/default/nginx/web/tmp/a"),
+                Path::new("/kube-system/coredns/coredns/tmp/a")
+            )
+            .is_err());
+        let fs = client(Some("default"));
+        assert!(fs
+            .check_container_path(Path::new("/nginx/web"), RemoteErrorType::CouldNotOpenFile)
+            .is_err());
+        assert!(fs
+            .check_container_path(
+                Path::new("/nginx/web/tmp"),
+                RemoteErrorType::CouldNotOpenFile
+            )
+            .is_ok());
+    }
+
+    fn client(namespace: Option<&str>) -> KubeNamespacedFs {
+        let runtime = Arc::new(
+            tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .unwrap(),
+        );
+        KubeNamespacedFs::new(&runtime, None, namespace.map(str::to_string))
+    }
+}
@@ -0,0 +1,441 @@
+//! ## LocalhostFs
+//!
+//! Remotefs client which works on the local host, to browse the local file system in the remote panel too
+
+use std::env;
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use remotefs::fs::stream::{ReadAndSeek, WriteAndSeek};
+use remotefs::fs::{File, Metadata, ReadStream, UnixPex, Welcome, WriteStream};
+use remotefs::{RemoteError, RemoteErrorType, RemoteFs, RemoteResult};
+
+use crate::host::{HostBridge, HostError, HostErrorType, Localhost};
+use crate::utils::path;
+
+/// Remotefs client on the local host.
+///
+/// The local file system is accessed through [`Localhost`], which is created on connect
+pub struct LocalhostFs {
+    /// Working directory to start from, when connecting
+    wrkdir: PathBuf,
+    host: Option<Localhost>,
+}
+
+impl LocalhostFs {
+    /// Instantiates a new `LocalhostFs`, which starts from `wrkdir` once connected
+    pub fn new(wrkdir: PathBuf) -> Self {
+        Self { wrkdir, host: None }
+    }
+
+    fn host(&mut self) -> RemoteResult<&mut Localhost> {
+        self.host
+            .as_mut()
+            .ok_or_else(|| RemoteError::new(RemoteErrorType::NotConnected))
+    }
+
+    /// Convert `p` to an absolute path, according to the working directory
+    fn abs_path(&mut self, p: &Path) -> RemoteResult<PathBuf> {
+        let wrkdir = self.host()?.pwd().map_err(remote_error)?;
+        Ok(path::absolutize(wrkdir.as_path(), p))
+    }
+
+    fn open_std_file(
+        path: &Path,
+        options: &mut std::fs::OpenOptions,
+    ) -> RemoteResult<std::fs::File> {
+        options.open(path).map_err(|err| {
+            let kind = match err.kind() {
+                std::io::ErrorKind::NotFound => RemoteErrorType::NoSuchFileOrDirectory,
+                std::io::ErrorKind::PermissionDenied => RemoteErrorType::PexError,
+                _ => RemoteErrorType::CouldNotOpenFile,
+            };
+            RemoteError::new_ex(kind, format!("{}: {err}", path.display()))
+        })
+    }
+}
+
+/// Seekable stream of a local file
+struct FileStream(std::fs::File);
+
+impl Read for FileStream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.0.read(buf)
+    }
+}
+
+impl Write for FileStream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.0.flush()
+    }
+}
+
+impl Seek for FileStream {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.0.seek(pos)
+    }
+}
+
+impl ReadAndSeek for FileStream {}
+
+impl WriteAndSeek for FileStream {}
+
+/// Convert the error of the local host into the error of a remotefs client.
+///
+/// Errors are never reported as connection errors, since the connection to the local host can't be lost
+fn remote_error(err: HostError) -> RemoteError {
+    let kind = match &err.error {
+        HostErrorType::NoSuchFileOrDirectory => RemoteErrorType::NoSuchFileOrDirectory,
+        HostErrorType::ReadonlyFile => RemoteErrorType::PexError,
+        HostErrorType::DirNotAccessible | HostErrorType::FileNotAccessible => {
+            RemoteErrorType::CouldNotOpenFile
+        }
+        HostErrorType::FileAlreadyExists | HostErrorType::CouldNotCreateFile => {
+            RemoteErrorType::FileCreateDenied
+        }
+        HostErrorType::ExecutionFailed => RemoteErrorType::IoError,
+        HostErrorType::DeleteFailed => RemoteErrorType::CouldNotRemoveFile,
+        HostErrorType::NotImplemented => RemoteErrorType::UnsupportedFeature,
+        HostErrorType::RemoteFs(err) => err.kind,
+    };
+    RemoteError::new_ex(kind, err)
+}
+
+impl RemoteFs for LocalhostFs {
+    fn connect(&mut self) -> RemoteResult<Welcome> {
+        if self.host.is_none() {
+            debug!("Connecting to localhost at {}", self.wrkdir.display());
+            self.host = Some(Localhost::new(self.wrkdir.clone()).map_err(remote_error)?);
+        }
+        Ok(Welcome::default())
+    }
+
+    fn disconnect(&mut self) -> RemoteResult<()> {
+        self.host = None;
+        Ok(())
+    }
+
+    fn is_connected(&mut self) -> bool {
+        self.host.is_some()
+    }
+
+    fn pwd(&mut self) -> RemoteResult<PathBuf> {
+        self.host()?.pwd().map_err(remote_error)
+    }
+
+    fn change_dir(&mut self, dir: &Path) -> RemoteResult<PathBuf> {
+        let dir = self.abs_path(dir)?;
+        // localhost changes the working directory of the process, which belongs to the host bridge
+        let cwd = env::current_dir();
+        let result = self
+            .host()?
+            .change_wrkdir(dir.as_path())
+            .map_err(remote_error);
+        if let Ok(cwd) = cwd {
+            if let Err(err) = env::set_current_dir(cwd.as_path()) {
+                warn!(
+                    "Failed to restore working directory {}: {err}",
+                    cwd.display()
+                );
+            }
+        }
+        result
+    }
+
+    fn list_dir(&mut self, path: &Path) -> RemoteResult<Vec<File>> {
+        let path = self.abs_path(path)?;
+        self.host()?.list_dir(path.as_path()).map_err(remote_error)
+    }
+
+    fn stat(&mut self, path: &Path) -> RemoteResult<File> {
+        let path = self.abs_path(path)?;
+        self.host()?.stat(path.as_path()).map_err(remote_error)
+    }
+
+    fn setstat(&mut self, path: &Path, metadata: Metadata) -> RemoteResult<()> {
+        let path = self.abs_path(path)?;
+        self.host()?
+            .setstat(path.as_path(), &metadata)
+            .map_err(remote_error)
+    }
+
+    fn exists(&mut self, path: &Path) -> RemoteResult<bool> {
+        let path = self.abs_path(path)?;
+        self.host()?.exists(path.as_path()).map_err(remote_error)
+    }
+
+    fn remove_file(&mut self, path: &Path) -> RemoteResult<()> {
+        let file = self.stat(path)?;
+        if file.is_dir() {
+            return Err(RemoteError::new_ex(
+                RemoteErrorType::BadFile,
+                format!("{} is a directory", file.path().display()),
+            ));
+        }
+        self.host()?.remove(&file).map_err(remote_error)
+    }
+
+    fn remove_dir(&mut self, path: &Path) -> RemoteResult<()> {
+        let path = self.abs_path(path)?;
+        std::fs::remove_dir(path.as_path()).map_err(|err| {
+            let kind = match path.read_dir().map(|mut x| x.next().is_some()) {
+                Ok(true) => RemoteErrorType::DirectoryNotEmpty,
+                _ => RemoteErrorType::CouldNotRemoveFile,
+            };
+            RemoteError::new_ex(kind, format!("{}: {err}", path.display()))
+        })
+    }
+
+    fn remove_dir_all(&mut self, path: &Path) -> RemoteResult<()> {
+        let file = self.stat(path)?;
+        if !file.is_dir() {
+            return self.host()?.remove(&file).map_err(remote_error);
+        }
+        // not through the host, which would list the working directory again, although it may be the one removed
+        std::fs::remove_dir_all(file.path()).map_err(|err| {
+            RemoteError::new_ex(
+                RemoteErrorType::CouldNotRemoveFile,
+                format!("{}: {err}", file.path().display()),
+            )
+        })
+    }
+
+    fn create_dir(&mut self, path: &Path, mode: UnixPex) -> RemoteResult<()> {
+        let path = self.abs_path(path)?;
+        let host = self.host()?;
+        match host.mkdir_ex(path.as_path(), false) {
+            Ok(()) => {}
+            Err(HostError {
+                error: HostErrorType::FileAlreadyExists,
+                ..
+            }) => {
+                return Err(RemoteError::new_ex(
+                    RemoteErrorType::DirectoryAlreadyExists,
+                    path.display(),
+                ))
+            }
+            Err(err) => return Err(remote_error(err)),
+        }
+        #[cfg(posix)]
+        if let Err(err) = host.chmod(path.as_path(), mode) {
+            warn!("Failed to set mode of {}: {err}", path.display());
+        }
+        #[cfg(win)]
+        let _ = mode;
+        Ok(())
+    }
+
+    fn symlink(&mut self, path: &Path, target: &Path) -> RemoteResult<()> {
+        let path = self.abs_path(path)?;
+        self.host()?
+            .symlink(path.as_path(), target)
+            .map_err(remote_error)
+    }
+
+    fn copy(&mut self, src: &Path, dest: &Path) -> RemoteResult<()> {
+        let file = self.stat(src)?;
+        let dest = self.abs_path(dest)?;
+        self.host()?
+            .copy(&file, dest.as_path())
+            .map_err(remote_error)
+    }
+
+    fn mov(&mut self, src: &Path, dest: &Path) -> RemoteResult<()> {
+        let file = self.stat(src)?;
+        let dest = self.abs_path(dest)?;
+        self.host()?
+            .rename(&file, dest.as_path())
+            .map_err(remote_error)
+    }
+
+    fn exec(&mut self, cmd: &str) -> RemoteResult<(u32, String)> {
+        let wrkdir = self.pwd()?;
+        // commands are run through the shell, since they're already wrapped into the remote shell
+        #[cfg(posix)]
+        let mut command = {
+            let mut command = Command::new("sh");
+            command.arg("-c").arg(cmd);
+            command
+        };
+        #[cfg(win)]
+        let mut command = {
+            let mut command = Command::new("cmd");
+            command.arg("/C").arg(cmd);
+            command
+        };
+        debug!("Executing command on localhost: {cmd}");
+        let output = command
+            .current_dir(wrkdir)
+            .output()
+            .map_err(|err| RemoteError::new_ex(RemoteErrorType::IoError, err))?;
+        Ok((
+            output.status.code().unwrap_or(-1) as u32,
+            String::from_utf8_lossy(&output.stdout).to_string(),
+        ))
+    }
+
+    fn append(&mut self, path: &Path, _metadata: &Metadata) -> RemoteResult<WriteStream> {
+        let path = self.abs_path(path)?;
+        let file = Self::open_std_file(path.as_path(), std::fs::OpenOptions::new().append(true))?;
+        Ok(WriteStream::from(
+            Box::new(FileStream(file)) as Box<dyn WriteAndSeek>
+        ))
+    }
+
+    fn create(&mut self, path: &Path, _metadata: &Metadata) -> RemoteResult<WriteStream> {
+        let path = self.abs_path(path)?;
+        let file = Self::open_std_file(
+            path.as_path(),
+            std::fs::OpenOptions::new()
+                .create(true)
+                .write(true)
+                .truncate(true),
+        )?;
+        Ok(WriteStream::from(
+            Box::new(FileStream(file)) as Box<dyn WriteAndSeek>
+        ))
+    }
+
+    fn open(&mut self, path: &Path) -> RemoteResult<ReadStream> {
+        let path = self.abs_path(path)?;
+        let file = Self::open_std_file(path.as_path(), std::fs::OpenOptions::new().read(true))?;
+        Ok(ReadStream::from(
+            Box::new(FileStream(file)) as Box<dyn ReadAndSeek>
+        ))
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    use super::*;
+
+    fn connected(dir: &TempDir) -> LocalhostFs {
+        let mut client = LocalhostFs::new(dir.path().to_path_buf());
+        assert_eq!(client.is_connected(), false);
+        assert!(client.connect().is_ok());
+        assert_eq!(client.is_connected(), true);
+        client
+    }
+
+    #[test]
+    fn should_not_work_before_connecting() {
+        let dir = TempDir::new().unwrap();
+        let mut client = LocalhostFs::new(dir.path().to_path_buf());
+        assert_eq!(
+            client.pwd().unwrap_err().kind,
+            RemoteErrorType::NotConnected
+        );
+        assert_eq!(
+            client.list_dir(Path::new(".")).unwrap_err().kind,
+            RemoteErrorType::NotConnected
+        );
+        // an unexisting directory can't be connected to
+        let mut client = LocalhostFs::new(dir.path().join("missing"));
+        assert!(client.connect().is_err());
+        assert_eq!(client.is_connected(), false);
+    }
+
+    #[test]
+    fn should_write_and_read_files() {
+        let dir = TempDir::new().unwrap();
+        let mut client = connected(&dir);
+        assert_eq!(client.pwd().unwrap(), dir.path());
+        let mut writer = client
+            .create(Path::new("omar.txt"), &Metadata::default())
+            .unwrap();
+        writer.write_all(b"hello").unwrap();
+        assert!(client.on_written(writer).is_ok());
+        let mut writer = client
+            .append(Path::new("omar.txt"), &Metadata::default())
+            .unwrap();
+        writer.write_all(b" world").unwrap();
+        drop(writer);
+        let mut reader = client.open(&dir.path().join("omar.txt")).unwrap();
+        assert_eq!(reader.seekable(), true);
+        reader.seek(SeekFrom::Start(6)).unwrap();
+        let mut content = String::new();
+        reader.read_to_string(&mut content).unwrap();
+        assert_eq!(content, "world");
+        assert_eq!(
+            client.stat(Path::new("omar.txt")).unwrap().metadata().size,
+            11
+        );
+        assert_eq!(
+            client.open(Path::new("missing.txt")).err().unwrap().kind,
+            RemoteErrorType::NoSuchFileOrDirectory
+        );
+    }
+
+    #[test]
+    fn should_manage_directories() {
+        let dir = TempDir::new().unwrap();
+        let mut client = connected(&dir);
+        assert!(client
+            .create_dir(Path::new("docs"), UnixPex::from(0o755))
+            .is_ok());
+        assert_eq!(
+            client
+                .create_dir(Path::new("docs"), UnixPex::from(0o755))
+                .unwrap_err()
+                .kind,
+            RemoteErrorType::DirectoryAlreadyExists
+        );
+        assert_eq!(
+            client.change_dir(Path::new("docs")).unwrap(),
+            dir.path().join("docs")
+        );
+        client
+            .create(Path::new("a.txt"), &Metadata::default())
+            .unwrap();
+        assert!(client.copy(Path::new("a.txt"), Path::new("b.txt")).is_ok());
+        assert!(client.mov(Path::new("b.txt"), Path::new("c.txt")).is_ok());
+        let mut names: Vec<String> = client
+            .list_dir(Path::new("."))
+            .unwrap()
+            .iter()
+            .map(|x| x.name())
+            .collect();
+        names.sort();
+        assert_eq!(names, vec!["a.txt", "c.txt"]);
+        assert_eq!(
+            client
+                .remove_dir(&dir.path().join("docs"))
+                .unwrap_err()
+                .kind,
+            RemoteErrorType::DirectoryNotEmpty
+        );
+        assert_eq!(
+            client
+                .remove_file(&dir.path().join("docs"))
+                .unwrap_err()
+                .kind,
+            RemoteErrorType::BadFile
+        );
+        assert!(client.remove_file(Path::new("c.txt")).is_ok());
+        assert!(client.remove_dir_all(&dir.path().join("docs")).is_ok());
+        assert_eq!(client.exists(&dir.path().join("docs")).unwrap(), false);
+    }
+
+    #[test]
+    #[cfg(posix)]
+    fn should_exec_commands_in_wrkdir() {
+        let dir = TempDir::new().unwrap();
+        let mut client = connected(&dir);
+        let (rc, output) = client.exec("/bin/sh -c 'pwd'").unwrap();
+        assert_eq!(rc, 0);
+        assert_eq!(
+            PathBuf::from(output.trim()).canonicalize().unwrap(),
+            dir.path().canonicalize().unwrap()
+        );
+        assert_eq!(client.exec("exit 3").unwrap().0, 3);
+    }
+}
@@ -2,14 +2,29 @@
 //!
 //! `filetransfer` is the module which provides the file transfer protocols and remotefs builders
 
+mod ftp_session;
+mod ftps_session;
 mod host_bridge_builder;
+pub mod host_key;
+mod host_key_guard;
+mod kube_session;
+mod localhost_fs;
 pub mod params;
+mod parked_session;
+mod proxy_jump;
 mod remotefs_builder;
+mod s3_session;
+#[cfg(smb_unix)]
+mod smb_session;
+mod ssh_session;
+mod webdav_session;
 
 // -- export types
 pub use host_bridge_builder::HostBridgeBuilder;
 pub use params::{FileTransferParams, HostBridgeParams, ProtocolParams};
+pub use parked_session::ParkedSession;
 pub use remotefs_builder::RemoteFsBuilder;
+pub use ssh_session::{RejectedHostKey, SshNegotiationHandle};
 
 /// This enum defines the different transfer protocol available in termscp
 
@@ -18,12 +33,91 @@ pub enum FileTransferProtocol {
     AwsS3,
     Ftp(bool), // Bool is for secure (true => ftps)
     Kube,
+    Localhost,
     Scp,
     Sftp,
     Smb,
     WebDAV,
 }
 
+/// Hint given when the FTPS server refuses a data connection which doesn't reuse the TLS session of the control connection
+const FTPS_SESSION_REUSE_HINT: &str = "the server requires the data connection to reuse the TLS session of the control connection (e.g. vsftpd `require_ssl_reuse=YES`): make sure the reuse is not turned off for the host (`ftp_tls_session_reuse = false` in the bookmark)";
+
+impl FileTransferProtocol {
+    /// Returns a hint to help the user to understand the cause of `error`, reported by the client of this protocol,
+    /// if it's a known failure
+    pub fn error_hint(&self, error: &str) -> Option<&'static str> {
+        match self {
+            FileTransferProtocol::Ftp(true) => {
+                let error = error.to_ascii_lowercase();
+                ["session reuse", "ssl_reuse", "522 ssl", "522 tls"]
+                    .iter()
+                    .any(|signature| error.contains(signature))
+                    .then_some(FTPS_SESSION_REUSE_HINT)
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns whether the modification time of the files written with this protocol can be set,
+    /// i.e. whether it's preserved on upload instead of being the time of the upload
+    pub fn preserves_mtime(&self) -> bool {
+        match self {
+            FileTransferProtocol::AwsS3
+            | FileTransferProtocol::Ftp(_)
+            | FileTransferProtocol::Smb
+            | FileTransferProtocol::WebDAV => false,
+            FileTransferProtocol::Kube
+            | FileTransferProtocol::Localhost
+            | FileTransferProtocol::Scp
+            | FileTransferProtocol::Sftp => true,
+        }
+    }
+
+    /// Returns whether the mode of the files written with this protocol can be changed
+    pub fn supports_chmod(&self) -> bool {
+        match self {
+            FileTransferProtocol::AwsS3
+            | FileTransferProtocol::Ftp(_)
+            | FileTransferProtocol::Smb
+            | FileTransferProtocol::WebDAV => false,
+            FileTransferProtocol::Kube
+            | FileTransferProtocol::Localhost
+            | FileTransferProtocol::Scp
+            | FileTransferProtocol::Sftp => true,
+        }
+    }
+
+    /// Returns whether commands can be executed on the hosts connected with this protocol
+    pub fn supports_exec(&self) -> bool {
+        match self {
+            FileTransferProtocol::AwsS3
+            | FileTransferProtocol::Ftp(_)
+            | FileTransferProtocol::Smb
+            | FileTransferProtocol::WebDAV => false,
+            FileTransferProtocol::Kube
+            | FileTransferProtocol::Localhost
+            | FileTransferProtocol::Scp
+            | FileTransferProtocol::Sftp => true,
+        }
+    }
+
+    /// Returns the maximum amount of connections opened to transfer files in parallel with this protocol,
+    /// so that the session limits of the servers are not exceeded
+    pub fn max_transfer_concurrency(&self) -> usize {
+        match self {
+            FileTransferProtocol::AwsS3 | FileTransferProtocol::Localhost => 16,
+            // sshd drops the connections beyond `MaxStartups` (10 by default) while authenticating
+            FileTransferProtocol::Scp
+            | FileTransferProtocol::Sftp
+            | FileTransferProtocol::WebDAV => 8,
+            FileTransferProtocol::Ftp(_)
+            | FileTransferProtocol::Kube
+            | FileTransferProtocol::Smb => 4,
+        }
+    }
+}
+
 // Traits
 
 impl std::fmt::Display for FileTransferProtocol {
@@ -38,6 +132,7 @@ impl std::fmt::Display for FileTransferProtocol {
                     false => "FTP",
                 },
                 FileTransferProtocol::Kube => "KUBE",
+                FileTransferProtocol::Localhost => "LOCALHOST",
                 FileTransferProtocol::Scp => "SCP",
                 FileTransferProtocol::Sftp => "SFTP",
                 FileTransferProtocol::Smb => "SMB",
@@ -54,6 +149,7 @@ impl std::str::FromStr for FileTransferProtocol {
             "FTP" => Ok(FileTransferProtocol::Ftp(false)),
             "FTPS" => Ok(FileTransferProtocol::Ftp(true)),
             "KUBE" => Ok(FileTransferProtocol::Kube),
+            "LOCALHOST" => Ok(FileTransferProtocol::Localhost),
             "S3" => Ok(FileTransferProtocol::AwsS3),
             "SCP" => Ok(FileTransferProtocol::Scp),
             "SFTP" => Ok(FileTransferProtocol::Sftp),
@@ -127,6 +223,10 @@ mod tests {
             FileTransferProtocol::from_str("KUBE").ok().unwrap(),
             FileTransferProtocol::Kube
         );
+        assert_eq!(
+            FileTransferProtocol::from_str("localhost").ok().unwrap(),
+            FileTransferProtocol::Localhost
+        );
         assert_eq!(
             FileTransferProtocol::from_str("SMB").ok().unwrap(),
             FileTransferProtocol::Smb
@@ -167,5 +267,84 @@ mod tests {
             String::from("WEBDAV")
         );
         assert_eq!(FileTransferProtocol::Kube.to_string(), String::from("KUBE"));
+        assert_eq!(
+            FileTransferProtocol::Localhost.to_string(),
+            String::from("LOCALHOST")
+        );
+    }
+
+    #[test]
+    fn should_give_hint_for_ftps_session_reuse_errors() {
+        let error = "Protocol error (Invalid response: [0] 522 SSL connection failed: session reuse required)";
+        assert_eq!(
+            FileTransferProtocol::Ftp(true).error_hint(error),
+            Some(FTPS_SESSION_REUSE_HINT)
+        );
+        assert_eq!(
+            FileTransferProtocol::Ftp(true).error_hint(
+                "522 SSL connection failed; see require_ssl_reuse option in vsftpd.conf"
+            ),
+            Some(FTPS_SESSION_REUSE_HINT)
+        );
+        // not related to session reuse
+        assert_eq!(
+            FileTransferProtocol::Ftp(true)
+                .error_hint("Protocol error (Invalid response: [550] file unavailable)"),
+            None
+        );
+        // the hint applies only to FTPS
+        assert_eq!(FileTransferProtocol::Ftp(false).error_hint(error), None);
+        assert_eq!(FileTransferProtocol::Sftp.error_hint(error), None);
+    }
+
+    #[test]
+    fn should_tell_whether_protocol_preserves_mtime() {
+        assert_eq!(FileTransferProtocol::Sftp.preserves_mtime(), true);
+        assert_eq!(FileTransferProtocol::Scp.preserves_mtime(), true);
+        assert_eq!(FileTransferProtocol::Kube.preserves_mtime(), true);
+        assert_eq!(FileTransferProtocol::Localhost.preserves_mtime(), true);
+        assert_eq!(FileTransferProtocol::Ftp(true).preserves_mtime(), false);
+        assert_eq!(FileTransferProtocol::AwsS3.preserves_mtime(), false);
+        assert_eq!(FileTransferProtocol::Smb.preserves_mtime(), false);
+        assert_eq!(FileTransferProtocol::WebDAV.preserves_mtime(), false);
+    }
+
+    #[test]
+    fn should_get_max_transfer_concurrency_of_protocol() {
+        assert_eq!(FileTransferProtocol::AwsS3.max_transfer_concurrency(), 16);
+        assert_eq!(
+            FileTransferProtocol::Localhost.max_transfer_concurrency(),
+            16
+        );
+        assert_eq!(FileTransferProtocol::Sftp.max_transfer_concurrency(), 8);
+        assert_eq!(FileTransferProtocol::Scp.max_transfer_concurrency(), 8);
+        assert_eq!(FileTransferProtocol::WebDAV.max_transfer_concurrency(), 8);
+        assert_eq!(
+            FileTransferProtocol::Ftp(true).max_transfer_concurrency(),
+            4
+        );
+        assert_eq!(FileTransferProtocol::Kube.max_transfer_concurrency(), 4);
+        assert_eq!(FileTransferProtocol::Smb.max_transfer_concurrency(), 4);
+    }
+
+    #[test]
+    fn should_tell_whether_protocol_supports_exec() {
+        assert_eq!(FileTransferProtocol::Sftp.supports_exec(), true);
+        assert_eq!(FileTransferProtocol::Scp.supports_exec(), true);
+        assert_eq!(FileTransferProtocol::Kube.supports_exec(), true);
+        assert_eq!(FileTransferProtocol::Localhost.supports_exec(), true);
+        assert_eq!(FileTransferProtocol::Ftp(false).supports_exec(), false);
+        assert_eq!(FileTransferProtocol::AwsS3.supports_exec(), false);
+        assert_eq!(FileTransferProtocol::Smb.supports_exec(), false);
+        assert_eq!(FileTransferProtocol::WebDAV.supports_exec(), false);
+    }
+
+    #[test]
+    fn should_tell_whether_protocol_supports_chmod() {
+        assert_eq!(FileTransferProtocol::Sftp.supports_chmod(), true);
+        assert_eq!(FileTransferProtocol::Scp.supports_chmod(), true);
+        assert_eq!(FileTransferProtocol::Ftp(true).supports_chmod(), false);
+        assert_eq!(FileTransferProtocol::AwsS3.supports_chmod(), false);
+        assert_eq!(FileTransferProtocol::WebDAV.supports_chmod(), false);
     }
 }
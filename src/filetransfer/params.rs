@@ -3,20 +3,34 @@
 //! file transfer parameters
 
 mod aws_s3;
+mod explorer;
 mod kube;
+mod modes;
+mod quick_command;
 mod smb;
+mod ssh;
+mod url_mapping;
 mod webdav;
 
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 
 pub use self::aws_s3::AwsS3Params;
+pub use self::explorer::ExplorerPrefs;
 pub use self::kube::KubeProtocolParams;
+pub use self::modes::ModePrefs;
+pub use self::quick_command::QuickCommand;
 pub use self::smb::SmbParams;
+pub use self::ssh::SshPrefs;
+pub use self::url_mapping::UrlMapping;
 pub use self::webdav::WebDAVProtocolParams;
 use super::FileTransferProtocol;
+use crate::config::bookmarks::Bookmark;
+use crate::system::bookmarks_client::BookmarksClient;
 
 /// Host bridge params
 #[derive(Debug, Clone)]
+#[allow(clippy::large_enum_variant)]
 pub enum HostBridgeParams {
     /// Localhost with starting working directory
     Localhost(PathBuf),
@@ -31,6 +45,14 @@ impl HostBridgeParams {
             HostBridgeParams::Remote(_, params) => params,
         }
     }
+
+    /// Get the identifier of the host, made of the protocol and the host name (e.g. `sftp://example.com`)
+    pub fn host_id(&self) -> String {
+        match self {
+            HostBridgeParams::Localhost(_) => String::from("localhost"),
+            HostBridgeParams::Remote(protocol, params) => params.host_id(*protocol),
+        }
+    }
 }
 
 /// Holds connection parameters for file transfers
@@ -40,6 +62,18 @@ pub struct FileTransferParams {
     pub params: ProtocolParams,
     pub remote_path: Option<PathBuf>,
     pub local_path: Option<PathBuf>,
+    /// Shell used to run commands on the remote host
+    pub remote_shell: Option<String>,
+    /// Commands which can be run on the remote host from the quick commands menu
+    pub quick_commands: Vec<QuickCommand>,
+    /// Remote directories served by a web server, mapped to their URLs
+    pub url_mappings: Vec<UrlMapping>,
+    /// Explorer preferences, overriding the configuration
+    pub explorer: ExplorerPrefs,
+    /// SSH preferences, overriding the configuration
+    pub ssh: SshPrefs,
+    /// Modes of the created remote entries, overriding the configuration
+    pub modes: ModePrefs,
 }
 
 /// Container for protocol params
@@ -74,6 +108,54 @@ impl ProtocolParams {
         }
     }
 
+    /// Returns whether the protocol authenticates with username and password
+    pub fn has_credentials(&self) -> bool {
+        matches!(
+            self,
+            ProtocolParams::Generic(_) | ProtocolParams::Smb(_) | ProtocolParams::WebDAV(_)
+        )
+    }
+
+    /// Get the username used to authenticate, if any
+    pub fn username(&self) -> Option<&str> {
+        match self {
+            ProtocolParams::Generic(params) => params.username.as_deref(),
+            ProtocolParams::Smb(params) => params.username.as_deref(),
+            ProtocolParams::WebDAV(params) => Some(params.username.as_str()),
+            ProtocolParams::AwsS3(_) | ProtocolParams::Kube(_) => None,
+        }
+    }
+
+    /// Set username and password used to authenticate.
+    /// An empty username is unset. Protocols which don't authenticate with username and password are left untouched
+    pub fn set_credentials(&mut self, username: String, password: String) {
+        let username = Some(username).filter(|x| !x.is_empty());
+        match self {
+            ProtocolParams::Generic(params) => {
+                params.username = username;
+                params.password = Some(password);
+            }
+            ProtocolParams::Smb(params) => {
+                params.username = username;
+                params.password = Some(password);
+            }
+            ProtocolParams::WebDAV(params) => {
+                params.username = username.unwrap_or_default();
+                params.password = password;
+            }
+            ProtocolParams::AwsS3(_) | ProtocolParams::Kube(_) => {}
+        }
+    }
+
+    /// Get the identifier of the host, when connected with `protocol` (e.g. `sftp://example.com`)
+    pub fn host_id(&self, protocol: FileTransferProtocol) -> String {
+        format!(
+            "{}://{}",
+            protocol.to_string().to_lowercase(),
+            self.host_name()
+        )
+    }
+
     pub fn host_name(&self) -> String {
         match self {
             ProtocolParams::AwsS3(params) => params.bucket_name.clone(),
@@ -96,6 +178,21 @@ pub struct GenericProtocolParams {
     pub port: u16,
     pub username: Option<String>,
     pub password: Option<String>,
+    /// Whether FTP data connections are opened in active mode, rather than passive
+    pub active_mode: bool,
+    /// Whether FTPS data connections reuse the TLS session of the control connection
+    pub tls_session_reuse: bool,
+    /// Credentials of the jump hosts of the `ProxyJump` directive, by name of the host in the directive.
+    /// They're asked when the authentication on a jump host fails, and they're never saved
+    pub jump_credentials: HashMap<String, JumpCredentials>,
+}
+
+/// Credentials to authenticate on a jump host with
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JumpCredentials {
+    pub username: String,
+    /// Password of the user; used to decrypt the keys too
+    pub password: String,
 }
 
 impl FileTransferParams {
@@ -106,6 +203,12 @@ impl FileTransferParams {
             params,
             remote_path: None,
             local_path: None,
+            remote_shell: None,
+            quick_commands: Vec::new(),
+            url_mappings: Vec::new(),
+            explorer: ExplorerPrefs::default(),
+            ssh: SshPrefs::default(),
+            modes: ModePrefs::default(),
         }
     }
 
@@ -121,6 +224,52 @@ impl FileTransferParams {
         self
     }
 
+    /// Set remote shell
+    pub fn remote_shell<S: AsRef<str>>(mut self, shell: Option<S>) -> Self {
+        self.remote_shell = shell.map(|x| x.as_ref().to_string());
+        self
+    }
+
+    /// Set quick commands
+    pub fn quick_commands(mut self, commands: Vec<QuickCommand>) -> Self {
+        self.quick_commands = commands;
+        self
+    }
+
+    /// Set URL mappings
+    pub fn url_mappings(mut self, mappings: Vec<UrlMapping>) -> Self {
+        self.url_mappings = mappings;
+        self
+    }
+
+    /// Set explorer preferences
+    pub fn explorer(mut self, prefs: ExplorerPrefs) -> Self {
+        self.explorer = prefs;
+        self
+    }
+
+    /// Set ssh preferences
+    pub fn ssh(mut self, prefs: SshPrefs) -> Self {
+        self.ssh = prefs;
+        self
+    }
+
+    /// Set the modes of the created remote entries
+    pub fn modes(mut self, prefs: ModePrefs) -> Self {
+        self.modes = prefs;
+        self
+    }
+
+    /// Returns whether `other` connects to the same host with the same protocol, user and credentials: on the same port,
+    /// share, bucket, endpoint or cluster
+    pub fn is_same_host(&self, other: &Self) -> bool {
+        let (a, b) = (Bookmark::from(self.clone()), Bookmark::from(other.clone()));
+        BookmarksClient::is_equivalent(&a, &b)
+            && a.password == b.password
+            && a.s3 == b.s3
+            && a.kube == b.kube
+    }
+
     /// Returns whether a password is supposed to be required for this protocol params.
     /// The result true is returned ONLY if the supposed secret is MISSING!!!
     #[cfg(test)]
@@ -156,7 +305,6 @@ impl ProtocolParams {
         }
     }
 
-    #[cfg(test)]
     /// Get a mutable reference to the inner generic protocol params
     pub fn mut_generic_params(&mut self) -> Option<&mut GenericProtocolParams> {
         match self {
@@ -211,6 +359,9 @@ impl Default for GenericProtocolParams {
             port: 22,
             username: None,
             password: None,
+            active_mode: false,
+            tls_session_reuse: true,
+            jump_credentials: HashMap::new(),
         }
     }
 }
@@ -240,6 +391,18 @@ impl GenericProtocolParams {
         self
     }
 
+    /// Set whether FTP data connections are opened in active mode
+    pub fn active_mode(mut self, active_mode: bool) -> Self {
+        self.active_mode = active_mode;
+        self
+    }
+
+    /// Set whether FTPS data connections reuse the TLS session of the control connection
+    pub fn tls_session_reuse(mut self, tls_session_reuse: bool) -> Self {
+        self.tls_session_reuse = tls_session_reuse;
+        self
+    }
+
     /// Returns whether a password is supposed to be required for this protocol params.
     /// The result true is returned ONLY if the supposed secret is MISSING!!!
     pub fn password_missing(&self) -> bool {
@@ -274,6 +437,93 @@ mod test {
         assert_eq!(params.local_path.as_deref().unwrap(), Path::new("/usr"));
     }
 
+    #[test]
+    fn should_tell_whether_params_connect_to_the_same_host() {
+        let params = |port: u16, username: &str| {
+            FileTransferParams::new(
+                FileTransferProtocol::Sftp,
+                ProtocolParams::Generic(
+                    GenericProtocolParams::default()
+                        .address("bastion.example.com")
+                        .port(port)
+                        .username(Some(username)),
+                ),
+            )
+        };
+        let host = params(22, "omar");
+        assert!(host.is_same_host(&params(22, "omar").remote_path(Some("/tmp"))));
+        assert!(!host.is_same_host(&params(2222, "omar")));
+        assert!(!host.is_same_host(&params(22, "root")));
+        let mut scp = params(22, "omar");
+        scp.protocol = FileTransferProtocol::Scp;
+        assert!(!host.is_same_host(&scp));
+        let with_password = |password: &str| {
+            FileTransferParams::new(
+                FileTransferProtocol::Sftp,
+                ProtocolParams::Generic(
+                    GenericProtocolParams::default()
+                        .address("bastion.example.com")
+                        .port(22)
+                        .username(Some("omar"))
+                        .password(Some(password)),
+                ),
+            )
+        };
+        assert!(with_password("secret").is_same_host(&with_password("secret")));
+        assert!(!with_password("secret").is_same_host(&with_password("wrong")));
+    }
+
+    #[test]
+    fn should_tell_whether_params_connect_to_the_same_share() {
+        let params = |share: &str| {
+            FileTransferParams::new(
+                FileTransferProtocol::Smb,
+                ProtocolParams::Smb(SmbParams::new("fileserver", share).username(Some("omar"))),
+            )
+        };
+        assert!(params("docs").is_same_host(&params("docs")));
+        assert!(!params("docs").is_same_host(&params("backups")));
+    }
+
+    #[test]
+    fn should_tell_whether_params_connect_to_the_same_bucket() {
+        let params = |bucket: &str, endpoint: Option<&str>| {
+            FileTransferParams::new(
+                FileTransferProtocol::AwsS3,
+                ProtocolParams::AwsS3(
+                    AwsS3Params::new(bucket, Some("eu-west-1"), None::<&str>).endpoint(endpoint),
+                ),
+            )
+        };
+        let host = params("backups", None);
+        assert!(host.is_same_host(&params("backups", None)));
+        assert!(!host.is_same_host(&params("logs", None)));
+        assert!(!host.is_same_host(&params("backups", Some("http://localhost:9000"))));
+    }
+
+    #[test]
+    fn should_get_host_id() {
+        let params = ProtocolParams::Generic(
+            GenericProtocolParams::default().address("bastion.example.com"),
+        );
+        assert_eq!(
+            params.host_id(FileTransferProtocol::Sftp).as_str(),
+            "sftp://bastion.example.com"
+        );
+        assert_eq!(
+            HostBridgeParams::Remote(FileTransferProtocol::Ftp(true), params)
+                .host_id()
+                .as_str(),
+            "ftps://bastion.example.com"
+        );
+        assert_eq!(
+            HostBridgeParams::Localhost(PathBuf::from("/tmp"))
+                .host_id()
+                .as_str(),
+            "localhost"
+        );
+    }
+
     #[test]
     fn params_default() {
         let params: GenericProtocolParams = ProtocolParams::default()
@@ -284,6 +534,8 @@ mod test {
         assert_eq!(params.port, 22);
         assert!(params.username.is_none());
         assert!(params.password.is_none());
+        assert_eq!(params.active_mode, false);
+        assert_eq!(params.tls_session_reuse, true);
     }
 
     #[test]
@@ -342,6 +594,31 @@ mod test {
         );
     }
 
+    #[test]
+    fn set_credentials() {
+        let mut params = ProtocolParams::Generic(
+            GenericProtocolParams::default()
+                .username(Some("omar"))
+                .password(Some("wrong")),
+        );
+        assert!(params.has_credentials());
+        assert_eq!(params.username(), Some("omar"));
+        params.set_credentials(String::from("root"), String::from("secret"));
+        assert_eq!(params.username(), Some("root"));
+        assert_eq!(
+            params.generic_params().unwrap().password.as_deref(),
+            Some("secret")
+        );
+        params.set_credentials(String::new(), String::from("secret"));
+        assert_eq!(params.username(), None);
+        let mut params =
+            ProtocolParams::AwsS3(AwsS3Params::new("omar", Some("eu-west-1"), Some("test")));
+        assert_eq!(params.has_credentials(), false);
+        params.set_credentials(String::from("root"), String::from("secret"));
+        assert_eq!(params.username(), None);
+        assert!(params.password_missing());
+    }
+
     #[test]
     fn set_default_secret_aws_s3() {
         let mut params = FileTransferParams::new(
@@ -1,3 +1,5 @@
+use std::path::{Path, PathBuf};
+
 /// Connection parameters for AWS S3 protocol
 #[derive(Debug, Clone)]
 pub struct AwsS3Params {
@@ -10,6 +12,14 @@ pub struct AwsS3Params {
     pub security_token: Option<String>,
     pub session_token: Option<String>,
     pub new_path_style: bool,
+    /// The requester pays the requests and the downloads, rather than the bucket owner
+    pub requester_pays: bool,
+    /// Storage class of the uploaded objects; the bucket default if unset
+    pub storage_class: Option<String>,
+    /// Accept invalid TLS certificates (e.g. self-signed) of the endpoint
+    pub accept_invalid_certs: bool,
+    /// PEM file with the certificates of the authorities trusted for the endpoint
+    pub ca_bundle: Option<PathBuf>,
 }
 
 // -- S3 params
@@ -27,6 +37,10 @@ impl AwsS3Params {
             security_token: None,
             session_token: None,
             new_path_style: false,
+            requester_pays: false,
+            storage_class: None,
+            accept_invalid_certs: false,
+            ca_bundle: None,
         }
     }
 
@@ -66,6 +80,43 @@ impl AwsS3Params {
         self
     }
 
+    /// Construct aws s3 params paying the requests as the requester
+    pub fn requester_pays(mut self, requester_pays: bool) -> Self {
+        self.requester_pays = requester_pays;
+        self
+    }
+
+    /// Construct aws s3 params with the storage class of the uploaded objects
+    pub fn storage_class<S: AsRef<str>>(mut self, storage_class: Option<S>) -> Self {
+        self.storage_class = storage_class.map(|x| x.as_ref().to_string());
+        self
+    }
+
+    /// Construct aws s3 params accepting invalid TLS certificates
+    pub fn accept_invalid_certs(mut self, accept_invalid_certs: bool) -> Self {
+        self.accept_invalid_certs = accept_invalid_certs;
+        self
+    }
+
+    /// Construct aws s3 params with the provided CA bundle
+    pub fn ca_bundle<P: AsRef<Path>>(mut self, ca_bundle: Option<P>) -> Self {
+        self.ca_bundle = ca_bundle.map(|x| x.as_ref().to_path_buf());
+        self
+    }
+
+    /// Get the URL of the endpoint, if any.
+    /// Endpoints without a scheme are reached through https; `http://` must be explicit
+    pub fn endpoint_url(&self) -> Option<String> {
+        self.endpoint
+            .as_deref()
+            .map(str::trim)
+            .filter(|x| !x.is_empty())
+            .map(|endpoint| match endpoint.contains("://") {
+                true => endpoint.to_string(),
+                false => format!("https://{endpoint}"),
+            })
+    }
+
     /// Returns whether a password is supposed to be required for this protocol params.
     /// The result true is returned ONLY if the supposed secret is MISSING!!!
     pub fn password_missing(&self) -> bool {
@@ -97,6 +148,10 @@ mod test {
         assert!(params.security_token.is_none());
         assert!(params.session_token.is_none());
         assert_eq!(params.new_path_style, false);
+        assert_eq!(params.requester_pays, false);
+        assert!(params.storage_class.is_none());
+        assert_eq!(params.accept_invalid_certs, false);
+        assert!(params.ca_bundle.is_none());
     }
 
     #[test]
@@ -107,7 +162,11 @@ mod test {
             .secret_access_key(Some("pluto"))
             .security_token(Some("omar"))
             .session_token(Some("gerry-scotti"))
-            .new_path_style(true);
+            .new_path_style(true)
+            .requester_pays(true)
+            .storage_class(Some("STANDARD_IA"))
+            .accept_invalid_certs(true)
+            .ca_bundle(Some("/etc/ssl/minio.pem"));
         assert_eq!(params.bucket_name.as_str(), "omar");
         assert_eq!(params.region.as_deref().unwrap(), "eu-west-1");
         assert_eq!(params.profile.as_deref().unwrap(), "test");
@@ -117,5 +176,42 @@ mod test {
         assert_eq!(params.security_token.as_deref().unwrap(), "omar");
         assert_eq!(params.session_token.as_deref().unwrap(), "gerry-scotti");
         assert_eq!(params.new_path_style, true);
+        assert_eq!(params.requester_pays, true);
+        assert_eq!(params.storage_class.as_deref().unwrap(), "STANDARD_IA");
+        assert_eq!(params.accept_invalid_certs, true);
+        assert_eq!(
+            params.ca_bundle.as_deref().unwrap(),
+            Path::new("/etc/ssl/minio.pem")
+        );
+    }
+
+    #[test]
+    fn should_get_endpoint_url() {
+        let params = AwsS3Params::new("omar", None, None);
+        assert!(params.endpoint_url().is_none());
+        assert!(params.clone().endpoint(Some("  ")).endpoint_url().is_none());
+        assert_eq!(
+            params
+                .clone()
+                .endpoint(Some("minio.lan:9000"))
+                .endpoint_url()
+                .unwrap(),
+            "https://minio.lan:9000"
+        );
+        assert_eq!(
+            params
+                .clone()
+                .endpoint(Some("http://localhost:9000"))
+                .endpoint_url()
+                .unwrap(),
+            "http://localhost:9000"
+        );
+        assert_eq!(
+            params
+                .endpoint(Some("https://s3.example.com"))
+                .endpoint_url()
+                .unwrap(),
+            "https://s3.example.com"
+        );
     }
 }
@@ -0,0 +1,53 @@
+use crate::explorer::{FileSorting, GroupDirs};
+
+/// Explorer preferences of a connection, which override the configuration when set
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExplorerPrefs {
+    /// Criteria to sort files by
+    pub file_sorting: Option<FileSorting>,
+    /// How to group directories; `Some(None)` means directories are not grouped
+    pub group_dirs: Option<Option<GroupDirs>>,
+    /// Whether hidden files are shown
+    pub show_hidden_files: Option<bool>,
+}
+
+impl ExplorerPrefs {
+    /// Fill the preferences which are not set with the provided defaults
+    pub fn or_defaults(
+        self,
+        file_sorting: FileSorting,
+        group_dirs: Option<GroupDirs>,
+        show_hidden_files: bool,
+    ) -> Self {
+        Self {
+            file_sorting: self.file_sorting.or(Some(file_sorting)),
+            group_dirs: self.group_dirs.or(Some(group_dirs)),
+            show_hidden_files: self.show_hidden_files.or(Some(show_hidden_files)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn should_fill_explorer_prefs_with_defaults() {
+        let prefs = ExplorerPrefs::default().or_defaults(FileSorting::Name, None, false);
+        assert_eq!(prefs.file_sorting, Some(FileSorting::Name));
+        assert_eq!(prefs.group_dirs, Some(None));
+        assert_eq!(prefs.show_hidden_files, Some(false));
+        let prefs = ExplorerPrefs {
+            file_sorting: Some(FileSorting::ModifyTime),
+            group_dirs: Some(Some(GroupDirs::First)),
+            show_hidden_files: None,
+        }
+        .or_defaults(FileSorting::Name, None, true);
+        assert_eq!(prefs.file_sorting, Some(FileSorting::ModifyTime));
+        assert_eq!(prefs.group_dirs, Some(Some(GroupDirs::First)));
+        assert_eq!(prefs.show_hidden_files, Some(true));
+    }
+}
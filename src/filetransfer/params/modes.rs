@@ -0,0 +1,54 @@
+use remotefs::fs::UnixPex;
+
+/// Modes applied to the entries created on the remote host, which override the configuration when set
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ModePrefs {
+    /// Mode of the uploaded files
+    pub file: Option<u32>,
+    /// Mode of the created directories
+    pub dir: Option<u32>,
+}
+
+impl ModePrefs {
+    /// Fill the modes which are not set with the provided defaults
+    pub fn or_defaults(self, file: Option<u32>, dir: Option<u32>) -> Self {
+        Self {
+            file: self.file.or(file),
+            dir: self.dir.or(dir),
+        }
+    }
+
+    /// Get the mode to apply to a created entry, if any; `dir` tells whether the entry is a directory
+    pub fn mode(&self, dir: bool) -> Option<UnixPex> {
+        match dir {
+            true => self.dir,
+            false => self.file,
+        }
+        .map(UnixPex::from)
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn should_fill_mode_prefs_with_defaults() {
+        let prefs = ModePrefs {
+            file: Some(0o660),
+            dir: None,
+        }
+        .or_defaults(Some(0o664), Some(0o2775));
+        assert_eq!(prefs.file, Some(0o660));
+        assert_eq!(prefs.dir, Some(0o2775));
+        assert_eq!(prefs.mode(true), Some(UnixPex::from(0o2775)));
+        assert_eq!(prefs.mode(false), Some(UnixPex::from(0o660)));
+        assert!(ModePrefs::default()
+            .or_defaults(None, None)
+            .mode(false)
+            .is_none());
+    }
+}
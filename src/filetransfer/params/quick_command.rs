@@ -0,0 +1,142 @@
+//! ## QuickCommand
+//!
+//! Named command saved along with a bookmark
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::utils::shell;
+
+/// Placeholder replaced with the selected remote paths
+const SELECTED_PATH_PLACEHOLDER: &str = "{selected_path}";
+/// Placeholder replaced with the remote working directory
+const WRKDIR_PLACEHOLDER: &str = "{wrkdir}";
+/// Separator of the quick commands written on a single line
+const LIST_SEPARATOR: &str = ";;";
+
+/// A named command which can be run on the remote host with one keystroke
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct QuickCommand {
+    /// Label displayed in the quick commands menu
+    pub label: String,
+    /// Command to run; may contain the `{selected_path}` and `{wrkdir}` placeholders
+    pub command: String,
+}
+
+impl QuickCommand {
+    #[cfg(test)]
+    pub fn new<S: AsRef<str>>(label: S, command: S) -> Self {
+        Self {
+            label: label.as_ref().to_string(),
+            command: command.as_ref().to_string(),
+        }
+    }
+
+    /// Parse the quick commands written on a single line as `label=command`, separated by `;;`
+    pub fn parse_list(s: &str) -> Result<Vec<Self>, String> {
+        s.split(LIST_SEPARATOR)
+            .map(str::trim)
+            .filter(|x| !x.is_empty())
+            .map(|entry| match entry.split_once('=') {
+                Some((label, command))
+                    if !label.trim().is_empty() && !command.trim().is_empty() =>
+                {
+                    Ok(Self {
+                        label: label.trim().to_string(),
+                        command: command.trim().to_string(),
+                    })
+                }
+                _ => Err(format!(
+                    "Invalid quick command \"{entry}\": expected label=command"
+                )),
+            })
+            .collect()
+    }
+
+    /// Write `commands` on a single line, as parsed by [`QuickCommand::parse_list`]
+    pub fn fmt_list(commands: &[Self]) -> String {
+        commands
+            .iter()
+            .map(|x| format!("{}={}", x.label, x.command))
+            .collect::<Vec<String>>()
+            .join(&format!(" {LIST_SEPARATOR} "))
+    }
+
+    /// Returns whether the command refers to the selected paths
+    pub fn uses_selection(&self) -> bool {
+        self.command.contains(SELECTED_PATH_PLACEHOLDER)
+    }
+
+    /// Get the command to run, replacing the placeholders with the shell-quoted `selected` paths
+    /// (separated by spaces) and `wrkdir`
+    pub fn render(&self, selected: &[&Path], wrkdir: &Path) -> String {
+        let selected = selected
+            .iter()
+            .map(|p| shell::single_quote(&p.to_string_lossy()))
+            .collect::<Vec<String>>()
+            .join(" ");
+        self.command
+            .replace(SELECTED_PATH_PLACEHOLDER, &selected)
+            .replace(
+                WRKDIR_PLACEHOLDER,
+                &shell::single_quote(&wrkdir.to_string_lossy()),
+            )
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn should_render_quick_command_without_placeholders() {
+        let cmd = QuickCommand::new("Uptime", "uptime");
+        assert_eq!(cmd.uses_selection(), false);
+        assert_eq!(cmd.render(&[], Path::new("/home/omar")), "uptime");
+    }
+
+    #[test]
+    fn should_render_quick_command_with_placeholders() {
+        let cmd = QuickCommand::new("Tail", "tail -n 100 {selected_path}");
+        assert_eq!(cmd.uses_selection(), true);
+        assert_eq!(
+            cmd.render(&[Path::new("/var/log/app.log")], Path::new("/")),
+            "tail -n 100 '/var/log/app.log'"
+        );
+        assert_eq!(
+            cmd.render(
+                &[Path::new("/tmp/a b"), Path::new("/tmp/it's")],
+                Path::new("/")
+            ),
+            r"tail -n 100 '/tmp/a b' '/tmp/it'\''s'"
+        );
+        let cmd = QuickCommand::new("Disk usage", "cd {wrkdir} && du -sh {selected_path}");
+        assert_eq!(
+            cmd.render(&[Path::new("/srv/www")], Path::new("/srv")),
+            "cd '/srv' && du -sh '/srv/www'"
+        );
+    }
+
+    #[test]
+    fn should_parse_quick_commands_list() {
+        let commands = vec![
+            QuickCommand::new("Disk usage", "df -h"),
+            QuickCommand::new("Env", "FOO=1 env | grep FOO"),
+        ];
+        assert_eq!(
+            QuickCommand::fmt_list(&commands),
+            "Disk usage=df -h ;; Env=FOO=1 env | grep FOO"
+        );
+        assert_eq!(
+            QuickCommand::parse_list("Disk usage=df -h ;; Env=FOO=1 env | grep FOO ;;").unwrap(),
+            commands
+        );
+        assert!(QuickCommand::parse_list("").unwrap().is_empty());
+        assert!(QuickCommand::parse_list("df -h").is_err());
+        assert!(QuickCommand::parse_list("Disk usage=").is_err());
+    }
+}
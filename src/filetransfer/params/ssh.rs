@@ -0,0 +1,81 @@
+/// SSH preferences of a connection, which override the configuration when set
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SshPrefs {
+    /// Whether the session is compressed
+    pub compression: Option<bool>,
+    /// Preferred ciphers, as a comma separated list
+    pub ciphers: Option<String>,
+}
+
+impl SshPrefs {
+    /// Fill the preferences which are not set with the provided defaults
+    pub fn or_defaults(self, compression: bool, ciphers: Option<String>) -> Self {
+        Self {
+            compression: self.compression.or(Some(compression)),
+            ciphers: self.ciphers.or(ciphers),
+        }
+    }
+
+    /// Whether compression is enabled
+    pub fn compression(&self) -> bool {
+        self.compression.unwrap_or(false)
+    }
+
+    /// Get the preferred ciphers, in order of preference
+    pub fn ciphers(&self) -> Vec<String> {
+        self.ciphers
+            .as_deref()
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|x| !x.is_empty())
+            .map(str::to_string)
+            .collect()
+    }
+
+    /// Whether the preferences change the defaults of the ssh session
+    pub fn is_default(&self) -> bool {
+        !self.compression() && self.ciphers().is_empty()
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn should_fill_ssh_prefs_with_defaults() {
+        let prefs = SshPrefs::default().or_defaults(false, None);
+        assert_eq!(prefs.compression, Some(false));
+        assert!(prefs.ciphers.is_none());
+        assert_eq!(prefs.is_default(), true);
+        let prefs = SshPrefs {
+            compression: Some(true),
+            ciphers: None,
+        }
+        .or_defaults(false, Some(String::from("aes256-ctr")));
+        assert_eq!(prefs.compression(), true);
+        assert_eq!(prefs.ciphers.as_deref(), Some("aes256-ctr"));
+        assert_eq!(prefs.is_default(), false);
+    }
+
+    #[test]
+    fn should_get_preferred_ciphers() {
+        let prefs = SshPrefs {
+            compression: None,
+            ciphers: Some(String::from(" aes128-gcm@openssh.com, aes256-ctr,,")),
+        };
+        assert_eq!(
+            prefs.ciphers(),
+            vec![
+                String::from("aes128-gcm@openssh.com"),
+                String::from("aes256-ctr")
+            ]
+        );
+        assert_eq!(prefs.is_default(), false);
+        assert!(SshPrefs::default().ciphers().is_empty());
+    }
+}
@@ -0,0 +1,116 @@
+//! ## UrlMapping
+//!
+//! Mapping of a remote directory to the URL it is served at, saved along with a bookmark
+
+use std::path::{Component, Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+/// Maps the files under a remote directory to the URLs they're served at by a web server
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct UrlMapping {
+    /// Remote directory served by the web server (e.g. `/var/www/html`)
+    pub path: PathBuf,
+    /// URL the directory is served at (e.g. `https://example.com`)
+    pub url: String,
+}
+
+impl UrlMapping {
+    #[cfg(test)]
+    pub fn new<P: AsRef<Path>, S: AsRef<str>>(path: P, url: S) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+            url: url.as_ref().to_string(),
+        }
+    }
+
+    /// Get the URL of the remote `path`, using the mapping among `mappings` with the longest matching path.
+    /// Directories get a trailing slash. Returns `None` if `path` is not under any mapped directory
+    pub fn resolve(mappings: &[Self], path: &Path, is_dir: bool) -> Option<String> {
+        let (mapping, relative) = mappings
+            .iter()
+            .filter_map(|x| path.strip_prefix(x.path.as_path()).ok().map(|r| (x, r)))
+            .max_by_key(|(x, _)| x.path.components().count())?;
+        let mut url = mapping.url.trim_end_matches('/').to_string();
+        for component in relative.components() {
+            if let Component::Normal(name) = component {
+                url.push('/');
+                url.push_str(&Self::encode(&name.to_string_lossy()));
+            }
+        }
+        if is_dir || relative.as_os_str().is_empty() {
+            url.push('/');
+        }
+        Some(url)
+    }
+
+    /// Percent-encode a path segment of the URL
+    fn encode(segment: &str) -> String {
+        segment
+            .bytes()
+            .map(|b| match b {
+                b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                    (b as char).to_string()
+                }
+                _ => format!("%{b:02X}"),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn should_resolve_url_with_longest_mapping() {
+        let mappings = vec![
+            UrlMapping::new("/var/www/html", "https://example.com/"),
+            UrlMapping::new("/var/www/html/blog", "https://blog.example.com"),
+        ];
+        assert_eq!(
+            UrlMapping::resolve(&mappings, Path::new("/var/www/html/index.html"), false).as_deref(),
+            Some("https://example.com/index.html")
+        );
+        assert_eq!(
+            UrlMapping::resolve(
+                &mappings,
+                Path::new("/var/www/html/blog/2024/post.html"),
+                false
+            )
+            .as_deref(),
+            Some("https://blog.example.com/2024/post.html")
+        );
+        assert_eq!(
+            UrlMapping::resolve(&mappings, Path::new("/var/www/html/assets"), true).as_deref(),
+            Some("https://example.com/assets/")
+        );
+        assert_eq!(
+            UrlMapping::resolve(&mappings, Path::new("/var/www/html"), true).as_deref(),
+            Some("https://example.com/")
+        );
+    }
+
+    #[test]
+    fn should_not_resolve_unmapped_paths() {
+        let mappings = vec![UrlMapping::new("/var/www/html", "https://example.com")];
+        assert!(
+            UrlMapping::resolve(&mappings, Path::new("/var/www/html2/a.html"), false).is_none()
+        );
+        assert!(UrlMapping::resolve(&mappings, Path::new("/home/omar/a.html"), false).is_none());
+        assert!(UrlMapping::resolve(&[], Path::new("/var/www/html/a.html"), false).is_none());
+    }
+
+    #[test]
+    fn should_encode_url_segments() {
+        let mappings = vec![UrlMapping::new("/srv", "https://example.com/static")];
+        assert_eq!(
+            UrlMapping::resolve(&mappings, Path::new("/srv/my docs/café & co.pdf"), false)
+                .as_deref(),
+            Some("https://example.com/static/my%20docs/caf%C3%A9%20%26%20co.pdf")
+        );
+    }
+}
@@ -0,0 +1,109 @@
+//! ## Parked session
+//!
+//! this module exposes the remote sessions kept connected while the user is back at the authentication form
+
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use remotefs::RemoteFs;
+
+use super::{FileTransferParams, SshNegotiationHandle};
+
+/// A connected remote client, kept alive when returning to the authentication form,
+/// so that connecting to the same host again resumes the session in its working directory
+pub struct ParkedSession {
+    params: FileTransferParams,
+    client: Box<dyn RemoteFs>,
+    ssh_negotiation: SshNegotiationHandle,
+    /// Working directory of the remote explorer when the session has been parked
+    wrkdir: PathBuf,
+    parked_at: Instant,
+    timeout: Duration,
+}
+
+impl ParkedSession {
+    /// Park the session of `client`, connected with `params`; it's closed once idle for longer than `timeout`
+    pub fn new(
+        params: FileTransferParams,
+        client: Box<dyn RemoteFs>,
+        ssh_negotiation: SshNegotiationHandle,
+        wrkdir: PathBuf,
+        timeout: Duration,
+    ) -> Self {
+        Self {
+            params,
+            client,
+            ssh_negotiation,
+            wrkdir,
+            parked_at: Instant::now(),
+            timeout,
+        }
+    }
+
+    /// Get the params the session has been connected with
+    pub fn params(&self) -> &FileTransferParams {
+        &self.params
+    }
+
+    /// Returns whether the session connects to the host of `params`, as the same user and with the same credentials
+    pub fn is_session_of(&self, params: &FileTransferParams) -> bool {
+        self.params.is_same_host(params)
+    }
+
+    /// Returns whether the session has been idle for longer than its timeout
+    pub fn is_expired(&self) -> bool {
+        self.parked_at.elapsed() >= self.timeout
+    }
+
+    /// Get the name of the host the session is connected to
+    pub fn host_name(&self) -> String {
+        self.params.params.host_name()
+    }
+
+    /// Resume the session, getting the client, the ssh negotiation handle and the working directory
+    pub fn resume(self) -> (Box<dyn RemoteFs>, SshNegotiationHandle, PathBuf) {
+        (self.client, self.ssh_negotiation, self.wrkdir)
+    }
+
+    /// Close the connection of the session
+    pub fn close(mut self) {
+        info!("Closing the session parked on {}", self.host_name());
+        if self.client.is_connected() {
+            if let Err(err) = self.client.disconnect() {
+                error!("Failed to close the parked session: {err}");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::filetransfer::localhost_fs::LocalhostFs;
+    use crate::filetransfer::params::GenericProtocolParams;
+    use crate::filetransfer::{FileTransferProtocol, ProtocolParams};
+
+    #[test]
+    fn should_park_session() {
+        let params = FileTransferParams::new(
+            FileTransferProtocol::Sftp,
+            ProtocolParams::Generic(GenericProtocolParams::default().address("bastion")),
+        );
+        let client = Box::new(LocalhostFs::new(PathBuf::from("/")));
+        let session = ParkedSession::new(
+            params.clone(),
+            client,
+            SshNegotiationHandle::default(),
+            PathBuf::from("/home/omar"),
+            Duration::from_secs(300),
+        );
+        assert!(session.is_session_of(&params));
+        assert!(!session.is_expired());
+        assert_eq!(session.host_name(), "bastion");
+        let (_, _, wrkdir) = session.resume();
+        assert_eq!(wrkdir, PathBuf::from("/home/omar"));
+    }
+}
@@ -0,0 +1,653 @@
+//! ## ProxyJump
+//!
+//! Connection to ssh servers through the jump hosts set by the `ProxyJump` directive of the ssh configuration
+
+use std::collections::HashMap;
+use std::io::{self, Read, Write};
+use std::net::{Shutdown, SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use remotefs::fs::{File, Metadata, ReadStream, UnixPex, Welcome, WriteStream};
+use remotefs::{RemoteError, RemoteErrorType, RemoteFs, RemoteResult};
+use remotefs_ssh::SshKeyStorage as SshKeyStorageTrait;
+use ssh2::{Channel, MethodType, Session};
+
+use super::host_key::KnownHosts;
+use super::ssh_session::{FailedJumpHost, HostKeyCheck, SshNegotiationHandle};
+use crate::system::sshkey_storage::SshKeyStorage;
+
+/// Interval between two polls of an idle tunnel
+const POLL_INTERVAL: Duration = Duration::from_millis(2);
+
+/// A jump host of the `ProxyJump` directive, in the `[user@]host[:port]` form
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JumpHost {
+    pub host: String,
+    pub port: Option<u16>,
+    pub username: Option<String>,
+}
+
+impl FromStr for JumpHost {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let s = s.strip_prefix("ssh://").unwrap_or(s);
+        let (username, s) = match s.rsplit_once('@') {
+            Some((username, s)) => (Some(username.to_string()), s),
+            None => (None, s),
+        };
+        // ipv6 addresses are enclosed in brackets
+        let (host, port) = match s.strip_prefix('[') {
+            Some(s) => match s.split_once(']') {
+                Some((host, port)) => (host, port.strip_prefix(':')),
+                None => return Err(format!("invalid jump host: {s}")),
+            },
+            None => match s.rsplit_once(':') {
+                Some((host, port)) => (host, Some(port)),
+                None => (s, None),
+            },
+        };
+        if host.is_empty() {
+            return Err(String::from("jump host can't be empty"));
+        }
+        let port = match port {
+            Some(port) => Some(
+                port.parse::<u16>()
+                    .map_err(|_| format!("invalid port for jump host {host}: {port}"))?,
+            ),
+            None => None,
+        };
+        Ok(Self {
+            host: host.to_string(),
+            port,
+            username,
+        })
+    }
+}
+
+impl JumpHost {
+    /// Parse the comma-separated list of jump hosts of a `ProxyJump` directive.
+    /// `none` means that no jump host is used
+    pub fn parse_list(s: &str) -> Result<Vec<Self>, String> {
+        if s.trim().eq_ignore_ascii_case("none") {
+            return Ok(Vec::new());
+        }
+        s.split(',').map(Self::from_str).collect()
+    }
+}
+
+/// A jump host, with the parameters resolved from the ssh configuration
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hop {
+    /// Name of the host as written in the `ProxyJump` directive
+    pub alias: String,
+    /// Address of the host
+    pub address: String,
+    pub port: u16,
+    pub username: String,
+}
+
+/// Authentication options of the jump hosts
+pub struct HopAuth {
+    pub key_storage: SshKeyStorage,
+    /// Passwords of the jump hosts, by name of the host; used to decrypt the keys and to authenticate when no key works.
+    /// The password of the target is never sent to the jump hosts
+    pub passwords: HashMap<String, String>,
+    /// Known hosts file the host keys of the jump hosts are verified against; `None` if any host key is accepted
+    pub known_hosts: Option<KnownHosts>,
+    /// Handle the refused host keys and the jump host the authentication failed on are reported to
+    pub negotiation: SshNegotiationHandle,
+    pub timeout: Duration,
+}
+
+/// Key storage which resolves the keys of `host`, whatever the address the client connects to is
+pub struct AliasKeyStorage {
+    storage: SshKeyStorage,
+    host: String,
+}
+
+impl AliasKeyStorage {
+    pub fn new(storage: SshKeyStorage, host: impl ToString) -> Self {
+        Self {
+            storage,
+            host: host.to_string(),
+        }
+    }
+}
+
+impl SshKeyStorageTrait for AliasKeyStorage {
+    fn resolve(&self, _host: &str, username: &str) -> Option<PathBuf> {
+        self.storage.resolve(&self.host, username)
+    }
+}
+
+/// Tunnel through a chain of jump hosts: each host forwards connections to the next one through a listener on localhost,
+/// and the last one forwards the connections accepted by the target listener to the target
+struct Tunnel {
+    running: Arc<AtomicBool>,
+    workers: Vec<JoinHandle<()>>,
+}
+
+impl Tunnel {
+    /// Connect to the jump hosts and start forwarding the connections accepted by `listener` to `target`
+    fn open(
+        hops: &[Hop],
+        target: (&str, u16),
+        listener: &TcpListener,
+        auth: &HopAuth,
+    ) -> RemoteResult<Self> {
+        let mut tunnel = Self {
+            running: Arc::new(AtomicBool::new(true)),
+            workers: Vec::with_capacity(hops.len()),
+        };
+        auth.negotiation.set_failed_jump_host(None);
+        let Some(first) = hops.first() else {
+            return Ok(tunnel);
+        };
+        let mut stream = Self::tcp_connect(&first.address, first.port, auth.timeout)?;
+        for (i, hop) in hops.iter().enumerate() {
+            let session = Self::session(stream, hop, auth)?;
+            let (next, hop_listener) = match hops.get(i + 1) {
+                Some(next) => (
+                    (next.address.clone(), next.port),
+                    TcpListener::bind("127.0.0.1:0").map_err(io_error)?,
+                ),
+                None => (
+                    (target.0.to_string(), target.1),
+                    listener.try_clone().map_err(io_error)?,
+                ),
+            };
+            let local_addr = hop_listener.local_addr().map_err(io_error)?;
+            debug!(
+                "forwarding {local_addr} to {}:{} through jump host {}",
+                next.0, next.1, hop.alias
+            );
+            let running = tunnel.running.clone();
+            tunnel.workers.push(thread::spawn(move || {
+                forward(&session, &hop_listener, (&next.0, next.1), &running)
+            }));
+            stream = match hops.get(i + 1) {
+                Some(_) => TcpStream::connect(local_addr).map_err(io_error)?,
+                None => break,
+            };
+        }
+        Ok(tunnel)
+    }
+
+    fn tcp_connect(address: &str, port: u16, timeout: Duration) -> RemoteResult<TcpStream> {
+        let addresses: Vec<SocketAddr> = (address, port)
+            .to_socket_addrs()
+            .map_err(|e| RemoteError::new_ex(RemoteErrorType::BadAddress, e))?
+            .collect();
+        addresses
+            .iter()
+            .find_map(|address| TcpStream::connect_timeout(address, timeout).ok())
+            .ok_or_else(|| {
+                RemoteError::new_ex(
+                    RemoteErrorType::ConnectionError,
+                    format!("could not connect to jump host {address}:{port}"),
+                )
+            })
+    }
+
+    /// Open an authenticated ssh session with `hop`, once its host key is verified
+    fn session(stream: TcpStream, hop: &Hop, auth: &HopAuth) -> RemoteResult<Session> {
+        let mut session =
+            Session::new().map_err(|e| RemoteError::new_ex(RemoteErrorType::ConnectionError, e))?;
+        session.set_tcp_stream(stream);
+        session.set_timeout(auth.timeout.as_millis() as u32);
+        let host_key = auth.known_hosts.clone().map(|known_hosts| {
+            let host = (hop.address.clone(), hop.port);
+            HostKeyCheck::new(host.clone(), host, known_hosts, auth.timeout)
+        });
+        if let Some(check) = host_key.as_ref().filter(|x| !x.algorithms.is_empty()) {
+            if let Err(err) = session.method_pref(MethodType::HostKey, &check.algorithms.join(","))
+            {
+                error!(
+                    "could not set host key algorithms of jump host {}: {err}",
+                    hop.alias
+                );
+            }
+        }
+        session.handshake().map_err(|e| {
+            RemoteError::new_ex(
+                RemoteErrorType::ProtocolError,
+                format!("handshake with jump host {} failed: {e}", hop.alias),
+            )
+        })?;
+        if let Some(check) = host_key {
+            let (key, _) = session.host_key().ok_or_else(|| {
+                RemoteError::new_ex(
+                    RemoteErrorType::ProtocolError,
+                    format!("jump host {} didn't send its host key", hop.alias),
+                )
+            })?;
+            check.verify(key, &auth.negotiation)?;
+        }
+        Self::authenticate(&session, hop, auth);
+        if !session.authenticated() {
+            auth.negotiation.set_failed_jump_host(Some(FailedJumpHost {
+                alias: hop.alias.clone(),
+                username: hop.username.clone(),
+            }));
+            return Err(RemoteError::new_ex(
+                RemoteErrorType::AuthenticationFailed,
+                format!(
+                    "could not authenticate as {} on jump host {}",
+                    hop.username, hop.alias
+                ),
+            ));
+        }
+        info!("authenticated on jump host {}", hop.alias);
+        Ok(session)
+    }
+
+    /// Authenticate on `hop`, like on the target: with the ssh agent, then with the ssh key and then with the password
+    /// of the hop
+    fn authenticate(session: &Session, hop: &Hop, auth: &HopAuth) {
+        let password = auth.passwords.get(&hop.alias).map(String::as_str);
+        match Self::authenticate_with_agent(session, &hop.username) {
+            Ok(true) => return,
+            Ok(false) => {}
+            Err(err) => error!(
+                "could not authenticate on {} with ssh agent: {err}",
+                hop.alias
+            ),
+        }
+        if let Some(key) = auth
+            .key_storage
+            .resolve(&hop.alias, &hop.username)
+            .or_else(|| auth.key_storage.resolve(&hop.address, &hop.username))
+        {
+            if let Err(err) =
+                session.userauth_pubkey_file(&hop.username, None, key.as_path(), password)
+            {
+                error!("could not authenticate on {} with key: {err}", hop.alias);
+            }
+            if session.authenticated() {
+                return;
+            }
+        }
+        if let Some(password) = password {
+            if let Err(err) = session.userauth_password(&hop.username, password) {
+                error!(
+                    "could not authenticate on {} with password: {err}",
+                    hop.alias
+                );
+            }
+        }
+    }
+
+    /// Try all the identities of the ssh agent; returns whether one of them has been accepted
+    fn authenticate_with_agent(session: &Session, username: &str) -> Result<bool, ssh2::Error> {
+        let mut agent = session.agent()?;
+        agent.connect()?;
+        agent.list_identities()?;
+        Ok(agent
+            .identities()?
+            .iter()
+            .any(|identity| agent.userauth(username, identity).is_ok()))
+    }
+}
+
+impl Drop for Tunnel {
+    fn drop(&mut self) {
+        self.running.store(false, Ordering::Relaxed);
+        for worker in self.workers.drain(..) {
+            let _ = worker.join();
+        }
+    }
+}
+
+/// Forward the connections accepted by `listener` to `target`, through `session`, until the tunnel is closed
+fn forward(session: &Session, listener: &TcpListener, target: (&str, u16), running: &AtomicBool) {
+    if let Err(err) = listener.set_nonblocking(true) {
+        error!("could not set listener as non-blocking: {err}");
+        return;
+    }
+    while running.load(Ordering::Relaxed) {
+        let stream = match listener.accept() {
+            Ok((stream, _)) => stream,
+            Err(err) if err.kind() == io::ErrorKind::WouldBlock => {
+                thread::sleep(POLL_INTERVAL);
+                continue;
+            }
+            Err(err) => {
+                error!("could not accept connection: {err}");
+                return;
+            }
+        };
+        session.set_blocking(true);
+        let channel = match session.channel_direct_tcpip(target.0, target.1, None) {
+            Ok(channel) => channel,
+            Err(err) => {
+                error!("could not open channel to {}:{}: {err}", target.0, target.1);
+                let _ = stream.shutdown(Shutdown::Both);
+                continue;
+            }
+        };
+        session.set_blocking(false);
+        if let Err(err) = pump(&stream, channel, running) {
+            error!("connection to {}:{} terminated: {err}", target.0, target.1);
+        }
+        let _ = stream.shutdown(Shutdown::Both);
+    }
+}
+
+/// Copy data between `stream` and `channel`, until one of them is closed
+fn pump(mut stream: &TcpStream, mut channel: Channel, running: &AtomicBool) -> io::Result<()> {
+    stream.set_nonblocking(true)?;
+    let mut buffer = [0; 32768];
+    while running.load(Ordering::Relaxed) {
+        let mut idle = true;
+        match stream.read(&mut buffer) {
+            Ok(0) => return Ok(()),
+            Ok(bytes) => {
+                idle = false;
+                write_all(&mut channel, &buffer[..bytes], running)?;
+            }
+            Err(err) if err.kind() == io::ErrorKind::WouldBlock => {}
+            Err(err) => return Err(err),
+        }
+        match channel.read(&mut buffer) {
+            Ok(0) if channel.eof() => return Ok(()),
+            Ok(0) => {}
+            Ok(bytes) => {
+                idle = false;
+                write_all(&mut stream, &buffer[..bytes], running)?;
+            }
+            Err(err) if err.kind() == io::ErrorKind::WouldBlock => {}
+            Err(err) => return Err(err),
+        }
+        if idle {
+            thread::sleep(POLL_INTERVAL);
+        }
+    }
+    Ok(())
+}
+
+/// Write the whole `data` on the non-blocking `writer`
+fn write_all(writer: &mut impl Write, mut data: &[u8], running: &AtomicBool) -> io::Result<()> {
+    while !data.is_empty() {
+        match writer.write(data) {
+            Ok(0) => return Err(io::ErrorKind::WriteZero.into()),
+            Ok(bytes) => data = &data[bytes..],
+            Err(err) if err.kind() == io::ErrorKind::WouldBlock => {
+                if !running.load(Ordering::Relaxed) {
+                    return Err(io::ErrorKind::Interrupted.into());
+                }
+                thread::sleep(POLL_INTERVAL);
+            }
+            Err(err) => return Err(err),
+        }
+    }
+    Ok(())
+}
+
+fn io_error(err: io::Error) -> RemoteError {
+    RemoteError::new_ex(RemoteErrorType::IoError, err)
+}
+
+/// Ssh client which connects to the target through jump hosts.
+///
+/// The client connects to a listener on localhost, whose connections are forwarded to the target
+/// by the tunnel, which is opened on connect
+pub struct ProxyJumpFs {
+    client: Box<dyn RemoteFs>,
+    hops: Vec<Hop>,
+    target: (String, u16),
+    /// Listener the client connects to; `None` if it couldn't be bound, so the connection fails
+    listener: Option<TcpListener>,
+    auth: HopAuth,
+    tunnel: Option<Tunnel>,
+}
+
+impl ProxyJumpFs {
+    /// Bind the listener the client connects to and build the client with the address of the listener
+    pub fn new<F>(hops: Vec<Hop>, target: (String, u16), auth: HopAuth, client: F) -> Self
+    where
+        F: FnOnce(SocketAddr) -> Box<dyn RemoteFs>,
+    {
+        let listener = match TcpListener::bind("127.0.0.1:0") {
+            Ok(listener) => Some(listener),
+            Err(err) => {
+                error!("could not bind the jump hosts tunnel: {err}");
+                None
+            }
+        };
+        // the client is never connected, if the listener couldn't be bound
+        let local_addr = listener
+            .as_ref()
+            .and_then(|x| x.local_addr().ok())
+            .unwrap_or_else(|| SocketAddr::from(([127, 0, 0, 1], 0)));
+        Self {
+            client: client(local_addr),
+            hops,
+            target,
+            listener,
+            auth,
+            tunnel: None,
+        }
+    }
+}
+
+impl RemoteFs for ProxyJumpFs {
+    fn connect(&mut self) -> RemoteResult<Welcome> {
+        if self.tunnel.is_none() {
+            let listener = self.listener.as_ref().ok_or_else(|| {
+                RemoteError::new_ex(
+                    RemoteErrorType::ConnectionError,
+                    "could not bind the jump hosts tunnel on localhost",
+                )
+            })?;
+            self.tunnel = Some(Tunnel::open(
+                &self.hops,
+                (&self.target.0, self.target.1),
+                listener,
+                &self.auth,
+            )?);
+        }
+        let result = self.client.connect();
+        if result.is_err() {
+            self.tunnel = None;
+        }
+        result
+    }
+
+    fn disconnect(&mut self) -> RemoteResult<()> {
+        let result = self.client.disconnect();
+        self.tunnel = None;
+        result
+    }
+
+    fn is_connected(&mut self) -> bool {
+        self.client.is_connected()
+    }
+
+    fn pwd(&mut self) -> RemoteResult<PathBuf> {
+        self.client.pwd()
+    }
+
+    fn change_dir(&mut self, dir: &Path) -> RemoteResult<PathBuf> {
+        self.client.change_dir(dir)
+    }
+
+    fn list_dir(&mut self, path: &Path) -> RemoteResult<Vec<File>> {
+        self.client.list_dir(path)
+    }
+
+    fn stat(&mut self, path: &Path) -> RemoteResult<File> {
+        self.client.stat(path)
+    }
+
+    fn setstat(&mut self, path: &Path, metadata: Metadata) -> RemoteResult<()> {
+        self.client.setstat(path, metadata)
+    }
+
+    fn exists(&mut self, path: &Path) -> RemoteResult<bool> {
+        self.client.exists(path)
+    }
+
+    fn remove_file(&mut self, path: &Path) -> RemoteResult<()> {
+        self.client.remove_file(path)
+    }
+
+    fn remove_dir(&mut self, path: &Path) -> RemoteResult<()> {
+        self.client.remove_dir(path)
+    }
+
+    fn remove_dir_all(&mut self, path: &Path) -> RemoteResult<()> {
+        self.client.remove_dir_all(path)
+    }
+
+    fn create_dir(&mut self, path: &Path, mode: UnixPex) -> RemoteResult<()> {
+        self.client.create_dir(path, mode)
+    }
+
+    fn symlink(&mut self, path: &Path, target: &Path) -> RemoteResult<()> {
+        self.client.symlink(path, target)
+    }
+
+    fn copy(&mut self, src: &Path, dest: &Path) -> RemoteResult<()> {
+        self.client.copy(src, dest)
+    }
+
+    fn mov(&mut self, src: &Path, dest: &Path) -> RemoteResult<()> {
+        self.client.mov(src, dest)
+    }
+
+    fn exec(&mut self, cmd: &str) -> RemoteResult<(u32, String)> {
+        self.client.exec(cmd)
+    }
+
+    fn append(&mut self, path: &Path, metadata: &Metadata) -> RemoteResult<WriteStream> {
+        self.client.append(path, metadata)
+    }
+
+    fn create(&mut self, path: &Path, metadata: &Metadata) -> RemoteResult<WriteStream> {
+        self.client.create(path, metadata)
+    }
+
+    fn open(&mut self, path: &Path) -> RemoteResult<ReadStream> {
+        self.client.open(path)
+    }
+
+    fn on_written(&mut self, writable: WriteStream) -> RemoteResult<()> {
+        self.client.on_written(writable)
+    }
+
+    fn on_read(&mut self, readable: ReadStream) -> RemoteResult<()> {
+        self.client.on_read(readable)
+    }
+
+    fn append_file(
+        &mut self,
+        path: &Path,
+        metadata: &Metadata,
+        reader: Box<dyn Read + Send>,
+    ) -> RemoteResult<u64> {
+        self.client.append_file(path, metadata, reader)
+    }
+
+    fn create_file(
+        &mut self,
+        path: &Path,
+        metadata: &Metadata,
+        reader: Box<dyn Read + Send>,
+    ) -> RemoteResult<u64> {
+        self.client.create_file(path, metadata, reader)
+    }
+
+    fn open_file(&mut self, src: &Path, dest: Box<dyn Write + Send>) -> RemoteResult<u64> {
+        self.client.open_file(src, dest)
+    }
+
+    fn find(&mut self, search: &str) -> RemoteResult<Vec<File>> {
+        self.client.find(search)
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn should_parse_jump_host() {
+        assert_eq!(
+            JumpHost::from_str("bastion").unwrap(),
+            JumpHost {
+                host: String::from("bastion"),
+                port: None,
+                username: None,
+            }
+        );
+        assert_eq!(
+            JumpHost::from_str("omar@bastion.example.com:2222").unwrap(),
+            JumpHost {
+                host: String::from("bastion.example.com"),
+                port: Some(2222),
+                username: Some(String::from("omar")),
+            }
+        );
+        assert_eq!(
+            JumpHost::from_str("ssh://root@[::1]:22").unwrap(),
+            JumpHost {
+                host: String::from("::1"),
+                port: Some(22),
+                username: Some(String::from("root")),
+            }
+        );
+        assert!(JumpHost::from_str("bastion:ssh").is_err());
+        assert!(JumpHost::from_str("omar@").is_err());
+    }
+
+    #[test]
+    fn should_parse_jump_host_list() {
+        let hops = JumpHost::parse_list("omar@bastion:2222, gateway").unwrap();
+        assert_eq!(hops.len(), 2);
+        assert_eq!(hops[0].host.as_str(), "bastion");
+        assert_eq!(hops[1].host.as_str(), "gateway");
+        assert!(JumpHost::parse_list("none").unwrap().is_empty());
+        assert!(JumpHost::parse_list("bastion,").is_err());
+    }
+
+    #[test]
+    fn should_not_connect_to_unreachable_jump_host() {
+        let auth = HopAuth {
+            key_storage: SshKeyStorage::default(),
+            passwords: HashMap::new(),
+            known_hosts: None,
+            negotiation: SshNegotiationHandle::default(),
+            timeout: Duration::from_secs(1),
+        };
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        // bind and drop a listener to get a closed port
+        let port = TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let hops = vec![Hop {
+            alias: String::from("bastion"),
+            address: String::from("127.0.0.1"),
+            port,
+            username: String::from("omar"),
+        }];
+        assert_eq!(
+            Tunnel::open(&hops, ("localhost", 22), &listener, &auth)
+                .err()
+                .unwrap()
+                .kind,
+            RemoteErrorType::ConnectionError
+        );
+    }
+}
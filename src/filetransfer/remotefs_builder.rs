@@ -2,26 +2,43 @@
 //!
 //! Remotefs client builder
 
+use std::env;
+use std::net::SocketAddr;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 
 use remotefs::RemoteFs;
 use remotefs_aws_s3::AwsS3Fs;
 use remotefs_ftp::FtpFs;
-use remotefs_kube::KubeMultiPodFs as KubeFs;
 #[cfg(smb_unix)]
 use remotefs_smb::SmbOptions;
 #[cfg(smb)]
 use remotefs_smb::{SmbCredentials, SmbFs};
-use remotefs_ssh::{ScpFs, SftpFs, SshAgentIdentity, SshConfigParseRule, SshOpts};
+use remotefs_ssh::{
+    KeyMethod, MethodType, ScpFs, SftpFs, SshAgentIdentity, SshConfigParseRule, SshOpts,
+};
 use remotefs_webdav::WebDAVFs;
-
+use ssh2_config::HostParams;
+
+use super::ftp_session::FtpModeFs;
+use super::ftps_session::FtpsSessionFs;
+use super::host_key::KnownHosts;
+use super::host_key_guard::HostKeyGuard;
+use super::kube_session::KubeNamespacedFs;
+use super::localhost_fs::LocalhostFs;
 #[cfg(not(smb))]
 use super::params::{AwsS3Params, GenericProtocolParams};
 #[cfg(smb)]
 use super::params::{AwsS3Params, GenericProtocolParams, SmbParams};
-use super::params::{KubeProtocolParams, WebDAVProtocolParams};
-use super::{FileTransferProtocol, ProtocolParams};
+use super::params::{KubeProtocolParams, SshPrefs, WebDAVProtocolParams};
+use super::proxy_jump::{AliasKeyStorage, Hop, HopAuth, JumpHost, ProxyJumpFs};
+use super::s3_session::AwsS3SessionFs;
+#[cfg(smb_unix)]
+use super::smb_session::SmbSharesFs;
+use super::ssh_session::{self, HostKeyCheck, SshClient, SshNegotiationHandle, SshPrefsFs};
+use super::webdav_session::WebDAVLockFs;
+use super::{FileTransferParams, FileTransferProtocol, ProtocolParams};
 use crate::system::config_client::ConfigClient;
 use crate::system::sshkey_storage::SshKeyStorage;
 use crate::utils::ssh as ssh_utils;
@@ -37,29 +54,67 @@ impl RemoteFsBuilder {
         protocol: FileTransferProtocol,
         params: ProtocolParams,
         config_client: &ConfigClient,
+    ) -> Box<dyn RemoteFs> {
+        Self::build_with_ssh_prefs(
+            protocol,
+            params,
+            &SshPrefs::default(),
+            SshNegotiationHandle::default(),
+            config_client,
+        )
+    }
+
+    /// Build RemoteFs client from the params of a connection, applying its ssh preferences to scp and sftp clients.
+    /// The methods negotiated by ssh clients on connect are reported to `negotiation`
+    pub fn build_connection(
+        params: &FileTransferParams,
+        negotiation: SshNegotiationHandle,
+        config_client: &ConfigClient,
+    ) -> Box<dyn RemoteFs> {
+        Self::build_with_ssh_prefs(
+            params.protocol,
+            params.params.clone(),
+            &params.ssh,
+            negotiation,
+            config_client,
+        )
+    }
+
+    fn build_with_ssh_prefs(
+        protocol: FileTransferProtocol,
+        params: ProtocolParams,
+        ssh: &SshPrefs,
+        negotiation: SshNegotiationHandle,
+        config_client: &ConfigClient,
     ) -> Box<dyn RemoteFs> {
         match (protocol, params) {
             (FileTransferProtocol::AwsS3, ProtocolParams::AwsS3(params)) => {
                 Box::new(Self::aws_s3_client(params))
             }
             (FileTransferProtocol::Ftp(secure), ProtocolParams::Generic(params)) => {
-                Box::new(Self::ftp_client(params, secure))
+                Self::ftp_client(params, secure)
             }
             (FileTransferProtocol::Kube, ProtocolParams::Kube(params)) => {
                 Box::new(Self::kube_client(params))
             }
+            (FileTransferProtocol::Localhost, _) => Box::new(Self::localhost_client()),
             (FileTransferProtocol::Scp, ProtocolParams::Generic(params)) => {
-                Box::new(Self::scp_client(params, config_client))
+                Self::ssh_client::<ScpFs>(params, ssh, negotiation, config_client)
             }
             (FileTransferProtocol::Sftp, ProtocolParams::Generic(params)) => {
-                Box::new(Self::sftp_client(params, config_client))
+                Self::ssh_client::<SftpFs>(params, ssh, negotiation, config_client)
+            }
+            // without a share, the shares of the server are browsed
+            #[cfg(smb_unix)]
+            (FileTransferProtocol::Smb, ProtocolParams::Smb(params)) if params.share.is_empty() => {
+                Box::new(SmbSharesFs::new(Self::smb_client(params)))
             }
             #[cfg(smb)]
             (FileTransferProtocol::Smb, ProtocolParams::Smb(params)) => {
                 Box::new(Self::smb_client(params))
             }
             (FileTransferProtocol::WebDAV, ProtocolParams::WebDAV(params)) => {
-                Box::new(Self::webdav_client(params))
+                Box::new(Self::webdav_client(params, config_client))
             }
             (protocol, params) => {
                 error!("Invalid params for protocol '{:?}'", protocol);
@@ -69,7 +124,8 @@ impl RemoteFsBuilder {
     }
 
     /// Build aws s3 client from parameters
-    fn aws_s3_client(params: AwsS3Params) -> AwsS3Fs {
+    fn aws_s3_client(params: AwsS3Params) -> AwsS3SessionFs {
+        let endpoint = params.endpoint_url();
         let mut client = AwsS3Fs::new(params.bucket_name).new_path_style(params.new_path_style);
         if let Some(region) = params.region {
             client = client.region(region);
@@ -77,7 +133,7 @@ impl RemoteFsBuilder {
         if let Some(profile) = params.profile {
             client = client.profile(profile);
         }
-        if let Some(endpoint) = params.endpoint {
+        if let Some(endpoint) = endpoint.as_deref() {
             client = client.endpoint(endpoint);
         }
         if let Some(access_key) = params.access_key {
@@ -92,12 +148,29 @@ impl RemoteFsBuilder {
         if let Some(session_token) = params.session_token {
             client = client.session_token(session_token);
         }
-        client
+        AwsS3SessionFs::new(client, endpoint)
+            .requester_pays(params.requester_pays)
+            .storage_class(params.storage_class)
+            .accept_invalid_certs(params.accept_invalid_certs)
+            .ca_bundle(params.ca_bundle)
     }
 
-    /// Build ftp client from parameters
-    fn ftp_client(params: GenericProtocolParams, secure: bool) -> FtpFs {
-        let mut client = FtpFs::new(params.address, params.port).passive_mode();
+    /// Build ftp client from parameters.
+    ///
+    /// FTPS clients reuse the TLS session of the control connection for the data connections, unless the reuse is
+    /// turned off, in which case the native-tls client is used, which doesn't reuse sessions
+    fn ftp_client(params: GenericProtocolParams, secure: bool) -> Box<dyn RemoteFs> {
+        if secure && params.tls_session_reuse {
+            let mut client = FtpsSessionFs::new(params.address, params.port, params.active_mode);
+            if let Some(username) = params.username {
+                client = client.username(username);
+            }
+            if let Some(password) = params.password {
+                client = client.password(password);
+            }
+            return Box::new(client);
+        }
+        let mut client = FtpFs::new(params.address, params.port);
         if let Some(username) = params.username {
             client = client.username(username);
         }
@@ -107,11 +180,11 @@ impl RemoteFsBuilder {
         if secure {
             client = client.secure(true, true);
         }
-        client
+        Box::new(FtpModeFs::new(client, params.active_mode))
     }
 
     /// Build kube client
-    fn kube_client(params: KubeProtocolParams) -> KubeFs {
+    fn kube_client(params: KubeProtocolParams) -> KubeNamespacedFs {
         let rt = Arc::new(
             tokio::runtime::Builder::new_current_thread()
                 .worker_threads(1)
@@ -119,26 +192,191 @@ impl RemoteFsBuilder {
                 .build()
                 .expect("Unable to create tokio runtime"),
         );
-        let kube_fs = KubeFs::new(&rt);
-        if let Some(config) = params.config() {
-            kube_fs.config(config)
-        } else {
-            kube_fs
+        let namespace = params.namespace.clone().filter(|x| !x.is_empty());
+        KubeNamespacedFs::new(&rt, params.config(), namespace)
+    }
+
+    /// Build localhost client, starting from the current directory; the entry directory is entered once connected
+    fn localhost_client() -> LocalhostFs {
+        LocalhostFs::new(env::current_dir().unwrap_or_else(|_| PathBuf::from("/")))
+    }
+
+    /// Build scp or sftp client, with the ssh preferences `ssh`; the ones which are not set are read from the
+    /// configuration.
+    ///
+    /// If the `ProxyJump` directive is set for the host in the ssh configuration, the client connects through the jump hosts
+    fn ssh_client<T>(
+        params: GenericProtocolParams,
+        ssh: &SshPrefs,
+        negotiation: SshNegotiationHandle,
+        config_client: &ConfigClient,
+    ) -> Box<dyn RemoteFs>
+    where
+        T: SshClient + From<SshOpts> + 'static,
+    {
+        let ssh = ssh.clone().or_defaults(
+            config_client.get_ssh_compression(),
+            config_client.get_ssh_ciphers().map(str::to_string),
+        );
+        let mut hops = Self::proxy_jump_hops(&params.address, config_client);
+        for hop in hops.iter_mut() {
+            if let Some(credentials) = params.jump_credentials.get(&hop.alias) {
+                hop.username = credentials.username.clone();
+            }
+        }
+        let ssh_config = Self::query_ssh_config(&params.address, config_client);
+        let target = (
+            ssh_config
+                .as_ref()
+                .and_then(|config| config.host_name.clone())
+                .unwrap_or_else(|| params.address.clone()),
+            ssh_config
+                .as_ref()
+                .and_then(|config| config.port)
+                .unwrap_or(params.port),
+        );
+        let timeout = ssh_config
+            .as_ref()
+            .and_then(|config| config.connect_timeout)
+            .unwrap_or(Duration::from_secs(30));
+        if hops.is_empty() {
+            return Box::new(Self::ssh_prefs_client::<T, _>(
+                &ssh,
+                negotiation,
+                config_client.get_ssh_config(),
+                Some(params.address.as_str()),
+                Self::host_key_check(target.clone(), target, timeout, config_client),
+                || Self::build_ssh_opts(params.clone(), config_client),
+            ));
         }
+        let auth = HopAuth {
+            key_storage: Self::make_ssh_storage(config_client),
+            passwords: params
+                .jump_credentials
+                .iter()
+                .map(|(alias, credentials)| (alias.clone(), credentials.password.clone()))
+                .collect(),
+            known_hosts: (!config_client.get_ssh_accept_any_host_key())
+                .then(|| KnownHosts::new(config_client.get_ssh_known_hosts())),
+            negotiation: negotiation.clone(),
+            timeout,
+        };
+        Box::new(ProxyJumpFs::new(hops, target.clone(), auth, |local_addr| {
+            Box::new(Self::ssh_prefs_client::<T, _>(
+                &ssh,
+                negotiation.clone(),
+                None,
+                None,
+                Self::host_key_check(
+                    (local_addr.ip().to_string(), local_addr.port()),
+                    target.clone(),
+                    timeout,
+                    config_client,
+                ),
+                || Self::build_tunneled_ssh_opts(params.clone(), local_addr, config_client),
+            ))
+        }))
     }
 
-    /// Build scp client
-    fn scp_client(params: GenericProtocolParams, config_client: &ConfigClient) -> ScpFs {
-        Self::build_ssh_opts(params, config_client).into()
+    /// Build the verification of the host key of `host`, reached at `address`, against the known hosts file.
+    /// `None` if any host key is accepted, as set in the configuration
+    fn host_key_check(
+        address: (String, u16),
+        host: (String, u16),
+        timeout: Duration,
+        config_client: &ConfigClient,
+    ) -> Option<HostKeyCheck> {
+        if config_client.get_ssh_accept_any_host_key() {
+            warn!("host key verification is disabled: accepting any host key");
+            return None;
+        }
+        Some(HostKeyCheck::new(
+            address,
+            host,
+            KnownHosts::new(config_client.get_ssh_known_hosts()),
+            timeout,
+        ))
     }
 
-    /// Build sftp client
-    fn sftp_client(params: GenericProtocolParams, config_client: &ConfigClient) -> SftpFs {
-        Self::build_ssh_opts(params, config_client).into()
+    /// Build the ssh client with the ssh options made by `opts`, applying the ssh preferences `ssh`.
+    /// Compression is enabled through a temporary ssh configuration, which includes the ssh configuration at `ssh_config`.
+    ///
+    /// If the preferences are not the defaults, the client falls back to the default ones if the server refuses them.
+    ///
+    /// If the host key is checked, the client connects to the host key guard: `host`, the host of the options, is
+    /// redirected to localhost through the temporary ssh configuration (`None` if the options point to localhost already).
+    /// If the host has known keys, they're the only host key types negotiated
+    fn ssh_prefs_client<T, F>(
+        ssh: &SshPrefs,
+        negotiation: SshNegotiationHandle,
+        ssh_config: Option<&str>,
+        host: Option<&str>,
+        host_key: Option<HostKeyCheck>,
+        opts: F,
+    ) -> SshPrefsFs<T>
+    where
+        T: SshClient + From<SshOpts>,
+        F: Fn() -> SshOpts,
+    {
+        let mut guard = host_key.map(HostKeyGuard::new);
+        let guard_port = guard
+            .as_ref()
+            .and_then(HostKeyGuard::local_addr)
+            .map(|x| x.port());
+        let redirect = guard_port.and(host);
+        let algorithms = guard
+            .as_ref()
+            .map(|x| x.check().algorithms.clone())
+            .unwrap_or_default();
+        let mut configs = Vec::new();
+        let mut redirected = true;
+        let mut client_opts = |compression: bool| {
+            let mut opts = opts();
+            if !algorithms.is_empty() {
+                opts = opts.method(KeyMethod::new(MethodType::HostKey, &algorithms));
+            }
+            if let Some(port) = guard_port {
+                opts = opts.port(port);
+            }
+            if compression || redirect.is_some() {
+                match ssh_session::override_config(ssh_config, redirect, compression) {
+                    Ok(file) => {
+                        opts =
+                            opts.config_file(file.path(), SshConfigParseRule::ALLOW_UNKNOWN_FIELDS);
+                        configs.push(file);
+                    }
+                    Err(err) => {
+                        error!("Could not write ssh config to enable compression or redirect the host: {err}");
+                        redirected &= redirect.is_none();
+                    }
+                }
+            }
+            opts
+        };
+        let (client, fallback) = if ssh.is_default() {
+            (T::from(client_opts(false)), None)
+        } else {
+            let mut prefs_opts = client_opts(ssh.compression());
+            let ciphers = ssh.ciphers();
+            if !ciphers.is_empty() {
+                debug!("preferred ssh ciphers: {}", ciphers.join(","));
+                prefs_opts = prefs_opts
+                    .method(KeyMethod::new(MethodType::CryptClientServer, &ciphers))
+                    .method(KeyMethod::new(MethodType::CryptServerClient, &ciphers));
+            }
+            (T::from(prefs_opts), Some(T::from(client_opts(false))))
+        };
+        // a client which would connect to the server directly must not connect at all
+        if let Some(guard) = guard.as_mut().filter(|_| !redirected) {
+            guard.close();
+        }
+        SshPrefsFs::new(client, configs, fallback, negotiation).host_key_guard(guard)
     }
 
     #[cfg(smb_unix)]
     fn smb_client(params: SmbParams) -> SmbFs {
+        // browsing the shares, each one is connected when entered
+        let one_share_per_server = !params.share.is_empty();
         let mut credentials = SmbCredentials::default()
             .server(format!("smb://{}:{}", params.address, params.port))
             .share(params.share);
@@ -156,7 +394,7 @@ impl RemoteFsBuilder {
         match SmbFs::try_new(
             credentials,
             SmbOptions::default()
-                .one_share_per_server(true)
+                .one_share_per_server(one_share_per_server)
                 .case_sensitive(false),
         ) {
             Ok(fs) => fs,
@@ -181,8 +419,10 @@ impl RemoteFsBuilder {
         SmbFs::new(credentials)
     }
 
-    fn webdav_client(params: WebDAVProtocolParams) -> WebDAVFs {
-        WebDAVFs::new(&params.username, &params.password, &params.uri)
+    fn webdav_client(params: WebDAVProtocolParams, config_client: &ConfigClient) -> WebDAVLockFs {
+        let client = WebDAVFs::new(&params.username, &params.password, &params.uri);
+        WebDAVLockFs::new(client, &params.uri, &params.username, &params.password)
+            .lock_uploads(config_client.get_webdav_lock_uploads())
     }
 
     /// Build ssh options from generic protocol params and client configuration
@@ -192,37 +432,14 @@ impl RemoteFsBuilder {
             .ssh_agent_identity(Some(SshAgentIdentity::All))
             .port(params.port);
         // get ssh config
-        let ssh_config = config_client
-            .get_ssh_config()
-            .and_then(|path| {
-                debug!("reading ssh config at {}", path);
-                ssh_utils::parse_ssh2_config(path).ok()
-            })
-            .map(|config| config.query(&params.address));
+        let ssh_config = Self::query_ssh_config(&params.address, config_client);
 
         //* override port
         if let Some(port) = ssh_config.as_ref().and_then(|config| config.port) {
             opts = opts.port(port);
         }
 
-        //*  get username. Case 1 provided in params
-        if let Some(username) = params.username {
-            opts = opts.username(username);
-        } else if let Some(ssh_config) = &ssh_config {
-            debug!("no username was provided, checking whether a user is set for this host");
-            if let Some(username) = &ssh_config.user {
-                debug!("found username from config: {username}");
-                opts = opts.username(username);
-            } else {
-                //* case 3: use system username; can't be None
-                debug!("no username was provided, using current username");
-                opts = opts.username(whoami::username());
-            }
-        } else {
-            //* case 3: use system username; can't be None
-            debug!("no username was provided, using current username");
-            opts = opts.username(whoami::username());
-        }
+        opts = opts.username(Self::ssh_username(params.username, ssh_config.as_ref()));
         if let Some(password) = params.password {
             opts = opts.password(password);
         }
@@ -235,6 +452,103 @@ impl RemoteFsBuilder {
         opts
     }
 
+    /// Build the ssh options to connect to the host of `params` through the jump hosts tunnel listening on `local_addr`.
+    ///
+    /// The ssh configuration is not set, since it would resolve the address of the host;
+    /// the keys are resolved for the host instead of the tunnel address
+    fn build_tunneled_ssh_opts(
+        params: GenericProtocolParams,
+        local_addr: SocketAddr,
+        config_client: &ConfigClient,
+    ) -> SshOpts {
+        let ssh_config = Self::query_ssh_config(&params.address, config_client);
+        let key_storage = AliasKeyStorage::new(
+            Self::make_ssh_storage(config_client),
+            params.address.as_str(),
+        );
+        let mut opts = SshOpts::new(local_addr.ip().to_string())
+            .key_storage(Box::new(key_storage))
+            .ssh_agent_identity(Some(SshAgentIdentity::All))
+            .port(local_addr.port())
+            .username(Self::ssh_username(params.username, ssh_config.as_ref()));
+        if let Some(password) = params.password {
+            opts = opts.password(password);
+        }
+        opts
+    }
+
+    /// Get the jump hosts to connect to `address` through, from the `ProxyJump` directive of the ssh configuration.
+    /// Each jump host is resolved with the ssh configuration too
+    fn proxy_jump_hops(address: &str, config_client: &ConfigClient) -> Vec<Hop> {
+        let Some(path) = config_client.get_ssh_config() else {
+            return Vec::new();
+        };
+        let proxy_jump = match ssh_utils::proxy_jump(path, address) {
+            Ok(Some(proxy_jump)) => proxy_jump,
+            Ok(None) => return Vec::new(),
+            Err(err) => {
+                error!("Could not read ProxyJump from ssh config: {err}");
+                return Vec::new();
+            }
+        };
+        debug!("found ProxyJump for {address}: {proxy_jump}");
+        let jump_hosts = match JumpHost::parse_list(&proxy_jump) {
+            Ok(jump_hosts) => jump_hosts,
+            Err(err) => {
+                error!("Invalid ProxyJump for {address}: {err}");
+                return Vec::new();
+            }
+        };
+        jump_hosts
+            .into_iter()
+            .map(|jump_host| {
+                let ssh_config = Self::query_ssh_config(&jump_host.host, config_client);
+                Hop {
+                    address: ssh_config
+                        .as_ref()
+                        .and_then(|config| config.host_name.clone())
+                        .unwrap_or_else(|| jump_host.host.clone()),
+                    port: jump_host
+                        .port
+                        .or(ssh_config.as_ref().and_then(|config| config.port))
+                        .unwrap_or(22),
+                    username: Self::ssh_username(jump_host.username, ssh_config.as_ref()),
+                    alias: jump_host.host,
+                }
+            })
+            .collect()
+    }
+
+    /// Get the params of `host` from the ssh configuration, if set
+    fn query_ssh_config(host: &str, config_client: &ConfigClient) -> Option<HostParams> {
+        config_client
+            .get_ssh_config()
+            .and_then(|path| {
+                debug!("reading ssh config at {}", path);
+                ssh_utils::parse_ssh2_config(path).ok()
+            })
+            .map(|config| config.query(host))
+    }
+
+    /// Get the username to authenticate with: the provided one, otherwise the one set in the ssh configuration,
+    /// otherwise the current username
+    fn ssh_username(username: Option<String>, ssh_config: Option<&HostParams>) -> String {
+        if let Some(username) = username {
+            return username;
+        }
+        debug!("no username was provided, checking whether a user is set for this host");
+        match ssh_config.and_then(|config| config.user.clone()) {
+            Some(username) => {
+                debug!("found username from config: {username}");
+                username
+            }
+            None => {
+                debug!("no username was provided, using current username");
+                whoami::username()
+            }
+        }
+    }
+
     /// Make ssh storage from `ConfigClient` if possible, empty otherwise (empty is implicit if degraded)
     fn make_ssh_storage(config_client: &ConfigClient) -> SshKeyStorage {
         SshKeyStorage::from(config_client)
@@ -246,9 +560,11 @@ mod test {
 
     use std::path::{Path, PathBuf};
 
+    use pretty_assertions::assert_eq;
     use tempfile::TempDir;
 
     use super::*;
+    use crate::utils::test_helpers;
 
     #[test]
     fn should_build_aws_s3_fs() {
@@ -259,12 +575,30 @@ mod test {
                 .access_key(Some("pippo"))
                 .secret_access_key(Some("pluto"))
                 .security_token(Some("omar"))
-                .session_token(Some("gerry-scotti")),
+                .session_token(Some("gerry-scotti"))
+                .accept_invalid_certs(true)
+                .ca_bundle(Some("/etc/ssl/minio.pem")),
         );
         let config_client = get_config_client();
         let _ = RemoteFsBuilder::build(FileTransferProtocol::AwsS3, params, &config_client);
     }
 
+    #[test]
+    fn should_build_aws_s3_fs_with_custom_endpoint() {
+        let params = AwsS3Params::new("omar", Some("us-east-1"), None)
+            .endpoint(Some("http://localhost:9000"))
+            .new_path_style(true);
+        let client = RemoteFsBuilder::aws_s3_client(params);
+        assert_eq!(client.endpoint(), Some("http://localhost:9000"));
+        let params =
+            AwsS3Params::new("omar", Some("us-east-1"), None).endpoint(Some("minio.local:9000"));
+        let client = RemoteFsBuilder::aws_s3_client(params);
+        assert_eq!(client.endpoint(), Some("https://minio.local:9000"));
+        let client =
+            RemoteFsBuilder::aws_s3_client(AwsS3Params::new("omar", Some("us-east-1"), None));
+        assert_eq!(client.endpoint(), None);
+    }
+
     #[test]
     fn should_build_ftp_fs() {
         let params = ProtocolParams::Generic(
@@ -291,6 +625,18 @@ mod test {
         let _ = RemoteFsBuilder::build(FileTransferProtocol::Kube, params, &config_client);
     }
 
+    #[test]
+    fn should_build_localhost_fs() {
+        let config_client = get_config_client();
+        let mut client = RemoteFsBuilder::build(
+            FileTransferProtocol::Localhost,
+            ProtocolParams::default(),
+            &config_client,
+        );
+        assert!(client.connect().is_ok());
+        assert_eq!(client.pwd().unwrap(), env::current_dir().unwrap());
+    }
+
     #[test]
     fn should_build_scp_fs() {
         let params = ProtocolParams::Generic(
@@ -317,6 +663,49 @@ mod test {
         let _ = RemoteFsBuilder::build(FileTransferProtocol::Sftp, params, &config_client);
     }
 
+    #[test]
+    fn should_build_sftp_fs_with_proxy_jump() {
+        let ssh_config = test_helpers::create_sample_file_with_content(
+            r#"
+Host bastion
+        HostName 127.0.0.1
+        User omar
+
+Host internal
+        HostName 10.0.0.2
+        ProxyJump bastion,root@gateway:2222
+"#,
+        );
+        let mut config_client = get_config_client();
+        config_client.set_ssh_config(Some(ssh_config.path().to_string_lossy().to_string()));
+        let hops = RemoteFsBuilder::proxy_jump_hops("internal", &config_client);
+        assert_eq!(
+            hops,
+            vec![
+                Hop {
+                    alias: String::from("bastion"),
+                    address: String::from("127.0.0.1"),
+                    port: 22,
+                    username: String::from("omar"),
+                },
+                Hop {
+                    alias: String::from("gateway"),
+                    address: String::from("gateway"),
+                    port: 2222,
+                    username: String::from("root"),
+                }
+            ]
+        );
+        assert!(RemoteFsBuilder::proxy_jump_hops("bastion", &config_client).is_empty());
+        let params = ProtocolParams::Generic(
+            GenericProtocolParams::default()
+                .address("internal")
+                .port(22)
+                .username(Some("omar")),
+        );
+        let _ = RemoteFsBuilder::build(FileTransferProtocol::Sftp, params, &config_client);
+    }
+
     #[test]
     #[cfg(smb)]
     fn should_build_smb_fs() {
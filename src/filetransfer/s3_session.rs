@@ -0,0 +1,974 @@
+//! ## S3Session
+//!
+//! Aws s3 client which reports the TLS errors of the endpoint when connecting, which sends the requester-pays and
+//! storage class headers with the requests and which applies the TLS settings of the endpoint
+
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use chrono::DateTime;
+use remotefs::fs::{File, FileType, Metadata, ReadStream, UnixPex, Welcome, WriteStream};
+use remotefs::{RemoteError, RemoteErrorType, RemoteFs, RemoteResult};
+use remotefs_aws_s3::client::Bucket;
+use remotefs_aws_s3::AwsS3Fs;
+use reqwest::blocking::{Client, Response};
+use reqwest::{Certificate, Method};
+use s3::bucket::CHUNK_SIZE;
+use s3::command::{Command, HttpMethod};
+use s3::request::blocking::AttoRequest;
+use s3::request::Request;
+use s3::serde_types::{
+    CompleteMultipartUploadData, InitiateMultipartUploadResponse, ListBucketResult, Part,
+};
+use s3::utils::read_chunk;
+
+use crate::utils::path::{absolutize, diff_paths};
+
+/// Header telling S3 the requester pays the requests
+const REQUEST_PAYER_HEADER: &str = "x-amz-request-payer";
+/// Header telling S3 the storage class of the uploaded objects
+const STORAGE_CLASS_HEADER: &str = "x-amz-storage-class";
+/// Content type of the uploaded objects
+const CONTENT_TYPE: &str = "application/octet-stream";
+
+/// Aws s3 client checking the TLS connection with the endpoint when connecting.
+///
+/// The [`AwsS3Fs`] doesn't send any request when connecting, so a misconfigured endpoint is reported only by the first
+/// operation, as a generic error: here the bucket is listed once connected, and TLS errors fail the authentication.
+///
+/// The [`AwsS3Fs`] can't send extra headers either, so the client only loads the credentials and makes the bucket while
+/// the requests are sent here, with the `x-amz-request-payer` header if the requester pays and with the
+/// `x-amz-storage-class` header on uploads if a storage class is set.
+///
+/// The [`AwsS3Fs`] verifies the certificates of the endpoint with its bundled authorities only, so when invalid
+/// certificates are accepted or a CA bundle is set, the requests are signed by the bucket and sent with a client
+/// configured with these settings
+pub struct AwsS3SessionFs {
+    client: AwsS3Fs,
+    endpoint: Option<String>,
+    requester_pays: bool,
+    storage_class: Option<String>,
+    accept_invalid_certs: bool,
+    ca_bundle: Option<PathBuf>,
+    /// Client sending the requests with the TLS settings; `None` if there are no settings or if not connected
+    tls_client: Option<Client>,
+    /// Bucket the requests are sent to, with the requester-pays header; `None` if not connected
+    bucket: Option<Bucket>,
+    /// Bucket the uploads are sent to, with the storage class header too
+    upload_bucket: Option<Bucket>,
+    wrkdir: PathBuf,
+}
+
+impl AwsS3SessionFs {
+    /// Wrap `client`, which connects to `endpoint` (if any, otherwise to AWS)
+    pub fn new(client: AwsS3Fs, endpoint: Option<String>) -> Self {
+        Self {
+            client,
+            endpoint,
+            requester_pays: false,
+            storage_class: None,
+            accept_invalid_certs: false,
+            ca_bundle: None,
+            tls_client: None,
+            bucket: None,
+            upload_bucket: None,
+            wrkdir: PathBuf::from("/"),
+        }
+    }
+
+    /// Set whether the requester pays the requests and the downloads
+    pub fn requester_pays(mut self, requester_pays: bool) -> Self {
+        self.requester_pays = requester_pays;
+        self
+    }
+
+    /// Set the storage class of the uploaded objects
+    pub fn storage_class(mut self, storage_class: Option<String>) -> Self {
+        self.storage_class = storage_class;
+        self
+    }
+
+    /// Set whether the invalid TLS certificates of the endpoint are accepted
+    pub fn accept_invalid_certs(mut self, accept_invalid_certs: bool) -> Self {
+        self.accept_invalid_certs = accept_invalid_certs;
+        self
+    }
+
+    /// Trust the authorities in the `ca_bundle` PEM file
+    pub fn ca_bundle(mut self, ca_bundle: Option<PathBuf>) -> Self {
+        self.ca_bundle = ca_bundle;
+        self
+    }
+
+    /// Make the client sending the requests with the TLS settings; `None` if there are no settings
+    fn make_tls_client(&self) -> RemoteResult<Option<Client>> {
+        if !self.accept_invalid_certs && self.ca_bundle.is_none() {
+            return Ok(None);
+        }
+        // transfers can last longer than any timeout
+        let mut builder = Client::builder()
+            .timeout(None)
+            .danger_accept_invalid_certs(self.accept_invalid_certs);
+        if let Some(ca_bundle) = self.ca_bundle.as_deref() {
+            for certificate in Self::read_ca_bundle(ca_bundle)? {
+                builder = builder.add_root_certificate(certificate);
+            }
+        }
+        builder.build().map(Some).map_err(|err| {
+            RemoteError::new_ex(
+                RemoteErrorType::AuthenticationFailed,
+                format!("Could not make TLS client: {}", Self::describe(&err)),
+            )
+        })
+    }
+
+    /// Read the certificates of the CA bundle at `path`
+    fn read_ca_bundle(path: &Path) -> RemoteResult<Vec<Certificate>> {
+        let bundle = std::fs::read(path).map_err(|err| {
+            RemoteError::new_ex(
+                RemoteErrorType::AuthenticationFailed,
+                format!("Could not read CA bundle {}: {err}", path.display()),
+            )
+        })?;
+        match Certificate::from_pem_bundle(&bundle) {
+            Ok(certificates) if !certificates.is_empty() => Ok(certificates),
+            Ok(_) => Err(RemoteError::new_ex(
+                RemoteErrorType::AuthenticationFailed,
+                format!(
+                    "Invalid CA bundle {}: it contains no PEM certificates",
+                    path.display()
+                ),
+            )),
+            Err(err) => Err(RemoteError::new_ex(
+                RemoteErrorType::AuthenticationFailed,
+                format!("Invalid CA bundle {}: {err}", path.display()),
+            )),
+        }
+    }
+
+    /// Describe `err` with its sources, which tell the TLS errors
+    fn describe(err: &dyn std::error::Error) -> String {
+        let mut text = err.to_string();
+        let mut source = err.source();
+        while let Some(err) = source {
+            text.push_str(&format!(": {err}"));
+            source = err.source();
+        }
+        text
+    }
+
+    /// Send the request of `command` for `path`, signed by `bucket`, through the TLS `client`
+    fn send(
+        client: &Client,
+        bucket: &Bucket,
+        path: &str,
+        command: Command,
+    ) -> Result<Response, String> {
+        let method = match command.http_verb() {
+            HttpMethod::Delete => Method::DELETE,
+            HttpMethod::Get => Method::GET,
+            HttpMethod::Head => Method::HEAD,
+            HttpMethod::Post => Method::POST,
+            HttpMethod::Put => Method::PUT,
+        };
+        let request = AttoRequest::new(bucket, path, command).map_err(|e| e.to_string())?;
+        let url = request.url().map_err(|e| e.to_string())?;
+        let mut builder = client
+            .request(method, url.as_str())
+            .body(request.request_body());
+        for (name, value) in request.headers().map_err(|e| e.to_string())?.iter() {
+            builder = builder.header(name.as_str(), value.as_bytes());
+        }
+        builder.send().map_err(|e| Self::describe(&e))
+    }
+
+    /// Send the request of `command` for `path` and return the status code of the response
+    fn send_status(
+        client: &Client,
+        bucket: &Bucket,
+        path: &str,
+        command: Command,
+    ) -> Result<u16, String> {
+        Self::send(client, bucket, path, command).map(|x| x.status().as_u16())
+    }
+
+    /// List the objects with key starting with `key`
+    fn list_objects(&self, bucket: &Bucket, key: &str) -> Result<Vec<ListBucketResult>, String> {
+        let Some(client) = self.tls_client.as_ref() else {
+            return bucket
+                .list(key.to_string(), None)
+                .map_err(|e| e.to_string());
+        };
+        let mut results = Vec::new();
+        let mut continuation_token = None;
+        loop {
+            let command = Command::ListObjectsV2 {
+                prefix: key.to_string(),
+                delimiter: None,
+                continuation_token,
+                start_after: None,
+                max_keys: None,
+            };
+            let response = Self::send(client, bucket, "/", command)?;
+            let status = response.status().as_u16();
+            let body = response.bytes().map_err(|e| Self::describe(&e))?;
+            if !(200..300).contains(&status) {
+                return Err(format!("S3 replied with status {status}"));
+            }
+            let result: ListBucketResult =
+                quick_xml::de::from_reader(body.as_ref()).map_err(|e| e.to_string())?;
+            continuation_token = result.next_continuation_token.clone();
+            results.push(result);
+            if continuation_token.is_none() {
+                return Ok(results);
+            }
+        }
+    }
+
+    /// Get the status code of the HEAD request of the object with `key`
+    fn head_object(&self, bucket: &Bucket, key: &str) -> Result<u16, String> {
+        match self.tls_client.as_ref() {
+            Some(client) => Self::send_status(client, bucket, key, Command::HeadObject),
+            None => bucket
+                .head_object(key)
+                .map(|(_, status)| status)
+                .map_err(|e| e.to_string()),
+        }
+    }
+
+    /// Delete the object with `key`
+    fn delete_object(&self, bucket: &Bucket, key: &str) -> Result<u16, String> {
+        match self.tls_client.as_ref() {
+            Some(client) => Self::send_status(client, bucket, key, Command::DeleteObject),
+            None => bucket
+                .delete_object(key)
+                .map(|x| x.status_code())
+                .map_err(|e| e.to_string()),
+        }
+    }
+
+    /// Put `content` into the object with `key`
+    fn put_object(&self, bucket: &Bucket, key: &str, content: &[u8]) -> Result<u16, String> {
+        let command = Command::PutObject {
+            content,
+            content_type: CONTENT_TYPE,
+            multipart: None,
+        };
+        match self.tls_client.as_ref() {
+            Some(client) => Self::send_status(client, bucket, key, command),
+            None => bucket
+                .put_object(key, content)
+                .map(|x| x.status_code())
+                .map_err(|e| e.to_string()),
+        }
+    }
+
+    /// Put the content read from `reader` into the object with `key`, uploading it in parts if larger than a chunk
+    fn put_object_stream<R: Read>(
+        &self,
+        bucket: &Bucket,
+        reader: &mut R,
+        key: &str,
+    ) -> Result<u16, String> {
+        let Some(client) = self.tls_client.as_ref() else {
+            return bucket
+                .put_object_stream(reader, key)
+                .map_err(|e| e.to_string());
+        };
+        let chunk = read_chunk(reader).map_err(|e| e.to_string())?;
+        if chunk.len() < CHUNK_SIZE {
+            return self.put_object(bucket, key, &chunk);
+        }
+        let command = Command::InitiateMultipartUpload {
+            content_type: CONTENT_TYPE,
+        };
+        let response = Self::send(client, bucket, key, command)?;
+        let status = response.status().as_u16();
+        let body = response.text().map_err(|e| Self::describe(&e))?;
+        if !(200..300).contains(&status) {
+            return Ok(status);
+        }
+        let upload: InitiateMultipartUploadResponse =
+            quick_xml::de::from_str(&body).map_err(|e| e.to_string())?;
+        let result = Self::put_parts(client, bucket, reader, &upload, chunk);
+        if !matches!(result, Ok(200..=299)) {
+            let command = Command::AbortMultipartUpload {
+                upload_id: &upload.upload_id,
+            };
+            if let Err(err) = Self::send(client, bucket, &upload.key, command) {
+                error!("Could not abort upload of {}: {err}", upload.key);
+            }
+        }
+        result
+    }
+
+    /// Upload the parts of the multipart `upload`, starting from the `chunk` already read from `reader`
+    fn put_parts<R: Read>(
+        client: &Client,
+        bucket: &Bucket,
+        reader: &mut R,
+        upload: &InitiateMultipartUploadResponse,
+        mut chunk: Vec<u8>,
+    ) -> Result<u16, String> {
+        let mut parts = Vec::new();
+        while !chunk.is_empty() {
+            let part_number = parts.len() as u32 + 1;
+            let command = Command::UploadPart {
+                part_number,
+                content: &chunk,
+                upload_id: &upload.upload_id,
+            };
+            let response = Self::send(client, bucket, &upload.key, command)?;
+            let status = response.status().as_u16();
+            if !(200..300).contains(&status) {
+                return Ok(status);
+            }
+            let etag = response
+                .headers()
+                .get("ETag")
+                .and_then(|x| x.to_str().ok())
+                .unwrap_or_default()
+                .to_string();
+            parts.push(Part { part_number, etag });
+            if chunk.len() < CHUNK_SIZE {
+                break;
+            }
+            chunk = read_chunk(reader).map_err(|e| e.to_string())?;
+        }
+        let command = Command::CompleteMultipartUpload {
+            upload_id: &upload.upload_id,
+            data: CompleteMultipartUploadData { parts },
+        };
+        Self::send_status(client, bucket, &upload.key, command)
+    }
+
+    /// Get the object with `key` into `writer`
+    fn get_object<W: Write + Send>(
+        &self,
+        bucket: &Bucket,
+        key: &str,
+        writer: &mut W,
+    ) -> Result<u16, String> {
+        let Some(client) = self.tls_client.as_ref() else {
+            return bucket
+                .get_object_to_writer(key, writer)
+                .map_err(|e| e.to_string());
+        };
+        let mut response = Self::send(client, bucket, key, Command::GetObject)?;
+        let status = response.status().as_u16();
+        if (200..300).contains(&status) {
+            io::copy(&mut response, writer).map_err(|e| e.to_string())?;
+        }
+        Ok(status)
+    }
+
+    #[cfg(test)]
+    /// Endpoint the client connects to, if any
+    pub fn endpoint(&self) -> Option<&str> {
+        self.endpoint.as_deref()
+    }
+
+    /// Returns whether `err` has been caused by the TLS connection with the endpoint
+    fn is_tls_error(err: &RemoteError) -> bool {
+        let text = err.to_string().to_lowercase();
+        ["certificate", "tls", "ssl", "handshake"]
+            .iter()
+            .any(|x| text.contains(x))
+    }
+
+    /// Make the authentication error for the TLS error `err`
+    fn tls_error(&self, err: RemoteError) -> RemoteError {
+        RemoteError::new_ex(
+            RemoteErrorType::AuthenticationFailed,
+            format!(
+                "TLS connection with {} failed: {err}",
+                self.endpoint.as_deref().unwrap_or("AWS")
+            ),
+        )
+    }
+
+    /// Headers to send with the requests; `upload` tells whether they are sent with the uploads
+    fn headers(&self, upload: bool) -> Vec<(&'static str, String)> {
+        let mut headers = Vec::new();
+        if self.requester_pays {
+            headers.push((REQUEST_PAYER_HEADER, String::from("requester")));
+        }
+        if let Some(storage_class) = self.storage_class.as_deref().filter(|_| upload) {
+            headers.push((STORAGE_CLASS_HEADER, storage_class.to_string()));
+        }
+        headers
+    }
+
+    /// Make a copy of `bucket` sending the headers of the requests
+    fn make_bucket(&self, bucket: &Bucket, upload: bool) -> RemoteResult<Bucket> {
+        let mut bucket = bucket.clone();
+        for (name, value) in self.headers(upload) {
+            let value = value.parse().map_err(|_| {
+                RemoteError::new_ex(
+                    RemoteErrorType::BadAddress,
+                    format!("invalid value for {name}: '{value}'"),
+                )
+            })?;
+            bucket.extra_headers_mut().insert(name, value);
+        }
+        Ok(bucket)
+    }
+
+    /// Make the error of the request `what` denied by S3
+    fn denied_error(&self, what: &str) -> RemoteError {
+        let hint = if self.requester_pays {
+            "the credentials must be allowed to pay the requests of the bucket"
+        } else {
+            "if the bucket is a requester-pays bucket, enable \"Requester pays\" in the connection parameters"
+        };
+        RemoteError::new_ex(
+            RemoteErrorType::ProtocolError,
+            format!("{what}: access denied; {hint}"),
+        )
+    }
+
+    /// Check the `status` code of the request `what`; errors are reported with `kind`
+    fn check_status(&self, status: u16, kind: RemoteErrorType, what: &str) -> RemoteResult<()> {
+        match status {
+            200..=299 => Ok(()),
+            403 => Err(self.denied_error(what)),
+            404 => Err(RemoteError::new_ex(
+                RemoteErrorType::NoSuchFileOrDirectory,
+                format!("{what}: no such object"),
+            )),
+            status => Err(RemoteError::new_ex(
+                kind,
+                format!("{what}: S3 replied with status {status}"),
+            )),
+        }
+    }
+
+    /// Check the connection status
+    fn check_connection(&self) -> RemoteResult<&Bucket> {
+        self.bucket
+            .as_ref()
+            .ok_or_else(|| RemoteError::new(RemoteErrorType::NotConnected))
+    }
+
+    /// Query the objects with key starting with `key`
+    fn query_objects(&self, key: &str, only_direct_children: bool) -> RemoteResult<Vec<File>> {
+        let bucket = self.check_connection()?;
+        match self.list_objects(bucket, key) {
+            Ok(results) => Ok(results
+                .iter()
+                .flat_map(|x| x.contents.iter())
+                .filter(|x| !only_direct_children || Self::is_direct_child(&x.key, key))
+                .map(|x| Self::object_file(&x.key, x.size, &x.last_modified))
+                .collect()),
+            Err(err) => {
+                // a denied list can't be told from its body, so ask the bucket the status
+                if matches!(self.head_object(bucket, "/"), Ok(403)) {
+                    Err(self.denied_error("Could not list objects"))
+                } else {
+                    Err(RemoteError::new_ex(RemoteErrorType::StatFailed, err))
+                }
+            }
+        }
+    }
+
+    /// Stat the object at `p`
+    fn stat_object(&self, p: &Path) -> RemoteResult<File> {
+        let key = Self::fmt_path(p, false);
+        debug!("Query stat object {}; key: {key}", p.display());
+        let path = absolutize(Path::new("/"), p);
+        self.query_objects(&key, false)?
+            .into_iter()
+            .find(|x| x.path == path)
+            .ok_or_else(|| {
+                RemoteError::new_ex(
+                    RemoteErrorType::NoSuchFileOrDirectory,
+                    format!("{}: No such file or directory", p.display()),
+                )
+            })
+    }
+
+    /// Returns whether the object with `key` is a direct child of `parent`
+    fn is_direct_child(key: &str, parent: &str) -> bool {
+        let name = Self::object_name(key);
+        key == format!("{parent}{name}") || key == format!("{parent}{name}/")
+    }
+
+    /// Make s3 absolute path from `p`
+    fn resolve(&self, p: &Path) -> PathBuf {
+        diff_paths(absolutize(&self.wrkdir, p), PathBuf::from("/")).unwrap_or_default()
+    }
+
+    /// Format `p` as the key expected by s3
+    fn fmt_path(p: &Path, is_dir: bool) -> String {
+        if p == Path::new("/") {
+            return String::new();
+        }
+        // NOTE: don't use is_absolute: on windows it won't work
+        let mut key = p.to_string_lossy().to_string();
+        #[cfg(win)]
+        {
+            key = key.replace('\\', "/");
+        }
+        if let Some(relative) = key.strip_prefix('/') {
+            key = relative.to_string();
+        }
+        if is_dir && !key.ends_with('/') {
+            key.push('/');
+        }
+        key
+    }
+
+    /// Get the name of the object with `key`
+    fn object_name(key: &str) -> String {
+        key.trim_end_matches('/')
+            .rsplit('/')
+            .next()
+            .unwrap_or_default()
+            .to_string()
+    }
+
+    /// Make the file of the object with `key`
+    fn object_file(key: &str, size: u64, last_modified: &str) -> File {
+        let modified = DateTime::parse_from_rfc3339(last_modified)
+            .map(SystemTime::from)
+            .unwrap_or(UNIX_EPOCH);
+        File {
+            path: absolutize(Path::new("/"), Path::new(key)),
+            metadata: Metadata {
+                file_type: if key.ends_with('/') {
+                    FileType::Directory
+                } else {
+                    FileType::File
+                },
+                modified: Some(modified),
+                size,
+                ..Default::default()
+            },
+        }
+    }
+}
+
+impl RemoteFs for AwsS3SessionFs {
+    fn connect(&mut self) -> RemoteResult<Welcome> {
+        self.tls_client = self.make_tls_client()?;
+        let welcome = self.client.connect()?;
+        let bucket = self
+            .client
+            .bucket()
+            .ok_or_else(|| RemoteError::new(RemoteErrorType::NotConnected))?;
+        let buckets = self
+            .make_bucket(bucket, false)
+            .and_then(|x| Ok((x, self.make_bucket(bucket, true)?)));
+        match buckets {
+            Ok((bucket, upload_bucket)) => {
+                self.bucket = Some(bucket);
+                self.upload_bucket = Some(upload_bucket);
+            }
+            Err(err) => {
+                let _ = self.client.disconnect();
+                return Err(err);
+            }
+        }
+        self.wrkdir = PathBuf::from("/");
+        debug!("checking connection with bucket...");
+        match self.list_dir(Path::new("/")) {
+            Err(err) if Self::is_tls_error(&err) => {
+                error!("TLS error with s3 endpoint: {err}");
+                let _ = self.disconnect();
+                Err(self.tls_error(err))
+            }
+            // other errors are reported by the operations
+            _ => Ok(welcome),
+        }
+    }
+
+    fn disconnect(&mut self) -> RemoteResult<()> {
+        self.bucket = None;
+        self.upload_bucket = None;
+        self.tls_client = None;
+        self.client.disconnect()
+    }
+
+    fn is_connected(&mut self) -> bool {
+        self.bucket.is_some()
+    }
+
+    fn pwd(&mut self) -> RemoteResult<PathBuf> {
+        self.check_connection()?;
+        Ok(self.wrkdir.clone())
+    }
+
+    fn change_dir(&mut self, dir: &Path) -> RemoteResult<PathBuf> {
+        self.check_connection()?;
+        // Always allow entering root
+        if dir == Path::new("/") {
+            self.wrkdir = dir.to_path_buf();
+            return Ok(self.wrkdir.clone());
+        }
+        let dir = self.resolve(dir);
+        let key = Self::fmt_path(&dir, true);
+        debug!("Entering directory {} (key {key})...", dir.display());
+        self.stat_object(Path::new(&key))?;
+        self.wrkdir = absolutize(Path::new("/"), &dir);
+        Ok(self.wrkdir.clone())
+    }
+
+    fn list_dir(&mut self, path: &Path) -> RemoteResult<Vec<File>> {
+        let key = Self::fmt_path(&self.resolve(path), true);
+        debug!("Query list directory {}; key: {key}", path.display());
+        self.query_objects(&key, true)
+    }
+
+    fn stat(&mut self, path: &Path) -> RemoteResult<File> {
+        self.check_connection()?;
+        let path = self.resolve(path);
+        if let Ok(file) = self.stat_object(&path) {
+            return Ok(file);
+        }
+        trace!("Failed to stat object as file; trying as a directory...");
+        self.stat_object(Path::new(&Self::fmt_path(&path, true)))
+    }
+
+    fn setstat(&mut self, path: &Path, metadata: Metadata) -> RemoteResult<()> {
+        self.client.setstat(path, metadata)
+    }
+
+    fn exists(&mut self, path: &Path) -> RemoteResult<bool> {
+        match self.stat(path) {
+            Ok(_) => Ok(true),
+            Err(RemoteError {
+                kind: RemoteErrorType::NoSuchFileOrDirectory,
+                ..
+            }) => Ok(false),
+            Err(err) => Err(err),
+        }
+    }
+
+    fn remove_file(&mut self, path: &Path) -> RemoteResult<()> {
+        let key = Self::fmt_path(&self.resolve(path), true);
+        debug!("Removing object {key}...");
+        let status = self
+            .delete_object(self.check_connection()?, &key)
+            .map_err(|e| {
+                RemoteError::new_ex(
+                    RemoteErrorType::ProtocolError,
+                    format!("Could not remove file: {e}"),
+                )
+            })?;
+        self.check_status(
+            status,
+            RemoteErrorType::ProtocolError,
+            "Could not remove file",
+        )
+    }
+
+    fn remove_dir(&mut self, path: &Path) -> RemoteResult<()> {
+        if !self.exists(path)? {
+            return Err(RemoteError::new(RemoteErrorType::NoSuchFileOrDirectory));
+        }
+        let key = Self::fmt_path(&self.resolve(path), true);
+        debug!("Removing object {key}...");
+        let status = self
+            .delete_object(self.check_connection()?, &key)
+            .map_err(|e| {
+                RemoteError::new_ex(
+                    RemoteErrorType::ProtocolError,
+                    format!("Could not remove directory: {e}"),
+                )
+            })?;
+        self.check_status(
+            status,
+            RemoteErrorType::ProtocolError,
+            "Could not remove directory",
+        )
+    }
+
+    fn remove_dir_all(&mut self, path: &Path) -> RemoteResult<()> {
+        if self.remove_dir(path).is_err() {
+            self.remove_file(path)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn create_dir(&mut self, path: &Path, _mode: UnixPex) -> RemoteResult<()> {
+        let key = Self::fmt_path(&self.resolve(path), true);
+        debug!("Making directory {key}...");
+        if self.stat_object(Path::new(&key)).is_ok() {
+            error!("Directory {key} already exists");
+            return Err(RemoteError::new(RemoteErrorType::DirectoryAlreadyExists));
+        }
+        let bucket = self
+            .upload_bucket
+            .as_ref()
+            .ok_or_else(|| RemoteError::new(RemoteErrorType::NotConnected))?;
+        let status = self.put_object(bucket, &key, &[]).map_err(|e| {
+            RemoteError::new_ex(
+                RemoteErrorType::FileCreateDenied,
+                format!("Could not make directory: {e}"),
+            )
+        })?;
+        self.check_status(
+            status,
+            RemoteErrorType::FileCreateDenied,
+            "Could not make directory",
+        )
+    }
+
+    fn symlink(&mut self, path: &Path, target: &Path) -> RemoteResult<()> {
+        self.client.symlink(path, target)
+    }
+
+    fn copy(&mut self, src: &Path, dest: &Path) -> RemoteResult<()> {
+        self.client.copy(src, dest)
+    }
+
+    fn mov(&mut self, src: &Path, dest: &Path) -> RemoteResult<()> {
+        self.client.mov(src, dest)
+    }
+
+    fn exec(&mut self, cmd: &str) -> RemoteResult<(u32, String)> {
+        self.client.exec(cmd)
+    }
+
+    fn append(&mut self, path: &Path, metadata: &Metadata) -> RemoteResult<WriteStream> {
+        self.client.append(path, metadata)
+    }
+
+    fn create(&mut self, path: &Path, metadata: &Metadata) -> RemoteResult<WriteStream> {
+        self.client.create(path, metadata)
+    }
+
+    fn open(&mut self, path: &Path) -> RemoteResult<ReadStream> {
+        self.client.open(path)
+    }
+
+    fn on_written(&mut self, writable: WriteStream) -> RemoteResult<()> {
+        self.client.on_written(writable)
+    }
+
+    fn on_read(&mut self, readable: ReadStream) -> RemoteResult<()> {
+        self.client.on_read(readable)
+    }
+
+    fn append_file(
+        &mut self,
+        path: &Path,
+        metadata: &Metadata,
+        reader: Box<dyn Read + Send>,
+    ) -> RemoteResult<u64> {
+        self.client.append_file(path, metadata, reader)
+    }
+
+    fn create_file(
+        &mut self,
+        path: &Path,
+        metadata: &Metadata,
+        mut reader: Box<dyn Read + Send>,
+    ) -> RemoteResult<u64> {
+        let key = Self::fmt_path(&self.resolve(path), false);
+        debug!("Query PUT for key '{key}'");
+        let bucket = self
+            .upload_bucket
+            .as_ref()
+            .ok_or_else(|| RemoteError::new(RemoteErrorType::NotConnected))?;
+        let status = self
+            .put_object_stream(bucket, &mut reader, &key)
+            .map_err(|e| {
+                RemoteError::new_ex(
+                    RemoteErrorType::ProtocolError,
+                    format!("Could not put file: {e}"),
+                )
+            })?;
+        self.check_status(status, RemoteErrorType::ProtocolError, "Could not put file")
+            .map(|_| metadata.size)
+    }
+
+    fn open_file(&mut self, src: &Path, mut dest: Box<dyn Write + Send>) -> RemoteResult<u64> {
+        if !self.exists(src)? {
+            return Err(RemoteError::new(RemoteErrorType::NoSuchFileOrDirectory));
+        }
+        let key = Self::fmt_path(&self.resolve(src), false);
+        info!("Query GET for key '{key}'");
+        let status = self
+            .get_object(self.check_connection()?, &key, &mut dest)
+            .map_err(|e| {
+                RemoteError::new_ex(
+                    RemoteErrorType::ProtocolError,
+                    format!("Could not get file: {e}"),
+                )
+            })?;
+        self.check_status(status, RemoteErrorType::ProtocolError, "Could not get file")
+            .map(|_| 0)
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn should_tell_tls_errors() {
+        assert!(AwsS3SessionFs::is_tls_error(&RemoteError::new_ex(
+            RemoteErrorType::ProtocolError,
+            "invalid peer certificate: UnknownIssuer"
+        )));
+        assert!(AwsS3SessionFs::is_tls_error(&RemoteError::new_ex(
+            RemoteErrorType::ProtocolError,
+            "TLS handshake failed"
+        )));
+        assert!(!AwsS3SessionFs::is_tls_error(&RemoteError::new_ex(
+            RemoteErrorType::ProtocolError,
+            "The specified bucket does not exist"
+        )));
+    }
+
+    #[test]
+    fn should_make_tls_error() {
+        let client = AwsS3SessionFs::new(
+            AwsS3Fs::new("omar"),
+            Some(String::from("https://minio.lan:9000")),
+        );
+        let err = client.tls_error(RemoteError::new_ex(
+            RemoteErrorType::ProtocolError,
+            "invalid peer certificate: UnknownIssuer",
+        ));
+        assert_eq!(err.kind, RemoteErrorType::AuthenticationFailed);
+        assert!(err
+            .to_string()
+            .contains("TLS connection with https://minio.lan:9000 failed"));
+        assert!(err.to_string().contains("UnknownIssuer"));
+    }
+
+    #[test]
+    fn should_make_tls_client_only_with_tls_settings() {
+        let client = AwsS3SessionFs::new(AwsS3Fs::new("omar"), None);
+        assert!(client.make_tls_client().unwrap().is_none());
+        let client = client.accept_invalid_certs(true);
+        assert!(client.make_tls_client().unwrap().is_some());
+    }
+
+    #[test]
+    fn should_fail_making_tls_client_with_bad_ca_bundle() {
+        let client = AwsS3SessionFs::new(AwsS3Fs::new("omar"), None)
+            .ca_bundle(Some(PathBuf::from("/this/bundle/does/not/exist.pem")));
+        let err = client.make_tls_client().unwrap_err();
+        assert_eq!(err.kind, RemoteErrorType::AuthenticationFailed);
+        assert!(err.to_string().contains("Could not read CA bundle"));
+        let bundle = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(bundle.path(), "not a certificate").unwrap();
+        let client = client.ca_bundle(Some(bundle.path().to_path_buf()));
+        let err = client.make_tls_client().unwrap_err();
+        assert_eq!(err.kind, RemoteErrorType::AuthenticationFailed);
+        assert!(err.to_string().contains("Invalid CA bundle"));
+    }
+
+    #[test]
+    fn should_make_request_headers() {
+        let client = AwsS3SessionFs::new(AwsS3Fs::new("omar"), None);
+        assert!(client.headers(false).is_empty());
+        assert!(client.headers(true).is_empty());
+        let client = client
+            .requester_pays(true)
+            .storage_class(Some(String::from("STANDARD_IA")));
+        assert_eq!(
+            client.headers(false),
+            vec![("x-amz-request-payer", String::from("requester"))]
+        );
+        assert_eq!(
+            client.headers(true),
+            vec![
+                ("x-amz-request-payer", String::from("requester")),
+                ("x-amz-storage-class", String::from("STANDARD_IA"))
+            ]
+        );
+    }
+
+    #[test]
+    fn should_check_status() {
+        let client = AwsS3SessionFs::new(AwsS3Fs::new("omar"), None);
+        assert!(client
+            .check_status(200, RemoteErrorType::ProtocolError, "Could not get file")
+            .is_ok());
+        assert_eq!(
+            client
+                .check_status(404, RemoteErrorType::ProtocolError, "Could not get file")
+                .unwrap_err()
+                .kind,
+            RemoteErrorType::NoSuchFileOrDirectory
+        );
+        let err = client
+            .check_status(500, RemoteErrorType::FileCreateDenied, "Could not put file")
+            .unwrap_err();
+        assert_eq!(err.kind, RemoteErrorType::FileCreateDenied);
+        assert!(err.to_string().contains("status 500"));
+    }
+
+    #[test]
+    fn should_hint_requester_pays_when_denied() {
+        let client = AwsS3SessionFs::new(AwsS3Fs::new("omar"), None);
+        let err = client
+            .check_status(403, RemoteErrorType::ProtocolError, "Could not get file")
+            .unwrap_err();
+        assert!(err.to_string().contains("enable \"Requester pays\""));
+        let client = client.requester_pays(true);
+        let err = client
+            .check_status(403, RemoteErrorType::ProtocolError, "Could not get file")
+            .unwrap_err();
+        assert!(err.to_string().contains("allowed to pay the requests"));
+    }
+
+    #[test]
+    fn should_format_keys() {
+        assert_eq!(AwsS3SessionFs::fmt_path(Path::new("/"), true), "");
+        assert_eq!(
+            AwsS3SessionFs::fmt_path(Path::new("/omar/readme.txt"), false),
+            "omar/readme.txt"
+        );
+        assert_eq!(
+            AwsS3SessionFs::fmt_path(Path::new("omar/docs"), true),
+            "omar/docs/"
+        );
+        assert_eq!(
+            AwsS3SessionFs::fmt_path(Path::new("omar/docs/"), true),
+            "omar/docs/"
+        );
+    }
+
+    #[test]
+    fn should_tell_direct_children() {
+        assert!(AwsS3SessionFs::is_direct_child("omar/readme.txt", "omar/"));
+        assert!(AwsS3SessionFs::is_direct_child("omar/docs/", "omar/"));
+        assert!(AwsS3SessionFs::is_direct_child("readme.txt", ""));
+        assert!(!AwsS3SessionFs::is_direct_child(
+            "omar/docs/readme.txt",
+            "omar/"
+        ));
+    }
+
+    #[test]
+    fn should_make_object_files() {
+        let file = AwsS3SessionFs::object_file("omar/readme.txt", 1024, "2021-08-28T10:20:37.000Z");
+        assert_eq!(file.path, PathBuf::from("/omar/readme.txt"));
+        assert!(file.is_file());
+        assert_eq!(file.metadata.size, 1024);
+        assert_eq!(
+            file.metadata
+                .modified
+                .unwrap()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_secs(),
+            1630146037
+        );
+        let dir = AwsS3SessionFs::object_file("omar/docs/", 0, "invalid");
+        assert_eq!(dir.path, PathBuf::from("/omar/docs"));
+        assert!(dir.is_dir());
+        assert_eq!(dir.metadata.modified, Some(UNIX_EPOCH));
+        assert_eq!(dir.name(), "docs");
+    }
+}
@@ -0,0 +1,265 @@
+//! ## SmbSession
+//!
+//! Smb client browsing the shares of the server, when no share has been specified
+
+use std::io::{Read, Write};
+use std::path::{Component, Path, PathBuf};
+
+use pavao::SmbDirentType;
+use remotefs::fs::{File, FileType, Metadata, ReadStream, UnixPex, Welcome, WriteStream};
+use remotefs::{RemoteError, RemoteErrorType, RemoteFs, RemoteResult};
+use remotefs_smb::SmbFs;
+
+use crate::utils::path as path_utils;
+
+/// Smb client connected to the server without a share.
+///
+/// The root directory lists the file shares of the server, while each share is browsed as a directory of the root,
+/// e.g. `/public/docs`. Since the root isn't a share, nothing can be created, moved or removed there
+pub struct SmbSharesFs {
+    client: SmbFs,
+    wrkdir: PathBuf,
+}
+
+impl SmbSharesFs {
+    /// Wrap `client`, connected to the server with an empty share
+    pub fn new(client: SmbFs) -> Self {
+        Self {
+            client,
+            wrkdir: PathBuf::from("/"),
+        }
+    }
+
+    /// Get the absolute path of `path`
+    fn absolutize(&self, path: &Path) -> PathBuf {
+        path_utils::absolutize(self.wrkdir.as_path(), path)
+    }
+
+    /// Returns whether `path` is the root directory, listing the shares
+    fn is_share_list(path: &Path) -> bool {
+        path.components().all(|x| matches!(x, Component::RootDir))
+    }
+
+    /// Returns whether `path` is a share or the root directory, which can't be modified
+    fn is_share_level(path: &Path) -> bool {
+        path.components()
+            .filter(|x| matches!(x, Component::Normal(_)))
+            .count()
+            <= 1
+    }
+
+    /// Fail if `path` is at the share list level, where nothing can be created, moved or removed
+    fn check_writable(&self, path: &Path) -> RemoteResult<()> {
+        let path = self.absolutize(path);
+        match Self::is_share_level(path.as_path()) {
+            true => Err(RemoteError::new_ex(
+                RemoteErrorType::FileCreateDenied,
+                "files can't be modified at the share list level: enter a share first",
+            )),
+            false => Ok(()),
+        }
+    }
+
+    /// The root directory, listing the shares
+    fn share_list() -> File {
+        File {
+            path: PathBuf::from("/"),
+            metadata: Metadata::default().file_type(FileType::Directory),
+        }
+    }
+
+    /// List the file shares of the server, as directories of the root
+    fn list_shares(&mut self) -> RemoteResult<Vec<File>> {
+        let dirents = self
+            .client
+            .client()
+            .list_dir("/")
+            .map_err(|e| RemoteError::new_ex(RemoteErrorType::StatFailed, e))?;
+        Ok(dirents
+            .into_iter()
+            .filter(|x| x.get_type() == SmbDirentType::FileShare)
+            .map(|x| File {
+                path: Path::new("/").join(x.name()),
+                metadata: Metadata::default().file_type(FileType::Directory),
+            })
+            .collect())
+    }
+}
+
+impl RemoteFs for SmbSharesFs {
+    fn connect(&mut self) -> RemoteResult<Welcome> {
+        let welcome = self.client.connect()?;
+        // the shares must be listable, otherwise there's nothing to browse
+        self.list_shares()?;
+        Ok(welcome)
+    }
+
+    fn disconnect(&mut self) -> RemoteResult<()> {
+        self.client.disconnect()
+    }
+
+    fn is_connected(&mut self) -> bool {
+        self.client.is_connected()
+    }
+
+    fn pwd(&mut self) -> RemoteResult<PathBuf> {
+        Ok(self.wrkdir.clone())
+    }
+
+    fn change_dir(&mut self, dir: &Path) -> RemoteResult<PathBuf> {
+        let dir = self.absolutize(dir);
+        if !Self::is_share_list(dir.as_path()) {
+            // entering a share selects it
+            self.client.change_dir(dir.as_path())?;
+        }
+        debug!("new working directory: {}", dir.display());
+        self.wrkdir = dir;
+        Ok(self.wrkdir.clone())
+    }
+
+    fn list_dir(&mut self, path: &Path) -> RemoteResult<Vec<File>> {
+        let path = self.absolutize(path);
+        match Self::is_share_list(path.as_path()) {
+            true => self.list_shares(),
+            false => self.client.list_dir(path.as_path()),
+        }
+    }
+
+    fn stat(&mut self, path: &Path) -> RemoteResult<File> {
+        let path = self.absolutize(path);
+        match Self::is_share_list(path.as_path()) {
+            true => Ok(Self::share_list()),
+            false => self.client.stat(path.as_path()),
+        }
+    }
+
+    fn setstat(&mut self, path: &Path, metadata: Metadata) -> RemoteResult<()> {
+        self.check_writable(path)?;
+        self.client.setstat(&self.absolutize(path), metadata)
+    }
+
+    fn exists(&mut self, path: &Path) -> RemoteResult<bool> {
+        let path = self.absolutize(path);
+        match Self::is_share_list(path.as_path()) {
+            true => Ok(true),
+            false => self.client.exists(path.as_path()),
+        }
+    }
+
+    fn remove_file(&mut self, path: &Path) -> RemoteResult<()> {
+        self.check_writable(path)?;
+        self.client.remove_file(&self.absolutize(path))
+    }
+
+    fn remove_dir(&mut self, path: &Path) -> RemoteResult<()> {
+        self.check_writable(path)?;
+        self.client.remove_dir(&self.absolutize(path))
+    }
+
+    fn remove_dir_all(&mut self, path: &Path) -> RemoteResult<()> {
+        self.check_writable(path)?;
+        self.client.remove_dir_all(&self.absolutize(path))
+    }
+
+    fn create_dir(&mut self, path: &Path, mode: UnixPex) -> RemoteResult<()> {
+        self.check_writable(path)?;
+        self.client.create_dir(&self.absolutize(path), mode)
+    }
+
+    fn symlink(&mut self, path: &Path, target: &Path) -> RemoteResult<()> {
+        self.check_writable(path)?;
+        self.client.symlink(&self.absolutize(path), target)
+    }
+
+    fn copy(&mut self, src: &Path, dest: &Path) -> RemoteResult<()> {
+        self.check_writable(dest)?;
+        self.client
+            .copy(&self.absolutize(src), &self.absolutize(dest))
+    }
+
+    fn mov(&mut self, src: &Path, dest: &Path) -> RemoteResult<()> {
+        self.check_writable(src)?;
+        self.check_writable(dest)?;
+        self.client
+            .mov(&self.absolutize(src), &self.absolutize(dest))
+    }
+
+    fn exec(&mut self, cmd: &str) -> RemoteResult<(u32, String)> {
+        self.client.exec(cmd)
+    }
+
+    fn append(&mut self, path: &Path, metadata: &Metadata) -> RemoteResult<WriteStream> {
+        self.check_writable(path)?;
+        self.client.append(&self.absolutize(path), metadata)
+    }
+
+    fn create(&mut self, path: &Path, metadata: &Metadata) -> RemoteResult<WriteStream> {
+        self.check_writable(path)?;
+        self.client.create(&self.absolutize(path), metadata)
+    }
+
+    fn open(&mut self, path: &Path) -> RemoteResult<ReadStream> {
+        self.client.open(&self.absolutize(path))
+    }
+
+    fn on_written(&mut self, writable: WriteStream) -> RemoteResult<()> {
+        self.client.on_written(writable)
+    }
+
+    fn on_read(&mut self, readable: ReadStream) -> RemoteResult<()> {
+        self.client.on_read(readable)
+    }
+
+    fn append_file(
+        &mut self,
+        path: &Path,
+        metadata: &Metadata,
+        reader: Box<dyn Read + Send>,
+    ) -> RemoteResult<u64> {
+        self.check_writable(path)?;
+        self.client
+            .append_file(&self.absolutize(path), metadata, reader)
+    }
+
+    fn create_file(
+        &mut self,
+        path: &Path,
+        metadata: &Metadata,
+        reader: Box<dyn Read + Send>,
+    ) -> RemoteResult<u64> {
+        self.check_writable(path)?;
+        self.client
+            .create_file(&self.absolutize(path), metadata, reader)
+    }
+
+    fn open_file(&mut self, src: &Path, dest: Box<dyn Write + Send>) -> RemoteResult<u64> {
+        self.client.open_file(&self.absolutize(src), dest)
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn should_tell_share_list_level() {
+        assert_eq!(SmbSharesFs::is_share_list(Path::new("/")), true);
+        assert_eq!(SmbSharesFs::is_share_list(Path::new("/public")), false);
+        assert_eq!(SmbSharesFs::is_share_level(Path::new("/")), true);
+        assert_eq!(SmbSharesFs::is_share_level(Path::new("/public")), true);
+        assert_eq!(
+            SmbSharesFs::is_share_level(Path::new("/public/docs")),
+            false
+        );
+    }
+
+    #[test]
+    fn should_make_share_list_directory() {
+        let root = SmbSharesFs::share_list();
+        assert!(root.is_dir());
+        assert_eq!(root.path(), Path::new("/"));
+    }
+}
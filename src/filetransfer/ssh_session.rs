@@ -0,0 +1,571 @@
+//! ## SshSession
+//!
+//! Ssh clients which apply the ssh preferences of the connection, such as compression and the preferred ciphers
+
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use remotefs::fs::{File, Metadata, ReadStream, UnixPex, Welcome, WriteStream};
+use remotefs::{RemoteError, RemoteErrorType, RemoteFs, RemoteResult};
+use remotefs_ssh::{ScpFs, SftpFs};
+use ssh2::{MethodType, Session};
+use tempfile::NamedTempFile;
+
+use super::host_key::{self, HostKey, HostKeyStatus, KnownHosts};
+use super::host_key_guard::{HostKeyGuard, Relay};
+
+/// Amount of files of a directory which are tried to get the free space, if the directory handle doesn't support it
+const STATVFS_MAX_FILES: usize = 8;
+
+/// Methods negotiated with the ssh server
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SshNegotiation {
+    /// Cipher of the client to server stream
+    pub cipher: String,
+    /// Compression of the client to server stream
+    pub compression: String,
+    /// Whether the server refused the preferences, so the session has been negotiated with the defaults
+    pub fallback: bool,
+}
+
+/// Host key the connection has been refused for, since it's not in the known hosts file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RejectedHostKey {
+    pub key: HostKey,
+    /// Either [`HostKeyStatus::Unknown`] or [`HostKeyStatus::Changed`]
+    pub status: HostKeyStatus,
+    pub known_hosts: KnownHosts,
+}
+
+/// Jump host the authentication has failed on
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FailedJumpHost {
+    /// Name of the host in the `ProxyJump` directive
+    pub alias: String,
+    pub username: String,
+}
+
+/// Handle to the methods negotiated by the last connection of an ssh client,
+/// to the host key it has been refused for, to the jump host it couldn't authenticate on
+/// and to the free space of its working directory
+#[derive(Debug, Clone, Default)]
+pub struct SshNegotiationHandle {
+    negotiation: Arc<Mutex<Option<SshNegotiation>>>,
+    rejected_host_key: Arc<Mutex<Option<RejectedHostKey>>>,
+    failed_jump_host: Arc<Mutex<Option<FailedJumpHost>>>,
+    free_space: Arc<Mutex<Option<u64>>>,
+}
+
+impl SshNegotiationHandle {
+    /// Get the methods negotiated by the last connection, if the client is an ssh client and it has connected
+    pub fn get(&self) -> Option<SshNegotiation> {
+        self.negotiation.lock().ok().and_then(|x| x.clone())
+    }
+
+    fn set(&self, negotiation: SshNegotiation) {
+        if let Ok(mut x) = self.negotiation.lock() {
+            *x = Some(negotiation);
+        }
+    }
+
+    /// Get the host key the last connection has been refused for, if any
+    pub fn rejected_host_key(&self) -> Option<RejectedHostKey> {
+        self.rejected_host_key.lock().ok().and_then(|x| x.clone())
+    }
+
+    fn set_rejected_host_key(&self, rejected: Option<RejectedHostKey>) {
+        if let Ok(mut x) = self.rejected_host_key.lock() {
+            *x = rejected;
+        }
+    }
+
+    /// Get the jump host the last connection couldn't authenticate on, if any
+    pub fn failed_jump_host(&self) -> Option<FailedJumpHost> {
+        self.failed_jump_host.lock().ok().and_then(|x| x.clone())
+    }
+
+    pub(super) fn set_failed_jump_host(&self, failed: Option<FailedJumpHost>) {
+        if let Ok(mut x) = self.failed_jump_host.lock() {
+            *x = failed;
+        }
+    }
+
+    /// Get the space available on the file system of the working directory, as of its last listing.
+    /// It's `None` if the server doesn't report it
+    pub fn free_space(&self) -> Option<u64> {
+        self.free_space.lock().ok().and_then(|x| *x)
+    }
+
+    fn set_free_space(&self, free_space: Option<u64>) {
+        if let Ok(mut x) = self.free_space.lock() {
+            *x = free_space;
+        }
+    }
+}
+
+/// A client running on an ssh session
+pub trait SshClient: RemoteFs {
+    /// Get the ssh session, if connected
+    fn ssh_session(&mut self) -> Option<&mut Session>;
+
+    /// Get the space available on the file system of the directory `dir`, whose `files` have been listed,
+    /// if the server supports the `statvfs` extension
+    fn free_space(&mut self, _dir: &Path, _files: &[File]) -> Option<u64> {
+        None
+    }
+}
+
+impl SshClient for ScpFs {
+    fn ssh_session(&mut self) -> Option<&mut Session> {
+        self.session()
+    }
+}
+
+impl SshClient for SftpFs {
+    fn ssh_session(&mut self) -> Option<&mut Session> {
+        self.session()
+    }
+
+    fn free_space(&mut self, dir: &Path, files: &[File]) -> Option<u64> {
+        let sftp = self.sftp()?;
+        // NOTE: some servers, such as OpenSSH, support `fstatvfs` on file handles only
+        let stat = sftp
+            .opendir(dir)
+            .and_then(|mut x| x.statvfs())
+            .ok()
+            .or_else(|| {
+                files
+                    .iter()
+                    .filter(|x| x.is_file())
+                    .take(STATVFS_MAX_FILES)
+                    .find_map(|x| sftp.open(x.path()).and_then(|mut x| x.statvfs()).ok())
+            })?;
+        Some(stat.f_bavail.saturating_mul(stat.f_frsize))
+    }
+}
+
+/// Write a temporary ssh configuration which makes the host `redirect` (if any) resolve to localhost and enables
+/// compression for every host (if `compression`), followed by the ssh configuration at `base`, if any.
+/// Directives of the first matching host take precedence, so they override the base ones
+pub fn override_config(
+    base: Option<&str>,
+    redirect: Option<&str>,
+    compression: bool,
+) -> io::Result<NamedTempFile> {
+    let mut config = NamedTempFile::new()?;
+    if let Some(host) = redirect {
+        writeln!(config, "Host {host}\n    HostName 127.0.0.1\n")?;
+    }
+    if compression {
+        writeln!(config, "Host *\n    Compression yes\n")?;
+    }
+    if let Some(base) = base {
+        match std::fs::read_to_string(base) {
+            Ok(base) => config.write_all(base.as_bytes())?,
+            Err(err) => error!("could not read ssh config at {base}: {err}"),
+        }
+    }
+    config.flush()?;
+    Ok(config)
+}
+
+/// Verification of the host key of the server against the known hosts file
+#[derive(Debug, Clone)]
+pub struct HostKeyCheck {
+    /// Address and port the client connects to
+    pub address: (String, u16),
+    /// Host and port of the server, as named in the known hosts file
+    pub host: (String, u16),
+    pub known_hosts: KnownHosts,
+    /// Host key algorithms to negotiate, for the types of the known keys of the host
+    pub algorithms: Vec<String>,
+    pub timeout: Duration,
+}
+
+impl HostKeyCheck {
+    /// Verify the host key of `host`, reached at `address`, against `known_hosts`.
+    /// If the host has known keys, they're the only host key types negotiated
+    pub fn new(
+        address: (String, u16),
+        host: (String, u16),
+        known_hosts: KnownHosts,
+        timeout: Duration,
+    ) -> Self {
+        let key_types = known_hosts
+            .key_types(host.0.as_str(), host.1)
+            .unwrap_or_else(|err| {
+                error!(
+                    "could not read known hosts file {}: {err}",
+                    known_hosts.path().display()
+                );
+                Vec::new()
+            });
+        Self {
+            address,
+            host,
+            algorithms: host_key::host_key_algorithms(&key_types),
+            known_hosts,
+            timeout,
+        }
+    }
+
+    /// Verify the host key `key`; the connection is refused if it's not known
+    pub fn verify(&self, key: &[u8], negotiation: &SshNegotiationHandle) -> RemoteResult<()> {
+        let key = HostKey::new(self.host.0.as_str(), self.host.1, key.to_vec());
+        let status = self.known_hosts.check(&key).map_err(|e| {
+            RemoteError::new_ex(
+                RemoteErrorType::ConnectionError,
+                format!(
+                    "could not read known hosts file {}: {e}",
+                    self.known_hosts.path().display()
+                ),
+            )
+        })?;
+        let msg = match status {
+            HostKeyStatus::Known => {
+                debug!("host key of {} is known: {}", key.host_name(), key.fingerprint());
+                negotiation.set_rejected_host_key(None);
+                return Ok(());
+            }
+            HostKeyStatus::Unknown => format!(
+                "the host key of '{}' is unknown ({} key fingerprint is {}): accept it connecting from the user interface, or add it to {}",
+                key.host_name(),
+                key.key_type(),
+                key.fingerprint(),
+                self.known_hosts.path().display()
+            ),
+            HostKeyStatus::Changed => format!(
+                "the host key of '{}' has changed ({} key fingerprint is {})! Someone could be eavesdropping on you (man-in-the-middle attack), or the host key has just been changed. If the change is expected, remove the old key of '{}' from {} and connect again",
+                key.host_name(),
+                key.key_type(),
+                key.fingerprint(),
+                key.host_name(),
+                self.known_hosts.path().display()
+            ),
+        };
+        error!("refusing to connect: {msg}");
+        negotiation.set_rejected_host_key(Some(RejectedHostKey {
+            key,
+            status,
+            known_hosts: self.known_hosts.clone(),
+        }));
+        Err(RemoteError::new_ex(RemoteErrorType::ConnectionError, msg))
+    }
+}
+
+/// Ssh client built with the ssh preferences of the connection.
+///
+/// If the server refuses the preferences during the handshake, the client is replaced by one built with the default
+/// preferences, rather than failing the connection. The negotiated methods are reported to the negotiation handle.
+///
+/// If the host key is checked, the clients connect through the [`HostKeyGuard`], which lets the handshake complete,
+/// and so the authentication start, only once the host key of the session is verified
+pub struct SshPrefsFs<T: SshClient> {
+    client: T,
+    /// Ssh configurations the clients have been built with; they're read on connect, so they must live as long as the
+    /// clients
+    _configs: Vec<NamedTempFile>,
+    /// Client built with the default preferences
+    fallback: Option<T>,
+    negotiation: SshNegotiationHandle,
+    /// Verification of the host key; `None` if any host key is accepted
+    host_key: Option<HostKeyGuard>,
+}
+
+impl<T: SshClient> SshPrefsFs<T> {
+    /// Wrap `client`, built with the ssh preferences and the ssh configurations `configs`.
+    /// `fallback` is the client built with the defaults; it's `None` if the preferences are the defaults already
+    pub fn new(
+        client: T,
+        configs: Vec<NamedTempFile>,
+        fallback: Option<T>,
+        negotiation: SshNegotiationHandle,
+    ) -> Self {
+        Self {
+            client,
+            _configs: configs,
+            fallback,
+            negotiation,
+            host_key: None,
+        }
+    }
+
+    /// Verify the host key of the server with `guard`, which the clients must connect to
+    pub fn host_key_guard(mut self, guard: Option<HostKeyGuard>) -> Self {
+        self.host_key = guard;
+        self
+    }
+
+    /// Connect the client, through the host key guard if any.
+    /// If the guard has closed the connection, its error is returned rather than the one of the client
+    fn connect_client(&mut self) -> RemoteResult<Welcome> {
+        let relay = match self.host_key.as_ref() {
+            Some(guard) => Some(guard.relay(&self.negotiation)?),
+            None => None,
+        };
+        let result = self.client.connect();
+        match relay.as_ref().and_then(Relay::error) {
+            Some(err) => {
+                let _ = self.client.disconnect();
+                Err(err)
+            }
+            None => result,
+        }
+    }
+}
+
+impl<T: SshClient> RemoteFs for SshPrefsFs<T> {
+    fn connect(&mut self) -> RemoteResult<Welcome> {
+        let (welcome, fallback) = match (self.connect_client(), self.fallback.take()) {
+            (Err(err), Some(fallback)) if err.kind == RemoteErrorType::ProtocolError => {
+                warn!("ssh handshake failed with the preferred compression and ciphers ({err}); retrying with the defaults");
+                self.client = fallback;
+                (self.connect_client()?, true)
+            }
+            (result, fallback) => {
+                self.fallback = fallback;
+                (result?, false)
+            }
+        };
+        if let Some(session) = self.client.ssh_session() {
+            // the guard has verified the key already; the session must have been signed with it
+            let verified = match (self.host_key.as_ref(), session.host_key()) {
+                (Some(guard), Some((key, _))) => guard.check().verify(key, &self.negotiation),
+                _ => Ok(()),
+            };
+            if let Err(err) = verified {
+                let _ = self.client.disconnect();
+                return Err(err);
+            }
+            let Some(session) = self.client.ssh_session() else {
+                return Ok(welcome);
+            };
+            let negotiation = SshNegotiation {
+                cipher: session
+                    .methods(MethodType::CryptCs)
+                    .unwrap_or("none")
+                    .to_string(),
+                compression: session
+                    .methods(MethodType::CompCs)
+                    .unwrap_or("none")
+                    .to_string(),
+                fallback,
+            };
+            debug!("negotiated ssh methods: {negotiation:?}");
+            self.negotiation.set(negotiation);
+        }
+        Ok(welcome)
+    }
+
+    fn disconnect(&mut self) -> RemoteResult<()> {
+        self.client.disconnect()
+    }
+
+    fn is_connected(&mut self) -> bool {
+        self.client.is_connected()
+    }
+
+    fn pwd(&mut self) -> RemoteResult<PathBuf> {
+        self.client.pwd()
+    }
+
+    fn change_dir(&mut self, dir: &Path) -> RemoteResult<PathBuf> {
+        self.client.change_dir(dir)
+    }
+
+    fn list_dir(&mut self, path: &Path) -> RemoteResult<Vec<File>> {
+        let files = self.client.list_dir(path)?;
+        if self.client.pwd().is_ok_and(|x| x == path) {
+            let free_space = self.client.free_space(path, &files);
+            self.negotiation.set_free_space(free_space);
+        }
+        Ok(files)
+    }
+
+    fn stat(&mut self, path: &Path) -> RemoteResult<File> {
+        self.client.stat(path)
+    }
+
+    fn setstat(&mut self, path: &Path, metadata: Metadata) -> RemoteResult<()> {
+        self.client.setstat(path, metadata)
+    }
+
+    fn exists(&mut self, path: &Path) -> RemoteResult<bool> {
+        self.client.exists(path)
+    }
+
+    fn remove_file(&mut self, path: &Path) -> RemoteResult<()> {
+        self.client.remove_file(path)
+    }
+
+    fn remove_dir(&mut self, path: &Path) -> RemoteResult<()> {
+        self.client.remove_dir(path)
+    }
+
+    fn remove_dir_all(&mut self, path: &Path) -> RemoteResult<()> {
+        self.client.remove_dir_all(path)
+    }
+
+    fn create_dir(&mut self, path: &Path, mode: UnixPex) -> RemoteResult<()> {
+        self.client.create_dir(path, mode)
+    }
+
+    fn symlink(&mut self, path: &Path, target: &Path) -> RemoteResult<()> {
+        self.client.symlink(path, target)
+    }
+
+    fn copy(&mut self, src: &Path, dest: &Path) -> RemoteResult<()> {
+        self.client.copy(src, dest)
+    }
+
+    fn mov(&mut self, src: &Path, dest: &Path) -> RemoteResult<()> {
+        self.client.mov(src, dest)
+    }
+
+    fn exec(&mut self, cmd: &str) -> RemoteResult<(u32, String)> {
+        self.client.exec(cmd)
+    }
+
+    fn append(&mut self, path: &Path, metadata: &Metadata) -> RemoteResult<WriteStream> {
+        self.client.append(path, metadata)
+    }
+
+    fn create(&mut self, path: &Path, metadata: &Metadata) -> RemoteResult<WriteStream> {
+        self.client.create(path, metadata)
+    }
+
+    fn open(&mut self, path: &Path) -> RemoteResult<ReadStream> {
+        self.client.open(path)
+    }
+
+    fn on_written(&mut self, writable: WriteStream) -> RemoteResult<()> {
+        self.client.on_written(writable)
+    }
+
+    fn on_read(&mut self, readable: ReadStream) -> RemoteResult<()> {
+        self.client.on_read(readable)
+    }
+
+    fn append_file(
+        &mut self,
+        path: &Path,
+        metadata: &Metadata,
+        reader: Box<dyn Read + Send>,
+    ) -> RemoteResult<u64> {
+        self.client.append_file(path, metadata, reader)
+    }
+
+    fn create_file(
+        &mut self,
+        path: &Path,
+        metadata: &Metadata,
+        reader: Box<dyn Read + Send>,
+    ) -> RemoteResult<u64> {
+        self.client.create_file(path, metadata, reader)
+    }
+
+    fn open_file(&mut self, src: &Path, dest: Box<dyn Write + Send>) -> RemoteResult<u64> {
+        self.client.open_file(src, dest)
+    }
+
+    fn find(&mut self, search: &str) -> RemoteResult<Vec<File>> {
+        self.client.find(search)
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::utils::test_helpers;
+
+    #[test]
+    fn should_write_override_config() {
+        let base = test_helpers::create_sample_file_with_content(
+            "Host bastion\n    HostName 10.0.0.1\n    Compression no\n    User omar\n",
+        );
+        let base = base.path().to_str().unwrap();
+        let parse = |config: &NamedTempFile| {
+            ssh2_config::SshConfig::default()
+                .parse(
+                    &mut io::BufReader::new(std::fs::File::open(config.path()).unwrap()),
+                    ssh2_config::ParseRule::ALLOW_UNKNOWN_FIELDS,
+                )
+                .unwrap()
+        };
+        let params = parse(&override_config(Some(base), None, true).unwrap()).query("bastion");
+        assert_eq!(params.compression, Some(true));
+        assert_eq!(params.host_name.as_deref(), Some("10.0.0.1"));
+        // the redirected host resolves to localhost, with the other directives of the base configuration
+        let params =
+            parse(&override_config(Some(base), Some("bastion"), false).unwrap()).query("bastion");
+        assert_eq!(params.host_name.as_deref(), Some("127.0.0.1"));
+        assert_eq!(params.compression, Some(false));
+        assert_eq!(params.user.as_deref(), Some("omar"));
+        assert!(override_config(None, None, true).is_ok());
+    }
+
+    #[test]
+    fn should_get_negotiated_methods() {
+        let handle = SshNegotiationHandle::default();
+        assert!(handle.get().is_none());
+        let negotiation = SshNegotiation {
+            cipher: String::from("aes256-ctr"),
+            compression: String::from("zlib"),
+            fallback: false,
+        };
+        handle.clone().set(negotiation.clone());
+        assert_eq!(handle.get(), Some(negotiation));
+    }
+
+    #[test]
+    fn should_verify_host_key() {
+        let tmpdir = tempfile::TempDir::new().unwrap();
+        let check = HostKeyCheck {
+            address: (String::from("127.0.0.1"), 40022),
+            host: (String::from("example.com"), 2022),
+            known_hosts: KnownHosts::new(tmpdir.path().join("known_hosts").to_str().unwrap()),
+            algorithms: Vec::new(),
+            timeout: Duration::from_secs(1),
+        };
+        let handle = SshNegotiationHandle::default();
+        let key = |seed: u8| {
+            let mut key = Vec::new();
+            key.extend_from_slice(&11u32.to_be_bytes());
+            key.extend_from_slice(b"ssh-ed25519");
+            key.extend_from_slice(&32u32.to_be_bytes());
+            key.extend((0..32).map(|x| x + seed));
+            key
+        };
+        // unknown keys are refused and reported to the handle
+        assert!(check.verify(&key(0), &handle).is_err());
+        let rejected = handle.rejected_host_key().unwrap();
+        assert_eq!(rejected.status, HostKeyStatus::Unknown);
+        assert_eq!(rejected.key.host_name(), "[example.com]:2022");
+        // once accepted, the key is known
+        rejected.known_hosts.add(&rejected.key).unwrap();
+        assert!(check.verify(&key(0), &handle).is_ok());
+        assert!(handle.rejected_host_key().is_none());
+        // a different key is refused
+        let err = check.verify(&key(1), &handle).unwrap_err();
+        assert!(err.to_string().contains("has changed"));
+        assert_eq!(
+            handle.rejected_host_key().unwrap().status,
+            HostKeyStatus::Changed
+        );
+    }
+
+    #[test]
+    fn should_get_free_space() {
+        let handle = SshNegotiationHandle::default();
+        assert!(handle.free_space().is_none());
+        handle.clone().set_free_space(Some(4096));
+        assert_eq!(handle.free_space(), Some(4096));
+        handle.set_free_space(None);
+        assert!(handle.free_space().is_none());
+    }
+}
@@ -0,0 +1,433 @@
+//! ## WebDAVSession
+//!
+//! WebDAV client which locks the files it uploads and doesn't overwrite files changed on the server
+
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+use remotefs::fs::{File, Metadata, ReadStream, UnixPex, Welcome, WriteStream};
+use remotefs::{RemoteError, RemoteErrorType, RemoteFs, RemoteResult};
+use remotefs_webdav::WebDAVFs;
+use reqwest::blocking::{Client, RequestBuilder, Response};
+use reqwest::header::{HeaderMap, CONTENT_TYPE, ETAG, IF_MATCH};
+use reqwest::{Method, StatusCode};
+
+/// Timeout of the locks acquired on the uploaded files
+const LOCK_TIMEOUT: &str = "Second-600";
+/// Body of the LOCK requests: an exclusive write lock
+const LOCK_BODY: &str = r#"<?xml version="1.0" encoding="utf-8" ?>
+<D:lockinfo xmlns:D="DAV:">
+    <D:lockscope><D:exclusive/></D:lockscope>
+    <D:locktype><D:write/></D:locktype>
+    <D:owner>termscp</D:owner>
+</D:lockinfo>
+"#;
+
+/// WebDAV client which, if `lock_uploads` is set, locks the destination of the uploads on servers supporting locks,
+/// and overwrites the files previously downloaded only if their ETag hasn't changed on the server meanwhile
+pub struct WebDAVLockFs {
+    client: WebDAVFs,
+    http: Client,
+    uri: String,
+    username: String,
+    password: String,
+    lock_uploads: bool,
+    /// Whether the server supports locks, according to the DAV header of OPTIONS; `None` until connected
+    supports_locks: Option<bool>,
+    /// ETags of the files downloaded, by path
+    etags: HashMap<PathBuf, String>,
+}
+
+impl WebDAVLockFs {
+    /// Wrap `client`, which connects to `uri` as `username`
+    pub fn new(client: WebDAVFs, uri: &str, username: &str, password: &str) -> Self {
+        Self {
+            client,
+            http: Client::new(),
+            uri: uri.to_string(),
+            username: username.to_string(),
+            password: password.to_string(),
+            lock_uploads: false,
+            supports_locks: None,
+            etags: HashMap::new(),
+        }
+    }
+
+    /// Lock the destination of the uploads and check the ETag of the files overwritten
+    pub fn lock_uploads(mut self, lock_uploads: bool) -> Self {
+        self.lock_uploads = lock_uploads;
+        self
+    }
+
+    /// Returns whether the DAV header of the server reports the compliance class 2, which provides locks
+    fn dav_supports_locks(dav: &str) -> bool {
+        dav.split(',').any(|class| class.trim() == "2")
+    }
+
+    /// Make the error for the response to a request on `path` which failed with `status`
+    fn status_error(status: StatusCode, path: &Path) -> RemoteError {
+        match status {
+            StatusCode::PRECONDITION_FAILED => RemoteError::new_ex(
+                RemoteErrorType::ProtocolError,
+                format!(
+                    "file changed on server: \"{}\" has been modified by another client since it was downloaded",
+                    path.display()
+                ),
+            ),
+            StatusCode::LOCKED => RemoteError::new_ex(
+                RemoteErrorType::ProtocolError,
+                format!("\"{}\" is locked by another client", path.display()),
+            ),
+            StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => {
+                RemoteError::new_ex(RemoteErrorType::CouldNotOpenFile, status)
+            }
+            StatusCode::BAD_REQUEST | StatusCode::NOT_FOUND => {
+                RemoteError::new_ex(RemoteErrorType::NoSuchFileOrDirectory, status)
+            }
+            _ => RemoteError::new_ex(RemoteErrorType::ProtocolError, status),
+        }
+    }
+
+    /// Resolve `path` against the working directory
+    fn path(&mut self, path: &Path) -> RemoteResult<PathBuf> {
+        match path.is_absolute() {
+            true => Ok(path.to_path_buf()),
+            false => Ok(self.client.pwd()?.join(path)),
+        }
+    }
+
+    /// Get the URL of the file at the absolute `path`
+    fn url(&self, path: &Path) -> String {
+        format!(
+            "{}{}",
+            self.uri.trim_end_matches('/'),
+            path.to_string_lossy()
+        )
+    }
+
+    /// Make a request with `method` to the file at the absolute `path`
+    fn request(&self, method: Method, path: &Path) -> RequestBuilder {
+        self.http
+            .request(method, self.url(path))
+            .basic_auth(self.username.as_str(), Some(self.password.as_str()))
+    }
+
+    /// Send `request`, making an error if it fails
+    fn send(request: RequestBuilder) -> RemoteResult<Response> {
+        request
+            .send()
+            .map_err(|err| RemoteError::new_ex(RemoteErrorType::ProtocolError, err))
+    }
+
+    /// Ask the server whether it supports locks
+    fn query_locks_support(&self) -> RemoteResult<bool> {
+        let response = Self::send(self.request(Method::OPTIONS, Path::new("/")))?;
+        let dav = response
+            .headers()
+            .get("dav")
+            .and_then(|x| x.to_str().ok())
+            .unwrap_or_default();
+        debug!("WebDAV server compliance classes: {dav}");
+        Ok(Self::dav_supports_locks(dav))
+    }
+
+    /// Lock the file at `path`, returning the lock token.
+    /// Returns `None` if the server refused to lock the file, since locks are not supported for it
+    fn lock(&self, path: &Path) -> RemoteResult<Option<String>> {
+        let response = Self::send(
+            self.request(Method::from_bytes(b"LOCK").unwrap(), path)
+                .header("timeout", LOCK_TIMEOUT)
+                .header(CONTENT_TYPE, "application/xml; charset=\"utf-8\"")
+                .body(LOCK_BODY),
+        )?;
+        match response.status() {
+            status if status.is_success() => Ok(response
+                .headers()
+                .get("lock-token")
+                .and_then(|x| x.to_str().ok())
+                .map(str::to_string)),
+            StatusCode::METHOD_NOT_ALLOWED | StatusCode::NOT_IMPLEMENTED => {
+                warn!(
+                    "could not lock {}: the server doesn't support locks for it",
+                    path.display()
+                );
+                Ok(None)
+            }
+            status => Err(Self::status_error(status, path)),
+        }
+    }
+
+    /// Release the lock `token` of the file at `path`
+    fn unlock(&self, path: &Path, token: &str) {
+        let result = Self::send(
+            self.request(Method::from_bytes(b"UNLOCK").unwrap(), path)
+                .header("lock-token", token),
+        );
+        match result {
+            Ok(response) if response.status().is_success() => {
+                debug!("unlocked {}", path.display());
+            }
+            Ok(response) => error!("could not unlock {}: {}", path.display(), response.status()),
+            Err(err) => error!("could not unlock {}: {err}", path.display()),
+        }
+    }
+
+    /// Upload `content` to `path`, with the lock `token` if any, and only if the file on the server still has the ETag it
+    /// had when it was downloaded, if it was
+    fn put(&mut self, path: &Path, content: Vec<u8>, token: Option<&str>) -> RemoteResult<()> {
+        let mut headers = HeaderMap::new();
+        if let Some(token) = token.and_then(|x| format!("({x})").parse().ok()) {
+            headers.insert("if", token);
+        }
+        if let Some(etag) = self.etags.get(path).and_then(|x| x.parse().ok()) {
+            headers.insert(IF_MATCH, etag);
+        }
+        let response = Self::send(
+            self.request(Method::PUT, path)
+                .headers(headers)
+                .header(CONTENT_TYPE, "application/octet-stream")
+                .body(content),
+        )?;
+        if !response.status().is_success() {
+            return Err(Self::status_error(response.status(), path));
+        }
+        // the file on the server is now the uploaded one
+        match response.headers().get(ETAG).and_then(|x| x.to_str().ok()) {
+            Some(etag) => self.etags.insert(path.to_path_buf(), etag.to_string()),
+            None => self.etags.remove(path),
+        };
+        Ok(())
+    }
+}
+
+impl RemoteFs for WebDAVLockFs {
+    fn connect(&mut self) -> RemoteResult<Welcome> {
+        let mut welcome = self.client.connect()?;
+        if self.lock_uploads {
+            let supports_locks = match self.query_locks_support() {
+                Ok(supports_locks) => supports_locks,
+                Err(err) => {
+                    error!("could not query the WebDAV server options: {err}");
+                    false
+                }
+            };
+            self.supports_locks = Some(supports_locks);
+            welcome.banner = Some(String::from(match supports_locks {
+                true => "uploads are locked",
+                false => "the server doesn't support locks: uploads are not locked",
+            }));
+        }
+        Ok(welcome)
+    }
+
+    fn disconnect(&mut self) -> RemoteResult<()> {
+        self.etags.clear();
+        self.client.disconnect()
+    }
+
+    fn is_connected(&mut self) -> bool {
+        self.client.is_connected()
+    }
+
+    fn pwd(&mut self) -> RemoteResult<PathBuf> {
+        self.client.pwd()
+    }
+
+    fn change_dir(&mut self, dir: &Path) -> RemoteResult<PathBuf> {
+        self.client.change_dir(dir)
+    }
+
+    fn list_dir(&mut self, path: &Path) -> RemoteResult<Vec<File>> {
+        self.client.list_dir(path)
+    }
+
+    fn stat(&mut self, path: &Path) -> RemoteResult<File> {
+        self.client.stat(path)
+    }
+
+    fn setstat(&mut self, path: &Path, metadata: Metadata) -> RemoteResult<()> {
+        self.client.setstat(path, metadata)
+    }
+
+    fn exists(&mut self, path: &Path) -> RemoteResult<bool> {
+        self.client.exists(path)
+    }
+
+    fn remove_file(&mut self, path: &Path) -> RemoteResult<()> {
+        self.client.remove_file(path)
+    }
+
+    fn remove_dir(&mut self, path: &Path) -> RemoteResult<()> {
+        self.client.remove_dir(path)
+    }
+
+    fn remove_dir_all(&mut self, path: &Path) -> RemoteResult<()> {
+        self.client.remove_dir_all(path)
+    }
+
+    fn create_dir(&mut self, path: &Path, mode: UnixPex) -> RemoteResult<()> {
+        self.client.create_dir(path, mode)
+    }
+
+    fn symlink(&mut self, path: &Path, target: &Path) -> RemoteResult<()> {
+        self.client.symlink(path, target)
+    }
+
+    fn copy(&mut self, src: &Path, dest: &Path) -> RemoteResult<()> {
+        self.client.copy(src, dest)
+    }
+
+    fn mov(&mut self, src: &Path, dest: &Path) -> RemoteResult<()> {
+        self.client.mov(src, dest)
+    }
+
+    fn exec(&mut self, cmd: &str) -> RemoteResult<(u32, String)> {
+        self.client.exec(cmd)
+    }
+
+    fn append(&mut self, path: &Path, metadata: &Metadata) -> RemoteResult<WriteStream> {
+        self.client.append(path, metadata)
+    }
+
+    fn create(&mut self, path: &Path, metadata: &Metadata) -> RemoteResult<WriteStream> {
+        self.client.create(path, metadata)
+    }
+
+    fn open(&mut self, path: &Path) -> RemoteResult<ReadStream> {
+        self.client.open(path)
+    }
+
+    fn on_written(&mut self, writable: WriteStream) -> RemoteResult<()> {
+        self.client.on_written(writable)
+    }
+
+    fn on_read(&mut self, readable: ReadStream) -> RemoteResult<()> {
+        self.client.on_read(readable)
+    }
+
+    fn append_file(
+        &mut self,
+        path: &Path,
+        metadata: &Metadata,
+        reader: Box<dyn Read + Send>,
+    ) -> RemoteResult<u64> {
+        self.client.append_file(path, metadata, reader)
+    }
+
+    fn create_file(
+        &mut self,
+        path: &Path,
+        metadata: &Metadata,
+        mut reader: Box<dyn Read + Send>,
+    ) -> RemoteResult<u64> {
+        if !self.lock_uploads {
+            return self.client.create_file(path, metadata, reader);
+        }
+        let path = self.path(path)?;
+        let mut content = Vec::new();
+        reader
+            .read_to_end(&mut content)
+            .map_err(|err| RemoteError::new_ex(RemoteErrorType::IoError, err))?;
+        let size = content.len() as u64;
+        let token = match self.supports_locks {
+            Some(true) => self.lock(path.as_path())?,
+            _ => None,
+        };
+        let result = self.put(path.as_path(), content, token.as_deref());
+        if let Some(token) = token.as_deref() {
+            self.unlock(path.as_path(), token);
+        }
+        result.map(|_| size)
+    }
+
+    fn open_file(&mut self, src: &Path, mut dest: Box<dyn Write + Send>) -> RemoteResult<u64> {
+        if !self.lock_uploads {
+            return self.client.open_file(src, dest);
+        }
+        let path = self.path(src)?;
+        let mut response = Self::send(self.request(Method::GET, path.as_path()))?;
+        if !response.status().is_success() {
+            return Err(Self::status_error(response.status(), path.as_path()));
+        }
+        // keep the ETag, so that the file is overwritten only if it hasn't changed on the server
+        match response.headers().get(ETAG).and_then(|x| x.to_str().ok()) {
+            Some(etag) => self.etags.insert(path, etag.to_string()),
+            None => self.etags.remove(&path),
+        };
+        response
+            .copy_to(&mut dest)
+            .map_err(|err| RemoteError::new_ex(RemoteErrorType::IoError, err))
+    }
+
+    fn find(&mut self, search: &str) -> RemoteResult<Vec<File>> {
+        self.client.find(search)
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn client() -> WebDAVLockFs {
+        WebDAVLockFs::new(
+            WebDAVFs::new("omar", "password", "http://localhost:3080/"),
+            "http://localhost:3080/",
+            "omar",
+            "password",
+        )
+    }
+
+    #[test]
+    fn should_tell_whether_dav_supports_locks() {
+        assert_eq!(WebDAVLockFs::dav_supports_locks("1, 2"), true);
+        assert_eq!(WebDAVLockFs::dav_supports_locks("1,2,3"), true);
+        assert_eq!(
+            WebDAVLockFs::dav_supports_locks("1, 3, extended-mkcol"),
+            false
+        );
+        assert_eq!(WebDAVLockFs::dav_supports_locks("1"), false);
+        assert_eq!(WebDAVLockFs::dav_supports_locks(""), false);
+    }
+
+    #[test]
+    fn should_make_status_errors() {
+        let path = Path::new("/docs/report.odt");
+        let err = WebDAVLockFs::status_error(StatusCode::PRECONDITION_FAILED, path);
+        assert_eq!(err.kind, RemoteErrorType::ProtocolError);
+        assert!(err.to_string().contains("file changed on server"));
+        assert!(err.to_string().contains("/docs/report.odt"));
+        let err = WebDAVLockFs::status_error(StatusCode::LOCKED, path);
+        assert!(err.to_string().contains("is locked by another client"));
+        assert_eq!(
+            WebDAVLockFs::status_error(StatusCode::NOT_FOUND, path).kind,
+            RemoteErrorType::NoSuchFileOrDirectory
+        );
+        assert_eq!(
+            WebDAVLockFs::status_error(StatusCode::FORBIDDEN, path).kind,
+            RemoteErrorType::CouldNotOpenFile
+        );
+    }
+
+    #[test]
+    fn should_resolve_urls() {
+        let mut client = client();
+        assert_eq!(
+            client.url(Path::new("/docs/report.odt")).as_str(),
+            "http://localhost:3080/docs/report.odt"
+        );
+        let path = client.path(Path::new("report.odt")).unwrap();
+        assert_eq!(path.as_path(), Path::new("/report.odt"));
+    }
+
+    #[test]
+    fn should_not_lock_uploads_by_default() {
+        let client = client();
+        assert_eq!(client.lock_uploads, false);
+        assert_eq!(client.supports_locks, None);
+        assert_eq!(client.lock_uploads(true).lock_uploads, true);
+    }
+}
@@ -4,7 +4,7 @@ use std::path::{Path, PathBuf};
 use remotefs::fs::{Metadata, UnixPex};
 use remotefs::File;
 
-use super::HostResult;
+use super::{HostResult, Xattr};
 
 /// Trait to bridge a remote filesystem to the host filesystem
 ///
@@ -66,12 +66,27 @@ pub trait HostBridge {
     /// Create a symlink from src to dst
     fn symlink(&mut self, src: &Path, dst: &Path) -> HostResult<()>;
 
+    /// Get the extended attributes of the file at `path`.
+    /// Hosts which don't support extended attributes return none
+    fn xattrs(&mut self, _path: &Path) -> Vec<Xattr> {
+        Vec::new()
+    }
+
+    /// Get the space available on the file system containing `path`, in bytes.
+    /// Hosts which can't tell the free space return none
+    fn free_space(&mut self, _path: &Path) -> Option<u64> {
+        None
+    }
+
     /// Change file mode to file, according to UNIX permissions
     fn chmod(&mut self, path: &Path, pex: UnixPex) -> HostResult<()>;
 
     /// Open file for reading
     fn open_file(&mut self, file: &Path) -> HostResult<Box<dyn Read + Send>>;
 
+    /// Open file for reading, starting from `offset`
+    fn open_file_at(&mut self, file: &Path, offset: u64) -> HostResult<Box<dyn Read + Send>>;
+
     /// Open file for writing
     fn create_file(
         &mut self,
@@ -79,6 +94,13 @@ pub trait HostBridge {
         metadata: &Metadata,
     ) -> HostResult<Box<dyn Write + Send>>;
 
+    /// Open an existing file for writing at its end
+    fn append_file(
+        &mut self,
+        file: &Path,
+        metadata: &Metadata,
+    ) -> HostResult<Box<dyn Write + Send>>;
+
     /// Finalize write operation
     fn finalize_write(&mut self, writer: Box<dyn Write + Send>) -> HostResult<()>;
 }
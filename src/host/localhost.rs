@@ -1,5 +1,5 @@
 use std::fs::{self, OpenOptions};
-use std::io::{Read, Write};
+use std::io::{Read, Seek, SeekFrom, Write};
 #[cfg(posix)]
 use std::os::unix::fs::PermissionsExt as _;
 use std::path::{Path, PathBuf};
@@ -8,8 +8,9 @@ use filetime::FileTime;
 use remotefs::fs::{FileType, Metadata, UnixPex};
 use remotefs::File;
 
-use super::{HostBridge, HostResult};
+use super::{HostBridge, HostResult, Xattr};
 use crate::host::{HostError, HostErrorType};
+use crate::system::disk;
 use crate::utils::path;
 
 /// Localhost is the entity which holds the information about the current directory and host.
@@ -60,6 +61,64 @@ impl Localhost {
     fn to_path(&self, p: &Path) -> PathBuf {
         path::absolutize(self.wrkdir.as_path(), p)
     }
+
+    /// Read the extended attributes of `p`.
+    /// If they can't be read (e.g. the file system doesn't support them), a warning is logged and none is returned
+    #[cfg(posix)]
+    fn read_xattrs(p: &Path) -> Vec<Xattr> {
+        let names = match xattr::list(p) {
+            Ok(names) => names,
+            Err(err) => {
+                warn!(
+                    "Could not read extended attributes of {}: {}",
+                    p.display(),
+                    err
+                );
+                return Vec::new();
+            }
+        };
+        names
+            .filter_map(|name| match xattr::get(p, &name) {
+                Ok(value) => Some(Xattr {
+                    name: name.to_string_lossy().to_string(),
+                    value: value.unwrap_or_default(),
+                }),
+                Err(err) => {
+                    warn!(
+                        "Could not read extended attribute {} of {}: {}",
+                        name.to_string_lossy(),
+                        p.display(),
+                        err
+                    );
+                    None
+                }
+            })
+            .collect()
+    }
+
+    #[cfg(win)]
+    fn read_xattrs(_p: &Path) -> Vec<Xattr> {
+        Vec::new()
+    }
+
+    /// Copy the extended attributes of `src` to `dst`.
+    /// Attributes which can't be written are skipped with a warning
+    #[cfg(posix)]
+    fn copy_xattrs(src: &Path, dst: &Path) {
+        for attr in Self::read_xattrs(src) {
+            if let Err(err) = xattr::set(dst, &attr.name, &attr.value) {
+                warn!(
+                    "Could not preserve extended attribute {} on {}: {}",
+                    attr.name,
+                    dst.display(),
+                    err
+                );
+            }
+        }
+    }
+
+    #[cfg(win)]
+    fn copy_xattrs(_src: &Path, _dst: &Path) {}
 }
 
 impl HostBridge for Localhost {
@@ -266,6 +325,7 @@ impl HostBridge for Localhost {
                 debug!("Directory {} doesn't exist; creating it", dst.display());
                 self.mkdir(dst.as_path())?;
             }
+            Self::copy_xattrs(entry.path(), dst.as_path());
             // Scan dir
             let dir_files: Vec<File> = self.list_dir(entry.path())?;
             // Iterate files
@@ -296,6 +356,7 @@ impl HostBridge for Localhost {
                     entry.path(),
                 ));
             }
+            Self::copy_xattrs(entry.path(), dst.as_path());
             info!("File copied");
         }
         // Reload directory if dst is pwd
@@ -438,6 +499,25 @@ impl HostBridge for Localhost {
         Err(HostError::from(HostErrorType::NotImplemented))
     }
 
+    fn xattrs(&mut self, path: &Path) -> Vec<Xattr> {
+        Self::read_xattrs(self.to_path(path).as_path())
+    }
+
+    fn free_space(&mut self, path: &Path) -> Option<u64> {
+        let path = self.to_path(path);
+        match disk::available_space(path.as_path()) {
+            Ok(space) => Some(space),
+            Err(err) => {
+                error!(
+                    "Could not get the free space at {}: {}",
+                    path.display(),
+                    err
+                );
+                None
+            }
+        }
+    }
+
     #[cfg(posix)]
     fn chmod(&mut self, path: &std::path::Path, pex: UnixPex) -> HostResult<()> {
         let path: PathBuf = self.to_path(path);
@@ -512,6 +592,35 @@ impl HostBridge for Localhost {
         }
     }
 
+    fn open_file_at(
+        &mut self,
+        file: &std::path::Path,
+        offset: u64,
+    ) -> HostResult<Box<dyn Read + Send>> {
+        let file: PathBuf = self.to_path(file);
+        info!("Opening file {} for read at {}", file.display(), offset);
+        let mut reader = match OpenOptions::new().read(true).open(file.as_path()) {
+            Ok(f) => f,
+            Err(err) => {
+                error!("Could not open file for read: {}", err);
+                return Err(HostError::new(
+                    HostErrorType::FileNotAccessible,
+                    Some(err),
+                    file.as_path(),
+                ));
+            }
+        };
+        if let Err(err) = reader.seek(SeekFrom::Start(offset)) {
+            error!("Could not seek file: {}", err);
+            return Err(HostError::new(
+                HostErrorType::FileNotAccessible,
+                Some(err),
+                file.as_path(),
+            ));
+        }
+        Ok(Box::new(reader))
+    }
+
     fn create_file(
         &mut self,
         file: &Path,
@@ -544,6 +653,33 @@ impl HostBridge for Localhost {
         }
     }
 
+    fn append_file(
+        &mut self,
+        file: &Path,
+        _metadata: &Metadata,
+    ) -> HostResult<Box<dyn Write + Send>> {
+        let file: PathBuf = self.to_path(file);
+        info!("Opening file {} for append", file.display());
+        match OpenOptions::new().append(true).open(file.as_path()) {
+            Ok(f) => Ok(Box::new(f)),
+            Err(err) => {
+                error!("Failed to open file: {}", err);
+                match self.exists(file.as_path())? {
+                    true => Err(HostError::new(
+                        HostErrorType::ReadonlyFile,
+                        Some(err),
+                        file.as_path(),
+                    )),
+                    false => Err(HostError::new(
+                        HostErrorType::NoSuchFileOrDirectory,
+                        Some(err),
+                        file.as_path(),
+                    )),
+                }
+            }
+        }
+    }
+
     fn finalize_write(&mut self, _writer: Box<dyn Write + Send>) -> HostResult<()> {
         // no-op
         Ok(())
@@ -567,7 +703,9 @@ mod tests {
     use super::*;
     #[cfg(posix)]
     use crate::utils::test_helpers::make_fsentry;
-    use crate::utils::test_helpers::{create_sample_file, make_file_at};
+    use crate::utils::test_helpers::{
+        create_sample_file, create_sample_file_with_content, make_file_at,
+    };
 
     #[test]
     fn test_host_error_new() {
@@ -686,6 +824,44 @@ mod tests {
         assert!(host.create_file(file.path(), &Metadata::default()).is_ok());
     }
 
+    #[test]
+    #[cfg(posix)]
+    fn test_host_localhost_open_read_at() {
+        let mut host: Localhost = Localhost::new(PathBuf::from("/dev")).ok().unwrap();
+        let file: tempfile::NamedTempFile = create_sample_file_with_content("Lorem ipsum");
+        let mut reader = host.open_file_at(file.path(), 6).unwrap();
+        let mut content = String::new();
+        reader.read_to_string(&mut content).unwrap();
+        assert_eq!(content.as_str(), "ipsum\n");
+        assert!(host
+            .open_file_at(
+                PathBuf::from("/bin/foo-bar-test-omar-123-456-789.txt").as_path(),
+                6
+            )
+            .is_err());
+    }
+
+    #[test]
+    #[cfg(posix)]
+    fn test_host_localhost_append_file() {
+        let mut host: Localhost = Localhost::new(PathBuf::from("/dev")).ok().unwrap();
+        let file: tempfile::NamedTempFile = create_sample_file_with_content("Lorem");
+        let mut writer = host.append_file(file.path(), &Metadata::default()).unwrap();
+        writer.write_all(b"ipsum\n").unwrap();
+        assert!(host.finalize_write(writer).is_ok());
+        assert_eq!(
+            fs::read_to_string(file.path()).unwrap().as_str(),
+            "Lorem\nipsum\n"
+        );
+        // file must exist
+        assert!(host
+            .append_file(
+                PathBuf::from("/tmp/foo-bar-test-omar-123-456-789.txt").as_path(),
+                &Metadata::default()
+            )
+            .is_err());
+    }
+
     #[test]
     #[cfg(any(target_os = "macos", target_os = "linux"))]
     fn test_host_localhost_open_write_err() {
@@ -861,6 +1037,36 @@ mod tests {
             .is_err());
     }
 
+    #[cfg(posix)]
+    #[test]
+    fn should_preserve_xattrs_on_copy() {
+        let tmpdir: tempfile::TempDir = tempfile::TempDir::new().unwrap();
+        let src = tmpdir.path().join("foo.txt");
+        assert!(StdFile::create(src.as_path()).is_ok());
+        // the file system of the temp dir could not support user attributes
+        if xattr::set(src.as_path(), "user.termscp.tag", b"red").is_err() {
+            return;
+        }
+        let mut host: Localhost = Localhost::new(PathBuf::from(tmpdir.path())).ok().unwrap();
+        let file = host.stat(src.as_path()).unwrap();
+        let dst = tmpdir.path().join("bar.txt");
+        assert!(host.copy(&file, dst.as_path()).is_ok());
+        let attr = Xattr {
+            name: String::from("user.termscp.tag"),
+            value: b"red".to_vec(),
+        };
+        assert!(host.xattrs(src.as_path()).contains(&attr));
+        assert!(host.xattrs(Path::new("bar.txt")).contains(&attr));
+    }
+
+    #[cfg(posix)]
+    #[test]
+    fn should_not_read_xattrs_of_missing_file() {
+        let tmpdir: tempfile::TempDir = tempfile::TempDir::new().unwrap();
+        let mut host: Localhost = Localhost::new(PathBuf::from(tmpdir.path())).ok().unwrap();
+        assert!(host.xattrs(Path::new("missing.txt")).is_empty());
+    }
+
     #[cfg(posix)]
     #[test]
     fn test_host_copy_file_absolute() {
@@ -17,6 +17,37 @@ pub use self::remote_bridged::RemoteBridged;
 
 pub type HostResult<T> = Result<T, HostError>;
 
+/// Extended attribute of a file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Xattr {
+    pub name: String,
+    pub value: Vec<u8>,
+}
+
+impl Xattr {
+    /// Maximum length of the value when formatted
+    const FMT_VALUE_MAX_LEN: usize = 64;
+
+    /// Format the value of the attribute, which is displayed as text if it's printable,
+    /// or as its size otherwise
+    pub fn fmt_value(&self) -> String {
+        match std::str::from_utf8(&self.value) {
+            Ok(text) if !text.chars().any(char::is_control) => {
+                match text.chars().count() > Self::FMT_VALUE_MAX_LEN {
+                    true => format!(
+                        "{}…",
+                        text.chars()
+                            .take(Self::FMT_VALUE_MAX_LEN)
+                            .collect::<String>()
+                    ),
+                    false => text.to_string(),
+                }
+            }
+            _ => format!("<{} bytes>", self.value.len()),
+        }
+    }
+}
+
 /// HostErrorType provides an overview of the specific host error
 #[derive(Error, Debug)]
 #[allow(dead_code)]
@@ -98,6 +129,22 @@ mod test {
 
     use super::*;
 
+    #[test]
+    fn should_fmt_xattr_value() {
+        let xattr = |value: &[u8]| Xattr {
+            name: String::from("user.tag"),
+            value: value.to_vec(),
+        };
+        assert_eq!(xattr(b"red").fmt_value(), "red");
+        assert_eq!(xattr(b"").fmt_value(), "");
+        assert_eq!(xattr(&[0x00, 0x01, 0xff]).fmt_value(), "<3 bytes>");
+        assert_eq!(xattr(b"line\n").fmt_value(), "<5 bytes>");
+        assert_eq!(
+            xattr("a".repeat(80).as_bytes()).fmt_value(),
+            format!("{}…", "a".repeat(64))
+        );
+    }
+
     #[test]
     fn test_host_fmt_error() {
         let err: HostError = HostError::new(
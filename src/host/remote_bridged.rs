@@ -1,13 +1,13 @@
 mod temp_mapped_file;
 
-use std::io::{Read, Write};
+use std::io::{Read, Seek, SeekFrom, Write};
 use std::path::{Path, PathBuf};
 
 use remotefs::fs::{Metadata, UnixPex};
 use remotefs::{File, RemoteError, RemoteErrorType, RemoteFs};
 
 use self::temp_mapped_file::TempMappedFile;
-use super::{HostBridge, HostError, HostResult};
+use super::{HostBridge, HostError, HostErrorType, HostResult};
 
 struct WriteStreamOp {
     path: PathBuf,
@@ -168,6 +168,24 @@ impl HostBridge for RemoteBridged {
         }
     }
 
+    fn open_file_at(&mut self, file: &Path, offset: u64) -> HostResult<Box<dyn Read + Send>> {
+        // the file can be read from an offset only if the stream is seekable
+        let mut stream = self.remote.open(file).map_err(HostError::from)?;
+        if !stream.seekable() {
+            let _ = self.remote.on_read(stream);
+            return Err(HostError::from(HostErrorType::NotImplemented));
+        }
+        if let Err(err) = stream.seek(SeekFrom::Start(offset)) {
+            let _ = self.remote.on_read(stream);
+            return Err(HostError::new(
+                HostErrorType::FileNotAccessible,
+                Some(err),
+                file,
+            ));
+        }
+        Ok(Box::new(stream))
+    }
+
     fn create_file(
         &mut self,
         file: &Path,
@@ -193,6 +211,18 @@ impl HostBridge for RemoteBridged {
         }
     }
 
+    fn append_file(
+        &mut self,
+        file: &Path,
+        metadata: &Metadata,
+    ) -> HostResult<Box<dyn Write + Send>> {
+        // appending requires streams, since the file can't be mapped on a temporary file
+        match self.remote.append(file, metadata) {
+            Ok(stream) => Ok(Box::new(stream)),
+            Err(e) => Err(HostError::from(e)),
+        }
+    }
+
     fn finalize_write(&mut self, _writer: Box<dyn Write + Send>) -> HostResult<()> {
         if let Some(WriteStreamOp {
             path,
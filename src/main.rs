@@ -26,8 +26,14 @@ use std::path::Path;
 use std::time::Duration;
 
 use self::activity_manager::{ActivityManager, NextActivity};
-use self::cli::{Args, ArgsSubcommands, RemoteArgs, RunOpts, Task};
+use self::cli::{
+    Args, ArgsSubcommands, BookmarksSubcommands, ConfigSubcommands, RemoteArgs, RunOpts, Task,
+    TransferTask,
+};
+use self::system::environment;
 use self::system::logging::{self, LogLevel};
+use self::system::theme_provider::Palette;
+use self::ui::activities::setup::ViewLayout;
 
 const APP_NAME: &str = env!("CARGO_PKG_NAME");
 const APP_BUILD_DATE: &str = env!("VERGEN_BUILD_TIMESTAMP");
@@ -73,10 +79,24 @@ fn main() {
 /// In case of success returns `RunOpts`
 /// in case something is wrong returns the error message
 fn parse_args(args: Args) -> Result<RunOpts, String> {
-    let run_opts = match args.nested {
+    let mut run_opts = match args.nested {
         Some(ArgsSubcommands::Update(_)) => RunOpts::update(),
+        Some(ArgsSubcommands::Bookmarks(args)) => match args.nested {
+            BookmarksSubcommands::Export(args) => {
+                RunOpts::export_bookmarks(args.file, args.include_passwords)
+            }
+            BookmarksSubcommands::Import(args) => {
+                RunOpts::import_bookmarks(args.file, args.overwrite)
+            }
+        },
         Some(ArgsSubcommands::LoadTheme(args)) => RunOpts::import_theme(args.theme),
-        Some(ArgsSubcommands::Config(_)) => RunOpts::config(),
+        Some(ArgsSubcommands::Transfer(args)) => RunOpts::transfer(args)?,
+        Some(ArgsSubcommands::Config(args)) if args.check => RunOpts::check_config(),
+        Some(ArgsSubcommands::Config(args)) => RunOpts::config(match args.nested {
+            Some(ConfigSubcommands::SshKeys(_)) => ViewLayout::SshKeys,
+            Some(ConfigSubcommands::Theme(_)) => ViewLayout::Theme,
+            None => ViewLayout::SetupForm,
+        }),
         None => {
             let mut run_opts: RunOpts = RunOpts::default();
             // Version
@@ -120,6 +140,10 @@ fn parse_args(args: Args) -> Result<RunOpts, String> {
             run_opts
         }
     };
+    // Palette
+    if args.no_color || environment::is_monochrome_terminal() {
+        run_opts.palette = Palette::Monochrome;
+    }
 
     Ok(run_opts)
 }
@@ -127,9 +151,69 @@ fn parse_args(args: Args) -> Result<RunOpts, String> {
 /// Run task and return rc
 fn run(run_opts: RunOpts) -> i32 {
     match run_opts.task {
+        Task::CheckConfig => run_check_config(),
+        Task::ExportBookmarks {
+            file,
+            include_passwords,
+        } => run_export_bookmarks(&file, include_passwords),
+        Task::ImportBookmarks { file, overwrite } => run_import_bookmarks(&file, overwrite),
         Task::ImportTheme(theme) => run_import_theme(&theme),
         Task::InstallUpdate => run_install_update(),
-        Task::Activity(activity) => run_activity(activity, run_opts.ticks, run_opts.remote),
+        Task::Transfer(task) => run_transfer(*task),
+        Task::Activity(activity) => {
+            run_activity(activity, run_opts.ticks, run_opts.palette, run_opts.remote)
+        }
+    }
+}
+
+fn run_check_config() -> i32 {
+    match support::check_config() {
+        Ok(report) => {
+            println!("{report}");
+            match report.has_errors() {
+                true => EXIT_CODE_ERROR,
+                false => EXIT_CODE_SUCCESS,
+            }
+        }
+        Err(err) => {
+            eprintln!("{err}");
+            EXIT_CODE_ERROR
+        }
+    }
+}
+
+fn run_export_bookmarks(file: &Path, include_passwords: bool) -> i32 {
+    match support::export_bookmarks(file, include_passwords) {
+        Ok(exported) => {
+            println!("{exported} bookmarks exported to {}", file.display());
+            if include_passwords {
+                println!("WARNING: the file contains passwords in plain text; keep it safe");
+            }
+            EXIT_CODE_SUCCESS
+        }
+        Err(err) => {
+            eprintln!("{err}");
+            EXIT_CODE_ERROR
+        }
+    }
+}
+
+fn run_import_bookmarks(file: &Path, overwrite: bool) -> i32 {
+    match support::import_bookmarks(file, overwrite) {
+        Ok((imported, skipped)) => {
+            println!("{} bookmarks imported", imported.len());
+            if !skipped.is_empty() {
+                println!(
+                    "Skipped existing bookmarks (use --overwrite to replace them): {}",
+                    skipped.join(", ")
+                );
+            }
+            EXIT_CODE_SUCCESS
+        }
+        Err(err) => {
+            eprintln!("{err}");
+            EXIT_CODE_ERROR
+        }
     }
 }
 
@@ -159,9 +243,41 @@ fn run_install_update() -> i32 {
     }
 }
 
-fn run_activity(activity: NextActivity, ticks: Duration, remote_args: RemoteArgs) -> i32 {
+fn run_transfer(task: TransferTask) -> i32 {
+    let quiet = task.quiet;
+    match ActivityManager::run_transfer(task) {
+        Ok(report) => {
+            for error in report.errors.iter() {
+                eprintln!("{error}");
+            }
+            if !quiet {
+                println!(
+                    "{} files transferred, {} unchanged, {} errors",
+                    report.transferred,
+                    report.unchanged,
+                    report.errors.len()
+                );
+            }
+            match report.errors.is_empty() {
+                true => EXIT_CODE_SUCCESS,
+                false => EXIT_CODE_ERROR,
+            }
+        }
+        Err(err) => {
+            eprintln!("Transfer failed: {err}");
+            EXIT_CODE_ERROR
+        }
+    }
+}
+
+fn run_activity(
+    activity: NextActivity,
+    ticks: Duration,
+    palette: Palette,
+    remote_args: RemoteArgs,
+) -> i32 {
     // Create activity manager (and context too)
-    let mut manager: ActivityManager = match ActivityManager::new(ticks) {
+    let mut manager: ActivityManager = match ActivityManager::new(ticks, palette) {
         Ok(m) => m,
         Err(err) => {
             eprintln!("Could not start activity manager: {err}");
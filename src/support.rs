@@ -6,7 +6,12 @@
 use std::fs;
 use std::path::{Path, PathBuf};
 
+use crate::config::bookmarks::UserHosts;
+use crate::config::params::DEFAULT_RECENTS_SIZE;
+use crate::config::serialization::{deserialize, serialize};
+use crate::config::validation::{self, ConfigReport};
 use crate::system::auto_update::{Update, UpdateStatus};
+use crate::system::bookmarks_client::BookmarksClient;
 use crate::system::config_client::ConfigClient;
 use crate::system::environment;
 use crate::system::notifications::Notification;
@@ -31,6 +36,40 @@ pub fn import_theme(p: &Path) -> Result<(), String> {
         .map_err(|e| format!("Could not import theme: {e}"))
 }
 
+/// Export bookmarks to the file at `p`, as plain TOML. Secrets are exported only if `include_passwords` is `true`.
+/// Returns the amount of exported bookmarks
+pub fn export_bookmarks(p: &Path, include_passwords: bool) -> Result<usize, String> {
+    let hosts = get_bookmarks_client()?.export_bookmarks(include_passwords);
+    let writer = fs::File::create(p).map_err(|e| format!("Could not export bookmarks: {e}"))?;
+    serialize(&hosts, Box::new(writer)).map_err(|e| format!("Could not export bookmarks: {e}"))?;
+    Ok(hosts.bookmarks.len())
+}
+
+/// Import bookmarks from the file at `p`, exported with [`export_bookmarks`].
+/// Returns the names of the imported bookmarks and of the existing ones which have been skipped
+pub fn import_bookmarks(p: &Path, overwrite: bool) -> Result<(Vec<String>, Vec<String>), String> {
+    let reader = fs::File::open(p).map_err(|e| format!("Could not import bookmarks: {e}"))?;
+    let hosts: UserHosts = deserialize(Box::new(reader))
+        .map_err(|e| format!("Could not import bookmarks: invalid bookmarks file: {e}"))?;
+    let mut client = get_bookmarks_client()?;
+    let result = client.import_bookmarks(hosts, overwrite);
+    client
+        .write_bookmarks()
+        .map_err(|e| format!("Could not write bookmarks: {e}"))?;
+    Ok(result)
+}
+
+/// Check configuration, theme and bookmarks files
+pub fn check_config() -> Result<ConfigReport, String> {
+    let cfg_dir: PathBuf = get_config_dir()?;
+    let (cfg_path, _) = environment::get_config_paths(cfg_dir.as_path());
+    Ok(validation::check_files(
+        cfg_path.as_path(),
+        environment::get_theme_path(cfg_dir.as_path()).as_path(),
+        environment::get_bookmarks_paths(cfg_dir.as_path()).as_path(),
+    ))
+}
+
 /// Install latest version of termscp if an update is available
 pub fn install_update() -> Result<String, String> {
     match Update::default()
@@ -73,6 +112,17 @@ fn get_config_dir() -> Result<PathBuf, String> {
     }
 }
 
+/// Get bookmarks client
+fn get_bookmarks_client() -> Result<BookmarksClient, String> {
+    let cfg_dir: PathBuf = get_config_dir()?;
+    let bookmarks_file: PathBuf = environment::get_bookmarks_paths(cfg_dir.as_path());
+    let recents_size = get_config_client()
+        .map(|x| x.get_recents_size())
+        .unwrap_or(DEFAULT_RECENTS_SIZE as usize);
+    BookmarksClient::new(bookmarks_file.as_path(), cfg_dir.as_path(), recents_size)
+        .map_err(|e| format!("Could not initialize bookmarks: {e}"))
+}
+
 /// Get configuration client
 fn get_config_client() -> Option<ConfigClient> {
     match get_config_dir() {
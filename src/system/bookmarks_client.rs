@@ -4,9 +4,12 @@
 
 // Crate
 // Ext
-use std::fs::OpenOptions;
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::Cursor;
 use std::path::{Path, PathBuf};
 use std::string::ToString;
+use std::thread::{self, JoinHandle};
 use std::time::SystemTime;
 
 use super::keys::filestorage::FileStorage;
@@ -15,7 +18,8 @@ use super::keys::keyringstorage::KeyringStorage;
 use super::keys::{KeyStorage, KeyStorageError};
 // Local
 use crate::config::{
-    bookmarks::{Bookmark, UserHosts},
+    bookmarks::{Bookmark, BookmarksState, ConnectionFailure, DirectoryBookmark, UserHosts},
+    migration,
     serialization::{deserialize, serialize, SerializerError, SerializerErrorKind},
 };
 use crate::filetransfer::FileTransferParams;
@@ -29,6 +33,13 @@ pub struct BookmarksClient {
     bookmarks_file: PathBuf,
     key: String,
     recents_size: usize,
+    /// Bookmarks state, stored in a sidecar file next to the bookmarks file
+    state: BookmarksState,
+    state_file: PathBuf,
+    /// Last thread spawned to write the state file
+    state_writer: Option<JoinHandle<()>>,
+    /// Changes applied migrating the bookmarks file from a legacy format
+    migration_report: Vec<String>,
 }
 
 impl BookmarksClient {
@@ -105,11 +116,16 @@ impl BookmarksClient {
                 }
             },
         };
+        let state_file = bookmarks_file.with_extension("state.toml");
         let mut client: BookmarksClient = BookmarksClient {
             hosts: default_hosts,
             bookmarks_file: PathBuf::from(bookmarks_file),
             key,
             recents_size,
+            state: Self::read_state(state_file.as_path()),
+            state_file,
+            state_writer: None,
+            migration_report: Vec::new(),
         };
         // If bookmark file doesn't exist, initialize it
         if !bookmarks_file.exists() {
@@ -139,50 +155,57 @@ impl BookmarksClient {
     pub fn get_bookmark(&self, key: &str) -> Option<FileTransferParams> {
         debug!("Getting bookmark {}", key);
         let mut entry: Bookmark = self.hosts.bookmarks.get(key).cloned()?;
-        // Decrypt password first
-        if let Some(pwd) = entry.password.as_mut() {
-            match self.decrypt_str(pwd.as_str()) {
-                Ok(decrypted_pwd) => {
-                    *pwd = decrypted_pwd;
-                }
-                Err(err) => {
-                    error!("Failed to decrypt `password` for bookmark {}: {}", key, err);
+        self.decrypt_bookmark(key, &mut entry);
+        // Then convert into
+        Some(FileTransferParams::from(entry))
+    }
+
+    /// Export bookmarks with plain secrets, which are removed if `include_secrets` is `false`.
+    /// Recents are not exported
+    pub fn export_bookmarks(&self, include_secrets: bool) -> UserHosts {
+        let bookmarks = self
+            .hosts
+            .bookmarks
+            .iter()
+            .map(|(name, bookmark)| {
+                let mut bookmark = bookmark.clone();
+                match include_secrets {
+                    true => self.decrypt_bookmark(name, &mut bookmark),
+                    false => Self::strip_secrets(&mut bookmark),
                 }
-            }
+                (name.clone(), bookmark)
+            })
+            .collect();
+        UserHosts {
+            bookmarks,
+            recents: HashMap::new(),
         }
-        // Decrypt AWS-S3 params
-        if let Some(s3) = entry.s3.as_mut() {
-            // Access key
-            if let Some(access_key) = s3.access_key.as_mut() {
-                match self.decrypt_str(access_key.as_str()) {
-                    Ok(plain) => {
-                        *access_key = plain;
-                    }
-                    Err(err) => {
-                        error!(
-                            "Failed to decrypt `access_key` for bookmark {}: {}",
-                            key, err
-                        );
-                    }
-                }
-            }
-            // Secret access key
-            if let Some(secret_access_key) = s3.secret_access_key.as_mut() {
-                match self.decrypt_str(secret_access_key.as_str()) {
-                    Ok(plain) => {
-                        *secret_access_key = plain;
-                    }
-                    Err(err) => {
-                        error!(
-                            "Failed to decrypt `secret_access_key` for bookmark {}: {}",
-                            key, err
-                        );
-                    }
-                }
+    }
+
+    /// Import the bookmarks of `hosts`, previously exported by [`Self::export_bookmarks`], encrypting their secrets.
+    /// Bookmarks with the name of an existing one replace it only if `overwrite` is `true`.
+    /// Returns the names of the imported and of the skipped bookmarks, sorted
+    pub fn import_bookmarks(
+        &mut self,
+        hosts: UserHosts,
+        overwrite: bool,
+    ) -> (Vec<String>, Vec<String>) {
+        let mut imported = Vec::new();
+        let mut skipped = Vec::new();
+        for (name, mut bookmark) in hosts.bookmarks {
+            if !overwrite && self.hosts.bookmarks.contains_key(&name) {
+                debug!("Bookmark {} already exists; skipping it", name);
+                skipped.push(name);
+                continue;
             }
+            self.encrypt_bookmark(&mut bookmark);
+            info!("Imported bookmark {}", name);
+            self.hosts.bookmarks.insert(name.clone(), bookmark);
+            imported.push(name);
         }
-        // Then convert into
-        Some(FileTransferParams::from(entry))
+        imported.sort();
+        skipped.sort();
+        (imported, skipped)
     }
 
     /// Add a new recent to bookmarks
@@ -202,20 +225,147 @@ impl BookmarksClient {
         let mut host: Bookmark = self.make_bookmark(params);
         // If not save_password, set secrets to `None`
         if !save_password {
-            host.password = None;
-            if let Some(s3) = host.s3.as_mut() {
-                s3.access_key = None;
-                s3.secret_access_key = None;
-            }
+            Self::strip_secrets(&mut host);
         }
         self.hosts.bookmarks.insert(name, host);
     }
 
+    /// Find a bookmark equivalent to `bookmark`, which is a bookmark connecting to the same host with the same user.
+    /// Passwords, secrets and paths are not taken into account.
+    /// If many equivalent bookmarks exist, the first one in alphabetical order is returned
+    pub fn find_equivalent(&self, bookmark: &Bookmark) -> Option<&str> {
+        self.hosts
+            .bookmarks
+            .iter()
+            .filter(|(_, other)| Self::is_equivalent(bookmark, other))
+            .map(|(name, _)| name.as_str())
+            .min()
+    }
+
+    /// Get the usernames used in bookmarks and recents to connect to `address`, without duplicates.
+    /// Bookmarks come first, both sorted by key
+    pub fn usernames(&self, address: &str) -> Vec<String> {
+        let mut usernames: Vec<String> = Vec::new();
+        for bookmarks in [&self.hosts.bookmarks, &self.hosts.recents] {
+            let mut entries: Vec<(&String, &Bookmark)> = bookmarks.iter().collect();
+            entries.sort_by(|a, b| a.0.cmp(b.0));
+            for (_, bookmark) in entries {
+                if bookmark.address.as_deref() != Some(address) {
+                    continue;
+                }
+                if let Some(username) = bookmark.username.as_ref().filter(|x| !x.is_empty()) {
+                    if !usernames.contains(username) {
+                        usernames.push(username.clone());
+                    }
+                }
+            }
+        }
+        usernames
+    }
+
+    /// Get the group the bookmark is listed under
+    pub fn get_bookmark_group(&self, name: &str) -> Option<&str> {
+        self.hosts
+            .bookmarks
+            .get(name)
+            .and_then(|x| x.group.as_deref())
+    }
+
+    /// Set the group the bookmark is listed under; empty groups are treated as `None`
+    pub fn set_bookmark_group(&mut self, name: &str, group: Option<String>) {
+        if let Some(bookmark) = self.hosts.bookmarks.get_mut(name) {
+            bookmark.group = group
+                .map(|x| x.trim().to_string())
+                .filter(|x| !x.is_empty());
+            debug!("Set group of bookmark {} to {:?}", name, bookmark.group);
+        }
+    }
+
     /// Delete entry from bookmarks
     pub fn del_bookmark(&mut self, name: &str) {
         let _ = self.hosts.bookmarks.remove(name);
         info!("Removed bookmark {}", name);
+        let failure = self.state.failures.remove(name);
+        let favorites = self.state.favorites.remove(name);
+        if failure.is_some() || favorites.is_some() {
+            self.write_state();
+        }
+    }
+
+    /// Get the last failed connection for bookmark
+    pub fn get_connection_failure(&self, name: &str) -> Option<&ConnectionFailure> {
+        self.state.failures.get(name)
     }
+
+    /// Record a failed connection for bookmark.
+    /// The state file is written in background
+    pub fn set_connection_failure(&mut self, name: &str, error: &str) {
+        debug!("Recording connection failure for bookmark {}", name);
+        self.state
+            .failures
+            .insert(name.to_string(), ConnectionFailure::new(error));
+        self.write_state();
+    }
+
+    /// Clear the last failed connection for bookmark.
+    /// The state file is written in background, only if a failure was recorded
+    pub fn clear_connection_failure(&mut self, name: &str) {
+        if self.state.failures.remove(name).is_some() {
+            debug!("Cleared connection failure for bookmark {}", name);
+            self.write_state();
+        }
+    }
+
+    /// Get the favorite remote files of bookmark
+    pub fn get_favorites(&self, name: &str) -> &[PathBuf] {
+        self.state
+            .favorites
+            .get(name)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Set the favorite remote files of bookmark.
+    /// The state file is written in background
+    pub fn set_favorites(&mut self, name: &str, favorites: Vec<PathBuf>) {
+        debug!(
+            "Setting {} favorites for bookmark {}",
+            favorites.len(),
+            name
+        );
+        if favorites.is_empty() {
+            self.state.favorites.remove(name);
+        } else {
+            self.state.favorites.insert(name.to_string(), favorites);
+        }
+        self.write_state();
+    }
+
+    /// Get the directories bookmarked on `host`
+    pub fn get_directory_bookmarks(&self, host: &str) -> &[DirectoryBookmark] {
+        self.state
+            .directories
+            .get(host)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Set the directories bookmarked on `host`.
+    /// The state file is written in background
+    pub fn set_directory_bookmarks(&mut self, host: &str, directories: Vec<DirectoryBookmark>) {
+        debug!(
+            "Setting {} directory bookmarks for host {}",
+            directories.len(),
+            host
+        );
+        if directories.is_empty() {
+            self.state.directories.remove(host);
+        } else {
+            self.state.directories.insert(host.to_string(), directories);
+        }
+        self.write_state();
+    }
+
     /// Iterate over recents keys
     pub fn iter_recents(&self) -> impl Iterator<Item = &String> + '_ {
         Box::new(self.hosts.recents.keys())
@@ -239,38 +389,56 @@ impl BookmarksClient {
             s3.access_key = None;
             s3.secret_access_key = None;
         }
-        // Check if duplicated
-        for (key, value) in &self.hosts.recents {
-            if *value == host {
-                debug!("Discarding recent since duplicated ({})", key);
-                // Don't save duplicates
-                return;
-            }
-        }
-        // If hosts size is bigger than self.recents_size; pop last
-        if self.hosts.recents.len() >= self.recents_size {
-            // Get keys
-            let mut keys: Vec<String> = Vec::with_capacity(self.hosts.recents.len());
-            for key in self.hosts.recents.keys() {
-                keys.push(key.clone());
-            }
-            // Sort keys; NOTE: most recent is the last element
-            keys.sort();
-            // Delete keys starting from the last one
-            for key in keys.iter() {
-                let _ = self.hosts.recents.remove(key);
-                debug!("Removed recent bookmark {}", key);
-                // If length is < self.recents_size; break
-                if self.hosts.recents.len() < self.recents_size {
-                    break;
-                }
+        // Replace the recents connecting to the same host, so that its timestamp is refreshed
+        self.hosts.recents.retain(|key, value| {
+            let duplicated = Self::is_equivalent(value, &host);
+            if duplicated {
+                debug!("Replacing recent {} since duplicated", key);
             }
-        }
+            !duplicated
+        });
+        // Make room for the new recent
+        self.evict_recents(self.recents_size.saturating_sub(1));
         let name: String = fmt_time(SystemTime::now(), "ISO%Y%m%dT%H%M%S");
         info!("Saved recent host {}", name);
         self.hosts.recents.insert(name, host);
     }
 
+    /// Promote the recent associated to `key` to a bookmark called `name`; the recent is removed.
+    /// Returns whether the recent existed
+    pub fn pin_recent<S: AsRef<str>>(&mut self, key: &str, name: S) -> bool {
+        let name = name.as_ref();
+        if name.is_empty() {
+            error!("Fatal error; bookmark name is empty");
+            panic!("Bookmark name can't be empty");
+        }
+        let Some(host) = self.hosts.recents.remove(key) else {
+            return false;
+        };
+        // NOTE: recents have no secrets, so there's nothing to encrypt
+        info!("Pinned recent host {} as bookmark {}", key, name);
+        self.hosts.bookmarks.insert(name.to_string(), host);
+        true
+    }
+
+    /// Suggest a name for the bookmark the recent associated to `key` would be pinned as, which is `user@host`.
+    /// If a bookmark with that name already exists, a numeric suffix is appended
+    pub fn suggest_pinned_name(&self, key: &str) -> Option<String> {
+        let params = self.get_recent(key)?;
+        let host = params.params.host_name();
+        let base = match params.params.username().filter(|x| !x.is_empty()) {
+            Some(username) => format!("{username}@{host}"),
+            None => host,
+        };
+        let mut name = base.clone();
+        let mut suffix = 2;
+        while self.hosts.bookmarks.contains_key(&name) {
+            name = format!("{base} ({suffix})");
+            suffix += 1;
+        }
+        Some(name)
+    }
+
     /// Delete entry from recents
     pub fn del_recent(&mut self, name: &str) {
         let _ = self.hosts.recents.remove(name);
@@ -298,32 +466,156 @@ impl BookmarksClient {
         }
     }
 
-    /// Read bookmarks from file
+    /// Take the changes applied migrating the bookmarks file from a legacy format, if it's been migrated
+    pub fn take_migration_report(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.migration_report)
+    }
+
+    /// Read bookmarks from file.
+    /// If the file has a legacy format, it's migrated to the current one, after backing up the original
     fn read_bookmarks(&mut self) -> Result<(), SerializerError> {
         // Open bookmarks file for read
         debug!("Reading bookmarks");
-        match OpenOptions::new()
-            .read(true)
-            .open(self.bookmarks_file.as_path())
-        {
-            Ok(reader) => {
-                // Deserialize
-                match deserialize(Box::new(reader)) {
-                    Ok(hosts) => {
-                        self.hosts = hosts;
-                        Ok(())
-                    }
-                    Err(err) => Err(err),
-                }
-            }
+        let data = match fs::read_to_string(self.bookmarks_file.as_path()) {
+            Ok(data) => data,
             Err(err) => {
                 error!("Failed to read bookmarks: {}", err);
-                Err(SerializerError::new_ex(
+                return Err(SerializerError::new_ex(
                     SerializerErrorKind::Io,
                     err.to_string(),
-                ))
+                ));
             }
+        };
+        // Deserialize
+        let err = match deserialize(Box::new(Cursor::new(data.clone()))) {
+            Ok(hosts) => {
+                self.hosts = hosts;
+                // Files written by older versions may have duplicated recents or more than allowed
+                self.dedup_recents();
+                self.evict_recents(self.recents_size);
+                return Ok(());
+            }
+            Err(err) => err,
+        };
+        let migrated = match migration::migrate_bookmarks(&data) {
+            Ok(migrated) => migrated,
+            Err(migration_err) => {
+                debug!("Bookmarks can't be migrated: {}", migration_err);
+                return Err(err);
+            }
+        };
+        info!("Migrating bookmarks from a legacy format: {}", err);
+        let backup = migration::backup(self.bookmarks_file.as_path()).map_err(|e| {
+            error!("Failed to back up bookmarks: {}", e);
+            SerializerError::new_ex(SerializerErrorKind::Io, e.to_string())
+        })?;
+        self.hosts = migrated.value;
+        self.write_bookmarks()?;
+        self.migration_report = std::iter::once(format!(
+            "backed up the original bookmarks to {}",
+            backup.display()
+        ))
+        .chain(migrated.changes)
+        .collect();
+        for change in self.migration_report.iter() {
+            info!("Bookmarks migration: {}", change);
         }
+        Ok(())
+    }
+
+    /// Read bookmarks state from file.
+    /// The state is not essential, so if it can't be read, the default state is returned
+    fn read_state(state_file: &Path) -> BookmarksState {
+        if !state_file.exists() {
+            return BookmarksState::default();
+        }
+        debug!("Reading bookmarks state");
+        match OpenOptions::new().read(true).open(state_file) {
+            Ok(reader) => deserialize(Box::new(reader)).unwrap_or_else(|err| {
+                warn!("Failed to parse bookmarks state: {}", err);
+                BookmarksState::default()
+            }),
+            Err(err) => {
+                warn!("Failed to read bookmarks state: {}", err);
+                BookmarksState::default()
+            }
+        }
+    }
+
+    /// Write bookmarks state to file in a background thread.
+    /// Writing is best-effort: errors are just logged.
+    /// Each writer waits for the previous one to terminate, so the last state is always the one written
+    fn write_state(&mut self) {
+        let state = self.state.clone();
+        let state_file = self.state_file.clone();
+        let previous_writer = self.state_writer.take();
+        self.state_writer = Some(thread::spawn(move || {
+            if let Some(writer) = previous_writer {
+                let _ = writer.join();
+            }
+            debug!("Writing bookmarks state");
+            let result = OpenOptions::new()
+                .create(true)
+                .write(true)
+                .truncate(true)
+                .open(state_file.as_path())
+                .map_err(|err| SerializerError::new_ex(SerializerErrorKind::Io, err.to_string()))
+                .and_then(|writer| serialize(&state, Box::new(writer)));
+            if let Err(err) = result {
+                warn!("Failed to write bookmarks state: {}", err);
+            }
+        }));
+    }
+
+    /// Remove the recents connecting to the same host of a more recent one
+    fn dedup_recents(&mut self) {
+        let mut keys: Vec<String> = self.hosts.recents.keys().cloned().collect();
+        // NOTE: most recent is the first element
+        keys.sort_by(|a, b| b.cmp(a));
+        let mut kept: Vec<Bookmark> = Vec::with_capacity(keys.len());
+        for key in keys {
+            let host = &self.hosts.recents[&key];
+            if kept.iter().any(|x| Self::is_equivalent(x, host)) {
+                debug!("Removed recent {} since duplicated", key);
+                self.hosts.recents.remove(&key);
+            } else {
+                kept.push(host.clone());
+            }
+        }
+    }
+
+    /// Remove the least recent entries from recents, until they're at most `size`
+    fn evict_recents(&mut self, size: usize) {
+        let mut keys: Vec<String> = self.hosts.recents.keys().cloned().collect();
+        // Sort keys; NOTE: most recent is the last element
+        keys.sort();
+        let exceeding = keys.len().saturating_sub(size);
+        for key in keys.iter().take(exceeding) {
+            let _ = self.hosts.recents.remove(key);
+            debug!("Removed recent bookmark {}", key);
+        }
+    }
+
+    /// Returns whether two bookmarks connect to the same host with the same user
+    pub fn is_equivalent(a: &Bookmark, b: &Bookmark) -> bool {
+        a.protocol == b.protocol
+            && a.address == b.address
+            && a.port == b.port
+            && a.username == b.username
+            && a.s3
+                .as_ref()
+                .map(|x| (&x.bucket, &x.region, &x.endpoint, &x.profile))
+                == b.s3
+                    .as_ref()
+                    .map(|x| (&x.bucket, &x.region, &x.endpoint, &x.profile))
+            && a.kube
+                .as_ref()
+                .map(|x| (&x.namespace, &x.cluster_url, &x.username))
+                == b.kube
+                    .as_ref()
+                    .map(|x| (&x.namespace, &x.cluster_url, &x.username))
+            && a.smb.as_ref().map(|x| (&x.share, &x.workgroup))
+                == b.smb.as_ref().map(|x| (&x.share, &x.workgroup))
     }
 
     /// Generate a new AES key
@@ -335,9 +627,15 @@ impl BookmarksClient {
     /// Make bookmark from credentials
     fn make_bookmark(&self, params: FileTransferParams) -> Bookmark {
         let mut bookmark: Bookmark = Bookmark::from(params);
+        self.encrypt_bookmark(&mut bookmark);
+        bookmark
+    }
+
+    /// Encrypt the secrets of `bookmark`
+    fn encrypt_bookmark(&self, bookmark: &mut Bookmark) {
         // Encrypt password
-        if let Some(pwd) = bookmark.password {
-            bookmark.password = Some(self.encrypt_str(pwd.as_str()));
+        if let Some(pwd) = bookmark.password.as_mut() {
+            *pwd = self.encrypt_str(pwd.as_str());
         }
         // Encrypt aws s3 params
         if let Some(s3) = bookmark.s3.as_mut() {
@@ -348,7 +646,64 @@ impl BookmarksClient {
                 *secret_access_key = self.encrypt_str(secret_access_key.as_str());
             }
         }
-        bookmark
+    }
+
+    /// Decrypt the secrets of the bookmark `name`; secrets which can't be decrypted are kept as they are
+    fn decrypt_bookmark(&self, name: &str, entry: &mut Bookmark) {
+        // Decrypt password first
+        if let Some(pwd) = entry.password.as_mut() {
+            match self.decrypt_str(pwd.as_str()) {
+                Ok(decrypted_pwd) => {
+                    *pwd = decrypted_pwd;
+                }
+                Err(err) => {
+                    error!(
+                        "Failed to decrypt `password` for bookmark {}: {}",
+                        name, err
+                    );
+                }
+            }
+        }
+        // Decrypt AWS-S3 params
+        if let Some(s3) = entry.s3.as_mut() {
+            // Access key
+            if let Some(access_key) = s3.access_key.as_mut() {
+                match self.decrypt_str(access_key.as_str()) {
+                    Ok(plain) => {
+                        *access_key = plain;
+                    }
+                    Err(err) => {
+                        error!(
+                            "Failed to decrypt `access_key` for bookmark {}: {}",
+                            name, err
+                        );
+                    }
+                }
+            }
+            // Secret access key
+            if let Some(secret_access_key) = s3.secret_access_key.as_mut() {
+                match self.decrypt_str(secret_access_key.as_str()) {
+                    Ok(plain) => {
+                        *secret_access_key = plain;
+                    }
+                    Err(err) => {
+                        error!(
+                            "Failed to decrypt `secret_access_key` for bookmark {}: {}",
+                            name, err
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    /// Remove the secrets from `bookmark`
+    fn strip_secrets(bookmark: &mut Bookmark) {
+        bookmark.password = None;
+        if let Some(s3) = bookmark.s3.as_mut() {
+            s3.access_key = None;
+            s3.secret_access_key = None;
+        }
     }
 
     /// Encrypt provided string using AES-128. Encrypted buffer is then converted to BASE64
@@ -741,6 +1096,124 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn should_replace_recent_to_same_host() {
+        let tmp_dir: tempfile::TempDir = TempDir::new().ok().unwrap();
+        let (cfg_path, key_path): (PathBuf, PathBuf) = get_paths(tmp_dir.path());
+        let mut client: BookmarksClient =
+            BookmarksClient::new(cfg_path.as_path(), key_path.as_path(), 16).unwrap();
+        client.add_recent(make_generic_ftparams(
+            FileTransferProtocol::Sftp,
+            "192.168.1.31",
+            22,
+            "pi",
+            None,
+        ));
+        client.add_recent(
+            make_generic_ftparams(FileTransferProtocol::Sftp, "192.168.1.31", 22, "pi", None)
+                .remote_path(Some("/var/www")),
+        );
+        // the previous recent is replaced
+        assert_eq!(client.iter_recents().count(), 1);
+        let key: String = client.iter_recents().next().unwrap().to_string();
+        assert_eq!(
+            client.get_recent(&key).unwrap().remote_path,
+            Some(PathBuf::from("/var/www"))
+        );
+        // another user is another recent; wait 1 second, cause the name depends on time
+        sleep(Duration::from_secs(1));
+        client.add_recent(make_generic_ftparams(
+            FileTransferProtocol::Sftp,
+            "192.168.1.31",
+            22,
+            "root",
+            None,
+        ));
+        assert_eq!(client.iter_recents().count(), 2);
+    }
+
+    #[test]
+    fn should_clean_duplicated_recents_on_read() {
+        let tmp_dir: tempfile::TempDir = TempDir::new().ok().unwrap();
+        let (cfg_path, key_path): (PathBuf, PathBuf) = get_paths(tmp_dir.path());
+        let mut client: BookmarksClient =
+            BookmarksClient::new(cfg_path.as_path(), key_path.as_path(), 16).unwrap();
+        let raspberry = Bookmark::from(make_generic_ftparams(
+            FileTransferProtocol::Sftp,
+            "192.168.1.31",
+            22,
+            "pi",
+            None,
+        ));
+        let server = Bookmark::from(make_generic_ftparams(
+            FileTransferProtocol::Scp,
+            "10.0.0.1",
+            22,
+            "root",
+            None,
+        ));
+        // as written by older versions
+        for (key, host) in [
+            ("ISO20230101T100000", &raspberry),
+            ("ISO20230102T100000", &server),
+            ("ISO20230103T100000", &raspberry),
+        ] {
+            client.hosts.recents.insert(key.to_string(), host.clone());
+        }
+        assert!(client.write_bookmarks().is_ok());
+        let client: BookmarksClient =
+            BookmarksClient::new(cfg_path.as_path(), key_path.as_path(), 16).unwrap();
+        let mut keys: Vec<&String> = client.iter_recents().collect();
+        keys.sort();
+        assert_eq!(keys, vec!["ISO20230102T100000", "ISO20230103T100000"]);
+        // recents are capped to the configured size
+        let client: BookmarksClient =
+            BookmarksClient::new(cfg_path.as_path(), key_path.as_path(), 1).unwrap();
+        assert_eq!(
+            client.iter_recents().collect::<Vec<&String>>(),
+            vec!["ISO20230103T100000"]
+        );
+    }
+
+    #[test]
+    fn should_pin_recent() {
+        let tmp_dir: tempfile::TempDir = TempDir::new().ok().unwrap();
+        let (cfg_path, key_path): (PathBuf, PathBuf) = get_paths(tmp_dir.path());
+        let mut client: BookmarksClient =
+            BookmarksClient::new(cfg_path.as_path(), key_path.as_path(), 16).unwrap();
+        client.add_recent(make_generic_ftparams(
+            FileTransferProtocol::Sftp,
+            "192.168.1.31",
+            22,
+            "pi",
+            Some("mypassword"),
+        ));
+        let key: String = client.iter_recents().next().unwrap().to_string();
+        assert_eq!(
+            client.suggest_pinned_name(&key).as_deref(),
+            Some("pi@192.168.1.31")
+        );
+        client.add_bookmark(
+            "pi@192.168.1.31",
+            make_generic_ftparams(FileTransferProtocol::Scp, "192.168.1.31", 22, "pi", None),
+            false,
+        );
+        assert_eq!(
+            client.suggest_pinned_name(&key).as_deref(),
+            Some("pi@192.168.1.31 (2)")
+        );
+        assert!(client.pin_recent(&key, "raspberry"));
+        assert_eq!(client.iter_recents().count(), 0);
+        let bookmark = ftparams_to_tup(client.get_bookmark("raspberry").unwrap());
+        assert_eq!(bookmark.0, String::from("192.168.1.31"));
+        assert_eq!(bookmark.2, FileTransferProtocol::Sftp);
+        assert_eq!(bookmark.3, String::from("pi"));
+        assert_eq!(bookmark.4, None);
+        // unexisting recent
+        assert!(!client.pin_recent(&key, "raspberry"));
+        assert!(client.suggest_pinned_name(&key).is_none());
+    }
+
     #[test]
     #[should_panic]
     fn test_system_bookmarks_add_bookmark_empty() {
@@ -778,6 +1251,465 @@ mod tests {
         assert!(client.decrypt_str("bidoof").is_err());
     }
 
+    #[test]
+    fn should_record_connection_failures() {
+        let tmp_dir: tempfile::TempDir = TempDir::new().ok().unwrap();
+        let (cfg_path, key_path): (PathBuf, PathBuf) = get_paths(tmp_dir.path());
+        let mut client: BookmarksClient =
+            BookmarksClient::new(cfg_path.as_path(), key_path.as_path(), 16).unwrap();
+        assert_eq!(
+            client.state_file,
+            tmp_dir.path().join("bookmarks.state.toml")
+        );
+        assert!(client.get_connection_failure("raspberry").is_none());
+        client.set_connection_failure("raspberry", "Connection refused\nat line 2");
+        client.set_connection_failure("omar", "Authentication failed");
+        assert_eq!(
+            client
+                .get_connection_failure("raspberry")
+                .unwrap()
+                .error
+                .as_str(),
+            "Connection refused"
+        );
+        client.state_writer.take().unwrap().join().unwrap();
+        // State is restored
+        let mut client: BookmarksClient =
+            BookmarksClient::new(cfg_path.as_path(), key_path.as_path(), 16).unwrap();
+        assert_eq!(
+            client
+                .get_connection_failure("omar")
+                .unwrap()
+                .error
+                .as_str(),
+            "Authentication failed"
+        );
+        // Clear state
+        client.clear_connection_failure("raspberry");
+        client.state_writer.take().unwrap().join().unwrap();
+        // nothing to clear; state is not written
+        client.clear_connection_failure("raspberry");
+        assert!(client.state_writer.is_none());
+        // deleting bookmark deletes its state
+        client.del_bookmark("omar");
+        client.state_writer.take().unwrap().join().unwrap();
+        let client: BookmarksClient =
+            BookmarksClient::new(cfg_path.as_path(), key_path.as_path(), 16).unwrap();
+        assert!(client.get_connection_failure("raspberry").is_none());
+        assert!(client.get_connection_failure("omar").is_none());
+    }
+
+    #[test]
+    fn should_set_bookmark_favorites() {
+        let tmp_dir: tempfile::TempDir = TempDir::new().ok().unwrap();
+        let (cfg_path, key_path): (PathBuf, PathBuf) = get_paths(tmp_dir.path());
+        let mut client: BookmarksClient =
+            BookmarksClient::new(cfg_path.as_path(), key_path.as_path(), 16).unwrap();
+        assert!(client.get_favorites("raspberry").is_empty());
+        client.set_favorites(
+            "raspberry",
+            vec![PathBuf::from("/etc/hosts"), PathBuf::from("/var/log")],
+        );
+        client.set_favorites("omar", vec![PathBuf::from("/home/omar/notes.txt")]);
+        client.state_writer.take().unwrap().join().unwrap();
+        // State is restored
+        let mut client: BookmarksClient =
+            BookmarksClient::new(cfg_path.as_path(), key_path.as_path(), 16).unwrap();
+        assert_eq!(
+            client.get_favorites("raspberry"),
+            &[PathBuf::from("/etc/hosts"), PathBuf::from("/var/log")]
+        );
+        // no favorites left
+        client.set_favorites("raspberry", vec![]);
+        assert!(!client.state.favorites.contains_key("raspberry"));
+        // deleting bookmark deletes its favorites
+        client.del_bookmark("omar");
+        client.state_writer.take().unwrap().join().unwrap();
+        let client: BookmarksClient =
+            BookmarksClient::new(cfg_path.as_path(), key_path.as_path(), 16).unwrap();
+        assert!(client.get_favorites("raspberry").is_empty());
+        assert!(client.get_favorites("omar").is_empty());
+    }
+
+    #[test]
+    fn should_set_directory_bookmarks() {
+        let tmp_dir: tempfile::TempDir = TempDir::new().ok().unwrap();
+        let (cfg_path, key_path): (PathBuf, PathBuf) = get_paths(tmp_dir.path());
+        let mut client: BookmarksClient =
+            BookmarksClient::new(cfg_path.as_path(), key_path.as_path(), 16).unwrap();
+        assert!(client
+            .get_directory_bookmarks("sftp://192.168.1.31")
+            .is_empty());
+        let logs = DirectoryBookmark {
+            name: String::from("logs"),
+            path: PathBuf::from("/var/log/nginx"),
+        };
+        client.set_directory_bookmarks("sftp://192.168.1.31", vec![logs.clone()]);
+        client.state_writer.take().unwrap().join().unwrap();
+        // State is restored
+        let mut client: BookmarksClient =
+            BookmarksClient::new(cfg_path.as_path(), key_path.as_path(), 16).unwrap();
+        assert_eq!(
+            client.get_directory_bookmarks("sftp://192.168.1.31"),
+            &[logs]
+        );
+        // directories are bookmarked for each host
+        assert!(client
+            .get_directory_bookmarks("scp://192.168.1.31")
+            .is_empty());
+        // no directories left
+        client.set_directory_bookmarks("sftp://192.168.1.31", vec![]);
+        assert!(!client.state.directories.contains_key("sftp://192.168.1.31"));
+    }
+
+    #[test]
+    fn should_set_bookmark_group() {
+        let tmp_dir: tempfile::TempDir = TempDir::new().ok().unwrap();
+        let (cfg_path, key_path): (PathBuf, PathBuf) = get_paths(tmp_dir.path());
+        let mut client: BookmarksClient =
+            BookmarksClient::new(cfg_path.as_path(), key_path.as_path(), 16).unwrap();
+        client.add_bookmark(
+            "raspberry",
+            make_generic_ftparams(FileTransferProtocol::Sftp, "192.168.1.31", 22, "pi", None),
+            false,
+        );
+        assert!(client.get_bookmark_group("raspberry").is_none());
+        client.set_bookmark_group("raspberry", Some(String::from(" home ")));
+        assert_eq!(client.get_bookmark_group("raspberry"), Some("home"));
+        // unexisting bookmark
+        client.set_bookmark_group("omar", Some(String::from("work")));
+        assert!(client.get_bookmark_group("omar").is_none());
+        // group is restored
+        assert!(client.write_bookmarks().is_ok());
+        let mut client: BookmarksClient =
+            BookmarksClient::new(cfg_path.as_path(), key_path.as_path(), 16).unwrap();
+        assert_eq!(client.get_bookmark_group("raspberry"), Some("home"));
+        // empty group means ungrouped
+        client.set_bookmark_group("raspberry", Some(String::new()));
+        assert!(client.get_bookmark_group("raspberry").is_none());
+    }
+
+    #[test]
+    fn should_ignore_bad_bookmarks_state() {
+        let tmp_dir: tempfile::TempDir = TempDir::new().ok().unwrap();
+        let (cfg_path, key_path): (PathBuf, PathBuf) = get_paths(tmp_dir.path());
+        std::fs::write(tmp_dir.path().join("bookmarks.state.toml"), "failures = 1").unwrap();
+        let client: BookmarksClient =
+            BookmarksClient::new(cfg_path.as_path(), key_path.as_path(), 16).unwrap();
+        assert!(client.state.failures.is_empty());
+    }
+
+    #[test]
+    fn should_find_equivalent_bookmark() {
+        let tmp_dir: tempfile::TempDir = TempDir::new().ok().unwrap();
+        let (cfg_path, key_path): (PathBuf, PathBuf) = get_paths(tmp_dir.path());
+        let mut client: BookmarksClient =
+            BookmarksClient::new(cfg_path.as_path(), key_path.as_path(), 16).unwrap();
+        client.add_bookmark(
+            "raspberry",
+            make_generic_ftparams(
+                FileTransferProtocol::Sftp,
+                "192.168.1.31",
+                22,
+                "pi",
+                Some("mypassword"),
+            ),
+            true,
+        );
+        client.add_bookmark("my-bucket", make_s3_ftparams(), true);
+        // Same host and user, password and paths are ignored
+        let mut params = make_generic_ftparams(
+            FileTransferProtocol::Sftp,
+            "192.168.1.31",
+            22,
+            "pi",
+            Some("another-password"),
+        );
+        params.remote_path = Some(PathBuf::from("/tmp"));
+        params.local_path = Some(PathBuf::from("/home/omar"));
+        assert_eq!(
+            client.find_equivalent(&Bookmark::from(params)),
+            Some("raspberry")
+        );
+        assert_eq!(
+            client.find_equivalent(&Bookmark::from(make_generic_ftparams(
+                FileTransferProtocol::Sftp,
+                "192.168.1.31",
+                22,
+                "pi",
+                None,
+            ))),
+            Some("raspberry")
+        );
+        // Secrets are ignored for s3
+        let mut params = make_s3_ftparams();
+        if let ProtocolParams::AwsS3(s3) = &mut params.params {
+            s3.access_key = None;
+            s3.secret_access_key = None;
+        }
+        assert_eq!(
+            client.find_equivalent(&Bookmark::from(params)),
+            Some("my-bucket")
+        );
+        // If many bookmarks are equivalent, the first in alphabetical order is returned
+        client.add_bookmark(
+            "pi",
+            make_generic_ftparams(FileTransferProtocol::Sftp, "192.168.1.31", 22, "pi", None),
+            false,
+        );
+        assert_eq!(
+            client.find_equivalent(&Bookmark::from(make_generic_ftparams(
+                FileTransferProtocol::Sftp,
+                "192.168.1.31",
+                22,
+                "pi",
+                None,
+            ))),
+            Some("pi")
+        );
+    }
+
+    #[test]
+    fn should_get_usernames_for_address() {
+        let tmp_dir: tempfile::TempDir = TempDir::new().ok().unwrap();
+        let (cfg_path, key_path): (PathBuf, PathBuf) = get_paths(tmp_dir.path());
+        let mut client: BookmarksClient =
+            BookmarksClient::new(cfg_path.as_path(), key_path.as_path(), 16).unwrap();
+        client.add_bookmark(
+            "raspberry",
+            make_generic_ftparams(FileTransferProtocol::Sftp, "192.168.1.31", 22, "pi", None),
+            false,
+        );
+        client.add_bookmark(
+            "raspberry-root",
+            make_generic_ftparams(FileTransferProtocol::Scp, "192.168.1.31", 22, "root", None),
+            false,
+        );
+        client.add_bookmark(
+            "server",
+            make_generic_ftparams(FileTransferProtocol::Sftp, "10.0.0.1", 22, "omar", None),
+            false,
+        );
+        client.add_recent(make_generic_ftparams(
+            FileTransferProtocol::Ftp(false),
+            "192.168.1.31",
+            21,
+            "ftp",
+            None,
+        ));
+        assert_eq!(
+            client.usernames("192.168.1.31"),
+            vec![
+                String::from("pi"),
+                String::from("root"),
+                String::from("ftp")
+            ]
+        );
+        assert_eq!(client.usernames("10.0.0.1"), vec![String::from("omar")]);
+        assert!(client.usernames("172.16.0.1").is_empty());
+    }
+
+    #[test]
+    fn should_not_find_equivalent_bookmark() {
+        let tmp_dir: tempfile::TempDir = TempDir::new().ok().unwrap();
+        let (cfg_path, key_path): (PathBuf, PathBuf) = get_paths(tmp_dir.path());
+        let mut client: BookmarksClient =
+            BookmarksClient::new(cfg_path.as_path(), key_path.as_path(), 16).unwrap();
+        let find = |client: &BookmarksClient, params: FileTransferParams| {
+            client.find_equivalent(&Bookmark::from(params)).is_some()
+        };
+        assert_eq!(
+            find(
+                &client,
+                make_generic_ftparams(FileTransferProtocol::Sftp, "192.168.1.31", 22, "pi", None)
+            ),
+            false
+        );
+        client.add_bookmark(
+            "raspberry",
+            make_generic_ftparams(FileTransferProtocol::Sftp, "192.168.1.31", 22, "pi", None),
+            false,
+        );
+        client.add_bookmark("my-bucket", make_s3_ftparams(), true);
+        // Different protocol
+        assert_eq!(
+            find(
+                &client,
+                make_generic_ftparams(FileTransferProtocol::Scp, "192.168.1.31", 22, "pi", None)
+            ),
+            false
+        );
+        // Different address
+        assert_eq!(
+            find(
+                &client,
+                make_generic_ftparams(FileTransferProtocol::Sftp, "192.168.1.32", 22, "pi", None)
+            ),
+            false
+        );
+        // Different port
+        assert_eq!(
+            find(
+                &client,
+                make_generic_ftparams(FileTransferProtocol::Sftp, "192.168.1.31", 2222, "pi", None)
+            ),
+            false
+        );
+        // Different username
+        assert_eq!(
+            find(
+                &client,
+                make_generic_ftparams(FileTransferProtocol::Sftp, "192.168.1.31", 22, "root", None)
+            ),
+            false
+        );
+        // Different bucket
+        let mut params = make_s3_ftparams();
+        if let ProtocolParams::AwsS3(s3) = &mut params.params {
+            s3.bucket_name = String::from("another-bucket");
+        }
+        assert_eq!(find(&client, params), false);
+    }
+
+    #[test]
+    fn should_export_and_import_bookmarks() {
+        let tmp_dir: tempfile::TempDir = TempDir::new().ok().unwrap();
+        let (cfg_path, key_path): (PathBuf, PathBuf) = get_paths(tmp_dir.path());
+        let mut client: BookmarksClient =
+            BookmarksClient::new(cfg_path.as_path(), key_path.as_path(), 16).unwrap();
+        client.add_bookmark(
+            "raspberry",
+            make_generic_ftparams(
+                FileTransferProtocol::Sftp,
+                "192.168.1.31",
+                22,
+                "pi",
+                Some("mypassword"),
+            ),
+            true,
+        );
+        client.add_recent(make_generic_ftparams(
+            FileTransferProtocol::Ftp(false),
+            "10.0.0.1",
+            21,
+            "omar",
+            None,
+        ));
+        // secrets are exported in plain text only if requested
+        let exported = client.export_bookmarks(true);
+        assert!(exported.recents.is_empty());
+        assert_eq!(
+            exported
+                .bookmarks
+                .get("raspberry")
+                .unwrap()
+                .password
+                .as_deref(),
+            Some("mypassword")
+        );
+        assert!(client
+            .export_bookmarks(false)
+            .bookmarks
+            .get("raspberry")
+            .unwrap()
+            .password
+            .is_none());
+        // import on another machine, which has its own key
+        let other_dir: tempfile::TempDir = TempDir::new().ok().unwrap();
+        let (other_cfg, other_key): (PathBuf, PathBuf) = get_paths(other_dir.path());
+        let mut other: BookmarksClient =
+            BookmarksClient::new(other_cfg.as_path(), other_key.as_path(), 16).unwrap();
+        assert_eq!(
+            other.import_bookmarks(exported, false),
+            (vec![String::from("raspberry")], vec![])
+        );
+        // the password is stored encrypted
+        assert_ne!(
+            other
+                .hosts
+                .bookmarks
+                .get("raspberry")
+                .unwrap()
+                .password
+                .as_deref(),
+            Some("mypassword")
+        );
+        let bookmark = ftparams_to_tup(other.get_bookmark("raspberry").unwrap());
+        assert_eq!(bookmark.0, String::from("192.168.1.31"));
+        assert_eq!(bookmark.4.as_deref(), Some("mypassword"));
+    }
+
+    #[test]
+    fn should_skip_or_overwrite_existing_bookmarks_on_import() {
+        let tmp_dir: tempfile::TempDir = TempDir::new().ok().unwrap();
+        let (cfg_path, key_path): (PathBuf, PathBuf) = get_paths(tmp_dir.path());
+        let mut client: BookmarksClient =
+            BookmarksClient::new(cfg_path.as_path(), key_path.as_path(), 16).unwrap();
+        client.add_bookmark(
+            "raspberry",
+            make_generic_ftparams(FileTransferProtocol::Sftp, "192.168.1.31", 22, "pi", None),
+            false,
+        );
+        let import = |address: &str| {
+            let mut hosts = UserHosts::default();
+            for name in ["raspberry", "nas"] {
+                hosts.bookmarks.insert(
+                    name.to_string(),
+                    Bookmark::from(make_generic_ftparams(
+                        FileTransferProtocol::Scp,
+                        address,
+                        22,
+                        "root",
+                        None,
+                    )),
+                );
+            }
+            hosts
+        };
+        assert_eq!(
+            client.import_bookmarks(import("10.0.0.2"), false),
+            (vec![String::from("nas")], vec![String::from("raspberry")])
+        );
+        assert_eq!(
+            ftparams_to_tup(client.get_bookmark("raspberry").unwrap()).0,
+            "192.168.1.31"
+        );
+        assert_eq!(
+            client.import_bookmarks(import("10.0.0.3"), true),
+            (vec![String::from("nas"), String::from("raspberry")], vec![])
+        );
+        assert_eq!(
+            ftparams_to_tup(client.get_bookmark("raspberry").unwrap()).0,
+            "10.0.0.3"
+        );
+    }
+
+    #[test]
+    fn test_system_bookmarks_migrate_legacy_file() {
+        let tmp_dir: tempfile::TempDir = TempDir::new().ok().unwrap();
+        let (cfg_path, key_path): (PathBuf, PathBuf) = get_paths(tmp_dir.path());
+        let legacy = include_str!("../config/fixtures/bookmarks_hand_edited.toml");
+        std::fs::write(cfg_path.as_path(), legacy).unwrap();
+        let mut client: BookmarksClient =
+            BookmarksClient::new(cfg_path.as_path(), key_path.as_path(), 16).unwrap();
+        assert_eq!(client.hosts.bookmarks.len(), 2);
+        assert!(client.get_bookmark("bucket").is_some());
+        let report = client.take_migration_report();
+        assert_eq!(report.len(), 7);
+        assert!(report[0].starts_with("backed up the original bookmarks to"));
+        assert!(client.take_migration_report().is_empty());
+        // the original file is kept as backup
+        assert_eq!(
+            std::fs::read_to_string(tmp_dir.path().join("bookmarks.toml.bak")).unwrap(),
+            legacy
+        );
+        // the migrated file is loaded as it is
+        let mut client: BookmarksClient =
+            BookmarksClient::new(cfg_path.as_path(), key_path.as_path(), 16).unwrap();
+        assert_eq!(client.hosts.bookmarks.len(), 2);
+        assert!(client.take_migration_report().is_empty());
+    }
+
     /// Get paths for configuration and key for bookmarks
     fn get_paths(dir: &Path) -> (PathBuf, PathBuf) {
         let k: PathBuf = PathBuf::from(dir);
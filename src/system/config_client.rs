@@ -4,26 +4,46 @@
 
 // Locals
 // Ext
-use std::fs::{create_dir, remove_file, File, OpenOptions};
-use std::io::Write;
+use std::fs::{self, create_dir, remove_file, File, OpenOptions};
+use std::io::{Cursor, Write};
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
 use std::string::ToString;
-
-use crate::config::params::{UserConfig, DEFAULT_NOTIFICATION_TRANSFER_THRESHOLD};
+use std::time::Duration;
+
+use crate::config::keymap::{Keymap, KeymapConfig};
+use crate::config::migration;
+use crate::config::params::{
+    UserConfig, DEFAULT_DELETE_CONFIRM_NAME_THRESHOLD, DEFAULT_DIRSTACK_SIZE,
+    DEFAULT_FIND_SPILL_THRESHOLD, DEFAULT_LOG_MAX_BYTES, DEFAULT_LOG_MESSAGE_MAX_LEN,
+    DEFAULT_NOTIFICATION_TRANSFER_THRESHOLD, DEFAULT_PARKED_SESSION_TIMEOUT, DEFAULT_RECENTS_SIZE,
+    DEFAULT_RECONNECT_RETRIES, DEFAULT_REMOTE_WATCHER_INTERVAL, DEFAULT_SSH_KNOWN_HOSTS,
+    DEFAULT_TRANSFER_BUFFER_KB, DEFAULT_TRANSFER_CONCURRENCY,
+    DEFAULT_TRANSFER_PROMPT_FILES_THRESHOLD, DEFAULT_TRANSFER_PROMPT_SIZE_THRESHOLD,
+    MAX_DIRSTACK_SIZE, MAX_RECONNECT_RETRIES, MAX_TRANSFER_BUFFER_KB, MAX_TRANSFER_CONCURRENCY,
+};
 use crate::config::serialization::{deserialize, serialize, SerializerError, SerializerErrorKind};
+use crate::config::validation;
+use crate::explorer::filter::FilterMode;
 use crate::explorer::GroupDirs;
 use crate::filetransfer::FileTransferProtocol;
+use crate::system::notifications::TerminalBell;
+use crate::system::watcher::{IgnorePatterns, DEFAULT_IGNORE_PATTERNS};
+use crate::ui::activities::auth::StartupFocus;
+use crate::utils::parser;
 
 // Types
 pub type SshHost = (String, String, PathBuf); // 0: host, 1: username, 2: RSA key path
 
 /// ConfigClient provides a high level API to communicate with the termscp configuration
+#[derive(Clone)]
 pub struct ConfigClient {
-    config: UserConfig,   // Configuration loaded
-    config_path: PathBuf, // Configuration TOML Path
-    ssh_key_dir: PathBuf, // SSH Key storage directory
-    degraded: bool,       // Indicates the `ConfigClient` is working in degraded mode
+    config: UserConfig,            // Configuration loaded
+    config_path: PathBuf,          // Configuration TOML Path
+    ssh_key_dir: PathBuf,          // SSH Key storage directory
+    degraded: bool,                // Indicates the `ConfigClient` is working in degraded mode
+    keymap: Keymap,                // Keybindings of the file explorers
+    migration_report: Vec<String>, // Changes applied migrating the configuration from a legacy format
 }
 
 impl ConfigClient {
@@ -42,6 +62,8 @@ impl ConfigClient {
             config_path: PathBuf::from(config_path),
             ssh_key_dir: PathBuf::from(ssh_key_dir),
             degraded: false,
+            keymap: Keymap::default(),
+            migration_report: Vec::new(),
         };
         // If ssh key directory doesn't exist, create it
         if !ssh_key_dir.exists() {
@@ -85,6 +107,8 @@ impl ConfigClient {
             config_path: PathBuf::default(),
             ssh_key_dir: PathBuf::default(),
             degraded: true,
+            keymap: Keymap::default(),
+            migration_report: Vec::new(),
         }
     }
 
@@ -215,6 +239,383 @@ impl ConfigClient {
         self.config.user_interface.notification_threshold = Some(value);
     }
 
+    /// Get value of `notification_min_duration_secs`; `0` means disabled
+    pub fn get_notification_min_duration(&self) -> Option<Duration> {
+        self.config
+            .user_interface
+            .notification_min_duration_secs
+            .filter(|secs| *secs > 0)
+            .map(Duration::from_secs)
+    }
+
+    /// Set new value for `notification_min_duration_secs`
+    #[cfg(test)]
+    pub fn set_notification_min_duration_secs(&mut self, value: Option<u64>) {
+        self.config.user_interface.notification_min_duration_secs = value;
+    }
+
+    /// Get value of `notification_unfocused_only`
+    pub fn get_notification_unfocused_only(&self) -> bool {
+        self.config
+            .user_interface
+            .notification_unfocused_only
+            .unwrap_or(false)
+    }
+
+    /// Set new value for `notification_unfocused_only`
+    #[cfg(test)]
+    pub fn set_notification_unfocused_only(&mut self, value: bool) {
+        self.config.user_interface.notification_unfocused_only = Some(value);
+    }
+
+    /// Get value of `disable_cache`
+    pub fn get_disable_cache(&self) -> bool {
+        self.config.user_interface.disable_cache.unwrap_or(false)
+    }
+
+    /// Set new value for `disable_cache`
+    pub fn set_disable_cache(&mut self, value: bool) {
+        self.config.user_interface.disable_cache = Some(value);
+    }
+
+    /// Get value of `delete_to_trash`
+    pub fn get_delete_to_trash(&self) -> bool {
+        self.config.user_interface.delete_to_trash.unwrap_or(false)
+    }
+
+    /// Set new value for `delete_to_trash`
+    pub fn set_delete_to_trash(&mut self, value: bool) {
+        self.config.user_interface.delete_to_trash = Some(value);
+    }
+
+    /// Get value of `preserve_symlinks`
+    pub fn get_preserve_symlinks(&self) -> bool {
+        self.config
+            .user_interface
+            .preserve_symlinks
+            .unwrap_or(false)
+    }
+
+    /// Set new value for `preserve_symlinks`
+    #[cfg(test)]
+    pub fn set_preserve_symlinks(&mut self, value: bool) {
+        self.config.user_interface.preserve_symlinks = Some(value);
+    }
+
+    /// Get value of `preserve_metadata`
+    pub fn get_preserve_metadata(&self) -> bool {
+        self.config.user_interface.preserve_metadata.unwrap_or(true)
+    }
+
+    /// Set new value for `preserve_metadata`
+    #[cfg(test)]
+    pub fn set_preserve_metadata(&mut self, value: bool) {
+        self.config.user_interface.preserve_metadata = Some(value);
+    }
+
+    /// Get value of `lock_panels_view`
+    pub fn get_lock_panels_view(&self) -> bool {
+        self.config.user_interface.lock_panels_view.unwrap_or(false)
+    }
+
+    /// Set new value for `lock_panels_view`
+    #[cfg(test)]
+    pub fn set_lock_panels_view(&mut self, value: bool) {
+        self.config.user_interface.lock_panels_view = Some(value);
+    }
+
+    /// Get `FilterMode` value from configuration (will be converted from string).
+    /// If not set or invalid, `FilterMode::Glob` is returned
+    pub fn get_filter_mode(&self) -> FilterMode {
+        self.config
+            .user_interface
+            .filter_mode
+            .as_deref()
+            .and_then(|val| FilterMode::from_str(val).ok())
+            .unwrap_or_default()
+    }
+
+    /// Set value for `filter_mode` in configuration
+    #[cfg(test)]
+    pub fn set_filter_mode(&mut self, val: FilterMode) {
+        self.config.user_interface.filter_mode = Some(val.to_string());
+    }
+
+    /// Get value of `keep_filter`
+    pub fn get_keep_filter(&self) -> bool {
+        self.config.user_interface.keep_filter.unwrap_or(false)
+    }
+
+    /// Set new value for `keep_filter`
+    #[cfg(test)]
+    pub fn set_keep_filter(&mut self, value: bool) {
+        self.config.user_interface.keep_filter = Some(value);
+    }
+
+    /// Get value of `confirm_edit_upload_with_diff`
+    pub fn get_confirm_edit_upload_with_diff(&self) -> bool {
+        self.config
+            .user_interface
+            .confirm_edit_upload_with_diff
+            .unwrap_or(false)
+    }
+
+    /// Set new value for `confirm_edit_upload_with_diff`
+    #[cfg(test)]
+    pub fn set_confirm_edit_upload_with_diff(&mut self, value: bool) {
+        self.config.user_interface.confirm_edit_upload_with_diff = Some(value);
+    }
+
+    /// Get the patterns of the files ignored by the file watcher, from `watcher_ignore`;
+    /// `DEFAULT_IGNORE_PATTERNS` if unset
+    pub fn get_watcher_ignore(&self) -> IgnorePatterns {
+        match self.config.user_interface.watcher_ignore.as_deref() {
+            Some(patterns) => IgnorePatterns::new(patterns),
+            None => IgnorePatterns::new(DEFAULT_IGNORE_PATTERNS),
+        }
+    }
+
+    /// Set new value for `watcher_ignore`
+    #[cfg(test)]
+    pub fn set_watcher_ignore(&mut self, patterns: Option<Vec<String>>) {
+        self.config.user_interface.watcher_ignore = patterns;
+    }
+
+    /// Get the interval between the polls of the remote directories watched by the file watcher,
+    /// from `remote_watcher_interval`; at least 1 second
+    pub fn get_remote_watcher_interval(&self) -> Duration {
+        Duration::from_secs(
+            self.config
+                .user_interface
+                .remote_watcher_interval
+                .unwrap_or(DEFAULT_REMOTE_WATCHER_INTERVAL)
+                .max(1),
+        )
+    }
+
+    /// Set new value for `remote_watcher_interval`
+    #[cfg(test)]
+    pub fn set_remote_watcher_interval(&mut self, value: u64) {
+        self.config.user_interface.remote_watcher_interval = Some(value);
+    }
+
+    /// Get the maximum amount of recent connections kept, from `recents_size`; at least 1
+    pub fn get_recents_size(&self) -> usize {
+        self.config
+            .user_interface
+            .recents_size
+            .unwrap_or(DEFAULT_RECENTS_SIZE)
+            .max(1) as usize
+    }
+
+    /// Set new value for `recents_size`
+    #[cfg(test)]
+    pub fn set_recents_size(&mut self, value: u64) {
+        self.config.user_interface.recents_size = Some(value);
+    }
+
+    /// Get value of `password_prompt_asterisks`
+    pub fn get_password_prompt_asterisks(&self) -> bool {
+        self.config
+            .user_interface
+            .password_prompt_asterisks
+            .unwrap_or(false)
+    }
+
+    /// Set new value for `password_prompt_asterisks`
+    #[cfg(test)]
+    pub fn set_password_prompt_asterisks(&mut self, value: bool) {
+        self.config.user_interface.password_prompt_asterisks = Some(value);
+    }
+
+    /// Get value of `mouse_capture`
+    pub fn get_mouse_capture(&self) -> bool {
+        self.config.user_interface.mouse_capture.unwrap_or(false)
+    }
+
+    /// Set new value for `mouse_capture`
+    pub fn set_mouse_capture(&mut self, value: bool) {
+        self.config.user_interface.mouse_capture = Some(value);
+    }
+
+    /// Get value of `log_message_max_len`; `0` means messages are never truncated
+    pub fn get_log_message_max_len(&self) -> usize {
+        self.config
+            .user_interface
+            .log_message_max_len
+            .unwrap_or(DEFAULT_LOG_MESSAGE_MAX_LEN) as usize
+    }
+
+    /// Set new value for `log_message_max_len`
+    #[cfg(test)]
+    pub fn set_log_message_max_len(&mut self, value: u64) {
+        self.config.user_interface.log_message_max_len = Some(value);
+    }
+
+    /// Get value of `log_max_bytes`; `0` means only the amount of records is bounded
+    pub fn get_log_max_bytes(&self) -> usize {
+        self.config
+            .user_interface
+            .log_max_bytes
+            .unwrap_or(DEFAULT_LOG_MAX_BYTES) as usize
+    }
+
+    /// Set new value for `log_max_bytes`
+    #[cfg(test)]
+    pub fn set_log_max_bytes(&mut self, value: u64) {
+        self.config.user_interface.log_max_bytes = Some(value);
+    }
+
+    /// Get value of `find_spill_threshold`; `0` means find results are always kept in memory
+    pub fn get_find_spill_threshold(&self) -> usize {
+        self.config
+            .user_interface
+            .find_spill_threshold
+            .unwrap_or(DEFAULT_FIND_SPILL_THRESHOLD) as usize
+    }
+
+    /// Set new value for `find_spill_threshold`
+    #[cfg(test)]
+    pub fn set_find_spill_threshold(&mut self, value: u64) {
+        self.config.user_interface.find_spill_threshold = Some(value);
+    }
+
+    /// Get value of `check_bookmarks_health`
+    pub fn get_check_bookmarks_health(&self) -> bool {
+        self.config
+            .user_interface
+            .check_bookmarks_health
+            .unwrap_or(false)
+    }
+
+    /// Set new value for `check_bookmarks_health`
+    #[cfg(test)]
+    pub fn set_check_bookmarks_health(&mut self, value: bool) {
+        self.config.user_interface.check_bookmarks_health = Some(value);
+    }
+
+    /// Get `TerminalBell` value from configuration (will be converted from string).
+    /// If not set or invalid, `TerminalBell::Off` is returned
+    pub fn get_terminal_bell(&self) -> TerminalBell {
+        self.config
+            .user_interface
+            .terminal_bell
+            .as_deref()
+            .and_then(|val| TerminalBell::from_str(val).ok())
+            .unwrap_or_default()
+    }
+
+    /// Set value for `terminal_bell` in configuration
+    #[cfg(test)]
+    pub fn set_terminal_bell(&mut self, val: TerminalBell) {
+        self.config.user_interface.terminal_bell = Some(val.to_string());
+    }
+
+    /// Get value of `transfer_prompt_files_threshold`
+    pub fn get_transfer_prompt_files_threshold(&self) -> u64 {
+        self.config
+            .user_interface
+            .transfer_prompt_files_threshold
+            .unwrap_or(DEFAULT_TRANSFER_PROMPT_FILES_THRESHOLD)
+    }
+
+    /// Set new value for `transfer_prompt_files_threshold`
+    #[cfg(test)]
+    pub fn set_transfer_prompt_files_threshold(&mut self, value: u64) {
+        self.config.user_interface.transfer_prompt_files_threshold = Some(value);
+    }
+
+    /// Get value of `transfer_prompt_size_threshold`
+    pub fn get_transfer_prompt_size_threshold(&self) -> u64 {
+        self.config
+            .user_interface
+            .transfer_prompt_size_threshold
+            .unwrap_or(DEFAULT_TRANSFER_PROMPT_SIZE_THRESHOLD)
+    }
+
+    /// Set new value for `transfer_prompt_size_threshold`
+    #[cfg(test)]
+    pub fn set_transfer_prompt_size_threshold(&mut self, value: u64) {
+        self.config.user_interface.transfer_prompt_size_threshold = Some(value);
+    }
+
+    /// Get value of `delete_confirm_name_threshold`
+    pub fn get_delete_confirm_name_threshold(&self) -> u64 {
+        self.config
+            .user_interface
+            .delete_confirm_name_threshold
+            .unwrap_or(DEFAULT_DELETE_CONFIRM_NAME_THRESHOLD)
+    }
+
+    /// Set new value for `delete_confirm_name_threshold`
+    #[cfg(test)]
+    pub fn set_delete_confirm_name_threshold(&mut self, value: u64) {
+        self.config.user_interface.delete_confirm_name_threshold = Some(value);
+    }
+
+    /// Get value of `transfer_concurrency`, between 1 and `MAX_TRANSFER_CONCURRENCY`
+    pub fn get_transfer_concurrency(&self) -> usize {
+        self.config
+            .user_interface
+            .transfer_concurrency
+            .unwrap_or(DEFAULT_TRANSFER_CONCURRENCY)
+            .clamp(1, MAX_TRANSFER_CONCURRENCY) as usize
+    }
+
+    /// Set new value for `transfer_concurrency`
+    pub fn set_transfer_concurrency(&mut self, value: u64) {
+        self.config.user_interface.transfer_concurrency = Some(value);
+    }
+
+    /// Get the size in bytes of the buffer used to copy the streams of transfers, from `transfer_buffer_kb`,
+    /// between 1 and `MAX_TRANSFER_BUFFER_KB` KiB
+    pub fn get_transfer_buffer_size(&self) -> usize {
+        self.config
+            .user_interface
+            .transfer_buffer_kb
+            .unwrap_or(DEFAULT_TRANSFER_BUFFER_KB)
+            .clamp(1, MAX_TRANSFER_BUFFER_KB) as usize
+            * 1024
+    }
+
+    /// Set new value for `transfer_buffer_kb`
+    #[cfg(test)]
+    pub fn set_transfer_buffer_kb(&mut self, value: u64) {
+        self.config.user_interface.transfer_buffer_kb = Some(value);
+    }
+
+    /// Get value of `dirstack_size`, between 1 and `MAX_DIRSTACK_SIZE`
+    pub fn get_dirstack_size(&self) -> usize {
+        self.config
+            .user_interface
+            .dirstack_size
+            .unwrap_or(DEFAULT_DIRSTACK_SIZE)
+            .clamp(1, MAX_DIRSTACK_SIZE) as usize
+    }
+
+    /// Set new value for `dirstack_size`
+    #[cfg(test)]
+    pub fn set_dirstack_size(&mut self, value: u64) {
+        self.config.user_interface.dirstack_size = Some(value);
+    }
+
+    /// Get `StartupFocus` value from configuration (will be converted from string).
+    /// If not set or invalid, `StartupFocus::Form` is returned
+    pub fn get_startup_focus(&self) -> StartupFocus {
+        self.config
+            .user_interface
+            .startup_focus
+            .as_deref()
+            .and_then(|val| StartupFocus::from_str(val).ok())
+            .unwrap_or_default()
+    }
+
+    /// Set value for `startup_focus` in configuration
+    #[cfg(test)]
+    pub fn set_startup_focus(&mut self, val: StartupFocus) {
+        self.config.user_interface.startup_focus = Some(val.to_string());
+    }
+
     // Remote params
 
     /// Get ssh config path
@@ -227,6 +628,150 @@ impl ConfigClient {
         self.config.remote.ssh_config = p;
     }
 
+    /// Get value of `ssh_compression`
+    pub fn get_ssh_compression(&self) -> bool {
+        self.config.remote.ssh_compression.unwrap_or(false)
+    }
+
+    /// Set new value for `ssh_compression`
+    #[cfg(test)]
+    pub fn set_ssh_compression(&mut self, value: bool) {
+        self.config.remote.ssh_compression = Some(value);
+    }
+
+    /// Get the preferred ciphers of ssh sessions
+    pub fn get_ssh_ciphers(&self) -> Option<&str> {
+        self.config.remote.ssh_ciphers.as_deref()
+    }
+
+    /// Set the preferred ciphers of ssh sessions
+    #[cfg(test)]
+    pub fn set_ssh_ciphers(&mut self, ciphers: Option<String>) {
+        self.config.remote.ssh_ciphers = ciphers;
+    }
+
+    /// Get the attempts to reconnect to the remote when the connection is lost, from `reconnect_retries`,
+    /// up to `MAX_RECONNECT_RETRIES`
+    pub fn get_reconnect_retries(&self) -> u32 {
+        self.config
+            .remote
+            .reconnect_retries
+            .unwrap_or(DEFAULT_RECONNECT_RETRIES)
+            .min(MAX_RECONNECT_RETRIES) as u32
+    }
+
+    /// Set new value for `reconnect_retries`
+    pub fn set_reconnect_retries(&mut self, value: u64) {
+        self.config.remote.reconnect_retries = Some(value);
+    }
+
+    /// Get value of `webdav_lock_uploads`
+    pub fn get_webdav_lock_uploads(&self) -> bool {
+        self.config.remote.webdav_lock_uploads.unwrap_or(false)
+    }
+
+    /// Set new value for `webdav_lock_uploads`
+    #[cfg(test)]
+    pub fn set_webdav_lock_uploads(&mut self, value: bool) {
+        self.config.remote.webdav_lock_uploads = Some(value);
+    }
+
+    /// Get the path of the known hosts file, from `ssh_known_hosts`; `~/.ssh/known_hosts` if it's not set
+    pub fn get_ssh_known_hosts(&self) -> &str {
+        self.config
+            .remote
+            .ssh_known_hosts
+            .as_deref()
+            .unwrap_or(DEFAULT_SSH_KNOWN_HOSTS)
+    }
+
+    /// Set new value for `ssh_known_hosts`
+    #[cfg(test)]
+    pub fn set_ssh_known_hosts(&mut self, p: Option<String>) {
+        self.config.remote.ssh_known_hosts = p;
+    }
+
+    /// Get value of `ssh_accept_any_host_key`
+    pub fn get_ssh_accept_any_host_key(&self) -> bool {
+        self.config.remote.ssh_accept_any_host_key.unwrap_or(false)
+    }
+
+    /// Set new value for `ssh_accept_any_host_key`
+    pub fn set_ssh_accept_any_host_key(&mut self, value: bool) {
+        self.config.remote.ssh_accept_any_host_key = Some(value);
+    }
+
+    /// Get the mode applied to the files uploaded to the remote host, from `default_file_mode`.
+    /// `None` if unset or invalid
+    pub fn get_default_file_mode(&self) -> Option<u32> {
+        self.config
+            .remote
+            .default_file_mode
+            .as_deref()
+            .and_then(parser::parse_octal_mode)
+    }
+
+    /// Set new value for `default_file_mode`
+    pub fn set_default_file_mode(&mut self, mode: Option<u32>) {
+        self.config.remote.default_file_mode = mode.map(|x| format!("{x:04o}"));
+    }
+
+    /// Get the mode applied to the directories created on the remote host, from `default_dir_mode`.
+    /// `None` if unset or invalid
+    pub fn get_default_dir_mode(&self) -> Option<u32> {
+        self.config
+            .remote
+            .default_dir_mode
+            .as_deref()
+            .and_then(parser::parse_octal_mode)
+    }
+
+    /// Set new value for `default_dir_mode`
+    pub fn set_default_dir_mode(&mut self, mode: Option<u32>) {
+        self.config.remote.default_dir_mode = mode.map(|x| format!("{x:04o}"));
+    }
+
+    /// Get how long a session kept connected, when returning to the authentication form, stays open,
+    /// from `parked_session_timeout`. `None` if sessions can't be kept connected
+    pub fn get_parked_session_timeout(&self) -> Option<Duration> {
+        match self
+            .config
+            .remote
+            .parked_session_timeout
+            .unwrap_or(DEFAULT_PARKED_SESSION_TIMEOUT)
+        {
+            0 => None,
+            secs => Some(Duration::from_secs(secs)),
+        }
+    }
+
+    /// Set new value for `parked_session_timeout`
+    pub fn set_parked_session_timeout(&mut self, value: u64) {
+        self.config.remote.parked_session_timeout = Some(value);
+    }
+
+    // Keymap
+
+    /// Get the keybindings of the file explorers
+    pub fn get_keymap(&self) -> &Keymap {
+        &self.keymap
+    }
+
+    /// Read the keymap from `keymap_path`.
+    /// If the file doesn't exist, the default keybindings are kept
+    pub fn read_keymap(&mut self, keymap_path: &Path) -> Result<(), SerializerError> {
+        if !keymap_path.exists() {
+            debug!("Keymap file doesn't exist; using default keybindings");
+            return Ok(());
+        }
+        let reader = File::open(keymap_path)
+            .map_err(|err| SerializerError::new_ex(SerializerErrorKind::Io, err.to_string()))?;
+        let config: KeymapConfig = deserialize(Box::new(reader))?;
+        self.keymap = Keymap::from_config(config)
+            .map_err(|err| SerializerError::new_ex(SerializerErrorKind::Syntax, err.to_string()))?;
+        Ok(())
+    }
+
     // SSH Keys
 
     /// Save a SSH key into configuration.
@@ -362,28 +907,59 @@ impl ConfigClient {
             ));
         }
         // Open bookmarks file for read
-        match OpenOptions::new()
-            .read(true)
-            .open(self.config_path.as_path())
-        {
-            Ok(reader) => {
-                // Deserialize
-                match deserialize(Box::new(reader)) {
-                    Ok(config) => {
-                        self.config = config;
-                        Ok(())
-                    }
-                    Err(err) => Err(err),
-                }
-            }
+        let data = match fs::read_to_string(self.config_path.as_path()) {
+            Ok(data) => data,
             Err(err) => {
                 error!("Failed to read configuration: {}", err);
-                Err(SerializerError::new_ex(
+                return Err(SerializerError::new_ex(
                     SerializerErrorKind::Io,
                     err.to_string(),
-                ))
+                ));
+            }
+        };
+        // Deserialize; if the configuration has a legacy format, migrate it
+        let (mut config, migrated) = match deserialize(Box::new(Cursor::new(data.clone()))) {
+            Ok(config) => (config, None),
+            Err(err) => match migration::migrate_config(&data) {
+                Ok(migrated) => {
+                    info!("Migrating configuration from a legacy format: {}", err);
+                    (migrated.value, Some(migrated.changes))
+                }
+                Err(migration_err) => {
+                    debug!("Configuration can't be migrated: {}", migration_err);
+                    return Err(err);
+                }
+            },
+        };
+        // downgrade bad values to defaults instead of failing
+        for issue in
+            validation::validate_user_config(&mut config, self.config_path.as_path(), None, true)
+        {
+            warn!("{issue}");
+        }
+        self.config = config;
+        if let Some(changes) = migrated {
+            let backup = migration::backup(self.config_path.as_path()).map_err(|e| {
+                error!("Failed to back up configuration: {}", e);
+                SerializerError::new_ex(SerializerErrorKind::Io, e.to_string())
+            })?;
+            self.write_config()?;
+            self.migration_report = std::iter::once(format!(
+                "backed up the original configuration to {}",
+                backup.display()
+            ))
+            .chain(changes)
+            .collect();
+            for change in self.migration_report.iter() {
+                info!("Configuration migration: {}", change);
             }
         }
+        Ok(())
+    }
+
+    /// Take the changes applied migrating the configuration from a legacy format, if it's been migrated
+    pub fn take_migration_report(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.migration_report)
     }
 
     /// Hosts are saved as `username@host` into configuration.
@@ -419,6 +995,7 @@ mod tests {
     use tempfile::TempDir;
 
     use super::*;
+    use crate::config::keymap::KeyAction;
     use crate::config::params::UserConfig;
     use crate::utils::random::random_alphanumeric_with_len;
 
@@ -644,6 +1221,371 @@ mod tests {
         assert_eq!(client.get_notification_threshold(), 64);
     }
 
+    #[test]
+    fn test_system_config_notification_min_duration() {
+        let tmp_dir: TempDir = TempDir::new().ok().unwrap();
+        let (cfg_path, key_path): (PathBuf, PathBuf) = get_paths(tmp_dir.path());
+        let mut client: ConfigClient = ConfigClient::new(cfg_path.as_path(), key_path.as_path())
+            .ok()
+            .unwrap();
+        assert!(client.get_notification_min_duration().is_none());
+        client.set_notification_min_duration_secs(Some(90));
+        assert_eq!(
+            client.get_notification_min_duration(),
+            Some(Duration::from_secs(90))
+        );
+        client.set_notification_min_duration_secs(Some(0));
+        assert!(client.get_notification_min_duration().is_none());
+    }
+
+    #[test]
+    fn test_system_config_notification_unfocused_only() {
+        let tmp_dir: TempDir = TempDir::new().ok().unwrap();
+        let (cfg_path, key_path): (PathBuf, PathBuf) = get_paths(tmp_dir.path());
+        let mut client: ConfigClient = ConfigClient::new(cfg_path.as_path(), key_path.as_path())
+            .ok()
+            .unwrap();
+        assert_eq!(client.get_notification_unfocused_only(), false);
+        client.set_notification_unfocused_only(true);
+        assert_eq!(client.get_notification_unfocused_only(), true);
+    }
+
+    #[test]
+    fn test_system_config_disable_cache() {
+        let tmp_dir: TempDir = TempDir::new().ok().unwrap();
+        let (cfg_path, key_path): (PathBuf, PathBuf) = get_paths(tmp_dir.path());
+        let mut client: ConfigClient = ConfigClient::new(cfg_path.as_path(), key_path.as_path())
+            .ok()
+            .unwrap();
+        assert_eq!(client.get_disable_cache(), false); // Null ?
+        client.set_disable_cache(true);
+        assert_eq!(client.get_disable_cache(), true);
+        client.set_disable_cache(false);
+        assert_eq!(client.get_disable_cache(), false);
+    }
+
+    #[test]
+    fn test_system_config_delete_to_trash() {
+        let tmp_dir: TempDir = TempDir::new().ok().unwrap();
+        let (cfg_path, key_path): (PathBuf, PathBuf) = get_paths(tmp_dir.path());
+        let mut client: ConfigClient = ConfigClient::new(cfg_path.as_path(), key_path.as_path())
+            .ok()
+            .unwrap();
+        assert_eq!(client.get_delete_to_trash(), false); // Null ?
+        client.set_delete_to_trash(true);
+        assert_eq!(client.get_delete_to_trash(), true);
+        client.set_delete_to_trash(false);
+        assert_eq!(client.get_delete_to_trash(), false);
+    }
+
+    #[test]
+    fn test_system_config_preserve_symlinks() {
+        let tmp_dir: TempDir = TempDir::new().ok().unwrap();
+        let (cfg_path, key_path): (PathBuf, PathBuf) = get_paths(tmp_dir.path());
+        let mut client: ConfigClient = ConfigClient::new(cfg_path.as_path(), key_path.as_path())
+            .ok()
+            .unwrap();
+        assert_eq!(client.get_preserve_symlinks(), false); // Null ?
+        client.set_preserve_symlinks(true);
+        assert_eq!(client.get_preserve_symlinks(), true);
+    }
+
+    #[test]
+    fn test_system_config_lock_panels_view() {
+        let tmp_dir: TempDir = TempDir::new().ok().unwrap();
+        let (cfg_path, key_path): (PathBuf, PathBuf) = get_paths(tmp_dir.path());
+        let mut client: ConfigClient = ConfigClient::new(cfg_path.as_path(), key_path.as_path())
+            .ok()
+            .unwrap();
+        assert_eq!(client.get_lock_panels_view(), false); // Null ?
+        client.set_lock_panels_view(true);
+        assert_eq!(client.get_lock_panels_view(), true);
+    }
+
+    #[test]
+    fn should_get_and_set_filter_options() {
+        let tmp_dir: TempDir = TempDir::new().ok().unwrap();
+        let (cfg_path, key_path): (PathBuf, PathBuf) = get_paths(tmp_dir.path());
+        let mut client: ConfigClient = ConfigClient::new(cfg_path.as_path(), key_path.as_path())
+            .ok()
+            .unwrap();
+        assert_eq!(client.get_filter_mode(), FilterMode::Glob);
+        client.set_filter_mode(FilterMode::Regex);
+        assert_eq!(client.get_filter_mode(), FilterMode::Regex);
+        client.config.user_interface.filter_mode = Some(String::from("fuzzy"));
+        assert_eq!(client.get_filter_mode(), FilterMode::Glob);
+        assert_eq!(client.get_keep_filter(), false);
+        client.set_keep_filter(true);
+        assert_eq!(client.get_keep_filter(), true);
+    }
+
+    #[test]
+    fn should_get_and_set_confirm_edit_upload_with_diff() {
+        let tmp_dir: TempDir = TempDir::new().ok().unwrap();
+        let (cfg_path, key_path): (PathBuf, PathBuf) = get_paths(tmp_dir.path());
+        let mut client: ConfigClient = ConfigClient::new(cfg_path.as_path(), key_path.as_path())
+            .ok()
+            .unwrap();
+        assert_eq!(client.get_confirm_edit_upload_with_diff(), false);
+        client.set_confirm_edit_upload_with_diff(true);
+        assert_eq!(client.get_confirm_edit_upload_with_diff(), true);
+    }
+
+    #[test]
+    fn should_get_and_set_watcher_ignore() {
+        let tmp_dir: TempDir = TempDir::new().ok().unwrap();
+        let (cfg_path, key_path): (PathBuf, PathBuf) = get_paths(tmp_dir.path());
+        let mut client: ConfigClient = ConfigClient::new(cfg_path.as_path(), key_path.as_path())
+            .ok()
+            .unwrap();
+        assert_eq!(
+            client.get_watcher_ignore().patterns(),
+            &[".git/", "*.swp", "*~", ".DS_Store"]
+        );
+        client.set_watcher_ignore(Some(vec![String::from("target/")]));
+        assert_eq!(client.get_watcher_ignore().patterns(), &["target/"]);
+        // an empty list ignores nothing
+        client.set_watcher_ignore(Some(vec![]));
+        assert!(client.get_watcher_ignore().is_empty());
+    }
+
+    #[test]
+    fn should_get_and_set_remote_watcher_interval() {
+        let tmp_dir: TempDir = TempDir::new().ok().unwrap();
+        let (cfg_path, key_path): (PathBuf, PathBuf) = get_paths(tmp_dir.path());
+        let mut client: ConfigClient = ConfigClient::new(cfg_path.as_path(), key_path.as_path())
+            .ok()
+            .unwrap();
+        assert_eq!(
+            client.get_remote_watcher_interval(),
+            Duration::from_secs(30)
+        );
+        client.set_remote_watcher_interval(5);
+        assert_eq!(client.get_remote_watcher_interval(), Duration::from_secs(5));
+        client.set_remote_watcher_interval(0);
+        assert_eq!(client.get_remote_watcher_interval(), Duration::from_secs(1));
+    }
+
+    #[test]
+    fn should_get_and_set_recents_size() {
+        let tmp_dir: TempDir = TempDir::new().ok().unwrap();
+        let (cfg_path, key_path): (PathBuf, PathBuf) = get_paths(tmp_dir.path());
+        let mut client: ConfigClient = ConfigClient::new(cfg_path.as_path(), key_path.as_path())
+            .ok()
+            .unwrap();
+        assert_eq!(client.get_recents_size(), 16);
+        client.set_recents_size(32);
+        assert_eq!(client.get_recents_size(), 32);
+        client.set_recents_size(0);
+        assert_eq!(client.get_recents_size(), 1);
+    }
+
+    #[test]
+    fn should_get_and_set_password_prompt_asterisks() {
+        let tmp_dir: TempDir = TempDir::new().ok().unwrap();
+        let (cfg_path, key_path): (PathBuf, PathBuf) = get_paths(tmp_dir.path());
+        let mut client: ConfigClient = ConfigClient::new(cfg_path.as_path(), key_path.as_path())
+            .ok()
+            .unwrap();
+        assert_eq!(client.get_password_prompt_asterisks(), false);
+        client.set_password_prompt_asterisks(true);
+        assert_eq!(client.get_password_prompt_asterisks(), true);
+    }
+
+    #[test]
+    fn should_get_and_set_mouse_capture() {
+        let tmp_dir: TempDir = TempDir::new().ok().unwrap();
+        let (cfg_path, key_path): (PathBuf, PathBuf) = get_paths(tmp_dir.path());
+        let mut client: ConfigClient = ConfigClient::new(cfg_path.as_path(), key_path.as_path())
+            .ok()
+            .unwrap();
+        assert_eq!(client.get_mouse_capture(), false);
+        client.set_mouse_capture(true);
+        assert_eq!(client.get_mouse_capture(), true);
+    }
+
+    #[test]
+    fn should_get_and_set_memory_bounds() {
+        let tmp_dir: TempDir = TempDir::new().ok().unwrap();
+        let (cfg_path, key_path): (PathBuf, PathBuf) = get_paths(tmp_dir.path());
+        let mut client: ConfigClient = ConfigClient::new(cfg_path.as_path(), key_path.as_path())
+            .ok()
+            .unwrap();
+        assert_eq!(client.get_log_message_max_len(), 4096);
+        assert_eq!(client.get_log_max_bytes(), 1048576);
+        assert_eq!(client.get_find_spill_threshold(), 50000);
+        client.set_log_message_max_len(0);
+        client.set_log_max_bytes(65536);
+        client.set_find_spill_threshold(100);
+        assert_eq!(client.get_log_message_max_len(), 0);
+        assert_eq!(client.get_log_max_bytes(), 65536);
+        assert_eq!(client.get_find_spill_threshold(), 100);
+        client.config.user_interface.find_spill_threshold = None;
+        assert_eq!(client.get_find_spill_threshold(), 50000);
+    }
+
+    #[test]
+    fn test_system_config_preserve_metadata() {
+        let tmp_dir: TempDir = TempDir::new().ok().unwrap();
+        let (cfg_path, key_path): (PathBuf, PathBuf) = get_paths(tmp_dir.path());
+        let mut client: ConfigClient = ConfigClient::new(cfg_path.as_path(), key_path.as_path())
+            .ok()
+            .unwrap();
+        assert_eq!(client.get_preserve_metadata(), true); // Null ?
+        client.set_preserve_metadata(false);
+        assert_eq!(client.get_preserve_metadata(), false);
+    }
+
+    #[test]
+    fn test_system_config_check_bookmarks_health() {
+        let tmp_dir: TempDir = TempDir::new().ok().unwrap();
+        let (cfg_path, key_path): (PathBuf, PathBuf) = get_paths(tmp_dir.path());
+        let mut client: ConfigClient = ConfigClient::new(cfg_path.as_path(), key_path.as_path())
+            .ok()
+            .unwrap();
+        assert_eq!(client.get_check_bookmarks_health(), false); // Null ?
+        client.set_check_bookmarks_health(true);
+        assert_eq!(client.get_check_bookmarks_health(), true);
+        client.set_check_bookmarks_health(false);
+        assert_eq!(client.get_check_bookmarks_health(), false);
+    }
+
+    #[test]
+    fn test_system_config_terminal_bell() {
+        let tmp_dir: TempDir = TempDir::new().ok().unwrap();
+        let (cfg_path, key_path): (PathBuf, PathBuf) = get_paths(tmp_dir.path());
+        let mut client: ConfigClient = ConfigClient::new(cfg_path.as_path(), key_path.as_path())
+            .ok()
+            .unwrap();
+        assert_eq!(client.get_terminal_bell(), TerminalBell::Off); // Null ?
+        client.set_terminal_bell(TerminalBell::Both);
+        assert_eq!(client.get_terminal_bell(), TerminalBell::Both);
+        client.config.user_interface.terminal_bell = Some(String::from("sometimes"));
+        assert_eq!(client.get_terminal_bell(), TerminalBell::Off);
+    }
+
+    #[test]
+    fn test_system_config_transfer_prompt_thresholds() {
+        let tmp_dir: TempDir = TempDir::new().ok().unwrap();
+        let (cfg_path, key_path): (PathBuf, PathBuf) = get_paths(tmp_dir.path());
+        let mut client: ConfigClient = ConfigClient::new(cfg_path.as_path(), key_path.as_path())
+            .ok()
+            .unwrap();
+        assert_eq!(
+            client.get_transfer_prompt_files_threshold(),
+            DEFAULT_TRANSFER_PROMPT_FILES_THRESHOLD
+        );
+        assert_eq!(
+            client.get_transfer_prompt_size_threshold(),
+            DEFAULT_TRANSFER_PROMPT_SIZE_THRESHOLD
+        );
+        client.set_transfer_prompt_files_threshold(500);
+        assert_eq!(client.get_transfer_prompt_files_threshold(), 500);
+        client.set_transfer_prompt_size_threshold(0);
+        assert_eq!(client.get_transfer_prompt_size_threshold(), 0);
+        client.config.user_interface.transfer_prompt_files_threshold = None;
+        assert_eq!(
+            client.get_transfer_prompt_files_threshold(),
+            DEFAULT_TRANSFER_PROMPT_FILES_THRESHOLD
+        );
+    }
+
+    #[test]
+    fn test_system_config_delete_confirm_name_threshold() {
+        let tmp_dir: TempDir = TempDir::new().ok().unwrap();
+        let (cfg_path, key_path): (PathBuf, PathBuf) = get_paths(tmp_dir.path());
+        let mut client: ConfigClient = ConfigClient::new(cfg_path.as_path(), key_path.as_path())
+            .ok()
+            .unwrap();
+        assert_eq!(
+            client.get_delete_confirm_name_threshold(),
+            DEFAULT_DELETE_CONFIRM_NAME_THRESHOLD
+        );
+        client.set_delete_confirm_name_threshold(0);
+        assert_eq!(client.get_delete_confirm_name_threshold(), 0);
+        client.config.user_interface.delete_confirm_name_threshold = None;
+        assert_eq!(
+            client.get_delete_confirm_name_threshold(),
+            DEFAULT_DELETE_CONFIRM_NAME_THRESHOLD
+        );
+    }
+
+    #[test]
+    fn test_system_config_transfer_concurrency() {
+        let tmp_dir: TempDir = TempDir::new().ok().unwrap();
+        let (cfg_path, key_path): (PathBuf, PathBuf) = get_paths(tmp_dir.path());
+        let mut client: ConfigClient = ConfigClient::new(cfg_path.as_path(), key_path.as_path())
+            .ok()
+            .unwrap();
+        assert_eq!(client.get_transfer_concurrency(), 1);
+        client.set_transfer_concurrency(4);
+        assert_eq!(client.get_transfer_concurrency(), 4);
+        client.set_transfer_concurrency(0);
+        assert_eq!(client.get_transfer_concurrency(), 1);
+        client.set_transfer_concurrency(1024);
+        assert_eq!(
+            client.get_transfer_concurrency(),
+            MAX_TRANSFER_CONCURRENCY as usize
+        );
+        client.config.user_interface.transfer_concurrency = None;
+        assert_eq!(client.get_transfer_concurrency(), 1);
+    }
+
+    #[test]
+    fn test_system_config_transfer_buffer_size() {
+        let tmp_dir: TempDir = TempDir::new().ok().unwrap();
+        let (cfg_path, key_path): (PathBuf, PathBuf) = get_paths(tmp_dir.path());
+        let mut client: ConfigClient = ConfigClient::new(cfg_path.as_path(), key_path.as_path())
+            .ok()
+            .unwrap();
+        assert_eq!(client.get_transfer_buffer_size(), 256 * 1024);
+        client.set_transfer_buffer_kb(64);
+        assert_eq!(client.get_transfer_buffer_size(), 64 * 1024);
+        client.set_transfer_buffer_kb(0);
+        assert_eq!(client.get_transfer_buffer_size(), 1024);
+        client.set_transfer_buffer_kb(u64::MAX);
+        assert_eq!(
+            client.get_transfer_buffer_size(),
+            MAX_TRANSFER_BUFFER_KB as usize * 1024
+        );
+        client.config.user_interface.transfer_buffer_kb = None;
+        assert_eq!(client.get_transfer_buffer_size(), 256 * 1024);
+    }
+
+    #[test]
+    fn test_system_config_dirstack_size() {
+        let tmp_dir: TempDir = TempDir::new().ok().unwrap();
+        let (cfg_path, key_path): (PathBuf, PathBuf) = get_paths(tmp_dir.path());
+        let mut client: ConfigClient = ConfigClient::new(cfg_path.as_path(), key_path.as_path())
+            .ok()
+            .unwrap();
+        assert_eq!(client.get_dirstack_size(), 16);
+        client.set_dirstack_size(32);
+        assert_eq!(client.get_dirstack_size(), 32);
+        client.set_dirstack_size(0);
+        assert_eq!(client.get_dirstack_size(), 1);
+        client.set_dirstack_size(100000);
+        assert_eq!(client.get_dirstack_size(), MAX_DIRSTACK_SIZE as usize);
+        client.config.user_interface.dirstack_size = None;
+        assert_eq!(client.get_dirstack_size(), 16);
+    }
+
+    #[test]
+    fn test_system_config_startup_focus() {
+        let tmp_dir: TempDir = TempDir::new().ok().unwrap();
+        let (cfg_path, key_path): (PathBuf, PathBuf) = get_paths(tmp_dir.path());
+        let mut client: ConfigClient = ConfigClient::new(cfg_path.as_path(), key_path.as_path())
+            .ok()
+            .unwrap();
+        assert_eq!(client.get_startup_focus(), StartupFocus::Form);
+        client.set_startup_focus(StartupFocus::Recents);
+        assert_eq!(client.get_startup_focus(), StartupFocus::Recents);
+        client.config.user_interface.startup_focus = Some(String::from("Bookmarks"));
+        assert_eq!(client.get_startup_focus(), StartupFocus::Bookmarks);
+        client.config.user_interface.startup_focus = Some(String::from("nowhere"));
+        assert_eq!(client.get_startup_focus(), StartupFocus::Form);
+    }
+
     #[test]
     fn should_get_and_set_ssh_config_dir() {
         let tmp_dir: TempDir = TempDir::new().ok().unwrap();
@@ -669,6 +1611,152 @@ mod tests {
         assert_eq!(client.get_ssh_config(), None);
     }
 
+    #[test]
+    fn should_get_and_set_ssh_session_prefs() {
+        let tmp_dir: TempDir = TempDir::new().ok().unwrap();
+        let (cfg_path, key_path): (PathBuf, PathBuf) = get_paths(tmp_dir.path());
+        let mut client: ConfigClient = ConfigClient::new(cfg_path.as_path(), key_path.as_path())
+            .ok()
+            .unwrap();
+        assert_eq!(client.get_ssh_compression(), false); // Null ?
+        assert!(client.get_ssh_ciphers().is_none());
+        client.set_ssh_compression(true);
+        client.set_ssh_ciphers(Some(String::from("aes256-ctr")));
+        assert_eq!(client.get_ssh_compression(), true);
+        assert_eq!(client.get_ssh_ciphers(), Some("aes256-ctr"));
+    }
+
+    #[test]
+    fn should_get_and_set_reconnect_retries() {
+        let tmp_dir: TempDir = TempDir::new().ok().unwrap();
+        let (cfg_path, key_path): (PathBuf, PathBuf) = get_paths(tmp_dir.path());
+        let mut client: ConfigClient = ConfigClient::new(cfg_path.as_path(), key_path.as_path())
+            .ok()
+            .unwrap();
+        assert_eq!(client.get_reconnect_retries(), 3);
+        client.set_reconnect_retries(0);
+        assert_eq!(client.get_reconnect_retries(), 0);
+        client.set_reconnect_retries(1000);
+        assert_eq!(client.get_reconnect_retries(), 10);
+        client.config.remote.reconnect_retries = None;
+        assert_eq!(client.get_reconnect_retries(), 3);
+    }
+
+    #[test]
+    fn should_get_and_set_parked_session_timeout() {
+        let tmp_dir: TempDir = TempDir::new().ok().unwrap();
+        let (cfg_path, key_path): (PathBuf, PathBuf) = get_paths(tmp_dir.path());
+        let mut client: ConfigClient = ConfigClient::new(cfg_path.as_path(), key_path.as_path())
+            .ok()
+            .unwrap();
+        assert_eq!(
+            client.get_parked_session_timeout(),
+            Some(Duration::from_secs(300))
+        );
+        client.set_parked_session_timeout(60);
+        assert_eq!(
+            client.get_parked_session_timeout(),
+            Some(Duration::from_secs(60))
+        );
+        client.set_parked_session_timeout(0);
+        assert_eq!(client.get_parked_session_timeout(), None);
+    }
+
+    #[test]
+    fn should_get_and_set_default_modes() {
+        let tmp_dir: TempDir = TempDir::new().ok().unwrap();
+        let (cfg_path, key_path): (PathBuf, PathBuf) = get_paths(tmp_dir.path());
+        let mut client: ConfigClient = ConfigClient::new(cfg_path.as_path(), key_path.as_path())
+            .ok()
+            .unwrap();
+        assert!(client.get_default_file_mode().is_none());
+        assert!(client.get_default_dir_mode().is_none());
+        client.set_default_file_mode(Some(0o664));
+        client.set_default_dir_mode(Some(0o2775));
+        assert_eq!(client.get_default_file_mode(), Some(0o664));
+        assert_eq!(client.get_default_dir_mode(), Some(0o2775));
+        assert_eq!(
+            client.config.remote.default_file_mode.as_deref(),
+            Some("0664")
+        );
+        client.config.remote.default_dir_mode = Some(String::from("rwxr-xr-x"));
+        assert!(client.get_default_dir_mode().is_none());
+        client.set_default_file_mode(None);
+        assert!(client.get_default_file_mode().is_none());
+    }
+
+    #[test]
+    fn should_get_and_set_ssh_host_key_verification() {
+        let tmp_dir: TempDir = TempDir::new().ok().unwrap();
+        let (cfg_path, key_path): (PathBuf, PathBuf) = get_paths(tmp_dir.path());
+        let mut client: ConfigClient = ConfigClient::new(cfg_path.as_path(), key_path.as_path())
+            .ok()
+            .unwrap();
+        assert!(client.get_ssh_known_hosts().ends_with("known_hosts"));
+        assert_eq!(client.get_ssh_accept_any_host_key(), false);
+        client.set_ssh_known_hosts(Some(String::from("/tmp/known_hosts")));
+        client.set_ssh_accept_any_host_key(true);
+        assert_eq!(client.get_ssh_known_hosts(), "/tmp/known_hosts");
+        assert_eq!(client.get_ssh_accept_any_host_key(), true);
+        client.set_ssh_known_hosts(None);
+        assert_eq!(client.get_ssh_known_hosts(), DEFAULT_SSH_KNOWN_HOSTS);
+    }
+
+    #[test]
+    fn should_get_and_set_webdav_lock_uploads() {
+        let tmp_dir: TempDir = TempDir::new().ok().unwrap();
+        let (cfg_path, key_path): (PathBuf, PathBuf) = get_paths(tmp_dir.path());
+        let mut client: ConfigClient = ConfigClient::new(cfg_path.as_path(), key_path.as_path())
+            .ok()
+            .unwrap();
+        assert_eq!(client.get_webdav_lock_uploads(), false);
+        client.set_webdav_lock_uploads(true);
+        assert_eq!(client.get_webdav_lock_uploads(), true);
+    }
+
+    #[test]
+    fn should_read_keymap() {
+        let tmp_dir: TempDir = TempDir::new().ok().unwrap();
+        let (cfg_path, key_path): (PathBuf, PathBuf) = get_paths(tmp_dir.path());
+        let keymap_path = tmp_dir.path().join("keys.toml");
+        let mut client: ConfigClient = ConfigClient::new(cfg_path.as_path(), key_path.as_path())
+            .ok()
+            .unwrap();
+        // missing file
+        assert!(client.read_keymap(keymap_path.as_path()).is_ok());
+        assert_eq!(client.get_keymap(), &Keymap::default());
+        // remapped keys
+        std::fs::write(
+            keymap_path.as_path(),
+            "toggle_hidden = \"CTRL+H\"\ncopy = [\"F5\", \"ALT+C\"]\n",
+        )
+        .unwrap();
+        assert!(client.read_keymap(keymap_path.as_path()).is_ok());
+        assert_eq!(
+            client
+                .get_keymap()
+                .fmt_bindings(KeyAction::ToggleHidden)
+                .as_str(),
+            "<CTRL+H>"
+        );
+        assert_eq!(
+            client.get_keymap().fmt_bindings(KeyAction::Copy).as_str(),
+            "<F5|ALT+C>"
+        );
+        // duplicate keys
+        std::fs::write(keymap_path.as_path(), "mkdir = \"n\"\n").unwrap();
+        let err = client.read_keymap(keymap_path.as_path()).unwrap_err();
+        assert_eq!(
+            err.to_string().as_str(),
+            r#"Syntax error (key <N> is bound to both "mkdir" and "new_file")"#
+        );
+        // keeps the last valid keymap
+        assert_eq!(
+            client.get_keymap().fmt_bindings(KeyAction::Copy).as_str(),
+            "<F5|ALT+C>"
+        );
+    }
+
     #[test]
     fn test_system_config_ssh_keys() {
         let tmp_dir: TempDir = TempDir::new().ok().unwrap();
@@ -724,6 +1812,31 @@ mod tests {
         assert_eq!(err.to_string(), "IO error (permission denied)");
     }
 
+    #[test]
+    fn test_system_config_migrate_legacy_file() {
+        let tmp_dir: TempDir = TempDir::new().ok().unwrap();
+        let (cfg_path, key_path): (PathBuf, PathBuf) = get_paths(tmp_dir.path());
+        let legacy = include_str!("../config/fixtures/config_missing_tables.toml");
+        std::fs::write(cfg_path.as_path(), legacy).unwrap();
+        let mut client: ConfigClient = ConfigClient::new(cfg_path.as_path(), key_path.as_path())
+            .ok()
+            .unwrap();
+        assert_eq!(client.degraded, false);
+        assert_eq!(client.get_default_protocol(), FileTransferProtocol::AwsS3);
+        let report = client.take_migration_report();
+        assert_eq!(report.len(), 6);
+        assert!(report[0].starts_with("backed up the original configuration to"));
+        let mut backup = cfg_path.clone().into_os_string();
+        backup.push(".bak");
+        assert_eq!(std::fs::read_to_string(backup).unwrap(), legacy);
+        // the migrated file is loaded as it is
+        let mut client: ConfigClient = ConfigClient::new(cfg_path.as_path(), key_path.as_path())
+            .ok()
+            .unwrap();
+        assert_eq!(client.get_default_protocol(), FileTransferProtocol::AwsS3);
+        assert!(client.take_migration_report().is_empty());
+    }
+
     /// Get paths for configuration and keys directory
     fn get_paths(dir: &Path) -> (PathBuf, PathBuf) {
         let mut k: PathBuf = PathBuf::from(dir);
@@ -0,0 +1,165 @@
+//! ## ConnectionTest
+//!
+//! `connection_test` is the module which tries the connection parameters of a remote host in a background thread,
+//! connecting and listing the working directory, without entering the file transfer activity.
+
+use std::sync::mpsc::{channel, Receiver, TryRecvError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use remotefs::{RemoteFs, RemoteResult};
+
+use crate::filetransfer::host_key::HostKeyStatus;
+use crate::filetransfer::{FileTransferParams, RemoteFsBuilder, SshNegotiationHandle};
+use crate::system::config_client::ConfigClient;
+
+/// Time after which a connection test which hasn't terminated is reported as failed
+pub const TEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Connection test running in a background thread
+pub struct ConnectionTest {
+    receiver: Receiver<Result<String, String>>,
+    started_at: Instant,
+    timeout: Duration,
+}
+
+impl ConnectionTest {
+    /// Start connecting to the host described by `params` in a background thread
+    pub fn start(
+        params: FileTransferParams,
+        config_client: ConfigClient,
+        timeout: Duration,
+    ) -> Self {
+        let (sender, receiver) = channel();
+        thread::spawn(move || {
+            let result = run(&params, &config_client);
+            debug!(
+                "connection test to {:?} terminated: {result:?}",
+                params.protocol
+            );
+            let _ = sender.send(result);
+        });
+        Self {
+            receiver,
+            started_at: Instant::now(),
+            timeout,
+        }
+    }
+
+    /// Get the outcome of the test, if it has terminated: the description of the connection or the error.
+    /// Once the timeout has elapsed the test is reported as failed, while its thread is left to terminate on its own
+    pub fn poll(&self) -> Option<Result<String, String>> {
+        match self.receiver.try_recv() {
+            Ok(result) => Some(result),
+            Err(TryRecvError::Disconnected) => {
+                Some(Err(String::from("the test terminated unexpectedly")))
+            }
+            Err(TryRecvError::Empty) if self.started_at.elapsed() >= self.timeout => Some(Err(
+                format!("no response after {} seconds", self.timeout.as_secs()),
+            )),
+            Err(TryRecvError::Empty) => None,
+        }
+    }
+}
+
+/// Connect to the host and list the working directory, then disconnect
+fn run(params: &FileTransferParams, config_client: &ConfigClient) -> Result<String, String> {
+    let negotiation = SshNegotiationHandle::default();
+    let mut client = RemoteFsBuilder::build_connection(params, negotiation.clone(), config_client);
+    let result = list_wrkdir(client.as_mut(), params);
+    if client.is_connected() {
+        let _ = client.disconnect();
+    }
+    result.map_err(|err| {
+        let err = err.to_string();
+        let err = match negotiation.rejected_host_key() {
+            Some(rejected) if rejected.status == HostKeyStatus::Unknown => format!(
+                "{err}: the {} key of the host ({}) will be asked to be accepted when connecting",
+                rejected.key.key_type(),
+                rejected.key.fingerprint()
+            ),
+            _ => err,
+        };
+        match params.protocol.error_hint(&err) {
+            Some(hint) => format!("{err}: {hint}"),
+            None => err,
+        }
+    })
+}
+
+fn list_wrkdir(client: &mut dyn RemoteFs, params: &FileTransferParams) -> RemoteResult<String> {
+    client.connect()?;
+    let wrkdir = match params.remote_path.as_deref() {
+        Some(path) => client.change_dir(path)?,
+        None => client.pwd()?,
+    };
+    let entries = client.list_dir(wrkdir.as_path())?;
+    Ok(format!(
+        "Connected successfully: \"{}\" contains {} entries",
+        wrkdir.display(),
+        entries.len()
+    ))
+}
+
+#[cfg(test)]
+mod test {
+
+    use std::path::Path;
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::filetransfer::{FileTransferProtocol, ProtocolParams};
+
+    fn localhost_params(path: &Path) -> FileTransferParams {
+        FileTransferParams::new(FileTransferProtocol::Localhost, ProtocolParams::default())
+            .remote_path(Some(path))
+    }
+
+    fn wait(test: ConnectionTest) -> Result<String, String> {
+        loop {
+            if let Some(result) = test.poll() {
+                return result;
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    #[test]
+    fn should_test_connection() {
+        let tempdir = tempfile::tempdir().unwrap();
+        std::fs::write(tempdir.path().join("a.txt"), "a").unwrap();
+        let test = ConnectionTest::start(
+            localhost_params(tempdir.path()),
+            ConfigClient::degraded(),
+            TEST_TIMEOUT,
+        );
+        let message = wait(test).unwrap();
+        assert!(message.ends_with("contains 1 entries"));
+    }
+
+    #[test]
+    fn should_report_connection_errors() {
+        let tempdir = tempfile::tempdir().unwrap();
+        let test = ConnectionTest::start(
+            localhost_params(tempdir.path().join("missing").as_path()),
+            ConfigClient::degraded(),
+            TEST_TIMEOUT,
+        );
+        assert!(wait(test).is_err());
+    }
+
+    #[test]
+    fn should_time_out_connection_test() {
+        let (_sender, receiver) = channel();
+        let test = ConnectionTest {
+            receiver,
+            started_at: Instant::now(),
+            timeout: Duration::ZERO,
+        };
+        assert_eq!(
+            test.poll(),
+            Some(Err(String::from("no response after 0 seconds")))
+        );
+    }
+}
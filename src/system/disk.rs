@@ -0,0 +1,54 @@
+//! ## Disk
+//!
+//! `disk` is the module which queries the file systems of the local host
+
+use std::io;
+use std::path::Path;
+
+/// Get the space available to the current user, in bytes, on the file system containing `path`
+#[cfg(posix)]
+pub fn available_space(path: &Path) -> io::Result<u64> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let path = CString::new(path.as_os_str().as_bytes())
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+    let mut stat = std::mem::MaybeUninit::<libc::statvfs>::uninit();
+    if unsafe { libc::statvfs(path.as_ptr(), stat.as_mut_ptr()) } != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    let stat = unsafe { stat.assume_init() };
+    // NOTE: the field types depend on the platform
+    #[allow(clippy::useless_conversion)]
+    Ok(u64::from(stat.f_bavail).saturating_mul(u64::from(stat.f_frsize)))
+}
+
+/// Get the space available to the current user, in bytes, on the drive containing `path`
+#[cfg(win)]
+pub fn available_space(path: &Path) -> io::Result<u64> {
+    use std::os::windows::ffi::OsStrExt;
+
+    use windows::core::PCWSTR;
+    use windows::Win32::Storage::FileSystem::GetDiskFreeSpaceExW;
+
+    let path: Vec<u16> = path.as_os_str().encode_wide().chain([0]).collect();
+    let mut available = 0;
+    unsafe { GetDiskFreeSpaceExW(PCWSTR(path.as_ptr()), Some(&mut available), None, None) }
+        .map_err(io::Error::other)?;
+    Ok(available)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use tempfile::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn should_get_available_space() {
+        let tmpdir = TempDir::new().unwrap();
+        assert!(available_space(tmpdir.path()).unwrap() > 0);
+        assert!(available_space(&tmpdir.path().join("missing")).is_err());
+    }
+}
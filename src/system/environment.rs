@@ -98,6 +98,50 @@ pub fn get_theme_path(config_dir: &Path) -> PathBuf {
     theme_file
 }
 
+/// Get path of the keymap file
+/// Returns: path of keys.toml
+pub fn get_keymap_path(config_dir: &Path) -> PathBuf {
+    let mut keymap_file: PathBuf = PathBuf::from(config_dir);
+    keymap_file.push("keys.toml");
+    keymap_file
+}
+
+/// Get path of the history of the executed commands
+/// Returns: path of exec_history
+pub fn get_exec_history_path(config_dir: &Path) -> PathBuf {
+    let mut history_file: PathBuf = PathBuf::from(config_dir);
+    history_file.push("exec_history");
+    history_file
+}
+
+/// Terminals which can't display colors
+const MONOCHROME_TERMS: &[&str] = &["dumb", "vt52", "vt100", "vt102", "vt220", "vt320"];
+
+/// Returns whether colors shouldn't be used: either the user disabled them with the `NO_COLOR` environment variable,
+/// or the terminal (`TERM`) can't display them, unless `COLORTERM` is set
+pub fn is_monochrome_terminal() -> bool {
+    is_monochrome(
+        std::env::var("NO_COLOR").ok().as_deref(),
+        std::env::var("TERM").ok().as_deref(),
+        std::env::var("COLORTERM").ok().as_deref(),
+    )
+}
+
+fn is_monochrome(no_color: Option<&str>, term: Option<&str>, colorterm: Option<&str>) -> bool {
+    if no_color.is_some_and(|x| !x.is_empty()) {
+        return true;
+    }
+    if colorterm.is_some_and(|x| !x.is_empty()) {
+        return false;
+    }
+    // e.g. `vt100-am` is a variant of `vt100`
+    term.is_some_and(|term| {
+        MONOCHROME_TERMS
+            .iter()
+            .any(|x| term == *x || term.strip_prefix(x).is_some_and(|x| x.starts_with('-')))
+    })
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -187,4 +231,26 @@ mod tests {
             PathBuf::from("/home/omar/.config/termscp/theme.toml"),
         );
     }
+
+    #[test]
+    fn should_get_exec_history_path() {
+        assert_eq!(
+            get_exec_history_path(Path::new("/home/omar/.config/termscp/")),
+            PathBuf::from("/home/omar/.config/termscp/exec_history"),
+        );
+    }
+
+    #[test]
+    fn should_tell_whether_terminal_is_monochrome() {
+        assert_eq!(is_monochrome(None, Some("xterm-256color"), None), false);
+        assert_eq!(is_monochrome(Some("1"), Some("xterm-256color"), None), true);
+        // empty NO_COLOR is ignored
+        assert_eq!(is_monochrome(Some(""), Some("xterm"), None), false);
+        assert_eq!(is_monochrome(None, Some("dumb"), None), true);
+        assert_eq!(is_monochrome(None, Some("vt100"), None), true);
+        assert_eq!(is_monochrome(None, Some("vt220-am"), None), true);
+        assert_eq!(is_monochrome(None, Some("vt1000"), None), false);
+        assert_eq!(is_monochrome(None, Some("vt100"), Some("truecolor")), false);
+        assert_eq!(is_monochrome(None, None, None), false);
+    }
 }
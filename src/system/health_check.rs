@@ -0,0 +1,284 @@
+//! ## HealthCheck
+//!
+//! `health_check` is the module which provides a lightweight prober to check whether hosts are reachable.
+//! The prober only tries to open a TCP connection to the host; no handshake or authentication is performed.
+
+use std::collections::{HashMap, HashSet};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::filetransfer::{FileTransferParams, ProtocolParams};
+
+/// Timeout for the connection attempt
+const PROBE_TIMEOUT: Duration = Duration::from_secs(3);
+/// Time for which a probe result is considered valid
+const CACHE_TTL: Duration = Duration::from_secs(180);
+
+/// Describes the health of a host
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum HostHealth {
+    Reachable,
+    Unreachable,
+}
+
+/// Health checker probes hosts in background threads and caches the results
+pub struct HealthChecker {
+    results: HashMap<String, (HostHealth, Instant)>,
+    pending: HashSet<String>,
+    sender: Sender<(String, HostHealth)>,
+    receiver: Receiver<(String, HostHealth)>,
+}
+
+impl Default for HealthChecker {
+    fn default() -> Self {
+        let (sender, receiver) = channel();
+        Self {
+            results: HashMap::new(),
+            pending: HashSet::new(),
+            sender,
+            receiver,
+        }
+    }
+}
+
+impl HealthChecker {
+    /// Start probing the host described by `params` and associate the result to `key`.
+    /// If a fresh result is already cached or a probe is already running, nothing happens
+    pub fn check(&mut self, key: &str, params: &FileTransferParams) {
+        if self.pending.contains(key) || self.get(key).is_some() {
+            return;
+        }
+        let Some((address, port)) = probe_address(&params.params) else {
+            debug!("no address to probe for {key}");
+            return;
+        };
+        self.pending.insert(key.to_string());
+        let sender = self.sender.clone();
+        let key = key.to_string();
+        thread::spawn(move || {
+            let health = probe(&address, port);
+            debug!("health check for {key} ({address}:{port}): {health:?}");
+            let _ = sender.send((key, health));
+        });
+    }
+
+    /// Collect the results of the probes which have terminated.
+    /// Returns whether at least one result has been received
+    pub fn poll(&mut self) -> bool {
+        let mut changed = false;
+        while let Ok((key, health)) = self.receiver.try_recv() {
+            self.pending.remove(&key);
+            self.results.insert(key, (health, Instant::now()));
+            changed = true;
+        }
+        changed
+    }
+
+    /// Get the health for `key`, if a result not older than the cache TTL exists
+    pub fn get(&self, key: &str) -> Option<HostHealth> {
+        self.results
+            .get(key)
+            .filter(|(_, checked_at)| checked_at.elapsed() < CACHE_TTL)
+            .map(|(health, _)| *health)
+    }
+}
+
+/// Get the address and the port to probe for the provided protocol params.
+/// Returns `None` if the address can't be determined
+fn probe_address(params: &ProtocolParams) -> Option<(String, u16)> {
+    match params {
+        ProtocolParams::Generic(params) => Some((params.address.clone(), params.port)),
+        #[cfg(posix)]
+        ProtocolParams::Smb(params) => Some((params.address.clone(), params.port)),
+        #[cfg(win)]
+        ProtocolParams::Smb(params) => Some((params.address.clone(), 445)),
+        ProtocolParams::WebDAV(params) => parse_url_address(&params.uri),
+        ProtocolParams::AwsS3(params) => match (params.endpoint.as_deref(), &params.region) {
+            (Some(endpoint), _) => parse_url_address(endpoint),
+            (None, Some(region)) => Some((format!("s3.{region}.amazonaws.com"), 443)),
+            (None, None) => None,
+        },
+        ProtocolParams::Kube(params) => params.cluster_url.as_deref().and_then(parse_url_address),
+    }
+}
+
+/// Extract host and port from an URL, such as `https://example.com:8443/path`.
+/// If the port is not specified, it is deduced from the scheme
+fn parse_url_address(url: &str) -> Option<(String, u16)> {
+    let (default_port, rest) = match url.split_once("://") {
+        Some((scheme, rest)) if scheme.eq_ignore_ascii_case("https") => (443, rest),
+        Some((_, rest)) => (80, rest),
+        None => (80, url),
+    };
+    // strip path and credentials
+    let authority = rest.split('/').next()?.rsplit('@').next()?;
+    let (host, port) = match authority.strip_prefix('[') {
+        // ipv6 address
+        Some(authority) => {
+            let (host, port) = authority.split_once(']')?;
+            (host, port.strip_prefix(':'))
+        }
+        None => match authority.split_once(':') {
+            Some((host, port)) => (host, Some(port)),
+            None => (authority, None),
+        },
+    };
+    if host.is_empty() {
+        return None;
+    }
+    let port = match port {
+        Some(port) => port.parse::<u16>().ok()?,
+        None => default_port,
+    };
+    Some((host.to_string(), port))
+}
+
+/// Try to open a TCP connection to address
+fn probe(address: &str, port: u16) -> HostHealth {
+    let addrs = match (address, port).to_socket_addrs() {
+        Ok(addrs) => addrs,
+        Err(err) => {
+            debug!("could not resolve {address}: {err}");
+            return HostHealth::Unreachable;
+        }
+    };
+    for addr in addrs {
+        if TcpStream::connect_timeout(&addr, PROBE_TIMEOUT).is_ok() {
+            return HostHealth::Reachable;
+        }
+    }
+    HostHealth::Unreachable
+}
+
+#[cfg(test)]
+mod test {
+
+    use std::net::TcpListener;
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::filetransfer::params::{
+        AwsS3Params, GenericProtocolParams, KubeProtocolParams, WebDAVProtocolParams,
+    };
+    use crate::filetransfer::FileTransferProtocol;
+
+    #[test]
+    fn should_get_probe_address_for_generic_params() {
+        let params = ProtocolParams::Generic(
+            GenericProtocolParams::default()
+                .address("192.168.1.31")
+                .port(2222),
+        );
+        assert_eq!(
+            probe_address(&params),
+            Some((String::from("192.168.1.31"), 2222))
+        );
+    }
+
+    #[test]
+    fn should_get_probe_address_for_webdav_params() {
+        let params = ProtocolParams::WebDAV(WebDAVProtocolParams {
+            uri: String::from("https://user@dav.example.com/remote.php"),
+            username: String::from("user"),
+            password: String::from("pass"),
+        });
+        assert_eq!(
+            probe_address(&params),
+            Some((String::from("dav.example.com"), 443))
+        );
+    }
+
+    #[test]
+    fn should_get_probe_address_for_s3_params() {
+        let params = ProtocolParams::AwsS3(AwsS3Params::new("bucket", Some("eu-west-1"), None));
+        assert_eq!(
+            probe_address(&params),
+            Some((String::from("s3.eu-west-1.amazonaws.com"), 443))
+        );
+        let params = ProtocolParams::AwsS3(
+            AwsS3Params::new("bucket", None, None).endpoint(Some("http://localhost:9000")),
+        );
+        assert_eq!(
+            probe_address(&params),
+            Some((String::from("localhost"), 9000))
+        );
+        let params = ProtocolParams::AwsS3(AwsS3Params::new("bucket", None, None));
+        assert_eq!(probe_address(&params), None);
+    }
+
+    #[test]
+    fn should_get_probe_address_for_kube_params() {
+        let params = ProtocolParams::Kube(KubeProtocolParams {
+            namespace: None,
+            cluster_url: Some(String::from("https://[::1]:6443")),
+            username: None,
+            client_cert: None,
+            client_key: None,
+        });
+        assert_eq!(probe_address(&params), Some((String::from("::1"), 6443)));
+        let params = ProtocolParams::Kube(KubeProtocolParams {
+            namespace: None,
+            cluster_url: None,
+            username: None,
+            client_cert: None,
+            client_key: None,
+        });
+        assert_eq!(probe_address(&params), None);
+    }
+
+    #[test]
+    fn should_parse_url_address() {
+        assert_eq!(
+            parse_url_address("http://example.com"),
+            Some((String::from("example.com"), 80))
+        );
+        assert_eq!(
+            parse_url_address("https://example.com:8443/foo/bar"),
+            Some((String::from("example.com"), 8443))
+        );
+        assert_eq!(
+            parse_url_address("example.com:81"),
+            Some((String::from("example.com"), 81))
+        );
+        assert_eq!(parse_url_address("https:///foo"), None);
+    }
+
+    #[test]
+    fn should_probe_hosts() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        assert_eq!(probe("127.0.0.1", port), HostHealth::Reachable);
+        drop(listener);
+        assert_eq!(probe("127.0.0.1", port), HostHealth::Unreachable);
+    }
+
+    #[test]
+    fn should_check_and_cache_health() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let params = FileTransferParams::new(
+            FileTransferProtocol::Sftp,
+            ProtocolParams::Generic(
+                GenericProtocolParams::default()
+                    .address("127.0.0.1")
+                    .port(port),
+            ),
+        );
+        let mut checker = HealthChecker::default();
+        assert!(checker.get("local").is_none());
+        checker.check("local", &params);
+        // wait for result
+        let started = Instant::now();
+        while !checker.poll() {
+            assert!(started.elapsed() < Duration::from_secs(10));
+            thread::sleep(Duration::from_millis(10));
+        }
+        assert_eq!(checker.get("local"), Some(HostHealth::Reachable));
+        // cached result; no new probe is started
+        checker.check("local", &params);
+        assert!(checker.pending.is_empty());
+    }
+}
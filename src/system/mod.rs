@@ -6,10 +6,17 @@
 pub mod auto_update;
 pub mod bookmarks_client;
 pub mod config_client;
+pub mod connection_test;
+pub mod disk;
 pub mod environment;
+pub mod health_check;
 mod keys;
 pub mod logging;
+pub mod netrc;
 pub mod notifications;
+#[cfg(posix)]
+pub mod signals;
 pub mod sshkey_storage;
 pub mod theme_provider;
+pub mod trash;
 pub mod watcher;
@@ -0,0 +1,307 @@
+//! ## Netrc
+//!
+//! `netrc` is the module which provides a parser for the `.netrc` file, which is used to store credentials for remote hosts
+
+use std::path::{Path, PathBuf};
+use std::str::Chars;
+
+use thiserror::Error;
+
+/// Environment variable which overrides the netrc path
+const NETRC_ENV: &str = "NETRC";
+
+/// Netrc parser error
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum NetrcError {
+    #[error("could not read netrc file: {0}")]
+    Io(String),
+    #[error("expected value for `{0}`")]
+    MissingValue(String),
+    #[error("unexpected token `{0}`")]
+    UnexpectedToken(String),
+    #[error("unterminated quoted string")]
+    UnterminatedQuote,
+}
+
+/// Credentials associated to a machine in the netrc file
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NetrcEntry {
+    pub login: Option<String>,
+    pub password: Option<String>,
+    pub account: Option<String>,
+}
+
+/// Parsed netrc file
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Netrc {
+    machines: Vec<(String, NetrcEntry)>,
+    default: Option<NetrcEntry>,
+}
+
+impl Netrc {
+    /// Read and parse the netrc file at the provided path
+    pub fn from_file(path: &Path) -> Result<Self, NetrcError> {
+        let data = std::fs::read_to_string(path).map_err(|e| NetrcError::Io(e.to_string()))?;
+        Self::parse(&data)
+    }
+
+    /// Parse netrc from string
+    pub fn parse(data: &str) -> Result<Self, NetrcError> {
+        let mut netrc = Self::default();
+        let mut lexer = Lexer::new(data);
+        // entry currently being parsed
+        let mut current: Option<(Option<String>, NetrcEntry)> = None;
+        while let Some(token) = lexer.next_token()? {
+            match token.as_str() {
+                "machine" => {
+                    netrc.push(current.take());
+                    current = Some((Some(lexer.value_for(&token)?), NetrcEntry::default()));
+                }
+                "default" => {
+                    netrc.push(current.take());
+                    current = Some((None, NetrcEntry::default()));
+                }
+                "login" | "password" | "account" => {
+                    let value = lexer.value_for(&token)?;
+                    let Some((_, entry)) = current.as_mut() else {
+                        return Err(NetrcError::UnexpectedToken(token));
+                    };
+                    match token.as_str() {
+                        "login" => entry.login = Some(value),
+                        "password" => entry.password = Some(value),
+                        _ => entry.account = Some(value),
+                    }
+                }
+                "macdef" => {
+                    // macro definitions are not supported; skip until an empty line
+                    lexer.value_for(&token)?;
+                    lexer.skip_macro();
+                }
+                _ => return Err(NetrcError::UnexpectedToken(token)),
+            }
+        }
+        netrc.push(current);
+        Ok(netrc)
+    }
+
+    /// Find the entry for `host`; if no machine matches, the `default` entry is returned
+    pub fn find(&self, host: &str) -> Option<&NetrcEntry> {
+        self.machines
+            .iter()
+            .find(|(name, _)| name == host)
+            .map(|(_, entry)| entry)
+            .or(self.default.as_ref())
+    }
+
+    fn push(&mut self, entry: Option<(Option<String>, NetrcEntry)>) {
+        match entry {
+            Some((Some(name), entry)) => self.machines.push((name, entry)),
+            // only the first default entry is taken
+            Some((None, entry)) if self.default.is_none() => self.default = Some(entry),
+            _ => {}
+        }
+    }
+}
+
+/// Get the path of the netrc file.
+/// The `NETRC` environment variable takes precedence over `~/.netrc`
+pub fn netrc_path() -> Option<PathBuf> {
+    match std::env::var_os(NETRC_ENV) {
+        Some(path) if !path.is_empty() => Some(PathBuf::from(path)),
+        _ => dirs::home_dir().map(|home| home.join(".netrc")),
+    }
+}
+
+/// Netrc tokenizer
+struct Lexer<'a> {
+    chars: std::iter::Peekable<Chars<'a>>,
+}
+
+impl<'a> Lexer<'a> {
+    fn new(data: &'a str) -> Self {
+        Self {
+            chars: data.chars().peekable(),
+        }
+    }
+
+    /// Get the next token; quoted tokens are unquoted and escapes are resolved
+    fn next_token(&mut self) -> Result<Option<String>, NetrcError> {
+        // skip whitespaces and comments
+        loop {
+            match self.chars.peek() {
+                Some(c) if c.is_whitespace() => {
+                    self.chars.next();
+                }
+                Some('#') => {
+                    for c in self.chars.by_ref() {
+                        if c == '\n' {
+                            break;
+                        }
+                    }
+                }
+                Some(_) => break,
+                None => return Ok(None),
+            }
+        }
+        let mut token = String::new();
+        if self.chars.peek() == Some(&'"') {
+            self.chars.next();
+            loop {
+                match self.chars.next() {
+                    Some('"') => break,
+                    Some('\\') => match self.chars.next() {
+                        Some(c) => token.push(c),
+                        None => return Err(NetrcError::UnterminatedQuote),
+                    },
+                    Some(c) => token.push(c),
+                    None => return Err(NetrcError::UnterminatedQuote),
+                }
+            }
+        } else {
+            while let Some(c) = self.chars.peek().copied() {
+                if c.is_whitespace() {
+                    break;
+                }
+                self.chars.next();
+                if c == '\\' {
+                    if let Some(c) = self.chars.next() {
+                        token.push(c);
+                    }
+                } else {
+                    token.push(c);
+                }
+            }
+        }
+        Ok(Some(token))
+    }
+
+    /// Get the value for the provided keyword
+    fn value_for(&mut self, keyword: &str) -> Result<String, NetrcError> {
+        self.next_token()?
+            .ok_or_else(|| NetrcError::MissingValue(keyword.to_string()))
+    }
+
+    /// Skip a macro definition, which terminates with an empty line
+    fn skip_macro(&mut self) {
+        let mut last = None;
+        for c in self.chars.by_ref() {
+            if c == '\n' && last == Some('\n') {
+                break;
+            }
+            if c != '\r' {
+                last = Some(c);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use std::io::Write;
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn should_parse_netrc() {
+        let netrc = Netrc::parse(
+            r#"# my hosts
+machine ftp.example.com login omar password secret
+machine 192.168.1.31
+    login pi
+    password raspberry
+    account pi-account
+"#,
+        )
+        .unwrap();
+        assert_eq!(
+            netrc.find("ftp.example.com").unwrap(),
+            &NetrcEntry {
+                login: Some(String::from("omar")),
+                password: Some(String::from("secret")),
+                account: None,
+            }
+        );
+        assert_eq!(
+            netrc.find("192.168.1.31").unwrap(),
+            &NetrcEntry {
+                login: Some(String::from("pi")),
+                password: Some(String::from("raspberry")),
+                account: Some(String::from("pi-account")),
+            }
+        );
+        assert!(netrc.find("unknown.example.com").is_none());
+    }
+
+    #[test]
+    fn should_fallback_to_default_entry() {
+        let netrc = Netrc::parse(
+            "machine ftp.example.com login omar password secret\ndefault login anonymous password guest@example.com\n",
+        )
+        .unwrap();
+        assert_eq!(
+            netrc.find("ftp.example.com").unwrap().login.as_deref(),
+            Some("omar")
+        );
+        let default = netrc.find("other.example.com").unwrap();
+        assert_eq!(default.login.as_deref(), Some("anonymous"));
+        assert_eq!(default.password.as_deref(), Some("guest@example.com"));
+    }
+
+    #[test]
+    fn should_parse_quoted_values() {
+        let netrc = Netrc::parse(
+            r#"machine ftp.example.com login "omar veeso" password "pass \"with\" spaces#1""#,
+        )
+        .unwrap();
+        let entry = netrc.find("ftp.example.com").unwrap();
+        assert_eq!(entry.login.as_deref(), Some("omar veeso"));
+        assert_eq!(entry.password.as_deref(), Some(r#"pass "with" spaces#1"#));
+    }
+
+    #[test]
+    fn should_skip_macro_definitions() {
+        let netrc = Netrc::parse(
+            "macdef init\ncd /pub\nls\n\nmachine ftp.example.com login omar password secret\n",
+        )
+        .unwrap();
+        assert_eq!(
+            netrc.find("ftp.example.com").unwrap().password.as_deref(),
+            Some("secret")
+        );
+    }
+
+    #[test]
+    fn should_fail_parsing_malformed_netrc() {
+        assert_eq!(
+            Netrc::parse("machine ftp.example.com login").unwrap_err(),
+            NetrcError::MissingValue(String::from("login"))
+        );
+        assert_eq!(
+            Netrc::parse("login omar").unwrap_err(),
+            NetrcError::UnexpectedToken(String::from("login"))
+        );
+        assert_eq!(
+            Netrc::parse("machine ftp.example.com foo bar").unwrap_err(),
+            NetrcError::UnexpectedToken(String::from("foo"))
+        );
+        assert_eq!(
+            Netrc::parse(r#"machine ftp.example.com password "secret"#).unwrap_err(),
+            NetrcError::UnterminatedQuote
+        );
+    }
+
+    #[test]
+    fn should_read_netrc_from_file() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        writeln!(file, "machine ftp.example.com login omar password secret").unwrap();
+        let netrc = Netrc::from_file(file.path()).unwrap();
+        assert_eq!(
+            netrc.find("ftp.example.com").unwrap().login.as_deref(),
+            Some("omar")
+        );
+        assert!(Netrc::from_file(Path::new("/this/file/does/not/exist")).is_err());
+    }
+}
@@ -4,8 +4,62 @@
 
 #[cfg(all(unix, not(target_os = "macos")))]
 use notify_rust::Hint;
+use std::fmt;
+use std::io::Write;
+use std::str::FromStr;
+
 use notify_rust::{Notification as OsNotification, Timeout};
 
+/// Defines on which events the terminal bell must be rung
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum TerminalBell {
+    #[default]
+    Off,
+    OnCompletion,
+    OnError,
+    Both,
+}
+
+impl TerminalBell {
+    /// Returns whether the bell must be rung when a transfer completes
+    pub fn on_completion(&self) -> bool {
+        matches!(self, Self::OnCompletion | Self::Both)
+    }
+
+    /// Returns whether the bell must be rung when a transfer fails
+    pub fn on_error(&self) -> bool {
+        matches!(self, Self::OnError | Self::Both)
+    }
+}
+
+impl fmt::Display for TerminalBell {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Off => "off",
+                Self::OnCompletion => "on_completion",
+                Self::OnError => "on_error",
+                Self::Both => "both",
+            }
+        )
+    }
+}
+
+impl FromStr for TerminalBell {
+    type Err = ();
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "off" => Ok(Self::Off),
+            "on_completion" => Ok(Self::OnCompletion),
+            "on_error" => Ok(Self::OnError),
+            "both" => Ok(Self::Both),
+            _ => Err(()),
+        }
+    }
+}
+
 /// A notification helper which provides all the functions to send the available notifications for termscp
 pub struct Notification;
 
@@ -19,6 +73,15 @@ impl Notification {
         );
     }
 
+    /// Notify a transfer has been completed, but some files couldn't be transferred
+    pub fn transfer_completed_with_errors<S: AsRef<str>>(body: S) {
+        Self::notify(
+            "Transfer completed with errors ⚠️",
+            body.as_ref(),
+            Some("transfer.error"),
+        );
+    }
+
     /// Notify a transfer has failed
     pub fn transfer_error<S: AsRef<str>>(body: S) {
         Self::notify("Transfer failed ❌", body.as_ref(), Some("transfer.error"));
@@ -47,6 +110,14 @@ impl Notification {
         Self::notify("Update installation failed ❌", err.as_ref(), None);
     }
 
+    /// Ring the terminal bell, writing BEL to stdout
+    pub fn terminal_bell() {
+        let mut stdout = std::io::stdout();
+        if let Err(err) = stdout.write_all(b"\x07").and_then(|_| stdout.flush()) {
+            error!("Could not ring terminal bell: {err}");
+        }
+    }
+
     /// Notify guest OS with provided Summary, body and optional category
     /// e.g. Category is supported on FreeBSD/Linux only
     #[allow(unused_variables)]
@@ -66,3 +137,39 @@ impl Notification {
         let _ = notification.show();
     }
 }
+
+#[cfg(test)]
+mod tests {
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn should_parse_terminal_bell() {
+        assert_eq!(TerminalBell::from_str("off"), Ok(TerminalBell::Off));
+        assert_eq!(
+            TerminalBell::from_str("ON_COMPLETION"),
+            Ok(TerminalBell::OnCompletion)
+        );
+        assert_eq!(
+            TerminalBell::from_str("on_error"),
+            Ok(TerminalBell::OnError)
+        );
+        assert_eq!(TerminalBell::from_str("both"), Ok(TerminalBell::Both));
+        assert!(TerminalBell::from_str("always").is_err());
+        assert_eq!(TerminalBell::OnCompletion.to_string(), "on_completion");
+    }
+
+    #[test]
+    fn should_tell_when_to_ring_terminal_bell() {
+        assert_eq!(TerminalBell::Off.on_completion(), false);
+        assert_eq!(TerminalBell::Off.on_error(), false);
+        assert_eq!(TerminalBell::OnCompletion.on_completion(), true);
+        assert_eq!(TerminalBell::OnCompletion.on_error(), false);
+        assert_eq!(TerminalBell::OnError.on_completion(), false);
+        assert_eq!(TerminalBell::OnError.on_error(), true);
+        assert_eq!(TerminalBell::Both.on_completion(), true);
+        assert_eq!(TerminalBell::Both.on_error(), true);
+    }
+}
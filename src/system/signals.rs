@@ -0,0 +1,91 @@
+//! ## Signals
+//!
+//! `signals` is the module which handles the job control signals (`SIGTSTP` and `SIGCONT`) on unix systems
+
+use std::io;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use signal_hook::consts::{SIGCONT, SIGTSTP};
+use signal_hook::low_level;
+use signal_hook::SigId;
+
+/// Listens for the job control signals.
+/// Once registered, `SIGTSTP` doesn't stop the process anymore: the suspension is reported instead,
+/// so that the terminal can be restored before calling [`stop`]
+pub struct SuspendListener {
+    suspend: Arc<AtomicBool>,
+    resume: Arc<AtomicBool>,
+    handlers: Vec<SigId>,
+}
+
+impl SuspendListener {
+    /// Register the handlers for `SIGTSTP` and `SIGCONT`
+    pub fn register() -> io::Result<Self> {
+        let suspend = Arc::new(AtomicBool::new(false));
+        let resume = Arc::new(AtomicBool::new(false));
+        let handlers = vec![
+            signal_hook::flag::register(SIGTSTP, suspend.clone())?,
+            signal_hook::flag::register(SIGCONT, resume.clone())?,
+        ];
+        Ok(Self {
+            suspend,
+            resume,
+            handlers,
+        })
+    }
+
+    /// Returns whether the suspension has been requested since the last call
+    pub fn take_suspend(&self) -> bool {
+        self.suspend.swap(false, Ordering::Relaxed)
+    }
+
+    /// Returns whether the process has been resumed since the last call
+    pub fn take_resume(&self) -> bool {
+        self.resume.swap(false, Ordering::Relaxed)
+    }
+}
+
+impl Drop for SuspendListener {
+    fn drop(&mut self) {
+        for handler in self.handlers.drain(..) {
+            low_level::unregister(handler);
+        }
+    }
+}
+
+/// Request the suspension of the process, as `<CTRL+Z>` does when the terminal is not in raw mode
+pub fn request_suspend() {
+    if let Err(err) = low_level::raise(SIGTSTP) {
+        error!("Could not raise SIGTSTP: {err}");
+    }
+}
+
+/// Stop the process; returns once the process is resumed by `SIGCONT` (e.g. with `fg`)
+pub fn stop() {
+    if let Err(err) = low_level::emulate_default_handler(SIGTSTP) {
+        error!("Could not stop process: {err}");
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn should_report_job_control_signals() {
+        let listener = SuspendListener::register().unwrap();
+        assert_eq!(listener.take_suspend(), false);
+        assert_eq!(listener.take_resume(), false);
+        // the process isn't stopped, since the handler is registered
+        request_suspend();
+        assert_eq!(listener.take_suspend(), true);
+        assert_eq!(listener.take_suspend(), false);
+        low_level::raise(SIGCONT).unwrap();
+        assert_eq!(listener.take_resume(), true);
+        assert_eq!(listener.take_resume(), false);
+    }
+}
@@ -8,14 +8,45 @@ use std::fs::OpenOptions;
 use std::path::{Path, PathBuf};
 use std::string::ToString;
 
+use tuirealm::ratatui::buffer::Buffer;
+use tuirealm::ratatui::style::{Color, Modifier};
+
 use crate::config::serialization::{deserialize, serialize, SerializerError, SerializerErrorKind};
 use crate::config::themes::Theme;
 
+/// Colors which the terminal is able to display
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum Palette {
+    /// The colors of the theme are used
+    #[default]
+    Colors,
+    /// Colors are dropped; only the bold, reverse (and other) attributes are kept.
+    /// Cells with a background color are displayed in reverse video
+    Monochrome,
+}
+
+impl Palette {
+    /// Map the cells of a rendered `buffer` to the palette
+    pub fn apply(self, buffer: &mut Buffer) {
+        if self == Palette::Colors {
+            return;
+        }
+        for cell in buffer.content.iter_mut() {
+            if cell.bg != Color::Reset {
+                cell.modifier.insert(Modifier::REVERSED);
+            }
+            cell.fg = Color::Reset;
+            cell.bg = Color::Reset;
+        }
+    }
+}
+
 /// ThemeProvider provides a high level API to communicate with the termscp theme
 pub struct ThemeProvider {
     theme: Theme,        // Theme loaded
     theme_path: PathBuf, // Theme TOML Path
     degraded: bool,      // Fallback mode; won't work with file system
+    palette: Palette,
 }
 
 impl ThemeProvider {
@@ -31,6 +62,7 @@ impl ThemeProvider {
             theme: default_theme,
             theme_path: theme_path.to_path_buf(),
             degraded: false,
+            palette: Palette::default(),
         };
         // If Config file doesn't exist, create it
         if !theme_path.exists() {
@@ -57,6 +89,7 @@ impl ThemeProvider {
             theme: Theme::default(),
             theme_path: PathBuf::default(),
             degraded: true,
+            palette: Palette::default(),
         }
     }
 
@@ -72,6 +105,16 @@ impl ThemeProvider {
         &mut self.theme
     }
 
+    /// Returns the palette the theme is rendered with
+    pub fn palette(&self) -> Palette {
+        self.palette
+    }
+
+    /// Set the palette the theme is rendered with
+    pub fn set_palette(&mut self, palette: Palette) {
+        self.palette = palette;
+    }
+
     // -- io
 
     /// Load theme from file
@@ -143,7 +186,8 @@ impl ThemeProvider {
 mod test {
     use pretty_assertions::assert_eq;
     use tempfile::TempDir;
-    use tuirealm::ratatui::style::Color;
+    use tuirealm::ratatui::layout::Rect;
+    use tuirealm::ratatui::style::Style;
 
     use super::*;
 
@@ -197,6 +241,55 @@ mod test {
         assert!(ThemeProvider::new(Path::new("/tmp/oifoif/omar")).is_err());
     }
 
+    #[test]
+    fn should_map_buffer_to_monochrome_palette() {
+        let area = Rect::new(0, 0, 8, 2);
+        let mut buffer = Buffer::empty(area);
+        buffer.set_string(0, 0, "dir", Style::default().fg(Color::Yellow));
+        buffer.set_string(4, 0, "file", Style::default().fg(Color::Rgb(128, 128, 128)));
+        // selected row, bold header and highlighted choice
+        buffer.set_string(
+            0,
+            1,
+            "sel",
+            Style::default()
+                .fg(Color::Cyan)
+                .add_modifier(Modifier::REVERSED),
+        );
+        buffer.set_string(
+            3,
+            1,
+            "B",
+            Style::default()
+                .fg(Color::Green)
+                .add_modifier(Modifier::BOLD),
+        );
+        buffer.set_string(
+            4,
+            1,
+            "Yes",
+            Style::default().fg(Color::Black).bg(Color::Red),
+        );
+        // colors are kept with the default palette
+        let colored = buffer.clone();
+        Palette::Colors.apply(&mut buffer);
+        assert_eq!(buffer, colored);
+        Palette::Monochrome.apply(&mut buffer);
+        let mut expected = Buffer::with_lines(["dir file", "selBYes "]);
+        expected.set_style(Rect::new(0, 1, 3, 1), Modifier::REVERSED);
+        expected.set_style(Rect::new(3, 1, 1, 1), Modifier::BOLD);
+        expected.set_style(Rect::new(4, 1, 3, 1), Modifier::REVERSED);
+        assert_eq!(buffer, expected);
+    }
+
+    #[test]
+    fn should_set_theme_provider_palette() {
+        let mut provider: ThemeProvider = ThemeProvider::degraded();
+        assert_eq!(provider.palette(), Palette::Colors);
+        provider.set_palette(Palette::Monochrome);
+        assert_eq!(provider.palette(), Palette::Monochrome);
+    }
+
     /// Get paths for theme file
     fn get_theme_path(dir: &Path) -> PathBuf {
         let mut p: PathBuf = PathBuf::from(dir);
@@ -0,0 +1,248 @@
+//! ## Trash
+//!
+//! `trash` is the module which moves local files to the trash of the current platform, instead of deleting them permanently
+
+use std::io;
+use std::path::Path;
+
+use thiserror::Error;
+
+pub type TrashResult<T> = Result<T, TrashError>;
+
+/// Trash error
+#[derive(Debug, Error)]
+pub enum TrashError {
+    /// There's no trash for this file (e.g. it's on another mount), so it can only be deleted permanently
+    #[error("no trash is available: {0}")]
+    Unavailable(&'static str),
+    #[error("could not move to trash: {0}")]
+    Io(#[from] io::Error),
+}
+
+/// Move the file or directory at `path` to the trash directory described by the freedesktop trash specification
+/// (`$XDG_DATA_HOME/Trash`)
+#[cfg(all(posix, not(macos)))]
+pub fn move_to_trash(path: &Path) -> TrashResult<()> {
+    let trash = dirs::data_dir()
+        .ok_or(TrashError::Unavailable("could not find the home directory"))?
+        .join("Trash");
+    move_to_freedesktop_trash(path, &trash)
+}
+
+/// Move the file or directory at `path` to `~/.Trash`
+#[cfg(macos)]
+pub fn move_to_trash(path: &Path) -> TrashResult<()> {
+    let trash = dirs::home_dir()
+        .ok_or(TrashError::Unavailable("could not find the home directory"))?
+        .join(".Trash");
+    if !trash.is_dir() {
+        return Err(TrashError::Unavailable("~/.Trash doesn't exist"));
+    }
+    let name = file_name(path)?;
+    let dest = (1..)
+        .map(|n| trash.join(trash_name(&name, n)))
+        .find(|dest| dest.symlink_metadata().is_err())
+        .unwrap();
+    std::fs::rename(path, dest).map_err(rename_error)
+}
+
+/// Move the file or directory at `path` to the recycle bin of its drive
+#[cfg(win)]
+pub fn move_to_trash(path: &Path) -> TrashResult<()> {
+    use std::os::windows::ffi::OsStrExt;
+    use std::path::{Component, PathBuf};
+
+    use windows::core::PCWSTR;
+    use windows::Win32::UI::Shell::{
+        SHFileOperationW, SHQueryRecycleBinW, FOF_ALLOWUNDO, FOF_NOCONFIRMATION, FOF_NOERRORUI,
+        FOF_SILENT, FO_DELETE, SHFILEOPSTRUCTW, SHQUERYRBINFO,
+    };
+
+    let path = std::path::absolute(path)?;
+    // without a recycle bin on the drive, the shell would delete the file permanently
+    let root: PathBuf = path
+        .components()
+        .take_while(|x| matches!(x, Component::Prefix(_) | Component::RootDir))
+        .collect();
+    let root: Vec<u16> = root.as_os_str().encode_wide().chain([0]).collect();
+    let mut info = SHQUERYRBINFO {
+        cbSize: std::mem::size_of::<SHQUERYRBINFO>() as u32,
+        ..Default::default()
+    };
+    if unsafe { SHQueryRecycleBinW(PCWSTR(root.as_ptr()), &mut info) }.is_err() {
+        return Err(TrashError::Unavailable("the drive has no recycle bin"));
+    }
+    // NOTE: the list of paths must be terminated by two nul characters
+    let from: Vec<u16> = path.as_os_str().encode_wide().chain([0, 0]).collect();
+    let mut op = SHFILEOPSTRUCTW {
+        wFunc: FO_DELETE,
+        pFrom: PCWSTR(from.as_ptr()),
+        fFlags: (FOF_ALLOWUNDO | FOF_NOCONFIRMATION | FOF_NOERRORUI | FOF_SILENT).0 as u16,
+        ..Default::default()
+    };
+    match unsafe { SHFileOperationW(&mut op) } {
+        0 if { op.fAnyOperationsAborted }.as_bool() => {
+            Err(io::Error::other("the operation has been aborted").into())
+        }
+        0 => Ok(()),
+        code => Err(io::Error::other(format!("shell operation failed with code {code:#x}")).into()),
+    }
+}
+
+/// Move `path` into the `trash` directory, writing its `.trashinfo` file as described by the freedesktop trash specification
+#[cfg(all(posix, not(macos)))]
+fn move_to_freedesktop_trash(path: &Path, trash: &Path) -> TrashResult<()> {
+    use std::fs::{self, OpenOptions};
+    use std::io::Write;
+
+    let path = std::path::absolute(path)?;
+    let files_dir = trash.join("files");
+    let info_dir = trash.join("info");
+    fs::create_dir_all(&files_dir)?;
+    fs::create_dir_all(&info_dir)?;
+    let name = file_name(&path)?;
+    let info = format!(
+        "[Trash Info]\nPath={}\nDeletionDate={}\n",
+        url_escape(&path),
+        chrono::Local::now().format("%Y-%m-%dT%H:%M:%S")
+    );
+    for n in 1.. {
+        let trash_name = trash_name(&name, n);
+        let dest = files_dir.join(&trash_name);
+        if dest.symlink_metadata().is_ok() {
+            continue;
+        }
+        // the info file is created first, to reserve the name
+        let info_path = info_dir.join(format!("{trash_name}.trashinfo"));
+        let mut info_file = match OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&info_path)
+        {
+            Ok(file) => file,
+            Err(err) if err.kind() == io::ErrorKind::AlreadyExists => continue,
+            Err(err) => return Err(err.into()),
+        };
+        if let Err(err) = info_file.write_all(info.as_bytes()) {
+            let _ = fs::remove_file(&info_path);
+            return Err(err.into());
+        }
+        return fs::rename(&path, dest).map_err(|err| {
+            let _ = fs::remove_file(&info_path);
+            rename_error(err)
+        });
+    }
+    unreachable!("names are exhausted")
+}
+
+/// Get the name of the file at `path`
+#[cfg(not(win))]
+fn file_name(path: &Path) -> TrashResult<String> {
+    path.file_name()
+        .map(|x| x.to_string_lossy().to_string())
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "path has no file name").into())
+}
+
+/// Get the name to give in the trash to the file `name`, at the `n`-th attempt (e.g. `report.2.txt`)
+#[cfg(not(win))]
+fn trash_name(name: &str, n: usize) -> String {
+    if n == 1 {
+        return name.to_string();
+    }
+    match name.rsplit_once('.') {
+        Some((stem, ext)) if !stem.is_empty() => format!("{stem}.{n}.{ext}"),
+        _ => format!("{name}.{n}"),
+    }
+}
+
+/// A file can't be renamed into a trash on another mount
+#[cfg(not(win))]
+fn rename_error(err: io::Error) -> TrashError {
+    match err.kind() {
+        io::ErrorKind::CrossesDevices => {
+            TrashError::Unavailable("the trash is on a different mount than the file")
+        }
+        _ => TrashError::Io(err),
+    }
+}
+
+/// Escape `path` like an URL, as required for the `Path` key of the trash info file
+#[cfg(all(posix, not(macos)))]
+fn url_escape(path: &Path) -> String {
+    use std::os::unix::ffi::OsStrExt;
+
+    path.as_os_str()
+        .as_bytes()
+        .iter()
+        .map(|x| match x {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' => {
+                (*x as char).to_string()
+            }
+            x => format!("%{x:02X}"),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+#[cfg(all(posix, not(macos)))]
+mod tests {
+
+    use std::fs;
+
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn should_move_file_to_freedesktop_trash() {
+        let tmpdir = TempDir::new().unwrap();
+        let trash = tmpdir.path().join("Trash");
+        let file = tmpdir.path().join("my report.txt");
+        fs::write(&file, "hello").unwrap();
+        assert!(move_to_freedesktop_trash(&file, &trash).is_ok());
+        assert!(!file.exists());
+        assert_eq!(
+            fs::read_to_string(trash.join("files/my report.txt")).unwrap(),
+            "hello"
+        );
+        let info = fs::read_to_string(trash.join("info/my report.txt.trashinfo")).unwrap();
+        assert!(info.starts_with("[Trash Info]\n"));
+        assert!(info.contains(&format!(
+            "Path={}/my%20report.txt\n",
+            url_escape(tmpdir.path())
+        )));
+        assert!(info.contains("DeletionDate="));
+        // a file with the same name gets a different name in the trash
+        fs::write(&file, "world").unwrap();
+        assert!(move_to_freedesktop_trash(&file, &trash).is_ok());
+        assert_eq!(
+            fs::read_to_string(trash.join("files/my report.2.txt")).unwrap(),
+            "world"
+        );
+        assert!(trash.join("info/my report.2.txt.trashinfo").exists());
+    }
+
+    #[test]
+    fn should_move_directory_to_freedesktop_trash() {
+        let tmpdir = TempDir::new().unwrap();
+        let trash = tmpdir.path().join("Trash");
+        let dir = tmpdir.path().join("project");
+        fs::create_dir_all(dir.join("src")).unwrap();
+        fs::write(dir.join("src/main.rs"), "fn main() {}").unwrap();
+        assert!(move_to_freedesktop_trash(&dir, &trash).is_ok());
+        assert!(!dir.exists());
+        assert!(trash.join("files/project/src/main.rs").exists());
+        // nothing is left in the trash if the file doesn't exist
+        assert!(move_to_freedesktop_trash(&dir, &trash).is_err());
+        assert!(!trash.join("info/project.2.trashinfo").exists());
+    }
+
+    #[test]
+    fn should_get_trash_name() {
+        assert_eq!(trash_name("report.txt", 1).as_str(), "report.txt");
+        assert_eq!(trash_name("report.txt", 3).as_str(), "report.3.txt");
+        assert_eq!(trash_name(".bashrc", 2).as_str(), ".bashrc.2");
+        assert_eq!(trash_name("Makefile", 2).as_str(), "Makefile.2");
+    }
+}
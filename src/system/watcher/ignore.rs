@@ -0,0 +1,111 @@
+//! ## Ignore
+//!
+//! this module exposes the patterns of the watched files whose changes are not synched
+
+use std::path::{Component, Path};
+
+use wildmatch::WildMatch;
+
+/// Patterns ignored when `watcher_ignore` is not configured: version control data, editor swap and backup files
+pub const DEFAULT_IGNORE_PATTERNS: &[&str] = &[".git/", "*.swp", "*~", ".DS_Store"];
+
+/// Patterns of the files whose changes are not synched.
+/// A pattern ending with `/` (e.g. `.git/`) matches a directory and everything inside it,
+/// while the other patterns (e.g. `*.swp`) are matched against the file name
+#[derive(Debug, Clone, Default)]
+pub struct IgnorePatterns {
+    patterns: Vec<String>,
+    dirs: Vec<WildMatch>,
+    names: Vec<WildMatch>,
+}
+
+impl IgnorePatterns {
+    /// Instantiate a new `IgnorePatterns` from `patterns`; empty patterns are skipped
+    pub fn new<S: AsRef<str>>(patterns: &[S]) -> Self {
+        let mut ignore = Self::default();
+        ignore.extend(patterns);
+        ignore
+    }
+
+    /// Parse a comma separated list of patterns (e.g. `target/, *.log`)
+    pub fn parse(patterns: &str) -> Self {
+        Self::new(&patterns.split(',').collect::<Vec<&str>>())
+    }
+
+    /// Add `patterns` to the ignored ones
+    pub fn extend<S: AsRef<str>>(&mut self, patterns: &[S]) {
+        for pattern in patterns.iter().map(|x| x.as_ref().trim()) {
+            if pattern.is_empty() || self.patterns.iter().any(|x| x == pattern) {
+                continue;
+            }
+            match pattern.strip_suffix('/') {
+                Some(dir) => self.dirs.push(WildMatch::new(dir)),
+                None => self.names.push(WildMatch::new(pattern)),
+            }
+            self.patterns.push(pattern.to_string());
+        }
+    }
+
+    /// Get the patterns, as they've been provided
+    pub fn patterns(&self) -> &[String] {
+        &self.patterns
+    }
+
+    /// Returns whether there are no patterns
+    pub fn is_empty(&self) -> bool {
+        self.patterns.is_empty()
+    }
+
+    /// Returns whether changes to `path`, relative to the watched path, are ignored
+    pub fn is_ignored(&self, path: &Path) -> bool {
+        let names: Vec<String> = path
+            .components()
+            .filter_map(|x| match x {
+                Component::Normal(name) => Some(name.to_string_lossy().to_string()),
+                _ => None,
+            })
+            .collect();
+        let Some(name) = names.last() else {
+            return false;
+        };
+        // the ignored directory itself may be changed, e.g. when removed
+        self.names.iter().any(|x| x.matches(name))
+            || names
+                .iter()
+                .any(|dir| self.dirs.iter().any(|x| x.matches(dir)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn should_ignore_default_patterns() {
+        let ignore = IgnorePatterns::new(DEFAULT_IGNORE_PATTERNS);
+        assert!(ignore.is_ignored(Path::new(".git/index")));
+        assert!(ignore.is_ignored(Path::new("vendor/lib/.git/objects/ab/cdef")));
+        assert!(ignore.is_ignored(Path::new(".git")));
+        assert!(ignore.is_ignored(Path::new("src/.main.rs.swp")));
+        assert!(ignore.is_ignored(Path::new("notes.txt~")));
+        assert!(ignore.is_ignored(Path::new("photos/.DS_Store")));
+        assert!(!ignore.is_ignored(Path::new("src/main.rs")));
+        assert!(!ignore.is_ignored(Path::new(".gitignore")));
+        assert!(!ignore.is_ignored(Path::new("")));
+    }
+
+    #[test]
+    fn should_parse_ignore_patterns() {
+        let mut ignore = IgnorePatterns::parse(" target/, *.log,,*.log ");
+        assert_eq!(ignore.patterns(), &["target/", "*.log"]);
+        assert!(ignore.is_ignored(Path::new("target/debug/termscp")));
+        assert!(ignore.is_ignored(Path::new("logs/access.log")));
+        assert!(!ignore.is_ignored(Path::new("src/targets.rs")));
+        ignore.extend(&["node_modules/"]);
+        assert!(ignore.is_ignored(Path::new("web/node_modules/react/index.js")));
+        assert!(IgnorePatterns::parse("").is_empty());
+    }
+}
@@ -3,17 +3,21 @@
 //! A watcher for file system paths, which reports changes on local fs
 
 mod change;
+mod ignore;
+mod remote;
 
 // -- export
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::path::{Path, PathBuf};
-use std::sync::mpsc::{channel, Receiver, RecvTimeoutError};
+use std::sync::mpsc::{channel, Receiver, TryRecvError};
 use std::time::Duration;
 
 pub use change::FsChange;
+pub use ignore::{IgnorePatterns, DEFAULT_IGNORE_PATTERNS};
 use notify::{
     Config, Error as WatcherError, Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher,
 };
+pub use remote::{RemoteChange, RemotePoller};
 use thiserror::Error;
 
 use crate::utils::path as path_utils;
@@ -90,9 +94,31 @@ impl TryFrom<Event> for FsWatcherEvent {
     }
 }
 
+/// Direction in which the changes to a watched path are synchronized
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WatchDirection {
+    /// Local changes are uploaded to the remote host
+    Upload,
+    /// Remote changes are downloaded to the local host
+    Download,
+}
+
+/// A local path watched by the `FsWatcher`
+#[derive(Debug)]
+struct WatchedPath {
+    /// Remote path synched with the local path
+    remote: PathBuf,
+    /// Patterns of the files whose changes aren't synched
+    ignore: IgnorePatterns,
+    /// Amount of changes ignored so far
+    ignored: usize,
+}
+
 /// File system watcher
 pub struct FsWatcher {
-    paths: HashMap<PathBuf, PathBuf>,
+    paths: HashMap<PathBuf, WatchedPath>,
+    /// Changes received from the worker, waiting to be applied
+    queue: VecDeque<FsChange>,
     receiver: Receiver<notify::Result<Event>>,
     watcher: RecommendedWatcher,
 }
@@ -104,20 +130,35 @@ impl FsWatcher {
 
         Ok(Self {
             paths: HashMap::default(),
+            queue: VecDeque::default(),
             receiver,
             watcher: RecommendedWatcher::new(tx, Config::default().with_poll_interval(delay))?,
         })
     }
 
-    /// Poll searching for the first available disk change
-    pub fn poll(&self) -> FsWatcherResult<Option<FsChange>> {
-        let res = match self.receiver.recv_timeout(Duration::from_millis(1)) {
-            Ok(res) => res,
-            Err(RecvTimeoutError::Timeout) => return Ok(None),
-            Err(RecvTimeoutError::Disconnected) => panic!("File watcher died"),
-        };
+    /// Poll searching for the first available disk change.
+    /// All the changes reported by the worker are moved into the apply queue, then the first change in the queue is returned
+    pub fn poll(&mut self) -> FsWatcherResult<Option<FsChange>> {
+        loop {
+            let res = match self.receiver.try_recv() {
+                Ok(res) => res,
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => panic!("File watcher died"),
+            };
+            if let Some(change) = self.build_fs_change(res)? {
+                self.queue.push_back(change);
+            }
+        }
+        Ok(self.queue.pop_front())
+    }
+
+    /// Returns the amount of changes waiting to be applied
+    pub fn pending(&self) -> usize {
+        self.queue.len()
+    }
 
-        // convert event to FsChange
+    /// Convert an event received from the worker into a `FsChange`
+    fn build_fs_change(&self, res: notify::Result<Event>) -> FsWatcherResult<Option<FsChange>> {
         let event = res
             .map(FsWatcherEvent::try_from)
             .map_err(FsWatcherError::from)?
@@ -134,13 +175,61 @@ impl FsWatcher {
         }
     }
 
-    /// Watch `local` path on localhost
-    pub fn watch(&mut self, local: &Path, remote: &Path) -> FsWatcherResult<()> {
+    /// Filter out `change` if it matches the ignore patterns of its watched path, counting it as ignored.
+    /// A file moved to an ignored path is left on the remote as it is, while a file moved from an ignored path,
+    /// such as the temporary file written by an editor, is reported as an update of the destination
+    pub fn filter_ignored(&mut self, change: FsChange) -> Option<FsChange> {
+        let ignored = match &change {
+            FsChange::Move(mov) => match (
+                self.is_ignored(mov.source()),
+                self.is_ignored(mov.destination()),
+            ) {
+                (true, false) => return self.build_remote_update(mov.destination()),
+                (_, destination) => destination.then(|| mov.destination().to_path_buf()),
+            },
+            FsChange::Remove(remove) => self
+                .is_ignored(remove.path())
+                .then(|| remove.path().to_path_buf()),
+            FsChange::Update(update) => self
+                .is_ignored(update.remote())
+                .then(|| update.remote().to_path_buf()),
+        };
+        match ignored {
+            Some(remote) => {
+                debug!("ignoring change to {}", remote.display());
+                if let Some((_, watched)) = self.find_synched_path_mut(&remote) {
+                    watched.ignored += 1;
+                }
+                None
+            }
+            None => Some(change),
+        }
+    }
+
+    /// Returns the amount of changes ignored so far at the watched `path`
+    pub fn ignored(&self, path: &Path) -> usize {
+        self.paths.get(path).map(|x| x.ignored).unwrap_or_default()
+    }
+
+    /// Watch `local` path on localhost, ignoring the changes to the files matching `ignore`
+    pub fn watch(
+        &mut self,
+        local: &Path,
+        remote: &Path,
+        ignore: IgnorePatterns,
+    ) -> FsWatcherResult<()> {
         // Start watcher if unwatched
         if !self.watched(local) {
             self.watcher.watch(local, RecursiveMode::Recursive)?;
             // Insert new path to paths
-            self.paths.insert(local.to_path_buf(), remote.to_path_buf());
+            self.paths.insert(
+                local.to_path_buf(),
+                WatchedPath {
+                    remote: remote.to_path_buf(),
+                    ignore,
+                    ignored: 0,
+                },
+            );
             Ok(())
         } else {
             Err(FsWatcherError::PathAlreadyWatched)
@@ -181,7 +270,38 @@ impl FsWatcher {
         self.paths
             .iter()
             .find(|(k, _)| path_utils::is_child_of(p, k))
-            .map(|(k, v)| (k.as_path(), v.as_path()))
+            .map(|(k, v)| (k.as_path(), v.remote.as_path()))
+    }
+
+    /// Given a certain remote path, returns the watched path synched with an ancestor of that path
+    fn find_synched_path_mut(&mut self, remote: &Path) -> Option<(&Path, &mut WatchedPath)> {
+        self.paths
+            .iter_mut()
+            .find(|(_, v)| path_utils::is_child_of(remote, v.remote.as_path()))
+            .map(|(k, v)| (k.as_path(), v))
+    }
+
+    /// Returns whether the changes to the `remote` path match the ignore patterns of its watched path
+    fn is_ignored(&self, remote: &Path) -> bool {
+        self.paths
+            .values()
+            .find(|x| path_utils::is_child_of(remote, x.remote.as_path()))
+            .and_then(|x| {
+                path_utils::diff_paths(remote, x.remote.as_path())
+                    .map(|relative| x.ignore.is_ignored(&relative))
+            })
+            .unwrap_or(false)
+    }
+
+    /// Build the `FsChange` updating the `remote` path from the local file synched with it
+    fn build_remote_update(&mut self, remote: &Path) -> Option<FsChange> {
+        let (local_watched, watched) = self.find_synched_path_mut(remote)?;
+        let relative = path_utils::diff_paths(remote, watched.remote.as_path())?;
+        Some(FsChange::update(
+            local_watched.join(relative),
+            local_watched,
+            watched.remote.as_path(),
+        ))
     }
 
     /// Build `FsChange` from path to local `changed_file`
@@ -246,11 +366,15 @@ mod test {
         let mut watcher = FsWatcher::init(Duration::from_secs(5)).unwrap();
         let tempdir = TempDir::new().unwrap();
         assert!(watcher
-            .watch(tempdir.path(), Path::new("/tmp/test"))
+            .watch(
+                tempdir.path(),
+                Path::new("/tmp/test"),
+                IgnorePatterns::default()
+            )
             .is_ok());
         // check if in paths
         assert_eq!(
-            watcher.paths.get(tempdir.path()).unwrap(),
+            watcher.paths.get(tempdir.path()).unwrap().remote.as_path(),
             Path::new("/tmp/test")
         );
         // close tempdir
@@ -262,14 +386,22 @@ mod test {
         let mut watcher = FsWatcher::init(Duration::from_secs(5)).unwrap();
         let tempdir = TempDir::new().unwrap();
         assert!(watcher
-            .watch(tempdir.path(), Path::new("/tmp/test"))
+            .watch(
+                tempdir.path(),
+                Path::new("/tmp/test"),
+                IgnorePatterns::default()
+            )
             .is_ok());
         // watch subdir
         let mut subdir = tempdir.path().to_path_buf();
         subdir.push("abc/def");
         // should return already watched
         assert!(watcher
-            .watch(subdir.as_path(), Path::new("/tmp/test/abc/def"))
+            .watch(
+                subdir.as_path(),
+                Path::new("/tmp/test/abc/def"),
+                IgnorePatterns::default()
+            )
             .is_err());
         // close tempdir
         assert!(tempdir.close().is_ok());
@@ -280,7 +412,11 @@ mod test {
         let mut watcher = FsWatcher::init(Duration::from_secs(5)).unwrap();
         let tempdir = TempDir::new().unwrap();
         assert!(watcher
-            .watch(tempdir.path(), Path::new("/tmp/test"))
+            .watch(
+                tempdir.path(),
+                Path::new("/tmp/test"),
+                IgnorePatterns::default()
+            )
             .is_ok());
         // unwatch
         assert!(watcher.unwatch(tempdir.path()).is_ok());
@@ -294,7 +430,11 @@ mod test {
         let mut watcher = FsWatcher::init(Duration::from_secs(5)).unwrap();
         let tempdir = TempDir::new().unwrap();
         assert!(watcher
-            .watch(tempdir.path(), Path::new("/tmp/test"))
+            .watch(
+                tempdir.path(),
+                Path::new("/tmp/test"),
+                IgnorePatterns::default()
+            )
             .is_ok());
         // unwatch
         let mut subdir = tempdir.path().to_path_buf();
@@ -308,18 +448,111 @@ mod test {
         assert!(tempdir.close().is_ok());
     }
 
+    #[test]
+    fn should_queue_changes_to_apply() {
+        let mut watcher = FsWatcher::init(Duration::from_secs(5)).unwrap();
+        assert_eq!(watcher.pending(), 0);
+        assert!(watcher.poll().unwrap().is_none());
+        let tempdir = TempDir::new().unwrap();
+        assert!(watcher
+            .watch(
+                tempdir.path(),
+                Path::new("/tmp/test"),
+                IgnorePatterns::default()
+            )
+            .is_ok());
+        for name in ["a.txt", "b.txt"] {
+            let change = watcher
+                .build_fs_change(Ok(Event::new(EventKind::Modify(
+                    notify::event::ModifyKind::Any,
+                ))
+                .add_path(tempdir.path().join(name))))
+                .unwrap()
+                .unwrap();
+            watcher.queue.push_back(change);
+        }
+        assert_eq!(watcher.pending(), 2);
+        let Some(FsChange::Update(update)) = watcher.poll().unwrap() else {
+            panic!("expected update");
+        };
+        assert_eq!(update.remote(), Path::new("/tmp/test/a.txt"));
+        assert_eq!(watcher.pending(), 1);
+        assert!(watcher.poll().unwrap().is_some());
+        assert_eq!(watcher.pending(), 0);
+        assert!(watcher.poll().unwrap().is_none());
+        // close tempdir
+        assert!(tempdir.close().is_ok());
+    }
+
     #[test]
     fn should_return_err_when_unwatching_unwatched_path() {
         let mut watcher = FsWatcher::init(Duration::from_secs(5)).unwrap();
         assert!(watcher.unwatch(Path::new("/tmp")).is_err());
     }
 
+    #[test]
+    fn should_filter_ignored_changes() {
+        let mut watcher = FsWatcher::init(Duration::from_secs(5)).unwrap();
+        let tempdir = TempDir::new().unwrap();
+        let remote = Path::new("/tmp/test");
+        assert!(watcher
+            .watch(
+                tempdir.path(),
+                remote,
+                IgnorePatterns::new(DEFAULT_IGNORE_PATTERNS)
+            )
+            .is_ok());
+        let local = |name: &str| tempdir.path().join(name);
+        let update = FsChange::update(local("src/main.rs"), tempdir.path(), remote);
+        assert_eq!(watcher.filter_ignored(update.clone()), Some(update));
+        assert!(watcher
+            .filter_ignored(FsChange::update(
+                local(".git/index"),
+                tempdir.path(),
+                remote
+            ))
+            .is_none());
+        assert!(watcher
+            .filter_ignored(FsChange::remove(
+                local("src/.main.rs.swp"),
+                tempdir.path(),
+                remote
+            ))
+            .is_none());
+        // a file moved to a backup is left on the remote
+        assert!(watcher
+            .filter_ignored(FsChange::mov(
+                local("notes.txt"),
+                local("notes.txt~"),
+                tempdir.path(),
+                remote
+            ))
+            .is_none());
+        // a file moved from a temporary file is uploaded
+        assert_eq!(
+            watcher.filter_ignored(FsChange::mov(
+                local("notes.txt~"),
+                local("notes.txt"),
+                tempdir.path(),
+                remote
+            )),
+            Some(FsChange::update(local("notes.txt"), tempdir.path(), remote))
+        );
+        assert_eq!(watcher.ignored(tempdir.path()), 3);
+        // close tempdir
+        assert!(tempdir.close().is_ok());
+    }
+
     #[test]
     fn should_tell_whether_path_is_watched() {
         let mut watcher = FsWatcher::init(Duration::from_secs(5)).unwrap();
         let tempdir = TempDir::new().unwrap();
         assert!(watcher
-            .watch(tempdir.path(), Path::new("/tmp/test"))
+            .watch(
+                tempdir.path(),
+                Path::new("/tmp/test"),
+                IgnorePatterns::default()
+            )
             .is_ok());
         assert_eq!(watcher.watched(tempdir.path()), true);
         let mut subdir = tempdir.path().to_path_buf();
@@ -337,7 +570,11 @@ mod test {
         let tempdir = TempDir::new().unwrap();
         let tempdir_path = PathBuf::from(format!("/private{}", tempdir.path().display()));
         assert!(watcher
-            .watch(tempdir_path.as_path(), Path::new("/tmp/test"))
+            .watch(
+                tempdir_path.as_path(),
+                Path::new("/tmp/test"),
+                IgnorePatterns::default()
+            )
             .is_ok());
         // create file
         let file_path = test_helpers::make_file_at(tempdir_path.as_path(), "test.txt").unwrap();
@@ -363,7 +600,11 @@ mod test {
         let tempdir = TempDir::new().unwrap();
         let tempdir_path = PathBuf::from(format!("/private{}", tempdir.path().display()));
         assert!(watcher
-            .watch(tempdir_path.as_path(), Path::new("/tmp/test"))
+            .watch(
+                tempdir_path.as_path(),
+                Path::new("/tmp/test"),
+                IgnorePatterns::default()
+            )
             .is_ok());
         // create file
         let file_path = test_helpers::make_file_at(tempdir_path.as_path(), "test.txt").unwrap();
@@ -391,7 +632,7 @@ mod test {
         let tempdir = TempDir::new().unwrap();
         let tempdir_path = PathBuf::from(format!("/private{}", tempdir.path().display()));
         assert!(watcher
-            .watch(tempdir_path.as_path(), Path::new("/tmp/test"))
+            .watch(tempdir_path.as_path(), Path::new("/tmp/test"), IgnorePatterns::default())
             .is_ok());
         // create file
         let file_path = test_helpers::make_file_at(tempdir_path.as_path(), "test.txt").unwrap();
@@ -425,7 +666,11 @@ mod test {
         let mut watcher = FsWatcher::init(Duration::from_secs(5)).unwrap();
         let tempdir = TempDir::new().unwrap();
         assert!(watcher
-            .watch(tempdir.path(), Path::new("/tmp/test"))
+            .watch(
+                tempdir.path(),
+                Path::new("/tmp/test"),
+                IgnorePatterns::default()
+            )
             .is_ok());
         assert!(watcher.poll().ok().unwrap().is_none());
         // close tempdir
@@ -436,9 +681,19 @@ mod test {
     #[cfg(target_os = "macos")]
     fn should_get_watched_paths() {
         let mut watcher = FsWatcher::init(Duration::from_secs(5)).unwrap();
-        assert!(watcher.watch(Path::new("/tmp"), Path::new("/tmp")).is_ok());
         assert!(watcher
-            .watch(Path::new("/home"), Path::new("/home"))
+            .watch(
+                Path::new("/tmp"),
+                Path::new("/tmp"),
+                IgnorePatterns::default()
+            )
+            .is_ok());
+        assert!(watcher
+            .watch(
+                Path::new("/home"),
+                Path::new("/home"),
+                IgnorePatterns::default()
+            )
             .is_ok());
         let mut watched_paths = watcher.watched_paths();
         watched_paths.sort();
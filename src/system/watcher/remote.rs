@@ -0,0 +1,336 @@
+//! ## Remote poller
+//!
+//! this module exposes the poller of the remote directories whose changes are downloaded to the local file system
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+use remotefs::File;
+
+use super::{FsWatcherError, FsWatcherResult, IgnorePatterns};
+use crate::utils::path as path_utils;
+
+/// Tolerance of the comparison between the modification time of a remote file and the time it has been uploaded at,
+/// to tell whether a change is the echo of an upload
+const ECHO_WINDOW: Duration = Duration::from_secs(10);
+
+/// State of a remote file in the snapshot of a polled directory
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct EntryState {
+    size: u64,
+    modified: Option<SystemTime>,
+}
+
+impl From<&File> for EntryState {
+    fn from(file: &File) -> Self {
+        Self {
+            size: file.metadata().size,
+            modified: file.metadata().modified,
+        }
+    }
+}
+
+/// A remote directory polled by the `RemotePoller`
+#[derive(Debug)]
+struct PolledPath {
+    /// Local path synched with the remote directory
+    local: PathBuf,
+    /// Patterns of the files whose changes aren't synched
+    ignore: IgnorePatterns,
+    /// Amount of changes ignored so far
+    ignored: usize,
+    /// Files of the directory at the last poll; `None` until the first poll
+    snapshot: Option<HashMap<PathBuf, EntryState>>,
+    last_poll: Option<Instant>,
+}
+
+/// A remote file which has been created or changed since the last poll, to download to `local`
+#[derive(Debug, Clone)]
+pub struct RemoteChange {
+    pub file: File,
+    pub local: PathBuf,
+}
+
+/// Poller of remote directories: the listing of each directory is compared to the one of the previous poll,
+/// by name, size and modification time, to find the files to download
+#[derive(Debug)]
+pub struct RemotePoller {
+    paths: HashMap<PathBuf, PolledPath>,
+    interval: Duration,
+    /// Remote files uploaded by termscp, with the modification times they may have been given
+    echoes: HashMap<PathBuf, Vec<SystemTime>>,
+}
+
+impl RemotePoller {
+    /// Instantiate a new `RemotePoller`, polling each directory every `interval`
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            paths: HashMap::default(),
+            interval,
+            echoes: HashMap::default(),
+        }
+    }
+
+    /// Poll the `remote` directory, downloading its changes into `local`, besides the files matching `ignore`
+    pub fn watch(
+        &mut self,
+        remote: &Path,
+        local: &Path,
+        ignore: IgnorePatterns,
+    ) -> FsWatcherResult<()> {
+        if self.watched(remote) {
+            return Err(FsWatcherError::PathAlreadyWatched);
+        }
+        self.paths.insert(
+            remote.to_path_buf(),
+            PolledPath {
+                local: local.to_path_buf(),
+                ignore,
+                ignored: 0,
+                snapshot: None,
+                last_poll: None,
+            },
+        );
+        Ok(())
+    }
+
+    /// Stop polling the directory `path` is in. Returns the unwatched directory
+    pub fn unwatch(&mut self, path: &Path) -> FsWatcherResult<PathBuf> {
+        let watched = self
+            .find_polled_path(path)
+            .ok_or(FsWatcherError::PathNotWatched)?;
+        self.paths.remove(watched.as_path());
+        self.echoes
+            .retain(|x, _| !path_utils::is_child_of(x.as_path(), watched.as_path()));
+        Ok(watched)
+    }
+
+    /// Returns whether `path`, or one of its ancestors, is polled
+    pub fn watched(&self, path: &Path) -> bool {
+        self.find_polled_path(path).is_some()
+    }
+
+    /// Returns the list of polled directories
+    pub fn watched_paths(&self) -> Vec<&Path> {
+        Vec::from_iter(self.paths.keys().map(|x| x.as_path()))
+    }
+
+    /// Returns the amount of changes ignored so far at the polled `path`
+    pub fn ignored(&self, path: &Path) -> usize {
+        self.paths.get(path).map(|x| x.ignored).unwrap_or_default()
+    }
+
+    /// Returns the polled directories which have to be polled again
+    pub fn due(&self) -> Vec<PathBuf> {
+        self.paths
+            .iter()
+            .filter(|(_, x)| x.last_poll.is_none_or(|t| t.elapsed() >= self.interval))
+            .map(|(k, _)| k.clone())
+            .collect()
+    }
+
+    /// Record that `remote` has been uploaded by termscp, being given one of the `mtimes`, so that the change isn't
+    /// downloaded back at the next poll
+    pub fn suppress_echo(&mut self, remote: &Path, mtimes: Vec<SystemTime>) {
+        if self.watched(remote) {
+            self.echoes.insert(remote.to_path_buf(), mtimes);
+        }
+    }
+
+    /// Update the snapshot of the polled `remote` directory with its `files`, listed recursively.
+    /// Returns the files created or changed since the previous poll; nothing is reported at the first poll.
+    /// Modification times are compared with the `tolerance` of the clock skew of the remote host
+    pub fn update(
+        &mut self,
+        remote: &Path,
+        files: &[File],
+        tolerance: Duration,
+    ) -> Vec<RemoteChange> {
+        let Some(polled) = self.paths.get_mut(remote) else {
+            return Vec::new();
+        };
+        polled.last_poll = Some(Instant::now());
+        let snapshot: HashMap<PathBuf, EntryState> = files
+            .iter()
+            .filter(|x| !x.is_dir())
+            .map(|x| (x.path().to_path_buf(), EntryState::from(x)))
+            .collect();
+        let Some(previous) = polled.snapshot.replace(snapshot) else {
+            return Vec::new();
+        };
+        let mut changes = Vec::new();
+        for file in files.iter().filter(|x| !x.is_dir()) {
+            if previous.get(file.path()) == Some(&EntryState::from(file)) {
+                continue;
+            }
+            let Some(relative) = path_utils::diff_paths(file.path(), remote) else {
+                continue;
+            };
+            if polled.ignore.is_ignored(&relative) {
+                debug!("ignoring remote change to {}", file.path().display());
+                polled.ignored += 1;
+                continue;
+            }
+            if let Some(mtimes) = self.echoes.get(file.path()) {
+                if Self::is_echo(mtimes, file.metadata().modified, tolerance) {
+                    debug!(
+                        "ignoring remote change to {}, since it has been uploaded by termscp",
+                        file.path().display()
+                    );
+                    continue;
+                }
+            }
+            changes.push(RemoteChange {
+                file: file.clone(),
+                local: polled.local.join(relative),
+            });
+        }
+        // echoes are consumed by the poll following the upload
+        self.echoes
+            .retain(|x, _| !path_utils::is_child_of(x.as_path(), remote));
+        changes
+    }
+
+    /// Returns whether `modified` is one of the `mtimes` given to a file uploaded by termscp
+    fn is_echo(mtimes: &[SystemTime], modified: Option<SystemTime>, tolerance: Duration) -> bool {
+        let Some(modified) = modified else {
+            return false;
+        };
+        let window = ECHO_WINDOW + tolerance;
+        mtimes.iter().any(|x| {
+            let diff = match modified.duration_since(*x) {
+                Ok(diff) => diff,
+                Err(err) => err.duration(),
+            };
+            diff <= window
+        })
+    }
+
+    /// Returns the polled directory `path` is in
+    fn find_polled_path(&self, path: &Path) -> Option<PathBuf> {
+        self.paths
+            .keys()
+            .find(|x| path_utils::is_child_of(path, x.as_path()))
+            .cloned()
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use pretty_assertions::assert_eq;
+    use remotefs::fs::{FileType, Metadata};
+
+    use super::*;
+
+    fn file(path: &str, size: u64, modified: SystemTime) -> File {
+        File {
+            path: PathBuf::from(path),
+            metadata: Metadata::default()
+                .file_type(FileType::File)
+                .size(size)
+                .modified(modified),
+        }
+    }
+
+    fn poller() -> RemotePoller {
+        let mut poller = RemotePoller::new(Duration::from_secs(30));
+        assert!(poller
+            .watch(
+                Path::new("/srv/www"),
+                Path::new("/home/omar/www"),
+                IgnorePatterns::new(&["*.swp"]),
+            )
+            .is_ok());
+        poller
+    }
+
+    #[test]
+    fn should_watch_and_unwatch_remote_paths() {
+        let mut poller = poller();
+        assert!(poller.watched(Path::new("/srv/www/index.html")));
+        assert!(!poller.watched(Path::new("/srv")));
+        assert!(poller
+            .watch(
+                Path::new("/srv/www"),
+                Path::new("/tmp"),
+                IgnorePatterns::default()
+            )
+            .is_err());
+        assert_eq!(poller.watched_paths(), vec![Path::new("/srv/www")]);
+        assert_eq!(poller.due(), vec![PathBuf::from("/srv/www")]);
+        assert_eq!(
+            poller.unwatch(Path::new("/srv/www/css")).unwrap(),
+            PathBuf::from("/srv/www")
+        );
+        assert!(poller.watched_paths().is_empty());
+        assert!(poller.unwatch(Path::new("/srv/www")).is_err());
+    }
+
+    #[test]
+    fn should_report_remote_changes_since_last_poll() {
+        let mut poller = poller();
+        let t0 = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000);
+        let t1 = t0 + Duration::from_secs(60);
+        let files = vec![
+            file("/srv/www/index.html", 10, t0),
+            file("/srv/www/css/main.css", 20, t0),
+        ];
+        // the first poll takes the snapshot
+        assert!(poller
+            .update(Path::new("/srv/www"), &files, Duration::ZERO)
+            .is_empty());
+        assert!(poller.due().is_empty());
+        let files = vec![
+            file("/srv/www/index.html", 10, t0),
+            file("/srv/www/css/main.css", 24, t1),
+            file("/srv/www/about.html", 5, t1),
+            file("/srv/www/.index.html.swp", 5, t1),
+        ];
+        let changes = poller.update(Path::new("/srv/www"), &files, Duration::ZERO);
+        assert_eq!(
+            changes.iter().map(|x| x.local.clone()).collect::<Vec<_>>(),
+            vec![
+                PathBuf::from("/home/omar/www/css/main.css"),
+                PathBuf::from("/home/omar/www/about.html"),
+            ]
+        );
+        assert_eq!(poller.ignored(Path::new("/srv/www")), 1);
+    }
+
+    #[test]
+    fn should_suppress_echo_of_uploaded_files() {
+        let mut poller = poller();
+        let t0 = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000);
+        let uploaded = t0 + Duration::from_secs(120);
+        poller.update(
+            Path::new("/srv/www"),
+            &[file("/srv/www/index.html", 10, t0)],
+            Duration::ZERO,
+        );
+        poller.suppress_echo(Path::new("/srv/www/index.html"), vec![uploaded]);
+        // not watched
+        poller.suppress_echo(Path::new("/srv/index.html"), vec![uploaded]);
+        let files = vec![file(
+            "/srv/www/index.html",
+            12,
+            uploaded + Duration::from_secs(2),
+        )];
+        assert!(poller
+            .update(Path::new("/srv/www"), &files, Duration::ZERO)
+            .is_empty());
+        // the echo is consumed by the poll
+        let files = vec![file(
+            "/srv/www/index.html",
+            16,
+            uploaded + Duration::from_secs(4),
+        )];
+        assert_eq!(
+            poller
+                .update(Path::new("/srv/www"), &files, Duration::ZERO)
+                .len(),
+            1
+        );
+    }
+}
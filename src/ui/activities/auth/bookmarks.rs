@@ -3,14 +3,122 @@
 //! `auth_activity` is the module which implements the authentication activity
 
 // Locals
-use super::{AuthActivity, FileTransferParams, FormTab, HostBridgeProtocol};
+use super::{
+    AuthActivity, BookmarksListRow, FileTransferParams, FileTransferProtocol, FormTab,
+    HostBridgeProtocol,
+};
+use crate::explorer::FileSorting;
 use crate::filetransfer::params::{
-    AwsS3Params, GenericProtocolParams, KubeProtocolParams, ProtocolParams, SmbParams,
-    WebDAVProtocolParams,
+    AwsS3Params, ExplorerPrefs, GenericProtocolParams, KubeProtocolParams, ModePrefs,
+    ProtocolParams, QuickCommand, SmbParams, SshPrefs, WebDAVProtocolParams,
 };
+use tuirealm::{State, StateValue};
+
+use super::Id;
+use crate::config::bookmarks::Bookmark;
 use crate::filetransfer::HostBridgeParams;
+use crate::system::health_check::HostHealth;
+use crate::utils::fmt::fmt_time;
 
 impl AuthActivity {
+    /// Start probing the bookmarked hosts, if health check is enabled
+    pub(super) fn check_bookmarks_health(&mut self) {
+        if !self.config().get_check_bookmarks_health() {
+            return;
+        }
+        let bookmarks: Vec<(String, FileTransferParams)> = match self.bookmarks_client() {
+            Some(cli) => self
+                .bookmarks_list
+                .iter()
+                .filter_map(|name| cli.get_bookmark(name).map(|params| (name.clone(), params)))
+                .collect(),
+            None => return,
+        };
+        let checker = self.context_mut().health_checker_mut();
+        for (name, params) in bookmarks.iter() {
+            checker.check(name, params);
+        }
+    }
+
+    /// Collect the bookmarks health results and refresh the bookmarks list if anything changed
+    pub(super) fn poll_bookmarks_health(&mut self) {
+        if self.config().get_check_bookmarks_health()
+            && self.context_mut().health_checker_mut().poll()
+        {
+            self.view_bookmarks_health();
+            self.redraw = true;
+        }
+    }
+
+    /// Get the health of each bookmark in the bookmarks list.
+    /// Returns `None` if health check is disabled
+    pub(super) fn bookmarks_health(&self) -> Option<Vec<Option<HostHealth>>> {
+        if !self.config().get_check_bookmarks_health() {
+            return None;
+        }
+        let checker = self.context().health_checker();
+        Some(
+            self.bookmarks_list
+                .iter()
+                .map(|name| checker.get(name))
+                .collect(),
+        )
+    }
+
+    /// Build the rows of the bookmarks list from the sorted bookmarks.
+    /// Bookmarks are listed flat if none of them has a group; otherwise each group gets a header, which hides its
+    /// bookmarks when collapsed, and the ungrouped bookmarks come last
+    pub(super) fn make_bookmarks_rows(&self) -> Vec<BookmarksListRow> {
+        let groups: Vec<Option<String>> = match self.bookmarks_client() {
+            Some(cli) => self
+                .bookmarks_list
+                .iter()
+                .map(|x| cli.get_bookmark_group(x).map(str::to_string))
+                .collect(),
+            None => return Vec::new(),
+        };
+        if groups.iter().all(Option::is_none) {
+            return (0..self.bookmarks_list.len())
+                .map(BookmarksListRow::Bookmark)
+                .collect();
+        }
+        let mut rows = Vec::with_capacity(groups.len());
+        let mut start = 0;
+        while start < groups.len() {
+            let group = &groups[start];
+            let size = groups[start..].iter().take_while(|x| *x == group).count();
+            let collapsed = self.collapsed_groups.contains(group);
+            rows.push(BookmarksListRow::Group {
+                name: group.clone(),
+                size,
+                collapsed,
+            });
+            if !collapsed {
+                rows.extend((start..start + size).map(BookmarksListRow::Bookmark));
+            }
+            start += size;
+        }
+        rows
+    }
+
+    /// Get the index in the bookmarks list of the bookmark at `row` of the bookmarks list.
+    /// Returns `None` if the row is a group header
+    pub(super) fn bookmark_at_row(&self, row: usize) -> Option<usize> {
+        match self.bookmarks_rows.get(row) {
+            Some(BookmarksListRow::Bookmark(idx)) => Some(*idx),
+            _ => None,
+        }
+    }
+
+    /// Collapse or expand the group whose header is at `row` of the bookmarks list
+    pub(super) fn toggle_bookmarks_group(&mut self, row: usize) {
+        if let Some(BookmarksListRow::Group { name, .. }) = self.bookmarks_rows.get(row).cloned() {
+            if !self.collapsed_groups.remove(&name) {
+                self.collapsed_groups.insert(name);
+            }
+        }
+    }
+
     /// Delete bookmark
     pub(super) fn del_bookmark(&mut self, idx: usize) {
         let name = self.bookmarks_list.get(idx).cloned();
@@ -34,7 +142,10 @@ impl AuthActivity {
                 if let Some(bookmark) = bookmarks_cli.get_bookmark(key) {
                     // Load parameters into components
                     match form_tab {
-                        FormTab::Remote => self.load_remote_bookmark_into_gui(bookmark),
+                        FormTab::Remote => {
+                            self.remote_bookmark = Some(key.clone());
+                            self.load_remote_bookmark_into_gui(bookmark);
+                        }
                         FormTab::HostBridge => self.load_host_bridge_bookmark_into_gui(bookmark),
                     }
                 }
@@ -42,36 +153,81 @@ impl AuthActivity {
         }
     }
 
-    /// Save current input fields as a bookmark
-    pub(super) fn save_bookmark(&mut self, form_tab: FormTab, name: String, save_password: bool) {
-        let params = match form_tab {
-            FormTab::Remote => match self.collect_remote_host_params() {
-                Ok(p) => p,
-                Err(e) => {
-                    self.mount_error(e);
-                    return;
-                }
-            },
-            FormTab::HostBridge => match self.collect_host_bridge_params() {
-                Ok(HostBridgeParams::Remote(protocol, params)) => FileTransferParams {
-                    protocol,
-                    params,
-                    remote_path: None,
-                    local_path: None,
-                },
-                Ok(HostBridgeParams::Localhost(_)) => {
-                    self.mount_error("You cannot save a localhost bookmark");
-                    return;
-                }
-                Err(e) => {
-                    self.mount_error(e);
-                    return;
+    /// Describe the last failed connection of the selected bookmark.
+    /// Returns `None` if the bookmarks list is not focused or if the last connection didn't fail
+    pub(super) fn selected_bookmark_failure(&self) -> Option<String> {
+        if self.app.focus() != Some(&Id::BookmarksList) {
+            return None;
+        }
+        let Ok(State::One(StateValue::Usize(idx))) = self.app.state(&Id::BookmarksList) else {
+            return None;
+        };
+        let name = self.bookmarks_list.get(self.bookmark_at_row(idx)?)?;
+        let failure = self.bookmarks_client()?.get_connection_failure(name)?;
+        Some(format!(
+            "Last connection failed on {}: {}",
+            fmt_time(failure.time(), "%b %d %Y %H:%M"),
+            failure.error
+        ))
+    }
+
+    /// Get the name of the bookmark loaded into the remote form, if the form still connects to its host
+    pub(super) fn loaded_remote_bookmark(&self) -> Option<String> {
+        let params = self.collect_remote_host_params().ok()?;
+        self.loaded_remote_params(&params)?;
+        self.remote_bookmark.clone()
+    }
+
+    /// Get the group of the bookmark loaded into the remote form, if any
+    pub(super) fn remote_bookmark_group(&self) -> Option<String> {
+        let name = self.loaded_remote_bookmark()?;
+        let name = name.as_str();
+        self.bookmarks_client()?
+            .get_bookmark_group(name)
+            .map(str::to_string)
+    }
+
+    /// Save current input fields as a bookmark, listed under `group`.
+    /// If provided, `quick_commands` replace the quick commands of the bookmark, as parsed by [`QuickCommand::parse_list`]
+    pub(super) fn save_bookmark(
+        &mut self,
+        form_tab: FormTab,
+        name: String,
+        save_password: bool,
+        group: Option<String>,
+        quick_commands: Option<String>,
+    ) {
+        let quick_commands = match quick_commands.as_deref().map(QuickCommand::parse_list) {
+            Some(Ok(commands)) => Some(commands),
+            Some(Err(err)) => {
+                self.mount_error(err);
+                return;
+            }
+            None => None,
+        };
+        let params = match self.collect_bookmark_params(form_tab) {
+            // Capture the current explorer defaults
+            Ok(p) => {
+                let prefs = p.explorer.clone().or_defaults(
+                    FileSorting::Name,
+                    self.config().get_group_dirs(),
+                    self.config().get_show_hidden_files(),
+                );
+                let p = p.explorer(prefs);
+                match quick_commands {
+                    Some(commands) => p.quick_commands(commands),
+                    None => p,
                 }
-            },
+            }
+            Err(e) => {
+                self.mount_error(e);
+                return;
+            }
         };
 
         if let Some(bookmarks_cli) = self.bookmarks_client_mut() {
             bookmarks_cli.add_bookmark(name.clone(), params, save_password);
+            bookmarks_cli.set_bookmark_group(&name, group);
             // Save bookmarks
             self.write_bookmarks();
             // Remove `name` from bookmarks if exists
@@ -81,6 +237,41 @@ impl AuthActivity {
             self.sort_bookmarks();
         }
     }
+
+    /// Get the name of an existing bookmark, other than `name`, equivalent to the one which would be saved from `form_tab`
+    pub(super) fn find_equivalent_bookmark(&self, form_tab: FormTab, name: &str) -> Option<String> {
+        let params = self.collect_bookmark_params(form_tab).ok()?;
+        self.bookmarks_client()?
+            .find_equivalent(&Bookmark::from(params))
+            .filter(|equivalent| *equivalent != name)
+            .map(str::to_string)
+    }
+
+    /// Collect the params to save as a bookmark from `form_tab`
+    fn collect_bookmark_params(
+        &self,
+        form_tab: FormTab,
+    ) -> Result<FileTransferParams, &'static str> {
+        match form_tab {
+            FormTab::Remote => self.collect_remote_host_params(),
+            FormTab::HostBridge => match self.collect_host_bridge_params()? {
+                HostBridgeParams::Remote(protocol, params) => Ok(FileTransferParams {
+                    protocol,
+                    params,
+                    remote_path: None,
+                    local_path: None,
+                    remote_shell: None,
+                    quick_commands: Vec::new(),
+                    url_mappings: Vec::new(),
+                    explorer: ExplorerPrefs::default(),
+                    ssh: SshPrefs::default(),
+                    modes: ModePrefs::default(),
+                }),
+                HostBridgeParams::Localhost(_) => Err("You cannot save a localhost bookmark"),
+            },
+        }
+    }
+
     /// Delete recent
     pub(super) fn del_recent(&mut self, idx: usize) {
         let name = self.recents_list.get(idx).cloned();
@@ -95,6 +286,30 @@ impl AuthActivity {
         }
     }
 
+    /// Get the suggested bookmark name for the selected recent (at index)
+    pub(super) fn suggest_pinned_recent_name(&self, idx: usize) -> Option<String> {
+        let key = self.recents_list.get(idx)?;
+        self.bookmarks_client()?.suggest_pinned_name(key)
+    }
+
+    /// Promote the selected recent (at index) to a bookmark called `name`
+    pub(super) fn pin_recent(&mut self, idx: usize, name: String) {
+        let key = self.recents_list.get(idx).cloned();
+        if let Some(client) = self.bookmarks_client_mut() {
+            if let Some(key) = key {
+                if client.pin_recent(&key, &name) {
+                    // Write bookmarks
+                    self.write_bookmarks();
+                    // Move element from recents to bookmarks
+                    self.recents_list.remove(idx);
+                    self.bookmarks_list.retain(|b| b.as_str() != name.as_str());
+                    self.bookmarks_list.push(name);
+                    self.sort_bookmarks();
+                }
+            }
+        }
+    }
+
     /// Load selected recent (at index) to input fields
     pub(super) fn load_recent(&mut self, form_tab: FormTab, idx: usize) {
         if let Some(client) = self.bookmarks_client() {
@@ -103,7 +318,10 @@ impl AuthActivity {
                 if let Some(bookmark) = client.get_recent(key) {
                     // Load parameters
                     match form_tab {
-                        FormTab::Remote => self.load_remote_bookmark_into_gui(bookmark),
+                        FormTab::Remote => {
+                            self.remote_bookmark = None;
+                            self.load_remote_bookmark_into_gui(bookmark);
+                        }
                         FormTab::HostBridge => self.load_host_bridge_bookmark_into_gui(bookmark),
                     }
                 }
@@ -159,11 +377,17 @@ impl AuthActivity {
 
     // -- privates
 
-    /// Sort bookmarks in list
+    /// Sort bookmarks in list by group, with the ungrouped ones last, then by name
     fn sort_bookmarks(&mut self) {
-        // Conver to lowercase when sorting
-        self.bookmarks_list
-            .sort_by(|a, b| a.to_lowercase().as_str().cmp(b.to_lowercase().as_str()));
+        let mut bookmarks_list = std::mem::take(&mut self.bookmarks_list);
+        if let Some(cli) = self.bookmarks_client() {
+            // Conver to lowercase when sorting
+            bookmarks_list.sort_by_cached_key(|x| {
+                let group = cli.get_bookmark_group(x).map(str::to_lowercase);
+                (group.is_none(), group, x.to_lowercase())
+            });
+        }
+        self.bookmarks_list = bookmarks_list;
     }
 
     /// Sort recents in list
@@ -175,7 +399,10 @@ impl AuthActivity {
     /// Load bookmark data into the gui components
     fn load_host_bridge_bookmark_into_gui(&mut self, bookmark: FileTransferParams) {
         // Load parameters into components
-        self.host_bridge_protocol = HostBridgeProtocol::Remote(bookmark.protocol);
+        self.host_bridge_protocol = match bookmark.protocol {
+            FileTransferProtocol::Localhost => HostBridgeProtocol::Localhost,
+            protocol => HostBridgeProtocol::Remote(protocol),
+        };
         self.mount_host_bridge_protocol(self.host_bridge_protocol);
         self.mount_remote_directory(
             FormTab::HostBridge,
@@ -215,6 +442,7 @@ impl AuthActivity {
     fn load_remote_bookmark_into_gui(&mut self, bookmark: FileTransferParams) {
         // Load parameters into components
         self.remote_protocol = bookmark.protocol;
+        self.loaded_remote = Some(bookmark.clone());
         self.mount_remote_protocol(bookmark.protocol);
         self.mount_remote_directory(
             FormTab::Remote,
@@ -253,6 +481,7 @@ impl AuthActivity {
         self.mount_port(form_tab, params.port);
         self.mount_username(form_tab, params.username.as_deref().unwrap_or(""));
         self.mount_password(form_tab, params.password.as_deref().unwrap_or(""));
+        self.mount_ftp_active_mode(form_tab, params.active_mode);
     }
 
     fn load_bookmark_s3_into_gui(&mut self, form_tab: FormTab, params: AwsS3Params) {
@@ -268,6 +497,18 @@ impl AuthActivity {
         self.mount_s3_security_token(form_tab, params.security_token.as_deref().unwrap_or(""));
         self.mount_s3_session_token(form_tab, params.session_token.as_deref().unwrap_or(""));
         self.mount_s3_new_path_style(form_tab, params.new_path_style);
+        self.mount_s3_requester_pays(form_tab, params.requester_pays);
+        self.mount_s3_storage_class(form_tab, params.storage_class.as_deref().unwrap_or(""));
+        self.mount_s3_accept_invalid_certs(form_tab, params.accept_invalid_certs);
+        self.mount_s3_ca_bundle(
+            form_tab,
+            params
+                .ca_bundle
+                .as_deref()
+                .map(|x| x.to_string_lossy().to_string())
+                .unwrap_or_default()
+                .as_str(),
+        );
     }
 
     fn load_bookmark_kube_into_gui(&mut self, form_tab: FormTab, params: KubeProtocolParams) {
@@ -5,21 +5,45 @@
 use tui_realm_stdlib::{Input, List, Radio};
 use tuirealm::command::{Cmd, CmdResult, Direction, Position};
 use tuirealm::event::{Key, KeyEvent, KeyModifiers};
-use tuirealm::props::{Alignment, BorderSides, BorderType, Borders, Color, InputType, TextSpan};
+use tuirealm::props::{
+    Alignment, BorderSides, BorderType, Borders, Color, InputType, Table, TextSpan,
+};
 use tuirealm::{Component, Event, MockComponent, NoUserEvent, State, StateValue};
 
 use super::{FormMsg, Msg, UiMsg};
+use crate::system::health_check::HostHealth;
 use crate::ui::activities::auth::FormTab;
 
 // -- bookmark list
 
+/// A row of the bookmarks list
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BookmarksListRow {
+    /// Header of a group of bookmarks; `None` for the ungrouped bookmarks
+    Group {
+        name: Option<String>,
+        size: usize,
+        collapsed: bool,
+    },
+    /// Bookmark at index in the bookmarks list
+    Bookmark(usize),
+}
+
 #[derive(MockComponent)]
 pub struct BookmarksList {
     component: List,
+    rows: Vec<BookmarksListRow>,
 }
 
 impl BookmarksList {
-    pub fn new(bookmarks: &[String], color: Color) -> Self {
+    /// Instantiates a new bookmarks list, made of `rows`, rendering `bookmarks` by index.
+    /// If `health` is provided, an health indicator is rendered next to each bookmark
+    pub fn new(
+        rows: Vec<BookmarksListRow>,
+        bookmarks: &[String],
+        health: Option<&[Option<HostHealth>]>,
+        color: Color,
+    ) -> Self {
         Self {
             component: List::default()
                 .borders(Borders::default().color(color).modifiers(BorderType::Plain))
@@ -28,12 +52,60 @@ impl BookmarksList {
                 .scroll(true)
                 .step(4)
                 .title("Bookmarks", Alignment::Left)
-                .rows(
-                    bookmarks
-                        .iter()
-                        .map(|x| vec![TextSpan::from(x.as_str())])
-                        .collect(),
-                ),
+                .rows(Self::rows(&rows, bookmarks, health)),
+            rows,
+        }
+    }
+
+    /// Build the rows for the bookmarks list
+    pub fn rows(
+        rows: &[BookmarksListRow],
+        bookmarks: &[String],
+        health: Option<&[Option<HostHealth>]>,
+    ) -> Table {
+        let grouped = rows
+            .iter()
+            .any(|x| matches!(x, BookmarksListRow::Group { .. }));
+        rows.iter()
+            .map(|row| match row {
+                BookmarksListRow::Group {
+                    name,
+                    size,
+                    collapsed,
+                } => {
+                    let arrow = if *collapsed { "▸" } else { "▾" };
+                    vec![TextSpan::from(format!(
+                        "{arrow} {} ({size})",
+                        name.as_deref().unwrap_or("Ungrouped")
+                    ))
+                    .bold()]
+                }
+                BookmarksListRow::Bookmark(i) => {
+                    let mut spans = Vec::with_capacity(3);
+                    if grouped {
+                        spans.push(TextSpan::from("  "));
+                    }
+                    if let Some(health) = health {
+                        spans.push(match health.get(*i).copied().flatten() {
+                            Some(HostHealth::Reachable) => TextSpan::from("● ").fg(Color::Green),
+                            Some(HostHealth::Unreachable) => TextSpan::from("● ").fg(Color::Red),
+                            None => TextSpan::from("  "),
+                        });
+                    }
+                    spans.push(TextSpan::from(
+                        bookmarks.get(*i).map(String::as_str).unwrap_or_default(),
+                    ));
+                    spans
+                }
+            })
+            .collect()
+    }
+
+    /// Get the selected row
+    fn selected_row(&self) -> Option<(usize, &BookmarksListRow)> {
+        match self.state() {
+            State::One(StateValue::Usize(row)) => self.rows.get(row).map(|x| (row, x)),
+            _ => None,
         }
     }
 }
@@ -76,21 +148,48 @@ impl Component<Msg, NoUserEvent> for BookmarksList {
             }
             Event::Keyboard(KeyEvent {
                 code: Key::Enter, ..
-            }) => match self.state() {
-                State::One(StateValue::Usize(choice)) => {
-                    Some(Msg::Form(FormMsg::LoadBookmark(choice)))
+            }) => match self.selected_row() {
+                Some((row, BookmarksListRow::Group { .. })) => {
+                    Some(Msg::Ui(UiMsg::ToggleBookmarksGroup(row)))
                 }
-                _ => Some(Msg::None),
+                Some((_, BookmarksListRow::Bookmark(choice))) => {
+                    Some(Msg::Form(FormMsg::LoadBookmark(*choice)))
+                }
+                None => Some(Msg::None),
             },
             Event::Keyboard(KeyEvent {
                 code: Key::Right, ..
-            }) => Some(Msg::Ui(UiMsg::BookmarksListBlur)),
+            }) => match self.selected_row() {
+                Some((
+                    row,
+                    BookmarksListRow::Group {
+                        collapsed: true, ..
+                    },
+                )) => Some(Msg::Ui(UiMsg::ToggleBookmarksGroup(row))),
+                _ => Some(Msg::Ui(UiMsg::BookmarksListBlur)),
+            },
+            Event::Keyboard(KeyEvent {
+                code: Key::Left, ..
+            }) => match self.selected_row() {
+                Some((
+                    row,
+                    BookmarksListRow::Group {
+                        collapsed: false, ..
+                    },
+                )) => Some(Msg::Ui(UiMsg::ToggleBookmarksGroup(row))),
+                _ => None,
+            },
             Event::Keyboard(KeyEvent { code: Key::Tab, .. }) => {
                 Some(Msg::Ui(UiMsg::BookmarksTabBlur))
             }
             Event::Keyboard(KeyEvent {
                 code: Key::Delete, ..
-            }) => Some(Msg::Ui(UiMsg::ShowDeleteBookmarkPopup)),
+            }) => match self.selected_row() {
+                Some((_, BookmarksListRow::Bookmark(_))) => {
+                    Some(Msg::Ui(UiMsg::ShowDeleteBookmarkPopup))
+                }
+                _ => Some(Msg::None),
+            },
             _ => None,
         }
     }
@@ -176,6 +275,10 @@ impl Component<Msg, NoUserEvent> for RecentsList {
             Event::Keyboard(KeyEvent {
                 code: Key::Delete, ..
             }) => Some(Msg::Ui(UiMsg::ShowDeleteRecentPopup)),
+            Event::Keyboard(KeyEvent {
+                code: Key::Char('p'),
+                modifiers: KeyModifiers::NONE,
+            }) => Some(Msg::Ui(UiMsg::ShowPinRecentPopup)),
             _ => None,
         }
     }
@@ -249,6 +352,75 @@ impl Component<Msg, NoUserEvent> for DeleteBookmarkPopup {
     }
 }
 
+// -- duplicate bookmark
+
+#[derive(MockComponent)]
+pub struct DuplicateBookmarkPopup {
+    component: Radio,
+    form_tab: FormTab,
+    equivalent: String,
+}
+
+impl DuplicateBookmarkPopup {
+    pub fn new(form_tab: FormTab, equivalent: &str, color: Color) -> Self {
+        Self {
+            component: Radio::default()
+                .borders(
+                    Borders::default()
+                        .color(color)
+                        .modifiers(BorderType::Rounded),
+                )
+                .choices(&["Update it", "Save anyway"])
+                .rewind(true)
+                .foreground(color)
+                .title(
+                    format!("An equivalent bookmark \"{equivalent}\" already exists"),
+                    Alignment::Center,
+                ),
+            form_tab,
+            equivalent: equivalent.to_string(),
+        }
+    }
+}
+
+impl Component<Msg, NoUserEvent> for DuplicateBookmarkPopup {
+    fn on(&mut self, ev: Event<NoUserEvent>) -> Option<Msg> {
+        match ev {
+            Event::Keyboard(KeyEvent { code: Key::Esc, .. }) => {
+                Some(Msg::Ui(UiMsg::CloseDuplicateBookmark))
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Left, ..
+            }) => {
+                self.perform(Cmd::Move(Direction::Left));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Right, ..
+            }) => {
+                self.perform(Cmd::Move(Direction::Right));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Enter, ..
+            }) => {
+                if matches!(
+                    self.perform(Cmd::Submit),
+                    CmdResult::Submit(State::One(StateValue::Usize(0)))
+                ) {
+                    Some(Msg::Form(FormMsg::UpdateBookmark(
+                        self.form_tab,
+                        self.equivalent.clone(),
+                    )))
+                } else {
+                    Some(Msg::Form(FormMsg::SaveDuplicateBookmark(self.form_tab)))
+                }
+            }
+            _ => None,
+        }
+    }
+}
+
 // -- delete recent
 
 #[derive(MockComponent)]
@@ -317,7 +489,86 @@ impl Component<Msg, NoUserEvent> for DeleteRecentPopup {
     }
 }
 
-// -- bookmark name
+// -- pin recent
+
+#[derive(MockComponent)]
+pub struct PinRecentPopup {
+    component: Input,
+}
+
+impl PinRecentPopup {
+    /// Instantiates the popup asking the name of the bookmark to pin the selected recent as, prefilled with `name`
+    pub fn new(name: &str, color: Color) -> Self {
+        Self {
+            component: Input::default()
+                .borders(
+                    Borders::default()
+                        .color(color)
+                        .modifiers(BorderType::Rounded),
+                )
+                .foreground(color)
+                .title("Pin recent host as bookmark", Alignment::Left)
+                .input_type(InputType::Text)
+                .value(name),
+        }
+    }
+}
+
+impl Component<Msg, NoUserEvent> for PinRecentPopup {
+    fn on(&mut self, ev: Event<NoUserEvent>) -> Option<Msg> {
+        match ev {
+            Event::Keyboard(KeyEvent { code: Key::Esc, .. }) => {
+                Some(Msg::Ui(UiMsg::ClosePinRecent))
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Left, ..
+            }) => {
+                self.perform(Cmd::Move(Direction::Left));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Right, ..
+            }) => {
+                self.perform(Cmd::Move(Direction::Right));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Home, ..
+            }) => {
+                self.perform(Cmd::GoTo(Position::Begin));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent { code: Key::End, .. }) => {
+                self.perform(Cmd::GoTo(Position::End));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Delete, ..
+            }) => {
+                self.perform(Cmd::Cancel);
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Backspace,
+                ..
+            }) => {
+                self.perform(Cmd::Delete);
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Char(ch),
+                ..
+            }) => {
+                self.perform(Cmd::Type(ch));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Enter, ..
+            }) => Some(Msg::Form(FormMsg::PinRecent)),
+            _ => None,
+        }
+    }
+}
 
 // -- save password
 
@@ -460,3 +711,183 @@ impl Component<Msg, NoUserEvent> for BookmarkName {
         }
     }
 }
+
+// -- new bookmark group
+
+#[derive(MockComponent)]
+pub struct BookmarkGroup {
+    component: Input,
+    form_tab: FormTab,
+}
+
+impl BookmarkGroup {
+    /// Instantiates the input of the group to list the new bookmark under, prefilled with `group`
+    pub fn new(form_tab: FormTab, group: &str, color: Color) -> Self {
+        Self {
+            component: Input::default()
+                .borders(
+                    Borders::default()
+                        .color(Color::Reset)
+                        .sides(BorderSides::TOP | BorderSides::LEFT | BorderSides::RIGHT)
+                        .modifiers(BorderType::Rounded),
+                )
+                .foreground(color)
+                .title("Group (optional)", Alignment::Left)
+                .input_type(InputType::Text)
+                .value(group),
+            form_tab,
+        }
+    }
+}
+
+impl Component<Msg, NoUserEvent> for BookmarkGroup {
+    fn on(&mut self, ev: Event<NoUserEvent>) -> Option<Msg> {
+        match ev {
+            Event::Keyboard(KeyEvent { code: Key::Esc, .. }) => {
+                Some(Msg::Ui(UiMsg::CloseSaveBookmark))
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Left, ..
+            }) => {
+                self.perform(Cmd::Move(Direction::Left));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Right, ..
+            }) => {
+                self.perform(Cmd::Move(Direction::Right));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Home, ..
+            }) => {
+                self.perform(Cmd::GoTo(Position::Begin));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent { code: Key::End, .. }) => {
+                self.perform(Cmd::GoTo(Position::End));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Delete, ..
+            }) => {
+                self.perform(Cmd::Cancel);
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Backspace,
+                ..
+            }) => {
+                self.perform(Cmd::Delete);
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Char(ch),
+                ..
+            }) => {
+                self.perform(Cmd::Type(ch));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Enter, ..
+            }) => Some(Msg::Form(FormMsg::SaveBookmark(self.form_tab))),
+            Event::Keyboard(KeyEvent { code: Key::Up, .. }) => {
+                Some(Msg::Ui(UiMsg::BookmarkGroupBlurUp))
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Down, ..
+            }) => Some(Msg::Ui(UiMsg::BookmarkGroupBlurDown)),
+            _ => None,
+        }
+    }
+}
+
+// -- new bookmark quick commands
+
+#[derive(MockComponent)]
+pub struct BookmarkQuickCommands {
+    component: Input,
+    form_tab: FormTab,
+}
+
+impl BookmarkQuickCommands {
+    /// Instantiates the input of the quick commands of the new bookmark, prefilled with `commands`
+    pub fn new(form_tab: FormTab, commands: &str, color: Color) -> Self {
+        Self {
+            component: Input::default()
+                .borders(
+                    Borders::default()
+                        .color(Color::Reset)
+                        .sides(BorderSides::TOP | BorderSides::LEFT | BorderSides::RIGHT)
+                        .modifiers(BorderType::Rounded),
+                )
+                .foreground(color)
+                .title("Quick commands (label=command ;; …)", Alignment::Left)
+                .input_type(InputType::Text)
+                .value(commands),
+            form_tab,
+        }
+    }
+}
+
+impl Component<Msg, NoUserEvent> for BookmarkQuickCommands {
+    fn on(&mut self, ev: Event<NoUserEvent>) -> Option<Msg> {
+        match ev {
+            Event::Keyboard(KeyEvent { code: Key::Esc, .. }) => {
+                Some(Msg::Ui(UiMsg::CloseSaveBookmark))
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Left, ..
+            }) => {
+                self.perform(Cmd::Move(Direction::Left));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Right, ..
+            }) => {
+                self.perform(Cmd::Move(Direction::Right));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Home, ..
+            }) => {
+                self.perform(Cmd::GoTo(Position::Begin));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent { code: Key::End, .. }) => {
+                self.perform(Cmd::GoTo(Position::End));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Delete, ..
+            }) => {
+                self.perform(Cmd::Cancel);
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Backspace,
+                ..
+            }) => {
+                self.perform(Cmd::Delete);
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Char(ch),
+                ..
+            }) => {
+                self.perform(Cmd::Type(ch));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Enter, ..
+            }) => Some(Msg::Form(FormMsg::SaveBookmark(self.form_tab))),
+            Event::Keyboard(KeyEvent { code: Key::Up, .. }) => {
+                Some(Msg::Ui(UiMsg::BookmarkQuickCommandsBlurUp))
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Down, ..
+            }) => Some(Msg::Ui(UiMsg::BookmarkQuickCommandsBlurDown)),
+            _ => None,
+        }
+    }
+}
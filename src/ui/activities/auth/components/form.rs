@@ -6,7 +6,9 @@ use tui_realm_stdlib::{Input, Radio};
 use tuirealm::command::{Cmd, CmdResult, Direction, Position};
 use tuirealm::event::{Key, KeyEvent, KeyModifiers};
 use tuirealm::props::{Alignment, BorderType, Borders, Color, InputType, Style};
-use tuirealm::{Component, Event, MockComponent, NoUserEvent, State, StateValue};
+use tuirealm::{
+    AttrValue, Attribute, Component, Event, MockComponent, NoUserEvent, State, StateValue,
+};
 
 use super::{FileTransferProtocol, FormMsg, Msg, UiMsg};
 use crate::ui::activities::auth::{
@@ -15,9 +17,9 @@ use crate::ui::activities::auth::{
     HOST_BRIDGE_RADIO_PROTOCOL_LOCALHOST, HOST_BRIDGE_RADIO_PROTOCOL_S3,
     HOST_BRIDGE_RADIO_PROTOCOL_SCP, HOST_BRIDGE_RADIO_PROTOCOL_SFTP,
     HOST_BRIDGE_RADIO_PROTOCOL_SMB, HOST_BRIDGE_RADIO_PROTOCOL_WEBDAV, REMOTE_RADIO_PROTOCOL_FTP,
-    REMOTE_RADIO_PROTOCOL_FTPS, REMOTE_RADIO_PROTOCOL_KUBE, REMOTE_RADIO_PROTOCOL_S3,
-    REMOTE_RADIO_PROTOCOL_SCP, REMOTE_RADIO_PROTOCOL_SFTP, REMOTE_RADIO_PROTOCOL_SMB,
-    REMOTE_RADIO_PROTOCOL_WEBDAV,
+    REMOTE_RADIO_PROTOCOL_FTPS, REMOTE_RADIO_PROTOCOL_KUBE, REMOTE_RADIO_PROTOCOL_LOCALHOST,
+    REMOTE_RADIO_PROTOCOL_S3, REMOTE_RADIO_PROTOCOL_SCP, REMOTE_RADIO_PROTOCOL_SFTP,
+    REMOTE_RADIO_PROTOCOL_SMB, REMOTE_RADIO_PROTOCOL_WEBDAV,
 };
 
 // -- protocol
@@ -37,9 +39,28 @@ impl RemoteProtocolRadio {
                         .modifiers(BorderType::Rounded),
                 )
                 .choices(if cfg!(smb) {
-                    &["SFTP", "SCP", "FTP", "FTPS", "S3", "Kube", "WebDAV", "SMB"]
+                    &[
+                        "SFTP",
+                        "SCP",
+                        "FTP",
+                        "FTPS",
+                        "S3",
+                        "Kube",
+                        "WebDAV",
+                        "Localhost",
+                        "SMB",
+                    ]
                 } else {
-                    &["SFTP", "SCP", "FTP", "FTPS", "S3", "Kube", "WebDAV"]
+                    &[
+                        "SFTP",
+                        "SCP",
+                        "FTP",
+                        "FTPS",
+                        "S3",
+                        "Kube",
+                        "WebDAV",
+                        "Localhost",
+                    ]
                 })
                 .foreground(color)
                 .rewind(true)
@@ -58,6 +79,7 @@ impl RemoteProtocolRadio {
             REMOTE_RADIO_PROTOCOL_SMB => FileTransferProtocol::Smb,
             REMOTE_RADIO_PROTOCOL_KUBE => FileTransferProtocol::Kube,
             REMOTE_RADIO_PROTOCOL_WEBDAV => FileTransferProtocol::WebDAV,
+            REMOTE_RADIO_PROTOCOL_LOCALHOST => FileTransferProtocol::Localhost,
             _ => FileTransferProtocol::Sftp,
         }
     }
@@ -73,6 +95,7 @@ impl RemoteProtocolRadio {
             FileTransferProtocol::Kube => REMOTE_RADIO_PROTOCOL_KUBE,
             FileTransferProtocol::Smb => REMOTE_RADIO_PROTOCOL_SMB,
             FileTransferProtocol::WebDAV => REMOTE_RADIO_PROTOCOL_WEBDAV,
+            FileTransferProtocol::Localhost => REMOTE_RADIO_PROTOCOL_LOCALHOST,
         }
     }
 }
@@ -159,7 +182,10 @@ impl HostBridgeProtocolRadio {
 
     fn protocol_to_opt(protocol: HostBridgeProtocol) -> usize {
         match protocol {
-            HostBridgeProtocol::Localhost => HOST_BRIDGE_RADIO_PROTOCOL_LOCALHOST,
+            HostBridgeProtocol::Localhost
+            | HostBridgeProtocol::Remote(FileTransferProtocol::Localhost) => {
+                HOST_BRIDGE_RADIO_PROTOCOL_LOCALHOST
+            }
             HostBridgeProtocol::Remote(FileTransferProtocol::Sftp) => {
                 HOST_BRIDGE_RADIO_PROTOCOL_SFTP
             }
@@ -423,10 +449,17 @@ impl Component<Msg, NoUserEvent> for InputPort {
 
 // -- username
 
-#[derive(MockComponent)]
+/// Attribute to set the usernames suggested on `<TAB>`
+pub const ATTR_USERNAME_SUGGESTIONS: &str = "username-suggestions";
+
 pub struct InputUsername {
     component: Input,
     form_tab: FormTab,
+    suggestions: Vec<String>,
+    /// Text typed by the user, before any suggestion was applied
+    search: Option<String>,
+    /// Last suggestion applied
+    last_suggestion: Option<String>,
 }
 
 impl InputUsername {
@@ -444,12 +477,108 @@ impl InputUsername {
                 .input_type(InputType::Text)
                 .value(username),
             form_tab,
+            suggestions: Vec::new(),
+            search: None,
+            last_suggestion: None,
+        }
+    }
+
+    /// Apply the next suggestion matching the text typed by the user.
+    /// Returns whether a suggestion was applied; once they've all been applied, returns false
+    fn suggest(&mut self) -> bool {
+        let search = self
+            .search
+            .clone()
+            .unwrap_or_else(|| self.component.state().unwrap_one().unwrap_string());
+        let candidates: Vec<&String> = self
+            .suggestions
+            .iter()
+            .filter(|x| x.starts_with(search.as_str()) && **x != search)
+            .collect();
+        let next = match self.last_suggestion.take() {
+            Some(last) => candidates
+                .iter()
+                .skip_while(|x| ***x != last)
+                .nth(1)
+                .map(|x| x.to_string()),
+            None => candidates.first().map(|x| x.to_string()),
+        };
+        match next {
+            Some(suggestion) => {
+                self.component
+                    .attr(Attribute::Value, AttrValue::String(suggestion.clone()));
+                self.search = Some(search);
+                self.last_suggestion = Some(suggestion);
+                true
+            }
+            None => {
+                self.search = None;
+                false
+            }
+        }
+    }
+}
+
+impl MockComponent for InputUsername {
+    fn view(&mut self, frame: &mut tuirealm::Frame, area: tuirealm::ratatui::prelude::Rect) {
+        self.component.view(frame, area);
+    }
+
+    fn attr(&mut self, attr: Attribute, value: AttrValue) {
+        match attr {
+            Attribute::Custom(ATTR_USERNAME_SUGGESTIONS) => {
+                self.suggestions = value
+                    .unwrap_payload()
+                    .unwrap_vec()
+                    .into_iter()
+                    .map(|x| x.unwrap_str())
+                    .collect();
+                self.search = None;
+                self.last_suggestion = None;
+                let title = match self.suggestions.is_empty() {
+                    true => "Username",
+                    false => "Username (<TAB> for suggestions)",
+                };
+                self.component.attr(
+                    Attribute::Title,
+                    AttrValue::Title((title.to_string(), Alignment::Left)),
+                );
+            }
+            _ => self.component.attr(attr, value),
+        }
+    }
+
+    fn query(&self, attr: Attribute) -> Option<AttrValue> {
+        self.component.query(attr)
+    }
+
+    fn state(&self) -> State {
+        self.component.state()
+    }
+
+    fn perform(&mut self, cmd: Cmd) -> CmdResult {
+        let res = self.component.perform(cmd);
+        if matches!(res, CmdResult::Changed(_)) {
+            // the user edited the value, so suggestions start over
+            self.search = None;
+            self.last_suggestion = None;
         }
+        res
     }
 }
 
 impl Component<Msg, NoUserEvent> for InputUsername {
     fn on(&mut self, ev: Event<NoUserEvent>) -> Option<Msg> {
+        if matches!(
+            ev,
+            Event::Keyboard(KeyEvent {
+                code: Key::Tab,
+                modifiers: KeyModifiers::NONE
+            })
+        ) && self.suggest()
+        {
+            return Some(Msg::None);
+        }
         let on_key_down = match self.form_tab {
             FormTab::Remote => Msg::Ui(UiMsg::Remote(UiAuthFormMsg::UsernameBlurDown)),
             FormTab::HostBridge => Msg::Ui(UiMsg::HostBridge(UiAuthFormMsg::UsernameBlurDown)),
@@ -633,6 +762,77 @@ impl Component<Msg, NoUserEvent> for InputS3Endpoint {
     }
 }
 
+// -- ftp active mode
+
+#[derive(MockComponent)]
+pub struct RadioFtpActiveMode {
+    component: Radio,
+    form_tab: FormTab,
+}
+
+impl RadioFtpActiveMode {
+    pub fn new(active_mode: bool, form_tab: FormTab, color: Color) -> Self {
+        Self {
+            component: Radio::default()
+                .borders(
+                    Borders::default()
+                        .color(color)
+                        .modifiers(BorderType::Rounded),
+                )
+                .choices(&["Yes", "No"])
+                .foreground(color)
+                .rewind(true)
+                .title("Active mode", Alignment::Left)
+                .value(usize::from(!active_mode)),
+            form_tab,
+        }
+    }
+}
+
+impl Component<Msg, NoUserEvent> for RadioFtpActiveMode {
+    fn on(&mut self, ev: Event<NoUserEvent>) -> Option<Msg> {
+        match ev {
+            Event::Keyboard(KeyEvent {
+                code: Key::Left, ..
+            }) => {
+                self.perform(Cmd::Move(Direction::Left));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Right, ..
+            }) => {
+                self.perform(Cmd::Move(Direction::Right));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Enter, ..
+            }) => Some(Msg::Form(FormMsg::Connect)),
+            Event::Keyboard(KeyEvent {
+                code: Key::Down, ..
+            }) => Some(if self.form_tab == FormTab::Remote {
+                Msg::Ui(UiMsg::Remote(UiAuthFormMsg::FtpActiveModeBlurDown))
+            } else {
+                Msg::Ui(UiMsg::HostBridge(UiAuthFormMsg::FtpActiveModeBlurDown))
+            }),
+            Event::Keyboard(KeyEvent { code: Key::Up, .. }) => {
+                Some(if self.form_tab == FormTab::Remote {
+                    Msg::Ui(UiMsg::Remote(UiAuthFormMsg::FtpActiveModeBlurUp))
+                } else {
+                    Msg::Ui(UiMsg::HostBridge(UiAuthFormMsg::FtpActiveModeBlurUp))
+                })
+            }
+            Event::Keyboard(KeyEvent { code: Key::Tab, .. }) => {
+                Some(if self.form_tab == FormTab::Remote {
+                    Msg::Ui(UiMsg::Remote(UiAuthFormMsg::ParamsFormBlur))
+                } else {
+                    Msg::Ui(UiMsg::HostBridge(UiAuthFormMsg::ParamsFormBlur))
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
 // -- s3 new path style
 
 #[derive(MockComponent)]
@@ -704,6 +904,242 @@ impl Component<Msg, NoUserEvent> for RadioS3NewPathStyle {
     }
 }
 
+// -- s3 requester pays
+
+#[derive(MockComponent)]
+pub struct RadioS3RequesterPays {
+    component: Radio,
+    form_tab: FormTab,
+}
+
+impl RadioS3RequesterPays {
+    pub fn new(requester_pays: bool, form_tab: FormTab, color: Color) -> Self {
+        Self {
+            component: Radio::default()
+                .borders(
+                    Borders::default()
+                        .color(color)
+                        .modifiers(BorderType::Rounded),
+                )
+                .choices(&["Yes", "No"])
+                .foreground(color)
+                .rewind(true)
+                .title("Requester pays", Alignment::Left)
+                .value(usize::from(!requester_pays)),
+            form_tab,
+        }
+    }
+}
+
+impl Component<Msg, NoUserEvent> for RadioS3RequesterPays {
+    fn on(&mut self, ev: Event<NoUserEvent>) -> Option<Msg> {
+        match ev {
+            Event::Keyboard(KeyEvent {
+                code: Key::Left, ..
+            }) => {
+                self.perform(Cmd::Move(Direction::Left));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Right, ..
+            }) => {
+                self.perform(Cmd::Move(Direction::Right));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Enter, ..
+            }) => Some(Msg::Form(FormMsg::Connect)),
+            Event::Keyboard(KeyEvent {
+                code: Key::Down, ..
+            }) => Some(if self.form_tab == FormTab::Remote {
+                Msg::Ui(UiMsg::Remote(UiAuthFormMsg::S3RequesterPaysBlurDown))
+            } else {
+                Msg::Ui(UiMsg::HostBridge(UiAuthFormMsg::S3RequesterPaysBlurDown))
+            }),
+            Event::Keyboard(KeyEvent { code: Key::Up, .. }) => {
+                Some(if self.form_tab == FormTab::Remote {
+                    Msg::Ui(UiMsg::Remote(UiAuthFormMsg::S3RequesterPaysBlurUp))
+                } else {
+                    Msg::Ui(UiMsg::HostBridge(UiAuthFormMsg::S3RequesterPaysBlurUp))
+                })
+            }
+            Event::Keyboard(KeyEvent { code: Key::Tab, .. }) => {
+                Some(if self.form_tab == FormTab::Remote {
+                    Msg::Ui(UiMsg::Remote(UiAuthFormMsg::ParamsFormBlur))
+                } else {
+                    Msg::Ui(UiMsg::HostBridge(UiAuthFormMsg::ParamsFormBlur))
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
+// -- s3 storage class
+
+#[derive(MockComponent)]
+pub struct InputS3StorageClass {
+    component: Input,
+    form_tab: FormTab,
+}
+
+impl InputS3StorageClass {
+    pub fn new(storage_class: &str, form_tab: FormTab, color: Color) -> Self {
+        Self {
+            component: Input::default()
+                .borders(
+                    Borders::default()
+                        .color(color)
+                        .modifiers(BorderType::Rounded),
+                )
+                .foreground(color)
+                .placeholder(
+                    "STANDARD_IA",
+                    Style::default().fg(Color::Rgb(128, 128, 128)),
+                )
+                .title("Storage class", Alignment::Left)
+                .input_type(InputType::Text)
+                .value(storage_class),
+            form_tab,
+        }
+    }
+}
+
+impl Component<Msg, NoUserEvent> for InputS3StorageClass {
+    fn on(&mut self, ev: Event<NoUserEvent>) -> Option<Msg> {
+        let on_key_down = match self.form_tab {
+            FormTab::Remote => Msg::Ui(UiMsg::Remote(UiAuthFormMsg::S3StorageClassBlurDown)),
+            FormTab::HostBridge => {
+                Msg::Ui(UiMsg::HostBridge(UiAuthFormMsg::S3StorageClassBlurDown))
+            }
+        };
+        let on_key_up = match self.form_tab {
+            FormTab::Remote => Msg::Ui(UiMsg::Remote(UiAuthFormMsg::S3StorageClassBlurUp)),
+            FormTab::HostBridge => Msg::Ui(UiMsg::HostBridge(UiAuthFormMsg::S3StorageClassBlurUp)),
+        };
+        let form_tab = self.form_tab;
+        handle_input_ev(self, ev, on_key_down, on_key_up, form_tab)
+    }
+}
+
+// -- s3 accept invalid certs
+
+#[derive(MockComponent)]
+pub struct RadioS3AcceptInvalidCerts {
+    component: Radio,
+    form_tab: FormTab,
+}
+
+impl RadioS3AcceptInvalidCerts {
+    pub fn new(accept_invalid_certs: bool, form_tab: FormTab, color: Color) -> Self {
+        Self {
+            component: Radio::default()
+                .borders(
+                    Borders::default()
+                        .color(color)
+                        .modifiers(BorderType::Rounded),
+                )
+                .choices(&["Yes", "No"])
+                .foreground(color)
+                .rewind(true)
+                .title("Accept invalid certificates", Alignment::Left)
+                .value(usize::from(!accept_invalid_certs)),
+            form_tab,
+        }
+    }
+}
+
+impl Component<Msg, NoUserEvent> for RadioS3AcceptInvalidCerts {
+    fn on(&mut self, ev: Event<NoUserEvent>) -> Option<Msg> {
+        match ev {
+            Event::Keyboard(KeyEvent {
+                code: Key::Left, ..
+            }) => {
+                self.perform(Cmd::Move(Direction::Left));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Right, ..
+            }) => {
+                self.perform(Cmd::Move(Direction::Right));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Enter, ..
+            }) => Some(Msg::Form(FormMsg::Connect)),
+            Event::Keyboard(KeyEvent {
+                code: Key::Down, ..
+            }) => Some(if self.form_tab == FormTab::Remote {
+                Msg::Ui(UiMsg::Remote(UiAuthFormMsg::S3AcceptInvalidCertsBlurDown))
+            } else {
+                Msg::Ui(UiMsg::HostBridge(
+                    UiAuthFormMsg::S3AcceptInvalidCertsBlurDown,
+                ))
+            }),
+            Event::Keyboard(KeyEvent { code: Key::Up, .. }) => {
+                Some(if self.form_tab == FormTab::Remote {
+                    Msg::Ui(UiMsg::Remote(UiAuthFormMsg::S3AcceptInvalidCertsBlurUp))
+                } else {
+                    Msg::Ui(UiMsg::HostBridge(UiAuthFormMsg::S3AcceptInvalidCertsBlurUp))
+                })
+            }
+            Event::Keyboard(KeyEvent { code: Key::Tab, .. }) => {
+                Some(if self.form_tab == FormTab::Remote {
+                    Msg::Ui(UiMsg::Remote(UiAuthFormMsg::ParamsFormBlur))
+                } else {
+                    Msg::Ui(UiMsg::HostBridge(UiAuthFormMsg::ParamsFormBlur))
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
+// -- s3 ca bundle
+
+#[derive(MockComponent)]
+pub struct InputS3CaBundle {
+    component: Input,
+    form_tab: FormTab,
+}
+
+impl InputS3CaBundle {
+    pub fn new(ca_bundle: &str, form_tab: FormTab, color: Color) -> Self {
+        Self {
+            component: Input::default()
+                .borders(
+                    Borders::default()
+                        .color(color)
+                        .modifiers(BorderType::Rounded),
+                )
+                .foreground(color)
+                .placeholder(
+                    "/etc/ssl/certs/minio.pem",
+                    Style::default().fg(Color::Rgb(128, 128, 128)),
+                )
+                .title("CA bundle", Alignment::Left)
+                .input_type(InputType::Text)
+                .value(ca_bundle),
+            form_tab,
+        }
+    }
+}
+
+impl Component<Msg, NoUserEvent> for InputS3CaBundle {
+    fn on(&mut self, ev: Event<NoUserEvent>) -> Option<Msg> {
+        let on_key_down = match self.form_tab {
+            FormTab::Remote => Msg::Ui(UiMsg::Remote(UiAuthFormMsg::S3CaBundleBlurDown)),
+            FormTab::HostBridge => Msg::Ui(UiMsg::HostBridge(UiAuthFormMsg::S3CaBundleBlurDown)),
+        };
+        let on_key_up = match self.form_tab {
+            FormTab::Remote => Msg::Ui(UiMsg::Remote(UiAuthFormMsg::S3CaBundleBlurUp)),
+            FormTab::HostBridge => Msg::Ui(UiMsg::HostBridge(UiAuthFormMsg::S3CaBundleBlurUp)),
+        };
+        let form_tab = self.form_tab;
+        handle_input_ev(self, ev, on_key_down, on_key_up, form_tab)
+    }
+}
+
 // -- s3 profile
 
 #[derive(MockComponent)]
@@ -1301,7 +1737,7 @@ fn handle_input_ev(
         }) => Some(Msg::Form(FormMsg::Connect)),
         Event::Keyboard(KeyEvent {
             // NOTE: escaped control sequence
-            code: Key::Char('c') | Key::Char('h') | Key::Char('r') | Key::Char('s'),
+            code: Key::Char('c') | Key::Char('h') | Key::Char('r') | Key::Char('s') | Key::Char('t'),
             modifiers: KeyModifiers::CONTROL,
         }) => Some(Msg::None),
         Event::Keyboard(KeyEvent {
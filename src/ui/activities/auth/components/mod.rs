@@ -10,24 +10,26 @@ mod popup;
 mod text;
 
 pub use bookmarks::{
-    BookmarkName, BookmarkSavePassword, BookmarksList, DeleteBookmarkPopup, DeleteRecentPopup,
-    RecentsList,
+    BookmarkGroup, BookmarkName, BookmarkQuickCommands, BookmarkSavePassword, BookmarksList,
+    BookmarksListRow, DeleteBookmarkPopup, DeleteRecentPopup, DuplicateBookmarkPopup,
+    PinRecentPopup, RecentsList,
 };
 #[cfg(posix)]
 pub use form::InputSmbWorkgroup;
 pub use form::{
     HostBridgeProtocolRadio, InputAddress, InputKubeClientCert, InputKubeClientKey,
     InputKubeClusterUrl, InputKubeNamespace, InputKubeUsername, InputLocalDirectory, InputPassword,
-    InputPort, InputRemoteDirectory, InputS3AccessKey, InputS3Bucket, InputS3Endpoint,
-    InputS3Profile, InputS3Region, InputS3SecretAccessKey, InputS3SecurityToken,
-    InputS3SessionToken, InputSmbShare, InputUsername, InputWebDAVUri, RadioS3NewPathStyle,
-    RemoteProtocolRadio,
+    InputPort, InputRemoteDirectory, InputS3AccessKey, InputS3Bucket, InputS3CaBundle,
+    InputS3Endpoint, InputS3Profile, InputS3Region, InputS3SecretAccessKey, InputS3SecurityToken,
+    InputS3SessionToken, InputS3StorageClass, InputSmbShare, InputUsername, InputWebDAVUri,
+    RadioFtpActiveMode, RadioS3AcceptInvalidCerts, RadioS3NewPathStyle, RadioS3RequesterPays,
+    RemoteProtocolRadio, ATTR_USERNAME_SUGGESTIONS,
 };
 pub use popup::{
     ErrorPopup, InfoPopup, InstallUpdatePopup, Keybindings, QuitPopup, ReleaseNotes, WaitPopup,
     WindowSizeError,
 };
-pub use text::{HelpFooter, NewVersionDisclaimer, Subtitle, Title};
+pub use text::{BookmarkConnectionFailure, HelpFooter, NewVersionDisclaimer, Subtitle, Title};
 use tui_realm_stdlib::Phantom;
 use tuirealm::event::{Event, Key, KeyEvent, KeyModifiers, NoUserEvent};
 use tuirealm::{Component, MockComponent};
@@ -66,6 +68,10 @@ impl Component<Msg, NoUserEvent> for GlobalListener {
                 code: Key::Char('s'),
                 modifiers: KeyModifiers::CONTROL,
             }) => Some(Msg::Ui(UiMsg::ShowSaveBookmarkPopup)),
+            Event::Keyboard(KeyEvent {
+                code: Key::Char('t'),
+                modifiers: KeyModifiers::CONTROL,
+            }) => Some(Msg::Form(FormMsg::TestConnection)),
             Event::WindowResize(_, _) => Some(Msg::Ui(UiMsg::WindowResized)),
             _ => None,
         }
@@ -389,14 +389,25 @@ impl Keybindings {
                         .add_col(TextSpan::new("<ENTER>").bold().fg(color))
                         .add_col(TextSpan::from("         Connect/Load bookmark"))
                         .add_row()
+                        .add_col(TextSpan::new("<ENTER|→|←>").bold().fg(color))
+                        .add_col(TextSpan::from("     Expand/collapse bookmarks group"))
+                        .add_row()
                         .add_col(TextSpan::new("<DEL|E>").bold().fg(color))
                         .add_col(TextSpan::from("         Delete selected bookmark"))
                         .add_row()
+                        .add_col(TextSpan::new("<P>").bold().fg(color))
+                        .add_col(TextSpan::from(
+                            "             Pin selected recent as bookmark",
+                        ))
+                        .add_row()
                         .add_col(TextSpan::new("<CTRL+C>").bold().fg(color))
                         .add_col(TextSpan::from("        Enter setup"))
                         .add_row()
                         .add_col(TextSpan::new("<CTRL+S>").bold().fg(color))
                         .add_col(TextSpan::from("        Save bookmark"))
+                        .add_row()
+                        .add_col(TextSpan::new("<CTRL+T>").bold().fg(color))
+                        .add_col(TextSpan::from("        Test connection"))
                         .build(),
                 ),
         }
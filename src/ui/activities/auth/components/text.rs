@@ -54,6 +54,29 @@ impl Component<Msg, NoUserEvent> for Subtitle {
     }
 }
 
+// -- bookmark connection failure
+
+#[derive(MockComponent)]
+pub struct BookmarkConnectionFailure {
+    component: Label,
+}
+
+impl BookmarkConnectionFailure {
+    pub fn new(color: Color) -> Self {
+        Self {
+            component: Label::default()
+                .foreground(color)
+                .modifiers(TextModifiers::DIM | TextModifiers::ITALIC),
+        }
+    }
+}
+
+impl Component<Msg, NoUserEvent> for BookmarkConnectionFailure {
+    fn on(&mut self, _ev: Event<NoUserEvent>) -> Option<Msg> {
+        None
+    }
+}
+
 // -- new version disclaimer
 
 #[derive(MockComponent)]
@@ -104,6 +127,8 @@ impl HelpFooter {
                 TextSpan::from(" Switch form "),
                 TextSpan::from("<ENTER>").bold().fg(key_color),
                 TextSpan::from(" Submit form "),
+                TextSpan::from("<CTRL+T>").bold().fg(key_color),
+                TextSpan::from(" Test connection "),
                 TextSpan::from("<F10|ESC>").bold().fg(key_color),
                 TextSpan::from(" Quit "),
             ]),
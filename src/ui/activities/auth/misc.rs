@@ -5,10 +5,14 @@
 use std::env;
 
 use super::{AuthActivity, FileTransferParams, FileTransferProtocol, FormTab, HostBridgeProtocol};
-use crate::filetransfer::params::ProtocolParams;
+use crate::config::bookmarks::Bookmark;
+use crate::filetransfer::params::{ExplorerPrefs, ModePrefs, ProtocolParams, SshPrefs};
 use crate::filetransfer::HostBridgeParams;
 use crate::system::auto_update::{Release, Update, UpdateStatus};
+use crate::system::bookmarks_client::BookmarksClient;
+use crate::system::connection_test::{self, ConnectionTest};
 use crate::system::notifications::Notification;
+use crate::utils::ssh as ssh_utils;
 
 impl AuthActivity {
     /// Get the default port for protocol
@@ -18,6 +22,7 @@ impl AuthActivity {
             FileTransferProtocol::Ftp(_) => 21,
             FileTransferProtocol::AwsS3 => 22, // Doesn't matter, since not used
             FileTransferProtocol::Kube => 22,  // Doesn't matter, since not used
+            FileTransferProtocol::Localhost => 22, // Doesn't matter, since not used
             FileTransferProtocol::Smb => 445,
             FileTransferProtocol::WebDAV => 80, // Doesn't matter, since not used
         }
@@ -48,6 +53,7 @@ impl AuthActivity {
                     FileTransferProtocol::Kube => {
                         self.collect_kube_host_params(FormTab::HostBridge)
                     }
+                    FileTransferProtocol::Localhost => return self.collect_localhost_host_params(),
                     FileTransferProtocol::Smb => self.collect_smb_host_params(FormTab::HostBridge),
                     FileTransferProtocol::Ftp(_)
                     | FileTransferProtocol::Scp
@@ -69,9 +75,10 @@ impl AuthActivity {
 
     /// Collect host params as `FileTransferParams`
     pub(super) fn collect_remote_host_params(&self) -> Result<FileTransferParams, &'static str> {
-        match self.remote_protocol {
+        let params = match self.remote_protocol {
             FileTransferProtocol::AwsS3 => self.collect_s3_host_params(FormTab::Remote),
             FileTransferProtocol::Kube => self.collect_kube_host_params(FormTab::Remote),
+            FileTransferProtocol::Localhost => self.collect_localhost_remote_params(),
             FileTransferProtocol::Smb => self.collect_smb_host_params(FormTab::Remote),
             FileTransferProtocol::Ftp(_)
             | FileTransferProtocol::Scp
@@ -79,7 +86,66 @@ impl AuthActivity {
                 self.collect_generic_host_params(self.remote_protocol, FormTab::Remote)
             }
             FileTransferProtocol::WebDAV => self.collect_webdav_host_params(FormTab::Remote),
+        }?;
+        Ok(with_loaded_prefs(params, self.loaded_remote.as_ref()))
+    }
+
+    /// Get the parameters of the bookmark or recent loaded into the remote form, if `params`, collected from the form,
+    /// still connect to its host
+    pub(super) fn loaded_remote_params(
+        &self,
+        params: &FileTransferParams,
+    ) -> Option<&FileTransferParams> {
+        self.loaded_remote
+            .as_ref()
+            .filter(|loaded| is_same_bookmark_host(params, loaded))
+    }
+
+    /// Start testing the connection with the parameters of the last focused form tab, showing the wait popup.
+    /// Nothing is saved to the recent connections
+    pub(super) fn test_connection(&mut self) {
+        let params = match self.last_form_tab {
+            FormTab::Remote => self.collect_remote_host_params(),
+            FormTab::HostBridge => self
+                .collect_host_bridge_params()
+                .map(|params| match params {
+                    HostBridgeParams::Localhost(path) => FileTransferParams::new(
+                        FileTransferProtocol::Localhost,
+                        ProtocolParams::default(),
+                    )
+                    .remote_path(Some(path)),
+                    HostBridgeParams::Remote(protocol, params) => {
+                        FileTransferParams::new(protocol, params)
+                    }
+                }),
+        };
+        let params = match params {
+            Ok(params) => params,
+            Err(err) => {
+                self.mount_error(err);
+                return;
+            }
+        };
+        self.connection_test = Some(ConnectionTest::start(
+            params,
+            self.config().clone(),
+            connection_test::TEST_TIMEOUT,
+        ));
+        self.mount_wait("Testing connection…");
+    }
+
+    /// Report the outcome of the connection test, once terminated
+    pub(super) fn poll_connection_test(&mut self) {
+        let Some(result) = self.connection_test.as_ref().and_then(|test| test.poll()) else {
+            return;
+        };
+        self.connection_test = None;
+        self.umount_wait();
+        match result {
+            Ok(message) => self.mount_info(message),
+            Err(err) => self.mount_error(format!("Connection test failed: {err}")),
         }
+        self.redraw = true;
     }
 
     fn collect_localhost_host_params(&self) -> Result<HostBridgeParams, &'static str> {
@@ -90,6 +156,15 @@ impl AuthActivity {
         Ok(HostBridgeParams::Localhost(path))
     }
 
+    /// Get input values from fields to browse the local host in the remote panel
+    fn collect_localhost_remote_params(&self) -> Result<FileTransferParams, &'static str> {
+        Ok(
+            FileTransferParams::new(FileTransferProtocol::Localhost, ProtocolParams::default())
+                .remote_path(self.get_input_remote_directory(FormTab::Remote))
+                .local_path(self.get_input_local_directory(FormTab::Remote)),
+        )
+    }
+
     /// Get input values from fields or return an error if fields are invalid to work as generic
     pub(super) fn collect_generic_host_params(
         &self,
@@ -108,6 +183,12 @@ impl AuthActivity {
             params: ProtocolParams::Generic(params),
             local_path: self.get_input_local_directory(form_tab),
             remote_path: self.get_input_remote_directory(form_tab),
+            remote_shell: None,
+            quick_commands: Vec::new(),
+            url_mappings: Vec::new(),
+            explorer: ExplorerPrefs::default(),
+            ssh: SshPrefs::default(),
+            modes: ModePrefs::default(),
         })
     }
 
@@ -125,6 +206,12 @@ impl AuthActivity {
             params: ProtocolParams::AwsS3(params),
             local_path: self.get_input_local_directory(form_tab),
             remote_path: self.get_input_remote_directory(form_tab),
+            remote_shell: None,
+            quick_commands: Vec::new(),
+            url_mappings: Vec::new(),
+            explorer: ExplorerPrefs::default(),
+            ssh: SshPrefs::default(),
+            modes: ModePrefs::default(),
         })
     }
 
@@ -140,6 +227,12 @@ impl AuthActivity {
             params: ProtocolParams::Kube(params),
             local_path: self.get_input_local_directory(form_tab),
             remote_path: self.get_input_remote_directory(form_tab),
+            remote_shell: None,
+            quick_commands: Vec::new(),
+            url_mappings: Vec::new(),
+            explorer: ExplorerPrefs::default(),
+            ssh: SshPrefs::default(),
+            modes: ModePrefs::default(),
         })
     }
 
@@ -155,6 +248,8 @@ impl AuthActivity {
         if params.port == 0 {
             return Err("Invalid port");
         }
+        // on unix, the shares of the server are browsed if the share is empty
+        #[cfg(smb_windows)]
         if params.share.is_empty() {
             return Err("Invalid share");
         }
@@ -163,6 +258,12 @@ impl AuthActivity {
             params: ProtocolParams::Smb(params),
             local_path: self.get_input_local_directory(form_tab),
             remote_path: self.get_input_remote_directory(form_tab),
+            remote_shell: None,
+            quick_commands: Vec::new(),
+            url_mappings: Vec::new(),
+            explorer: ExplorerPrefs::default(),
+            ssh: SshPrefs::default(),
+            modes: ModePrefs::default(),
         })
     }
 
@@ -179,9 +280,42 @@ impl AuthActivity {
             params: ProtocolParams::WebDAV(params),
             local_path: self.get_input_local_directory(form_tab),
             remote_path: self.get_input_remote_directory(form_tab),
+            remote_shell: None,
+            quick_commands: Vec::new(),
+            url_mappings: Vec::new(),
+            explorer: ExplorerPrefs::default(),
+            ssh: SshPrefs::default(),
+            modes: ModePrefs::default(),
         })
     }
 
+    /// Get the usernames to suggest for `address`: the user set for the host in the ssh configuration,
+    /// then the usernames used in bookmarks and recents, then the current username
+    pub(super) fn username_suggestions(&self, address: &str) -> Vec<String> {
+        let mut suggestions: Vec<String> = Vec::new();
+        if !address.is_empty() {
+            if let Some(user) = self
+                .config()
+                .get_ssh_config()
+                .and_then(|path| ssh_utils::parse_ssh2_config(path).ok())
+                .and_then(|config| config.query(address).user)
+            {
+                suggestions.push(user);
+            }
+            if let Some(bookmarks_client) = self.bookmarks_client() {
+                suggestions.extend(bookmarks_client.usernames(address));
+            }
+        }
+        suggestions.push(whoami::username());
+        let mut usernames: Vec<String> = Vec::with_capacity(suggestions.len());
+        for username in suggestions {
+            if !usernames.contains(&username) {
+                usernames.push(username);
+            }
+        }
+        usernames
+    }
+
     // -- update install
 
     /// If enabled in configuration, check for updates from Github
@@ -227,6 +361,21 @@ impl AuthActivity {
         }
     }
 
+    /// Report the changes applied migrating the configuration and the bookmarks from a legacy format.
+    /// Files are migrated once, so the report is shown only the first time
+    pub(super) fn report_migrations(&mut self) {
+        let mut changes = self.context_mut().config_mut().take_migration_report();
+        if let Some(client) = self.bookmarks_client_mut() {
+            changes.extend(client.take_migration_report());
+        }
+        if !changes.is_empty() {
+            self.mount_info(format!(
+                "Files written by an older version of termscp have been migrated: {}",
+                changes.join("; ")
+            ));
+        }
+    }
+
     /// Install latest termscp version via GUI
     pub(super) fn install_update(&mut self) {
         // Umount release notes
@@ -257,3 +406,111 @@ impl AuthActivity {
         }
     }
 }
+
+/// Returns whether `a` and `b` connect to the same host with the same user
+fn is_same_bookmark_host(a: &FileTransferParams, b: &FileTransferParams) -> bool {
+    BookmarksClient::is_equivalent(&Bookmark::from(a.clone()), &Bookmark::from(b.clone()))
+}
+
+/// Attach the remote shell, quick commands, URL mappings, explorer, ssh and mode preferences of `loaded`, the parameters
+/// loaded into the remote form, to `params`, collected from the form, along with the FTPS TLS session reuse, which has
+/// no field in the form.
+/// They're not attached if the form has been edited to connect to another host
+fn with_loaded_prefs(
+    params: FileTransferParams,
+    loaded: Option<&FileTransferParams>,
+) -> FileTransferParams {
+    match loaded.filter(|loaded| is_same_bookmark_host(&params, loaded)) {
+        Some(loaded) => {
+            let mut params = params
+                .remote_shell(loaded.remote_shell.as_deref())
+                .quick_commands(loaded.quick_commands.clone())
+                .url_mappings(loaded.url_mappings.clone())
+                .explorer(loaded.explorer.clone())
+                .ssh(loaded.ssh.clone())
+                .modes(loaded.modes);
+            if let (ProtocolParams::Generic(generic), Some(loaded)) =
+                (&mut params.params, loaded.params.generic_params())
+            {
+                generic.tls_session_reuse = loaded.tls_session_reuse;
+            }
+            params
+        }
+        None => params,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::filetransfer::params::{GenericProtocolParams, QuickCommand};
+
+    fn sftp_params(address: &str, password: Option<&str>) -> FileTransferParams {
+        FileTransferParams::new(
+            FileTransferProtocol::Sftp,
+            ProtocolParams::Generic(
+                GenericProtocolParams::default()
+                    .address(address)
+                    .port(22)
+                    .username(Some("omar"))
+                    .password(password),
+            ),
+        )
+    }
+
+    #[test]
+    fn should_attach_prefs_of_loaded_bookmark() {
+        let loaded = sftp_params("10.0.0.1", None)
+            .remote_shell(Some("/bin/bash"))
+            .quick_commands(vec![QuickCommand::new("disk usage", "df -h")]);
+        // the password has been amended
+        let params = with_loaded_prefs(sftp_params("10.0.0.1", Some("secret")), Some(&loaded));
+        assert_eq!(params.remote_shell.as_deref(), Some("/bin/bash"));
+        assert_eq!(params.quick_commands, loaded.quick_commands);
+    }
+
+    #[test]
+    fn should_attach_tls_session_reuse_of_loaded_bookmark() {
+        let ftps_params = |tls_session_reuse: bool| {
+            FileTransferParams::new(
+                FileTransferProtocol::Ftp(true),
+                ProtocolParams::Generic(
+                    GenericProtocolParams::default()
+                        .address("10.0.0.1")
+                        .port(21)
+                        .username(Some("omar"))
+                        .tls_session_reuse(tls_session_reuse),
+                ),
+            )
+        };
+        let params = with_loaded_prefs(ftps_params(true), Some(&ftps_params(false)));
+        assert_eq!(
+            params.params.generic_params().unwrap().tls_session_reuse,
+            false
+        );
+    }
+
+    #[test]
+    fn should_not_attach_prefs_of_loaded_bookmark_to_another_host() {
+        let loaded = sftp_params("10.0.0.1", None)
+            .remote_shell(Some("/bin/bash"))
+            .quick_commands(vec![QuickCommand::new("disk usage", "df -h")]);
+        let params = with_loaded_prefs(sftp_params("10.0.0.2", None), Some(&loaded));
+        assert!(params.remote_shell.is_none());
+        assert!(params.quick_commands.is_empty());
+        // the protocol has changed
+        let params = FileTransferParams {
+            protocol: FileTransferProtocol::Scp,
+            ..sftp_params("10.0.0.1", None)
+        };
+        assert!(with_loaded_prefs(params, Some(&loaded))
+            .remote_shell
+            .is_none());
+        assert!(with_loaded_prefs(sftp_params("10.0.0.1", None), None)
+            .remote_shell
+            .is_none());
+    }
+}
@@ -11,17 +11,23 @@ mod view;
 
 // locals
 // Includes
+use std::collections::HashSet;
+use std::fmt;
+use std::str::FromStr;
 use std::time::Duration;
 
 use tuirealm::application::PollStrategy;
 use tuirealm::listener::EventListenerCfg;
 use tuirealm::{Application, NoUserEvent, Update};
 
+use self::components::BookmarksListRow;
 use super::{Activity, Context, ExitReason, CROSSTERM_MAX_POLL};
 use crate::config::themes::Theme;
 use crate::filetransfer::{FileTransferParams, FileTransferProtocol};
 use crate::system::bookmarks_client::BookmarksClient;
 use crate::system::config_client::ConfigClient;
+use crate::system::connection_test::ConnectionTest;
+use crate::utils::tty;
 
 // host bridge protocol radio
 const HOST_BRIDGE_RADIO_PROTOCOL_LOCALHOST: usize = 0;
@@ -42,15 +48,20 @@ const REMOTE_RADIO_PROTOCOL_FTPS: usize = 3;
 const REMOTE_RADIO_PROTOCOL_S3: usize = 4;
 const REMOTE_RADIO_PROTOCOL_KUBE: usize = 5;
 const REMOTE_RADIO_PROTOCOL_WEBDAV: usize = 6;
-const REMOTE_RADIO_PROTOCOL_SMB: usize = 7; // Keep as last
+const REMOTE_RADIO_PROTOCOL_LOCALHOST: usize = 7;
+const REMOTE_RADIO_PROTOCOL_SMB: usize = 8; // Keep as last
 
 // -- components
 #[derive(Debug, Eq, PartialEq, Clone, Hash)]
 pub enum Id {
+    BookmarkConnectionFailure,
+    BookmarkGroup,
     BookmarkName,
+    BookmarkQuickCommands,
     BookmarkSavePassword,
     BookmarksList,
     DeleteBookmarkPopup,
+    DuplicateBookmarkPopup,
     DeleteRecentPopup,
     ErrorPopup,
     GlobalListener,
@@ -61,6 +72,7 @@ pub enum Id {
     Keybindings,
     NewVersionChangelog,
     NewVersionDisclaimer,
+    PinRecentPopup,
     QuitPopup,
     RecentsList,
     Remote(AuthFormId),
@@ -73,6 +85,7 @@ pub enum Id {
 #[derive(Debug, Eq, PartialEq, Clone, Hash)]
 pub enum AuthFormId {
     Address,
+    FtpActiveMode,
     KubeNamespace,
     KubeClusterUrl,
     KubeUsername,
@@ -83,8 +96,12 @@ pub enum AuthFormId {
     Port,
     Protocol,
     RemoteDirectory,
+    S3RequesterPays,
+    S3AcceptInvalidCerts,
     S3AccessKey,
     S3Bucket,
+    S3CaBundle,
+    S3StorageClass,
     S3Endpoint,
     S3NewPathStyle,
     S3Profile,
@@ -115,22 +132,32 @@ enum FormMsg {
     InstallUpdate,
     LoadBookmark(usize),
     LoadRecent(usize),
+    PinRecent,
     HostBridgeProtocolChanged(HostBridgeProtocol),
     RemoteProtocolChanged(FileTransferProtocol),
     Quit,
     SaveBookmark(FormTab),
+    SaveDuplicateBookmark(FormTab),
+    TestConnection,
+    UpdateBookmark(FormTab, String),
 }
 
 #[derive(Debug, PartialEq, Eq)]
 pub enum UiMsg {
+    BookmarkGroupBlurDown,
+    BookmarkGroupBlurUp,
+    BookmarkQuickCommandsBlurDown,
+    BookmarkQuickCommandsBlurUp,
     BookmarksListBlur,
     BookmarksTabBlur,
     CloseDeleteBookmark,
     CloseDeleteRecent,
+    CloseDuplicateBookmark,
     CloseErrorPopup,
     CloseInfoPopup,
     CloseInstallUpdatePopup,
     CloseKeybindingsPopup,
+    ClosePinRecent,
     CloseQuitPopup,
     CloseSaveBookmark,
     HostBridge(UiAuthFormMsg),
@@ -141,9 +168,11 @@ pub enum UiMsg {
     ShowDeleteBookmarkPopup,
     ShowDeleteRecentPopup,
     ShowKeybindingsPopup,
+    ShowPinRecentPopup,
     ShowQuitPopup,
     ShowReleaseNotes,
     ShowSaveBookmarkPopup,
+    ToggleBookmarksGroup(usize),
     WindowResized,
 }
 
@@ -152,6 +181,8 @@ pub enum UiAuthFormMsg {
     AddressBlurDown,
     AddressBlurUp,
     ChangeFormTab,
+    FtpActiveModeBlurDown,
+    FtpActiveModeBlurUp,
     KubeNamespaceBlurDown,
     KubeNamespaceBlurUp,
     KubeClusterUrlBlurDown,
@@ -173,10 +204,18 @@ pub enum UiAuthFormMsg {
     ProtocolBlurUp,
     RemoteDirectoryBlurDown,
     RemoteDirectoryBlurUp,
+    S3RequesterPaysBlurDown,
+    S3RequesterPaysBlurUp,
+    S3AcceptInvalidCertsBlurDown,
+    S3AcceptInvalidCertsBlurUp,
     S3AccessKeyBlurDown,
     S3AccessKeyBlurUp,
     S3BucketBlurDown,
     S3BucketBlurUp,
+    S3CaBundleBlurDown,
+    S3CaBundleBlurUp,
+    S3StorageClassBlurDown,
+    S3StorageClassBlurUp,
     S3EndpointBlurDown,
     S3EndpointBlurUp,
     S3NewPathStyleBlurDown,
@@ -226,6 +265,41 @@ enum FormTab {
     Remote,
 }
 
+/// Component which is focused when the authentication activity starts
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum StartupFocus {
+    #[default]
+    Form,
+    Bookmarks,
+    Recents,
+}
+
+impl fmt::Display for StartupFocus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Self::Form => "form",
+                Self::Bookmarks => "bookmarks",
+                Self::Recents => "recents",
+            }
+        )
+    }
+}
+
+impl FromStr for StartupFocus {
+    type Err = ();
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "form" => Ok(Self::Form),
+            "bookmarks" => Ok(Self::Bookmarks),
+            "recents" => Ok(Self::Recents),
+            _ => Err(()),
+        }
+    }
+}
+
 // Store keys
 const STORE_KEY_LATEST_VERSION: &str = "AUTH_LATEST_VERSION";
 const STORE_KEY_RELEASE_NOTES: &str = "AUTH_RELEASE_NOTES";
@@ -235,6 +309,10 @@ pub struct AuthActivity {
     app: Application<Id, Msg, NoUserEvent>,
     /// List of bookmarks
     bookmarks_list: Vec<String>,
+    /// Rows of the bookmarks list, with the headers of the groups
+    bookmarks_rows: Vec<BookmarksListRow>,
+    /// Groups collapsed in the bookmarks list; `None` for the ungrouped bookmarks
+    collapsed_groups: HashSet<Option<String>>,
     /// List of recent hosts
     recents_list: Vec<String>,
     /// Exit reason
@@ -246,6 +324,15 @@ pub struct AuthActivity {
     last_form_tab: FormTab,
     /// Remote file transfer protocol
     remote_protocol: FileTransferProtocol,
+    /// Parameters of the bookmark or recent loaded into the remote form, with its remote shell, quick commands,
+    /// URL mappings, explorer, ssh and mode preferences. They apply only while the form connects to the same host
+    loaded_remote: Option<FileTransferParams>,
+    /// Name of the bookmark loaded into the remote form
+    remote_bookmark: Option<String>,
+    /// Whether the auth form is collapsed, until it gets focused
+    form_collapsed: bool,
+    /// Connection test running for the form parameters
+    connection_test: Option<ConnectionTest>,
     context: Option<Context>,
 }
 
@@ -260,12 +347,18 @@ impl AuthActivity {
             ),
             context: None,
             bookmarks_list: Vec::new(),
+            bookmarks_rows: Vec::new(),
+            collapsed_groups: HashSet::new(),
             exit_reason: None,
             last_form_tab: FormTab::Remote,
             recents_list: Vec::new(),
             redraw: true,
             host_bridge_protocol: HostBridgeProtocol::Localhost,
             remote_protocol: FileTransferProtocol::Sftp,
+            loaded_remote: None,
+            remote_bookmark: None,
+            form_collapsed: false,
+            connection_test: None,
         }
     }
 
@@ -312,6 +405,17 @@ impl AuthActivity {
         }
     }
 
+    /// Returns whether the protocol selected in the form tab is FTP(S), which has the data connection mode
+    fn is_ftp(&self, form_tab: FormTab) -> bool {
+        match form_tab {
+            FormTab::Remote => matches!(self.remote_protocol, FileTransferProtocol::Ftp(_)),
+            FormTab::HostBridge => matches!(
+                self.host_bridge_protocol,
+                HostBridgeProtocol::Remote(FileTransferProtocol::Ftp(_))
+            ),
+        }
+    }
+
     /// Get input mask for protocol
     fn file_transfer_protocol_input_mask(protocol: FileTransferProtocol) -> InputMask {
         match protocol {
@@ -320,6 +424,7 @@ impl AuthActivity {
             | FileTransferProtocol::Scp
             | FileTransferProtocol::Sftp => InputMask::Generic,
             FileTransferProtocol::Kube => InputMask::Kube,
+            FileTransferProtocol::Localhost => InputMask::Localhost,
             FileTransferProtocol::Smb => InputMask::Smb,
             FileTransferProtocol::WebDAV => InputMask::WebDAV,
         }
@@ -351,9 +456,12 @@ impl Activity for AuthActivity {
         // Init bookmarks client
         if self.bookmarks_client().is_some() {
             self.init_bookmarks_client();
+            self.check_bookmarks_health();
             self.view_bookmarks();
             self.view_recent_connections();
         }
+        self.init_startup_focus();
+        self.report_migrations();
         // Verify error state from context
         if let Some(err) = self.context_mut().error() {
             self.mount_error(err.as_str());
@@ -382,6 +490,14 @@ impl Activity for AuthActivity {
                 self.mount_error(format!("Application error: {err}"));
             }
         }
+        // Collect bookmarks health
+        self.poll_bookmarks_health();
+        // Collect the connection test outcome
+        self.poll_connection_test();
+        // Close the idle parked sessions
+        if let Some(ctx) = self.context.as_mut() {
+            ctx.close_expired_sessions();
+        }
         // View
         if self.redraw {
             self.view();
@@ -395,6 +511,23 @@ impl Activity for AuthActivity {
         self.exit_reason.as_ref()
     }
 
+    /// `on_suspend` is the function which must be called before the process is stopped (e.g. on `SIGTSTP`).
+    /// It gives the terminal back to the shell
+    fn on_suspend(&mut self) {
+        if let Some(ctx) = self.context.as_mut() {
+            tty::leave_terminal(ctx.terminal());
+        }
+    }
+
+    /// `on_resume` is the function which must be called once the process has been resumed (`SIGCONT`).
+    /// It takes the terminal back and forces a full redraw of the interface
+    fn on_resume(&mut self) {
+        if let Some(ctx) = self.context.as_mut() {
+            tty::enter_terminal(ctx.terminal());
+        }
+        self.redraw = true;
+    }
+
     /// `on_destroy` is the function which cleans up runtime variables and data before terminating the activity.
     /// This function must be called once before terminating the activity.
     /// This function finally releases the context
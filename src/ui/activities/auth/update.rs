@@ -2,6 +2,7 @@
 //!
 //! Update impl
 
+use tuirealm::props::{AttrValue, Attribute, PropPayload, PropValue};
 use tuirealm::{State, StateValue};
 
 use super::{
@@ -12,11 +13,31 @@ use super::{
 impl Update<Msg> for AuthActivity {
     fn update(&mut self, msg: Option<Msg>) -> Option<Msg> {
         self.redraw = true;
-        match msg.unwrap_or(Msg::None) {
+        let msg = match msg.unwrap_or(Msg::None) {
             Msg::Form(msg) => self.update_form(msg),
-            Msg::Ui(msg) => self.update_ui(msg),
+            Msg::Ui(msg) => {
+                let msg = self.update_ui(msg);
+                // Suggest usernames for the address once the username gets focused
+                match self.app.focus() {
+                    Some(Id::HostBridge(AuthFormId::Username)) => {
+                        self.update_username_suggestions(FormTab::HostBridge)
+                    }
+                    Some(Id::Remote(AuthFormId::Username)) => {
+                        self.update_username_suggestions(FormTab::Remote)
+                    }
+                    _ => {}
+                }
+                msg
+            }
             Msg::None => None,
+        };
+        // Expand the form once it gets focused
+        if self.form_collapsed
+            && matches!(self.app.focus(), Some(Id::HostBridge(_) | Id::Remote(_)))
+        {
+            self.form_collapsed = false;
         }
+        msg
     }
 }
 
@@ -36,9 +57,12 @@ impl AuthActivity {
                     return None;
                 };
 
+                // the bookmark is the one of the params, unless the form has been edited to connect to another host
+                let remote_bookmark = self.loaded_remote_bookmark();
                 self.save_recent();
                 // Set file transfer params to context
                 self.context_mut().set_remote_params(remote_params);
+                self.context_mut().set_remote_bookmark(remote_bookmark);
                 // set host bridge params
                 self.context_mut()
                     .set_host_bridge_params(host_bridge_params);
@@ -46,13 +70,15 @@ impl AuthActivity {
                 self.exit_reason = Some(super::ExitReason::Connect);
             }
             FormMsg::DeleteBookmark => {
-                if let Ok(State::One(StateValue::Usize(idx))) = self.app.state(&Id::BookmarksList) {
+                if let Ok(State::One(StateValue::Usize(row))) = self.app.state(&Id::BookmarksList) {
                     // Umount dialog
                     self.umount_bookmark_del_dialog();
-                    // Delete bookmark
-                    self.del_bookmark(idx);
-                    // Update bookmarks
-                    self.view_bookmarks()
+                    // Delete bookmark; group headers can't be deleted
+                    if let Some(idx) = self.bookmark_at_row(row) {
+                        self.del_bookmark(idx);
+                        // Update bookmarks
+                        self.view_bookmarks()
+                    }
                 }
             }
             FormMsg::DeleteRecent => {
@@ -65,9 +91,25 @@ impl AuthActivity {
                     self.view_recent_connections();
                 }
             }
+            FormMsg::PinRecent => {
+                let name = self.get_pinned_recent_name();
+                // Umount dialog
+                self.umount_recent_pin_dialog();
+                if let Ok(State::One(StateValue::Usize(idx))) = self.app.state(&Id::RecentsList) {
+                    if !name.is_empty() {
+                        self.pin_recent(idx, name);
+                        // Update bookmarks and recents
+                        self.view_bookmarks();
+                        self.view_recent_connections();
+                    }
+                }
+            }
             FormMsg::EnterSetup => {
                 self.exit_reason = Some(ExitReason::EnterSetup);
             }
+            FormMsg::TestConnection => {
+                self.test_connection();
+            }
             FormMsg::InstallUpdate => {
                 self.install_update();
             }
@@ -76,7 +118,7 @@ impl AuthActivity {
                 // Give focus to input password (or to protocol if not generic)
                 let focus = match self.last_form_tab {
                     FormTab::Remote => match self.remote_input_mask() {
-                        InputMask::Localhost => &Id::Remote(AuthFormId::LocalDirectory),
+                        InputMask::Localhost => &Id::Remote(AuthFormId::RemoteDirectory),
                         InputMask::Generic => &Id::Remote(AuthFormId::Password),
                         InputMask::Smb => &Id::Remote(AuthFormId::Password),
                         InputMask::AwsS3 => &Id::Remote(AuthFormId::S3Bucket),
@@ -100,7 +142,7 @@ impl AuthActivity {
                 // Give focus to input password (or to protocol if not generic)
                 let focus = match self.last_form_tab {
                     FormTab::Remote => match self.remote_input_mask() {
-                        InputMask::Localhost => &Id::Remote(AuthFormId::LocalDirectory),
+                        InputMask::Localhost => &Id::Remote(AuthFormId::RemoteDirectory),
                         InputMask::Generic => &Id::Remote(AuthFormId::Password),
                         InputMask::Smb => &Id::Remote(AuthFormId::Password),
                         InputMask::AwsS3 => &Id::Remote(AuthFormId::S3Bucket),
@@ -148,15 +190,25 @@ impl AuthActivity {
             }
             FormMsg::SaveBookmark(form_tab) => {
                 // get bookmark name
-                let (name, save_password) = self.get_new_bookmark();
-                // Save bookmark
+                let (name, save_password, group, quick_commands) = self.get_new_bookmark();
+                // Ask what to do if an equivalent bookmark already exists
                 if !name.is_empty() {
-                    self.save_bookmark(form_tab, name, save_password);
+                    if let Some(equivalent) = self.find_equivalent_bookmark(form_tab, &name) {
+                        self.mount_duplicate_bookmark_dialog(form_tab, &equivalent);
+                        return None;
+                    }
                 }
-                // Umount popup
-                self.umount_bookmark_save_dialog();
-                // Reload bookmarks
-                self.view_bookmarks()
+                self.save_new_bookmark(form_tab, name, save_password, group, quick_commands);
+            }
+            FormMsg::SaveDuplicateBookmark(form_tab) => {
+                let (name, save_password, group, quick_commands) = self.get_new_bookmark();
+                self.umount_duplicate_bookmark_dialog();
+                self.save_new_bookmark(form_tab, name, save_password, group, quick_commands);
+            }
+            FormMsg::UpdateBookmark(form_tab, name) => {
+                let (_, save_password, group, quick_commands) = self.get_new_bookmark();
+                self.umount_duplicate_bookmark_dialog();
+                self.save_new_bookmark(form_tab, name, save_password, group, quick_commands);
             }
         }
         None
@@ -193,8 +245,24 @@ impl AuthActivity {
                 assert!(self.app.active(&Id::RecentsList).is_ok());
             }
             UiMsg::BookmarkNameBlur => {
+                assert!(self.app.active(&Id::BookmarkGroup).is_ok());
+            }
+            UiMsg::BookmarkGroupBlurDown => {
+                let id = match self.app.mounted(&Id::BookmarkQuickCommands) {
+                    true => &Id::BookmarkQuickCommands,
+                    false => &Id::BookmarkSavePassword,
+                };
+                assert!(self.app.active(id).is_ok());
+            }
+            UiMsg::BookmarkGroupBlurUp => {
+                assert!(self.app.active(&Id::BookmarkName).is_ok());
+            }
+            UiMsg::BookmarkQuickCommandsBlurDown => {
                 assert!(self.app.active(&Id::BookmarkSavePassword).is_ok());
             }
+            UiMsg::BookmarkQuickCommandsBlurUp => {
+                assert!(self.app.active(&Id::BookmarkGroup).is_ok());
+            }
             UiMsg::BookmarksTabBlur => {
                 assert!(self
                     .app
@@ -218,6 +286,10 @@ impl AuthActivity {
             UiMsg::CloseDeleteRecent => {
                 assert!(self.app.umount(&Id::DeleteRecentPopup).is_ok());
             }
+            UiMsg::CloseDuplicateBookmark => {
+                self.umount_duplicate_bookmark_dialog();
+                assert!(self.app.active(&Id::BookmarkName).is_ok());
+            }
             UiMsg::CloseErrorPopup => {
                 self.umount_error();
             }
@@ -231,10 +303,12 @@ impl AuthActivity {
             UiMsg::CloseKeybindingsPopup => {
                 self.umount_help();
             }
+            UiMsg::ClosePinRecent => {
+                self.umount_recent_pin_dialog();
+            }
             UiMsg::CloseQuitPopup => self.umount_quit(),
             UiMsg::CloseSaveBookmark => {
-                assert!(self.app.umount(&Id::BookmarkName).is_ok());
-                assert!(self.app.umount(&Id::BookmarkSavePassword).is_ok());
+                self.umount_bookmark_save_dialog();
             }
             UiMsg::HostBridge(UiAuthFormMsg::LocalDirectoryBlurDown) => {
                 assert!(self
@@ -268,6 +342,8 @@ impl AuthActivity {
                     .app
                     .active(match self.host_bridge_input_mask() {
                         InputMask::Localhost => unreachable!(),
+                        InputMask::Generic if self.is_ftp(FormTab::HostBridge) =>
+                            &Id::HostBridge(AuthFormId::FtpActiveMode),
                         InputMask::Generic => &Id::HostBridge(AuthFormId::RemoteDirectory),
                         #[cfg(posix)]
                         InputMask::Smb => &Id::HostBridge(AuthFormId::SmbWorkgroup),
@@ -284,6 +360,8 @@ impl AuthActivity {
                     .app
                     .active(match self.remote_input_mask() {
                         InputMask::Localhost => unreachable!(),
+                        InputMask::Generic if self.is_ftp(FormTab::Remote) =>
+                            &Id::Remote(AuthFormId::FtpActiveMode),
                         InputMask::Generic => &Id::Remote(AuthFormId::RemoteDirectory),
                         #[cfg(posix)]
                         InputMask::Smb => &Id::Remote(AuthFormId::SmbWorkgroup),
@@ -358,7 +436,7 @@ impl AuthActivity {
                 assert!(self
                     .app
                     .active(match self.remote_input_mask() {
-                        InputMask::Localhost => &Id::Remote(AuthFormId::LocalDirectory),
+                        InputMask::Localhost => &Id::Remote(AuthFormId::RemoteDirectory),
                         InputMask::Generic => &Id::Remote(AuthFormId::Address),
                         InputMask::Smb => &Id::Remote(AuthFormId::Address),
                         InputMask::AwsS3 => &Id::Remote(AuthFormId::S3Bucket),
@@ -376,7 +454,10 @@ impl AuthActivity {
             UiMsg::Remote(UiAuthFormMsg::ProtocolBlurUp) => {
                 assert!(self
                     .app
-                    .active(&Id::Remote(AuthFormId::LocalDirectory))
+                    .active(match self.remote_input_mask() {
+                        InputMask::Localhost => &Id::Remote(AuthFormId::RemoteDirectory),
+                        _ => &Id::Remote(AuthFormId::LocalDirectory),
+                    })
                     .is_ok());
             }
             UiMsg::RececentsListBlur => {
@@ -391,7 +472,10 @@ impl AuthActivity {
             UiMsg::Remote(UiAuthFormMsg::RemoteDirectoryBlurDown) => {
                 assert!(self
                     .app
-                    .active(&Id::Remote(AuthFormId::LocalDirectory))
+                    .active(match self.remote_input_mask() {
+                        InputMask::Localhost => &Id::Remote(AuthFormId::Protocol),
+                        _ => &Id::Remote(AuthFormId::LocalDirectory),
+                    })
                     .is_ok());
             }
             UiMsg::HostBridge(UiAuthFormMsg::RemoteDirectoryBlurUp) => {
@@ -399,13 +483,15 @@ impl AuthActivity {
                     .app
                     .active(match self.host_bridge_input_mask() {
                         InputMask::Localhost => unreachable!(),
+                        InputMask::Generic if self.is_ftp(FormTab::HostBridge) =>
+                            &Id::HostBridge(AuthFormId::FtpActiveMode),
                         InputMask::Generic => &Id::HostBridge(AuthFormId::Password),
                         #[cfg(posix)]
                         InputMask::Smb => &Id::HostBridge(AuthFormId::SmbWorkgroup),
                         #[cfg(win)]
                         InputMask::Smb => &Id::HostBridge(AuthFormId::Password),
                         InputMask::Kube => &Id::HostBridge(AuthFormId::KubeClientKey),
-                        InputMask::AwsS3 => &Id::HostBridge(AuthFormId::S3NewPathStyle),
+                        InputMask::AwsS3 => &Id::HostBridge(AuthFormId::S3CaBundle),
                         InputMask::WebDAV => &Id::HostBridge(AuthFormId::Password),
                     })
                     .is_ok());
@@ -414,14 +500,16 @@ impl AuthActivity {
                 assert!(self
                     .app
                     .active(match self.remote_input_mask() {
-                        InputMask::Localhost => unreachable!(),
+                        InputMask::Localhost => &Id::Remote(AuthFormId::Protocol),
+                        InputMask::Generic if self.is_ftp(FormTab::Remote) =>
+                            &Id::Remote(AuthFormId::FtpActiveMode),
                         InputMask::Generic => &Id::Remote(AuthFormId::Password),
                         #[cfg(posix)]
                         InputMask::Smb => &Id::Remote(AuthFormId::SmbWorkgroup),
                         #[cfg(win)]
                         InputMask::Smb => &Id::Remote(AuthFormId::Password),
                         InputMask::Kube => &Id::Remote(AuthFormId::KubeClientKey),
-                        InputMask::AwsS3 => &Id::Remote(AuthFormId::S3NewPathStyle),
+                        InputMask::AwsS3 => &Id::Remote(AuthFormId::S3CaBundle),
                         InputMask::WebDAV => &Id::Remote(AuthFormId::Password),
                     })
                     .is_ok());
@@ -597,13 +685,13 @@ impl AuthActivity {
             UiMsg::HostBridge(UiAuthFormMsg::S3NewPathStyleBlurDown) => {
                 assert!(self
                     .app
-                    .active(&Id::HostBridge(AuthFormId::RemoteDirectory))
+                    .active(&Id::HostBridge(AuthFormId::S3RequesterPays))
                     .is_ok());
             }
             UiMsg::Remote(UiAuthFormMsg::S3NewPathStyleBlurDown) => {
                 assert!(self
                     .app
-                    .active(&Id::Remote(AuthFormId::RemoteDirectory))
+                    .active(&Id::Remote(AuthFormId::S3RequesterPays))
                     .is_ok());
             }
             UiMsg::HostBridge(UiAuthFormMsg::S3NewPathStyleBlurUp) => {
@@ -618,6 +706,120 @@ impl AuthActivity {
                     .active(&Id::Remote(AuthFormId::S3SessionToken))
                     .is_ok());
             }
+            UiMsg::HostBridge(UiAuthFormMsg::S3RequesterPaysBlurDown) => {
+                assert!(self
+                    .app
+                    .active(&Id::HostBridge(AuthFormId::S3StorageClass))
+                    .is_ok());
+            }
+            UiMsg::Remote(UiAuthFormMsg::S3RequesterPaysBlurDown) => {
+                assert!(self
+                    .app
+                    .active(&Id::Remote(AuthFormId::S3StorageClass))
+                    .is_ok());
+            }
+            UiMsg::HostBridge(UiAuthFormMsg::S3RequesterPaysBlurUp) => {
+                assert!(self
+                    .app
+                    .active(&Id::HostBridge(AuthFormId::S3NewPathStyle))
+                    .is_ok());
+            }
+            UiMsg::Remote(UiAuthFormMsg::S3RequesterPaysBlurUp) => {
+                assert!(self
+                    .app
+                    .active(&Id::Remote(AuthFormId::S3NewPathStyle))
+                    .is_ok());
+            }
+            UiMsg::HostBridge(UiAuthFormMsg::S3StorageClassBlurDown) => {
+                assert!(self
+                    .app
+                    .active(&Id::HostBridge(AuthFormId::S3AcceptInvalidCerts))
+                    .is_ok());
+            }
+            UiMsg::Remote(UiAuthFormMsg::S3StorageClassBlurDown) => {
+                assert!(self
+                    .app
+                    .active(&Id::Remote(AuthFormId::S3AcceptInvalidCerts))
+                    .is_ok());
+            }
+            UiMsg::HostBridge(UiAuthFormMsg::S3StorageClassBlurUp) => {
+                assert!(self
+                    .app
+                    .active(&Id::HostBridge(AuthFormId::S3RequesterPays))
+                    .is_ok());
+            }
+            UiMsg::Remote(UiAuthFormMsg::S3StorageClassBlurUp) => {
+                assert!(self
+                    .app
+                    .active(&Id::Remote(AuthFormId::S3RequesterPays))
+                    .is_ok());
+            }
+            UiMsg::HostBridge(UiAuthFormMsg::S3AcceptInvalidCertsBlurDown) => {
+                assert!(self
+                    .app
+                    .active(&Id::HostBridge(AuthFormId::S3CaBundle))
+                    .is_ok());
+            }
+            UiMsg::Remote(UiAuthFormMsg::S3AcceptInvalidCertsBlurDown) => {
+                assert!(self.app.active(&Id::Remote(AuthFormId::S3CaBundle)).is_ok());
+            }
+            UiMsg::HostBridge(UiAuthFormMsg::S3AcceptInvalidCertsBlurUp) => {
+                assert!(self
+                    .app
+                    .active(&Id::HostBridge(AuthFormId::S3StorageClass))
+                    .is_ok());
+            }
+            UiMsg::Remote(UiAuthFormMsg::S3AcceptInvalidCertsBlurUp) => {
+                assert!(self
+                    .app
+                    .active(&Id::Remote(AuthFormId::S3StorageClass))
+                    .is_ok());
+            }
+            UiMsg::HostBridge(UiAuthFormMsg::S3CaBundleBlurDown) => {
+                assert!(self
+                    .app
+                    .active(&Id::HostBridge(AuthFormId::RemoteDirectory))
+                    .is_ok());
+            }
+            UiMsg::Remote(UiAuthFormMsg::S3CaBundleBlurDown) => {
+                assert!(self
+                    .app
+                    .active(&Id::Remote(AuthFormId::RemoteDirectory))
+                    .is_ok());
+            }
+            UiMsg::HostBridge(UiAuthFormMsg::S3CaBundleBlurUp) => {
+                assert!(self
+                    .app
+                    .active(&Id::HostBridge(AuthFormId::S3AcceptInvalidCerts))
+                    .is_ok());
+            }
+            UiMsg::Remote(UiAuthFormMsg::S3CaBundleBlurUp) => {
+                assert!(self
+                    .app
+                    .active(&Id::Remote(AuthFormId::S3AcceptInvalidCerts))
+                    .is_ok());
+            }
+            UiMsg::HostBridge(UiAuthFormMsg::FtpActiveModeBlurDown) => {
+                assert!(self
+                    .app
+                    .active(&Id::HostBridge(AuthFormId::RemoteDirectory))
+                    .is_ok());
+            }
+            UiMsg::Remote(UiAuthFormMsg::FtpActiveModeBlurDown) => {
+                assert!(self
+                    .app
+                    .active(&Id::Remote(AuthFormId::RemoteDirectory))
+                    .is_ok());
+            }
+            UiMsg::HostBridge(UiAuthFormMsg::FtpActiveModeBlurUp) => {
+                assert!(self
+                    .app
+                    .active(&Id::HostBridge(AuthFormId::Password))
+                    .is_ok());
+            }
+            UiMsg::Remote(UiAuthFormMsg::FtpActiveModeBlurUp) => {
+                assert!(self.app.active(&Id::Remote(AuthFormId::Password)).is_ok());
+            }
             UiMsg::HostBridge(UiAuthFormMsg::KubeClientCertBlurDown) => {
                 assert!(self
                     .app
@@ -786,7 +988,11 @@ impl AuthActivity {
                 assert!(self.app.active(&Id::Remote(AuthFormId::Password)).is_ok());
             }
             UiMsg::SaveBookmarkPasswordBlur => {
-                assert!(self.app.active(&Id::BookmarkName).is_ok());
+                let id = match self.app.mounted(&Id::BookmarkQuickCommands) {
+                    true => &Id::BookmarkQuickCommands,
+                    false => &Id::BookmarkGroup,
+                };
+                assert!(self.app.active(id).is_ok());
             }
             UiMsg::ShowDeleteBookmarkPopup => {
                 self.mount_bookmark_del_dialog();
@@ -797,6 +1003,13 @@ impl AuthActivity {
             UiMsg::ShowKeybindingsPopup => {
                 self.mount_keybindings();
             }
+            UiMsg::ShowPinRecentPopup => {
+                if let Ok(State::One(StateValue::Usize(idx))) = self.app.state(&Id::RecentsList) {
+                    if let Some(name) = self.suggest_pinned_recent_name(idx) {
+                        self.mount_recent_pin_dialog(&name);
+                    }
+                }
+            }
             UiMsg::ShowQuitPopup => {
                 self.mount_quit();
             }
@@ -859,6 +1072,20 @@ impl AuthActivity {
             UiMsg::Remote(UiAuthFormMsg::WebDAVUriBlurUp) => {
                 assert!(self.app.active(&Id::Remote(AuthFormId::Protocol)).is_ok());
             }
+            UiMsg::ToggleBookmarksGroup(row) => {
+                self.toggle_bookmarks_group(row);
+                self.view_bookmarks();
+                // keep the header selected
+                assert!(self
+                    .app
+                    .attr(
+                        &Id::BookmarksList,
+                        Attribute::Value,
+                        AttrValue::Payload(PropPayload::One(PropValue::Usize(row)))
+                    )
+                    .is_ok());
+                assert!(self.app.active(&Id::BookmarksList).is_ok());
+            }
             UiMsg::WindowResized => {
                 self.redraw = true;
             }
@@ -866,4 +1093,22 @@ impl AuthActivity {
 
         None
     }
+
+    /// Save the bookmark named `name` from `form_tab`, then close the save dialog and reload bookmarks
+    fn save_new_bookmark(
+        &mut self,
+        form_tab: FormTab,
+        name: String,
+        save_password: bool,
+        group: Option<String>,
+        quick_commands: Option<String>,
+    ) {
+        if !name.is_empty() {
+            self.save_bookmark(form_tab, name, save_password, group, quick_commands);
+        }
+        // Umount popup
+        self.umount_bookmark_save_dialog();
+        // Reload bookmarks
+        self.view_bookmarks()
+    }
 }
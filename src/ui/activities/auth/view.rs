@@ -6,18 +6,18 @@
 use std::path::PathBuf;
 use std::str::FromStr;
 
-use tuirealm::props::Color;
+use tuirealm::props::{Color, PropPayload, PropValue};
 use tuirealm::ratatui::layout::{Constraint, Direction, Layout};
 use tuirealm::ratatui::widgets::Clear;
-use tuirealm::{State, StateValue, Sub, SubClause, SubEventClause};
+use tuirealm::{AttrValue, Attribute, State, StateValue, Sub, SubClause, SubEventClause};
 
 use super::{
     components, AuthActivity, AuthFormId, Context, FileTransferProtocol, FormTab,
-    HostBridgeProtocol, Id, InputMask,
+    HostBridgeProtocol, Id, InputMask, StartupFocus,
 };
 use crate::filetransfer::params::{
-    AwsS3Params, GenericProtocolParams, KubeProtocolParams, ProtocolParams, SmbParams,
-    WebDAVProtocolParams,
+    AwsS3Params, GenericProtocolParams, KubeProtocolParams, ProtocolParams, QuickCommand,
+    SmbParams, WebDAVProtocolParams,
 };
 use crate::filetransfer::FileTransferParams;
 use crate::utils::ui::{Popup, Size};
@@ -63,6 +63,11 @@ impl AuthActivity {
         self.mount_s3_security_token(FormTab::HostBridge, "");
         self.mount_s3_session_token(FormTab::HostBridge, "");
         self.mount_s3_new_path_style(FormTab::HostBridge, false);
+        self.mount_s3_requester_pays(FormTab::HostBridge, false);
+        self.mount_s3_storage_class(FormTab::HostBridge, "");
+        self.mount_s3_accept_invalid_certs(FormTab::HostBridge, false);
+        self.mount_s3_ca_bundle(FormTab::HostBridge, "");
+        self.mount_ftp_active_mode(FormTab::HostBridge, false);
         self.mount_kube_client_cert(FormTab::HostBridge, "");
         self.mount_kube_client_key(FormTab::HostBridge, "");
         self.mount_kube_cluster_url(FormTab::HostBridge, "");
@@ -96,6 +101,11 @@ impl AuthActivity {
         self.mount_s3_security_token(FormTab::Remote, "");
         self.mount_s3_session_token(FormTab::Remote, "");
         self.mount_s3_new_path_style(FormTab::Remote, false);
+        self.mount_s3_requester_pays(FormTab::Remote, false);
+        self.mount_s3_storage_class(FormTab::Remote, "");
+        self.mount_s3_accept_invalid_certs(FormTab::Remote, false);
+        self.mount_s3_ca_bundle(FormTab::Remote, "");
+        self.mount_ftp_active_mode(FormTab::Remote, false);
         self.mount_kube_client_cert(FormTab::Remote, "");
         self.mount_kube_client_key(FormTab::Remote, "");
         self.mount_kube_cluster_url(FormTab::Remote, "");
@@ -128,16 +138,38 @@ impl AuthActivity {
         // Load bookmarks
         self.view_bookmarks();
         self.view_recent_connections();
+        self.mount_bookmark_connection_failure();
         // Global listener
         self.init_global_listener();
         // Active protocol
         assert!(self.app.active(&Id::Remote(AuthFormId::Protocol)).is_ok());
     }
 
+    /// Focus the component set as `startup_focus` in the configuration.
+    /// If it's not the form, the form is collapsed until it gets focused
+    pub(super) fn init_startup_focus(&mut self) {
+        let id = match self.config().get_startup_focus() {
+            StartupFocus::Form => return,
+            StartupFocus::Bookmarks => Id::BookmarksList,
+            StartupFocus::Recents => Id::RecentsList,
+        };
+        self.form_collapsed = true;
+        assert!(self.app.active(&id).is_ok());
+    }
+
     /// Display view on canvas
     pub(super) fn view(&mut self) {
         self.redraw = false;
+        let bookmark_failure = self.selected_bookmark_failure();
+        if let Some(failure) = bookmark_failure.as_deref() {
+            let _ = self.app.attr(
+                &Id::BookmarkConnectionFailure,
+                Attribute::Text,
+                AttrValue::String(failure.to_string()),
+            );
+        }
         let mut ctx: Context = self.context.take().unwrap();
+        let palette = ctx.theme_provider().palette();
         let _ = ctx.terminal().raw_mut().draw(|f| {
             // Check window size
             let height: u16 = f.area().height;
@@ -155,7 +187,12 @@ impl AuthActivity {
                 .split(f.area());
             // Footer
             self.app.view(&Id::HelpFooter, f, body[1]);
-            let auth_form_len = 7 + self.max_input_mask_size();
+            // Input masks are hidden while the form is collapsed
+            let input_mask_size = match self.form_collapsed {
+                true => 0,
+                false => self.max_input_mask_size(),
+            };
+            let auth_form_len = 7 + input_mask_size;
             let main_chunks = Layout::default()
                 .direction(Direction::Vertical)
                 .margin(1)
@@ -171,11 +208,11 @@ impl AuthActivity {
             let auth_chunks = Layout::default()
                 .constraints(
                     [
-                        Constraint::Length(1),                          // h1
-                        Constraint::Length(1),                          // h2
-                        Constraint::Length(1),                          // Version
-                        Constraint::Length(self.max_input_mask_size()), // Input mask
-                        Constraint::Length(1), // Prevents last field to overflow
+                        Constraint::Length(1),               // h1
+                        Constraint::Length(1),               // h2
+                        Constraint::Length(1),               // Version
+                        Constraint::Length(input_mask_size), // Input mask
+                        Constraint::Length(1),               // Prevents last field to overflow
                     ]
                     .as_ref(),
                 )
@@ -207,10 +244,23 @@ impl AuthActivity {
                 .direction(Direction::Horizontal)
                 .split(auth_chunks[3]);
             // Input mask
-            self.render_host_bridge_input_mask(f, host_bridge_and_remote_chunks[0]);
-            self.render_remote_input_mask(f, host_bridge_and_remote_chunks[1]);
+            if !self.form_collapsed {
+                self.render_host_bridge_input_mask(f, host_bridge_and_remote_chunks[0]);
+                self.render_remote_input_mask(f, host_bridge_and_remote_chunks[1]);
+            }
             // Bookmark chunks
-            self.app.view(&Id::BookmarksList, f, bookmark_chunks[0]);
+            if bookmark_failure.is_some() {
+                let bookmarks_list_chunks = Layout::default()
+                    .constraints([Constraint::Min(3), Constraint::Length(1)].as_ref())
+                    .direction(Direction::Vertical)
+                    .split(bookmark_chunks[0]);
+                self.app
+                    .view(&Id::BookmarksList, f, bookmarks_list_chunks[0]);
+                self.app
+                    .view(&Id::BookmarkConnectionFailure, f, bookmarks_list_chunks[1]);
+            } else {
+                self.app.view(&Id::BookmarksList, f, bookmark_chunks[0]);
+            }
             self.app.view(&Id::RecentsList, f, bookmark_chunks[1]);
             // Popups
             if self.app.mounted(&Id::ErrorPopup) {
@@ -248,6 +298,11 @@ impl AuthActivity {
                 let popup = Popup(Size::Percentage(30), Size::Unit(3)).draw_in(f.area());
                 f.render_widget(Clear, popup);
                 self.app.view(&Id::DeleteRecentPopup, f, popup);
+            } else if self.app.mounted(&Id::PinRecentPopup) {
+                // make popup
+                let popup = Popup(Size::Percentage(40), Size::Unit(3)).draw_in(f.area());
+                f.render_widget(Clear, popup);
+                self.app.view(&Id::PinRecentPopup, f, popup);
             } else if self.app.mounted(&Id::NewVersionChangelog) {
                 // make popup
                 let popup = Popup(Size::Percentage(90), Size::Percentage(85)).draw_in(f.area());
@@ -269,23 +324,42 @@ impl AuthActivity {
                 let popup = Popup(Size::Percentage(50), Size::Percentage(70)).draw_in(f.area());
                 f.render_widget(Clear, popup);
                 self.app.view(&Id::Keybindings, f, popup);
+            } else if self.app.mounted(&Id::DuplicateBookmarkPopup) {
+                // make popup
+                let popup = Popup(Size::Percentage(50), Size::Unit(3)).draw_in(f.area());
+                f.render_widget(Clear, popup);
+                self.app.view(&Id::DuplicateBookmarkPopup, f, popup);
             } else if self.app.mounted(&Id::BookmarkSavePassword) {
+                // Quick commands are saved only for the remote bookmarks
+                let quick_commands = self.app.mounted(&Id::BookmarkQuickCommands);
                 // make popup
-                let popup = Popup(Size::Percentage(20), Size::Percentage(20)).draw_in(f.area());
+                let (width, height) = match quick_commands {
+                    true => (Size::Percentage(40), Size::Unit(13)),
+                    false => (Size::Percentage(20), Size::Unit(10)),
+                };
+                let popup = Popup(width, height).draw_in(f.area());
                 f.render_widget(Clear, popup);
                 let popup_chunks = Layout::default()
                     .direction(Direction::Vertical)
                     .constraints(
                         [
-                            Constraint::Length(3), // Input form
-                            Constraint::Length(4), // Yes/No
+                            Constraint::Length(3),                                  // Input form
+                            Constraint::Length(3),                                  // Group
+                            Constraint::Length(if quick_commands { 3 } else { 0 }), // Quick commands
+                            Constraint::Length(4),                                  // Yes/No
                         ]
                         .as_ref(),
                     )
                     .split(popup);
                 self.app.view(&Id::BookmarkName, f, popup_chunks[0]);
-                self.app.view(&Id::BookmarkSavePassword, f, popup_chunks[1]);
+                self.app.view(&Id::BookmarkGroup, f, popup_chunks[1]);
+                if quick_commands {
+                    self.app
+                        .view(&Id::BookmarkQuickCommands, f, popup_chunks[2]);
+                }
+                self.app.view(&Id::BookmarkSavePassword, f, popup_chunks[3]);
             }
+            palette.apply(f.buffer_mut());
         });
         self.context = Some(ctx);
     }
@@ -427,7 +501,10 @@ impl AuthActivity {
                 self.app.view(&view_ids[2], f, input_mask[2]);
                 self.app.view(&view_ids[3], f, input_mask[3]);
             }
-            InputMask::Localhost => unreachable!(),
+            InputMask::Localhost => {
+                let view_ids = self.get_remote_localhost_view();
+                self.app.view(&view_ids[0], f, input_mask[0]);
+            }
             InputMask::Smb => {
                 let view_ids = self.get_remote_smb_view();
                 self.app.view(&view_ids[0], f, input_mask[0]);
@@ -454,12 +531,56 @@ impl AuthActivity {
                 Self::fmt_bookmark(x, self.bookmarks_client().unwrap().get_bookmark(x).unwrap())
             })
             .collect();
+        let health = self.bookmarks_health();
         let bookmarks_color = self.theme().auth_bookmarks;
+        self.bookmarks_rows = self.make_bookmarks_rows();
         assert!(self
             .app
             .remount(
                 Id::BookmarksList,
-                Box::new(components::BookmarksList::new(&bookmarks, bookmarks_color)),
+                Box::new(components::BookmarksList::new(
+                    self.bookmarks_rows.clone(),
+                    &bookmarks,
+                    health.as_deref(),
+                    bookmarks_color
+                )),
+                vec![]
+            )
+            .is_ok());
+    }
+
+    /// Update the health indicators of the bookmarks list, preserving the current selection
+    pub(super) fn view_bookmarks_health(&mut self) {
+        let bookmarks: Vec<String> = self
+            .bookmarks_list
+            .iter()
+            .map(|x| {
+                Self::fmt_bookmark(x, self.bookmarks_client().unwrap().get_bookmark(x).unwrap())
+            })
+            .collect();
+        let health = self.bookmarks_health();
+        assert!(self
+            .app
+            .attr(
+                &Id::BookmarksList,
+                Attribute::Content,
+                AttrValue::Table(components::BookmarksList::rows(
+                    &self.bookmarks_rows,
+                    &bookmarks,
+                    health.as_deref()
+                ))
+            )
+            .is_ok());
+    }
+
+    /// Mount the label describing the last failed connection of the selected bookmark
+    fn mount_bookmark_connection_failure(&mut self) {
+        let bookmarks_color = self.theme().auth_bookmarks;
+        assert!(self
+            .app
+            .remount(
+                Id::BookmarkConnectionFailure,
+                Box::new(components::BookmarkConnectionFailure::new(bookmarks_color)),
                 vec![]
             )
             .is_ok());
@@ -595,6 +716,27 @@ impl AuthActivity {
         assert!(self.app.active(&Id::DeleteBookmarkPopup).is_ok());
     }
 
+    /// Mount the dialog asking what to do when an equivalent bookmark named `equivalent` already exists
+    pub(super) fn mount_duplicate_bookmark_dialog(&mut self, form_tab: FormTab, equivalent: &str) {
+        let warn_color = self.theme().misc_warn_dialog;
+        assert!(self
+            .app
+            .remount(
+                Id::DuplicateBookmarkPopup,
+                Box::new(components::DuplicateBookmarkPopup::new(
+                    form_tab, equivalent, warn_color
+                )),
+                vec![]
+            )
+            .is_ok());
+        assert!(self.app.active(&Id::DuplicateBookmarkPopup).is_ok());
+    }
+
+    /// Umount duplicate bookmark dialog
+    pub(super) fn umount_duplicate_bookmark_dialog(&mut self) {
+        let _ = self.app.umount(&Id::DuplicateBookmarkPopup);
+    }
+
     /// umount delete bookmark dialog
     pub(super) fn umount_bookmark_del_dialog(&mut self) {
         let _ = self.app.umount(&Id::DeleteBookmarkPopup);
@@ -619,6 +761,33 @@ impl AuthActivity {
         let _ = self.app.umount(&Id::DeleteRecentPopup);
     }
 
+    /// Mount the dialog asking the name of the bookmark to pin the selected recent as, suggesting `name`
+    pub(super) fn mount_recent_pin_dialog(&mut self, name: &str) {
+        let save_color = self.theme().misc_save_dialog;
+        assert!(self
+            .app
+            .remount(
+                Id::PinRecentPopup,
+                Box::new(components::PinRecentPopup::new(name, save_color)),
+                vec![]
+            )
+            .is_ok());
+        assert!(self.app.active(&Id::PinRecentPopup).is_ok());
+    }
+
+    /// Umount pin recent dialog
+    pub(super) fn umount_recent_pin_dialog(&mut self) {
+        let _ = self.app.umount(&Id::PinRecentPopup);
+    }
+
+    /// Get the bookmark name typed into the pin recent dialog
+    pub(super) fn get_pinned_recent_name(&self) -> String {
+        match self.app.state(&Id::PinRecentPopup) {
+            Ok(State::One(StateValue::String(name))) => name,
+            _ => String::default(),
+        }
+    }
+
     /// Mount bookmark save dialog
     pub(super) fn mount_bookmark_save_dialog(&mut self, form_tab: FormTab) {
         let save_color = self.theme().misc_save_dialog;
@@ -631,6 +800,40 @@ impl AuthActivity {
                 vec![]
             )
             .is_ok());
+        // Keep the group of the bookmark being updated
+        let group = match form_tab {
+            FormTab::Remote => self.remote_bookmark_group().unwrap_or_default(),
+            FormTab::HostBridge => String::new(),
+        };
+        assert!(self
+            .app
+            .remount(
+                Id::BookmarkGroup,
+                Box::new(components::BookmarkGroup::new(form_tab, &group, save_color)),
+                vec![]
+            )
+            .is_ok());
+        // Keep the quick commands of the bookmark being updated
+        if form_tab == FormTab::Remote {
+            let commands = self
+                .collect_remote_host_params()
+                .ok()
+                .and_then(|params| {
+                    self.loaded_remote_params(&params)
+                        .map(|loaded| QuickCommand::fmt_list(&loaded.quick_commands))
+                })
+                .unwrap_or_default();
+            assert!(self
+                .app
+                .remount(
+                    Id::BookmarkQuickCommands,
+                    Box::new(components::BookmarkQuickCommands::new(
+                        form_tab, &commands, save_color
+                    )),
+                    vec![]
+                )
+                .is_ok());
+        }
         assert!(self
             .app
             .remount(
@@ -646,6 +849,8 @@ impl AuthActivity {
     /// Umount bookmark save dialog
     pub(super) fn umount_bookmark_save_dialog(&mut self) {
         let _ = self.app.umount(&Id::BookmarkName);
+        let _ = self.app.umount(&Id::BookmarkGroup);
+        let _ = self.app.umount(&Id::BookmarkQuickCommands);
         let _ = self.app.umount(&Id::BookmarkSavePassword);
     }
 
@@ -817,6 +1022,21 @@ impl AuthActivity {
             .is_ok());
     }
 
+    /// Update the usernames suggested in the username input of `form_tab`, for the address in the form
+    pub(super) fn update_username_suggestions(&mut self, form_tab: FormTab) {
+        let address = self.get_input_addr(form_tab);
+        let suggestions = self
+            .username_suggestions(address.as_str())
+            .into_iter()
+            .map(PropValue::Str)
+            .collect();
+        let _ = self.app.attr(
+            &Self::form_tab_id(form_tab, AuthFormId::Username),
+            Attribute::Custom(components::ATTR_USERNAME_SUGGESTIONS),
+            AttrValue::Payload(PropPayload::Vec(suggestions)),
+        );
+    }
+
     pub(super) fn mount_password(&mut self, form_tab: FormTab, password: &str) {
         let password_color = self.theme().auth_password;
         let id = Self::form_tab_id(form_tab, AuthFormId::Password);
@@ -963,6 +1183,91 @@ impl AuthActivity {
             .is_ok());
     }
 
+    pub(super) fn mount_s3_requester_pays(&mut self, form_tab: FormTab, requester_pays: bool) {
+        let color = self.theme().auth_address;
+        let id = Self::form_tab_id(form_tab, AuthFormId::S3RequesterPays);
+        assert!(self
+            .app
+            .remount(
+                id,
+                Box::new(components::RadioS3RequesterPays::new(
+                    requester_pays,
+                    form_tab,
+                    color
+                )),
+                vec![]
+            )
+            .is_ok());
+    }
+
+    pub(super) fn mount_s3_storage_class(&mut self, form_tab: FormTab, storage_class: &str) {
+        let color = self.theme().auth_address;
+        let id = Self::form_tab_id(form_tab, AuthFormId::S3StorageClass);
+        assert!(self
+            .app
+            .remount(
+                id,
+                Box::new(components::InputS3StorageClass::new(
+                    storage_class,
+                    form_tab,
+                    color
+                )),
+                vec![]
+            )
+            .is_ok());
+    }
+
+    pub(super) fn mount_s3_accept_invalid_certs(
+        &mut self,
+        form_tab: FormTab,
+        accept_invalid_certs: bool,
+    ) {
+        let color = self.theme().auth_address;
+        let id = Self::form_tab_id(form_tab, AuthFormId::S3AcceptInvalidCerts);
+        assert!(self
+            .app
+            .remount(
+                id,
+                Box::new(components::RadioS3AcceptInvalidCerts::new(
+                    accept_invalid_certs,
+                    form_tab,
+                    color
+                )),
+                vec![]
+            )
+            .is_ok());
+    }
+
+    pub(super) fn mount_s3_ca_bundle(&mut self, form_tab: FormTab, ca_bundle: &str) {
+        let color = self.theme().auth_address;
+        let id = Self::form_tab_id(form_tab, AuthFormId::S3CaBundle);
+        assert!(self
+            .app
+            .remount(
+                id,
+                Box::new(components::InputS3CaBundle::new(ca_bundle, form_tab, color)),
+                vec![]
+            )
+            .is_ok());
+    }
+
+    pub(super) fn mount_ftp_active_mode(&mut self, form_tab: FormTab, active_mode: bool) {
+        let color = self.theme().auth_password;
+        let id = Self::form_tab_id(form_tab, AuthFormId::FtpActiveMode);
+        assert!(self
+            .app
+            .remount(
+                id,
+                Box::new(components::RadioFtpActiveMode::new(
+                    active_mode,
+                    form_tab,
+                    color
+                )),
+                vec![]
+            )
+            .is_ok());
+    }
+
     pub(super) fn mount_kube_namespace(&mut self, form_tab: FormTab, value: &str) {
         let color = self.theme().auth_port;
         let id = Self::form_tab_id(form_tab, AuthFormId::KubeNamespace);
@@ -1086,11 +1391,13 @@ impl AuthActivity {
         let port: u16 = self.get_input_port(form_tab);
         let username = self.get_input_username(form_tab);
         let password = self.get_input_password(form_tab);
+        let active_mode = self.is_ftp(form_tab) && self.get_input_ftp_active_mode(form_tab);
         GenericProtocolParams::default()
             .address(addr)
             .port(port)
             .username(username)
             .password(password)
+            .active_mode(active_mode)
     }
 
     /// Collect s3 input values from view
@@ -1104,6 +1411,10 @@ impl AuthActivity {
         let security_token = self.get_input_s3_security_token(form_tab);
         let session_token = self.get_input_s3_session_token(form_tab);
         let new_path_style = self.get_input_s3_new_path_style(form_tab);
+        let requester_pays = self.get_input_s3_requester_pays(form_tab);
+        let storage_class = self.get_input_s3_storage_class(form_tab);
+        let accept_invalid_certs = self.get_input_s3_accept_invalid_certs(form_tab);
+        let ca_bundle = self.get_input_s3_ca_bundle(form_tab);
         AwsS3Params::new(bucket, region, profile)
             .endpoint(endpoint)
             .access_key(access_key)
@@ -1111,6 +1422,10 @@ impl AuthActivity {
             .security_token(security_token)
             .session_token(session_token)
             .new_path_style(new_path_style)
+            .requester_pays(requester_pays)
+            .storage_class(storage_class)
+            .accept_invalid_certs(accept_invalid_certs)
+            .ca_bundle(ca_bundle)
     }
 
     /// Collect s3 input values from view
@@ -1334,6 +1649,54 @@ impl AuthActivity {
         )
     }
 
+    pub(super) fn get_input_s3_requester_pays(&self, form_tab: FormTab) -> bool {
+        matches!(
+            self.app
+                .state(&Self::form_tab_id(form_tab, AuthFormId::S3RequesterPays)),
+            Ok(State::One(StateValue::Usize(0)))
+        )
+    }
+
+    pub(super) fn get_input_s3_storage_class(&self, form_tab: FormTab) -> Option<String> {
+        match self
+            .app
+            .state(&Self::form_tab_id(form_tab, AuthFormId::S3StorageClass))
+        {
+            Ok(State::One(StateValue::String(x))) if !x.trim().is_empty() => {
+                Some(x.trim().to_uppercase())
+            }
+            _ => None,
+        }
+    }
+
+    pub(super) fn get_input_s3_accept_invalid_certs(&self, form_tab: FormTab) -> bool {
+        matches!(
+            self.app.state(&Self::form_tab_id(
+                form_tab,
+                AuthFormId::S3AcceptInvalidCerts
+            )),
+            Ok(State::One(StateValue::Usize(0)))
+        )
+    }
+
+    pub(super) fn get_input_s3_ca_bundle(&self, form_tab: FormTab) -> Option<PathBuf> {
+        match self
+            .app
+            .state(&Self::form_tab_id(form_tab, AuthFormId::S3CaBundle))
+        {
+            Ok(State::One(StateValue::String(x))) if !x.is_empty() => Some(PathBuf::from(x)),
+            _ => None,
+        }
+    }
+
+    pub(super) fn get_input_ftp_active_mode(&self, form_tab: FormTab) -> bool {
+        matches!(
+            self.app
+                .state(&Self::form_tab_id(form_tab, AuthFormId::FtpActiveMode)),
+            Ok(State::One(StateValue::Usize(0)))
+        )
+    }
+
     pub(super) fn get_input_kube_namespace(&self, form_tab: FormTab) -> Option<String> {
         match self
             .app
@@ -1405,20 +1768,26 @@ impl AuthActivity {
         }
     }
 
-    /// Get new bookmark params
-    pub(super) fn get_new_bookmark(&self) -> (String, bool) {
+    /// Get new bookmark params: name, whether to save the password, group and quick commands, if they can be edited
+    pub(super) fn get_new_bookmark(&self) -> (String, bool, Option<String>, Option<String>) {
         let name = match self.app.state(&Id::BookmarkName) {
             Ok(State::One(StateValue::String(name))) => name,
             _ => String::default(),
         };
-        if matches!(
+        let group = match self.app.state(&Id::BookmarkGroup) {
+            Ok(State::One(StateValue::String(group))) => Some(group),
+            _ => None,
+        };
+        let quick_commands = match self.app.state(&Id::BookmarkQuickCommands) {
+            Ok(State::One(StateValue::String(commands))) => Some(commands),
+            Ok(State::None) if self.app.mounted(&Id::BookmarkQuickCommands) => Some(String::new()),
+            _ => None,
+        };
+        let save_password = matches!(
             self.app.state(&Id::BookmarkSavePassword),
             Ok(State::One(StateValue::Usize(0)))
-        ) {
-            (name, true)
-        } else {
-            (name, false)
-        }
+        );
+        (name, save_password, group, quick_commands)
     }
 
     // -- len
@@ -1453,6 +1822,14 @@ impl AuthActivity {
     /// Format recent connection to display on ui
     fn fmt_recent(b: FileTransferParams) -> String {
         let protocol: String = b.protocol.to_string().to_lowercase();
+        if b.protocol == FileTransferProtocol::Localhost {
+            return format!(
+                "{protocol}://{}",
+                b.remote_path
+                    .map(|x| x.to_string_lossy().to_string())
+                    .unwrap_or_default()
+            );
+        }
         match b.params {
             ProtocolParams::AwsS3(s3) => {
                 let profile: String = match s3.profile {
@@ -1519,13 +1896,32 @@ impl AuthActivity {
 
     /// Get the visible element in the generic params form, based on current focus
     fn get_host_bridge_generic_params_view(&self) -> [Id; 4] {
+        let ftp = self.is_ftp(FormTab::HostBridge);
         match self.app.focus() {
+            Some(&Id::HostBridge(AuthFormId::FtpActiveMode)) => [
+                Id::HostBridge(AuthFormId::Port),
+                Id::HostBridge(AuthFormId::Username),
+                Id::HostBridge(AuthFormId::Password),
+                Id::HostBridge(AuthFormId::FtpActiveMode),
+            ],
+            Some(&Id::HostBridge(AuthFormId::RemoteDirectory)) if ftp => [
+                Id::HostBridge(AuthFormId::Username),
+                Id::HostBridge(AuthFormId::Password),
+                Id::HostBridge(AuthFormId::FtpActiveMode),
+                Id::HostBridge(AuthFormId::RemoteDirectory),
+            ],
             Some(&Id::HostBridge(AuthFormId::RemoteDirectory)) => [
                 Id::HostBridge(AuthFormId::Port),
                 Id::HostBridge(AuthFormId::Username),
                 Id::HostBridge(AuthFormId::Password),
                 Id::HostBridge(AuthFormId::RemoteDirectory),
             ],
+            Some(&Id::HostBridge(AuthFormId::LocalDirectory)) if ftp => [
+                Id::HostBridge(AuthFormId::Password),
+                Id::HostBridge(AuthFormId::FtpActiveMode),
+                Id::HostBridge(AuthFormId::RemoteDirectory),
+                Id::HostBridge(AuthFormId::LocalDirectory),
+            ],
             Some(&Id::HostBridge(AuthFormId::LocalDirectory)) => [
                 Id::HostBridge(AuthFormId::Username),
                 Id::HostBridge(AuthFormId::Password),
@@ -1543,13 +1939,32 @@ impl AuthActivity {
 
     /// Get the visible element in the generic params form, based on current focus
     fn get_remote_generic_params_view(&self) -> [Id; 4] {
+        let ftp = self.is_ftp(FormTab::Remote);
         match self.app.focus() {
+            Some(&Id::Remote(AuthFormId::FtpActiveMode)) => [
+                Id::Remote(AuthFormId::Port),
+                Id::Remote(AuthFormId::Username),
+                Id::Remote(AuthFormId::Password),
+                Id::Remote(AuthFormId::FtpActiveMode),
+            ],
+            Some(&Id::Remote(AuthFormId::RemoteDirectory)) if ftp => [
+                Id::Remote(AuthFormId::Username),
+                Id::Remote(AuthFormId::Password),
+                Id::Remote(AuthFormId::FtpActiveMode),
+                Id::Remote(AuthFormId::RemoteDirectory),
+            ],
             Some(&Id::Remote(AuthFormId::RemoteDirectory)) => [
                 Id::Remote(AuthFormId::Port),
                 Id::Remote(AuthFormId::Username),
                 Id::Remote(AuthFormId::Password),
                 Id::Remote(AuthFormId::RemoteDirectory),
             ],
+            Some(&Id::Remote(AuthFormId::LocalDirectory)) if ftp => [
+                Id::Remote(AuthFormId::Password),
+                Id::Remote(AuthFormId::FtpActiveMode),
+                Id::Remote(AuthFormId::RemoteDirectory),
+                Id::Remote(AuthFormId::LocalDirectory),
+            ],
             Some(&Id::Remote(AuthFormId::LocalDirectory)) => [
                 Id::Remote(AuthFormId::Username),
                 Id::Remote(AuthFormId::Password),
@@ -1569,6 +1984,11 @@ impl AuthActivity {
         [Id::HostBridge(AuthFormId::LocalDirectory)]
     }
 
+    /// Get the visible element in the localhost form of the remote panel
+    fn get_remote_localhost_view(&self) -> [Id; 1] {
+        [Id::Remote(AuthFormId::RemoteDirectory)]
+    }
+
     /// Get the visible element in the aws-s3 form, based on current focus
     fn get_host_bridge_s3_view(&self) -> [Id; 4] {
         match self.app.focus() {
@@ -1602,15 +2022,39 @@ impl AuthActivity {
                 Id::HostBridge(AuthFormId::S3SessionToken),
                 Id::HostBridge(AuthFormId::S3NewPathStyle),
             ],
-            Some(&Id::HostBridge(AuthFormId::RemoteDirectory)) => [
+            Some(&Id::HostBridge(AuthFormId::S3RequesterPays)) => [
                 Id::HostBridge(AuthFormId::S3SecurityToken),
                 Id::HostBridge(AuthFormId::S3SessionToken),
                 Id::HostBridge(AuthFormId::S3NewPathStyle),
-                Id::HostBridge(AuthFormId::RemoteDirectory),
+                Id::HostBridge(AuthFormId::S3RequesterPays),
             ],
-            Some(&Id::HostBridge(AuthFormId::LocalDirectory)) => [
+            Some(&Id::HostBridge(AuthFormId::S3StorageClass)) => [
                 Id::HostBridge(AuthFormId::S3SessionToken),
                 Id::HostBridge(AuthFormId::S3NewPathStyle),
+                Id::HostBridge(AuthFormId::S3RequesterPays),
+                Id::HostBridge(AuthFormId::S3StorageClass),
+            ],
+            Some(&Id::HostBridge(AuthFormId::S3AcceptInvalidCerts)) => [
+                Id::HostBridge(AuthFormId::S3NewPathStyle),
+                Id::HostBridge(AuthFormId::S3RequesterPays),
+                Id::HostBridge(AuthFormId::S3StorageClass),
+                Id::HostBridge(AuthFormId::S3AcceptInvalidCerts),
+            ],
+            Some(&Id::HostBridge(AuthFormId::S3CaBundle)) => [
+                Id::HostBridge(AuthFormId::S3RequesterPays),
+                Id::HostBridge(AuthFormId::S3StorageClass),
+                Id::HostBridge(AuthFormId::S3AcceptInvalidCerts),
+                Id::HostBridge(AuthFormId::S3CaBundle),
+            ],
+            Some(&Id::HostBridge(AuthFormId::RemoteDirectory)) => [
+                Id::HostBridge(AuthFormId::S3StorageClass),
+                Id::HostBridge(AuthFormId::S3AcceptInvalidCerts),
+                Id::HostBridge(AuthFormId::S3CaBundle),
+                Id::HostBridge(AuthFormId::RemoteDirectory),
+            ],
+            Some(&Id::HostBridge(AuthFormId::LocalDirectory)) => [
+                Id::HostBridge(AuthFormId::S3AcceptInvalidCerts),
+                Id::HostBridge(AuthFormId::S3CaBundle),
                 Id::HostBridge(AuthFormId::RemoteDirectory),
                 Id::HostBridge(AuthFormId::LocalDirectory),
             ],
@@ -1656,15 +2100,39 @@ impl AuthActivity {
                 Id::Remote(AuthFormId::S3SessionToken),
                 Id::Remote(AuthFormId::S3NewPathStyle),
             ],
-            Some(&Id::Remote(AuthFormId::RemoteDirectory)) => [
+            Some(&Id::Remote(AuthFormId::S3RequesterPays)) => [
                 Id::Remote(AuthFormId::S3SecurityToken),
                 Id::Remote(AuthFormId::S3SessionToken),
                 Id::Remote(AuthFormId::S3NewPathStyle),
-                Id::Remote(AuthFormId::RemoteDirectory),
+                Id::Remote(AuthFormId::S3RequesterPays),
             ],
-            Some(&Id::Remote(AuthFormId::LocalDirectory)) => [
+            Some(&Id::Remote(AuthFormId::S3StorageClass)) => [
                 Id::Remote(AuthFormId::S3SessionToken),
                 Id::Remote(AuthFormId::S3NewPathStyle),
+                Id::Remote(AuthFormId::S3RequesterPays),
+                Id::Remote(AuthFormId::S3StorageClass),
+            ],
+            Some(&Id::Remote(AuthFormId::S3AcceptInvalidCerts)) => [
+                Id::Remote(AuthFormId::S3NewPathStyle),
+                Id::Remote(AuthFormId::S3RequesterPays),
+                Id::Remote(AuthFormId::S3StorageClass),
+                Id::Remote(AuthFormId::S3AcceptInvalidCerts),
+            ],
+            Some(&Id::Remote(AuthFormId::S3CaBundle)) => [
+                Id::Remote(AuthFormId::S3RequesterPays),
+                Id::Remote(AuthFormId::S3StorageClass),
+                Id::Remote(AuthFormId::S3AcceptInvalidCerts),
+                Id::Remote(AuthFormId::S3CaBundle),
+            ],
+            Some(&Id::Remote(AuthFormId::RemoteDirectory)) => [
+                Id::Remote(AuthFormId::S3StorageClass),
+                Id::Remote(AuthFormId::S3AcceptInvalidCerts),
+                Id::Remote(AuthFormId::S3CaBundle),
+                Id::Remote(AuthFormId::RemoteDirectory),
+            ],
+            Some(&Id::Remote(AuthFormId::LocalDirectory)) => [
+                Id::Remote(AuthFormId::S3AcceptInvalidCerts),
+                Id::Remote(AuthFormId::S3CaBundle),
                 Id::Remote(AuthFormId::RemoteDirectory),
                 Id::Remote(AuthFormId::LocalDirectory),
             ],
@@ -2004,6 +2472,13 @@ impl AuthActivity {
                         }),
                         Self::no_popup_mounted_clause(),
                     ),
+                    Sub::new(
+                        SubEventClause::Keyboard(KeyEvent {
+                            code: Key::Char('t'),
+                            modifiers: KeyModifiers::CONTROL,
+                        }),
+                        Self::no_popup_mounted_clause(),
+                    ),
                     Sub::new(SubEventClause::WindowResize, SubClause::Always)
                 ]
             )
@@ -2025,6 +2500,8 @@ impl AuthActivity {
             Id::Keybindings,
             Id::DeleteBookmarkPopup,
             Id::DeleteRecentPopup,
+            Id::DuplicateBookmarkPopup,
+            Id::PinRecentPopup,
             Id::InstallUpdatePopup,
             Id::BookmarkSavePassword,
             Id::WaitPopup
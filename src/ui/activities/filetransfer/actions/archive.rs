@@ -0,0 +1,194 @@
+//! ## FileTransferActivity
+//!
+//! `filetransfer_activiy` is the module which implements the Filetransfer activity, which is the main activity afterall
+
+use super::{FileExplorerTab, FileTransferActivity, LogLevel, SelectedFile};
+use crate::utils::archive::{self, ArchiveFormat};
+use crate::utils::shell::{self, DEFAULT_REMOTE_SHELL};
+
+impl FileTransferActivity {
+    /// Show the popup to name the archive of the selected entries of the current explorer
+    pub(crate) fn action_show_archive_popup(&mut self) {
+        let selected = match self.browser.tab() {
+            FileExplorerTab::HostBridge => self.get_local_selected_entries(),
+            FileExplorerTab::Remote => self.get_remote_selected_entries(),
+            FileExplorerTab::FindHostBridge | FileExplorerTab::FindRemote => return,
+        };
+        let name = match selected {
+            SelectedFile::One(file) => file.name(),
+            SelectedFile::Many(_) => String::from("archive"),
+            SelectedFile::None => return,
+        };
+        if let Err(err) = self.check_archive_supported(self.browser.tab()) {
+            self.mount_error(format!("Could not create archive: {err}"));
+            return;
+        }
+        self.mount_archive(&name);
+    }
+
+    /// Compress the selected entries of the current explorer into the archive `name`, in the working directory.
+    /// Returns the name of the archive, if it has been created
+    pub(crate) fn action_create_archive(
+        &mut self,
+        name: String,
+        format: ArchiveFormat,
+    ) -> Option<String> {
+        let name = name.trim();
+        if name.is_empty() {
+            return None;
+        }
+        let name = format.archive_name(name);
+        let tab = self.browser.tab();
+        let (files, wrkdir) = match tab {
+            FileExplorerTab::HostBridge => (
+                self.get_local_selected_entries().get_files(),
+                self.host_bridge().wrkdir.clone(),
+            ),
+            FileExplorerTab::Remote => (
+                self.get_remote_selected_entries().get_files(),
+                self.remote().wrkdir.clone(),
+            ),
+            FileExplorerTab::FindHostBridge | FileExplorerTab::FindRemote => return None,
+        };
+        if files.is_empty() {
+            return None;
+        }
+        let archive = wrkdir.join(&name);
+        let exists = match tab {
+            FileExplorerTab::HostBridge => self.host_bridge_file_exists(archive.as_path()),
+            _ => self.remote_file_exists(archive.as_path()),
+        };
+        if exists
+            && self.config().get_prompt_on_file_replace()
+            && !self.should_replace_file(name.clone())
+        {
+            return None;
+        }
+        let names: Vec<String> = files.iter().map(|x| x.name()).collect();
+        self.mount_panel_wait(format!("Compressing {} entries into {name}…", names.len()));
+        let result = match tab {
+            FileExplorerTab::HostBridge => {
+                archive::create_archive(format, archive.as_path(), wrkdir.as_path(), &names)
+                    .map(|count| format!("{count} entries"))
+                    .map_err(|e| e.to_string())
+            }
+            _ => self
+                .remote_archive_exec(&format.create_command(&name, wrkdir.as_path(), &names))
+                .map(|_| format!("{} entries", names.len())),
+        };
+        self.umount_wait();
+        match result {
+            Ok(entries) => {
+                self.log(
+                    LogLevel::Info,
+                    format!("Compressed {entries} into \"{}\"", archive.display()),
+                );
+                Some(name)
+            }
+            Err(err) => {
+                self.log_and_alert(
+                    LogLevel::Error,
+                    format!("Could not create archive \"{}\": {err}", archive.display()),
+                );
+                None
+            }
+        }
+    }
+
+    /// Extract the selected archive of the current explorer into the working directory
+    pub(crate) fn action_extract_archive(&mut self) {
+        let tab = self.browser.tab();
+        let (selected, wrkdir) = match tab {
+            FileExplorerTab::HostBridge => (
+                self.get_local_selected_entries(),
+                self.host_bridge().wrkdir.clone(),
+            ),
+            FileExplorerTab::Remote => (
+                self.get_remote_selected_entries(),
+                self.remote().wrkdir.clone(),
+            ),
+            FileExplorerTab::FindHostBridge | FileExplorerTab::FindRemote => return,
+        };
+        let file = match selected {
+            SelectedFile::One(file) if file.is_file() => file,
+            SelectedFile::One(_) => {
+                self.mount_error("Only archives can be extracted");
+                return;
+            }
+            SelectedFile::Many(_) => {
+                self.mount_error("Select a single archive to extract");
+                return;
+            }
+            SelectedFile::None => return,
+        };
+        let Some(format) = ArchiveFormat::from_name(&file.name()) else {
+            self.mount_error(format!(
+                "\"{}\" is not a supported archive (tar, tar.gz, tgz or zip)",
+                file.name()
+            ));
+            return;
+        };
+        if let Err(err) = self.check_archive_supported(tab) {
+            self.mount_error(format!("Could not extract archive: {err}"));
+            return;
+        }
+        self.mount_panel_wait(format!("Extracting {}…", file.name()));
+        let result = match tab {
+            FileExplorerTab::HostBridge => {
+                archive::extract_archive(format, file.path(), wrkdir.as_path())
+                    .map_err(|e| e.to_string())
+            }
+            _ => self.remote_archive_exec(&format.extract_command(file.path(), wrkdir.as_path())),
+        };
+        self.umount_wait();
+        match result {
+            Ok(()) => self.log(
+                LogLevel::Info,
+                format!(
+                    "Extracted \"{}\" into \"{}\"",
+                    file.path().display(),
+                    wrkdir.display()
+                ),
+            ),
+            Err(err) => self.log_and_alert(
+                LogLevel::Error,
+                format!("Could not extract \"{}\": {err}", file.path().display()),
+            ),
+        }
+    }
+
+    /// Check whether archives can be handled on the explorer `tab`: the host bridge must be the local host, where
+    /// archives are handled by termscp, while the remote must support executing commands, to run `tar` or `zip`
+    fn check_archive_supported(&self, tab: FileExplorerTab) -> Result<(), String> {
+        match tab {
+            FileExplorerTab::HostBridge if !self.host_bridge.is_localhost() => Err(String::from(
+                "archives are supported only on the local host and on the remotes supporting commands",
+            )),
+            FileExplorerTab::Remote if !self.is_exec_supported(tab) => Err(format!(
+                "{} doesn't support executing commands, so archives can't be handled on the remote host; transfer the files and handle them locally",
+                self.context()
+                    .remote_params()
+                    .map(|x| x.protocol.to_string())
+                    .unwrap_or_default()
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// Run the archive command `cmd` on the remote host
+    fn remote_archive_exec(&mut self, cmd: &str) -> Result<(), String> {
+        let cmd = shell::wrap_command(
+            self.context()
+                .remote_params()
+                .and_then(|params| params.remote_shell.as_deref())
+                .unwrap_or(DEFAULT_REMOTE_SHELL),
+            cmd,
+        );
+        debug!("running archive command: {cmd}");
+        match self.client.exec(cmd.as_str()) {
+            Ok((0, _)) => Ok(()),
+            Ok((rc, output)) => Err(format!("command exited with code {rc}: {}", output.trim())),
+            Err(err) => Err(err.to_string()),
+        }
+    }
+}
@@ -8,6 +8,7 @@ use std::path::PathBuf;
 use remotefs::File;
 
 use super::{FileExplorerTab, FileTransferActivity, LogLevel, Msg, PendingActionMsg};
+use crate::utils::clipboard;
 
 /// Describes destination for sync browsing
 enum SyncBrowsingDestination {
@@ -54,6 +55,58 @@ impl FileTransferActivity {
         }
     }
 
+    /// Go to the path copied in the system clipboard on the current panel.
+    /// If the path points to a file, its parent directory is entered and the file name is returned,
+    /// so that the file can be highlighted once the file list has been reloaded
+    pub(crate) fn action_go_to_clipboard_path(&mut self) -> Option<String> {
+        let text = match clipboard::read_text() {
+            Ok(text) => text,
+            Err(err) => {
+                self.mount_info(format!("Could not read the clipboard: {err}"));
+                return None;
+            }
+        };
+        let Some(path) = clipboard::parse_path(&text) else {
+            self.mount_info("The clipboard doesn't contain a path");
+            return None;
+        };
+        let tab = self.browser.tab();
+        let (path, entry) = match tab {
+            FileExplorerTab::HostBridge => {
+                let path = self.host_bridge_to_abs_path(path.as_path());
+                let entry = self
+                    .host_bridge
+                    .stat(path.as_path())
+                    .map_err(|e| e.to_string());
+                (path, entry)
+            }
+            FileExplorerTab::Remote => {
+                let path = self.remote_to_abs_path(path.as_path());
+                let entry = self.client.stat(path.as_path()).map_err(|e| e.to_string());
+                (path, entry)
+            }
+            FileExplorerTab::FindHostBridge | FileExplorerTab::FindRemote => return None,
+        };
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(err) => {
+                self.mount_info(format!("Could not go to \"{}\": {err}", path.display()));
+                return None;
+            }
+        };
+        // if the path is a file, enter its parent directory
+        let (dir, file) = match entry.path().parent() {
+            Some(parent) if !entry.is_dir() => (parent.to_path_buf(), Some(entry.name())),
+            _ => (path, None),
+        };
+        let dir = dir.to_string_lossy().to_string();
+        match tab {
+            FileExplorerTab::HostBridge => self.action_change_local_dir(dir),
+            _ => self.action_change_remote_dir(dir),
+        }
+        file
+    }
+
     /// Go to previous directory from localhost
     pub(crate) fn action_go_to_previous_local_dir(&mut self) {
         if let Some(d) = self.host_bridge_mut().popd() {
@@ -76,6 +129,42 @@ impl FileTransferActivity {
         }
     }
 
+    /// Show the directory history of the current explorer, from the most recent directory
+    pub(crate) fn action_show_dir_history(&mut self) {
+        let explorer = match self.browser.tab() {
+            FileExplorerTab::HostBridge => self.host_bridge(),
+            FileExplorerTab::Remote => self.remote(),
+            FileExplorerTab::FindHostBridge | FileExplorerTab::FindRemote => return,
+        };
+        let dirs: Vec<PathBuf> = explorer.iter_dirstack().rev().map(PathBuf::from).collect();
+        if dirs.is_empty() {
+            self.mount_info("There are no previous directories");
+            return;
+        }
+        self.mount_dir_history(&dirs);
+    }
+
+    /// Go to the directory at `idx` in the history of the current explorer, where `0` is the most recent directory.
+    /// The directories visited after it are removed from the history
+    pub(crate) fn action_go_to_history_entry(&mut self, idx: usize) {
+        let explorer = match self.browser.tab() {
+            FileExplorerTab::HostBridge => self.host_bridge_mut(),
+            FileExplorerTab::Remote => self.remote_mut(),
+            FileExplorerTab::FindHostBridge | FileExplorerTab::FindRemote => return,
+        };
+        let Some(dir) = explorer
+            .dirstack_len()
+            .checked_sub(idx + 1)
+            .and_then(|idx| explorer.jump_to(idx))
+        else {
+            return;
+        };
+        match self.browser.tab() {
+            FileExplorerTab::HostBridge => self.host_bridge_changedir(dir.as_path(), false),
+            _ => self.remote_changedir(dir.as_path(), false),
+        }
+    }
+
     /// Go to upper directory on local host
     pub(crate) fn action_go_to_local_upper_dir(&mut self) {
         // Get pwd
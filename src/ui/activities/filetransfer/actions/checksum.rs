@@ -0,0 +1,356 @@
+//! ## FileTransferActivity
+//!
+//! `filetransfer_activiy` is the module which implements the Filetransfer activity, which is the main activity afterall
+
+use std::io::{self, Read, Write};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use remotefs::RemoteErrorType;
+
+use super::{File, FileTransferActivity, LogLevel, SelectedFile};
+use crate::ui::activities::filetransfer::lib::browser::FileExplorerTab;
+use crate::ui::activities::filetransfer::lib::transfer::ProgressWriter;
+use crate::utils::checksum::{Checksum, ChecksumAlgorithm};
+use crate::utils::shell::{self, DEFAULT_REMOTE_SHELL};
+
+/// Size of the buffer used to stream files through the hasher
+const BUFSIZE: usize = 65535;
+/// Input events are read at this interval while streaming, so that the computation can be aborted
+const INPUT_EVENT_INTERVAL: Duration = Duration::from_millis(500);
+
+impl FileTransferActivity {
+    /// Compute the checksum of the highlighted file with `algorithm`.
+    /// On the remote host the checksum command is executed when available, otherwise the file is streamed
+    /// through the hasher. The digest is logged and displayed in a popup
+    pub(crate) fn action_checksum(&mut self, algorithm: ChecksumAlgorithm) {
+        let (selected, remote) = match self.browser.tab() {
+            FileExplorerTab::HostBridge => (self.get_local_selected_entries(), false),
+            FileExplorerTab::Remote => (self.get_remote_selected_entries(), true),
+            FileExplorerTab::FindHostBridge => (self.get_found_selected_entries(), false),
+            FileExplorerTab::FindRemote => (self.get_found_selected_entries(), true),
+        };
+        let SelectedFile::One(file) = selected else {
+            return;
+        };
+        if file.is_dir() || self.refuse_special_file(&file, remote, "read") {
+            return;
+        }
+        match self.file_checksum(&file, remote, algorithm) {
+            Ok(Some(digest)) => {
+                self.log(
+                    LogLevel::Info,
+                    format!(
+                        "{algorithm} checksum of \"{}\": {digest}",
+                        file.path().display()
+                    ),
+                );
+                self.mount_checksum(algorithm, file.name().as_str(), digest);
+            }
+            Ok(None) => self.log(
+                LogLevel::Warn,
+                format!(
+                    "Computation of the {algorithm} checksum of \"{}\" aborted",
+                    file.path().display()
+                ),
+            ),
+            Err(err) => self.log_and_alert(
+                LogLevel::Error,
+                format!(
+                    "Could not compute the {algorithm} checksum of \"{}\": {err}",
+                    file.path().display()
+                ),
+            ),
+        }
+    }
+
+    /// Compare the SHA256 of the highlighted file with the one of the file with the same name
+    /// in the working directory of the other explorer, displaying both digests in a popup
+    pub(crate) fn action_compare_checksums(&mut self) {
+        let algorithm = ChecksumAlgorithm::Sha256;
+        let (selected, other_wrkdir) = match self.browser.tab() {
+            FileExplorerTab::HostBridge => (
+                self.get_local_selected_entries(),
+                self.remote().wrkdir.clone(),
+            ),
+            FileExplorerTab::Remote => (
+                self.get_remote_selected_entries(),
+                self.host_bridge().wrkdir.clone(),
+            ),
+            FileExplorerTab::FindHostBridge | FileExplorerTab::FindRemote => return,
+        };
+        let SelectedFile::One(file) = selected else {
+            return;
+        };
+        if file.is_dir()
+            || self.refuse_special_file(
+                &file,
+                self.browser.tab() == FileExplorerTab::Remote,
+                "read",
+            )
+        {
+            return;
+        }
+        let counterpart_path = other_wrkdir.join(file.name());
+        let counterpart = match self.browser.tab() {
+            FileExplorerTab::HostBridge => self
+                .client
+                .stat(counterpart_path.as_path())
+                .map_err(|err| err.to_string()),
+            _ => self
+                .host_bridge
+                .stat(counterpart_path.as_path())
+                .map_err(|err| err.to_string()),
+        };
+        let counterpart = match counterpart {
+            Ok(counterpart) if counterpart.is_file() => counterpart,
+            Ok(_) => {
+                self.log_and_alert(
+                    LogLevel::Error,
+                    format!("\"{}\" is not a file", counterpart_path.display()),
+                );
+                return;
+            }
+            Err(err) => {
+                self.log_and_alert(
+                    LogLevel::Error,
+                    format!("Could not stat \"{}\": {err}", counterpart_path.display()),
+                );
+                return;
+            }
+        };
+        let (host_bridge_file, remote_file) = match self.browser.tab() {
+            FileExplorerTab::HostBridge => (file, counterpart),
+            _ => (counterpart, file),
+        };
+        let digests = self
+            .file_checksum(&host_bridge_file, false, algorithm)
+            .and_then(|host_bridge_digest| match host_bridge_digest {
+                Some(host_bridge_digest) => Ok(self
+                    .file_checksum(&remote_file, true, algorithm)?
+                    .map(|remote_digest| (host_bridge_digest, remote_digest))),
+                None => Ok(None),
+            });
+        match digests {
+            Ok(Some((host_bridge_digest, remote_digest))) => {
+                let verdict = match host_bridge_digest == remote_digest {
+                    true => "match",
+                    false => "DON'T match",
+                };
+                self.log(
+                    LogLevel::Info,
+                    format!(
+                        "{algorithm} checksums of \"{}\" ({host_bridge_digest}) and \"{}\" ({remote_digest}) {verdict}",
+                        host_bridge_file.path().display(),
+                        remote_file.path().display()
+                    ),
+                );
+                self.mount_checksum_compare(
+                    algorithm,
+                    host_bridge_file.name().as_str(),
+                    &host_bridge_digest,
+                    &remote_digest,
+                );
+            }
+            Ok(None) => self.log(
+                LogLevel::Warn,
+                format!(
+                    "Comparison of the {algorithm} checksums of \"{}\" aborted",
+                    host_bridge_file.name()
+                ),
+            ),
+            Err(err) => self.log_and_alert(
+                LogLevel::Error,
+                format!(
+                    "Could not compare the {algorithm} checksums of \"{}\": {err}",
+                    host_bridge_file.name()
+                ),
+            ),
+        }
+    }
+
+    /// Compute the checksum of `file`, on the remote host if `remote`, otherwise on the host bridge.
+    /// Returns `None` if the computation has been aborted
+    fn file_checksum(
+        &mut self,
+        file: &File,
+        remote: bool,
+        algorithm: ChecksumAlgorithm,
+    ) -> Result<Option<String>, String> {
+        match remote {
+            true => match self.remote_exec_checksum(file, algorithm) {
+                Some(digest) => Ok(Some(digest)),
+                None => self.remote_stream_checksum(file, algorithm),
+            },
+            false => self.host_bridge_stream_checksum(file, algorithm),
+        }
+    }
+
+    /// Compute the checksum of `file` running the checksum command on the remote host.
+    /// Returns `None` if the command is not supported or available
+    fn remote_exec_checksum(
+        &mut self,
+        file: &File,
+        algorithm: ChecksumAlgorithm,
+    ) -> Option<String> {
+        let cmd = shell::wrap_command(
+            self.context()
+                .remote_params()
+                .and_then(|params| params.remote_shell.as_deref())
+                .unwrap_or(DEFAULT_REMOTE_SHELL),
+            &format!(
+                "{} {}",
+                algorithm.command(),
+                shell::single_quote(&file.path().to_string_lossy())
+            ),
+        );
+        self.mount_blocking_wait(format!(
+            "Computing {algorithm} checksum of \"{}\"…",
+            file.name()
+        ));
+        let result = self.client.exec(cmd.as_str());
+        self.umount_wait();
+        match result {
+            Ok((0, output)) => algorithm.parse_command_output(&output),
+            Ok((rc, output)) => {
+                debug!("\"{cmd}\" exited with {rc}: {output}");
+                None
+            }
+            Err(err) => {
+                debug!("could not execute \"{cmd}\": {err}");
+                None
+            }
+        }
+    }
+
+    /// Compute the checksum of `file` on the host bridge, streaming it through the hasher
+    fn host_bridge_stream_checksum(
+        &mut self,
+        file: &File,
+        algorithm: ChecksumAlgorithm,
+    ) -> Result<Option<String>, String> {
+        let mut reader = self
+            .host_bridge
+            .open_file(file.path())
+            .map_err(|err| err.to_string())?;
+        let label = self.mount_checksum_progress(file, algorithm);
+        let result = self.stream_checksum(&mut reader, Checksum::new(algorithm), &label);
+        self.umount_progress_bar();
+        result
+    }
+
+    /// Compute the checksum of `file` on the remote host, streaming it through the hasher.
+    /// If the protocol doesn't support streams, the file is written into the hasher at once
+    fn remote_stream_checksum(
+        &mut self,
+        file: &File,
+        algorithm: ChecksumAlgorithm,
+    ) -> Result<Option<String>, String> {
+        let label = self.mount_checksum_progress(file, algorithm);
+        let result = match self.client.open(file.path()) {
+            Ok(mut reader) => {
+                let result = self.stream_checksum(&mut reader, Checksum::new(algorithm), &label);
+                if let Err(err) = self.client.on_read(reader) {
+                    self.log(
+                        LogLevel::Warn,
+                        format!("Could not finalize remote stream: \"{err}\""),
+                    );
+                }
+                result
+            }
+            Err(err) if err.kind == RemoteErrorType::UnsupportedFeature => {
+                let checksum = SharedChecksum::new(algorithm);
+                let writer = ProgressWriter::new(checksum.clone(), self.transfer.progress());
+                let result = self.client.open_file(file.path(), Box::new(writer));
+                self.transfer.sync_progress();
+                match result {
+                    Ok(_) => Ok(Some(checksum.finalize(algorithm))),
+                    Err(_) if self.transfer.aborted() => Ok(None),
+                    Err(err) => Err(err.to_string()),
+                }
+            }
+            Err(err) => Err(err.to_string()),
+        };
+        self.umount_progress_bar();
+        result
+    }
+
+    /// Initialize the transfer states and mount the progress bar for the checksum of `file`.
+    /// Returns the label of the progress bar
+    fn mount_checksum_progress(&mut self, file: &File, algorithm: ChecksumAlgorithm) -> String {
+        let size = file.metadata().size as usize;
+        let label = format!("Computing {algorithm} checksum of \"{}\"", file.name());
+        self.transfer.reset();
+        self.transfer.full.init(size);
+        self.transfer.partial.init(size);
+        self.mount_progress_bar(format!("{label}…"));
+        self.update_progress_bar(label.clone());
+        self.view();
+        label
+    }
+
+    /// Stream `reader` through `checksum`, updating the progress bar.
+    /// Returns the digest, or `None` if the computation has been aborted
+    fn stream_checksum(
+        &mut self,
+        reader: &mut dyn Read,
+        checksum: Checksum,
+        label: &str,
+    ) -> Result<Option<String>, String> {
+        let mut writer = ProgressWriter::new(checksum, self.transfer.progress());
+        let mut buffer = vec![0; BUFSIZE];
+        let mut last_progress_val: f64 = 0.0;
+        let mut last_input_event_fetch: Option<Instant> = None;
+        loop {
+            if last_input_event_fetch.is_none_or(|fetch| fetch.elapsed() >= INPUT_EVENT_INTERVAL) {
+                self.tick();
+                last_input_event_fetch = Some(Instant::now());
+            }
+            let bytes_read = match reader.read(&mut buffer) {
+                Ok(0) => break,
+                Ok(bytes_read) => bytes_read,
+                Err(err) => return Err(err.to_string()),
+            };
+            // writing to the hasher fails only if the computation has been aborted
+            if writer.write_all(&buffer[..bytes_read]).is_err() {
+                return Ok(None);
+            }
+            self.transfer.sync_progress();
+            if last_progress_val < self.transfer.partial.calc_progress() - 0.01 {
+                self.update_progress_bar(label.to_string());
+                self.view();
+                last_progress_val = self.transfer.partial.calc_progress();
+            }
+        }
+        match self.transfer.aborted() {
+            true => Ok(None),
+            false => Ok(Some(writer.into_inner().finalize())),
+        }
+    }
+}
+
+/// Hasher shared with the writer given to the remote client, for protocols which don't support streams
+#[derive(Clone)]
+struct SharedChecksum(Arc<Mutex<Checksum>>);
+
+impl SharedChecksum {
+    fn new(algorithm: ChecksumAlgorithm) -> Self {
+        Self(Arc::new(Mutex::new(Checksum::new(algorithm))))
+    }
+
+    /// Get the digest of the data written so far
+    fn finalize(self, algorithm: ChecksumAlgorithm) -> String {
+        let mut checksum = self.0.lock().unwrap();
+        std::mem::replace(&mut *checksum, Checksum::new(algorithm)).finalize()
+    }
+}
+
+impl Write for SharedChecksum {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
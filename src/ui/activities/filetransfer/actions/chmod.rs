@@ -1,99 +1,150 @@
+use std::path::Path;
+
 use remotefs::fs::UnixPex;
+use remotefs::File;
 
 use super::{FileTransferActivity, LogLevel};
 
+/// Amount of entries after which the progress of a recursive chmod is refreshed
+const CHMOD_PROGRESS_STEP: usize = 100;
+
 impl FileTransferActivity {
-    pub fn action_local_chmod(&mut self, mode: UnixPex) {
+    pub fn action_local_chmod(&mut self, mode: UnixPex, recursive: bool) {
         let files = self.get_local_selected_entries().get_files();
-
-        for file in files {
-            if let Err(err) = self.host_bridge.chmod(file.path(), mode) {
-                self.log_and_alert(
-                    LogLevel::Error,
-                    format!(
-                        "could not change mode for {}: {}",
-                        file.path().display(),
-                        err
-                    ),
-                );
-                return;
-            }
-            self.log(
-                LogLevel::Info,
-                format!("changed mode to {:#o} for {}", u32::from(mode), file.name()),
-            );
-        }
+        self.host_bridge_chmod(files, mode, recursive);
     }
 
-    pub fn action_remote_chmod(&mut self, mode: UnixPex) {
+    pub fn action_remote_chmod(&mut self, mode: UnixPex, recursive: bool) {
         let files = self.get_remote_selected_entries().get_files();
+        self.remote_chmod(files, mode, recursive);
+    }
 
-        for file in files {
-            let mut metadata = file.metadata.clone();
-            metadata.mode = Some(mode);
-
-            if let Err(err) = self.client.setstat(file.path(), metadata) {
-                self.log_and_alert(
-                    LogLevel::Error,
-                    format!(
-                        "could not change mode for {}: {}",
-                        file.path().display(),
-                        err
-                    ),
-                );
-                return;
-            }
-            self.log(
-                LogLevel::Info,
-                format!("changed mode to {:#o} for {}", u32::from(mode), file.name()),
-            );
-        }
+    pub fn action_find_local_chmod(&mut self, mode: UnixPex, recursive: bool) {
+        let files = self.get_found_selected_entries().get_files();
+        self.host_bridge_chmod(files, mode, recursive);
     }
 
-    pub fn action_find_local_chmod(&mut self, mode: UnixPex) {
+    pub fn action_find_remote_chmod(&mut self, mode: UnixPex, recursive: bool) {
         let files = self.get_found_selected_entries().get_files();
+        self.remote_chmod(files, mode, recursive);
+    }
 
-        for file in files {
-            if let Err(err) = self.host_bridge.chmod(file.path(), mode) {
-                self.log_and_alert(
-                    LogLevel::Error,
-                    format!(
-                        "could not change mode for {}: {}",
-                        file.path().display(),
-                        err
-                    ),
-                );
-                return;
-            }
-            self.log(
-                LogLevel::Info,
-                format!("changed mode to {:#o} for {}", u32::from(mode), file.name()),
-            );
-        }
+    fn host_bridge_chmod(&mut self, files: Vec<File>, mode: UnixPex, recursive: bool) {
+        self.chmod_files(
+            files,
+            mode,
+            recursive,
+            |activity, file| {
+                activity
+                    .host_bridge
+                    .chmod(file.path(), mode)
+                    .map_err(|e| e.to_string())
+            },
+            |activity, path| {
+                activity
+                    .host_bridge
+                    .list_dir(path)
+                    .map_err(|e| e.to_string())
+            },
+        );
     }
 
-    pub fn action_find_remote_chmod(&mut self, mode: UnixPex) {
-        let files = self.get_found_selected_entries().get_files();
+    fn remote_chmod(&mut self, files: Vec<File>, mode: UnixPex, recursive: bool) {
+        self.chmod_files(
+            files,
+            mode,
+            recursive,
+            |activity, file| {
+                let mut metadata = file.metadata.clone();
+                metadata.mode = Some(mode);
+                activity
+                    .client
+                    .setstat(file.path(), metadata)
+                    .map_err(|e| e.to_string())
+            },
+            |activity, path| activity.client.list_dir(path).map_err(|e| e.to_string()),
+        );
+    }
 
+    /// Apply `mode` to `files` with `chmod_fn`; if `recursive`, to the entries of the directories too, listed with
+    /// `list_dir_fn`. Symbolic links are not followed.
+    /// Errors don't stop the operation: they're logged and summarized once every entry has been processed
+    fn chmod_files<C, L>(
+        &mut self,
+        files: Vec<File>,
+        mode: UnixPex,
+        recursive: bool,
+        chmod_fn: C,
+        list_dir_fn: L,
+    ) where
+        C: Fn(&mut Self, &File) -> Result<(), String>,
+        L: Fn(&mut Self, &Path) -> Result<Vec<File>, String>,
+    {
+        let mut changed = 0;
+        let mut errors: Vec<String> = Vec::new();
         for file in files {
-            let mut metadata = file.metadata.clone();
-            metadata.mode = Some(mode);
-
-            if let Err(err) = self.client.setstat(file.path(), metadata) {
-                self.log_and_alert(
-                    LogLevel::Error,
-                    format!(
+            let mut entries = vec![file];
+            while let Some(entry) = entries.pop() {
+                if recursive && entry.is_dir() {
+                    match list_dir_fn(self, entry.path()) {
+                        Ok(children) => {
+                            entries.extend(children.into_iter().filter(|x| !x.is_symlink()))
+                        }
+                        Err(err) => errors.push(format!(
+                            "could not list directory {}: {}",
+                            entry.path().display(),
+                            err
+                        )),
+                    }
+                }
+                match chmod_fn(self, &entry) {
+                    Ok(()) if recursive => {
+                        changed += 1;
+                        if changed % CHMOD_PROGRESS_STEP == 0 {
+                            self.update_chmod_progress(changed);
+                        }
+                    }
+                    Ok(()) => {
+                        self.log(
+                            LogLevel::Info,
+                            format!(
+                                "changed mode to {:#o} for {}",
+                                u32::from(mode),
+                                entry.name()
+                            ),
+                        );
+                    }
+                    Err(err) => errors.push(format!(
                         "could not change mode for {}: {}",
-                        file.path().display(),
+                        entry.path().display(),
                         err
-                    ),
-                );
-                return;
+                    )),
+                }
+                self.poll_panel_wait();
             }
+        }
+        if recursive {
             self.log(
                 LogLevel::Info,
-                format!("changed mode to {:#o} for {}", u32::from(mode), file.name()),
+                format!(
+                    "changed mode to {:#o} recursively for {} entries",
+                    u32::from(mode),
+                    changed
+                ),
             );
         }
+        match errors.len() {
+            0 => {}
+            1 => self.log_and_alert(LogLevel::Error, errors.remove(0)),
+            failed => {
+                for err in errors.iter() {
+                    self.log(LogLevel::Error, err.clone());
+                }
+                self.mount_error(format!(
+                    "{} errors while changing mode (see the log for details); first error: {}",
+                    failed, errors[0]
+                ));
+            }
+        }
     }
 }
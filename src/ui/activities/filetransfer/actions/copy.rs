@@ -7,7 +7,11 @@ use std::path::{Path, PathBuf};
 
 use remotefs::{File, RemoteErrorType};
 
-use super::{FileTransferActivity, LogLevel, SelectedFile, TransferPayload};
+use super::{
+    FileExplorerTab, FileTransferActivity, LogLevel, SelectedFile, TransferOpts, TransferPayload,
+};
+use crate::ui::activities::filetransfer::lib::transfer::duplicate_name;
+use crate::ui::activities::filetransfer::lib::undo::UndoHost;
 
 impl FileTransferActivity {
     /// Copy file on local
@@ -25,6 +29,7 @@ impl FileTransferActivity {
                     let mut dest_path: PathBuf = base_path.clone();
                     dest_path.push(entry.name());
                     self.local_copy_file(entry, dest_path.as_path());
+                    self.poll_panel_wait();
                 }
             }
             SelectedFile::None => {}
@@ -46,12 +51,43 @@ impl FileTransferActivity {
                     let mut dest_path: PathBuf = base_path.clone();
                     dest_path.push(entry.name());
                     self.remote_copy_file(entry, dest_path.as_path());
+                    self.poll_panel_wait();
                 }
             }
             SelectedFile::None => {}
         }
     }
 
+    /// Duplicate the selected entries of the current explorer in their directory.
+    /// Returns the name of the first copy, so that it can be highlighted once the file list has been reloaded
+    pub(crate) fn action_duplicate(&mut self) -> Option<String> {
+        let tab = self.browser.tab();
+        let (selected, explorer) = match tab {
+            FileExplorerTab::HostBridge => (self.get_local_selected_entries(), self.host_bridge()),
+            FileExplorerTab::Remote => (self.get_remote_selected_entries(), self.remote()),
+            FileExplorerTab::FindHostBridge | FileExplorerTab::FindRemote => return None,
+        };
+        let entries = match selected {
+            SelectedFile::One(entry) => vec![entry],
+            SelectedFile::Many(entries) => entries,
+            SelectedFile::None => return None,
+        };
+        let mut taken: Vec<String> = explorer.iter_files_all().map(|x| x.name()).collect();
+        let mut first = None;
+        for entry in entries {
+            let name = duplicate_name(&entry.name(), |x| taken.iter().any(|t| t == x));
+            let dest = entry.path().with_file_name(&name);
+            match tab {
+                FileExplorerTab::HostBridge => self.local_copy_file(&entry, dest.as_path()),
+                _ => self.remote_copy_file(entry, dest.as_path()),
+            }
+            first.get_or_insert_with(|| name.clone());
+            taken.push(name);
+            self.poll_panel_wait();
+        }
+        first
+    }
+
     fn local_copy_file(&mut self, entry: &File, dest: &Path) {
         match self.host_bridge.copy(entry, dest) {
             Ok(_) => {
@@ -63,6 +99,7 @@ impl FileTransferActivity {
                         dest.display()
                     ),
                 );
+                self.invalidate_undo(UndoHost::HostBridge, dest);
             }
             Err(err) => self.log_and_alert(
                 LogLevel::Error,
@@ -87,11 +124,14 @@ impl FileTransferActivity {
                         dest.display()
                     ),
                 );
+                self.invalidate_undo(UndoHost::Remote, dest);
             }
             Err(err) => match err.kind {
                 RemoteErrorType::UnsupportedFeature => {
                     // If copy is not supported, perform the tricky copy
-                    let _ = self.tricky_copy(entry, dest);
+                    if self.tricky_copy(entry, dest).is_ok() {
+                        self.invalidate_undo(UndoHost::Remote, dest);
+                    }
                 }
                 _ => self.log_and_alert(
                     LogLevel::Error,
@@ -126,9 +166,11 @@ impl FileTransferActivity {
             let mut tempdir_path: PathBuf = tempdir.path().to_path_buf();
             tempdir_path.push(entry.name());
             // Download file
-            if let Err(err) =
-                self.filetransfer_recv(TransferPayload::Any(entry), tempdir.path(), None)
-            {
+            if let Err(err) = self.filetransfer_recv(
+                TransferPayload::Any(entry),
+                tempdir.path(),
+                TransferOpts::default(),
+            ) {
                 self.log_and_alert(
                     LogLevel::Error,
                     format!("Copy failed: failed to download file: {err}"),
@@ -155,7 +197,7 @@ impl FileTransferActivity {
             if let Err(err) = self.filetransfer_send(
                 TransferPayload::Any(tempdir_entry),
                 wrkdir.as_path(),
-                Some(String::from(dest.to_string_lossy())),
+                TransferOpts::default().save_as(Some(String::from(dest.to_string_lossy()))),
             ) {
                 self.log_and_alert(
                     LogLevel::Error,
@@ -179,9 +221,11 @@ impl FileTransferActivity {
             // Download file
             let name = entry.name();
             let entry_path = entry.path().to_path_buf();
-            if let Err(err) =
-                self.filetransfer_recv(TransferPayload::File(entry), tmpfile.path(), Some(name))
-            {
+            if let Err(err) = self.filetransfer_recv(
+                TransferPayload::File(entry),
+                tmpfile.path(),
+                TransferOpts::default().save_as(Some(name)),
+            ) {
                 self.log_and_alert(
                     LogLevel::Error,
                     format!("Copy failed: could not download to temporary file: {err}"),
@@ -209,7 +253,7 @@ impl FileTransferActivity {
             if let Err(err) = self.filetransfer_send(
                 TransferPayload::File(tmpfile_entry),
                 wrkdir.as_path(),
-                Some(String::from(dest.to_string_lossy())),
+                TransferOpts::default().save_as(Some(String::from(dest.to_string_lossy()))),
             ) {
                 self.log_and_alert(
                     LogLevel::Error,
@@ -0,0 +1,175 @@
+//! ## FileTransferActivity
+//!
+//! `filetransfer_activiy` is the module which implements the Filetransfer activity, which is the main activity afterall
+
+// locals
+use std::path::Path;
+
+use super::{File, FileExplorerTab, FileTransferActivity, LogLevel, SelectedFile, TransferPayload};
+use crate::ui::activities::filetransfer::lib::undo::UndoHost;
+
+impl FileTransferActivity {
+    /// Mark the selected entries of the current panel as cut, so that they're moved to the directory they're pasted in.
+    /// If no entry is selected, the entries cut previously are released
+    pub(crate) fn action_cut(&mut self) {
+        let tab = self.browser.tab();
+        let selected = match tab {
+            FileExplorerTab::HostBridge => self.get_local_selected_entries(),
+            FileExplorerTab::Remote => self.get_remote_selected_entries(),
+            FileExplorerTab::FindHostBridge | FileExplorerTab::FindRemote => return,
+        };
+        let files = match selected {
+            SelectedFile::One(entry) => vec![entry],
+            SelectedFile::Many(entries) => entries,
+            SelectedFile::None => {
+                if self.browser.take_cut().is_some() {
+                    self.log(LogLevel::Info, String::from("Released the cut entries"));
+                }
+                return;
+            }
+        };
+        self.log(
+            LogLevel::Info,
+            format!(
+                "Cut {} entries; paste them in a directory to move them there",
+                files.len()
+            ),
+        );
+        self.browser.set_cut(tab, files);
+    }
+
+    /// Move the cut entries to the working directory of the current panel.
+    /// Entries are renamed when pasted on the panel they've been cut from, otherwise they're transferred and removed
+    /// from the source once the transfer has completed. Entries colliding with the files of the directory are given
+    /// a new name, so that nothing is replaced.
+    /// The entries which haven't been moved, because of an error or since the transfer has been aborted, are kept cut
+    pub(crate) fn action_paste(&mut self) {
+        let tab = self.browser.tab();
+        let wrkdir = match tab {
+            FileExplorerTab::HostBridge => self.host_bridge().wrkdir.clone(),
+            FileExplorerTab::Remote => self.remote().wrkdir.clone(),
+            FileExplorerTab::FindHostBridge | FileExplorerTab::FindRemote => return,
+        };
+        let Some(cut) = self.browser.take_cut() else {
+            return;
+        };
+        // a previous transfer may have been aborted
+        self.transfer.reset();
+        let mut taken: Vec<String> = Vec::with_capacity(cut.files.len());
+        let mut kept: Vec<File> = Vec::new();
+        for entry in cut.files {
+            // once the transfer has been aborted, the remaining entries are not moved
+            if self.transfer.aborted() {
+                kept.push(entry);
+                continue;
+            }
+            let name = self.paste_name(tab, wrkdir.as_path(), &entry, &taken);
+            let moved = match cut.tab == tab {
+                true => self.paste_rename(&entry, wrkdir.as_path(), &name),
+                false => self.paste_transfer(&entry, wrkdir.as_path(), &name),
+            };
+            match moved {
+                true => taken.push(name),
+                false => kept.push(entry),
+            }
+        }
+        if !kept.is_empty() {
+            self.browser.set_cut(cut.tab, kept);
+        }
+    }
+
+    /// Get the name of `entry` once pasted in `dir`, on the host of `tab`: its own name, unless it collides with a file
+    /// of the directory or with the `taken` names
+    fn paste_name(
+        &mut self,
+        tab: FileExplorerTab,
+        dir: &Path,
+        entry: &File,
+        taken: &[String],
+    ) -> String {
+        let name = entry.name();
+        // an entry pasted in its own directory is left where it is
+        if entry.path().parent() == Some(dir) {
+            return name;
+        }
+        let path = dir.join(&name);
+        let exists = taken.contains(&name)
+            || match tab {
+                FileExplorerTab::Remote | FileExplorerTab::FindRemote => {
+                    self.remote_file_exists(path.as_path())
+                }
+                FileExplorerTab::HostBridge | FileExplorerTab::FindHostBridge => {
+                    self.host_bridge_file_exists(path.as_path())
+                }
+            };
+        match exists {
+            true => self.suggest_name(tab, dir, &name, taken),
+            false => name,
+        }
+    }
+
+    /// Move `entry` to `dir`, as `name`, on the host of the current panel. Returns whether it has been moved
+    fn paste_rename(&mut self, entry: &File, dir: &Path, name: &str) -> bool {
+        if entry.path().parent() == Some(dir) {
+            return true;
+        }
+        let dest = dir.join(name);
+        match self.browser.tab() {
+            FileExplorerTab::HostBridge => {
+                let moved = self.local_rename_file(entry, dest.as_path());
+                if moved {
+                    self.push_undo_move(UndoHost::HostBridge, entry.path(), dest.as_path());
+                }
+                moved
+            }
+            _ => {
+                let moved = self.remote_rename_file(entry, dest.as_path());
+                if moved {
+                    self.push_undo_move(UndoHost::Remote, entry.path(), dest.as_path());
+                }
+                moved
+            }
+        }
+    }
+
+    /// Transfer `entry` to `dir`, as `name`, on the host of the current panel, then remove it from the other host.
+    /// The source is removed only if the transfer has completed and `entry` is on the destination.
+    /// Returns whether it has been moved
+    fn paste_transfer(&mut self, entry: &File, dir: &Path, name: &str) -> bool {
+        let opts = self.transfer_opts().save_as(Some(name));
+        let dest = dir.join(name);
+        let result = match self.browser.tab() {
+            FileExplorerTab::Remote => {
+                self.filetransfer_send(TransferPayload::Any(entry.clone()), dir, opts)
+            }
+            _ => self.filetransfer_recv(TransferPayload::Any(entry.clone()), dir, opts),
+        };
+        if let Err(err) = result {
+            self.log_and_alert(
+                LogLevel::Error,
+                format!("Could not move \"{}\": {err}", entry.path().display()),
+            );
+            return false;
+        }
+        let transferred = !self.transfer.aborted()
+            && self.transfer.first_error().is_none()
+            && match self.browser.tab() {
+                FileExplorerTab::Remote => self.remote_file_exists(dest.as_path()),
+                _ => self.host_bridge_file_exists(dest.as_path()),
+            };
+        if !transferred {
+            self.log(
+                LogLevel::Warn,
+                format!(
+                    "\"{}\" has not been transferred completely, so it has been kept",
+                    entry.path().display()
+                ),
+            );
+            return false;
+        }
+        match self.browser.tab() {
+            FileExplorerTab::Remote => self.local_remove_file(entry),
+            _ => self.remote_remove_file(entry),
+        }
+    }
+}
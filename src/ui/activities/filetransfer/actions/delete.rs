@@ -5,9 +5,119 @@
 // locals
 use remotefs::File;
 
-use super::{FileTransferActivity, LogLevel, SelectedFile};
+use super::{FileExplorerTab, FileTransferActivity, LogLevel, SelectedFile};
+use crate::system::trash::{self, TrashError};
+use crate::ui::activities::filetransfer::lib::transfer::TransferEstimate;
+use crate::ui::activities::filetransfer::lib::undo::UndoHost;
+
+/// Maximum amount of entries counted in a directory before prompting for its deletion
+const DELETE_COUNT_MAX_ENTRIES: usize = 50_000;
 
 impl FileTransferActivity {
+    /// Mount the delete popup for the selected entries.
+    /// If a single directory is selected, its entries are counted first (the count can be aborted with CTRL+C);
+    /// if they're more than the configured threshold, the user has to type the directory name to confirm the deletion.
+    /// Entries are not counted for protocols where listing directories is expensive
+    pub(crate) fn action_show_delete_popup(&mut self) {
+        let tab = self.browser.tab();
+        let selected = match tab {
+            FileExplorerTab::HostBridge => self.get_local_selected_entries(),
+            FileExplorerTab::Remote => self.get_remote_selected_entries(),
+            FileExplorerTab::FindHostBridge | FileExplorerTab::FindRemote => SelectedFile::None,
+        };
+        let trash = self.is_deleting_to_trash(tab);
+        let dir = match selected {
+            SelectedFile::One(entry) if entry.is_dir() => entry,
+            _ if trash => return self.mount_radio_delete("Move file(s) to trash?"),
+            _ => return self.mount_radio_delete("Permanently delete file(s)?"),
+        };
+        let name = dir.name();
+        if self.is_listing_expensive(tab) {
+            return self.mount_radio_delete(match trash {
+                true => format!("Move directory \"{name}\" to trash?"),
+                false => format!("Permanently delete directory \"{name}\" and all of its content?"),
+            });
+        }
+        let Some(estimate) = self.count_dir_entries(&dir, tab == FileExplorerTab::Remote) else {
+            self.log(
+                LogLevel::Info,
+                format!("Deletion of \"{}\" cancelled", dir.path().display()),
+            );
+            return;
+        };
+        // the directory itself is not an entry
+        let entries = estimate.entries().saturating_sub(1);
+        let count = match estimate.is_complete() {
+            true => format!("~{entries}"),
+            false => format!("over {entries}"),
+        };
+        let title = match trash {
+            true => format!("Move directory \"{name}\" containing {count} entries to trash?"),
+            false => {
+                format!("Permanently delete directory \"{name}\" containing {count} entries?")
+            }
+        };
+        let threshold = self.config().get_delete_confirm_name_threshold();
+        if threshold > 0 && entries as u64 >= threshold {
+            self.mount_delete_confirm_name(&name, title);
+        } else {
+            self.mount_radio_delete(title);
+        }
+    }
+
+    /// Whether the entries deleted from `tab` are moved to the trash rather than deleted permanently.
+    /// Only files on the local host are moved to the trash
+    fn is_deleting_to_trash(&self, tab: FileExplorerTab) -> bool {
+        matches!(
+            tab,
+            FileExplorerTab::HostBridge | FileExplorerTab::FindHostBridge
+        ) && self.host_bridge.is_localhost()
+            && self.config().get_delete_to_trash()
+    }
+
+    /// Count the entries of `dir`, up to `DELETE_COUNT_MAX_ENTRIES` or the configured threshold.
+    /// Returns `None` if the user aborted the count
+    fn count_dir_entries(&mut self, dir: &File, remote: bool) -> Option<TransferEstimate> {
+        let max_entries = DELETE_COUNT_MAX_ENTRIES
+            .max(self.config().get_delete_confirm_name_threshold() as usize);
+        let mut estimate = TransferEstimate::new(std::slice::from_ref(dir));
+        self.transfer.reset();
+        self.mount_scan_wait("Counting entries to delete…");
+        while !estimate.is_complete() && estimate.entries() <= max_entries {
+            let result = estimate.scan_next(|path| match remote {
+                true => self.client.list_dir(path).map_err(|e| e.to_string()),
+                false => self.host_bridge.list_dir(path).map_err(|e| e.to_string()),
+            });
+            if let Err((path, err)) = result {
+                self.log(
+                    LogLevel::Error,
+                    format!("Could not list directory {}: {}", path.display(), err),
+                );
+            }
+            self.update_scan_wait(format!(
+                "Counting entries to delete… ({} items found)",
+                estimate.entries().saturating_sub(1)
+            ));
+            // read events
+            self.tick();
+            if self.transfer.aborted() {
+                self.umount_wait();
+                return None;
+            }
+        }
+        self.umount_wait();
+        for link in estimate.loops() {
+            self.log(
+                LogLevel::Warn,
+                format!(
+                    "Skipped \"{}\" while counting entries: symbolic link loop detected",
+                    link.display()
+                ),
+            );
+        }
+        Some(estimate)
+    }
+
     pub(crate) fn action_local_delete(&mut self) {
         match self.get_local_selected_entries() {
             SelectedFile::One(entry) => {
@@ -19,6 +129,7 @@ impl FileTransferActivity {
                 for entry in entries.iter() {
                     // Delete file
                     self.local_remove_file(entry);
+                    self.poll_panel_wait();
                 }
             }
             SelectedFile::None => {}
@@ -36,13 +147,47 @@ impl FileTransferActivity {
                 for entry in entries.iter() {
                     // Delete file
                     self.remote_remove_file(entry);
+                    self.poll_panel_wait();
                 }
             }
             SelectedFile::None => {}
         }
     }
 
-    pub(crate) fn local_remove_file(&mut self, entry: &File) {
+    /// Remove `entry` from the host bridge, or move it to the trash. Returns whether it has been removed
+    pub(crate) fn local_remove_file(&mut self, entry: &File) -> bool {
+        if self.is_deleting_to_trash(FileExplorerTab::HostBridge) {
+            match trash::move_to_trash(entry.path()) {
+                Ok(()) => {
+                    self.log(
+                        LogLevel::Info,
+                        format!("Moved \"{}\" to trash", entry.path().display()),
+                    );
+                    self.invalidate_undo(UndoHost::HostBridge, entry.path());
+                    return true;
+                }
+                Err(TrashError::Unavailable(reason)) => {
+                    self.log(
+                        LogLevel::Warn,
+                        format!(
+                            "Could not move \"{}\" to trash ({reason}); deleting it permanently",
+                            entry.path().display()
+                        ),
+                    );
+                }
+                Err(err) => {
+                    self.log_and_alert(
+                        LogLevel::Error,
+                        format!(
+                            "Could not delete file \"{}\": {}",
+                            entry.path().display(),
+                            err
+                        ),
+                    );
+                    return false;
+                }
+            }
+        }
         match self.host_bridge.remove(entry) {
             Ok(_) => {
                 // Log
@@ -50,6 +195,8 @@ impl FileTransferActivity {
                     LogLevel::Info,
                     format!("Removed file \"{}\"", entry.path().display()),
                 );
+                self.invalidate_undo(UndoHost::HostBridge, entry.path());
+                true
             }
             Err(err) => {
                 self.log_and_alert(
@@ -60,17 +207,21 @@ impl FileTransferActivity {
                         err
                     ),
                 );
+                false
             }
         }
     }
 
-    pub(crate) fn remote_remove_file(&mut self, entry: &File) {
+    /// Remove `entry` from the remote. Returns whether it has been removed
+    pub(crate) fn remote_remove_file(&mut self, entry: &File) -> bool {
         match self.client.remove_dir_all(entry.path()) {
             Ok(_) => {
                 self.log(
                     LogLevel::Info,
                     format!("Removed file \"{}\"", entry.path().display()),
                 );
+                self.invalidate_undo(UndoHost::Remote, entry.path());
+                true
             }
             Err(err) => {
                 self.log_and_alert(
@@ -81,6 +232,7 @@ impl FileTransferActivity {
                         err
                     ),
                 );
+                false
             }
         }
     }
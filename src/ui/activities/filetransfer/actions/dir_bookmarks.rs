@@ -0,0 +1,171 @@
+//! ## FileTransferActivity
+//!
+//! `filetransfer_activiy` is the module which implements the Filetransfer activity, which is the main activity afterall
+
+use super::{FileExplorerTab, FileTransferActivity, LogLevel};
+use crate::config::bookmarks::DirectoryBookmark;
+
+impl FileTransferActivity {
+    /// Show the popup to bookmark the working directory of the current explorer
+    pub(crate) fn action_show_bookmark_dir_popup(&mut self) {
+        let wrkdir = match self.browser.tab() {
+            FileExplorerTab::HostBridge => self.host_bridge().wrkdir.clone(),
+            FileExplorerTab::Remote => self.remote().wrkdir.clone(),
+            FileExplorerTab::FindHostBridge | FileExplorerTab::FindRemote => return,
+        };
+        if self.dir_bookmarks_host().is_none() {
+            self.mount_error("Directories can't be bookmarked: the bookmarks are not available");
+            return;
+        }
+        let suggestion = wrkdir
+            .file_name()
+            .map(|x| x.to_string_lossy().to_string())
+            .unwrap_or_else(|| String::from("root"));
+        self.mount_bookmark_dir(wrkdir.as_path(), &suggestion);
+    }
+
+    /// Bookmark the working directory of the current explorer as `name`.
+    /// A directory bookmarked with the same name is replaced
+    pub(crate) fn action_bookmark_dir(&mut self, name: String) {
+        let name = name.trim().to_string();
+        if name.is_empty() {
+            return;
+        }
+        let Some(host) = self.dir_bookmarks_host() else {
+            return;
+        };
+        let path = match self.browser.tab() {
+            FileExplorerTab::HostBridge => self.host_bridge().wrkdir.clone(),
+            _ => self.remote().wrkdir.clone(),
+        };
+        let mut directories = self.dir_bookmarks(&host);
+        directories.retain(|x| x.name != name);
+        self.log(
+            LogLevel::Info,
+            format!("Bookmarked \"{}\" as \"{name}\"", path.display()),
+        );
+        directories.push(DirectoryBookmark { name, path });
+        directories.sort_by(|a, b| a.name.cmp(&b.name));
+        self.set_dir_bookmarks(&host, directories);
+    }
+
+    /// Show the directories bookmarked on the host of the current explorer
+    pub(crate) fn action_show_dir_bookmarks(&mut self) {
+        let Some(host) = self.dir_bookmarks_host() else {
+            return;
+        };
+        let directories = self.dir_bookmarks(&host);
+        if directories.is_empty() {
+            self.mount_info(format!(
+                "There are no bookmarked directories on {host}; press <SHIFT+B> to bookmark the current directory"
+            ));
+            return;
+        }
+        self.mount_dir_bookmarks(&directories);
+    }
+
+    /// Go to the directory bookmarked at `idx` on the host of the current explorer.
+    /// If the directory doesn't exist anymore, an error is reported, but the bookmark is kept
+    pub(crate) fn action_go_to_dir_bookmark(&mut self, idx: usize) {
+        let Some(host) = self.dir_bookmarks_host() else {
+            return;
+        };
+        let Some(dir) = self.dir_bookmarks(&host).into_iter().nth(idx) else {
+            return;
+        };
+        let tab = self.browser.tab();
+        let exists = match tab {
+            FileExplorerTab::HostBridge => self
+                .host_bridge
+                .stat(dir.path.as_path())
+                .map(|x| x.is_dir())
+                .map_err(|e| e.to_string()),
+            _ => self
+                .client
+                .stat(dir.path.as_path())
+                .map(|x| x.is_dir())
+                .map_err(|e| e.to_string()),
+        };
+        match exists {
+            Ok(true) => {}
+            Ok(false) => {
+                self.mount_error(format!(
+                    "Could not go to \"{}\": \"{}\" is not a directory",
+                    dir.name,
+                    dir.path.display()
+                ));
+                return;
+            }
+            Err(err) => {
+                self.log_and_alert(
+                    LogLevel::Error,
+                    format!(
+                        "Could not go to \"{}\": \"{}\" doesn't exist anymore ({err})",
+                        dir.name,
+                        dir.path.display()
+                    ),
+                );
+                return;
+            }
+        }
+        let path = dir.path.to_string_lossy().to_string();
+        match tab {
+            FileExplorerTab::HostBridge => self.action_change_local_dir(path),
+            _ => self.action_change_remote_dir(path),
+        }
+    }
+
+    /// Remove the directory bookmarked at `idx` on the host of the current explorer
+    pub(crate) fn action_remove_dir_bookmark(&mut self, idx: usize) {
+        let Some(host) = self.dir_bookmarks_host() else {
+            return;
+        };
+        let mut directories = self.dir_bookmarks(&host);
+        if idx >= directories.len() {
+            return;
+        }
+        let dir = directories.remove(idx);
+        self.log(
+            LogLevel::Info,
+            format!(
+                "Removed bookmarked directory \"{}\" ({})",
+                dir.name,
+                dir.path.display()
+            ),
+        );
+        self.set_dir_bookmarks(&host, directories);
+    }
+
+    /// Returns whether there are directories bookmarked on the host of the current explorer
+    pub(crate) fn has_dir_bookmarks(&self) -> bool {
+        self.dir_bookmarks_host()
+            .is_some_and(|host| !self.dir_bookmarks(&host).is_empty())
+    }
+
+    /// Get the identifier of the host of the current explorer, which the directories are bookmarked for.
+    /// Returns `None` if the bookmarks are not available
+    fn dir_bookmarks_host(&self) -> Option<String> {
+        self.context().bookmarks_client()?;
+        match self.browser.tab() {
+            FileExplorerTab::HostBridge => self.context().host_bridge_params().map(|x| x.host_id()),
+            FileExplorerTab::Remote => self
+                .context()
+                .remote_params()
+                .map(|x| x.params.host_id(x.protocol)),
+            FileExplorerTab::FindHostBridge | FileExplorerTab::FindRemote => None,
+        }
+    }
+
+    fn dir_bookmarks(&self, host: &str) -> Vec<DirectoryBookmark> {
+        self.context()
+            .bookmarks_client()
+            .map(|client| client.get_directory_bookmarks(host).to_vec())
+            .unwrap_or_default()
+    }
+
+    fn set_dir_bookmarks(&mut self, host: &str, directories: Vec<DirectoryBookmark>) {
+        if let Some(client) = self.context_mut().bookmarks_client_mut() {
+            client.set_directory_bookmarks(host, directories);
+        }
+    }
+}
@@ -7,10 +7,27 @@ use std::io::Read;
 use std::path::{Path, PathBuf};
 use std::time::SystemTime;
 
+use bytesize::ByteSize;
 use remotefs::fs::Metadata;
 use remotefs::File;
 
-use super::{FileTransferActivity, LogLevel, SelectedFile, TransferPayload};
+use super::{
+    FileExplorerTab, FileTransferActivity, LogLevel, Msg, PendingActionMsg, SelectedFile,
+    TransferOpts, TransferPayload,
+};
+use crate::ui::activities::filetransfer::lib::remote_version::RemoteVersion;
+use crate::utils::diff::UnifiedDiff;
+use crate::utils::io::copy_with_progress;
+
+/// Amount of bytes inspected to tell whether a file is binary
+const BINARY_INSPECT_SIZE: usize = 2048;
+
+/// What to do with an edited file, whose remote copy has changed since it was downloaded
+enum EditConflictAnswer {
+    Overwrite,
+    SaveAs(String),
+    Discard,
+}
 
 impl FileTransferActivity {
     pub(crate) fn action_edit_local_file(&mut self) {
@@ -22,7 +39,7 @@ impl FileTransferActivity {
         // Edit all entries
         for entry in entries.iter() {
             // Check if file
-            if entry.is_file() {
+            if entry.is_file() && !self.refuse_special_file(entry, false, "edited") {
                 self.log(
                     LogLevel::Info,
                     format!("Opening file \"{}\"…", entry.path().display()),
@@ -49,7 +66,7 @@ impl FileTransferActivity {
         // Edit all entries
         for entry in entries.into_iter() {
             // Check if file
-            if entry.is_file() {
+            if entry.is_file() && !self.refuse_special_file(&entry, true, "edited") {
                 self.log(
                     LogLevel::Info,
                     format!("Opening file \"{}\"…", entry.path().display()),
@@ -65,6 +82,7 @@ impl FileTransferActivity {
     /// Edit a file on localhost
     fn edit_bridged_local_file(&mut self, entry: &File) -> Result<(), String> {
         // Download file
+        let cache: PathBuf = self.cache_path()?;
         let tmpfile: String =
             match self.get_cache_tmp_name(&entry.name(), entry.extension().as_deref()) {
                 None => {
@@ -72,12 +90,6 @@ impl FileTransferActivity {
                 }
                 Some(p) => p,
             };
-        let cache: PathBuf = match self.cache.as_ref() {
-            None => {
-                return Err("Could not create tempdir".to_string());
-            }
-            Some(p) => p.path().to_path_buf(),
-        };
 
         // open from host bridge
         let mut reader = match self.host_bridge.open_file(entry.path()) {
@@ -97,10 +109,12 @@ impl FileTransferActivity {
             }
         };
 
-        let new_file_size = match std::io::copy(&mut reader, &mut writer) {
-            Err(err) => return Err(format!("Could not write file: {err}")),
-            Ok(size) => size,
-        };
+        let buffer_size = self.config().get_transfer_buffer_size();
+        let new_file_size =
+            match copy_with_progress(&mut reader, &mut writer, buffer_size, |_| true) {
+                Err(err) => return Err(format!("Could not write file: {err}")),
+                Ok(size) => size,
+            };
 
         // edit file
 
@@ -127,7 +141,7 @@ impl FileTransferActivity {
                 }
             };
 
-            if let Err(err) = std::io::copy(&mut reader, &mut writer) {
+            if let Err(err) = copy_with_progress(&mut reader, &mut writer, buffer_size, |_| true) {
                 return Err(format!("Could not write file: {err}"));
             }
 
@@ -228,13 +242,26 @@ impl FileTransferActivity {
         // Download file
         let file_name = file.name();
         let file_path = file.path().to_path_buf();
+        // Keep the version of the remote file, to tell whether it changes while being edited
+        let version = match self.client.stat(file_path.as_path()) {
+            Ok(entry) => RemoteVersion::from(entry.metadata()),
+            Err(_) => RemoteVersion::from(file.metadata()),
+        };
         if let Err(err) = self.filetransfer_recv(
             TransferPayload::File(file),
             tmpfile.as_path(),
-            Some(file_name.clone()),
+            TransferOpts::default().save_as(Some(file_name.clone())),
         ) {
             return Err(format!("Could not open file {file_name}: {err}"));
         }
+        // Keep the downloaded content, to show the diff of the changes before uploading them
+        let original = match self.config().get_confirm_edit_upload_with_diff() {
+            true => Some(
+                std::fs::read(tmpfile.as_path())
+                    .map_err(|err| format!("Could not read file {file_name}: {err}"))?,
+            ),
+            false => None,
+        };
         // Get current file modification time
         let prev_mtime: SystemTime = match self.host_bridge.stat(tmpfile.as_path()) {
             Ok(e) => e.metadata().modified.unwrap_or(std::time::UNIX_EPOCH),
@@ -274,6 +301,40 @@ impl FileTransferActivity {
                         file_path.display()
                     ),
                 );
+                if let Some(original) = original.as_deref() {
+                    if !self.confirm_edit_upload(
+                        file_path.as_path(),
+                        original,
+                        tmpfile.as_path(),
+                    )? {
+                        self.log(
+                            LogLevel::Info,
+                            format!(
+                                "Changes to \"{}\" have not been uploaded",
+                                file_path.display()
+                            ),
+                        );
+                        return Ok(());
+                    }
+                }
+                // Check whether the remote file has changed in the meantime
+                let mut file_name = file_name;
+                if self.has_edited_remote_file_changed(file_path.as_path(), &version) {
+                    match self.resolve_edit_conflict(&file_name) {
+                        EditConflictAnswer::Overwrite => {}
+                        EditConflictAnswer::SaveAs(name) => file_name = name,
+                        EditConflictAnswer::Discard => {
+                            self.log(
+                                LogLevel::Info,
+                                format!(
+                                    "Changes to \"{}\" have been discarded",
+                                    file_path.display()
+                                ),
+                            );
+                            return Ok(());
+                        }
+                    }
+                }
                 // Get local fs entry
                 let tmpfile_entry = match self.host_bridge.stat(tmpfile.as_path()) {
                     Ok(e) => e,
@@ -290,7 +351,7 @@ impl FileTransferActivity {
                 if let Err(err) = self.filetransfer_send(
                     TransferPayload::File(tmpfile_entry),
                     wrkdir.as_path(),
-                    Some(file_name),
+                    TransferOpts::default().save_as(Some(file_name)),
                 ) {
                     return Err(format!(
                         "Could not write file {}: {}",
@@ -308,4 +369,100 @@ impl FileTransferActivity {
         }
         Ok(())
     }
+
+    /// Returns whether the remote file at `path` has changed since `version`; a file which can't be stat anymore
+    /// (e.g. it has been removed) is considered changed
+    fn has_edited_remote_file_changed(&mut self, path: &Path, version: &RemoteVersion) -> bool {
+        match self.client.stat(path) {
+            Ok(entry) => version.has_changed(&RemoteVersion::from(entry.metadata())),
+            Err(err) => {
+                debug!("could not stat {}: {err}", path.display());
+                true
+            }
+        }
+    }
+
+    /// Ask the user what to do with the edited file `file_name`, since the remote file has changed since it was opened
+    fn resolve_edit_conflict(&mut self, file_name: &str) -> EditConflictAnswer {
+        self.log(
+            LogLevel::Warn,
+            format!("\"{file_name}\" has changed on the remote host since it was opened"),
+        );
+        self.mount_edit_conflict(file_name);
+        let answer = self.wait_for_pending_msg(&[
+            Msg::PendingAction(PendingActionMsg::OverwriteEditedFile),
+            Msg::PendingAction(PendingActionMsg::SaveEditedFileAs),
+            Msg::PendingAction(PendingActionMsg::CloseEditConflictPopup),
+        ]);
+        self.umount_edit_conflict();
+        match answer {
+            Msg::PendingAction(PendingActionMsg::OverwriteEditedFile) => {
+                EditConflictAnswer::Overwrite
+            }
+            Msg::PendingAction(PendingActionMsg::SaveEditedFileAs) => {
+                let wrkdir = self.remote().wrkdir.clone();
+                let suggestion =
+                    self.suggest_name(FileExplorerTab::Remote, wrkdir.as_path(), file_name, &[]);
+                match self.should_rename_file(file_name, &suggestion) {
+                    Some(name) => EditConflictAnswer::SaveAs(name),
+                    None => EditConflictAnswer::Discard,
+                }
+            }
+            _ => EditConflictAnswer::Discard,
+        }
+    }
+
+    /// Show the diff between the `original` content of the remote file at `remote` and the `edited` file,
+    /// and ask the user whether to upload the changes. Binary contents are not diffed: only their sizes are shown
+    fn confirm_edit_upload(
+        &mut self,
+        remote: &Path,
+        original: &[u8],
+        edited: &Path,
+    ) -> Result<bool, String> {
+        let edited = std::fs::read(edited)
+            .map_err(|err| format!("Could not read file \"{}\": {err}", edited.display()))?;
+        let is_binary = |content: &[u8]| {
+            content_inspector::inspect(&content[..content.len().min(BINARY_INSPECT_SIZE)])
+                .is_binary()
+        };
+        let sizes = format!(
+            "{} → {}",
+            ByteSize(original.len() as u64),
+            ByteSize(edited.len() as u64)
+        );
+        let (summary, lines) = match is_binary(original) || is_binary(&edited) {
+            true => (
+                format!("binary file, {sizes}"),
+                vec![String::from("Binary files are not compared")],
+            ),
+            false => {
+                let path = remote.display().to_string();
+                let diff = UnifiedDiff::new(
+                    &String::from_utf8_lossy(original),
+                    &String::from_utf8_lossy(&edited),
+                    &format!("a{path}"),
+                    &format!("b{path}"),
+                );
+                let summary = format!("+{} -{} lines, {sizes}", diff.added(), diff.removed());
+                match diff.is_empty() {
+                    true => (
+                        summary,
+                        vec![String::from("Only the line endings have changed")],
+                    ),
+                    false => (summary, diff.lines().to_vec()),
+                }
+            }
+        };
+        self.mount_edit_diff(
+            format!("Upload changes ({summary})? <ENTER> to upload, <ESC> to discard"),
+            &lines,
+        );
+        let answer = self.wait_for_pending_msg(&[
+            Msg::PendingAction(PendingActionMsg::UploadEditedFile),
+            Msg::PendingAction(PendingActionMsg::CloseEditDiffPopup),
+        ]);
+        self.umount_edit_diff();
+        Ok(answer == Msg::PendingAction(PendingActionMsg::UploadEditedFile))
+    }
 }
@@ -4,13 +4,16 @@
 
 // locals
 use super::{FileTransferActivity, LogLevel};
+use crate::utils::shell::{self, DEFAULT_REMOTE_SHELL};
 
 impl FileTransferActivity {
-    pub(crate) fn action_local_exec(&mut self, input: String) {
+    /// Execute command on the host bridge. Returns the output of the command, if it has been executed
+    pub(crate) fn action_local_exec(&mut self, input: String) -> Option<String> {
         match self.host_bridge.exec(input.as_str()) {
             Ok(output) => {
                 // Reload files
                 self.log(LogLevel::Info, format!("\"{input}\": {output}"));
+                Some(output)
             }
             Err(err) => {
                 // Report err
@@ -18,18 +21,34 @@ impl FileTransferActivity {
                     LogLevel::Error,
                     format!("Could not execute command \"{input}\": {err}"),
                 );
+                None
             }
         }
     }
 
-    pub(crate) fn action_remote_exec(&mut self, input: String) {
-        match self.client.as_mut().exec(input.as_str()) {
+    /// Execute command on remote host.
+    /// Unless `raw` is set, the command is wrapped into the remote shell configured for the host.
+    /// Returns the exitcode and the output of the command, if it has been executed
+    pub(crate) fn action_remote_exec(&mut self, input: String, raw: bool) -> Option<(u32, String)> {
+        let cmd = match raw {
+            true => input.clone(),
+            false => shell::wrap_command(
+                self.context()
+                    .remote_params()
+                    .and_then(|params| params.remote_shell.as_deref())
+                    .unwrap_or(DEFAULT_REMOTE_SHELL),
+                &input,
+            ),
+        };
+        debug!("executing remote command: {cmd}");
+        match self.client.as_mut().exec(cmd.as_str()) {
             Ok((rc, output)) => {
                 // Reload files
                 self.log(
                     LogLevel::Info,
                     format!("\"{input}\" (exitcode: {rc}): {output}"),
                 );
+                Some((rc, output))
             }
             Err(err) => {
                 // Report err
@@ -37,6 +56,7 @@ impl FileTransferActivity {
                     LogLevel::Error,
                     format!("Could not execute command \"{input}\": {err}"),
                 );
+                None
             }
         }
     }
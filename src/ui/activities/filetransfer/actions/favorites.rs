@@ -0,0 +1,150 @@
+//! ## FileTransferActivity
+//!
+//! `filetransfer_activiy` is the module which implements the Filetransfer activity, which is the main activity afterall
+
+use std::path::PathBuf;
+
+use super::{File, FileExplorerTab, FileTransferActivity, LogLevel, TransferPayload};
+
+impl FileTransferActivity {
+    /// Load the favorites of the bookmark the remote params have been loaded from, if any
+    pub(crate) fn load_favorites(&mut self) {
+        let favorites = match (
+            self.context().remote_bookmark(),
+            self.context().bookmarks_client(),
+        ) {
+            (Some(name), Some(client)) => client.get_favorites(name).to_vec(),
+            _ => return,
+        };
+        self.favorites = favorites;
+    }
+
+    /// Pin the selected remote entries as favorites, or unpin them if they're favorites already
+    pub(crate) fn action_toggle_favorite(&mut self) {
+        let entries = self.get_remote_selected_entries().get_files();
+        if entries.is_empty() {
+            return;
+        }
+        for entry in entries {
+            let path = entry.path().to_path_buf();
+            match self.favorites.iter().position(|x| x == &path) {
+                Some(idx) => {
+                    self.favorites.remove(idx);
+                    self.log(
+                        LogLevel::Info,
+                        format!("Removed \"{}\" from favorites", path.display()),
+                    );
+                }
+                None => {
+                    self.log(
+                        LogLevel::Info,
+                        format!("Added \"{}\" to favorites", path.display()),
+                    );
+                    self.favorites.push(path);
+                }
+            }
+        }
+        self.save_favorites();
+    }
+
+    /// Show the favorites. The ones which don't exist anymore on the remote are flagged, but kept
+    pub(crate) fn action_show_favorites(&mut self) {
+        if self.favorites.is_empty() {
+            self.mount_info("There are no favorites; press <*> to pin the selected remote file");
+            return;
+        }
+        let favorites: Vec<(PathBuf, bool)> = self
+            .favorites
+            .clone()
+            .into_iter()
+            .map(|x| {
+                let exists = self.remote_file_exists(x.as_path());
+                (x, exists)
+            })
+            .collect();
+        self.mount_favorites(&favorites);
+    }
+
+    /// Go to the favorite at `idx`. If it is a file, its parent directory is entered and the file name is returned,
+    /// so that the file can be highlighted once the file list has been reloaded
+    pub(crate) fn action_go_to_favorite(&mut self, idx: usize) -> Option<String> {
+        let entry = self.favorite_entry(idx)?;
+        let (dir, file) = match entry.path().parent() {
+            Some(parent) if !entry.is_dir() => (parent.to_path_buf(), Some(entry.name())),
+            _ => (entry.path().to_path_buf(), None),
+        };
+        self.action_change_remote_dir(dir.to_string_lossy().to_string());
+        file
+    }
+
+    /// Open the favorite at `idx`
+    pub(crate) fn action_open_favorite(&mut self, idx: usize) {
+        if let Some(entry) = self.favorite_entry(idx) {
+            self.action_open_remote_file(&entry, None);
+        }
+    }
+
+    /// Download the favorite at `idx` to the working directory of the host bridge
+    pub(crate) fn action_download_favorite(&mut self, idx: usize) {
+        let Some(entry) = self.favorite_entry(idx) else {
+            return;
+        };
+        let wrkdir = self.host_bridge().wrkdir.clone();
+        let name = entry.name();
+        if self.config().get_prompt_on_file_replace()
+            && self.host_bridge_file_exists(wrkdir.join(&name).as_path())
+            && !self.should_replace_file(name.clone())
+        {
+            return;
+        }
+        match self.filetransfer_recv(
+            TransferPayload::Any(entry),
+            wrkdir.as_path(),
+            self.transfer_opts(),
+        ) {
+            Ok(_) => self.notify_hidden_entries(FileExplorerTab::HostBridge, "Downloaded", &[name]),
+            Err(err) => {
+                self.log_and_alert(LogLevel::Error, format!("Could not download file: {err}"))
+            }
+        }
+    }
+
+    /// Unpin the favorite at `idx`
+    pub(crate) fn action_remove_favorite(&mut self, idx: usize) {
+        if idx >= self.favorites.len() {
+            return;
+        }
+        let path = self.favorites.remove(idx);
+        self.log(
+            LogLevel::Info,
+            format!("Removed \"{}\" from favorites", path.display()),
+        );
+        self.save_favorites();
+    }
+
+    /// Get the entry of the favorite at `idx` from the remote
+    fn favorite_entry(&mut self, idx: usize) -> Option<File> {
+        let path = self.favorites.get(idx)?.clone();
+        match self.client.stat(path.as_path()) {
+            Ok(entry) => Some(entry),
+            Err(err) => {
+                self.log_and_alert(
+                    LogLevel::Error,
+                    format!("Could not find favorite \"{}\": {err}", path.display()),
+                );
+                None
+            }
+        }
+    }
+
+    /// Store the favorites in the state of the bookmark the remote params have been loaded from, if any
+    fn save_favorites(&mut self) {
+        let Some(name) = self.context().remote_bookmark().map(str::to_string) else {
+            return;
+        };
+        let favorites = self.favorites.clone();
+        if let Some(client) = self.context_mut().bookmarks_client_mut() {
+            client.set_favorites(&name, favorites);
+        }
+    }
+}
@@ -1,44 +1,87 @@
-use std::str::FromStr;
-
-use regex::Regex;
 use remotefs::File;
-use wildmatch::WildMatch;
 
-use crate::ui::activities::filetransfer::lib::browser::FileExplorerTab;
+use super::LogLevel;
+use crate::explorer::filter::Filter;
+use crate::ui::activities::filetransfer::lib::browser::{FileExplorerTab, FoundExplorerTab};
 use crate::ui::activities::filetransfer::FileTransferActivity;
 
-#[derive(Clone, Debug)]
-pub enum Filter {
-    Regex(Regex),
-    Wildcard(WildMatch),
-}
-
-impl FromStr for Filter {
-    type Err = ();
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        // try as regex
-        if let Ok(regex) = Regex::new(s) {
-            Ok(Self::Regex(regex))
-        } else {
-            Ok(Self::Wildcard(WildMatch::new(s)))
+impl FileTransferActivity {
+    /// Filter the files of the current panel by name with `input`.
+    /// If `keep_filter` is set, the panel itself is filtered until the filter is cleared with an empty input,
+    /// otherwise the matching files are listed in the find explorer
+    pub(crate) fn action_filter(&mut self, input: String) {
+        let tab = self.browser.tab();
+        if input.is_empty() {
+            self.set_panel_filter(tab, None);
+            return;
+        }
+        let filter = match Filter::parse(&input, self.config().get_filter_mode()) {
+            Ok(filter) => filter,
+            Err(err) => {
+                self.log_and_alert(LogLevel::Error, err);
+                return;
+            }
+        };
+        if self.config().get_keep_filter() {
+            self.set_panel_filter(tab, Some(filter));
+            return;
         }
+        let files = self.filter(&filter);
+        // Get wrkdir
+        let wrkdir = match tab {
+            FileExplorerTab::HostBridge => self.host_bridge().wrkdir.clone(),
+            _ => self.remote().wrkdir.clone(),
+        };
+        // Create explorer and load files
+        self.browser.set_found(
+            match tab {
+                FileExplorerTab::HostBridge => FoundExplorerTab::Local,
+                _ => FoundExplorerTab::Remote,
+            },
+            files,
+            wrkdir.as_path(),
+        );
+        // Keep the filter on the results, so transfers know they're filtered
+        if let Some(found) = self.browser.found_mut() {
+            found.set_filter(Some(filter.clone()));
+        }
+        // Mount result widget
+        self.mount_find(filter.to_string(), false);
+        self.update_find_list();
+        // Move focus to the panel the results belong to
+        self.focus_tab(match tab {
+            FileExplorerTab::HostBridge => FileExplorerTab::FindHostBridge,
+            FileExplorerTab::Remote => FileExplorerTab::FindRemote,
+            _ => FileExplorerTab::FindHostBridge,
+        });
     }
-}
 
-impl Filter {
-    fn matches(&self, s: &str) -> bool {
-        debug!("matching '{s}' with {:?}", self);
-        match self {
-            Self::Regex(re) => re.is_match(s),
-            Self::Wildcard(wm) => wm.matches(s),
+    /// Set the filter kept by the panel of `tab`, or clear it
+    fn set_panel_filter(&mut self, tab: FileExplorerTab, filter: Option<Filter>) {
+        let msg = filter.as_ref().map(|x| format!("Filtering files by {x}"));
+        let explorer = match tab {
+            FileExplorerTab::HostBridge | FileExplorerTab::FindHostBridge => {
+                self.browser.host_bridge_mut()
+            }
+            FileExplorerTab::Remote | FileExplorerTab::FindRemote => self.browser.remote_mut(),
+        };
+        let cleared = filter.is_none() && explorer.filter().is_some();
+        explorer.set_filter(filter);
+        if let Some(msg) = msg {
+            self.log(LogLevel::Info, msg);
+        } else if cleared {
+            self.log(LogLevel::Info, String::from("Filter cleared"));
         }
+        self.refresh_view_options(tab);
     }
-}
 
-impl FileTransferActivity {
-    pub fn filter(&self, filter: &str) -> Vec<File> {
-        let filter = Filter::from_str(filter).unwrap();
+    /// Get the filter applied to the entries of the current panel, if any
+    pub(crate) fn active_filter(&self) -> Option<String> {
+        self.browser.explorer().filter().map(|x| x.to_string())
+    }
 
+    /// Get the files of the current panel matching `filter`
+    pub fn filter(&self, filter: &Filter) -> Vec<File> {
         match self.browser.tab() {
             FileExplorerTab::HostBridge => self.browser.host_bridge().iter_files(),
             FileExplorerTab::Remote => self.browser.remote().iter_files(),
@@ -56,11 +56,9 @@ impl FileTransferActivity {
                         // Do not replace
                         return;
                     }
-                    if let Err(err) = self.filetransfer_send(
-                        TransferPayload::Any(entry),
-                        wrkdir.as_path(),
-                        opts.save_as,
-                    ) {
+                    if let Err(err) =
+                        self.filetransfer_send(TransferPayload::Any(entry), wrkdir.as_path(), opts)
+                    {
                         self.log_and_alert(
                             LogLevel::Error,
                             format!("Could not upload file: {err}"),
@@ -78,11 +76,9 @@ impl FileTransferActivity {
                         // Do not replace
                         return;
                     }
-                    if let Err(err) = self.filetransfer_recv(
-                        TransferPayload::Any(entry),
-                        wrkdir.as_path(),
-                        opts.save_as,
-                    ) {
+                    if let Err(err) =
+                        self.filetransfer_recv(TransferPayload::Any(entry), wrkdir.as_path(), opts)
+                    {
                         self.log_and_alert(
                             LogLevel::Error,
                             format!("Could not download file: {err}"),
@@ -119,7 +115,10 @@ impl FileTransferActivity {
                         if let Err(err) = self.filetransfer_send(
                             TransferPayload::Many(entries),
                             dest_path.as_path(),
-                            None,
+                            TransferOpts::default()
+                                .concurrency(opts.concurrency)
+                                .preserve_symlinks(opts.preserve_symlinks)
+                                .preserve_metadata(opts.preserve_metadata),
                         ) {
                             {
                                 self.log_and_alert(
@@ -150,7 +149,10 @@ impl FileTransferActivity {
                         if let Err(err) = self.filetransfer_recv(
                             TransferPayload::Many(entries),
                             dest_path.as_path(),
-                            None,
+                            TransferOpts::default()
+                                .concurrency(opts.concurrency)
+                                .preserve_symlinks(opts.preserve_symlinks)
+                                .preserve_metadata(opts.preserve_metadata),
                         ) {
                             self.log_and_alert(
                                 LogLevel::Error,
@@ -175,6 +177,7 @@ impl FileTransferActivity {
                 for entry in entries.iter() {
                     // Delete file
                     self.remove_found_file(entry);
+                    self.poll_panel_wait();
                 }
             }
             SelectedFile::None => {}
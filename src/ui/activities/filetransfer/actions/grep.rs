@@ -0,0 +1,203 @@
+//! ## FileTransferActivity
+//!
+//! `filetransfer_activiy` is the module which implements the Filetransfer activity, which is the main activity afterall
+
+use std::io;
+use std::path::Path;
+
+use remotefs::RemoteErrorType;
+
+use super::walkdir::WalkdirError;
+use super::{File, FileExplorerTab, FileTransferActivity, LogLevel};
+use crate::filetransfer::FileTransferProtocol;
+use crate::ui::activities::filetransfer::lib::browser::FoundExplorerTab;
+use crate::ui::activities::filetransfer::lib::grep::{
+    self, ContentMatcher, CONTENT_SEARCH_MAX_FILE_SIZE,
+};
+use crate::ui::activities::filetransfer::lib::walkdir::WalkdirResults;
+use crate::utils::shell::{self, DEFAULT_REMOTE_SHELL};
+
+impl FileTransferActivity {
+    /// Search the files which contain `needle` in the working directory of the current explorer, and show them in the
+    /// find explorer. On SSH remotes the search is performed by `grep`; otherwise the files are read by termscp,
+    /// skipping the ones larger than [`CONTENT_SEARCH_MAX_FILE_SIZE`]
+    pub(crate) fn action_search_content(&mut self, needle: String) {
+        if needle.is_empty() {
+            return;
+        }
+        self.mount_walkdir_wait();
+        let (res, wrkdir) = match self.browser.tab() {
+            FileExplorerTab::HostBridge => (
+                self.search_host_bridge_content(&needle),
+                self.host_bridge().wrkdir.clone(),
+            ),
+            FileExplorerTab::Remote if self.remote_supports_exec() => (
+                self.search_remote_content_with_grep(&needle),
+                self.remote().wrkdir.clone(),
+            ),
+            FileExplorerTab::Remote => (
+                self.search_remote_content(&needle),
+                self.remote().wrkdir.clone(),
+            ),
+            FileExplorerTab::FindHostBridge | FileExplorerTab::FindRemote => {
+                self.umount_wait();
+                return;
+            }
+        };
+        self.umount_wait();
+        match res {
+            Err(WalkdirError::Error(err)) => {
+                self.mount_error(format!("Could not search file contents: {err}"));
+            }
+            Err(WalkdirError::Aborted) => {
+                self.mount_info("Search aborted");
+            }
+            Ok(files) if files.is_empty() => {
+                self.mount_info(format!(r#"There are no files containing "{needle}""#));
+            }
+            Ok(files) => {
+                let tab = self.browser.tab();
+                self.browser.set_found(
+                    match tab {
+                        FileExplorerTab::HostBridge => FoundExplorerTab::Local,
+                        _ => FoundExplorerTab::Remote,
+                    },
+                    files,
+                    wrkdir.as_path(),
+                );
+                self.mount_find(format!(r#"Files containing "{needle}""#), false);
+                self.update_find_list();
+                self.focus_tab(match tab {
+                    FileExplorerTab::HostBridge => FileExplorerTab::FindHostBridge,
+                    _ => FileExplorerTab::FindRemote,
+                });
+            }
+        }
+    }
+
+    /// Whether commands can be executed on the remote, which is the case of SSH remotes only
+    fn remote_supports_exec(&self) -> bool {
+        matches!(
+            self.context().remote_params().map(|x| x.protocol),
+            Some(FileTransferProtocol::Scp | FileTransferProtocol::Sftp)
+        )
+    }
+
+    /// Search `needle` in the files under the remote working directory, running `grep` on the remote host
+    fn search_remote_content_with_grep(&mut self, needle: &str) -> Result<Vec<File>, WalkdirError> {
+        let wrkdir = self.remote().wrkdir.clone();
+        let cmd = shell::wrap_command(
+            self.context()
+                .remote_params()
+                .and_then(|params| params.remote_shell.as_deref())
+                .unwrap_or(DEFAULT_REMOTE_SHELL),
+            &grep::grep_command(needle, wrkdir.as_path()),
+        );
+        debug!("searching file contents: {cmd}");
+        let (rc, output) = self
+            .client
+            .exec(cmd.as_str())
+            .map_err(|err| WalkdirError::Error(err.to_string()))?;
+        let paths = grep::parse_grep_output(&output);
+        match rc {
+            // 1 means that no line has been selected
+            0 | 1 => {}
+            _ if !paths.is_empty() => self.log(
+                LogLevel::Warn,
+                format!("grep exited with code {rc}: some files could not be searched"),
+            ),
+            _ => {
+                return Err(WalkdirError::Error(format!(
+                    "grep exited with code {rc}: {}",
+                    output.trim()
+                )))
+            }
+        }
+        let mut files = Vec::with_capacity(paths.len());
+        for (i, path) in paths.iter().enumerate() {
+            match self.client.stat(path) {
+                Ok(file) => files.push(file),
+                Err(err) => debug!("could not stat {}: {err}", path.display()),
+            }
+            self.update_content_search_progress(i + 1, files.len());
+            self.check_aborted()?;
+        }
+        Ok(files)
+    }
+
+    /// Search `needle` in the files under the remote working directory, reading them
+    fn search_remote_content(&mut self, needle: &str) -> Result<Vec<File>, WalkdirError> {
+        let entries = self.action_walkdir_remote()?;
+        self.search_content(needle, entries, |activity, path, matcher| {
+            match activity.client.open(path) {
+                Ok(mut reader) => {
+                    let result = io::copy(&mut reader, &mut matcher.clone());
+                    if let Err(err) = activity.client.on_read(reader) {
+                        debug!("could not finalize remote stream: {err}");
+                    }
+                    result.map(|_| ()).map_err(|err| err.to_string())
+                }
+                Err(err) if err.kind == RemoteErrorType::UnsupportedFeature => activity
+                    .client
+                    .open_file(path, Box::new(matcher.clone()))
+                    .map(|_| ())
+                    .map_err(|err| err.to_string()),
+                Err(err) => Err(err.to_string()),
+            }
+        })
+    }
+
+    /// Search `needle` in the files under the working directory of the host bridge, reading them
+    fn search_host_bridge_content(&mut self, needle: &str) -> Result<Vec<File>, WalkdirError> {
+        let entries = self.action_walkdir_local()?;
+        self.search_content(needle, entries, |activity, path, matcher| {
+            let mut reader = activity
+                .host_bridge
+                .open_file(path)
+                .map_err(|err| err.to_string())?;
+            io::copy(&mut reader, &mut matcher.clone())
+                .map(|_| ())
+                .map_err(|err| err.to_string())
+        })
+    }
+
+    /// Get the `entries` which contain `needle`, writing each file into a [`ContentMatcher`] with `read_fn`.
+    /// Directories and files larger than [`CONTENT_SEARCH_MAX_FILE_SIZE`] are skipped
+    fn search_content<F>(
+        &mut self,
+        needle: &str,
+        entries: WalkdirResults,
+        read_fn: F,
+    ) -> Result<Vec<File>, WalkdirError>
+    where
+        F: Fn(&mut Self, &Path, &ContentMatcher) -> Result<(), String>,
+    {
+        let files = entries
+            .into_files()
+            .map_err(|err| WalkdirError::Error(format!("Could not read search results: {err}")))?;
+        let mut found = Vec::new();
+        let mut searched = 0;
+        for file in files {
+            let file = file.map_err(|err| {
+                WalkdirError::Error(format!("Could not read search results: {err}"))
+            })?;
+            if !file.is_file() || file.metadata().size > CONTENT_SEARCH_MAX_FILE_SIZE {
+                continue;
+            }
+            let matcher = ContentMatcher::new(needle);
+            // the read fails once the matcher has found the needle
+            if let Err(err) = read_fn(self, file.path(), &matcher) {
+                if !matcher.is_match() {
+                    debug!("could not search {}: {err}", file.path().display());
+                }
+            }
+            if matcher.is_match() {
+                found.push(file);
+            }
+            searched += 1;
+            self.update_content_search_progress(searched, found.len());
+            self.check_aborted()?;
+        }
+        Ok(found)
+    }
+}
@@ -0,0 +1,95 @@
+//! ## FileTransferActivity
+//!
+//! `filetransfer_activiy` is the module which implements the Filetransfer activity, which is the main activity afterall
+
+use std::env;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use chrono::Local;
+
+use super::{FileTransferActivity, LogLevel};
+use crate::system::environment;
+
+impl FileTransferActivity {
+    /// Display the next level of the log filter
+    pub(crate) fn action_cycle_log_level_filter(&mut self) {
+        self.log_filter.level = self.log_filter.level.next();
+        self.update_logbox();
+    }
+
+    /// Show the popup to search text in the log
+    pub(crate) fn action_show_log_search_popup(&mut self) {
+        let search = self.log_filter.search().to_string();
+        self.mount_log_search(&search);
+    }
+
+    /// Search `text` in the displayed log records and move to the newest match
+    pub(crate) fn action_search_log(&mut self, text: String) {
+        self.log_filter.set_search(&text);
+        self.update_logbox();
+        self.move_logbox_to_match();
+    }
+
+    /// Move to the next older match of the log search if `older`, otherwise to the previous one
+    pub(crate) fn action_move_log_match(&mut self, older: bool) {
+        if self.log_filter.move_match(older).is_some() {
+            self.update_logbox();
+            self.move_logbox_to_match();
+        }
+    }
+
+    /// Show the popup to export the log, pre-filled with a timestamped file in the configuration directory
+    pub(crate) fn action_show_log_export_popup(&mut self) {
+        let path = Self::log_export_dir().join(format!(
+            "termscp-{}.log",
+            Local::now().format("%Y%m%d-%H%M%S")
+        ));
+        self.mount_log_export(path.as_path());
+    }
+
+    /// Export all the log records, from the oldest, to the file at `path`.
+    /// Relative paths are relative to the configuration directory
+    pub(crate) fn action_export_log(&mut self, path: String) {
+        let path = path.trim();
+        if path.is_empty() {
+            return;
+        }
+        let path = Self::log_export_dir().join(path);
+        match self.write_log_records(path.as_path()) {
+            Ok(count) => self.log(
+                LogLevel::Info,
+                format!("Exported {count} log records to \"{}\"", path.display()),
+            ),
+            Err(err) => self.log_and_alert(
+                LogLevel::Error,
+                format!("Could not export log to \"{}\": {err}", path.display()),
+            ),
+        }
+    }
+
+    /// Directory where the log is exported by default
+    fn log_export_dir() -> PathBuf {
+        environment::init_config_dir()
+            .ok()
+            .flatten()
+            .unwrap_or_else(env::temp_dir)
+    }
+
+    fn write_log_records(&self, path: &Path) -> io::Result<usize> {
+        let records: Vec<_> = self.log_records.iter().collect();
+        let mut writer = BufWriter::new(File::create(path)?);
+        for record in records.iter().rev() {
+            writeln!(
+                writer,
+                "{} [{:5}]: {}",
+                record.time.format("%Y-%m-%dT%H:%M:%S%Z"),
+                record.level.name(),
+                record.msg
+            )?;
+        }
+        writer.flush()?;
+        Ok(records.len())
+    }
+}
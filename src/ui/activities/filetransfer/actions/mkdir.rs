@@ -7,7 +7,8 @@ use std::path::PathBuf;
 
 use remotefs::fs::UnixPex;
 
-use super::{FileTransferActivity, LogLevel};
+use super::{FileExplorerTab, FileTransferActivity, LogLevel};
+use crate::ui::activities::filetransfer::lib::undo::UndoHost;
 
 impl FileTransferActivity {
     pub(crate) fn action_local_mkdir(&mut self, input: String) {
@@ -18,6 +19,15 @@ impl FileTransferActivity {
             Ok(_) => {
                 // Reload files
                 self.log(LogLevel::Info, format!("Created directory \"{input}\""));
+                self.invalidate_undo(
+                    UndoHost::HostBridge,
+                    PathBuf::from(input.as_str()).as_path(),
+                );
+                self.notify_hidden_entries(
+                    FileExplorerTab::HostBridge,
+                    "Created directory",
+                    &[input],
+                );
             }
             Err(err) => {
                 // Report err
@@ -36,6 +46,9 @@ impl FileTransferActivity {
             Ok(_) => {
                 // Reload files
                 self.log(LogLevel::Info, format!("Created directory \"{input}\""));
+                self.apply_remote_mode(PathBuf::from(input.as_str()).as_path(), true);
+                self.invalidate_undo(UndoHost::Remote, PathBuf::from(input.as_str()).as_path());
+                self.notify_hidden_entries(FileExplorerTab::Remote, "Created directory", &[input]);
             }
             Err(err) => {
                 // Report err
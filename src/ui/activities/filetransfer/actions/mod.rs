@@ -11,25 +11,41 @@ use super::{
     FileTransferActivity, Id, LogLevel, Msg, PendingActionMsg, TransferMsg, TransferOpts,
     TransferPayload, UiMsg,
 };
+use crate::explorer::special::SpecialFileType;
+use crate::filetransfer::FileTransferProtocol;
 
 // actions
+pub(crate) mod archive;
 pub(crate) mod change_dir;
+pub(crate) mod checksum;
 pub(crate) mod chmod;
 pub(crate) mod copy;
+pub(crate) mod cut;
 pub(crate) mod delete;
+pub(crate) mod dir_bookmarks;
 pub(crate) mod edit;
 pub(crate) mod exec;
+pub(crate) mod favorites;
 pub(crate) mod filter;
 pub(crate) mod find;
+pub(crate) mod grep;
+pub(crate) mod log;
 pub(crate) mod mkdir;
 pub(crate) mod newfile;
 pub(crate) mod open;
 mod pending;
+pub(crate) mod preview;
+pub(crate) mod queue;
+pub(crate) mod quick_command;
 pub(crate) mod rename;
+pub(crate) mod retry;
 pub(crate) mod save;
 pub(crate) mod scan;
 pub(crate) mod submit;
 pub(crate) mod symlink;
+pub(crate) mod sync;
+pub(crate) mod undo;
+pub(crate) mod url;
 pub(crate) mod walkdir;
 pub(crate) mod watcher;
 
@@ -141,6 +157,43 @@ impl FileTransferActivity {
         }
     }
 
+    /// Get the type of `file` if it is a special file, which can't be transferred or read.
+    /// Files on the local host are classified when selected, the others when their directory is scanned
+    pub(crate) fn special_file_type(&self, file: &File, remote: bool) -> Option<SpecialFileType> {
+        if !SpecialFileType::is_candidate(file) {
+            return None;
+        }
+        let local = match remote {
+            true => {
+                self.context().remote_params().map(|x| x.protocol)
+                    == Some(FileTransferProtocol::Localhost)
+            }
+            false => self.host_bridge.is_localhost(),
+        };
+        match (local, remote) {
+            (true, _) => SpecialFileType::classify_local(file.path()),
+            (false, true) => self.remote().special_file(file.path()),
+            (false, false) => self.host_bridge().special_file(file.path()),
+        }
+    }
+
+    /// If `file` is a special file, log and alert that it can't be `action` (e.g. "opened") and return `true`
+    pub(crate) fn refuse_special_file(&mut self, file: &File, remote: bool, action: &str) -> bool {
+        match self.special_file_type(file, remote) {
+            Some(kind) => {
+                self.log_and_alert(
+                    LogLevel::Error,
+                    format!(
+                        "\"{}\" is a {kind}: it can't be {action}",
+                        file.path().display()
+                    ),
+                );
+                true
+            }
+            None => false,
+        }
+    }
+
     // -- private
 
     fn get_selected_index(&self, id: &Id) -> SelectedFileIndex {
@@ -8,7 +8,7 @@ use std::path::PathBuf;
 
 use remotefs::fs::Metadata;
 
-use super::{File, FileTransferActivity, LogLevel};
+use super::{File, FileExplorerTab, FileTransferActivity, LogLevel};
 
 impl FileTransferActivity {
     pub(crate) fn action_local_newfile(&mut self, input: String) {
@@ -52,6 +52,7 @@ impl FileTransferActivity {
             LogLevel::Info,
             format!("Created file \"{}\"", file_path.display()),
         );
+        self.notify_hidden_entries(FileExplorerTab::HostBridge, "Created file", &[input]);
     }
 
     pub(crate) fn action_remote_newfile(&mut self, input: String) {
@@ -110,6 +111,11 @@ impl FileTransferActivity {
                                 LogLevel::Info,
                                 format!("Created file \"{}\"", file_path.display()),
                             );
+                            self.notify_hidden_entries(
+                                FileExplorerTab::Remote,
+                                "Created file",
+                                &[input],
+                            );
                         }
                     }
                 }
@@ -6,7 +6,8 @@
 // ext
 use std::path::{Path, PathBuf};
 
-use super::{File, FileTransferActivity, LogLevel, SelectedFile, TransferPayload};
+use super::{File, FileTransferActivity, LogLevel, SelectedFile, TransferOpts, TransferPayload};
+use crate::utils::io::copy_with_progress;
 
 impl FileTransferActivity {
     /// Open local file
@@ -35,6 +36,9 @@ impl FileTransferActivity {
 
     /// Perform open lopcal file
     pub(crate) fn action_open_local_file(&mut self, entry: &File, open_with: Option<&str>) {
+        if self.refuse_special_file(entry, false, "opened") {
+            return;
+        }
         if self.host_bridge.is_localhost() {
             self.open_path_with(entry.path(), open_with);
         } else {
@@ -44,7 +48,17 @@ impl FileTransferActivity {
 
     /// Open remote file. The file is first downloaded to a temporary directory on localhost
     pub(crate) fn action_open_remote_file(&mut self, entry: &File, open_with: Option<&str>) {
+        if self.refuse_special_file(entry, true, "opened") {
+            return;
+        }
         // Download file
+        let cache: PathBuf = match self.cache_path() {
+            Err(err) => {
+                self.log_and_alert(LogLevel::Error, err);
+                return;
+            }
+            Ok(p) => p,
+        };
         let tmpfile: String =
             match self.get_cache_tmp_name(&entry.name(), entry.extension().as_deref()) {
                 None => {
@@ -53,17 +67,10 @@ impl FileTransferActivity {
                 }
                 Some(p) => p,
             };
-        let cache: PathBuf = match self.cache.as_ref() {
-            None => {
-                self.log(LogLevel::Error, String::from("Could not create tempdir"));
-                return;
-            }
-            Some(p) => p.path().to_path_buf(),
-        };
         match self.filetransfer_recv(
             TransferPayload::Any(entry.clone()),
             cache.as_path(),
-            Some(tmpfile.clone()),
+            TransferOpts::default().save_as(Some(tmpfile.clone())),
         ) {
             Ok(_) => {
                 // Make file and open if file exists
@@ -110,6 +117,13 @@ impl FileTransferActivity {
 
     fn open_bridged_file(&mut self, entry: &File, open_with: Option<&str>) {
         // Download file
+        let cache: PathBuf = match self.cache_path() {
+            Err(err) => {
+                self.log_and_alert(LogLevel::Error, err);
+                return;
+            }
+            Ok(p) => p,
+        };
         let tmpfile: String =
             match self.get_cache_tmp_name(&entry.name(), entry.extension().as_deref()) {
                 None => {
@@ -118,13 +132,6 @@ impl FileTransferActivity {
                 }
                 Some(p) => p,
             };
-        let cache: PathBuf = match self.cache.as_ref() {
-            None => {
-                self.log(LogLevel::Error, String::from("Could not create tempdir"));
-                return;
-            }
-            Some(p) => p.path().to_path_buf(),
-        };
 
         let tmpfile = cache.join(tmpfile);
 
@@ -149,7 +156,8 @@ impl FileTransferActivity {
             }
         };
 
-        if let Err(err) = std::io::copy(&mut reader, &mut writer) {
+        let buffer_size = self.config().get_transfer_buffer_size();
+        if let Err(err) = copy_with_progress(&mut reader, &mut writer, buffer_size, |_| true) {
             self.log(LogLevel::Error, format!("Failed to write file: {err}"));
             return;
         }
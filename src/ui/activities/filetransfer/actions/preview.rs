@@ -0,0 +1,95 @@
+//! ## FileTransferActivity
+//!
+//! `filetransfer_activiy` is the module which implements the Filetransfer activity, which is the main activity afterall
+
+use remotefs::RemoteErrorType;
+
+use super::{File, FileTransferActivity, LogLevel, SelectedFile};
+use crate::ui::activities::filetransfer::lib::preview::{self, ChunkWriter, Preview};
+
+impl FileTransferActivity {
+    /// Preview the first chunk of the selected remote file, without downloading it
+    pub(crate) fn action_preview_remote_file(&mut self) {
+        let entry = match self.get_remote_selected_entries() {
+            SelectedFile::One(entry) => entry,
+            _ => return,
+        };
+        if entry.is_dir() {
+            self.mount_error("Cannot preview a directory");
+            return;
+        }
+        if self.refuse_special_file(&entry, true, "previewed") {
+            return;
+        }
+        let chunk = match self.read_remote_chunk(&entry, 0) {
+            Ok(chunk) => chunk,
+            Err(err) => {
+                self.log_and_alert(LogLevel::Error, format!("Could not preview file: {err}"));
+                return;
+            }
+        };
+        match Preview::new(entry, chunk) {
+            Some(preview) => {
+                self.mount_preview(&preview);
+                self.preview = Some(preview);
+            }
+            None => self.log_and_alert(
+                LogLevel::Error,
+                String::from("Could not preview file: file is binary"),
+            ),
+        }
+    }
+
+    /// Load the next chunk of the file being previewed
+    pub(crate) fn action_preview_load_more(&mut self) {
+        let Some(mut preview) = self.preview.take() else {
+            return;
+        };
+        if !preview.is_complete() {
+            match self.read_remote_chunk(preview.file(), preview.offset()) {
+                Ok(chunk) => {
+                    preview.push_chunk(chunk);
+                    self.update_preview(&preview);
+                }
+                Err(err) => self.log(
+                    LogLevel::Error,
+                    format!("Could not load more of the preview: {err}"),
+                ),
+            }
+        }
+        self.preview = Some(preview);
+    }
+
+    /// Close the preview, discarding its content
+    pub(crate) fn action_close_preview(&mut self) {
+        self.preview = None;
+        self.umount_preview();
+    }
+
+    /// Read the chunk of the remote `file` starting at `offset`.
+    /// If the protocol doesn't support streams, the file is downloaded until the chunk is full
+    fn read_remote_chunk(&mut self, file: &File, offset: u64) -> Result<Vec<u8>, String> {
+        match self.client.open(file.path()) {
+            Ok(mut reader) => {
+                let result = preview::read_chunk(&mut reader, offset).map_err(|x| x.to_string());
+                if let Err(err) = self.client.on_read(reader) {
+                    self.log(
+                        LogLevel::Warn,
+                        format!("Could not finalize remote stream: \"{err}\""),
+                    );
+                }
+                result
+            }
+            Err(err) if err.kind == RemoteErrorType::UnsupportedFeature => {
+                let writer = ChunkWriter::new(offset);
+                match self.client.open_file(file.path(), Box::new(writer.clone())) {
+                    Ok(_) => Ok(writer.take()),
+                    // the download is stopped by the writer once the chunk is full
+                    Err(_) if writer.is_full() => Ok(writer.take()),
+                    Err(err) => Err(err.to_string()),
+                }
+            }
+            Err(err) => Err(err.to_string()),
+        }
+    }
+}
@@ -0,0 +1,330 @@
+//! ## FileTransferActivity
+//!
+//! `filetransfer_activiy` is the module which implements the Filetransfer activity, which is the main activity afterall
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use chrono::Local;
+
+use super::{File, FileExplorerTab, FileTransferActivity, LogLevel, SelectedFile};
+use crate::ui::activities::filetransfer::lib::parallel::{RemoteSession, TransferDirection};
+use crate::ui::activities::filetransfer::lib::queue::{QueueEvent, QueueItemState, QueueWorker};
+use crate::ui::activities::filetransfer::lib::schedule;
+use crate::ui::activities::filetransfer::lib::unfinished::UnfinishedWork;
+
+impl FileTransferActivity {
+    /// Put the entries selected in the current explorer in the transfer queue.
+    /// They're transferred in background to the working directory of the other explorer
+    pub(crate) fn action_enqueue_transfer(&mut self) {
+        let (direction, selected, wrkdir) = match self.browser.tab() {
+            FileExplorerTab::HostBridge => (
+                TransferDirection::Upload,
+                self.get_local_selected_entries(),
+                self.remote().wrkdir.clone(),
+            ),
+            FileExplorerTab::Remote => (
+                TransferDirection::Download,
+                self.get_remote_selected_entries(),
+                self.host_bridge().wrkdir.clone(),
+            ),
+            FileExplorerTab::FindHostBridge | FileExplorerTab::FindRemote => return,
+        };
+        let entries = match selected {
+            SelectedFile::One(entry) => vec![entry],
+            SelectedFile::Many(entries) => entries,
+            SelectedFile::None => return,
+        };
+        let mut renames = HashMap::new();
+        if self.config().get_prompt_on_file_replace() {
+            // Check which file would be replaced
+            let existing: Vec<&File> = entries
+                .iter()
+                .filter(|x| {
+                    let dest = Self::file_to_check_many(x, wrkdir.as_path());
+                    match direction {
+                        TransferDirection::Upload => self.remote_file_exists(dest.as_path()),
+                        TransferDirection::Download => self.host_bridge_file_exists(dest.as_path()),
+                    }
+                })
+                .collect();
+            // A single file which already exists may be resumed, so it's transferred in foreground
+            if entries.len() == 1 && existing.len() == 1 && entries[0].is_file() {
+                match direction {
+                    TransferDirection::Upload => self.action_local_send(),
+                    TransferDirection::Download => self.action_remote_recv(),
+                }
+                return;
+            }
+            if !existing.is_empty() {
+                let dest_tab = match direction {
+                    TransferDirection::Upload => FileExplorerTab::Remote,
+                    TransferDirection::Download => FileExplorerTab::HostBridge,
+                };
+                match self
+                    .should_replace_or_rename_files(existing, Some((dest_tab, wrkdir.as_path())))
+                {
+                    Some(new_names) => renames = new_names,
+                    None => return,
+                }
+            }
+        }
+        // the entries are scanned only if the free space at the destination is known
+        let free_space = match direction {
+            TransferDirection::Upload => self.ssh_negotiation.free_space(),
+            TransferDirection::Download => self.host_bridge.free_space(wrkdir.as_path()),
+        };
+        if free_space.is_some() {
+            let Some(size) = self.scan_queued_transfer_size(direction, &entries) else {
+                return;
+            };
+            if !self.should_transfer_exceeding_free_space(size, free_space, wrkdir.as_path()) {
+                return;
+            }
+        }
+        for entry in entries {
+            let name = entry.name();
+            let dest = wrkdir.join(renames.get(&name).unwrap_or(&name));
+            self.log(
+                LogLevel::Info,
+                format!(
+                    "Queued transfer of \"{}\" to \"{}\"",
+                    entry.path().display(),
+                    dest.display()
+                ),
+            );
+            self.transfer_queue.push(direction, entry, dest);
+        }
+        if !self.transfer_queue.has_worker() {
+            self.spawn_transfer_queue_worker(direction);
+        }
+        self.poll_transfer_queue();
+    }
+
+    pub(crate) fn action_show_transfer_queue(&mut self) {
+        self.mount_transfer_queue();
+    }
+
+    /// Remove the item at `idx` from the transfer queue; if it's being transferred, its transfer is aborted
+    pub(crate) fn action_remove_queue_item(&mut self, idx: usize) {
+        match self.transfer_queue.remove(idx) {
+            Some(item) if matches!(item.state, QueueItemState::Scheduled(_)) => self.log(
+                LogLevel::Info,
+                format!(
+                    "Cancelled the scheduled transfer of \"{}\"",
+                    item.source.path().display()
+                ),
+            ),
+            Some(item) => self.log(
+                LogLevel::Info,
+                format!(
+                    "Removed transfer of \"{}\" from the queue",
+                    item.source.path().display()
+                ),
+            ),
+            None => {
+                if let Some(item) = self.transfer_queue.items().get(idx) {
+                    self.log(
+                        LogLevel::Warn,
+                        format!(
+                            "Aborting the transfer of \"{}\"…",
+                            item.source.path().display()
+                        ),
+                    );
+                }
+            }
+        }
+        self.update_transfer_queue();
+    }
+
+    /// Poll the transfer queue worker, starting the next pending transfer once the active one has terminated
+    pub(crate) fn poll_transfer_queue(&mut self) {
+        if self.transfer_queue.items().is_empty() {
+            return;
+        }
+        self.start_scheduled_transfers();
+        let active = self.transfer_queue.is_active();
+        for event in self.transfer_queue.poll() {
+            match event {
+                QueueEvent::ConnectionFailed(err) => self.log_and_alert(
+                    LogLevel::Error,
+                    format!("Could not start the queued transfers: {err}"),
+                ),
+                QueueEvent::Finished { id, result } => self.on_queue_item_finished(id, result),
+                QueueEvent::Planned { .. }
+                | QueueEvent::FileStarted { .. }
+                | QueueEvent::FileTransferred { .. }
+                | QueueEvent::FileSkipped { .. }
+                | QueueEvent::FileFailed { .. } => {}
+            }
+        }
+        self.show_queue_failures();
+        if self.transfer_queue.unfinished() > 0 && !self.transfer_queue.has_worker() {
+            if let Some(item) = self
+                .transfer_queue
+                .items()
+                .iter()
+                .find(|x| x.state == QueueItemState::Pending)
+            {
+                let direction = item.direction;
+                self.spawn_transfer_queue_worker(direction);
+            }
+        }
+        if let Some(item) = self.transfer_queue.dispatch() {
+            let msg = format!(
+                "Transferring \"{}\" to \"{}\"…",
+                item.source.path().display(),
+                item.dest.display()
+            );
+            self.log(LogLevel::Info, msg);
+        }
+        if self.transfer_queue.release_idle_worker() {
+            debug!("released the idle transfer queue worker until the scheduled transfers start");
+        }
+        if active || self.transfer_queue.is_active() {
+            if self.app.mounted(&super::Id::TransferQueuePopup) {
+                self.update_transfer_queue();
+            }
+            self.redraw = true;
+        }
+    }
+
+    /// Mark the scheduled transfers whose time has come as pending, provided that the connections are alive.
+    /// Dead connections are re-established on the next draw, then the transfers are started.
+    /// If the system was asleep when a transfer was due, it's started now and the delay is logged
+    fn start_scheduled_transfers(&mut self) {
+        let now = Local::now();
+        if !self.transfer_queue.has_due(now) {
+            return;
+        }
+        self.verify_connections();
+        if !self.remote_connected
+            || (!self.host_bridge.is_localhost() && !self.host_bridge_connected)
+        {
+            return;
+        }
+        for (id, at) in self.transfer_queue.start_due(now) {
+            let Some(item) = self.transfer_queue.get(id) else {
+                continue;
+            };
+            let source = item.source.path().display().to_string();
+            match schedule::is_late(at, now) {
+                true => self.log(
+                    LogLevel::Warn,
+                    format!(
+                        "The transfer of \"{source}\" was scheduled at {}; starting it now, since the system was probably asleep",
+                        schedule::fmt_start_time(at, now)
+                    ),
+                ),
+                false => self.log(
+                    LogLevel::Info,
+                    format!("Starting the scheduled transfer of \"{source}\""),
+                ),
+            }
+        }
+    }
+
+    /// Stop the transfer queue worker, cancelling the pending transfers
+    pub(crate) fn stop_transfer_queue(&mut self) {
+        let unfinished = self.transfer_queue.unfinished();
+        self.transfer_queue.stop();
+        if unfinished > 0 {
+            self.log(
+                LogLevel::Warn,
+                format!("{unfinished} queued transfers have been cancelled"),
+            );
+        }
+    }
+
+    /// Get the work which would be interrupted by disconnecting: the queued transfers and the pending watcher uploads
+    pub(crate) fn unfinished_work(&self) -> UnfinishedWork {
+        UnfinishedWork::new(&self.transfer_queue, self.watcher_pending)
+    }
+
+    /// Spawn the worker of the transfer queue, with its own connections to the hosts
+    fn spawn_transfer_queue_worker(&mut self, direction: TransferDirection) {
+        let host_bridge_params = self.context().host_bridge_params().unwrap().clone();
+        let remote_params = self.context().remote_params().unwrap().clone();
+        let config_client = self.config().clone();
+        let mtime_tolerance = self.clock_skew.tolerance();
+        let preserve_symlinks = self.config().get_preserve_symlinks();
+        let preserve_metadata = self.config().get_preserve_metadata();
+        // the queue may hold transfers in both directions, whose destinations may not set times alike
+        let preserve_uploads =
+            preserve_metadata && self.can_preserve_metadata(TransferDirection::Upload);
+        let preserve_downloads =
+            preserve_metadata && self.can_preserve_metadata(TransferDirection::Download);
+        let modes = self.remote_modes();
+        let worker = QueueWorker::spawn(self.transfer_queue.transfer.progress(), move || {
+            RemoteSession::connect(direction, host_bridge_params, remote_params, &config_client)
+                .map(|session| {
+                    session
+                        .mtime_tolerance(mtime_tolerance)
+                        .preserve_symlinks(preserve_symlinks)
+                        .preserve_metadata_of(TransferDirection::Upload, preserve_uploads)
+                        .preserve_metadata_of(TransferDirection::Download, preserve_downloads)
+                        .modes(modes)
+                })
+        });
+        self.transfer_queue.set_worker(worker);
+    }
+
+    /// List the entries of the queued items which couldn't be transferred in the failed transfers popup, from which
+    /// they can be retried, once the queue has been drained and no other failure is being shown
+    pub(crate) fn show_queue_failures(&mut self) {
+        if self.failed_transfers.is_some() {
+            return;
+        }
+        let Some(failed) = self.transfer_queue.take_failures() else {
+            return;
+        };
+        self.log(
+            LogLevel::Warn,
+            format!(
+                "{} queued entries could not be transferred; first error: {}",
+                failed.failures.len(),
+                failed.failures[0].error
+            ),
+        );
+        self.mount_failed_transfers(&failed.failures);
+        self.failed_transfers = Some(failed);
+    }
+
+    fn on_queue_item_finished(&mut self, id: usize, result: Result<(), String>) {
+        let Some(item) = self.transfer_queue.get(id) else {
+            return;
+        };
+        let source = item.source.path().display().to_string();
+        let dest: PathBuf = item.dest.clone();
+        let direction = item.direction;
+        self.notify_queue_item_finished(
+            &item.source.name(),
+            &self.transfer_queue.transfer,
+            &result,
+        );
+        match result {
+            Ok(()) => self.log(
+                LogLevel::Info,
+                format!("Transferred \"{source}\" to \"{}\"", dest.display()),
+            ),
+            Err(err) => self.log_and_alert(
+                LogLevel::Error,
+                format!("Could not transfer \"{source}\": {err}"),
+            ),
+        }
+        // Reload the destination explorer, if it's showing the destination directory
+        match direction {
+            TransferDirection::Upload if dest.parent() == Some(self.remote().wrkdir.as_path()) => {
+                self.reload_remote_dir();
+                self.update_remote_filelist();
+            }
+            TransferDirection::Download
+                if dest.parent() == Some(self.host_bridge().wrkdir.as_path()) =>
+            {
+                self.reload_host_bridge_dir();
+                self.update_host_bridge_filelist();
+            }
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,56 @@
+//! ## FileTransferActivity
+//!
+//! `filetransfer_activiy` is the module which implements the Filetransfer activity, which is the main activity afterall
+
+use std::path::Path;
+
+use super::{FileTransferActivity, LogLevel};
+use crate::filetransfer::params::QuickCommand;
+
+impl FileTransferActivity {
+    /// Show the quick commands menu of the current host
+    pub(crate) fn action_show_quick_commands(&mut self) {
+        let commands = self.quick_commands();
+        if commands.is_empty() {
+            self.mount_info("No quick commands are defined for this bookmark");
+            return;
+        }
+        self.mount_quick_commands(&commands);
+    }
+
+    /// Run the quick command at `idx` on the remote host, showing its output in the exec output popup.
+    /// The `{selected_path}` placeholder is replaced with the entries selected in the remote explorer
+    pub(crate) fn action_quick_command(&mut self, idx: usize) {
+        self.umount_quick_commands();
+        let Some(command) = self.quick_commands().into_iter().nth(idx) else {
+            return;
+        };
+        let selected = self.get_remote_selected_entries().get_files();
+        if command.uses_selection() && selected.is_empty() {
+            self.log_and_alert(
+                LogLevel::Error,
+                format!(
+                    "Quick command \"{}\" requires a selected remote entry",
+                    command.label
+                ),
+            );
+            return;
+        }
+        let selected: Vec<&Path> = selected.iter().map(|x| x.path()).collect();
+        let cmd = command.render(&selected, self.remote().wrkdir.as_path());
+        self.mount_blocking_wait(format!("Executing '{}'…", command.label));
+        let output = self.action_remote_exec(cmd.clone(), false);
+        self.umount_wait();
+        if let Some((rc, output)) = output {
+            self.mount_exec_output(&cmd, Some(rc), &output);
+        }
+    }
+
+    /// Get the quick commands defined for the remote host
+    fn quick_commands(&self) -> Vec<QuickCommand> {
+        self.context()
+            .remote_params()
+            .map(|params| params.quick_commands.clone())
+            .unwrap_or_default()
+    }
+}
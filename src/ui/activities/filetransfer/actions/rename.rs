@@ -8,13 +8,16 @@ use std::path::{Path, PathBuf};
 use remotefs::RemoteErrorType;
 
 use super::{File, FileTransferActivity, LogLevel, SelectedFile};
+use crate::ui::activities::filetransfer::lib::undo::UndoHost;
 
 impl FileTransferActivity {
     pub(crate) fn action_local_rename(&mut self, input: String) {
         match self.get_local_selected_entries() {
             SelectedFile::One(entry) => {
                 let dest_path: PathBuf = PathBuf::from(input);
-                self.local_rename_file(&entry, dest_path.as_path());
+                if self.local_rename_file(&entry, dest_path.as_path()) {
+                    self.push_undo_move(UndoHost::HostBridge, entry.path(), dest_path.as_path());
+                }
             }
             SelectedFile::Many(entries) => {
                 // Try to copy each file to Input/{FILE_NAME}
@@ -23,7 +26,14 @@ impl FileTransferActivity {
                 for entry in entries.iter() {
                     let mut dest_path: PathBuf = base_path.clone();
                     dest_path.push(entry.name());
-                    self.local_rename_file(entry, dest_path.as_path());
+                    if self.local_rename_file(entry, dest_path.as_path()) {
+                        self.push_undo_move(
+                            UndoHost::HostBridge,
+                            entry.path(),
+                            dest_path.as_path(),
+                        );
+                    }
+                    self.poll_panel_wait();
                 }
             }
             SelectedFile::None => {}
@@ -34,7 +44,9 @@ impl FileTransferActivity {
         match self.get_remote_selected_entries() {
             SelectedFile::One(entry) => {
                 let dest_path: PathBuf = PathBuf::from(input);
-                self.remote_rename_file(&entry, dest_path.as_path());
+                if self.remote_rename_file(&entry, dest_path.as_path()) {
+                    self.push_undo_move(UndoHost::Remote, entry.path(), dest_path.as_path());
+                }
             }
             SelectedFile::Many(entries) => {
                 // Try to copy each file to Input/{FILE_NAME}
@@ -43,14 +55,18 @@ impl FileTransferActivity {
                 for entry in entries.iter() {
                     let mut dest_path: PathBuf = base_path.clone();
                     dest_path.push(entry.name());
-                    self.remote_rename_file(entry, dest_path.as_path());
+                    if self.remote_rename_file(entry, dest_path.as_path()) {
+                        self.push_undo_move(UndoHost::Remote, entry.path(), dest_path.as_path());
+                    }
+                    self.poll_panel_wait();
                 }
             }
             SelectedFile::None => {}
         }
     }
 
-    fn local_rename_file(&mut self, entry: &File, dest: &Path) {
+    /// Move `entry` to `dest` on the host bridge. Returns whether it has been moved
+    pub(crate) fn local_rename_file(&mut self, entry: &File, dest: &Path) -> bool {
         match self.host_bridge.rename(entry, dest) {
             Ok(_) => {
                 self.log(
@@ -61,20 +77,25 @@ impl FileTransferActivity {
                         dest.display()
                     ),
                 );
+                true
+            }
+            Err(err) => {
+                self.log_and_alert(
+                    LogLevel::Error,
+                    format!(
+                        "Could not move \"{}\" to \"{}\": {}",
+                        entry.path().display(),
+                        dest.display(),
+                        err
+                    ),
+                );
+                false
             }
-            Err(err) => self.log_and_alert(
-                LogLevel::Error,
-                format!(
-                    "Could not move \"{}\" to \"{}\": {}",
-                    entry.path().display(),
-                    dest.display(),
-                    err
-                ),
-            ),
         }
     }
 
-    pub(crate) fn remote_rename_file(&mut self, entry: &File, dest: &Path) {
+    /// Move `entry` to `dest` on the remote. Returns whether it has been moved
+    pub(crate) fn remote_rename_file(&mut self, entry: &File, dest: &Path) -> bool {
         match self.client.as_mut().mov(entry.path(), dest) {
             Ok(_) => {
                 self.log(
@@ -85,25 +106,29 @@ impl FileTransferActivity {
                         dest.display()
                     ),
                 );
+                true
             }
             Err(err) if err.kind == RemoteErrorType::UnsupportedFeature => {
-                self.tricky_move(entry, dest);
+                self.tricky_move(entry, dest)
+            }
+            Err(err) => {
+                self.log_and_alert(
+                    LogLevel::Error,
+                    format!(
+                        "Could not move \"{}\" to \"{}\": {}",
+                        entry.path().display(),
+                        dest.display(),
+                        err
+                    ),
+                );
+                false
             }
-            Err(err) => self.log_and_alert(
-                LogLevel::Error,
-                format!(
-                    "Could not move \"{}\" to \"{}\": {}",
-                    entry.path().display(),
-                    dest.display(),
-                    err
-                ),
-            ),
         }
     }
 
     /// Tricky move will be used whenever copy command is not available on remote host.
     /// It basically uses the tricky_copy function, then it just deletes the previous entry (`entry`)
-    fn tricky_move(&mut self, entry: &File, dest: &Path) {
+    fn tricky_move(&mut self, entry: &File, dest: &Path) -> bool {
         debug!(
             "Using tricky-move to move entry {} to {}",
             entry.path().display(),
@@ -113,26 +138,33 @@ impl FileTransferActivity {
             // Delete remote existing entry
             debug!("Tricky-copy worked; removing existing remote entry");
             match self.client.remove_dir_all(entry.path()) {
-                Ok(_) => self.log(
-                    LogLevel::Info,
-                    format!(
-                        "Moved \"{}\" to \"{}\"",
-                        entry.path().display(),
-                        dest.display()
-                    ),
-                ),
-                Err(err) => self.log_and_alert(
-                    LogLevel::Error,
-                    format!(
-                        "Copied \"{}\" to \"{}\"; but failed to remove src: {}",
-                        entry.path().display(),
-                        dest.display(),
-                        err
-                    ),
-                ),
+                Ok(_) => {
+                    self.log(
+                        LogLevel::Info,
+                        format!(
+                            "Moved \"{}\" to \"{}\"",
+                            entry.path().display(),
+                            dest.display()
+                        ),
+                    );
+                    true
+                }
+                Err(err) => {
+                    self.log_and_alert(
+                        LogLevel::Error,
+                        format!(
+                            "Copied \"{}\" to \"{}\"; but failed to remove src: {}",
+                            entry.path().display(),
+                            dest.display(),
+                            err
+                        ),
+                    );
+                    false
+                }
             }
         } else {
             error!("Tricky move aborted due to tricky-copy failure");
+            false
         }
     }
 }
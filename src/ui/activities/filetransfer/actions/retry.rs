@@ -0,0 +1,24 @@
+//! ## FileTransferActivity
+//!
+//! `filetransfer_activiy` is the module which implements the Filetransfer activity, which is the main activity afterall
+
+use super::{FileTransferActivity, LogLevel};
+use crate::ui::activities::filetransfer::lib::parallel::TransferDirection;
+
+impl FileTransferActivity {
+    /// Transfer again the entries which couldn't be transferred by the last transfer
+    pub(crate) fn action_retry_failed_transfers(&mut self) {
+        self.umount_failed_transfers();
+        let Some(failed) = self.failed_transfers.take() else {
+            return;
+        };
+        let verb = match failed.direction {
+            TransferDirection::Upload => "upload",
+            TransferDirection::Download => "download",
+        };
+        if let Err(err) = self.filetransfer_retry(failed) {
+            self.log_and_alert(LogLevel::Error, format!("Could not {verb} file: {err}"));
+        }
+        self.show_queue_failures();
+    }
+}
@@ -3,63 +3,135 @@
 //! `filetransfer_activiy` is the module which implements the Filetransfer activity, which is the main activity afterall
 
 // locals
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 
+use bytesize::ByteSize;
+use chrono::{DateTime, Local};
+use tuirealm::{State, StateValue};
+
 use super::{
-    File, FileTransferActivity, LogLevel, Msg, PendingActionMsg, SelectedFile, TransferOpts,
-    TransferPayload,
+    File, FileExplorerTab, FileTransferActivity, Id, LogLevel, Msg, PendingActionMsg, SelectedFile,
+    TransferOpts, TransferPayload,
 };
+use crate::filetransfer::HostBridgeParams;
+use crate::ui::activities::filetransfer::lib::schedule;
+use crate::ui::activities::filetransfer::lib::transfer::{self, TransferEstimate};
+
+/// Answer of the user to the transfer confirmation popup
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum TransferConfirmation {
+    Transfer,
+    /// Start the transfer later, at the provided time
+    Schedule(DateTime<Local>),
+    Cancel,
+}
+
+/// Answer of the user to the replace popup
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum ReplaceAnswer {
+    Replace,
+    Resume,
+    /// Save the transferred file under the provided name
+    Rename(String),
+    Skip,
+}
 
 impl FileTransferActivity {
     pub(crate) fn action_local_saveas(&mut self, input: String) {
-        self.local_send_file(TransferOpts::default().save_as(Some(input)));
+        self.local_send_file(self.transfer_opts().save_as(Some(input)));
     }
 
     pub(crate) fn action_remote_saveas(&mut self, input: String) {
-        self.remote_recv_file(TransferOpts::default().save_as(Some(input)));
+        self.remote_recv_file(self.transfer_opts().save_as(Some(input)));
     }
 
     pub(crate) fn action_local_send(&mut self) {
-        self.local_send_file(TransferOpts::default());
+        self.local_send_file(self.transfer_opts());
     }
 
     pub(crate) fn action_remote_recv(&mut self) {
-        self.remote_recv_file(TransferOpts::default());
+        self.remote_recv_file(self.transfer_opts());
     }
 
-    fn local_send_file(&mut self, opts: TransferOpts) {
+    /// Get the options for a transfer started by the user
+    pub(crate) fn transfer_opts(&self) -> TransferOpts {
+        TransferOpts::default()
+            .concurrency(self.transfer_concurrency())
+            .preserve_symlinks(self.config().get_preserve_symlinks())
+            .preserve_metadata(self.config().get_preserve_metadata())
+    }
+
+    /// Get the amount of files to transfer in parallel: the configured `transfer_concurrency`,
+    /// capped to the connections allowed by the protocols of both hosts
+    fn transfer_concurrency(&self) -> usize {
+        let concurrency = self.config().get_transfer_concurrency();
+        let Some(context) = self.context.as_ref() else {
+            return concurrency;
+        };
+        let remote_cap = context
+            .remote_params()
+            .map(|params| params.protocol.max_transfer_concurrency())
+            .unwrap_or(concurrency);
+        let host_bridge_cap = match context.host_bridge_params() {
+            Some(HostBridgeParams::Remote(protocol, _)) => protocol.max_transfer_concurrency(),
+            Some(HostBridgeParams::Localhost(_)) | None => concurrency,
+        };
+        concurrency.min(remote_cap).min(host_bridge_cap)
+    }
+
+    fn local_send_file(&mut self, mut opts: TransferOpts) {
         let wrkdir: PathBuf = self.remote().wrkdir.clone();
         match self.get_local_selected_entries() {
             SelectedFile::One(entry) => {
                 let file_to_check = Self::file_to_check(&entry, opts.save_as.as_ref());
+                let mut name = opts.save_as.clone().unwrap_or_else(|| entry.name());
+                let mut resume = false;
                 if self.config().get_prompt_on_file_replace()
                     && self.remote_file_exists(file_to_check.as_path())
-                    && !self
-                        .should_replace_file(opts.save_as.clone().unwrap_or_else(|| entry.name()))
                 {
-                    // Do not replace
-                    return;
-                }
-                if let Err(err) = self.filetransfer_send(
-                    TransferPayload::Any(entry),
-                    wrkdir.as_path(),
-                    opts.save_as,
-                ) {
-                    {
-                        self.log_and_alert(
-                            LogLevel::Error,
-                            format!("Could not upload file: {err}"),
-                        );
+                    let resumable = self
+                        .client
+                        .stat(wrkdir.join(&file_to_check).as_path())
+                        .is_ok_and(|dest| Self::is_partial_copy(&entry, &dest));
+                    let suggestion =
+                        self.suggest_name(FileExplorerTab::Remote, wrkdir.as_path(), &name, &[]);
+                    match self.should_replace_or_resume_file(
+                        name.clone(),
+                        resumable,
+                        Some(suggestion),
+                    ) {
+                        ReplaceAnswer::Replace => {}
+                        ReplaceAnswer::Resume => resume = true,
+                        ReplaceAnswer::Rename(new_name) => {
+                            opts = opts.save_as(Some(new_name.as_str()));
+                            name = new_name;
+                        }
+                        // Do not replace
+                        ReplaceAnswer::Skip => return,
+                    }
+                }
+                let payload = match resume {
+                    true => TransferPayload::Resume(entry),
+                    false => TransferPayload::Any(entry),
+                };
+                match self.filetransfer_send(payload, wrkdir.as_path(), opts) {
+                    Ok(_) => {
+                        self.notify_hidden_entries(FileExplorerTab::Remote, "Uploaded", &[name])
+                    }
+                    Err(err) => {
+                        self.log_and_alert(LogLevel::Error, format!("Could not upload file: {err}"))
                     }
                 }
             }
             SelectedFile::Many(entries) => {
                 // In case of selection: save multiple files in wrkdir/input
                 let mut dest_path: PathBuf = wrkdir;
-                if let Some(save_as) = opts.save_as {
+                if let Some(save_as) = opts.save_as.as_ref() {
                     dest_path.push(save_as);
                 }
                 // Iter files
+                let mut renames = HashMap::new();
                 if self.config().get_prompt_on_file_replace() {
                     // Check which file would be replaced
                     let existing_files: Vec<&File> = entries
@@ -70,21 +142,51 @@ impl FileTransferActivity {
                             )
                         })
                         .collect();
-                    // Check whether to replace files
-                    if !existing_files.is_empty() && !self.should_replace_files(existing_files) {
-                        return;
+                    // Check whether to replace or rename files
+                    if !existing_files.is_empty() {
+                        match self.should_replace_or_rename_files(
+                            existing_files,
+                            Some((FileExplorerTab::Remote, dest_path.as_path())),
+                        ) {
+                            Some(new_names) => renames = new_names,
+                            None => return,
+                        }
                     }
                 }
-                if let Err(err) = self.filetransfer_send(
-                    TransferPayload::Many(entries),
-                    dest_path.as_path(),
-                    None,
-                ) {
-                    {
-                        self.log_and_alert(
-                            LogLevel::Error,
-                            format!("Could not upload file: {err}"),
-                        );
+                // Entries created in the working directory
+                let names: Vec<String> = match opts.save_as.as_ref() {
+                    Some(save_as) => vec![save_as.clone()],
+                    None => entries
+                        .iter()
+                        .map(|x| renames.get(&x.name()).cloned().unwrap_or_else(|| x.name()))
+                        .collect(),
+                };
+                // renamed entries are transferred one by one, once the others have been transferred
+                let (entries, renamed) = transfer::split_renamed(entries, &renames);
+                let result = match entries.is_empty() {
+                    true => Ok(()),
+                    false => self.filetransfer_send(
+                        TransferPayload::Many(entries),
+                        dest_path.as_path(),
+                        TransferOpts::default()
+                            .concurrency(opts.concurrency)
+                            .preserve_symlinks(opts.preserve_symlinks)
+                            .preserve_metadata(opts.preserve_metadata),
+                    ),
+                };
+                match result.and_then(|_| {
+                    self.transfer_renamed(
+                        renamed,
+                        dest_path.as_path(),
+                        &opts,
+                        FileExplorerTab::Remote,
+                    )
+                }) {
+                    Ok(_) => {
+                        self.notify_hidden_entries(FileExplorerTab::Remote, "Uploaded", &names)
+                    }
+                    Err(err) => {
+                        self.log_and_alert(LogLevel::Error, format!("Could not upload file: {err}"))
                     }
                 }
             }
@@ -92,38 +194,63 @@ impl FileTransferActivity {
         }
     }
 
-    fn remote_recv_file(&mut self, opts: TransferOpts) {
+    fn remote_recv_file(&mut self, mut opts: TransferOpts) {
         let wrkdir: PathBuf = self.host_bridge().wrkdir.clone();
         match self.get_remote_selected_entries() {
             SelectedFile::One(entry) => {
                 let file_to_check = Self::file_to_check(&entry, opts.save_as.as_ref());
+                let mut name = opts.save_as.clone().unwrap_or_else(|| entry.name());
+                let mut resume = false;
                 if self.config().get_prompt_on_file_replace()
                     && self.host_bridge_file_exists(file_to_check.as_path())
-                    && !self
-                        .should_replace_file(opts.save_as.clone().unwrap_or_else(|| entry.name()))
                 {
-                    return;
-                }
-                if let Err(err) = self.filetransfer_recv(
-                    TransferPayload::Any(entry),
-                    wrkdir.as_path(),
-                    opts.save_as,
-                ) {
-                    {
-                        self.log_and_alert(
-                            LogLevel::Error,
-                            format!("Could not download file: {err}"),
-                        );
+                    let resumable = self
+                        .host_bridge
+                        .stat(wrkdir.join(&file_to_check).as_path())
+                        .is_ok_and(|dest| Self::is_partial_copy(&entry, &dest));
+                    let suggestion = self.suggest_name(
+                        FileExplorerTab::HostBridge,
+                        wrkdir.as_path(),
+                        &name,
+                        &[],
+                    );
+                    match self.should_replace_or_resume_file(
+                        name.clone(),
+                        resumable,
+                        Some(suggestion),
+                    ) {
+                        ReplaceAnswer::Replace => {}
+                        ReplaceAnswer::Resume => resume = true,
+                        ReplaceAnswer::Rename(new_name) => {
+                            opts = opts.save_as(Some(new_name.as_str()));
+                            name = new_name;
+                        }
+                        // Do not replace
+                        ReplaceAnswer::Skip => return,
                     }
                 }
+                let payload = match resume {
+                    true => TransferPayload::Resume(entry),
+                    false => TransferPayload::Any(entry),
+                };
+                match self.filetransfer_recv(payload, wrkdir.as_path(), opts) {
+                    Ok(_) => self.notify_hidden_entries(
+                        FileExplorerTab::HostBridge,
+                        "Downloaded",
+                        &[name],
+                    ),
+                    Err(err) => self
+                        .log_and_alert(LogLevel::Error, format!("Could not download file: {err}")),
+                }
             }
             SelectedFile::Many(entries) => {
                 // In case of selection: save multiple files in wrkdir/input
                 let mut dest_path: PathBuf = wrkdir;
-                if let Some(save_as) = opts.save_as {
+                if let Some(save_as) = opts.save_as.as_ref() {
                     dest_path.push(save_as);
                 }
                 // Iter files
+                let mut renames = HashMap::new();
                 if self.config().get_prompt_on_file_replace() {
                     // Check which file would be replaced
                     let existing_files: Vec<&File> = entries
@@ -134,22 +261,53 @@ impl FileTransferActivity {
                             )
                         })
                         .collect();
-                    // Check whether to replace files
-                    if !existing_files.is_empty() && !self.should_replace_files(existing_files) {
-                        return;
+                    // Check whether to replace or rename files
+                    if !existing_files.is_empty() {
+                        match self.should_replace_or_rename_files(
+                            existing_files,
+                            Some((FileExplorerTab::HostBridge, dest_path.as_path())),
+                        ) {
+                            Some(new_names) => renames = new_names,
+                            None => return,
+                        }
                     }
                 }
-                if let Err(err) = self.filetransfer_recv(
-                    TransferPayload::Many(entries),
-                    dest_path.as_path(),
-                    None,
-                ) {
-                    {
-                        self.log_and_alert(
-                            LogLevel::Error,
-                            format!("Could not download file: {err}"),
-                        );
-                    }
+                // Entries created in the working directory
+                let names: Vec<String> = match opts.save_as.as_ref() {
+                    Some(save_as) => vec![save_as.clone()],
+                    None => entries
+                        .iter()
+                        .map(|x| renames.get(&x.name()).cloned().unwrap_or_else(|| x.name()))
+                        .collect(),
+                };
+                // renamed entries are transferred one by one, once the others have been transferred
+                let (entries, renamed) = transfer::split_renamed(entries, &renames);
+                let result = match entries.is_empty() {
+                    true => Ok(()),
+                    false => self.filetransfer_recv(
+                        TransferPayload::Many(entries),
+                        dest_path.as_path(),
+                        TransferOpts::default()
+                            .concurrency(opts.concurrency)
+                            .preserve_symlinks(opts.preserve_symlinks)
+                            .preserve_metadata(opts.preserve_metadata),
+                    ),
+                };
+                match result.and_then(|_| {
+                    self.transfer_renamed(
+                        renamed,
+                        dest_path.as_path(),
+                        &opts,
+                        FileExplorerTab::HostBridge,
+                    )
+                }) {
+                    Ok(_) => self.notify_hidden_entries(
+                        FileExplorerTab::HostBridge,
+                        "Downloaded",
+                        &names,
+                    ),
+                    Err(err) => self
+                        .log_and_alert(LogLevel::Error, format!("Could not download file: {err}")),
                 }
             }
             SelectedFile::None => {}
@@ -158,44 +316,334 @@ impl FileTransferActivity {
 
     /// Set pending transfer into storage
     pub(crate) fn should_replace_file(&mut self, file_name: String) -> bool {
-        self.mount_radio_replace(&file_name);
+        self.should_replace_or_resume_file(file_name, false, None) == ReplaceAnswer::Replace
+    }
+
+    /// Ask the user whether to replace the existing file `file_name` or, if `resumable`, to resume the transfer.
+    /// If a `rename` suggestion is provided, the user may also save the file under a different name, starting from it
+    pub(crate) fn should_replace_or_resume_file(
+        &mut self,
+        file_name: String,
+        resumable: bool,
+        rename: Option<String>,
+    ) -> ReplaceAnswer {
+        self.mount_radio_replace(&file_name, resumable, rename.is_some());
         // Wait for answer
         trace!("Asking user whether he wants to replace file {}", file_name);
-        if self.wait_for_pending_msg(&[
+        let answer = self.wait_for_pending_msg(&[
             Msg::PendingAction(PendingActionMsg::CloseReplacePopups),
+            Msg::PendingAction(PendingActionMsg::RenamePendingFile),
+            Msg::PendingAction(PendingActionMsg::ResumePendingFile),
             Msg::PendingAction(PendingActionMsg::TransferPendingFile),
-        ]) == Msg::PendingAction(PendingActionMsg::TransferPendingFile)
+        ]);
+        self.umount_radio_replace();
+        match (answer, rename) {
+            (Msg::PendingAction(PendingActionMsg::TransferPendingFile), _) => {
+                trace!("User wants to replace file");
+                ReplaceAnswer::Replace
+            }
+            (Msg::PendingAction(PendingActionMsg::ResumePendingFile), _) => {
+                trace!("User wants to resume file transfer");
+                ReplaceAnswer::Resume
+            }
+            (Msg::PendingAction(PendingActionMsg::RenamePendingFile), Some(suggestion)) => {
+                match self.should_rename_file(&file_name, &suggestion) {
+                    Some(name) => ReplaceAnswer::Rename(name),
+                    None => ReplaceAnswer::Skip,
+                }
+            }
+            _ => {
+                trace!("The user doesn't want replace file");
+                ReplaceAnswer::Skip
+            }
+        }
+    }
+
+    /// Ask the user the name to save `file_name` as, pre-filling the input with `suggestion`.
+    /// Returns `None` if the user doesn't want to transfer the file anymore
+    pub(super) fn should_rename_file(
+        &mut self,
+        file_name: &str,
+        suggestion: &str,
+    ) -> Option<String> {
+        self.mount_replace_rename(file_name, suggestion);
+        trace!("Asking user the name to save file {} as", file_name);
+        let answer = self.wait_for_pending_msg(&[
+            Msg::PendingAction(PendingActionMsg::CloseReplaceRenamePopup),
+            Msg::PendingAction(PendingActionMsg::SubmitPendingRename),
+        ]);
+        let name = match self.app.state(&Id::ReplaceRenamePopup) {
+            Ok(State::One(StateValue::String(name))) => name.trim().to_string(),
+            _ => String::new(),
+        };
+        self.umount_replace_rename();
+        match answer == Msg::PendingAction(PendingActionMsg::SubmitPendingRename)
+            && !name.is_empty()
         {
-            trace!("User wants to replace file");
-            self.umount_radio_replace();
-            true
-        } else {
-            trace!("The user doesn't want replace file");
-            self.umount_radio_replace();
-            false
+            true => {
+                trace!("User wants to save file {} as {}", file_name, name);
+                Some(name)
+            }
+            false => {
+                trace!("The user doesn't want to rename file");
+                None
+            }
         }
     }
 
+    /// Suggest a name for `name`, which doesn't collide with the files in `dir` on the host of `tab`,
+    /// nor with the names already `taken` by other renamed files
+    pub(super) fn suggest_name(
+        &mut self,
+        tab: FileExplorerTab,
+        dir: &Path,
+        name: &str,
+        taken: &[String],
+    ) -> String {
+        transfer::collision_name(name, |candidate| {
+            let path = dir.join(candidate);
+            taken.iter().any(|x| x == candidate)
+                || match tab {
+                    FileExplorerTab::Remote | FileExplorerTab::FindRemote => {
+                        self.remote_file_exists(path.as_path())
+                    }
+                    FileExplorerTab::HostBridge | FileExplorerTab::FindHostBridge => {
+                        self.host_bridge_file_exists(path.as_path())
+                    }
+                }
+        })
+    }
+
+    /// Transfer the `renamed` entries of a payload to `dest`, on the host of `tab`, each under its new name
+    fn transfer_renamed(
+        &mut self,
+        renamed: Vec<(File, String)>,
+        dest: &Path,
+        opts: &TransferOpts,
+        tab: FileExplorerTab,
+    ) -> Result<(), String> {
+        for (entry, name) in renamed {
+            if self.transfer.aborted() {
+                break;
+            }
+            let opts = TransferOpts::default()
+                .preserve_symlinks(opts.preserve_symlinks)
+                .preserve_metadata(opts.preserve_metadata)
+                .save_as(Some(name));
+            match tab {
+                FileExplorerTab::Remote | FileExplorerTab::FindRemote => {
+                    self.filetransfer_send(TransferPayload::Any(entry), dest, opts)?
+                }
+                FileExplorerTab::HostBridge | FileExplorerTab::FindHostBridge => {
+                    self.filetransfer_recv(TransferPayload::Any(entry), dest, opts)?
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns whether `dest` may be a partial copy of `src`, left by an interrupted transfer (i.e. it's a smaller file)
+    fn is_partial_copy(src: &File, dest: &File) -> bool {
+        src.is_file()
+            && dest.is_file()
+            && dest.metadata().size > 0
+            && dest.metadata().size < src.metadata().size
+    }
+
     /// Set pending transfer for many files into storage and mount radio
     pub(crate) fn should_replace_files(&mut self, files: Vec<&File>) -> bool {
+        self.should_replace_or_rename_files(files, None).is_some()
+    }
+
+    /// Ask the user whether to replace the existing `files` or, if the host and the directory the files are transferred
+    /// to are provided as `dest`, to save each of them under a different name.
+    /// Returns `None` if the files mustn't be transferred, otherwise the new names of the renamed files, by file name
+    pub(crate) fn should_replace_or_rename_files(
+        &mut self,
+        files: Vec<&File>,
+        dest: Option<(FileExplorerTab, &Path)>,
+    ) -> Option<HashMap<String, String>> {
         let file_names: Vec<String> = files.iter().map(|x| x.name()).collect();
-        self.mount_radio_replace_many(file_names.as_slice());
+        let filter = self.active_filter();
+        self.mount_radio_replace_many(file_names.as_slice(), filter.as_deref(), dest.is_some());
         // Wait for answer
         trace!(
             "Asking user whether he wants to replace files {:?}",
             file_names
         );
-        if self.wait_for_pending_msg(&[
+        let answer = self.wait_for_pending_msg(&[
             Msg::PendingAction(PendingActionMsg::CloseReplacePopups),
+            Msg::PendingAction(PendingActionMsg::RenamePendingFile),
             Msg::PendingAction(PendingActionMsg::TransferPendingFile),
-        ]) == Msg::PendingAction(PendingActionMsg::TransferPendingFile)
+        ]);
+        self.umount_radio_replace();
+        match (answer, dest) {
+            (Msg::PendingAction(PendingActionMsg::TransferPendingFile), _) => {
+                trace!("User wants to replace files");
+                Some(HashMap::new())
+            }
+            (Msg::PendingAction(PendingActionMsg::RenamePendingFile), Some((tab, dir))) => {
+                let mut renames = HashMap::new();
+                for name in file_names {
+                    let taken: Vec<String> = renames.values().cloned().collect();
+                    let suggestion = self.suggest_name(tab, dir, &name, &taken);
+                    let new_name = self.should_rename_file(&name, &suggestion)?;
+                    renames.insert(name, new_name);
+                }
+                Some(renames)
+            }
+            _ => {
+                trace!("The user doesn't want replace file");
+                None
+            }
+        }
+    }
+
+    /// Mount the transfer confirmation popup and ask the user whether to proceed with a transfer of the estimated size
+    /// to the resolved destination directory `dest`. If `schedulable`, the user may schedule the transfer for later
+    pub(crate) fn should_transfer_payload(
+        &mut self,
+        estimate: &TransferEstimate,
+        dest: &Path,
+        schedulable: bool,
+    ) -> TransferConfirmation {
+        trace!(
+            "Asking user whether he wants to transfer {} to {}",
+            estimate,
+            dest.display()
+        );
+        let title = match self.active_filter() {
+            Some(filter) => format!(
+                "This transfer to \"{}\" contains {estimate}, filtered by {filter}. Do you want to continue?",
+                dest.display()
+            ),
+            None => format!(
+                "This transfer to \"{}\" contains {estimate}. Do you want to continue?",
+                dest.display()
+            ),
+        };
+        self.mount_transfer_confirm_popup(&title, schedulable);
+        let answer = self.wait_for_pending_msg(&[
+            Msg::PendingAction(PendingActionMsg::CloseTransferConfirmPopup),
+            Msg::PendingAction(PendingActionMsg::SchedulePendingPayload),
+            Msg::PendingAction(PendingActionMsg::TransferPendingPayload),
+        ]);
+        self.umount_transfer_confirm_popup();
+        match answer {
+            Msg::PendingAction(PendingActionMsg::TransferPendingPayload) => {
+                trace!("User wants to transfer payload");
+                TransferConfirmation::Transfer
+            }
+            Msg::PendingAction(PendingActionMsg::SchedulePendingPayload) => {
+                match self.ask_transfer_start_time() {
+                    Some(at) => TransferConfirmation::Schedule(at),
+                    None => TransferConfirmation::Cancel,
+                }
+            }
+            _ => {
+                trace!("The user doesn't want to transfer payload");
+                TransferConfirmation::Cancel
+            }
+        }
+    }
+
+    /// Ask the user whether to transfer `size` bytes to `dest`, if they exceed the `free_space` there.
+    /// The transfer is always allowed if the free space is unknown
+    pub(crate) fn should_transfer_exceeding_free_space(
+        &mut self,
+        size: u64,
+        free_space: Option<u64>,
+        dest: &Path,
+    ) -> bool {
+        let Some(free_space) = free_space.filter(|x| size > *x) else {
+            return true;
+        };
+        warn!(
+            "transfer of {} to {} exceeds the free space ({})",
+            ByteSize(size),
+            dest.display(),
+            ByteSize(free_space)
+        );
+        let title = format!(
+            "This transfer of {} exceeds the free space at \"{}\" ({}). Do you want to continue?",
+            ByteSize(size),
+            dest.display(),
+            ByteSize(free_space)
+        );
+        self.mount_transfer_confirm_popup(&title, false);
+        let answer = self.wait_for_pending_msg(&[
+            Msg::PendingAction(PendingActionMsg::CloseTransferConfirmPopup),
+            Msg::PendingAction(PendingActionMsg::TransferPendingPayload),
+        ]);
+        self.umount_transfer_confirm_popup();
+        if answer == Msg::PendingAction(PendingActionMsg::TransferPendingPayload) {
+            true
+        } else {
+            self.log(
+                LogLevel::Info,
+                format!(
+                    "Transfer to \"{}\" cancelled by the user, since it exceeds the free space",
+                    dest.display()
+                ),
+            );
+            false
+        }
+    }
+
+    /// Ask the user when to start a scheduled transfer, until a valid time is given.
+    /// Returns `None` if the user closed the popup
+    fn ask_transfer_start_time(&mut self) -> Option<DateTime<Local>> {
+        let mut error = None;
+        loop {
+            self.mount_schedule_transfer_popup(error.as_deref());
+            let answer = self.wait_for_pending_msg(&[
+                Msg::PendingAction(PendingActionMsg::CloseScheduleTransferPopup),
+                Msg::PendingAction(PendingActionMsg::SubmitPendingSchedule),
+            ]);
+            let input = match self.app.state(&Id::ScheduleTransferPopup) {
+                Ok(State::One(StateValue::String(input))) => input,
+                _ => String::new(),
+            };
+            self.umount_schedule_transfer_popup();
+            if answer != Msg::PendingAction(PendingActionMsg::SubmitPendingSchedule) {
+                trace!("The user doesn't want to schedule the transfer");
+                return None;
+            }
+            match schedule::parse_start_time(&input, Local::now()) {
+                Ok(at) => return Some(at),
+                Err(err) => error = Some(err),
+            }
+        }
+    }
+
+    /// Mount the transfer confirmation popup and ask the user whether to mirror the selected entries to `dest`,
+    /// deleting the entries of the destination directories which don't exist in the source
+    pub(crate) fn should_mirror(&mut self, dest: &Path) -> bool {
+        trace!(
+            "Asking user whether he wants to mirror to {}",
+            dest.display()
+        );
+        self.should_confirm_transfer(&format!(
+            "Entries under \"{}\" which don't exist in the source will be deleted. Do you want to continue?",
+            dest.display()
+        ))
+    }
+
+    /// Mount the transfer confirmation popup with `title` and wait for the answer of the user
+    fn should_confirm_transfer(&mut self, title: &str) -> bool {
+        self.mount_transfer_confirm_popup(title, false);
+        // Wait for answer
+        if self.wait_for_pending_msg(&[
+            Msg::PendingAction(PendingActionMsg::CloseTransferConfirmPopup),
+            Msg::PendingAction(PendingActionMsg::TransferPendingPayload),
+        ]) == Msg::PendingAction(PendingActionMsg::TransferPendingPayload)
         {
-            trace!("User wants to replace files");
-            self.umount_radio_replace();
+            trace!("User wants to transfer payload");
+            self.umount_transfer_confirm_popup();
             true
         } else {
-            trace!("The user doesn't want replace file");
-            self.umount_radio_replace();
+            trace!("The user doesn't want to transfer payload");
+            self.umount_transfer_confirm_popup();
             false
         }
     }
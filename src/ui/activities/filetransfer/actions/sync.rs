@@ -0,0 +1,49 @@
+//! ## FileTransferActivity
+//!
+//! `filetransfer_activiy` is the module which implements the Filetransfer activity, which is the main activity afterall
+
+use super::{FileExplorerTab, FileTransferActivity, LogLevel, SelectedFile, TransferPayload};
+use crate::ui::activities::filetransfer::lib::sync::SyncMode;
+
+impl FileTransferActivity {
+    /// Transfer the selected entries to the working directory of the other explorer in sync `mode`:
+    /// only new and changed files are copied and, when mirroring, the entries of the destination directories which
+    /// don't exist in the source are deleted
+    pub(crate) fn action_sync_transfer(&mut self, mode: SyncMode) {
+        let tab = self.browser.tab();
+        let (selected, wrkdir) = match tab {
+            FileExplorerTab::HostBridge => (
+                self.get_local_selected_entries(),
+                self.remote().wrkdir.clone(),
+            ),
+            FileExplorerTab::Remote => (
+                self.get_remote_selected_entries(),
+                self.host_bridge().wrkdir.clone(),
+            ),
+            FileExplorerTab::FindHostBridge | FileExplorerTab::FindRemote => return,
+        };
+        let (payload, dest, has_dirs) = match selected {
+            SelectedFile::One(entry) => {
+                let dest = wrkdir.join(entry.name());
+                let is_dir = entry.is_dir();
+                (TransferPayload::Any(entry), dest, is_dir)
+            }
+            SelectedFile::Many(entries) => {
+                let has_dirs = entries.iter().any(|x| x.is_dir());
+                (TransferPayload::Many(entries), wrkdir.clone(), has_dirs)
+            }
+            SelectedFile::None => return,
+        };
+        if mode == SyncMode::Mirror && has_dirs && !self.should_mirror(dest.as_path()) {
+            return;
+        }
+        let opts = self.transfer_opts().sync(mode);
+        let result = match tab {
+            FileExplorerTab::HostBridge => self.filetransfer_send(payload, wrkdir.as_path(), opts),
+            _ => self.filetransfer_recv(payload, wrkdir.as_path(), opts),
+        };
+        if let Err(err) = result {
+            self.log_and_alert(LogLevel::Error, format!("Could not sync files: {err}"));
+        }
+    }
+}
@@ -0,0 +1,79 @@
+//! ## FileTransferActivity
+//!
+//! `filetransfer_activiy` is the module which implements the Filetransfer activity, which is the main activity afterall
+
+use std::path::Path;
+
+use super::{FileTransferActivity, LogLevel};
+use crate::ui::activities::filetransfer::lib::undo::UndoHost;
+use crate::utils::path;
+
+impl FileTransferActivity {
+    /// Revert the most recent rename or move, moving the entry back to its original path
+    pub(crate) fn action_undo(&mut self) {
+        let Some(op) = self.undo.pop() else {
+            self.mount_info("There is nothing to undo");
+            return;
+        };
+        // the move is reversible only if the entry is still at its destination and its source is free
+        let (entry, src_exists) = match op.host {
+            UndoHost::HostBridge => (
+                self.host_bridge.stat(op.dest.as_path()).ok(),
+                self.host_bridge_file_exists(op.src.as_path()),
+            ),
+            UndoHost::Remote => (
+                self.client.stat(op.dest.as_path()).ok(),
+                self.remote_file_exists(op.src.as_path()),
+            ),
+        };
+        let entry = match entry {
+            Some(entry) if !src_exists => entry,
+            _ => {
+                self.log_and_alert(
+                    LogLevel::Warn,
+                    format!(
+                        "Could not undo the move of \"{}\" to \"{}\": the entries have changed since",
+                        op.src.display(),
+                        op.dest.display()
+                    ),
+                );
+                return;
+            }
+        };
+        let undone = match op.host {
+            UndoHost::HostBridge => self.local_rename_file(&entry, op.src.as_path()),
+            UndoHost::Remote => self.remote_rename_file(&entry, op.src.as_path()),
+        };
+        if undone {
+            self.log(
+                LogLevel::Info,
+                format!(
+                    "Undone the move of \"{}\" to \"{}\"",
+                    op.src.display(),
+                    op.dest.display()
+                ),
+            );
+        }
+    }
+
+    /// Push the move of `src` to `dest` on `host` on the undo stack
+    pub(crate) fn push_undo_move(&mut self, host: UndoHost, src: &Path, dest: &Path) {
+        let dest = self.undo_path(host, dest);
+        self.undo.push(host, src, dest.as_path());
+    }
+
+    /// Invalidate the moves on `host` which can't be undone anymore, since `path` has been changed
+    pub(crate) fn invalidate_undo(&mut self, host: UndoHost, path: &Path) {
+        let path = self.undo_path(host, path);
+        self.undo.invalidate(host, path.as_path());
+    }
+
+    /// Absolutize `p` with the working directory of the explorer of `host`
+    fn undo_path(&self, host: UndoHost, p: &Path) -> std::path::PathBuf {
+        let wrkdir = match host {
+            UndoHost::HostBridge => self.host_bridge().wrkdir.as_path(),
+            UndoHost::Remote => self.remote().wrkdir.as_path(),
+        };
+        path::absolutize(wrkdir, p)
+    }
+}
@@ -0,0 +1,77 @@
+//! ## FileTransferActivity
+//!
+//! `filetransfer_activiy` is the module which implements the Filetransfer activity, which is the main activity afterall
+
+use super::{FileTransferActivity, LogLevel};
+use crate::filetransfer::params::UrlMapping;
+use crate::utils::clipboard;
+
+impl FileTransferActivity {
+    /// Open the URLs of the selected remote entries in the browser, as set by the url mappings of the bookmark
+    pub(crate) fn action_open_remote_url(&mut self) {
+        for url in self.remote_urls() {
+            match open::that(url.as_str()) {
+                Ok(()) => self.log(LogLevel::Info, format!("Opened \"{url}\"")),
+                Err(err) => {
+                    self.log_and_alert(LogLevel::Error, format!("Could not open \"{url}\": {err}"))
+                }
+            }
+        }
+    }
+
+    /// Copy the URLs of the selected remote entries to the clipboard, one per line
+    pub(crate) fn action_copy_remote_url(&mut self) {
+        let urls = self.remote_urls();
+        if urls.is_empty() {
+            return;
+        }
+        let text = urls.join("\n");
+        match clipboard::write_text(&text) {
+            Ok(()) => self.log(LogLevel::Info, format!("Copied \"{text}\" to clipboard")),
+            Err(err) => self.log_and_alert(
+                LogLevel::Error,
+                format!("Could not write to clipboard: {err}"),
+            ),
+        }
+    }
+
+    /// Get the URLs of the selected remote entries.
+    /// Tells the user if the bookmark has no mappings or if the entries are not served by any of them
+    fn remote_urls(&mut self) -> Vec<String> {
+        let mappings: Vec<UrlMapping> = self
+            .context()
+            .remote_params()
+            .map(|params| params.url_mappings.clone())
+            .unwrap_or_default();
+        if mappings.is_empty() {
+            self.mount_info(
+                "No URL mappings are defined for this bookmark; set them with the `url_mappings` key in bookmarks.toml",
+            );
+            return Vec::new();
+        }
+        let mut urls = Vec::new();
+        let mut unmapped = Vec::new();
+        for entry in self.get_remote_selected_entries().get_files() {
+            match UrlMapping::resolve(&mappings, entry.path(), entry.is_dir()) {
+                Some(url) => urls.push(url),
+                None => unmapped.push(entry.path().display().to_string()),
+            }
+        }
+        if !unmapped.is_empty() {
+            let served: Vec<String> = mappings
+                .iter()
+                .map(|x| format!("\"{}\"", x.path.display()))
+                .collect();
+            let msg = format!(
+                "\"{}\" is not served by any URL mapping of this bookmark (mapped directories: {})",
+                unmapped.join("\", \""),
+                served.join(", ")
+            );
+            match urls.is_empty() {
+                true => self.mount_info(msg),
+                false => self.log(LogLevel::Warn, msg),
+            }
+        }
+        urls
+    }
+}
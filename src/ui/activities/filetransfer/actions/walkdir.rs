@@ -3,10 +3,11 @@
 //! `filetransfer_activiy` is the module which implements the Filetransfer activity, which is the main activity afterall
 
 // locals
-use std::path::{Path, PathBuf};
+use std::path::Path;
 
 use super::{File, FileTransferActivity};
-use crate::ui::activities::filetransfer::lib::walkdir::WalkdirStates;
+use crate::ui::activities::filetransfer::lib::visited::VisitedDirs;
+use crate::ui::activities::filetransfer::lib::walkdir::{WalkdirResults, WalkdirStates};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum WalkdirError {
@@ -15,15 +16,16 @@ pub enum WalkdirError {
 }
 
 impl FileTransferActivity {
-    pub(crate) fn action_walkdir_local(&mut self) -> Result<Vec<File>, WalkdirError> {
-        let mut acc = Vec::with_capacity(32_768);
+    pub(crate) fn action_walkdir_local(&mut self) -> Result<WalkdirResults, WalkdirError> {
+        let mut acc = self.walkdir_results();
 
         let pwd = self
             .host_bridge
             .pwd()
             .map_err(|e| WalkdirError::Error(e.to_string()))?;
 
-        self.walkdir(&mut acc, &pwd, |activity, path| {
+        let mut visited = self.host_bridge_visited_dirs();
+        self.walkdir(&mut acc, &pwd, &mut visited, |activity, path| {
             activity
                 .host_bridge
                 .list_dir(path)
@@ -33,25 +35,29 @@ impl FileTransferActivity {
         Ok(acc)
     }
 
-    pub(crate) fn action_walkdir_remote(&mut self) -> Result<Vec<File>, WalkdirError> {
-        let mut acc = Vec::with_capacity(32_768);
+    pub(crate) fn action_walkdir_remote(&mut self) -> Result<WalkdirResults, WalkdirError> {
+        let mut acc = self.walkdir_results();
 
         let pwd = self
             .client
             .pwd()
             .map_err(|e| WalkdirError::Error(e.to_string()))?;
 
-        self.walkdir(&mut acc, &pwd, |activity, path| {
-            activity.client.list_dir(path).map_err(|e| e.to_string())
-        })?;
+        self.walkdir(
+            &mut acc,
+            &pwd,
+            &mut VisitedDirs::remote(),
+            |activity, path| activity.client.list_dir(path).map_err(|e| e.to_string()),
+        )?;
 
         Ok(acc)
     }
 
     fn walkdir<F>(
         &mut self,
-        acc: &mut Vec<File>,
+        acc: &mut WalkdirResults,
         path: &Path,
+        visited: &mut VisitedDirs,
         list_dir_fn: F,
     ) -> Result<(), WalkdirError>
     where
@@ -69,11 +75,13 @@ impl FileTransferActivity {
         let dirs = dir_entries
             .iter()
             .filter(|entry| entry.is_dir())
-            .map(|entry| entry.path.clone())
-            .collect::<Vec<PathBuf>>();
+            .cloned()
+            .collect::<Vec<File>>();
 
         // extend acc
-        acc.extend(dir_entries.clone());
+        acc.extend(dir_entries).map_err(|err| {
+            WalkdirError::Error(format!("Could not write search results to cache: {err}"))
+        })?;
         // update view
         self.update_walkdir_entries(acc.len());
 
@@ -81,13 +89,19 @@ impl FileTransferActivity {
         self.check_aborted()?;
 
         for dir in dirs {
-            self.walkdir(acc, &dir, list_dir_fn)?;
+            // don't descend into symbolic link loops
+            if !visited.enter(&dir) {
+                self.log_symlink_loop(&dir);
+                continue;
+            }
+            self.walkdir(acc, dir.path(), visited, list_dir_fn)?;
+            visited.leave(dir.path());
         }
 
         Ok(())
     }
 
-    fn check_aborted(&mut self) -> Result<(), WalkdirError> {
+    pub(crate) fn check_aborted(&mut self) -> Result<(), WalkdirError> {
         // read events
         self.tick();
 
@@ -102,4 +116,12 @@ impl FileTransferActivity {
     fn init_walkdir(&mut self) {
         self.walkdir = WalkdirStates::default();
     }
+
+    /// Results of a walkdir, spilled to the cache directory beyond `find_spill_threshold`
+    fn walkdir_results(&self) -> WalkdirResults {
+        WalkdirResults::new(
+            self.config().get_find_spill_threshold(),
+            self.cache.as_ref().map(|x| x.path()),
+        )
+    }
 }
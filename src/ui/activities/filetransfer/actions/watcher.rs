@@ -4,32 +4,41 @@
 
 use std::path::{Path, PathBuf};
 
-use super::{FileTransferActivity, LogLevel, Msg, SelectedFile, TransferMsg, UiMsg};
+use super::{
+    FileExplorerTab, FileTransferActivity, LogLevel, Msg, SelectedFile, TransferMsg, UiMsg,
+};
+use crate::system::watcher::{IgnorePatterns, WatchDirection};
 
 impl FileTransferActivity {
     pub fn action_show_radio_watch(&mut self) {
         // return if fswatcher is not working
-        if self.fswatcher.is_none() {
+        if self.fswatcher.is_none() && self.remote_poller.is_none() {
             return;
         }
-        // get local entry
-        if let Some((watched, local, remote)) = self.get_watcher_dirs() {
-            self.mount_radio_watch(
-                watched,
-                local.to_string_lossy().to_string().as_str(),
-                remote.to_string_lossy().to_string().as_str(),
-            );
+        // get selected entry
+        match self.get_watcher_dirs() {
+            Some((_, watched, source, destination)) => {
+                self.mount_radio_watch(
+                    watched,
+                    source.to_string_lossy().to_string().as_str(),
+                    destination.to_string_lossy().to_string().as_str(),
+                );
+            }
+            None if matches!(self.browser.tab(), FileExplorerTab::Remote)
+                && self.remote_poller.is_some() =>
+            {
+                self.mount_error("Only a directory can be watched on the remote host");
+            }
+            None => {}
         }
     }
 
     pub fn action_show_watched_paths_list(&mut self) {
         // return if fswatcher is not working
-        if self.fswatcher.is_none() {
+        if self.fswatcher.is_none() && self.remote_poller.is_none() {
             return;
         }
-        let watched_paths: Vec<PathBuf> = self
-            .map_on_fswatcher(|w| w.watched_paths().iter().map(|p| p.to_path_buf()).collect())
-            .unwrap_or_default();
+        let watched_paths = self.watched_paths();
         self.mount_watched_paths_list(watched_paths.as_slice());
     }
 
@@ -37,28 +46,43 @@ impl FileTransferActivity {
         // umount radio
         self.umount_radio_watcher();
         // return if fswatcher is not working
-        if self.fswatcher.is_none() {
+        if self.fswatcher.is_none() && self.remote_poller.is_none() {
             return;
         }
         match self.get_watcher_dirs() {
-            Some((true, local, _)) => self.unwatch_path(&local),
-            Some((false, local, remote)) => self.watch_path(&local, &remote),
+            Some((direction, true, source, _)) => self.unwatch_path(direction, &source),
+            // ask for the files to ignore, besides the configured ones
+            Some((_, false, _, _)) => {
+                let ignored = self.config().get_watcher_ignore();
+                self.mount_watch_ignore(ignored.patterns());
+            }
             None => {}
         }
     }
 
+    /// Watch the selected path, ignoring the files matching the comma separated `patterns`, besides the configured ones
+    pub fn action_watch(&mut self, patterns: String) {
+        self.umount_watch_ignore();
+        // return if fswatcher is not working
+        if self.fswatcher.is_none() && self.remote_poller.is_none() {
+            return;
+        }
+        if let Some((direction, false, source, destination)) = self.get_watcher_dirs() {
+            let mut ignore = self.config().get_watcher_ignore();
+            ignore.extend(IgnorePatterns::parse(&patterns).patterns());
+            self.watch_path(direction, &source, &destination, ignore);
+        }
+    }
+
     pub fn action_toggle_watch_for(&mut self, index: usize) {
         // umount
         self.umount_watched_paths_list();
         // return if fswatcher is not working
-        if self.fswatcher.is_none() {
+        if self.fswatcher.is_none() && self.remote_poller.is_none() {
             return;
         }
         // get path
-        if let Some(path) = self
-            .map_on_fswatcher(|w| w.watched_paths().get(index).map(|p| p.to_path_buf()))
-            .flatten()
-        {
+        if let Some((path, _, direction)) = self.watched_paths().get(index).cloned() {
             // ask whether to unwatch
             self.mount_radio_watch(true, path.to_string_lossy().to_string().as_str(), "");
             // wait for response
@@ -67,43 +91,91 @@ impl FileTransferActivity {
                 Msg::Transfer(TransferMsg::ToggleWatch),
             ]) {
                 // unwatch path
-                self.unwatch_path(&path);
+                self.unwatch_path(direction, &path);
             }
             self.umount_radio_watcher();
         }
         self.action_show_watched_paths_list();
     }
 
-    fn watch_path(&mut self, local: &Path, remote: &Path) {
+    /// Get the watched paths, with the amount of changes ignored and the direction of the synchronization;
+    /// the host paths come first
+    fn watched_paths(&mut self) -> Vec<(PathBuf, usize, WatchDirection)> {
+        let mut paths: Vec<(PathBuf, usize, WatchDirection)> = self
+            .map_on_fswatcher(|w| {
+                w.watched_paths()
+                    .iter()
+                    .map(|p| (p.to_path_buf(), w.ignored(p), WatchDirection::Upload))
+                    .collect()
+            })
+            .unwrap_or_default();
+        if let Some(poller) = self.remote_poller.as_ref() {
+            let mut remote: Vec<(PathBuf, usize, WatchDirection)> = poller
+                .watched_paths()
+                .iter()
+                .map(|p| (p.to_path_buf(), poller.ignored(p), WatchDirection::Download))
+                .collect();
+            remote.sort_by(|a, b| a.0.cmp(&b.0));
+            paths.extend(remote);
+        }
+        paths
+    }
+
+    /// Synchronize the changes from `source` to `destination`, in the given `direction`
+    fn watch_path(
+        &mut self,
+        direction: WatchDirection,
+        source: &Path,
+        destination: &Path,
+        ignore: IgnorePatterns,
+    ) {
         debug!(
             "tracking changes at {} to {}",
-            local.display(),
-            remote.display()
+            source.display(),
+            destination.display()
         );
-        match self.map_on_fswatcher(|w| w.watch(local, remote)) {
+        let ignored = match ignore.is_empty() {
+            true => String::new(),
+            false => format!(", except for {}", ignore.patterns().join(", ")),
+        };
+        let result = match direction {
+            WatchDirection::Upload => {
+                self.map_on_fswatcher(|w| w.watch(source, destination, ignore))
+            }
+            WatchDirection::Download => self
+                .remote_poller
+                .as_mut()
+                .map(|w| w.watch(source, destination, ignore)),
+        };
+        match result {
             Some(Ok(())) => {
                 self.log(
                     LogLevel::Info,
                     format!(
-                        "changes to {} will now be synched with {}",
-                        local.display(),
-                        remote.display()
+                        "changes to {} will now be synched with {}{}",
+                        source.display(),
+                        destination.display(),
+                        ignored
                     ),
                 );
             }
             Some(Err(err)) => {
                 self.log_and_alert(
                     LogLevel::Error,
-                    format!("could not track changes to {}: {}", local.display(), err),
+                    format!("could not track changes to {}: {}", source.display(), err),
                 );
             }
             None => {}
         }
     }
 
-    fn unwatch_path(&mut self, path: &Path) {
+    fn unwatch_path(&mut self, direction: WatchDirection, path: &Path) {
         debug!("unwatching path at {}", path.display());
-        match self.map_on_fswatcher(|w| w.unwatch(path)) {
+        let result = match direction {
+            WatchDirection::Upload => self.map_on_fswatcher(|w| w.unwatch(path)),
+            WatchDirection::Download => self.remote_poller.as_mut().map(|w| w.unwatch(path)),
+        };
+        match result {
             Some(Ok(path)) => {
                 self.log(
                     LogLevel::Info,
@@ -117,18 +189,43 @@ impl FileTransferActivity {
         }
     }
 
-    fn get_watcher_dirs(&mut self) -> Option<(bool, PathBuf, PathBuf)> {
-        if let SelectedFile::One(file) = self.get_local_selected_entries() {
-            // check if entry is already watched
-            let watched = self
-                .map_on_fswatcher(|w| w.watched(file.path()))
-                .unwrap_or(false);
-            // mount dialog
-            let mut remote = self.remote().wrkdir.clone();
-            remote.push(file.name().as_str());
-            Some((watched, file.path().to_path_buf(), remote))
-        } else {
-            None
+    /// Get the direction of the synchronization of the selected entry, whether it's watched, its path and the path
+    /// its changes are synched with.
+    /// Host entries are uploaded, while remote directories are downloaded, if the protocol supports it
+    fn get_watcher_dirs(&mut self) -> Option<(WatchDirection, bool, PathBuf, PathBuf)> {
+        match self.browser.tab() {
+            FileExplorerTab::HostBridge => {
+                if let SelectedFile::One(file) = self.get_local_selected_entries() {
+                    // check if entry is already watched
+                    let watched = self.map_on_fswatcher(|w| w.watched(file.path()))?;
+                    // mount dialog
+                    let mut remote = self.remote().wrkdir.clone();
+                    remote.push(file.name().as_str());
+                    Some((
+                        WatchDirection::Upload,
+                        watched,
+                        file.path().to_path_buf(),
+                        remote,
+                    ))
+                } else {
+                    None
+                }
+            }
+            FileExplorerTab::Remote => match self.get_remote_selected_entries() {
+                SelectedFile::One(file) if file.is_dir() => {
+                    let watched = self.remote_poller.as_ref()?.watched(file.path());
+                    let mut local = self.host_bridge().wrkdir.clone();
+                    local.push(file.name().as_str());
+                    Some((
+                        WatchDirection::Download,
+                        watched,
+                        file.path().to_path_buf(),
+                        local,
+                    ))
+                }
+                _ => None,
+            },
+            FileExplorerTab::FindHostBridge | FileExplorerTab::FindRemote => None,
         }
     }
 }
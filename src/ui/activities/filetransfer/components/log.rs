@@ -10,6 +10,9 @@ use tuirealm::{Component, Event, MockComponent, NoUserEvent, Props, State, State
 
 use super::{Msg, UiMsg};
 
+/// Attribute to move the list index to the given row
+pub const LOG_PROP_LIST_INDEX: &str = "list_index";
+
 pub struct Log {
     props: Props,
     states: OwnStates,
@@ -46,6 +49,13 @@ impl MockComponent for Log {
             .props
             .get_or(Attribute::Background, AttrValue::Color(Color::Reset))
             .unwrap_color();
+        let title = self
+            .props
+            .get_or(
+                Attribute::Title,
+                AttrValue::Title((String::from("Log"), Alignment::Left)),
+            )
+            .unwrap_title();
         // Make list
         let list_items: Vec<ListItem> = self
             .props
@@ -58,7 +68,7 @@ impl MockComponent for Log {
         let w = TuiList::new(list_items)
             .block(tui_realm_stdlib::utils::get_block(
                 borders,
-                Some(("Log".to_string(), Alignment::Left)),
+                Some(title),
                 focus,
                 None,
             ))
@@ -76,6 +86,10 @@ impl MockComponent for Log {
     }
 
     fn attr(&mut self, attr: Attribute, value: AttrValue) {
+        if let (Attribute::Custom(LOG_PROP_LIST_INDEX), AttrValue::Length(index)) = (attr, &value) {
+            self.states.list_index_at(*index);
+            return;
+        }
         self.props.set(attr, value);
         if matches!(attr, Attribute::Content) {
             self.states.set_list_len(
@@ -194,6 +208,26 @@ impl Component<Msg, NoUserEvent> for Log {
                 code: Key::BackTab | Key::Tab | Key::Char('p'),
                 ..
             }) => Some(Msg::Ui(UiMsg::LogBackTabbed)),
+            Event::Keyboard(KeyEvent {
+                code: Key::Char('f'),
+                ..
+            }) => Some(Msg::Ui(UiMsg::CycleLogLevelFilter)),
+            Event::Keyboard(KeyEvent {
+                code: Key::Char('/'),
+                ..
+            }) => Some(Msg::Ui(UiMsg::ShowLogSearchPopup)),
+            Event::Keyboard(KeyEvent {
+                code: Key::Char('n'),
+                ..
+            }) => Some(Msg::Ui(UiMsg::MoveLogMatch(true))),
+            Event::Keyboard(KeyEvent {
+                code: Key::Char('N'),
+                ..
+            }) => Some(Msg::Ui(UiMsg::MoveLogMatch(false))),
+            Event::Keyboard(KeyEvent {
+                code: Key::Char('s'),
+                ..
+            }) => Some(Msg::Ui(UiMsg::ShowLogExportPopup)),
             _ => None,
         }
     }
@@ -235,6 +269,13 @@ impl OwnStates {
         }
     }
 
+    /// Set list index at `index`, if in range
+    pub fn list_index_at(&mut self, index: usize) {
+        if index < self.list_len {
+            self.list_index = index;
+        }
+    }
+
     /// Set list index at last item
     pub fn list_index_at_last(&mut self) {
         self.list_index = match self.list_len {
@@ -2,51 +2,154 @@
 //!
 //! file transfer activity components
 
+use bytesize::ByteSize;
 use tui_realm_stdlib::Span;
 use tuirealm::props::{Color, TextSpan};
 use tuirealm::{Component, Event, MockComponent, NoUserEvent};
 
 use super::Msg;
 
+/// Live state of the session displayed in the footer bar
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct FooterState {
+    /// Transfer rate in bytes per second, while a transfer is in progress
+    pub transfer_rate: Option<u64>,
+    /// Amount of errors logged in the session
+    pub errors: usize,
+    /// Amount of paths watched by the fs watcher, if it's running
+    pub watched: Option<usize>,
+    /// Amount of scheduled transfers and the start time of the next one
+    pub scheduled: Option<(usize, String)>,
+    /// Whether commands can't be executed on the host of the current panel
+    pub exec_unsupported: bool,
+}
+
+/// Colors of the footer bar
+#[derive(Debug, Clone, Copy)]
+pub struct FooterColors {
+    pub keys: Color,
+    pub transfer: Color,
+    pub errors: Color,
+    pub watcher: Color,
+    pub scheduled: Color,
+}
+
 #[derive(MockComponent)]
 pub struct FooterBar {
     component: Span,
 }
 
 impl FooterBar {
-    pub fn new(key_color: Color) -> Self {
+    /// Instantiates the footer bar, displaying `state` followed by the keybinding hints.
+    /// While a transfer is in progress, the hints are replaced by the transfer rate
+    pub fn new(state: &FooterState, colors: FooterColors) -> Self {
+        let mut spans = Vec::new();
+        if state.errors > 0 {
+            spans.push(
+                TextSpan::new(format!("{} errors", state.errors))
+                    .bold()
+                    .fg(colors.errors),
+            );
+            spans.push(TextSpan::from(" "));
+        }
+        if let Some(watched) = state.watched.filter(|x| *x > 0) {
+            spans.push(
+                TextSpan::new(format!("Watching {watched} paths"))
+                    .bold()
+                    .fg(colors.watcher),
+            );
+            spans.push(TextSpan::from(" "));
+        }
+        if let Some((count, next)) = state.scheduled.as_ref() {
+            spans.push(
+                TextSpan::new(format!("{count} scheduled (next at {next})"))
+                    .bold()
+                    .fg(colors.scheduled),
+            );
+            spans.push(TextSpan::from(" "));
+        }
+        match state.transfer_rate {
+            Some(rate) => spans.push(
+                TextSpan::new(format!("Transferring at {}/s", ByteSize(rate)))
+                    .bold()
+                    .fg(colors.transfer),
+            ),
+            None => spans.extend(Self::hints(colors.keys, state.exec_unsupported)),
+        }
+        Self {
+            component: Span::default().spans(&spans),
+        }
+    }
+
+    /// Keybinding hints displayed while idle
+    fn hints(key_color: Color, exec_unsupported: bool) -> Vec<TextSpan> {
+        // the exec hint is greyed out when commands can't be executed on the current host
+        let exec_hint = match exec_unsupported {
+            true => [
+                TextSpan::from("<X>").bold().fg(Color::Rgb(128, 128, 128)),
+                TextSpan::from(" Exec ").fg(Color::Rgb(128, 128, 128)),
+            ],
+            false => [
+                TextSpan::from("<X>").bold().fg(key_color),
+                TextSpan::from(" Exec "),
+            ],
+        };
+        let [exec_key, exec_label] = exec_hint;
+        vec![
+            TextSpan::from("<F1|H>").bold().fg(key_color),
+            TextSpan::from(" Help "),
+            TextSpan::from("<TAB>").bold().fg(key_color),
+            TextSpan::from(" Change tab "),
+            TextSpan::from("<SPACE>").bold().fg(key_color),
+            TextSpan::from(" Transfer "),
+            TextSpan::from("<ENTER>").bold().fg(key_color),
+            TextSpan::from(" Enter dir "),
+            TextSpan::from("<F2|S>").bold().fg(key_color),
+            TextSpan::from(" Save as "),
+            TextSpan::from("<F3|V>").bold().fg(key_color),
+            TextSpan::from(" View "),
+            TextSpan::from("<F4|O>").bold().fg(key_color),
+            TextSpan::from(" Edit "),
+            TextSpan::from("<F5|C>").bold().fg(key_color),
+            TextSpan::from(" Copy "),
+            TextSpan::from("<F6|R>").bold().fg(key_color),
+            TextSpan::from(" Rename "),
+            TextSpan::from("<F7|D>").bold().fg(key_color),
+            TextSpan::from(" Make dir "),
+            TextSpan::from("<F8|DEL>").bold().fg(key_color),
+            TextSpan::from(" Delete "),
+            exec_key,
+            exec_label,
+            TextSpan::from("<F10|Q>").bold().fg(key_color),
+            TextSpan::from(" Quit "),
+        ]
+    }
+}
+
+impl Component<Msg, NoUserEvent> for FooterBar {
+    fn on(&mut self, _: Event<NoUserEvent>) -> Option<Msg> {
+        None
+    }
+}
+
+/// Footer line showing the full name of the highlighted entry, when it's elided in the explorer
+#[derive(MockComponent)]
+pub struct FileNameBar {
+    component: Span,
+}
+
+impl FileNameBar {
+    pub fn new(name: &str, color: Color) -> Self {
         Self {
             component: Span::default().spans(&[
-                TextSpan::from("<F1|H>").bold().fg(key_color),
-                TextSpan::from(" Help "),
-                TextSpan::from("<TAB>").bold().fg(key_color),
-                TextSpan::from(" Change tab "),
-                TextSpan::from("<SPACE>").bold().fg(key_color),
-                TextSpan::from(" Transfer "),
-                TextSpan::from("<ENTER>").bold().fg(key_color),
-                TextSpan::from(" Enter dir "),
-                TextSpan::from("<F2|S>").bold().fg(key_color),
-                TextSpan::from(" Save as "),
-                TextSpan::from("<F3|V>").bold().fg(key_color),
-                TextSpan::from(" View "),
-                TextSpan::from("<F4|O>").bold().fg(key_color),
-                TextSpan::from(" Edit "),
-                TextSpan::from("<F5|C>").bold().fg(key_color),
-                TextSpan::from(" Copy "),
-                TextSpan::from("<F6|R>").bold().fg(key_color),
-                TextSpan::from(" Rename "),
-                TextSpan::from("<F7|D>").bold().fg(key_color),
-                TextSpan::from(" Make dir "),
-                TextSpan::from("<F8|DEL>").bold().fg(key_color),
-                TextSpan::from(" Delete "),
-                TextSpan::from("<F10|Q>").bold().fg(key_color),
-                TextSpan::from(" Quit "),
+                TextSpan::from("Name: ").bold().fg(color),
+                TextSpan::from(name),
             ]),
         }
     }
 }
 
-impl Component<Msg, NoUserEvent> for FooterBar {
+impl Component<Msg, NoUserEvent> for FileNameBar {
     fn on(&mut self, _: Event<NoUserEvent>) -> Option<Msg> {
         None
     }
@@ -14,18 +14,26 @@ mod misc;
 mod popups;
 mod transfer;
 
-pub use misc::FooterBar;
+pub use misc::{FileNameBar, FooterBar, FooterColors, FooterState};
 pub use popups::{
-    ChmodPopup, CopyPopup, DeletePopup, DisconnectPopup, ErrorPopup, ExecPopup, FatalPopup,
-    FileInfoPopup, FilterPopup, GotoPopup, KeybindingsPopup, MkdirPopup, NewfilePopup,
-    OpenWithPopup, ProgressBarFull, ProgressBarPartial, QuitPopup, RenamePopup, ReplacePopup,
-    ReplacingFilesListPopup, SaveAsPopup, SortingPopup, StatusBarLocal, StatusBarRemote,
-    SymlinkPopup, SyncBrowsingMkdirPopup, WaitPopup, WalkdirWaitPopup, WatchedPathsList,
-    WatcherPopup, ATTR_FILES,
+    ArchivePopup, BookmarkDirPopup, ChecksumComparePopup, ChecksumPopup, ChmodPopup, CopyPopup,
+    CredentialsPasswordPopup, CredentialsUsernamePopup, DeleteConfirmNamePopup, DeletePopup,
+    DirBookmarksPopup, DirHistoryPopup, DisconnectPopup, EditConflictPopup, EditDiffPopup,
+    ErrorPopup, ExecOutputPopup, ExecPopup, FailedTransfersPopup, FatalPopup, FavoritesPopup,
+    FileInfoPopup, FilterPopup, GotoPopup, HostKeyFingerprintPopup, HostKeyPopup, KeybindingsPopup,
+    LogExportPopup, LogSearchPopup, MkdirPopup, NewfilePopup, OpenWithPopup, PreviewPopup,
+    ProgressBarFull, ProgressBarPartial, QuickCommandsPopup, QuitPopup, RenamePopup, ReplacePopup,
+    ReplaceRenamePopup, ReplacingFilesListPopup, SaveAsPopup, ScheduleTransferPopup, SortingPopup,
+    StatusBarLocal, StatusBarRemote, SymlinkPopup, SyncBrowsingMkdirPopup, TransferConfirmPopup,
+    TransferQueuePopup, TransferScanWaitPopup, UpdateBookmarkPopup, WaitPopup, WalkdirWaitPopup,
+    WatchIgnorePopup, WatchedPathsList, WatcherPopup, ATTR_FILES,
+};
+pub use transfer::{
+    ExplorerFind, ExplorerFuzzy, ExplorerLocal, ExplorerRemote, FILE_LIST_PROP_LIST_INDEX,
+    FILE_LIST_PROP_UNSELECTABLE,
 };
-pub use transfer::{ExplorerFind, ExplorerFuzzy, ExplorerLocal, ExplorerRemote};
 
-pub use self::log::Log;
+pub use self::log::{Log, LOG_PROP_LIST_INDEX};
 
 #[derive(Default, MockComponent)]
 pub struct GlobalListener {
@@ -46,8 +54,32 @@ impl Component<Msg, NoUserEvent> for GlobalListener {
                 code: Key::Char('h') | Key::Function(1),
                 modifiers: KeyModifiers::NONE,
             }) => Some(Msg::Ui(UiMsg::ShowKeybindingsPopup)),
+            Event::Keyboard(KeyEvent {
+                code: Key::Char('q'),
+                modifiers: KeyModifiers::CONTROL,
+            }) => Some(Msg::Ui(UiMsg::ShowTransferQueuePopup)),
+            Event::Keyboard(KeyEvent {
+                code: Key::Char('z'),
+                modifiers: KeyModifiers::CONTROL,
+            }) => Some(Msg::Ui(UiMsg::Suspend)),
             Event::WindowResize(_, _) => Some(Msg::Ui(UiMsg::WindowResized)),
             _ => None,
         }
     }
 }
+
+/// Listens to the focus events reported by the terminal
+#[derive(Default, MockComponent)]
+pub struct FocusListener {
+    component: Phantom,
+}
+
+impl Component<Msg, NoUserEvent> for FocusListener {
+    fn on(&mut self, ev: Event<NoUserEvent>) -> Option<Msg> {
+        match ev {
+            Event::FocusGained => Some(Msg::Ui(UiMsg::TerminalFocusChanged(true))),
+            Event::FocusLost => Some(Msg::Ui(UiMsg::TerminalFocusChanged(false))),
+            _ => None,
+        }
+    }
+}
@@ -2,30 +2,144 @@
 //!
 //! popups components
 
+mod buttons;
 mod chmod;
+mod edit_diff;
 mod goto;
+mod preview;
 
+use std::path::Path;
 use std::time::UNIX_EPOCH;
 
 use bytesize::ByteSize;
 use remotefs::File;
-use tui_realm_stdlib::{Input, List, Paragraph, ProgressBar, Radio, Span};
+use tui_realm_stdlib::props::INPUT_PLACEHOLDER;
+use tui_realm_stdlib::{Input, List, Paragraph, ProgressBar, Span};
 use tuirealm::command::{Cmd, CmdResult, Direction, Position};
 use tuirealm::event::{Key, KeyEvent, KeyModifiers};
 use tuirealm::props::{
     Alignment, BorderSides, BorderType, Borders, Color, InputType, Style, TableBuilder, TextSpan,
 };
-use tuirealm::{Component, Event, MockComponent, NoUserEvent, State, StateValue};
+use tuirealm::{
+    AttrValue, Attribute, Component, Event, MockComponent, NoUserEvent, State, StateValue,
+};
 #[cfg(posix)]
 use uzers::{get_group_by_gid, get_user_by_uid};
 
+use self::buttons::Buttons;
 pub use self::chmod::ChmodPopup;
+pub use self::edit_diff::EditDiffPopup;
 pub use self::goto::{GotoPopup, ATTR_FILES};
+pub use self::preview::PreviewPopup;
+use super::super::browser::FileExplorerTab;
+use super::super::lib::transfer::TransferFailure;
 use super::super::Browser;
 use super::{Msg, PendingActionMsg, TransferMsg, UiMsg};
-use crate::explorer::FileSorting;
+use crate::config::bookmarks::DirectoryBookmark;
+use crate::config::keymap::{KeyAction, Keymap};
+use crate::explorer::filter::{Filter, FilterMode};
+use crate::explorer::special::SpecialFileType;
+use crate::explorer::{FileExplorer, FileSorting};
+use crate::filetransfer::params::QuickCommand;
+use crate::host::Xattr;
+use crate::system::watcher::WatchDirection;
+use crate::utils::archive::ArchiveFormat;
+use crate::utils::checksum::ChecksumAlgorithm;
 use crate::utils::fmt::fmt_time;
 
+#[derive(MockComponent)]
+pub struct ChecksumPopup {
+    component: Paragraph,
+    digest: String,
+}
+
+impl ChecksumPopup {
+    pub fn new(algorithm: ChecksumAlgorithm, name: &str, digest: String, color: Color) -> Self {
+        Self {
+            component: Paragraph::default()
+                .alignment(Alignment::Center)
+                .borders(
+                    Borders::default()
+                        .color(color)
+                        .modifiers(BorderType::Rounded),
+                )
+                .foreground(color)
+                .title(
+                    format!("{algorithm} of \"{name}\" (<C> to copy)"),
+                    Alignment::Center,
+                )
+                .text(&[TextSpan::from(digest.as_str())])
+                .wrap(true),
+            digest,
+        }
+    }
+}
+
+impl Component<Msg, NoUserEvent> for ChecksumPopup {
+    fn on(&mut self, ev: Event<NoUserEvent>) -> Option<Msg> {
+        match ev {
+            Event::Keyboard(KeyEvent {
+                code: Key::Esc | Key::Enter,
+                ..
+            }) => Some(Msg::Ui(UiMsg::CloseChecksumPopup)),
+            Event::Keyboard(KeyEvent {
+                code: Key::Char('c'),
+                modifiers: KeyModifiers::NONE,
+            }) => Some(Msg::Ui(UiMsg::CopyToClipboard(self.digest.clone()))),
+            _ => None,
+        }
+    }
+}
+
+#[derive(MockComponent)]
+pub struct ChecksumComparePopup {
+    component: Paragraph,
+}
+
+impl ChecksumComparePopup {
+    pub fn new(
+        algorithm: ChecksumAlgorithm,
+        name: &str,
+        host_bridge_digest: &str,
+        remote_digest: &str,
+        color: Color,
+    ) -> Self {
+        let verdict = match host_bridge_digest == remote_digest {
+            true => TextSpan::new("Files match").bold(),
+            false => TextSpan::new("Files DON'T match").bold(),
+        };
+        Self {
+            component: Paragraph::default()
+                .alignment(Alignment::Center)
+                .borders(
+                    Borders::default()
+                        .color(color)
+                        .modifiers(BorderType::Rounded),
+                )
+                .foreground(color)
+                .title(format!("{algorithm} of \"{name}\""), Alignment::Center)
+                .text(&[
+                    TextSpan::from(format!("Local:  {host_bridge_digest}")),
+                    TextSpan::from(format!("Remote: {remote_digest}")),
+                    verdict,
+                ])
+                .wrap(true),
+        }
+    }
+}
+
+impl Component<Msg, NoUserEvent> for ChecksumComparePopup {
+    fn on(&mut self, ev: Event<NoUserEvent>) -> Option<Msg> {
+        match ev {
+            Event::Keyboard(KeyEvent {
+                code: Key::Esc | Key::Enter,
+                ..
+            }) => Some(Msg::Ui(UiMsg::CloseChecksumComparePopup)),
+            _ => None,
+        }
+    }
+}
+
 #[derive(MockComponent)]
 pub struct CopyPopup {
     component: Input,
@@ -113,13 +227,17 @@ impl Component<Msg, NoUserEvent> for CopyPopup {
 }
 
 #[derive(MockComponent)]
-pub struct FilterPopup {
+pub struct ArchivePopup {
     component: Input,
+    format: ArchiveFormat,
 }
 
-impl FilterPopup {
-    pub fn new(color: Color) -> Self {
+impl ArchivePopup {
+    /// Instantiates the popup to name the archive of the selected entries, prefilled with `name`
+    pub fn new(name: &str, color: Color) -> Self {
+        let format = ArchiveFormat::TarGz;
         Self {
+            format,
             component: Input::default()
                 .borders(
                     Borders::default()
@@ -129,18 +247,43 @@ impl FilterPopup {
                 .foreground(color)
                 .input_type(InputType::Text)
                 .placeholder(
-                    "regex or wildmatch",
+                    "archive name",
                     Style::default().fg(Color::Rgb(128, 128, 128)),
                 )
-                .title(
-                    "Filter files by regex or wildmatch in the current directory",
-                    Alignment::Center,
-                ),
+                .title(Self::title(format), Alignment::Center)
+                .value(format.archive_name(name)),
+        }
+    }
+
+    fn title(format: ArchiveFormat) -> String {
+        let other = match format {
+            ArchiveFormat::Zip => ArchiveFormat::TarGz,
+            _ => ArchiveFormat::Zip,
+        };
+        format!("Compress selection to a {format} archive (<TAB> for {other})")
+    }
+
+    /// Switch between tar.gz and zip, replacing the extension of the name
+    fn toggle_format(&mut self) {
+        let format = match self.format {
+            ArchiveFormat::Zip => ArchiveFormat::TarGz,
+            _ => ArchiveFormat::Zip,
+        };
+        if let State::One(StateValue::String(name)) = self.state() {
+            self.attr(
+                Attribute::Value,
+                AttrValue::String(self.format.replace_extension(&name, format)),
+            );
         }
+        self.format = format;
+        self.attr(
+            Attribute::Title,
+            AttrValue::Title((Self::title(format), Alignment::Center)),
+        );
     }
 }
 
-impl Component<Msg, NoUserEvent> for FilterPopup {
+impl Component<Msg, NoUserEvent> for ArchivePopup {
     fn on(&mut self, ev: Event<NoUserEvent>) -> Option<Msg> {
         match ev {
             Event::Keyboard(KeyEvent {
@@ -178,6 +321,10 @@ impl Component<Msg, NoUserEvent> for FilterPopup {
                 self.perform(Cmd::Delete);
                 Some(Msg::None)
             }
+            Event::Keyboard(KeyEvent { code: Key::Tab, .. }) => {
+                self.toggle_format();
+                Some(Msg::None)
+            }
             Event::Keyboard(KeyEvent {
                 code: Key::Char(ch),
                 ..
@@ -188,11 +335,13 @@ impl Component<Msg, NoUserEvent> for FilterPopup {
             Event::Keyboard(KeyEvent {
                 code: Key::Enter, ..
             }) => match self.state() {
-                State::One(StateValue::String(filter)) => Some(Msg::Ui(UiMsg::FilterFiles(filter))),
+                State::One(StateValue::String(name)) => {
+                    Some(Msg::Transfer(TransferMsg::CreateArchive(name, self.format)))
+                }
                 _ => Some(Msg::None),
             },
             Event::Keyboard(KeyEvent { code: Key::Esc, .. }) => {
-                Some(Msg::Ui(UiMsg::CloseFilterPopup))
+                Some(Msg::Ui(UiMsg::CloseArchivePopup))
             }
             _ => None,
         }
@@ -200,28 +349,36 @@ impl Component<Msg, NoUserEvent> for FilterPopup {
 }
 
 #[derive(MockComponent)]
-pub struct DeletePopup {
-    component: Radio,
+pub struct BookmarkDirPopup {
+    component: Input,
 }
 
-impl DeletePopup {
-    pub fn new(color: Color) -> Self {
+impl BookmarkDirPopup {
+    /// Instantiates the popup asking the name to bookmark `dir` as, pre-filled with `suggestion`
+    pub fn new(dir: &Path, suggestion: &str, color: Color) -> Self {
         Self {
-            component: Radio::default()
+            component: Input::default()
                 .borders(
                     Borders::default()
                         .color(color)
                         .modifiers(BorderType::Rounded),
                 )
                 .foreground(color)
-                .choices(&["Yes", "No"])
-                .value(1)
-                .title("Delete file(s)?", Alignment::Center),
+                .input_type(InputType::Text)
+                .value(suggestion)
+                .placeholder(
+                    "Bookmark name",
+                    Style::default().fg(Color::Rgb(128, 128, 128)),
+                )
+                .title(
+                    format!("Bookmark \"{}\" as…", dir.display()),
+                    Alignment::Center,
+                ),
         }
     }
 }
 
-impl Component<Msg, NoUserEvent> for DeletePopup {
+impl Component<Msg, NoUserEvent> for BookmarkDirPopup {
     fn on(&mut self, ev: Event<NoUserEvent>) -> Option<Msg> {
         match ev {
             Event::Keyboard(KeyEvent {
@@ -236,28 +393,46 @@ impl Component<Msg, NoUserEvent> for DeletePopup {
                 self.perform(Cmd::Move(Direction::Right));
                 Some(Msg::None)
             }
-            Event::Keyboard(KeyEvent { code: Key::Esc, .. }) => {
-                Some(Msg::Ui(UiMsg::CloseDeletePopup))
+            Event::Keyboard(KeyEvent {
+                code: Key::Home, ..
+            }) => {
+                self.perform(Cmd::GoTo(Position::Begin));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent { code: Key::End, .. }) => {
+                self.perform(Cmd::GoTo(Position::End));
+                Some(Msg::None)
             }
             Event::Keyboard(KeyEvent {
-                code: Key::Char('y'),
-                modifiers: KeyModifiers::NONE,
-            }) => Some(Msg::Transfer(TransferMsg::DeleteFile)),
+                code: Key::Delete, ..
+            }) => {
+                self.perform(Cmd::Cancel);
+                Some(Msg::None)
+            }
             Event::Keyboard(KeyEvent {
-                code: Key::Char('n'),
-                modifiers: KeyModifiers::NONE,
-            }) => Some(Msg::Ui(UiMsg::CloseDeletePopup)),
+                code: Key::Backspace,
+                ..
+            }) => {
+                self.perform(Cmd::Delete);
+                Some(Msg::None)
+            }
             Event::Keyboard(KeyEvent {
-                code: Key::Enter, ..
+                code: Key::Char(ch),
+                ..
             }) => {
-                if matches!(
-                    self.perform(Cmd::Submit),
-                    CmdResult::Submit(State::One(StateValue::Usize(0)))
-                ) {
-                    Some(Msg::Transfer(TransferMsg::DeleteFile))
-                } else {
-                    Some(Msg::Ui(UiMsg::CloseDeletePopup))
+                self.perform(Cmd::Type(ch));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Enter, ..
+            }) => match self.state() {
+                State::One(StateValue::String(name)) => {
+                    Some(Msg::Transfer(TransferMsg::BookmarkDirectory(name)))
                 }
+                _ => Some(Msg::None),
+            },
+            Event::Keyboard(KeyEvent { code: Key::Esc, .. }) => {
+                Some(Msg::Ui(UiMsg::CloseBookmarkDirPopup))
             }
             _ => None,
         }
@@ -265,27 +440,27 @@ impl Component<Msg, NoUserEvent> for DeletePopup {
 }
 
 #[derive(MockComponent)]
-pub struct DisconnectPopup {
-    component: Radio,
+pub struct CredentialsPasswordPopup {
+    component: Input,
 }
 
-impl DisconnectPopup {
+impl CredentialsPasswordPopup {
     pub fn new(color: Color) -> Self {
         Self {
-            component: Radio::default()
+            component: Input::default()
                 .borders(
                     Borders::default()
                         .color(color)
                         .modifiers(BorderType::Rounded),
                 )
                 .foreground(color)
-                .choices(&["Yes", "No"])
-                .title("Are you sure you want to disconnect?", Alignment::Center),
+                .input_type(InputType::Password('*'))
+                .title("Password", Alignment::Left),
         }
     }
 }
 
-impl Component<Msg, NoUserEvent> for DisconnectPopup {
+impl Component<Msg, NoUserEvent> for CredentialsPasswordPopup {
     fn on(&mut self, ev: Event<NoUserEvent>) -> Option<Msg> {
         match ev {
             Event::Keyboard(KeyEvent {
@@ -300,75 +475,58 @@ impl Component<Msg, NoUserEvent> for DisconnectPopup {
                 self.perform(Cmd::Move(Direction::Right));
                 Some(Msg::None)
             }
-            Event::Keyboard(KeyEvent { code: Key::Esc, .. }) => {
-                Some(Msg::Ui(UiMsg::CloseDisconnectPopup))
+            Event::Keyboard(KeyEvent {
+                code: Key::Home, ..
+            }) => {
+                self.perform(Cmd::GoTo(Position::Begin));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent { code: Key::End, .. }) => {
+                self.perform(Cmd::GoTo(Position::End));
+                Some(Msg::None)
             }
             Event::Keyboard(KeyEvent {
-                code: Key::Char('y'),
-                modifiers: KeyModifiers::NONE,
-            }) => Some(Msg::Ui(UiMsg::Disconnect)),
+                code: Key::Delete, ..
+            }) => {
+                self.perform(Cmd::Cancel);
+                Some(Msg::None)
+            }
             Event::Keyboard(KeyEvent {
-                code: Key::Char('n'),
-                modifiers: KeyModifiers::NONE,
-            }) => Some(Msg::Ui(UiMsg::CloseDisconnectPopup)),
+                code: Key::Backspace,
+                ..
+            }) => {
+                self.perform(Cmd::Delete);
+                Some(Msg::None)
+            }
             Event::Keyboard(KeyEvent {
-                code: Key::Enter, ..
+                code: Key::Char(ch),
+                ..
             }) => {
-                if matches!(
-                    self.perform(Cmd::Submit),
-                    CmdResult::Submit(State::One(StateValue::Usize(0)))
-                ) {
-                    Some(Msg::Ui(UiMsg::Disconnect))
-                } else {
-                    Some(Msg::Ui(UiMsg::CloseDisconnectPopup))
-                }
+                self.perform(Cmd::Type(ch));
+                Some(Msg::None)
             }
-            _ => None,
-        }
-    }
-}
-
-#[derive(MockComponent)]
-pub struct ErrorPopup {
-    component: Paragraph,
-}
-
-impl ErrorPopup {
-    pub fn new<S: AsRef<str>>(text: S, color: Color) -> Self {
-        Self {
-            component: Paragraph::default()
-                .alignment(Alignment::Center)
-                .borders(
-                    Borders::default()
-                        .color(color)
-                        .modifiers(BorderType::Rounded),
-                )
-                .foreground(color)
-                .text(&[TextSpan::from(text.as_ref())])
-                .wrap(true),
-        }
-    }
-}
-
-impl Component<Msg, NoUserEvent> for ErrorPopup {
-    fn on(&mut self, ev: Event<NoUserEvent>) -> Option<Msg> {
-        match ev {
             Event::Keyboard(KeyEvent {
-                code: Key::Esc | Key::Enter,
+                code: Key::Tab | Key::Up | Key::Down,
                 ..
-            }) => Some(Msg::Ui(UiMsg::CloseErrorPopup)),
+            }) => Some(Msg::Ui(UiMsg::CredentialsPopupTabbed)),
+            Event::Keyboard(KeyEvent {
+                code: Key::Enter, ..
+            }) => Some(Msg::Ui(UiMsg::RetryWithCredentials)),
+            Event::Keyboard(KeyEvent { code: Key::Esc, .. }) => {
+                Some(Msg::Ui(UiMsg::CloseCredentialsPopup))
+            }
             _ => None,
         }
     }
 }
 
 #[derive(MockComponent)]
-pub struct ExecPopup {
+pub struct CredentialsUsernamePopup {
     component: Input,
 }
 
-impl ExecPopup {
-    pub fn new(color: Color) -> Self {
+impl CredentialsUsernamePopup {
+    pub fn new(username: Option<&str>, host: &str, color: Color) -> Self {
         Self {
             component: Input::default()
                 .borders(
@@ -378,13 +536,17 @@ impl ExecPopup {
                 )
                 .foreground(color)
                 .input_type(InputType::Text)
-                .placeholder("ps a", Style::default().fg(Color::Rgb(128, 128, 128)))
-                .title("Execute command", Alignment::Center),
+                .placeholder("username", Style::default().fg(Color::Rgb(128, 128, 128)))
+                .title(
+                    format!("Authentication to '{host}' failed; retry as…"),
+                    Alignment::Left,
+                )
+                .value(username.unwrap_or_default()),
         }
     }
 }
 
-impl Component<Msg, NoUserEvent> for ExecPopup {
+impl Component<Msg, NoUserEvent> for CredentialsUsernamePopup {
     fn on(&mut self, ev: Event<NoUserEvent>) -> Option<Msg> {
         match ev {
             Event::Keyboard(KeyEvent {
@@ -429,16 +591,15 @@ impl Component<Msg, NoUserEvent> for ExecPopup {
                 self.perform(Cmd::Type(ch));
                 Some(Msg::None)
             }
+            Event::Keyboard(KeyEvent {
+                code: Key::Tab | Key::Up | Key::Down,
+                ..
+            }) => Some(Msg::Ui(UiMsg::CredentialsPopupTabbed)),
             Event::Keyboard(KeyEvent {
                 code: Key::Enter, ..
-            }) => match self.state() {
-                State::One(StateValue::String(i)) => {
-                    Some(Msg::Transfer(TransferMsg::ExecuteCmd(i)))
-                }
-                _ => Some(Msg::None),
-            },
+            }) => Some(Msg::Ui(UiMsg::CredentialsPopupTabbed)),
             Event::Keyboard(KeyEvent { code: Key::Esc, .. }) => {
-                Some(Msg::Ui(UiMsg::CloseExecPopup))
+                Some(Msg::Ui(UiMsg::CloseCredentialsPopup))
             }
             _ => None,
         }
@@ -446,15 +607,1015 @@ impl Component<Msg, NoUserEvent> for ExecPopup {
 }
 
 #[derive(MockComponent)]
-pub struct FatalPopup {
-    component: Paragraph,
+pub struct FilterPopup {
+    component: Input,
+    /// If true, files are searched by content rather than filtered by name
+    content: bool,
+    /// Mode of the filters without the `re:` or `glob:` prefix
+    default_mode: FilterMode,
+    /// Whether the title reports an invalid filter
+    error: bool,
 }
 
-impl FatalPopup {
-    pub fn new<S: AsRef<str>>(text: S, color: Color) -> Self {
+impl FilterPopup {
+    pub fn new(color: Color, default_mode: FilterMode) -> Self {
         Self {
-            component: Paragraph::default()
-                .alignment(Alignment::Center)
+            content: false,
+            default_mode,
+            error: false,
+            component: Input::default()
+                .borders(
+                    Borders::default()
+                        .color(color)
+                        .modifiers(BorderType::Rounded),
+                )
+                .foreground(color)
+                .input_type(InputType::Text)
+                .placeholder(
+                    Self::placeholder(false, default_mode),
+                    Style::default().fg(Color::Rgb(128, 128, 128)),
+                )
+                .title(Self::title(false, default_mode), Alignment::Center),
+        }
+    }
+
+    fn title(content: bool, default_mode: FilterMode) -> String {
+        match content {
+            true => String::from("Search files containing text (<TAB> to filter by name)"),
+            false => format!("Filter files by {default_mode} (<TAB> to search contents)"),
+        }
+    }
+
+    fn placeholder(content: bool, default_mode: FilterMode) -> &'static str {
+        match (content, default_mode) {
+            (true, _) => "text",
+            (false, FilterMode::Glob) => "*.log, or re:regex",
+            (false, FilterMode::Regex) => r"\.log$, or glob:pattern",
+        }
+    }
+
+    /// Toggle between name and content mode
+    fn toggle_content(&mut self) {
+        self.content = !self.content;
+        self.error = false;
+        self.attr(
+            Attribute::Title,
+            AttrValue::Title((
+                Self::title(self.content, self.default_mode),
+                Alignment::Center,
+            )),
+        );
+        self.attr(
+            Attribute::Custom(INPUT_PLACEHOLDER),
+            AttrValue::String(Self::placeholder(self.content, self.default_mode).to_string()),
+        );
+    }
+
+    /// Report the error of the filter in the title, keeping the popup open
+    fn show_error(&mut self, err: String) {
+        self.error = true;
+        self.attr(
+            Attribute::Title,
+            AttrValue::Title((format!("{err} (<ESC> to close)"), Alignment::Center)),
+        );
+    }
+
+    /// Restore the title once the filter is edited
+    fn clear_error(&mut self) {
+        if self.error {
+            self.error = false;
+            self.attr(
+                Attribute::Title,
+                AttrValue::Title((
+                    Self::title(self.content, self.default_mode),
+                    Alignment::Center,
+                )),
+            );
+        }
+    }
+}
+
+impl Component<Msg, NoUserEvent> for FilterPopup {
+    fn on(&mut self, ev: Event<NoUserEvent>) -> Option<Msg> {
+        match ev {
+            Event::Keyboard(KeyEvent {
+                code: Key::Left, ..
+            }) => {
+                self.perform(Cmd::Move(Direction::Left));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Right, ..
+            }) => {
+                self.perform(Cmd::Move(Direction::Right));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Home, ..
+            }) => {
+                self.perform(Cmd::GoTo(Position::Begin));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent { code: Key::End, .. }) => {
+                self.perform(Cmd::GoTo(Position::End));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Delete, ..
+            }) => {
+                self.perform(Cmd::Cancel);
+                self.clear_error();
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Backspace,
+                ..
+            }) => {
+                self.perform(Cmd::Delete);
+                self.clear_error();
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent { code: Key::Tab, .. }) => {
+                self.toggle_content();
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Char(ch),
+                ..
+            }) => {
+                self.perform(Cmd::Type(ch));
+                self.clear_error();
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Enter, ..
+            }) => match self.state() {
+                State::One(StateValue::String(needle)) if self.content => {
+                    Some(Msg::Transfer(TransferMsg::SearchContent(needle)))
+                }
+                State::One(StateValue::String(filter)) => {
+                    match Filter::parse(&filter, self.default_mode) {
+                        Ok(_) => Some(Msg::Ui(UiMsg::FilterFiles(filter))),
+                        Err(err) => {
+                            self.show_error(err);
+                            Some(Msg::None)
+                        }
+                    }
+                }
+                // an empty filter clears the filter of the panel
+                _ if !self.content => Some(Msg::Ui(UiMsg::FilterFiles(String::new()))),
+                _ => Some(Msg::None),
+            },
+            Event::Keyboard(KeyEvent { code: Key::Esc, .. }) => {
+                Some(Msg::Ui(UiMsg::CloseFilterPopup))
+            }
+            _ => None,
+        }
+    }
+}
+
+#[derive(MockComponent)]
+pub struct DeletePopup {
+    component: Buttons,
+}
+
+impl DeletePopup {
+    pub fn new<S: AsRef<str>>(title: S, color: Color) -> Self {
+        Self {
+            component: Buttons::default()
+                .borders(
+                    Borders::default()
+                        .color(color)
+                        .modifiers(BorderType::Rounded),
+                )
+                .foreground(color)
+                .choices(&["Yes", "No"])
+                .value(1)
+                .title(title.as_ref(), Alignment::Center),
+        }
+    }
+}
+
+impl Component<Msg, NoUserEvent> for DeletePopup {
+    fn on(&mut self, ev: Event<NoUserEvent>) -> Option<Msg> {
+        match ev {
+            // clicking a choice submits it
+            Event::Mouse(ev) if self.component.click(&ev).is_some() => {
+                self.on(Event::Keyboard(KeyEvent::from(Key::Enter)))
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Left, ..
+            }) => {
+                self.perform(Cmd::Move(Direction::Left));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Right, ..
+            }) => {
+                self.perform(Cmd::Move(Direction::Right));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent { code: Key::Esc, .. }) => {
+                Some(Msg::Ui(UiMsg::CloseDeletePopup))
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Char('y'),
+                modifiers: KeyModifiers::NONE,
+            }) => Some(Msg::Transfer(TransferMsg::DeleteFile)),
+            Event::Keyboard(KeyEvent {
+                code: Key::Char('n'),
+                modifiers: KeyModifiers::NONE,
+            }) => Some(Msg::Ui(UiMsg::CloseDeletePopup)),
+            Event::Keyboard(KeyEvent {
+                code: Key::Enter, ..
+            }) => {
+                if matches!(
+                    self.perform(Cmd::Submit),
+                    CmdResult::Submit(State::One(StateValue::Usize(0)))
+                ) {
+                    Some(Msg::Transfer(TransferMsg::DeleteFile))
+                } else {
+                    Some(Msg::Ui(UiMsg::CloseDeletePopup))
+                }
+            }
+            _ => None,
+        }
+    }
+}
+
+#[derive(MockComponent)]
+pub struct DeleteConfirmNamePopup {
+    component: Input,
+    /// Name the user has to type to confirm the deletion
+    name: String,
+}
+
+impl DeleteConfirmNamePopup {
+    pub fn new<S: AsRef<str>>(name: &str, title: S, color: Color) -> Self {
+        Self {
+            component: Input::default()
+                .borders(
+                    Borders::default()
+                        .color(color)
+                        .modifiers(BorderType::Rounded),
+                )
+                .foreground(color)
+                .input_type(InputType::Text)
+                .placeholder(
+                    format!("Type \"{name}\" to confirm"),
+                    Style::default().fg(Color::Rgb(128, 128, 128)),
+                )
+                .title(title.as_ref(), Alignment::Center),
+            name: name.to_string(),
+        }
+    }
+}
+
+impl Component<Msg, NoUserEvent> for DeleteConfirmNamePopup {
+    fn on(&mut self, ev: Event<NoUserEvent>) -> Option<Msg> {
+        match ev {
+            Event::Keyboard(KeyEvent {
+                code: Key::Left, ..
+            }) => {
+                self.perform(Cmd::Move(Direction::Left));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Right, ..
+            }) => {
+                self.perform(Cmd::Move(Direction::Right));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Home, ..
+            }) => {
+                self.perform(Cmd::GoTo(Position::Begin));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent { code: Key::End, .. }) => {
+                self.perform(Cmd::GoTo(Position::End));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Delete, ..
+            }) => {
+                self.perform(Cmd::Cancel);
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Backspace,
+                ..
+            }) => {
+                self.perform(Cmd::Delete);
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Char(ch),
+                ..
+            }) => {
+                self.perform(Cmd::Type(ch));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Enter, ..
+            }) => match self.state() {
+                // delete only if the typed name matches
+                State::One(StateValue::String(name)) if name == self.name => {
+                    Some(Msg::Transfer(TransferMsg::DeleteFile))
+                }
+                _ => Some(Msg::None),
+            },
+            Event::Keyboard(KeyEvent { code: Key::Esc, .. }) => {
+                Some(Msg::Ui(UiMsg::CloseDeletePopup))
+            }
+            _ => None,
+        }
+    }
+}
+
+#[derive(MockComponent)]
+pub struct DirBookmarksPopup {
+    component: List,
+}
+
+impl DirBookmarksPopup {
+    pub fn new(directories: &[DirectoryBookmark], color: Color) -> Self {
+        let width = directories
+            .iter()
+            .map(|x| x.name.chars().count())
+            .max()
+            .unwrap_or_default();
+        Self {
+            component: List::default()
+                .borders(
+                    Borders::default()
+                        .color(color)
+                        .modifiers(BorderType::Rounded),
+                )
+                .rewind(true)
+                .scroll(true)
+                .step(4)
+                .highlighted_color(color)
+                .highlighted_str("➤ ")
+                .title(
+                    "Bookmarked directories (<ENTER> go to, <DEL> remove)",
+                    Alignment::Center,
+                )
+                .rows(
+                    directories
+                        .iter()
+                        .map(|x| {
+                            vec![
+                                TextSpan::from(format!("{:width$}  ", x.name)).bold(),
+                                TextSpan::from(x.path.to_string_lossy()),
+                            ]
+                        })
+                        .collect(),
+                ),
+        }
+    }
+
+    fn selected(&self) -> Option<usize> {
+        match self.component.state() {
+            State::One(StateValue::Usize(idx)) => Some(idx),
+            _ => None,
+        }
+    }
+}
+
+impl Component<Msg, NoUserEvent> for DirBookmarksPopup {
+    fn on(&mut self, ev: Event<NoUserEvent>) -> Option<Msg> {
+        match ev {
+            Event::Keyboard(KeyEvent { code: Key::Esc, .. }) => {
+                Some(Msg::Ui(UiMsg::CloseDirBookmarksPopup))
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Down, ..
+            }) => {
+                self.perform(Cmd::Move(Direction::Down));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent { code: Key::Up, .. }) => {
+                self.perform(Cmd::Move(Direction::Up));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::PageDown,
+                ..
+            }) => {
+                self.perform(Cmd::Scroll(Direction::Down));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::PageUp, ..
+            }) => {
+                self.perform(Cmd::Scroll(Direction::Up));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Home, ..
+            }) => {
+                self.perform(Cmd::GoTo(Position::Begin));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent { code: Key::End, .. }) => {
+                self.perform(Cmd::GoTo(Position::End));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Enter, ..
+            }) => Some(
+                self.selected()
+                    .map(|idx| Msg::Transfer(TransferMsg::GoToDirBookmark(idx)))
+                    .unwrap_or(Msg::None),
+            ),
+            Event::Keyboard(KeyEvent {
+                code: Key::Char('e') | Key::Delete,
+                modifiers: KeyModifiers::NONE,
+            }) => Some(
+                self.selected()
+                    .map(|idx| Msg::Transfer(TransferMsg::RemoveDirBookmark(idx)))
+                    .unwrap_or(Msg::None),
+            ),
+            _ => None,
+        }
+    }
+}
+
+#[derive(MockComponent)]
+pub struct DirHistoryPopup {
+    component: List,
+}
+
+impl DirHistoryPopup {
+    /// Make the popup listing `dirs`, from the most recent directory
+    pub fn new(dirs: &[std::path::PathBuf], color: Color) -> Self {
+        Self {
+            component: List::default()
+                .borders(
+                    Borders::default()
+                        .color(color)
+                        .modifiers(BorderType::Rounded),
+                )
+                .rewind(true)
+                .scroll(true)
+                .step(4)
+                .highlighted_color(color)
+                .highlighted_str("➤ ")
+                .title("Previous directories", Alignment::Center)
+                .rows(
+                    dirs.iter()
+                        .map(|x| vec![TextSpan::from(x.to_string_lossy())])
+                        .collect(),
+                ),
+        }
+    }
+}
+
+impl Component<Msg, NoUserEvent> for DirHistoryPopup {
+    fn on(&mut self, ev: Event<NoUserEvent>) -> Option<Msg> {
+        match ev {
+            Event::Keyboard(KeyEvent { code: Key::Esc, .. }) => {
+                Some(Msg::Ui(UiMsg::CloseDirHistoryPopup))
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Down, ..
+            }) => {
+                self.perform(Cmd::Move(Direction::Down));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent { code: Key::Up, .. }) => {
+                self.perform(Cmd::Move(Direction::Up));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::PageDown,
+                ..
+            }) => {
+                self.perform(Cmd::Scroll(Direction::Down));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::PageUp, ..
+            }) => {
+                self.perform(Cmd::Scroll(Direction::Up));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Home, ..
+            }) => {
+                self.perform(Cmd::GoTo(Position::Begin));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent { code: Key::End, .. }) => {
+                self.perform(Cmd::GoTo(Position::End));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Enter, ..
+            }) => {
+                if let State::One(StateValue::Usize(idx)) = self.component.state() {
+                    Some(Msg::Transfer(TransferMsg::GoToHistoryEntry(idx)))
+                } else {
+                    Some(Msg::None)
+                }
+            }
+            _ => None,
+        }
+    }
+}
+
+#[derive(MockComponent)]
+pub struct FailedTransfersPopup {
+    component: List,
+}
+
+impl FailedTransfersPopup {
+    /// Make the popup listing the entries which couldn't be transferred, with their error
+    pub fn new(failures: &[TransferFailure], color: Color) -> Self {
+        Self {
+            component: List::default()
+                .borders(
+                    Borders::default()
+                        .color(color)
+                        .modifiers(BorderType::Rounded),
+                )
+                .rewind(true)
+                .scroll(true)
+                .step(4)
+                .highlighted_color(color)
+                .highlighted_str("➤ ")
+                .title(
+                    format!(
+                        "{} entries could not be transferred: <R> retry failed, <ESC> close",
+                        failures.len()
+                    ),
+                    Alignment::Center,
+                )
+                .rows(
+                    failures
+                        .iter()
+                        .map(|x| {
+                            vec![
+                                TextSpan::from(x.source.path().to_string_lossy()).fg(color),
+                                TextSpan::from(format!(": {}", x.error)),
+                            ]
+                        })
+                        .collect(),
+                ),
+        }
+    }
+}
+
+impl Component<Msg, NoUserEvent> for FailedTransfersPopup {
+    fn on(&mut self, ev: Event<NoUserEvent>) -> Option<Msg> {
+        match ev {
+            Event::Keyboard(KeyEvent { code: Key::Esc, .. }) => {
+                Some(Msg::Ui(UiMsg::CloseFailedTransfersPopup))
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Down, ..
+            }) => {
+                self.perform(Cmd::Move(Direction::Down));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent { code: Key::Up, .. }) => {
+                self.perform(Cmd::Move(Direction::Up));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::PageDown,
+                ..
+            }) => {
+                self.perform(Cmd::Scroll(Direction::Down));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::PageUp, ..
+            }) => {
+                self.perform(Cmd::Scroll(Direction::Up));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Home, ..
+            }) => {
+                self.perform(Cmd::GoTo(Position::Begin));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent { code: Key::End, .. }) => {
+                self.perform(Cmd::GoTo(Position::End));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Char('r') | Key::Enter,
+                ..
+            }) => Some(Msg::Transfer(TransferMsg::RetryFailedTransfers)),
+            _ => None,
+        }
+    }
+}
+
+#[derive(MockComponent)]
+pub struct DisconnectPopup {
+    component: Buttons,
+    interrupts: bool,
+    parkable: bool,
+}
+
+impl DisconnectPopup {
+    /// If `interrupts` is true, disconnecting interrupts some work, so it must be chosen explicitly:
+    /// the choice is "Disconnect anyway", the popup defaults to "Cancel" and `<Y>` is disabled.
+    /// Otherwise, if `parkable` is true, the session can be kept connected with "Keep connected" (`<K>`)
+    pub fn new<S: Into<String>>(title: S, color: Color, interrupts: bool, parkable: bool) -> Self {
+        let parkable = parkable && !interrupts;
+        Self {
+            component: Buttons::default()
+                .borders(
+                    Borders::default()
+                        .color(color)
+                        .modifiers(BorderType::Rounded),
+                )
+                .foreground(color)
+                .choices(match (interrupts, parkable) {
+                    (true, _) => &["Disconnect anyway", "Cancel"],
+                    (false, true) => &["Yes", "Keep connected", "No"],
+                    (false, false) => &["Yes", "No"],
+                })
+                .value(usize::from(interrupts))
+                .title(title, Alignment::Center),
+            interrupts,
+            parkable,
+        }
+    }
+}
+
+impl Component<Msg, NoUserEvent> for DisconnectPopup {
+    fn on(&mut self, ev: Event<NoUserEvent>) -> Option<Msg> {
+        match ev {
+            // clicking a choice submits it
+            Event::Mouse(ev) if self.component.click(&ev).is_some() => {
+                self.on(Event::Keyboard(KeyEvent::from(Key::Enter)))
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Left, ..
+            }) => {
+                self.perform(Cmd::Move(Direction::Left));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Right, ..
+            }) => {
+                self.perform(Cmd::Move(Direction::Right));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent { code: Key::Esc, .. }) => {
+                Some(Msg::Ui(UiMsg::CloseDisconnectPopup))
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Char('y'),
+                modifiers: KeyModifiers::NONE,
+            }) if !self.interrupts => Some(Msg::Ui(UiMsg::Disconnect)),
+            Event::Keyboard(KeyEvent {
+                code: Key::Char('k'),
+                modifiers: KeyModifiers::NONE,
+            }) if self.parkable => Some(Msg::Ui(UiMsg::ParkSession)),
+            Event::Keyboard(KeyEvent {
+                code: Key::Char('n'),
+                modifiers: KeyModifiers::NONE,
+            }) => Some(Msg::Ui(UiMsg::CloseDisconnectPopup)),
+            Event::Keyboard(KeyEvent {
+                code: Key::Enter, ..
+            }) => match self.perform(Cmd::Submit) {
+                CmdResult::Submit(State::One(StateValue::Usize(0))) => {
+                    Some(Msg::Ui(UiMsg::Disconnect))
+                }
+                CmdResult::Submit(State::One(StateValue::Usize(1))) if self.parkable => {
+                    Some(Msg::Ui(UiMsg::ParkSession))
+                }
+                _ => Some(Msg::Ui(UiMsg::CloseDisconnectPopup)),
+            },
+            _ => None,
+        }
+    }
+}
+
+#[derive(MockComponent)]
+pub struct ErrorPopup {
+    component: Paragraph,
+}
+
+impl ErrorPopup {
+    pub fn new<S: AsRef<str>>(text: S, color: Color) -> Self {
+        Self {
+            component: Paragraph::default()
+                .alignment(Alignment::Center)
+                .borders(
+                    Borders::default()
+                        .color(color)
+                        .modifiers(BorderType::Rounded),
+                )
+                .foreground(color)
+                .text(&[TextSpan::from(text.as_ref())])
+                .wrap(true),
+        }
+    }
+}
+
+impl Component<Msg, NoUserEvent> for ErrorPopup {
+    fn on(&mut self, ev: Event<NoUserEvent>) -> Option<Msg> {
+        match ev {
+            Event::Keyboard(KeyEvent {
+                code: Key::Esc | Key::Enter,
+                ..
+            }) => Some(Msg::Ui(UiMsg::CloseErrorPopup)),
+            _ => None,
+        }
+    }
+}
+
+#[derive(MockComponent)]
+pub struct ExecPopup {
+    component: Input,
+    /// If true, the command is not wrapped into the remote shell
+    raw: bool,
+    /// Commands executed previously, from the most recent one
+    history: Vec<String>,
+    /// Index of the history entry being displayed, if any
+    history_index: Option<usize>,
+    /// Command typed before browsing the history
+    draft: String,
+}
+
+impl ExecPopup {
+    pub fn new(color: Color, history: Vec<String>) -> Self {
+        Self {
+            raw: false,
+            history,
+            history_index: None,
+            draft: String::new(),
+            component: Input::default()
+                .borders(
+                    Borders::default()
+                        .color(color)
+                        .modifiers(BorderType::Rounded),
+                )
+                .foreground(color)
+                .input_type(InputType::Text)
+                .placeholder("ps a", Style::default().fg(Color::Rgb(128, 128, 128)))
+                .title(Self::title(false), Alignment::Center),
+        }
+    }
+
+    fn title(raw: bool) -> &'static str {
+        match raw {
+            true => "Execute raw command (<CTRL+R> to wrap into remote shell)",
+            false => "Execute command (<CTRL+R> for raw mode)",
+        }
+    }
+
+    /// Toggle raw mode
+    fn toggle_raw(&mut self) {
+        self.raw = !self.raw;
+        self.attr(
+            Attribute::Title,
+            AttrValue::Title((Self::title(self.raw).to_string(), Alignment::Center)),
+        );
+    }
+
+    fn input(&self) -> String {
+        match self.state() {
+            State::One(StateValue::String(input)) => input,
+            _ => String::new(),
+        }
+    }
+
+    fn set_input(&mut self, input: String) {
+        self.attr(Attribute::Value, AttrValue::String(input));
+    }
+
+    /// Display the previous command of the history
+    fn history_prev(&mut self) {
+        let index = match self.history_index {
+            None if self.history.is_empty() => return,
+            None => {
+                self.draft = self.input();
+                0
+            }
+            Some(index) => (index + 1).min(self.history.len() - 1),
+        };
+        self.history_index = Some(index);
+        self.set_input(self.history[index].clone());
+    }
+
+    /// Display the next command of the history, or the typed one once the end of the history is reached
+    fn history_next(&mut self) {
+        match self.history_index {
+            None => {}
+            Some(0) => {
+                self.history_index = None;
+                let draft = std::mem::take(&mut self.draft);
+                self.set_input(draft);
+            }
+            Some(index) => {
+                self.history_index = Some(index - 1);
+                self.set_input(self.history[index - 1].clone());
+            }
+        }
+    }
+
+    /// Complete the input with the most recent command of the history starting with it
+    fn complete(&mut self) {
+        let input = self.input();
+        if input.is_empty() {
+            return;
+        }
+        if let Some(command) = self
+            .history
+            .iter()
+            .find(|x| x.len() > input.len() && x.starts_with(input.as_str()))
+            .cloned()
+        {
+            self.set_input(command);
+        }
+    }
+}
+
+impl Component<Msg, NoUserEvent> for ExecPopup {
+    fn on(&mut self, ev: Event<NoUserEvent>) -> Option<Msg> {
+        match ev {
+            Event::Keyboard(KeyEvent {
+                code: Key::Left, ..
+            }) => {
+                self.perform(Cmd::Move(Direction::Left));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Right, ..
+            }) => {
+                self.perform(Cmd::Move(Direction::Right));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Home, ..
+            }) => {
+                self.perform(Cmd::GoTo(Position::Begin));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent { code: Key::End, .. }) => {
+                self.perform(Cmd::GoTo(Position::End));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Delete, ..
+            }) => {
+                self.perform(Cmd::Cancel);
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Backspace,
+                ..
+            }) => {
+                self.perform(Cmd::Delete);
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Char('r'),
+                modifiers: KeyModifiers::CONTROL,
+            }) => {
+                self.toggle_raw();
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent { code: Key::Up, .. }) => {
+                self.history_prev();
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Down, ..
+            }) => {
+                self.history_next();
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent { code: Key::Tab, .. }) => {
+                self.complete();
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Char(ch),
+                ..
+            }) => {
+                self.perform(Cmd::Type(ch));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Enter, ..
+            }) => match self.state() {
+                State::One(StateValue::String(i)) if self.raw => {
+                    Some(Msg::Transfer(TransferMsg::ExecuteRawCmd(i)))
+                }
+                State::One(StateValue::String(i)) => {
+                    Some(Msg::Transfer(TransferMsg::ExecuteCmd(i)))
+                }
+                _ => Some(Msg::None),
+            },
+            Event::Keyboard(KeyEvent { code: Key::Esc, .. }) => {
+                Some(Msg::Ui(UiMsg::CloseExecPopup))
+            }
+            _ => None,
+        }
+    }
+}
+
+#[derive(MockComponent)]
+pub struct ExecOutputPopup {
+    component: List,
+}
+
+impl ExecOutputPopup {
+    /// Instantiates the popup showing the `output` of `command`, exited with `exitcode` if known
+    pub fn new(command: &str, exitcode: Option<u32>, output: &str, color: Color) -> Self {
+        let title = match exitcode {
+            Some(rc) => format!("{command} (exitcode: {rc})"),
+            None => command.to_string(),
+        };
+        let mut rows = TableBuilder::default();
+        for (i, line) in output.lines().enumerate() {
+            if i > 0 {
+                rows.add_row();
+            }
+            rows.add_col(TextSpan::from(line));
+        }
+        Self {
+            component: List::default()
+                .borders(
+                    Borders::default()
+                        .color(color)
+                        .modifiers(BorderType::Rounded),
+                )
+                .scroll(true)
+                .step(8)
+                .highlighted_str("? ")
+                .title(title, Alignment::Center)
+                .rewind(true)
+                .rows(rows.build()),
+        }
+    }
+}
+
+impl Component<Msg, NoUserEvent> for ExecOutputPopup {
+    fn on(&mut self, ev: Event<NoUserEvent>) -> Option<Msg> {
+        match ev {
+            Event::Keyboard(KeyEvent {
+                code: Key::Esc | Key::Enter,
+                ..
+            }) => Some(Msg::Ui(UiMsg::CloseExecOutputPopup)),
+            Event::Keyboard(KeyEvent {
+                code: Key::Down, ..
+            }) => {
+                self.perform(Cmd::Move(Direction::Down));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent { code: Key::Up, .. }) => {
+                self.perform(Cmd::Move(Direction::Up));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::PageDown,
+                ..
+            }) => {
+                self.perform(Cmd::Scroll(Direction::Down));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::PageUp, ..
+            }) => {
+                self.perform(Cmd::Scroll(Direction::Up));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Home, ..
+            }) => {
+                self.perform(Cmd::GoTo(Position::Begin));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent { code: Key::End, .. }) => {
+                self.perform(Cmd::GoTo(Position::End));
+                Some(Msg::None)
+            }
+            _ => None,
+        }
+    }
+}
+
+#[derive(MockComponent)]
+pub struct FatalPopup {
+    component: Paragraph,
+}
+
+impl FatalPopup {
+    pub fn new<S: AsRef<str>>(text: S, color: Color) -> Self {
+        Self {
+            component: Paragraph::default()
+                .alignment(Alignment::Center)
                 .borders(
                     Borders::default()
                         .color(color)
@@ -471,9 +1632,131 @@ impl Component<Msg, NoUserEvent> for FatalPopup {
     fn on(&mut self, ev: Event<NoUserEvent>) -> Option<Msg> {
         match ev {
             Event::Keyboard(KeyEvent {
-                code: Key::Esc | Key::Enter,
-                ..
-            }) => Some(Msg::Ui(UiMsg::CloseFatalPopup)),
+                code: Key::Esc | Key::Enter,
+                ..
+            }) => Some(Msg::Ui(UiMsg::CloseFatalPopup)),
+            _ => None,
+        }
+    }
+}
+
+#[derive(MockComponent)]
+pub struct FavoritesPopup {
+    component: List,
+}
+
+impl FavoritesPopup {
+    /// Make the popup listing `favorites`, along with whether they exist on the remote
+    pub fn new(favorites: &[(std::path::PathBuf, bool)], color: Color) -> Self {
+        Self {
+            component: List::default()
+                .borders(
+                    Borders::default()
+                        .color(color)
+                        .modifiers(BorderType::Rounded),
+                )
+                .rewind(true)
+                .scroll(true)
+                .step(4)
+                .highlighted_color(color)
+                .highlighted_str("➤ ")
+                .title(
+                    "Favorites (<ENTER> go to, <O> open, <D> download, <DEL> remove)",
+                    Alignment::Center,
+                )
+                .rows(
+                    favorites
+                        .iter()
+                        .map(|(path, exists)| {
+                            let mut row = vec![TextSpan::from(path.to_string_lossy())];
+                            if !exists {
+                                row.push(TextSpan::from("  (not found)").fg(Color::Red).italic());
+                            }
+                            row
+                        })
+                        .collect(),
+                ),
+        }
+    }
+
+    fn selected(&self) -> Option<usize> {
+        match self.component.state() {
+            State::One(StateValue::Usize(idx)) => Some(idx),
+            _ => None,
+        }
+    }
+}
+
+impl Component<Msg, NoUserEvent> for FavoritesPopup {
+    fn on(&mut self, ev: Event<NoUserEvent>) -> Option<Msg> {
+        match ev {
+            Event::Keyboard(KeyEvent { code: Key::Esc, .. }) => {
+                Some(Msg::Ui(UiMsg::CloseFavoritesPopup))
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Down, ..
+            }) => {
+                self.perform(Cmd::Move(Direction::Down));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent { code: Key::Up, .. }) => {
+                self.perform(Cmd::Move(Direction::Up));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::PageDown,
+                ..
+            }) => {
+                self.perform(Cmd::Scroll(Direction::Down));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::PageUp, ..
+            }) => {
+                self.perform(Cmd::Scroll(Direction::Up));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Home, ..
+            }) => {
+                self.perform(Cmd::GoTo(Position::Begin));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent { code: Key::End, .. }) => {
+                self.perform(Cmd::GoTo(Position::End));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Enter, ..
+            }) => Some(
+                self.selected()
+                    .map(|idx| Msg::Transfer(TransferMsg::GoToFavorite(idx)))
+                    .unwrap_or(Msg::None),
+            ),
+            Event::Keyboard(KeyEvent {
+                code: Key::Char('o'),
+                modifiers: KeyModifiers::NONE,
+            }) => Some(
+                self.selected()
+                    .map(|idx| Msg::Transfer(TransferMsg::OpenFavorite(idx)))
+                    .unwrap_or(Msg::None),
+            ),
+            Event::Keyboard(KeyEvent {
+                code: Key::Char('d'),
+                modifiers: KeyModifiers::NONE,
+            }) => Some(
+                self.selected()
+                    .map(|idx| Msg::Transfer(TransferMsg::DownloadFavorite(idx)))
+                    .unwrap_or(Msg::None),
+            ),
+            Event::Keyboard(KeyEvent {
+                code: Key::Char('e') | Key::Delete,
+                modifiers: KeyModifiers::NONE,
+            }) => Some(
+                self.selected()
+                    .map(|idx| Msg::Transfer(TransferMsg::RemoveFavorite(idx)))
+                    .unwrap_or(Msg::None),
+            ),
             _ => None,
         }
     }
@@ -485,7 +1768,9 @@ pub struct FileInfoPopup {
 }
 
 impl FileInfoPopup {
-    pub fn new(file: &File) -> Self {
+    /// Instantiates the file info popup; `special` is the type of the file if it is a special file,
+    /// `xattrs` are the extended attributes of the file, if any
+    pub fn new(file: &File, special: Option<SpecialFileType>, xattrs: &[Xattr]) -> Self {
         let mut texts: TableBuilder = TableBuilder::default();
         // Abs path
         let real_path = file.metadata().symlink.as_deref();
@@ -497,17 +1782,26 @@ impl FileInfoPopup {
         texts
             .add_col(TextSpan::from("Path: "))
             .add_col(TextSpan::new(path.as_str()).fg(Color::Yellow));
-        if let Some(filetype) = file.extension() {
+        if let Some(special) = special {
+            // Special files have no extension nor size
             texts
                 .add_row()
                 .add_col(TextSpan::from("File type: "))
-                .add_col(TextSpan::new(filetype).fg(Color::LightGreen));
+                .add_col(TextSpan::new(special.to_string()).fg(Color::LightMagenta));
+        } else {
+            if let Some(filetype) = file.extension() {
+                texts
+                    .add_row()
+                    .add_col(TextSpan::from("File type: "))
+                    .add_col(TextSpan::new(filetype).fg(Color::LightGreen));
+            }
+            let (bsize, size): (ByteSize, u64) =
+                (ByteSize(file.metadata().size), file.metadata().size);
+            texts
+                .add_row()
+                .add_col(TextSpan::from("Size: "))
+                .add_col(TextSpan::new(format!("{bsize} ({size})").as_str()).fg(Color::Cyan));
         }
-        let (bsize, size): (ByteSize, u64) = (ByteSize(file.metadata().size), file.metadata().size);
-        texts
-            .add_row()
-            .add_col(TextSpan::from("Size: "))
-            .add_col(TextSpan::new(format!("{bsize} ({size})").as_str()).fg(Color::Cyan));
         let atime: String = fmt_time(
             file.metadata().accessed.unwrap_or(UNIX_EPOCH),
             "%b %d %Y %H:%M:%S",
@@ -562,6 +1856,24 @@ impl FileInfoPopup {
             .add_row()
             .add_col(TextSpan::from("Group: "))
             .add_col(TextSpan::new(group.as_str()).fg(Color::Blue));
+        for (i, attr) in xattrs.iter().enumerate() {
+            texts
+                .add_row()
+                .add_col(TextSpan::from(match i {
+                    0 => "Extended attributes: ",
+                    _ => "",
+                }))
+                .add_col(
+                    TextSpan::new(format!("{} = {}", attr.name, attr.fmt_value()).as_str())
+                        .fg(Color::LightMagenta),
+                );
+        }
+        if file.is_file() && special.is_none() {
+            texts
+                .add_row()
+                .add_col(TextSpan::from("Checksum: "))
+                .add_col(TextSpan::new("<M> MD5, <S> SHA256").fg(Color::Cyan));
+        }
         Self {
             component: List::default()
                 .borders(Borders::default().modifiers(BorderType::Rounded))
@@ -579,6 +1891,124 @@ impl Component<Msg, NoUserEvent> for FileInfoPopup {
                 code: Key::Esc | Key::Enter,
                 ..
             }) => Some(Msg::Ui(UiMsg::CloseFileInfoPopup)),
+            Event::Keyboard(KeyEvent {
+                code: Key::Char('m'),
+                modifiers: KeyModifiers::NONE,
+            }) => Some(Msg::Transfer(TransferMsg::ComputeChecksum(
+                ChecksumAlgorithm::Md5,
+            ))),
+            Event::Keyboard(KeyEvent {
+                code: Key::Char('s'),
+                modifiers: KeyModifiers::NONE,
+            }) => Some(Msg::Transfer(TransferMsg::ComputeChecksum(
+                ChecksumAlgorithm::Sha256,
+            ))),
+            _ => None,
+        }
+    }
+}
+
+/// Width of the column of the keys in the keybindings popup
+const KEYBINDINGS_KEY_WIDTH: usize = 18;
+
+#[derive(MockComponent)]
+pub struct HostKeyFingerprintPopup {
+    component: Paragraph,
+}
+
+impl HostKeyFingerprintPopup {
+    pub fn new(
+        host: &str,
+        key_type: &str,
+        fingerprint: &str,
+        known_hosts: &Path,
+        color: Color,
+    ) -> Self {
+        Self {
+            component: Paragraph::default()
+                .alignment(Alignment::Center)
+                .borders(
+                    Borders::default()
+                        .color(color)
+                        .modifiers(BorderType::Rounded),
+                )
+                .foreground(color)
+                .text(&[
+                    TextSpan::from(format!(
+                        "The authenticity of host '{host}' can't be established."
+                    )),
+                    TextSpan::from(format!("{key_type} key fingerprint is {fingerprint}")),
+                    TextSpan::from(format!(
+                        "If accepted, the key is saved to {}",
+                        known_hosts.display()
+                    )),
+                ])
+                .title("Unknown host key", Alignment::Center)
+                .wrap(true),
+        }
+    }
+}
+
+impl Component<Msg, NoUserEvent> for HostKeyFingerprintPopup {
+    fn on(&mut self, _ev: Event<NoUserEvent>) -> Option<Msg> {
+        None
+    }
+}
+
+#[derive(MockComponent)]
+pub struct HostKeyPopup {
+    component: Buttons,
+}
+
+impl HostKeyPopup {
+    pub fn new(color: Color) -> Self {
+        Self {
+            component: Buttons::default()
+                .borders(
+                    Borders::default()
+                        .color(color)
+                        .modifiers(BorderType::Rounded),
+                )
+                .foreground(color)
+                .choices(&["Accept", "Reject"])
+                .value(1)
+                .title("Accept the host key and connect?", Alignment::Center),
+        }
+    }
+}
+
+impl Component<Msg, NoUserEvent> for HostKeyPopup {
+    fn on(&mut self, ev: Event<NoUserEvent>) -> Option<Msg> {
+        match ev {
+            // clicking a choice submits it
+            Event::Mouse(ev) if self.component.click(&ev).is_some() => {
+                self.on(Event::Keyboard(KeyEvent::from(Key::Enter)))
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Left, ..
+            }) => {
+                self.perform(Cmd::Move(Direction::Left));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Right, ..
+            }) => {
+                self.perform(Cmd::Move(Direction::Right));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent { code: Key::Esc, .. }) => Some(Msg::Ui(UiMsg::RejectHostKey)),
+            Event::Keyboard(KeyEvent {
+                code: Key::Enter, ..
+            }) => {
+                if matches!(
+                    self.perform(Cmd::Submit),
+                    CmdResult::Submit(State::One(StateValue::Usize(0)))
+                ) {
+                    Some(Msg::Ui(UiMsg::AcceptHostKey))
+                } else {
+                    Some(Msg::Ui(UiMsg::RejectHostKey))
+                }
+            }
             _ => None,
         }
     }
@@ -590,7 +2020,198 @@ pub struct KeybindingsPopup {
 }
 
 impl KeybindingsPopup {
-    pub fn new(key_color: Color) -> Self {
+    pub fn new(key_color: Color, keymap: &Keymap) -> Self {
+        let keys: Vec<(String, &str)> = vec![
+            (String::from("<ESC>"), "Disconnect"),
+            (String::from("<BACKSPACE>"), "Go to previous directory"),
+            (String::from("<TAB|RIGHT|LEFT>"), "Change explorer tab"),
+            (String::from("<UP/DOWN>"), "Move up/down in list"),
+            (String::from("<ENTER>"), "Enter directory"),
+            (
+                keymap.fmt_bindings(KeyAction::Transfer),
+                "Upload/Download file",
+            ),
+            (
+                keymap.fmt_bindings(KeyAction::Enqueue),
+                "Queue upload/download in background",
+            ),
+            (
+                String::from("<BACKTAB>"),
+                "Switch between explorer and log window",
+            ),
+            (
+                keymap.fmt_bindings(KeyAction::ToggleHidden),
+                "Toggle hidden files",
+            ),
+            (
+                keymap.fmt_bindings(KeyAction::FileSorting),
+                "Change file sorting mode",
+            ),
+            (keymap.fmt_bindings(KeyAction::Copy), "Copy"),
+            (keymap.fmt_bindings(KeyAction::Mkdir), "Make directory"),
+            (keymap.fmt_bindings(KeyAction::FuzzySearch), "Search files"),
+            (keymap.fmt_bindings(KeyAction::Goto), "Go to path"),
+            (String::from("<H|F1>"), "Show help"),
+            (
+                keymap.fmt_bindings(KeyAction::FileInfo),
+                "Show info about selected file",
+            ),
+            (
+                keymap.fmt_bindings(KeyAction::QuickCommands),
+                "Run a quick command of the bookmark",
+            ),
+            (
+                keymap.fmt_bindings(KeyAction::Symlink),
+                "Create symlink pointing to the current selected entry",
+            ),
+            (
+                keymap.fmt_bindings(KeyAction::Reload),
+                "Reload directory content",
+            ),
+            (keymap.fmt_bindings(KeyAction::Mark), "Select file"),
+            (keymap.fmt_bindings(KeyAction::NewFile), "Create new file"),
+            (
+                keymap.fmt_bindings(KeyAction::OpenTextFile),
+                "Open text file with preferred editor",
+            ),
+            (keymap.fmt_bindings(KeyAction::LogPanel), "Toggle log panel"),
+            (String::from("<Q|F10>"), "Quit termscp"),
+            (keymap.fmt_bindings(KeyAction::Rename), "Rename file"),
+            (keymap.fmt_bindings(KeyAction::SaveAs), "Save file as"),
+            (
+                keymap.fmt_bindings(KeyAction::Watch),
+                "Watch/unwatch file changes",
+            ),
+            (
+                keymap.fmt_bindings(KeyAction::ParentDirectory),
+                "Go to parent directory",
+            ),
+            (
+                keymap.fmt_bindings(KeyAction::Open),
+                "Open file with default application for file type",
+            ),
+            (
+                keymap.fmt_bindings(KeyAction::OpenWith),
+                "Open file with specified application",
+            ),
+            (
+                keymap.fmt_bindings(KeyAction::Exec),
+                "Execute shell command",
+            ),
+            (
+                keymap.fmt_bindings(KeyAction::SyncBrowsing),
+                "Toggle synchronized browsing",
+            ),
+            (
+                keymap.fmt_bindings(KeyAction::Chmod),
+                "Change file permissions",
+            ),
+            (
+                keymap.fmt_bindings(KeyAction::Filter),
+                "Filter files or search contents",
+            ),
+            (
+                keymap.fmt_bindings(KeyAction::ShowDelete),
+                "Delete selected file",
+            ),
+            (
+                keymap.fmt_bindings(KeyAction::ToggleFavorite),
+                "Pin/unpin remote file as favorite",
+            ),
+            (
+                keymap.fmt_bindings(KeyAction::SelectAll),
+                "Select all files",
+            ),
+            (
+                keymap.fmt_bindings(KeyAction::DeselectAll),
+                "Deselect all files",
+            ),
+            (String::from("<ALT+BACKSPACE>"), "Show previous directories"),
+            (
+                keymap.fmt_bindings(KeyAction::CompareChecksums),
+                "Compare SHA256 with the file on the other host",
+            ),
+            (
+                keymap.fmt_bindings(KeyAction::Duplicate),
+                "Duplicate selected files",
+            ),
+            (
+                keymap.fmt_bindings(KeyAction::Cut),
+                "Cut selected files, to move them",
+            ),
+            (
+                keymap.fmt_bindings(KeyAction::Paste),
+                "Move cut files to the current directory",
+            ),
+            (
+                keymap.fmt_bindings(KeyAction::Compress),
+                "Compress selected files into an archive",
+            ),
+            (
+                keymap.fmt_bindings(KeyAction::Extract),
+                "Extract selected archive here",
+            ),
+            (
+                keymap.fmt_bindings(KeyAction::BookmarkDirectory),
+                "Bookmark current directory",
+            ),
+            (
+                keymap.fmt_bindings(KeyAction::DirectoryBookmarks),
+                "Go to a bookmarked directory",
+            ),
+            (
+                keymap.fmt_bindings(KeyAction::Favorites),
+                "Show remote favorites",
+            ),
+            (
+                keymap.fmt_bindings(KeyAction::Mirror),
+                "Mirror to the other host, deleting extraneous files",
+            ),
+            (
+                keymap.fmt_bindings(KeyAction::OpenUrl),
+                "Open remote file URL in browser",
+            ),
+            (
+                keymap.fmt_bindings(KeyAction::Preview),
+                "Preview remote file",
+            ),
+            (
+                keymap.fmt_bindings(KeyAction::Sync),
+                "Sync to the other host, copying only changed files",
+            ),
+            (
+                keymap.fmt_bindings(KeyAction::CopyUrl),
+                "Copy remote file URL to clipboard",
+            ),
+            (String::from("<CTRL+C>"), "Interrupt file transfer"),
+            (
+                keymap.fmt_bindings(KeyAction::GotoClipboard),
+                "Go to path in clipboard",
+            ),
+            (String::from("<CTRL+Q>"), "Show transfer queue"),
+            (
+                keymap.fmt_bindings(KeyAction::WatchedPaths),
+                "Show watched paths",
+            ),
+            (
+                keymap.fmt_bindings(KeyAction::Undo),
+                "Undo the last rename/move",
+            ),
+            (keymap.fmt_bindings(KeyAction::SwapPanels), "Swap panels"),
+            (String::from("<CTRL+Z>"), "Suspend termscp"),
+        ];
+        let mut rows = TableBuilder::default();
+        for (i, (key, description)) in keys.iter().enumerate() {
+            if i > 0 {
+                rows.add_row();
+            }
+            let padding = KEYBINDINGS_KEY_WIDTH.saturating_sub(key.len()).max(1);
+            rows.add_col(TextSpan::new(key).bold().fg(key_color))
+                .add_col(TextSpan::from(format!(
+                    "{}{description}",
+                    " ".repeat(padding)
+                )));
+        }
         Self {
             component: List::default()
                 .borders(Borders::default().modifiers(BorderType::Rounded))
@@ -599,134 +2220,7 @@ impl KeybindingsPopup {
                 .highlighted_str("? ")
                 .title("Keybindings", Alignment::Center)
                 .rewind(true)
-                .rows(
-                    TableBuilder::default()
-                        .add_col(TextSpan::new("<ESC>").bold().fg(key_color))
-                        .add_col(TextSpan::from("             Disconnect"))
-                        .add_row()
-                        .add_col(TextSpan::new("<BACKSPACE>").bold().fg(key_color))
-                        .add_col(TextSpan::from("       Go to previous directory"))
-                        .add_row()
-                        .add_col(TextSpan::new("<TAB|RIGHT|LEFT>").bold().fg(key_color))
-                        .add_col(TextSpan::from("  Change explorer tab"))
-                        .add_row()
-                        .add_col(TextSpan::new("<UP/DOWN>").bold().fg(key_color))
-                        .add_col(TextSpan::from("         Move up/down in list"))
-                        .add_row()
-                        .add_col(TextSpan::new("<ENTER>").bold().fg(key_color))
-                        .add_col(TextSpan::from("           Enter directory"))
-                        .add_row()
-                        .add_col(TextSpan::new("<SPACE>").bold().fg(key_color))
-                        .add_col(TextSpan::from("           Upload/Download file"))
-                        .add_row()
-                        .add_col(TextSpan::new("<BACKTAB>").bold().fg(key_color))
-                        .add_col(TextSpan::from(
-                            "         Switch between explorer and log window",
-                        ))
-                        .add_row()
-                        .add_col(TextSpan::new("<A>").bold().fg(key_color))
-                        .add_col(TextSpan::from("               Toggle hidden files"))
-                        .add_row()
-                        .add_col(TextSpan::new("<B>").bold().fg(key_color))
-                        .add_col(TextSpan::from("               Change file sorting mode"))
-                        .add_row()
-                        .add_col(TextSpan::new("<C|F5>").bold().fg(key_color))
-                        .add_col(TextSpan::from("            Copy"))
-                        .add_row()
-                        .add_col(TextSpan::new("<D|F7>").bold().fg(key_color))
-                        .add_col(TextSpan::from("            Make directory"))
-                        .add_row()
-                        .add_col(TextSpan::new("<F>").bold().fg(key_color))
-                        .add_col(TextSpan::from("               Search files"))
-                        .add_row()
-                        .add_col(TextSpan::new("<G>").bold().fg(key_color))
-                        .add_col(TextSpan::from("               Go to path"))
-                        .add_row()
-                        .add_col(TextSpan::new("<H|F1>").bold().fg(key_color))
-                        .add_col(TextSpan::from("            Show help"))
-                        .add_row()
-                        .add_col(TextSpan::new("<I>").bold().fg(key_color))
-                        .add_col(TextSpan::from(
-                            "               Show info about selected file",
-                        ))
-                        .add_row()
-                        .add_col(TextSpan::new("<K>").bold().fg(key_color))
-                        .add_col(TextSpan::from(
-                            "               Create symlink pointing to the current selected entry",
-                        ))
-                        .add_row()
-                        .add_col(TextSpan::new("<L>").bold().fg(key_color))
-                        .add_col(TextSpan::from("               Reload directory content"))
-                        .add_row()
-                        .add_col(TextSpan::new("<M>").bold().fg(key_color))
-                        .add_col(TextSpan::from("               Select file"))
-                        .add_row()
-                        .add_col(TextSpan::new("<N>").bold().fg(key_color))
-                        .add_col(TextSpan::from("               Create new file"))
-                        .add_row()
-                        .add_col(TextSpan::new("<O|F4>").bold().fg(key_color))
-                        .add_col(TextSpan::from(
-                            "            Open text file with preferred editor",
-                        ))
-                        .add_row()
-                        .add_col(TextSpan::new("<P>").bold().fg(key_color))
-                        .add_col(TextSpan::from("               Toggle log panel"))
-                        .add_row()
-                        .add_col(TextSpan::new("<Q|F10>").bold().fg(key_color))
-                        .add_col(TextSpan::from("           Quit termscp"))
-                        .add_row()
-                        .add_col(TextSpan::new("<R|F6>").bold().fg(key_color))
-                        .add_col(TextSpan::from("            Rename file"))
-                        .add_row()
-                        .add_col(TextSpan::new("<S|F2>").bold().fg(key_color))
-                        .add_col(TextSpan::from("            Save file as"))
-                        .add_row()
-                        .add_col(TextSpan::new("<T>").bold().fg(key_color))
-                        .add_col(TextSpan::from("               Watch/unwatch file changes"))
-                        .add_row()
-                        .add_col(TextSpan::new("<U>").bold().fg(key_color))
-                        .add_col(TextSpan::from("               Go to parent directory"))
-                        .add_row()
-                        .add_col(TextSpan::new("<V|F3>").bold().fg(key_color))
-                        .add_col(TextSpan::from(
-                            "            Open file with default application for file type",
-                        ))
-                        .add_row()
-                        .add_col(TextSpan::new("<W>").bold().fg(key_color))
-                        .add_col(TextSpan::from(
-                            "               Open file with specified application",
-                        ))
-                        .add_row()
-                        .add_col(TextSpan::new("<X>").bold().fg(key_color))
-                        .add_col(TextSpan::from("               Execute shell command"))
-                        .add_row()
-                        .add_col(TextSpan::new("<Y>").bold().fg(key_color))
-                        .add_col(TextSpan::from(
-                            "               Toggle synchronized browsing",
-                        ))
-                        .add_row()
-                        .add_col(TextSpan::new("<Z>").bold().fg(key_color))
-                        .add_col(TextSpan::from("               Change file permissions"))
-                        .add_row()
-                        .add_col(TextSpan::new("</>").bold().fg(key_color))
-                        .add_col(TextSpan::from("               Filter files"))
-                        .add_row()
-                        .add_col(TextSpan::new("<DEL|F8|E>").bold().fg(key_color))
-                        .add_col(TextSpan::from("        Delete selected file"))
-                        .add_row()
-                        .add_col(TextSpan::new("<CTRL+A>").bold().fg(key_color))
-                        .add_col(TextSpan::from("          Select all files"))
-                        .add_row()
-                        .add_col(TextSpan::new("<ALT+A>").bold().fg(key_color))
-                        .add_col(TextSpan::from("          Deselect all files"))
-                        .add_row()
-                        .add_col(TextSpan::new("<CTRL+C>").bold().fg(key_color))
-                        .add_col(TextSpan::from("          Interrupt file transfer"))
-                        .add_row()
-                        .add_col(TextSpan::new("<CTRL+T>").bold().fg(key_color))
-                        .add_col(TextSpan::from("          Show watched paths"))
-                        .build(),
-                ),
+                .rows(rows.build()),
         }
     }
 }
@@ -739,37 +2233,211 @@ impl Component<Msg, NoUserEvent> for KeybindingsPopup {
                 ..
             }) => Some(Msg::Ui(UiMsg::CloseKeybindingsPopup)),
             Event::Keyboard(KeyEvent {
-                code: Key::Down, ..
+                code: Key::Down, ..
+            }) => {
+                self.perform(Cmd::Move(Direction::Down));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent { code: Key::Up, .. }) => {
+                self.perform(Cmd::Move(Direction::Up));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::PageDown,
+                ..
+            }) => {
+                self.perform(Cmd::Scroll(Direction::Down));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::PageUp, ..
+            }) => {
+                self.perform(Cmd::Scroll(Direction::Up));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Home, ..
+            }) => {
+                self.perform(Cmd::GoTo(Position::Begin));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent { code: Key::End, .. }) => {
+                self.perform(Cmd::GoTo(Position::End));
+                Some(Msg::None)
+            }
+            _ => None,
+        }
+    }
+}
+
+#[derive(MockComponent)]
+pub struct LogExportPopup {
+    component: Input,
+}
+
+impl LogExportPopup {
+    /// Instantiates the popup asking the path to export the log to, pre-filled with `path`
+    pub fn new(path: &Path, color: Color) -> Self {
+        Self {
+            component: Input::default()
+                .borders(
+                    Borders::default()
+                        .color(color)
+                        .modifiers(BorderType::Rounded),
+                )
+                .foreground(color)
+                .input_type(InputType::Text)
+                .value(path.to_string_lossy())
+                .placeholder(
+                    "/tmp/termscp.log",
+                    Style::default().fg(Color::Rgb(128, 128, 128)),
+                )
+                .title("Export log to…", Alignment::Center),
+        }
+    }
+}
+
+impl Component<Msg, NoUserEvent> for LogExportPopup {
+    fn on(&mut self, ev: Event<NoUserEvent>) -> Option<Msg> {
+        match ev {
+            Event::Keyboard(KeyEvent {
+                code: Key::Left, ..
+            }) => {
+                self.perform(Cmd::Move(Direction::Left));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Right, ..
+            }) => {
+                self.perform(Cmd::Move(Direction::Right));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Home, ..
+            }) => {
+                self.perform(Cmd::GoTo(Position::Begin));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent { code: Key::End, .. }) => {
+                self.perform(Cmd::GoTo(Position::End));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Delete, ..
+            }) => {
+                self.perform(Cmd::Cancel);
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Backspace,
+                ..
+            }) => {
+                self.perform(Cmd::Delete);
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Char(ch),
+                ..
+            }) => {
+                self.perform(Cmd::Type(ch));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Enter, ..
+            }) => match self.state() {
+                State::One(StateValue::String(path)) => {
+                    Some(Msg::Transfer(TransferMsg::ExportLog(path)))
+                }
+                _ => Some(Msg::None),
+            },
+            Event::Keyboard(KeyEvent { code: Key::Esc, .. }) => {
+                Some(Msg::Ui(UiMsg::CloseLogExportPopup))
+            }
+            _ => None,
+        }
+    }
+}
+
+#[derive(MockComponent)]
+pub struct LogSearchPopup {
+    component: Input,
+}
+
+impl LogSearchPopup {
+    /// Instantiates the popup asking the text to search in the log, pre-filled with the current `search`
+    pub fn new(search: &str, color: Color) -> Self {
+        Self {
+            component: Input::default()
+                .borders(
+                    Borders::default()
+                        .color(color)
+                        .modifiers(BorderType::Rounded),
+                )
+                .foreground(color)
+                .input_type(InputType::Text)
+                .value(search)
+                .placeholder(
+                    "Text to search (empty to clear the search)",
+                    Style::default().fg(Color::Rgb(128, 128, 128)),
+                )
+                .title("Search log", Alignment::Center),
+        }
+    }
+}
+
+impl Component<Msg, NoUserEvent> for LogSearchPopup {
+    fn on(&mut self, ev: Event<NoUserEvent>) -> Option<Msg> {
+        match ev {
+            Event::Keyboard(KeyEvent {
+                code: Key::Left, ..
+            }) => {
+                self.perform(Cmd::Move(Direction::Left));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Right, ..
+            }) => {
+                self.perform(Cmd::Move(Direction::Right));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Home, ..
             }) => {
-                self.perform(Cmd::Move(Direction::Down));
+                self.perform(Cmd::GoTo(Position::Begin));
                 Some(Msg::None)
             }
-            Event::Keyboard(KeyEvent { code: Key::Up, .. }) => {
-                self.perform(Cmd::Move(Direction::Up));
+            Event::Keyboard(KeyEvent { code: Key::End, .. }) => {
+                self.perform(Cmd::GoTo(Position::End));
                 Some(Msg::None)
             }
             Event::Keyboard(KeyEvent {
-                code: Key::PageDown,
-                ..
+                code: Key::Delete, ..
             }) => {
-                self.perform(Cmd::Scroll(Direction::Down));
+                self.perform(Cmd::Cancel);
                 Some(Msg::None)
             }
             Event::Keyboard(KeyEvent {
-                code: Key::PageUp, ..
+                code: Key::Backspace,
+                ..
             }) => {
-                self.perform(Cmd::Scroll(Direction::Up));
+                self.perform(Cmd::Delete);
                 Some(Msg::None)
             }
             Event::Keyboard(KeyEvent {
-                code: Key::Home, ..
+                code: Key::Char(ch),
+                ..
             }) => {
-                self.perform(Cmd::GoTo(Position::Begin));
+                self.perform(Cmd::Type(ch));
                 Some(Msg::None)
             }
-            Event::Keyboard(KeyEvent { code: Key::End, .. }) => {
-                self.perform(Cmd::GoTo(Position::End));
-                Some(Msg::None)
+            Event::Keyboard(KeyEvent {
+                code: Key::Enter, ..
+            }) => match self.state() {
+                State::One(StateValue::String(search)) => Some(Msg::Ui(UiMsg::SearchLog(search))),
+                _ => Some(Msg::None),
+            },
+            Event::Keyboard(KeyEvent { code: Key::Esc, .. }) => {
+                Some(Msg::Ui(UiMsg::CloseLogSearchPopup))
             }
             _ => None,
         }
@@ -1106,23 +2774,136 @@ impl Component<Msg, NoUserEvent> for ProgressBarPartial {
     }
 }
 
+#[derive(MockComponent)]
+pub struct QuickCommandsPopup {
+    component: List,
+}
+
+impl QuickCommandsPopup {
+    pub fn new(commands: &[QuickCommand], color: Color) -> Self {
+        Self {
+            component: List::default()
+                .borders(
+                    Borders::default()
+                        .color(color)
+                        .modifiers(BorderType::Rounded),
+                )
+                .rewind(true)
+                .scroll(true)
+                .step(4)
+                .highlighted_color(color)
+                .highlighted_str("➤ ")
+                .title("Quick commands", Alignment::Center)
+                .rows(
+                    commands
+                        .iter()
+                        .enumerate()
+                        .map(|(i, x)| {
+                            let key = match i < 9 {
+                                true => format!("{} ", i + 1),
+                                false => String::from("  "),
+                            };
+                            vec![
+                                TextSpan::from(key).fg(color),
+                                TextSpan::from(format!("{}  ", x.label)).bold(),
+                                TextSpan::from(x.command.as_str()).italic(),
+                            ]
+                        })
+                        .collect(),
+                ),
+        }
+    }
+}
+
+impl Component<Msg, NoUserEvent> for QuickCommandsPopup {
+    fn on(&mut self, ev: Event<NoUserEvent>) -> Option<Msg> {
+        match ev {
+            Event::Keyboard(KeyEvent { code: Key::Esc, .. }) => {
+                Some(Msg::Ui(UiMsg::CloseQuickCommandsPopup))
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Down, ..
+            }) => {
+                self.perform(Cmd::Move(Direction::Down));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent { code: Key::Up, .. }) => {
+                self.perform(Cmd::Move(Direction::Up));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::PageDown,
+                ..
+            }) => {
+                self.perform(Cmd::Scroll(Direction::Down));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::PageUp, ..
+            }) => {
+                self.perform(Cmd::Scroll(Direction::Up));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Home, ..
+            }) => {
+                self.perform(Cmd::GoTo(Position::Begin));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent { code: Key::End, .. }) => {
+                self.perform(Cmd::GoTo(Position::End));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Char(ch @ '1'..='9'),
+                ..
+            }) => {
+                let idx = ch as usize - '1' as usize;
+                match idx < self.component.states.list_len {
+                    true => Some(Msg::Transfer(TransferMsg::ExecuteQuickCommand(idx))),
+                    false => Some(Msg::None),
+                }
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Enter, ..
+            }) => {
+                // get state
+                if let State::One(StateValue::Usize(idx)) = self.component.state() {
+                    Some(Msg::Transfer(TransferMsg::ExecuteQuickCommand(idx)))
+                } else {
+                    Some(Msg::None)
+                }
+            }
+            _ => None,
+        }
+    }
+}
+
 #[derive(MockComponent)]
 pub struct QuitPopup {
-    component: Radio,
+    component: Buttons,
+    interrupts: bool,
 }
 
 impl QuitPopup {
-    pub fn new(color: Color) -> Self {
+    /// If `interrupts` is true, quitting interrupts some work, so it must be chosen explicitly:
+    /// the choice is "Quit anyway", the popup defaults to "Cancel" and `<Y>` is disabled
+    pub fn new<S: Into<String>>(title: S, color: Color, interrupts: bool) -> Self {
         Self {
-            component: Radio::default()
+            component: Buttons::default()
                 .borders(
                     Borders::default()
                         .color(color)
                         .modifiers(BorderType::Rounded),
                 )
                 .foreground(color)
-                .choices(&["Yes", "No"])
-                .title("Are you sure you want to quit termscp?", Alignment::Center),
+                .choices(match interrupts {
+                    true => &["Quit anyway", "Cancel"],
+                    false => &["Yes", "No"],
+                })
+                .value(usize::from(interrupts))
+                .title(title, Alignment::Center),
+            interrupts,
         }
     }
 }
@@ -1130,6 +2911,10 @@ impl QuitPopup {
 impl Component<Msg, NoUserEvent> for QuitPopup {
     fn on(&mut self, ev: Event<NoUserEvent>) -> Option<Msg> {
         match ev {
+            // clicking a choice submits it
+            Event::Mouse(ev) if self.component.click(&ev).is_some() => {
+                self.on(Event::Keyboard(KeyEvent::from(Key::Enter)))
+            }
             Event::Keyboard(KeyEvent {
                 code: Key::Left, ..
             }) => {
@@ -1148,7 +2933,7 @@ impl Component<Msg, NoUserEvent> for QuitPopup {
             Event::Keyboard(KeyEvent {
                 code: Key::Char('y'),
                 modifiers: KeyModifiers::NONE,
-            }) => Some(Msg::Ui(UiMsg::Quit)),
+            }) if !self.interrupts => Some(Msg::Ui(UiMsg::Quit)),
             Event::Keyboard(KeyEvent {
                 code: Key::Char('n'),
                 modifiers: KeyModifiers::NONE,
@@ -1258,25 +3043,51 @@ impl Component<Msg, NoUserEvent> for RenamePopup {
 
 #[derive(MockComponent)]
 pub struct ReplacePopup {
-    component: Radio,
+    component: Buttons,
+    resumable: bool,
+    renamable: bool,
 }
 
 impl ReplacePopup {
-    pub fn new(filename: Option<&str>, color: Color) -> Self {
-        let text = match filename {
-            Some(f) => format!(r#"File "{f}" already exists. Overwrite file?"#),
-            None => "Overwrite files?".to_string(),
+    /// If `resumable`, the existing file is smaller than the transferred one and the transfer can be resumed.
+    /// If `renamable`, the transferred files can be saved under a different name
+    pub fn new(filename: Option<&str>, resumable: bool, renamable: bool, color: Color) -> Self {
+        let text = match (filename, resumable) {
+            (Some(f), true) => {
+                format!(r#"File "{f}" already exists and is smaller. Overwrite or resume file?"#)
+            }
+            (Some(f), false) => format!(r#"File "{f}" already exists. Overwrite file?"#),
+            (None, _) => "Overwrite files?".to_string(),
         };
+        let mut choices = vec!["Yes", "No"];
+        if resumable {
+            choices.push("Resume");
+        }
+        if renamable {
+            choices.push("Rename…");
+        }
         Self {
-            component: Radio::default()
+            component: Buttons::default()
                 .borders(
                     Borders::default()
                         .color(color)
                         .modifiers(BorderType::Rounded),
                 )
                 .foreground(color)
-                .choices(&["Yes", "No"])
+                .choices(&choices)
                 .title(text, Alignment::Center),
+            resumable,
+            renamable,
+        }
+    }
+
+    /// Get the message for the choice at `index`
+    fn choice_msg(&self, index: usize) -> PendingActionMsg {
+        match index {
+            0 => PendingActionMsg::TransferPendingFile,
+            2 if self.resumable => PendingActionMsg::ResumePendingFile,
+            2 | 3 if self.renamable => PendingActionMsg::RenamePendingFile,
+            _ => PendingActionMsg::CloseReplacePopups,
         }
     }
 }
@@ -1284,6 +3095,10 @@ impl ReplacePopup {
 impl Component<Msg, NoUserEvent> for ReplacePopup {
     fn on(&mut self, ev: Event<NoUserEvent>) -> Option<Msg> {
         match ev {
+            // clicking a choice submits it
+            Event::Mouse(ev) if self.component.click(&ev).is_some() => {
+                self.on(Event::Keyboard(KeyEvent::from(Key::Enter)))
+            }
             Event::Keyboard(KeyEvent { code: Key::Tab, .. }) => {
                 Some(Msg::Ui(UiMsg::ReplacePopupTabbed))
             }
@@ -1310,18 +3125,169 @@ impl Component<Msg, NoUserEvent> for ReplacePopup {
                 code: Key::Char('n'),
                 modifiers: KeyModifiers::NONE,
             }) => Some(Msg::PendingAction(PendingActionMsg::CloseReplacePopups)),
+            Event::Keyboard(KeyEvent {
+                code: Key::Char('r'),
+                modifiers: KeyModifiers::NONE,
+            }) if self.resumable => Some(Msg::PendingAction(PendingActionMsg::ResumePendingFile)),
+            Event::Keyboard(KeyEvent {
+                code: Key::Char('e'),
+                modifiers: KeyModifiers::NONE,
+            }) if self.renamable => Some(Msg::PendingAction(PendingActionMsg::RenamePendingFile)),
             Event::Keyboard(KeyEvent {
                 code: Key::Enter, ..
+            }) => match self.perform(Cmd::Submit) {
+                CmdResult::Submit(State::One(StateValue::Usize(index))) => {
+                    Some(Msg::PendingAction(self.choice_msg(index)))
+                }
+                _ => Some(Msg::PendingAction(PendingActionMsg::CloseReplacePopups)),
+            },
+            _ => None,
+        }
+    }
+}
+
+#[derive(MockComponent)]
+pub struct EditConflictPopup {
+    component: Buttons,
+}
+
+impl EditConflictPopup {
+    pub fn new(file_name: &str, color: Color) -> Self {
+        Self {
+            component: Buttons::default()
+                .borders(
+                    Borders::default()
+                        .color(color)
+                        .modifiers(BorderType::Rounded),
+                )
+                .foreground(color)
+                .choices(&["Overwrite", "Save as…", "Discard"])
+                .value(2)
+                .title(
+                    format!(r#""{file_name}" has changed on the remote since it was opened"#),
+                    Alignment::Center,
+                ),
+        }
+    }
+}
+
+impl Component<Msg, NoUserEvent> for EditConflictPopup {
+    fn on(&mut self, ev: Event<NoUserEvent>) -> Option<Msg> {
+        match ev {
+            // clicking a choice submits it
+            Event::Mouse(ev) if self.component.click(&ev).is_some() => {
+                self.on(Event::Keyboard(KeyEvent::from(Key::Enter)))
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Left, ..
             }) => {
-                if matches!(
-                    self.perform(Cmd::Submit),
-                    CmdResult::Submit(State::One(StateValue::Usize(0)))
-                ) {
-                    Some(Msg::PendingAction(PendingActionMsg::TransferPendingFile))
-                } else {
-                    Some(Msg::PendingAction(PendingActionMsg::CloseReplacePopups))
+                self.perform(Cmd::Move(Direction::Left));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Right, ..
+            }) => {
+                self.perform(Cmd::Move(Direction::Right));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent { code: Key::Esc, .. }) => {
+                Some(Msg::PendingAction(PendingActionMsg::CloseEditConflictPopup))
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Enter, ..
+            }) => match self.perform(Cmd::Submit) {
+                CmdResult::Submit(State::One(StateValue::Usize(0))) => {
+                    Some(Msg::PendingAction(PendingActionMsg::OverwriteEditedFile))
                 }
+                CmdResult::Submit(State::One(StateValue::Usize(1))) => {
+                    Some(Msg::PendingAction(PendingActionMsg::SaveEditedFileAs))
+                }
+                _ => Some(Msg::PendingAction(PendingActionMsg::CloseEditConflictPopup)),
+            },
+            _ => None,
+        }
+    }
+}
+
+#[derive(MockComponent)]
+pub struct ReplaceRenamePopup {
+    component: Input,
+}
+
+impl ReplaceRenamePopup {
+    /// Instantiates the popup asking for the name to save `file_name` as, pre-filled with `suggestion`
+    pub fn new(file_name: &str, suggestion: &str, color: Color) -> Self {
+        Self {
+            component: Input::default()
+                .borders(
+                    Borders::default()
+                        .color(color)
+                        .modifiers(BorderType::Rounded),
+                )
+                .foreground(color)
+                .input_type(InputType::Text)
+                .value(suggestion)
+                .title(format!(r#"Save "{file_name}" as…"#), Alignment::Center),
+        }
+    }
+}
+
+impl Component<Msg, NoUserEvent> for ReplaceRenamePopup {
+    fn on(&mut self, ev: Event<NoUserEvent>) -> Option<Msg> {
+        match ev {
+            Event::Keyboard(KeyEvent {
+                code: Key::Left, ..
+            }) => {
+                self.perform(Cmd::Move(Direction::Left));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Right, ..
+            }) => {
+                self.perform(Cmd::Move(Direction::Right));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Home, ..
+            }) => {
+                self.perform(Cmd::GoTo(Position::Begin));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent { code: Key::End, .. }) => {
+                self.perform(Cmd::GoTo(Position::End));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Delete, ..
+            }) => {
+                self.perform(Cmd::Cancel);
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Backspace,
+                ..
+            }) => {
+                self.perform(Cmd::Delete);
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Char(ch),
+                ..
+            }) => {
+                self.perform(Cmd::Type(ch));
+                Some(Msg::None)
             }
+            Event::Keyboard(KeyEvent {
+                code: Key::Enter, ..
+            }) => match self.state() {
+                State::One(StateValue::String(name)) if !name.trim().is_empty() => {
+                    Some(Msg::PendingAction(PendingActionMsg::SubmitPendingRename))
+                }
+                _ => Some(Msg::None),
+            },
+            Event::Keyboard(KeyEvent { code: Key::Esc, .. }) => Some(Msg::PendingAction(
+                PendingActionMsg::CloseReplaceRenamePopup,
+            )),
             _ => None,
         }
     }
@@ -1333,7 +3299,15 @@ pub struct ReplacingFilesListPopup {
 }
 
 impl ReplacingFilesListPopup {
-    pub fn new(files: &[String], color: Color) -> Self {
+    /// Instantiates the list of the files to replace.
+    /// If set, `filter` is reported in the title, since only the entries matching it are transferred
+    pub fn new(files: &[String], filter: Option<&str>, color: Color) -> Self {
+        let title = match filter {
+            Some(filter) => {
+                format!("The following files are going to be replaced (filtered by {filter})")
+            }
+            None => String::from("The following files are going to be replaced"),
+        };
         Self {
             component: List::default()
                 .borders(
@@ -1345,10 +3319,7 @@ impl ReplacingFilesListPopup {
                 .step(4)
                 .highlighted_color(color)
                 .highlighted_str("➤ ")
-                .title(
-                    "The following files are going to be replaced",
-                    Alignment::Center,
-                )
+                .title(title, Alignment::Center)
                 .rows(files.iter().map(|x| vec![TextSpan::from(x)]).collect()),
         }
     }
@@ -1489,13 +3460,13 @@ impl Component<Msg, NoUserEvent> for SaveAsPopup {
 
 #[derive(MockComponent)]
 pub struct SortingPopup {
-    component: Radio,
+    component: Buttons,
 }
 
 impl SortingPopup {
     pub fn new(value: FileSorting, color: Color) -> Self {
         Self {
-            component: Radio::default()
+            component: Buttons::default()
                 .borders(
                     Borders::default()
                         .color(color)
@@ -1528,6 +3499,10 @@ impl Component<Msg, NoUserEvent> for SortingPopup {
                 code: Key::Esc | Key::Enter,
                 ..
             }) => return Some(Msg::Ui(UiMsg::CloseFileSortingPopup)),
+            Event::Mouse(ev) => match self.component.click(&ev) {
+                Some(i) => CmdResult::Changed(State::One(StateValue::Usize(i))),
+                None => return None,
+            },
             _ => return None,
         };
         if let CmdResult::Changed(State::One(StateValue::Usize(i))) = result {
@@ -1550,16 +3525,53 @@ pub struct StatusBarLocal {
 }
 
 impl StatusBarLocal {
-    pub fn new(browser: &Browser, sorting_color: Color, hidden_color: Color) -> Self {
+    /// Instantiates the local status bar.
+    /// If `watcher_pending` is greater than 0, the amount of watcher uploads in flight is displayed.
+    /// `free_space` is displayed if known
+    pub fn new(
+        browser: &Browser,
+        sorting_color: Color,
+        hidden_color: Color,
+        watcher_pending: usize,
+        watcher_color: Color,
+        free_space: Option<u64>,
+    ) -> Self {
         let file_sorting = file_sorting_label(browser.host_bridge().file_sorting);
         let hidden_files = hidden_files_label(browser.host_bridge().hidden_files_visible());
+        let mut spans = vec![
+            TextSpan::new("File sorting: ").fg(sorting_color),
+            TextSpan::new(file_sorting).fg(sorting_color).reversed(),
+            TextSpan::new(" Hidden files: ").fg(hidden_color),
+            TextSpan::new(hidden_files).fg(hidden_color).reversed(),
+        ];
+        if browser.is_view_locked() {
+            spans.push(TextSpan::new(" View locked").fg(hidden_color).bold());
+        }
+        if let Some(filter) = filter_label(browser.host_bridge()) {
+            spans.push(TextSpan::new(" Filter: ").fg(hidden_color));
+            spans.push(TextSpan::new(filter).fg(hidden_color).reversed());
+        }
+        let cut = browser.cut_count(FileExplorerTab::HostBridge);
+        if cut > 0 {
+            spans.push(
+                TextSpan::new(format!(" ✂ {cut} cut"))
+                    .fg(hidden_color)
+                    .bold(),
+            );
+        }
+        if watcher_pending > 0 {
+            spans.push(TextSpan::new(" "));
+            spans.push(
+                TextSpan::new(format!("⇡{watcher_pending} pending"))
+                    .fg(watcher_color)
+                    .bold(),
+            );
+        }
+        if let Some(free_space) = free_space {
+            spans.push(free_space_label(free_space, hidden_color));
+        }
         Self {
-            component: Span::default().spans(&[
-                TextSpan::new("File sorting: ").fg(sorting_color),
-                TextSpan::new(file_sorting).fg(sorting_color).reversed(),
-                TextSpan::new(" Hidden files: ").fg(hidden_color),
-                TextSpan::new(hidden_files).fg(hidden_color).reversed(),
-            ]),
+            component: Span::default().spans(&spans),
         }
     }
 }
@@ -1576,11 +3588,17 @@ pub struct StatusBarRemote {
 }
 
 impl StatusBarRemote {
+    /// Instantiates the remote status bar.
+    /// If `clock_skew` is set, the clock skew of the remote host is displayed.
+    /// `free_space` is displayed if the server reports it
     pub fn new(
         browser: &Browser,
         sorting_color: Color,
         hidden_color: Color,
         sync_color: Color,
+        clock_skew: Option<String>,
+        clock_skew_color: Color,
+        free_space: Option<u64>,
     ) -> Self {
         let file_sorting = file_sorting_label(browser.remote().file_sorting);
         let hidden_files = hidden_files_label(browser.remote().hidden_files_visible());
@@ -1588,49 +3606,345 @@ impl StatusBarRemote {
             true => "ON ",
             false => "OFF",
         };
+        let mut spans = vec![
+            TextSpan::new("File sorting: ").fg(sorting_color),
+            TextSpan::new(file_sorting).fg(sorting_color).reversed(),
+            TextSpan::new(" Hidden files: ").fg(hidden_color),
+            TextSpan::new(hidden_files).fg(hidden_color).reversed(),
+            TextSpan::new(" Sync browsing: ").fg(sync_color),
+            TextSpan::new(sync_browsing).fg(sync_color).reversed(),
+        ];
+        if browser.is_view_locked() {
+            spans.push(TextSpan::new(" View locked").fg(hidden_color).bold());
+        }
+        if let Some(filter) = filter_label(browser.remote()) {
+            spans.push(TextSpan::new(" Filter: ").fg(hidden_color));
+            spans.push(TextSpan::new(filter).fg(hidden_color).reversed());
+        }
+        let cut = browser.cut_count(FileExplorerTab::Remote);
+        if cut > 0 {
+            spans.push(
+                TextSpan::new(format!(" ✂ {cut} cut"))
+                    .fg(hidden_color)
+                    .bold(),
+            );
+        }
+        if let Some(clock_skew) = clock_skew {
+            spans.push(TextSpan::new(" "));
+            spans.push(
+                TextSpan::new(format!("Clock skew: {clock_skew}"))
+                    .fg(clock_skew_color)
+                    .bold(),
+            );
+        }
+        if let Some(free_space) = free_space {
+            spans.push(free_space_label(free_space, hidden_color));
+        }
+        Self {
+            component: Span::default().spans(&spans),
+        }
+    }
+}
+
+impl Component<Msg, NoUserEvent> for StatusBarRemote {
+    fn on(&mut self, _ev: Event<NoUserEvent>) -> Option<Msg> {
+        None
+    }
+}
+
+/// Label of the filter of `explorer`, with the amount of listed entries out of the unfiltered ones
+fn filter_label(explorer: &FileExplorer) -> Option<String> {
+    let filter = explorer.filter()?;
+    let (listed, total) = explorer.filter_counts();
+    Some(format!("{filter} {listed}/{total}"))
+}
+
+fn free_space_label(free_space: u64, color: Color) -> TextSpan {
+    TextSpan::new(format!(" Free: {}", ByteSize(free_space))).fg(color)
+}
+
+fn file_sorting_label(sorting: FileSorting) -> &'static str {
+    match sorting {
+        FileSorting::CreationTime => "By creation time",
+        FileSorting::ModifyTime => "By modify time",
+        FileSorting::Name => "By name",
+        FileSorting::Size => "By size",
+        FileSorting::None => "",
+    }
+}
+
+fn hidden_files_label(visible: bool) -> &'static str {
+    match visible {
+        true => "Show",
+        false => "Hide",
+    }
+}
+
+#[derive(MockComponent)]
+pub struct SymlinkPopup {
+    component: Input,
+}
+
+impl SymlinkPopup {
+    pub fn new(color: Color) -> Self {
+        Self {
+            component: Input::default()
+                .borders(
+                    Borders::default()
+                        .color(color)
+                        .modifiers(BorderType::Rounded),
+                )
+                .foreground(color)
+                .input_type(InputType::Text)
+                .placeholder(
+                    "Symlink name",
+                    Style::default().fg(Color::Rgb(128, 128, 128)),
+                )
+                .title(
+                    "Create a symlink pointing to the selected entry",
+                    Alignment::Center,
+                ),
+        }
+    }
+}
+
+impl Component<Msg, NoUserEvent> for SymlinkPopup {
+    fn on(&mut self, ev: Event<NoUserEvent>) -> Option<Msg> {
+        match ev {
+            Event::Keyboard(KeyEvent {
+                code: Key::Left, ..
+            }) => {
+                self.perform(Cmd::Move(Direction::Left));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Right, ..
+            }) => {
+                self.perform(Cmd::Move(Direction::Right));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Home, ..
+            }) => {
+                self.perform(Cmd::GoTo(Position::Begin));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent { code: Key::End, .. }) => {
+                self.perform(Cmd::GoTo(Position::End));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Delete, ..
+            }) => {
+                self.perform(Cmd::Cancel);
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Backspace,
+                ..
+            }) => {
+                self.perform(Cmd::Delete);
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Char(ch),
+                ..
+            }) => {
+                self.perform(Cmd::Type(ch));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Enter, ..
+            }) => match self.state() {
+                State::One(StateValue::String(i)) => {
+                    Some(Msg::Transfer(TransferMsg::CreateSymlink(i)))
+                }
+                _ => Some(Msg::None),
+            },
+            Event::Keyboard(KeyEvent { code: Key::Esc, .. }) => {
+                Some(Msg::Ui(UiMsg::CloseSymlinkPopup))
+            }
+            _ => None,
+        }
+    }
+}
+
+#[derive(MockComponent)]
+pub struct SyncBrowsingMkdirPopup {
+    component: Buttons,
+}
+
+impl SyncBrowsingMkdirPopup {
+    pub fn new(color: Color, dir_name: &str) -> Self {
+        Self {
+            component: Buttons::default()
+                .borders(
+                    Borders::default()
+                        .color(color)
+                        .modifiers(BorderType::Rounded),
+                )
+                .foreground(color)
+                .choices(&["Yes", "No"])
+                .title(
+                    format!(
+                        r#"Sync browsing: directory "{dir_name}" doesn't exist. Do you want to create it?"#
+                    ),
+                    Alignment::Center,
+                ),
+        }
+    }
+}
+
+impl Component<Msg, NoUserEvent> for SyncBrowsingMkdirPopup {
+    fn on(&mut self, ev: Event<NoUserEvent>) -> Option<Msg> {
+        match ev {
+            // clicking a choice submits it
+            Event::Mouse(ev) if self.component.click(&ev).is_some() => {
+                self.on(Event::Keyboard(KeyEvent::from(Key::Enter)))
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Left, ..
+            }) => {
+                self.perform(Cmd::Move(Direction::Left));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Right, ..
+            }) => {
+                self.perform(Cmd::Move(Direction::Right));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent { code: Key::Esc, .. }) => Some(Msg::PendingAction(
+                PendingActionMsg::CloseSyncBrowsingMkdirPopup,
+            )),
+            Event::Keyboard(KeyEvent {
+                code: Key::Char('y'),
+                modifiers: KeyModifiers::NONE,
+            }) => Some(Msg::PendingAction(PendingActionMsg::MakePendingDirectory)),
+            Event::Keyboard(KeyEvent {
+                code: Key::Char('n'),
+                modifiers: KeyModifiers::NONE,
+            }) => Some(Msg::PendingAction(
+                PendingActionMsg::CloseSyncBrowsingMkdirPopup,
+            )),
+            Event::Keyboard(KeyEvent {
+                code: Key::Enter, ..
+            }) => {
+                if matches!(
+                    self.perform(Cmd::Submit),
+                    CmdResult::Submit(State::One(StateValue::Usize(0)))
+                ) {
+                    Some(Msg::PendingAction(PendingActionMsg::MakePendingDirectory))
+                } else {
+                    Some(Msg::PendingAction(
+                        PendingActionMsg::CloseSyncBrowsingMkdirPopup,
+                    ))
+                }
+            }
+            _ => None,
+        }
+    }
+}
+
+#[derive(MockComponent)]
+pub struct TransferConfirmPopup {
+    component: Buttons,
+    schedulable: bool,
+}
+
+impl TransferConfirmPopup {
+    /// Instantiates the transfer confirmation popup.
+    /// If `schedulable`, the transfer may be scheduled for later too
+    pub fn new<S: AsRef<str>>(title: S, color: Color, schedulable: bool) -> Self {
+        let choices: &[&str] = match schedulable {
+            true => &["Yes", "Schedule", "No"],
+            false => &["Yes", "No"],
+        };
         Self {
-            component: Span::default().spans(&[
-                TextSpan::new("File sorting: ").fg(sorting_color),
-                TextSpan::new(file_sorting).fg(sorting_color).reversed(),
-                TextSpan::new(" Hidden files: ").fg(hidden_color),
-                TextSpan::new(hidden_files).fg(hidden_color).reversed(),
-                TextSpan::new(" Sync browsing: ").fg(sync_color),
-                TextSpan::new(sync_browsing).fg(sync_color).reversed(),
-            ]),
+            component: Buttons::default()
+                .borders(
+                    Borders::default()
+                        .color(color)
+                        .modifiers(BorderType::Rounded),
+                )
+                .foreground(color)
+                .choices(choices)
+                .value(choices.len() - 1)
+                .title(title.as_ref(), Alignment::Center),
+            schedulable,
+        }
+    }
+}
+
+impl Component<Msg, NoUserEvent> for TransferConfirmPopup {
+    fn on(&mut self, ev: Event<NoUserEvent>) -> Option<Msg> {
+        match ev {
+            // clicking a choice submits it
+            Event::Mouse(ev) if self.component.click(&ev).is_some() => {
+                self.on(Event::Keyboard(KeyEvent::from(Key::Enter)))
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Left, ..
+            }) => {
+                self.perform(Cmd::Move(Direction::Left));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Right, ..
+            }) => {
+                self.perform(Cmd::Move(Direction::Right));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent { code: Key::Esc, .. }) => Some(Msg::PendingAction(
+                PendingActionMsg::CloseTransferConfirmPopup,
+            )),
+            Event::Keyboard(KeyEvent {
+                code: Key::Char('y'),
+                modifiers: KeyModifiers::NONE,
+            }) => Some(Msg::PendingAction(PendingActionMsg::TransferPendingPayload)),
+            Event::Keyboard(KeyEvent {
+                code: Key::Char('s'),
+                modifiers: KeyModifiers::NONE,
+            }) if self.schedulable => {
+                Some(Msg::PendingAction(PendingActionMsg::SchedulePendingPayload))
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Char('n'),
+                modifiers: KeyModifiers::NONE,
+            }) => Some(Msg::PendingAction(
+                PendingActionMsg::CloseTransferConfirmPopup,
+            )),
+            Event::Keyboard(KeyEvent {
+                code: Key::Enter, ..
+            }) => match self.perform(Cmd::Submit) {
+                CmdResult::Submit(State::One(StateValue::Usize(0))) => {
+                    Some(Msg::PendingAction(PendingActionMsg::TransferPendingPayload))
+                }
+                CmdResult::Submit(State::One(StateValue::Usize(1))) if self.schedulable => {
+                    Some(Msg::PendingAction(PendingActionMsg::SchedulePendingPayload))
+                }
+                _ => Some(Msg::PendingAction(
+                    PendingActionMsg::CloseTransferConfirmPopup,
+                )),
+            },
+            _ => None,
         }
     }
 }
 
-impl Component<Msg, NoUserEvent> for StatusBarRemote {
-    fn on(&mut self, _ev: Event<NoUserEvent>) -> Option<Msg> {
-        None
-    }
-}
-
-fn file_sorting_label(sorting: FileSorting) -> &'static str {
-    match sorting {
-        FileSorting::CreationTime => "By creation time",
-        FileSorting::ModifyTime => "By modify time",
-        FileSorting::Name => "By name",
-        FileSorting::Size => "By size",
-        FileSorting::None => "",
-    }
-}
-
-fn hidden_files_label(visible: bool) -> &'static str {
-    match visible {
-        true => "Show",
-        false => "Hide",
-    }
-}
-
 #[derive(MockComponent)]
-pub struct SymlinkPopup {
+pub struct ScheduleTransferPopup {
     component: Input,
 }
 
-impl SymlinkPopup {
-    pub fn new(color: Color) -> Self {
+impl ScheduleTransferPopup {
+    /// Instantiates the popup asking for the start time of a scheduled transfer.
+    /// If set, `error` is the reason why the previous input was rejected
+    pub fn new(color: Color, error: Option<&str>) -> Self {
+        let title = match error {
+            Some(err) => format!("Invalid start time: {err}"),
+            None => String::from("Start the transfer at (HH:MM or +duration, e.g. 02:00 or +2h)"),
+        };
         Self {
             component: Input::default()
                 .borders(
@@ -1640,19 +3954,13 @@ impl SymlinkPopup {
                 )
                 .foreground(color)
                 .input_type(InputType::Text)
-                .placeholder(
-                    "Symlink name",
-                    Style::default().fg(Color::Rgb(128, 128, 128)),
-                )
-                .title(
-                    "Create a symlink pointing to the selected entry",
-                    Alignment::Center,
-                ),
+                .placeholder("02:00", Style::default().fg(Color::Rgb(128, 128, 128)))
+                .title(title, Alignment::Center),
         }
     }
 }
 
-impl Component<Msg, NoUserEvent> for SymlinkPopup {
+impl Component<Msg, NoUserEvent> for ScheduleTransferPopup {
     fn on(&mut self, ev: Event<NoUserEvent>) -> Option<Msg> {
         match ev {
             Event::Keyboard(KeyEvent {
@@ -1699,29 +4007,65 @@ impl Component<Msg, NoUserEvent> for SymlinkPopup {
             }
             Event::Keyboard(KeyEvent {
                 code: Key::Enter, ..
-            }) => match self.state() {
-                State::One(StateValue::String(i)) => {
-                    Some(Msg::Transfer(TransferMsg::CreateSymlink(i)))
-                }
-                _ => Some(Msg::None),
-            },
-            Event::Keyboard(KeyEvent { code: Key::Esc, .. }) => {
-                Some(Msg::Ui(UiMsg::CloseSymlinkPopup))
-            }
+            }) => Some(Msg::PendingAction(PendingActionMsg::SubmitPendingSchedule)),
+            Event::Keyboard(KeyEvent { code: Key::Esc, .. }) => Some(Msg::PendingAction(
+                PendingActionMsg::CloseScheduleTransferPopup,
+            )),
             _ => None,
         }
     }
 }
 
 #[derive(MockComponent)]
-pub struct SyncBrowsingMkdirPopup {
-    component: Radio,
+pub struct TransferScanWaitPopup {
+    component: Paragraph,
 }
 
-impl SyncBrowsingMkdirPopup {
-    pub fn new(color: Color, dir_name: &str) -> Self {
+impl TransferScanWaitPopup {
+    pub fn new<S: AsRef<str>>(text: S, color: Color) -> Self {
+        Self {
+            component: Paragraph::default()
+                .alignment(Alignment::Center)
+                .borders(
+                    Borders::default()
+                        .color(color)
+                        .modifiers(BorderType::Rounded),
+                )
+                .foreground(color)
+                .text(&[
+                    TextSpan::from(text.as_ref()),
+                    TextSpan::from("Press 'CTRL+C' to abort"),
+                ])
+                .wrap(true),
+        }
+    }
+}
+
+impl Component<Msg, NoUserEvent> for TransferScanWaitPopup {
+    fn on(&mut self, ev: Event<NoUserEvent>) -> Option<Msg> {
+        if matches!(
+            ev,
+            Event::Keyboard(KeyEvent {
+                code: Key::Char('c'),
+                modifiers: KeyModifiers::CONTROL
+            })
+        ) {
+            Some(Msg::Transfer(TransferMsg::AbortTransfer))
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(MockComponent)]
+pub struct UpdateBookmarkPopup {
+    component: Buttons,
+}
+
+impl UpdateBookmarkPopup {
+    pub fn new(name: &str, color: Color) -> Self {
         Self {
-            component: Radio::default()
+            component: Buttons::default()
                 .borders(
                     Borders::default()
                         .color(color)
@@ -1730,18 +4074,20 @@ impl SyncBrowsingMkdirPopup {
                 .foreground(color)
                 .choices(&["Yes", "No"])
                 .title(
-                    format!(
-                        r#"Sync browsing: directory "{dir_name}" doesn't exist. Do you want to create it?"#
-                    ),
+                    format!("Save the new credentials to bookmark \"{name}\"?"),
                     Alignment::Center,
                 ),
         }
     }
 }
 
-impl Component<Msg, NoUserEvent> for SyncBrowsingMkdirPopup {
+impl Component<Msg, NoUserEvent> for UpdateBookmarkPopup {
     fn on(&mut self, ev: Event<NoUserEvent>) -> Option<Msg> {
         match ev {
+            // clicking a choice submits it
+            Event::Mouse(ev) if self.component.click(&ev).is_some() => {
+                self.on(Event::Keyboard(KeyEvent::from(Key::Enter)))
+            }
             Event::Keyboard(KeyEvent {
                 code: Key::Left, ..
             }) => {
@@ -1754,19 +4100,17 @@ impl Component<Msg, NoUserEvent> for SyncBrowsingMkdirPopup {
                 self.perform(Cmd::Move(Direction::Right));
                 Some(Msg::None)
             }
-            Event::Keyboard(KeyEvent { code: Key::Esc, .. }) => Some(Msg::PendingAction(
-                PendingActionMsg::CloseSyncBrowsingMkdirPopup,
-            )),
+            Event::Keyboard(KeyEvent { code: Key::Esc, .. }) => {
+                Some(Msg::Ui(UiMsg::CloseUpdateBookmarkPopup))
+            }
             Event::Keyboard(KeyEvent {
                 code: Key::Char('y'),
                 modifiers: KeyModifiers::NONE,
-            }) => Some(Msg::PendingAction(PendingActionMsg::MakePendingDirectory)),
+            }) => Some(Msg::Ui(UiMsg::UpdateBookmarkCredentials)),
             Event::Keyboard(KeyEvent {
                 code: Key::Char('n'),
                 modifiers: KeyModifiers::NONE,
-            }) => Some(Msg::PendingAction(
-                PendingActionMsg::CloseSyncBrowsingMkdirPopup,
-            )),
+            }) => Some(Msg::Ui(UiMsg::CloseUpdateBookmarkPopup)),
             Event::Keyboard(KeyEvent {
                 code: Key::Enter, ..
             }) => {
@@ -1774,11 +4118,9 @@ impl Component<Msg, NoUserEvent> for SyncBrowsingMkdirPopup {
                     self.perform(Cmd::Submit),
                     CmdResult::Submit(State::One(StateValue::Usize(0)))
                 ) {
-                    Some(Msg::PendingAction(PendingActionMsg::MakePendingDirectory))
+                    Some(Msg::Ui(UiMsg::UpdateBookmarkCredentials))
                 } else {
-                    Some(Msg::PendingAction(
-                        PendingActionMsg::CloseSyncBrowsingMkdirPopup,
-                    ))
+                    Some(Msg::Ui(UiMsg::CloseUpdateBookmarkPopup))
                 }
             }
             _ => None,
@@ -1855,13 +4197,94 @@ impl Component<Msg, NoUserEvent> for WalkdirWaitPopup {
     }
 }
 
+#[derive(MockComponent)]
+pub struct TransferQueuePopup {
+    component: List,
+}
+
+impl TransferQueuePopup {
+    pub fn new(rows: Vec<Vec<TextSpan>>, color: Color) -> Self {
+        Self {
+            component: List::default()
+                .borders(
+                    Borders::default()
+                        .color(color)
+                        .modifiers(BorderType::Rounded),
+                )
+                .rewind(true)
+                .scroll(true)
+                .step(4)
+                .highlighted_color(color)
+                .highlighted_str("➤ ")
+                .title(
+                    "Transfer queue (<E> to remove or cancel)",
+                    Alignment::Center,
+                )
+                .rows(rows),
+        }
+    }
+}
+
+impl Component<Msg, NoUserEvent> for TransferQueuePopup {
+    fn on(&mut self, ev: Event<NoUserEvent>) -> Option<Msg> {
+        match ev {
+            Event::Keyboard(KeyEvent { code: Key::Esc, .. }) => {
+                Some(Msg::Ui(UiMsg::CloseTransferQueuePopup))
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Down, ..
+            }) => {
+                self.perform(Cmd::Move(Direction::Down));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent { code: Key::Up, .. }) => {
+                self.perform(Cmd::Move(Direction::Up));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::PageDown,
+                ..
+            }) => {
+                self.perform(Cmd::Scroll(Direction::Down));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::PageUp, ..
+            }) => {
+                self.perform(Cmd::Scroll(Direction::Up));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Home, ..
+            }) => {
+                self.perform(Cmd::GoTo(Position::Begin));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent { code: Key::End, .. }) => {
+                self.perform(Cmd::GoTo(Position::End));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Char('e') | Key::Delete,
+                ..
+            }) => match self.component.state() {
+                State::One(StateValue::Usize(idx)) if self.component.states.list_len > 0 => {
+                    Some(Msg::Transfer(TransferMsg::RemoveQueueItem(idx)))
+                }
+                _ => Some(Msg::None),
+            },
+            _ => None,
+        }
+    }
+}
+
 #[derive(MockComponent)]
 pub struct WatchedPathsList {
     component: List,
 }
 
 impl WatchedPathsList {
-    pub fn new(paths: &[std::path::PathBuf], color: Color) -> Self {
+    pub fn new(paths: &[(std::path::PathBuf, usize, WatchDirection)], color: Color) -> Self {
         Self {
             component: List::default()
                 .borders(
@@ -1875,13 +4298,24 @@ impl WatchedPathsList {
                 .highlighted_color(color)
                 .highlighted_str("➤ ")
                 .title(
-                    "These files are currently synched with the remote host",
+                    "These files are currently synched between the hosts (⬆ upload, ⬇ download)",
                     Alignment::Center,
                 )
                 .rows(
                     paths
                         .iter()
-                        .map(|x| vec![TextSpan::from(x.to_string_lossy().to_string())])
+                        .map(|(path, ignored, direction)| {
+                            let marker = match direction {
+                                WatchDirection::Upload => "⬆ ",
+                                WatchDirection::Download => "⬇ ",
+                            };
+                            vec![
+                                TextSpan::from(marker),
+                                TextSpan::from(path.to_string_lossy().to_string()),
+                                TextSpan::from(format!(" ({ignored} changes ignored)"))
+                                    .fg(Color::DarkGray),
+                            ]
+                        })
                         .collect(),
                 ),
         }
@@ -1942,9 +4376,99 @@ impl Component<Msg, NoUserEvent> for WatchedPathsList {
     }
 }
 
+#[derive(MockComponent)]
+pub struct WatchIgnorePopup {
+    component: Input,
+}
+
+impl WatchIgnorePopup {
+    pub fn new(ignored: &[String], color: Color) -> Self {
+        let placeholder = match ignored.is_empty() {
+            true => String::from("Patterns of the files not to sync, e.g. target/, *.log"),
+            false => format!("Already ignored: {}", ignored.join(", ")),
+        };
+        Self {
+            component: Input::default()
+                .borders(
+                    Borders::default()
+                        .color(color)
+                        .modifiers(BorderType::Rounded),
+                )
+                .foreground(color)
+                .input_type(InputType::Text)
+                .placeholder(placeholder, Style::default().fg(Color::Rgb(128, 128, 128)))
+                .title(
+                    "Ignore also these files (comma separated)",
+                    Alignment::Center,
+                ),
+        }
+    }
+}
+
+impl Component<Msg, NoUserEvent> for WatchIgnorePopup {
+    fn on(&mut self, ev: Event<NoUserEvent>) -> Option<Msg> {
+        match ev {
+            Event::Keyboard(KeyEvent {
+                code: Key::Left, ..
+            }) => {
+                self.perform(Cmd::Move(Direction::Left));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Right, ..
+            }) => {
+                self.perform(Cmd::Move(Direction::Right));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Home, ..
+            }) => {
+                self.perform(Cmd::GoTo(Position::Begin));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent { code: Key::End, .. }) => {
+                self.perform(Cmd::GoTo(Position::End));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Delete, ..
+            }) => {
+                self.perform(Cmd::Cancel);
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Backspace,
+                ..
+            }) => {
+                self.perform(Cmd::Delete);
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Char(ch),
+                ..
+            }) => {
+                self.perform(Cmd::Type(ch));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Enter, ..
+            }) => match self.state() {
+                State::One(StateValue::String(patterns)) => {
+                    Some(Msg::Transfer(TransferMsg::WatchPath(patterns)))
+                }
+                _ => Some(Msg::Transfer(TransferMsg::WatchPath(String::new()))),
+            },
+            Event::Keyboard(KeyEvent { code: Key::Esc, .. }) => {
+                Some(Msg::Ui(UiMsg::CloseWatchIgnorePopup))
+            }
+            _ => None,
+        }
+    }
+}
+
 #[derive(MockComponent)]
 pub struct WatcherPopup {
-    component: Radio,
+    component: Buttons,
 }
 
 impl WatcherPopup {
@@ -1954,7 +4478,7 @@ impl WatcherPopup {
             true => format!(r#"Stop synchronizing changes at "{local}"?"#),
         };
         Self {
-            component: Radio::default()
+            component: Buttons::default()
                 .borders(
                     Borders::default()
                         .color(color)
@@ -1970,6 +4494,10 @@ impl WatcherPopup {
 impl Component<Msg, NoUserEvent> for WatcherPopup {
     fn on(&mut self, ev: Event<NoUserEvent>) -> Option<Msg> {
         match ev {
+            // clicking a choice submits it
+            Event::Mouse(ev) if self.component.click(&ev).is_some() => {
+                self.on(Event::Keyboard(KeyEvent::from(Key::Enter)))
+            }
             Event::Keyboard(KeyEvent {
                 code: Key::Left, ..
             }) => {
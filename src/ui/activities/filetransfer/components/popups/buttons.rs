@@ -0,0 +1,129 @@
+//! ## Buttons
+//!
+//! `Buttons` is a radio whose choices can be clicked with the mouse
+
+use tui_realm_stdlib::Radio;
+use tuirealm::command::{Cmd, CmdResult};
+use tuirealm::event::{MouseButton, MouseEvent, MouseEventKind};
+use tuirealm::props::{Alignment, AttrValue, Attribute, Borders, Color, PropPayload, PropValue};
+use tuirealm::ratatui::layout::{Margin, Rect};
+use tuirealm::{Frame, MockComponent, State};
+use unicode_width::UnicodeWidthStr;
+
+#[derive(Default)]
+pub struct Buttons {
+    component: Radio,
+    /// Width of the choices
+    widths: Vec<u16>,
+    /// Area the radio has been rendered into the last time
+    area: Rect,
+}
+
+impl Buttons {
+    pub fn foreground(mut self, fg: Color) -> Self {
+        self.component = self.component.foreground(fg);
+        self
+    }
+
+    pub fn borders(mut self, b: Borders) -> Self {
+        self.component = self.component.borders(b);
+        self
+    }
+
+    pub fn title<S: Into<String>>(mut self, t: S, a: Alignment) -> Self {
+        self.component = self.component.title(t, a);
+        self
+    }
+
+    pub fn choices<S: AsRef<str>>(mut self, choices: &[S]) -> Self {
+        self.widths = choices.iter().map(|x| x.as_ref().width() as u16).collect();
+        self.component = self.component.choices(choices);
+        self
+    }
+
+    pub fn value(mut self, i: usize) -> Self {
+        self.component = self.component.value(i);
+        self
+    }
+
+    /// If `ev` is a left click on a choice, select it and return its index
+    pub fn click(&mut self, ev: &MouseEvent) -> Option<usize> {
+        if ev.kind != MouseEventKind::Down(MouseButton::Left) {
+            return None;
+        }
+        let choice = Self::choice_at(&self.widths, self.area, ev.column, ev.row)?;
+        self.component.attr(
+            Attribute::Value,
+            AttrValue::Payload(PropPayload::One(PropValue::Usize(choice))),
+        );
+        Some(choice)
+    }
+
+    /// Get the choice at `column` and `row`, laid out as tabs padded by a space and divided by a line
+    fn choice_at(widths: &[u16], area: Rect, column: u16, row: u16) -> Option<usize> {
+        let inner = area.inner(Margin::new(1, 1));
+        if row != inner.y || column < inner.x || column >= inner.right() {
+            return None;
+        }
+        let mut x = inner.x;
+        for (i, width) in widths.iter().enumerate() {
+            let end = x + width + 2;
+            if column < end {
+                return Some(i);
+            }
+            x = end + 1;
+            if column < x {
+                return None;
+            }
+        }
+        None
+    }
+}
+
+impl MockComponent for Buttons {
+    fn view(&mut self, frame: &mut Frame, area: Rect) {
+        self.area = area;
+        self.component.view(frame, area);
+    }
+
+    fn query(&self, attr: Attribute) -> Option<AttrValue> {
+        self.component.query(attr)
+    }
+
+    fn attr(&mut self, attr: Attribute, value: AttrValue) {
+        self.component.attr(attr, value);
+    }
+
+    fn state(&self) -> State {
+        self.component.state()
+    }
+
+    fn perform(&mut self, cmd: Cmd) -> CmdResult {
+        self.component.perform(cmd)
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn should_get_clicked_choice() {
+        // "│ Yes │ No │" rendered at column 10
+        let area = Rect::new(10, 5, 20, 3);
+        let widths = [3, 2];
+        assert_eq!(Buttons::choice_at(&widths, area, 11, 6), Some(0));
+        assert_eq!(Buttons::choice_at(&widths, area, 15, 6), Some(0));
+        // divider
+        assert_eq!(Buttons::choice_at(&widths, area, 16, 6), None);
+        assert_eq!(Buttons::choice_at(&widths, area, 17, 6), Some(1));
+        assert_eq!(Buttons::choice_at(&widths, area, 20, 6), Some(1));
+        assert_eq!(Buttons::choice_at(&widths, area, 21, 6), None);
+        // borders
+        assert_eq!(Buttons::choice_at(&widths, area, 10, 6), None);
+        assert_eq!(Buttons::choice_at(&widths, area, 12, 5), None);
+    }
+}
@@ -1,8 +1,11 @@
 use remotefs::fs::{UnixPex, UnixPexClass};
-use tui_realm_stdlib::Checkbox;
+use tui_realm_stdlib::{Checkbox, Input};
 use tuirealm::command::{Cmd, CmdResult, Direction};
 use tuirealm::event::{Key, KeyEvent};
-use tuirealm::props::{Alignment, AttrValue, Attribute, BorderSides, Borders, Color};
+use tuirealm::props::{
+    Alignment, AttrValue, Attribute, BorderSides, Borders, Color, InputType, PropPayload,
+    PropValue, Style,
+};
 use tuirealm::ratatui::layout::{Constraint, Direction as LayoutDirection, Layout};
 use tuirealm::{Component, Event, MockComponent, NoUserEvent, Props, State, StateValue};
 
@@ -14,6 +17,8 @@ pub enum Item {
     User,
     Group,
     Others,
+    Octal,
+    Recursive,
 }
 
 #[derive(Clone, Copy, PartialEq, Eq)]
@@ -30,6 +35,10 @@ pub struct ChmodPopup {
     user: Checkbox,
     group: Checkbox,
     others: Checkbox,
+    octal: Input,
+    recursive: Checkbox,
+    /// Whether the selection contains directories, which the mode can be applied recursively to
+    directories: bool,
 }
 
 /// Make checkbox values from unix pex class
@@ -48,8 +57,35 @@ fn make_pex_values(mode: UnixPexClass) -> Vec<usize> {
     values
 }
 
+/// Parse the octal representation of a mode, such as `755`; returns `None` if it's incomplete or invalid
+fn parse_octal_mode(octal: &str) -> Option<UnixPex> {
+    if !(3..=4).contains(&octal.len()) {
+        return None;
+    }
+    u32::from_str_radix(octal, 8)
+        .ok()
+        .filter(|x| *x <= 0o777)
+        .map(UnixPex::from)
+}
+
+/// Format the octal representation of `mode`
+fn fmt_octal_mode(mode: UnixPex) -> String {
+    format!("{:03o}", u32::from(mode))
+}
+
 impl ChmodPopup {
-    pub fn new(pex: UnixPex, color: Color, title: String) -> Self {
+    /// Instantiate a new `ChmodPopup`; the mode can be applied recursively if the selection contains `directories`
+    pub fn new(pex: UnixPex, color: Color, title: String, directories: bool) -> Self {
+        fn validate(octal: &str) -> bool {
+            parse_octal_mode(octal).is_some()
+        }
+        fn char_valid(_input: &str, incoming: char) -> bool {
+            ('0'..='7').contains(&incoming)
+        }
+        let recursive_color = match directories {
+            true => color,
+            false => Color::DarkGray,
+        };
         Self {
             props: Props::default(),
             color,
@@ -78,31 +114,54 @@ impl ChmodPopup {
                 .borders(Borders::default().sides(BorderSides::NONE))
                 .values(&make_pex_values(pex.others()))
                 .rewind(true),
+            octal: Input::default()
+                .foreground(color)
+                .borders(Borders::default().sides(BorderSides::NONE))
+                .invalid_style(Style::default().fg(Color::Red))
+                .input_type(InputType::Custom(validate, char_valid))
+                .input_len(4)
+                .title("Octal", Alignment::Left)
+                .value(fmt_octal_mode(pex)),
+            recursive: Checkbox::default()
+                .foreground(recursive_color)
+                .choices(&["Apply recursively"])
+                .title(
+                    match directories {
+                        true => "Directories",
+                        false => "Directories only",
+                    },
+                    Alignment::Left,
+                )
+                .borders(Borders::default().sides(BorderSides::NONE))
+                .values(&[]),
+            directories,
         }
     }
 
-    fn get_active_checkbox(&mut self) -> &'_ mut Checkbox {
+    fn get_active_component(&mut self) -> &'_ mut dyn MockComponent {
         match self.states.focus {
             Item::Group => &mut self.group,
             Item::Others => &mut self.others,
             Item::User => &mut self.user,
+            Item::Octal => &mut self.octal,
+            Item::Recursive => &mut self.recursive,
         }
     }
 
     fn toggle_checkbox_focus(&mut self, value: bool) {
-        match self.states.focus {
-            Item::User => self.user.attr(Attribute::Focus, AttrValue::Flag(value)),
-            Item::Group => self.group.attr(Attribute::Focus, AttrValue::Flag(value)),
-            Item::Others => self.others.attr(Attribute::Focus, AttrValue::Flag(value)),
-        }
+        self.get_active_component()
+            .attr(Attribute::Focus, AttrValue::Flag(value));
     }
 
     fn active_checkbox_up(&mut self) {
         self.toggle_checkbox_focus(false);
         let next = match self.states.focus {
-            Item::User => Item::Others,
+            Item::User if self.directories => Item::Recursive,
+            Item::User => Item::Octal,
             Item::Group => Item::User,
             Item::Others => Item::Group,
+            Item::Octal => Item::Others,
+            Item::Recursive => Item::Octal,
         };
 
         self.states.focus = next;
@@ -115,7 +174,9 @@ impl ChmodPopup {
         let next = match self.states.focus {
             Item::User => Item::Group,
             Item::Group => Item::Others,
-            Item::Others => Item::User,
+            Item::Others => Item::Octal,
+            Item::Octal if self.directories => Item::Recursive,
+            Item::Octal | Item::Recursive => Item::User,
         };
 
         self.states.focus = next;
@@ -144,6 +205,39 @@ impl ChmodPopup {
             Self::checkbox_state_to_pex_class(self.others.state()),
         )
     }
+
+    /// Returns whether the mode has to be applied recursively
+    fn get_recursive(&self) -> bool {
+        self.directories && !self.recursive.state().unwrap_vec().is_empty()
+    }
+
+    /// Update the octal field after the checkboxes have changed
+    fn sync_octal(&mut self) {
+        let octal = fmt_octal_mode(self.get_mode());
+        self.octal.attr(Attribute::Value, AttrValue::String(octal));
+    }
+
+    /// Update the checkboxes after the octal field has changed, if it's a valid mode
+    fn sync_checkboxes(&mut self) {
+        let Some(mode) = parse_octal_mode(self.octal.states.get_value().as_str()) else {
+            return;
+        };
+        for (checkbox, class) in [
+            (&mut self.user, mode.user()),
+            (&mut self.group, mode.group()),
+            (&mut self.others, mode.others()),
+        ] {
+            checkbox.attr(
+                Attribute::Value,
+                AttrValue::Payload(PropPayload::Vec(
+                    make_pex_values(class)
+                        .into_iter()
+                        .map(PropValue::Usize)
+                        .collect(),
+                )),
+            );
+        }
+    }
 }
 
 impl MockComponent for ChmodPopup {
@@ -151,18 +245,19 @@ impl MockComponent for ChmodPopup {
         self.props.set(attr, value.clone());
 
         if attr == Attribute::Focus {
-            self.get_active_checkbox().attr(attr, value);
+            self.get_active_component().attr(attr, value);
         } else {
             self.user.attr(attr, value.clone());
             self.group.attr(attr, value.clone());
-            self.others.attr(attr, value);
+            self.others.attr(attr, value.clone());
+            self.octal.attr(attr, value);
         }
     }
 
     fn perform(&mut self, cmd: Cmd) -> CmdResult {
         match cmd {
             Cmd::Move(Direction::Left) | Cmd::Move(Direction::Right) => {
-                self.get_active_checkbox().perform(cmd)
+                self.get_active_component().perform(cmd)
             }
             Cmd::Move(Direction::Up) => {
                 self.active_checkbox_up();
@@ -172,7 +267,20 @@ impl MockComponent for ChmodPopup {
                 self.active_checkbox_down();
                 CmdResult::None
             }
-            Cmd::Toggle => self.get_active_checkbox().perform(cmd),
+            Cmd::Toggle => match self.states.focus {
+                Item::User | Item::Group | Item::Others => {
+                    let result = self.get_active_component().perform(cmd);
+                    self.sync_octal();
+                    result
+                }
+                Item::Recursive => self.recursive.perform(cmd),
+                Item::Octal => CmdResult::None,
+            },
+            Cmd::Type(_) | Cmd::Delete | Cmd::Cancel if self.states.focus == Item::Octal => {
+                let result = self.octal.perform(cmd);
+                self.sync_checkboxes();
+                result
+            }
             Cmd::Submit => CmdResult::Submit(self.state()),
             _ => CmdResult::None,
         }
@@ -198,6 +306,8 @@ impl MockComponent for ChmodPopup {
                     Constraint::Length(3),
                     Constraint::Length(3),
                     Constraint::Length(3),
+                    Constraint::Length(3),
+                    Constraint::Length(3),
                 ]
                 .as_ref(),
             )
@@ -220,6 +330,8 @@ impl MockComponent for ChmodPopup {
         self.user.view(frame, chunks[0]);
         self.group.view(frame, chunks[1]);
         self.others.view(frame, chunks[2]);
+        self.octal.view(frame, chunks[3]);
+        self.recursive.view(frame, chunks[4]);
     }
 }
 
@@ -258,10 +370,103 @@ impl Component<Msg, NoUserEvent> for ChmodPopup {
                 self.perform(Cmd::Toggle);
                 Some(Msg::None)
             }
+            Event::Keyboard(KeyEvent {
+                code: Key::Char(ch),
+                ..
+            }) => {
+                self.perform(Cmd::Type(ch));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Backspace,
+                ..
+            }) => {
+                self.perform(Cmd::Delete);
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Delete, ..
+            }) => {
+                self.perform(Cmd::Cancel);
+                Some(Msg::None)
+            }
             Event::Keyboard(KeyEvent {
                 code: Key::Enter, ..
-            }) => Some(Msg::Transfer(TransferMsg::Chmod(self.get_mode()))),
+            }) => Some(Msg::Transfer(TransferMsg::Chmod(
+                self.get_mode(),
+                self.get_recursive(),
+            ))),
             _ => None,
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn should_parse_and_format_octal_mode() {
+        assert_eq!(parse_octal_mode("755").map(u32::from), Some(0o755));
+        assert_eq!(parse_octal_mode("0644").map(u32::from), Some(0o644));
+        assert!(parse_octal_mode("75").is_none());
+        assert!(parse_octal_mode("1755").is_none());
+        assert!(parse_octal_mode("789").is_none());
+        assert_eq!(fmt_octal_mode(UnixPex::from(0o700)), "700");
+        assert_eq!(fmt_octal_mode(UnixPex::from(0o44)), "044");
+    }
+
+    #[test]
+    fn should_sync_octal_field_with_checkboxes() {
+        let mut popup = ChmodPopup::new(
+            UnixPex::from(0o644),
+            Color::Reset,
+            String::from("chmod"),
+            true,
+        );
+        assert_eq!(popup.octal.states.get_value(), "644");
+        // toggle user execute
+        popup.perform(Cmd::Move(Direction::Right));
+        popup.perform(Cmd::Move(Direction::Right));
+        popup.perform(Cmd::Toggle);
+        assert_eq!(u32::from(popup.get_mode()), 0o744);
+        assert_eq!(popup.octal.states.get_value(), "744");
+        // type the mode
+        popup.perform(Cmd::Move(Direction::Down));
+        popup.perform(Cmd::Move(Direction::Down));
+        popup.perform(Cmd::Move(Direction::Down));
+        for _ in 0..3 {
+            popup.perform(Cmd::Delete);
+        }
+        popup.perform(Cmd::Type('7'));
+        popup.perform(Cmd::Type('5'));
+        assert_eq!(u32::from(popup.get_mode()), 0o744);
+        popup.perform(Cmd::Type('5'));
+        assert_eq!(u32::from(popup.get_mode()), 0o755);
+        // apply recursively
+        assert_eq!(popup.get_recursive(), false);
+        popup.perform(Cmd::Move(Direction::Down));
+        popup.perform(Cmd::Toggle);
+        assert_eq!(popup.get_recursive(), true);
+    }
+
+    #[test]
+    fn should_not_apply_recursively_to_files() {
+        let mut popup = ChmodPopup::new(
+            UnixPex::from(0o644),
+            Color::Reset,
+            String::from("chmod"),
+            false,
+        );
+        // from the octal field, the focus goes back to the user checkbox
+        for _ in 0..4 {
+            popup.perform(Cmd::Move(Direction::Down));
+        }
+        popup.perform(Cmd::Toggle);
+        assert_eq!(popup.get_recursive(), false);
+        assert_eq!(u32::from(popup.get_mode()), 0o244);
+    }
+}
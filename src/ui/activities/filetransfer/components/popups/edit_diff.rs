@@ -0,0 +1,99 @@
+use tui_realm_stdlib::Textarea;
+use tuirealm::command::{Cmd, Direction, Position};
+use tuirealm::event::{Key, KeyEvent};
+use tuirealm::props::{Alignment, BorderType, Borders, Color, TextSpan};
+use tuirealm::{Component, Event, MockComponent, NoUserEvent};
+
+use super::{Msg, PendingActionMsg};
+
+/// Diff of an edited remote file, to be confirmed before uploading the changes
+#[derive(MockComponent)]
+pub struct EditDiffPopup {
+    component: Textarea,
+}
+
+impl EditDiffPopup {
+    pub fn new(title: String, lines: &[String], color: Color) -> Self {
+        Self {
+            component: Textarea::default()
+                .borders(
+                    Borders::default()
+                        .color(color)
+                        .modifiers(BorderType::Rounded),
+                )
+                .title(title, Alignment::Center)
+                .step(8)
+                .highlighted_str("> ")
+                .text_rows(
+                    lines
+                        .iter()
+                        .map(|line| Self::row(line))
+                        .collect::<Vec<TextSpan>>()
+                        .as_slice(),
+                ),
+        }
+    }
+
+    /// Make the row of the text area for a line of the diff, colored by kind
+    fn row(line: &str) -> TextSpan {
+        let span = TextSpan::from(line);
+        if line.starts_with("+++") || line.starts_with("---") {
+            span.bold()
+        } else if line.starts_with("@@") {
+            span.fg(Color::Cyan)
+        } else if line.starts_with('+') {
+            span.fg(Color::Green)
+        } else if line.starts_with('-') {
+            span.fg(Color::Red)
+        } else {
+            span
+        }
+    }
+}
+
+impl Component<Msg, NoUserEvent> for EditDiffPopup {
+    fn on(&mut self, ev: Event<NoUserEvent>) -> Option<Msg> {
+        match ev {
+            Event::Keyboard(KeyEvent {
+                code: Key::Enter, ..
+            }) => Some(Msg::PendingAction(PendingActionMsg::UploadEditedFile)),
+            Event::Keyboard(KeyEvent { code: Key::Esc, .. }) => {
+                Some(Msg::PendingAction(PendingActionMsg::CloseEditDiffPopup))
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Down, ..
+            }) => {
+                self.perform(Cmd::Move(Direction::Down));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent { code: Key::Up, .. }) => {
+                self.perform(Cmd::Move(Direction::Up));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::PageDown,
+                ..
+            }) => {
+                self.perform(Cmd::Scroll(Direction::Down));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::PageUp, ..
+            }) => {
+                self.perform(Cmd::Scroll(Direction::Up));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Home, ..
+            }) => {
+                self.perform(Cmd::GoTo(Position::Begin));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent { code: Key::End, .. }) => {
+                self.perform(Cmd::GoTo(Position::End));
+                Some(Msg::None)
+            }
+            _ => None,
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use tui_realm_stdlib::Input;
 use tuirealm::command::{Cmd, CmdResult, Direction, Position};
@@ -9,31 +9,47 @@ use tuirealm::{
 };
 
 use crate::ui::activities::filetransfer::{Msg, TransferMsg, UiMsg};
+use crate::utils::path;
 
 pub const ATTR_FILES: &str = "files";
 
 #[derive(Default)]
 struct OwnStates {
-    /// Path and name of the files
-    files: Vec<(String, String)>,
+    /// Working directory, which the relative paths refer to
+    wrkdir: PathBuf,
+    /// Directory whose entries are cached in `files`
+    listed: Option<PathBuf>,
+    /// Directory whose entries have been requested, cached once they are received
+    pending: Option<PathBuf>,
+    /// Names of the directories in `listed`, sorted
+    files: Vec<String>,
     search: Option<String>,
     last_suggestion: Option<String>,
 }
 
 impl OwnStates {
+    /// Cache the `files` listed by the host, which are the entries of the directory requested by the last rescan, if any
     pub fn set_files(&mut self, files: Vec<String>) {
-        self.files = files
+        if let Some(dir) = self.pending.take() {
+            self.listed = Some(dir);
+        }
+        let mut names: Vec<String> = files
             .into_iter()
             .map(|f| {
-                (
-                    f.clone(),
-                    PathBuf::from(&f)
-                        .file_name()
-                        .map(|x| x.to_string_lossy().to_string())
-                        .unwrap_or(f),
-                )
+                PathBuf::from(&f)
+                    .file_name()
+                    .map(|x| x.to_string_lossy().to_string())
+                    .unwrap_or(f)
             })
             .collect();
+        names.sort();
+        names.dedup();
+        self.files = names;
+    }
+
+    /// Resolve the directory `dir` typed by the user, relative to the working directory if it's not absolute
+    fn resolve_dir(&self, dir: &str) -> PathBuf {
+        path::normalize(&path::absolutize(&self.wrkdir, Path::new(dir)))
     }
 }
 
@@ -96,75 +112,52 @@ impl OwnStates {
         }
     }
 
-    /// Suggest files based on the input
+    /// Complete the last component of the `input` path with the directories starting with it, as a shell does:
+    /// every call suggests the next match. Once the only match is suggested, the next call completes its entries.
+    ///
+    /// If the directory of the input is not the listed one, a rescan of it is required
     pub fn suggest(&mut self, input: &str) -> Suggestion {
-        debug!(
-            "Suggesting for: {input}; files {files:?}",
-            files = self.files
-        );
-
-        let is_path = PathBuf::from(input).is_absolute();
-
-        // case 1. search if any file starts with the input; get first if suggestion is `None`, otherwise get first after suggestion
-        let suggestions: Vec<&String> = self
+        let (dir, prefix) = match input.rfind('/') {
+            Some(index) => input.split_at(index + 1),
+            None => ("", input),
+        };
+        let listed_dir = self.resolve_dir(dir);
+        if self.listed.as_ref() != Some(&listed_dir) {
+            debug!("Rescan required at: {}", listed_dir.display());
+            self.last_suggestion = None;
+            self.pending = Some(listed_dir.clone());
+            return Suggestion::Rescan(listed_dir);
+        }
+        // hidden directories are completed only if asked for
+        let suggestions: Vec<String> = self
             .files
             .iter()
-            .filter(|(path, file_name)| {
-                if is_path {
-                    path.contains(input)
-                } else {
-                    file_name.contains(input)
-                }
+            .filter(|name| {
+                name.starts_with(prefix) && (prefix.starts_with('.') || !name.starts_with('.'))
             })
-            .map(|(path, _)| path)
+            .map(|name| format!("{dir}{name}/"))
             .collect();
-
         debug!("Suggestions for {input}: {:?}", suggestions);
-
-        // case 1. if suggestions not empty; then suggest next
-        if !suggestions.is_empty() {
-            let suggestion;
-            if let Some(last_suggestion) = self.last_suggestion.take() {
-                suggestion = suggestions
-                    .iter()
-                    .skip_while(|f| **f != &last_suggestion)
-                    .nth(1)
-                    .unwrap_or_else(|| suggestions.first().unwrap())
-                    .to_string();
-            } else {
-                suggestion = suggestions.first().map(|x| x.to_string()).unwrap();
+        let last_index = self
+            .last_suggestion
+            .take()
+            .and_then(|last| suggestions.iter().position(|x| *x == last));
+        match (last_index, suggestions.len()) {
+            (_, 0) => Suggestion::None,
+            // the only match has been accepted: complete its entries
+            (Some(_), 1) => {
+                let input = suggestions[0].clone();
+                self.search = Some(input.clone());
+                self.suggest(&input)
+            }
+            (last_index, len) => {
+                let suggestion =
+                    suggestions[last_index.map(|x| (x + 1) % len).unwrap_or_default()].clone();
+                debug!("Suggested: {suggestion}");
+                self.last_suggestion = Some(suggestion.clone());
+                Suggestion::Suggest(suggestion)
             }
-
-            debug!("Suggested: {suggestion}");
-            self.last_suggestion = Some(suggestion.clone());
-
-            return Suggestion::Suggest(suggestion);
-        }
-
-        self.last_suggestion = None;
-
-        // case 2. otherwise convert suggest to a path and get the parent
-        // to rescan the files
-        let input_as_path = if input.starts_with('/') {
-            input.to_string()
-        } else {
-            format!("./{}", input)
-        };
-
-        let p = PathBuf::from(input_as_path);
-        let parent = p
-            .parent()
-            .map(|p| p.to_path_buf())
-            .unwrap_or_else(|| PathBuf::from("/"));
-
-        // if path is `.`, then return None
-        if parent == PathBuf::from(".") {
-            return Suggestion::None;
         }
-
-        debug!("Rescan required at: {}", parent.display());
-
-        Suggestion::Rescan(parent)
     }
 }
 
@@ -174,8 +167,13 @@ pub struct GotoPopup {
 }
 
 impl GotoPopup {
-    pub fn new(color: Color, files: Vec<String>) -> Self {
-        let mut states = OwnStates::default();
+    /// Instantiates the popup, whose relative paths refer to `wrkdir`, which contains the directories `files`
+    pub fn new(color: Color, wrkdir: &Path, files: Vec<String>) -> Self {
+        let mut states = OwnStates {
+            wrkdir: wrkdir.to_path_buf(),
+            pending: Some(wrkdir.to_path_buf()),
+            ..Default::default()
+        };
         states.set_files(files);
 
         Self {
@@ -249,6 +247,7 @@ impl MockComponent for GotoPopup {
                 let res = self.input.perform(cmd);
                 if let CmdResult::Changed(State::One(StateValue::String(new_text))) = &res {
                     self.states.search = Some(new_text.clone());
+                    self.states.last_suggestion = None;
                 }
                 res
             }
@@ -342,94 +341,94 @@ mod test {
         assert_eq!(s, s2);
     }
 
-    #[test]
-    fn test_should_suggest_next() {
+    fn make_states(wrkdir: &str, files: &[&str]) -> OwnStates {
         let mut states = OwnStates {
-            files: vec![
-                ("/home/foo".to_string(), "foo".to_string()),
-                ("/home/bar".to_string(), "bar".to_string()),
-                ("/home/buzz".to_string(), "buzz".to_string()),
-                ("/home/fizz".to_string(), "fizz".to_string()),
-            ],
-            search: None,
-            last_suggestion: None,
+            wrkdir: PathBuf::from(wrkdir),
+            pending: Some(PathBuf::from(wrkdir)),
+            ..Default::default()
         };
+        states.set_files(files.iter().map(|x| format!("{wrkdir}/{x}")).collect());
+        states
+    }
+
+    #[test]
+    fn test_should_suggest_next() {
+        let mut states = make_states("/home", &["foo", "bar", "buzz", "fizz"]);
 
         let s = states.suggest("f");
-        assert_eq!(Suggestion::Suggest("/home/foo".to_string()), s);
+        assert_eq!(Suggestion::Suggest("fizz/".to_string()), s);
         let s = states.suggest("f");
-        assert_eq!(Suggestion::Suggest("/home/fizz".to_string()), s);
+        assert_eq!(Suggestion::Suggest("foo/".to_string()), s);
 
         let s = states.suggest("f");
-        assert_eq!(Suggestion::Suggest("/home/foo".to_string()), s);
+        assert_eq!(Suggestion::Suggest("fizz/".to_string()), s);
+        assert_eq!(states.computed_search().as_str(), "fizz/");
     }
 
     #[test]
     #[cfg(posix)]
     fn test_should_suggest_absolute_path() {
-        let mut states = OwnStates {
-            files: vec![
-                ("/home/foo".to_string(), "foo".to_string()),
-                ("/home/bar".to_string(), "bar".to_string()),
-                ("/home/buzz".to_string(), "buzz".to_string()),
-                ("/home/fizz".to_string(), "fizz".to_string()),
-            ],
-            search: None,
-            last_suggestion: None,
-        };
+        let mut states = make_states("/home", &["foo", "bar", "buzz", "fizz"]);
 
-        let s = states.suggest("/home/f");
-        assert_eq!(Suggestion::Suggest("/home/foo".to_string()), s);
+        let s = states.suggest("/home/b");
+        assert_eq!(Suggestion::Suggest("/home/bar/".to_string()), s);
+        let s = states.suggest("/home/b");
+        assert_eq!(Suggestion::Suggest("/home/buzz/".to_string()), s);
     }
 
     #[test]
+    #[cfg(posix)]
     fn test_should_suggest_rescan() {
-        let mut states = OwnStates {
-            files: vec![
-                ("/home/foo".to_string(), "foo".to_string()),
-                ("/home/bar".to_string(), "bar".to_string()),
-                ("/home/buzz".to_string(), "buzz".to_string()),
-                ("/home/fizz".to_string(), "fizz".to_string()),
-            ],
-            search: None,
-            last_suggestion: None,
-        };
+        let mut states = make_states("/home", &["foo", "bar", "buzz", "fizz"]);
+
+        let s = states.suggest("/var/l");
+        assert_eq!(Suggestion::Rescan(PathBuf::from("/var")), s);
+        // relative to the working directory
+        let s = states.suggest("foo/../bar/b");
+        assert_eq!(Suggestion::Rescan(PathBuf::from("/home/bar")), s);
+        // once listed, the directory is cached
+        states.set_files(vec![
+            "/home/bar/baz".to_string(),
+            "/home/bar/qux".to_string(),
+        ]);
+        let s = states.suggest("foo/../bar/b");
+        assert_eq!(Suggestion::Suggest("foo/../bar/baz/".to_string()), s);
+        let s = states.suggest("foo/../bar/q");
+        assert_eq!(Suggestion::Suggest("foo/../bar/qux/".to_string()), s);
+    }
 
-        let s = states.suggest("/home/user");
-        assert_eq!(Suggestion::Rescan(PathBuf::from("/home")), s);
+    #[test]
+    #[cfg(posix)]
+    fn test_should_complete_entries_of_only_match() {
+        let mut states = make_states("/home", &["foo", "bar"]);
+
+        let s = states.suggest("fo");
+        assert_eq!(Suggestion::Suggest("foo/".to_string()), s);
+        // the only match is accepted
+        let s = states.suggest("fo");
+        assert_eq!(Suggestion::Rescan(PathBuf::from("/home/foo")), s);
+        assert_eq!(states.computed_search().as_str(), "foo/");
     }
 
     #[test]
     fn test_should_suggest_none() {
-        let mut states = OwnStates {
-            files: vec![
-                ("/home/foo".to_string(), "foo".to_string()),
-                ("/home/bar".to_string(), "bar".to_string()),
-                ("/home/buzz".to_string(), "buzz".to_string()),
-                ("/home/fizz".to_string(), "fizz".to_string()),
-            ],
-            search: None,
-            last_suggestion: None,
-        };
+        let mut states = make_states("/home", &["foo", "bar", "buzz", "fizz"]);
 
-        let s = states.suggest("");
-        assert_eq!(Suggestion::Suggest("/home/foo".to_string()), s);
+        let s = states.suggest("qu");
+        assert_eq!(Suggestion::None, s);
+        // listing failed
+        let mut states = make_states("/home", &[]);
+        let s = states.suggest("f");
+        assert_eq!(Suggestion::None, s);
     }
 
     #[test]
-    fn test_should_suggest_none_if_dot() {
-        let mut states = OwnStates {
-            files: vec![
-                ("/home/foo".to_string(), "foo".to_string()),
-                ("/home/bar".to_string(), "bar".to_string()),
-                ("/home/buzz".to_string(), "buzz".to_string()),
-                ("/home/fizz".to_string(), "fizz".to_string()),
-            ],
-            search: None,
-            last_suggestion: None,
-        };
+    fn test_should_suggest_hidden_only_if_asked() {
+        let mut states = make_states("/home", &[".config", "code"]);
 
-        let s = states.suggest("./th");
-        assert_eq!(Suggestion::None, s);
+        let s = states.suggest("");
+        assert_eq!(Suggestion::Suggest("code/".to_string()), s);
+        let s = states.suggest(".c");
+        assert_eq!(Suggestion::Suggest(".config/".to_string()), s);
     }
 }
@@ -0,0 +1,84 @@
+use tui_realm_stdlib::Textarea;
+use tuirealm::command::{Cmd, Direction, Position};
+use tuirealm::event::{Key, KeyEvent, KeyModifiers};
+use tuirealm::props::{Alignment, BorderType, Borders, Color, TextSpan};
+use tuirealm::{Component, Event, MockComponent, NoUserEvent};
+
+use super::{Msg, TransferMsg, UiMsg};
+
+/// Read-only preview of the content of a remote file
+#[derive(MockComponent)]
+pub struct PreviewPopup {
+    component: Textarea,
+}
+
+impl PreviewPopup {
+    pub fn new(title: String, lines: &[String], color: Color) -> Self {
+        Self {
+            component: Textarea::default()
+                .borders(
+                    Borders::default()
+                        .color(color)
+                        .modifiers(BorderType::Rounded),
+                )
+                .title(title, Alignment::Center)
+                .step(8)
+                .highlighted_str("> ")
+                .text_rows(Self::rows(lines).as_slice()),
+        }
+    }
+
+    /// Make the rows of the text area from `lines`
+    pub fn rows(lines: &[String]) -> Vec<TextSpan> {
+        lines.iter().map(TextSpan::from).collect()
+    }
+}
+
+impl Component<Msg, NoUserEvent> for PreviewPopup {
+    fn on(&mut self, ev: Event<NoUserEvent>) -> Option<Msg> {
+        match ev {
+            Event::Keyboard(KeyEvent {
+                code: Key::Esc | Key::Enter,
+                ..
+            }) => Some(Msg::Ui(UiMsg::ClosePreviewPopup)),
+            Event::Keyboard(KeyEvent {
+                code: Key::Char('m'),
+                modifiers: KeyModifiers::NONE,
+            }) => Some(Msg::Transfer(TransferMsg::LoadMorePreview)),
+            Event::Keyboard(KeyEvent {
+                code: Key::Down, ..
+            }) => {
+                self.perform(Cmd::Move(Direction::Down));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent { code: Key::Up, .. }) => {
+                self.perform(Cmd::Move(Direction::Up));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::PageDown,
+                ..
+            }) => {
+                self.perform(Cmd::Scroll(Direction::Down));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::PageUp, ..
+            }) => {
+                self.perform(Cmd::Scroll(Direction::Up));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent {
+                code: Key::Home, ..
+            }) => {
+                self.perform(Cmd::GoTo(Position::Begin));
+                Some(Msg::None)
+            }
+            Event::Keyboard(KeyEvent { code: Key::End, .. }) => {
+                self.perform(Cmd::GoTo(Position::End));
+                Some(Msg::None)
+            }
+            _ => None,
+        }
+    }
+}
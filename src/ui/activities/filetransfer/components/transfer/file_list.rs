@@ -2,17 +2,53 @@
 //!
 //! `FileList` component renders a file list tab
 
+use std::time::{Duration, Instant};
+
 use tuirealm::command::{Cmd, CmdResult, Direction, Position};
 use tuirealm::props::{
-    Alignment, AttrValue, Attribute, Borders, Color, Style, Table, TextModifiers, TextSpan,
+    Alignment, AttrValue, Attribute, Borders, Color, PropPayload, PropValue, Style, Table,
+    TextModifiers, TextSpan,
 };
+use tuirealm::ratatui::layout::{Margin, Rect};
 use tuirealm::ratatui::text::{Line, Span};
 use tuirealm::ratatui::widgets::{List as TuiList, ListDirection, ListItem, ListState};
 use tuirealm::{MockComponent, Props, State, StateValue};
 
 pub const FILE_LIST_CMD_SELECT_ALL: &str = "A";
 pub const FILE_LIST_CMD_DESELECT_ALL: &str = "D";
+/// Attribute to move the list index to the file at the provided index (`AttrValue::Length`)
+pub const FILE_LIST_PROP_LIST_INDEX: &str = "list_index";
+/// Attribute with the indexes of the files which are listed, but skipped when selecting all the files
+/// (`AttrValue::Payload` of a `PropPayload::Vec` of `PropValue::Usize`)
+pub const FILE_LIST_PROP_UNSELECTABLE: &str = "unselectable";
 const PROP_DOT_DOT: &str = "dot_dot";
+/// A second click on the same row within this interval is a double click
+const DOUBLE_CLICK_INTERVAL: Duration = Duration::from_millis(500);
+/// Rows moved by a step of the mouse wheel
+const MOUSE_SCROLL_ROWS: usize = 3;
+
+/// Outcome of a mouse click on the file list
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Click {
+    /// The click is outside of the file list
+    Outside,
+    /// The click is on the file list, but not on a row
+    List,
+    /// The row has been clicked and highlighted
+    Row,
+    /// The highlighted row has been clicked twice
+    Double,
+}
+
+/// Layout of the list the last time it has been rendered, to map the mouse events onto the rows
+#[derive(Clone, Default)]
+struct MouseStates {
+    area: Rect,
+    /// Index of the first row displayed
+    offset: usize,
+    /// Row clicked the last time and when
+    last_click: Option<(usize, Instant)>,
+}
 
 /// OwnStates contains states for this component
 #[derive(Clone, Default)]
@@ -59,6 +95,12 @@ impl OwnStates {
         self.list_index = 0;
     }
 
+    /// Move list index to `index`; if out of bounds, the index is moved to the last element
+    pub fn list_index_at(&mut self, index: usize) {
+        self.list_index = index;
+        self.fix_list_index();
+    }
+
     pub fn list_index_at_last(&mut self) {
         self.list_index = match self.list_len() {
             0 => 0,
@@ -107,10 +149,15 @@ impl OwnStates {
         self.incr_list_index(false);
     }
 
-    /// Select all files
-    pub fn select_all(&mut self, has_dot_dot: bool) {
-        for i in 0..self.list_len() {
-            self.select(i + if has_dot_dot { 1 } else { 0 });
+    /// Select all files, but the `unselectable` ones.
+    /// Only the listed entries are selected, so the `..` entry is never part of the selection
+    pub fn select_all(&mut self, has_dot_dot: bool, unselectable: &[usize]) {
+        let files = match has_dot_dot {
+            true => self.list_len().saturating_sub(1),
+            false => self.list_len(),
+        };
+        for i in (0..files).filter(|x| !unselectable.contains(x)) {
+            self.select(i);
         }
     }
 
@@ -138,6 +185,7 @@ impl OwnStates {
 pub struct FileList {
     props: Props,
     states: OwnStates,
+    mouse: MouseStates,
 }
 
 impl FileList {
@@ -187,6 +235,76 @@ impl FileList {
             .map(|x| x.unwrap_flag())
             .unwrap_or(false)
     }
+
+    /// Returns the indexes of the files skipped when selecting all the files
+    fn unselectable(&self) -> Vec<usize> {
+        match self
+            .props
+            .get(Attribute::Custom(FILE_LIST_PROP_UNSELECTABLE))
+        {
+            Some(AttrValue::Payload(PropPayload::Vec(values))) => values
+                .into_iter()
+                .filter_map(|x| match x {
+                    PropValue::Usize(index) => Some(index),
+                    _ => None,
+                })
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Returns whether the list has the focus
+    pub fn is_focused(&self) -> bool {
+        self.props
+            .get_or(Attribute::Focus, AttrValue::Flag(false))
+            .unwrap_flag()
+    }
+
+    /// Returns whether `column` and `row` of the terminal are within the list
+    pub fn contains(&self, column: u16, row: u16) -> bool {
+        let area = self.mouse.area;
+        (area.left()..area.right()).contains(&column) && (area.top()..area.bottom()).contains(&row)
+    }
+
+    /// Handle a click at `column` and `row` of the terminal, highlighting the clicked row
+    pub fn click(&mut self, column: u16, row: u16) -> Click {
+        if !self.contains(column, row) {
+            return Click::Outside;
+        }
+        let rows = self.mouse.area.inner(Margin::new(1, 1));
+        if !(rows.left()..rows.right()).contains(&column)
+            || !(rows.top()..rows.bottom()).contains(&row)
+        {
+            return Click::List;
+        }
+        let index = self.mouse.offset + usize::from(row - rows.top());
+        if index >= self.states.list_len() {
+            return Click::List;
+        }
+        self.states.list_index_at(index);
+        let now = Instant::now();
+        match self.mouse.last_click.take() {
+            Some((last, at))
+                if last == index && now.duration_since(at) <= DOUBLE_CLICK_INTERVAL =>
+            {
+                Click::Double
+            }
+            _ => {
+                self.mouse.last_click = Some((index, now));
+                Click::Row
+            }
+        }
+    }
+
+    /// Move the list index by the rows of a step of the mouse wheel, down if `down`
+    pub fn wheel(&mut self, down: bool) {
+        for _ in 0..MOUSE_SCROLL_ROWS {
+            match down {
+                true => self.states.incr_list_index(false),
+                false => self.states.decr_list_index(false),
+            }
+        }
+    }
 }
 
 impl MockComponent for FileList {
@@ -206,7 +324,15 @@ impl MockComponent for FileList {
             .props
             .get_or(Attribute::Focus, AttrValue::Flag(false))
             .unwrap_flag();
-        let div = tui_realm_stdlib::utils::get_block(borders, Some(title), focus, None);
+        let inactive_style = self
+            .props
+            .get(Attribute::FocusStyle)
+            .map(|x| x.unwrap_style());
+        let mut div =
+            tui_realm_stdlib::utils::get_block(borders, Some(title), focus, inactive_style);
+        if let (false, Some(inactive_style)) = (focus, inactive_style) {
+            div = div.title_style(inactive_style);
+        }
         // Make list entries
         let init_table_iter = if self.has_dot_dot() {
             vec![vec![TextSpan::from("..")]]
@@ -277,9 +403,18 @@ impl MockComponent for FileList {
         let mut state: ListState = ListState::default();
         state.select(Some(self.states.list_index));
         frame.render_stateful_widget(list, area, &mut state);
+        self.mouse.area = area;
+        self.mouse.offset = state.offset();
     }
 
     fn attr(&mut self, attr: Attribute, value: AttrValue) {
+        if let (Attribute::Custom(FILE_LIST_PROP_LIST_INDEX), AttrValue::Length(index)) =
+            (attr, &value)
+        {
+            let offset = if self.has_dot_dot() { 1 } else { 0 };
+            self.states.list_index_at(index + offset);
+            return;
+        }
         self.props.set(attr, value);
         if matches!(attr, Attribute::Content) {
             self.states.init_list_states(
@@ -375,7 +510,8 @@ impl MockComponent for FileList {
                 }
             }
             Cmd::Custom(FILE_LIST_CMD_SELECT_ALL) => {
-                self.states.select_all(self.has_dot_dot());
+                self.states
+                    .select_all(self.has_dot_dot(), self.unselectable().as_slice());
                 CmdResult::None
             }
             Cmd::Custom(FILE_LIST_CMD_DESELECT_ALL) => {
@@ -398,3 +534,97 @@ impl MockComponent for FileList {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn should_select_all_listed_files() {
+        let mut states = OwnStates::default();
+        states.init_list_states(3, false);
+        states.select_all(false, &[]);
+        let mut selection = states.get_selection();
+        selection.sort();
+        assert_eq!(selection, vec![0, 1, 2]);
+        states.deselect_all();
+        assert!(states.is_selection_empty());
+    }
+
+    #[test]
+    fn should_select_all_files_without_dot_dot() {
+        let mut states = OwnStates::default();
+        states.init_list_states(3, true);
+        states.select_all(true, &[]);
+        let mut selection = states.get_selection();
+        selection.sort();
+        assert_eq!(selection, vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn should_select_all_filtered_files_only() {
+        // the filter view only lists the files matching the filter
+        let mut component = FileList::default().rows(vec![
+            vec![TextSpan::from("foo.txt")],
+            vec![TextSpan::from("bar.txt")],
+        ]);
+        component.perform(Cmd::Custom(FILE_LIST_CMD_SELECT_ALL));
+        assert_eq!(
+            component.state(),
+            State::Vec(vec![StateValue::Usize(0), StateValue::Usize(1)])
+        );
+        // applying a new filter resets the selection
+        component.attr(
+            Attribute::Content,
+            AttrValue::Table(vec![vec![TextSpan::from("foo.txt")]]),
+        );
+        assert_eq!(component.state(), State::One(StateValue::Usize(0)));
+        component.perform(Cmd::Custom(FILE_LIST_CMD_SELECT_ALL));
+        assert_eq!(component.state(), State::Vec(vec![StateValue::Usize(0)]));
+    }
+
+    #[test]
+    fn should_not_select_unselectable_files() {
+        // the directories which don't match the filter are listed, but not selected
+        let mut component = FileList::default().rows(vec![
+            vec![TextSpan::from("docs/")],
+            vec![TextSpan::from("foo.txt")],
+            vec![TextSpan::from("src/")],
+        ]);
+        component.attr(
+            Attribute::Custom(FILE_LIST_PROP_UNSELECTABLE),
+            AttrValue::Payload(PropPayload::Vec(vec![
+                PropValue::Usize(0),
+                PropValue::Usize(2),
+            ])),
+        );
+        component.perform(Cmd::Custom(FILE_LIST_CMD_SELECT_ALL));
+        assert_eq!(component.state(), State::Vec(vec![StateValue::Usize(1)]));
+    }
+
+    #[test]
+    fn should_move_list_index_to_file() {
+        let rows = vec![
+            vec![TextSpan::from("foo.txt")],
+            vec![TextSpan::from("bar.txt")],
+            vec![TextSpan::from("baz.txt")],
+        ];
+        let mut component = FileList::default().rows(rows.clone());
+        component.attr(
+            Attribute::Custom(FILE_LIST_PROP_LIST_INDEX),
+            AttrValue::Length(2),
+        );
+        assert_eq!(component.state(), State::One(StateValue::Usize(2)));
+        // the `..` entry is skipped
+        let mut component = FileList::default().dot_dot(true).rows(rows);
+        component.attr(
+            Attribute::Custom(FILE_LIST_PROP_LIST_INDEX),
+            AttrValue::Length(1),
+        );
+        assert_eq!(component.state(), State::One(StateValue::Usize(1)));
+        assert_eq!(component.states.list_index(), 2);
+    }
+}
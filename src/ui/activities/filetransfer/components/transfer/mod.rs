@@ -6,21 +6,33 @@ mod file_list;
 mod file_list_with_search;
 
 use tuirealm::command::{Cmd, CmdResult, Direction, Position};
-use tuirealm::event::{Key, KeyEvent, KeyModifiers};
+use tuirealm::event::{Key, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
 use tuirealm::props::{Alignment, Borders, Color, TextSpan};
 use tuirealm::{Component, Event, MockComponent, NoUserEvent, State, StateValue};
 
-use self::file_list::FileList;
+use self::file_list::{Click, FileList};
+pub use self::file_list::{FILE_LIST_PROP_LIST_INDEX, FILE_LIST_PROP_UNSELECTABLE};
 use self::file_list_with_search::FileListWithSearch;
+use super::super::browser::{FileExplorerTab, PanelSide};
 use super::{Msg, TransferMsg, UiMsg};
+use crate::config::keymap::{KeyAction, Keymap};
+use crate::ui::activities::filetransfer::lib::sync::SyncMode;
 
 #[derive(MockComponent)]
 pub struct ExplorerFuzzy {
     component: FileListWithSearch,
+    keymap: Keymap,
 }
 
 impl ExplorerFuzzy {
-    pub fn new<S: AsRef<str>>(title: S, files: &[&str], bg: Color, fg: Color, hg: Color) -> Self {
+    pub fn new<S: AsRef<str>>(
+        title: S,
+        files: &[&str],
+        bg: Color,
+        fg: Color,
+        hg: Color,
+        keymap: &Keymap,
+    ) -> Self {
         Self {
             component: FileListWithSearch::default()
                 .background(bg)
@@ -29,6 +41,7 @@ impl ExplorerFuzzy {
                 .highlighted_color(hg)
                 .title(title, Alignment::Left)
                 .rows(files.iter().map(|x| vec![TextSpan::from(x)]).collect()),
+            keymap: keymap.clone(),
         }
     }
 
@@ -104,13 +117,14 @@ impl ExplorerFuzzy {
                 self.perform(Cmd::Move(Direction::Up));
                 Some(Msg::None)
             }
+            // scrolling past the end of the results loads the ones spilled to disk
             Event::Keyboard(KeyEvent {
                 code: Key::PageDown,
                 ..
-            }) => {
-                self.perform(Cmd::Scroll(Direction::Down));
-                Some(Msg::None)
-            }
+            }) => match self.perform(Cmd::Scroll(Direction::Down)) {
+                CmdResult::None => Some(Msg::Ui(UiMsg::LoadMoreFound)),
+                _ => Some(Msg::None),
+            },
             Event::Keyboard(KeyEvent {
                 code: Key::PageUp, ..
             }) => {
@@ -125,28 +139,7 @@ impl ExplorerFuzzy {
             }
             Event::Keyboard(KeyEvent { code: Key::End, .. }) => {
                 self.perform(Cmd::GoTo(Position::End));
-                Some(Msg::None)
-            }
-            Event::Keyboard(KeyEvent {
-                code: Key::Char('a'),
-                modifiers: KeyModifiers::CONTROL,
-            }) => {
-                let _ = self.perform(Cmd::Custom(file_list::FILE_LIST_CMD_SELECT_ALL));
-                Some(Msg::None)
-            }
-            Event::Keyboard(KeyEvent {
-                code: Key::Char('a'),
-                modifiers: KeyModifiers::ALT,
-            }) => {
-                let _ = self.perform(Cmd::Custom(file_list::FILE_LIST_CMD_DESELECT_ALL));
-                Some(Msg::None)
-            }
-            Event::Keyboard(KeyEvent {
-                code: Key::Char('m'),
-                modifiers: KeyModifiers::NONE,
-            }) => {
-                let _ = self.perform(Cmd::Toggle);
-                Some(Msg::None)
+                Some(Msg::Ui(UiMsg::LoadMoreFound))
             }
             Event::Keyboard(KeyEvent { code: Key::Tab, .. }) => {
                 self.perform(Cmd::Change);
@@ -157,52 +150,33 @@ impl ExplorerFuzzy {
                 Some(Msg::Ui(UiMsg::CloseFindExplorer))
             }
             Event::Keyboard(KeyEvent {
-                code: Key::Left | Key::Right,
-                ..
-            }) => Some(Msg::Ui(UiMsg::ChangeTransferWindow)),
+                code: Key::Left, ..
+            }) => Some(Msg::Ui(UiMsg::FocusPanel(PanelSide::Left))),
+            Event::Keyboard(KeyEvent {
+                code: Key::Right, ..
+            }) => Some(Msg::Ui(UiMsg::FocusPanel(PanelSide::Right))),
             Event::Keyboard(KeyEvent {
                 code: Key::Enter, ..
             }) => Some(Msg::Transfer(TransferMsg::EnterDirectory)),
-            Event::Keyboard(KeyEvent {
-                code: Key::Char(' '),
-                ..
-            }) => Some(Msg::Transfer(TransferMsg::TransferFile)),
             Event::Keyboard(KeyEvent {
                 code: Key::Backspace,
                 ..
             }) => Some(Msg::Transfer(TransferMsg::GoToPreviousDirectory)),
-            Event::Keyboard(KeyEvent {
-                code: Key::Char('a'),
-                modifiers: KeyModifiers::NONE,
-            }) => Some(Msg::Ui(UiMsg::ToggleHiddenFiles)),
-            Event::Keyboard(KeyEvent {
-                code: Key::Char('b'),
-                modifiers: KeyModifiers::NONE,
-            }) => Some(Msg::Ui(UiMsg::ShowFileSortingPopup)),
-            Event::Keyboard(KeyEvent {
-                code: Key::Char('e') | Key::Delete | Key::Function(8),
-                modifiers: KeyModifiers::NONE,
-            }) => Some(Msg::Ui(UiMsg::ShowDeletePopup)),
-            Event::Keyboard(KeyEvent {
-                code: Key::Char('i'),
-                modifiers: KeyModifiers::NONE,
-            }) => Some(Msg::Ui(UiMsg::ShowFileInfoPopup)),
-            Event::Keyboard(KeyEvent {
-                code: Key::Char('s') | Key::Function(2),
-                modifiers: KeyModifiers::NONE,
-            }) => Some(Msg::Ui(UiMsg::ShowSaveAsPopup)),
-            Event::Keyboard(KeyEvent {
-                code: Key::Char('v') | Key::Function(3),
-                modifiers: KeyModifiers::NONE,
-            }) => Some(Msg::Transfer(TransferMsg::OpenFile)),
-            Event::Keyboard(KeyEvent {
-                code: Key::Char('w'),
-                modifiers: KeyModifiers::NONE,
-            }) => Some(Msg::Ui(UiMsg::ShowOpenWithPopup)),
-            Event::Keyboard(KeyEvent {
-                code: Key::Char('z'),
-                modifiers: KeyModifiers::NONE,
-            }) => Some(Msg::Ui(UiMsg::ShowChmodPopup)),
+            Event::Keyboard(ev) => match self.keymap.action(&ev)? {
+                action @ (KeyAction::Transfer
+                | KeyAction::Mark
+                | KeyAction::SelectAll
+                | KeyAction::DeselectAll
+                | KeyAction::ToggleHidden
+                | KeyAction::FileSorting
+                | KeyAction::ShowDelete
+                | KeyAction::FileInfo
+                | KeyAction::SaveAs
+                | KeyAction::Open
+                | KeyAction::OpenWith
+                | KeyAction::Chmod) => on_action(&mut self.component, action),
+                _ => None,
+            },
             _ => None,
         }
     }
@@ -220,10 +194,18 @@ impl Component<Msg, NoUserEvent> for ExplorerFuzzy {
 #[derive(MockComponent)]
 pub struct ExplorerFind {
     component: FileList,
+    keymap: Keymap,
 }
 
 impl ExplorerFind {
-    pub fn new<S: AsRef<str>>(title: S, files: &[&str], bg: Color, fg: Color, hg: Color) -> Self {
+    pub fn new<S: AsRef<str>>(
+        title: S,
+        files: &[&str],
+        bg: Color,
+        fg: Color,
+        hg: Color,
+        keymap: &Keymap,
+    ) -> Self {
         Self {
             component: FileList::default()
                 .background(bg)
@@ -232,12 +214,16 @@ impl ExplorerFind {
                 .highlighted_color(hg)
                 .title(title, Alignment::Left)
                 .rows(files.iter().map(|x| vec![TextSpan::from(x)]).collect()),
+            keymap: keymap.clone(),
         }
     }
 }
 
 impl Component<Msg, NoUserEvent> for ExplorerFind {
     fn on(&mut self, ev: Event<NoUserEvent>) -> Option<Msg> {
+        if !is_for_explorer(&self.component, &ev) {
+            return None;
+        }
         match ev {
             Event::Keyboard(KeyEvent {
                 code: Key::Down, ..
@@ -272,78 +258,44 @@ impl Component<Msg, NoUserEvent> for ExplorerFind {
                 self.perform(Cmd::GoTo(Position::End));
                 Some(Msg::None)
             }
-            Event::Keyboard(KeyEvent {
-                code: Key::Char('a'),
-                modifiers: KeyModifiers::CONTROL,
-            }) => {
-                let _ = self.perform(Cmd::Custom(file_list::FILE_LIST_CMD_SELECT_ALL));
-                Some(Msg::None)
-            }
-            Event::Keyboard(KeyEvent {
-                code: Key::Char('a'),
-                modifiers: KeyModifiers::ALT,
-            }) => {
-                let _ = self.perform(Cmd::Custom(file_list::FILE_LIST_CMD_DESELECT_ALL));
-                Some(Msg::None)
-            }
-            Event::Keyboard(KeyEvent {
-                code: Key::Char('m'),
-                modifiers: KeyModifiers::NONE,
-            }) => {
-                let _ = self.perform(Cmd::Toggle);
-                Some(Msg::None)
-            }
             // -- comp msg
             Event::Keyboard(KeyEvent { code: Key::Esc, .. }) => {
                 Some(Msg::Ui(UiMsg::CloseFindExplorer))
             }
             Event::Keyboard(KeyEvent {
-                code: Key::Left | Key::Right | Key::Tab | Key::BackTab,
+                code: Key::Left, ..
+            }) => Some(Msg::Ui(UiMsg::FocusPanel(PanelSide::Left))),
+            Event::Keyboard(KeyEvent {
+                code: Key::Right, ..
+            }) => Some(Msg::Ui(UiMsg::FocusPanel(PanelSide::Right))),
+            Event::Keyboard(KeyEvent {
+                code: Key::Tab | Key::BackTab,
                 ..
             }) => Some(Msg::Ui(UiMsg::ChangeTransferWindow)),
             Event::Keyboard(KeyEvent {
                 code: Key::Enter, ..
             }) => Some(Msg::Transfer(TransferMsg::EnterDirectory)),
-            Event::Keyboard(KeyEvent {
-                code: Key::Char(' '),
-                ..
-            }) => Some(Msg::Transfer(TransferMsg::TransferFile)),
             Event::Keyboard(KeyEvent {
                 code: Key::Backspace,
                 ..
             }) => Some(Msg::Transfer(TransferMsg::GoToPreviousDirectory)),
-            Event::Keyboard(KeyEvent {
-                code: Key::Char('a'),
-                modifiers: KeyModifiers::NONE,
-            }) => Some(Msg::Ui(UiMsg::ToggleHiddenFiles)),
-            Event::Keyboard(KeyEvent {
-                code: Key::Char('b'),
-                modifiers: KeyModifiers::NONE,
-            }) => Some(Msg::Ui(UiMsg::ShowFileSortingPopup)),
-            Event::Keyboard(KeyEvent {
-                code: Key::Char('e') | Key::Delete | Key::Function(8),
-                modifiers: KeyModifiers::NONE,
-            }) => Some(Msg::Ui(UiMsg::ShowDeletePopup)),
-            Event::Keyboard(KeyEvent {
-                code: Key::Char('i'),
-                modifiers: KeyModifiers::NONE,
-            }) => Some(Msg::Ui(UiMsg::ShowFileInfoPopup)),
-            Event::Keyboard(KeyEvent {
-                code: Key::Char('s') | Key::Function(2),
-                modifiers: KeyModifiers::NONE,
-            }) => Some(Msg::Ui(UiMsg::ShowSaveAsPopup)),
-            Event::Keyboard(KeyEvent {
-                code: Key::Char('v') | Key::Function(3),
-                modifiers: KeyModifiers::NONE,
-            }) => Some(Msg::Transfer(TransferMsg::OpenFile)),
-            Event::Keyboard(KeyEvent {
-                code: Key::Char('w'),
-                modifiers: KeyModifiers::NONE,
-            }) => Some(Msg::Ui(UiMsg::ShowOpenWithPopup)),
-            Event::Keyboard(KeyEvent {
-                code: Key::Char('z'),
-                modifiers: KeyModifiers::NONE,
-            }) => Some(Msg::Ui(UiMsg::ShowChmodPopup)),
+            Event::Keyboard(ev) => match self.keymap.action(&ev)? {
+                action @ (KeyAction::Transfer
+                | KeyAction::Mark
+                | KeyAction::SelectAll
+                | KeyAction::DeselectAll
+                | KeyAction::ToggleHidden
+                | KeyAction::FileSorting
+                | KeyAction::ShowDelete
+                | KeyAction::FileInfo
+                | KeyAction::SaveAs
+                | KeyAction::Open
+                | KeyAction::OpenWith
+                | KeyAction::Chmod) => on_action(&mut self.component, action),
+                _ => None,
+            },
+            // the find explorer replaces the explorer of either side
+            Event::Mouse(ev) => on_mouse(&mut self.component, ev, FileExplorerTab::FindHostBridge),
             _ => None,
         }
     }
@@ -352,10 +304,18 @@ impl Component<Msg, NoUserEvent> for ExplorerFind {
 #[derive(MockComponent)]
 pub struct ExplorerLocal {
     component: FileList,
+    keymap: Keymap,
 }
 
 impl ExplorerLocal {
-    pub fn new<S: AsRef<str>>(title: S, files: &[&str], bg: Color, fg: Color, hg: Color) -> Self {
+    pub fn new<S: AsRef<str>>(
+        title: S,
+        files: &[&str],
+        bg: Color,
+        fg: Color,
+        hg: Color,
+        keymap: &Keymap,
+    ) -> Self {
         Self {
             component: FileList::default()
                 .background(bg)
@@ -365,12 +325,16 @@ impl ExplorerLocal {
                 .title(title, Alignment::Left)
                 .rows(files.iter().map(|x| vec![TextSpan::from(x)]).collect())
                 .dot_dot(true),
+            keymap: keymap.clone(),
         }
     }
 }
 
 impl Component<Msg, NoUserEvent> for ExplorerLocal {
     fn on(&mut self, ev: Event<NoUserEvent>) -> Option<Msg> {
+        if !is_for_explorer(&self.component, &ev) {
+            return None;
+        }
         match ev {
             Event::Keyboard(KeyEvent {
                 code: Key::Down, ..
@@ -405,35 +369,24 @@ impl Component<Msg, NoUserEvent> for ExplorerLocal {
                 self.perform(Cmd::GoTo(Position::End));
                 Some(Msg::None)
             }
-            Event::Keyboard(KeyEvent {
-                code: Key::Char('a'),
-                modifiers: KeyModifiers::CONTROL,
-            }) => {
-                let _ = self.perform(Cmd::Custom(file_list::FILE_LIST_CMD_SELECT_ALL));
-                Some(Msg::None)
-            }
-            Event::Keyboard(KeyEvent {
-                code: Key::Char('a'),
-                modifiers: KeyModifiers::ALT,
-            }) => {
-                let _ = self.perform(Cmd::Custom(file_list::FILE_LIST_CMD_DESELECT_ALL));
-                Some(Msg::None)
-            }
-            Event::Keyboard(KeyEvent {
-                code: Key::Char('m'),
-                modifiers: KeyModifiers::NONE,
-            }) => {
-                let _ = self.perform(Cmd::Toggle);
-                Some(Msg::None)
-            }
             // -- comp msg
             Event::Keyboard(KeyEvent { code: Key::Esc, .. }) => {
                 Some(Msg::Ui(UiMsg::ShowDisconnectPopup))
             }
             Event::Keyboard(KeyEvent {
-                code: Key::Right | Key::Tab | Key::BackTab,
+                code: Key::Left, ..
+            }) => Some(Msg::Ui(UiMsg::FocusPanel(PanelSide::Left))),
+            Event::Keyboard(KeyEvent {
+                code: Key::Right, ..
+            }) => Some(Msg::Ui(UiMsg::FocusPanel(PanelSide::Right))),
+            Event::Keyboard(KeyEvent {
+                code: Key::Tab | Key::BackTab,
                 ..
             }) => Some(Msg::Ui(UiMsg::ChangeTransferWindow)),
+            Event::Keyboard(KeyEvent {
+                code: Key::Backspace,
+                modifiers: KeyModifiers::ALT,
+            }) => Some(Msg::Ui(UiMsg::ShowDirHistoryPopup)),
             Event::Keyboard(KeyEvent {
                 code: Key::Backspace,
                 ..
@@ -447,112 +400,16 @@ impl Component<Msg, NoUserEvent> for ExplorerLocal {
                     Some(Msg::Transfer(TransferMsg::EnterDirectory))
                 }
             }
-            Event::Keyboard(KeyEvent {
-                code: Key::Char(' '),
-                ..
-            }) => {
-                if matches!(self.component.state(), State::One(StateValue::String(_))) {
-                    Some(Msg::None)
-                } else {
-                    Some(Msg::Transfer(TransferMsg::TransferFile))
-                }
-            }
-            Event::Keyboard(KeyEvent {
-                code: Key::Char('a'),
-                modifiers: KeyModifiers::NONE,
-            }) => Some(Msg::Ui(UiMsg::ToggleHiddenFiles)),
-            Event::Keyboard(KeyEvent {
-                code: Key::Char('b'),
-                modifiers: KeyModifiers::NONE,
-            }) => Some(Msg::Ui(UiMsg::ShowFileSortingPopup)),
-            Event::Keyboard(KeyEvent {
-                code: Key::Char('c') | Key::Function(5),
-                modifiers: KeyModifiers::NONE,
-            }) => Some(Msg::Ui(UiMsg::ShowCopyPopup)),
-            Event::Keyboard(KeyEvent {
-                code: Key::Char('d') | Key::Function(7),
-                modifiers: KeyModifiers::NONE,
-            }) => Some(Msg::Ui(UiMsg::ShowMkdirPopup)),
-            Event::Keyboard(KeyEvent {
-                code: Key::Char('e') | Key::Delete | Key::Function(8),
-                modifiers: KeyModifiers::NONE,
-            }) => Some(Msg::Ui(UiMsg::ShowDeletePopup)),
-            Event::Keyboard(KeyEvent {
-                code: Key::Char('f'),
-                modifiers: KeyModifiers::NONE,
-            }) => Some(Msg::Transfer(TransferMsg::InitFuzzySearch)),
-            Event::Keyboard(KeyEvent {
-                code: Key::Char('g'),
-                modifiers: KeyModifiers::NONE,
-            }) => Some(Msg::Ui(UiMsg::ShowGotoPopup)),
-            Event::Keyboard(KeyEvent {
-                code: Key::Char('i'),
-                modifiers: KeyModifiers::NONE,
-            }) => Some(Msg::Ui(UiMsg::ShowFileInfoPopup)),
-            Event::Keyboard(KeyEvent {
-                code: Key::Char('k'),
-                modifiers: KeyModifiers::NONE,
-            }) => Some(Msg::Ui(UiMsg::ShowSymlinkPopup)),
-            Event::Keyboard(KeyEvent {
-                code: Key::Char('l'),
-                modifiers: KeyModifiers::NONE,
-            }) => Some(Msg::Transfer(TransferMsg::ReloadDir)),
-            Event::Keyboard(KeyEvent {
-                code: Key::Char('n'),
-                modifiers: KeyModifiers::NONE,
-            }) => Some(Msg::Ui(UiMsg::ShowNewFilePopup)),
-            Event::Keyboard(KeyEvent {
-                code: Key::Char('o') | Key::Function(4),
-                modifiers: KeyModifiers::NONE,
-            }) => Some(Msg::Transfer(TransferMsg::OpenTextFile)),
-            Event::Keyboard(KeyEvent {
-                code: Key::Char('p'),
-                modifiers: KeyModifiers::NONE,
-            }) => Some(Msg::Ui(UiMsg::ShowLogPanel)),
-            Event::Keyboard(KeyEvent {
-                code: Key::Char('r') | Key::Function(6),
-                modifiers: KeyModifiers::NONE,
-            }) => Some(Msg::Ui(UiMsg::ShowRenamePopup)),
-            Event::Keyboard(KeyEvent {
-                code: Key::Char('s') | Key::Function(2),
-                modifiers: KeyModifiers::NONE,
-            }) => Some(Msg::Ui(UiMsg::ShowSaveAsPopup)),
-            Event::Keyboard(KeyEvent {
-                code: Key::Char('t'),
-                modifiers: KeyModifiers::NONE,
-            }) => Some(Msg::Ui(UiMsg::ShowWatcherPopup)),
-            Event::Keyboard(KeyEvent {
-                code: Key::Char('t'),
-                modifiers: KeyModifiers::CONTROL,
-            }) => Some(Msg::Ui(UiMsg::ShowWatchedPathsList)),
-            Event::Keyboard(KeyEvent {
-                code: Key::Char('u'),
-                modifiers: KeyModifiers::NONE,
-            }) => Some(Msg::Transfer(TransferMsg::GoToParentDirectory)),
-            Event::Keyboard(KeyEvent {
-                code: Key::Char('x'),
-                modifiers: KeyModifiers::NONE,
-            }) => Some(Msg::Ui(UiMsg::ShowExecPopup)),
-            Event::Keyboard(KeyEvent {
-                code: Key::Char('y'),
-                modifiers: KeyModifiers::NONE,
-            }) => Some(Msg::Ui(UiMsg::ToggleSyncBrowsing)),
-            Event::Keyboard(KeyEvent {
-                code: Key::Char('v') | Key::Function(3),
-                modifiers: KeyModifiers::NONE,
-            }) => Some(Msg::Transfer(TransferMsg::OpenFile)),
-            Event::Keyboard(KeyEvent {
-                code: Key::Char('w'),
-                modifiers: KeyModifiers::NONE,
-            }) => Some(Msg::Ui(UiMsg::ShowOpenWithPopup)),
-            Event::Keyboard(KeyEvent {
-                code: Key::Char('z'),
-                modifiers: KeyModifiers::NONE,
-            }) => Some(Msg::Ui(UiMsg::ShowChmodPopup)),
-            Event::Keyboard(KeyEvent {
-                code: Key::Char('/'),
-                modifiers: KeyModifiers::NONE,
-            }) => Some(Msg::Ui(UiMsg::ShowFilterPopup)),
+            Event::Keyboard(ev) => match self.keymap.action(&ev)? {
+                // remote only
+                KeyAction::Favorites
+                | KeyAction::Preview
+                | KeyAction::ToggleFavorite
+                | KeyAction::OpenUrl
+                | KeyAction::CopyUrl => None,
+                action => on_action(&mut self.component, action),
+            },
+            Event::Mouse(ev) => on_mouse(&mut self.component, ev, FileExplorerTab::HostBridge),
             _ => None,
         }
     }
@@ -561,10 +418,18 @@ impl Component<Msg, NoUserEvent> for ExplorerLocal {
 #[derive(MockComponent)]
 pub struct ExplorerRemote {
     component: FileList,
+    keymap: Keymap,
 }
 
 impl ExplorerRemote {
-    pub fn new<S: AsRef<str>>(title: S, files: &[&str], bg: Color, fg: Color, hg: Color) -> Self {
+    pub fn new<S: AsRef<str>>(
+        title: S,
+        files: &[&str],
+        bg: Color,
+        fg: Color,
+        hg: Color,
+        keymap: &Keymap,
+    ) -> Self {
         Self {
             component: FileList::default()
                 .background(bg)
@@ -574,12 +439,16 @@ impl ExplorerRemote {
                 .title(title, Alignment::Left)
                 .rows(files.iter().map(|x| vec![TextSpan::from(x)]).collect())
                 .dot_dot(true),
+            keymap: keymap.clone(),
         }
     }
 }
 
 impl Component<Msg, NoUserEvent> for ExplorerRemote {
     fn on(&mut self, ev: Event<NoUserEvent>) -> Option<Msg> {
+        if !is_for_explorer(&self.component, &ev) {
+            return None;
+        }
         match ev {
             Event::Keyboard(KeyEvent {
                 code: Key::Down, ..
@@ -614,35 +483,24 @@ impl Component<Msg, NoUserEvent> for ExplorerRemote {
                 self.perform(Cmd::GoTo(Position::End));
                 Some(Msg::None)
             }
-            Event::Keyboard(KeyEvent {
-                code: Key::Char('a'),
-                modifiers: KeyModifiers::CONTROL,
-            }) => {
-                let _ = self.perform(Cmd::Custom(file_list::FILE_LIST_CMD_SELECT_ALL));
-                Some(Msg::None)
-            }
-            Event::Keyboard(KeyEvent {
-                code: Key::Char('a'),
-                modifiers: KeyModifiers::ALT,
-            }) => {
-                let _ = self.perform(Cmd::Custom(file_list::FILE_LIST_CMD_DESELECT_ALL));
-                Some(Msg::None)
-            }
-            Event::Keyboard(KeyEvent {
-                code: Key::Char('m'),
-                modifiers: KeyModifiers::NONE,
-            }) => {
-                let _ = self.perform(Cmd::Toggle);
-                Some(Msg::None)
-            }
             // -- comp msg
             Event::Keyboard(KeyEvent { code: Key::Esc, .. }) => {
                 Some(Msg::Ui(UiMsg::ShowDisconnectPopup))
             }
             Event::Keyboard(KeyEvent {
-                code: Key::Left | Key::Tab | Key::BackTab,
+                code: Key::Left, ..
+            }) => Some(Msg::Ui(UiMsg::FocusPanel(PanelSide::Left))),
+            Event::Keyboard(KeyEvent {
+                code: Key::Right, ..
+            }) => Some(Msg::Ui(UiMsg::FocusPanel(PanelSide::Right))),
+            Event::Keyboard(KeyEvent {
+                code: Key::Tab | Key::BackTab,
                 ..
             }) => Some(Msg::Ui(UiMsg::ChangeTransferWindow)),
+            Event::Keyboard(KeyEvent {
+                code: Key::Backspace,
+                modifiers: KeyModifiers::ALT,
+            }) => Some(Msg::Ui(UiMsg::ShowDirHistoryPopup)),
             Event::Keyboard(KeyEvent {
                 code: Key::Backspace,
                 ..
@@ -656,113 +514,117 @@ impl Component<Msg, NoUserEvent> for ExplorerRemote {
                     Some(Msg::Transfer(TransferMsg::EnterDirectory))
                 }
             }
-            Event::Keyboard(KeyEvent {
-                code: Key::Char(' '),
-                ..
-            }) => {
-                if matches!(self.component.state(), State::One(StateValue::String(_))) {
-                    Some(Msg::None)
-                } else {
-                    Some(Msg::Transfer(TransferMsg::TransferFile))
-                }
-            }
-            Event::Keyboard(KeyEvent {
-                code: Key::Char('a'),
-                modifiers: KeyModifiers::NONE,
-            }) => Some(Msg::Ui(UiMsg::ToggleHiddenFiles)),
-            Event::Keyboard(KeyEvent {
-                code: Key::Char('b'),
-                modifiers: KeyModifiers::NONE,
-            }) => Some(Msg::Ui(UiMsg::ShowFileSortingPopup)),
-            Event::Keyboard(KeyEvent {
-                code: Key::Char('c') | Key::Function(5),
-                modifiers: KeyModifiers::NONE,
-            }) => Some(Msg::Ui(UiMsg::ShowCopyPopup)),
-            Event::Keyboard(KeyEvent {
-                code: Key::Char('d') | Key::Function(7),
-                modifiers: KeyModifiers::NONE,
-            }) => Some(Msg::Ui(UiMsg::ShowMkdirPopup)),
-            Event::Keyboard(KeyEvent {
-                code: Key::Char('e') | Key::Delete | Key::Function(8),
-                modifiers: KeyModifiers::NONE,
-            }) => Some(Msg::Ui(UiMsg::ShowDeletePopup)),
-            Event::Keyboard(KeyEvent {
-                code: Key::Char('f'),
-                modifiers: KeyModifiers::NONE,
-            }) => Some(Msg::Transfer(TransferMsg::InitFuzzySearch)),
-            Event::Keyboard(KeyEvent {
-                code: Key::Char('g'),
-                modifiers: KeyModifiers::NONE,
-            }) => Some(Msg::Ui(UiMsg::ShowGotoPopup)),
-            Event::Keyboard(KeyEvent {
-                code: Key::Char('i'),
-                modifiers: KeyModifiers::NONE,
-            }) => Some(Msg::Ui(UiMsg::ShowFileInfoPopup)),
-            Event::Keyboard(KeyEvent {
-                code: Key::Char('k'),
-                modifiers: KeyModifiers::NONE,
-            }) => Some(Msg::Ui(UiMsg::ShowSymlinkPopup)),
-            Event::Keyboard(KeyEvent {
-                code: Key::Char('l'),
-                modifiers: KeyModifiers::NONE,
-            }) => Some(Msg::Transfer(TransferMsg::ReloadDir)),
-            Event::Keyboard(KeyEvent {
-                code: Key::Char('n'),
-                modifiers: KeyModifiers::NONE,
-            }) => Some(Msg::Ui(UiMsg::ShowNewFilePopup)),
-            Event::Keyboard(KeyEvent {
-                code: Key::Char('o') | Key::Function(4),
-                modifiers: KeyModifiers::NONE,
-            }) => Some(Msg::Transfer(TransferMsg::OpenTextFile)),
-            Event::Keyboard(KeyEvent {
-                code: Key::Char('p'),
-                modifiers: KeyModifiers::NONE,
-            }) => Some(Msg::Ui(UiMsg::ShowLogPanel)),
-            Event::Keyboard(KeyEvent {
-                code: Key::Char('r') | Key::Function(6),
-                modifiers: KeyModifiers::NONE,
-            }) => Some(Msg::Ui(UiMsg::ShowRenamePopup)),
-            Event::Keyboard(KeyEvent {
-                code: Key::Char('s') | Key::Function(2),
-                modifiers: KeyModifiers::NONE,
-            }) => Some(Msg::Ui(UiMsg::ShowSaveAsPopup)),
-            Event::Keyboard(KeyEvent {
-                code: Key::Char('t'),
-                modifiers: KeyModifiers::NONE,
-            }) => Some(Msg::Ui(UiMsg::ShowWatcherPopup)),
-            Event::Keyboard(KeyEvent {
-                code: Key::Char('t'),
-                modifiers: KeyModifiers::CONTROL,
-            }) => Some(Msg::Ui(UiMsg::ShowWatchedPathsList)),
-            Event::Keyboard(KeyEvent {
-                code: Key::Char('u'),
-                modifiers: KeyModifiers::NONE,
-            }) => Some(Msg::Transfer(TransferMsg::GoToParentDirectory)),
-            Event::Keyboard(KeyEvent {
-                code: Key::Char('x'),
-                modifiers: KeyModifiers::NONE,
-            }) => Some(Msg::Ui(UiMsg::ShowExecPopup)),
-            Event::Keyboard(KeyEvent {
-                code: Key::Char('y'),
-                modifiers: KeyModifiers::NONE,
-            }) => Some(Msg::Ui(UiMsg::ToggleSyncBrowsing)),
-            Event::Keyboard(KeyEvent {
-                code: Key::Char('v') | Key::Function(3),
-                modifiers: KeyModifiers::NONE,
-            }) => Some(Msg::Transfer(TransferMsg::OpenFile)),
-            Event::Keyboard(KeyEvent {
-                code: Key::Char('w'),
-                modifiers: KeyModifiers::NONE,
-            }) => Some(Msg::Ui(UiMsg::ShowOpenWithPopup)),
-            Event::Keyboard(KeyEvent {
-                code: Key::Char('z'),
-                modifiers: KeyModifiers::NONE,
-            }) => Some(Msg::Ui(UiMsg::ShowChmodPopup)),
-            Event::Keyboard(KeyEvent {
-                code: Key::Char('/'),
-                modifiers: KeyModifiers::NONE,
-            }) => Some(Msg::Ui(UiMsg::ShowFilterPopup)),
+            Event::Keyboard(ev) => on_action(&mut self.component, self.keymap.action(&ev)?),
+            Event::Mouse(ev) => on_mouse(&mut self.component, ev, FileExplorerTab::Remote),
             _ => None,
         }
     }
 }
+
+/// Returns whether the explorer of `component` handles `ev`: without focus, it's subscribed to the mouse events only
+fn is_for_explorer(component: &FileList, ev: &Event<NoUserEvent>) -> bool {
+    component.is_focused() || matches!(ev, Event::Mouse(_))
+}
+
+/// Handle a mouse event on the file list of the explorer of `tab`.
+/// Clicking the explorer gives it the focus; once focused, a click highlights the clicked row, while double clicking
+/// it enters the directory or opens the file. The wheel moves the list
+fn on_mouse(component: &mut FileList, ev: MouseEvent, tab: FileExplorerTab) -> Option<Msg> {
+    if !component.contains(ev.column, ev.row) {
+        return None;
+    }
+    match ev.kind {
+        MouseEventKind::Down(MouseButton::Left) if !component.is_focused() => {
+            Some(Msg::Ui(UiMsg::FocusExplorer(tab)))
+        }
+        MouseEventKind::ScrollDown | MouseEventKind::ScrollUp => {
+            component.wheel(ev.kind == MouseEventKind::ScrollDown);
+            Some(Msg::None)
+        }
+        MouseEventKind::Down(MouseButton::Left) => match component.click(ev.column, ev.row) {
+            Click::Double if matches!(component.state(), State::One(StateValue::String(_))) => {
+                Some(Msg::Transfer(TransferMsg::GoToParentDirectory))
+            }
+            Click::Double => Some(Msg::Transfer(TransferMsg::OpenEntry)),
+            Click::Outside | Click::List | Click::Row => Some(Msg::None),
+        },
+        _ => None,
+    }
+}
+
+/// Handle the explorer `action`, performing it on the file list or returning the message it sends
+fn on_action<C: MockComponent>(component: &mut C, action: KeyAction) -> Option<Msg> {
+    match action {
+        KeyAction::Transfer => {
+            if matches!(component.state(), State::One(StateValue::String(_))) {
+                Some(Msg::None)
+            } else {
+                Some(Msg::Transfer(TransferMsg::TransferFile))
+            }
+        }
+        KeyAction::Enqueue => {
+            if matches!(component.state(), State::One(StateValue::String(_))) {
+                Some(Msg::None)
+            } else {
+                Some(Msg::Transfer(TransferMsg::EnqueueTransfer))
+            }
+        }
+        KeyAction::Mark => {
+            let _ = component.perform(Cmd::Toggle);
+            Some(Msg::None)
+        }
+        KeyAction::SelectAll => {
+            let _ = component.perform(Cmd::Custom(file_list::FILE_LIST_CMD_SELECT_ALL));
+            Some(Msg::None)
+        }
+        KeyAction::DeselectAll => {
+            let _ = component.perform(Cmd::Custom(file_list::FILE_LIST_CMD_DESELECT_ALL));
+            Some(Msg::None)
+        }
+        KeyAction::ToggleHidden => Some(Msg::Ui(UiMsg::ToggleHiddenFiles)),
+        KeyAction::FileSorting => Some(Msg::Ui(UiMsg::ShowFileSortingPopup)),
+        KeyAction::Copy => Some(Msg::Ui(UiMsg::ShowCopyPopup)),
+        KeyAction::CompareChecksums => Some(Msg::Transfer(TransferMsg::CompareChecksums)),
+        KeyAction::Mirror => Some(Msg::Transfer(TransferMsg::SyncTransfer(SyncMode::Mirror))),
+        KeyAction::Sync => Some(Msg::Transfer(TransferMsg::SyncTransfer(
+            SyncMode::Incremental,
+        ))),
+        KeyAction::Mkdir => Some(Msg::Ui(UiMsg::ShowMkdirPopup)),
+        KeyAction::ShowDelete => Some(Msg::Ui(UiMsg::ShowDeletePopup)),
+        KeyAction::FuzzySearch => Some(Msg::Transfer(TransferMsg::InitFuzzySearch)),
+        KeyAction::Favorites => Some(Msg::Ui(UiMsg::ShowFavoritesPopup)),
+        KeyAction::Goto => Some(Msg::Ui(UiMsg::ShowGotoPopup)),
+        KeyAction::GotoClipboard => Some(Msg::Transfer(TransferMsg::GoToClipboardPath)),
+        KeyAction::FileInfo => Some(Msg::Ui(UiMsg::ShowFileInfoPopup)),
+        KeyAction::QuickCommands => Some(Msg::Ui(UiMsg::ShowQuickCommandsPopup)),
+        KeyAction::Symlink => Some(Msg::Ui(UiMsg::ShowSymlinkPopup)),
+        KeyAction::Reload => Some(Msg::Transfer(TransferMsg::ReloadDir)),
+        KeyAction::NewFile => Some(Msg::Ui(UiMsg::ShowNewFilePopup)),
+        KeyAction::OpenTextFile => Some(Msg::Transfer(TransferMsg::OpenTextFile)),
+        KeyAction::LogPanel => Some(Msg::Ui(UiMsg::ShowLogPanel)),
+        KeyAction::Preview => Some(Msg::Transfer(TransferMsg::PreviewFile)),
+        KeyAction::Rename => Some(Msg::Ui(UiMsg::ShowRenamePopup)),
+        KeyAction::SaveAs => Some(Msg::Ui(UiMsg::ShowSaveAsPopup)),
+        KeyAction::Watch => Some(Msg::Ui(UiMsg::ShowWatcherPopup)),
+        KeyAction::WatchedPaths => Some(Msg::Ui(UiMsg::ShowWatchedPathsList)),
+        KeyAction::Undo => Some(Msg::Transfer(TransferMsg::Undo)),
+        KeyAction::ParentDirectory => Some(Msg::Transfer(TransferMsg::GoToParentDirectory)),
+        KeyAction::Open => Some(Msg::Transfer(TransferMsg::OpenFile)),
+        KeyAction::OpenWith => Some(Msg::Ui(UiMsg::ShowOpenWithPopup)),
+        KeyAction::Exec => Some(Msg::Ui(UiMsg::ShowExecPopup)),
+        KeyAction::SwapPanels => Some(Msg::Ui(UiMsg::SwapPanels)),
+        KeyAction::SyncBrowsing => Some(Msg::Ui(UiMsg::ToggleSyncBrowsing)),
+        KeyAction::Chmod => Some(Msg::Ui(UiMsg::ShowChmodPopup)),
+        KeyAction::Filter => Some(Msg::Ui(UiMsg::ShowFilterPopup)),
+        KeyAction::ToggleFavorite => Some(Msg::Transfer(TransferMsg::ToggleFavorite)),
+        KeyAction::OpenUrl => Some(Msg::Transfer(TransferMsg::OpenRemoteUrl)),
+        KeyAction::CopyUrl => Some(Msg::Transfer(TransferMsg::CopyRemoteUrl)),
+        KeyAction::Duplicate => Some(Msg::Transfer(TransferMsg::DuplicateFiles)),
+        KeyAction::Cut => Some(Msg::Transfer(TransferMsg::CutFiles)),
+        KeyAction::Paste => Some(Msg::Transfer(TransferMsg::PasteFiles)),
+        KeyAction::Compress => Some(Msg::Ui(UiMsg::ShowArchivePopup)),
+        KeyAction::Extract => Some(Msg::Transfer(TransferMsg::ExtractArchive)),
+        KeyAction::BookmarkDirectory => Some(Msg::Ui(UiMsg::ShowBookmarkDirPopup)),
+        KeyAction::DirectoryBookmarks => Some(Msg::Ui(UiMsg::ShowDirBookmarksPopup)),
+    }
+}
@@ -1,7 +1,11 @@
 use std::path::Path;
+use std::time::SystemTime;
 
-use super::{FileTransferActivity, LogLevel, TransferPayload};
-use crate::system::watcher::FsChange;
+use remotefs::File;
+
+use super::lib::visited::VisitedDirs;
+use super::{FileTransferActivity, LogLevel, TransferOpts, TransferPayload};
+use crate::system::watcher::{FsChange, RemoteChange};
 
 impl FileTransferActivity {
     /// poll file watcher
@@ -10,7 +14,16 @@ impl FileTransferActivity {
             return;
         }
         let watcher = self.fswatcher.as_mut().unwrap();
-        match watcher.poll() {
+        // changes to the ignored files are dropped before being applied
+        let change = watcher
+            .poll()
+            .map(|change| change.and_then(|x| watcher.filter_ignored(x)));
+        // the change being applied is in flight too
+        if let Ok(Some(_)) = change {
+            let pending = watcher.pending() + 1;
+            self.update_watcher_pending(pending);
+        }
+        match change {
             Ok(None) => {}
             Ok(Some(FsChange::Move(mov))) => {
                 debug!(
@@ -42,6 +55,79 @@ impl FileTransferActivity {
                 );
             }
         }
+        let pending = self.fswatcher.as_ref().map(|x| x.pending()).unwrap_or(0);
+        self.update_watcher_pending(pending);
+    }
+
+    /// poll the remote directories watched by the remote poller, downloading their changes
+    pub(super) fn poll_remote_watcher(&mut self) {
+        if !self.remote_connected {
+            return;
+        }
+        let due = self
+            .remote_poller
+            .as_ref()
+            .map(|x| x.due())
+            .unwrap_or_default();
+        for remote in due {
+            let files = match self.list_watched_remote_dir(&remote) {
+                Ok(files) => files,
+                Err(err) => {
+                    self.log(
+                        LogLevel::Error,
+                        format!(
+                            "failed to poll watched remote directory {}: {}",
+                            remote.display(),
+                            err
+                        ),
+                    );
+                    continue;
+                }
+            };
+            let tolerance = self.clock_skew.tolerance();
+            let changes = match self.remote_poller.as_mut() {
+                Some(poller) => poller.update(&remote, &files, tolerance),
+                None => return,
+            };
+            for change in changes {
+                debug!(
+                    "remote poller reported an update from {} to {}",
+                    change.file.path().display(),
+                    change.local.display()
+                );
+                self.download_watched_file(change);
+            }
+        }
+    }
+
+    /// List the files under the watched remote directory `path`, recursively
+    fn list_watched_remote_dir(&mut self, path: &Path) -> Result<Vec<File>, String> {
+        let mut files = Vec::new();
+        let mut dirs = vec![path.to_path_buf()];
+        let mut visited = VisitedDirs::remote();
+        while let Some(dir) = dirs.pop() {
+            let entries = self.client.list_dir(&dir).map_err(|e| e.to_string())?;
+            for entry in entries {
+                if entry.is_dir() {
+                    // don't descend into symbolic link loops
+                    if !visited.enter(&entry) {
+                        continue;
+                    }
+                    dirs.push(entry.path().to_path_buf());
+                }
+                files.push(entry);
+            }
+        }
+        Ok(files)
+    }
+
+    /// Update the amount of watcher changes in flight; the status bar is refreshed if it has changed
+    fn update_watcher_pending(&mut self, pending: usize) {
+        if self.watcher_pending != pending {
+            self.watcher_pending = pending;
+            self.refresh_local_status_bar();
+            self.redraw = true;
+        }
     }
 
     fn move_watched_file(&mut self, source: &Path, destination: &Path) {
@@ -67,7 +153,7 @@ impl FileTransferActivity {
             }
         };
         // rename using action
-        self.remote_rename_file(&origin, destination)
+        self.remote_rename_file(&origin, destination);
     }
 
     fn remove_watched_file(&mut self, file: &Path) {
@@ -87,6 +173,39 @@ impl FileTransferActivity {
         }
     }
 
+    fn download_watched_file(&mut self, change: RemoteChange) {
+        let RemoteChange { file, local } = change;
+        trace!(
+            "syncing remote file {} with host {}",
+            file.path().display(),
+            local.display()
+        );
+        let remote = file.path().to_path_buf();
+        let local_path = local.parent().unwrap_or_else(|| Path::new("/"));
+        match self.filetransfer_recv(
+            TransferPayload::Any(file),
+            local_path,
+            TransferOpts::default().preserve_metadata(self.config().get_preserve_metadata()),
+        ) {
+            Ok(()) => {
+                self.log(
+                    LogLevel::Info,
+                    format!(
+                        "synched remote file {} with {}",
+                        remote.display(),
+                        local.display()
+                    ),
+                );
+            }
+            Err(err) => {
+                self.log(
+                    LogLevel::Error,
+                    format!("failed to sync remote file {}: {}", remote.display(), err),
+                );
+            }
+        }
+    }
+
     fn upload_watched_file(&mut self, host: &Path, remote: &Path) {
         // stat host file
         let entry = match self.host_bridge.stat(host) {
@@ -110,8 +229,20 @@ impl FileTransferActivity {
             remote.display()
         );
         let remote_path = remote.parent().unwrap_or_else(|| Path::new("/"));
-        match self.filetransfer_send(TransferPayload::Any(entry), remote_path, None) {
+        let modified = entry.metadata().modified;
+        match self.filetransfer_send(
+            TransferPayload::Any(entry),
+            remote_path,
+            TransferOpts::default().preserve_metadata(self.config().get_preserve_metadata()),
+        ) {
             Ok(()) => {
+                // the upload mustn't be downloaded back, if the remote path is polled too: the remote file is given
+                // either the time of the upload or the modification time of the host file
+                let mut mtimes = vec![SystemTime::now()];
+                mtimes.extend(modified);
+                if let Some(poller) = self.remote_poller.as_mut() {
+                    poller.suppress_echo(remote, mtimes);
+                }
                 self.log(
                     LogLevel::Info,
                     format!(
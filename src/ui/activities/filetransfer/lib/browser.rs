@@ -2,19 +2,22 @@
 //!
 //! `filetransfer_activiy` is the module which implements the Filetransfer activity, which is the main activity afterall
 
+use std::io;
 use std::path::Path;
 
 use nucleo::Utf32String;
 use remotefs::File;
 
+use super::walkdir::SpilledFiles;
 use crate::explorer::builder::FileExplorerBuilder;
 use crate::explorer::{FileExplorer, FileSorting};
+use crate::filetransfer::params::ExplorerPrefs;
 use crate::system::config_client::ConfigClient;
 
 const FUZZY_SEARCH_THRESHOLD: u16 = 50;
 
 /// File explorer tab
-#[derive(Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum FileExplorerTab {
     HostBridge,
     Remote,
@@ -29,23 +32,44 @@ pub enum FoundExplorerTab {
     Remote,
 }
 
+/// Side of the screen where a panel is displayed
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PanelSide {
+    Left,
+    Right,
+}
+
 /// Browser contains the browser options
 pub struct Browser {
     host_bridge: FileExplorer, // Local File explorer state
     remote: FileExplorer,      // Remote File explorer state
     found: Option<Found>,      // File explorer for find result
     tab: FileExplorerTab,      // Current selected tab
+    swapped: bool,             // Whether the host bridge panel is displayed on the right
+    lock_view: bool,           // Whether view options are applied to both panels
+    cut: Option<CutFiles>,     // Entries cut, to be moved where they're pasted
     pub sync_browsing: bool,
 }
 
+/// Entries cut from a panel, to be moved to the directory they're pasted in
+pub struct CutFiles {
+    /// Panel the entries have been cut from
+    pub tab: FileExplorerTab,
+    pub files: Vec<File>,
+}
+
 impl Browser {
-    /// Build a new `Browser` struct
-    pub fn new(cli: &ConfigClient) -> Self {
+    /// Build a new `Browser` struct.
+    /// Explorer preferences of the connection override the configuration
+    pub fn new(cli: &ConfigClient, prefs: &ExplorerPrefs) -> Self {
         Self {
-            host_bridge: Self::build_local_explorer(cli),
-            remote: Self::build_remote_explorer(cli),
+            host_bridge: Self::build_local_explorer(cli, prefs),
+            remote: Self::build_remote_explorer(cli, prefs),
             found: None,
             tab: FileExplorerTab::HostBridge,
+            swapped: false,
+            lock_view: cli.get_lock_panels_view(),
+            cut: None,
             sync_browsing: false,
         }
     }
@@ -87,27 +111,73 @@ impl Browser {
     /// Perform fuzzy search on found tab
     pub fn fuzzy_search(&mut self, needle: &str) {
         if let Some(x) = self.found.as_mut() {
+            x.needle = Some(needle.to_string());
             x.fuzzy_search(needle)
         }
     }
 
     /// Initialize fuzzy search
     pub fn init_fuzzy_search(&mut self) {
-        if let Some(explorer) = self.found_mut() {
-            explorer.set_files(vec![]);
+        if let Some(found) = self.found.as_mut() {
+            found.needle = Some(String::new());
+            found.explorer.set_files(vec![]);
         }
     }
 
+    /// Set the found explorer with `files`, searched on the panel of `tab`.
+    /// The found explorer inherits the hidden files and group dirs options from its source panel
     pub fn set_found(&mut self, tab: FoundExplorerTab, files: Vec<File>, wrkdir: &Path) {
-        let mut explorer = Self::build_found_explorer(wrkdir);
+        let source = match tab {
+            FoundExplorerTab::Local => &self.host_bridge,
+            FoundExplorerTab::Remote => &self.remote,
+        };
+        let mut explorer = Self::build_found_explorer(source, wrkdir);
         explorer.set_files(files.clone());
         self.found = Some(Found {
             tab,
             explorer,
             search_results: files,
+            spilled: None,
+            needle: None,
         });
     }
 
+    /// Attach to the found explorer the results which have been spilled to disk, loaded by `load_more_found()`
+    pub fn set_found_spilled(&mut self, spilled: SpilledFiles) {
+        if let Some(found) = self.found.as_mut() {
+            found.spilled = Some(spilled);
+        }
+    }
+
+    /// Amount of results of the found explorer which are still on disk
+    pub fn found_spilled(&self) -> usize {
+        self.found
+            .as_ref()
+            .and_then(|x| x.spilled.as_ref())
+            .map(|x| x.remaining())
+            .unwrap_or_default()
+    }
+
+    /// Load up to `amount` results from disk into the found explorer, applying the current fuzzy search to them.
+    /// Returns the amount of results loaded
+    pub fn load_more_found(&mut self, amount: usize) -> io::Result<usize> {
+        let Some(found) = self.found.as_mut() else {
+            return Ok(0);
+        };
+        let Some(spilled) = found.spilled.as_mut() else {
+            return Ok(0);
+        };
+        let files = spilled.read(amount)?;
+        let loaded = files.len();
+        found.search_results.extend(files);
+        match found.needle.clone() {
+            Some(needle) if needle.is_empty() => {}
+            Some(needle) => found.fuzzy_search(&needle),
+            None => found.explorer.set_files(found.search_results.clone()),
+        }
+        Ok(loaded)
+    }
+
     pub fn del_found(&mut self) {
         self.found = None;
     }
@@ -126,43 +196,148 @@ impl Browser {
         self.tab = tab;
     }
 
+    /// Swap the sides where the host bridge and the remote panels are displayed
+    pub fn swap_panels(&mut self) {
+        self.swapped = !self.swapped;
+    }
+
+    /// Get the side of the screen where the panel of `tab` is displayed.
+    /// Find result tabs are displayed in place of the panel they've been searched on
+    pub fn side_of(&self, tab: FileExplorerTab) -> PanelSide {
+        let host_bridge = matches!(
+            tab,
+            FileExplorerTab::HostBridge | FileExplorerTab::FindHostBridge
+        );
+        match host_bridge != self.swapped {
+            true => PanelSide::Left,
+            false => PanelSide::Right,
+        }
+    }
+
+    /// Returns whether view options are applied to both panels
+    pub fn is_view_locked(&self) -> bool {
+        self.lock_view
+    }
+
+    /// Toggle hidden files on the panel of `tab`; the found explorer, if searched on that panel, follows it.
+    /// If the view is locked, the other panel gets the same option
+    pub fn toggle_hidden_files(&mut self, tab: FileExplorerTab) {
+        let explorer = self.panel_mut(tab);
+        explorer.toggle_hidden_files();
+        let visible = explorer.hidden_files_visible();
+        if self.lock_view {
+            self.panel_mut(Self::other_panel(tab))
+                .set_hidden_files(visible);
+        }
+        if let Some(found) = self.found.as_mut() {
+            if self.lock_view || Self::found_on(found.tab, tab) {
+                found.explorer.set_hidden_files(visible);
+            }
+        }
+    }
+
+    /// Sort the panel of `tab` by `sorting`.
+    /// If the view is locked, the other panel is sorted the same way.
+    /// The found explorer keeps its order, which is the one of the search results
+    pub fn sort_by(&mut self, tab: FileExplorerTab, sorting: FileSorting) {
+        self.panel_mut(tab).sort_by(sorting);
+        if self.lock_view {
+            self.panel_mut(Self::other_panel(tab)).sort_by(sorting);
+        }
+    }
+
+    /// Get the panel of `tab`; find result tabs refer to the panel they've been searched on
+    fn panel_mut(&mut self, tab: FileExplorerTab) -> &mut FileExplorer {
+        match tab {
+            FileExplorerTab::HostBridge | FileExplorerTab::FindHostBridge => &mut self.host_bridge,
+            FileExplorerTab::Remote | FileExplorerTab::FindRemote => &mut self.remote,
+        }
+    }
+
+    fn other_panel(tab: FileExplorerTab) -> FileExplorerTab {
+        match tab {
+            FileExplorerTab::HostBridge | FileExplorerTab::FindHostBridge => {
+                FileExplorerTab::Remote
+            }
+            FileExplorerTab::Remote | FileExplorerTab::FindRemote => FileExplorerTab::HostBridge,
+        }
+    }
+
+    /// Returns whether results of `found` have been searched on the panel of `tab`
+    fn found_on(found: FoundExplorerTab, tab: FileExplorerTab) -> bool {
+        matches!(
+            (found, tab),
+            (
+                FoundExplorerTab::Local,
+                FileExplorerTab::HostBridge | FileExplorerTab::FindHostBridge
+            ) | (
+                FoundExplorerTab::Remote,
+                FileExplorerTab::Remote | FileExplorerTab::FindRemote
+            )
+        )
+    }
+
+    /// Mark `files` of the panel of `tab` as cut, replacing the entries cut previously
+    pub fn set_cut(&mut self, tab: FileExplorerTab, files: Vec<File>) {
+        self.cut = Some(CutFiles { tab, files });
+    }
+
+    /// Get the amount of entries cut from the panel of `tab`
+    pub fn cut_count(&self, tab: FileExplorerTab) -> usize {
+        self.cut
+            .as_ref()
+            .filter(|x| x.tab == tab)
+            .map(|x| x.files.len())
+            .unwrap_or_default()
+    }
+
+    /// Take the cut entries, if any
+    pub fn take_cut(&mut self) -> Option<CutFiles> {
+        self.cut.take()
+    }
+
     /// Invert the current state for the sync browsing
     pub fn toggle_sync_browsing(&mut self) {
         self.sync_browsing = !self.sync_browsing;
     }
 
     /// Build a file explorer with local host setup
-    pub fn build_local_explorer(cli: &ConfigClient) -> FileExplorer {
-        let mut builder = Self::build_explorer(cli);
+    pub fn build_local_explorer(cli: &ConfigClient, prefs: &ExplorerPrefs) -> FileExplorer {
+        let mut builder = Self::build_explorer(cli, prefs);
         builder.with_formatter(cli.get_local_file_fmt().as_deref());
         builder.build()
     }
 
     /// Build a file explorer with remote host setup
-    pub fn build_remote_explorer(cli: &ConfigClient) -> FileExplorer {
-        let mut builder = Self::build_explorer(cli);
+    pub fn build_remote_explorer(cli: &ConfigClient, prefs: &ExplorerPrefs) -> FileExplorer {
+        let mut builder = Self::build_explorer(cli, prefs);
         builder.with_formatter(cli.get_remote_file_fmt().as_deref());
         builder.build()
     }
 
-    /// Build explorer reading configuration from `ConfigClient`
-    fn build_explorer(cli: &ConfigClient) -> FileExplorerBuilder {
+    /// Build explorer reading configuration from `ConfigClient`, unless overridden by `prefs`
+    fn build_explorer(cli: &ConfigClient, prefs: &ExplorerPrefs) -> FileExplorerBuilder {
         let mut builder: FileExplorerBuilder = FileExplorerBuilder::new();
         // Set common keys
         builder
-            .with_file_sorting(FileSorting::Name)
-            .with_stack_size(16)
-            .with_group_dirs(cli.get_group_dirs())
-            .with_hidden_files(cli.get_show_hidden_files());
+            .with_file_sorting(prefs.file_sorting.unwrap_or(FileSorting::Name))
+            .with_stack_size(cli.get_dirstack_size())
+            .with_group_dirs(prefs.group_dirs.unwrap_or_else(|| cli.get_group_dirs()))
+            .with_hidden_files(
+                prefs
+                    .show_hidden_files
+                    .unwrap_or_else(|| cli.get_show_hidden_files()),
+            );
         builder
     }
 
-    /// Build explorer reading from `ConfigClient`, for found result (has some differences)
-    fn build_found_explorer(wrkdir: &Path) -> FileExplorer {
+    /// Build explorer for found result, with the options of the `source` panel.
+    /// Files are not sorted, since results are displayed in the order they've been found or ranked
+    fn build_found_explorer(source: &FileExplorer, wrkdir: &Path) -> FileExplorer {
         FileExplorerBuilder::new()
             .with_file_sorting(FileSorting::None)
-            .with_group_dirs(None)
-            .with_hidden_files(true)
+            .with_group_dirs(source.group_dirs)
+            .with_hidden_files(source.hidden_files_visible())
             .with_stack_size(0)
             .with_formatter(Some(
                 format!("{{PATH:36:{}}} {{SYMLINK}}", wrkdir.display()).as_str(),
@@ -176,6 +351,10 @@ struct Found {
     explorer: FileExplorer,
     /// Search results; original copy of files
     search_results: Vec<File>,
+    /// Search results beyond the spill threshold, not loaded yet
+    spilled: Option<SpilledFiles>,
+    /// Current fuzzy search, if results are fuzzy searched
+    needle: Option<String>,
     tab: FoundExplorerTab,
 }
 
@@ -215,3 +394,208 @@ impl Found {
         );
     }
 }
+
+#[cfg(test)]
+mod tests {
+
+    use std::path::PathBuf;
+
+    use pretty_assertions::assert_eq;
+    use remotefs::fs::{FileType, Metadata};
+
+    use super::*;
+    use crate::explorer::GroupDirs;
+    use crate::ui::activities::filetransfer::lib::walkdir::WalkdirResults;
+
+    #[test]
+    fn should_display_host_bridge_on_the_left_by_default() {
+        let browser = Browser::new(&ConfigClient::degraded(), &ExplorerPrefs::default());
+        assert_eq!(
+            browser.side_of(FileExplorerTab::HostBridge),
+            PanelSide::Left
+        );
+        assert_eq!(
+            browser.side_of(FileExplorerTab::FindHostBridge),
+            PanelSide::Left
+        );
+        assert_eq!(browser.side_of(FileExplorerTab::Remote), PanelSide::Right);
+        assert_eq!(
+            browser.side_of(FileExplorerTab::FindRemote),
+            PanelSide::Right
+        );
+    }
+
+    #[test]
+    fn should_swap_panels() {
+        let mut browser = Browser::new(&ConfigClient::degraded(), &ExplorerPrefs::default());
+        browser.change_tab(FileExplorerTab::Remote);
+        browser.swap_panels();
+        assert_eq!(
+            browser.side_of(FileExplorerTab::HostBridge),
+            PanelSide::Right
+        );
+        assert_eq!(
+            browser.side_of(FileExplorerTab::FindHostBridge),
+            PanelSide::Right
+        );
+        assert_eq!(browser.side_of(FileExplorerTab::Remote), PanelSide::Left);
+        assert_eq!(
+            browser.side_of(FileExplorerTab::FindRemote),
+            PanelSide::Left
+        );
+        // the selected tab still refers to the same explorer
+        assert!(browser.tab() == FileExplorerTab::Remote);
+        browser.swap_panels();
+        assert_eq!(
+            browser.side_of(FileExplorerTab::HostBridge),
+            PanelSide::Left
+        );
+    }
+
+    fn file(name: &str) -> File {
+        File {
+            path: PathBuf::from(name),
+            metadata: Metadata::default().file_type(FileType::File),
+        }
+    }
+
+    #[test]
+    fn should_inherit_found_explorer_options_from_source_panel() {
+        let mut browser = Browser::new(&ConfigClient::degraded(), &ExplorerPrefs::default());
+        browser.host_bridge_mut().set_hidden_files(false);
+        browser
+            .host_bridge_mut()
+            .group_dirs_by(Some(GroupDirs::Last));
+        browser.remote_mut().set_hidden_files(true);
+        browser.remote_mut().group_dirs_by(Some(GroupDirs::First));
+        let files = vec![file("/home/omar/.bashrc"), file("/home/omar/notes.txt")];
+        browser.set_found(FoundExplorerTab::Local, files.clone(), Path::new("/home"));
+        let found = browser.found().unwrap();
+        assert_eq!(found.hidden_files_visible(), false);
+        assert_eq!(found.group_dirs, Some(GroupDirs::Last));
+        assert_eq!(found.get_file_sorting(), FileSorting::None);
+        assert_eq!(found.iter_files().count(), 1);
+        browser.set_found(FoundExplorerTab::Remote, files, Path::new("/home"));
+        let found = browser.found().unwrap();
+        assert_eq!(found.hidden_files_visible(), true);
+        assert_eq!(found.group_dirs, Some(GroupDirs::First));
+        assert_eq!(found.iter_files().count(), 2);
+    }
+
+    #[test]
+    fn should_toggle_view_options_of_targeted_panel() {
+        let mut browser = Browser::new(&ConfigClient::degraded(), &ExplorerPrefs::default());
+        assert_eq!(browser.is_view_locked(), false);
+        let hidden = browser.remote().hidden_files_visible();
+        browser.set_found(FoundExplorerTab::Remote, vec![], Path::new("/"));
+        browser.toggle_hidden_files(FileExplorerTab::FindRemote);
+        assert_eq!(browser.remote().hidden_files_visible(), !hidden);
+        assert_eq!(browser.found().unwrap().hidden_files_visible(), !hidden);
+        assert_eq!(browser.host_bridge().hidden_files_visible(), hidden);
+        browser.toggle_hidden_files(FileExplorerTab::HostBridge);
+        assert_eq!(browser.host_bridge().hidden_files_visible(), !hidden);
+        // the found explorer follows the remote panel only
+        assert_eq!(browser.found().unwrap().hidden_files_visible(), !hidden);
+        browser.sort_by(FileExplorerTab::Remote, FileSorting::Size);
+        assert_eq!(browser.remote().get_file_sorting(), FileSorting::Size);
+        assert_eq!(browser.host_bridge().get_file_sorting(), FileSorting::Name);
+        assert_eq!(
+            browser.found().unwrap().get_file_sorting(),
+            FileSorting::None
+        );
+    }
+
+    #[test]
+    fn should_mirror_view_options_when_locked() {
+        let mut config = ConfigClient::degraded();
+        config.set_lock_panels_view(true);
+        let mut browser = Browser::new(&config, &ExplorerPrefs::default());
+        assert_eq!(browser.is_view_locked(), true);
+        let hidden = browser.host_bridge().hidden_files_visible();
+        browser.set_found(FoundExplorerTab::Local, vec![], Path::new("/"));
+        browser.toggle_hidden_files(FileExplorerTab::Remote);
+        assert_eq!(browser.host_bridge().hidden_files_visible(), !hidden);
+        assert_eq!(browser.remote().hidden_files_visible(), !hidden);
+        assert_eq!(browser.found().unwrap().hidden_files_visible(), !hidden);
+        browser.sort_by(FileExplorerTab::HostBridge, FileSorting::ModifyTime);
+        assert_eq!(
+            browser.host_bridge().get_file_sorting(),
+            FileSorting::ModifyTime
+        );
+        assert_eq!(browser.remote().get_file_sorting(), FileSorting::ModifyTime);
+    }
+
+    #[test]
+    fn should_load_spilled_found_results() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let mut results = WalkdirResults::new(2, Some(dir.path()));
+        results
+            .extend((0..5000).map(|i| file(&format!("/home/omar/file-{i}.txt"))))
+            .unwrap();
+        let (files, spilled) = results.into_parts().unwrap();
+        let mut browser = Browser::new(&ConfigClient::degraded(), &ExplorerPrefs::default());
+        browser.set_found(FoundExplorerTab::Local, files, Path::new("/home"));
+        browser.set_found_spilled(spilled.unwrap());
+        assert_eq!(browser.found_spilled(), 4998);
+        assert_eq!(browser.found().unwrap().iter_files().count(), 2);
+        assert_eq!(browser.load_more_found(1000).unwrap(), 1000);
+        assert_eq!(browser.found_spilled(), 3998);
+        assert_eq!(browser.found().unwrap().iter_files().count(), 1002);
+        // loaded results are fuzzy searched too
+        browser.fuzzy_search("file-4999");
+        assert!(browser
+            .found()
+            .unwrap()
+            .iter_files()
+            .all(|x| x.name() != "file-4999.txt"));
+        assert_eq!(browser.load_more_found(10_000).unwrap(), 3998);
+        assert_eq!(browser.found_spilled(), 0);
+        assert_eq!(
+            browser.found().unwrap().iter_files().next().unwrap().name(),
+            "file-4999.txt"
+        );
+        assert_eq!(browser.load_more_found(1000).unwrap(), 0);
+    }
+
+    #[test]
+    fn should_cut_files() {
+        let mut browser = Browser::new(&ConfigClient::degraded(), &ExplorerPrefs::default());
+        assert!(browser.take_cut().is_none());
+        browser.set_cut(
+            FileExplorerTab::Remote,
+            vec![file("/home/omar/a.txt"), file("/home/omar/b.txt")],
+        );
+        assert_eq!(browser.cut_count(FileExplorerTab::Remote), 2);
+        assert_eq!(browser.cut_count(FileExplorerTab::HostBridge), 0);
+        let cut = browser.take_cut().unwrap();
+        assert!(cut.tab == FileExplorerTab::Remote);
+        assert_eq!(cut.files.len(), 2);
+        assert_eq!(browser.cut_count(FileExplorerTab::Remote), 0);
+    }
+
+    #[test]
+    fn should_apply_explorer_prefs() {
+        let config = ConfigClient::degraded();
+        let browser = Browser::new(&config, &ExplorerPrefs::default());
+        assert_eq!(browser.remote().get_file_sorting(), FileSorting::Name);
+        assert_eq!(browser.remote().group_dirs, config.get_group_dirs());
+        assert_eq!(
+            browser.remote().hidden_files_visible(),
+            config.get_show_hidden_files()
+        );
+        let prefs = ExplorerPrefs {
+            file_sorting: Some(FileSorting::ModifyTime),
+            group_dirs: Some(Some(GroupDirs::First)),
+            show_hidden_files: Some(!config.get_show_hidden_files()),
+        };
+        let browser = Browser::new(&config, &prefs);
+        for explorer in [browser.host_bridge(), browser.remote()] {
+            assert_eq!(explorer.get_file_sorting(), FileSorting::ModifyTime);
+            assert_eq!(explorer.group_dirs, Some(GroupDirs::First));
+            assert_eq!(
+                explorer.hidden_files_visible(),
+                !config.get_show_hidden_files()
+            );
+        }
+    }
+}
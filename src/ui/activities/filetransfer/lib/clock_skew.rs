@@ -0,0 +1,128 @@
+//! ## FileTransferActivity
+//!
+//! `filetransfer_activiy` is the module which implements the Filetransfer activity, which is the main activity afterall
+
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Skews up to this amount are ignored
+const THRESHOLD: Duration = Duration::from_secs(30);
+/// The skew is measured again after this interval
+const CHECK_INTERVAL: Duration = Duration::from_secs(600);
+
+/// Clock skew between the local host and the remote host.
+///
+/// When it exceeds the threshold, modification times are compared with a tolerance of the measured skew
+#[derive(Debug, Default)]
+pub struct ClockSkew {
+    /// Skew in seconds; positive if the remote clock is ahead
+    skew: Option<i64>,
+    last_check: Option<Instant>,
+}
+
+impl ClockSkew {
+    /// Command which prints the remote time
+    pub const COMMAND: &'static str = "date +%s";
+
+    /// Whether the skew has never been measured or should be measured again
+    pub fn should_measure(&self) -> bool {
+        self.last_check
+            .is_none_or(|check| check.elapsed() >= CHECK_INTERVAL)
+    }
+
+    /// Update the skew from the `output` of [`Self::COMMAND`], executed between `sent` and `received`.
+    /// Returns the skew, if the output is valid and it exceeds the threshold
+    pub fn update(&mut self, output: &str, sent: SystemTime, received: SystemTime) -> Option<i64> {
+        self.last_check = Some(Instant::now());
+        let remote = output.trim().parse::<i64>().ok()?;
+        // the remote time is sampled, more or less, halfway through the roundtrip
+        let local = received
+            .duration_since(UNIX_EPOCH)
+            .ok()?
+            .saturating_sub(received.duration_since(sent).unwrap_or_default() / 2)
+            .as_secs() as i64;
+        self.skew = Some(remote - local);
+        self.exceeding()
+    }
+
+    /// Get the skew, if it exceeds the threshold
+    pub fn exceeding(&self) -> Option<i64> {
+        self.skew
+            .filter(|skew| skew.unsigned_abs() > THRESHOLD.as_secs())
+    }
+
+    /// Tolerance to apply when comparing the modification times of a local and of a remote file
+    pub fn tolerance(&self) -> Duration {
+        self.exceeding()
+            .map(|skew| Duration::from_secs(skew.unsigned_abs()))
+            .unwrap_or_default()
+    }
+
+    /// Format `skew` for the user (e.g. `+5m 3s`)
+    pub fn fmt(skew: i64) -> String {
+        let sign = if skew < 0 { '-' } else { '+' };
+        let secs = skew.unsigned_abs();
+        match (secs / 3600, secs % 3600 / 60, secs % 60) {
+            (0, 0, s) => format!("{sign}{s}s"),
+            (0, m, s) => format!("{sign}{m}m {s}s"),
+            (h, m, _) => format!("{sign}{h}h {m}m"),
+        }
+    }
+}
+
+/// Whether the modification times `a` and `b` are equal, within `tolerance`
+pub fn mtime_equal(a: Option<SystemTime>, b: Option<SystemTime>, tolerance: Duration) -> bool {
+    match (a, b) {
+        (Some(a), Some(b)) => {
+            let diff = a.duration_since(b).or_else(|_| b.duration_since(a));
+            diff.is_ok_and(|diff| diff <= tolerance)
+        }
+        (a, b) => a == b,
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn should_measure_clock_skew() {
+        let mut clock_skew = ClockSkew::default();
+        assert_eq!(clock_skew.should_measure(), true);
+        let sent = UNIX_EPOCH + Duration::from_secs(1_000_000);
+        let received = sent + Duration::from_secs(2);
+        // skew within the threshold
+        assert_eq!(clock_skew.update("1000011\n", sent, received), None);
+        assert_eq!(clock_skew.should_measure(), false);
+        assert_eq!(clock_skew.tolerance(), Duration::ZERO);
+        // remote clock is behind
+        assert_eq!(clock_skew.update("999701", sent, received), Some(-300));
+        assert_eq!(clock_skew.tolerance(), Duration::from_secs(300));
+        // invalid output keeps the previous measure
+        assert_eq!(clock_skew.update("date: not found", sent, received), None);
+        assert_eq!(clock_skew.exceeding(), Some(-300));
+    }
+
+    #[test]
+    fn should_format_clock_skew() {
+        assert_eq!(ClockSkew::fmt(45).as_str(), "+45s");
+        assert_eq!(ClockSkew::fmt(-303).as_str(), "-5m 3s");
+        assert_eq!(ClockSkew::fmt(7380).as_str(), "+2h 3m");
+    }
+
+    #[test]
+    fn should_compare_mtimes_with_tolerance() {
+        let a = UNIX_EPOCH + Duration::from_secs(1_000);
+        let b = UNIX_EPOCH + Duration::from_secs(1_100);
+        assert_eq!(mtime_equal(Some(a), Some(a), Duration::ZERO), true);
+        assert_eq!(mtime_equal(Some(a), Some(b), Duration::ZERO), false);
+        assert_eq!(
+            mtime_equal(Some(b), Some(a), Duration::from_secs(100)),
+            true
+        );
+        assert_eq!(mtime_equal(Some(a), None, Duration::from_secs(100)), false);
+        assert_eq!(mtime_equal(None, None, Duration::ZERO), true);
+    }
+}
@@ -0,0 +1,93 @@
+//! ## FileTransferActivity
+//!
+//! `filetransfer_activiy` is the module which implements the Filetransfer activity, which is the main activity afterall
+
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+/// Maximum amount of commands kept in the history
+const EXEC_HISTORY_SIZE: usize = 50;
+
+/// History of the commands executed with the exec popup, persisted to a file across sessions
+#[derive(Debug, Default)]
+pub struct ExecHistory {
+    /// Commands, from the most recent one
+    commands: Vec<String>,
+    /// File the history is persisted to
+    path: Option<PathBuf>,
+}
+
+impl ExecHistory {
+    /// Load the history from the file at `path`, if any. A missing or unreadable file is an empty history
+    pub fn load(path: Option<PathBuf>) -> Self {
+        let commands = path
+            .as_ref()
+            .and_then(|x| fs::read_to_string(x).ok())
+            .map(|x| {
+                x.lines()
+                    .filter(|x| !x.trim().is_empty())
+                    .take(EXEC_HISTORY_SIZE)
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default();
+        Self { commands, path }
+    }
+
+    /// Get the commands, from the most recent one
+    pub fn commands(&self) -> &[String] {
+        &self.commands
+    }
+
+    /// Push `command` as the most recent one, removing its previous occurrence, then save the history
+    pub fn push(&mut self, command: &str) {
+        if command.trim().is_empty() {
+            return;
+        }
+        self.commands.retain(|x| x != command);
+        self.commands.insert(0, command.to_string());
+        self.commands.truncate(EXEC_HISTORY_SIZE);
+        if let Err(err) = self.save() {
+            error!("Could not save exec history: {err}");
+        }
+    }
+
+    fn save(&self) -> io::Result<()> {
+        match self.path.as_ref() {
+            Some(path) => fs::write(path, self.commands.join("\n")),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn should_push_commands_to_exec_history() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("exec_history");
+        let mut history = ExecHistory::load(Some(path.clone()));
+        assert!(history.commands().is_empty());
+        history.push("df -h");
+        history.push("uptime");
+        history.push("   ");
+        history.push("df -h");
+        assert_eq!(history.commands(), &["df -h", "uptime"]);
+        for i in 0..60 {
+            history.push(&format!("echo {i}"));
+        }
+        assert_eq!(history.commands().len(), EXEC_HISTORY_SIZE);
+        assert_eq!(history.commands()[0], "echo 59");
+        // the history is restored in the next session
+        let history = ExecHistory::load(Some(path));
+        assert_eq!(history.commands().len(), EXEC_HISTORY_SIZE);
+        assert_eq!(history.commands()[0], "echo 59");
+        assert!(ExecHistory::load(None).commands().is_empty());
+    }
+}
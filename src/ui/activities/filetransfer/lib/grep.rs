@@ -0,0 +1,161 @@
+//! ## FileTransferActivity
+//!
+//! `filetransfer_activiy` is the module which implements the Filetransfer activity, which is the main activity afterall
+
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use crate::utils::shell;
+
+/// Files larger than this are skipped by the content search performed by termscp, rather than by the remote host
+pub const CONTENT_SEARCH_MAX_FILE_SIZE: u64 = 8 * 1024 * 1024;
+
+/// Command listing the files under `dir` which contain `needle` on the remote host.
+/// The needle is matched as a fixed string; binary files are skipped and unreadable files are ignored
+pub fn grep_command(needle: &str, dir: &Path) -> String {
+    format!(
+        "grep -rlIsF -e {} -- {}",
+        shell::single_quote(needle),
+        shell::single_quote(&dir.to_string_lossy())
+    )
+}
+
+/// Get the paths printed by the command made by [`grep_command`]
+pub fn parse_grep_output(output: &str) -> Vec<PathBuf> {
+    output
+        .lines()
+        .map(|x| x.trim_end_matches('\r'))
+        .filter(|x| !x.is_empty())
+        .map(PathBuf::from)
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MatchResult {
+    Searching,
+    Found,
+    Binary,
+}
+
+#[derive(Debug)]
+struct MatcherState {
+    result: MatchResult,
+    /// Last bytes written, which may contain the beginning of the needle
+    tail: Vec<u8>,
+    inspected: bool,
+}
+
+/// Writer looking for a needle in the content of the file written into it.
+/// Once the needle is found, or the file turns out to be binary, writes fail, so that the rest of the file is not read
+#[derive(Debug, Clone)]
+pub struct ContentMatcher {
+    needle: Arc<Vec<u8>>,
+    state: Arc<Mutex<MatcherState>>,
+}
+
+impl ContentMatcher {
+    pub fn new(needle: &str) -> Self {
+        Self {
+            needle: Arc::new(needle.as_bytes().to_vec()),
+            state: Arc::new(Mutex::new(MatcherState {
+                result: MatchResult::Searching,
+                tail: Vec::new(),
+                inspected: false,
+            })),
+        }
+    }
+
+    /// Whether the content written so far contains the needle
+    pub fn is_match(&self) -> bool {
+        self.state
+            .lock()
+            .map(|x| x.result == MatchResult::Found)
+            .unwrap_or(false)
+    }
+}
+
+impl Write for ContentMatcher {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut state = self
+            .state
+            .lock()
+            .map_err(|_| io::Error::other("content matcher is poisoned"))?;
+        if state.result != MatchResult::Searching {
+            return Err(io::Error::other("content search is over"));
+        }
+        if !state.inspected {
+            state.inspected = true;
+            if content_inspector::inspect(buf).is_binary() {
+                state.result = MatchResult::Binary;
+                return Err(io::Error::other("file is binary"));
+            }
+        }
+        let mut window = std::mem::take(&mut state.tail);
+        window.extend_from_slice(buf);
+        if window
+            .windows(self.needle.len().max(1))
+            .any(|x| x == self.needle.as_slice())
+        {
+            state.result = MatchResult::Found;
+            return Err(io::Error::other("needle found"));
+        }
+        let keep = self.needle.len().saturating_sub(1).min(window.len());
+        state.tail = window.split_off(window.len() - keep);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn should_make_grep_command() {
+        assert_eq!(
+            grep_command("it's -v", Path::new("/var/log")).as_str(),
+            r"grep -rlIsF -e 'it'\''s -v' -- '/var/log'"
+        );
+    }
+
+    #[test]
+    fn should_parse_grep_output() {
+        assert_eq!(
+            parse_grep_output("/var/log/syslog\r\n\n/var/log/nginx/error.log\n"),
+            vec![
+                PathBuf::from("/var/log/syslog"),
+                PathBuf::from("/var/log/nginx/error.log")
+            ]
+        );
+        assert!(parse_grep_output("").is_empty());
+    }
+
+    #[test]
+    fn should_match_content_across_writes() {
+        let mut matcher = ContentMatcher::new("connection refused");
+        assert!(matcher.write(b"[error] upstream: conne").is_ok());
+        assert_eq!(matcher.is_match(), false);
+        assert!(matcher.clone().write(b"ction refused\n").is_err());
+        assert_eq!(matcher.is_match(), true);
+        // not found
+        let mut matcher = ContentMatcher::new("timeout");
+        assert!(io::copy(&mut "all good\n".repeat(4096).as_bytes(), &mut matcher).is_ok());
+        assert_eq!(matcher.is_match(), false);
+    }
+
+    #[test]
+    fn should_skip_binary_content() {
+        let mut matcher = ContentMatcher::new("ELF");
+        assert!(matcher
+            .write(&[0x7f, b'E', b'L', b'F', 0x00, 0x00])
+            .is_err());
+        assert_eq!(matcher.is_match(), false);
+    }
+}
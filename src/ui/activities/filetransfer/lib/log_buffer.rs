@@ -0,0 +1,136 @@
+//! ## FileTransferActivity
+//!
+//! `filetransfer_activiy` is the module which implements the Filetransfer activity, which is the main activity afterall
+
+use std::collections::VecDeque;
+
+/// Records of the log panel, newest first, bounded both in amount and in the total size of their messages
+#[derive(Debug)]
+pub struct LogBuffer<R: AsRef<str>> {
+    records: VecDeque<R>,
+    /// Total size in bytes of the messages of `records`
+    bytes: usize,
+    capacity: usize,
+    /// Maximum value of `bytes`; `0` means that only the amount of records is bounded
+    max_bytes: usize,
+}
+
+impl<R: AsRef<str>> LogBuffer<R> {
+    /// Instantiate a new buffer holding up to `capacity` records, whose messages take up to `max_bytes`
+    pub fn new(capacity: usize, max_bytes: usize) -> Self {
+        Self {
+            records: VecDeque::with_capacity(capacity),
+            bytes: 0,
+            capacity,
+            max_bytes,
+        }
+    }
+
+    /// Push `record` as the newest one, dropping the oldest records which don't fit anymore.
+    /// The newest record is always kept, even if its message alone exceeds `max_bytes`
+    pub fn push(&mut self, record: R) {
+        self.bytes += record.as_ref().len();
+        self.records.push_front(record);
+        while self.records.len() > 1
+            && (self.records.len() > self.capacity
+                || (self.max_bytes > 0 && self.bytes > self.max_bytes))
+        {
+            if let Some(oldest) = self.records.pop_back() {
+                self.bytes -= oldest.as_ref().len();
+            }
+        }
+    }
+
+    /// Iterate over records, from the newest to the oldest
+    pub fn iter(&self) -> impl Iterator<Item = &R> {
+        self.records.iter()
+    }
+
+    /// Amount of records in the buffer
+    #[cfg(test)]
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    /// Total size in bytes of the messages in the buffer
+    #[cfg(test)]
+    pub fn bytes(&self) -> usize {
+        self.bytes
+    }
+}
+
+/// Truncate `msg` to `max_len` characters, telling how many characters have been cut.
+/// `0` means that messages are never truncated
+pub fn truncate_message(mut msg: String, max_len: usize) -> String {
+    if max_len == 0 {
+        return msg;
+    }
+    match msg.char_indices().nth(max_len) {
+        None => msg,
+        Some((offset, _)) => {
+            let cut = msg[offset..].chars().count();
+            msg.truncate(offset);
+            msg.push_str(&format!("… ({cut} more characters)"));
+            msg
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn should_bound_amount_of_records() {
+        let mut buffer = LogBuffer::new(256, 0);
+        for i in 0..100_000 {
+            buffer.push(format!("record {i}"));
+        }
+        assert_eq!(buffer.len(), 256);
+        assert_eq!(buffer.iter().next().unwrap(), "record 99999");
+        assert_eq!(buffer.iter().last().unwrap(), "record 99744");
+        assert_eq!(
+            buffer.bytes(),
+            buffer.iter().map(|x| x.len()).sum::<usize>()
+        );
+    }
+
+    #[test]
+    fn should_bound_total_size_of_messages() {
+        let mut buffer = LogBuffer::new(256, 64 * 1024);
+        for i in 0..10_000 {
+            buffer.push(format!("{i:05}{}", "x".repeat(1019)));
+        }
+        // each message takes 1KB
+        assert_eq!(buffer.len(), 64);
+        assert_eq!(buffer.bytes(), 64 * 1024);
+        assert!(buffer.iter().next().unwrap().starts_with("09999"));
+        // a message larger than the limit is kept, alone
+        buffer.push("y".repeat(100 * 1024));
+        assert_eq!(buffer.len(), 1);
+        assert_eq!(buffer.bytes(), 100 * 1024);
+        buffer.push(String::from("small"));
+        assert_eq!(buffer.len(), 1);
+        assert_eq!(buffer.iter().next().unwrap(), "small");
+    }
+
+    #[test]
+    fn should_truncate_messages() {
+        assert_eq!(truncate_message(String::from("hello"), 0), "hello");
+        assert_eq!(truncate_message(String::from("hello"), 5), "hello");
+        assert_eq!(
+            truncate_message(String::from("hello world"), 5),
+            "hello… (6 more characters)"
+        );
+        // multi-byte characters are never split
+        assert_eq!(
+            truncate_message(String::from("àèìòù"), 2),
+            "àè… (3 more characters)"
+        );
+        let huge = truncate_message("z".repeat(10 * 1024 * 1024), 4096);
+        assert!(huge.len() < 4200);
+    }
+}
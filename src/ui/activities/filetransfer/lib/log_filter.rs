@@ -0,0 +1,173 @@
+//! ## FileTransferActivity
+//!
+//! `filetransfer_activiy` is the module which implements the Filetransfer activity, which is the main activity afterall
+
+use super::super::LogLevel;
+
+/// Level of the records displayed in the log panel
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum LogLevelFilter {
+    #[default]
+    All,
+    Info,
+    Warn,
+    Error,
+}
+
+impl LogLevelFilter {
+    /// Get the filter following this one, cycling back to `All`
+    pub fn next(self) -> Self {
+        match self {
+            Self::All => Self::Info,
+            Self::Info => Self::Warn,
+            Self::Warn => Self::Error,
+            Self::Error => Self::All,
+        }
+    }
+
+    /// Returns whether the records with `level` are displayed
+    pub fn matches(self, level: &LogLevel) -> bool {
+        matches!(
+            (self, level),
+            (Self::All, _)
+                | (Self::Info, LogLevel::Info)
+                | (Self::Warn, LogLevel::Warn)
+                | (Self::Error, LogLevel::Error)
+        )
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::All => "all",
+            Self::Info => "info",
+            Self::Warn => "warn",
+            Self::Error => "error",
+        }
+    }
+}
+
+/// View state of the log panel: the level filter and the text search among the displayed records
+#[derive(Debug, Default)]
+pub struct LogFilter {
+    pub level: LogLevelFilter,
+    /// Searched text, lowercase
+    search: Option<String>,
+    /// Rows of the displayed records matching the search, from the newest
+    matches: Vec<usize>,
+    /// Index in `matches` of the current match
+    current: usize,
+}
+
+impl LogFilter {
+    /// Search `text` among the records; an empty text clears the search
+    pub fn set_search(&mut self, text: &str) {
+        let text = text.trim();
+        self.search = (!text.is_empty()).then(|| text.to_lowercase());
+        self.matches.clear();
+        self.current = 0;
+    }
+
+    /// Get the searched text
+    pub fn search(&self) -> &str {
+        self.search.as_deref().unwrap_or_default()
+    }
+
+    /// Returns whether `msg` matches the search, ignoring case
+    pub fn is_match(&self, msg: &str) -> bool {
+        self.search
+            .as_deref()
+            .is_some_and(|search| msg.to_lowercase().contains(search))
+    }
+
+    /// Set the rows of the displayed records which match the search
+    pub fn set_matches(&mut self, matches: Vec<usize>) {
+        self.matches = matches;
+        self.current = self.current.min(self.matches.len().saturating_sub(1));
+    }
+
+    /// Get the row of the current match
+    pub fn current_match(&self) -> Option<usize> {
+        self.matches.get(self.current).copied()
+    }
+
+    /// Move to the next match if `older`, otherwise to the previous one, wrapping around.
+    /// Returns the row of the new current match
+    pub fn move_match(&mut self, older: bool) -> Option<usize> {
+        let count = self.matches.len();
+        if count == 0 {
+            return None;
+        }
+        self.current = match older {
+            true => (self.current + 1) % count,
+            false => (self.current + count - 1) % count,
+        };
+        self.current_match()
+    }
+
+    /// Title of the log panel, reporting the filter and the search
+    pub fn title(&self) -> String {
+        let mut title = String::from("Log");
+        if self.level != LogLevelFilter::All {
+            title.push_str(&format!(" [{}]", self.level.name()));
+        }
+        if let Some(search) = self.search.as_deref() {
+            let current = match self.matches.is_empty() {
+                true => 0,
+                false => self.current + 1,
+            };
+            title.push_str(&format!(" /{search} ({current}/{})", self.matches.len()));
+        }
+        title
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn should_filter_log_records_by_level() {
+        let mut filter = LogLevelFilter::default();
+        assert!(filter.matches(&LogLevel::Info));
+        assert!(filter.matches(&LogLevel::Error));
+        filter = filter.next();
+        assert_eq!(filter, LogLevelFilter::Info);
+        assert!(!filter.matches(&LogLevel::Warn));
+        filter = filter.next().next();
+        assert_eq!(filter, LogLevelFilter::Error);
+        assert!(filter.matches(&LogLevel::Error));
+        assert!(!filter.matches(&LogLevel::Info));
+        assert_eq!(filter.next(), LogLevelFilter::All);
+    }
+
+    #[test]
+    fn should_search_log_records() {
+        let mut filter = LogFilter::default();
+        assert!(!filter.is_match("Connected to example.com"));
+        assert_eq!(filter.title(), "Log");
+        filter.set_search(" EXAMPLE ");
+        assert!(filter.is_match("Connected to example.com"));
+        assert!(!filter.is_match("Disconnected"));
+        filter.set_matches(vec![1, 4, 6]);
+        assert_eq!(filter.current_match(), Some(1));
+        assert_eq!(filter.move_match(true), Some(4));
+        assert_eq!(filter.move_match(true), Some(6));
+        assert_eq!(filter.move_match(true), Some(1));
+        assert_eq!(filter.move_match(false), Some(6));
+        filter.level = LogLevelFilter::Warn;
+        assert_eq!(filter.title(), "Log [warn] /example (3/3)");
+        // the current match is kept within the matches
+        filter.set_matches(vec![2]);
+        assert_eq!(filter.current_match(), Some(2));
+        filter.set_matches(vec![]);
+        assert_eq!(filter.current_match(), None);
+        assert_eq!(filter.move_match(true), None);
+        assert_eq!(filter.title(), "Log [warn] /example (0/0)");
+        filter.set_search("");
+        assert!(!filter.is_match("Connected to example.com"));
+        assert_eq!(filter.title(), "Log [warn]");
+    }
+}
@@ -0,0 +1,122 @@
+//! ## FileTransferActivity
+//!
+//! `filetransfer_activiy` is the module which implements the Filetransfer activity, which is the main activity afterall
+
+use std::path::{Path, PathBuf};
+
+use remotefs::fs::{Metadata, UnixPex};
+use remotefs::{RemoteFs, RemoteResult};
+
+use crate::filetransfer::params::ModePrefs;
+use crate::filetransfer::FileTransferParams;
+use crate::system::config_client::ConfigClient;
+
+/// Metadata of the source entries to apply to the destination of a transfer.
+///
+/// Files get their metadata as soon as they've been written, while directories get it once the whole transfer has
+/// terminated: writing their content would change their modification time afterwards, and a read-only mode would
+/// prevent their content from being written at all
+#[derive(Debug, Default)]
+pub struct MetadataState {
+    /// Whether the metadata must be applied; it's not if disabled or if the destination can't set times
+    enabled: bool,
+    /// Directories created by the transfer, with the metadata of their source, in the order they've been created
+    dirs: Vec<(PathBuf, Metadata)>,
+}
+
+impl MetadataState {
+    pub fn new(enabled: bool) -> Self {
+        Self {
+            enabled,
+            dirs: Vec::new(),
+        }
+    }
+
+    /// Whether the metadata of the source must be applied to the destination
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Schedule the directory created at `path` to get the `metadata` of its source, once the transfer has terminated
+    pub fn push_dir(&mut self, path: &Path, metadata: &Metadata) {
+        if self.enabled {
+            self.dirs.push((path.to_path_buf(), metadata.clone()));
+        }
+    }
+
+    /// Take the directories which must get the metadata of their source, the innermost ones first
+    pub fn take_dirs(&mut self) -> Vec<(PathBuf, Metadata)> {
+        let mut dirs = std::mem::take(&mut self.dirs);
+        dirs.reverse();
+        dirs
+    }
+}
+
+/// Get `metadata` with its mode replaced by `mode`, if any
+pub fn with_mode(metadata: &Metadata, mode: Option<UnixPex>) -> Metadata {
+    let mut metadata = metadata.clone();
+    if mode.is_some() {
+        metadata.mode = mode;
+    }
+    metadata
+}
+
+/// Set the `mode` of the entry at `path` on the remote, keeping its other metadata
+/// Get the modes to apply to the entries created on the remote of `params`, if its protocol can change them
+pub fn remote_modes(params: &FileTransferParams, config: &ConfigClient) -> ModePrefs {
+    match params.protocol.supports_chmod() {
+        true => params.modes.or_defaults(
+            config.get_default_file_mode(),
+            config.get_default_dir_mode(),
+        ),
+        false => ModePrefs::default(),
+    }
+}
+
+pub fn set_remote_mode(client: &mut dyn RemoteFs, path: &Path, mode: UnixPex) -> RemoteResult<()> {
+    let metadata = with_mode(client.stat(path)?.metadata(), Some(mode));
+    client.setstat(path, metadata)
+}
+
+#[cfg(test)]
+mod test {
+
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn should_restore_directories_innermost_first() {
+        let mut metadata = MetadataState::new(true);
+        assert_eq!(metadata.is_enabled(), true);
+        let modified = Metadata::default().modified(UNIX_EPOCH + Duration::from_secs(1_000));
+        metadata.push_dir(Path::new("/backup"), &modified);
+        metadata.push_dir(Path::new("/backup/photos"), &Metadata::default());
+        let dirs: Vec<PathBuf> = metadata.take_dirs().into_iter().map(|(x, _)| x).collect();
+        assert_eq!(
+            dirs,
+            vec![PathBuf::from("/backup/photos"), PathBuf::from("/backup")]
+        );
+        assert!(metadata.take_dirs().is_empty());
+    }
+
+    #[test]
+    fn should_not_collect_directories_if_disabled() {
+        let mut metadata = MetadataState::default();
+        assert_eq!(metadata.is_enabled(), false);
+        metadata.push_dir(Path::new("/backup"), &Metadata::default());
+        assert!(metadata.take_dirs().is_empty());
+    }
+
+    #[test]
+    fn should_replace_mode_of_metadata() {
+        let metadata = Metadata::default().mode(UnixPex::from(0o644));
+        assert_eq!(
+            with_mode(&metadata, Some(UnixPex::from(0o664))).mode,
+            Some(UnixPex::from(0o664))
+        );
+        assert_eq!(with_mode(&metadata, None).mode, Some(UnixPex::from(0o644)));
+    }
+}
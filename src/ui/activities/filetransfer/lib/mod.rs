@@ -3,5 +3,23 @@
 //! `filetransfer_activiy` is the module which implements the Filetransfer activity, which is the main activity afterall
 
 pub(crate) mod browser;
+pub(crate) mod clock_skew;
+pub(crate) mod exec_history;
+pub(crate) mod grep;
+pub(crate) mod log_buffer;
+pub(crate) mod log_filter;
+pub(crate) mod metadata;
+pub(crate) mod parallel;
+pub(crate) mod preview;
+pub(crate) mod queue;
+pub(crate) mod reconnect;
+pub(crate) mod remote_version;
+pub(crate) mod schedule;
+pub(crate) mod special;
+pub(crate) mod sync;
 pub(crate) mod transfer;
+pub(crate) mod undo;
+pub(crate) mod unfinished;
+pub(crate) mod visited;
+pub(crate) mod wait;
 pub(crate) mod walkdir;
@@ -0,0 +1,688 @@
+//! ## FileTransferActivity
+//!
+//! `filetransfer_activiy` is the module which implements the Filetransfer activity, which is the main activity afterall
+
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, Receiver};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use remotefs::fs::{File, Metadata, UnixPex};
+use remotefs::{RemoteErrorType, RemoteFs};
+
+use super::clock_skew;
+use super::metadata::{self, MetadataState};
+use super::queue::QueueSession;
+use super::transfer::{ProgressReader, ProgressWriter, TransferProgress};
+use super::visited::VisitedDirs;
+use crate::filetransfer::params::ModePrefs;
+use crate::filetransfer::{
+    FileTransferParams, HostBridgeBuilder, HostBridgeParams, RemoteFsBuilder, SshNegotiationHandle,
+};
+use crate::host::{HostBridge, HostError, HostErrorType};
+use crate::system::config_client::ConfigClient;
+use crate::utils::io::copy_with_progress;
+
+/// Direction of a parallel transfer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransferDirection {
+    /// From the host bridge to the remote host
+    Upload,
+    /// From the remote host to the host bridge
+    Download,
+}
+
+/// A file to transfer from `source` to the `dest` path
+#[derive(Debug)]
+pub struct TransferJob {
+    pub source: File,
+    pub dest: PathBuf,
+}
+
+impl TransferJob {
+    pub fn new(source: File, dest: PathBuf) -> Self {
+        Self { source, dest }
+    }
+}
+
+/// Outcome of a successful job
+#[derive(Debug, PartialEq, Eq)]
+pub enum JobOutcome {
+    Transferred,
+    /// The destination file is equal to source, so it hasn't been transferred
+    Unchanged,
+}
+
+/// Events reported by the workers
+#[derive(Debug)]
+pub enum WorkerEvent {
+    /// The worker could not connect; its share of jobs is left to the other workers
+    ConnectionFailed(String),
+    /// A job has terminated
+    Done(TransferJob, Result<JobOutcome, String>),
+}
+
+/// Connection used by a worker to transfer its jobs
+pub trait WorkerSession {
+    /// Transfer the file described by `job`, reporting the bytes transferred to `progress`
+    fn transfer(
+        &mut self,
+        job: &TransferJob,
+        progress: &TransferProgress,
+    ) -> Result<JobOutcome, String>;
+
+    /// Close the connection
+    fn disconnect(&mut self);
+}
+
+/// Pool of workers which transfer a queue of jobs in parallel, each one with its own session.
+/// Workers stop picking jobs as soon as the transfer is aborted
+pub struct TransferWorkers {
+    workers: Vec<JoinHandle<()>>,
+    queue: Arc<Mutex<VecDeque<TransferJob>>>,
+    receiver: Receiver<WorkerEvent>,
+}
+
+impl TransferWorkers {
+    /// Spawn `concurrency` workers to transfer `jobs`.
+    /// Each worker opens its session with `connect`
+    pub fn spawn<C, S>(
+        concurrency: usize,
+        jobs: Vec<TransferJob>,
+        progress: TransferProgress,
+        connect: C,
+    ) -> Self
+    where
+        C: Fn() -> Result<S, String> + Send + Sync + 'static,
+        S: WorkerSession,
+    {
+        let concurrency = concurrency.clamp(1, jobs.len().max(1));
+        let queue = Arc::new(Mutex::new(VecDeque::from(jobs)));
+        let connect = Arc::new(connect);
+        let (sender, receiver) = channel();
+        let workers = (0..concurrency)
+            .map(|id| {
+                let queue = queue.clone();
+                let connect = connect.clone();
+                let progress = progress.clone();
+                let sender = sender.clone();
+                thread::spawn(move || {
+                    let mut session = match connect() {
+                        Ok(session) => session,
+                        Err(err) => {
+                            error!("transfer worker {id} could not connect: {err}");
+                            let _ = sender.send(WorkerEvent::ConnectionFailed(err));
+                            return;
+                        }
+                    };
+                    debug!("transfer worker {id} connected");
+                    while !progress.aborted() {
+                        let Some(job) = queue.lock().unwrap().pop_front() else {
+                            break;
+                        };
+                        let result = session.transfer(&job, &progress);
+                        if sender.send(WorkerEvent::Done(job, result)).is_err() {
+                            break;
+                        }
+                    }
+                    session.disconnect();
+                    debug!("transfer worker {id} terminated");
+                })
+            })
+            .collect();
+        Self {
+            workers,
+            queue,
+            receiver,
+        }
+    }
+
+    /// Collect the events reported by the workers since the last call
+    pub fn poll(&self) -> Vec<WorkerEvent> {
+        self.receiver.try_iter().collect()
+    }
+
+    /// Returns whether all the workers have terminated
+    pub fn is_finished(&self) -> bool {
+        self.workers.iter().all(|x| x.is_finished())
+    }
+
+    /// Wait for the workers to terminate.
+    /// Returns the events which haven't been polled yet and the jobs which haven't been processed,
+    /// because the transfer has been aborted or no worker could connect
+    pub fn join(self) -> (Vec<WorkerEvent>, Vec<TransferJob>) {
+        for worker in self.workers {
+            if worker.join().is_err() {
+                error!("a transfer worker panicked");
+            }
+        }
+        let events = self.receiver.try_iter().collect();
+        let pending = self.queue.lock().unwrap().drain(..).collect();
+        (events, pending)
+    }
+}
+
+/// Worker session with its own connections to the host bridge and to the remote host
+pub struct RemoteSession {
+    direction: TransferDirection,
+    host_bridge: Box<dyn HostBridge>,
+    client: Box<dyn RemoteFs>,
+    /// Tolerance of the comparison of the modification times, because of the clock skew of the remote host
+    mtime_tolerance: Duration,
+    /// Whether symbolic links are re-created at the destination, instead of being followed
+    preserve_symlinks: bool,
+    /// Whether modification times are compared, or files are compared by size only
+    compare_mtime: bool,
+    /// Metadata to apply to the destination of the transferred entries
+    metadata: MetadataState,
+    /// Whether the metadata of the source is applied to the destination of the queued uploads
+    preserve_upload_metadata: bool,
+    /// Whether the metadata of the source is applied to the destination of the queued downloads
+    preserve_download_metadata: bool,
+    /// Modes to apply to the entries created on the remote
+    modes: ModePrefs,
+    /// Size of the buffer used to copy the streams
+    buffer_size: usize,
+}
+
+impl RemoteSession {
+    /// Open new connections to the host bridge and to the remote host
+    pub fn connect(
+        direction: TransferDirection,
+        host_bridge_params: HostBridgeParams,
+        remote_params: FileTransferParams,
+        config_client: &ConfigClient,
+    ) -> Result<Self, String> {
+        let mut host_bridge = HostBridgeBuilder::build(host_bridge_params, config_client);
+        host_bridge.connect().map_err(|err| err.to_string())?;
+        let mut client = RemoteFsBuilder::build_connection(
+            &remote_params,
+            SshNegotiationHandle::default(),
+            config_client,
+        );
+        if let Err(err) = client.connect() {
+            let _ = host_bridge.disconnect();
+            return Err(err.to_string());
+        }
+        Ok(Self {
+            direction,
+            host_bridge,
+            client,
+            mtime_tolerance: Duration::ZERO,
+            preserve_symlinks: false,
+            compare_mtime: true,
+            metadata: MetadataState::default(),
+            preserve_upload_metadata: false,
+            preserve_download_metadata: false,
+            modes: ModePrefs::default(),
+            buffer_size: config_client.get_transfer_buffer_size(),
+        })
+    }
+
+    /// Set the tolerance of the comparison of the modification times
+    pub fn mtime_tolerance(mut self, tolerance: Duration) -> Self {
+        self.mtime_tolerance = tolerance;
+        self
+    }
+
+    /// Set whether modification times are compared to tell whether a file has changed, or only sizes are
+    pub fn compare_mtime(mut self, compare: bool) -> Self {
+        self.compare_mtime = compare;
+        self
+    }
+
+    /// Set whether symbolic links are re-created at the destination, instead of being followed
+    pub fn preserve_symlinks(mut self, preserve: bool) -> Self {
+        self.preserve_symlinks = preserve;
+        self
+    }
+
+    /// Set whether the metadata of the source is applied to the destination
+    pub fn preserve_metadata(mut self, preserve: bool) -> Self {
+        let direction = self.direction;
+        self.metadata = MetadataState::new(preserve);
+        self.preserve_metadata_of(direction, preserve)
+    }
+
+    /// Set whether the metadata of the source is applied to the destination of the queued transfers in `direction`
+    pub fn preserve_metadata_of(mut self, direction: TransferDirection, preserve: bool) -> Self {
+        match direction {
+            TransferDirection::Upload => self.preserve_upload_metadata = preserve,
+            TransferDirection::Download => self.preserve_download_metadata = preserve,
+        }
+        self
+    }
+
+    /// Set the modes applied to the entries created on the remote
+    pub fn modes(mut self, modes: ModePrefs) -> Self {
+        self.modes = modes;
+        self
+    }
+
+    /// Apply the configured mode to the entry created on the remote at `path`, if any
+    fn apply_remote_mode(&mut self, path: &Path, dir: bool) {
+        if let Some(mode) = self.modes.mode(dir) {
+            if let Err(err) = metadata::set_remote_mode(self.client.as_mut(), path, mode) {
+                warn!("failed to set mode for {}: {}", path.display(), err);
+            }
+        }
+    }
+
+    /// Get the entry at `path` on the host the files are read from, according to `direction`
+    pub fn stat_source(
+        &mut self,
+        direction: TransferDirection,
+        path: &Path,
+    ) -> Result<File, String> {
+        match direction {
+            TransferDirection::Upload => self.host_bridge.stat(path).map_err(|err| err.to_string()),
+            TransferDirection::Download => self.client.stat(path).map_err(|err| err.to_string()),
+        }
+    }
+
+    /// Get the entry at `path` on the host the files are written to, according to `direction`, if it exists
+    pub fn stat_dest(&mut self, direction: TransferDirection, path: &Path) -> Option<File> {
+        match direction {
+            TransferDirection::Upload => self.client.stat(path).ok(),
+            TransferDirection::Download => self.host_bridge.stat(path).ok(),
+        }
+    }
+
+    fn upload(
+        &mut self,
+        job: &TransferJob,
+        progress: &TransferProgress,
+    ) -> Result<JobOutcome, String> {
+        let metadata = self
+            .host_bridge
+            .stat(job.source.path())
+            .map_err(|err| err.to_string())?
+            .metadata()
+            .clone();
+        if let Ok(remote) = self.client.stat(job.dest.as_path()) {
+            if self.is_unchanged(&metadata, remote.metadata()) {
+                return Ok(JobOutcome::Unchanged);
+            }
+        }
+        let reader = self
+            .host_bridge
+            .open_file(job.source.path())
+            .map_err(|err| err.to_string())?;
+        let mut reader = ProgressReader::new(reader, progress.clone());
+        match self.client.create(job.dest.as_path(), &metadata) {
+            Ok(mut writer) => {
+                let result = copy_with_progress(&mut reader, &mut writer, self.buffer_size, |_| {
+                    !progress.aborted()
+                });
+                let finalized = self.client.on_written(writer);
+                result.map_err(|err| err.to_string())?;
+                finalized.map_err(|err| err.to_string())?;
+            }
+            Err(err) if err.kind == RemoteErrorType::UnsupportedFeature => {
+                self.client
+                    .create_file(job.dest.as_path(), &metadata, Box::new(reader))
+                    .map_err(|err| err.to_string())?;
+            }
+            Err(err) => return Err(err.to_string()),
+        }
+        if self.metadata.is_enabled() {
+            if let Err(err) = self.client.setstat(job.dest.as_path(), metadata) {
+                error!("failed to set stat for {}: {}", job.dest.display(), err);
+            }
+        }
+        self.apply_remote_mode(job.dest.as_path(), false);
+        Ok(JobOutcome::Transferred)
+    }
+
+    fn download(
+        &mut self,
+        job: &TransferJob,
+        progress: &TransferProgress,
+    ) -> Result<JobOutcome, String> {
+        if let Ok(host_bridge) = self.host_bridge.stat(job.dest.as_path()) {
+            if self.is_unchanged(job.source.metadata(), host_bridge.metadata()) {
+                return Ok(JobOutcome::Unchanged);
+            }
+        }
+        let writer = self
+            .host_bridge
+            .create_file(job.dest.as_path(), job.source.metadata())
+            .map_err(|err| err.to_string())?;
+        let mut writer = ProgressWriter::new(writer, progress.clone());
+        match self.client.open(job.source.path()) {
+            Ok(mut reader) => {
+                let result = copy_with_progress(&mut reader, &mut writer, self.buffer_size, |_| {
+                    !progress.aborted()
+                });
+                let finalized = self.client.on_read(reader);
+                result.map_err(|err| err.to_string())?;
+                finalized.map_err(|err| err.to_string())?;
+                self.host_bridge
+                    .finalize_write(writer.into_inner())
+                    .map_err(|err| err.to_string())?;
+            }
+            Err(err) if err.kind == RemoteErrorType::UnsupportedFeature => {
+                self.client
+                    .open_file(job.source.path(), Box::new(writer))
+                    .map_err(|err| err.to_string())?;
+            }
+            Err(err) => return Err(err.to_string()),
+        }
+        if self.metadata.is_enabled() {
+            if let Err(err) = self
+                .host_bridge
+                .setstat(job.dest.as_path(), job.source.metadata())
+            {
+                error!("failed to set stat for {}: {}", job.dest.display(), err);
+            }
+        }
+        Ok(JobOutcome::Transferred)
+    }
+
+    /// Files with the same size and modification time are considered equal
+    fn is_unchanged(&self, source: &Metadata, dest: &Metadata) -> bool {
+        source.size == dest.size
+            && (!self.compare_mtime
+                || clock_skew::mtime_equal(source.modified, dest.modified, self.mtime_tolerance))
+    }
+}
+
+impl WorkerSession for RemoteSession {
+    fn transfer(
+        &mut self,
+        job: &TransferJob,
+        progress: &TransferProgress,
+    ) -> Result<JobOutcome, String> {
+        match self.direction {
+            TransferDirection::Upload => self.upload(job, progress),
+            TransferDirection::Download => self.download(job, progress),
+        }
+    }
+
+    fn disconnect(&mut self) {
+        if let Err(err) = self.client.disconnect() {
+            warn!("could not disconnect transfer worker from remote: {err}");
+        }
+        if let Err(err) = self.host_bridge.disconnect() {
+            warn!("could not disconnect transfer worker from host bridge: {err}");
+        }
+    }
+}
+
+impl QueueSession for RemoteSession {
+    fn plan(
+        &mut self,
+        direction: TransferDirection,
+        source: &File,
+        dest: &Path,
+    ) -> Result<Vec<TransferJob>, String> {
+        let mut visited = match direction {
+            TransferDirection::Upload if self.host_bridge.is_localhost() => VisitedDirs::local(),
+            _ => VisitedDirs::remote(),
+        }
+        .preserve_symlinks(self.preserve_symlinks);
+        self.metadata = MetadataState::new(match direction {
+            TransferDirection::Upload => self.preserve_upload_metadata,
+            TransferDirection::Download => self.preserve_download_metadata,
+        });
+        let mut jobs = Vec::new();
+        self.plan_entry(direction, source, dest, &mut visited, &mut jobs)?;
+        Ok(jobs)
+    }
+
+    fn transfer(
+        &mut self,
+        direction: TransferDirection,
+        job: &TransferJob,
+        progress: &TransferProgress,
+    ) -> Result<JobOutcome, String> {
+        match direction {
+            TransferDirection::Upload => self.upload(job, progress),
+            TransferDirection::Download => self.download(job, progress),
+        }
+    }
+
+    fn finish(&mut self, direction: TransferDirection) {
+        for (path, metadata) in self.metadata.take_dirs() {
+            let result = match direction {
+                TransferDirection::Upload => self
+                    .client
+                    .setstat(path.as_path(), metadata)
+                    .map_err(|err| err.to_string()),
+                TransferDirection::Download => self
+                    .host_bridge
+                    .setstat(path.as_path(), &metadata)
+                    .map_err(|err| err.to_string()),
+            };
+            if let Err(err) = result {
+                error!("failed to set stat for {}: {}", path.display(), err);
+            }
+        }
+    }
+
+    fn disconnect(&mut self) {
+        WorkerSession::disconnect(self)
+    }
+}
+
+impl RemoteSession {
+    /// Create the directory tree of `source` at `dest`, collecting into `jobs` the files to transfer
+    fn plan_entry(
+        &mut self,
+        direction: TransferDirection,
+        source: &File,
+        dest: &Path,
+        visited: &mut VisitedDirs,
+        jobs: &mut Vec<TransferJob>,
+    ) -> Result<(), String> {
+        if let Some(target) = visited.preserved_link(source) {
+            if self.symlink(direction, dest, target)? {
+                return Ok(());
+            }
+        }
+        if !source.is_dir() {
+            jobs.push(TransferJob::new(source.clone(), dest.to_path_buf()));
+            return Ok(());
+        }
+        if !visited.enter(source) {
+            warn!("skipping symbolic link loop at {}", source.path().display());
+            return Ok(());
+        }
+        let entries = match direction {
+            TransferDirection::Upload => {
+                match self.client.create_dir(dest, UnixPex::from(0o755)) {
+                    Ok(_) => self.apply_remote_mode(dest, true),
+                    Err(err) if err.kind == RemoteErrorType::DirectoryAlreadyExists => {}
+                    Err(err) => return Err(err.to_string()),
+                }
+                self.metadata.push_dir(
+                    dest,
+                    &metadata::with_mode(source.metadata(), self.modes.mode(true)),
+                );
+                self.host_bridge
+                    .list_dir(source.path())
+                    .map_err(|err| err.to_string())?
+            }
+            TransferDirection::Download => {
+                self.host_bridge
+                    .mkdir_ex(dest, true)
+                    .map_err(|err| err.to_string())?;
+                self.metadata.push_dir(dest, source.metadata());
+                self.client
+                    .list_dir(source.path())
+                    .map_err(|err| err.to_string())?
+            }
+        };
+        for entry in entries.iter() {
+            self.plan_entry(
+                direction,
+                entry,
+                dest.join(entry.name()).as_path(),
+                visited,
+                jobs,
+            )?;
+        }
+        visited.leave(source.path());
+        Ok(())
+    }
+
+    /// Create at `dest` a symbolic link pointing at `target`.
+    /// Returns `false` if symbolic links are not supported at the destination, so the link must be followed instead
+    fn symlink(
+        &mut self,
+        direction: TransferDirection,
+        dest: &Path,
+        target: &Path,
+    ) -> Result<bool, String> {
+        let result = match direction {
+            TransferDirection::Upload => match self.client.symlink(dest, target) {
+                Err(err) if err.kind == RemoteErrorType::UnsupportedFeature => Ok(false),
+                result => result.map(|_| true).map_err(|err| err.to_string()),
+            },
+            TransferDirection::Download => match self.host_bridge.symlink(dest, target) {
+                Err(HostError {
+                    error: HostErrorType::NotImplemented,
+                    ..
+                }) => Ok(false),
+                result => result.map(|_| true).map_err(|err| err.to_string()),
+            },
+        };
+        if let Ok(false) = result {
+            warn!(
+                "symbolic links are not supported at the destination: following {}",
+                dest.display()
+            );
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    use pretty_assertions::assert_eq;
+    use remotefs::fs::{FileType, Metadata};
+
+    use super::*;
+    use crate::ui::activities::filetransfer::lib::transfer::TransferStates;
+
+    /// Session which "transfers" files sleeping for a while; files named `fail` can't be transferred
+    struct SleepSession {
+        active: Arc<AtomicUsize>,
+        max_active: Arc<AtomicUsize>,
+    }
+
+    impl WorkerSession for SleepSession {
+        fn transfer(
+            &mut self,
+            job: &TransferJob,
+            _progress: &TransferProgress,
+        ) -> Result<JobOutcome, String> {
+            let active = self.active.fetch_add(1, Ordering::SeqCst) + 1;
+            self.max_active.fetch_max(active, Ordering::SeqCst);
+            thread::sleep(Duration::from_millis(20));
+            self.active.fetch_sub(1, Ordering::SeqCst);
+            match job.source.name().as_str() {
+                "fail" => Err(String::from("permission denied")),
+                _ => Ok(JobOutcome::Transferred),
+            }
+        }
+
+        fn disconnect(&mut self) {}
+    }
+
+    fn make_job(name: &str) -> TransferJob {
+        let path = PathBuf::from("/tmp").join(name);
+        TransferJob::new(
+            File {
+                path: path.clone(),
+                metadata: Metadata::default().file_type(FileType::File),
+            },
+            path,
+        )
+    }
+
+    fn sleep_session(
+        active: &Arc<AtomicUsize>,
+        max_active: &Arc<AtomicUsize>,
+    ) -> impl Fn() -> Result<SleepSession, String> + Send + Sync + 'static {
+        let active = active.clone();
+        let max_active = max_active.clone();
+        move || {
+            Ok(SleepSession {
+                active: active.clone(),
+                max_active: max_active.clone(),
+            })
+        }
+    }
+
+    #[test]
+    fn should_transfer_jobs_in_parallel_and_collect_errors() {
+        let active = Arc::new(AtomicUsize::new(0));
+        let max_active = Arc::new(AtomicUsize::new(0));
+        let jobs: Vec<TransferJob> = ["a", "b", "fail", "c", "d", "e", "f", "g"]
+            .into_iter()
+            .map(make_job)
+            .collect();
+        let states = TransferStates::default();
+        let workers = TransferWorkers::spawn(
+            4,
+            jobs,
+            states.progress(),
+            sleep_session(&active, &max_active),
+        );
+        let (events, pending) = workers.join();
+        assert!(pending.is_empty());
+        assert_eq!(events.len(), 8);
+        let errors: Vec<String> = events
+            .into_iter()
+            .filter_map(|x| match x {
+                WorkerEvent::Done(job, Err(err)) => Some(format!("{}: {err}", job.source.name())),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(errors, vec![String::from("fail: permission denied")]);
+        assert!(max_active.load(Ordering::SeqCst) > 1);
+        assert!(max_active.load(Ordering::SeqCst) <= 4);
+    }
+
+    #[test]
+    fn should_stop_workers_on_abort() {
+        let active = Arc::new(AtomicUsize::new(0));
+        let max_active = Arc::new(AtomicUsize::new(0));
+        let jobs: Vec<TransferJob> = (0..100).map(|x| make_job(&x.to_string())).collect();
+        let mut states = TransferStates::default();
+        let workers = TransferWorkers::spawn(
+            2,
+            jobs,
+            states.progress(),
+            sleep_session(&active, &max_active),
+        );
+        thread::sleep(Duration::from_millis(50));
+        states.abort();
+        let (events, pending) = workers.join();
+        assert!(!pending.is_empty());
+        assert_eq!(events.len() + pending.len(), 100);
+    }
+
+    #[test]
+    fn should_leave_jobs_pending_if_no_worker_connects() {
+        let jobs: Vec<TransferJob> = ["a", "b"].into_iter().map(make_job).collect();
+        let states = TransferStates::default();
+        let workers = TransferWorkers::spawn(3, jobs, states.progress(), || {
+            Err::<SleepSession, String>(String::from("connection refused"))
+        });
+        let (events, pending) = workers.join();
+        assert_eq!(pending.len(), 2);
+        // workers are never more than jobs
+        assert_eq!(events.len(), 2);
+        assert!(events.iter().all(
+            |x| matches!(x, WorkerEvent::ConnectionFailed(err) if err == "connection refused")
+        ));
+    }
+}
@@ -0,0 +1,219 @@
+//! ## FileTransferActivity
+//!
+//! `filetransfer_activiy` is the module which implements the Filetransfer activity, which is the main activity afterall
+
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::sync::{Arc, Mutex};
+
+use bytesize::ByteSize;
+use remotefs::fs::ReadStream;
+use remotefs::File;
+
+/// Size of the chunks of the file loaded into the preview
+pub const PREVIEW_CHUNK_SIZE: usize = 16 * 1024;
+
+/// Read-only preview of the first chunks of a remote file, kept in memory
+#[derive(Debug)]
+pub struct Preview {
+    file: File,
+    data: Vec<u8>,
+    complete: bool,
+}
+
+impl Preview {
+    /// Make the preview of `file` out of its first `chunk`.
+    /// Returns `None` if the content of the file is binary
+    pub fn new(file: File, chunk: Vec<u8>) -> Option<Self> {
+        if content_inspector::inspect(&chunk).is_binary() {
+            return None;
+        }
+        let mut preview = Self {
+            file,
+            data: Vec::with_capacity(chunk.len()),
+            complete: false,
+        };
+        preview.push_chunk(chunk);
+        Some(preview)
+    }
+
+    /// The file being previewed
+    pub fn file(&self) -> &File {
+        &self.file
+    }
+
+    /// Offset of the next chunk to load
+    pub fn offset(&self) -> u64 {
+        self.data.len() as u64
+    }
+
+    /// Whether the whole file has been loaded
+    pub fn is_complete(&self) -> bool {
+        self.complete
+    }
+
+    /// Append the next `chunk` of the file. A chunk shorter than [`PREVIEW_CHUNK_SIZE`] is the last one
+    pub fn push_chunk(&mut self, chunk: Vec<u8>) {
+        let size = self.file.metadata().size;
+        self.complete = chunk.len() < PREVIEW_CHUNK_SIZE
+            || (size > 0 && self.data.len() as u64 + chunk.len() as u64 >= size);
+        self.data.extend(chunk);
+    }
+
+    /// Lines of the loaded content, with tabs expanded
+    pub fn lines(&self) -> Vec<String> {
+        String::from_utf8_lossy(&self.data)
+            .lines()
+            .map(|x| x.replace('\t', "    "))
+            .collect()
+    }
+
+    /// Title of the preview, reporting how much of the file has been loaded
+    pub fn title(&self) -> String {
+        match self.complete {
+            true => self.file.name(),
+            false => format!(
+                "{} ({} of {}, <M> to load more)",
+                self.file.name(),
+                ByteSize(self.offset()),
+                ByteSize(self.file.metadata().size)
+            ),
+        }
+    }
+}
+
+/// Read the chunk of the file starting at `offset` from `reader`.
+/// If the stream is not seekable, the content before `offset` is read and discarded
+pub fn read_chunk(reader: &mut ReadStream, offset: u64) -> io::Result<Vec<u8>> {
+    if reader.seekable() {
+        reader.seek(SeekFrom::Start(offset))?;
+    } else {
+        io::copy(&mut reader.take(offset), &mut io::sink())?;
+    }
+    let mut chunk = Vec::with_capacity(PREVIEW_CHUNK_SIZE);
+    reader
+        .take(PREVIEW_CHUNK_SIZE as u64)
+        .read_to_end(&mut chunk)?;
+    Ok(chunk)
+}
+
+/// Writer collecting the chunk of the file starting at `offset`, for protocols which don't support streams.
+/// Once the chunk is full, writes fail, so that the rest of the file is not downloaded
+#[derive(Debug, Clone)]
+pub struct ChunkWriter {
+    chunk: Arc<Mutex<Vec<u8>>>,
+    skip: u64,
+}
+
+impl ChunkWriter {
+    pub fn new(offset: u64) -> Self {
+        Self {
+            chunk: Arc::new(Mutex::new(Vec::with_capacity(PREVIEW_CHUNK_SIZE))),
+            skip: offset,
+        }
+    }
+
+    /// Take the collected chunk
+    pub fn take(&self) -> Vec<u8> {
+        self.chunk
+            .lock()
+            .map(|mut x| std::mem::take(&mut *x))
+            .unwrap_or_default()
+    }
+
+    /// Whether the chunk is full
+    pub fn is_full(&self) -> bool {
+        self.chunk
+            .lock()
+            .map(|x| x.len() >= PREVIEW_CHUNK_SIZE)
+            .unwrap_or(true)
+    }
+}
+
+impl Write for ChunkWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let skipped = buf.len().min(self.skip as usize);
+        self.skip -= skipped as u64;
+        let mut chunk = self
+            .chunk
+            .lock()
+            .map_err(|_| io::Error::other("preview chunk is poisoned"))?;
+        let free = PREVIEW_CHUNK_SIZE - chunk.len();
+        if free == 0 {
+            return Err(io::Error::other("preview chunk is full"));
+        }
+        let data = &buf[skipped..];
+        chunk.extend_from_slice(&data[..data.len().min(free)]);
+        Ok(skipped + data.len().min(free))
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use std::path::PathBuf;
+
+    use pretty_assertions::assert_eq;
+    use remotefs::fs::Metadata;
+
+    use super::*;
+
+    fn file(size: u64) -> File {
+        File {
+            path: PathBuf::from("/var/log/syslog"),
+            metadata: Metadata::default().size(size),
+        }
+    }
+
+    #[test]
+    fn should_load_preview_by_chunks() {
+        let size = (PREVIEW_CHUNK_SIZE + 6) as u64;
+        let mut chunk = "a\tb\n".repeat(PREVIEW_CHUNK_SIZE / 4).into_bytes();
+        chunk.truncate(PREVIEW_CHUNK_SIZE);
+        let mut preview = Preview::new(file(size), chunk).unwrap();
+        assert_eq!(preview.is_complete(), false);
+        assert_eq!(preview.offset(), PREVIEW_CHUNK_SIZE as u64);
+        assert_eq!(preview.lines()[0].as_str(), "a    b");
+        assert_eq!(
+            preview.title().as_str(),
+            "syslog (16.4 KB of 16.4 KB, <M> to load more)"
+        );
+        preview.push_chunk(b"last\n".to_vec());
+        assert_eq!(preview.is_complete(), true);
+        assert_eq!(preview.lines().last().unwrap().as_str(), "last");
+        assert_eq!(preview.title().as_str(), "syslog");
+    }
+
+    #[test]
+    fn should_refuse_binary_preview() {
+        assert!(Preview::new(file(4), vec![0x00, 0xff, 0x00, 0x01]).is_none());
+    }
+
+    #[test]
+    fn should_read_chunk_from_stream() {
+        let data: Vec<u8> = (0..PREVIEW_CHUNK_SIZE * 2 + 10)
+            .map(|x| (x % 256) as u8)
+            .collect();
+        let reader: Box<dyn Read + Send> = Box::new(io::Cursor::new(data.clone()));
+        let mut reader = ReadStream::from(reader);
+        let chunk = read_chunk(&mut reader, PREVIEW_CHUNK_SIZE as u64).unwrap();
+        assert_eq!(
+            chunk.as_slice(),
+            &data[PREVIEW_CHUNK_SIZE..PREVIEW_CHUNK_SIZE * 2]
+        );
+    }
+
+    #[test]
+    fn should_collect_chunk_with_writer() {
+        let data: Vec<u8> = (0..PREVIEW_CHUNK_SIZE * 2)
+            .map(|x| (x % 256) as u8)
+            .collect();
+        let writer = ChunkWriter::new(10);
+        assert!(io::copy(&mut data.as_slice(), &mut writer.clone()).is_err());
+        assert_eq!(writer.is_full(), true);
+        assert_eq!(writer.take().as_slice(), &data[10..PREVIEW_CHUNK_SIZE + 10]);
+    }
+}
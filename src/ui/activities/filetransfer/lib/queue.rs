@@ -0,0 +1,710 @@
+//! ## FileTransferActivity
+//!
+//! `filetransfer_activiy` is the module which implements the Filetransfer activity, which is the main activity afterall
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::thread::{self, JoinHandle};
+
+use chrono::{DateTime, Local};
+use remotefs::File;
+
+use super::parallel::{JobOutcome, TransferDirection, TransferJob};
+use super::transfer::{FailedTransfers, TransferFailure, TransferProgress, TransferStates};
+
+/// State of a queued transfer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum QueueItemState {
+    /// Waiting for its start time
+    Scheduled(DateTime<Local>),
+    Pending,
+    Active,
+    Done,
+    Failed(String),
+}
+
+impl fmt::Display for QueueItemState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Scheduled(at) => write!(f, "scheduled at {}", at.format("%Y-%m-%d %H:%M")),
+            Self::Pending => write!(f, "pending"),
+            Self::Active => write!(f, "active"),
+            Self::Done => write!(f, "done"),
+            Self::Failed(err) => write!(f, "failed: {err}"),
+        }
+    }
+}
+
+/// An entry queued for the transfer to `dest`
+#[derive(Debug)]
+pub struct QueueItem {
+    pub id: usize,
+    pub direction: TransferDirection,
+    pub source: File,
+    pub dest: PathBuf,
+    pub state: QueueItemState,
+}
+
+impl QueueItem {
+    /// Returns whether the item still has to be transferred
+    pub fn is_unfinished(&self) -> bool {
+        matches!(
+            self.state,
+            QueueItemState::Scheduled(_) | QueueItemState::Pending | QueueItemState::Active
+        )
+    }
+}
+
+/// Queue of the transfers started by the user, run in background by a [`QueueWorker`], one at a time
+#[derive(Default)]
+pub struct TransferQueue {
+    items: Vec<QueueItem>,
+    next_id: usize,
+    worker: Option<QueueWorker>,
+    /// Progress of the active item; `full` is the item, `partial` the file being transferred
+    pub transfer: TransferStates,
+    /// Entries of the queued items which couldn't be transferred, by direction, to retry them
+    failures: Vec<FailedTransfers>,
+}
+
+impl TransferQueue {
+    /// Put `source` in the queue, to be transferred to `dest`.
+    /// Returns the id of the item
+    pub fn push(&mut self, direction: TransferDirection, source: File, dest: PathBuf) -> usize {
+        self.push_item(direction, source, dest, QueueItemState::Pending)
+    }
+
+    /// Put `source` in the queue, to be transferred to `dest` once `at` has come; see [`Self::start_due`].
+    /// Returns the id of the item
+    pub fn schedule(
+        &mut self,
+        direction: TransferDirection,
+        source: File,
+        dest: PathBuf,
+        at: DateTime<Local>,
+    ) -> usize {
+        self.push_item(direction, source, dest, QueueItemState::Scheduled(at))
+    }
+
+    fn push_item(
+        &mut self,
+        direction: TransferDirection,
+        source: File,
+        dest: PathBuf,
+        state: QueueItemState,
+    ) -> usize {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.items.push(QueueItem {
+            id,
+            direction,
+            source,
+            dest,
+            state,
+        });
+        id
+    }
+
+    /// Returns the amount of scheduled items and the earliest start time among them
+    pub fn scheduled(&self) -> Option<(usize, DateTime<Local>)> {
+        let times = self.items.iter().filter_map(|x| match x.state {
+            QueueItemState::Scheduled(at) => Some(at),
+            _ => None,
+        });
+        let count = times.clone().count();
+        times.min().map(|at| (count, at))
+    }
+
+    /// Returns whether the start time of a scheduled item has come
+    pub fn has_due(&self, now: DateTime<Local>) -> bool {
+        self.items
+            .iter()
+            .any(|x| matches!(x.state, QueueItemState::Scheduled(at) if at <= now))
+    }
+
+    /// Mark the scheduled items whose start time has come as pending.
+    /// Returns the id and the start time of the started items
+    pub fn start_due(&mut self, now: DateTime<Local>) -> Vec<(usize, DateTime<Local>)> {
+        let mut started = Vec::new();
+        for item in self.items.iter_mut() {
+            if let QueueItemState::Scheduled(at) = item.state {
+                if at <= now {
+                    item.state = QueueItemState::Pending;
+                    started.push((item.id, at));
+                }
+            }
+        }
+        started
+    }
+
+    /// Get the queued items
+    pub fn items(&self) -> &[QueueItem] {
+        &self.items
+    }
+
+    /// Returns the amount of items which still have to be transferred
+    pub fn unfinished(&self) -> usize {
+        self.items.iter().filter(|x| x.is_unfinished()).count()
+    }
+
+    /// Returns whether an item is being transferred
+    pub fn is_active(&self) -> bool {
+        self.items.iter().any(|x| x.state == QueueItemState::Active)
+    }
+
+    /// Remove the item at `idx`.
+    /// The active item can't be removed: its transfer is aborted instead and it's marked as failed once the worker stops.
+    /// Returns the removed item
+    pub fn remove(&mut self, idx: usize) -> Option<QueueItem> {
+        match self.items.get(idx)?.state {
+            QueueItemState::Active => {
+                self.transfer.abort();
+                None
+            }
+            _ => Some(self.items.remove(idx)),
+        }
+    }
+
+    /// Returns whether the worker has been started
+    pub fn has_worker(&self) -> bool {
+        self.worker.is_some()
+    }
+
+    /// Set the worker which runs the transfers
+    pub fn set_worker(&mut self, worker: QueueWorker) {
+        self.worker = Some(worker);
+    }
+
+    /// Stop the worker if it's idle and only scheduled items are left, so that its connections aren't kept open
+    /// while waiting; a new worker is spawned once the items are started.
+    /// Returns whether the worker has been stopped
+    pub fn release_idle_worker(&mut self) -> bool {
+        let idle = self
+            .items
+            .iter()
+            .all(|x| !matches!(x.state, QueueItemState::Pending | QueueItemState::Active));
+        if !idle || self.scheduled().is_none() {
+            return false;
+        }
+        match self.worker.take() {
+            Some(worker) => {
+                worker.stop();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Give the next pending item to the worker, if it's idle.
+    /// Returns the item which has been started
+    pub fn dispatch(&mut self) -> Option<&QueueItem> {
+        if self.is_active() {
+            return None;
+        }
+        let worker = self.worker.as_ref()?;
+        let item = self
+            .items
+            .iter_mut()
+            .find(|x| x.state == QueueItemState::Pending)?;
+        self.transfer.reset();
+        self.transfer.full.init(0);
+        self.transfer.partial.init(0);
+        worker.push(QueueJob {
+            id: item.id,
+            direction: item.direction,
+            source: item.source.clone(),
+            dest: item.dest.clone(),
+        });
+        item.state = QueueItemState::Active;
+        Some(item)
+    }
+
+    /// Collect the events reported by the worker since the last call
+    pub fn poll(&mut self) -> Vec<QueueEvent> {
+        let events = self.worker.as_ref().map(|x| x.poll()).unwrap_or_default();
+        for event in events.iter() {
+            match event {
+                QueueEvent::ConnectionFailed(err) => {
+                    // the worker is gone: the items can't be transferred
+                    self.worker = None;
+                    for item in self.items.iter_mut().filter(|x| x.is_unfinished()) {
+                        item.state = QueueItemState::Failed(err.clone());
+                    }
+                }
+                QueueEvent::Planned { size, .. } => self.transfer.full.init(*size),
+                QueueEvent::FileStarted { size, .. } => self.transfer.partial.init(*size),
+                QueueEvent::FileTransferred { .. } => self.transfer.count_file(),
+                QueueEvent::FileSkipped { size, .. } => {
+                    self.transfer.full.update_progress(*size);
+                    self.transfer.count_file();
+                }
+                QueueEvent::FileFailed { id, failure } => {
+                    if let Some(direction) = self.get(*id).map(|x| x.direction) {
+                        self.push_failure(direction, failure.clone());
+                    }
+                }
+                QueueEvent::Finished { id, result } => {
+                    if let Some(item) = self.items.iter_mut().find(|x| x.id == *id) {
+                        item.state = match result {
+                            Ok(()) => QueueItemState::Done,
+                            Err(err) => QueueItemState::Failed(err.clone()),
+                        };
+                    }
+                }
+            }
+        }
+        self.transfer.sync_progress();
+        events
+    }
+
+    /// Keep the `failure` of a transfer in `direction`, to retry it once the queue has been drained
+    fn push_failure(&mut self, direction: TransferDirection, failure: TransferFailure) {
+        match self.failures.iter_mut().find(|x| x.direction == direction) {
+            Some(failed) => failed.failures.push(failure),
+            None => self.failures.push(FailedTransfers {
+                direction,
+                failures: vec![failure],
+            }),
+        }
+    }
+
+    /// Take the entries which couldn't be transferred in a direction, once no item is left to transfer.
+    /// Call it again to take the ones of the other direction
+    pub fn take_failures(&mut self) -> Option<FailedTransfers> {
+        match self.unfinished() == 0 && !self.failures.is_empty() {
+            true => Some(self.failures.remove(0)),
+            false => None,
+        }
+    }
+
+    /// Get the item with the provided `id`
+    pub fn get(&self, id: usize) -> Option<&QueueItem> {
+        self.items.iter().find(|x| x.id == id)
+    }
+
+    /// Abort the active transfer, stop the worker and mark the unfinished items as failed
+    pub fn stop(&mut self) {
+        self.transfer.abort();
+        if let Some(worker) = self.worker.take() {
+            worker.stop();
+        }
+        for item in self.items.iter_mut().filter(|x| x.is_unfinished()) {
+            item.state = QueueItemState::Failed(String::from("cancelled"));
+        }
+    }
+}
+
+/// A queued item given to the worker
+#[derive(Debug)]
+pub struct QueueJob {
+    id: usize,
+    direction: TransferDirection,
+    source: File,
+    dest: PathBuf,
+}
+
+/// Events reported by the queue worker
+#[derive(Debug, PartialEq, Eq)]
+pub enum QueueEvent {
+    /// The worker could not connect; no item can be transferred
+    ConnectionFailed(String),
+    /// The directories of the item have been created and its files, of `size` bytes, are going to be transferred
+    Planned { id: usize, size: usize },
+    /// A file of the item is being transferred
+    FileStarted {
+        id: usize,
+        path: PathBuf,
+        size: usize,
+    },
+    /// A file of the item has been transferred
+    FileTransferred { id: usize },
+    /// A file of the item hasn't been transferred, since it hasn't changed
+    FileSkipped { id: usize, size: usize },
+    /// A file of the item, or the item itself, couldn't be transferred
+    FileFailed { id: usize, failure: TransferFailure },
+    /// The transfer of the item has terminated
+    Finished {
+        id: usize,
+        result: Result<(), String>,
+    },
+}
+
+/// Connection used by the queue worker to transfer the items
+pub trait QueueSession {
+    /// Create at `dest` the directories of `source` and get the files to transfer
+    fn plan(
+        &mut self,
+        direction: TransferDirection,
+        source: &File,
+        dest: &Path,
+    ) -> Result<Vec<TransferJob>, String>;
+
+    /// Transfer the file described by `job`, reporting the bytes transferred to `progress`
+    fn transfer(
+        &mut self,
+        direction: TransferDirection,
+        job: &TransferJob,
+        progress: &TransferProgress,
+    ) -> Result<JobOutcome, String>;
+
+    /// Terminate the transfer of the item planned last, once all of its files have been transferred
+    fn finish(&mut self, _direction: TransferDirection) {}
+
+    /// Close the connection
+    fn disconnect(&mut self);
+}
+
+/// Worker which transfers the queued items with its own session, in the order they're pushed
+pub struct QueueWorker {
+    jobs: Sender<QueueJob>,
+    events: Receiver<QueueEvent>,
+    handle: JoinHandle<()>,
+}
+
+impl QueueWorker {
+    /// Spawn the worker, which opens its session with `connect`
+    pub fn spawn<C, S>(progress: TransferProgress, connect: C) -> Self
+    where
+        C: FnOnce() -> Result<S, String> + Send + 'static,
+        S: QueueSession,
+    {
+        let (jobs, receiver) = channel::<QueueJob>();
+        let (sender, events) = channel();
+        let handle = thread::spawn(move || {
+            let mut session = match connect() {
+                Ok(session) => session,
+                Err(err) => {
+                    error!("transfer queue worker could not connect: {err}");
+                    let _ = sender.send(QueueEvent::ConnectionFailed(err));
+                    return;
+                }
+            };
+            debug!("transfer queue worker connected");
+            // terminates once the queue is dropped
+            for job in receiver.iter() {
+                let result = Self::run(&mut session, &job, &progress, &sender);
+                if sender
+                    .send(QueueEvent::Finished { id: job.id, result })
+                    .is_err()
+                {
+                    break;
+                }
+            }
+            session.disconnect();
+            debug!("transfer queue worker terminated");
+        });
+        Self {
+            jobs,
+            events,
+            handle,
+        }
+    }
+
+    fn run<S: QueueSession>(
+        session: &mut S,
+        job: &QueueJob,
+        progress: &TransferProgress,
+        sender: &Sender<QueueEvent>,
+    ) -> Result<(), String> {
+        let files = match session.plan(job.direction, &job.source, job.dest.as_path()) {
+            Ok(files) => files,
+            Err(err) => {
+                let _ = sender.send(QueueEvent::FileFailed {
+                    id: job.id,
+                    failure: TransferFailure::new(&job.source, job.dest.clone(), err.clone()),
+                });
+                return Err(err);
+            }
+        };
+        let _ = sender.send(QueueEvent::Planned {
+            id: job.id,
+            size: files
+                .iter()
+                .map(|x| x.source.metadata().size as usize)
+                .sum(),
+        });
+        // errors of the single files don't stop the transfer of the item
+        let mut errors = Vec::new();
+        for file in files.iter() {
+            if progress.aborted() {
+                return Err(String::from("aborted"));
+            }
+            let size = file.source.metadata().size as usize;
+            let _ = sender.send(QueueEvent::FileStarted {
+                id: job.id,
+                path: file.source.path().to_path_buf(),
+                size,
+            });
+            match session.transfer(job.direction, file, progress) {
+                Ok(JobOutcome::Transferred) => {
+                    let _ = sender.send(QueueEvent::FileTransferred { id: job.id });
+                }
+                Ok(JobOutcome::Unchanged) => {
+                    let _ = sender.send(QueueEvent::FileSkipped { id: job.id, size });
+                }
+                Err(_) if progress.aborted() => return Err(String::from("aborted")),
+                Err(err) => {
+                    errors.push(format!("{}: {err}", file.source.path().display()));
+                    let _ = sender.send(QueueEvent::FileFailed {
+                        id: job.id,
+                        failure: TransferFailure::new(&file.source, file.dest.clone(), err),
+                    });
+                }
+            }
+        }
+        session.finish(job.direction);
+        match errors.len() {
+            0 => Ok(()),
+            1 => Err(errors.remove(0)),
+            n => Err(format!("{n} files failed; first error: {}", errors[0])),
+        }
+    }
+
+    fn push(&self, job: QueueJob) {
+        if self.jobs.send(job).is_err() {
+            error!("transfer queue worker has terminated");
+        }
+    }
+
+    fn poll(&self) -> Vec<QueueEvent> {
+        self.events.try_iter().collect()
+    }
+
+    /// Wait for the worker to terminate the active transfer and disconnect
+    fn stop(self) {
+        drop(self.jobs);
+        if self.handle.join().is_err() {
+            error!("transfer queue worker panicked");
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use std::time::{Duration, Instant};
+
+    use pretty_assertions::assert_eq;
+    use remotefs::fs::{FileType, Metadata};
+
+    use super::*;
+
+    /// Session which "transfers" files sleeping for a while; directories contain the files `a`, `fail` and `b`
+    struct SleepSession;
+
+    impl QueueSession for SleepSession {
+        fn plan(
+            &mut self,
+            _direction: TransferDirection,
+            source: &File,
+            dest: &Path,
+        ) -> Result<Vec<TransferJob>, String> {
+            match source.is_dir() {
+                true => Ok(["a", "fail", "b"]
+                    .into_iter()
+                    .map(|name| {
+                        TransferJob::new(
+                            make_file(&source.path().join(name), false),
+                            dest.join(name),
+                        )
+                    })
+                    .collect()),
+                false => Ok(vec![TransferJob::new(source.clone(), dest.to_path_buf())]),
+            }
+        }
+
+        fn transfer(
+            &mut self,
+            _direction: TransferDirection,
+            job: &TransferJob,
+            _progress: &TransferProgress,
+        ) -> Result<JobOutcome, String> {
+            thread::sleep(Duration::from_millis(20));
+            match job.source.name().as_str() {
+                "fail" => Err(String::from("permission denied")),
+                _ => Ok(JobOutcome::Transferred),
+            }
+        }
+
+        fn disconnect(&mut self) {}
+    }
+
+    fn make_file(path: &Path, is_dir: bool) -> File {
+        File {
+            path: path.to_path_buf(),
+            metadata: Metadata::default()
+                .file_type(match is_dir {
+                    true => FileType::Directory,
+                    false => FileType::File,
+                })
+                .size(1024),
+        }
+    }
+
+    /// Poll the queue, dispatching the pending items, until no item is unfinished
+    fn drain(queue: &mut TransferQueue) -> Vec<QueueEvent> {
+        let started = Instant::now();
+        let mut events = Vec::new();
+        while queue.unfinished() > 0 {
+            assert!(started.elapsed() < Duration::from_secs(5));
+            queue.dispatch();
+            events.extend(queue.poll());
+            thread::sleep(Duration::from_millis(5));
+        }
+        events
+    }
+
+    #[test]
+    fn should_transfer_queued_items_in_order() {
+        let mut queue = TransferQueue::default();
+        queue.push(
+            TransferDirection::Upload,
+            make_file(Path::new("/tmp/a.txt"), false),
+            PathBuf::from("/home/omar/a.txt"),
+        );
+        queue.push(
+            TransferDirection::Download,
+            make_file(Path::new("/home/omar/docs"), true),
+            PathBuf::from("/tmp/docs"),
+        );
+        assert_eq!(queue.unfinished(), 2);
+        // nothing is dispatched without a worker
+        assert!(queue.dispatch().is_none());
+        queue.set_worker(QueueWorker::spawn(queue.transfer.progress(), || {
+            Ok(SleepSession)
+        }));
+        assert_eq!(queue.dispatch().unwrap().id, 0);
+        // one item at a time
+        assert!(queue.dispatch().is_none());
+        assert_eq!(queue.items()[0].state, QueueItemState::Active);
+        let events = drain(&mut queue);
+        assert_eq!(queue.items()[0].state, QueueItemState::Done);
+        assert_eq!(
+            queue.items()[1].state,
+            QueueItemState::Failed(String::from("/home/omar/docs/fail: permission denied"))
+        );
+        assert!(events.contains(&QueueEvent::Planned { id: 1, size: 3072 }));
+        assert_eq!(
+            events
+                .iter()
+                .filter(|x| matches!(x, QueueEvent::FileStarted { id: 1, .. }))
+                .count(),
+            3
+        );
+        // the failed file is kept to be retried
+        let failure = TransferFailure::new(
+            &make_file(Path::new("/home/omar/docs/fail"), false),
+            PathBuf::from("/tmp/docs/fail"),
+            String::from("permission denied"),
+        );
+        assert!(events.contains(&QueueEvent::FileFailed {
+            id: 1,
+            failure: failure.clone()
+        }));
+        let failed = queue.take_failures().unwrap();
+        assert_eq!(failed.direction, TransferDirection::Download);
+        assert_eq!(failed.failures, vec![failure]);
+        assert!(queue.take_failures().is_none());
+        // the files of the last item are counted by its progress
+        assert_eq!(queue.transfer.summary().files, 2);
+        assert_eq!(
+            queue.items()[1].state.to_string().starts_with("failed"),
+            true
+        );
+    }
+
+    #[test]
+    fn should_remove_queued_items() {
+        let mut queue = TransferQueue::default();
+        for name in ["a", "b", "c"] {
+            queue.push(
+                TransferDirection::Upload,
+                make_file(&Path::new("/tmp").join(name), false),
+                PathBuf::from("/home/omar").join(name),
+            );
+        }
+        queue.set_worker(QueueWorker::spawn(queue.transfer.progress(), || {
+            Ok(SleepSession)
+        }));
+        queue.dispatch();
+        // the active item is aborted, not removed
+        assert!(queue.remove(0).is_none());
+        assert_eq!(queue.transfer.aborted(), true);
+        assert_eq!(queue.remove(2).unwrap().source.name(), "c");
+        assert!(queue.remove(5).is_none());
+        drain(&mut queue);
+        assert_eq!(queue.items().len(), 2);
+        assert_eq!(queue.unfinished(), 0);
+        queue.stop();
+    }
+
+    #[test]
+    fn should_start_scheduled_items_when_due() {
+        let mut queue = TransferQueue::default();
+        let now = Local::now();
+        queue.schedule(
+            TransferDirection::Upload,
+            make_file(Path::new("/tmp/a.txt"), false),
+            PathBuf::from("/home/omar/a.txt"),
+            now + chrono::Duration::hours(2),
+        );
+        queue.schedule(
+            TransferDirection::Upload,
+            make_file(Path::new("/tmp/b.txt"), false),
+            PathBuf::from("/home/omar/b.txt"),
+            now + chrono::Duration::hours(1),
+        );
+        assert_eq!(
+            queue.scheduled(),
+            Some((2, now + chrono::Duration::hours(1)))
+        );
+        assert_eq!(queue.unfinished(), 2);
+        queue.set_worker(QueueWorker::spawn(queue.transfer.progress(), || {
+            Ok(SleepSession)
+        }));
+        // scheduled items are not dispatched
+        assert!(queue.dispatch().is_none());
+        assert_eq!(queue.has_due(now), false);
+        assert!(queue.start_due(now).is_empty());
+        // the idle worker is released while waiting
+        assert_eq!(queue.release_idle_worker(), true);
+        assert_eq!(queue.has_worker(), false);
+        let later = now + chrono::Duration::minutes(90);
+        assert_eq!(queue.has_due(later), true);
+        assert_eq!(
+            queue.start_due(later),
+            vec![(1, now + chrono::Duration::hours(1))]
+        );
+        assert_eq!(queue.items()[1].state, QueueItemState::Pending);
+        assert_eq!(queue.release_idle_worker(), false);
+        // scheduled items can be cancelled
+        assert_eq!(queue.remove(0).unwrap().source.name(), "a.txt");
+        assert!(queue.scheduled().is_none());
+    }
+
+    #[test]
+    fn should_fail_items_if_worker_cannot_connect() {
+        let mut queue = TransferQueue::default();
+        queue.push(
+            TransferDirection::Upload,
+            make_file(Path::new("/tmp/a.txt"), false),
+            PathBuf::from("/home/omar/a.txt"),
+        );
+        queue.set_worker(QueueWorker::spawn(queue.transfer.progress(), || {
+            Err::<SleepSession, String>(String::from("connection refused"))
+        }));
+        let events = drain(&mut queue);
+        assert_eq!(
+            events,
+            vec![QueueEvent::ConnectionFailed(String::from(
+                "connection refused"
+            ))]
+        );
+        assert_eq!(
+            queue.items()[0].state,
+            QueueItemState::Failed(String::from("connection refused"))
+        );
+        assert_eq!(queue.has_worker(), false);
+    }
+}
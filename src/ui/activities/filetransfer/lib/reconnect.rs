@@ -0,0 +1,140 @@
+//! ## FileTransferActivity
+//!
+//! `filetransfer_activiy` is the module which implements the Filetransfer activity, which is the main activity afterall
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use remotefs::{RemoteError, RemoteErrorType};
+
+/// Delay before the second attempt; it's doubled after each failed attempt
+const BACKOFF_BASE: Duration = Duration::from_secs(1);
+/// Maximum delay between two attempts
+const BACKOFF_MAX: Duration = Duration::from_secs(30);
+
+/// Returns whether `err` means that the connection with the remote has been lost,
+/// rather than the operation has failed
+pub fn is_connection_lost(err: &RemoteError) -> bool {
+    matches!(
+        err.kind,
+        RemoteErrorType::ConnectionError
+            | RemoteErrorType::NotConnected
+            | RemoteErrorType::ProtocolError
+    )
+}
+
+/// State of the reconnection to the remote, after the connection has been lost
+#[derive(Debug)]
+pub struct Reconnect {
+    /// Working directory when the connection has been lost, restored once reconnected
+    wrkdir: PathBuf,
+    /// Attempts made so far
+    attempts: u32,
+    /// Maximum amount of attempts
+    retries: u32,
+    next_attempt: Instant,
+}
+
+impl Reconnect {
+    /// Start reconnecting, up to `retries` attempts; the first attempt is due immediately
+    pub fn new(wrkdir: PathBuf, retries: u32) -> Self {
+        Self {
+            wrkdir,
+            attempts: 0,
+            retries,
+            next_attempt: Instant::now(),
+        }
+    }
+
+    /// Get the working directory to restore
+    pub fn wrkdir(&self) -> &Path {
+        self.wrkdir.as_path()
+    }
+
+    /// Returns whether the next attempt is due
+    pub fn is_due(&self) -> bool {
+        Instant::now() >= self.next_attempt
+    }
+
+    /// Number of the next attempt, starting from 1
+    pub fn attempt(&self) -> u32 {
+        self.attempts + 1
+    }
+
+    /// Maximum amount of attempts
+    pub fn retries(&self) -> u32 {
+        self.retries
+    }
+
+    /// Register a failed attempt, scheduling the next one after the backoff.
+    /// Returns the delay before the next attempt, or `None` if there are no attempts left
+    pub fn failed(&mut self) -> Option<Duration> {
+        self.attempts += 1;
+        if self.attempts >= self.retries {
+            return None;
+        }
+        let delay = Self::backoff(self.attempts);
+        self.next_attempt = Instant::now() + delay;
+        Some(delay)
+    }
+
+    /// Delay after the failed attempt `attempt` (starting from 1)
+    fn backoff(attempt: u32) -> Duration {
+        BACKOFF_BASE
+            .saturating_mul(2u32.saturating_pow(attempt.saturating_sub(1)))
+            .min(BACKOFF_MAX)
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn should_tell_lost_connection() {
+        assert!(is_connection_lost(&RemoteError::new(
+            RemoteErrorType::ProtocolError
+        )));
+        assert!(is_connection_lost(&RemoteError::new(
+            RemoteErrorType::NotConnected
+        )));
+        assert!(is_connection_lost(&RemoteError::new(
+            RemoteErrorType::ConnectionError
+        )));
+        assert!(!is_connection_lost(&RemoteError::new(
+            RemoteErrorType::NoSuchFileOrDirectory
+        )));
+        assert!(!is_connection_lost(&RemoteError::new(
+            RemoteErrorType::PexError
+        )));
+    }
+
+    #[test]
+    fn should_back_off_between_attempts() {
+        assert_eq!(Reconnect::backoff(1), Duration::from_secs(1));
+        assert_eq!(Reconnect::backoff(2), Duration::from_secs(2));
+        assert_eq!(Reconnect::backoff(4), Duration::from_secs(8));
+        assert_eq!(Reconnect::backoff(10), BACKOFF_MAX);
+        assert_eq!(Reconnect::backoff(u32::MAX), BACKOFF_MAX);
+    }
+
+    #[test]
+    fn should_give_up_after_retries() {
+        let mut reconnect = Reconnect::new(PathBuf::from("/home/omar/docs"), 3);
+        assert_eq!(reconnect.wrkdir(), Path::new("/home/omar/docs"));
+        assert_eq!(reconnect.is_due(), true);
+        assert_eq!(reconnect.attempt(), 1);
+        assert_eq!(reconnect.failed(), Some(Duration::from_secs(1)));
+        assert_eq!(reconnect.is_due(), false);
+        assert_eq!(reconnect.attempt(), 2);
+        assert_eq!(reconnect.failed(), Some(Duration::from_secs(2)));
+        assert_eq!(reconnect.attempt(), 3);
+        assert_eq!(reconnect.failed(), None);
+        // a single attempt
+        let mut reconnect = Reconnect::new(PathBuf::from("/"), 1);
+        assert_eq!(reconnect.failed(), None);
+    }
+}
@@ -0,0 +1,85 @@
+//! ## FileTransferActivity
+//!
+//! `filetransfer_activiy` is the module which implements the Filetransfer activity, which is the main activity afterall
+
+use std::time::UNIX_EPOCH;
+
+use remotefs::fs::Metadata;
+
+/// Version of a remote file, captured when it's downloaded to be edited,
+/// to tell whether someone else changed it before the edited file is uploaded
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RemoteVersion {
+    /// Modification time, in seconds since the epoch
+    modified: Option<u64>,
+    size: u64,
+}
+
+impl RemoteVersion {
+    /// Whether the file has changed since this version.
+    ///
+    /// Modification times are compared in whole seconds, and since many protocols report them with a coarser
+    /// resolution (e.g. FTP listings in minutes), the sizes are compared too
+    pub fn has_changed(&self, current: &RemoteVersion) -> bool {
+        self.modified != current.modified || self.size != current.size
+    }
+}
+
+impl From<&Metadata> for RemoteVersion {
+    fn from(metadata: &Metadata) -> Self {
+        Self {
+            modified: metadata
+                .modified
+                .and_then(|x| x.duration_since(UNIX_EPOCH).ok())
+                .map(|x| x.as_secs()),
+            size: metadata.size,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use std::time::Duration;
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn should_tell_whether_remote_file_has_changed() {
+        let version = RemoteVersion::from(&metadata(1000, 64));
+        assert_eq!(
+            version.has_changed(&RemoteVersion::from(&metadata(1000, 64))),
+            false
+        );
+        assert_eq!(
+            version.has_changed(&RemoteVersion::from(&metadata(1060, 64))),
+            true
+        );
+        // same modification time, but different size
+        assert_eq!(
+            version.has_changed(&RemoteVersion::from(&metadata(1000, 70))),
+            true
+        );
+        // sub-second differences are ignored
+        let mut precise = metadata(1000, 64);
+        precise.modified = Some(UNIX_EPOCH + Duration::from_millis(1_000_500));
+        assert_eq!(version.has_changed(&RemoteVersion::from(&precise)), false);
+        // no modification time
+        let unknown = RemoteVersion::from(&Metadata {
+            size: 64,
+            ..Default::default()
+        });
+        assert_eq!(unknown.has_changed(&unknown), false);
+        assert_eq!(unknown.has_changed(&version), true);
+    }
+
+    fn metadata(modified: u64, size: u64) -> Metadata {
+        Metadata {
+            modified: Some(UNIX_EPOCH + Duration::from_secs(modified)),
+            size,
+            ..Default::default()
+        }
+    }
+}
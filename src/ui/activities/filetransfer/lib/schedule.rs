@@ -0,0 +1,127 @@
+//! ## FileTransferActivity
+//!
+//! `filetransfer_activiy` is the module which implements the Filetransfer activity, which is the main activity afterall
+
+use chrono::{DateTime, Duration, Local, NaiveTime, TimeZone};
+
+/// A scheduled transfer which starts later than this after its time is reported as late,
+/// e.g. because the system was asleep when it was due
+pub const LATE_START_THRESHOLD: Duration = Duration::seconds(60);
+
+/// Parse the start time of a scheduled transfer, which is either a time of the day (`HH:MM`), meaning its next
+/// occurrence after `now`, or a delay from `now` (`+duration`, e.g. `+45m` or `+1h30m`).
+/// The units of a delay are `d`, `h`, `m` and `s`
+pub fn parse_start_time(input: &str, now: DateTime<Local>) -> Result<DateTime<Local>, String> {
+    let input = input.trim();
+    match input.strip_prefix('+') {
+        Some(delay) => parse_delay(delay).map(|delay| now + delay),
+        None => parse_time_of_day(input, now),
+    }
+}
+
+/// Returns whether a transfer scheduled at `at`, which is started at `now`, is late
+pub fn is_late(at: DateTime<Local>, now: DateTime<Local>) -> bool {
+    now - at > LATE_START_THRESHOLD
+}
+
+/// Format the start time `at`; the date is omitted if it's the same of `now`
+pub fn fmt_start_time(at: DateTime<Local>, now: DateTime<Local>) -> String {
+    match at.date_naive() == now.date_naive() {
+        true => at.format("%H:%M").to_string(),
+        false => at.format("%Y-%m-%d %H:%M").to_string(),
+    }
+}
+
+fn parse_delay(delay: &str) -> Result<Duration, String> {
+    if delay.is_empty() {
+        return Err(String::from("missing duration after '+'"));
+    }
+    let mut total = Duration::zero();
+    let mut digits = String::new();
+    for c in delay.chars() {
+        if c.is_ascii_digit() {
+            digits.push(c);
+            continue;
+        }
+        let amount: i64 = digits
+            .parse()
+            .map_err(|_| format!("missing amount before '{c}'"))?;
+        digits.clear();
+        total += match c {
+            'd' => Duration::days(amount),
+            'h' => Duration::hours(amount),
+            'm' => Duration::minutes(amount),
+            's' => Duration::seconds(amount),
+            _ => return Err(format!("unknown unit '{c}' (use d, h, m or s)")),
+        };
+    }
+    match digits.is_empty() {
+        true => Ok(total),
+        false => Err(format!("missing unit after '{digits}' (use d, h, m or s)")),
+    }
+}
+
+fn parse_time_of_day(input: &str, now: DateTime<Local>) -> Result<DateTime<Local>, String> {
+    let time = NaiveTime::parse_from_str(input, "%H:%M")
+        .map_err(|_| format!("\"{input}\" is neither a time (HH:MM) nor a delay (+duration)"))?;
+    let mut day = now.date_naive();
+    // the next occurrence of the time
+    if time <= now.time() {
+        day = day.succ_opt().ok_or("date out of range")?;
+    }
+    Local
+        .from_local_datetime(&day.and_time(time))
+        .earliest()
+        .ok_or_else(|| format!("{input} doesn't exist in the local time zone on {day}"))
+}
+
+#[cfg(test)]
+mod test {
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn at(day: u32, hour: u32, minute: u32) -> DateTime<Local> {
+        Local
+            .with_ymd_and_hms(2024, 3, day, hour, minute, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn should_parse_time_of_day() {
+        let now = at(12, 22, 30);
+        assert_eq!(parse_start_time("23:15", now).unwrap(), at(12, 23, 15));
+        // already passed today
+        assert_eq!(parse_start_time(" 02:00 ", now).unwrap(), at(13, 2, 0));
+        assert_eq!(parse_start_time("22:30", now).unwrap(), at(13, 22, 30));
+        assert!(parse_start_time("25:00", now).is_err());
+        assert!(parse_start_time("tonight", now).is_err());
+    }
+
+    #[test]
+    fn should_parse_delay() {
+        let now = at(12, 22, 30);
+        assert_eq!(parse_start_time("+45m", now).unwrap(), at(12, 23, 15));
+        assert_eq!(parse_start_time("+1h30m", now).unwrap(), at(13, 0, 0));
+        assert_eq!(parse_start_time("+1d", now).unwrap(), at(13, 22, 30));
+        assert_eq!(
+            parse_start_time("+90s", now).unwrap(),
+            now + Duration::seconds(90)
+        );
+        assert!(parse_start_time("+", now).is_err());
+        assert!(parse_start_time("+90", now).is_err());
+        assert!(parse_start_time("+2w", now).is_err());
+        assert!(parse_start_time("+h", now).is_err());
+    }
+
+    #[test]
+    fn should_tell_late_start_and_fmt_start_time() {
+        let now = at(12, 22, 30);
+        assert_eq!(is_late(at(12, 22, 30), now), false);
+        assert_eq!(is_late(at(12, 22, 29) + Duration::seconds(30), now), false);
+        assert_eq!(is_late(at(12, 2, 0), now), true);
+        assert_eq!(fmt_start_time(at(12, 23, 15), now), "23:15");
+        assert_eq!(fmt_start_time(at(13, 2, 0), now), "2024-03-13 02:00");
+    }
+}
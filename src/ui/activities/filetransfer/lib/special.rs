@@ -0,0 +1,215 @@
+//! ## FileTransferActivity
+//!
+//! `filetransfer_activiy` is the module which implements the Filetransfer activity, which is the main activity afterall
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use remotefs::fs::File;
+
+use crate::explorer::special::SpecialFileType;
+use crate::utils::shell;
+
+/// Files among `files` which may be special files, to be classified
+pub fn candidates(files: &[File]) -> Vec<&File> {
+    files
+        .iter()
+        .filter(|x| SpecialFileType::is_candidate(x))
+        .collect()
+}
+
+/// Classify `candidates` on the local host
+pub fn classify_local(candidates: &[&File]) -> HashMap<PathBuf, SpecialFileType> {
+    candidates
+        .iter()
+        .filter_map(|file| {
+            SpecialFileType::classify_local(file.path())
+                .map(|kind| (file.path().to_path_buf(), kind))
+        })
+        .collect()
+}
+
+/// Command classifying `candidates` on the remote host.
+/// For each special file, the command prints its type character and its index among the candidates
+pub fn classify_command(candidates: &[&File]) -> String {
+    let paths: Vec<String> = candidates
+        .iter()
+        .map(|x| shell::single_quote(&x.path().to_string_lossy()))
+        .collect();
+    format!(
+        r#"i=0; for f in {}; do if [ -b "$f" ]; then echo "b $i"; elif [ -c "$f" ]; then echo "c $i"; elif [ -p "$f" ]; then echo "p $i"; elif [ -S "$f" ]; then echo "s $i"; fi; i=$((i+1)); done"#,
+        paths.join(" ")
+    )
+}
+
+/// Get the special files among `candidates` from the output of the command made by [`classify_command`]
+pub fn parse_classify_output(
+    output: &str,
+    candidates: &[&File],
+) -> HashMap<PathBuf, SpecialFileType> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let (kind, index) = line.trim_end_matches('\r').split_once(' ')?;
+            let mut kind = kind.chars();
+            let kind = match (kind.next(), kind.next()) {
+                (Some(c), None) => SpecialFileType::from_char(c)?,
+                _ => return None,
+            };
+            let file = candidates.get(index.parse::<usize>().ok()?)?;
+            Some((file.path().to_path_buf(), kind))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+
+    use std::path::Path;
+    use std::time::SystemTime;
+
+    use pretty_assertions::assert_eq;
+    use remotefs::fs::{FileType, Metadata};
+
+    use super::*;
+
+    #[test]
+    fn should_get_candidates() {
+        let files = vec![
+            make_file("/dev/null", FileType::File, 0),
+            make_file("/dev/shm", FileType::Directory, 0),
+            make_file("/dev/core", FileType::File, 4096),
+            make_file("/dev/initctl", FileType::File, 0),
+        ];
+        let candidates = candidates(&files);
+        assert_eq!(candidates.len(), 2);
+        assert_eq!(candidates[0].path(), Path::new("/dev/null"));
+        assert_eq!(candidates[1].path(), Path::new("/dev/initctl"));
+    }
+
+    #[test]
+    fn should_make_classify_command() {
+        let files = [
+            make_file("/dev/null", FileType::File, 0),
+            make_file("/tmp/it's", FileType::File, 0),
+        ];
+        let candidates: Vec<&File> = files.iter().collect();
+        let cmd = classify_command(&candidates);
+        assert!(cmd.starts_with(r"i=0; for f in '/dev/null' '/tmp/it'\''s'; do "));
+        assert!(cmd.contains(r#"if [ -b "$f" ]; then echo "b $i";"#));
+        assert!(cmd.ends_with("i=$((i+1)); done"));
+    }
+
+    #[test]
+    fn should_parse_classify_output() {
+        let files = [
+            make_file("/dev/sda", FileType::File, 0),
+            make_file("/dev/empty", FileType::File, 0),
+            make_file("/dev/tty", FileType::File, 0),
+            make_file("/run/initctl", FileType::File, 0),
+            make_file("/run/socket", FileType::File, 0),
+        ];
+        let candidates: Vec<&File> = files.iter().collect();
+        let special =
+            parse_classify_output("b 0\nc 2\r\np 3\ns 4\nx 1\nc 99\nc\ncc 1\n\n", &candidates);
+        assert_eq!(special.len(), 4);
+        assert_eq!(
+            special.get(Path::new("/dev/sda")),
+            Some(&SpecialFileType::BlockDevice)
+        );
+        assert_eq!(
+            special.get(Path::new("/dev/tty")),
+            Some(&SpecialFileType::CharDevice)
+        );
+        assert_eq!(
+            special.get(Path::new("/run/initctl")),
+            Some(&SpecialFileType::Fifo)
+        );
+        assert_eq!(
+            special.get(Path::new("/run/socket")),
+            Some(&SpecialFileType::Socket)
+        );
+        assert!(!special.contains_key(Path::new("/dev/empty")));
+    }
+
+    #[test]
+    #[cfg(posix)]
+    fn should_classify_command_output_through_shell() {
+        let tmpdir = tempfile::TempDir::new().unwrap();
+        let fifo = tmpdir.path().join("it's a fifo");
+        assert!(std::process::Command::new("mkfifo")
+            .arg(fifo.as_path())
+            .status()
+            .unwrap()
+            .success());
+        std::fs::write(tmpdir.path().join("empty"), b"").unwrap();
+        let files = [
+            make_file(
+                tmpdir.path().join("empty").to_str().unwrap(),
+                FileType::File,
+                0,
+            ),
+            make_file(fifo.to_str().unwrap(), FileType::File, 0),
+            make_file("/dev/null", FileType::File, 0),
+        ];
+        let candidates: Vec<&File> = files.iter().collect();
+        let output = std::process::Command::new("/bin/sh")
+            .arg("-c")
+            .arg(classify_command(&candidates))
+            .output()
+            .unwrap();
+        let special =
+            parse_classify_output(std::str::from_utf8(&output.stdout).unwrap(), &candidates);
+        assert_eq!(special, classify_local(&candidates));
+        assert_eq!(special.len(), 2);
+        assert_eq!(special.get(fifo.as_path()), Some(&SpecialFileType::Fifo));
+        assert_eq!(
+            special.get(Path::new("/dev/null")),
+            Some(&SpecialFileType::CharDevice)
+        );
+    }
+
+    #[test]
+    #[cfg(posix)]
+    fn should_classify_special_files_listed_by_localhost_bridge() {
+        use crate::host::{HostBridge, Localhost};
+
+        let tmpdir = tempfile::TempDir::new().unwrap();
+        assert!(std::process::Command::new("mkfifo")
+            .arg(tmpdir.path().join("fifo"))
+            .status()
+            .unwrap()
+            .success());
+        std::fs::write(tmpdir.path().join("empty"), b"").unwrap();
+        std::fs::write(tmpdir.path().join("regular"), b"hello").unwrap();
+        let mut host = Localhost::new(tmpdir.path().to_path_buf()).unwrap();
+        let files = host.list_dir(tmpdir.path()).unwrap();
+        assert_eq!(files.len(), 3);
+        // fifos are listed as regular files
+        let candidates = candidates(&files);
+        assert_eq!(candidates.len(), 2);
+        let special = classify_local(&candidates);
+        assert_eq!(special.len(), 1);
+        assert_eq!(
+            special.get(tmpdir.path().join("fifo").as_path()),
+            Some(&SpecialFileType::Fifo)
+        );
+    }
+
+    fn make_file(path: &str, file_type: FileType, size: u64) -> File {
+        File {
+            path: PathBuf::from(path),
+            metadata: Metadata {
+                accessed: None,
+                created: None,
+                file_type,
+                gid: None,
+                mode: None,
+                modified: Some(SystemTime::UNIX_EPOCH),
+                size,
+                symlink: None,
+                uid: None,
+            },
+        }
+    }
+}
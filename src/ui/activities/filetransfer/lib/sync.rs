@@ -0,0 +1,158 @@
+//! ## FileTransferActivity
+//!
+//! `filetransfer_activiy` is the module which implements the Filetransfer activity, which is the main activity afterall
+
+use std::fmt;
+use std::time::Duration;
+
+use remotefs::fs::Metadata;
+use remotefs::File;
+
+use super::clock_skew;
+
+/// Sync mode of a transfer, where only the new and the changed files are copied to the destination
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyncMode {
+    /// Files with the same size and modification time at the destination are skipped
+    Incremental,
+    /// Like [`SyncMode::Incremental`], but the entries of the destination directories which don't exist in the source are deleted
+    Mirror,
+}
+
+/// State of a transfer in sync mode
+#[derive(Debug)]
+pub struct SyncState {
+    mode: SyncMode,
+    /// Whether modification times are compared; they're not if the destination doesn't preserve them
+    compare_mtime: bool,
+    pub copied: usize,
+    pub skipped: usize,
+    pub deleted: usize,
+}
+
+impl SyncState {
+    pub fn new(mode: SyncMode, compare_mtime: bool) -> Self {
+        Self {
+            mode,
+            compare_mtime,
+            copied: 0,
+            skipped: 0,
+            deleted: 0,
+        }
+    }
+
+    /// Whether the entries of the destination directories which don't exist in the source must be deleted
+    pub fn is_mirror(&self) -> bool {
+        self.mode == SyncMode::Mirror
+    }
+
+    /// Whether modification times are compared, or files are compared by size only
+    pub fn compare_mtime(&self) -> bool {
+        self.compare_mtime
+    }
+
+    /// Whether the file at the destination with `dest` metadata is up to date with the `source` file.
+    /// Modification times are compared within `tolerance`
+    pub fn is_unchanged(&self, source: &Metadata, dest: &Metadata, tolerance: Duration) -> bool {
+        source.size == dest.size
+            && (!self.compare_mtime
+                || clock_skew::mtime_equal(source.modified, dest.modified, tolerance))
+    }
+
+    /// Get the entries of the destination directory `dest` which don't exist in the source directory, `source`
+    pub fn extraneous<'a>(source: &[File], dest: &'a [File]) -> Vec<&'a File> {
+        dest.iter()
+            .filter(|x| !source.iter().any(|entry| entry.name() == x.name()))
+            .collect()
+    }
+}
+
+impl fmt::Display for SyncState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} copied, {} skipped, {} deleted",
+            self.copied, self.skipped, self.deleted
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use std::path::PathBuf;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn metadata(size: u64, modified: SystemTime) -> Metadata {
+        Metadata::default().size(size).modified(modified)
+    }
+
+    fn file(name: &str) -> File {
+        File {
+            path: PathBuf::from("/srv/www").join(name),
+            metadata: Metadata::default(),
+        }
+    }
+
+    #[test]
+    fn should_tell_whether_file_is_unchanged() {
+        let source = metadata(1024, UNIX_EPOCH + Duration::from_secs(1_000));
+        let touched = metadata(1024, UNIX_EPOCH + Duration::from_secs(1_060));
+        let sync = SyncState::new(SyncMode::Incremental, true);
+        assert_eq!(sync.is_unchanged(&source, &source, Duration::ZERO), true);
+        assert_eq!(sync.is_unchanged(&source, &touched, Duration::ZERO), false);
+        assert_eq!(
+            sync.is_unchanged(&source, &touched, Duration::from_secs(60)),
+            true
+        );
+        assert_eq!(
+            sync.is_unchanged(
+                &source,
+                &metadata(512, source.modified.unwrap()),
+                Duration::ZERO
+            ),
+            false
+        );
+        // size only
+        let sync = SyncState::new(SyncMode::Incremental, false);
+        assert_eq!(sync.compare_mtime(), false);
+        assert_eq!(sync.is_unchanged(&source, &touched, Duration::ZERO), true);
+        assert_eq!(
+            sync.is_unchanged(
+                &source,
+                &metadata(512, source.modified.unwrap()),
+                Duration::ZERO
+            ),
+            false
+        );
+    }
+
+    #[test]
+    fn should_get_extraneous_entries_in_mirror_mode() {
+        let source = vec![file("index.html"), file("assets")];
+        let dest = vec![file("assets"), file("old.html"), file("index.html")];
+        assert_eq!(
+            SyncState::new(SyncMode::Incremental, true).is_mirror(),
+            false
+        );
+        assert_eq!(SyncState::new(SyncMode::Mirror, true).is_mirror(), true);
+        let extraneous: Vec<String> = SyncState::extraneous(&source, &dest)
+            .into_iter()
+            .map(|x| x.name())
+            .collect();
+        assert_eq!(extraneous, vec![String::from("old.html")]);
+    }
+
+    #[test]
+    fn should_format_sync_summary() {
+        let mut sync = SyncState::new(SyncMode::Mirror, true);
+        sync.copied = 3;
+        sync.skipped = 12;
+        sync.deleted = 1;
+        assert_eq!(sync.to_string().as_str(), "3 copied, 12 skipped, 1 deleted");
+    }
+}
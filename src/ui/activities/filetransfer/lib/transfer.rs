@@ -2,18 +2,43 @@
 //!
 //! `filetransfer_activiy` is the module which implements the Filetransfer activity, which is the main activity afterall
 
+use std::collections::{HashMap, VecDeque};
 use std::fmt;
-use std::time::Instant;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use bytesize::ByteSize;
+use remotefs::File;
+
+use super::parallel::TransferDirection;
+use super::sync::SyncMode;
+use super::visited::VisitedDirs;
+use crate::utils::fmt::fmt_millis;
+use crate::utils::path;
 
 // -- States and progress
 
+/// Minimum interval between two samples of the transfer rate window
+const RATE_SAMPLE_INTERVAL: Duration = Duration::from_millis(250);
+/// Amount of samples kept to calculate the transfer rate (about the last 3 seconds)
+const RATE_WINDOW_SAMPLES: usize = 12;
+
 /// TransferStates contains the states related to the transfer process
 pub struct TransferStates {
-    aborted: bool,               // Describes whether the transfer process has been aborted
+    progress: TransferProgress,  // Progress shared with the progress adapters
     pub full: ProgressStates,    // full transfer states
     pub partial: ProgressStates, // Partial transfer states
+    /// Files transferred, or skipped since unchanged
+    files: usize,
+    /// Files which couldn't be transferred
+    failed: usize,
+    /// First error occurred during the transfer
+    first_error: Option<String>,
+    /// Entries which couldn't be transferred, which can be retried once the transfer has terminated
+    failures: Vec<TransferFailure>,
 }
 
 /// Progress states describes the states for the progress of a single transfer part
@@ -21,6 +46,10 @@ pub struct ProgressStates {
     started: Instant,
     total: usize,
     written: usize,
+    /// Bytes already written when the transfer has been initialized (i.e. resumed transfers)
+    resumed_from: usize,
+    /// Last samples of the bytes written, used to calculate the transfer rate
+    samples: VecDeque<(Instant, usize)>,
 }
 
 impl Default for TransferStates {
@@ -33,39 +62,136 @@ impl TransferStates {
     /// Instantiates a new transfer states
     pub fn new() -> TransferStates {
         TransferStates {
-            aborted: false,
+            progress: TransferProgress::default(),
             full: ProgressStates::default(),
             partial: ProgressStates::default(),
+            files: 0,
+            failed: 0,
+            first_error: None,
+            failures: Vec::new(),
         }
     }
 
     /// Re-intiialize transfer states
     pub fn reset(&mut self) {
-        self.aborted = false;
+        self.progress.aborted.store(false, Ordering::Relaxed);
+        self.progress.take();
+        self.files = 0;
+        self.failed = 0;
+        self.first_error = None;
+        self.failures.clear();
     }
 
     /// Set aborted to true
     pub fn abort(&mut self) {
-        self.aborted = true;
+        self.progress.aborted.store(true, Ordering::Relaxed);
     }
 
     /// Returns whether transfer has been aborted
     pub fn aborted(&self) -> bool {
-        self.aborted
+        self.progress.aborted()
+    }
+
+    /// Get a handle to the transfer progress, to be given to the progress adapters
+    pub fn progress(&self) -> TransferProgress {
+        self.progress.clone()
+    }
+
+    /// Apply the bytes reported by the progress adapters since the last call to the partial and full progress.
+    /// Returns the amount of bytes applied
+    pub fn sync_progress(&mut self) -> usize {
+        let delta = self.progress.take();
+        self.partial.update_progress(delta);
+        self.full.update_progress(delta);
+        delta
+    }
+
+    /// Apply the bytes reported by the progress adapters since the last call to the full progress only.
+    /// Used by parallel transfers, whose partial progress counts the files transferred
+    pub fn sync_full_progress(&mut self) -> usize {
+        let delta = self.progress.take();
+        self.full.update_progress(delta);
+        delta
     }
 
     /// Returns the size of the entire transfer
     pub fn full_size(&self) -> usize {
         self.full.total
     }
+
+    /// Count a file which has been transferred, or skipped since unchanged
+    pub fn count_file(&mut self) {
+        self.files += 1;
+    }
+
+    /// Count `n` files which couldn't be transferred, the first of them because of `first_error`
+    pub fn count_failures(&mut self, n: usize, first_error: &str) {
+        if n == 0 {
+            return;
+        }
+        self.failed += n;
+        if self.first_error.is_none() {
+            self.first_error = Some(first_error.to_string());
+        }
+    }
+
+    /// Keep the entry which couldn't be transferred, so that it can be retried.
+    /// The failure must be counted on its own with `count_failures`
+    pub fn push_failure(&mut self, failure: TransferFailure) {
+        self.failures.push(failure);
+    }
+
+    /// Take the entries which couldn't be transferred
+    pub fn take_failures(&mut self) -> Vec<TransferFailure> {
+        std::mem::take(&mut self.failures)
+    }
+
+    /// Get the first error occurred during the transfer, if any has been counted
+    pub fn first_error(&self) -> Option<&str> {
+        self.first_error.as_deref()
+    }
+
+    /// Get the summary of the terminated transfer
+    pub fn summary(&self) -> TransferSummary {
+        TransferSummary {
+            files: self.files,
+            failed: self.failed,
+            bytes: (self.full.written - self.full.resumed_from) as u64,
+            elapsed: self.full.started.elapsed(),
+        }
+    }
+
+    /// Returns whether the notification for the terminated transfer should be sent.
+    /// The size of the entire payload (a single file or all the files of a recursive transfer) is compared with
+    /// `threshold`; if `min_duration` is set, the notification is sent also if the transfer lasted at least as long
+    pub fn should_notify(&self, threshold: u64, min_duration: Option<Duration>) -> bool {
+        Self::notification_due(
+            self.full_size(),
+            self.full.started.elapsed(),
+            threshold,
+            min_duration,
+        )
+    }
+
+    fn notification_due(
+        size: usize,
+        elapsed: Duration,
+        threshold: u64,
+        min_duration: Option<Duration>,
+    ) -> bool {
+        size as u64 >= threshold || min_duration.is_some_and(|min| elapsed >= min)
+    }
 }
 
 impl Default for ProgressStates {
     fn default() -> Self {
+        let started = Instant::now();
         ProgressStates {
-            started: Instant::now(),
+            started,
             written: 0,
             total: 0,
+            resumed_from: 0,
+            samples: VecDeque::from([(started, 0)]),
         }
     }
 }
@@ -73,20 +199,20 @@ impl Default for ProgressStates {
 impl fmt::Display for ProgressStates {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let eta: String = match self.calc_eta() {
-            0 => String::from("--:--"),
-            seconds => format!(
-                "{:0width$}:{:0width$}",
-                (seconds / 60),
-                (seconds % 60),
-                width = 2
+            None => String::from("--:--"),
+            Some(seconds) => format!(
+                "{:02}:{:02}:{:02}",
+                seconds / 3600,
+                (seconds % 3600) / 60,
+                seconds % 60
             ),
         };
         write!(
             f,
-            "{:.2}% - ETA {} ({}/s)",
+            "{:.2}% — {}/s — ETA {}",
             self.calc_progress_percentage(),
-            eta,
-            ByteSize(self.calc_bytes_per_second())
+            ByteSize(self.calc_rate()),
+            eta
         )
     }
 }
@@ -94,14 +220,37 @@ impl fmt::Display for ProgressStates {
 impl ProgressStates {
     /// Initialize a new Progress State
     pub fn init(&mut self, sz: usize) {
+        self.init_resumed(sz, 0);
+    }
+
+    /// Initialize a new Progress State for a transfer resumed after `written` bytes
+    pub fn init_resumed(&mut self, sz: usize, written: usize) {
         self.started = Instant::now();
         self.total = sz;
-        self.written = 0;
+        self.written = written;
+        self.resumed_from = written;
+        // Reset the rate window, so that the rate of the previous transfer is not reported
+        self.samples.clear();
+        self.samples.push_back((self.started, written));
     }
 
     /// Update progress state
     pub fn update_progress(&mut self, delta: usize) -> f64 {
+        self.update_progress_at(delta, Instant::now())
+    }
+
+    fn update_progress_at(&mut self, delta: usize, now: Instant) -> f64 {
         self.written += delta;
+        let sample_due = self
+            .samples
+            .back()
+            .is_none_or(|(sampled, _)| now.duration_since(*sampled) >= RATE_SAMPLE_INTERVAL);
+        if sample_due {
+            self.samples.push_back((now, self.written));
+            if self.samples.len() > RATE_WINDOW_SAMPLES {
+                self.samples.pop_front();
+            }
+        }
         self.calc_progress_percentage()
     }
 
@@ -133,25 +282,292 @@ impl ProgressStates {
     pub fn calc_bytes_per_second(&self) -> u64 {
         // bytes_written : elapsed_secs = x : 1
         let elapsed_secs: u64 = self.started.elapsed().as_secs();
+        let written = (self.written - self.resumed_from) as u64;
         match elapsed_secs {
             0 => match self.written == self.total {
                 // NOTE: would divide by 0 :D
-                true => self.total as u64, // Download completed in less than 1 second
-                false => 0,                // 0 B/S
+                true => written, // Download completed in less than 1 second
+                false => 0,      // 0 B/S
             },
-            _ => self.written as u64 / elapsed_secs,
+            _ => written / elapsed_secs,
         }
     }
 
-    /// Calculate ETA for current transfer as seconds
-    fn calc_eta(&self) -> u64 {
-        let elapsed_secs: u64 = self.started.elapsed().as_secs();
-        let prog: f64 = self.calc_progress_percentage();
-        match prog as u64 {
-            0 => 0,
-            _ => ((elapsed_secs * 100) / (prog as u64)) - elapsed_secs,
+    /// Calculate the instantaneous transfer rate in bytes per second, over the last samples of the window.
+    /// Returns `0` until at least two samples have been collected
+    pub fn calc_rate(&self) -> u64 {
+        let (Some((oldest_at, oldest)), Some((newest_at, newest))) =
+            (self.samples.front(), self.samples.back())
+        else {
+            return 0;
+        };
+        let elapsed = newest_at.duration_since(*oldest_at).as_secs_f64();
+        match elapsed > 0.0 {
+            true => ((newest - oldest) as f64 / elapsed) as u64,
+            false => 0,
         }
     }
+
+    /// Calculate ETA for current transfer as seconds, based on the transfer rate.
+    /// Returns `None` if the rate is `0`
+    fn calc_eta(&self) -> Option<u64> {
+        match self.calc_rate() {
+            0 => None,
+            rate => Some((self.total.saturating_sub(self.written) as u64).div_ceil(rate)),
+        }
+    }
+}
+
+// -- Summary
+
+/// Summary of a terminated transfer, reported by its notification
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransferSummary {
+    /// Files transferred, or skipped since unchanged
+    pub files: usize,
+    /// Files which couldn't be transferred
+    pub failed: usize,
+    /// Bytes transferred
+    pub bytes: u64,
+    /// Duration of the transfer
+    pub elapsed: Duration,
+}
+
+impl fmt::Display for TransferSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let rate = match self.elapsed.as_millis() {
+            0 => self.bytes,
+            millis => (self.bytes as u128 * 1000 / millis) as u64,
+        };
+        write!(
+            f,
+            "{} {} ({}) in {} seconds; at {}/s",
+            self.files,
+            if self.files == 1 { "file" } else { "files" },
+            ByteSize(self.bytes),
+            fmt_millis(self.elapsed),
+            ByteSize(rate)
+        )?;
+        if self.failed > 0 {
+            write!(f, "; {} failed", self.failed)?;
+        }
+        Ok(())
+    }
+}
+
+// -- Failures
+
+/// Entry which couldn't be transferred, while the transfer went on with the others
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransferFailure {
+    /// Entry to transfer
+    pub source: File,
+    /// Path the entry had to be written to
+    pub dest: PathBuf,
+    pub error: String,
+}
+
+impl TransferFailure {
+    pub fn new(source: &File, dest: PathBuf, error: String) -> Self {
+        Self {
+            source: source.clone(),
+            dest,
+            error,
+        }
+    }
+}
+
+/// Entries which couldn't be transferred by the last transfer, which can be retried
+#[derive(Debug)]
+pub struct FailedTransfers {
+    pub direction: TransferDirection,
+    pub failures: Vec<TransferFailure>,
+}
+
+// -- Estimate
+
+/// Estimate of the amount of files and bytes of a transfer payload.
+/// Directories are scanned one at a time with `scan_next`, so the scan can be interrupted at any moment
+#[derive(Debug, Default)]
+pub struct TransferEstimate {
+    /// Amount of files found
+    files: usize,
+    /// Total size of the files found
+    size: usize,
+    /// Amount of entries (files and directories) found
+    entries: usize,
+    /// Directories which still have to be scanned
+    pending: Vec<PathBuf>,
+    /// Directories found so far, to skip symbolic link loops
+    visited: VisitedDirs,
+    /// Symbolic links which have been skipped, since they would loop
+    loops: Vec<PathBuf>,
+}
+
+impl TransferEstimate {
+    /// Instantiates a new estimate for the provided entries
+    pub fn new(entries: &[File]) -> Self {
+        let mut estimate = Self::default();
+        estimate.push_entries(entries);
+        estimate
+    }
+
+    /// Scan the next pending directory listing its content with `list_dir`.
+    /// If listing fails, the directory is skipped and the error is returned along with the directory path
+    pub fn scan_next<F>(&mut self, list_dir: F) -> Result<(), (PathBuf, String)>
+    where
+        F: FnOnce(&Path) -> Result<Vec<File>, String>,
+    {
+        let Some(dir) = self.pending.pop() else {
+            return Ok(());
+        };
+        let entries = list_dir(dir.as_path()).map_err(|err| (dir, err))?;
+        self.push_entries(&entries);
+        Ok(())
+    }
+
+    /// Returns whether all the directories have been scanned
+    pub fn is_complete(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    /// Returns the total size of the files found
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Returns the amount of entries found
+    pub fn entries(&self) -> usize {
+        self.entries
+    }
+
+    /// Returns the symbolic links which haven't been scanned, since they would loop
+    pub fn loops(&self) -> &[PathBuf] {
+        &self.loops
+    }
+
+    /// Returns whether the amount of files or the size exceeds the provided thresholds.
+    /// A threshold set to `0` is disabled
+    pub fn exceeds(&self, files_threshold: u64, size_threshold: u64) -> bool {
+        (files_threshold > 0 && self.files as u64 >= files_threshold)
+            || (size_threshold > 0 && self.size as u64 >= size_threshold)
+    }
+
+    fn push_entries(&mut self, entries: &[File]) {
+        for entry in entries {
+            self.entries += 1;
+            if entry.is_dir() {
+                if self.visited.enter(entry) {
+                    self.pending.push(entry.path().to_path_buf());
+                } else {
+                    warn!("skipping symbolic link loop at {}", entry.path().display());
+                    self.loops.push(entry.path().to_path_buf());
+                }
+            } else {
+                self.files += 1;
+                self.size += entry.metadata().size as usize;
+            }
+        }
+    }
+}
+
+impl fmt::Display for TransferEstimate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let at_least = match self.is_complete() {
+            true => "",
+            false => "at least ",
+        };
+        write!(
+            f,
+            "{at_least}{} files ({})",
+            self.files,
+            ByteSize(self.size as u64)
+        )
+    }
+}
+
+// -- Progress adapters
+
+/// Handle shared between the transfer states and the progress adapters.
+/// The adapters report the bytes transferred for each chunk and fail as soon as the transfer is aborted
+#[derive(Debug, Clone, Default)]
+pub struct TransferProgress {
+    /// Bytes reported since the last sync
+    transferred: Arc<AtomicUsize>,
+    aborted: Arc<AtomicBool>,
+}
+
+impl TransferProgress {
+    fn report(&self, bytes: usize) {
+        self.transferred.fetch_add(bytes, Ordering::Relaxed);
+    }
+
+    fn take(&self) -> usize {
+        self.transferred.swap(0, Ordering::Relaxed)
+    }
+
+    /// Returns whether the transfer has been aborted
+    pub fn aborted(&self) -> bool {
+        self.aborted.load(Ordering::Relaxed)
+    }
+
+    fn check_aborted(&self) -> io::Result<()> {
+        match self.aborted() {
+            true => Err(io::Error::other("transfer aborted")),
+            false => Ok(()),
+        }
+    }
+}
+
+/// Reader which reports to a [`TransferProgress`] the bytes read from the inner reader
+pub struct ProgressReader<R> {
+    inner: R,
+    progress: TransferProgress,
+}
+
+impl<R> ProgressReader<R> {
+    pub fn new(inner: R, progress: TransferProgress) -> Self {
+        Self { inner, progress }
+    }
+}
+
+impl<R: Read> Read for ProgressReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.progress.check_aborted()?;
+        let bytes = self.inner.read(buf)?;
+        self.progress.report(bytes);
+        Ok(bytes)
+    }
+}
+
+/// Writer which reports to a [`TransferProgress`] the bytes written to the inner writer
+pub struct ProgressWriter<W> {
+    inner: W,
+    progress: TransferProgress,
+}
+
+impl<W> ProgressWriter<W> {
+    pub fn new(inner: W, progress: TransferProgress) -> Self {
+        Self { inner, progress }
+    }
+
+    /// Get back the inner writer
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: Write> Write for ProgressWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.progress.check_aborted()?;
+        let bytes = self.inner.write(buf)?;
+        self.progress.report(bytes);
+        Ok(bytes)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
 }
 
 // -- Options
@@ -161,6 +577,14 @@ impl ProgressStates {
 pub struct TransferOpts {
     /// Save file as
     pub save_as: Option<String>,
+    /// Amount of files transferred in parallel for recursive and multiple transfers; `0` and `1` are sequential
+    pub concurrency: usize,
+    /// Re-create symbolic links as links on the destination, instead of transferring their target
+    pub preserve_symlinks: bool,
+    /// Apply the modification time, access time and mode of the source to the destination
+    pub preserve_metadata: bool,
+    /// Copy only the new and the changed files
+    pub sync: Option<SyncMode>,
 }
 
 impl TransferOpts {
@@ -169,6 +593,94 @@ impl TransferOpts {
         self.save_as = n.map(|x| x.as_ref().to_string());
         self
     }
+
+    /// Define the amount of files to transfer in parallel
+    pub fn concurrency(mut self, n: usize) -> Self {
+        self.concurrency = n;
+        self
+    }
+
+    /// Define whether symbolic links are re-created as links on the destination
+    pub fn preserve_symlinks(mut self, preserve: bool) -> Self {
+        self.preserve_symlinks = preserve;
+        self
+    }
+
+    /// Define whether the metadata of the source is applied to the destination
+    pub fn preserve_metadata(mut self, preserve: bool) -> Self {
+        self.preserve_metadata = preserve;
+        self
+    }
+
+    /// Define the sync mode of the transfer
+    pub fn sync(mut self, mode: SyncMode) -> Self {
+        self.sync = Some(mode);
+        self
+    }
+
+    /// Returns whether files must be transferred in parallel
+    pub fn is_parallel(&self) -> bool {
+        self.concurrency > 1
+    }
+}
+
+// -- Destination
+
+/// Resolve the destination directory of a transfer, `dest`, as the host with working directory `wrkdir` sees it:
+/// relative paths are joined to the working directory and the `.` and `..` components are resolved
+pub fn resolve_destination(wrkdir: &Path, dest: &Path) -> PathBuf {
+    path::normalize(&path::absolutize(wrkdir, dest))
+}
+
+// -- Collisions
+
+/// Maximum counter tried to find a free name for a colliding file
+const MAX_COLLISION_COUNTER: usize = 1000;
+
+/// Get a name for `name` which doesn't collide with the files for which `exists` returns true,
+/// appending a counter to its stem: `report.pdf` becomes `report (1).pdf`, then `report (2).pdf` and so on
+pub fn collision_name(name: &str, mut exists: impl FnMut(&str) -> bool) -> String {
+    // only the file name may have an extension; hidden files, such as `.bashrc`, have none
+    let base = name.rfind('/').map(|x| x + 1).unwrap_or_default();
+    let (stem, extension) = match name[base..].rfind('.') {
+        Some(index) if index > 0 => name.split_at(base + index),
+        _ => (name, ""),
+    };
+    (1..=MAX_COLLISION_COUNTER)
+        .map(|n| format!("{stem} ({n}){extension}"))
+        .find(|candidate| !exists(candidate))
+        .unwrap_or_else(|| format!("{stem} (1){extension}"))
+}
+
+/// Get a name for the copy of `name` which doesn't collide with the files for which `exists` returns true,
+/// appending `copy` to its stem: `report.txt` becomes `report copy.txt`, then `report copy 2.txt` and so on
+pub fn duplicate_name(name: &str, mut exists: impl FnMut(&str) -> bool) -> String {
+    // hidden files, such as `.bashrc`, have no extension
+    let (stem, extension) = match name.rfind('.') {
+        Some(index) if index > 0 => name.split_at(index),
+        _ => (name, ""),
+    };
+    std::iter::once(format!("{stem} copy{extension}"))
+        .chain((2..=MAX_COLLISION_COUNTER).map(|n| format!("{stem} copy {n}{extension}")))
+        .find(|candidate| !exists(candidate))
+        .unwrap_or_else(|| format!("{stem} copy{extension}"))
+}
+
+/// Split the `entries` of a payload into those keeping their name and those renamed by the user to avoid a collision,
+/// according to `renames`, which associates the original names to the new ones
+pub fn split_renamed(
+    entries: Vec<File>,
+    renames: &HashMap<String, String>,
+) -> (Vec<File>, Vec<(File, String)>) {
+    let mut kept = Vec::with_capacity(entries.len());
+    let mut renamed = Vec::new();
+    for entry in entries {
+        match renames.get(&entry.name()) {
+            Some(name) => renamed.push((entry, name.clone())),
+            None => kept.push(entry),
+        }
+    }
+    (kept, renamed)
 }
 
 #[cfg(test)]
@@ -177,6 +689,7 @@ mod test {
     use std::time::Duration;
 
     use pretty_assertions::assert_eq;
+    use remotefs::fs::{FileType, Metadata};
 
     use super::*;
 
@@ -191,31 +704,40 @@ mod test {
         assert_eq!(states.total, 1024);
         assert_eq!(states.written, 0);
         assert_eq!(states.calc_bytes_per_second(), 0);
-        assert_eq!(states.calc_eta(), 0);
+        assert_eq!(states.calc_rate(), 0);
+        assert_eq!(states.calc_eta(), None);
         assert_eq!(states.calc_progress_percentage(), 0.0);
         assert_eq!(states.calc_progress(), 0.0);
-        assert_eq!(states.to_string().as_str(), "0.00% - ETA --:-- (0 B/s)");
+        assert_eq!(states.to_string().as_str(), "0.00% — 0 B/s — ETA --:--");
         // Wait 4 second (virtually)
+        let started = states.started;
         states.started = states.started.checked_sub(Duration::from_secs(4)).unwrap();
         // Update state
-        states.update_progress(256);
+        states.update_progress_at(256, started + Duration::from_secs(4));
         assert_eq!(states.total, 1024);
         assert_eq!(states.written, 256);
         assert_eq!(states.calc_bytes_per_second(), 64); // 256 bytes in 4 seconds
-        assert_eq!(states.calc_eta(), 12); // 16 total sub 4
+        assert_eq!(states.calc_rate(), 64);
+        assert_eq!(states.calc_eta(), Some(12)); // 768 bytes left at 64 B/s
         assert_eq!(states.calc_progress_percentage(), 25.0);
         assert_eq!(states.calc_progress(), 0.25);
-        assert_eq!(states.to_string().as_str(), "25.00% - ETA 00:12 (64 B/s)");
+        assert_eq!(
+            states.to_string().as_str(),
+            "25.00% — 64 B/s — ETA 00:00:12"
+        );
         // 100%
         states.started = states.started.checked_sub(Duration::from_secs(12)).unwrap();
-        states.update_progress(768);
+        states.update_progress_at(768, started + Duration::from_secs(16));
         assert_eq!(states.total, 1024);
         assert_eq!(states.written, 1024);
         assert_eq!(states.calc_bytes_per_second(), 64); // 256 bytes in 4 seconds
-        assert_eq!(states.calc_eta(), 0); // 16 total sub 4
+        assert_eq!(states.calc_eta(), Some(0));
         assert_eq!(states.calc_progress_percentage(), 100.0);
         assert_eq!(states.calc_progress(), 1.0);
-        assert_eq!(states.to_string().as_str(), "100.00% - ETA --:-- (64 B/s)");
+        assert_eq!(
+            states.to_string().as_str(),
+            "100.00% — 64 B/s — ETA 00:00:00"
+        );
         // Check if terminated at started
         states.started = Instant::now();
         assert_eq!(states.calc_bytes_per_second(), 1024);
@@ -226,10 +748,188 @@ mod test {
         assert_eq!(states.calc_progress(), 0.0);
     }
 
+    #[test]
+    fn should_calculate_rate_over_sliding_window() {
+        let mut states = ProgressStates::default();
+        states.init(100 * 1024 * 1024);
+        let started = states.started;
+        // slow start: 1KB every second
+        for sec in 1..=10 {
+            states.update_progress_at(1024, started + Duration::from_secs(sec));
+        }
+        assert_eq!(states.calc_rate(), 1024);
+        // then 1MB every second: the slow samples leave the window
+        for sec in 11..=30 {
+            states.update_progress_at(1024 * 1024, started + Duration::from_secs(sec));
+        }
+        assert_eq!(states.samples.len(), RATE_WINDOW_SAMPLES);
+        assert_eq!(states.calc_rate(), 1024 * 1024);
+        // 100MB - 20MB - 10KB left
+        assert_eq!(
+            states.calc_eta(),
+            Some(((80 * 1024 * 1024 - 10 * 1024) as u64).div_ceil(1024 * 1024))
+        );
+        assert_eq!(
+            states.to_string().as_str(),
+            "20.01% — 1048.6 KB/s — ETA 00:01:20"
+        );
+        // samples are not collected more often than the sample interval
+        let len = states.samples.len();
+        states.update_progress_at(1024, started + Duration::from_millis(30_100));
+        assert_eq!(states.samples.len(), len);
+        // the window is reset with the next file
+        states.init(1024);
+        assert_eq!(states.calc_rate(), 0);
+        assert_eq!(states.calc_eta(), None);
+        assert_eq!(states.to_string().as_str(), "0.00% — 0 B/s — ETA --:--");
+    }
+
+    #[test]
+    fn should_init_resumed_progress_states() {
+        let mut states = ProgressStates::default();
+        states.init_resumed(1024, 768);
+        assert_eq!(states.calc_progress(), 0.75);
+        assert_eq!(states.calc_rate(), 0);
+        assert_eq!(states.to_string().as_str(), "75.00% — 0 B/s — ETA --:--");
+        // the bytes written before resuming are not considered for the rate
+        let started = states.started;
+        states.started = states.started.checked_sub(Duration::from_secs(2)).unwrap();
+        states.update_progress_at(128, started + Duration::from_secs(2));
+        assert_eq!(states.calc_rate(), 64);
+        assert_eq!(states.calc_bytes_per_second(), 64);
+        assert_eq!(states.calc_eta(), Some(2));
+        // init resets
+        states.init(1024);
+        assert_eq!(states.calc_progress(), 0.0);
+        assert_eq!(states.resumed_from, 0);
+    }
+
+    #[test]
+    fn should_summarize_transfer() {
+        let mut states = TransferStates::default();
+        states.full.init(4096);
+        states.full.update_progress(4096);
+        states.count_file();
+        states.count_file();
+        states.count_failures(0, "not counted");
+        assert_eq!(states.first_error(), None);
+        states.count_failures(3, "permission denied");
+        states.count_failures(1, "no space left on device");
+        assert_eq!(states.first_error(), Some("permission denied"));
+        let summary = states.summary();
+        assert_eq!(summary.files, 2);
+        assert_eq!(summary.failed, 4);
+        assert_eq!(summary.bytes, 4096);
+        // reset clears the summary
+        states.reset();
+        assert_eq!(states.summary().files, 0);
+        assert_eq!(states.summary().failed, 0);
+        assert_eq!(states.first_error(), None);
+    }
+
+    #[test]
+    fn should_keep_failures_until_taken() {
+        let mut states = TransferStates::default();
+        let file = File {
+            path: PathBuf::from("/photos/a.jpg"),
+            metadata: Metadata::default(),
+        };
+        states.push_failure(TransferFailure::new(
+            &file,
+            PathBuf::from("/backup/a.jpg"),
+            String::from("permission denied"),
+        ));
+        let failures = states.take_failures();
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].source.path(), Path::new("/photos/a.jpg"));
+        assert_eq!(failures[0].dest, PathBuf::from("/backup/a.jpg"));
+        assert!(states.take_failures().is_empty());
+        // reset clears the failures
+        states.push_failure(failures[0].clone());
+        states.reset();
+        assert!(states.take_failures().is_empty());
+    }
+
+    #[test]
+    fn should_format_transfer_summary() {
+        let mut summary = TransferSummary {
+            files: 12,
+            failed: 0,
+            bytes: 4000,
+            elapsed: Duration::from_millis(2000),
+        };
+        assert_eq!(
+            summary.to_string().as_str(),
+            "12 files (4.0 KB) in 2.000 seconds; at 2.0 KB/s"
+        );
+        summary.files = 1;
+        summary.failed = 2;
+        assert_eq!(
+            summary.to_string().as_str(),
+            "1 file (4.0 KB) in 2.000 seconds; at 2.0 KB/s; 2 failed"
+        );
+    }
+
+    #[test]
+    fn should_notify_single_file_payload_by_size() {
+        let mut states = TransferStates::default();
+        states.full.init(1024);
+        assert_eq!(states.should_notify(1024, None), true);
+        assert_eq!(states.should_notify(2048, None), false);
+        assert_eq!(
+            states.should_notify(2048, Some(Duration::from_secs(30))),
+            false
+        );
+    }
+
+    #[test]
+    fn should_notify_recursive_payload_by_cumulative_size() {
+        // 10,000 files of 1KB each
+        let mut states = TransferStates::default();
+        states.full.init(10_000 * 1024);
+        states.partial.init(1024);
+        assert_eq!(states.should_notify(1024 * 1024, None), true);
+        assert_eq!(states.should_notify(512 * 1024 * 1024, None), false);
+    }
+
+    #[test]
+    fn should_notify_long_transfers_by_duration() {
+        let threshold = 512 * 1024 * 1024;
+        let min_duration = Some(Duration::from_secs(60));
+        assert_eq!(
+            TransferStates::notification_due(
+                4096,
+                Duration::from_secs(40 * 60),
+                threshold,
+                min_duration
+            ),
+            true
+        );
+        assert_eq!(
+            TransferStates::notification_due(
+                4096,
+                Duration::from_secs(59),
+                threshold,
+                min_duration
+            ),
+            false
+        );
+        // duration is not considered unless set
+        assert_eq!(
+            TransferStates::notification_due(4096, Duration::from_secs(40 * 60), threshold, None),
+            false
+        );
+        // empty payload
+        assert_eq!(
+            TransferStates::notification_due(0, Duration::ZERO, 0, None),
+            true
+        );
+    }
+
     #[test]
     fn test_ui_activities_filetransfer_lib_transfer_states() {
         let mut states: TransferStates = TransferStates::default();
-        assert_eq!(states.aborted, false);
+        assert_eq!(states.aborted(), false);
         assert_eq!(states.full.total, 0);
         assert_eq!(states.full.written, 0);
         assert!(states.full.started.elapsed().as_secs() < 5);
@@ -245,11 +945,308 @@ mod test {
         assert_eq!(states.full_size(), 1024);
     }
 
+    #[test]
+    fn should_estimate_transfer() {
+        let tree = |p: &Path| -> Result<Vec<File>, String> {
+            match p.to_string_lossy().as_ref() {
+                "/tmp/a" => Ok(vec![
+                    make_entry("/tmp/a/b", true, 0),
+                    make_entry("/tmp/a/c.txt", false, 1024),
+                ]),
+                "/tmp/a/b" => Ok(vec![
+                    make_entry("/tmp/a/b/d.txt", false, 2048),
+                    make_entry("/tmp/a/b/e.txt", false, 4096),
+                ]),
+                _ => Err(String::from("no such directory")),
+            }
+        };
+        let mut estimate = TransferEstimate::new(&[
+            make_entry("/tmp/a", true, 0),
+            make_entry("/tmp/f.txt", false, 512),
+        ]);
+        assert_eq!(estimate.is_complete(), false);
+        assert_eq!(estimate.files, 1);
+        assert_eq!(estimate.size(), 512);
+        assert_eq!(estimate.entries(), 2);
+        assert_eq!(estimate.to_string().as_str(), "at least 1 files (512 B)");
+        assert!(estimate.scan_next(tree).is_ok());
+        assert_eq!(estimate.is_complete(), false);
+        assert_eq!(estimate.files, 2);
+        assert_eq!(estimate.entries(), 4);
+        assert!(estimate.scan_next(tree).is_ok());
+        assert_eq!(estimate.is_complete(), true);
+        assert_eq!(estimate.files, 4);
+        assert_eq!(estimate.size(), 7680);
+        assert_eq!(estimate.entries(), 6);
+        assert_eq!(estimate.to_string().as_str(), "4 files (7.7 KB)");
+        // nothing left to scan
+        assert!(estimate.scan_next(tree).is_ok());
+        assert_eq!(estimate.entries(), 6);
+    }
+
+    #[test]
+    fn should_skip_directories_which_cannot_be_listed() {
+        let mut estimate = TransferEstimate::new(&[make_entry("/tmp/a", true, 0)]);
+        assert_eq!(
+            estimate.scan_next(|_| Err(String::from("permission denied"))),
+            Err((PathBuf::from("/tmp/a"), String::from("permission denied")))
+        );
+        assert_eq!(estimate.is_complete(), true);
+        assert_eq!(estimate.files, 0);
+    }
+
+    #[test]
+    #[cfg(posix)]
+    fn should_skip_symlink_loops() {
+        use std::fs;
+        use std::os::unix::fs::symlink;
+
+        let tmpdir = tempfile::tempdir().unwrap();
+        let dir = tmpdir.path().join("a");
+        fs::create_dir(dir.as_path()).unwrap();
+        fs::write(dir.join("b.txt"), b"hello").unwrap();
+        symlink(".", dir.join("loop")).unwrap();
+        // list directories as servers which report links to directories as directories
+        let list_dir = |path: &Path| -> Result<Vec<File>, String> {
+            let mut entries = Vec::new();
+            for entry in fs::read_dir(path).map_err(|e| e.to_string())? {
+                let path = entry.map_err(|e| e.to_string())?.path();
+                let mut metadata = Metadata::from(fs::metadata(path.as_path()).unwrap());
+                if let Ok(target) = fs::read_link(path.as_path()) {
+                    metadata.set_symlink(target);
+                }
+                entries.push(File { path, metadata });
+            }
+            Ok(entries)
+        };
+        let mut estimate = TransferEstimate::new(&[File {
+            path: dir.clone(),
+            metadata: Metadata::from(fs::metadata(dir.as_path()).unwrap()),
+        }]);
+        let mut scans = 0;
+        while !estimate.is_complete() {
+            assert!(estimate.scan_next(list_dir).is_ok());
+            scans += 1;
+            assert!(scans < 10, "the scan doesn't terminate");
+        }
+        assert_eq!(scans, 1);
+        assert_eq!(estimate.files, 1);
+        assert_eq!(estimate.size(), 5);
+        assert_eq!(estimate.loops(), &[dir.join("loop")]);
+    }
+
+    #[test]
+    fn should_tell_whether_estimate_exceeds_thresholds() {
+        let estimate = TransferEstimate::new(&[
+            make_entry("/tmp/a.txt", false, 1024),
+            make_entry("/tmp/b.txt", false, 1024),
+        ]);
+        assert_eq!(estimate.exceeds(0, 0), false);
+        assert_eq!(estimate.exceeds(3, 4096), false);
+        assert_eq!(estimate.exceeds(2, 4096), true);
+        assert_eq!(estimate.exceeds(3, 2048), true);
+        assert_eq!(estimate.exceeds(0, 2048), true);
+        assert_eq!(estimate.exceeds(1, 0), true);
+    }
+
     #[test]
     fn transfer_opts() {
         let opts = TransferOpts::default();
         assert!(opts.save_as.is_none());
+        assert_eq!(opts.is_parallel(), false);
+        assert_eq!(opts.preserve_symlinks, false);
+        assert_eq!(opts.preserve_metadata, false);
+        assert_eq!(opts.sync, None);
         let opts = TransferOpts::default().save_as(Some("omar.txt"));
         assert_eq!(opts.save_as.as_deref().unwrap(), "omar.txt");
+        assert_eq!(opts.concurrency(1).is_parallel(), false);
+        assert_eq!(TransferOpts::default().concurrency(4).is_parallel(), true);
+        assert_eq!(
+            TransferOpts::default()
+                .preserve_symlinks(true)
+                .preserve_symlinks,
+            true
+        );
+        assert_eq!(
+            TransferOpts::default()
+                .preserve_metadata(true)
+                .preserve_metadata,
+            true
+        );
+        assert_eq!(
+            TransferOpts::default().sync(SyncMode::Mirror).sync,
+            Some(SyncMode::Mirror)
+        );
+    }
+
+    #[test]
+    fn should_resolve_transfer_destination() {
+        // relative to the working directory (e.g. SFTP home directory)
+        assert_eq!(
+            resolve_destination(Path::new("/home/omar"), Path::new("www")),
+            PathBuf::from("/home/omar/www")
+        );
+        // absolute paths are kept
+        assert_eq!(
+            resolve_destination(Path::new("/home/omar"), Path::new("/srv/www")),
+            PathBuf::from("/srv/www")
+        );
+        assert_eq!(
+            resolve_destination(Path::new("/home/omar"), Path::new("../shared/./www/")),
+            PathBuf::from("/home/shared/www")
+        );
+        // the root can't be left (e.g. S3 bucket or chrooted FTP server)
+        assert_eq!(
+            resolve_destination(Path::new("/"), Path::new("../site")),
+            PathBuf::from("/site")
+        );
+        assert_eq!(
+            resolve_destination(Path::new("/"), Path::new(".")),
+            PathBuf::from("/")
+        );
+    }
+
+    #[test]
+    fn should_make_collision_names() {
+        assert_eq!(
+            collision_name("report.pdf", |_| false).as_str(),
+            "report (1).pdf"
+        );
+        let existing = ["report (1).pdf", "report (2).pdf"];
+        assert_eq!(
+            collision_name("report.pdf", |x| existing.contains(&x)).as_str(),
+            "report (3).pdf"
+        );
+        assert_eq!(
+            collision_name("Makefile", |_| false).as_str(),
+            "Makefile (1)"
+        );
+        assert_eq!(collision_name(".bashrc", |_| false).as_str(), ".bashrc (1)");
+        assert_eq!(
+            collision_name("www.example.org/.htaccess", |_| false).as_str(),
+            "www.example.org/.htaccess (1)"
+        );
+        assert_eq!(
+            collision_name("backup.tar.gz", |_| false).as_str(),
+            "backup.tar (1).gz"
+        );
+    }
+
+    #[test]
+    fn should_make_duplicate_names() {
+        assert_eq!(
+            duplicate_name("report.txt", |_| false).as_str(),
+            "report copy.txt"
+        );
+        let existing = ["report copy.txt", "report copy 2.txt"];
+        assert_eq!(
+            duplicate_name("report.txt", |x| existing.contains(&x)).as_str(),
+            "report copy 3.txt"
+        );
+        assert_eq!(duplicate_name("photos", |_| false).as_str(), "photos copy");
+        assert_eq!(
+            duplicate_name(".bashrc", |_| false).as_str(),
+            ".bashrc copy"
+        );
+        assert_eq!(
+            duplicate_name("backup.tar.gz", |_| false).as_str(),
+            "backup.tar copy.gz"
+        );
+    }
+
+    #[test]
+    fn should_split_renamed_entries_of_payload() {
+        let entries = vec![
+            make_entry("/home/omar/report.pdf", false, 1024),
+            make_entry("/home/omar/notes.txt", false, 64),
+            make_entry("/home/omar/photos", true, 0),
+        ];
+        let renames = HashMap::from([(String::from("report.pdf"), String::from("report (1).pdf"))]);
+        let (kept, renamed) = split_renamed(entries, &renames);
+        assert_eq!(
+            kept.iter().map(|x| x.name()).collect::<Vec<String>>(),
+            vec![String::from("notes.txt"), String::from("photos")]
+        );
+        assert_eq!(renamed.len(), 1);
+        assert_eq!(renamed[0].0.name().as_str(), "report.pdf");
+        assert_eq!(renamed[0].1.as_str(), "report (1).pdf");
+        // nothing renamed
+        let (kept, renamed) = split_renamed(kept, &HashMap::new());
+        assert_eq!(kept.len(), 2);
+        assert!(renamed.is_empty());
+    }
+
+    fn make_entry(path: &str, is_dir: bool, size: u64) -> File {
+        File {
+            path: PathBuf::from(path),
+            metadata: Metadata {
+                file_type: if is_dir {
+                    FileType::Directory
+                } else {
+                    FileType::File
+                },
+                size,
+                ..Default::default()
+            },
+        }
+    }
+
+    #[test]
+    fn should_report_progress_for_each_chunk_read() {
+        const SIZE: usize = 8 * 1024 * 1024 + 100;
+        const CHUNK: usize = 65536;
+        let mut states = TransferStates::default();
+        states.partial.init(SIZE);
+        states.full.init(SIZE);
+        let mut reader = ProgressReader::new(io::Cursor::new(vec![0xcd; SIZE]), states.progress());
+        let mut buffer = vec![0; CHUNK];
+        let mut chunks = 0;
+        loop {
+            let bytes = reader.read(&mut buffer).unwrap();
+            if bytes == 0 {
+                break;
+            }
+            chunks += 1;
+            assert_eq!(states.sync_progress(), bytes);
+        }
+        assert_eq!(chunks, SIZE.div_ceil(CHUNK));
+        assert_eq!(states.partial.written, SIZE);
+        assert_eq!(states.full.written, SIZE);
+        assert_eq!(states.partial.calc_progress(), 1.0);
+        assert_eq!(states.sync_progress(), 0);
+    }
+
+    #[test]
+    fn should_report_progress_for_each_chunk_written() {
+        const SIZE: usize = 4 * 1024 * 1024;
+        let mut states = TransferStates::default();
+        states.partial.init(SIZE);
+        let mut writer = ProgressWriter::new(Vec::new(), states.progress());
+        let copied = io::copy(&mut io::Cursor::new(vec![0xcd; SIZE]), &mut writer).unwrap();
+        assert_eq!(copied as usize, SIZE);
+        assert_eq!(writer.into_inner().len(), SIZE);
+        assert_eq!(states.sync_progress(), SIZE);
+        assert_eq!(states.partial.calc_progress(), 1.0);
+    }
+
+    #[test]
+    fn should_fail_progress_adapters_when_aborted() {
+        let mut states = TransferStates::default();
+        let mut reader = ProgressReader::new(io::Cursor::new(vec![0; 1024]), states.progress());
+        let mut writer = ProgressWriter::new(Vec::new(), states.progress());
+        let mut buffer = [0; 256];
+        assert_eq!(reader.read(&mut buffer).unwrap(), 256);
+        assert_eq!(writer.write(&buffer).unwrap(), 256);
+        states.abort();
+        assert!(reader.read(&mut buffer).is_err());
+        assert!(writer.write(&buffer).is_err());
+        assert!(io::copy(&mut reader, &mut io::sink()).is_err());
+        // nothing is reported once aborted
+        assert_eq!(states.sync_progress(), 512);
+        // progress is reset along with the abort flag
+        states.reset();
+        assert_eq!(reader.read(&mut buffer).unwrap(), 256);
+        states.reset();
+        assert_eq!(states.sync_progress(), 0);
     }
 }
@@ -0,0 +1,144 @@
+//! ## FileTransferActivity
+//!
+//! `filetransfer_activiy` is the module which implements the Filetransfer activity, which is the main activity afterall
+
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+
+/// Maximum amount of operations which can be undone
+const UNDO_STACK_SIZE: usize = 16;
+
+/// Host where a reversible operation has been performed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UndoHost {
+    HostBridge,
+    Remote,
+}
+
+/// A rename (or move) of `src` to `dest`, which is reverted moving `dest` back to `src`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UndoMove {
+    pub host: UndoHost,
+    pub src: PathBuf,
+    pub dest: PathBuf,
+}
+
+/// Stack of the last reversible operations performed in the session
+#[derive(Debug, Default)]
+pub struct UndoStack {
+    moves: VecDeque<UndoMove>,
+}
+
+impl UndoStack {
+    /// Push the move of `src` to `dest` on `host`.
+    /// Moves are undone from the most recent one, so a move doesn't invalidate the previous ones
+    pub fn push(&mut self, host: UndoHost, src: &Path, dest: &Path) {
+        if self.moves.len() == UNDO_STACK_SIZE {
+            self.moves.pop_front();
+        }
+        self.moves.push_back(UndoMove {
+            host,
+            src: src.to_path_buf(),
+            dest: dest.to_path_buf(),
+        });
+    }
+
+    /// Take the most recent operation
+    pub fn pop(&mut self) -> Option<UndoMove> {
+        self.moves.pop_back()
+    }
+
+    /// Invalidate the operations on `host` which can't be undone anymore, since `path` has been changed by a later
+    /// operation: reverting them would affect `path` or its content
+    pub fn invalidate(&mut self, host: UndoHost, path: &Path) {
+        self.moves.retain(|x| {
+            x.host != host || !(Self::is_related(&x.src, path) || Self::is_related(&x.dest, path))
+        });
+    }
+
+    /// Whether `a` and `b` are the same path, or one of them contains the other
+    fn is_related(a: &Path, b: &Path) -> bool {
+        a.starts_with(b) || b.starts_with(a)
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn should_undo_last_move() {
+        let mut stack = UndoStack::default();
+        assert!(stack.pop().is_none());
+        stack.push(
+            UndoHost::Remote,
+            Path::new("/home/omar/a.txt"),
+            Path::new("/home/omar/b.txt"),
+        );
+        stack.push(
+            UndoHost::HostBridge,
+            Path::new("/tmp/notes"),
+            Path::new("/tmp/notes.bak"),
+        );
+        assert_eq!(
+            stack.pop().unwrap(),
+            UndoMove {
+                host: UndoHost::HostBridge,
+                src: PathBuf::from("/tmp/notes"),
+                dest: PathBuf::from("/tmp/notes.bak")
+            }
+        );
+        assert_eq!(stack.pop().unwrap().src, PathBuf::from("/home/omar/a.txt"));
+        assert!(stack.pop().is_none());
+    }
+
+    #[test]
+    fn should_invalidate_moves_no_longer_reversible() {
+        let mut stack = UndoStack::default();
+        stack.push(
+            UndoHost::Remote,
+            Path::new("/srv/www/index.htm"),
+            Path::new("/srv/www/index.html"),
+        );
+        stack.push(
+            UndoHost::Remote,
+            Path::new("/srv/old"),
+            Path::new("/srv/archive/old"),
+        );
+        // same path on the other host
+        stack.invalidate(UndoHost::HostBridge, Path::new("/srv/www/index.html"));
+        assert_eq!(stack.moves.len(), 2);
+        // parent directory of the destination deleted
+        stack.invalidate(UndoHost::Remote, Path::new("/srv/archive"));
+        assert_eq!(stack.moves.len(), 1);
+        // file created at the source
+        stack.invalidate(UndoHost::Remote, Path::new("/srv/www/index.htm"));
+        assert!(stack.moves.is_empty());
+    }
+
+    #[test]
+    fn should_undo_chained_moves() {
+        let mut stack = UndoStack::default();
+        stack.push(UndoHost::Remote, Path::new("/a"), Path::new("/b"));
+        stack.push(UndoHost::Remote, Path::new("/b"), Path::new("/c"));
+        assert_eq!(stack.pop().unwrap().src, PathBuf::from("/b"));
+        assert_eq!(stack.pop().unwrap().src, PathBuf::from("/a"));
+    }
+
+    #[test]
+    fn should_keep_last_moves() {
+        let mut stack = UndoStack::default();
+        for i in 0..20 {
+            stack.push(
+                UndoHost::HostBridge,
+                PathBuf::from(format!("/tmp/{i}")).as_path(),
+                PathBuf::from(format!("/tmp/{i}.bak")).as_path(),
+            );
+        }
+        assert_eq!(stack.moves.len(), UNDO_STACK_SIZE);
+        assert_eq!(stack.moves.front().unwrap().src, PathBuf::from("/tmp/4"));
+    }
+}
@@ -0,0 +1,134 @@
+//! ## FileTransferActivity
+//!
+//! `filetransfer_activiy` is the module which implements the Filetransfer activity, which is the main activity afterall
+
+use super::queue::TransferQueue;
+
+/// Work which would be interrupted by disconnecting from the remote or quitting termscp
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct UnfinishedWork {
+    /// Transfers being transferred
+    pub active: usize,
+    /// Transfers waiting in the queue, including the scheduled ones
+    pub queued: usize,
+    /// Changes reported by the file watcher which haven't been uploaded yet
+    pub watcher_pending: usize,
+}
+
+impl UnfinishedWork {
+    /// Collect the unfinished work from the transfer queue and the changes pending in the file watcher
+    pub fn new(queue: &TransferQueue, watcher_pending: usize) -> Self {
+        let active = usize::from(queue.is_active());
+        Self {
+            active,
+            queued: queue.unfinished().saturating_sub(active),
+            watcher_pending,
+        }
+    }
+
+    /// Returns whether disconnecting must be confirmed explicitly, since something would be interrupted
+    pub fn requires_confirmation(&self) -> bool {
+        self.active + self.queued + self.watcher_pending > 0
+    }
+
+    /// Describe what would be interrupted, e.g. "1 transfer in progress, 3 pending watcher uploads".
+    /// Returns `None` if there's nothing to interrupt
+    pub fn summary(&self) -> Option<String> {
+        let parts: Vec<String> = [
+            (self.active, "transfer in progress", "transfers in progress"),
+            (self.queued, "queued transfer", "queued transfers"),
+            (
+                self.watcher_pending,
+                "pending watcher upload",
+                "pending watcher uploads",
+            ),
+        ]
+        .into_iter()
+        .filter(|(n, _, _)| *n > 0)
+        .map(|(n, one, many)| format!("{n} {}", if n == 1 { one } else { many }))
+        .collect();
+        match parts.is_empty() {
+            true => None,
+            false => Some(parts.join(", ")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use std::path::PathBuf;
+
+    use pretty_assertions::assert_eq;
+    use remotefs::fs::{FileType, Metadata};
+    use remotefs::File;
+
+    use super::*;
+    use crate::ui::activities::filetransfer::lib::parallel::TransferDirection;
+
+    fn file(name: &str) -> File {
+        File {
+            path: PathBuf::from(name),
+            metadata: Metadata::default().file_type(FileType::File),
+        }
+    }
+
+    #[test]
+    fn should_not_require_confirmation_without_unfinished_work() {
+        let work = UnfinishedWork::new(&TransferQueue::default(), 0);
+        assert_eq!(work, UnfinishedWork::default());
+        assert_eq!(work.requires_confirmation(), false);
+        assert!(work.summary().is_none());
+    }
+
+    #[test]
+    fn should_require_confirmation_with_pending_watcher_uploads() {
+        let work = UnfinishedWork::new(&TransferQueue::default(), 3);
+        assert_eq!(work.requires_confirmation(), true);
+        assert_eq!(work.summary().unwrap(), "3 pending watcher uploads");
+    }
+
+    #[test]
+    fn should_count_queued_transfers() {
+        let mut queue = TransferQueue::default();
+        queue.push(
+            TransferDirection::Upload,
+            file("a.txt"),
+            PathBuf::from("/a"),
+        );
+        queue.push(
+            TransferDirection::Upload,
+            file("b.txt"),
+            PathBuf::from("/b"),
+        );
+        let work = UnfinishedWork::new(&queue, 1);
+        assert_eq!(work.active, 0);
+        assert_eq!(work.queued, 2);
+        assert_eq!(work.requires_confirmation(), true);
+        assert_eq!(
+            work.summary().unwrap(),
+            "2 queued transfers, 1 pending watcher upload"
+        );
+        // cancelled transfers don't count
+        queue.remove(1);
+        queue.remove(0);
+        assert_eq!(
+            UnfinishedWork::new(&queue, 0).requires_confirmation(),
+            false
+        );
+    }
+
+    #[test]
+    fn should_summarize_active_transfer() {
+        let work = UnfinishedWork {
+            active: 1,
+            queued: 1,
+            watcher_pending: 0,
+        };
+        assert_eq!(work.requires_confirmation(), true);
+        assert_eq!(
+            work.summary().unwrap(),
+            "1 transfer in progress, 1 queued transfer"
+        );
+    }
+}
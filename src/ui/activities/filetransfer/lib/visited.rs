@@ -0,0 +1,207 @@
+//! ## FileTransferActivity
+//!
+//! `filetransfer_activiy` is the module which implements the Filetransfer activity, which is the main activity afterall
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use remotefs::File;
+
+use crate::utils::path;
+
+/// Identity of a visited directory
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum DirId {
+    /// Device and inode of a local directory
+    Inode(u64, u64),
+    /// Path of the directory, with symbolic links resolved
+    Path(PathBuf),
+}
+
+/// Directories being visited by a recursive operation, used to detect symbolic link loops:
+/// a directory can't be entered again while it's being visited.
+/// It also tells whether symbolic links must be followed or preserved as links
+#[derive(Debug, Default)]
+pub struct VisitedDirs {
+    /// Whether directories are on the local file system and can be identified by device and inode
+    local: bool,
+    /// Whether symbolic links are not followed, but re-created as links at the destination
+    preserve_symlinks: bool,
+    /// Identity of the visited directories by their path
+    dirs: HashMap<PathBuf, DirId>,
+    ids: HashSet<DirId>,
+}
+
+impl VisitedDirs {
+    /// Track directories on the local file system, identified by device and inode
+    pub fn local() -> Self {
+        Self {
+            local: true,
+            ..Default::default()
+        }
+    }
+
+    /// Track remote directories, identified by their path with symbolic links resolved
+    pub fn remote() -> Self {
+        Self::default()
+    }
+
+    /// Set whether symbolic links are re-created as links at the destination, instead of being followed
+    pub fn preserve_symlinks(mut self, preserve: bool) -> Self {
+        self.preserve_symlinks = preserve;
+        self
+    }
+
+    /// Get the target of `entry`, if it's a symbolic link which must be re-created instead of followed
+    pub fn preserved_link<'a>(&self, entry: &'a File) -> Option<&'a Path> {
+        match self.preserve_symlinks {
+            true => entry.metadata().symlink.as_deref(),
+            false => None,
+        }
+    }
+
+    /// Enter `dir`.
+    /// Returns `false` if `dir` is already being visited, which means that descending into it would loop
+    pub fn enter(&mut self, dir: &File) -> bool {
+        let id = self.id(dir);
+        if !self.ids.insert(id.clone()) {
+            return false;
+        }
+        self.dirs.insert(dir.path().to_path_buf(), id);
+        true
+    }
+
+    /// Leave `dir`, once all of its content has been visited
+    pub fn leave(&mut self, dir: &Path) {
+        if let Some(id) = self.dirs.remove(dir) {
+            self.ids.remove(&id);
+        }
+    }
+
+    fn id(&self, dir: &File) -> DirId {
+        if self.local {
+            if let Some(id) = Self::local_id(dir.path()) {
+                return id;
+            }
+        }
+        DirId::Path(self.resolve(dir))
+    }
+
+    #[cfg(posix)]
+    fn local_id(path: &Path) -> Option<DirId> {
+        use std::os::unix::fs::MetadataExt;
+
+        std::fs::metadata(path)
+            .ok()
+            .map(|metadata| DirId::Inode(metadata.dev(), metadata.ino()))
+    }
+
+    #[cfg(win)]
+    fn local_id(path: &Path) -> Option<DirId> {
+        std::fs::canonicalize(path).ok().map(DirId::Path)
+    }
+
+    /// Resolve the path of `dir` from the resolved path of its parent, if it's being visited, and its symlink target
+    fn resolve(&self, dir: &File) -> PathBuf {
+        let Some(parent) = dir.path().parent() else {
+            return path::normalize(dir.path());
+        };
+        let parent = match self.dirs.get(parent) {
+            Some(DirId::Path(resolved)) => resolved.as_path(),
+            _ => parent,
+        };
+        match dir.metadata().symlink.as_deref() {
+            Some(target) => path::normalize(&parent.join(target)),
+            None => path::normalize(&parent.join(dir.name())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    #[cfg(posix)]
+    use std::fs;
+    #[cfg(posix)]
+    use std::os::unix::fs::symlink;
+
+    use pretty_assertions::assert_eq;
+    use remotefs::fs::{FileType, Metadata};
+
+    use super::*;
+
+    #[test]
+    #[cfg(posix)]
+    fn should_detect_local_symlink_loop() {
+        let tmpdir = tempfile::tempdir().unwrap();
+        let loop_path = tmpdir.path().join("loop");
+        symlink(".", loop_path.as_path()).unwrap();
+        let mut visited = VisitedDirs::local();
+        assert_eq!(visited.enter(&local_dir(tmpdir.path())), true);
+        assert_eq!(visited.enter(&local_dir(loop_path.as_path())), false);
+        // once left, the directory can be entered again
+        visited.leave(tmpdir.path());
+        assert_eq!(visited.enter(&local_dir(loop_path.as_path())), true);
+    }
+
+    #[test]
+    fn should_detect_remote_symlink_loop() {
+        let mut visited = VisitedDirs::remote();
+        assert_eq!(visited.enter(&remote_dir("/home/omar", None)), true);
+        assert_eq!(visited.enter(&remote_dir("/home/omar/docs", None)), true);
+        // link to a directory which is not an ancestor
+        assert_eq!(
+            visited.enter(&remote_dir("/home/omar/docs/tmp", Some("/tmp"))),
+            true
+        );
+        assert_eq!(
+            visited.enter(&remote_dir("/home/omar/docs/loop", Some("."))),
+            false
+        );
+        assert_eq!(
+            visited.enter(&remote_dir("/home/omar/docs/up", Some("../.."))),
+            true
+        );
+        // children of a link are resolved from the link target
+        assert_eq!(
+            visited.enter(&remote_dir("/home/omar/docs/up/omar", None)),
+            false
+        );
+        visited.leave(Path::new("/home/omar/docs/up"));
+        visited.leave(Path::new("/home/omar/docs/tmp"));
+        assert_eq!(
+            visited.enter(&remote_dir("/home/omar/docs/up", Some("/home"))),
+            true
+        );
+    }
+
+    #[test]
+    fn should_tell_whether_symlinks_are_preserved() {
+        let link = remote_dir("/home/omar/docs", Some("/srv/docs"));
+        let dir = remote_dir("/home/omar/music", None);
+        let visited = VisitedDirs::remote();
+        assert_eq!(visited.preserved_link(&link), None);
+        let visited = VisitedDirs::remote().preserve_symlinks(true);
+        assert_eq!(visited.preserved_link(&link), Some(Path::new("/srv/docs")));
+        assert_eq!(visited.preserved_link(&dir), None);
+    }
+
+    #[cfg(posix)]
+    fn local_dir(path: &Path) -> File {
+        File {
+            path: path.to_path_buf(),
+            metadata: Metadata::from(fs::metadata(path).unwrap()),
+        }
+    }
+
+    fn remote_dir(path: &str, symlink: Option<&str>) -> File {
+        let mut metadata = Metadata::default().file_type(FileType::Directory);
+        if let Some(target) = symlink {
+            metadata.set_symlink(PathBuf::from(target));
+        }
+        File {
+            path: PathBuf::from(path),
+            metadata,
+        }
+    }
+}
@@ -0,0 +1,180 @@
+//! ## Wait
+//!
+//! wait states for the operations pending on a file explorer panel
+
+use std::time::{Duration, Instant};
+
+use tuirealm::props::Alignment;
+
+use super::super::Id;
+
+/// Operations lasting longer than this threshold are displayed with the blocking wait popup
+pub const BLOCKING_WAIT_THRESHOLD: Duration = Duration::from_millis(400);
+/// Interval between two spinner frames
+const SPINNER_INTERVAL: Duration = Duration::from_millis(100);
+const SPINNER_FRAMES: [char; 10] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+
+/// Describes an operation pending on a file explorer panel
+#[derive(Debug)]
+pub struct PendingOperation {
+    /// Panel the operation is pending on
+    panel: Id,
+    /// Operation description
+    text: String,
+    /// Panel title before the operation started
+    title: Option<(String, Alignment)>,
+    started: Instant,
+    /// Whether the blocking popup has been displayed already
+    escalated: bool,
+}
+
+impl PendingOperation {
+    /// Start a new pending operation on `panel`; `title` is the title of the panel to restore once the operation terminates
+    pub fn start(panel: Id, text: impl ToString, title: Option<(String, Alignment)>) -> Self {
+        Self {
+            panel,
+            text: text.to_string(),
+            title,
+            started: Instant::now(),
+            escalated: false,
+        }
+    }
+
+    /// Get the panel the operation is pending on
+    pub fn panel(&self) -> &Id {
+        &self.panel
+    }
+
+    /// Get the operation description
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Get the panel title to restore once the operation terminates
+    pub fn title(&self) -> Option<&(String, Alignment)> {
+        self.title.as_ref()
+    }
+
+    /// Time elapsed since the operation started
+    pub fn elapsed(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    /// Returns whether the operation has exceeded the threshold, and so the blocking popup should be displayed
+    pub fn should_block(&self) -> bool {
+        Self::exceeds_threshold(self.elapsed())
+    }
+
+    /// Returns whether the blocking popup must be displayed now, which is the first time it's asked once the operation
+    /// has exceeded the threshold
+    pub fn escalate(&mut self) -> bool {
+        let escalate = !self.escalated && self.should_block();
+        self.escalated |= escalate;
+        escalate
+    }
+
+    /// Format the panel title with the spinner and the operation description
+    pub fn fmt_title(&self) -> String {
+        Self::fmt_title_at(
+            self.title.as_ref().map(|(t, _)| t.as_str()).unwrap_or(""),
+            &self.text,
+            self.elapsed(),
+        )
+    }
+
+    fn exceeds_threshold(elapsed: Duration) -> bool {
+        elapsed >= BLOCKING_WAIT_THRESHOLD
+    }
+
+    fn spinner_at(elapsed: Duration) -> char {
+        let frame = (elapsed.as_millis() / SPINNER_INTERVAL.as_millis()) as usize;
+        SPINNER_FRAMES[frame % SPINNER_FRAMES.len()]
+    }
+
+    fn fmt_title_at(title: &str, text: &str, elapsed: Duration) -> String {
+        format!("{title}{} {text}", Self::spinner_at(elapsed))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn should_start_pending_operation() {
+        let op = PendingOperation::start(
+            Id::ExplorerHostBridge,
+            "Removing file(s)…",
+            Some((String::from("localhost:/tmp "), Alignment::Left)),
+        );
+        assert_eq!(op.panel(), &Id::ExplorerHostBridge);
+        assert_eq!(op.text(), "Removing file(s)…");
+        assert_eq!(
+            op.title(),
+            Some(&(String::from("localhost:/tmp "), Alignment::Left))
+        );
+        assert!(op.elapsed() < BLOCKING_WAIT_THRESHOLD);
+        assert_eq!(op.should_block(), false);
+    }
+
+    #[test]
+    fn should_tell_whether_operation_exceeds_threshold() {
+        assert_eq!(PendingOperation::exceeds_threshold(Duration::ZERO), false);
+        assert_eq!(
+            PendingOperation::exceeds_threshold(Duration::from_millis(399)),
+            false
+        );
+        assert_eq!(
+            PendingOperation::exceeds_threshold(Duration::from_millis(400)),
+            true
+        );
+        assert_eq!(
+            PendingOperation::exceeds_threshold(Duration::from_secs(5)),
+            true
+        );
+    }
+
+    #[test]
+    fn should_escalate_slow_operation() {
+        let mut op = PendingOperation::start(Id::ExplorerRemote, "Removing file(s)…", None);
+        assert_eq!(op.escalate(), false);
+        // a slow step of the operation
+        std::thread::sleep(BLOCKING_WAIT_THRESHOLD);
+        assert_eq!(op.escalate(), true);
+        // the popup is displayed once
+        assert_eq!(op.escalate(), false);
+        assert_eq!(op.should_block(), true);
+    }
+
+    #[test]
+    fn should_rotate_spinner() {
+        assert_eq!(PendingOperation::spinner_at(Duration::ZERO), '⠋');
+        assert_eq!(
+            PendingOperation::spinner_at(Duration::from_millis(150)),
+            '⠙'
+        );
+        assert_eq!(
+            PendingOperation::spinner_at(Duration::from_millis(950)),
+            '⠏'
+        );
+        assert_eq!(
+            PendingOperation::spinner_at(Duration::from_millis(1000)),
+            '⠋'
+        );
+    }
+
+    #[test]
+    fn should_format_panel_title() {
+        assert_eq!(
+            PendingOperation::fmt_title_at(
+                "localhost:/tmp ",
+                "Loading…",
+                Duration::from_millis(250)
+            ),
+            "localhost:/tmp ⠹ Loading…"
+        );
+    }
+}
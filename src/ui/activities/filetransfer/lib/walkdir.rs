@@ -1,4 +1,360 @@
+//! ## FileTransferActivity
+//!
+//! `filetransfer_activiy` is the module which implements the Filetransfer activity, which is the main activity afterall
+
+use std::fs;
+use std::io::{self, BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, UNIX_EPOCH};
+
+use remotefs::fs::{FileType, Metadata, UnixPex};
+use remotefs::File;
+
+/// Amount of spilled results loaded back at once
+pub const SPILL_PAGE_SIZE: usize = 1024;
+
 #[derive(Debug, Default)]
 pub struct WalkdirStates {
     pub aborted: bool,
 }
+
+/// Files collected by a walkdir. Once `threshold` files are held in memory, the next ones are spilled to a
+/// temporary file, to be loaded back on demand
+#[derive(Debug)]
+pub struct WalkdirResults {
+    files: Vec<File>,
+    /// `0` means that files are never spilled
+    threshold: usize,
+    /// Directory where the spill file is created; if `None`, files are never spilled
+    spill_dir: Option<PathBuf>,
+    spill: Option<BufWriter<fs::File>>,
+    spilled: usize,
+}
+
+impl WalkdirResults {
+    pub fn new(threshold: usize, spill_dir: Option<&Path>) -> Self {
+        Self {
+            files: Vec::with_capacity(threshold.clamp(1, 32_768)),
+            threshold,
+            spill_dir: spill_dir.map(|x| x.to_path_buf()),
+            spill: None,
+            spilled: 0,
+        }
+    }
+
+    /// Add `files` to the results, spilling them once the threshold has been reached
+    pub fn extend(&mut self, files: impl IntoIterator<Item = File>) -> io::Result<()> {
+        for file in files {
+            match self.spill_dir.as_deref() {
+                Some(dir) if self.threshold > 0 && self.files.len() >= self.threshold => {
+                    if self.spill.is_none() {
+                        self.spill = Some(BufWriter::new(tempfile::tempfile_in(dir)?));
+                    }
+                    write_file(self.spill.as_mut().unwrap(), &file)?;
+                    self.spilled += 1;
+                }
+                _ => self.files.push(file),
+            }
+        }
+        Ok(())
+    }
+
+    /// Total amount of files, including the spilled ones
+    pub fn len(&self) -> usize {
+        self.files.len() + self.spilled
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Amount of files spilled to disk
+    #[cfg(test)]
+    pub fn spilled(&self) -> usize {
+        self.spilled
+    }
+
+    /// Split the results into the files held in memory and the spilled ones, if any
+    pub fn into_parts(self) -> io::Result<(Vec<File>, Option<SpilledFiles>)> {
+        let spilled = match self.spill {
+            None => None,
+            Some(writer) => {
+                let mut file = writer.into_inner().map_err(|err| err.into_error())?;
+                file.seek(SeekFrom::Start(0))?;
+                Some(SpilledFiles {
+                    reader: BufReader::new(file),
+                    remaining: self.spilled,
+                })
+            }
+        };
+        Ok((self.files, spilled))
+    }
+
+    /// Iterate over all the files, in the order they have been collected, reading the spilled ones back
+    pub fn into_files(self) -> io::Result<impl Iterator<Item = io::Result<File>>> {
+        let (files, spilled) = self.into_parts()?;
+        Ok(files
+            .into_iter()
+            .map(Ok)
+            .chain(spilled.into_iter().flatten()))
+    }
+}
+
+/// Files spilled to disk by [`WalkdirResults`], read back in the order they've been collected.
+/// The file is removed as soon as this is dropped
+#[derive(Debug)]
+pub struct SpilledFiles {
+    reader: BufReader<fs::File>,
+    remaining: usize,
+}
+
+impl SpilledFiles {
+    /// Amount of files not read yet
+    pub fn remaining(&self) -> usize {
+        self.remaining
+    }
+
+    /// Read up to `amount` files
+    pub fn read(&mut self, amount: usize) -> io::Result<Vec<File>> {
+        self.by_ref().take(amount).collect()
+    }
+}
+
+impl Iterator for SpilledFiles {
+    type Item = io::Result<File>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        match read_file(&mut self.reader) {
+            Ok(file) => {
+                self.remaining -= 1;
+                Some(Ok(file))
+            }
+            Err(err) => {
+                // the rest of the file can't be trusted
+                self.remaining = 0;
+                Some(Err(err))
+            }
+        }
+    }
+}
+
+// -- serialization
+
+fn write_file(w: &mut impl Write, file: &File) -> io::Result<()> {
+    write_bytes(w, &path_to_bytes(file.path()))?;
+    let metadata = file.metadata();
+    w.write_all(&[match metadata.file_type {
+        FileType::Directory => 0,
+        FileType::File => 1,
+        FileType::Symlink => 2,
+    }])?;
+    w.write_all(&metadata.size.to_le_bytes())?;
+    for time in [metadata.modified, metadata.accessed, metadata.created] {
+        match time.and_then(|x| x.duration_since(UNIX_EPOCH).ok()) {
+            None => w.write_all(&[0])?,
+            Some(time) => {
+                w.write_all(&[1])?;
+                w.write_all(&time.as_secs().to_le_bytes())?;
+                w.write_all(&time.subsec_nanos().to_le_bytes())?;
+            }
+        }
+    }
+    match metadata.symlink.as_deref() {
+        None => w.write_all(&[0])?,
+        Some(symlink) => {
+            w.write_all(&[1])?;
+            write_bytes(w, &path_to_bytes(symlink))?;
+        }
+    }
+    for value in [metadata.mode.map(u32::from), metadata.uid, metadata.gid] {
+        match value {
+            None => w.write_all(&[0])?,
+            Some(value) => {
+                w.write_all(&[1])?;
+                w.write_all(&value.to_le_bytes())?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn read_file(r: &mut impl Read) -> io::Result<File> {
+    let path = bytes_to_path(read_bytes(r)?);
+    let mut metadata = Metadata::default().file_type(match read_u8(r)? {
+        0 => FileType::Directory,
+        1 => FileType::File,
+        2 => FileType::Symlink,
+        _ => return Err(io::Error::new(io::ErrorKind::InvalidData, "bad file type")),
+    });
+    metadata.size = u64::from_le_bytes(read_array(r)?);
+    let mut times = [None; 3];
+    for time in times.iter_mut() {
+        if read_u8(r)? == 1 {
+            let secs = u64::from_le_bytes(read_array(r)?);
+            let nanos = u32::from_le_bytes(read_array(r)?);
+            *time = Some(UNIX_EPOCH + Duration::new(secs, nanos));
+        }
+    }
+    [metadata.modified, metadata.accessed, metadata.created] = times;
+    if read_u8(r)? == 1 {
+        metadata.symlink = Some(bytes_to_path(read_bytes(r)?));
+    }
+    let mut values = [None; 3];
+    for value in values.iter_mut() {
+        if read_u8(r)? == 1 {
+            *value = Some(u32::from_le_bytes(read_array(r)?));
+        }
+    }
+    let [mode, uid, gid] = values;
+    metadata.mode = mode.map(UnixPex::from);
+    metadata.uid = uid;
+    metadata.gid = gid;
+    Ok(File { path, metadata })
+}
+
+fn write_bytes(w: &mut impl Write, bytes: &[u8]) -> io::Result<()> {
+    w.write_all(&(bytes.len() as u32).to_le_bytes())?;
+    w.write_all(bytes)
+}
+
+fn read_bytes(r: &mut impl Read) -> io::Result<Vec<u8>> {
+    let len = u32::from_le_bytes(read_array(r)?) as usize;
+    let mut bytes = vec![0; len];
+    r.read_exact(&mut bytes)?;
+    Ok(bytes)
+}
+
+fn read_u8(r: &mut impl Read) -> io::Result<u8> {
+    read_array::<1>(r).map(|[x]| x)
+}
+
+fn read_array<const N: usize>(r: &mut impl Read) -> io::Result<[u8; N]> {
+    let mut buf = [0; N];
+    r.read_exact(&mut buf)?;
+    Ok(buf)
+}
+
+#[cfg(posix)]
+fn path_to_bytes(path: &Path) -> Vec<u8> {
+    use std::os::unix::ffi::OsStrExt;
+    path.as_os_str().as_bytes().to_vec()
+}
+
+#[cfg(posix)]
+fn bytes_to_path(bytes: Vec<u8>) -> PathBuf {
+    use std::os::unix::ffi::OsStringExt;
+    PathBuf::from(std::ffi::OsString::from_vec(bytes))
+}
+
+#[cfg(win)]
+fn path_to_bytes(path: &Path) -> Vec<u8> {
+    path.to_string_lossy().into_owned().into_bytes()
+}
+
+#[cfg(win)]
+fn bytes_to_path(bytes: Vec<u8>) -> PathBuf {
+    PathBuf::from(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+#[cfg(test)]
+mod test {
+
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    use super::*;
+
+    fn file(i: usize) -> File {
+        File {
+            path: PathBuf::from(format!("/home/omar/docs/file-{i}.txt")),
+            metadata: Metadata::default()
+                .file_type(FileType::File)
+                .size(i as u64)
+                .modified(UNIX_EPOCH + Duration::new(1_700_000_000 + i as u64, 42))
+                .mode(UnixPex::from(0o644))
+                .uid(1000),
+        }
+    }
+
+    #[test]
+    fn should_keep_results_in_memory_below_threshold() {
+        let dir = TempDir::new().unwrap();
+        let mut results = WalkdirResults::new(100, Some(dir.path()));
+        results.extend((0..100).map(file)).unwrap();
+        assert_eq!(results.len(), 100);
+        assert_eq!(results.spilled(), 0);
+        let (files, spilled) = results.into_parts().unwrap();
+        assert_eq!(files.len(), 100);
+        assert!(spilled.is_none());
+        // never spill without a directory or with threshold 0
+        let mut results = WalkdirResults::new(10, None);
+        results.extend((0..100).map(file)).unwrap();
+        assert_eq!(results.spilled(), 0);
+        let mut results = WalkdirResults::new(0, Some(dir.path()));
+        results.extend((0..100).map(file)).unwrap();
+        assert_eq!(results.spilled(), 0);
+    }
+
+    #[test]
+    fn should_spill_and_read_back_results() {
+        let dir = TempDir::new().unwrap();
+        let mut results = WalkdirResults::new(1000, Some(dir.path()));
+        for chunk in (0..250_000).collect::<Vec<usize>>().chunks(4096) {
+            results.extend(chunk.iter().copied().map(file)).unwrap();
+        }
+        assert_eq!(results.len(), 250_000);
+        assert_eq!(results.spilled(), 249_000);
+        let (files, spilled) = results.into_parts().unwrap();
+        assert_eq!(files.len(), 1000);
+        let mut spilled = spilled.unwrap();
+        assert_eq!(spilled.remaining(), 249_000);
+        let page = spilled.read(SPILL_PAGE_SIZE).unwrap();
+        assert_eq!(page.len(), SPILL_PAGE_SIZE);
+        assert_eq!(page[0], file(1000));
+        assert_eq!(spilled.remaining(), 249_000 - SPILL_PAGE_SIZE);
+        let rest: Vec<File> = spilled.by_ref().map(|x| x.unwrap()).collect();
+        assert_eq!(rest.len(), 249_000 - SPILL_PAGE_SIZE);
+        assert_eq!(rest.last().unwrap(), &file(249_999));
+        assert_eq!(spilled.remaining(), 0);
+        assert!(spilled.read(SPILL_PAGE_SIZE).unwrap().is_empty());
+    }
+
+    #[test]
+    fn should_iterate_over_all_results() {
+        let dir = TempDir::new().unwrap();
+        let mut results = WalkdirResults::new(3, Some(dir.path()));
+        results.extend((0..10).map(file)).unwrap();
+        let files: Vec<File> = results.into_files().unwrap().map(|x| x.unwrap()).collect();
+        assert_eq!(files, (0..10).map(file).collect::<Vec<File>>());
+    }
+
+    #[test]
+    fn should_serialize_metadata() {
+        let files = [
+            File {
+                path: PathBuf::from("/tmp/link"),
+                metadata: Metadata::default()
+                    .file_type(FileType::Symlink)
+                    .symlink("/tmp/target")
+                    .accessed(UNIX_EPOCH + Duration::from_secs(10))
+                    .created(UNIX_EPOCH + Duration::from_secs(20))
+                    .gid(100),
+            },
+            File {
+                path: PathBuf::from("/tmp/dir with spaces/àèì"),
+                metadata: Metadata::default().file_type(FileType::Directory),
+            },
+        ];
+        let mut buf = Vec::new();
+        for file in files.iter() {
+            write_file(&mut buf, file).unwrap();
+        }
+        let mut reader = buf.as_slice();
+        assert_eq!(read_file(&mut reader).unwrap(), files[0]);
+        assert_eq!(read_file(&mut reader).unwrap(), files[1]);
+        assert!(read_file(&mut reader).is_err());
+    }
+}
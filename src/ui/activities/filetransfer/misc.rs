@@ -1,22 +1,31 @@
 use std::env;
 use std::path::{Path, PathBuf};
 
-use bytesize::ByteSize;
+use chrono::Local;
 use tuirealm::props::{
     Alignment, AttrValue, Attribute, Color, PropPayload, PropValue, TableBuilder, TextSpan,
 };
 use tuirealm::{PollStrategy, Update};
+use unicode_width::UnicodeWidthStr;
 
-use super::browser::FileExplorerTab;
-use super::{ConfigClient, FileTransferActivity, Id, LogLevel, LogRecord, TransferPayload};
-use crate::filetransfer::{HostBridgeParams, ProtocolParams};
+use super::actions::SelectedFile;
+use super::browser::{FileExplorerTab, FoundExplorerTab};
+use super::components::{
+    FooterState, FILE_LIST_PROP_LIST_INDEX, FILE_LIST_PROP_UNSELECTABLE, LOG_PROP_LIST_INDEX,
+};
+use super::lib::exec_history::ExecHistory;
+use super::lib::transfer::TransferStates;
+use super::lib::{log_buffer, schedule};
+use super::{
+    ConfigClient, FileTransferActivity, Id, LogLevel, LogRecord, TransferPayload, INPUT_POPUPS,
+};
+use crate::explorer::FileExplorer;
+use crate::filetransfer::{FileTransferProtocol, HostBridgeParams, ProtocolParams};
 use crate::system::environment;
 use crate::system::notifications::Notification;
-use crate::utils::fmt::{fmt_millis, fmt_path_elide_ex};
+use crate::utils::fmt::fmt_path_elide_ex;
 use crate::utils::path;
 
-const LOG_CAPACITY: usize = 256;
-
 impl FileTransferActivity {
     /// Call `Application::tick()` and process messages in `Update`
     pub(super) fn tick(&mut self) {
@@ -46,14 +55,14 @@ impl FileTransferActivity {
             LogLevel::Info => info!("{}", msg),
             LogLevel::Warn => warn!("{}", msg),
         }
-        // Create log record
-        let record: LogRecord = LogRecord::new(level, msg);
-        //Check if history overflows the size
-        if self.log_records.len() + 1 > LOG_CAPACITY {
-            self.log_records.pop_back(); // Start cleaning events from back
+        if matches!(level, LogLevel::Error) {
+            self.errors_logged += 1;
         }
-        // Eventually push front the new record
-        self.log_records.push_front(record);
+        // Create log record; the full message has been written to the log file
+        let msg = log_buffer::truncate_message(msg, self.config().get_log_message_max_len());
+        let record: LogRecord = LogRecord::new(level, msg);
+        // Push front the new record; the oldest ones are dropped if the buffer overflows
+        self.log_records.push(record);
         // Update log
         self.update_logbox();
         // flag redraw
@@ -68,6 +77,18 @@ impl FileTransferActivity {
         self.update_logbox();
     }
 
+    /// Append to `error`, reported by the remote client, the hint about its cause if it's a known failure
+    pub(super) fn with_remote_error_hint(&self, error: String) -> String {
+        match self
+            .context()
+            .remote_params()
+            .and_then(|params| params.protocol.error_hint(&error))
+        {
+            Some(hint) => format!("{error}: {hint}"),
+            None => error,
+        }
+    }
+
     /// Initialize configuration client if possible.
     /// This function doesn't return errors.
     pub(super) fn init_config_client() -> ConfigClient {
@@ -88,6 +109,15 @@ impl FileTransferActivity {
         }
     }
 
+    /// Load the history of the commands executed in the previous sessions from the configuration directory
+    pub(super) fn init_exec_history() -> ExecHistory {
+        let path = environment::init_config_dir()
+            .ok()
+            .flatten()
+            .map(|dir| environment::get_exec_history_path(dir.as_path()));
+        ExecHistory::load(path)
+    }
+
     /// Set text editor to use
     pub(super) fn setup_text_editor(&self) {
         env::set_var("EDITOR", self.config().get_text_editor());
@@ -106,7 +136,10 @@ impl FileTransferActivity {
     /// Get remote hostname
     pub(super) fn get_remote_hostname(&self) -> String {
         let ft_params = self.context().remote_params().unwrap();
-        self.get_hostname(&ft_params.params)
+        match ft_params.protocol {
+            FileTransferProtocol::Localhost => String::from("LOCALHOST"),
+            _ => self.get_hostname(&ft_params.params),
+        }
     }
 
     pub(super) fn get_hostbridge_hostname(&self) -> String {
@@ -137,6 +170,121 @@ impl FileTransferActivity {
         }
     }
 
+    /// Move the focus to the other panel
+    pub(super) fn change_transfer_window(&mut self) {
+        let new_tab = match self.browser.tab() {
+            FileExplorerTab::HostBridge if self.browser.found().is_some() => {
+                FileExplorerTab::FindRemote
+            }
+            FileExplorerTab::FindHostBridge | FileExplorerTab::HostBridge => {
+                FileExplorerTab::Remote
+            }
+            FileExplorerTab::Remote if self.browser.found().is_some() => {
+                FileExplorerTab::FindHostBridge
+            }
+            FileExplorerTab::FindRemote | FileExplorerTab::Remote => FileExplorerTab::HostBridge,
+        };
+        self.focus_tab(new_tab);
+    }
+
+    /// Give focus to the explorer of `tab`, which has been clicked.
+    /// The find explorer is rendered in place of the explorer of its side, which is ignored meanwhile
+    pub(super) fn focus_clicked_explorer(&mut self, tab: FileExplorerTab) {
+        let tab = match (tab, self.browser.found_tab()) {
+            (FileExplorerTab::HostBridge, Some(FoundExplorerTab::Local))
+            | (FileExplorerTab::Remote, Some(FoundExplorerTab::Remote))
+            | (FileExplorerTab::FindHostBridge | FileExplorerTab::FindRemote, None) => return,
+            (FileExplorerTab::FindHostBridge | FileExplorerTab::FindRemote, Some(found)) => {
+                match found {
+                    FoundExplorerTab::Local => FileExplorerTab::FindHostBridge,
+                    FoundExplorerTab::Remote => FileExplorerTab::FindRemote,
+                }
+            }
+            (tab, _) => tab,
+        };
+        // the explorer of the current tab may have lost the focus to the log panel
+        self.focus_tab(tab);
+    }
+
+    /// Give focus to the explorer of `tab` and make it the current tab
+    pub(super) fn focus_tab(&mut self, tab: FileExplorerTab) {
+        match tab {
+            FileExplorerTab::HostBridge => {
+                assert!(self.app.active(&Id::ExplorerHostBridge).is_ok())
+            }
+            FileExplorerTab::Remote => assert!(self.app.active(&Id::ExplorerRemote).is_ok()),
+            FileExplorerTab::FindHostBridge | FileExplorerTab::FindRemote => {
+                assert!(self.app.active(&Id::ExplorerFind).is_ok())
+            }
+        }
+        self.browser.change_tab(tab);
+    }
+
+    /// Notify the user if any of the entries in `names`, which have just been created in the working directory
+    /// of `tab` (e.g. "Created file", "Uploaded"), is filtered out from the explorer, since hidden files are not shown
+    pub(super) fn notify_hidden_entries(
+        &mut self,
+        tab: FileExplorerTab,
+        action: &str,
+        names: &[String],
+    ) {
+        let (explorer, remote) = match tab {
+            FileExplorerTab::HostBridge => (self.host_bridge(), false),
+            FileExplorerTab::Remote => (self.remote(), true),
+            FileExplorerTab::FindHostBridge | FileExplorerTab::FindRemote => return,
+        };
+        let wrkdir = explorer.wrkdir.clone();
+        let hidden: Vec<&String> = names
+            .iter()
+            .filter(|name| explorer.hides(Path::new(name.as_str())))
+            .collect();
+        // Report only entries which actually exist (e.g. the transfer could have been cancelled)
+        let hidden: Vec<String> = hidden
+            .into_iter()
+            .filter(|name| {
+                let path = path::absolutize(wrkdir.as_path(), Path::new(name.as_str()));
+                match remote {
+                    true => self.remote_file_exists(path.as_path()),
+                    false => self.host_bridge_file_exists(path.as_path()),
+                }
+            })
+            .map(|name| format!("\"{name}\""))
+            .collect();
+        if hidden.is_empty() {
+            return;
+        }
+        let msg = format!(
+            "{action} {} (hidden files are currently not shown — press <A>)",
+            hidden.join(", ")
+        );
+        self.log(LogLevel::Info, msg.clone());
+        self.update_logbox();
+        self.mount_info(msg);
+    }
+
+    /// Returns whether listing directories on the explorer of `tab` is expensive,
+    /// so that walking through them (e.g. to count their entries) should be avoided
+    pub(super) fn is_listing_expensive(&self, tab: FileExplorerTab) -> bool {
+        let protocol = match tab {
+            FileExplorerTab::HostBridge | FileExplorerTab::FindHostBridge => {
+                match self.context().host_bridge_params() {
+                    Some(HostBridgeParams::Remote(protocol, _)) => *protocol,
+                    _ => return false,
+                }
+            }
+            FileExplorerTab::Remote | FileExplorerTab::FindRemote => {
+                match self.context().remote_params() {
+                    Some(params) => params.protocol,
+                    None => return false,
+                }
+            }
+        };
+        matches!(
+            protocol,
+            FileTransferProtocol::AwsS3 | FileTransferProtocol::Kube | FileTransferProtocol::WebDAV
+        )
+    }
+
     /// Get connection message to show to client
     pub(super) fn get_connection_msg(params: &ProtocolParams) -> String {
         match params {
@@ -148,13 +296,22 @@ impl FileTransferActivity {
                 format!("Connecting to {}:{}…", params.address, params.port)
             }
             ProtocolParams::AwsS3(params) => {
+                let style = match params.new_path_style {
+                    true => "path style",
+                    false => "virtual-hosted style",
+                };
+                let endpoint = params.endpoint_url().unwrap_or_else(|| String::from("AWS"));
                 info!(
-                    "Client is not connected to remote; connecting to {}{} ({})",
-                    params.endpoint.as_deref().unwrap_or(""),
+                    "Client is not connected to remote; connecting to {} at {} ({}, {})",
                     params.bucket_name,
-                    params.region.as_deref().unwrap_or("custom")
+                    endpoint,
+                    params.region.as_deref().unwrap_or("custom"),
+                    style
                 );
-                format!("Connecting to {}…", params.bucket_name)
+                format!(
+                    "Connecting to {} at {endpoint} ({style})…",
+                    params.bucket_name
+                )
             }
             ProtocolParams::Kube(params) => {
                 let namespace = params.namespace.as_deref().unwrap_or("default");
@@ -182,70 +339,251 @@ impl FileTransferActivity {
     /// The notification is sent only when these conditions are satisfied:
     ///
     /// - notifications are enabled
-    /// - transfer size is greater or equal than notification threshold
+    /// - payload size is greater or equal than notification threshold, or the transfer lasted at least the minimum duration
+    /// - the terminal is not focused, if `notification_unfocused_only` is set
     pub(super) fn notify_transfer_completed(&self, payload: &TransferPayload) {
-        if self.config().get_notifications()
-            && self.config().get_notification_threshold() as usize <= self.transfer.full_size()
-        {
+        if self.config().get_terminal_bell().on_completion() {
+            self.ring_terminal_bell();
+        }
+        if self.should_notify_transfer(&self.transfer) {
             Notification::transfer_completed(self.transfer_completed_msg(payload));
         }
     }
 
+    /// Send the notification regarding the terminated transfer of the queued entry `name`, as for the transfers
+    /// in foreground; the progress of the queued entry is in `transfer`
+    pub(super) fn notify_queue_item_finished(
+        &self,
+        name: &str,
+        transfer: &TransferStates,
+        result: &Result<(), String>,
+    ) {
+        let bell = self.config().get_terminal_bell();
+        if (result.is_ok() && bell.on_completion()) || (result.is_err() && bell.on_error()) {
+            self.ring_terminal_bell();
+        }
+        if !self.should_notify_transfer(transfer) {
+            return;
+        }
+        let summary = transfer.summary();
+        match result {
+            Ok(()) => Notification::transfer_completed(format!(
+                "\"{name}\" has been successfully transferred ({summary})"
+            )),
+            Err(err) if transfer.aborted() || summary.files == 0 => {
+                Notification::transfer_error(err)
+            }
+            Err(err) => Notification::transfer_completed_with_errors(format!(
+                "{summary}; first error: {err}"
+            )),
+        }
+    }
+
+    /// Record the outcome of the connection to the remote for the bookmark the remote params have been loaded from, if any.
+    /// A failure is recorded with its error, while a successful connection clears the last failure
+    pub(super) fn update_bookmark_connection_outcome(&mut self, error: Option<&str>) {
+        let Some(name) = self.context().remote_bookmark().map(str::to_string) else {
+            return;
+        };
+        if let Some(client) = self.context_mut().bookmarks_client_mut() {
+            match error {
+                Some(error) => client.set_connection_failure(&name, error),
+                None => client.clear_connection_failure(&name),
+            }
+        }
+    }
+
+    /// Save the credentials of the current remote params to the bookmark used to connect
+    pub(super) fn update_bookmark_credentials(&mut self) {
+        let Some(name) = self.context().remote_bookmark().map(str::to_string) else {
+            return;
+        };
+        let params = self.context().remote_params().unwrap().clone();
+        let result = match self.context_mut().bookmarks_client_mut() {
+            Some(client) => {
+                client.add_bookmark(name.as_str(), params, true);
+                client.write_bookmarks()
+            }
+            None => return,
+        };
+        match result {
+            Ok(()) => self.log(
+                LogLevel::Info,
+                format!("Saved the new credentials to bookmark \"{name}\""),
+            ),
+            Err(err) => self.log_and_alert(
+                LogLevel::Error,
+                format!("Could not save bookmark \"{name}\": {err}"),
+            ),
+        }
+    }
+
     /// Send notification regarding transfer error
+    /// If some files have been transferred anyway, the transfer is notified as completed with the amount of failures,
+    /// otherwise the first error is notified as a failed transfer.
     /// The notification is sent only when these conditions are satisfied:
     ///
     /// - notifications are enabled
-    /// - transfer size is greater or equal than notification threshold
-    pub(super) fn notify_transfer_error(&self, msg: &str) {
-        if self.config().get_notifications()
-            && self.config().get_notification_threshold() as usize <= self.transfer.full_size()
-        {
-            Notification::transfer_error(msg);
+    /// - payload size is greater or equal than notification threshold, or the transfer lasted at least the minimum duration
+    /// - the terminal is not focused, if `notification_unfocused_only` is set
+    pub(super) fn notify_transfer_error(&mut self, msg: &str) {
+        if self.config().get_terminal_bell().on_error() {
+            self.ring_terminal_bell();
+        }
+        let aborted = self.transfer.aborted();
+        // transfers which stopped at their first failure haven't counted it yet
+        if !aborted && self.transfer.first_error().is_none() {
+            self.transfer.count_failures(1, msg);
+        }
+        if !self.should_notify_transfer(&self.transfer) {
+            return;
+        }
+        let summary = self.transfer.summary();
+        let first_error = self.transfer.first_error().unwrap_or(msg);
+        match aborted || summary.files == 0 {
+            true => Notification::transfer_error(first_error),
+            false => Notification::transfer_completed_with_errors(format!(
+                "{summary}; first error: {first_error}"
+            )),
+        }
+    }
+
+    fn should_notify_transfer(&self, transfer: &TransferStates) -> bool {
+        if !self.config().get_notifications() {
+            return false;
+        }
+        // the focus is unknown until the terminal reports it
+        if self.config().get_notification_unfocused_only() && self.terminal_focused == Some(true) {
+            debug!("transfer notification suppressed: terminal is focused");
+            return false;
+        }
+        transfer.should_notify(
+            self.config().get_notification_threshold(),
+            self.config().get_notification_min_duration(),
+        )
+    }
+
+    /// Ring the terminal bell, unless the user is typing into an input popup
+    fn ring_terminal_bell(&self) {
+        if self.is_input_popup_active() {
+            debug!("terminal bell suppressed: input popup is active");
+            return;
         }
+        Notification::terminal_bell();
+    }
+
+    /// Returns whether the currently focused component is an input popup
+    fn is_input_popup_active(&self) -> bool {
+        self.app.focus().is_some_and(|id| INPUT_POPUPS.contains(id))
     }
 
     fn transfer_completed_msg(&self, payload: &TransferPayload) -> String {
-        let transfer_stats = format!(
-            "took {} seconds; at {}/s",
-            fmt_millis(self.transfer.partial.started().elapsed()),
-            ByteSize(self.transfer.partial.calc_bytes_per_second()),
-        );
+        let summary = self.transfer.summary();
         match payload {
-            TransferPayload::File(file) => {
+            TransferPayload::File(file) | TransferPayload::Resume(file) => {
                 format!(
-                    "File \"{}\" has been successfully transferred ({})",
-                    file.name(),
-                    transfer_stats
+                    "File \"{}\" has been successfully transferred ({summary})",
+                    file.name()
                 )
             }
             TransferPayload::Any(entry) => {
                 format!(
-                    "\"{}\" has been successfully transferred ({})",
-                    entry.name(),
-                    transfer_stats
+                    "\"{}\" has been successfully transferred ({summary})",
+                    entry.name()
                 )
             }
             TransferPayload::Many(entries) => {
                 format!(
-                    "{} files has been successfully transferred ({})",
-                    entries.len(),
-                    transfer_stats
+                    "{} entries have been successfully transferred ({summary})",
+                    entries.len()
                 )
             }
         }
     }
 
+    /// Get the width of the content of an explorer panel
+    pub(super) fn explorer_width(&mut self) -> usize {
+        self.context_mut()
+            .terminal()
+            .raw()
+            .size()
+            .map(|x| (x.width / 2).saturating_sub(2))
+            .unwrap_or(0) as usize
+    }
+
+    /// Get the full name of the entry highlighted in the current explorer, if it's elided in the explorer
+    pub(super) fn highlighted_elided_name(&mut self) -> Option<String> {
+        let width = self.explorer_width();
+        let (explorer, selected) = match self.browser.tab() {
+            FileExplorerTab::HostBridge => (self.host_bridge(), self.get_local_selected_entries()),
+            FileExplorerTab::Remote => (self.remote(), self.get_remote_selected_entries()),
+            FileExplorerTab::FindHostBridge | FileExplorerTab::FindRemote => {
+                (self.found()?, self.get_found_selected_entries())
+            }
+        };
+        match selected {
+            SelectedFile::One(entry) if explorer.is_name_elided(&entry, width) => {
+                Some(entry.name())
+            }
+            _ => None,
+        }
+    }
+
+    /// Format again the entries of the explorers to fit the current width, without reloading the directories
+    pub(super) fn refit_file_lists(&mut self) {
+        let width = self.explorer_width();
+        let host_bridge: Vec<Vec<TextSpan>> = self.file_list_rows(self.host_bridge(), width);
+        let remote: Vec<Vec<TextSpan>> = self.file_list_rows(self.remote(), width);
+        assert!(self
+            .app
+            .attr(
+                &Id::ExplorerHostBridge,
+                Attribute::Content,
+                AttrValue::Table(host_bridge)
+            )
+            .is_ok());
+        assert!(self
+            .app
+            .attr(
+                &Id::ExplorerRemote,
+                Attribute::Content,
+                AttrValue::Table(remote)
+            )
+            .is_ok());
+        if self.found().is_some() {
+            self.update_find_list();
+        }
+    }
+
+    /// Make the rows of the file list of `explorer`, colored by file type
+    fn file_list_rows(&self, explorer: &FileExplorer, width: usize) -> Vec<Vec<TextSpan>> {
+        let theme = self.theme();
+        explorer
+            .iter_files()
+            .map(
+                |x| vec![TextSpan::from(explorer.fmt_file(x, width)).fg(theme.explorer_file_fg(x))],
+            )
+            .collect()
+    }
+
+    /// Make the attribute with the indexes of the files of `explorer` which don't match its filter, which are
+    /// listed to browse them, but skipped when selecting all the files
+    fn unselectable_rows(explorer: &FileExplorer) -> AttrValue {
+        AttrValue::Payload(PropPayload::Vec(
+            explorer
+                .iter_files()
+                .enumerate()
+                .filter(|(_, x)| !explorer.matches_filter(x))
+                .map(|(idx, _)| PropValue::Usize(idx))
+                .collect(),
+        ))
+    }
+
     /// Update host bridge file list
     pub(super) fn update_host_bridge_filelist(&mut self) {
         self.reload_host_bridge_dir();
         // Get width
-        let width = self
-            .context_mut()
-            .terminal()
-            .raw()
-            .size()
-            .map(|x| (x.width / 2) - 2)
-            .unwrap_or(0) as usize;
+        let width = self.explorer_width();
         let hostname = self.get_hostbridge_hostname();
 
         let hostname: String = format!(
@@ -253,14 +591,10 @@ impl FileTransferActivity {
             fmt_path_elide_ex(
                 self.host_bridge().wrkdir.as_path(),
                 width,
-                hostname.len() + 3
+                hostname.width() + 3
             ) // 3 because of '/…/'
         );
-        let files: Vec<Vec<TextSpan>> = self
-            .host_bridge()
-            .iter_files()
-            .map(|x| vec![TextSpan::from(self.host_bridge().fmt_file(x))])
-            .collect();
+        let files: Vec<Vec<TextSpan>> = self.file_list_rows(self.host_bridge(), width);
         // Update content and title
         assert!(self
             .app
@@ -270,6 +604,15 @@ impl FileTransferActivity {
                 AttrValue::Table(files)
             )
             .is_ok());
+        let unselectable = Self::unselectable_rows(self.host_bridge());
+        assert!(self
+            .app
+            .attr(
+                &Id::ExplorerHostBridge,
+                Attribute::Custom(FILE_LIST_PROP_UNSELECTABLE),
+                unselectable
+            )
+            .is_ok());
         assert!(self
             .app
             .attr(
@@ -278,19 +621,37 @@ impl FileTransferActivity {
                 AttrValue::Title((hostname, Alignment::Left))
             )
             .is_ok());
+        // the amount of filtered entries is reported in the status bar
+        if self.host_bridge().filter().is_some() {
+            self.refresh_local_status_bar();
+        }
+    }
+
+    /// Highlight the file named `name` in the explorer of the current tab, if it is listed
+    pub(super) fn highlight_file(&mut self, name: &str) {
+        let (explorer, id) = match self.browser.tab() {
+            FileExplorerTab::HostBridge => (self.host_bridge(), Id::ExplorerHostBridge),
+            FileExplorerTab::Remote => (self.remote(), Id::ExplorerRemote),
+            FileExplorerTab::FindHostBridge | FileExplorerTab::FindRemote => return,
+        };
+        let index = explorer.iter_files().position(|x| x.name() == name);
+        if let Some(index) = index {
+            assert!(self
+                .app
+                .attr(
+                    &id,
+                    Attribute::Custom(FILE_LIST_PROP_LIST_INDEX),
+                    AttrValue::Length(index)
+                )
+                .is_ok());
+        }
     }
 
     /// Update remote file list
     pub(super) fn update_remote_filelist(&mut self) {
         self.reload_remote_dir();
 
-        let width = self
-            .context_mut()
-            .terminal()
-            .raw()
-            .size()
-            .map(|x| (x.width / 2) - 2)
-            .unwrap_or(0) as usize;
+        let width = self.explorer_width();
         let hostname = self.get_remote_hostname();
         let hostname: String = format!(
             "{}:{} ",
@@ -298,14 +659,10 @@ impl FileTransferActivity {
             fmt_path_elide_ex(
                 self.remote().wrkdir.as_path(),
                 width,
-                hostname.len() + 3 // 3 because of '/…/'
+                hostname.width() + 3 // 3 because of '/…/'
             )
         );
-        let files: Vec<Vec<TextSpan>> = self
-            .remote()
-            .iter_files()
-            .map(|x| vec![TextSpan::from(self.remote().fmt_file(x))])
-            .collect();
+        let files: Vec<Vec<TextSpan>> = self.file_list_rows(self.remote(), width);
         // Update content and title
         assert!(self
             .app
@@ -315,6 +672,15 @@ impl FileTransferActivity {
                 AttrValue::Table(files)
             )
             .is_ok());
+        let unselectable = Self::unselectable_rows(self.remote());
+        assert!(self
+            .app
+            .attr(
+                &Id::ExplorerRemote,
+                Attribute::Custom(FILE_LIST_PROP_UNSELECTABLE),
+                unselectable
+            )
+            .is_ok());
         assert!(self
             .app
             .attr(
@@ -323,16 +689,31 @@ impl FileTransferActivity {
                 AttrValue::Title((hostname, Alignment::Left))
             )
             .is_ok());
+        // the amount of filtered entries is reported in the status bar
+        if self.remote().filter().is_some() {
+            self.refresh_remote_status_bar();
+        }
     }
 
     /// Update log box
     pub(super) fn update_logbox(&mut self) {
         let mut table: TableBuilder = TableBuilder::default();
-        for (idx, record) in self.log_records.iter().enumerate() {
+        let mut matches = Vec::new();
+        let records = self
+            .log_records
+            .iter()
+            .filter(|x| self.log_filter.level.matches(&x.level));
+        for (idx, record) in records.enumerate() {
             // Add row if not first row
             if idx > 0 {
                 table.add_row();
             }
+            // Highlight the records matching the search
+            let mut msg = TextSpan::from(record.msg.as_str());
+            if self.log_filter.is_match(record.msg.as_str()) {
+                msg = msg.reversed();
+                matches.push(idx);
+            }
             let fg = match record.level {
                 LogLevel::Error => Color::Red,
                 LogLevel::Warn => Color::Yellow,
@@ -344,23 +725,11 @@ impl FileTransferActivity {
                     record.time.format("%Y-%m-%dT%H:%M:%S%Z")
                 )))
                 .add_col(TextSpan::from(" ["))
-                .add_col(
-                    TextSpan::new(
-                        format!(
-                            "{:5}",
-                            match record.level {
-                                LogLevel::Error => "ERROR",
-                                LogLevel::Warn => "WARN",
-                                LogLevel::Info => "INFO",
-                            }
-                        )
-                        .as_str(),
-                    )
-                    .fg(fg),
-                )
+                .add_col(TextSpan::new(format!("{:5}", record.level.name()).as_str()).fg(fg))
                 .add_col(TextSpan::from("]: "))
-                .add_col(TextSpan::from(record.msg.as_str()));
+                .add_col(msg);
         }
+        self.log_filter.set_matches(matches);
         assert!(self
             .app
             .attr(
@@ -369,6 +738,28 @@ impl FileTransferActivity {
                 AttrValue::Table(table.build())
             )
             .is_ok());
+        assert!(self
+            .app
+            .attr(
+                &Id::Log,
+                Attribute::Title,
+                AttrValue::Title((self.log_filter.title(), Alignment::Left))
+            )
+            .is_ok());
+    }
+
+    /// Move the log panel to the current match of the search
+    pub(super) fn move_logbox_to_match(&mut self) {
+        if let Some(row) = self.log_filter.current_match() {
+            assert!(self
+                .app
+                .attr(
+                    &Id::Log,
+                    Attribute::Custom(LOG_PROP_LIST_INDEX),
+                    AttrValue::Length(row)
+                )
+                .is_ok());
+        }
     }
 
     pub(super) fn update_progress_bar(&mut self, filename: String) {
@@ -416,6 +807,54 @@ impl FileTransferActivity {
                 AttrValue::Title((filename, Alignment::Center))
             )
             .is_ok());
+        self.refresh_footer_bar();
+    }
+
+    /// Get the state of the session displayed in the footer bar
+    pub(super) fn footer_state(&self) -> FooterState {
+        let transfer_rate = if self.app.mounted(&Id::ProgressBarPartial) {
+            Some(self.transfer.partial.calc_rate())
+        } else if self.transfer_queue.is_active() {
+            Some(self.transfer_queue.transfer.partial.calc_rate())
+        } else {
+            None
+        };
+        FooterState {
+            transfer_rate,
+            errors: self.errors_logged,
+            watched: match (self.fswatcher.as_ref(), self.remote_poller.as_ref()) {
+                (None, None) => None,
+                (fswatcher, poller) => Some(
+                    fswatcher
+                        .map(|x| x.watched_paths().len())
+                        .unwrap_or_default()
+                        + poller.map(|x| x.watched_paths().len()).unwrap_or_default(),
+                ),
+            },
+            scheduled: self
+                .transfer_queue
+                .scheduled()
+                .map(|(count, at)| (count, schedule::fmt_start_time(at, Local::now()))),
+            exec_unsupported: !self.is_exec_supported(self.browser.tab()),
+        }
+    }
+
+    /// Returns whether commands can be executed on the host of `tab`
+    pub(super) fn is_exec_supported(&self, tab: FileExplorerTab) -> bool {
+        let Some(context) = self.context.as_ref() else {
+            return true;
+        };
+        match tab {
+            FileExplorerTab::HostBridge => match context.host_bridge_params() {
+                Some(HostBridgeParams::Remote(protocol, _)) => protocol.supports_exec(),
+                Some(HostBridgeParams::Localhost(_)) | None => true,
+            },
+            FileExplorerTab::Remote => context
+                .remote_params()
+                .map(|params| params.protocol.supports_exec())
+                .unwrap_or(true),
+            FileExplorerTab::FindHostBridge | FileExplorerTab::FindRemote => false,
+        }
     }
 
     /// Finalize find process
@@ -429,27 +868,12 @@ impl FileTransferActivity {
             _ => FileExplorerTab::HostBridge,
         };
         // Give focus to new tab
-        match new_tab {
-            FileExplorerTab::HostBridge => {
-                assert!(self.app.active(&Id::ExplorerHostBridge).is_ok())
-            }
-            FileExplorerTab::Remote => {
-                assert!(self.app.active(&Id::ExplorerRemote).is_ok())
-            }
-            FileExplorerTab::FindHostBridge | FileExplorerTab::FindRemote => {
-                assert!(self.app.active(&Id::ExplorerFind).is_ok())
-            }
-        }
-        self.browser.change_tab(new_tab);
+        self.focus_tab(new_tab);
     }
 
     pub(super) fn update_find_list(&mut self) {
-        let files: Vec<Vec<TextSpan>> = self
-            .found()
-            .unwrap()
-            .iter_files()
-            .map(|x| vec![TextSpan::from(self.found().unwrap().fmt_file(x))])
-            .collect();
+        let width = self.explorer_width();
+        let files: Vec<Vec<TextSpan>> = self.file_list_rows(self.found().unwrap(), width);
         assert!(self
             .app
             .attr(
@@ -469,6 +893,27 @@ impl FileTransferActivity {
         }
     }
 
+    /// Refresh the status bars and the file lists after the view options of the panel of `tab` have changed.
+    /// If the view is locked, both panels are refreshed
+    pub(super) fn refresh_view_options(&mut self, tab: FileExplorerTab) {
+        let locked = self.browser.is_view_locked();
+        let host_bridge = matches!(
+            tab,
+            FileExplorerTab::HostBridge | FileExplorerTab::FindHostBridge
+        );
+        if locked || host_bridge {
+            self.refresh_local_status_bar();
+            self.update_host_bridge_filelist();
+        }
+        if locked || !host_bridge {
+            self.refresh_remote_status_bar();
+            self.update_remote_filelist();
+        }
+        if self.browser.found().is_some() && self.app.mounted(&Id::ExplorerFind) {
+            self.update_find_list();
+        }
+    }
+
     pub(super) fn update_browser_file_list_swapped(&mut self) {
         match self.browser.tab() {
             FileExplorerTab::HostBridge | FileExplorerTab::FindHostBridge => {
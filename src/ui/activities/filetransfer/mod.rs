@@ -13,16 +13,30 @@ mod update;
 mod view;
 
 // locals
-use std::collections::VecDeque;
 use std::path::PathBuf;
 use std::time::Duration;
 
 // Includes
 use chrono::{DateTime, Local};
+use components::FooterState;
 use lib::browser;
-use lib::browser::Browser;
-use lib::transfer::{TransferOpts, TransferStates};
+use lib::browser::{Browser, FileExplorerTab, PanelSide};
+use lib::clock_skew::ClockSkew;
+use lib::exec_history::ExecHistory;
+use lib::log_buffer::LogBuffer;
+use lib::log_filter::LogFilter;
+use lib::metadata::MetadataState;
+use lib::preview::Preview;
+use lib::reconnect::Reconnect;
+use lib::sync::{SyncMode, SyncState};
+use lib::transfer::{FailedTransfers, TransferOpts, TransferStates};
+use lib::undo::UndoStack;
+use lib::wait::PendingOperation;
 use lib::walkdir::WalkdirStates;
+// the transfer queue is used by the transfers run without the user interface too
+pub(crate) use lib::metadata::remote_modes;
+pub(crate) use lib::parallel::{RemoteSession, TransferDirection};
+pub(crate) use lib::queue::{QueueEvent, QueueWorker, TransferQueue};
 use remotefs::RemoteFs;
 use session::TransferPayload;
 use tempfile::TempDir;
@@ -32,53 +46,115 @@ use super::{Activity, Context, ExitReason, CROSSTERM_MAX_POLL};
 use crate::config::themes::Theme;
 use crate::explorer::{FileExplorer, FileSorting};
 use crate::filetransfer::{
-    FileTransferParams, HostBridgeBuilder, HostBridgeParams, RemoteFsBuilder,
+    FileTransferParams, FileTransferProtocol, HostBridgeBuilder, HostBridgeParams, RemoteFsBuilder,
+    SshNegotiationHandle,
 };
 use crate::host::HostBridge;
 use crate::system::config_client::ConfigClient;
-use crate::system::watcher::FsWatcher;
+use crate::system::watcher::{FsWatcher, RemotePoller};
+use crate::utils::archive::ArchiveFormat;
+use crate::utils::checksum::ChecksumAlgorithm;
+use crate::utils::tty;
+
+/// Maximum amount of records displayed in the log panel
+const LOG_CAPACITY: usize = 256;
 
 // -- components
 
 #[derive(Debug, Eq, PartialEq, Clone, Hash)]
 enum Id {
+    ArchivePopup,
+    BookmarkDirPopup,
+    ChecksumComparePopup,
+    ChecksumPopup,
     ChmodPopup,
     CopyPopup,
+    CredentialsPasswordPopup,
+    CredentialsUsernamePopup,
+    DeleteConfirmNamePopup,
     DeletePopup,
+    DirBookmarksPopup,
+    DirHistoryPopup,
     DisconnectPopup,
+    EditConflictPopup,
+    EditDiffPopup,
     ErrorPopup,
+    ExecOutputPopup,
     ExecPopup,
     ExplorerFind,
     ExplorerHostBridge,
     ExplorerRemote,
+    FailedTransfersPopup,
     FatalPopup,
+    FavoritesPopup,
     FileInfoPopup,
+    FileNameBar,
     FilterPopup,
+    FocusListener,
     FooterBar,
     GlobalListener,
     GotoPopup,
+    HostKeyFingerprintPopup,
+    HostKeyPopup,
     KeybindingsPopup,
     Log,
+    LogExportPopup,
+    LogSearchPopup,
     MkdirPopup,
     NewfilePopup,
     OpenWithPopup,
+    PreviewPopup,
     ProgressBarFull,
     ProgressBarPartial,
+    QuickCommandsPopup,
     QuitPopup,
     RenamePopup,
     ReplacePopup,
+    ReplaceRenamePopup,
     ReplacingFilesListPopup,
     SaveAsPopup,
+    ScheduleTransferPopup,
     SortingPopup,
     StatusBarHostBridge,
     StatusBarRemote,
     SymlinkPopup,
     SyncBrowsingMkdirPopup,
+    TransferConfirmPopup,
+    TransferQueuePopup,
+    TransferQueueProgressBarFull,
+    TransferQueueProgressBarPartial,
+    UpdateBookmarkPopup,
     WaitPopup,
+    WatchIgnorePopup,
     WatchedPathsList,
     WatcherPopup,
 }
 
+/// Popups into which the user types, e.g. to tell whether to ring the terminal bell
+const INPUT_POPUPS: &[Id] = &[
+    Id::ArchivePopup,
+    Id::BookmarkDirPopup,
+    Id::ChmodPopup,
+    Id::CopyPopup,
+    Id::CredentialsPasswordPopup,
+    Id::CredentialsUsernamePopup,
+    Id::DeleteConfirmNamePopup,
+    Id::ExecPopup,
+    Id::FilterPopup,
+    Id::GotoPopup,
+    Id::LogExportPopup,
+    Id::LogSearchPopup,
+    Id::MkdirPopup,
+    Id::NewfilePopup,
+    Id::OpenWithPopup,
+    Id::RenamePopup,
+    Id::ReplaceRenamePopup,
+    Id::SaveAsPopup,
+    Id::ScheduleTransferPopup,
+    Id::SymlinkPopup,
+    Id::WatchIgnorePopup,
+];
+
 #[derive(Debug, PartialEq)]
 enum Msg {
     PendingAction(PendingActionMsg),
@@ -90,93 +166,189 @@ enum Msg {
 #[derive(Debug, PartialEq)]
 enum PendingActionMsg {
     CloseReplacePopups,
+    CloseReplaceRenamePopup,
+    CloseScheduleTransferPopup,
     CloseSyncBrowsingMkdirPopup,
+    CloseEditConflictPopup,
+    CloseEditDiffPopup,
+    CloseTransferConfirmPopup,
     MakePendingDirectory,
+    OverwriteEditedFile,
+    RenamePendingFile,
+    ResumePendingFile,
+    SaveEditedFileAs,
+    SchedulePendingPayload,
+    SubmitPendingRename,
+    SubmitPendingSchedule,
     TransferPendingFile,
+    TransferPendingPayload,
+    UploadEditedFile,
 }
 
 #[derive(Debug, PartialEq)]
 enum TransferMsg {
     AbortWalkdir,
     AbortTransfer,
-    Chmod(remotefs::fs::UnixPex),
+    /// Bookmark the working directory of the current explorer under the name
+    BookmarkDirectory(String),
+    /// Apply the mode; recursively to the directories too, if set
+    Chmod(remotefs::fs::UnixPex, bool),
+    CompareChecksums,
+    ComputeChecksum(ChecksumAlgorithm),
     CopyFileTo(String),
+    CopyRemoteUrl,
+    /// Compress the selected entries into the named archive
+    CreateArchive(String, ArchiveFormat),
     CreateSymlink(String),
+    CutFiles,
     DeleteFile,
+    DownloadFavorite(usize),
+    DuplicateFiles,
+    /// Put the selected entries in the transfer queue
+    EnqueueTransfer,
     EnterDirectory,
     ExecuteCmd(String),
+    ExecuteQuickCommand(usize),
+    ExecuteRawCmd(String),
+    /// Export the log records to the file at the path
+    ExportLog(String),
+    ExtractArchive,
     GoTo(String),
+    GoToClipboardPath,
+    GoToDirBookmark(usize),
+    GoToFavorite(usize),
+    GoToHistoryEntry(usize),
     GoToParentDirectory,
     GoToPreviousDirectory,
     InitFuzzySearch,
+    LoadMorePreview,
     Mkdir(String),
     NewFile(String),
+    OpenFavorite(usize),
+    /// Enter the highlighted directory or open the highlighted file
+    OpenEntry,
     OpenFile,
     OpenFileWith(String),
+    OpenRemoteUrl,
     OpenTextFile,
+    PasteFiles,
+    PreviewFile,
     ReloadDir,
+    RemoveDirBookmark(usize),
+    RemoveFavorite(usize),
+    RemoveQueueItem(usize),
     RenameFile(String),
     RescanGotoFiles(PathBuf),
+    RetryFailedTransfers,
     SaveFileAs(String),
+    SearchContent(String),
+    SyncTransfer(SyncMode),
+    ToggleFavorite,
     ToggleWatch,
     ToggleWatchFor(usize),
     TransferFile,
+    Undo,
+    WatchPath(String),
 }
 
 #[derive(Debug, PartialEq)]
 enum UiMsg {
+    AcceptHostKey,
     ChangeFileSorting(FileSorting),
     ChangeTransferWindow,
+    CloseArchivePopup,
+    CloseBookmarkDirPopup,
+    CloseChecksumComparePopup,
+    CloseChecksumPopup,
     CloseChmodPopup,
     CloseCopyPopup,
+    CloseCredentialsPopup,
     CloseDeletePopup,
+    CloseDirBookmarksPopup,
+    CloseDirHistoryPopup,
     CloseDisconnectPopup,
     CloseErrorPopup,
+    CloseExecOutputPopup,
     CloseExecPopup,
+    CloseFailedTransfersPopup,
     CloseFatalPopup,
+    CloseFavoritesPopup,
     CloseFileInfoPopup,
     CloseFileSortingPopup,
     CloseFilterPopup,
     CloseFindExplorer,
     CloseGotoPopup,
     CloseKeybindingsPopup,
+    CloseLogExportPopup,
+    CloseLogSearchPopup,
     CloseMkdirPopup,
     CloseNewFilePopup,
     CloseOpenWithPopup,
+    ClosePreviewPopup,
+    CloseQuickCommandsPopup,
     CloseQuitPopup,
     CloseRenamePopup,
     CloseSaveAsPopup,
     CloseSymlinkPopup,
+    CloseTransferQueuePopup,
+    CloseUpdateBookmarkPopup,
+    CloseWatchIgnorePopup,
     CloseWatchedPathsList,
     CloseWatcherPopup,
+    CopyToClipboard(String),
+    CredentialsPopupTabbed,
+    CycleLogLevelFilter,
     Disconnect,
     FilterFiles(String),
+    /// Give the focus to the explorer which has been clicked
+    FocusExplorer(FileExplorerTab),
+    FocusPanel(PanelSide),
     FuzzySearch(String),
+    LoadMoreFound,
+    /// Move to the next match of the log search if true, otherwise to the previous one
+    MoveLogMatch(bool),
     LogBackTabbed,
+    ParkSession,
     Quit,
+    RejectHostKey,
     ReplacePopupTabbed,
+    RetryWithCredentials,
+    SearchLog(String),
+    ShowArchivePopup,
+    ShowBookmarkDirPopup,
     ShowChmodPopup,
     ShowCopyPopup,
     ShowDeletePopup,
+    ShowDirBookmarksPopup,
+    ShowDirHistoryPopup,
     ShowDisconnectPopup,
     ShowExecPopup,
+    ShowFavoritesPopup,
     ShowFileInfoPopup,
     ShowFileSortingPopup,
     ShowFilterPopup,
     ShowGotoPopup,
     ShowKeybindingsPopup,
+    ShowLogExportPopup,
     ShowLogPanel,
+    ShowLogSearchPopup,
     ShowMkdirPopup,
     ShowNewFilePopup,
     ShowOpenWithPopup,
+    ShowQuickCommandsPopup,
     ShowQuitPopup,
     ShowRenamePopup,
     ShowSaveAsPopup,
     ShowSymlinkPopup,
+    ShowTransferQueuePopup,
     ShowWatchedPathsList,
     ShowWatcherPopup,
+    Suspend,
+    SwapPanels,
+    TerminalFocusChanged(bool),
     ToggleHiddenFiles,
     ToggleSyncBrowsing,
+    UpdateBookmarkCredentials,
     WindowResized,
 }
 
@@ -194,6 +366,23 @@ struct LogRecord {
     pub msg: String,
 }
 
+impl LogLevel {
+    /// Name of the level, as displayed in the log panel
+    fn name(&self) -> &'static str {
+        match self {
+            LogLevel::Error => "ERROR",
+            LogLevel::Warn => "WARN",
+            LogLevel::Info => "INFO",
+        }
+    }
+}
+
+impl AsRef<str> for LogRecord {
+    fn as_ref(&self) -> &str {
+        self.msg.as_str()
+    }
+}
+
 impl LogRecord {
     /// Instantiates a new LogRecord
     pub fn new(level: LogLevel, msg: String) -> LogRecord {
@@ -219,22 +408,66 @@ pub struct FileTransferActivity {
     host_bridge: Box<dyn HostBridge>,
     /// Remote host client
     client: Box<dyn RemoteFs>,
+    /// Methods negotiated by the remote client, if it's an ssh client
+    ssh_negotiation: SshNegotiationHandle,
     /// Browser
     browser: Browser,
     /// Current log lines
-    log_records: VecDeque<LogRecord>,
+    log_records: LogBuffer<LogRecord>,
+    /// Level filter and text search of the log panel
+    log_filter: LogFilter,
     /// Fuzzy search states
     walkdir: WalkdirStates,
     /// Transfer states
     transfer: TransferStates,
+    /// Transfers run in background
+    transfer_queue: TransferQueue,
+    /// Entries which couldn't be transferred by the last transfer, which can be retried
+    failed_transfers: Option<FailedTransfers>,
+    /// Quick operation pending on a panel
+    pending_operation: Option<PendingOperation>,
     /// Temporary directory where to store temporary stuff
     cache: Option<TempDir>,
     /// Fs watcher
     fswatcher: Option<FsWatcher>,
+    /// Poller of the remote directories whose changes are downloaded
+    remote_poller: Option<RemotePoller>,
+    /// Fs watcher changes in flight, as displayed in the status bar
+    watcher_pending: usize,
+    /// Space available on the file system of the host bridge working directory, as displayed in the status bar
+    host_bridge_free_space: Option<u64>,
     /// host bridge connected
     host_bridge_connected: bool,
     /// remote connected once
     remote_connected: bool,
+    /// Reconnection to the remote, after the connection has been lost
+    reconnect: Option<Reconnect>,
+    /// Credentials have been amended after an authentication failure; the bookmark may be updated once connected
+    credentials_amended: bool,
+    /// Jump host the credentials popup asks the credentials of; `None` if it asks the ones of the remote
+    jump_host_credentials: Option<String>,
+    /// Clock skew of the remote host
+    clock_skew: ClockSkew,
+    /// Remote files pinned as favorites; they're stored in the bookmark state, if connected through a bookmark
+    favorites: Vec<PathBuf>,
+    /// State of the transfer in progress, if it's in sync mode
+    sync: Option<SyncState>,
+    /// Metadata to apply to the destination of the current transfer
+    metadata: MetadataState,
+    /// Renames and moves which can be undone
+    undo: UndoStack,
+    /// Preview of the remote file being shown
+    preview: Option<Preview>,
+    /// Amount of errors logged in the session
+    errors_logged: usize,
+    /// State of the session displayed in the footer bar
+    footer: FooterState,
+    /// Whether the terminal has the focus; `None` until the terminal reports it
+    terminal_focused: Option<bool>,
+    /// Whether the remote session is kept connected in the context once the activity is destroyed
+    park_session: bool,
+    /// Commands executed with the exec popup
+    exec_history: ExecHistory,
 }
 
 impl FileTransferActivity {
@@ -250,6 +483,7 @@ impl FileTransferActivity {
         let host_bridge = HostBridgeBuilder::build(host_bridge_params, &config_client);
         let host_bridge_connected = host_bridge.is_localhost();
         let enable_fs_watcher = host_bridge.is_localhost();
+        let ssh_negotiation = SshNegotiationHandle::default();
         Self {
             exit_reason: None,
             context: None,
@@ -260,26 +494,54 @@ impl FileTransferActivity {
             ),
             redraw: true,
             host_bridge,
-            client: RemoteFsBuilder::build(
-                remote_params.protocol,
-                remote_params.params.clone(),
+            client: RemoteFsBuilder::build_connection(
+                remote_params,
+                ssh_negotiation.clone(),
                 &config_client,
             ),
-            browser: Browser::new(&config_client),
-            log_records: VecDeque::with_capacity(256), // 256 events is enough I guess
+            ssh_negotiation,
+            browser: Browser::new(&config_client, &remote_params.explorer),
+            log_records: LogBuffer::new(LOG_CAPACITY, config_client.get_log_max_bytes()),
+            log_filter: LogFilter::default(),
             walkdir: WalkdirStates::default(),
             transfer: TransferStates::default(),
-            cache: match TempDir::new() {
-                Ok(d) => Some(d),
-                Err(_) => None,
+            transfer_queue: TransferQueue::default(),
+            failed_transfers: None,
+            pending_operation: None,
+            cache: match config_client.get_disable_cache() {
+                true => None,
+                false => TempDir::new().ok(),
             },
             fswatcher: if enable_fs_watcher {
                 FsWatcher::init(Duration::from_secs(5)).ok()
             } else {
                 None
             },
+            // changes are detected by the modification times, which are reliable only if they're preserved
+            remote_poller: match remote_params.protocol.preserves_mtime() {
+                true => Some(RemotePoller::new(
+                    config_client.get_remote_watcher_interval(),
+                )),
+                false => None,
+            },
+            watcher_pending: 0,
+            host_bridge_free_space: None,
             host_bridge_connected,
             remote_connected: false,
+            reconnect: None,
+            credentials_amended: false,
+            jump_host_credentials: None,
+            clock_skew: ClockSkew::default(),
+            favorites: Vec::new(),
+            sync: None,
+            metadata: MetadataState::default(),
+            undo: UndoStack::default(),
+            preview: None,
+            errors_logged: 0,
+            footer: FooterState::default(),
+            terminal_focused: None,
+            park_session: false,
+            exec_history: Self::init_exec_history(),
         }
     }
 
@@ -325,6 +587,18 @@ impl FileTransferActivity {
         })
     }
 
+    /// Get path to the cache directory.
+    /// Returns an error message if the cache is disabled in configuration or if it couldn't be created
+    fn cache_path(&self) -> Result<PathBuf, String> {
+        match self.cache.as_ref() {
+            Some(cache) => Ok(cache.path().to_path_buf()),
+            None if self.config().get_disable_cache() => Err(String::from(
+                "Temporary cache is disabled in configuration: files can't be staged on local disk",
+            )),
+            None => Err(String::from("Could not create tempdir")),
+        }
+    }
+
     /// Returns a reference to context
     fn context(&self) -> &Context {
         self.context.as_ref().unwrap()
@@ -367,6 +641,7 @@ impl Activity for FileTransferActivity {
         debug!("Initializing activity...");
         // Set context
         self.context = Some(context);
+        self.load_favorites();
         // Clear terminal
         if let Err(err) = self.context.as_mut().unwrap().terminal().clear_screen() {
             error!("Failed to clear screen: {}", err);
@@ -375,6 +650,15 @@ impl Activity for FileTransferActivity {
         if let Err(err) = self.context_mut().terminal().enable_raw_mode() {
             error!("Failed to enter raw mode: {}", err);
         }
+        // Notifications are sent only when unfocused, so the terminal must report the focus
+        if self.config().get_notification_unfocused_only() {
+            tty::set_focus_reporting(true);
+        }
+        if self.config().get_mouse_capture() {
+            if let Err(err) = self.context_mut().terminal().enable_mouse_capture() {
+                error!("Failed to enable mouse capture: {}", err);
+            }
+        }
         // Get files at current pwd
         if self.host_bridge.is_localhost() {
             debug!("Reloading host bridge directory");
@@ -387,6 +671,11 @@ impl Activity for FileTransferActivity {
         // init view
         self.init();
         debug!("Initialized view");
+        // Resume the session parked on the same host, if any
+        let params = self.context().remote_params().cloned();
+        if let Some(session) = params.and_then(|x| self.context_mut().take_parked_session(&x)) {
+            self.resume_parked_session(session);
+        }
         // Verify error state from context
         if let Some(err) = self.context.as_mut().unwrap().error() {
             error!("Fatal error on create: {}", err);
@@ -419,13 +708,21 @@ impl Activity for FileTransferActivity {
             self.redraw = true;
         }
         // Check if connected to remote (popup must be None, otherwise would try reconnecting in loop in case of error)
-        if (!self.client.is_connected() || !self.remote_connected)
+        if self.reconnect.is_some() {
+            // the connection has been lost: reconnect and restore the working directory
+            self.reconnect_to_remote();
+        } else if (!self.client.is_connected() || !self.remote_connected)
             && !self.app.mounted(&Id::FatalPopup)
+            && !self.app.mounted(&Id::CredentialsUsernamePopup)
+            && !self.app.mounted(&Id::HostKeyPopup)
             && self.host_bridge.is_connected()
         {
             let ftparams = self.context().remote_params().unwrap();
             // print params
-            let msg: String = Self::get_connection_msg(&ftparams.params);
+            let msg: String = match ftparams.protocol {
+                FileTransferProtocol::Localhost => String::from("Opening LOCALHOST…"),
+                _ => Self::get_connection_msg(&ftparams.params),
+            };
             // Set init state to connecting popup
             self.mount_blocking_wait(msg.as_str());
             // Connect to remote
@@ -433,9 +730,20 @@ impl Activity for FileTransferActivity {
             // Redraw
             self.redraw = true;
         }
+        if self.remote_connected && self.clock_skew.should_measure() {
+            self.measure_clock_skew();
+        }
         self.tick();
         // poll
         self.poll_watcher();
+        self.poll_remote_watcher();
+        self.poll_transfer_queue();
+        self.refresh_footer_bar();
+        // Animate pending operation
+        if self.pending_operation.is_some() {
+            self.refresh_panel_wait();
+            self.redraw = true;
+        }
         // View
         if self.redraw {
             self.view();
@@ -449,6 +757,25 @@ impl Activity for FileTransferActivity {
         self.exit_reason.as_ref()
     }
 
+    /// `on_suspend` is the function which must be called before the process is stopped (e.g. on `SIGTSTP`).
+    /// It gives the terminal back to the shell
+    fn on_suspend(&mut self) {
+        if let Some(ctx) = self.context.as_mut() {
+            tty::leave_terminal(ctx.terminal());
+        }
+    }
+
+    /// `on_resume` is the function which must be called once the process has been resumed (`SIGCONT`).
+    /// It takes the terminal back and forces a full redraw of the interface
+    fn on_resume(&mut self) {
+        if let Some(ctx) = self.context.as_mut() {
+            tty::enter_terminal(ctx.terminal());
+        }
+        self.redraw = true;
+        // the connection may have been dropped during the suspension
+        self.verify_connections();
+    }
+
     /// `on_destroy` is the function which cleans up runtime variables and data before terminating the activity.
     /// This function must be called once before terminating the activity.
     fn on_destroy(&mut self) -> Option<Context> {
@@ -458,6 +785,15 @@ impl Activity for FileTransferActivity {
                 error!("Failed to delete cache: {}", err);
             }
         }
+        if self.config().get_notification_unfocused_only() {
+            tty::set_focus_reporting(false);
+        }
+        // Give the mouse back to the terminal, for the text selection
+        if self.config().get_mouse_capture() {
+            if let Err(err) = self.context_mut().terminal().disable_mouse_capture() {
+                error!("Failed to disable mouse capture: {}", err);
+            }
+        }
         // Disable raw mode
         if let Err(err) = self.context_mut().terminal().disable_raw_mode() {
             error!("Failed to disable raw mode: {}", err);
@@ -465,8 +801,10 @@ impl Activity for FileTransferActivity {
         if let Err(err) = self.context_mut().terminal().clear_screen() {
             error!("Failed to clear screen: {}", err);
         }
-        // Disconnect client
-        if self.client.is_connected() {
+        // Park or disconnect client
+        if self.park_session && self.client.is_connected() {
+            self.park_client();
+        } else if self.client.is_connected() {
             let _ = self.client.disconnect();
         }
         // disconnect host bridge
@@ -2,21 +2,51 @@
 //!
 //! `filetransfer_activiy` is the module which implements the Filetransfer activity, which is the main activity afterall
 
-use std::io::{Read, Write};
+use std::collections::HashMap;
+use std::io::{Read, Seek, SeekFrom, Write};
 use std::path::{Path, PathBuf};
-use std::time::Instant;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
 
 use bytesize::ByteSize;
+use chrono::{DateTime, Local};
 use remotefs::fs::{File, Metadata, ReadStream, UnixPex, Welcome, WriteStream};
 use remotefs::{RemoteError, RemoteErrorType, RemoteResult};
 use thiserror::Error;
 
-use super::{FileTransferActivity, LogLevel};
-use crate::host::HostError;
+use super::actions::save::TransferConfirmation;
+use super::lib::clock_skew::{self, ClockSkew};
+use super::lib::metadata::{self, MetadataState};
+use super::lib::parallel::{
+    JobOutcome, RemoteSession, TransferDirection, TransferJob, TransferWorkers, WorkerEvent,
+};
+use super::lib::reconnect::{self, Reconnect};
+use super::lib::schedule;
+use super::lib::special;
+use super::lib::sync::{SyncMode, SyncState};
+use super::lib::transfer::{
+    self, FailedTransfers, ProgressReader, ProgressWriter, TransferEstimate, TransferFailure,
+};
+use super::lib::visited::VisitedDirs;
+use super::{FileTransferActivity, Id, LogLevel, TransferOpts};
+use crate::explorer::special::SpecialFileType;
+use crate::filetransfer::host_key::HostKeyStatus;
+use crate::filetransfer::params::{JumpCredentials, ModePrefs};
+use crate::filetransfer::{
+    FileTransferProtocol, HostBridgeParams, ParkedSession, RejectedHostKey, RemoteFsBuilder,
+    SshNegotiationHandle,
+};
+use crate::host::{HostError, HostErrorType};
 use crate::utils::fmt::fmt_millis;
+use crate::utils::io::{self, CopyError};
+use crate::utils::shell::{self, DEFAULT_REMOTE_SHELL};
 
-/// Buffer size for remote I/O
-const BUFSIZE: usize = 65535;
+/// Maximum amount of entries scanned before asking the user to confirm a transfer
+const PRESCAN_MAX_ENTRIES: usize = 100_000;
+/// Maximum time spent scanning entries before asking the user to confirm a transfer
+const PRESCAN_TIMEOUT: Duration = Duration::from_secs(5);
+/// Interval between two polls of the transfer workers
+const WORKERS_POLL_INTERVAL: Duration = Duration::from_millis(50);
 
 /// Describes the reason that caused an error during a file transfer
 #[derive(Error, Debug)]
@@ -37,11 +67,13 @@ enum TransferErrorReason {
 /// - File: describes an individual `File` to send
 /// - Any: Can be any kind of `File`, but just one
 /// - Many: a list of `File`
+/// - Resume: an individual `File`, whose destination is a partial copy; only the missing bytes are transferred
 #[derive(Debug)]
 pub(super) enum TransferPayload {
     File(File),
     Any(File),
     Many(Vec<File>),
+    Resume(File),
 }
 
 impl FileTransferActivity {
@@ -65,6 +97,7 @@ impl FileTransferActivity {
                     ),
                 );
 
+                self.measure_clock_skew();
                 // Try to change directory to entry directory
                 let mut remote_chdir: Option<PathBuf> = None;
                 if let Some(remote_path) = &entry_dir {
@@ -98,6 +131,7 @@ impl FileTransferActivity {
                 if !self.remote_connected {
                     return;
                 }
+                self.update_bookmark_connection_outcome(None);
 
                 if let Some(banner) = banner {
                     // Log welcome
@@ -119,6 +153,8 @@ impl FileTransferActivity {
                         ),
                     );
                 }
+                self.log_ssh_negotiation();
+                self.log_ftp_mode();
                 // Try to change directory to entry directory
                 let mut remote_chdir: Option<PathBuf> = None;
                 if let Some(remote_path) = &entry_dir {
@@ -133,12 +169,214 @@ impl FileTransferActivity {
                 // Update file lists
                 self.update_host_bridge_filelist();
                 self.update_remote_filelist();
+                // Offer to save the amended credentials to the bookmark
+                if std::mem::take(&mut self.credentials_amended) {
+                    if let Some(name) = self.context().remote_bookmark().map(str::to_string) {
+                        self.mount_update_bookmark(name.as_str());
+                    }
+                }
             }
-            Err(err) => {
-                // Set popup fatal error
+            Err(err)
+                if err.kind == RemoteErrorType::AuthenticationFailed
+                    && self.ssh_negotiation.failed_jump_host().is_some() =>
+            {
+                let jump_host = self.ssh_negotiation.failed_jump_host().unwrap();
+                self.log(
+                    LogLevel::Error,
+                    format!(
+                        "Authentication to jump host '{}' failed: {}",
+                        jump_host.alias, err
+                    ),
+                );
+                // Ask for the credentials of the jump host
                 self.umount_wait();
-                self.mount_fatal(err.to_string());
+                self.mount_credentials(Some(jump_host.username.as_str()), &jump_host.alias);
+                self.jump_host_credentials = Some(jump_host.alias);
+            }
+            Err(err)
+                if err.kind == RemoteErrorType::AuthenticationFailed
+                    && ft_params.params.has_credentials() =>
+            {
+                self.update_bookmark_connection_outcome(Some(&err.to_string()));
+                self.log(
+                    LogLevel::Error,
+                    format!(
+                        "Authentication to '{}' failed: {}",
+                        self.get_remote_hostname(),
+                        err
+                    ),
+                );
+                // Ask for different credentials
+                self.umount_wait();
+                let host = self.get_remote_hostname();
+                self.mount_credentials(ft_params.params.username(), &host);
+                self.jump_host_credentials = None;
+            }
+            Err(err) => match self.ssh_negotiation.rejected_host_key() {
+                // Ask whether to accept the key
+                Some(RejectedHostKey {
+                    key,
+                    status: HostKeyStatus::Unknown,
+                    known_hosts,
+                }) => {
+                    self.log(LogLevel::Warn, err.to_string());
+                    self.umount_wait();
+                    self.mount_host_key(&key, known_hosts.path());
+                }
+                _ => {
+                    self.update_bookmark_connection_outcome(Some(&err.to_string()));
+                    // Set popup fatal error
+                    self.umount_wait();
+                    self.mount_fatal(err.to_string());
+                }
+            },
+        }
+    }
+
+    /// Save the host key the connection has been refused for to the known hosts file, then connect again
+    pub(super) fn accept_host_key(&mut self) {
+        let Some(rejected) = self.ssh_negotiation.rejected_host_key() else {
+            return;
+        };
+        if let Err(err) = rejected.known_hosts.add(&rejected.key) {
+            self.mount_fatal(format!(
+                "Could not save the host key to {}: {err}",
+                rejected.known_hosts.path().display()
+            ));
+            return;
+        }
+        self.log(
+            LogLevel::Info,
+            format!(
+                "Added the {} key of '{}' ({}) to {}",
+                rejected.key.key_type(),
+                rejected.key.host_name(),
+                rejected.key.fingerprint(),
+                rejected.known_hosts.path().display()
+            ),
+        );
+        // the host key types to negotiate are read from the known hosts file, when the client is built
+        let ft_params = self.context().remote_params().unwrap().clone();
+        self.client = RemoteFsBuilder::build_connection(
+            &ft_params,
+            self.ssh_negotiation.clone(),
+            self.config(),
+        );
+        // Connection is established again on the next draw
+        self.remote_connected = false;
+    }
+
+    /// Retry to connect to remote, authenticating with `username` and `password`,
+    /// on the jump host the credentials have been asked for, if any
+    pub(super) fn retry_with_credentials(&mut self, username: String, password: String) {
+        let mut ft_params = self.context().remote_params().unwrap().clone();
+        match (
+            self.jump_host_credentials.take(),
+            ft_params.params.mut_generic_params(),
+        ) {
+            (Some(jump_host), Some(params)) => {
+                // the credentials of the jump hosts are never saved to the bookmark
+                params
+                    .jump_credentials
+                    .insert(jump_host, JumpCredentials { username, password });
+            }
+            _ => {
+                ft_params.params.set_credentials(username, password);
+                self.credentials_amended = true;
+            }
+        }
+        self.client = RemoteFsBuilder::build_connection(
+            &ft_params,
+            self.ssh_negotiation.clone(),
+            self.config(),
+        );
+        self.context_mut().set_remote_params(ft_params);
+        // Connection is established again on the next draw
+        self.remote_connected = false;
+    }
+
+    /// Log the cipher and the compression negotiated with the remote, if it's an ssh remote
+    fn log_ssh_negotiation(&mut self) {
+        let Some(negotiation) = self.ssh_negotiation.get() else {
+            return;
+        };
+        if negotiation.fallback {
+            self.log(
+                LogLevel::Warn,
+                String::from("The server refused the preferred ssh ciphers or compression: the connection uses the defaults"),
+            );
+        }
+        self.log(
+            LogLevel::Info,
+            format!(
+                "Ssh session negotiated with cipher {} and compression {}",
+                negotiation.cipher, negotiation.compression
+            ),
+        );
+    }
+
+    /// Log the mode of the data connections, if it's an ftp remote
+    fn log_ftp_mode(&mut self) {
+        let ft_params = self.context().remote_params().unwrap();
+        let FileTransferProtocol::Ftp(_) = ft_params.protocol else {
+            return;
+        };
+        let Some(params) = ft_params.params.generic_params() else {
+            return;
+        };
+        let mode = match params.active_mode {
+            true => "active",
+            false => "passive",
+        };
+        self.log(
+            LogLevel::Info,
+            format!("Ftp data connections are opened in {mode} mode"),
+        );
+    }
+
+    /// Measure the clock skew of the remote host, executing [`ClockSkew::COMMAND`].
+    /// The skew is measured only on SSH remotes, since the other protocols don't support exec
+    pub(super) fn measure_clock_skew(&mut self) {
+        let ft_params = self.context().remote_params().unwrap();
+        if !matches!(
+            ft_params.protocol,
+            FileTransferProtocol::Scp | FileTransferProtocol::Sftp
+        ) {
+            return;
+        }
+        let cmd = shell::wrap_command(
+            ft_params
+                .remote_shell
+                .as_deref()
+                .unwrap_or(DEFAULT_REMOTE_SHELL),
+            ClockSkew::COMMAND,
+        );
+        let previous = self.clock_skew.exceeding();
+        let sent = SystemTime::now();
+        let output = match self.client.exec(cmd.as_str()) {
+            Ok((0, output)) => output,
+            Ok((rc, output)) => {
+                debug!("\"{cmd}\" exited with {rc}: {output}");
+                String::new()
+            }
+            Err(err) => {
+                debug!("could not execute \"{cmd}\": {err}");
+                String::new()
+            }
+        };
+        let skew = self.clock_skew.update(&output, sent, SystemTime::now());
+        if skew != previous {
+            if let Some(skew) = skew {
+                self.log(
+                    LogLevel::Warn,
+                    format!(
+                        "The clock of '{}' is {} off the local clock: modification times are compared with this tolerance",
+                        self.get_remote_hostname(),
+                        ClockSkew::fmt(skew)
+                    ),
+                );
             }
+            self.refresh_remote_status_bar();
         }
     }
 
@@ -147,12 +385,194 @@ impl FileTransferActivity {
         let msg: String = format!("Disconnecting from {}…", self.get_remote_hostname());
         // Show popup disconnecting
         self.mount_wait(msg.as_str());
+        // Cancel the queued transfers
+        self.stop_transfer_queue();
         // Disconnect
         let _ = self.client.disconnect();
         // Quit
         self.exit_reason = Some(super::ExitReason::Disconnect);
     }
 
+    /// Return to the authentication form keeping the remote session connected,
+    /// so that connecting to the same host again resumes it
+    pub(super) fn park(&mut self) {
+        // Cancel the queued transfers
+        self.stop_transfer_queue();
+        self.park_session = true;
+        self.exit_reason = Some(super::ExitReason::Disconnect);
+    }
+
+    /// Move the connected remote client to the parked sessions of the context,
+    /// replacing it with a client which is never connected
+    pub(super) fn park_client(&mut self) {
+        let Some(timeout) = self.config().get_parked_session_timeout() else {
+            return;
+        };
+        let params = self.context().remote_params().unwrap().clone();
+        let placeholder = RemoteFsBuilder::build_connection(
+            &params,
+            SshNegotiationHandle::default(),
+            self.config(),
+        );
+        let client = std::mem::replace(&mut self.client, placeholder);
+        let session = ParkedSession::new(
+            params,
+            client,
+            self.ssh_negotiation.clone(),
+            self.remote().wrkdir.clone(),
+            timeout,
+        );
+        self.context_mut().park_session(session);
+    }
+
+    /// Resume the remote session parked on the same host, in the directory it has been parked in.
+    /// If the connection has been closed meanwhile, the remote is connected as usual
+    pub(super) fn resume_parked_session(&mut self, session: ParkedSession) {
+        let (mut client, ssh_negotiation, wrkdir) = session.resume();
+        if !client.is_connected() || client.pwd().is_err() {
+            self.log(
+                LogLevel::Warn,
+                format!(
+                    "The session parked on '{}' has been closed; connecting again",
+                    self.get_remote_hostname()
+                ),
+            );
+            let _ = client.disconnect();
+            return;
+        }
+        self.client = client;
+        self.ssh_negotiation = ssh_negotiation;
+        self.remote_connected = true;
+        self.log(
+            LogLevel::Info,
+            format!(
+                "Resumed the session parked on '{}'",
+                self.get_remote_hostname()
+            ),
+        );
+        self.remote_changedir(wrkdir.as_path(), false);
+        self.update_host_bridge_filelist();
+        self.update_remote_filelist();
+    }
+
+    /// Verify that the connections are still alive, e.g. after the process has been suspended.
+    /// Dead connections are closed and then re-established on the next draw
+    pub(super) fn verify_connections(&mut self) {
+        self.verify_remote_connection();
+        if !self.host_bridge.is_localhost()
+            && self.host_bridge_connected
+            && self.host_bridge.pwd().is_err()
+        {
+            self.log(
+                LogLevel::Warn,
+                format!(
+                    "Connection with '{}' has been lost; reconnecting…",
+                    self.get_hostbridge_hostname()
+                ),
+            );
+            let _ = self.host_bridge.disconnect();
+            self.host_bridge_connected = false;
+        }
+    }
+
+    /// Verify that the connection with the remote is still alive, e.g. after an operation has failed
+    fn verify_remote_connection(&mut self) {
+        if self.remote_connected && self.client.pwd().is_err() {
+            self.connection_lost();
+        }
+    }
+
+    /// Start reconnecting if `err` reports that the connection with the remote has been lost.
+    /// Returns whether the connection has been lost
+    fn check_connection_lost(&mut self, err: &RemoteError) -> bool {
+        let lost = self.remote_connected && reconnect::is_connection_lost(err);
+        if lost {
+            self.connection_lost();
+        }
+        lost
+    }
+
+    /// Close the lost connection with the remote and start reconnecting, up to `reconnect_retries` times;
+    /// the working directory is restored once reconnected.
+    /// If the reconnection is disabled, the connection is re-established once on the next draw
+    fn connection_lost(&mut self) {
+        self.log(
+            LogLevel::Warn,
+            format!(
+                "Connection with '{}' has been lost; reconnecting…",
+                self.get_remote_hostname()
+            ),
+        );
+        let _ = self.client.disconnect();
+        self.remote_connected = false;
+        let retries = self.config().get_reconnect_retries();
+        if retries > 0 && self.reconnect.is_none() {
+            self.reconnect = Some(Reconnect::new(self.remote().wrkdir.clone(), retries));
+        }
+    }
+
+    /// Attempt to reconnect to the remote, if the attempt is due.
+    /// The client is rebuilt from the connection params; once connected, the previous working directory is restored.
+    /// If the attempt fails, the next one is scheduled with a backoff, while the fatal popup is shown after the last one
+    pub(super) fn reconnect_to_remote(&mut self) {
+        let Some(reconnect) = self.reconnect.as_ref().filter(|x| x.is_due()) else {
+            return;
+        };
+        let (attempt, retries) = (reconnect.attempt(), reconnect.retries());
+        let hostname = self.get_remote_hostname();
+        self.mount_blocking_wait(format!(
+            "Connection lost, reconnecting to '{hostname}'… (attempt {attempt} of {retries})"
+        ));
+        let ft_params = self.context().remote_params().unwrap().clone();
+        self.client = RemoteFsBuilder::build_connection(
+            &ft_params,
+            self.ssh_negotiation.clone(),
+            self.config(),
+        );
+        self.redraw = true;
+        match self.client.connect() {
+            Ok(_) => {
+                self.remote_connected = self.client.is_connected();
+                let wrkdir = self.reconnect.take().unwrap().wrkdir().to_path_buf();
+                self.log(LogLevel::Info, format!("Reconnected to '{hostname}'"));
+                self.umount_wait();
+                if let Err(err) = self.client.change_dir(wrkdir.as_path()) {
+                    self.log(
+                        LogLevel::Warn,
+                        format!(
+                            "Could not restore the working directory \"{}\": {err}",
+                            wrkdir.display()
+                        ),
+                    );
+                }
+                self.reload_remote_dir();
+                self.update_remote_filelist();
+            }
+            Err(err) => match self.reconnect.as_mut().and_then(|x| x.failed()) {
+                Some(delay) => {
+                    self.log(
+                        LogLevel::Warn,
+                        format!(
+                            "Reconnection attempt {attempt} of {retries} failed: {err}; retrying in {}s",
+                            delay.as_secs()
+                        ),
+                    );
+                    self.mount_wait(format!(
+                        "Connection lost, reconnecting to '{hostname}'… (next attempt in {}s)",
+                        delay.as_secs()
+                    ));
+                }
+                None => {
+                    self.reconnect = None;
+                    self.umount_wait();
+                    self.mount_fatal(format!(
+                        "Could not reconnect to '{hostname}' after {retries} attempts: {err}"
+                    ));
+                }
+            },
+        }
+    }
+
     /// disconnect from remote and then quit
     pub(super) fn disconnect_and_quit(&mut self) {
         self.disconnect();
@@ -165,24 +585,34 @@ impl FileTransferActivity {
             return;
         }
         // Get current entries
-        if let Ok(wrkdir) = self.client.pwd() {
-            self.mount_blocking_wait("Loading remote directory...");
+        match self.client.pwd() {
+            Ok(wrkdir) => {
+                self.mount_panel_wait_on(Id::ExplorerRemote, "Loading remote directory...");
 
-            let res = self.remote_scan(wrkdir.as_path());
+                let res = self.remote_scan(wrkdir.as_path());
 
-            self.umount_wait();
+                self.umount_wait();
 
-            match res {
-                Ok(_) => {
-                    self.remote_mut().wrkdir = wrkdir;
-                }
-                Err(err) => {
-                    self.log_and_alert(
-                        LogLevel::Error,
-                        format!("Could not scan current remote directory: {err}"),
-                    );
+                match res {
+                    Ok(_) => {
+                        self.remote_mut().wrkdir = wrkdir;
+                        // the free space has been updated by the listing
+                        self.refresh_remote_status_bar();
+                    }
+                    Err(err) if self.check_connection_lost(&err) => {}
+                    Err(err) => {
+                        self.log_and_alert(
+                            LogLevel::Error,
+                            self.with_remote_error_hint(format!(
+                                "Could not scan current remote directory: {err}"
+                            )),
+                        );
+                    }
                 }
             }
+            Err(err) => {
+                self.check_connection_lost(&err);
+            }
         }
     }
 
@@ -192,11 +622,12 @@ impl FileTransferActivity {
             return;
         }
 
-        self.mount_blocking_wait("Loading host bridge directory...");
+        self.mount_panel_wait_on(Id::ExplorerHostBridge, "Loading host bridge directory...");
 
         let wrkdir = match self.host_bridge.pwd() {
             Ok(wrkdir) => wrkdir,
             Err(err) => {
+                self.umount_wait();
                 self.log_and_alert(
                     LogLevel::Error,
                     format!("Could not scan current host bridge directory: {err}"),
@@ -211,7 +642,9 @@ impl FileTransferActivity {
 
         match res {
             Ok(_) => {
+                self.host_bridge_free_space = self.host_bridge.free_space(wrkdir.as_path());
                 self.host_bridge_mut().wrkdir = wrkdir;
+                self.refresh_local_status_bar();
             }
             Err(err) => {
                 self.log_and_alert(
@@ -226,8 +659,10 @@ impl FileTransferActivity {
     fn host_bridge_scan(&mut self, path: &Path) -> Result<(), HostError> {
         match self.host_bridge.list_dir(path) {
             Ok(files) => {
+                let special = self.host_bridge_special_files(&files);
                 // Set files and sort (sorting is implicit)
                 self.host_bridge_mut().set_files(files);
+                self.host_bridge_mut().set_special_files(special);
 
                 Ok(())
             }
@@ -239,14 +674,123 @@ impl FileTransferActivity {
     fn remote_scan(&mut self, path: &Path) -> RemoteResult<()> {
         match self.client.list_dir(path) {
             Ok(files) => {
+                let special = self.remote_special_files(&files);
                 // Set files and sort (sorting is implicit)
                 self.remote_mut().set_files(files);
+                self.remote_mut().set_special_files(special);
                 Ok(())
             }
             Err(err) => Err(err),
         }
     }
 
+    /// Classify the special files among `files` on the host bridge.
+    /// On remote host bridges they're classified only if commands can be executed
+    fn host_bridge_special_files(&mut self, files: &[File]) -> HashMap<PathBuf, SpecialFileType> {
+        let candidates = special::candidates(files);
+        if candidates.is_empty() {
+            return HashMap::new();
+        }
+        if self.host_bridge.is_localhost() {
+            return special::classify_local(&candidates);
+        }
+        if !matches!(
+            self.context().host_bridge_params(),
+            Some(HostBridgeParams::Remote(
+                FileTransferProtocol::Scp | FileTransferProtocol::Sftp,
+                _
+            ))
+        ) {
+            return HashMap::new();
+        }
+        let cmd = shell::wrap_command(
+            DEFAULT_REMOTE_SHELL,
+            &special::classify_command(&candidates),
+        );
+        match self.host_bridge.exec(cmd.as_str()) {
+            Ok(output) => special::parse_classify_output(&output, &candidates),
+            Err(err) => {
+                debug!("could not classify special files: {err}");
+                HashMap::new()
+            }
+        }
+    }
+
+    /// Classify the special files among `files` on the remote.
+    /// They're classified only if commands can be executed on the remote, or if the remote is the local host
+    fn remote_special_files(&mut self, files: &[File]) -> HashMap<PathBuf, SpecialFileType> {
+        let candidates = special::candidates(files);
+        if candidates.is_empty() {
+            return HashMap::new();
+        }
+        let Some(params) = self.context().remote_params() else {
+            return HashMap::new();
+        };
+        match params.protocol {
+            FileTransferProtocol::Localhost => return special::classify_local(&candidates),
+            FileTransferProtocol::Scp | FileTransferProtocol::Sftp => {}
+            _ => return HashMap::new(),
+        }
+        let cmd = shell::wrap_command(
+            params
+                .remote_shell
+                .as_deref()
+                .unwrap_or(DEFAULT_REMOTE_SHELL),
+            &special::classify_command(&candidates),
+        );
+        match self.client.exec(cmd.as_str()) {
+            Ok((0, output)) => special::parse_classify_output(&output, &candidates),
+            Ok((rc, output)) => {
+                debug!("\"{cmd}\" exited with {rc}: {output}");
+                HashMap::new()
+            }
+            Err(err) => {
+                debug!("could not execute \"{cmd}\": {err}");
+                HashMap::new()
+            }
+        }
+    }
+
+    /// Exclude the special files from `payload`, since reading them may block forever.
+    /// A single special file is an error, while special files among many entries are skipped
+    fn exclude_special_files(
+        &mut self,
+        payload: TransferPayload,
+        remote: bool,
+    ) -> Result<TransferPayload, String> {
+        match payload {
+            TransferPayload::Any(ref file)
+            | TransferPayload::File(ref file)
+            | TransferPayload::Resume(ref file) => match self.special_file_type(file, remote) {
+                Some(kind) => Err(format!(
+                    "\"{}\" is a {kind}: it can't be transferred",
+                    file.path().display()
+                )),
+                None => Ok(payload),
+            },
+            TransferPayload::Many(entries) => Ok(TransferPayload::Many(
+                entries
+                    .into_iter()
+                    .filter(|entry| match self.special_file_type(entry, remote) {
+                        Some(kind) => {
+                            self.log_special_file_skipped(entry, kind);
+                            false
+                        }
+                        None => true,
+                    })
+                    .collect(),
+            )),
+        }
+    }
+
+    /// Log that `entry` has been skipped by a transfer, since it is a special file
+    fn log_special_file_skipped(&mut self, entry: &File, kind: SpecialFileType) {
+        self.log(
+            LogLevel::Warn,
+            format!("Skipped \"{}\": it is a {kind}", entry.path().display()),
+        );
+    }
+
     /// Send fs entry to remote.
     /// If dst_name is Some, entry will be saved with a different name.
     /// If entry is a directory, this applies to directory only
@@ -254,20 +798,92 @@ impl FileTransferActivity {
         &mut self,
         payload: TransferPayload,
         curr_remote_path: &Path,
-        dst_name: Option<String>,
+        opts: TransferOpts,
     ) -> Result<(), String> {
+        let payload = self.exclude_special_files(payload, false)?;
+        // Resolve the destination as the remote sees it, so that mistakes are visible up front
+        let dest = self.resolve_remote_destination(curr_remote_path);
+        let curr_remote_path = dest.as_path();
+        // Estimate transfer size; the user may cancel or schedule the transfer
+        let total_transfer_size = match self.estimate_transfer_size(
+            &payload,
+            curr_remote_path,
+            opts.sync.is_none(),
+            |activity, path| {
+                activity
+                    .host_bridge
+                    .list_dir(path)
+                    .map_err(|e| e.to_string())
+            },
+        ) {
+            Ok(size) => size,
+            Err(Some(at)) => {
+                self.schedule_transfer(
+                    TransferDirection::Upload,
+                    payload,
+                    curr_remote_path,
+                    opts.save_as,
+                    at,
+                );
+                return Ok(());
+            }
+            Err(None) => return Ok(()),
+        };
+        let free_space = self.ssh_negotiation.free_space();
+        if !self.should_transfer_exceeding_free_space(
+            total_transfer_size as u64,
+            free_space,
+            curr_remote_path,
+        ) {
+            return Ok(());
+        }
+        self.log(
+            LogLevel::Info,
+            format!("Uploading to \"{}\"", curr_remote_path.display()),
+        );
+        self.start_sync(opts.sync, TransferDirection::Upload);
+        self.start_preserving_metadata(opts.preserve_metadata, TransferDirection::Upload);
+        let parallel = opts.is_parallel();
+        let dst_name = opts.save_as.clone();
         // Use different method based on payload
         let result = match payload {
-            TransferPayload::Any(ref entry) => {
-                self.filetransfer_send_any(entry, curr_remote_path, dst_name)
-            }
+            TransferPayload::Any(ref entry) if parallel && entry.is_dir() => self
+                .filetransfer_send_parallel(
+                    std::slice::from_ref(entry),
+                    curr_remote_path,
+                    opts,
+                    total_transfer_size,
+                ),
+            TransferPayload::Many(ref entries) if parallel => self.filetransfer_send_parallel(
+                entries,
+                curr_remote_path,
+                opts,
+                total_transfer_size,
+            ),
+            TransferPayload::Any(ref entry) => self.filetransfer_send_any(
+                entry,
+                curr_remote_path,
+                dst_name,
+                total_transfer_size,
+                opts.preserve_symlinks,
+            ),
             TransferPayload::File(ref file) => {
                 self.filetransfer_send_file(file, curr_remote_path, dst_name)
             }
-            TransferPayload::Many(ref entries) => {
-                self.filetransfer_send_many(entries, curr_remote_path)
+            TransferPayload::Many(ref entries) => self.filetransfer_send_many(
+                entries,
+                curr_remote_path,
+                total_transfer_size,
+                opts.preserve_symlinks,
+            ),
+            TransferPayload::Resume(ref file) => {
+                self.filetransfer_send_resume(file, curr_remote_path, dst_name)
             }
         };
+        self.finish_preserving_metadata(TransferDirection::Upload);
+        self.finish_sync();
+        let result = self.collect_failed_transfers(TransferDirection::Upload, result);
+        let result = result.map_err(|err| self.with_remote_error_hint(err));
         // Notify
         match &result {
             Ok(_) => {
@@ -275,6 +891,8 @@ impl FileTransferActivity {
             }
             Err(e) => {
                 self.notify_transfer_error(e.as_str());
+                // the transfer may have failed since the connection has been lost
+                self.verify_remote_connection();
             }
         }
         result
@@ -310,22 +928,58 @@ impl FileTransferActivity {
         result.map_err(|x| x.to_string())
     }
 
+    /// Resume the upload of one file to remote at specified path, appending to the remote file the missing bytes
+    fn filetransfer_send_resume(
+        &mut self,
+        file: &File,
+        curr_remote_path: &Path,
+        dst_name: Option<String>,
+    ) -> Result<(), String> {
+        let file_name: String = file.name();
+        let remote_path: PathBuf =
+            curr_remote_path.join(dst_name.unwrap_or_else(|| file_name.clone()));
+        // Bytes already transferred
+        let offset = self
+            .client
+            .stat(remote_path.as_path())
+            .map(|x| x.metadata().size as usize)
+            .unwrap_or_default();
+        // Reset states
+        self.transfer.reset();
+        self.transfer
+            .full
+            .init_resumed(file.metadata.size as usize, offset);
+        // Mount progress bar
+        self.mount_progress_bar(format!("Uploading {}…", file.path.display()));
+        // Send
+        let result =
+            self.filetransfer_send_one_resumed(file, remote_path.as_path(), file_name, offset);
+        // Umount progress bar
+        self.umount_progress_bar();
+        // Return result
+        result.map_err(|x| x.to_string())
+    }
+
     /// Send a `TransferPayload` of type `Any`
     fn filetransfer_send_any(
         &mut self,
         entry: &File,
         curr_remote_path: &Path,
         dst_name: Option<String>,
+        total_transfer_size: usize,
+        preserve_symlinks: bool,
     ) -> Result<(), String> {
         // Reset states
         self.transfer.reset();
-        // Calculate total size of transfer
-        let total_transfer_size: usize = self.get_total_transfer_size_host(entry);
         self.transfer.full.init(total_transfer_size);
         // Mount progress bar
         self.mount_progress_bar(format!("Uploading {}…", entry.path().display()));
         // Send recurse
-        let result = self.filetransfer_send_recurse(entry, curr_remote_path, dst_name);
+        let mut visited = self
+            .host_bridge_visited_dirs()
+            .preserve_symlinks(preserve_symlinks);
+        let result =
+            self.filetransfer_send_recurse(entry, curr_remote_path, dst_name, &mut visited);
         // Umount progress bar
         self.umount_progress_bar();
         result
@@ -336,45 +990,196 @@ impl FileTransferActivity {
         &mut self,
         entries: &[File],
         curr_remote_path: &Path,
+        total_transfer_size: usize,
+        preserve_symlinks: bool,
     ) -> Result<(), String> {
         // Reset states
         self.transfer.reset();
-        // Calculate total size of transfer
-        let total_transfer_size: usize = entries
-            .iter()
-            .map(|x| self.get_total_transfer_size_host(x))
-            .sum();
         self.transfer.full.init(total_transfer_size);
         // Mount progress bar
         self.mount_progress_bar(format!("Uploading {} entries…", entries.len()));
         // Send recurse
-        let result = entries
-            .iter()
-            .map(|x| self.filetransfer_send_recurse(x, curr_remote_path, None))
-            .find(|x| x.is_err())
-            .unwrap_or(Ok(()));
+        let mut visited = self
+            .host_bridge_visited_dirs()
+            .preserve_symlinks(preserve_symlinks);
+        let mut result = Ok(());
+        for entry in entries {
+            if self.transfer.aborted() {
+                break;
+            }
+            if let Err(err) =
+                self.filetransfer_send_recurse(entry, curr_remote_path, None, &mut visited)
+            {
+                if self.transfer.aborted() {
+                    result = Err(err);
+                    break;
+                }
+                self.record_transfer_failure(entry, curr_remote_path.join(entry.name()), err);
+            }
+        }
         // Umount progress bar
         self.umount_progress_bar();
         result
     }
 
-    fn filetransfer_send_recurse(
+    /// Send many entries to remote, transferring up to `opts.concurrency` files in parallel.
+    /// Directories are created first through the current connection, then the files are sent by the workers
+    fn filetransfer_send_parallel(
         &mut self,
-        entry: &File,
+        entries: &[File],
         curr_remote_path: &Path,
-        dst_name: Option<String>,
+        opts: TransferOpts,
+        total_transfer_size: usize,
     ) -> Result<(), String> {
-        // Write popup
-        let file_name = entry.name();
-        // Get remote path
-        let mut remote_path: PathBuf = PathBuf::from(curr_remote_path);
-        let remote_file_name: PathBuf = match dst_name {
-            Some(s) => PathBuf::from(s.as_str()),
-            None => PathBuf::from(file_name.as_str()),
-        };
-        remote_path.push(remote_file_name);
-        // Match entry
-        let result: Result<(), String> = if entry.is_dir() {
+        // Reset states
+        self.transfer.reset();
+        self.transfer.full.init(total_transfer_size);
+        // Mount progress bar
+        self.mount_progress_bar(format!("Uploading {} entries…", entries.len()));
+        // Create directories and collect files
+        let mut jobs = Vec::new();
+        let mut errors = Vec::new();
+        let mut visited = self
+            .host_bridge_visited_dirs()
+            .preserve_symlinks(opts.preserve_symlinks);
+        let mut dst_name = opts.save_as;
+        for entry in entries {
+            if self.transfer.aborted() {
+                break;
+            }
+            self.filetransfer_send_plan(
+                entry,
+                curr_remote_path,
+                dst_name.take(),
+                &mut visited,
+                &mut jobs,
+                &mut errors,
+            );
+        }
+        let result =
+            self.filetransfer_parallel(TransferDirection::Upload, jobs, errors, opts.concurrency);
+        // Umount progress bar
+        self.umount_progress_bar();
+        // Scan dir on remote
+        self.reload_remote_dir();
+        result
+    }
+
+    /// Create on remote the directory tree of `entry` and collect into `jobs` the files to send.
+    /// Errors are collected into `errors` and the entries which caused them are skipped
+    fn filetransfer_send_plan(
+        &mut self,
+        entry: &File,
+        curr_remote_path: &Path,
+        dst_name: Option<String>,
+        visited: &mut VisitedDirs,
+        jobs: &mut Vec<TransferJob>,
+        errors: &mut Vec<String>,
+    ) {
+        let remote_path = curr_remote_path.join(dst_name.unwrap_or_else(|| entry.name()));
+        if let Some(target) = visited.preserved_link(entry) {
+            match self.filetransfer_send_symlink(remote_path.as_path(), target) {
+                Ok(true) => return,
+                Ok(false) => {}
+                Err(err) => {
+                    errors.push(err);
+                    return;
+                }
+            }
+        }
+        if !entry.is_dir() {
+            jobs.push(TransferJob::new(entry.clone(), remote_path));
+            return;
+        }
+        if !visited.enter(entry) {
+            self.log_symlink_loop(entry);
+            return;
+        }
+        // Read events, so that the transfer can be aborted
+        self.tick();
+        match self
+            .client
+            .create_dir(remote_path.as_path(), UnixPex::from(0o755))
+        {
+            Ok(_) => {
+                self.log(
+                    LogLevel::Info,
+                    format!("Created directory \"{}\"", remote_path.display()),
+                );
+                self.apply_remote_mode(remote_path.as_path(), true);
+            }
+            Err(err) if err.kind == RemoteErrorType::DirectoryAlreadyExists => {}
+            Err(err) => {
+                errors.push(format!(
+                    "Failed to create directory \"{}\": {}",
+                    remote_path.display(),
+                    err
+                ));
+                visited.leave(entry.path());
+                return;
+            }
+        }
+        let dir_metadata = metadata::with_mode(entry.metadata(), self.remote_modes().mode(true));
+        self.metadata.push_dir(remote_path.as_path(), &dir_metadata);
+        match self.host_bridge.list_dir(entry.path()) {
+            Ok(entries) => {
+                let special = self.host_bridge_special_files(&entries);
+                for entry in entries.iter() {
+                    if self.transfer.aborted() {
+                        break;
+                    }
+                    if let Some(kind) = special.get(entry.path()) {
+                        self.log_special_file_skipped(entry, *kind);
+                        continue;
+                    }
+                    self.filetransfer_send_plan(
+                        entry,
+                        remote_path.as_path(),
+                        None,
+                        visited,
+                        jobs,
+                        errors,
+                    );
+                }
+                self.mirror_remote_dir(&entries, remote_path.as_path());
+            }
+            Err(err) => errors.push(format!(
+                "Could not scan directory \"{}\": {}",
+                entry.path().display(),
+                err
+            )),
+        }
+        visited.leave(entry.path());
+    }
+
+    fn filetransfer_send_recurse(
+        &mut self,
+        entry: &File,
+        curr_remote_path: &Path,
+        dst_name: Option<String>,
+        visited: &mut VisitedDirs,
+    ) -> Result<(), String> {
+        // Write popup
+        let file_name = entry.name();
+        // Get remote path
+        let mut remote_path: PathBuf = PathBuf::from(curr_remote_path);
+        let remote_file_name: PathBuf = match dst_name {
+            Some(s) => PathBuf::from(s.as_str()),
+            None => PathBuf::from(file_name.as_str()),
+        };
+        remote_path.push(remote_file_name);
+        // Re-create symbolic link, if it mustn't be followed
+        if let Some(target) = visited.preserved_link(entry) {
+            if self.filetransfer_send_symlink(remote_path.as_path(), target)? {
+                return Ok(());
+            }
+        }
+        // Match entry
+        let result: Result<(), String> = if entry.is_dir() {
+            if !visited.enter(entry) {
+                self.log_symlink_loop(entry);
+                return Ok(());
+            }
             // Create directory on remote first
             match self
                 .client
@@ -385,6 +1190,7 @@ impl FileTransferActivity {
                         LogLevel::Info,
                         format!("Created directory \"{}\"", remote_path.display()),
                     );
+                    self.apply_remote_mode(remote_path.as_path(), true);
                 }
                 Err(err) if err.kind == RemoteErrorType::DirectoryAlreadyExists => {
                     self.log(
@@ -396,51 +1202,74 @@ impl FileTransferActivity {
                     );
                 }
                 Err(err) => {
-                    self.log_and_alert(
-                        LogLevel::Error,
-                        format!(
-                            "Failed to create directory \"{}\": {}",
-                            remote_path.display(),
-                            err
-                        ),
-                    );
-                    return Err(err.to_string());
+                    return Err(format!(
+                        "Failed to create directory \"{}\": {}",
+                        remote_path.display(),
+                        err
+                    ));
                 }
             }
+            let dir_metadata =
+                metadata::with_mode(entry.metadata(), self.remote_modes().mode(true));
+            self.metadata.push_dir(remote_path.as_path(), &dir_metadata);
             // Get files in dir
             match self.host_bridge.list_dir(entry.path()) {
                 Ok(entries) => {
+                    let special = self.host_bridge_special_files(&entries);
                     // Iterate over files
                     for entry in entries.iter() {
                         // If aborted; break
                         if self.transfer.aborted() {
                             break;
                         }
-                        // Send entry; name is always None after first call
-                        self.filetransfer_send_recurse(entry, remote_path.as_path(), None)?
+                        if let Some(kind) = special.get(entry.path()) {
+                            self.log_special_file_skipped(entry, *kind);
+                            continue;
+                        }
+                        // Send entry; name is always None after first call.
+                        // Failures are kept and the transfer goes on with the next entries, unless aborted
+                        if let Err(err) = self.filetransfer_send_recurse(
+                            entry,
+                            remote_path.as_path(),
+                            None,
+                            visited,
+                        ) {
+                            if self.transfer.aborted() {
+                                return Err(err);
+                            }
+                            self.record_transfer_failure(
+                                entry,
+                                remote_path.join(entry.name()),
+                                err,
+                            );
+                        }
                     }
+                    self.mirror_remote_dir(&entries, remote_path.as_path());
+                    visited.leave(entry.path());
                     Ok(())
                 }
-                Err(err) => {
-                    self.log_and_alert(
-                        LogLevel::Error,
+                Err(err) => Err(format!(
+                    "Could not scan directory \"{}\": {}",
+                    entry.path().display(),
+                    err
+                )),
+            }
+        } else {
+            match self.filetransfer_send_one(entry, remote_path.as_path(), file_name) {
+                Err(TransferErrorReason::Abrupted) => {
+                    // Keep the partial file, so that the transfer can be resumed
+                    self.log(
+                        LogLevel::Info,
                         format!(
-                            "Could not scan directory \"{}\": {}",
-                            entry.path().display(),
-                            err
+                            "Partial file \"{}\" has been kept: upload it again to resume the transfer",
+                            remote_path.display()
                         ),
                     );
-                    Err(err.to_string())
+                    Err(TransferErrorReason::Abrupted.to_string())
                 }
-            }
-        } else {
-            match self.filetransfer_send_one(entry, remote_path.as_path(), file_name) {
                 Err(err) => {
-                    // If transfer was abrupted or there was an IO error on remote, remove file
-                    if matches!(
-                        err,
-                        TransferErrorReason::Abrupted | TransferErrorReason::RemoteIoError(_)
-                    ) {
+                    // If there was an IO error on remote, remove file
+                    if matches!(err, TransferErrorReason::RemoteIoError(_)) {
                         // Stat file on remote and remove it if exists
                         match self.client.stat(remote_path.as_path()) {
                             Err(err) => self.log(
@@ -506,11 +1335,12 @@ impl FileTransferActivity {
                 ),
             );
             self.transfer.full.update_progress(metadata.size as usize);
+            self.count_file(false);
             return Ok(());
         }
         // Upload file
         // Try to open host_bridge file
-        match self.host_bridge.open_file(host_bridge.path.as_path()) {
+        let result = match self.host_bridge.open_file(host_bridge.path.as_path()) {
             Ok(host_bridge_read) => match self.client.create(remote, &metadata) {
                 Ok(rhnd) => self.filetransfer_send_one_with_stream(
                     host_bridge,
@@ -518,6 +1348,7 @@ impl FileTransferActivity {
                     file_name,
                     host_bridge_read,
                     rhnd,
+                    0,
                 ),
                 Err(err) if err.kind == RemoteErrorType::UnsupportedFeature => self
                     .filetransfer_send_one_wno_stream(
@@ -529,18 +1360,86 @@ impl FileTransferActivity {
                 Err(err) => Err(TransferErrorReason::FileTransferError(err)),
             },
             Err(err) => Err(TransferErrorReason::HostError(err)),
+        };
+        if result.is_ok() {
+            self.count_file(true);
+        }
+        result
+    }
+
+    /// Send the bytes of the host_bridge file following the first `offset`, appending them to the remote file.
+    /// If the remote file can't be appended, or the host_bridge file can't be read from `offset`, the entire file is sent
+    fn filetransfer_send_one_resumed(
+        &mut self,
+        host_bridge: &File,
+        remote: &Path,
+        file_name: String,
+        offset: usize,
+    ) -> Result<(), TransferErrorReason> {
+        let metadata = self
+            .host_bridge
+            .stat(host_bridge.path.as_path())
+            .map_err(TransferErrorReason::HostError)
+            .map(|x| x.metadata().clone())?;
+        let streams = match offset > 0 && offset < metadata.size as usize {
+            true => match self
+                .host_bridge
+                .open_file_at(host_bridge.path.as_path(), offset as u64)
+            {
+                Ok(reader) => match self.client.append(remote, &metadata) {
+                    Ok(writer) => Ok((reader, writer)),
+                    Err(err) => Err(err.to_string()),
+                },
+                Err(err) => Err(err.to_string()),
+            },
+            false => Err(String::from("the remote file is not a partial copy")),
+        };
+        match streams {
+            Ok((reader, writer)) => {
+                self.log(
+                    LogLevel::Info,
+                    format!(
+                        "Resuming upload of \"{}\" to \"{}\" from {}",
+                        host_bridge.path.display(),
+                        remote.display(),
+                        ByteSize(offset as u64)
+                    ),
+                );
+                self.filetransfer_send_one_with_stream(
+                    host_bridge,
+                    remote,
+                    file_name,
+                    reader,
+                    writer,
+                    offset,
+                )
+            }
+            Err(err) => {
+                self.log(
+                    LogLevel::Warn,
+                    format!(
+                        "Could not resume upload of \"{}\" ({err}): the whole file will be sent",
+                        host_bridge.path.display()
+                    ),
+                );
+                self.transfer.full.init(metadata.size as usize);
+                self.filetransfer_send_one(host_bridge, remote, file_name)
+            }
         }
     }
 
-    /// Send file to remote using stream
+    /// Send file to remote using stream.
+    /// The first `offset` bytes of the file have already been sent, and `reader` starts after them
     fn filetransfer_send_one_with_stream(
         &mut self,
         host: &File,
         remote: &Path,
         file_name: String,
-        mut reader: Box<dyn Read + Send>,
+        reader: Box<dyn Read + Send>,
         mut writer: WriteStream,
+        offset: usize,
     ) -> Result<(), TransferErrorReason> {
+        let mut reader = ProgressReader::new(reader, self.transfer.progress());
         // Write file
         let file_size = self
             .host_bridge
@@ -548,66 +1447,14 @@ impl FileTransferActivity {
             .map_err(TransferErrorReason::HostError)
             .map(|x| x.metadata().size as usize)?;
         // Init transfer
-        self.transfer.partial.init(file_size);
+        self.transfer.partial.init_resumed(file_size, offset);
 
         // Write remote file
-        let mut total_bytes_written: usize = 0;
-        let mut last_progress_val: f64 = 0.0;
-        let mut last_input_event_fetch: Option<Instant> = None;
-        // While the entire file hasn't been completely written,
-        // Or filetransfer has been aborted
-        while total_bytes_written < file_size && !self.transfer.aborted() {
-            // Handle input events (each 500ms) or if never fetched before
-            if last_input_event_fetch.is_none()
-                || last_input_event_fetch
-                    .unwrap_or_else(Instant::now)
-                    .elapsed()
-                    .as_millis()
-                    >= 500
-            {
-                // Read events
-                self.tick();
-                // Reset instant
-                last_input_event_fetch = Some(Instant::now());
-            }
-            // Read till you can
-            let mut buffer: [u8; BUFSIZE] = [0; BUFSIZE];
-            let delta: usize = match reader.read(&mut buffer) {
-                Ok(bytes_read) => {
-                    total_bytes_written += bytes_read;
-                    if bytes_read == 0 {
-                        continue;
-                    } else {
-                        let mut delta: usize = 0;
-                        while delta < bytes_read {
-                            // Write bytes
-                            match writer.write(&buffer[delta..bytes_read]) {
-                                Ok(bytes) => {
-                                    delta += bytes;
-                                }
-                                Err(err) => {
-                                    return Err(TransferErrorReason::RemoteIoError(err));
-                                }
-                            }
-                        }
-                        delta
-                    }
-                }
-                Err(err) => {
-                    return Err(TransferErrorReason::HostIoError(err));
-                }
-            };
-            // Increase progress
-            self.transfer.partial.update_progress(delta);
-            self.transfer.full.update_progress(delta);
-            // Draw only if a significant progress has been made (performance improvement)
-            if last_progress_val < self.transfer.partial.calc_progress() - 0.01 {
-                // Draw
-                self.update_progress_bar(format!("Uploading \"{file_name}\"…"));
-                self.view();
-                last_progress_val = self.transfer.partial.calc_progress();
-            }
-        }
+        let result = self.copy_stream(
+            &mut reader,
+            &mut writer,
+            format!("Uploading \"{file_name}\"…"),
+        );
         // Finalize stream
         if let Err(err) = self.client.on_written(writer) {
             self.log(
@@ -619,10 +1466,13 @@ impl FileTransferActivity {
         if self.transfer.aborted() {
             return Err(TransferErrorReason::Abrupted);
         }
-        // set stat
-        if let Err(err) = self.client.setstat(remote, host.metadata().clone()) {
-            error!("failed to set stat for {}: {}", remote.display(), err);
+        match result {
+            Err(CopyError::Read(err)) => return Err(TransferErrorReason::HostIoError(err)),
+            Err(CopyError::Write(err)) => return Err(TransferErrorReason::RemoteIoError(err)),
+            Err(CopyError::Aborted) | Ok(_) => {}
         }
+        self.apply_remote_metadata(remote, host.metadata().clone());
+        self.apply_remote_mode(remote, false);
         self.log(
             LogLevel::Info,
             format!(
@@ -636,6 +1486,35 @@ impl FileTransferActivity {
         Ok(())
     }
 
+    /// Copy `reader` into `writer` with the configured buffer size, handling the input events and drawing the
+    /// progress bar between chunks, until the stream is over or the transfer is aborted
+    fn copy_stream(
+        &mut self,
+        reader: &mut dyn Read,
+        writer: &mut dyn Write,
+        progress_msg: String,
+    ) -> Result<u64, CopyError> {
+        let buffer_size = self.config().get_transfer_buffer_size();
+        let mut last_progress_val: f64 = 0.0;
+        let mut last_input_event_fetch: Option<Instant> = None;
+        io::copy_with_progress(reader, writer, buffer_size, |_| {
+            // Handle input events (each 500ms) or if never fetched before
+            if last_input_event_fetch.is_none_or(|x| x.elapsed() >= Duration::from_millis(500)) {
+                self.tick();
+                last_input_event_fetch = Some(Instant::now());
+            }
+            // Increase progress
+            self.transfer.sync_progress();
+            // Draw only if a significant progress has been made (performance improvement)
+            if last_progress_val < self.transfer.partial.calc_progress() - 0.01 {
+                self.update_progress_bar(progress_msg.clone());
+                self.view();
+                last_progress_val = self.transfer.partial.calc_progress();
+            }
+            !self.transfer.aborted()
+        })
+    }
+
     /// Send an `File` to remote without using streams.
     fn filetransfer_send_one_wno_stream(
         &mut self,
@@ -662,17 +1541,19 @@ impl FileTransferActivity {
         // Draw before
         self.update_progress_bar(format!("Uploading \"{file_name}\"…"));
         self.view();
-        // Send file
+        // Send file; progress is reported by the reader for each chunk
+        let reader = Box::new(ProgressReader::new(reader, self.transfer.progress()));
         if let Err(err) = self.client.create_file(remote, &metadata, reader) {
-            return Err(TransferErrorReason::FileTransferError(err));
-        }
-        // set stat
-        if let Err(err) = self.client.setstat(remote, metadata) {
-            error!("failed to set stat for {}: {}", remote.display(), err);
+            self.transfer.sync_progress();
+            return match self.transfer.aborted() {
+                true => Err(TransferErrorReason::Abrupted),
+                false => Err(TransferErrorReason::FileTransferError(err)),
+            };
         }
+        self.apply_remote_metadata(remote, metadata);
+        self.apply_remote_mode(remote, false);
         // Set transfer size ok
-        self.transfer.partial.update_progress(file_size);
-        self.transfer.full.update_progress(file_size);
+        self.transfer.sync_progress();
         // Draw again after
         self.update_progress_bar(format!("Uploading \"{file_name}\"…"));
         self.view();
@@ -697,17 +1578,84 @@ impl FileTransferActivity {
         &mut self,
         payload: TransferPayload,
         host_bridge_path: &Path,
-        dst_name: Option<String>,
+        opts: TransferOpts,
     ) -> Result<(), String> {
-        let result = match payload {
-            TransferPayload::Any(ref entry) => {
-                self.filetransfer_recv_any(entry, host_bridge_path, dst_name)
+        let payload = self.exclude_special_files(payload, true)?;
+        // Resolve the destination as the host bridge sees it, so that mistakes are visible up front
+        let dest = self.resolve_host_bridge_destination(host_bridge_path);
+        let host_bridge_path = dest.as_path();
+        // Estimate transfer size; the user may cancel or schedule the transfer
+        let total_transfer_size = match self.estimate_transfer_size(
+            &payload,
+            host_bridge_path,
+            opts.sync.is_none(),
+            |activity, path| activity.client.list_dir(path).map_err(|e| e.to_string()),
+        ) {
+            Ok(size) => size,
+            Err(Some(at)) => {
+                self.schedule_transfer(
+                    TransferDirection::Download,
+                    payload,
+                    host_bridge_path,
+                    opts.save_as,
+                    at,
+                );
+                return Ok(());
             }
+            Err(None) => return Ok(()),
+        };
+        let free_space = self.host_bridge.free_space(host_bridge_path);
+        if !self.should_transfer_exceeding_free_space(
+            total_transfer_size as u64,
+            free_space,
+            host_bridge_path,
+        ) {
+            return Ok(());
+        }
+        self.log(
+            LogLevel::Info,
+            format!("Downloading to \"{}\"", host_bridge_path.display()),
+        );
+        self.start_sync(opts.sync, TransferDirection::Download);
+        self.start_preserving_metadata(opts.preserve_metadata, TransferDirection::Download);
+        let parallel = opts.is_parallel();
+        let dst_name = opts.save_as.clone();
+        let result = match payload {
+            TransferPayload::Any(ref entry) if parallel && entry.is_dir() => self
+                .filetransfer_recv_parallel(
+                    std::slice::from_ref(entry),
+                    host_bridge_path,
+                    opts,
+                    total_transfer_size,
+                ),
+            TransferPayload::Many(ref entries) if parallel => self.filetransfer_recv_parallel(
+                entries,
+                host_bridge_path,
+                opts,
+                total_transfer_size,
+            ),
+            TransferPayload::Any(ref entry) => self.filetransfer_recv_any(
+                entry,
+                host_bridge_path,
+                dst_name,
+                total_transfer_size,
+                opts.preserve_symlinks,
+            ),
             TransferPayload::File(ref file) => self.filetransfer_recv_file(file, host_bridge_path),
-            TransferPayload::Many(ref entries) => {
-                self.filetransfer_recv_many(entries, host_bridge_path)
+            TransferPayload::Many(ref entries) => self.filetransfer_recv_many(
+                entries,
+                host_bridge_path,
+                total_transfer_size,
+                opts.preserve_symlinks,
+            ),
+            TransferPayload::Resume(ref file) => {
+                self.filetransfer_recv_resume(file, host_bridge_path, dst_name)
             }
         };
+        self.finish_preserving_metadata(TransferDirection::Download);
+        self.finish_sync();
+        let result = self.collect_failed_transfers(TransferDirection::Download, result);
+        let result = result.map_err(|err| self.with_remote_error_hint(err));
         // Notify
         match &result {
             Ok(_) => {
@@ -715,6 +1663,8 @@ impl FileTransferActivity {
             }
             Err(e) => {
                 self.notify_transfer_error(e.as_str());
+                // the transfer may have failed since the connection has been lost
+                self.verify_remote_connection();
             }
         }
         result
@@ -728,16 +1678,17 @@ impl FileTransferActivity {
         entry: &File,
         host_path: &Path,
         dst_name: Option<String>,
+        total_transfer_size: usize,
+        preserve_symlinks: bool,
     ) -> Result<(), String> {
         // Reset states
         self.transfer.reset();
-        // Calculate total transfer size
-        let total_transfer_size: usize = self.get_total_transfer_size_remote(entry);
         self.transfer.full.init(total_transfer_size);
         // Mount progress bar
         self.mount_progress_bar(format!("Downloading {}…", entry.path().display()));
         // Receive
-        let result = self.filetransfer_recv_recurse(entry, host_path, dst_name);
+        let mut visited = VisitedDirs::remote().preserve_symlinks(preserve_symlinks);
+        let result = self.filetransfer_recv_recurse(entry, host_path, dst_name, &mut visited);
         // Umount progress bar
         self.umount_progress_bar();
         result
@@ -764,70 +1715,227 @@ impl FileTransferActivity {
         result.map_err(|x| x.to_string())
     }
 
+    /// Resume the download of a single file from remote, appending to the host_bridge file the missing bytes
+    fn filetransfer_recv_resume(
+        &mut self,
+        entry: &File,
+        host_bridge_path: &Path,
+        dst_name: Option<String>,
+    ) -> Result<(), String> {
+        let host_bridge_file_path: PathBuf =
+            host_bridge_path.join(dst_name.unwrap_or_else(|| entry.name()));
+        // Bytes already transferred
+        let offset = self
+            .host_bridge
+            .stat(host_bridge_file_path.as_path())
+            .map(|x| x.metadata().size as usize)
+            .unwrap_or_default();
+        // Reset states
+        self.transfer.reset();
+        self.transfer
+            .full
+            .init_resumed(entry.metadata.size as usize, offset);
+        // Mount progress bar
+        self.mount_progress_bar(format!("Downloading {}…", entry.path.display()));
+        // Receive
+        let result = self.filetransfer_recv_one_resumed(
+            host_bridge_file_path.as_path(),
+            entry,
+            entry.name(),
+            offset,
+        );
+        // Umount progress bar
+        self.umount_progress_bar();
+        // Return result
+        result.map_err(|x| x.to_string())
+    }
+
     /// Send many entries to remote
     fn filetransfer_recv_many(
         &mut self,
         entries: &[File],
         curr_remote_path: &Path,
+        total_transfer_size: usize,
+        preserve_symlinks: bool,
     ) -> Result<(), String> {
         // Reset states
         self.transfer.reset();
-        // Calculate total size of transfer
-        let total_transfer_size: usize = entries
-            .iter()
-            .map(|x| self.get_total_transfer_size_remote(x))
-            .sum();
         self.transfer.full.init(total_transfer_size);
         // Mount progress bar
         self.mount_progress_bar(format!("Downloading {} entries…", entries.len()));
         // Send recurse
-        let result = entries
-            .iter()
-            .map(|x| self.filetransfer_recv_recurse(x, curr_remote_path, None))
-            .find(|x| x.is_err())
-            .unwrap_or(Ok(()));
+        let mut visited = VisitedDirs::remote().preserve_symlinks(preserve_symlinks);
+        let mut result = Ok(());
+        for entry in entries {
+            if self.transfer.aborted() {
+                break;
+            }
+            if let Err(err) =
+                self.filetransfer_recv_recurse(entry, curr_remote_path, None, &mut visited)
+            {
+                if self.transfer.aborted() {
+                    result = Err(err);
+                    break;
+                }
+                self.record_transfer_failure(entry, curr_remote_path.join(entry.name()), err);
+            }
+        }
         // Umount progress bar
         self.umount_progress_bar();
         result
     }
 
-    fn filetransfer_recv_recurse(
+    /// Receive many entries from remote, transferring up to `opts.concurrency` files in parallel.
+    /// Directories are created first through the current connection, then the files are received by the workers
+    fn filetransfer_recv_parallel(
+        &mut self,
+        entries: &[File],
+        host_bridge_path: &Path,
+        opts: TransferOpts,
+        total_transfer_size: usize,
+    ) -> Result<(), String> {
+        // Reset states
+        self.transfer.reset();
+        self.transfer.full.init(total_transfer_size);
+        // Mount progress bar
+        self.mount_progress_bar(format!("Downloading {} entries…", entries.len()));
+        // Create directories and collect files
+        let mut jobs = Vec::new();
+        let mut errors = Vec::new();
+        let mut visited = VisitedDirs::remote().preserve_symlinks(opts.preserve_symlinks);
+        let mut dst_name = opts.save_as;
+        for entry in entries {
+            if self.transfer.aborted() {
+                break;
+            }
+            self.filetransfer_recv_plan(
+                entry,
+                host_bridge_path,
+                dst_name.take(),
+                &mut visited,
+                &mut jobs,
+                &mut errors,
+            );
+        }
+        let result =
+            self.filetransfer_parallel(TransferDirection::Download, jobs, errors, opts.concurrency);
+        // Umount progress bar
+        self.umount_progress_bar();
+        // Reload directory on host_bridge
+        self.reload_host_bridge_dir();
+        result
+    }
+
+    /// Create on the host bridge the directory tree of `entry` and collect into `jobs` the files to receive.
+    /// Errors are collected into `errors` and the entries which caused them are skipped
+    fn filetransfer_recv_plan(
         &mut self,
         entry: &File,
         host_bridge_path: &Path,
         dst_name: Option<String>,
-    ) -> Result<(), String> {
-        // Write popup
-        let file_name = entry.name();
-        // Match entry
-        let result: Result<(), String> = if entry.is_dir() {
-            // Get dir name
-            let mut host_bridge_dir_path: PathBuf = PathBuf::from(host_bridge_path);
-            match dst_name {
-                Some(name) => host_bridge_dir_path.push(name),
-                None => host_bridge_dir_path.push(entry.name()),
+        visited: &mut VisitedDirs,
+        jobs: &mut Vec<TransferJob>,
+        errors: &mut Vec<String>,
+    ) {
+        let host_bridge_path = host_bridge_path.join(dst_name.unwrap_or_else(|| entry.name()));
+        if let Some(target) = visited.preserved_link(entry) {
+            match self.filetransfer_recv_symlink(host_bridge_path.as_path(), target) {
+                Ok(true) => return,
+                Ok(false) => {}
+                Err(err) => {
+                    errors.push(err);
+                    return;
+                }
             }
-            // Create directory on host_bridge
-            match self
-                .host_bridge
-                .mkdir_ex(host_bridge_dir_path.as_path(), true)
-            {
-                Ok(_) => {
-                    // Apply file mode to directory
-                    if let Err(err) = self
-                        .host_bridge
-                        .setstat(host_bridge_dir_path.as_path(), entry.metadata())
-                    {
-                        self.log(
-                            LogLevel::Error,
-                            format!(
-                                "Could not set stat to directory {:?} to \"{}\": {}",
-                                entry.metadata(),
-                                host_bridge_dir_path.display(),
-                                err
-                            ),
-                        );
+        }
+        if !entry.is_dir() {
+            jobs.push(TransferJob::new(entry.clone(), host_bridge_path));
+            return;
+        }
+        if !visited.enter(entry) {
+            self.log_symlink_loop(entry);
+            return;
+        }
+        // Read events, so that the transfer can be aborted
+        self.tick();
+        if let Err(err) = self.host_bridge.mkdir_ex(host_bridge_path.as_path(), true) {
+            errors.push(format!(
+                "Failed to create directory \"{}\": {}",
+                host_bridge_path.display(),
+                err
+            ));
+            visited.leave(entry.path());
+            return;
+        }
+        self.metadata
+            .push_dir(host_bridge_path.as_path(), entry.metadata());
+        match self.client.list_dir(entry.path()) {
+            Ok(entries) => {
+                let special = self.remote_special_files(&entries);
+                for entry in entries.iter() {
+                    if self.transfer.aborted() {
+                        break;
+                    }
+                    if let Some(kind) = special.get(entry.path()) {
+                        self.log_special_file_skipped(entry, *kind);
+                        continue;
                     }
+                    self.filetransfer_recv_plan(
+                        entry,
+                        host_bridge_path.as_path(),
+                        None,
+                        visited,
+                        jobs,
+                        errors,
+                    );
+                }
+                self.mirror_host_bridge_dir(&entries, host_bridge_path.as_path());
+            }
+            Err(err) => errors.push(format!(
+                "Could not scan directory \"{}\": {}",
+                entry.path().display(),
+                err
+            )),
+        }
+        visited.leave(entry.path());
+    }
+
+    fn filetransfer_recv_recurse(
+        &mut self,
+        entry: &File,
+        host_bridge_path: &Path,
+        dst_name: Option<String>,
+        visited: &mut VisitedDirs,
+    ) -> Result<(), String> {
+        // Write popup
+        let file_name = entry.name();
+        // Re-create symbolic link, if it mustn't be followed
+        if let Some(target) = visited.preserved_link(entry) {
+            let link_path = host_bridge_path.join(dst_name.as_deref().unwrap_or(&file_name));
+            if self.filetransfer_recv_symlink(link_path.as_path(), target)? {
+                return Ok(());
+            }
+        }
+        // Match entry
+        let result: Result<(), String> = if entry.is_dir() {
+            if !visited.enter(entry) {
+                self.log_symlink_loop(entry);
+                return Ok(());
+            }
+            // Get dir name
+            let mut host_bridge_dir_path: PathBuf = PathBuf::from(host_bridge_path);
+            match dst_name {
+                Some(name) => host_bridge_dir_path.push(name),
+                None => host_bridge_dir_path.push(entry.name()),
+            }
+            // Create directory on host_bridge
+            match self
+                .host_bridge
+                .mkdir_ex(host_bridge_dir_path.as_path(), true)
+            {
+                Ok(_) => {
+                    self.metadata
+                        .push_dir(host_bridge_dir_path.as_path(), entry.metadata());
                     self.log(
                         LogLevel::Info,
                         format!("Created directory \"{}\"", host_bridge_dir_path.display()),
@@ -835,46 +1943,52 @@ impl FileTransferActivity {
                     // Get files in dir
                     match self.client.list_dir(entry.path()) {
                         Ok(entries) => {
+                            let special = self.remote_special_files(&entries);
                             // Iterate over files
                             for entry in entries.iter() {
                                 // If transfer has been aborted; break
                                 if self.transfer.aborted() {
                                     break;
                                 }
+                                if let Some(kind) = special.get(entry.path()) {
+                                    self.log_special_file_skipped(entry, *kind);
+                                    continue;
+                                }
                                 // Receive entry; name is always None after first call
-                                // Local path becomes host_bridge_dir_path
-                                self.filetransfer_recv_recurse(
+                                // Local path becomes host_bridge_dir_path.
+                                // Failures are kept and the transfer goes on with the next entries, unless aborted
+                                if let Err(err) = self.filetransfer_recv_recurse(
                                     entry,
                                     host_bridge_dir_path.as_path(),
                                     None,
-                                )?
+                                    visited,
+                                ) {
+                                    if self.transfer.aborted() {
+                                        return Err(err);
+                                    }
+                                    self.record_transfer_failure(
+                                        entry,
+                                        host_bridge_dir_path.join(entry.name()),
+                                        err,
+                                    );
+                                }
                             }
+                            self.mirror_host_bridge_dir(&entries, host_bridge_dir_path.as_path());
+                            visited.leave(entry.path());
                             Ok(())
                         }
-                        Err(err) => {
-                            self.log_and_alert(
-                                LogLevel::Error,
-                                format!(
-                                    "Could not scan directory \"{}\": {}",
-                                    entry.path().display(),
-                                    err
-                                ),
-                            );
-                            Err(err.to_string())
-                        }
-                    }
-                }
-                Err(err) => {
-                    self.log(
-                        LogLevel::Error,
-                        format!(
-                            "Failed to create directory \"{}\": {}",
-                            host_bridge_dir_path.display(),
+                        Err(err) => Err(format!(
+                            "Could not scan directory \"{}\": {}",
+                            entry.path().display(),
                             err
-                        ),
-                    );
-                    Err(err.to_string())
+                        )),
+                    }
                 }
+                Err(err) => Err(format!(
+                    "Failed to create directory \"{}\": {}",
+                    host_bridge_dir_path.display(),
+                    err
+                )),
             }
         } else {
             // Get host_bridge file
@@ -888,11 +2002,18 @@ impl FileTransferActivity {
             if let Err(err) =
                 self.filetransfer_recv_one(host_bridge_file_path.as_path(), entry, file_name)
             {
-                // If transfer was abrupted or there was an IO error on remote, remove file
-                if matches!(
-                    err,
-                    TransferErrorReason::Abrupted | TransferErrorReason::HostIoError(_)
-                ) {
+                // If transfer was abrupted keep the partial file, so that the transfer can be resumed
+                if matches!(err, TransferErrorReason::Abrupted) {
+                    self.log(
+                        LogLevel::Info,
+                        format!(
+                            "Partial file \"{}\" has been kept: download it again to resume the transfer",
+                            host_bridge_file_path.display()
+                        ),
+                    );
+                }
+                // If there was an IO error on host bridge, remove file
+                if matches!(err, TransferErrorReason::HostIoError(_)) {
                     // Stat file
                     match self.host_bridge.stat(host_bridge_file_path.as_path()) {
                         Err(err) => self.log(
@@ -954,11 +2075,12 @@ impl FileTransferActivity {
             self.transfer
                 .full
                 .update_progress(remote.metadata().size as usize);
+            self.count_file(false);
             return Ok(());
         }
 
         // Try to open host_bridge file
-        match self.host_bridge.create_file(host_bridge, &remote.metadata) {
+        let result = match self.host_bridge.create_file(host_bridge, &remote.metadata) {
             Ok(writer) => {
                 // Download file from remote
                 match self.client.open(remote.path.as_path()) {
@@ -968,6 +2090,7 @@ impl FileTransferActivity {
                         file_name,
                         rhnd,
                         writer,
+                        0,
                     ),
                     Err(err) if err.kind == RemoteErrorType::UnsupportedFeature => {
                         self.filetransfer_recv_one_wno_stream(host_bridge, remote, file_name)
@@ -976,76 +2099,108 @@ impl FileTransferActivity {
                 }
             }
             Err(err) => Err(TransferErrorReason::HostError(err)),
+        };
+        if result.is_ok() {
+            self.count_file(true);
         }
+        result
     }
 
-    /// Receive an `File` from remote using stream
-    fn filetransfer_recv_one_with_stream(
+    /// Receive the bytes of the remote file following the first `offset`, appending them to the host_bridge file.
+    /// If the remote stream can't be seeked (or streams are not supported), or the host_bridge file can't be appended,
+    /// the entire file is received
+    fn filetransfer_recv_one_resumed(
         &mut self,
         host_bridge: &Path,
         remote: &File,
         file_name: String,
-        mut reader: ReadStream,
-        mut writer: Box<dyn Write + Send>,
+        offset: usize,
     ) -> Result<(), TransferErrorReason> {
-        let mut total_bytes_written: usize = 0;
-        // Init transfer
-        self.transfer.partial.init(remote.metadata.size as usize);
-        // Write host_bridge file
-        let mut last_progress_val: f64 = 0.0;
-        let mut last_input_event_fetch: Option<Instant> = None;
-        // While the entire file hasn't been completely read,
-        // Or filetransfer has been aborted
-        while total_bytes_written < remote.metadata.size as usize && !self.transfer.aborted() {
-            // Handle input events (each 500 ms) or is None
-            if last_input_event_fetch.is_none()
-                || last_input_event_fetch
-                    .unwrap_or_else(Instant::now)
-                    .elapsed()
-                    .as_millis()
-                    >= 500
-            {
-                // Read events
-                self.tick();
-                // Reset instant
-                last_input_event_fetch = Some(Instant::now());
-            }
-            // Read till you can
-            let mut buffer: [u8; BUFSIZE] = [0; BUFSIZE];
-            let delta: usize = match reader.read(&mut buffer) {
-                Ok(bytes_read) => {
-                    total_bytes_written += bytes_read;
-                    if bytes_read == 0 {
-                        continue;
-                    } else {
-                        let mut delta: usize = 0;
-                        while delta < bytes_read {
-                            // Write bytes
-                            match writer.write(&buffer[delta..bytes_read]) {
-                                Ok(bytes) => delta += bytes,
-                                Err(err) => {
-                                    return Err(TransferErrorReason::HostIoError(err));
-                                }
-                            }
+        let reader = match offset > 0 && offset < remote.metadata.size as usize {
+            true => match self.client.open(remote.path.as_path()) {
+                Ok(mut stream) if stream.seekable() => {
+                    match stream.seek(SeekFrom::Start(offset as u64)) {
+                        Ok(_) => Ok(stream),
+                        Err(err) => {
+                            let _ = self.client.on_read(stream);
+                            Err(err.to_string())
                         }
-                        delta
                     }
                 }
+                Ok(stream) => {
+                    let _ = self.client.on_read(stream);
+                    Err(String::from("the remote stream doesn't support seek"))
+                }
+                Err(err) => Err(err.to_string()),
+            },
+            false => Err(String::from("the local file is not a partial copy")),
+        };
+        let streams = match reader {
+            Ok(reader) => match self.host_bridge.append_file(host_bridge, &remote.metadata) {
+                Ok(writer) => Ok((reader, writer)),
                 Err(err) => {
-                    return Err(TransferErrorReason::RemoteIoError(err));
+                    let _ = self.client.on_read(reader);
+                    Err(err.to_string())
                 }
-            };
-            // Set progress
-            self.transfer.partial.update_progress(delta);
-            self.transfer.full.update_progress(delta);
-            // Draw only if a significant progress has been made (performance improvement)
-            if last_progress_val < self.transfer.partial.calc_progress() - 0.01 {
-                // Draw
-                self.update_progress_bar(format!("Downloading \"{file_name}\""));
-                self.view();
-                last_progress_val = self.transfer.partial.calc_progress();
+            },
+            Err(err) => Err(err),
+        };
+        match streams {
+            Ok((reader, writer)) => {
+                self.log(
+                    LogLevel::Info,
+                    format!(
+                        "Resuming download of \"{}\" to \"{}\" from {}",
+                        remote.path.display(),
+                        host_bridge.display(),
+                        ByteSize(offset as u64)
+                    ),
+                );
+                self.filetransfer_recv_one_with_stream(
+                    host_bridge,
+                    remote,
+                    file_name,
+                    reader,
+                    writer,
+                    offset,
+                )
+            }
+            Err(err) => {
+                self.log(
+                    LogLevel::Warn,
+                    format!(
+                        "Could not resume download of \"{}\" ({err}): the whole file will be received",
+                        remote.path.display()
+                    ),
+                );
+                self.transfer.full.init(remote.metadata.size as usize);
+                self.filetransfer_recv_one(host_bridge, remote, file_name)
             }
         }
+    }
+
+    /// Receive an `File` from remote using stream.
+    /// The first `offset` bytes of the file have already been received, and `reader` starts after them
+    fn filetransfer_recv_one_with_stream(
+        &mut self,
+        host_bridge: &Path,
+        remote: &File,
+        file_name: String,
+        mut reader: ReadStream,
+        writer: Box<dyn Write + Send>,
+        offset: usize,
+    ) -> Result<(), TransferErrorReason> {
+        let mut writer = ProgressWriter::new(writer, self.transfer.progress());
+        // Init transfer
+        self.transfer
+            .partial
+            .init_resumed(remote.metadata.size as usize, offset);
+        // Write host_bridge file
+        let result = self.copy_stream(
+            &mut reader,
+            &mut writer,
+            format!("Downloading \"{file_name}\""),
+        );
         // Finalize stream
         if let Err(err) = self.client.on_read(reader) {
             self.log(
@@ -1057,24 +2212,18 @@ impl FileTransferActivity {
         if self.transfer.aborted() {
             return Err(TransferErrorReason::Abrupted);
         }
+        match result {
+            Err(CopyError::Read(err)) => return Err(TransferErrorReason::RemoteIoError(err)),
+            Err(CopyError::Write(err)) => return Err(TransferErrorReason::HostIoError(err)),
+            Err(CopyError::Aborted) | Ok(_) => {}
+        }
 
         // finalize write
         self.host_bridge
-            .finalize_write(writer)
+            .finalize_write(writer.into_inner())
             .map_err(TransferErrorReason::HostError)?;
 
-        // Apply file mode to file
-        if let Err(err) = self.host_bridge.setstat(host_bridge, remote.metadata()) {
-            self.log(
-                LogLevel::Error,
-                format!(
-                    "Could not set stat to file {:?} to \"{}\": {}",
-                    remote.metadata(),
-                    host_bridge.display(),
-                    err
-                ),
-            );
-        }
+        self.apply_host_bridge_metadata(host_bridge, remote.metadata());
         // Log
         self.log(
             LogLevel::Info,
@@ -1098,42 +2247,30 @@ impl FileTransferActivity {
         file_name: String,
     ) -> Result<(), TransferErrorReason> {
         // Open host_bridge file
-        let reader = self
+        let writer = self
             .host_bridge
             .create_file(host_bridge, &remote.metadata)
-            .map_err(TransferErrorReason::HostError)
-            .map(Box::new)?;
+            .map_err(TransferErrorReason::HostError)?;
         // Init transfer
         self.transfer.partial.init(remote.metadata.size as usize);
         // Draw before transfer
         self.update_progress_bar(format!("Downloading \"{file_name}\""));
         self.view();
-        // recv wno stream
-        if let Err(err) = self.client.open_file(remote.path.as_path(), reader) {
-            return Err(TransferErrorReason::FileTransferError(err));
+        // recv wno stream; progress is reported by the writer for each chunk
+        let writer = Box::new(ProgressWriter::new(writer, self.transfer.progress()));
+        if let Err(err) = self.client.open_file(remote.path.as_path(), writer) {
+            self.transfer.sync_progress();
+            return match self.transfer.aborted() {
+                true => Err(TransferErrorReason::Abrupted),
+                false => Err(TransferErrorReason::FileTransferError(err)),
+            };
         }
         // Update progress at the end
-        self.transfer
-            .partial
-            .update_progress(remote.metadata.size as usize);
-        self.transfer
-            .full
-            .update_progress(remote.metadata.size as usize);
+        self.transfer.sync_progress();
         // Draw after transfer
         self.update_progress_bar(format!("Downloading \"{file_name}\""));
         self.view();
-        // Apply file mode to file
-        if let Err(err) = self.host_bridge.setstat(host_bridge, remote.metadata()) {
-            self.log(
-                LogLevel::Error,
-                format!(
-                    "Could not set stat to file {:?} to \"{}\": {}",
-                    remote.metadata(),
-                    host_bridge.display(),
-                    err
-                ),
-            );
-        }
+        self.apply_host_bridge_metadata(host_bridge, remote.metadata());
         // Log
         self.log(
             LogLevel::Info,
@@ -1218,6 +2355,7 @@ impl FileTransferActivity {
                     self.remote_mut().pushd(prev_dir.as_path())
                 }
             }
+            Err(err) if self.check_connection_lost(&err) => {}
             Err(err) => {
                 // Report err
                 self.log_and_alert(
@@ -1230,23 +2368,18 @@ impl FileTransferActivity {
 
     /// Download provided file as a temporary file
     pub(super) fn download_file_as_temp(&mut self, file: &File) -> Result<PathBuf, String> {
-        let tmpfile: PathBuf = match self.cache.as_ref() {
-            Some(cache) => {
-                let mut p: PathBuf = cache.path().to_path_buf();
+        let tmpfile: PathBuf = match self.cache_path() {
+            Ok(mut p) => {
                 p.push(file.name());
                 p
             }
-            None => {
-                return Err(String::from(
-                    "Could not create tempfile: cache not available",
-                ))
-            }
+            Err(err) => return Err(format!("Could not create tempfile: {err}")),
         };
         // Download file
         match self.filetransfer_recv(
             TransferPayload::File(file.clone()),
             tmpfile.as_path(),
-            Some(file.name()),
+            TransferOpts::default().save_as(Some(file.name())),
         ) {
             Err(err) => Err(format!(
                 "Could not download {} to temporary file: {}",
@@ -1259,55 +2392,771 @@ impl FileTransferActivity {
 
     // -- transfer sizes
 
-    /// Get total size of transfer for host_bridgehost
-    fn get_total_transfer_size_host(&mut self, entry: &File) -> usize {
-        if entry.is_dir() {
-            // List dir
-            match self.host_bridge.list_dir(entry.path()) {
-                Ok(files) => files
-                    .iter()
-                    .map(|x| self.get_total_transfer_size_host(x))
-                    .sum(),
-                Err(err) => {
+    /// Estimate the total size of the transfer, listing directories with `list_dir_fn`.
+    /// The pre-scan is bounded by `PRESCAN_MAX_ENTRIES` and `PRESCAN_TIMEOUT`; if the estimate exceeds
+    /// the configured thresholds, or the scan couldn't be completed within the bounds, the user is asked
+    /// to confirm the transfer and, once confirmed, the scan is completed.
+    /// If `schedulable`, the user may schedule the transfer for later instead.
+    ///
+    /// Returns `Err(Some(time))` if the user scheduled the transfer at `time`, and `Err(None)` if they aborted the scan
+    /// or refused the transfer
+    fn estimate_transfer_size<F>(
+        &mut self,
+        payload: &TransferPayload,
+        dest: &Path,
+        schedulable: bool,
+        list_dir_fn: F,
+    ) -> Result<usize, Option<DateTime<Local>>>
+    where
+        F: Fn(&mut Self, &Path) -> Result<Vec<File>, String> + Copy,
+    {
+        let mut estimate = match payload {
+            TransferPayload::File(file) | TransferPayload::Resume(file) => {
+                return Ok(file.metadata.size as usize)
+            }
+            TransferPayload::Any(entry) => TransferEstimate::new(std::slice::from_ref(entry)),
+            TransferPayload::Many(entries) => TransferEstimate::new(entries),
+        };
+        self.transfer.reset();
+        if !estimate.is_complete() {
+            self.mount_transfer_scan_wait();
+        }
+        // bounded pre-scan
+        let started = Instant::now();
+        while !estimate.is_complete()
+            && estimate.entries() < PRESCAN_MAX_ENTRIES
+            && started.elapsed() < PRESCAN_TIMEOUT
+        {
+            self.scan_transfer_next(&mut estimate, list_dir_fn)
+                .map_err(|_| None)?;
+        }
+        // ask for confirmation
+        let files_threshold = self.config().get_transfer_prompt_files_threshold();
+        let size_threshold = self.config().get_transfer_prompt_size_threshold();
+        let prompt = match estimate.is_complete() {
+            true => estimate.exceeds(files_threshold, size_threshold),
+            false => files_threshold > 0 || size_threshold > 0,
+        };
+        if prompt {
+            self.umount_wait();
+            match self.should_transfer_payload(&estimate, dest, schedulable) {
+                TransferConfirmation::Transfer => {}
+                TransferConfirmation::Schedule(at) => return Err(Some(at)),
+                TransferConfirmation::Cancel => {
                     self.log(
-                        LogLevel::Error,
+                        LogLevel::Info,
                         format!(
-                            "Could not list directory {}: {}",
-                            entry.path().display(),
-                            err
+                            "Transfer of {estimate} to \"{}\" cancelled by the user",
+                            dest.display()
                         ),
                     );
-                    0
+                    return Err(None);
                 }
             }
-        } else {
-            entry.metadata.size as usize
+            if !estimate.is_complete() {
+                self.mount_transfer_scan_wait();
+            }
+        }
+        // complete the scan to get the total size of the transfer
+        while !estimate.is_complete() {
+            self.scan_transfer_next(&mut estimate, list_dir_fn)
+                .map_err(|_| None)?;
         }
+        self.umount_wait();
+        Ok(estimate.size())
     }
 
-    /// Get total size of transfer for remote host
-    fn get_total_transfer_size_remote(&mut self, entry: &File) -> usize {
-        if entry.is_dir() {
-            // List directory
-            match self.client.list_dir(entry.path()) {
-                Ok(files) => files
-                    .iter()
-                    .map(|x| self.get_total_transfer_size_remote(x))
-                    .sum(),
-                Err(err) => {
-                    self.log(
-                        LogLevel::Error,
-                        format!(
-                            "Could not list directory {}: {}",
-                            entry.path().display(),
-                            err
-                        ),
-                    );
-                    0
+    /// Get the total size of the files of `entries`, which are transferred in `direction`, scanning their directories.
+    /// Returns `None` if the user aborted the scan
+    pub(super) fn scan_queued_transfer_size(
+        &mut self,
+        direction: TransferDirection,
+        entries: &[File],
+    ) -> Option<u64> {
+        let mut estimate = TransferEstimate::new(entries);
+        self.transfer.reset();
+        if !estimate.is_complete() {
+            self.mount_transfer_scan_wait();
+        }
+        while !estimate.is_complete() {
+            let result = match direction {
+                TransferDirection::Upload => {
+                    self.scan_transfer_next(&mut estimate, |activity, path| {
+                        activity
+                            .host_bridge
+                            .list_dir(path)
+                            .map_err(|e| e.to_string())
+                    })
                 }
+                TransferDirection::Download => self
+                    .scan_transfer_next(&mut estimate, |activity, path| {
+                        activity.client.list_dir(path).map_err(|e| e.to_string())
+                    }),
+            };
+            result.ok()?;
+        }
+        self.umount_wait();
+        Some(estimate.size() as u64)
+    }
+
+    /// Put `payload` in the transfer queue, to be transferred to `dest` once `at` has come.
+    /// If set, `save_as` is the name of a single entry on the destination
+    fn schedule_transfer(
+        &mut self,
+        direction: TransferDirection,
+        payload: TransferPayload,
+        dest: &Path,
+        save_as: Option<String>,
+        at: DateTime<Local>,
+    ) {
+        let entries: Vec<(File, PathBuf)> = match payload {
+            TransferPayload::Any(entry)
+            | TransferPayload::File(entry)
+            | TransferPayload::Resume(entry) => {
+                let name = save_as.unwrap_or_else(|| entry.name());
+                vec![(entry, dest.join(name))]
             }
-        } else {
-            entry.metadata.size as usize
+            TransferPayload::Many(entries) => entries
+                .into_iter()
+                .map(|entry| {
+                    let dest = dest.join(entry.name());
+                    (entry, dest)
+                })
+                .collect(),
+        };
+        let start = schedule::fmt_start_time(at, Local::now());
+        for (entry, dest) in entries {
+            self.log(
+                LogLevel::Info,
+                format!(
+                    "Scheduled transfer of \"{}\" to \"{}\" at {start}",
+                    entry.path().display(),
+                    dest.display()
+                ),
+            );
+            self.transfer_queue.schedule(direction, entry, dest, at);
+        }
+        self.refresh_footer_bar();
+    }
+
+    /// Scan the next pending directory of `estimate` and read input events.
+    /// Returns error if the user aborted the scan
+    fn scan_transfer_next<F>(
+        &mut self,
+        estimate: &mut TransferEstimate,
+        list_dir_fn: F,
+    ) -> Result<(), TransferErrorReason>
+    where
+        F: Fn(&mut Self, &Path) -> Result<Vec<File>, String>,
+    {
+        if let Err((path, err)) = estimate.scan_next(|path| list_dir_fn(self, path)) {
+            self.log(
+                LogLevel::Error,
+                format!("Could not list directory {}: {}", path.display(), err),
+            );
+        }
+        self.update_transfer_scan_entries(estimate.entries());
+        // read events
+        self.tick();
+        if self.transfer.aborted() {
+            self.umount_wait();
+            self.log(
+                LogLevel::Warn,
+                String::from("Transfer aborted while estimating its size"),
+            );
+            return Err(TransferErrorReason::Abrupted);
+        }
+        Ok(())
+    }
+
+    /// Resolve the destination directory of an upload, `dest`, as the remote sees it
+    pub(super) fn resolve_remote_destination(&mut self, dest: &Path) -> PathBuf {
+        let wrkdir = match self.client.pwd() {
+            Ok(wrkdir) => wrkdir,
+            Err(_) => self.remote().wrkdir.clone(),
+        };
+        transfer::resolve_destination(wrkdir.as_path(), dest)
+    }
+
+    /// Resolve the destination directory of a download, `dest`, as the host bridge sees it
+    pub(super) fn resolve_host_bridge_destination(&mut self, dest: &Path) -> PathBuf {
+        let wrkdir = match self.host_bridge.pwd() {
+            Ok(wrkdir) => wrkdir,
+            Err(_) => self.host_bridge().wrkdir.clone(),
+        };
+        transfer::resolve_destination(wrkdir.as_path(), dest)
+    }
+
+    /// Get the tracker of the directories visited on the host bridge
+    pub(super) fn host_bridge_visited_dirs(&self) -> VisitedDirs {
+        match self.host_bridge.is_localhost() {
+            true => VisitedDirs::local(),
+            false => VisitedDirs::remote(),
+        }
+    }
+
+    /// Create on remote at `remote_path` a symbolic link pointing at `target`, replacing the file at `remote_path` if any.
+    /// Returns `false` if the protocol doesn't support symbolic links, so the link must be followed instead
+    fn filetransfer_send_symlink(
+        &mut self,
+        remote_path: &Path,
+        target: &Path,
+    ) -> Result<bool, String> {
+        if let Ok(file) = self.client.stat(remote_path) {
+            if !file.is_dir() {
+                let _ = self.client.remove_file(remote_path);
+            }
+        }
+        match self.client.symlink(remote_path, target) {
+            Ok(()) => {
+                self.log_symlink_created(remote_path, target);
+                Ok(true)
+            }
+            Err(err) if err.kind == RemoteErrorType::UnsupportedFeature => {
+                self.log_symlink_unsupported(remote_path);
+                Ok(false)
+            }
+            Err(err) => {
+                let err = format!(
+                    "Failed to create symlink \"{}\": {}",
+                    remote_path.display(),
+                    err
+                );
+                self.log(LogLevel::Error, err.clone());
+                Err(err)
+            }
+        }
+    }
+
+    /// Create on the host bridge at `path` a symbolic link pointing at `target`, replacing the file at `path` if any.
+    /// Returns `false` if the host bridge doesn't support symbolic links, so the link must be followed instead
+    fn filetransfer_recv_symlink(&mut self, path: &Path, target: &Path) -> Result<bool, String> {
+        if let Ok(file) = self.host_bridge.stat(path) {
+            if !file.is_dir() {
+                let _ = self.host_bridge.remove(&file);
+            }
+        }
+        match self.host_bridge.symlink(path, target) {
+            Ok(()) => {
+                self.log_symlink_created(path, target);
+                Ok(true)
+            }
+            Err(err)
+                if matches!(
+                    &err.error,
+                    HostErrorType::NotImplemented
+                        | HostErrorType::RemoteFs(RemoteError {
+                            kind: RemoteErrorType::UnsupportedFeature,
+                            ..
+                        })
+                ) =>
+            {
+                self.log_symlink_unsupported(path);
+                Ok(false)
+            }
+            Err(err) => {
+                let err = format!("Failed to create symlink \"{}\": {}", path.display(), err);
+                self.log(LogLevel::Error, err.clone());
+                Err(err)
+            }
+        }
+    }
+
+    fn log_symlink_created(&mut self, path: &Path, target: &Path) {
+        self.log(
+            LogLevel::Info,
+            format!(
+                "Created symlink \"{}\" pointing at \"{}\"",
+                path.display(),
+                target.display()
+            ),
+        );
+    }
+
+    fn log_symlink_unsupported(&mut self, path: &Path) {
+        self.log(
+            LogLevel::Warn,
+            format!(
+                "Symbolic links are not supported at the destination: following \"{}\"",
+                path.display()
+            ),
+        );
+    }
+
+    /// Log that `entry` won't be entered, since it's a symbolic link loop
+    pub(super) fn log_symlink_loop(&mut self, entry: &File) {
+        self.log(
+            LogLevel::Warn,
+            format!(
+                "Skipped \"{}\": symbolic link loop detected",
+                entry.path().display()
+            ),
+        );
+    }
+
+    /// Transfer `jobs` with `concurrency` workers, each one with its own connections to the hosts.
+    /// The errors of the single files are collected, along with the ones in `errors`, and reported once all
+    /// the workers have terminated
+    fn filetransfer_parallel(
+        &mut self,
+        direction: TransferDirection,
+        jobs: Vec<TransferJob>,
+        mut errors: Vec<String>,
+        concurrency: usize,
+    ) -> Result<(), String> {
+        let verb = match direction {
+            TransferDirection::Upload => "Uploading",
+            TransferDirection::Download => "Downloading",
+        };
+        let total_files = jobs.len();
+        // partial progress counts the files terminated
+        self.transfer.partial.init(total_files);
+        let pending = match self.transfer.aborted() {
+            true => jobs,
+            false => {
+                let host_bridge_params = self.context().host_bridge_params().unwrap().clone();
+                let remote_params = self.context().remote_params().unwrap().clone();
+                let config_client = self.config().clone();
+                let mtime_tolerance = self.clock_skew.tolerance();
+                let compare_mtime = self.sync.as_ref().is_none_or(|x| x.compare_mtime());
+                let preserve_metadata = self.metadata.is_enabled();
+                let modes = self.remote_modes();
+                let workers = TransferWorkers::spawn(
+                    concurrency,
+                    jobs,
+                    self.transfer.progress(),
+                    move || {
+                        RemoteSession::connect(
+                            direction,
+                            host_bridge_params.clone(),
+                            remote_params.clone(),
+                            &config_client,
+                        )
+                        .map(|session| {
+                            session
+                                .mtime_tolerance(mtime_tolerance)
+                                .compare_mtime(compare_mtime)
+                                .preserve_metadata(preserve_metadata)
+                                .modes(modes)
+                        })
+                    },
+                );
+                self.log(
+                    LogLevel::Info,
+                    format!(
+                        "{verb} {total_files} files with up to {concurrency} parallel connections"
+                    ),
+                );
+                while !workers.is_finished() {
+                    // Read events
+                    self.tick();
+                    for event in workers.poll() {
+                        self.on_worker_event(event, &mut errors);
+                    }
+                    self.transfer.sync_full_progress();
+                    self.update_progress_bar(format!("{verb} {total_files} files…"));
+                    self.view();
+                    thread::sleep(WORKERS_POLL_INTERVAL);
+                }
+                let (events, pending) = workers.join();
+                for event in events {
+                    self.on_worker_event(event, &mut errors);
+                }
+                self.transfer.sync_full_progress();
+                pending
+            }
+        };
+        if self.transfer.aborted() {
+            self.log_and_alert(
+                LogLevel::Warn,
+                format!(
+                    "{verb} aborted: {} of {total_files} files have not been transferred",
+                    pending.len()
+                ),
+            );
+            return Err(TransferErrorReason::Abrupted.to_string());
+        }
+        let failed = errors.len() + pending.len();
+        if !pending.is_empty() {
+            errors.push(format!(
+                "{} files have not been transferred, since no worker could connect",
+                pending.len()
+            ));
+        }
+        if let Some(first) = errors.first() {
+            self.transfer.count_failures(failed, first);
+        }
+        for error in errors.iter() {
+            self.log(LogLevel::Warn, error.clone());
+        }
+        match errors.len() {
+            0 => Ok(()),
+            1 => Err(errors.remove(0)),
+            n => Err(format!(
+                "{n} errors occurred during the transfer (see the log panel); first error: {}",
+                errors[0]
+            )),
+        }
+    }
+
+    /// Handle an event reported by a transfer worker
+    fn on_worker_event(&mut self, event: WorkerEvent, errors: &mut Vec<String>) {
+        match event {
+            WorkerEvent::ConnectionFailed(err) => self.log(
+                LogLevel::Warn,
+                format!("A transfer worker could not connect: {err}"),
+            ),
+            WorkerEvent::Done(job, Ok(JobOutcome::Transferred)) => {
+                self.transfer.partial.update_progress(1);
+                self.count_file(true);
+                self.log(
+                    LogLevel::Info,
+                    format!(
+                        "Saved file \"{}\" to \"{}\"",
+                        job.source.path().display(),
+                        job.dest.display()
+                    ),
+                );
+            }
+            WorkerEvent::Done(job, Ok(JobOutcome::Unchanged)) => {
+                self.transfer.partial.update_progress(1);
+                self.count_file(false);
+                self.transfer
+                    .full
+                    .update_progress(job.source.metadata().size as usize);
+                self.log(
+                    LogLevel::Info,
+                    format!(
+                        "file {} won't be transferred since hasn't changed",
+                        job.source.path().display()
+                    ),
+                );
+            }
+            WorkerEvent::Done(job, Err(err)) => {
+                self.transfer.partial.update_progress(1);
+                // errors caused by the abort are not reported
+                if !self.transfer.aborted() {
+                    errors.push(format!(
+                        "Could not transfer \"{}\": {}",
+                        job.source.path().display(),
+                        err
+                    ));
+                    self.transfer
+                        .push_failure(TransferFailure::new(&job.source, job.dest, err));
+                }
+            }
+        }
+    }
+
+    // -- sync
+
+    /// Start a transfer in sync `mode`, if any, towards the host bridge or the remote, according to `direction`.
+    /// Files are compared by size only if the destination doesn't preserve modification times
+    fn start_sync(&mut self, mode: Option<SyncMode>, direction: TransferDirection) {
+        let Some(mode) = mode else {
+            self.sync = None;
+            return;
+        };
+        let protocol = self.destination_protocol(direction);
+        let compare_mtime = protocol.is_none_or(|x| x.preserves_mtime());
+        if let Some(protocol) = protocol.filter(|_| !compare_mtime) {
+            self.log(
+                LogLevel::Warn,
+                format!(
+                    "{protocol} doesn't preserve modification times: files are compared by size only"
+                ),
+            );
+        }
+        self.sync = Some(SyncState::new(mode, compare_mtime));
+    }
+
+    /// Get the protocol of the destination of a transfer in `direction`; `None` if it's the local host
+    pub(super) fn destination_protocol(
+        &self,
+        direction: TransferDirection,
+    ) -> Option<FileTransferProtocol> {
+        match direction {
+            TransferDirection::Upload => self.context().remote_params().map(|x| x.protocol),
+            TransferDirection::Download => match self.context().host_bridge_params() {
+                Some(HostBridgeParams::Remote(protocol, _)) => Some(*protocol),
+                _ => None,
+            },
+        }
+    }
+
+    /// Whether the metadata of the source can be applied to the destination of a transfer in `direction`
+    pub(super) fn can_preserve_metadata(&self, direction: TransferDirection) -> bool {
+        self.destination_protocol(direction)
+            .is_none_or(|x| x.preserves_mtime())
+    }
+
+    /// Start applying the metadata of the source to the destination of the transfer, if `preserve` is true.
+    /// If the destination can't set times, this is logged once for the whole transfer
+    fn start_preserving_metadata(&mut self, preserve: bool, direction: TransferDirection) {
+        let supported = self.can_preserve_metadata(direction);
+        if let Some(protocol) = self
+            .destination_protocol(direction)
+            .filter(|_| preserve && !supported)
+        {
+            self.log(
+                LogLevel::Warn,
+                format!(
+                    "{protocol} can't set modification times: file metadata won't be preserved"
+                ),
+            );
+        }
+        self.metadata = MetadataState::new(preserve && supported);
+    }
+
+    /// Apply the metadata of their source to the directories created by the transfer, now that their content has
+    /// been written
+    fn finish_preserving_metadata(&mut self, direction: TransferDirection) {
+        for (path, metadata) in self.metadata.take_dirs() {
+            let result = match direction {
+                TransferDirection::Upload => self
+                    .client
+                    .setstat(path.as_path(), metadata)
+                    .map_err(|err| err.to_string()),
+                TransferDirection::Download => self
+                    .host_bridge
+                    .setstat(path.as_path(), &metadata)
+                    .map_err(|err| err.to_string()),
+            };
+            if let Err(err) = result {
+                self.log(
+                    LogLevel::Error,
+                    format!(
+                        "Could not set stat to directory \"{}\": {}",
+                        path.display(),
+                        err
+                    ),
+                );
+            }
+        }
+    }
+
+    /// Apply `metadata` to the file written on the remote at `path`, if metadata must be preserved
+    fn apply_remote_metadata(&mut self, path: &Path, metadata: Metadata) {
+        if !self.metadata.is_enabled() {
+            return;
+        }
+        if let Err(err) = self.client.setstat(path, metadata) {
+            error!("failed to set stat for {}: {}", path.display(), err);
+        }
+    }
+
+    /// Get the modes to apply to the entries created on the remote, if its protocol can change them
+    pub(super) fn remote_modes(&self) -> ModePrefs {
+        self.context()
+            .remote_params()
+            .map(|params| metadata::remote_modes(params, self.config()))
+            .unwrap_or_default()
+    }
+
+    /// Apply the configured mode to the entry created on the remote at `path`, if any.
+    /// Failures are logged, but don't abort the transfer
+    pub(super) fn apply_remote_mode(&mut self, path: &Path, dir: bool) {
+        let Some(mode) = self.remote_modes().mode(dir) else {
+            return;
+        };
+        if let Err(err) = metadata::set_remote_mode(self.client.as_mut(), path, mode) {
+            self.log(
+                LogLevel::Warn,
+                format!(
+                    "Could not set mode {:04o} to \"{}\": {}",
+                    u32::from(mode),
+                    path.display(),
+                    err
+                ),
+            );
+        }
+    }
+
+    /// Apply `metadata` to the file written on the host bridge at `path`, if metadata must be preserved
+    fn apply_host_bridge_metadata(&mut self, path: &Path, metadata: &Metadata) {
+        if !self.metadata.is_enabled() {
+            return;
+        }
+        if let Err(err) = self.host_bridge.setstat(path, metadata) {
+            self.log(
+                LogLevel::Error,
+                format!(
+                    "Could not set stat to file {:?} to \"{}\": {}",
+                    metadata,
+                    path.display(),
+                    err
+                ),
+            );
+        }
+    }
+
+    /// Keep the entry `source` which couldn't be transferred to `dest`, while the transfer goes on with the next
+    /// entries, so that it can be retried once the transfer has terminated
+    fn record_transfer_failure(&mut self, source: &File, dest: PathBuf, error: String) {
+        self.log(
+            LogLevel::Warn,
+            format!(
+                "Could not transfer \"{}\" to \"{}\": {}",
+                source.path().display(),
+                dest.display(),
+                error
+            ),
+        );
+        self.transfer.count_failures(1, &error);
+        self.transfer
+            .push_failure(TransferFailure::new(source, dest, error));
+    }
+
+    /// Collect the entries which couldn't be transferred in `direction`, unless the transfer has been aborted.
+    /// If any, they're listed in the failed transfers popup, from which they can be retried, and the transfer fails
+    fn collect_failed_transfers(
+        &mut self,
+        direction: TransferDirection,
+        result: Result<(), String>,
+    ) -> Result<(), String> {
+        let failures = self.transfer.take_failures();
+        if failures.is_empty() || self.transfer.aborted() {
+            return result;
+        }
+        let failed = format!(
+            "{} entries could not be transferred; first error: {}",
+            failures.len(),
+            failures[0].error
+        );
+        self.mount_failed_transfers(&failures);
+        self.failed_transfers = Some(FailedTransfers {
+            direction,
+            failures,
+        });
+        result.and(Err(failed))
+    }
+
+    /// Transfer again the entries which couldn't be transferred by the last transfer
+    pub(super) fn filetransfer_retry(&mut self, failed: FailedTransfers) -> Result<(), String> {
+        let FailedTransfers {
+            direction,
+            failures,
+        } = failed;
+        let opts = self.transfer_opts();
+        // Reset states
+        self.transfer.reset();
+        self.transfer.full.init(
+            failures
+                .iter()
+                .map(|x| x.source.metadata().size as usize)
+                .sum(),
+        );
+        // Mount progress bar
+        self.mount_progress_bar(format!("Retrying {} entries…", failures.len()));
+        self.start_preserving_metadata(opts.preserve_metadata, direction);
+        let mut visited = match direction {
+            TransferDirection::Upload => self.host_bridge_visited_dirs(),
+            TransferDirection::Download => VisitedDirs::remote(),
+        }
+        .preserve_symlinks(opts.preserve_symlinks);
+        let mut result = Ok(());
+        for failure in failures {
+            if self.transfer.aborted() {
+                break;
+            }
+            let (Some(dir), Some(name)) = (failure.dest.parent(), failure.dest.file_name()) else {
+                continue;
+            };
+            let name = Some(name.to_string_lossy().to_string());
+            let retried = match direction {
+                TransferDirection::Upload => {
+                    self.filetransfer_send_recurse(&failure.source, dir, name, &mut visited)
+                }
+                TransferDirection::Download => {
+                    self.filetransfer_recv_recurse(&failure.source, dir, name, &mut visited)
+                }
+            };
+            if let Err(err) = retried {
+                if self.transfer.aborted() {
+                    result = Err(err);
+                    break;
+                }
+                self.record_transfer_failure(&failure.source, failure.dest.clone(), err);
+            }
+        }
+        // Umount progress bar
+        self.umount_progress_bar();
+        self.finish_preserving_metadata(direction);
+        let result = self.collect_failed_transfers(direction, result);
+        self.reload_host_bridge_dir();
+        self.reload_remote_dir();
+        result
+    }
+
+    /// Terminate the transfer in sync mode, if any, logging its summary
+    fn finish_sync(&mut self) {
+        if let Some(sync) = self.sync.take() {
+            self.log(LogLevel::Info, format!("Sync completed: {sync}"));
+        }
+    }
+
+    /// Count a file which has been copied or skipped in the summary of the transfer and, in sync mode, of the sync
+    fn count_file(&mut self, copied: bool) {
+        self.transfer.count_file();
+        if let Some(sync) = self.sync.as_mut() {
+            match copied {
+                true => sync.copied += 1,
+                false => sync.skipped += 1,
+            }
+        }
+    }
+
+    /// In mirror mode, delete the entries of the remote directory at `remote_path` which don't exist in `source`
+    fn mirror_remote_dir(&mut self, source: &[File], remote_path: &Path) {
+        if !self.sync.as_ref().is_some_and(SyncState::is_mirror) || self.transfer.aborted() {
+            return;
+        }
+        let dest = match self.client.list_dir(remote_path) {
+            Ok(dest) => dest,
+            Err(err) => {
+                self.log(
+                    LogLevel::Error,
+                    format!(
+                        "Could not scan directory \"{}\": {}",
+                        remote_path.display(),
+                        err
+                    ),
+                );
+                return;
+            }
+        };
+        for entry in SyncState::extraneous(source, &dest) {
+            if self.remote_remove_file(entry) {
+                self.count_sync_deleted();
+            }
+        }
+    }
+
+    /// In mirror mode, delete the entries of the host bridge directory at `path` which don't exist in `source`
+    fn mirror_host_bridge_dir(&mut self, source: &[File], path: &Path) {
+        if !self.sync.as_ref().is_some_and(SyncState::is_mirror) || self.transfer.aborted() {
+            return;
+        }
+        let dest = match self.host_bridge.list_dir(path) {
+            Ok(dest) => dest,
+            Err(err) => {
+                self.log(
+                    LogLevel::Error,
+                    format!("Could not scan directory \"{}\": {}", path.display(), err),
+                );
+                return;
+            }
+        };
+        for entry in SyncState::extraneous(source, &dest) {
+            if self.local_remove_file(entry) {
+                self.count_sync_deleted();
+            }
+        }
+    }
+
+    fn count_sync_deleted(&mut self) {
+        if let Some(sync) = self.sync.as_mut() {
+            sync.deleted += 1;
         }
     }
 
@@ -1317,8 +3166,7 @@ impl FileTransferActivity {
     fn has_host_bridge_file_changed(&mut self, host_bridge: &Path, remote: &File) -> bool {
         // check if files are equal (in case, don't transfer)
         if let Ok(host_bridge_file) = self.host_bridge.stat(host_bridge) {
-            host_bridge_file.metadata().modified != remote.metadata().modified
-                || host_bridge_file.metadata().size != remote.metadata().size
+            !self.is_file_unchanged(remote.metadata(), host_bridge_file.metadata())
         } else {
             true
         }
@@ -1328,13 +3176,25 @@ impl FileTransferActivity {
     fn has_remote_file_changed(&mut self, remote: &Path, host_bridge_metadata: &Metadata) -> bool {
         // check if files are equal (in case, don't transfer)
         if let Ok(remote_file) = self.client.stat(remote) {
-            host_bridge_metadata.modified != remote_file.metadata().modified
-                || host_bridge_metadata.size != remote_file.metadata().size
+            !self.is_file_unchanged(host_bridge_metadata, remote_file.metadata())
         } else {
             true
         }
     }
 
+    /// Files with the same size and modification time are considered equal.
+    /// In sync mode, modification times are not compared if the destination doesn't preserve them
+    fn is_file_unchanged(&self, source: &Metadata, dest: &Metadata) -> bool {
+        let tolerance = self.clock_skew.tolerance();
+        match self.sync.as_ref() {
+            Some(sync) => sync.is_unchanged(source, dest, tolerance),
+            None => {
+                source.size == dest.size
+                    && clock_skew::mtime_equal(source.modified, dest.modified, tolerance)
+            }
+        }
+    }
+
     // -- file exist
 
     pub(crate) fn host_bridge_file_exists(&mut self, p: &Path) -> bool {
@@ -4,14 +4,15 @@
 
 // locals
 // externals
-use remotefs::fs::File;
 use tuirealm::props::{AttrValue, Attribute};
 use tuirealm::{State, StateValue, Update};
 
 use super::actions::walkdir::WalkdirError;
 use super::actions::SelectedFile;
 use super::browser::{FileExplorerTab, FoundExplorerTab};
-use super::{ExitReason, FileTransferActivity, Id, Msg, TransferMsg, TransferOpts, UiMsg};
+use super::lib::walkdir::{WalkdirResults, SPILL_PAGE_SIZE};
+use super::{ExitReason, FileTransferActivity, Id, LogLevel, Msg, TransferMsg, UiMsg};
+use crate::utils::clipboard;
 
 impl Update<Msg> for FileTransferActivity {
     fn update(&mut self, msg: Option<Msg>) -> Option<Msg> {
@@ -36,23 +37,44 @@ impl FileTransferActivity {
             TransferMsg::AbortWalkdir => {
                 self.walkdir.aborted = true;
             }
-            TransferMsg::Chmod(mode) => {
+            TransferMsg::BookmarkDirectory(name) => {
+                self.umount_bookmark_dir();
+                self.action_bookmark_dir(name);
+            }
+            TransferMsg::ExportLog(path) => {
+                self.umount_log_export();
+                self.action_export_log(path);
+            }
+            TransferMsg::CompareChecksums => {
+                self.action_compare_checksums();
+            }
+            TransferMsg::ComputeChecksum(algorithm) => {
+                self.umount_file_info();
+                self.action_checksum(algorithm);
+            }
+            TransferMsg::Chmod(mode, recursive) => {
                 self.umount_chmod();
-                self.mount_blocking_wait("Applying new file mode…");
+                // large trees may take a while: the progress is displayed in the wait popup
+                match recursive {
+                    true => self.mount_blocking_wait("Applying new file mode recursively…"),
+                    false => self.mount_panel_wait("Applying new file mode…"),
+                }
                 match self.browser.tab() {
                     FileExplorerTab::HostBridge | FileExplorerTab::FindHostBridge
                         if self.host_bridge.is_localhost() && cfg!(windows) => {}
-                    FileExplorerTab::HostBridge => self.action_local_chmod(mode),
-                    FileExplorerTab::FindHostBridge => self.action_find_local_chmod(mode),
-                    FileExplorerTab::Remote => self.action_remote_chmod(mode),
-                    FileExplorerTab::FindRemote => self.action_find_remote_chmod(mode),
+                    FileExplorerTab::HostBridge => self.action_local_chmod(mode, recursive),
+                    FileExplorerTab::FindHostBridge => {
+                        self.action_find_local_chmod(mode, recursive)
+                    }
+                    FileExplorerTab::Remote => self.action_remote_chmod(mode, recursive),
+                    FileExplorerTab::FindRemote => self.action_find_remote_chmod(mode, recursive),
                 }
                 self.umount_wait();
                 self.update_browser_file_list();
             }
             TransferMsg::CopyFileTo(dest) => {
                 self.umount_copy();
-                self.mount_blocking_wait("Copying file(s)…");
+                self.mount_panel_wait("Copying file(s)…");
                 match self.browser.tab() {
                     FileExplorerTab::HostBridge => self.action_local_copy(dest),
                     FileExplorerTab::Remote => self.action_remote_copy(dest),
@@ -62,9 +84,33 @@ impl FileTransferActivity {
                 // Reload files
                 self.update_browser_file_list()
             }
+            TransferMsg::DuplicateFiles => {
+                self.mount_panel_wait("Duplicating file(s)…");
+                let file = self.action_duplicate();
+                self.umount_wait();
+                // Reload files
+                self.update_browser_file_list();
+                if let Some(file) = file {
+                    self.highlight_file(&file);
+                }
+            }
+            TransferMsg::CreateArchive(name, format) => {
+                self.umount_archive();
+                let archive = self.action_create_archive(name, format);
+                // Reload files
+                self.update_browser_file_list();
+                if let Some(archive) = archive {
+                    self.highlight_file(&archive);
+                }
+            }
+            TransferMsg::CutFiles => {
+                self.action_cut();
+                self.refresh_local_status_bar();
+                self.refresh_remote_status_bar();
+            }
             TransferMsg::CreateSymlink(name) => {
                 self.umount_symlink();
-                self.mount_blocking_wait("Creating symlink…");
+                self.mount_panel_wait("Creating symlink…");
                 match self.browser.tab() {
                     FileExplorerTab::HostBridge => self.action_local_symlink(name),
                     FileExplorerTab::Remote => self.action_remote_symlink(name),
@@ -76,7 +122,7 @@ impl FileTransferActivity {
             }
             TransferMsg::DeleteFile => {
                 self.umount_radio_delete();
-                self.mount_blocking_wait("Removing file(s)…");
+                self.mount_panel_wait("Removing file(s)…");
                 match self.browser.tab() {
                     FileExplorerTab::HostBridge => self.action_local_delete(),
                     FileExplorerTab::Remote => self.action_remote_delete(),
@@ -112,6 +158,12 @@ impl FileTransferActivity {
                     FileExplorerTab::FindRemote => self.update_remote_filelist(),
                 }
             }
+            TransferMsg::DownloadFavorite(idx) => {
+                self.umount_favorites();
+                self.action_download_favorite(idx);
+                // Reload files
+                self.update_browser_file_list_swapped();
+            }
             TransferMsg::EnterDirectory if self.browser.tab() == FileExplorerTab::HostBridge => {
                 if let SelectedFile::One(entry) = self.get_local_selected_entries() {
                     self.action_submit_local(entry);
@@ -143,19 +195,45 @@ impl FileTransferActivity {
                 // Reload files
                 self.update_browser_file_list()
             }
-            TransferMsg::ExecuteCmd(cmd) => {
+            TransferMsg::ExtractArchive => {
+                self.action_extract_archive();
+                // Reload files
+                self.update_browser_file_list();
+            }
+            TransferMsg::ExecuteCmd(ref cmd) | TransferMsg::ExecuteRawCmd(ref cmd) => {
+                let raw = matches!(msg, TransferMsg::ExecuteRawCmd(_));
+                let cmd = cmd.clone();
                 // Exex command
                 self.umount_exec();
+                self.exec_history.push(cmd.as_str());
+                // the command is run with a single blocking call, which can't be checked against the threshold
                 self.mount_blocking_wait(format!("Executing '{cmd}'…").as_str());
-                match self.browser.tab() {
-                    FileExplorerTab::HostBridge => self.action_local_exec(cmd),
-                    FileExplorerTab::Remote => self.action_remote_exec(cmd),
+                let output = match self.browser.tab() {
+                    FileExplorerTab::HostBridge => self
+                        .action_local_exec(cmd.clone())
+                        .map(|output| (None, output)),
+                    FileExplorerTab::Remote => self
+                        .action_remote_exec(cmd.clone(), raw)
+                        .map(|(rc, output)| (Some(rc), output)),
                     _ => panic!("Found tab doesn't support EXEC"),
-                }
+                };
                 self.umount_wait();
+                if let Some((rc, output)) = output {
+                    self.mount_exec_output(&cmd, rc, &output);
+                }
+                // Reload files
+                self.update_browser_file_list()
+            }
+            TransferMsg::ExecuteQuickCommand(idx) => {
+                self.action_quick_command(idx);
                 // Reload files
                 self.update_browser_file_list()
             }
+            TransferMsg::GoToHistoryEntry(idx) => {
+                self.umount_dir_history();
+                self.action_go_to_history_entry(idx);
+                self.update_browser_file_list()
+            }
             TransferMsg::GoTo(dir) => {
                 match self.browser.tab() {
                     FileExplorerTab::HostBridge => self.action_change_local_dir(dir),
@@ -171,6 +249,41 @@ impl FileTransferActivity {
                 // Reload files
                 self.update_browser_file_list()
             }
+            TransferMsg::GoToClipboardPath => {
+                let file = self.action_go_to_clipboard_path();
+                // Reload files if sync
+                if self.browser.sync_browsing && self.browser.found().is_none() {
+                    self.update_browser_file_list_swapped();
+                }
+                // Reload files
+                self.update_browser_file_list();
+                if let Some(file) = file {
+                    self.highlight_file(&file);
+                }
+            }
+            TransferMsg::GoToDirBookmark(idx) => {
+                self.umount_dir_bookmarks();
+                self.action_go_to_dir_bookmark(idx);
+                // Reload files if sync
+                if self.browser.sync_browsing && self.browser.found().is_none() {
+                    self.update_browser_file_list_swapped();
+                }
+                // Reload files
+                self.update_browser_file_list();
+            }
+            TransferMsg::GoToFavorite(idx) => {
+                self.umount_favorites();
+                let file = self.action_go_to_favorite(idx);
+                // Reload files if sync
+                if self.browser.sync_browsing && self.browser.found().is_none() {
+                    self.update_browser_file_list_swapped();
+                }
+                // Reload files
+                self.update_browser_file_list();
+                if let Some(file) = file {
+                    self.highlight_file(&file);
+                }
+            }
             TransferMsg::GoToParentDirectory => {
                 match self.browser.tab() {
                     FileExplorerTab::HostBridge => {
@@ -217,7 +330,7 @@ impl FileTransferActivity {
                 // Mount wait
                 self.mount_walkdir_wait();
                 // Find
-                let res: Result<Vec<File>, WalkdirError> = match self.browser.tab() {
+                let res: Result<WalkdirResults, WalkdirError> = match self.browser.tab() {
                     FileExplorerTab::HostBridge => self.action_walkdir_local(),
                     FileExplorerTab::Remote => self.action_walkdir_remote(),
                     _ => panic!("Trying to search for files, while already in a find result"),
@@ -237,12 +350,20 @@ impl FileTransferActivity {
                         // If no file has been found notify user
                         self.mount_info("There are no files in the current directory");
                     }
-                    Ok(files) => {
+                    Ok(results) => {
                         // Get wrkdir
                         let wrkdir = match self.browser.tab() {
                             FileExplorerTab::HostBridge => self.host_bridge().wrkdir.clone(),
                             _ => self.remote().wrkdir.clone(),
                         };
+                        let total = results.len();
+                        let (files, spilled) = match results.into_parts() {
+                            Ok(parts) => parts,
+                            Err(err) => {
+                                self.mount_error(format!("Could not read search results: {err}"));
+                                return None;
+                            }
+                        };
                         // Create explorer and load files
                         self.browser.set_found(
                             match self.browser.tab() {
@@ -252,13 +373,23 @@ impl FileTransferActivity {
                             files,
                             wrkdir.as_path(),
                         );
+                        if let Some(spilled) = spilled {
+                            self.log(
+                                LogLevel::Info,
+                                format!(
+                                    "Found {total} entries: {} of them have been written to the cache and will be loaded when scrolling past the end of the results",
+                                    spilled.remaining()
+                                ),
+                            );
+                            self.browser.set_found_spilled(spilled);
+                        }
                         // init fuzzy search to display nothing
                         self.browser.init_fuzzy_search();
                         // Mount result widget
                         self.mount_find(format!(r#"Searching at "{}""#, wrkdir.display()), true);
                         self.update_find_list();
-                        // Initialize tab
-                        self.browser.change_tab(match self.browser.tab() {
+                        // Move focus to the panel the results belong to
+                        self.focus_tab(match self.browser.tab() {
                             FileExplorerTab::HostBridge => FileExplorerTab::FindHostBridge,
                             FileExplorerTab::Remote => FileExplorerTab::FindRemote,
                             _ => FileExplorerTab::FindHostBridge,
@@ -286,6 +417,25 @@ impl FileTransferActivity {
                 // Reload files
                 self.update_browser_file_list()
             }
+            TransferMsg::OpenFavorite(idx) => {
+                self.umount_favorites();
+                self.action_open_favorite(idx);
+            }
+            TransferMsg::OpenEntry => {
+                let selected = match self.browser.tab() {
+                    FileExplorerTab::HostBridge => self.get_local_selected_entries(),
+                    FileExplorerTab::Remote => self.get_remote_selected_entries(),
+                    FileExplorerTab::FindHostBridge | FileExplorerTab::FindRemote => {
+                        self.get_found_selected_entries()
+                    }
+                };
+                return match selected {
+                    SelectedFile::One(entry) if entry.is_file() => {
+                        self.update_transfer(TransferMsg::OpenFile)
+                    }
+                    _ => self.update_transfer(TransferMsg::EnterDirectory),
+                };
+            }
             TransferMsg::OpenFile => match self.browser.tab() {
                 FileExplorerTab::HostBridge => self.action_open_local(),
                 FileExplorerTab::Remote => self.action_open_remote(),
@@ -303,6 +453,16 @@ impl FileTransferActivity {
                 }
                 self.umount_openwith();
             }
+            TransferMsg::LoadMorePreview => self.action_preview_load_more(),
+            TransferMsg::PasteFiles => {
+                self.action_paste();
+                // Reload files of both panels, since entries may have been moved from the other one
+                self.update_browser_file_list();
+                self.update_browser_file_list_swapped();
+                self.refresh_local_status_bar();
+                self.refresh_remote_status_bar();
+            }
+            TransferMsg::PreviewFile => self.action_preview_remote_file(),
             TransferMsg::OpenTextFile => {
                 match self.browser.tab() {
                     FileExplorerTab::HostBridge => self.action_edit_local_file(),
@@ -312,9 +472,24 @@ impl FileTransferActivity {
                 self.update_browser_file_list()
             }
             TransferMsg::ReloadDir => self.update_browser_file_list(),
+            TransferMsg::RemoveDirBookmark(idx) => {
+                self.action_remove_dir_bookmark(idx);
+                match self.has_dir_bookmarks() {
+                    true => self.action_show_dir_bookmarks(),
+                    false => self.umount_dir_bookmarks(),
+                }
+            }
+            TransferMsg::RemoveFavorite(idx) => {
+                self.action_remove_favorite(idx);
+                match self.favorites.is_empty() {
+                    true => self.umount_favorites(),
+                    false => self.action_show_favorites(),
+                }
+            }
+            TransferMsg::RemoveQueueItem(idx) => self.action_remove_queue_item(idx),
             TransferMsg::RenameFile(dest) => {
                 self.umount_rename();
-                self.mount_blocking_wait("Moving file(s)…");
+                self.mount_panel_wait("Moving file(s)…");
                 match self.browser.tab() {
                     FileExplorerTab::HostBridge => self.action_local_rename(dest),
                     FileExplorerTab::Remote => self.action_remote_rename(dest),
@@ -333,6 +508,11 @@ impl FileTransferActivity {
                     .collect();
                 self.update_goto(files);
             }
+            TransferMsg::RetryFailedTransfers => {
+                self.action_retry_failed_transfers();
+                self.update_host_bridge_filelist();
+                self.update_remote_filelist();
+            }
             TransferMsg::SaveFileAs(dest) => {
                 self.umount_saveas();
                 match self.browser.tab() {
@@ -340,22 +520,42 @@ impl FileTransferActivity {
                     FileExplorerTab::Remote => self.action_remote_saveas(dest),
                     FileExplorerTab::FindHostBridge | FileExplorerTab::FindRemote => {
                         // Get entry
-                        self.action_find_transfer(TransferOpts::default().save_as(Some(dest)));
+                        self.action_find_transfer(self.transfer_opts().save_as(Some(dest)));
                     }
                 }
                 self.umount_saveas();
                 // Reload files
                 self.update_browser_file_list_swapped();
             }
+            TransferMsg::SearchContent(needle) => {
+                self.umount_filter();
+                self.action_search_content(needle);
+            }
+            TransferMsg::SyncTransfer(mode) => {
+                self.action_sync_transfer(mode);
+                self.update_browser_file_list_swapped();
+            }
 
+            TransferMsg::ToggleFavorite => self.action_toggle_favorite(),
+            TransferMsg::OpenRemoteUrl => self.action_open_remote_url(),
+            TransferMsg::CopyRemoteUrl => self.action_copy_remote_url(),
             TransferMsg::ToggleWatch => self.action_toggle_watch(),
             TransferMsg::ToggleWatchFor(index) => self.action_toggle_watch_for(index),
+            TransferMsg::WatchPath(patterns) => self.action_watch(patterns),
+            TransferMsg::Undo => {
+                self.action_undo();
+                self.update_browser_file_list()
+            }
+            TransferMsg::EnqueueTransfer => {
+                self.action_enqueue_transfer();
+                self.update_browser_file_list_swapped();
+            }
             TransferMsg::TransferFile => {
                 match self.browser.tab() {
                     FileExplorerTab::HostBridge => self.action_local_send(),
                     FileExplorerTab::Remote => self.action_remote_recv(),
                     FileExplorerTab::FindHostBridge | FileExplorerTab::FindRemote => {
-                        self.action_find_transfer(TransferOpts::default())
+                        self.action_find_transfer(self.transfer_opts())
                     }
                 }
                 self.update_browser_file_list_swapped();
@@ -368,54 +568,39 @@ impl FileTransferActivity {
 
     fn update_ui(&mut self, msg: UiMsg) -> Option<Msg> {
         match msg {
+            UiMsg::CloseChecksumComparePopup => self.umount_checksum_compare(),
+            UiMsg::CloseChecksumPopup => self.umount_checksum(),
+            UiMsg::CloseArchivePopup => self.umount_archive(),
+            UiMsg::CloseBookmarkDirPopup => self.umount_bookmark_dir(),
             UiMsg::CloseChmodPopup => self.umount_chmod(),
-            UiMsg::ChangeFileSorting(sorting) => {
-                match self.browser.tab() {
-                    FileExplorerTab::HostBridge | FileExplorerTab::FindHostBridge => {
-                        self.host_bridge_mut().sort_by(sorting);
-                        self.refresh_local_status_bar();
-                    }
-                    FileExplorerTab::Remote | FileExplorerTab::FindRemote => {
-                        self.remote_mut().sort_by(sorting);
-                        self.refresh_remote_status_bar()
-                    }
-                }
-                self.update_browser_file_list();
+            UiMsg::AcceptHostKey => {
+                self.umount_host_key();
+                self.accept_host_key();
             }
-            UiMsg::ChangeTransferWindow => {
-                let new_tab = match self.browser.tab() {
-                    FileExplorerTab::HostBridge if self.browser.found().is_some() => {
-                        FileExplorerTab::FindRemote
-                    }
-                    FileExplorerTab::FindHostBridge | FileExplorerTab::HostBridge => {
-                        FileExplorerTab::Remote
-                    }
-                    FileExplorerTab::Remote if self.browser.found().is_some() => {
-                        FileExplorerTab::FindHostBridge
-                    }
-                    FileExplorerTab::FindRemote | FileExplorerTab::Remote => {
-                        FileExplorerTab::HostBridge
-                    }
-                };
-                // Set focus
-                match new_tab {
-                    FileExplorerTab::HostBridge => {
-                        assert!(self.app.active(&Id::ExplorerHostBridge).is_ok())
-                    }
-                    FileExplorerTab::Remote => {
-                        assert!(self.app.active(&Id::ExplorerRemote).is_ok())
-                    }
-                    FileExplorerTab::FindHostBridge | FileExplorerTab::FindRemote => {
-                        assert!(self.app.active(&Id::ExplorerFind).is_ok())
-                    }
-                }
-                self.browser.change_tab(new_tab);
+            UiMsg::ChangeFileSorting(sorting) => {
+                let tab = self.browser.tab();
+                self.browser.sort_by(tab, sorting);
+                self.refresh_view_options(tab);
             }
+            UiMsg::ChangeTransferWindow => self.change_transfer_window(),
             UiMsg::CloseCopyPopup => self.umount_copy(),
             UiMsg::CloseDeletePopup => self.umount_radio_delete(),
+            UiMsg::CloseCredentialsPopup => {
+                self.umount_credentials();
+                self.exit_reason = Some(ExitReason::Disconnect);
+            }
+            UiMsg::CloseDirBookmarksPopup => self.umount_dir_bookmarks(),
+            UiMsg::CloseDirHistoryPopup => self.umount_dir_history(),
             UiMsg::CloseDisconnectPopup => self.umount_disconnect(),
             UiMsg::CloseErrorPopup => self.umount_error(),
+            UiMsg::CloseExecOutputPopup => self.umount_exec_output(),
             UiMsg::CloseExecPopup => self.umount_exec(),
+            UiMsg::CloseFailedTransfersPopup => {
+                self.umount_failed_transfers();
+                self.failed_transfers = None;
+                self.show_queue_failures();
+            }
+            UiMsg::CloseFavoritesPopup => self.umount_favorites(),
             UiMsg::CloseFatalPopup => {
                 self.umount_fatal();
                 self.exit_reason = Some(ExitReason::Disconnect);
@@ -432,57 +617,91 @@ impl FileTransferActivity {
             UiMsg::CloseMkdirPopup => self.umount_mkdir(),
             UiMsg::CloseNewFilePopup => self.umount_newfile(),
             UiMsg::CloseOpenWithPopup => self.umount_openwith(),
+            UiMsg::ClosePreviewPopup => self.action_close_preview(),
+            UiMsg::CloseQuickCommandsPopup => self.umount_quick_commands(),
             UiMsg::CloseQuitPopup => self.umount_quit(),
             UiMsg::CloseRenamePopup => self.umount_rename(),
             UiMsg::CloseSaveAsPopup => self.umount_saveas(),
             UiMsg::CloseSymlinkPopup => self.umount_symlink(),
+            UiMsg::CloseTransferQueuePopup => self.umount_transfer_queue(),
+            UiMsg::CloseUpdateBookmarkPopup => self.umount_update_bookmark(),
+            UiMsg::CloseWatchIgnorePopup => self.umount_watch_ignore(),
             UiMsg::CloseWatchedPathsList => self.umount_watched_paths_list(),
             UiMsg::CloseWatcherPopup => self.umount_radio_watcher(),
+            UiMsg::CopyToClipboard(text) => {
+                self.umount_checksum();
+                match clipboard::write_text(&text) {
+                    Ok(()) => self.log(LogLevel::Info, format!("Copied \"{text}\" to clipboard")),
+                    Err(err) => self.log_and_alert(
+                        LogLevel::Error,
+                        format!("Could not write to clipboard: {err}"),
+                    ),
+                }
+            }
+            UiMsg::CredentialsPopupTabbed => {
+                if let Ok(Some(AttrValue::Flag(true))) = self
+                    .app
+                    .query(&Id::CredentialsUsernamePopup, Attribute::Focus)
+                {
+                    assert!(self.app.active(&Id::CredentialsPasswordPopup).is_ok());
+                } else {
+                    assert!(self.app.active(&Id::CredentialsUsernamePopup).is_ok());
+                }
+            }
             UiMsg::Disconnect => {
                 self.disconnect();
                 self.umount_disconnect();
             }
+            UiMsg::ParkSession => {
+                self.park();
+                self.umount_disconnect();
+            }
             UiMsg::FilterFiles(filter) => {
                 self.umount_filter();
-                let files = self.filter(&filter);
-                // Get wrkdir
-                let wrkdir = match self.browser.tab() {
-                    FileExplorerTab::HostBridge => self.host_bridge().wrkdir.clone(),
-                    _ => self.remote().wrkdir.clone(),
-                };
-                // Create explorer and load files
-                self.browser.set_found(
-                    match self.browser.tab() {
-                        FileExplorerTab::HostBridge => FoundExplorerTab::Local,
-                        _ => FoundExplorerTab::Remote,
-                    },
-                    files,
-                    wrkdir.as_path(),
-                );
-                // Mount result widget
-                self.mount_find(&filter, false);
-                self.update_find_list();
-                // Initialize tab
-                self.browser.change_tab(match self.browser.tab() {
-                    FileExplorerTab::HostBridge => FileExplorerTab::FindHostBridge,
-                    FileExplorerTab::Remote => FileExplorerTab::FindRemote,
-                    _ => FileExplorerTab::FindHostBridge,
-                });
+                self.action_filter(filter);
+            }
+            UiMsg::FocusExplorer(tab) => self.focus_clicked_explorer(tab),
+            UiMsg::FocusPanel(side) => {
+                if self.browser.side_of(self.browser.tab()) != side {
+                    self.change_transfer_window();
+                }
             }
             UiMsg::FuzzySearch(needle) => {
                 self.browser.fuzzy_search(&needle);
                 self.update_find_list();
             }
+            UiMsg::LoadMoreFound => {
+                if self.browser.found_spilled() > 0 {
+                    match self.browser.load_more_found(SPILL_PAGE_SIZE) {
+                        Ok(_) => self.update_find_list(),
+                        Err(err) => self.mount_error(format!(
+                            "Could not load search results from cache: {err}"
+                        )),
+                    }
+                }
+            }
             UiMsg::ShowLogPanel => {
                 assert!(self.app.active(&Id::Log).is_ok());
             }
-            UiMsg::LogBackTabbed => {
-                assert!(self.app.active(&Id::ExplorerHostBridge).is_ok());
-            }
+            UiMsg::LogBackTabbed => self.focus_tab(self.browser.tab()),
+            UiMsg::CloseLogExportPopup => self.umount_log_export(),
+            UiMsg::CloseLogSearchPopup => self.umount_log_search(),
+            UiMsg::CycleLogLevelFilter => self.action_cycle_log_level_filter(),
+            UiMsg::MoveLogMatch(older) => self.action_move_log_match(older),
+            UiMsg::SearchLog(text) => {
+                self.umount_log_search();
+                self.action_search_log(text);
+            }
+            UiMsg::ShowLogExportPopup => self.action_show_log_export_popup(),
+            UiMsg::ShowLogSearchPopup => self.action_show_log_search_popup(),
             UiMsg::Quit => {
                 self.disconnect_and_quit();
                 self.umount_quit();
             }
+            UiMsg::RejectHostKey => {
+                self.umount_host_key();
+                self.exit_reason = Some(ExitReason::Disconnect);
+            }
             UiMsg::ReplacePopupTabbed => {
                 if let Ok(Some(AttrValue::Flag(true))) =
                     self.app.query(&Id::ReplacePopup, Attribute::Focus)
@@ -492,6 +711,20 @@ impl FileTransferActivity {
                     assert!(self.app.active(&Id::ReplacePopup).is_ok());
                 }
             }
+            UiMsg::RetryWithCredentials => {
+                let username = match self.app.state(&Id::CredentialsUsernamePopup) {
+                    Ok(State::One(StateValue::String(username))) => username,
+                    _ => String::new(),
+                };
+                let password = match self.app.state(&Id::CredentialsPasswordPopup) {
+                    Ok(State::One(StateValue::String(password))) => password,
+                    _ => String::new(),
+                };
+                self.umount_credentials();
+                self.retry_with_credentials(username, password);
+            }
+            UiMsg::ShowArchivePopup => self.action_show_archive_popup(),
+            UiMsg::ShowBookmarkDirPopup => self.action_show_bookmark_dir_popup(),
             UiMsg::ShowChmodPopup => {
                 let selected_file = match self.browser.tab() {
                     #[cfg(posix)]
@@ -505,6 +738,11 @@ impl FileTransferActivity {
                         SelectedFile::None
                     }
                 };
+                let directories = match &selected_file {
+                    SelectedFile::One(file) => file.is_dir(),
+                    SelectedFile::Many(files) => files.iter().any(|x| x.is_dir()),
+                    SelectedFile::None => false,
+                };
                 if let Some(mode) = selected_file.unix_pex() {
                     self.mount_chmod(
                         mode,
@@ -517,26 +755,40 @@ impl FileTransferActivity {
                             }
                             SelectedFile::None => "".to_string(),
                         },
+                        directories,
                     );
                 }
             }
             UiMsg::ShowCopyPopup => self.mount_copy(),
-            UiMsg::ShowDeletePopup => self.mount_radio_delete(),
+            UiMsg::ShowDeletePopup => self.action_show_delete_popup(),
+            UiMsg::ShowDirBookmarksPopup => self.action_show_dir_bookmarks(),
+            UiMsg::ShowDirHistoryPopup => self.action_show_dir_history(),
             UiMsg::ShowDisconnectPopup => self.mount_disconnect(),
-            UiMsg::ShowExecPopup => self.mount_exec(),
+            UiMsg::ShowExecPopup if self.is_exec_supported(self.browser.tab()) => self.mount_exec(),
+            UiMsg::ShowExecPopup => {}
+            UiMsg::ShowFavoritesPopup => self.action_show_favorites(),
             UiMsg::ShowFileInfoPopup if self.browser.tab() == FileExplorerTab::HostBridge => {
                 if let SelectedFile::One(file) = self.get_local_selected_entries() {
-                    self.mount_file_info(&file);
+                    let xattrs = self.host_bridge.xattrs(file.path());
+                    let special = self.special_file_type(&file, false);
+                    self.mount_file_info(&file, special, &xattrs);
                 }
             }
             UiMsg::ShowFileInfoPopup if self.browser.tab() == FileExplorerTab::Remote => {
                 if let SelectedFile::One(file) = self.get_remote_selected_entries() {
-                    self.mount_file_info(&file);
+                    let special = self.special_file_type(&file, true);
+                    self.mount_file_info(&file, special, &[]);
                 }
             }
             UiMsg::ShowFileInfoPopup => {
                 if let SelectedFile::One(file) = self.get_found_selected_entries() {
-                    self.mount_file_info(&file);
+                    let local = matches!(self.browser.found_tab(), Some(FoundExplorerTab::Local));
+                    let xattrs = match local {
+                        true => self.host_bridge.xattrs(file.path()),
+                        false => Vec::new(),
+                    };
+                    let special = self.special_file_type(&file, !local);
+                    self.mount_file_info(&file, special, &xattrs);
                 }
             }
             UiMsg::ShowFileSortingPopup => self.mount_file_sorting(),
@@ -546,6 +798,7 @@ impl FileTransferActivity {
             UiMsg::ShowMkdirPopup => self.mount_mkdir(),
             UiMsg::ShowNewFilePopup => self.mount_newfile(),
             UiMsg::ShowOpenWithPopup => self.mount_openwith(),
+            UiMsg::ShowQuickCommandsPopup => self.action_show_quick_commands(),
             UiMsg::ShowQuitPopup => self.mount_quit(),
             UiMsg::ShowRenamePopup => self.mount_rename(),
             UiMsg::ShowSaveAsPopup => self.mount_saveas(),
@@ -563,25 +816,30 @@ impl FileTransferActivity {
                     );
                 }
             }
+            UiMsg::ShowTransferQueuePopup => self.action_show_transfer_queue(),
             UiMsg::ShowWatchedPathsList => self.action_show_watched_paths_list(),
             UiMsg::ShowWatcherPopup => self.action_show_radio_watch(),
-            UiMsg::ToggleHiddenFiles => match self.browser.tab() {
-                FileExplorerTab::FindHostBridge | FileExplorerTab::HostBridge => {
-                    self.browser.host_bridge_mut().toggle_hidden_files();
-                    self.refresh_local_status_bar();
-                    self.update_browser_file_list();
-                }
-                FileExplorerTab::FindRemote | FileExplorerTab::Remote => {
-                    self.browser.remote_mut().toggle_hidden_files();
-                    self.refresh_remote_status_bar();
-                    self.update_browser_file_list();
-                }
-            },
+            UiMsg::Suspend => {
+                #[cfg(posix)]
+                crate::system::signals::request_suspend();
+            }
+            UiMsg::SwapPanels => self.browser.swap_panels(),
+            UiMsg::TerminalFocusChanged(focused) => self.terminal_focused = Some(focused),
+            UiMsg::ToggleHiddenFiles => {
+                let tab = self.browser.tab();
+                self.browser.toggle_hidden_files(tab);
+                self.refresh_view_options(tab);
+            }
             UiMsg::ToggleSyncBrowsing => {
                 self.browser.toggle_sync_browsing();
                 self.refresh_remote_status_bar();
             }
+            UiMsg::UpdateBookmarkCredentials => {
+                self.umount_update_bookmark();
+                self.update_bookmark_credentials();
+            }
             UiMsg::WindowResized => {
+                self.refit_file_lists();
                 self.redraw = true;
             }
         }
@@ -4,18 +4,35 @@
 
 // locals
 // Ext
+use std::path::Path;
+
+use chrono::Local;
 use remotefs::fs::{File, UnixPex};
 use tuirealm::event::{Key, KeyEvent, KeyModifiers};
-use tuirealm::props::{PropPayload, PropValue, TextSpan};
+use tuirealm::props::{Alignment, Color, PropPayload, PropValue, Style, TextSpan};
 use tuirealm::ratatui::layout::{Constraint, Direction, Layout};
 use tuirealm::ratatui::widgets::Clear;
-use tuirealm::{AttrValue, Attribute, Sub, SubClause, SubEventClause};
+use tuirealm::{AttrValue, Attribute, NoUserEvent, Sub, SubClause, SubEventClause};
 use unicode_width::UnicodeWidthStr;
 
-use super::browser::{FileExplorerTab, FoundExplorerTab};
+use super::browser::{FileExplorerTab, FoundExplorerTab, PanelSide};
 use super::components::ATTR_FILES;
+use super::lib::clock_skew::ClockSkew;
+use super::lib::parallel::TransferDirection;
+use super::lib::preview::Preview;
+use super::lib::queue::QueueItemState;
+use super::lib::schedule;
+use super::lib::transfer::TransferFailure;
+use super::lib::wait::PendingOperation;
 use super::{components, Context, FileTransferActivity, Id};
+use crate::config::bookmarks::DirectoryBookmark;
+use crate::explorer::special::SpecialFileType;
 use crate::explorer::FileSorting;
+use crate::filetransfer::host_key::HostKey;
+use crate::filetransfer::params::QuickCommand;
+use crate::host::Xattr;
+use crate::system::watcher::WatchDirection;
+use crate::utils::checksum::ChecksumAlgorithm;
 use crate::utils::ui::{Popup, Size};
 
 impl FileTransferActivity {
@@ -30,17 +47,10 @@ impl FileTransferActivity {
         let remote_explorer_background = self.theme().transfer_remote_explorer_background;
         let remote_explorer_foreground = self.theme().transfer_remote_explorer_foreground;
         let remote_explorer_highlighted = self.theme().transfer_remote_explorer_highlighted;
-        let key_color = self.theme().misc_keys;
         let log_panel = self.theme().transfer_log_window;
         let log_background = self.theme().transfer_log_background;
-        assert!(self
-            .app
-            .mount(
-                Id::FooterBar,
-                Box::new(components::FooterBar::new(key_color)),
-                vec![]
-            )
-            .is_ok());
+        let keymap = self.config().get_keymap().clone();
+        self.mount_footer_bar();
         assert!(self
             .app
             .mount(
@@ -50,9 +60,10 @@ impl FileTransferActivity {
                     &[],
                     local_explorer_background,
                     local_explorer_foreground,
-                    local_explorer_highlighted
+                    local_explorer_highlighted,
+                    &keymap
                 )),
-                vec![]
+                Self::explorer_mouse_subs()
             )
             .is_ok());
         assert!(self
@@ -64,9 +75,10 @@ impl FileTransferActivity {
                     &[],
                     remote_explorer_background,
                     remote_explorer_foreground,
-                    remote_explorer_highlighted
+                    remote_explorer_highlighted,
+                    &keymap
                 )),
-                vec![]
+                Self::explorer_mouse_subs()
             )
             .is_ok());
         assert!(self
@@ -77,6 +89,8 @@ impl FileTransferActivity {
                 vec![]
             )
             .is_ok());
+        self.set_explorer_inactive_style(Id::ExplorerHostBridge, local_explorer_foreground);
+        self.set_explorer_inactive_style(Id::ExplorerRemote, remote_explorer_foreground);
         // Load status bar
         self.refresh_local_status_bar();
         self.refresh_remote_status_bar();
@@ -85,6 +99,7 @@ impl FileTransferActivity {
         // self.update_remote_filelist();
         // Global listener
         self.mount_global_listener();
+        self.mount_focus_listener();
         // Give focus to local explorer
         assert!(self.app.active(&Id::ExplorerHostBridge).is_ok());
     }
@@ -94,7 +109,13 @@ impl FileTransferActivity {
     /// View gui
     pub(super) fn view(&mut self) {
         self.redraw = false;
+        // Show the full name of the highlighted entry in the footer, if elided
+        let elided_name = self.highlighted_elided_name();
+        if let Some(name) = elided_name.as_deref() {
+            self.mount_file_name_bar(name);
+        }
         let mut context: Context = self.context.take().unwrap();
+        let palette = context.theme_provider().palette();
         let _ = context.terminal.raw_mut().draw(|f| {
             // Prepare chunks
             let body = Layout::default()
@@ -135,26 +156,42 @@ impl FileTransferActivity {
                 .horizontal_margin(1)
                 .split(bottom_chunks[0]);
             // Draw footer
-            self.app.view(&Id::FooterBar, f, body[1]);
-            // Draw explorers
+            match elided_name {
+                Some(_) => self.app.view(&Id::FileNameBar, f, body[1]),
+                None => self.app.view(&Id::FooterBar, f, body[1]),
+            }
+            // Draw explorers; panels may be swapped
+            let (host_bridge_chunk, remote_chunk) =
+                match self.browser.side_of(FileExplorerTab::HostBridge) {
+                    PanelSide::Left => (0, 1),
+                    PanelSide::Right => (1, 0),
+                };
             // @! Local explorer (Find or default)
             if matches!(self.browser.found_tab(), Some(FoundExplorerTab::Local)) {
-                self.app.view(&Id::ExplorerFind, f, tabs_chunks[0]);
+                self.app
+                    .view(&Id::ExplorerFind, f, tabs_chunks[host_bridge_chunk]);
             } else {
-                self.app.view(&Id::ExplorerHostBridge, f, tabs_chunks[0]);
+                self.app
+                    .view(&Id::ExplorerHostBridge, f, tabs_chunks[host_bridge_chunk]);
             }
             // @! Remote explorer (Find or default)
             if matches!(self.browser.found_tab(), Some(FoundExplorerTab::Remote)) {
-                self.app.view(&Id::ExplorerFind, f, tabs_chunks[1]);
+                self.app
+                    .view(&Id::ExplorerFind, f, tabs_chunks[remote_chunk]);
             } else {
-                self.app.view(&Id::ExplorerRemote, f, tabs_chunks[1]);
+                self.app
+                    .view(&Id::ExplorerRemote, f, tabs_chunks[remote_chunk]);
             }
             // Draw log box
             self.app.view(&Id::Log, f, bottom_chunks[1]);
             // Draw status bar
+            self.app.view(
+                &Id::StatusBarHostBridge,
+                f,
+                status_bar_chunks[host_bridge_chunk],
+            );
             self.app
-                .view(&Id::StatusBarHostBridge, f, status_bar_chunks[0]);
-            self.app.view(&Id::StatusBarRemote, f, status_bar_chunks[1]);
+                .view(&Id::StatusBarRemote, f, status_bar_chunks[remote_chunk]);
             // @! Draw popups
             if self.app.mounted(&Id::FatalPopup) {
                 let popup = Popup(
@@ -165,13 +202,51 @@ impl FileTransferActivity {
                 f.render_widget(Clear, popup);
                 // make popup
                 self.app.view(&Id::FatalPopup, f, popup);
+            } else if self.app.mounted(&Id::CredentialsUsernamePopup) {
+                let popup = Popup(Size::Percentage(50), Size::Unit(6)).draw_in(f.area());
+                f.render_widget(Clear, popup);
+                let popup_chunks = Layout::default()
+                    .direction(Direction::Vertical)
+                    .constraints(
+                        [
+                            Constraint::Length(3), // Username
+                            Constraint::Length(3), // Password
+                        ]
+                        .as_ref(),
+                    )
+                    .split(popup);
+                self.app
+                    .view(&Id::CredentialsUsernamePopup, f, popup_chunks[0]);
+                self.app
+                    .view(&Id::CredentialsPasswordPopup, f, popup_chunks[1]);
+            } else if self.app.mounted(&Id::HostKeyPopup) {
+                let popup = Popup(Size::Percentage(60), Size::Unit(9)).draw_in(f.area());
+                f.render_widget(Clear, popup);
+                let popup_chunks = Layout::default()
+                    .direction(Direction::Vertical)
+                    .constraints(
+                        [
+                            Constraint::Length(6), // Fingerprint
+                            Constraint::Length(3), // Accept or reject
+                        ]
+                        .as_ref(),
+                    )
+                    .split(popup);
+                self.app
+                    .view(&Id::HostKeyFingerprintPopup, f, popup_chunks[0]);
+                self.app.view(&Id::HostKeyPopup, f, popup_chunks[1]);
+            } else if self.app.mounted(&Id::ArchivePopup) {
+                let popup = Popup(Size::Percentage(50), Size::Unit(3)).draw_in(f.area());
+                f.render_widget(Clear, popup);
+                // make popup
+                self.app.view(&Id::ArchivePopup, f, popup);
             } else if self.app.mounted(&Id::CopyPopup) {
                 let popup = Popup(Size::Percentage(40), Size::Unit(3)).draw_in(f.area());
                 f.render_widget(Clear, popup);
                 // make popup
                 self.app.view(&Id::CopyPopup, f, popup);
             } else if self.app.mounted(&Id::ChmodPopup) {
-                let popup = Popup(Size::Percentage(50), Size::Unit(12)).draw_in(f.area());
+                let popup = Popup(Size::Percentage(50), Size::Unit(18)).draw_in(f.area());
                 f.render_widget(Clear, popup);
                 // make popup
                 self.app.view(&Id::ChmodPopup, f, popup);
@@ -185,6 +260,21 @@ impl FileTransferActivity {
                 f.render_widget(Clear, popup);
                 // make popup
                 self.app.view(&Id::GotoPopup, f, popup);
+            } else if self.app.mounted(&Id::BookmarkDirPopup) {
+                let popup = Popup(Size::Percentage(40), Size::Unit(3)).draw_in(f.area());
+                f.render_widget(Clear, popup);
+                // make popup
+                self.app.view(&Id::BookmarkDirPopup, f, popup);
+            } else if self.app.mounted(&Id::LogSearchPopup) {
+                let popup = Popup(Size::Percentage(40), Size::Unit(3)).draw_in(f.area());
+                f.render_widget(Clear, popup);
+                // make popup
+                self.app.view(&Id::LogSearchPopup, f, popup);
+            } else if self.app.mounted(&Id::LogExportPopup) {
+                let popup = Popup(Size::Percentage(60), Size::Unit(3)).draw_in(f.area());
+                f.render_widget(Clear, popup);
+                // make popup
+                self.app.view(&Id::LogExportPopup, f, popup);
             } else if self.app.mounted(&Id::MkdirPopup) {
                 let popup = Popup(Size::Percentage(40), Size::Unit(3)).draw_in(f.area());
                 f.render_widget(Clear, popup);
@@ -220,11 +310,26 @@ impl FileTransferActivity {
                 f.render_widget(Clear, popup);
                 // make popup
                 self.app.view(&Id::ExecPopup, f, popup);
+            } else if self.app.mounted(&Id::ExecOutputPopup) {
+                let popup = Popup(Size::Percentage(80), Size::Percentage(70)).draw_in(f.area());
+                f.render_widget(Clear, popup);
+                // make popup
+                self.app.view(&Id::ExecOutputPopup, f, popup);
             } else if self.app.mounted(&Id::FileInfoPopup) {
                 let popup = Popup(Size::Percentage(50), Size::Percentage(50)).draw_in(f.area());
                 f.render_widget(Clear, popup);
                 // make popup
                 self.app.view(&Id::FileInfoPopup, f, popup);
+            } else if self.app.mounted(&Id::ChecksumComparePopup) {
+                let popup = Popup(Size::Percentage(60), Size::Unit(5)).draw_in(f.area());
+                f.render_widget(Clear, popup);
+                // make popup
+                self.app.view(&Id::ChecksumComparePopup, f, popup);
+            } else if self.app.mounted(&Id::ChecksumPopup) {
+                let popup = Popup(Size::Percentage(60), Size::Unit(4)).draw_in(f.area());
+                f.render_widget(Clear, popup);
+                // make popup
+                self.app.view(&Id::ChecksumPopup, f, popup);
             } else if self.app.mounted(&Id::ProgressBarPartial) {
                 let popup = Popup(Size::Percentage(50), Size::Percentage(20)).draw_in(f.area());
                 f.render_widget(Clear, popup);
@@ -242,10 +347,14 @@ impl FileTransferActivity {
                 self.app.view(&Id::ProgressBarFull, f, popup_chunks[0]);
                 self.app.view(&Id::ProgressBarPartial, f, popup_chunks[1]);
             } else if self.app.mounted(&Id::DeletePopup) {
-                let popup = Popup(Size::Percentage(30), Size::Unit(3)).draw_in(f.area());
+                let popup = Popup(Size::Percentage(50), Size::Unit(3)).draw_in(f.area());
                 f.render_widget(Clear, popup);
                 // make popup
                 self.app.view(&Id::DeletePopup, f, popup);
+            } else if self.app.mounted(&Id::DeleteConfirmNamePopup) {
+                let popup = Popup(Size::Percentage(50), Size::Unit(3)).draw_in(f.area());
+                f.render_widget(Clear, popup);
+                self.app.view(&Id::DeleteConfirmNamePopup, f, popup);
             } else if self.app.mounted(&Id::ReplacePopup) {
                 // NOTE: handle extended / normal modes
                 if self.is_radio_replace_extended() {
@@ -270,21 +379,95 @@ impl FileTransferActivity {
                     // make popup
                     self.app.view(&Id::ReplacePopup, f, popup);
                 }
+            } else if self.app.mounted(&Id::ReplaceRenamePopup) {
+                let popup = Popup(Size::Percentage(50), Size::Unit(3)).draw_in(f.area());
+                f.render_widget(Clear, popup);
+                // make popup
+                self.app.view(&Id::ReplaceRenamePopup, f, popup);
             } else if self.app.mounted(&Id::DisconnectPopup) {
-                let popup = Popup(Size::Percentage(30), Size::Unit(3)).draw_in(f.area());
+                // the choice to keep the session connected needs more room
+                let width = match self.config().get_parked_session_timeout() {
+                    Some(_) => Size::Percentage(60),
+                    None => self.disconnect_popup_width(),
+                };
+                let popup = Popup(width, Size::Unit(3)).draw_in(f.area());
                 f.render_widget(Clear, popup);
                 // make popup
                 self.app.view(&Id::DisconnectPopup, f, popup);
+            } else if self.app.mounted(&Id::UpdateBookmarkPopup) {
+                let popup = Popup(Size::Percentage(50), Size::Unit(3)).draw_in(f.area());
+                f.render_widget(Clear, popup);
+                // make popup
+                self.app.view(&Id::UpdateBookmarkPopup, f, popup);
             } else if self.app.mounted(&Id::QuitPopup) {
-                let popup = Popup(Size::Percentage(30), Size::Unit(3)).draw_in(f.area());
+                let popup = Popup(self.disconnect_popup_width(), Size::Unit(3)).draw_in(f.area());
                 f.render_widget(Clear, popup);
                 // make popup
                 self.app.view(&Id::QuitPopup, f, popup);
+            } else if self.app.mounted(&Id::DirHistoryPopup) {
+                let popup = Popup(Size::Percentage(60), Size::Percentage(50)).draw_in(f.area());
+                f.render_widget(Clear, popup);
+                // make popup
+                self.app.view(&Id::DirHistoryPopup, f, popup);
+            } else if self.app.mounted(&Id::PreviewPopup) {
+                let popup = Popup(Size::Percentage(80), Size::Percentage(80)).draw_in(f.area());
+                f.render_widget(Clear, popup);
+                // make popup
+                self.app.view(&Id::PreviewPopup, f, popup);
+            } else if self.app.mounted(&Id::EditDiffPopup) {
+                let popup = Popup(Size::Percentage(80), Size::Percentage(80)).draw_in(f.area());
+                f.render_widget(Clear, popup);
+                // make popup
+                self.app.view(&Id::EditDiffPopup, f, popup);
+            } else if self.app.mounted(&Id::EditConflictPopup) {
+                let popup = Popup(Size::Percentage(60), Size::Unit(3)).draw_in(f.area());
+                f.render_widget(Clear, popup);
+                // make popup
+                self.app.view(&Id::EditConflictPopup, f, popup);
+            } else if self.app.mounted(&Id::DirBookmarksPopup) {
+                let popup = Popup(Size::Percentage(60), Size::Percentage(50)).draw_in(f.area());
+                f.render_widget(Clear, popup);
+                // make popup
+                self.app.view(&Id::DirBookmarksPopup, f, popup);
+            } else if self.app.mounted(&Id::FavoritesPopup) {
+                let popup = Popup(Size::Percentage(60), Size::Percentage(50)).draw_in(f.area());
+                f.render_widget(Clear, popup);
+                // make popup
+                self.app.view(&Id::FavoritesPopup, f, popup);
+            } else if self.app.mounted(&Id::QuickCommandsPopup) {
+                let popup = Popup(Size::Percentage(60), Size::Percentage(50)).draw_in(f.area());
+                f.render_widget(Clear, popup);
+                // make popup
+                self.app.view(&Id::QuickCommandsPopup, f, popup);
+            } else if self.app.mounted(&Id::TransferQueuePopup) {
+                let popup = Popup(Size::Percentage(70), Size::Percentage(60)).draw_in(f.area());
+                f.render_widget(Clear, popup);
+                let popup_chunks = Layout::default()
+                    .direction(Direction::Vertical)
+                    .constraints(
+                        [
+                            Constraint::Min(3),    // List
+                            Constraint::Length(2), // Full
+                            Constraint::Length(2), // Partial
+                        ]
+                        .as_ref(),
+                    )
+                    .split(popup);
+                self.app.view(&Id::TransferQueuePopup, f, popup_chunks[0]);
+                self.app
+                    .view(&Id::TransferQueueProgressBarFull, f, popup_chunks[1]);
+                self.app
+                    .view(&Id::TransferQueueProgressBarPartial, f, popup_chunks[2]);
             } else if self.app.mounted(&Id::WatchedPathsList) {
                 let popup = Popup(Size::Percentage(60), Size::Percentage(50)).draw_in(f.area());
                 f.render_widget(Clear, popup);
                 // make popup
                 self.app.view(&Id::WatchedPathsList, f, popup);
+            } else if self.app.mounted(&Id::WatchIgnorePopup) {
+                let popup = Popup(Size::Percentage(60), Size::Unit(3)).draw_in(f.area());
+                f.render_widget(Clear, popup);
+                // make popup
+                self.app.view(&Id::WatchIgnorePopup, f, popup);
             } else if self.app.mounted(&Id::WatcherPopup) {
                 let popup = Popup(Size::Percentage(60), Size::Unit(3)).draw_in(f.area());
                 f.render_widget(Clear, popup);
@@ -304,6 +487,11 @@ impl FileTransferActivity {
                 f.render_widget(Clear, popup);
                 // make popup
                 self.app.view(&Id::ErrorPopup, f, popup);
+            } else if self.app.mounted(&Id::FailedTransfersPopup) {
+                let popup = Popup(Size::Percentage(80), Size::Percentage(50)).draw_in(f.area());
+                f.render_widget(Clear, popup);
+                // make popup
+                self.app.view(&Id::FailedTransfersPopup, f, popup);
             } else if self.app.mounted(&Id::WaitPopup) {
                 let wait_popup_lines = self
                     .app
@@ -322,12 +510,23 @@ impl FileTransferActivity {
                 f.render_widget(Clear, popup);
                 // make popup
                 self.app.view(&Id::SyncBrowsingMkdirPopup, f, popup);
+            } else if self.app.mounted(&Id::TransferConfirmPopup) {
+                let popup = Popup(Size::Percentage(60), Size::Unit(3)).draw_in(f.area());
+                f.render_widget(Clear, popup);
+                // make popup
+                self.app.view(&Id::TransferConfirmPopup, f, popup);
+            } else if self.app.mounted(&Id::ScheduleTransferPopup) {
+                let popup = Popup(Size::Percentage(60), Size::Unit(3)).draw_in(f.area());
+                f.render_widget(Clear, popup);
+                // make popup
+                self.app.view(&Id::ScheduleTransferPopup, f, popup);
             } else if self.app.mounted(&Id::KeybindingsPopup) {
                 let popup = Popup(Size::Percentage(50), Size::Percentage(80)).draw_in(f.area());
                 f.render_widget(Clear, popup);
                 // make popup
                 self.app.view(&Id::KeybindingsPopup, f, popup);
             }
+            palette.apply(f.buffer_mut());
         });
         // Re-give context
         self.context = Some(context);
@@ -335,6 +534,19 @@ impl FileTransferActivity {
 
     // -- partials
 
+    /// Mount the footer line with the full name of the highlighted entry
+    fn mount_file_name_bar(&mut self, name: &str) {
+        let key_color = self.theme().misc_keys;
+        assert!(self
+            .app
+            .remount(
+                Id::FileNameBar,
+                Box::new(components::FileNameBar::new(name, key_color)),
+                vec![],
+            )
+            .is_ok());
+    }
+
     /// Mount info box
     pub(super) fn mount_info<S: AsRef<str>>(&mut self, text: S) {
         // Mount
@@ -435,12 +647,148 @@ impl FileTransferActivity {
         self.view();
     }
 
+    pub(super) fn update_content_search_progress(&mut self, searched: usize, found: usize) {
+        let text = format!("Searching file contents… ({searched} files searched, {found} found)");
+        let _ = self.app.attr(
+            &Id::WaitPopup,
+            Attribute::Text,
+            AttrValue::Payload(PropPayload::Vec(vec![
+                PropValue::TextSpan(TextSpan::from(text)),
+                PropValue::TextSpan(TextSpan::from("Press 'CTRL+C' to abort")),
+            ])),
+        );
+
+        self.view();
+    }
+
+    pub(super) fn update_chmod_progress(&mut self, entries: usize) {
+        let text = format!("Applying new file mode recursively… ({entries} entries changed)");
+        let _ = self.app.attr(
+            &Id::WaitPopup,
+            Attribute::Text,
+            AttrValue::Payload(PropPayload::Vec(vec![PropValue::TextSpan(TextSpan::from(
+                text,
+            ))])),
+        );
+
+        self.view();
+    }
+
+    pub(super) fn mount_transfer_scan_wait(&mut self) {
+        self.mount_scan_wait("Estimating transfer size…");
+    }
+
+    pub(super) fn update_transfer_scan_entries(&mut self, entries: usize) {
+        self.update_scan_wait(format!("Estimating transfer size… ({entries} items found)"));
+    }
+
+    /// Mount the wait popup for an abortable directory scan
+    pub(super) fn mount_scan_wait<S: AsRef<str>>(&mut self, text: S) {
+        let color = self.theme().misc_info_dialog;
+        assert!(self
+            .app
+            .remount(
+                Id::WaitPopup,
+                Box::new(components::TransferScanWaitPopup::new(text, color)),
+                vec![],
+            )
+            .is_ok());
+        assert!(self.app.active(&Id::WaitPopup).is_ok());
+
+        self.view();
+    }
+
+    pub(super) fn update_scan_wait<S: AsRef<str>>(&mut self, text: S) {
+        let _ = self.app.attr(
+            &Id::WaitPopup,
+            Attribute::Text,
+            AttrValue::Payload(PropPayload::Vec(vec![
+                PropValue::TextSpan(TextSpan::from(text.as_ref())),
+                PropValue::TextSpan(TextSpan::from("Press 'CTRL+C' to abort")),
+            ])),
+        );
+
+        self.view();
+    }
+
     pub(super) fn mount_blocking_wait<S: AsRef<str>>(&mut self, text: S) {
         self.mount_wait(text);
         self.view();
     }
 
+    /// Mount a wait for a quick operation pending on the current explorer panel.
+    /// A spinner is rendered in the panel title, while the blocking wait popup is displayed only once the operation
+    /// exceeds the threshold; operations on many entries must call [`Self::poll_panel_wait`] after each of them
+    pub(super) fn mount_panel_wait<S: AsRef<str>>(&mut self, text: S) {
+        let panel = match self.browser.tab() {
+            FileExplorerTab::HostBridge => Id::ExplorerHostBridge,
+            FileExplorerTab::Remote => Id::ExplorerRemote,
+            FileExplorerTab::FindHostBridge | FileExplorerTab::FindRemote => Id::ExplorerFind,
+        };
+        self.mount_panel_wait_on(panel, text);
+    }
+
+    /// Mount a wait for a quick operation pending on `panel`
+    pub(super) fn mount_panel_wait_on<S: AsRef<str>>(&mut self, panel: Id, text: S) {
+        self.umount_panel_wait();
+        let title = match self.app.query(&panel, Attribute::Title) {
+            Ok(Some(AttrValue::Title(title))) => Some(title),
+            _ => None,
+        };
+        self.pending_operation = Some(PendingOperation::start(panel, text.as_ref(), title));
+        self.refresh_panel_wait();
+        self.view();
+    }
+
+    /// Update the spinner of the pending operation.
+    /// If the operation has exceeded the threshold, the blocking wait popup is mounted instead
+    pub(super) fn refresh_panel_wait(&mut self) {
+        let Some(op) = self.pending_operation.as_ref() else {
+            return;
+        };
+        if op.should_block() {
+            if !self.app.mounted(&Id::WaitPopup) {
+                let text = op.text().to_string();
+                self.mount_wait(text);
+            }
+        } else {
+            let panel = op.panel().clone();
+            let alignment = op.title().map(|(_, a)| *a).unwrap_or(Alignment::Left);
+            let title = op.fmt_title();
+            let _ = self.app.attr(
+                &panel,
+                Attribute::Title,
+                AttrValue::Title((title, alignment)),
+            );
+        }
+    }
+
+    /// Check the pending operation from within the operation, between its blocking calls, since the activity doesn't
+    /// tick meanwhile: once the operation exceeds the threshold, the blocking wait popup is displayed
+    pub(super) fn poll_panel_wait(&mut self) {
+        if self
+            .pending_operation
+            .as_mut()
+            .is_some_and(PendingOperation::escalate)
+        {
+            self.refresh_panel_wait();
+            self.view();
+        }
+    }
+
+    /// Terminate the pending operation, restoring the panel title
+    fn umount_panel_wait(&mut self) {
+        if let Some(op) = self.pending_operation.take() {
+            if let Some(title) = op.title().cloned() {
+                let _ = self
+                    .app
+                    .attr(op.panel(), Attribute::Title, AttrValue::Title(title));
+            }
+        }
+    }
+
     pub(super) fn umount_wait(&mut self) {
+        self.umount_panel_wait();
         let _ = self.app.umount(&Id::WaitPopup);
     }
 
@@ -448,11 +796,20 @@ impl FileTransferActivity {
     pub(super) fn mount_quit(&mut self) {
         // Protocol
         let quit_color = self.theme().misc_quit_dialog;
+        let work = self.unfinished_work();
+        let title = match work.summary() {
+            Some(summary) => format!("{summary}: quit anyway?"),
+            None => String::from("Are you sure you want to quit termscp?"),
+        };
         assert!(self
             .app
             .remount(
                 Id::QuitPopup,
-                Box::new(components::QuitPopup::new(quit_color)),
+                Box::new(components::QuitPopup::new(
+                    title,
+                    quit_color,
+                    work.requires_confirmation()
+                )),
                 vec![],
             )
             .is_ok());
@@ -468,11 +825,21 @@ impl FileTransferActivity {
     pub(super) fn mount_disconnect(&mut self) {
         // Protocol
         let quit_color = self.theme().misc_quit_dialog;
+        let work = self.unfinished_work();
+        let title = match work.summary() {
+            Some(summary) => format!("{summary}: disconnect anyway?"),
+            None => String::from("Are you sure you want to disconnect?"),
+        };
         assert!(self
             .app
             .remount(
                 Id::DisconnectPopup,
-                Box::new(components::DisconnectPopup::new(quit_color)),
+                Box::new(components::DisconnectPopup::new(
+                    title,
+                    quit_color,
+                    work.requires_confirmation(),
+                    self.config().get_parked_session_timeout().is_some()
+                )),
                 vec![],
             )
             .is_ok());
@@ -484,14 +851,106 @@ impl FileTransferActivity {
         let _ = self.app.umount(&Id::DisconnectPopup);
     }
 
-    pub(super) fn mount_chmod(&mut self, mode: UnixPex, title: String) {
+    /// Width of the disconnect and quit popups, which is larger when they report the unfinished work
+    fn disconnect_popup_width(&self) -> Size {
+        match self.unfinished_work().requires_confirmation() {
+            true => Size::Percentage(60),
+            false => Size::Percentage(30),
+        }
+    }
+
+    /// Mount the credentials popup, shown when the authentication to the remote host fails
+    /// Mount the popup asking the credentials to authenticate on `host` with
+    pub(super) fn mount_credentials(&mut self, username: Option<&str>, host: &str) {
+        let color = self.theme().misc_input_dialog;
+        assert!(self
+            .app
+            .remount(
+                Id::CredentialsUsernamePopup,
+                Box::new(components::CredentialsUsernamePopup::new(
+                    username, host, color
+                )),
+                vec![],
+            )
+            .is_ok());
+        assert!(self
+            .app
+            .remount(
+                Id::CredentialsPasswordPopup,
+                Box::new(components::CredentialsPasswordPopup::new(color)),
+                vec![],
+            )
+            .is_ok());
+        // Focus the password if the username is known
+        match username {
+            Some(_) => assert!(self.app.active(&Id::CredentialsPasswordPopup).is_ok()),
+            None => assert!(self.app.active(&Id::CredentialsUsernamePopup).is_ok()),
+        }
+    }
+
+    pub(super) fn umount_credentials(&mut self) {
+        let _ = self.app.umount(&Id::CredentialsUsernamePopup);
+        let _ = self.app.umount(&Id::CredentialsPasswordPopup);
+    }
+
+    /// Mount the popup asking whether to accept the unknown host key `key`, saving it to `known_hosts`
+    pub(super) fn mount_host_key(&mut self, key: &HostKey, known_hosts: &Path) {
+        let color = self.theme().misc_warn_dialog;
+        assert!(self
+            .app
+            .remount(
+                Id::HostKeyFingerprintPopup,
+                Box::new(components::HostKeyFingerprintPopup::new(
+                    &key.host_name(),
+                    &key.key_type(),
+                    &key.fingerprint(),
+                    known_hosts,
+                    color
+                )),
+                vec![],
+            )
+            .is_ok());
+        assert!(self
+            .app
+            .remount(
+                Id::HostKeyPopup,
+                Box::new(components::HostKeyPopup::new(color)),
+                vec![],
+            )
+            .is_ok());
+        assert!(self.app.active(&Id::HostKeyPopup).is_ok());
+    }
+
+    pub(super) fn umount_host_key(&mut self) {
+        let _ = self.app.umount(&Id::HostKeyFingerprintPopup);
+        let _ = self.app.umount(&Id::HostKeyPopup);
+    }
+
+    pub(super) fn mount_update_bookmark(&mut self, name: &str) {
+        let color = self.theme().misc_info_dialog;
+        assert!(self
+            .app
+            .remount(
+                Id::UpdateBookmarkPopup,
+                Box::new(components::UpdateBookmarkPopup::new(name, color)),
+                vec![],
+            )
+            .is_ok());
+        assert!(self.app.active(&Id::UpdateBookmarkPopup).is_ok());
+    }
+
+    pub(super) fn umount_update_bookmark(&mut self) {
+        let _ = self.app.umount(&Id::UpdateBookmarkPopup);
+    }
+
+    pub(super) fn mount_chmod(&mut self, mode: UnixPex, title: String, directories: bool) {
         // Mount
         let color = self.theme().misc_input_dialog;
         assert!(self
             .app
             .remount(
                 Id::ChmodPopup,
-                Box::new(components::ChmodPopup::new(mode, color, title)),
+                Box::new(components::ChmodPopup::new(mode, color, title, directories,)),
                 vec![],
             )
             .is_ok());
@@ -512,7 +971,10 @@ impl FileTransferActivity {
             .app
             .remount(
                 Id::FilterPopup,
-                Box::new(components::FilterPopup::new(input_color)),
+                Box::new(components::FilterPopup::new(
+                    input_color,
+                    self.config().get_filter_mode()
+                )),
                 vec![],
             )
             .is_ok());
@@ -536,13 +998,33 @@ impl FileTransferActivity {
         let _ = self.app.umount(&Id::CopyPopup);
     }
 
+    pub(super) fn mount_archive(&mut self, name: &str) {
+        let input_color = self.theme().misc_input_dialog;
+        assert!(self
+            .app
+            .remount(
+                Id::ArchivePopup,
+                Box::new(components::ArchivePopup::new(name, input_color)),
+                vec![],
+            )
+            .is_ok());
+        assert!(self.app.active(&Id::ArchivePopup).is_ok());
+    }
+
+    pub(super) fn umount_archive(&mut self) {
+        let _ = self.app.umount(&Id::ArchivePopup);
+    }
+
     pub(super) fn mount_exec(&mut self) {
         let input_color = self.theme().misc_input_dialog;
         assert!(self
             .app
             .remount(
                 Id::ExecPopup,
-                Box::new(components::ExecPopup::new(input_color)),
+                Box::new(components::ExecPopup::new(
+                    input_color,
+                    self.exec_history.commands().to_vec()
+                )),
                 vec![],
             )
             .is_ok());
@@ -553,6 +1035,23 @@ impl FileTransferActivity {
         let _ = self.app.umount(&Id::ExecPopup);
     }
 
+    pub(super) fn mount_exec_output(&mut self, cmd: &str, rc: Option<u32>, output: &str) {
+        let color = self.theme().misc_info_dialog;
+        assert!(self
+            .app
+            .remount(
+                Id::ExecOutputPopup,
+                Box::new(components::ExecOutputPopup::new(cmd, rc, output, color)),
+                vec![],
+            )
+            .is_ok());
+        assert!(self.app.active(&Id::ExecOutputPopup).is_ok());
+    }
+
+    pub(super) fn umount_exec_output(&mut self) {
+        let _ = self.app.umount(&Id::ExecOutputPopup);
+    }
+
     pub(super) fn mount_find(&mut self, msg: impl ToString, fuzzy_search: bool) {
         // Get color
         let (bg, fg, hg) = match self.browser.tab() {
@@ -568,6 +1067,7 @@ impl FileTransferActivity {
             ),
         };
 
+        let keymap = self.config().get_keymap().clone();
         // Mount component
         assert!(self
             .app
@@ -580,6 +1080,7 @@ impl FileTransferActivity {
                         bg,
                         fg,
                         hg,
+                        &keymap,
                     ))
                 } else {
                     Box::new(components::ExplorerFind::new(
@@ -588,11 +1089,13 @@ impl FileTransferActivity {
                         bg,
                         fg,
                         hg,
+                        &keymap,
                     ))
                 },
-                vec![],
+                Self::explorer_mouse_subs(),
             )
             .is_ok());
+        self.set_explorer_inactive_style(Id::ExplorerFind, fg);
         assert!(self.app.active(&Id::ExplorerFind).is_ok());
     }
 
@@ -600,8 +1103,22 @@ impl FileTransferActivity {
         let _ = self.app.umount(&Id::ExplorerFind);
     }
 
+    /// Set the style of the border and title of the explorer `id`, used while it doesn't have focus
+    fn set_explorer_inactive_style(&mut self, id: Id, foreground: Color) {
+        let color: Color = self.theme().explorer_inactive_fg(foreground);
+        assert!(self
+            .app
+            .attr(
+                &id,
+                Attribute::FocusStyle,
+                AttrValue::Style(Style::default().fg(color))
+            )
+            .is_ok());
+    }
+
     pub(super) fn mount_goto(&mut self) {
         // get files
+        let wrkdir = self.browser.explorer().wrkdir.clone();
         let files = self
             .browser
             .explorer()
@@ -615,7 +1132,11 @@ impl FileTransferActivity {
             .app
             .remount(
                 Id::GotoPopup,
-                Box::new(components::GotoPopup::new(input_color, files)),
+                Box::new(components::GotoPopup::new(
+                    input_color,
+                    wrkdir.as_path(),
+                    files
+                )),
                 vec![],
             )
             .is_ok());
@@ -639,20 +1160,54 @@ impl FileTransferActivity {
         let _ = self.app.umount(&Id::GotoPopup);
     }
 
-    pub(super) fn mount_mkdir(&mut self) {
+    pub(super) fn mount_log_search(&mut self, search: &str) {
         let input_color = self.theme().misc_input_dialog;
         assert!(self
             .app
             .remount(
-                Id::MkdirPopup,
-                Box::new(components::MkdirPopup::new(input_color)),
+                Id::LogSearchPopup,
+                Box::new(components::LogSearchPopup::new(search, input_color)),
                 vec![],
             )
             .is_ok());
-        assert!(self.app.active(&Id::MkdirPopup).is_ok());
+        assert!(self.app.active(&Id::LogSearchPopup).is_ok());
     }
 
-    pub(super) fn umount_mkdir(&mut self) {
+    pub(super) fn umount_log_search(&mut self) {
+        let _ = self.app.umount(&Id::LogSearchPopup);
+    }
+
+    pub(super) fn mount_log_export(&mut self, path: &Path) {
+        let input_color = self.theme().misc_input_dialog;
+        assert!(self
+            .app
+            .remount(
+                Id::LogExportPopup,
+                Box::new(components::LogExportPopup::new(path, input_color)),
+                vec![],
+            )
+            .is_ok());
+        assert!(self.app.active(&Id::LogExportPopup).is_ok());
+    }
+
+    pub(super) fn umount_log_export(&mut self) {
+        let _ = self.app.umount(&Id::LogExportPopup);
+    }
+
+    pub(super) fn mount_mkdir(&mut self) {
+        let input_color = self.theme().misc_input_dialog;
+        assert!(self
+            .app
+            .remount(
+                Id::MkdirPopup,
+                Box::new(components::MkdirPopup::new(input_color)),
+                vec![],
+            )
+            .is_ok());
+        assert!(self.app.active(&Id::MkdirPopup).is_ok());
+    }
+
+    pub(super) fn umount_mkdir(&mut self) {
         let _ = self.app.umount(&Id::MkdirPopup);
     }
 
@@ -783,21 +1338,39 @@ impl FileTransferActivity {
         let _ = self.app.umount(&Id::SortingPopup);
     }
 
-    pub(super) fn mount_radio_delete(&mut self) {
+    pub(super) fn mount_radio_delete<S: AsRef<str>>(&mut self, title: S) {
         let warn_color = self.theme().misc_warn_dialog;
         assert!(self
             .app
             .remount(
                 Id::DeletePopup,
-                Box::new(components::DeletePopup::new(warn_color)),
+                Box::new(components::DeletePopup::new(title, warn_color)),
                 vec![],
             )
             .is_ok());
         assert!(self.app.active(&Id::DeletePopup).is_ok());
     }
 
+    /// Mount the delete popup which requires the user to type `name` to confirm the deletion.
+    /// The popup is shown in place of the delete radio, and it's umounted along with it
+    pub(super) fn mount_delete_confirm_name<S: AsRef<str>>(&mut self, name: &str, title: S) {
+        let warn_color = self.theme().misc_warn_dialog;
+        assert!(self
+            .app
+            .remount(
+                Id::DeleteConfirmNamePopup,
+                Box::new(components::DeleteConfirmNamePopup::new(
+                    name, title, warn_color
+                )),
+                vec![],
+            )
+            .is_ok());
+        assert!(self.app.active(&Id::DeleteConfirmNamePopup).is_ok());
+    }
+
     pub(super) fn umount_radio_delete(&mut self) {
         let _ = self.app.umount(&Id::DeletePopup);
+        let _ = self.app.umount(&Id::DeleteConfirmNamePopup);
     }
 
     pub(super) fn mount_radio_watch(&mut self, watch: bool, local: &str, remote: &str) {
@@ -819,7 +1392,27 @@ impl FileTransferActivity {
         let _ = self.app.umount(&Id::WatcherPopup);
     }
 
-    pub(super) fn mount_watched_paths_list(&mut self, paths: &[std::path::PathBuf]) {
+    pub(super) fn mount_watch_ignore(&mut self, ignored: &[String]) {
+        let input_color = self.theme().misc_input_dialog;
+        assert!(self
+            .app
+            .remount(
+                Id::WatchIgnorePopup,
+                Box::new(components::WatchIgnorePopup::new(ignored, input_color)),
+                vec![],
+            )
+            .is_ok());
+        assert!(self.app.active(&Id::WatchIgnorePopup).is_ok());
+    }
+
+    pub(super) fn umount_watch_ignore(&mut self) {
+        let _ = self.app.umount(&Id::WatchIgnorePopup);
+    }
+
+    pub(super) fn mount_watched_paths_list(
+        &mut self,
+        paths: &[(std::path::PathBuf, usize, WatchDirection)],
+    ) {
         let info_color = self.theme().misc_info_dialog;
         assert!(self
             .app
@@ -836,26 +1429,378 @@ impl FileTransferActivity {
         let _ = self.app.umount(&Id::WatchedPathsList);
     }
 
-    pub(super) fn mount_radio_replace(&mut self, file_name: &str) {
+    pub(super) fn mount_dir_history(&mut self, dirs: &[std::path::PathBuf]) {
+        let info_color = self.theme().misc_info_dialog;
+        assert!(self
+            .app
+            .remount(
+                Id::DirHistoryPopup,
+                Box::new(components::DirHistoryPopup::new(dirs, info_color)),
+                vec![],
+            )
+            .is_ok());
+        assert!(self.app.active(&Id::DirHistoryPopup).is_ok());
+    }
+
+    pub(super) fn umount_dir_history(&mut self) {
+        let _ = self.app.umount(&Id::DirHistoryPopup);
+    }
+
+    pub(super) fn mount_failed_transfers(&mut self, failures: &[TransferFailure]) {
+        let error_color = self.theme().misc_error_dialog;
+        assert!(self
+            .app
+            .remount(
+                Id::FailedTransfersPopup,
+                Box::new(components::FailedTransfersPopup::new(failures, error_color)),
+                vec![],
+            )
+            .is_ok());
+        assert!(self.app.active(&Id::FailedTransfersPopup).is_ok());
+    }
+
+    pub(super) fn umount_failed_transfers(&mut self) {
+        let _ = self.app.umount(&Id::FailedTransfersPopup);
+    }
+
+    pub(super) fn mount_favorites(&mut self, favorites: &[(std::path::PathBuf, bool)]) {
+        let info_color = self.theme().misc_info_dialog;
+        assert!(self
+            .app
+            .remount(
+                Id::FavoritesPopup,
+                Box::new(components::FavoritesPopup::new(favorites, info_color)),
+                vec![],
+            )
+            .is_ok());
+        assert!(self.app.active(&Id::FavoritesPopup).is_ok());
+    }
+
+    pub(super) fn umount_favorites(&mut self) {
+        let _ = self.app.umount(&Id::FavoritesPopup);
+    }
+
+    pub(super) fn mount_bookmark_dir(&mut self, dir: &Path, suggestion: &str) {
+        let input_color = self.theme().misc_input_dialog;
+        assert!(self
+            .app
+            .remount(
+                Id::BookmarkDirPopup,
+                Box::new(components::BookmarkDirPopup::new(
+                    dir,
+                    suggestion,
+                    input_color
+                )),
+                vec![],
+            )
+            .is_ok());
+        assert!(self.app.active(&Id::BookmarkDirPopup).is_ok());
+    }
+
+    pub(super) fn umount_bookmark_dir(&mut self) {
+        let _ = self.app.umount(&Id::BookmarkDirPopup);
+    }
+
+    pub(super) fn mount_dir_bookmarks(&mut self, directories: &[DirectoryBookmark]) {
+        let info_color = self.theme().misc_info_dialog;
+        assert!(self
+            .app
+            .remount(
+                Id::DirBookmarksPopup,
+                Box::new(components::DirBookmarksPopup::new(directories, info_color)),
+                vec![],
+            )
+            .is_ok());
+        assert!(self.app.active(&Id::DirBookmarksPopup).is_ok());
+    }
+
+    pub(super) fn umount_dir_bookmarks(&mut self) {
+        let _ = self.app.umount(&Id::DirBookmarksPopup);
+    }
+
+    pub(super) fn mount_preview(&mut self, preview: &Preview) {
+        let info_color = self.theme().misc_info_dialog;
+        assert!(self
+            .app
+            .remount(
+                Id::PreviewPopup,
+                Box::new(components::PreviewPopup::new(
+                    preview.title(),
+                    &preview.lines(),
+                    info_color
+                )),
+                vec![],
+            )
+            .is_ok());
+        assert!(self.app.active(&Id::PreviewPopup).is_ok());
+    }
+
+    /// Update the content of the preview popup, keeping the scroll position
+    pub(super) fn update_preview(&mut self, preview: &Preview) {
+        let rows = components::PreviewPopup::rows(&preview.lines())
+            .into_iter()
+            .map(PropValue::TextSpan)
+            .collect();
+        let _ = self.app.attr(
+            &Id::PreviewPopup,
+            Attribute::Text,
+            AttrValue::Payload(PropPayload::Vec(rows)),
+        );
+        let _ = self.app.attr(
+            &Id::PreviewPopup,
+            Attribute::Title,
+            AttrValue::Title((preview.title(), Alignment::Center)),
+        );
+    }
+
+    pub(super) fn umount_preview(&mut self) {
+        let _ = self.app.umount(&Id::PreviewPopup);
+    }
+
+    pub(super) fn mount_edit_diff(&mut self, title: String, lines: &[String]) {
+        let save_color = self.theme().misc_save_dialog;
+        assert!(self
+            .app
+            .remount(
+                Id::EditDiffPopup,
+                Box::new(components::EditDiffPopup::new(title, lines, save_color)),
+                vec![],
+            )
+            .is_ok());
+        assert!(self.app.active(&Id::EditDiffPopup).is_ok());
+    }
+
+    pub(super) fn umount_edit_diff(&mut self) {
+        let _ = self.app.umount(&Id::EditDiffPopup);
+    }
+
+    pub(super) fn mount_edit_conflict(&mut self, file_name: &str) {
+        let warn_color = self.theme().misc_warn_dialog;
+        assert!(self
+            .app
+            .remount(
+                Id::EditConflictPopup,
+                Box::new(components::EditConflictPopup::new(file_name, warn_color)),
+                vec![],
+            )
+            .is_ok());
+        assert!(self.app.active(&Id::EditConflictPopup).is_ok());
+    }
+
+    pub(super) fn umount_edit_conflict(&mut self) {
+        let _ = self.app.umount(&Id::EditConflictPopup);
+    }
+
+    pub(super) fn mount_quick_commands(&mut self, commands: &[QuickCommand]) {
+        let info_color = self.theme().misc_info_dialog;
+        assert!(self
+            .app
+            .remount(
+                Id::QuickCommandsPopup,
+                Box::new(components::QuickCommandsPopup::new(commands, info_color)),
+                vec![],
+            )
+            .is_ok());
+        assert!(self.app.active(&Id::QuickCommandsPopup).is_ok());
+    }
+
+    pub(super) fn umount_quick_commands(&mut self) {
+        let _ = self.app.umount(&Id::QuickCommandsPopup);
+    }
+
+    /// Mount the transfer queue popup, with the progress bars of the active item
+    pub(super) fn mount_transfer_queue(&mut self) {
+        let info_color = self.theme().misc_info_dialog;
+        let prog_color_full = self.theme().transfer_progress_bar_full;
+        let prog_color_partial = self.theme().transfer_progress_bar_partial;
+        assert!(self
+            .app
+            .remount(
+                Id::TransferQueuePopup,
+                Box::new(components::TransferQueuePopup::new(
+                    self.transfer_queue_rows(),
+                    info_color
+                )),
+                vec![],
+            )
+            .is_ok());
+        assert!(self
+            .app
+            .remount(
+                Id::TransferQueueProgressBarFull,
+                Box::new(components::ProgressBarFull::new(
+                    0.0,
+                    "",
+                    "",
+                    prog_color_full
+                )),
+                vec![],
+            )
+            .is_ok());
+        assert!(self
+            .app
+            .remount(
+                Id::TransferQueueProgressBarPartial,
+                Box::new(components::ProgressBarPartial::new(
+                    0.0,
+                    "",
+                    "",
+                    prog_color_partial
+                )),
+                vec![],
+            )
+            .is_ok());
+        self.update_transfer_queue();
+        assert!(self.app.active(&Id::TransferQueuePopup).is_ok());
+    }
+
+    /// Update the items and the progress bars of the transfer queue popup
+    pub(super) fn update_transfer_queue(&mut self) {
+        assert!(self
+            .app
+            .attr(
+                &Id::TransferQueuePopup,
+                Attribute::Content,
+                AttrValue::Table(self.transfer_queue_rows())
+            )
+            .is_ok());
+        let active = self
+            .transfer_queue
+            .items()
+            .iter()
+            .find(|x| x.state == QueueItemState::Active)
+            .map(|x| x.source.name());
+        let (full, partial) = match active {
+            Some(name) => (
+                (
+                    self.transfer_queue.transfer.full.to_string(),
+                    self.transfer_queue.transfer.full.calc_progress(),
+                    name,
+                ),
+                (
+                    self.transfer_queue.transfer.partial.to_string(),
+                    self.transfer_queue.transfer.partial.calc_progress(),
+                    String::new(),
+                ),
+            ),
+            None => (
+                (String::new(), 0.0, String::from("No active transfer")),
+                (String::new(), 0.0, String::new()),
+            ),
+        };
+        for (id, (label, progress, title)) in [
+            (Id::TransferQueueProgressBarFull, full),
+            (Id::TransferQueueProgressBarPartial, partial),
+        ] {
+            assert!(self
+                .app
+                .attr(&id, Attribute::Text, AttrValue::String(label))
+                .is_ok());
+            assert!(self
+                .app
+                .attr(
+                    &id,
+                    Attribute::Value,
+                    AttrValue::Payload(PropPayload::One(PropValue::F64(progress)))
+                )
+                .is_ok());
+            assert!(self
+                .app
+                .attr(
+                    &id,
+                    Attribute::Title,
+                    AttrValue::Title((title, Alignment::Center))
+                )
+                .is_ok());
+        }
+    }
+
+    pub(super) fn umount_transfer_queue(&mut self) {
+        let _ = self.app.umount(&Id::TransferQueuePopup);
+        let _ = self.app.umount(&Id::TransferQueueProgressBarFull);
+        let _ = self.app.umount(&Id::TransferQueueProgressBarPartial);
+    }
+
+    fn transfer_queue_rows(&self) -> Vec<Vec<TextSpan>> {
+        let now = Local::now();
+        let rows: Vec<Vec<TextSpan>> = self
+            .transfer_queue
+            .items()
+            .iter()
+            .map(|x| {
+                let arrow = match x.direction {
+                    TransferDirection::Upload => "↑",
+                    TransferDirection::Download => "↓",
+                };
+                let state = match &x.state {
+                    QueueItemState::Failed(_) => TextSpan::from("failed  ").fg(Color::Red),
+                    QueueItemState::Done => TextSpan::from("done    ").fg(Color::Green),
+                    QueueItemState::Active => TextSpan::from("active  ").fg(Color::Yellow),
+                    QueueItemState::Scheduled(at) => {
+                        TextSpan::from(format!("{:<8}", schedule::fmt_start_time(*at, now)))
+                            .fg(Color::Cyan)
+                    }
+                    QueueItemState::Pending => TextSpan::from("pending "),
+                };
+                let mut row = vec![
+                    state,
+                    TextSpan::from(format!(
+                        "{arrow} {} → {}",
+                        x.source.path().display(),
+                        x.dest.display()
+                    )),
+                ];
+                if let QueueItemState::Failed(err) = &x.state {
+                    row.push(TextSpan::from(format!(" ({err})")).italic());
+                }
+                row
+            })
+            .collect();
+        match rows.is_empty() {
+            true => vec![vec![TextSpan::from("The transfer queue is empty").italic()]],
+            false => rows,
+        }
+    }
+
+    pub(super) fn mount_radio_replace(
+        &mut self,
+        file_name: &str,
+        resumable: bool,
+        renamable: bool,
+    ) {
         let warn_color = self.theme().misc_warn_dialog;
         assert!(self
             .app
             .remount(
                 Id::ReplacePopup,
-                Box::new(components::ReplacePopup::new(Some(file_name), warn_color)),
+                Box::new(components::ReplacePopup::new(
+                    Some(file_name),
+                    resumable,
+                    renamable,
+                    warn_color
+                )),
                 vec![],
             )
             .is_ok());
         assert!(self.app.active(&Id::ReplacePopup).is_ok());
     }
 
-    pub(super) fn mount_radio_replace_many(&mut self, files: &[String]) {
+    /// Mount the replace popup for many files.
+    /// If set, `filter` is the filter the transferred entries have been selected by
+    pub(super) fn mount_radio_replace_many(
+        &mut self,
+        files: &[String],
+        filter: Option<&str>,
+        renamable: bool,
+    ) {
         let warn_color = self.theme().misc_warn_dialog;
         assert!(self
             .app
             .remount(
                 Id::ReplacingFilesListPopup,
-                Box::new(components::ReplacingFilesListPopup::new(files, warn_color)),
+                Box::new(components::ReplacingFilesListPopup::new(
+                    files, filter, warn_color
+                )),
                 vec![],
             )
             .is_ok());
@@ -863,7 +1808,9 @@ impl FileTransferActivity {
             .app
             .remount(
                 Id::ReplacePopup,
-                Box::new(components::ReplacePopup::new(None, warn_color)),
+                Box::new(components::ReplacePopup::new(
+                    None, false, renamable, warn_color
+                )),
                 vec![],
             )
             .is_ok());
@@ -880,12 +1827,38 @@ impl FileTransferActivity {
         let _ = self.app.umount(&Id::ReplacingFilesListPopup); // NOTE: replace anyway
     }
 
-    pub(super) fn mount_file_info(&mut self, file: &File) {
+    pub(super) fn mount_replace_rename(&mut self, file_name: &str, suggestion: &str) {
+        let input_color = self.theme().misc_input_dialog;
+        assert!(self
+            .app
+            .remount(
+                Id::ReplaceRenamePopup,
+                Box::new(components::ReplaceRenamePopup::new(
+                    file_name,
+                    suggestion,
+                    input_color
+                )),
+                vec![],
+            )
+            .is_ok());
+        assert!(self.app.active(&Id::ReplaceRenamePopup).is_ok());
+    }
+
+    pub(super) fn umount_replace_rename(&mut self) {
+        let _ = self.app.umount(&Id::ReplaceRenamePopup);
+    }
+
+    pub(super) fn mount_file_info(
+        &mut self,
+        file: &File,
+        special: Option<SpecialFileType>,
+        xattrs: &[Xattr],
+    ) {
         assert!(self
             .app
             .remount(
                 Id::FileInfoPopup,
-                Box::new(components::FileInfoPopup::new(file)),
+                Box::new(components::FileInfoPopup::new(file, special, xattrs)),
                 vec![],
             )
             .is_ok());
@@ -896,9 +1869,95 @@ impl FileTransferActivity {
         let _ = self.app.umount(&Id::FileInfoPopup);
     }
 
+    pub(super) fn mount_checksum(
+        &mut self,
+        algorithm: ChecksumAlgorithm,
+        name: &str,
+        digest: String,
+    ) {
+        let info_color = self.theme().misc_info_dialog;
+        assert!(self
+            .app
+            .remount(
+                Id::ChecksumPopup,
+                Box::new(components::ChecksumPopup::new(
+                    algorithm, name, digest, info_color
+                )),
+                vec![],
+            )
+            .is_ok());
+        assert!(self.app.active(&Id::ChecksumPopup).is_ok());
+    }
+
+    pub(super) fn umount_checksum(&mut self) {
+        let _ = self.app.umount(&Id::ChecksumPopup);
+    }
+
+    pub(super) fn mount_checksum_compare(
+        &mut self,
+        algorithm: ChecksumAlgorithm,
+        name: &str,
+        host_bridge_digest: &str,
+        remote_digest: &str,
+    ) {
+        // mismatching digests are displayed as an error
+        let color = match host_bridge_digest == remote_digest {
+            true => self.theme().misc_info_dialog,
+            false => self.theme().misc_error_dialog,
+        };
+        assert!(self
+            .app
+            .remount(
+                Id::ChecksumComparePopup,
+                Box::new(components::ChecksumComparePopup::new(
+                    algorithm,
+                    name,
+                    host_bridge_digest,
+                    remote_digest,
+                    color
+                )),
+                vec![],
+            )
+            .is_ok());
+        assert!(self.app.active(&Id::ChecksumComparePopup).is_ok());
+    }
+
+    pub(super) fn umount_checksum_compare(&mut self) {
+        let _ = self.app.umount(&Id::ChecksumComparePopup);
+    }
+
+    /// Remount the footer bar if the state of the session displayed in it has changed
+    pub(super) fn refresh_footer_bar(&mut self) {
+        let state = self.footer_state();
+        if state != self.footer {
+            self.footer = state;
+            self.mount_footer_bar();
+            self.redraw = true;
+        }
+    }
+
+    fn mount_footer_bar(&mut self) {
+        let colors = components::FooterColors {
+            keys: self.theme().misc_keys,
+            transfer: self.theme().transfer_progress_bar_partial,
+            errors: self.theme().misc_error_dialog,
+            watcher: self.theme().transfer_status_sync_browsing,
+            scheduled: self.theme().transfer_status_sorting,
+        };
+        assert!(self
+            .app
+            .remount(
+                Id::FooterBar,
+                Box::new(components::FooterBar::new(&self.footer, colors)),
+                vec![],
+            )
+            .is_ok());
+    }
+
     pub(super) fn refresh_local_status_bar(&mut self) {
         let sorting_color = self.theme().transfer_status_sorting;
         let hidden_color = self.theme().transfer_status_hidden;
+        let watcher_color = self.theme().transfer_status_sync_browsing;
         assert!(self
             .app
             .remount(
@@ -906,7 +1965,10 @@ impl FileTransferActivity {
                 Box::new(components::StatusBarLocal::new(
                     &self.browser,
                     sorting_color,
-                    hidden_color
+                    hidden_color,
+                    self.watcher_pending,
+                    watcher_color,
+                    self.host_bridge_free_space
                 )),
                 vec![],
             )
@@ -917,6 +1979,8 @@ impl FileTransferActivity {
         let sorting_color = self.theme().transfer_status_sorting;
         let hidden_color = self.theme().transfer_status_hidden;
         let sync_color = self.theme().transfer_status_sync_browsing;
+        let clock_skew_color = self.theme().misc_warn_dialog;
+        let clock_skew = self.clock_skew.exceeding().map(ClockSkew::fmt);
         assert!(self
             .app
             .remount(
@@ -925,7 +1989,10 @@ impl FileTransferActivity {
                     &self.browser,
                     sorting_color,
                     hidden_color,
-                    sync_color
+                    sync_color,
+                    clock_skew,
+                    clock_skew_color,
+                    self.ssh_negotiation.free_space()
                 )),
                 vec![],
             )
@@ -966,14 +2033,53 @@ impl FileTransferActivity {
         let _ = self.app.umount(&Id::SyncBrowsingMkdirPopup);
     }
 
+    pub(super) fn mount_transfer_confirm_popup(&mut self, title: &str, schedulable: bool) {
+        let color = self.theme().misc_warn_dialog;
+        assert!(self
+            .app
+            .remount(
+                Id::TransferConfirmPopup,
+                Box::new(components::TransferConfirmPopup::new(
+                    title,
+                    color,
+                    schedulable
+                )),
+                vec![],
+            )
+            .is_ok());
+        assert!(self.app.active(&Id::TransferConfirmPopup).is_ok());
+    }
+
+    pub(super) fn umount_transfer_confirm_popup(&mut self) {
+        let _ = self.app.umount(&Id::TransferConfirmPopup);
+    }
+
+    pub(super) fn mount_schedule_transfer_popup(&mut self, error: Option<&str>) {
+        let input_color = self.theme().misc_input_dialog;
+        assert!(self
+            .app
+            .remount(
+                Id::ScheduleTransferPopup,
+                Box::new(components::ScheduleTransferPopup::new(input_color, error)),
+                vec![],
+            )
+            .is_ok());
+        assert!(self.app.active(&Id::ScheduleTransferPopup).is_ok());
+    }
+
+    pub(super) fn umount_schedule_transfer_popup(&mut self) {
+        let _ = self.app.umount(&Id::ScheduleTransferPopup);
+    }
+
     /// Mount help
     pub(super) fn mount_help(&mut self) {
         let key_color = self.theme().misc_keys;
+        let keymap = self.config().get_keymap().clone();
         assert!(self
             .app
             .remount(
                 Id::KeybindingsPopup,
-                Box::new(components::KeybindingsPopup::new(key_color)),
+                Box::new(components::KeybindingsPopup::new(key_color, &keymap)),
                 vec![],
             )
             .is_ok());
@@ -1059,38 +2165,101 @@ impl FileTransferActivity {
                         }),
                         Self::no_popup_mounted_clause(),
                     ),
+                    Sub::new(
+                        SubEventClause::Keyboard(KeyEvent {
+                            code: Key::Char('z'),
+                            modifiers: KeyModifiers::CONTROL,
+                        }),
+                        SubClause::Always,
+                    ),
                     Sub::new(SubEventClause::WindowResize, SubClause::Always)
                 ]
             )
             .is_ok());
     }
 
+    /// Mount the listener of the focus events reported by the terminal
+    fn mount_focus_listener(&mut self) {
+        assert!(self
+            .app
+            .mount(
+                Id::FocusListener,
+                Box::<components::FocusListener>::default(),
+                vec![Sub::new(SubEventClause::Any, SubClause::Always)]
+            )
+            .is_ok());
+    }
+
     /// Returns a sub clause which requires that no popup is mounted in order to be satisfied
     fn no_popup_mounted_clause() -> SubClause<Id> {
+        SubClause::and(
+            SubClause::not(SubClause::IsMounted(Id::ExplorerFind)),
+            Self::no_dialog_mounted_clause(),
+        )
+    }
+
+    /// Returns the subscriptions of the explorers, so that the explorer without focus gets it once it's clicked.
+    /// Mouse events can't be matched regardless of their position, so the explorers without focus ignore the
+    /// other events. The find explorer is a panel too, so it doesn't prevent them
+    fn explorer_mouse_subs() -> Vec<Sub<Id, NoUserEvent>> {
+        vec![Sub::new(
+            SubEventClause::Any,
+            Self::no_dialog_mounted_clause(),
+        )]
+    }
+
+    /// Returns a sub clause which requires that no popup, except for the find explorer, is mounted in order to be
+    /// satisfied
+    fn no_dialog_mounted_clause() -> SubClause<Id> {
         tuirealm::subclause_and_not!(
+            Id::ArchivePopup,
+            Id::BookmarkDirPopup,
+            Id::ChecksumComparePopup,
+            Id::ChecksumPopup,
             Id::CopyPopup,
+            Id::CredentialsPasswordPopup,
+            Id::CredentialsUsernamePopup,
+            Id::DeleteConfirmNamePopup,
             Id::DeletePopup,
+            Id::DirBookmarksPopup,
+            Id::DirHistoryPopup,
             Id::DisconnectPopup,
+            Id::EditConflictPopup,
+            Id::EditDiffPopup,
             Id::ErrorPopup,
+            Id::ExecOutputPopup,
             Id::ExecPopup,
+            Id::FailedTransfersPopup,
             Id::FatalPopup,
+            Id::FavoritesPopup,
             Id::FileInfoPopup,
             Id::GotoPopup,
+            Id::HostKeyFingerprintPopup,
+            Id::HostKeyPopup,
             Id::KeybindingsPopup,
+            Id::LogExportPopup,
+            Id::LogSearchPopup,
             Id::MkdirPopup,
             Id::NewfilePopup,
             Id::OpenWithPopup,
+            Id::PreviewPopup,
             Id::ProgressBarFull,
             Id::ProgressBarPartial,
-            Id::ExplorerFind,
+            Id::QuickCommandsPopup,
             Id::QuitPopup,
             Id::RenamePopup,
             Id::ReplacePopup,
+            Id::ReplaceRenamePopup,
             Id::SaveAsPopup,
+            Id::ScheduleTransferPopup,
             Id::SortingPopup,
             Id::SyncBrowsingMkdirPopup,
             Id::SymlinkPopup,
+            Id::TransferConfirmPopup,
+            Id::TransferQueuePopup,
+            Id::UpdateBookmarkPopup,
             Id::WatcherPopup,
+            Id::WatchIgnorePopup,
             Id::WatchedPathsList,
             Id::ChmodPopup,
             Id::WaitPopup,
@@ -38,6 +38,14 @@ pub trait Activity {
     /// If not, the call will return `None`, otherwise return`Some(ExitReason)`
     fn will_umount(&self) -> Option<&ExitReason>;
 
+    /// `on_suspend` is the function which must be called before the process is stopped (e.g. on `SIGTSTP`).
+    /// It gives the terminal back to the shell
+    fn on_suspend(&mut self);
+
+    /// `on_resume` is the function which must be called once the process has been resumed (`SIGCONT`).
+    /// It takes the terminal back and forces a full redraw of the interface
+    fn on_resume(&mut self);
+
     /// `on_destroy` is the function which cleans up runtime variables and data before terminating the activity.
     /// This function must be called once before terminating the activity.
     /// This function finally releases the context
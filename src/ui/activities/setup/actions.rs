@@ -270,6 +270,24 @@ impl SetupActivity {
             IdTheme::StatusSync => {
                 theme.transfer_status_sync_browsing = color;
             }
+            IdTheme::ExplorerDirFg => {
+                theme.explorer_dir_fg = Self::file_type_color(color);
+            }
+            IdTheme::ExplorerSymlinkFg => {
+                theme.explorer_symlink_fg = Self::file_type_color(color);
+            }
+            IdTheme::ExplorerExecFg => {
+                theme.explorer_exec_fg = Self::file_type_color(color);
+            }
+            IdTheme::ExplorerArchiveFg => {
+                theme.explorer_archive_fg = Self::file_type_color(color);
+            }
+            IdTheme::ExplorerImageFg => {
+                theme.explorer_image_fg = Self::file_type_color(color);
+            }
+            IdTheme::ExplorerCodeFg => {
+                theme.explorer_code_fg = Self::file_type_color(color);
+            }
             _ => {}
         }
     }
@@ -361,6 +379,25 @@ impl SetupActivity {
         let transfer_status_sync_browsing = self
             .get_color(&Id::Theme(IdTheme::StatusSync))
             .map_err(|_| Id::Theme(IdTheme::StatusSync))?;
+        // explorer file types
+        let explorer_dir_fg = self
+            .get_color(&Id::Theme(IdTheme::ExplorerDirFg))
+            .map_err(|_| Id::Theme(IdTheme::ExplorerDirFg))?;
+        let explorer_symlink_fg = self
+            .get_color(&Id::Theme(IdTheme::ExplorerSymlinkFg))
+            .map_err(|_| Id::Theme(IdTheme::ExplorerSymlinkFg))?;
+        let explorer_exec_fg = self
+            .get_color(&Id::Theme(IdTheme::ExplorerExecFg))
+            .map_err(|_| Id::Theme(IdTheme::ExplorerExecFg))?;
+        let explorer_archive_fg = self
+            .get_color(&Id::Theme(IdTheme::ExplorerArchiveFg))
+            .map_err(|_| Id::Theme(IdTheme::ExplorerArchiveFg))?;
+        let explorer_image_fg = self
+            .get_color(&Id::Theme(IdTheme::ExplorerImageFg))
+            .map_err(|_| Id::Theme(IdTheme::ExplorerImageFg))?;
+        let explorer_code_fg = self
+            .get_color(&Id::Theme(IdTheme::ExplorerCodeFg))
+            .map_err(|_| Id::Theme(IdTheme::ExplorerCodeFg))?;
         // Update theme
         let theme = self.theme_mut();
         theme.auth_address = auth_address;
@@ -390,9 +427,20 @@ impl SetupActivity {
         theme.transfer_status_hidden = transfer_status_hidden;
         theme.transfer_status_sorting = transfer_status_sorting;
         theme.transfer_status_sync_browsing = transfer_status_sync_browsing;
+        theme.explorer_dir_fg = Self::file_type_color(explorer_dir_fg);
+        theme.explorer_symlink_fg = Self::file_type_color(explorer_symlink_fg);
+        theme.explorer_exec_fg = Self::file_type_color(explorer_exec_fg);
+        theme.explorer_archive_fg = Self::file_type_color(explorer_archive_fg);
+        theme.explorer_image_fg = Self::file_type_color(explorer_image_fg);
+        theme.explorer_code_fg = Self::file_type_color(explorer_code_fg);
         Ok(())
     }
 
+    /// Get the color of a file type in the explorers; the default color is unset, so the explorer foreground is used
+    fn file_type_color(color: Color) -> Option<Color> {
+        Some(color).filter(|x| *x != Color::Reset)
+    }
+
     /// Get color from component
     fn get_color(&self, component: &Id) -> Result<Color, ()> {
         match self.app.state(component) {
@@ -9,13 +9,15 @@ use tuirealm::props::{Alignment, BorderType, Borders, Color, InputType, Style};
 use tuirealm::{Component, Event, MockComponent, NoUserEvent};
 
 use super::{ConfigMsg, Msg};
+use crate::config::params::{MAX_RECONNECT_RETRIES, MAX_TRANSFER_CONCURRENCY};
 use crate::explorer::GroupDirs as GroupDirsEnum;
 use crate::filetransfer::FileTransferProtocol;
 use crate::ui::activities::setup::{
-    RADIO_PROTOCOL_FTP, RADIO_PROTOCOL_FTPS, RADIO_PROTOCOL_KUBE, RADIO_PROTOCOL_S3,
-    RADIO_PROTOCOL_SCP, RADIO_PROTOCOL_SFTP, RADIO_PROTOCOL_SMB, RADIO_PROTOCOL_WEBDAV,
+    RADIO_PROTOCOL_FTP, RADIO_PROTOCOL_FTPS, RADIO_PROTOCOL_KUBE, RADIO_PROTOCOL_LOCALHOST,
+    RADIO_PROTOCOL_S3, RADIO_PROTOCOL_SCP, RADIO_PROTOCOL_SFTP, RADIO_PROTOCOL_SMB,
+    RADIO_PROTOCOL_WEBDAV,
 };
-use crate::utils::parser::parse_bytesize;
+use crate::utils::parser::{parse_bytesize, parse_octal_mode};
 
 // -- components
 
@@ -67,7 +69,17 @@ impl DefaultProtocol {
                         .color(Color::Cyan)
                         .modifiers(BorderType::Rounded),
                 )
-                .choices(&["SFTP", "SCP", "FTP", "FTPS", "Kube", "S3", "SMB", "WebDAV"])
+                .choices(&[
+                    "SFTP",
+                    "SCP",
+                    "FTP",
+                    "FTPS",
+                    "Kube",
+                    "S3",
+                    "SMB",
+                    "WebDAV",
+                    "Localhost",
+                ])
                 .foreground(Color::Cyan)
                 .rewind(true)
                 .title("Default protocol", Alignment::Left)
@@ -80,6 +92,7 @@ impl DefaultProtocol {
                     FileTransferProtocol::AwsS3 => RADIO_PROTOCOL_S3,
                     FileTransferProtocol::Smb => RADIO_PROTOCOL_SMB,
                     FileTransferProtocol::WebDAV => RADIO_PROTOCOL_WEBDAV,
+                    FileTransferProtocol::Localhost => RADIO_PROTOCOL_LOCALHOST,
                 }),
         }
     }
@@ -96,6 +109,158 @@ impl Component<Msg, NoUserEvent> for DefaultProtocol {
     }
 }
 
+#[derive(MockComponent)]
+pub struct DisableCache {
+    component: Radio,
+}
+
+impl DisableCache {
+    pub fn new(disabled: bool) -> Self {
+        Self {
+            component: Radio::default()
+                .borders(
+                    Borders::default()
+                        .color(Color::LightGreen)
+                        .modifiers(BorderType::Rounded),
+                )
+                .choices(&["Yes", "No"])
+                .foreground(Color::LightGreen)
+                .rewind(true)
+                .title("Disable temporary cache?", Alignment::Left)
+                .value(usize::from(!disabled)),
+        }
+    }
+}
+
+impl Component<Msg, NoUserEvent> for DisableCache {
+    fn on(&mut self, ev: Event<NoUserEvent>) -> Option<Msg> {
+        handle_radio_ev(
+            self,
+            ev,
+            Msg::Config(ConfigMsg::DisableCacheBlurDown),
+            Msg::Config(ConfigMsg::DisableCacheBlurUp),
+        )
+    }
+}
+
+#[derive(MockComponent)]
+pub struct DefaultDirMode {
+    component: Input,
+}
+
+impl DefaultDirMode {
+    pub fn new(value: &str) -> Self {
+        // -- validators
+        fn validate(mode: &str) -> bool {
+            mode.is_empty() || parse_octal_mode(mode).is_some()
+        }
+        fn char_valid(_input: &str, incoming: char) -> bool {
+            ('0'..='7').contains(&incoming)
+        }
+        Self {
+            component: Input::default()
+                .borders(
+                    Borders::default()
+                        .color(Color::LightBlue)
+                        .modifiers(BorderType::Rounded),
+                )
+                .foreground(Color::LightBlue)
+                .invalid_style(Style::default().fg(Color::Red))
+                .input_type(InputType::Custom(validate, char_valid))
+                .placeholder("0755", Style::default().fg(Color::Rgb(128, 128, 128)))
+                .title("Mode of the created remote directories", Alignment::Left)
+                .value(value),
+        }
+    }
+}
+
+impl Component<Msg, NoUserEvent> for DefaultDirMode {
+    fn on(&mut self, ev: Event<NoUserEvent>) -> Option<Msg> {
+        handle_input_ev(
+            self,
+            ev,
+            Msg::Config(ConfigMsg::DefaultDirModeBlurDown),
+            Msg::Config(ConfigMsg::DefaultDirModeBlurUp),
+        )
+    }
+}
+
+#[derive(MockComponent)]
+pub struct DefaultFileMode {
+    component: Input,
+}
+
+impl DefaultFileMode {
+    pub fn new(value: &str) -> Self {
+        // -- validators
+        fn validate(mode: &str) -> bool {
+            mode.is_empty() || parse_octal_mode(mode).is_some()
+        }
+        fn char_valid(_input: &str, incoming: char) -> bool {
+            ('0'..='7').contains(&incoming)
+        }
+        Self {
+            component: Input::default()
+                .borders(
+                    Borders::default()
+                        .color(Color::LightGreen)
+                        .modifiers(BorderType::Rounded),
+                )
+                .foreground(Color::LightGreen)
+                .invalid_style(Style::default().fg(Color::Red))
+                .input_type(InputType::Custom(validate, char_valid))
+                .placeholder("0644", Style::default().fg(Color::Rgb(128, 128, 128)))
+                .title("Mode of the uploaded remote files", Alignment::Left)
+                .value(value),
+        }
+    }
+}
+
+impl Component<Msg, NoUserEvent> for DefaultFileMode {
+    fn on(&mut self, ev: Event<NoUserEvent>) -> Option<Msg> {
+        handle_input_ev(
+            self,
+            ev,
+            Msg::Config(ConfigMsg::DefaultFileModeBlurDown),
+            Msg::Config(ConfigMsg::DefaultFileModeBlurUp),
+        )
+    }
+}
+
+#[derive(MockComponent)]
+pub struct DeleteToTrash {
+    component: Radio,
+}
+
+impl DeleteToTrash {
+    pub fn new(enabled: bool) -> Self {
+        Self {
+            component: Radio::default()
+                .borders(
+                    Borders::default()
+                        .color(Color::LightRed)
+                        .modifiers(BorderType::Rounded),
+                )
+                .choices(&["Yes", "No"])
+                .foreground(Color::LightRed)
+                .rewind(true)
+                .title("Move deleted local files to trash?", Alignment::Left)
+                .value(usize::from(!enabled)),
+        }
+    }
+}
+
+impl Component<Msg, NoUserEvent> for DeleteToTrash {
+    fn on(&mut self, ev: Event<NoUserEvent>) -> Option<Msg> {
+        handle_radio_ev(
+            self,
+            ev,
+            Msg::Config(ConfigMsg::DeleteToTrashBlurDown),
+            Msg::Config(ConfigMsg::DeleteToTrashBlurUp),
+        )
+    }
+}
+
 #[derive(MockComponent)]
 pub struct GroupDirs {
     component: Radio,
@@ -202,6 +367,85 @@ impl Component<Msg, NoUserEvent> for NotificationsEnabled {
     }
 }
 
+#[derive(MockComponent)]
+pub struct MouseCapture {
+    component: Radio,
+}
+
+impl MouseCapture {
+    pub fn new(enabled: bool) -> Self {
+        Self {
+            component: Radio::default()
+                .borders(
+                    Borders::default()
+                        .color(Color::LightCyan)
+                        .modifiers(BorderType::Rounded),
+                )
+                .choices(&["Yes", "No"])
+                .foreground(Color::LightCyan)
+                .rewind(true)
+                .title("Enable mouse support?", Alignment::Left)
+                .value(usize::from(!enabled)),
+        }
+    }
+}
+
+impl Component<Msg, NoUserEvent> for MouseCapture {
+    fn on(&mut self, ev: Event<NoUserEvent>) -> Option<Msg> {
+        handle_radio_ev(
+            self,
+            ev,
+            Msg::Config(ConfigMsg::MouseCaptureBlurDown),
+            Msg::Config(ConfigMsg::MouseCaptureBlurUp),
+        )
+    }
+}
+
+#[derive(MockComponent)]
+pub struct ParkedSessionTimeout {
+    component: Input,
+}
+
+impl ParkedSessionTimeout {
+    pub fn new(value: &str) -> Self {
+        // -- validators
+        fn validate(secs: &str) -> bool {
+            secs.parse::<u64>().is_ok()
+        }
+        fn char_valid(_input: &str, incoming: char) -> bool {
+            incoming.is_ascii_digit()
+        }
+        Self {
+            component: Input::default()
+                .borders(
+                    Borders::default()
+                        .color(Color::LightMagenta)
+                        .modifiers(BorderType::Rounded),
+                )
+                .foreground(Color::LightMagenta)
+                .invalid_style(Style::default().fg(Color::Red))
+                .input_type(InputType::Custom(validate, char_valid))
+                .placeholder("300", Style::default().fg(Color::Rgb(128, 128, 128)))
+                .title(
+                    "Keep disconnected sessions open for (seconds)",
+                    Alignment::Left,
+                )
+                .value(value),
+        }
+    }
+}
+
+impl Component<Msg, NoUserEvent> for ParkedSessionTimeout {
+    fn on(&mut self, ev: Event<NoUserEvent>) -> Option<Msg> {
+        handle_input_ev(
+            self,
+            ev,
+            Msg::Config(ConfigMsg::ParkedSessionTimeoutBlurDown),
+            Msg::Config(ConfigMsg::ParkedSessionTimeoutBlurUp),
+        )
+    }
+}
+
 #[derive(MockComponent)]
 pub struct PromptOnFileReplace {
     component: Radio,
@@ -315,6 +559,50 @@ impl Component<Msg, NoUserEvent> for NotificationsThreshold {
     }
 }
 
+#[derive(MockComponent)]
+pub struct ReconnectRetries {
+    component: Input,
+}
+
+impl ReconnectRetries {
+    pub fn new(value: &str) -> Self {
+        // -- validators
+        fn validate(retries: &str) -> bool {
+            retries
+                .parse::<u64>()
+                .is_ok_and(|x| x <= MAX_RECONNECT_RETRIES)
+        }
+        fn char_valid(_input: &str, incoming: char) -> bool {
+            incoming.is_ascii_digit()
+        }
+        Self {
+            component: Input::default()
+                .borders(
+                    Borders::default()
+                        .color(Color::LightRed)
+                        .modifiers(BorderType::Rounded),
+                )
+                .foreground(Color::LightRed)
+                .invalid_style(Style::default().fg(Color::Red))
+                .input_type(InputType::Custom(validate, char_valid))
+                .placeholder("3", Style::default().fg(Color::Rgb(128, 128, 128)))
+                .title("Reconnection attempts", Alignment::Left)
+                .value(value),
+        }
+    }
+}
+
+impl Component<Msg, NoUserEvent> for ReconnectRetries {
+    fn on(&mut self, ev: Event<NoUserEvent>) -> Option<Msg> {
+        handle_input_ev(
+            self,
+            ev,
+            Msg::Config(ConfigMsg::ReconnectRetriesBlurDown),
+            Msg::Config(ConfigMsg::ReconnectRetriesBlurUp),
+        )
+    }
+}
+
 #[derive(MockComponent)]
 pub struct RemoteFileFmt {
     component: Input,
@@ -389,6 +677,50 @@ impl Component<Msg, NoUserEvent> for SshConfig {
     }
 }
 
+#[derive(MockComponent)]
+pub struct TransferConcurrency {
+    component: Input,
+}
+
+impl TransferConcurrency {
+    pub fn new(value: &str) -> Self {
+        // -- validators
+        fn validate(files: &str) -> bool {
+            files
+                .parse::<u64>()
+                .is_ok_and(|x| (1..=MAX_TRANSFER_CONCURRENCY).contains(&x))
+        }
+        fn char_valid(_input: &str, incoming: char) -> bool {
+            incoming.is_ascii_digit()
+        }
+        Self {
+            component: Input::default()
+                .borders(
+                    Borders::default()
+                        .color(Color::Yellow)
+                        .modifiers(BorderType::Rounded),
+                )
+                .foreground(Color::Yellow)
+                .invalid_style(Style::default().fg(Color::Red))
+                .input_type(InputType::Custom(validate, char_valid))
+                .placeholder("1", Style::default().fg(Color::Rgb(128, 128, 128)))
+                .title("Files transferred in parallel", Alignment::Left)
+                .value(value),
+        }
+    }
+}
+
+impl Component<Msg, NoUserEvent> for TransferConcurrency {
+    fn on(&mut self, ev: Event<NoUserEvent>) -> Option<Msg> {
+        handle_input_ev(
+            self,
+            ev,
+            Msg::Config(ConfigMsg::TransferConcurrencyBlurDown),
+            Msg::Config(ConfigMsg::TransferConcurrencyBlurUp),
+        )
+    }
+}
+
 #[derive(MockComponent)]
 pub struct TextEditor {
     component: Input,
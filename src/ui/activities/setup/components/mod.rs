@@ -11,8 +11,10 @@ mod theme;
 
 pub(super) use commons::{ErrorPopup, Footer, Header, Keybindings, QuitPopup, SavePopup};
 pub(super) use config::{
-    CheckUpdates, DefaultProtocol, GroupDirs, HiddenFiles, LocalFileFmt, NotificationsEnabled,
-    NotificationsThreshold, PromptOnFileReplace, RemoteFileFmt, SshConfig, TextEditor,
+    CheckUpdates, DefaultDirMode, DefaultFileMode, DefaultProtocol, DeleteToTrash, DisableCache,
+    GroupDirs, HiddenFiles, LocalFileFmt, MouseCapture, NotificationsEnabled,
+    NotificationsThreshold, ParkedSessionTimeout, PromptOnFileReplace, ReconnectRetries,
+    RemoteFileFmt, SshConfig, TextEditor, TransferConcurrency,
 };
 pub(super) use ssh::{DelSshKeyPopup, SshHost, SshKeys, SshUsername};
 pub(super) use theme::*;
@@ -99,6 +99,27 @@ impl Component<Msg, NoUserEvent> for TransferTitle2 {
     }
 }
 
+#[derive(MockComponent)]
+pub struct FileTypesTitle {
+    component: Label,
+}
+
+impl Default for FileTypesTitle {
+    fn default() -> Self {
+        Self {
+            component: Label::default()
+                .modifiers(TextModifiers::BOLD)
+                .text("Explorer file types"),
+        }
+    }
+}
+
+impl Component<Msg, NoUserEvent> for FileTypesTitle {
+    fn on(&mut self, _ev: Event<NoUserEvent>) -> Option<Msg> {
+        None
+    }
+}
+
 #[derive(MockComponent)]
 pub struct AuthAddress {
     component: InputColor,
@@ -773,6 +794,156 @@ impl Component<Msg, NoUserEvent> for StatusSync {
     }
 }
 
+#[derive(MockComponent)]
+pub struct ExplorerDirFg {
+    component: InputColor,
+}
+
+impl ExplorerDirFg {
+    pub fn new(value: Color) -> Self {
+        Self {
+            component: InputColor::new(
+                "Directories",
+                IdTheme::ExplorerDirFg,
+                value,
+                Msg::Theme(ThemeMsg::ExplorerDirFgBlurDown),
+                Msg::Theme(ThemeMsg::ExplorerDirFgBlurUp),
+            ),
+        }
+    }
+}
+
+impl Component<Msg, NoUserEvent> for ExplorerDirFg {
+    fn on(&mut self, ev: Event<NoUserEvent>) -> Option<Msg> {
+        self.component.on(ev)
+    }
+}
+
+#[derive(MockComponent)]
+pub struct ExplorerSymlinkFg {
+    component: InputColor,
+}
+
+impl ExplorerSymlinkFg {
+    pub fn new(value: Color) -> Self {
+        Self {
+            component: InputColor::new(
+                "Symbolic links",
+                IdTheme::ExplorerSymlinkFg,
+                value,
+                Msg::Theme(ThemeMsg::ExplorerSymlinkFgBlurDown),
+                Msg::Theme(ThemeMsg::ExplorerSymlinkFgBlurUp),
+            ),
+        }
+    }
+}
+
+impl Component<Msg, NoUserEvent> for ExplorerSymlinkFg {
+    fn on(&mut self, ev: Event<NoUserEvent>) -> Option<Msg> {
+        self.component.on(ev)
+    }
+}
+
+#[derive(MockComponent)]
+pub struct ExplorerExecFg {
+    component: InputColor,
+}
+
+impl ExplorerExecFg {
+    pub fn new(value: Color) -> Self {
+        Self {
+            component: InputColor::new(
+                "Executables",
+                IdTheme::ExplorerExecFg,
+                value,
+                Msg::Theme(ThemeMsg::ExplorerExecFgBlurDown),
+                Msg::Theme(ThemeMsg::ExplorerExecFgBlurUp),
+            ),
+        }
+    }
+}
+
+impl Component<Msg, NoUserEvent> for ExplorerExecFg {
+    fn on(&mut self, ev: Event<NoUserEvent>) -> Option<Msg> {
+        self.component.on(ev)
+    }
+}
+
+#[derive(MockComponent)]
+pub struct ExplorerArchiveFg {
+    component: InputColor,
+}
+
+impl ExplorerArchiveFg {
+    pub fn new(value: Color) -> Self {
+        Self {
+            component: InputColor::new(
+                "Archives",
+                IdTheme::ExplorerArchiveFg,
+                value,
+                Msg::Theme(ThemeMsg::ExplorerArchiveFgBlurDown),
+                Msg::Theme(ThemeMsg::ExplorerArchiveFgBlurUp),
+            ),
+        }
+    }
+}
+
+impl Component<Msg, NoUserEvent> for ExplorerArchiveFg {
+    fn on(&mut self, ev: Event<NoUserEvent>) -> Option<Msg> {
+        self.component.on(ev)
+    }
+}
+
+#[derive(MockComponent)]
+pub struct ExplorerImageFg {
+    component: InputColor,
+}
+
+impl ExplorerImageFg {
+    pub fn new(value: Color) -> Self {
+        Self {
+            component: InputColor::new(
+                "Images",
+                IdTheme::ExplorerImageFg,
+                value,
+                Msg::Theme(ThemeMsg::ExplorerImageFgBlurDown),
+                Msg::Theme(ThemeMsg::ExplorerImageFgBlurUp),
+            ),
+        }
+    }
+}
+
+impl Component<Msg, NoUserEvent> for ExplorerImageFg {
+    fn on(&mut self, ev: Event<NoUserEvent>) -> Option<Msg> {
+        self.component.on(ev)
+    }
+}
+
+#[derive(MockComponent)]
+pub struct ExplorerCodeFg {
+    component: InputColor,
+}
+
+impl ExplorerCodeFg {
+    pub fn new(value: Color) -> Self {
+        Self {
+            component: InputColor::new(
+                "Source code",
+                IdTheme::ExplorerCodeFg,
+                value,
+                Msg::Theme(ThemeMsg::ExplorerCodeFgBlurDown),
+                Msg::Theme(ThemeMsg::ExplorerCodeFgBlurUp),
+            ),
+        }
+    }
+}
+
+impl Component<Msg, NoUserEvent> for ExplorerCodeFg {
+    fn on(&mut self, ev: Event<NoUserEvent>) -> Option<Msg> {
+        self.component.on(ev)
+    }
+}
+
 // -- input color
 
 #[derive(MockComponent)]
@@ -785,6 +956,13 @@ struct InputColor {
 
 impl InputColor {
     pub fn new(name: &str, id: IdTheme, color: Color, on_key_down: Msg, on_key_up: Msg) -> Self {
+        // 256 colors palette indexes are valid colors too
+        fn validate(color: &str) -> bool {
+            crate::utils::parser::parse_color(color).is_some()
+        }
+        fn char_valid(_input: &str, _incoming: char) -> bool {
+            true
+        }
         let value = crate::utils::fmt::fmt_color(&color);
         Self {
             component: Input::default()
@@ -794,7 +972,7 @@ impl InputColor {
                         .modifiers(BorderType::Rounded),
                 )
                 .foreground(color)
-                .input_type(InputType::Color)
+                .input_type(InputType::Custom(validate, char_valid))
                 .placeholder("#aa33ee", Style::default().fg(Color::Rgb(128, 128, 128)))
                 .title(name, Alignment::Left)
                 .value(value),
@@ -806,7 +984,7 @@ impl InputColor {
 
     fn update_color(&mut self, result: CmdResult) -> Option<Msg> {
         if let CmdResult::Changed(State::One(StateValue::String(color))) = result {
-            let color = tuirealm::utils::parser::parse_color(&color).unwrap();
+            let color = crate::utils::parser::parse_color(&color).unwrap();
             self.attr(Attribute::Foreground, AttrValue::Color(color));
             self.attr(
                 Attribute::Borders,
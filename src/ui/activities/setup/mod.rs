@@ -23,6 +23,7 @@ use super::{Activity, Context, ExitReason, CROSSTERM_MAX_POLL};
 use crate::config::themes::Theme;
 use crate::system::config_client::ConfigClient;
 use crate::system::theme_provider::ThemeProvider;
+use crate::utils::tty;
 
 // radio
 const RADIO_PROTOCOL_SFTP: usize = 0;
@@ -33,6 +34,7 @@ const RADIO_PROTOCOL_KUBE: usize = 4;
 const RADIO_PROTOCOL_S3: usize = 5;
 const RADIO_PROTOCOL_SMB: usize = 6;
 const RADIO_PROTOCOL_WEBDAV: usize = 7;
+const RADIO_PROTOCOL_LOCALHOST: usize = 8;
 
 // -- components
 #[derive(Debug, Eq, PartialEq, Clone, Hash)]
@@ -57,16 +59,24 @@ enum IdCommon {
 #[derive(Debug, Eq, PartialEq, Clone, Hash)]
 enum IdConfig {
     CheckUpdates,
+    DefaultDirMode,
+    DefaultFileMode,
     DefaultProtocol,
+    DeleteToTrash,
+    DisableCache,
     GroupDirs,
     HiddenFiles,
     LocalFileFmt,
+    MouseCapture,
     NotificationsEnabled,
     NotificationsThreshold,
+    ParkedSessionTimeout,
     PromptOnFileReplace,
+    ReconnectRetries,
     RemoteFileFmt,
     SshConfig,
     TextEditor,
+    TransferConcurrency,
 }
 
 #[derive(Debug, Eq, PartialEq, Clone, Hash)]
@@ -87,12 +97,19 @@ pub enum IdTheme {
     AuthRecentHosts,
     AuthTitle,
     AuthUsername,
+    ExplorerArchiveFg,
+    ExplorerCodeFg,
+    ExplorerDirFg,
+    ExplorerExecFg,
+    ExplorerImageFg,
     ExplorerLocalBg,
     ExplorerLocalFg,
     ExplorerLocalHg,
     ExplorerRemoteBg,
     ExplorerRemoteFg,
     ExplorerRemoteHg,
+    ExplorerSymlinkFg,
+    FileTypesTitle,
     LogBg,
     LogWindow,
     MiscError,
@@ -143,26 +160,42 @@ pub enum ConfigMsg {
     CheckUpdatesBlurDown,
     CheckUpdatesBlurUp,
     ConfigChanged,
+    DefaultDirModeBlurDown,
+    DefaultDirModeBlurUp,
+    DefaultFileModeBlurDown,
+    DefaultFileModeBlurUp,
     DefaultProtocolBlurDown,
     DefaultProtocolBlurUp,
+    DeleteToTrashBlurDown,
+    DeleteToTrashBlurUp,
+    DisableCacheBlurDown,
+    DisableCacheBlurUp,
     GroupDirsBlurDown,
     GroupDirsBlurUp,
     HiddenFilesBlurDown,
     HiddenFilesBlurUp,
     LocalFileFmtBlurDown,
     LocalFileFmtBlurUp,
+    MouseCaptureBlurDown,
+    MouseCaptureBlurUp,
     NotificationsEnabledBlurDown,
     NotificationsEnabledBlurUp,
     NotificationsThresholdBlurDown,
     NotificationsThresholdBlurUp,
+    ParkedSessionTimeoutBlurDown,
+    ParkedSessionTimeoutBlurUp,
     PromptOnFileReplaceBlurDown,
     PromptOnFileReplaceBlurUp,
+    ReconnectRetriesBlurDown,
+    ReconnectRetriesBlurUp,
     RemoteFileFmtBlurDown,
     RemoteFileFmtBlurUp,
     SshConfigBlurDown,
     SshConfigBlurUp,
     TextEditorBlurDown,
     TextEditorBlurUp,
+    TransferConcurrencyBlurDown,
+    TransferConcurrencyBlurUp,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -195,6 +228,16 @@ pub enum ThemeMsg {
     AuthUsernameBlurDown,
     AuthUsernameBlurUp,
     ColorChanged(IdTheme, Color),
+    ExplorerArchiveFgBlurDown,
+    ExplorerArchiveFgBlurUp,
+    ExplorerCodeFgBlurDown,
+    ExplorerCodeFgBlurUp,
+    ExplorerDirFgBlurDown,
+    ExplorerDirFgBlurUp,
+    ExplorerExecFgBlurDown,
+    ExplorerExecFgBlurUp,
+    ExplorerImageFgBlurDown,
+    ExplorerImageFgBlurUp,
     ExplorerLocalBgBlurDown,
     ExplorerLocalBgBlurUp,
     ExplorerLocalFgBlurDown,
@@ -207,6 +250,8 @@ pub enum ThemeMsg {
     ExplorerRemoteFgBlurUp,
     ExplorerRemoteHgBlurDown,
     ExplorerRemoteHgBlurUp,
+    ExplorerSymlinkFgBlurDown,
+    ExplorerSymlinkFgBlurUp,
     LogBgBlurDown,
     LogBgBlurUp,
     LogWindowBlurDown,
@@ -241,7 +286,7 @@ pub enum ThemeMsg {
 const STORE_CONFIG_CHANGED: &str = "SETUP_CONFIG_CHANGED";
 
 /// Current view layout
-#[derive(PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ViewLayout {
     SetupForm,
     SshKeys,
@@ -258,7 +303,7 @@ pub struct SetupActivity {
 }
 
 impl SetupActivity {
-    pub fn new(ticks: Duration) -> Self {
+    pub fn new(ticks: Duration, layout: ViewLayout) -> Self {
         Self {
             app: Application::init(
                 EventListenerCfg::default()
@@ -267,7 +312,7 @@ impl SetupActivity {
             ),
             exit_reason: None,
             context: None,
-            layout: ViewLayout::SetupForm,
+            layout,
             redraw: true, // Draw at first `on_draw`
         }
     }
@@ -336,7 +381,7 @@ impl Activity for SetupActivity {
             error!("Failed to enter raw mode: {}", err);
         }
         // Init view
-        self.init(ViewLayout::SetupForm);
+        self.init(self.layout);
         // Verify error state from context
         if let Some(err) = self.context.as_mut().unwrap().error() {
             self.mount_error(err.as_str());
@@ -379,6 +424,23 @@ impl Activity for SetupActivity {
         self.exit_reason.as_ref()
     }
 
+    /// `on_suspend` is the function which must be called before the process is stopped (e.g. on `SIGTSTP`).
+    /// It gives the terminal back to the shell
+    fn on_suspend(&mut self) {
+        if let Some(ctx) = self.context.as_mut() {
+            tty::leave_terminal(ctx.terminal());
+        }
+    }
+
+    /// `on_resume` is the function which must be called once the process has been resumed (`SIGCONT`).
+    /// It takes the terminal back and forces a full redraw of the interface
+    fn on_resume(&mut self) {
+        if let Some(ctx) = self.context.as_mut() {
+            tty::enter_terminal(ctx.terminal());
+        }
+        self.redraw = true;
+    }
+
     /// `on_destroy` is the function which cleans up runtime variables and data before terminating the activity.
     /// This function must be called once before terminating the activity.
     /// This function finally releases the context
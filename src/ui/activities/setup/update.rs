@@ -111,14 +111,53 @@ impl SetupActivity {
             ConfigMsg::CheckUpdatesBlurUp => {
                 assert!(self.app.active(&Id::Config(IdConfig::HiddenFiles)).is_ok());
             }
+            ConfigMsg::DefaultDirModeBlurDown => {
+                assert!(self.app.active(&Id::Config(IdConfig::MouseCapture)).is_ok());
+            }
+            ConfigMsg::DefaultDirModeBlurUp => {
+                assert!(self
+                    .app
+                    .active(&Id::Config(IdConfig::DefaultFileMode))
+                    .is_ok());
+            }
+            ConfigMsg::DefaultFileModeBlurDown => {
+                assert!(self
+                    .app
+                    .active(&Id::Config(IdConfig::DefaultDirMode))
+                    .is_ok());
+            }
+            ConfigMsg::DefaultFileModeBlurUp => {
+                assert!(self.app.active(&Id::Config(IdConfig::GroupDirs)).is_ok());
+            }
             ConfigMsg::DefaultProtocolBlurDown => {
                 assert!(self.app.active(&Id::Config(IdConfig::HiddenFiles)).is_ok());
             }
             ConfigMsg::DefaultProtocolBlurUp => {
                 assert!(self.app.active(&Id::Config(IdConfig::TextEditor)).is_ok());
             }
+            ConfigMsg::DeleteToTrashBlurDown => {
+                assert!(self
+                    .app
+                    .active(&Id::Config(IdConfig::ReconnectRetries))
+                    .is_ok());
+            }
+            ConfigMsg::DeleteToTrashBlurUp => {
+                assert!(self.app.active(&Id::Config(IdConfig::DisableCache)).is_ok());
+            }
+            ConfigMsg::DisableCacheBlurDown => {
+                assert!(self
+                    .app
+                    .active(&Id::Config(IdConfig::DeleteToTrash))
+                    .is_ok());
+            }
+            ConfigMsg::DisableCacheBlurUp => {
+                assert!(self.app.active(&Id::Config(IdConfig::SshConfig)).is_ok());
+            }
             ConfigMsg::GroupDirsBlurDown => {
-                assert!(self.app.active(&Id::Config(IdConfig::LocalFileFmt)).is_ok());
+                assert!(self
+                    .app
+                    .active(&Id::Config(IdConfig::DefaultFileMode))
+                    .is_ok());
             }
             ConfigMsg::GroupDirsBlurUp => {
                 assert!(self
@@ -142,7 +181,22 @@ impl SetupActivity {
                     .is_ok());
             }
             ConfigMsg::LocalFileFmtBlurUp => {
-                assert!(self.app.active(&Id::Config(IdConfig::GroupDirs)).is_ok());
+                assert!(self
+                    .app
+                    .active(&Id::Config(IdConfig::TransferConcurrency))
+                    .is_ok());
+            }
+            ConfigMsg::MouseCaptureBlurDown => {
+                assert!(self
+                    .app
+                    .active(&Id::Config(IdConfig::TransferConcurrency))
+                    .is_ok());
+            }
+            ConfigMsg::MouseCaptureBlurUp => {
+                assert!(self
+                    .app
+                    .active(&Id::Config(IdConfig::DefaultDirMode))
+                    .is_ok());
             }
             ConfigMsg::NotificationsEnabledBlurDown => {
                 assert!(self
@@ -165,12 +219,33 @@ impl SetupActivity {
                     .active(&Id::Config(IdConfig::NotificationsEnabled))
                     .is_ok());
             }
+            ConfigMsg::ParkedSessionTimeoutBlurDown => {
+                assert!(self.app.active(&Id::Config(IdConfig::TextEditor)).is_ok());
+            }
+            ConfigMsg::ParkedSessionTimeoutBlurUp => {
+                assert!(self
+                    .app
+                    .active(&Id::Config(IdConfig::ReconnectRetries))
+                    .is_ok());
+            }
             ConfigMsg::PromptOnFileReplaceBlurDown => {
                 assert!(self.app.active(&Id::Config(IdConfig::GroupDirs)).is_ok());
             }
             ConfigMsg::PromptOnFileReplaceBlurUp => {
                 assert!(self.app.active(&Id::Config(IdConfig::CheckUpdates)).is_ok());
             }
+            ConfigMsg::ReconnectRetriesBlurDown => {
+                assert!(self
+                    .app
+                    .active(&Id::Config(IdConfig::ParkedSessionTimeout))
+                    .is_ok());
+            }
+            ConfigMsg::ReconnectRetriesBlurUp => {
+                assert!(self
+                    .app
+                    .active(&Id::Config(IdConfig::DeleteToTrash))
+                    .is_ok());
+            }
             ConfigMsg::RemoteFileFmtBlurDown => {
                 assert!(self
                     .app
@@ -187,10 +262,19 @@ impl SetupActivity {
                     .is_ok());
             }
             ConfigMsg::TextEditorBlurUp => {
-                assert!(self.app.active(&Id::Config(IdConfig::SshConfig)).is_ok());
+                assert!(self
+                    .app
+                    .active(&Id::Config(IdConfig::ParkedSessionTimeout))
+                    .is_ok());
+            }
+            ConfigMsg::TransferConcurrencyBlurDown => {
+                assert!(self.app.active(&Id::Config(IdConfig::LocalFileFmt)).is_ok());
+            }
+            ConfigMsg::TransferConcurrencyBlurUp => {
+                assert!(self.app.active(&Id::Config(IdConfig::MouseCapture)).is_ok());
             }
             ConfigMsg::SshConfigBlurDown => {
-                assert!(self.app.active(&Id::Config(IdConfig::TextEditor)).is_ok());
+                assert!(self.app.active(&Id::Config(IdConfig::DisableCache)).is_ok());
             }
             ConfigMsg::SshConfigBlurUp => {
                 assert!(self
@@ -283,7 +367,7 @@ impl SetupActivity {
                 assert!(self.app.active(&Id::Theme(IdTheme::AuthAddress)).is_ok());
             }
             ThemeMsg::AuthProtocolBlurUp => {
-                assert!(self.app.active(&Id::Theme(IdTheme::StatusSync)).is_ok());
+                assert!(self.app.active(&Id::Theme(IdTheme::ExplorerCodeFg)).is_ok());
             }
             ThemeMsg::AuthRecentHostsBlurDown => {
                 assert!(self.app.active(&Id::Theme(IdTheme::MiscError)).is_ok());
@@ -451,11 +535,65 @@ impl SetupActivity {
                 assert!(self.app.active(&Id::Theme(IdTheme::StatusSorting)).is_ok());
             }
             ThemeMsg::StatusSyncBlurDown => {
-                assert!(self.app.active(&Id::Theme(IdTheme::AuthProtocol)).is_ok());
+                assert!(self.app.active(&Id::Theme(IdTheme::ExplorerDirFg)).is_ok());
             }
             ThemeMsg::StatusSyncBlurUp => {
                 assert!(self.app.active(&Id::Theme(IdTheme::StatusHidden)).is_ok());
             }
+            ThemeMsg::ExplorerDirFgBlurDown => {
+                assert!(self
+                    .app
+                    .active(&Id::Theme(IdTheme::ExplorerSymlinkFg))
+                    .is_ok());
+            }
+            ThemeMsg::ExplorerDirFgBlurUp => {
+                assert!(self.app.active(&Id::Theme(IdTheme::StatusSync)).is_ok());
+            }
+            ThemeMsg::ExplorerSymlinkFgBlurDown => {
+                assert!(self.app.active(&Id::Theme(IdTheme::ExplorerExecFg)).is_ok());
+            }
+            ThemeMsg::ExplorerSymlinkFgBlurUp => {
+                assert!(self.app.active(&Id::Theme(IdTheme::ExplorerDirFg)).is_ok());
+            }
+            ThemeMsg::ExplorerExecFgBlurDown => {
+                assert!(self
+                    .app
+                    .active(&Id::Theme(IdTheme::ExplorerArchiveFg))
+                    .is_ok());
+            }
+            ThemeMsg::ExplorerExecFgBlurUp => {
+                assert!(self
+                    .app
+                    .active(&Id::Theme(IdTheme::ExplorerSymlinkFg))
+                    .is_ok());
+            }
+            ThemeMsg::ExplorerArchiveFgBlurDown => {
+                assert!(self
+                    .app
+                    .active(&Id::Theme(IdTheme::ExplorerImageFg))
+                    .is_ok());
+            }
+            ThemeMsg::ExplorerArchiveFgBlurUp => {
+                assert!(self.app.active(&Id::Theme(IdTheme::ExplorerExecFg)).is_ok());
+            }
+            ThemeMsg::ExplorerImageFgBlurDown => {
+                assert!(self.app.active(&Id::Theme(IdTheme::ExplorerCodeFg)).is_ok());
+            }
+            ThemeMsg::ExplorerImageFgBlurUp => {
+                assert!(self
+                    .app
+                    .active(&Id::Theme(IdTheme::ExplorerArchiveFg))
+                    .is_ok());
+            }
+            ThemeMsg::ExplorerCodeFgBlurDown => {
+                assert!(self.app.active(&Id::Theme(IdTheme::AuthProtocol)).is_ok());
+            }
+            ThemeMsg::ExplorerCodeFgBlurUp => {
+                assert!(self
+                    .app
+                    .active(&Id::Theme(IdTheme::ExplorerImageFg))
+                    .is_ok());
+            }
             ThemeMsg::ColorChanged(id, color) => {
                 self.action_save_color(id, color);
                 // Set unsaved changes to true
@@ -12,7 +12,7 @@ use tuirealm::{State, StateValue};
 
 use super::{
     components, Context, Id, IdCommon, IdConfig, SetupActivity, ViewLayout, RADIO_PROTOCOL_KUBE,
-    RADIO_PROTOCOL_WEBDAV,
+    RADIO_PROTOCOL_LOCALHOST, RADIO_PROTOCOL_WEBDAV,
 };
 use crate::explorer::GroupDirs;
 use crate::filetransfer::FileTransferProtocol;
@@ -21,6 +21,7 @@ use crate::ui::activities::setup::{
     RADIO_PROTOCOL_SMB,
 };
 use crate::utils::fmt::fmt_bytes;
+use crate::utils::parser;
 
 impl SetupActivity {
     // -- view
@@ -37,6 +38,7 @@ impl SetupActivity {
 
     pub(super) fn view_setup(&mut self) {
         let mut ctx: Context = self.context.take().unwrap();
+        let palette = ctx.theme_provider().palette();
         let _ = ctx.terminal().raw_mut().draw(|f| {
             // Prepare main chunks
             let chunks = Layout::default()
@@ -45,7 +47,7 @@ impl SetupActivity {
                 .constraints(
                     [
                         Constraint::Length(3), // Current tab
-                        Constraint::Min(18),   // Main body
+                        Constraint::Min(30),   // Main body
                         Constraint::Length(1), // Help footer
                     ]
                     .as_ref(),
@@ -70,6 +72,10 @@ impl SetupActivity {
                         Constraint::Length(3), // Updates tab
                         Constraint::Length(3), // Prompt file replace
                         Constraint::Length(3), // Group dirs
+                        Constraint::Length(3), // Default file mode
+                        Constraint::Length(3), // Default dir mode
+                        Constraint::Length(3), // Mouse capture
+                        Constraint::Length(3), // Transfer concurrency
                         Constraint::Length(1), // Prevent overflow
                     ]
                     .as_ref(),
@@ -96,6 +102,26 @@ impl SetupActivity {
             );
             self.app
                 .view(&Id::Config(IdConfig::GroupDirs), f, ui_cfg_chunks_col1[5]);
+            self.app.view(
+                &Id::Config(IdConfig::DefaultFileMode),
+                f,
+                ui_cfg_chunks_col1[6],
+            );
+            self.app.view(
+                &Id::Config(IdConfig::DefaultDirMode),
+                f,
+                ui_cfg_chunks_col1[7],
+            );
+            self.app.view(
+                &Id::Config(IdConfig::MouseCapture),
+                f,
+                ui_cfg_chunks_col1[8],
+            );
+            self.app.view(
+                &Id::Config(IdConfig::TransferConcurrency),
+                f,
+                ui_cfg_chunks_col1[9],
+            );
             // Column 2
             let ui_cfg_chunks_col2 = Layout::default()
                 .direction(Direction::Vertical)
@@ -106,6 +132,10 @@ impl SetupActivity {
                         Constraint::Length(3), // Notifications enabled
                         Constraint::Length(3), // Notifications threshold
                         Constraint::Length(3), // Ssh config
+                        Constraint::Length(3), // Disable cache
+                        Constraint::Length(3), // Delete to trash
+                        Constraint::Length(3), // Reconnect retries
+                        Constraint::Length(3), // Parked session timeout
                         Constraint::Length(1), // Prevent overflow
                     ]
                     .as_ref(),
@@ -133,8 +163,29 @@ impl SetupActivity {
             );
             self.app
                 .view(&Id::Config(IdConfig::SshConfig), f, ui_cfg_chunks_col2[4]);
+            self.app.view(
+                &Id::Config(IdConfig::DisableCache),
+                f,
+                ui_cfg_chunks_col2[5],
+            );
+            self.app.view(
+                &Id::Config(IdConfig::DeleteToTrash),
+                f,
+                ui_cfg_chunks_col2[6],
+            );
+            self.app.view(
+                &Id::Config(IdConfig::ReconnectRetries),
+                f,
+                ui_cfg_chunks_col2[7],
+            );
+            self.app.view(
+                &Id::Config(IdConfig::ParkedSessionTimeout),
+                f,
+                ui_cfg_chunks_col2[8],
+            );
             // Popups
             self.view_popups(f);
+            palette.apply(f.buffer_mut());
         });
         // Put context back to context
         self.context = Some(ctx);
@@ -206,6 +257,48 @@ impl SetupActivity {
                 vec![]
             )
             .is_ok());
+        // Default modes
+        let fmt_mode = |mode: Option<u32>| mode.map(|x| format!("{x:04o}")).unwrap_or_default();
+        let file_mode = fmt_mode(self.config().get_default_file_mode());
+        assert!(self
+            .app
+            .remount(
+                Id::Config(IdConfig::DefaultFileMode),
+                Box::new(components::DefaultFileMode::new(&file_mode)),
+                vec![]
+            )
+            .is_ok());
+        let dir_mode = fmt_mode(self.config().get_default_dir_mode());
+        assert!(self
+            .app
+            .remount(
+                Id::Config(IdConfig::DefaultDirMode),
+                Box::new(components::DefaultDirMode::new(&dir_mode)),
+                vec![]
+            )
+            .is_ok());
+        // Mouse capture
+        assert!(self
+            .app
+            .remount(
+                Id::Config(IdConfig::MouseCapture),
+                Box::new(components::MouseCapture::new(
+                    self.config().get_mouse_capture()
+                )),
+                vec![]
+            )
+            .is_ok());
+        // Transfer concurrency
+        assert!(self
+            .app
+            .remount(
+                Id::Config(IdConfig::TransferConcurrency),
+                Box::new(components::TransferConcurrency::new(
+                    &self.config().get_transfer_concurrency().to_string()
+                )),
+                vec![]
+            )
+            .is_ok());
         // Local File Fmt
         assert!(self
             .app
@@ -261,6 +354,53 @@ impl SetupActivity {
                 vec![]
             )
             .is_ok());
+        // Disable cache
+        assert!(self
+            .app
+            .remount(
+                Id::Config(IdConfig::DisableCache),
+                Box::new(components::DisableCache::new(
+                    self.config().get_disable_cache()
+                )),
+                vec![]
+            )
+            .is_ok());
+        // Delete to trash
+        assert!(self
+            .app
+            .remount(
+                Id::Config(IdConfig::DeleteToTrash),
+                Box::new(components::DeleteToTrash::new(
+                    self.config().get_delete_to_trash()
+                )),
+                vec![]
+            )
+            .is_ok());
+        // Reconnect retries
+        assert!(self
+            .app
+            .remount(
+                Id::Config(IdConfig::ReconnectRetries),
+                Box::new(components::ReconnectRetries::new(
+                    &self.config().get_reconnect_retries().to_string()
+                )),
+                vec![]
+            )
+            .is_ok());
+        // Parked session timeout
+        let timeout = self
+            .config()
+            .get_parked_session_timeout()
+            .map(|x| x.as_secs())
+            .unwrap_or_default();
+        assert!(self
+            .app
+            .remount(
+                Id::Config(IdConfig::ParkedSessionTimeout),
+                Box::new(components::ParkedSessionTimeout::new(&timeout.to_string())),
+                vec![]
+            )
+            .is_ok());
     }
 
     /// Collect values from input and put them into the configuration
@@ -282,6 +422,7 @@ impl SetupActivity {
                 RADIO_PROTOCOL_S3 => FileTransferProtocol::AwsS3,
                 RADIO_PROTOCOL_SMB => FileTransferProtocol::Smb,
                 RADIO_PROTOCOL_WEBDAV => FileTransferProtocol::WebDAV,
+                RADIO_PROTOCOL_LOCALHOST => FileTransferProtocol::Localhost,
                 _ => FileTransferProtocol::Sftp,
             };
             self.config_mut().set_default_protocol(protocol);
@@ -324,6 +465,30 @@ impl SetupActivity {
             };
             self.config_mut().set_group_dirs(dirs);
         }
+        if let Ok(State::One(StateValue::String(mode))) =
+            self.app.state(&Id::Config(IdConfig::DefaultFileMode))
+        {
+            self.config_mut()
+                .set_default_file_mode(parser::parse_octal_mode(mode));
+        }
+        if let Ok(State::One(StateValue::String(mode))) =
+            self.app.state(&Id::Config(IdConfig::DefaultDirMode))
+        {
+            self.config_mut()
+                .set_default_dir_mode(parser::parse_octal_mode(mode));
+        }
+        if let Ok(State::One(StateValue::Usize(opt))) =
+            self.app.state(&Id::Config(IdConfig::MouseCapture))
+        {
+            self.config_mut().set_mouse_capture(opt == 0);
+        }
+        if let Ok(State::One(StateValue::String(files))) =
+            self.app.state(&Id::Config(IdConfig::TransferConcurrency))
+        {
+            if let Ok(files) = files.parse() {
+                self.config_mut().set_transfer_concurrency(files);
+            }
+        }
         if let Ok(State::One(StateValue::Usize(opt))) =
             self.app.state(&Id::Config(IdConfig::NotificationsEnabled))
         {
@@ -349,5 +514,29 @@ impl SetupActivity {
                 self.config_mut().set_ssh_config(Some(path));
             }
         }
+        if let Ok(State::One(StateValue::Usize(opt))) =
+            self.app.state(&Id::Config(IdConfig::DisableCache))
+        {
+            self.config_mut().set_disable_cache(opt == 0);
+        }
+        if let Ok(State::One(StateValue::Usize(opt))) =
+            self.app.state(&Id::Config(IdConfig::DeleteToTrash))
+        {
+            self.config_mut().set_delete_to_trash(opt == 0);
+        }
+        if let Ok(State::One(StateValue::String(retries))) =
+            self.app.state(&Id::Config(IdConfig::ReconnectRetries))
+        {
+            if let Ok(retries) = retries.parse() {
+                self.config_mut().set_reconnect_retries(retries);
+            }
+        }
+        if let Ok(State::One(StateValue::String(secs))) =
+            self.app.state(&Id::Config(IdConfig::ParkedSessionTimeout))
+        {
+            if let Ok(secs) = secs.parse() {
+                self.config_mut().set_parked_session_timeout(secs);
+            }
+        }
     }
 }
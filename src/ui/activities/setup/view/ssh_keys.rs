@@ -24,6 +24,7 @@ impl SetupActivity {
 
     pub(crate) fn view_ssh_keys(&mut self) {
         let mut ctx: Context = self.context.take().unwrap();
+        let palette = ctx.theme_provider().palette();
         let _ = ctx.terminal().raw_mut().draw(|f| {
             // Prepare main chunks
             let chunks = Layout::default()
@@ -65,6 +66,7 @@ impl SetupActivity {
                 self.app
                     .view(&Id::Ssh(IdSsh::SshUsername), f, popup_chunks[1]);
             }
+            palette.apply(f.buffer_mut());
         });
         // Put context back to context
         self.context = Some(ctx);
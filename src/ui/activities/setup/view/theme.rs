@@ -5,6 +5,7 @@
 
 // Locals
 // Ext
+use tuirealm::props::Color;
 use tuirealm::ratatui::layout::{Constraint, Direction, Layout};
 
 use super::{components, Context, Id, IdCommon, IdTheme, SetupActivity, Theme, ViewLayout};
@@ -26,6 +27,7 @@ impl SetupActivity {
 
     pub(super) fn view_theme(&mut self) {
         let mut ctx: Context = self.context.take().unwrap();
+        let palette = ctx.theme_provider().palette();
         let _ = ctx.terminal().raw_mut().draw(|f| {
             // Prepare main chunks
             let chunks = Layout::default()
@@ -48,10 +50,11 @@ impl SetupActivity {
                 .direction(Direction::Horizontal)
                 .constraints(
                     [
-                        Constraint::Percentage(25),
-                        Constraint::Percentage(25),
-                        Constraint::Percentage(25),
-                        Constraint::Percentage(25),
+                        Constraint::Percentage(20),
+                        Constraint::Percentage(20),
+                        Constraint::Percentage(20),
+                        Constraint::Percentage(20),
+                        Constraint::Percentage(20),
                     ]
                     .as_ref(),
                 )
@@ -234,8 +237,60 @@ impl SetupActivity {
                 f,
                 transfer_colors_layout_col2[7],
             );
+            let file_types_colors_layout = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints(
+                    [
+                        Constraint::Length(1), // Title
+                        Constraint::Length(3), // directories
+                        Constraint::Length(3), // symlinks
+                        Constraint::Length(3), // executables
+                        Constraint::Length(3), // archives
+                        Constraint::Length(3), // images
+                        Constraint::Length(3), // source code
+                        Constraint::Length(1), // Prevent overflow
+                    ]
+                    .as_ref(),
+                )
+                .split(colors_layout[4]);
+            self.app.view(
+                &Id::Theme(IdTheme::FileTypesTitle),
+                f,
+                file_types_colors_layout[0],
+            );
+            self.app.view(
+                &Id::Theme(IdTheme::ExplorerDirFg),
+                f,
+                file_types_colors_layout[1],
+            );
+            self.app.view(
+                &Id::Theme(IdTheme::ExplorerSymlinkFg),
+                f,
+                file_types_colors_layout[2],
+            );
+            self.app.view(
+                &Id::Theme(IdTheme::ExplorerExecFg),
+                f,
+                file_types_colors_layout[3],
+            );
+            self.app.view(
+                &Id::Theme(IdTheme::ExplorerArchiveFg),
+                f,
+                file_types_colors_layout[4],
+            );
+            self.app.view(
+                &Id::Theme(IdTheme::ExplorerImageFg),
+                f,
+                file_types_colors_layout[5],
+            );
+            self.app.view(
+                &Id::Theme(IdTheme::ExplorerCodeFg),
+                f,
+                file_types_colors_layout[6],
+            );
             // Popups
             self.view_popups(f);
+            palette.apply(f.buffer_mut());
         });
         // Put context back to context
         self.context = Some(ctx);
@@ -274,6 +329,14 @@ impl SetupActivity {
                 vec![]
             )
             .is_ok());
+        assert!(self
+            .app
+            .remount(
+                Id::Theme(IdTheme::FileTypesTitle),
+                Box::<components::FileTypesTitle>::default(),
+                vec![]
+            )
+            .is_ok());
     }
 
     /// Load values from theme into input fields
@@ -515,5 +578,65 @@ impl SetupActivity {
                 vec![]
             )
             .is_ok());
+        assert!(self
+            .app
+            .remount(
+                Id::Theme(IdTheme::ExplorerDirFg),
+                Box::new(components::ExplorerDirFg::new(
+                    theme.explorer_dir_fg.unwrap_or(Color::Reset)
+                )),
+                vec![]
+            )
+            .is_ok());
+        assert!(self
+            .app
+            .remount(
+                Id::Theme(IdTheme::ExplorerSymlinkFg),
+                Box::new(components::ExplorerSymlinkFg::new(
+                    theme.explorer_symlink_fg.unwrap_or(Color::Reset)
+                )),
+                vec![]
+            )
+            .is_ok());
+        assert!(self
+            .app
+            .remount(
+                Id::Theme(IdTheme::ExplorerExecFg),
+                Box::new(components::ExplorerExecFg::new(
+                    theme.explorer_exec_fg.unwrap_or(Color::Reset)
+                )),
+                vec![]
+            )
+            .is_ok());
+        assert!(self
+            .app
+            .remount(
+                Id::Theme(IdTheme::ExplorerArchiveFg),
+                Box::new(components::ExplorerArchiveFg::new(
+                    theme.explorer_archive_fg.unwrap_or(Color::Reset)
+                )),
+                vec![]
+            )
+            .is_ok());
+        assert!(self
+            .app
+            .remount(
+                Id::Theme(IdTheme::ExplorerImageFg),
+                Box::new(components::ExplorerImageFg::new(
+                    theme.explorer_image_fg.unwrap_or(Color::Reset)
+                )),
+                vec![]
+            )
+            .is_ok());
+        assert!(self
+            .app
+            .remount(
+                Id::Theme(IdTheme::ExplorerCodeFg),
+                Box::new(components::ExplorerCodeFg::new(
+                    theme.explorer_code_fg.unwrap_or(Color::Reset)
+                )),
+                vec![]
+            )
+            .is_ok());
     }
 }
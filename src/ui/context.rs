@@ -6,17 +6,23 @@
 use tuirealm::terminal::{CrosstermTerminalAdapter, TerminalBridge};
 
 use super::store::Store;
-use crate::filetransfer::{FileTransferParams, HostBridgeParams};
+use crate::filetransfer::{FileTransferParams, HostBridgeParams, ParkedSession};
 use crate::system::bookmarks_client::BookmarksClient;
 use crate::system::config_client::ConfigClient;
+use crate::system::health_check::HealthChecker;
 use crate::system::theme_provider::ThemeProvider;
 
 /// Context holds data structures shared by the activities
 pub struct Context {
     host_bridge_params: Option<HostBridgeParams>,
     remote_params: Option<FileTransferParams>,
+    /// Name of the bookmark the remote params have been loaded from
+    remote_bookmark: Option<String>,
     bookmarks_client: Option<BookmarksClient>,
+    /// Remote sessions kept connected while back at the authentication form
+    parked_sessions: Vec<ParkedSession>,
     config_client: ConfigClient,
+    health_checker: HealthChecker,
     pub(crate) store: Store,
     pub(crate) terminal: TerminalBridge<CrosstermTerminalAdapter>,
     theme_provider: ThemeProvider,
@@ -36,9 +42,12 @@ impl Context {
 
         Context {
             bookmarks_client,
+            parked_sessions: Vec::new(),
             config_client,
+            health_checker: HealthChecker::default(),
             host_bridge_params: None,
             remote_params: None,
+            remote_bookmark: None,
             store: Store::init(),
             terminal,
             theme_provider,
@@ -52,6 +61,10 @@ impl Context {
         self.remote_params.as_ref()
     }
 
+    pub fn remote_bookmark(&self) -> Option<&str> {
+        self.remote_bookmark.as_deref()
+    }
+
     pub fn host_bridge_params(&self) -> Option<&HostBridgeParams> {
         self.host_bridge_params.as_ref()
     }
@@ -72,6 +85,14 @@ impl Context {
         &mut self.config_client
     }
 
+    pub fn health_checker(&self) -> &HealthChecker {
+        &self.health_checker
+    }
+
+    pub fn health_checker_mut(&mut self) -> &mut HealthChecker {
+        &mut self.health_checker
+    }
+
     pub(crate) fn store(&self) -> &Store {
         &self.store
     }
@@ -98,10 +119,49 @@ impl Context {
         self.remote_params = Some(params);
     }
 
+    pub fn set_remote_bookmark(&mut self, name: Option<String>) {
+        self.remote_bookmark = name;
+    }
+
     pub fn set_host_bridge_params(&mut self, params: HostBridgeParams) {
         self.host_bridge_params = Some(params);
     }
 
+    // -- parked sessions
+
+    /// Keep `session` connected, replacing the session parked on the same host, if any
+    pub fn park_session(&mut self, session: ParkedSession) {
+        info!("Parking the session on {}", session.host_name());
+        if let Some(index) = self
+            .parked_sessions
+            .iter()
+            .position(|x| x.is_session_of(session.params()))
+        {
+            self.parked_sessions.remove(index).close();
+        }
+        self.parked_sessions.push(session);
+    }
+
+    /// Take the session parked on the host of `params`, if it hasn't expired
+    pub fn take_parked_session(&mut self, params: &FileTransferParams) -> Option<ParkedSession> {
+        self.close_expired_sessions();
+        let index = self
+            .parked_sessions
+            .iter()
+            .position(|x| x.is_session_of(params))?;
+        Some(self.parked_sessions.remove(index))
+    }
+
+    /// Close the parked sessions which have been idle for longer than their timeout
+    pub fn close_expired_sessions(&mut self) {
+        let (expired, parked): (Vec<ParkedSession>, Vec<ParkedSession>) =
+            std::mem::take(&mut self.parked_sessions)
+                .into_iter()
+                .partition(ParkedSession::is_expired);
+        self.parked_sessions = parked;
+        expired.into_iter().for_each(ParkedSession::close);
+    }
+
     // -- error
 
     /// Get error message and remove it from the context
@@ -112,6 +172,9 @@ impl Context {
 
 impl Drop for Context {
     fn drop(&mut self) {
+        self.parked_sessions
+            .drain(..)
+            .for_each(ParkedSession::close);
         if let Err(err) = self.terminal.restore() {
             error!("Could not restore terminal: {err}");
         }
@@ -0,0 +1,395 @@
+//! ## Archive
+//!
+//! `archive` is the module which creates and extracts tar and zip archives, either locally or with the commands
+//! run on a remote host
+
+use std::fmt;
+use std::fs::{self, File};
+use std::io;
+use std::path::{Path, PathBuf};
+
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+use super::shell;
+
+/// Format of an archive
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveFormat {
+    Tar,
+    TarGz,
+    Zip,
+}
+
+impl ArchiveFormat {
+    /// Extension of the archives created with this format
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Tar => "tar",
+            Self::TarGz => "tar.gz",
+            Self::Zip => "zip",
+        }
+    }
+
+    /// Get the format of the archive named `name`, by its extension. Returns `None` if it's not an archive
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.to_ascii_lowercase();
+        if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+            Some(Self::TarGz)
+        } else if name.ends_with(".tar") {
+            Some(Self::Tar)
+        } else if name.ends_with(".zip") {
+            Some(Self::Zip)
+        } else {
+            None
+        }
+    }
+
+    /// Get the name of the archive `name`, with the extension of the format, if missing
+    pub fn archive_name(&self, name: &str) -> String {
+        match Self::from_name(name) == Some(*self) {
+            true => name.to_string(),
+            false => format!("{name}.{}", self.extension()),
+        }
+    }
+
+    /// Get `name`, with the extension of this format replaced by the one of `other`
+    pub fn replace_extension(&self, name: &str, other: Self) -> String {
+        let suffix = format!(".{}", self.extension());
+        match name.strip_suffix(suffix.as_str()) {
+            Some(stem) => format!("{stem}.{}", other.extension()),
+            None => name.to_string(),
+        }
+    }
+
+    /// Command which creates the `archive` with the entries `names` of the directory `dir` on a remote host
+    pub fn create_command(&self, archive: &str, dir: &Path, names: &[String]) -> String {
+        // NOTE: entries are prefixed with `./`, so that names starting with `-` are not taken as options
+        let names: Vec<String> = names
+            .iter()
+            .map(|x| shell::single_quote(&format!("./{x}")))
+            .collect();
+        let cmd = match self {
+            Self::Tar => "tar cf",
+            Self::TarGz => "tar czf",
+            Self::Zip => "zip -qry",
+        };
+        format!(
+            "cd {} && {cmd} {} {}",
+            shell::single_quote(&dir.to_string_lossy()),
+            shell::single_quote(archive),
+            names.join(" ")
+        )
+    }
+
+    /// Command which extracts the `archive` into the directory `dir` on a remote host
+    pub fn extract_command(&self, archive: &Path, dir: &Path) -> String {
+        let cmd = match self {
+            Self::Tar => "tar xf",
+            Self::TarGz => "tar xzf",
+            Self::Zip => "unzip -qo",
+        };
+        format!(
+            "cd {} && {cmd} {}",
+            shell::single_quote(&dir.to_string_lossy()),
+            shell::single_quote(&archive.to_string_lossy())
+        )
+    }
+}
+
+impl fmt::Display for ArchiveFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.extension())
+    }
+}
+
+/// Create the `archive` with the entries `names` of the directory `dir`, recursively.
+/// Symbolic links are archived as links. Returns the amount of entries archived
+pub fn create_archive(
+    format: ArchiveFormat,
+    archive: &Path,
+    dir: &Path,
+    names: &[String],
+) -> io::Result<usize> {
+    let file = File::create(archive)?;
+    let result = match format {
+        ArchiveFormat::Tar => {
+            create_tar(tar::Builder::new(file), dir, names).and_then(FinishArchive::finish)
+        }
+        ArchiveFormat::TarGz => create_tar(
+            tar::Builder::new(GzEncoder::new(file, Compression::default())),
+            dir,
+            names,
+        )
+        .and_then(FinishArchive::finish),
+        ArchiveFormat::Zip => create_zip(file, dir, names).and_then(FinishArchive::finish),
+    };
+    // don't leave a broken archive behind
+    if let Err(err) = result {
+        let _ = fs::remove_file(archive);
+        return Err(err);
+    }
+    Ok(count_entries(dir, names))
+}
+
+/// Extract the `archive` into the directory `dest`.
+/// Entries which would be extracted outside of `dest` are skipped
+pub fn extract_archive(format: ArchiveFormat, archive: &Path, dest: &Path) -> io::Result<()> {
+    let file = File::open(archive)?;
+    match format {
+        ArchiveFormat::Tar => tar::Archive::new(file).unpack(dest),
+        ArchiveFormat::TarGz => tar::Archive::new(GzDecoder::new(file)).unpack(dest),
+        ArchiveFormat::Zip => ZipArchive::new(file)
+            .and_then(|mut x| x.extract(dest))
+            .map_err(io::Error::other),
+    }
+}
+
+/// Finish writing the archive, flushing the writer
+trait FinishArchive {
+    fn finish(self) -> io::Result<()>;
+}
+
+impl FinishArchive for tar::Builder<File> {
+    fn finish(self) -> io::Result<()> {
+        self.into_inner().map(|_| ())
+    }
+}
+
+impl FinishArchive for tar::Builder<GzEncoder<File>> {
+    fn finish(self) -> io::Result<()> {
+        self.into_inner()?.finish().map(|_| ())
+    }
+}
+
+impl FinishArchive for ZipWriter<File> {
+    fn finish(self) -> io::Result<()> {
+        ZipWriter::finish(self)
+            .map(|_| ())
+            .map_err(io::Error::other)
+    }
+}
+
+fn create_tar<W: io::Write>(
+    mut builder: tar::Builder<W>,
+    dir: &Path,
+    names: &[String],
+) -> io::Result<tar::Builder<W>> {
+    builder.follow_symlinks(false);
+    for name in names {
+        let path = dir.join(name);
+        match path.symlink_metadata()?.is_dir() {
+            true => builder.append_dir_all(name, path)?,
+            false => builder.append_path_with_name(path, name)?,
+        }
+    }
+    Ok(builder)
+}
+
+fn create_zip(file: File, dir: &Path, names: &[String]) -> io::Result<ZipWriter<File>> {
+    let mut writer = ZipWriter::new(file);
+    let mut entries: Vec<PathBuf> = names.iter().map(PathBuf::from).rev().collect();
+    while let Some(relative) = entries.pop() {
+        let path = dir.join(&relative);
+        let metadata = path.symlink_metadata()?;
+        let name = zip_entry_name(&relative);
+        let options = zip_options(&metadata);
+        if metadata.is_symlink() {
+            let target = fs::read_link(&path)?;
+            writer
+                .add_symlink(name, target.to_string_lossy(), options)
+                .map_err(io::Error::other)?;
+        } else if metadata.is_dir() {
+            writer
+                .add_directory(name, options)
+                .map_err(io::Error::other)?;
+            let mut children: Vec<PathBuf> = fs::read_dir(&path)?
+                .map(|x| x.map(|x| relative.join(x.file_name())))
+                .collect::<io::Result<_>>()?;
+            children.sort();
+            entries.extend(children.into_iter().rev());
+        } else {
+            writer.start_file(name, options).map_err(io::Error::other)?;
+            io::copy(&mut File::open(&path)?, &mut writer)?;
+        }
+    }
+    Ok(writer)
+}
+
+/// Name of the entry at the `relative` path in a zip archive, which always uses `/` as separator
+fn zip_entry_name(relative: &Path) -> String {
+    relative
+        .components()
+        .map(|x| x.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+#[cfg(posix)]
+fn zip_options(metadata: &fs::Metadata) -> SimpleFileOptions {
+    use std::os::unix::fs::PermissionsExt;
+
+    SimpleFileOptions::default()
+        .compression_method(CompressionMethod::Deflated)
+        .unix_permissions(metadata.permissions().mode() & 0o7777)
+}
+
+#[cfg(win)]
+fn zip_options(_metadata: &fs::Metadata) -> SimpleFileOptions {
+    SimpleFileOptions::default().compression_method(CompressionMethod::Deflated)
+}
+
+/// Count the entries `names` of `dir`, with the entries of the directories, without following symbolic links
+fn count_entries(dir: &Path, names: &[String]) -> usize {
+    let mut count = 0;
+    let mut entries: Vec<PathBuf> = names.iter().map(|x| dir.join(x)).collect();
+    while let Some(path) = entries.pop() {
+        count += 1;
+        if path.symlink_metadata().is_ok_and(|x| x.is_dir()) {
+            if let Ok(dir) = fs::read_dir(&path) {
+                entries.extend(dir.flatten().map(|x| x.path()));
+            }
+        }
+    }
+    count
+}
+
+#[cfg(test)]
+mod test {
+
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn should_get_archive_format_from_name() {
+        assert_eq!(
+            ArchiveFormat::from_name("backup.tar.gz"),
+            Some(ArchiveFormat::TarGz)
+        );
+        assert_eq!(
+            ArchiveFormat::from_name("backup.TGZ"),
+            Some(ArchiveFormat::TarGz)
+        );
+        assert_eq!(
+            ArchiveFormat::from_name("backup.tar"),
+            Some(ArchiveFormat::Tar)
+        );
+        assert_eq!(
+            ArchiveFormat::from_name("backup.zip"),
+            Some(ArchiveFormat::Zip)
+        );
+        assert_eq!(ArchiveFormat::from_name("backup.gz"), None);
+        assert_eq!(ArchiveFormat::from_name("README.md"), None);
+    }
+
+    #[test]
+    fn should_make_archive_name() {
+        assert_eq!(ArchiveFormat::TarGz.archive_name("www"), "www.tar.gz");
+        assert_eq!(
+            ArchiveFormat::TarGz.archive_name("www.tar.gz"),
+            "www.tar.gz"
+        );
+        assert_eq!(
+            ArchiveFormat::Zip.archive_name("www.tar.gz"),
+            "www.tar.gz.zip"
+        );
+        assert_eq!(
+            ArchiveFormat::TarGz.replace_extension("www.tar.gz", ArchiveFormat::Zip),
+            "www.zip"
+        );
+        assert_eq!(
+            ArchiveFormat::TarGz.replace_extension("www", ArchiveFormat::Zip),
+            "www"
+        );
+    }
+
+    #[test]
+    fn should_make_remote_commands() {
+        assert_eq!(
+            ArchiveFormat::TarGz.create_command(
+                "www.tar.gz",
+                Path::new("/srv"),
+                &[String::from("www"), String::from("-rf")]
+            ),
+            "cd '/srv' && tar czf 'www.tar.gz' './www' './-rf'"
+        );
+        assert_eq!(
+            ArchiveFormat::Zip.create_command(
+                "it's.zip",
+                Path::new("/srv"),
+                &[String::from("www")]
+            ),
+            r"cd '/srv' && zip -qry 'it'\''s.zip' './www'"
+        );
+        assert_eq!(
+            ArchiveFormat::Tar.extract_command(Path::new("/srv/www.tar"), Path::new("/srv")),
+            "cd '/srv' && tar xf '/srv/www.tar'"
+        );
+        assert_eq!(
+            ArchiveFormat::Zip.extract_command(Path::new("/srv/www.zip"), Path::new("/srv")),
+            "cd '/srv' && unzip -qo '/srv/www.zip'"
+        );
+    }
+
+    #[test]
+    fn should_create_and_extract_archives() {
+        for format in [ArchiveFormat::Tar, ArchiveFormat::TarGz, ArchiveFormat::Zip] {
+            let tmpdir = TempDir::new().unwrap();
+            let src = tmpdir.path().join("src");
+            fs::create_dir_all(src.join("www/css")).unwrap();
+            fs::write(src.join("www/index.html"), "<html></html>").unwrap();
+            fs::write(src.join("www/css/main.css"), "body {}").unwrap();
+            fs::write(src.join("notes.txt"), "hello").unwrap();
+            let archive = tmpdir.path().join(format.archive_name("backup"));
+            assert_eq!(
+                create_archive(
+                    format,
+                    archive.as_path(),
+                    src.as_path(),
+                    &[String::from("www"), String::from("notes.txt")]
+                )
+                .unwrap(),
+                5
+            );
+            let dest = tmpdir.path().join("dest");
+            fs::create_dir(&dest).unwrap();
+            assert!(extract_archive(format, archive.as_path(), dest.as_path()).is_ok());
+            assert_eq!(
+                fs::read_to_string(dest.join("www/css/main.css")).unwrap(),
+                "body {}"
+            );
+            assert_eq!(
+                fs::read_to_string(dest.join("www/index.html")).unwrap(),
+                "<html></html>"
+            );
+            assert_eq!(fs::read_to_string(dest.join("notes.txt")).unwrap(), "hello");
+        }
+    }
+
+    #[test]
+    fn should_not_create_archive_of_missing_entries() {
+        let tmpdir = TempDir::new().unwrap();
+        let archive = tmpdir.path().join("backup.zip");
+        assert!(create_archive(
+            ArchiveFormat::Zip,
+            archive.as_path(),
+            tmpdir.path(),
+            &[String::from("missing")]
+        )
+        .is_err());
+        assert!(!archive.exists());
+    }
+
+    #[test]
+    fn should_fail_to_extract_invalid_archive() {
+        let tmpdir = TempDir::new().unwrap();
+        let archive = tmpdir.path().join("backup.zip");
+        fs::write(&archive, "not an archive").unwrap();
+        assert!(extract_archive(ArchiveFormat::Zip, archive.as_path(), tmpdir.path()).is_err());
+    }
+}
@@ -0,0 +1,150 @@
+//! ## Checksum
+//!
+//! `checksum` is the module which provides the hashers used to compute the checksum of files
+
+use std::fmt;
+use std::io::{self, Write};
+
+use sha2::{Digest, Sha256};
+
+/// Algorithm used to compute a file checksum
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChecksumAlgorithm {
+    Md5,
+    Sha256,
+}
+
+impl ChecksumAlgorithm {
+    /// Command which computes the checksum on a remote host
+    pub fn command(&self) -> &'static str {
+        match self {
+            Self::Md5 => "md5sum",
+            Self::Sha256 => "sha256sum",
+        }
+    }
+
+    /// Get the digest from the output of [`Self::command`] (e.g. `<digest>  <path>`).
+    /// Returns `None` if the output doesn't start with a valid digest
+    pub fn parse_command_output(&self, output: &str) -> Option<String> {
+        let digest = output.split_whitespace().next()?.trim_start_matches('\\');
+        (digest.len() == self.digest_len() && digest.chars().all(|c| c.is_ascii_hexdigit()))
+            .then(|| digest.to_ascii_lowercase())
+    }
+
+    /// Length of the hex digest
+    fn digest_len(&self) -> usize {
+        match self {
+            Self::Md5 => 32,
+            Self::Sha256 => 64,
+        }
+    }
+}
+
+impl fmt::Display for ChecksumAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Md5 => write!(f, "MD5"),
+            Self::Sha256 => write!(f, "SHA256"),
+        }
+    }
+}
+
+/// Hasher which computes the checksum of the data written into it
+pub enum Checksum {
+    Md5(md5::Context),
+    Sha256(Sha256),
+}
+
+impl Checksum {
+    /// Instantiate a new hasher for `algorithm`
+    pub fn new(algorithm: ChecksumAlgorithm) -> Self {
+        match algorithm {
+            ChecksumAlgorithm::Md5 => Self::Md5(md5::Context::new()),
+            ChecksumAlgorithm::Sha256 => Self::Sha256(Sha256::new()),
+        }
+    }
+
+    /// Hash `data`
+    pub fn update(&mut self, data: &[u8]) {
+        match self {
+            Self::Md5(ctx) => ctx.consume(data),
+            Self::Sha256(hasher) => hasher.update(data),
+        }
+    }
+
+    /// Get the hex digest of the data hashed so far
+    pub fn finalize(self) -> String {
+        match self {
+            Self::Md5(ctx) => format!("{:x}", ctx.compute()),
+            Self::Sha256(hasher) => format!("{:x}", hasher.finalize()),
+        }
+    }
+}
+
+impl Write for Checksum {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.update(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn should_compute_checksum() {
+        let mut md5 = Checksum::new(ChecksumAlgorithm::Md5);
+        md5.update(b"hello ");
+        md5.update(b"world\n");
+        assert_eq!(md5.finalize(), "6f5902ac237024bdd0c176cb93063dc4");
+        let mut sha256 = Checksum::new(ChecksumAlgorithm::Sha256);
+        sha256.write_all(b"hello world\n").unwrap();
+        assert_eq!(
+            sha256.finalize(),
+            "a948904f2f0f479b8f8197694b30184b0d2ed1c1cd2a1ec0fb85d299a192a447"
+        );
+        assert_eq!(
+            Checksum::new(ChecksumAlgorithm::Md5).finalize(),
+            "d41d8cd98f00b204e9800998ecf8427e"
+        );
+    }
+
+    #[test]
+    fn should_parse_command_output() {
+        assert_eq!(
+            ChecksumAlgorithm::Md5
+                .parse_command_output("6F5902AC237024BDD0C176CB93063DC4  /tmp/hello.txt\n")
+                .as_deref(),
+            Some("6f5902ac237024bdd0c176cb93063dc4")
+        );
+        assert_eq!(
+            ChecksumAlgorithm::Sha256
+                .parse_command_output(
+                    r"\a948904f2f0f479b8f8197694b30184b0d2ed1c1cd2a1ec0fb85d299a192a447  /tmp/a\nb"
+                )
+                .as_deref(),
+            Some("a948904f2f0f479b8f8197694b30184b0d2ed1c1cd2a1ec0fb85d299a192a447")
+        );
+        assert!(ChecksumAlgorithm::Md5.parse_command_output("").is_none());
+        assert!(ChecksumAlgorithm::Md5
+            .parse_command_output("sh: md5sum: command not found")
+            .is_none());
+        assert!(ChecksumAlgorithm::Sha256
+            .parse_command_output("6f5902ac237024bdd0c176cb93063dc4  /tmp/hello.txt")
+            .is_none());
+    }
+
+    #[test]
+    fn should_display_checksum_algorithm() {
+        assert_eq!(ChecksumAlgorithm::Md5.to_string(), "MD5");
+        assert_eq!(ChecksumAlgorithm::Sha256.command(), "sha256sum");
+    }
+}
@@ -0,0 +1,95 @@
+//! ## Clipboard
+//!
+//! `clipboard` is the module which provides utilities to access the system clipboard
+
+use std::path::PathBuf;
+
+/// Read text from the system clipboard
+pub fn read_text() -> Result<String, String> {
+    arboard::Clipboard::new()
+        .and_then(|mut clipboard| clipboard.get_text())
+        .map_err(|e| e.to_string())
+}
+
+/// Write text to the system clipboard
+pub fn write_text(text: &str) -> Result<(), String> {
+    arboard::Clipboard::new()
+        .and_then(|mut clipboard| clipboard.set_text(text))
+        .map_err(|e| e.to_string())
+}
+
+/// Get the path contained in `text`, if it looks like a path.
+/// Surrounding whitespaces, quotes and the `file://` scheme are removed.
+/// Only absolute paths and paths starting with `./` or `../` are accepted
+pub fn parse_path(text: &str) -> Option<PathBuf> {
+    let text = text.trim();
+    let text = ['"', '\'', '`']
+        .into_iter()
+        .find_map(|quote| {
+            text.strip_prefix(quote)
+                .and_then(|text| text.strip_suffix(quote))
+        })
+        .unwrap_or(text)
+        .trim();
+    let text = text.strip_prefix("file://").unwrap_or(text);
+    if text.is_empty() || text.contains("://") || text.chars().any(char::is_control) {
+        return None;
+    }
+    let path = PathBuf::from(text);
+    let looks_like_path = text.starts_with('/')
+        || text.starts_with("./")
+        || text.starts_with("../")
+        || path.is_absolute();
+    looks_like_path.then_some(path)
+}
+
+#[cfg(test)]
+mod test {
+
+    use std::path::Path;
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn should_parse_path_from_text() {
+        assert_eq!(
+            parse_path("/var/log/app/x.log").as_deref(),
+            Some(Path::new("/var/log/app/x.log"))
+        );
+        assert_eq!(
+            parse_path("  /var/log/app \n").as_deref(),
+            Some(Path::new("/var/log/app"))
+        );
+        assert_eq!(
+            parse_path("\"/home/omar/my documents\"").as_deref(),
+            Some(Path::new("/home/omar/my documents"))
+        );
+        assert_eq!(
+            parse_path("`/etc/hosts`").as_deref(),
+            Some(Path::new("/etc/hosts"))
+        );
+        assert_eq!(
+            parse_path("'../src/main.rs'").as_deref(),
+            Some(Path::new("../src/main.rs"))
+        );
+        assert_eq!(parse_path("./docs").as_deref(), Some(Path::new("./docs")));
+        assert_eq!(
+            parse_path("file:///tmp/a.txt").as_deref(),
+            Some(Path::new("/tmp/a.txt"))
+        );
+    }
+
+    #[test]
+    fn should_not_parse_path_from_text() {
+        assert!(parse_path("").is_none());
+        assert!(parse_path("   ").is_none());
+        assert!(parse_path("\"\"").is_none());
+        assert!(parse_path("check the logs").is_none());
+        assert!(parse_path("x.log").is_none());
+        assert!(parse_path("https://example.com/var/log").is_none());
+        assert!(parse_path("/var/log/a.log\n/var/log/b.log").is_none());
+        assert!(parse_path("/tmp/\u{0}").is_none());
+    }
+}
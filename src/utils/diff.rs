@@ -0,0 +1,271 @@
+//! ## Diff
+//!
+//! `diff` computes the unified diff between two versions of a text
+
+/// Lines of context around the changes of a hunk
+const CONTEXT_LINES: usize = 3;
+/// Maximum amount of cells of the table used to match the changed lines.
+/// Bigger changed blocks are reported as entirely replaced
+const MAX_TABLE_CELLS: usize = 4_000_000;
+
+/// A line of the diff between two texts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffLine<'a> {
+    /// Line in both texts
+    Common(&'a str),
+    /// Line of the old text only
+    Removed(&'a str),
+    /// Line of the new text only
+    Added(&'a str),
+}
+
+impl DiffLine<'_> {
+    fn is_change(&self) -> bool {
+        !matches!(self, Self::Common(_))
+    }
+}
+
+/// Unified diff between two texts
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnifiedDiff {
+    lines: Vec<String>,
+    added: usize,
+    removed: usize,
+}
+
+impl UnifiedDiff {
+    /// Compute the unified diff from `old` to `new`, whose headers report `old_name` and `new_name`
+    pub fn new(old: &str, new: &str, old_name: &str, new_name: &str) -> Self {
+        let old_lines: Vec<&str> = old.lines().collect();
+        let new_lines: Vec<&str> = new.lines().collect();
+        let diff = diff_lines(&old_lines, &new_lines);
+        let added = diff
+            .iter()
+            .filter(|x| matches!(x, DiffLine::Added(_)))
+            .count();
+        let removed = diff
+            .iter()
+            .filter(|x| matches!(x, DiffLine::Removed(_)))
+            .count();
+        let mut lines = Vec::new();
+        if added + removed > 0 {
+            lines.push(format!("--- {old_name}"));
+            lines.push(format!("+++ {new_name}"));
+            for hunk in hunks(&diff) {
+                Self::push_hunk(&mut lines, &diff, hunk);
+            }
+        }
+        Self {
+            lines,
+            added,
+            removed,
+        }
+    }
+
+    /// Returns whether the texts have the same lines
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+
+    /// Get the lines of the diff, headers included
+    pub fn lines(&self) -> &[String] {
+        &self.lines
+    }
+
+    /// Amount of lines added
+    pub fn added(&self) -> usize {
+        self.added
+    }
+
+    /// Amount of lines removed
+    pub fn removed(&self) -> usize {
+        self.removed
+    }
+
+    /// Write the hunk of `diff` at `range` to `lines`
+    fn push_hunk(lines: &mut Vec<String>, diff: &[DiffLine], range: (usize, usize)) {
+        let (start, end) = range;
+        let old_before = diff[..start]
+            .iter()
+            .filter(|x| !matches!(x, DiffLine::Added(_)))
+            .count();
+        let new_before = diff[..start]
+            .iter()
+            .filter(|x| !matches!(x, DiffLine::Removed(_)))
+            .count();
+        let hunk = &diff[start..end];
+        let old_len = hunk
+            .iter()
+            .filter(|x| !matches!(x, DiffLine::Added(_)))
+            .count();
+        let new_len = hunk
+            .iter()
+            .filter(|x| !matches!(x, DiffLine::Removed(_)))
+            .count();
+        // empty ranges start at the line before
+        lines.push(format!(
+            "@@ -{},{} +{},{} @@",
+            old_before + usize::from(old_len > 0),
+            old_len,
+            new_before + usize::from(new_len > 0),
+            new_len
+        ));
+        for line in hunk {
+            lines.push(match line {
+                DiffLine::Common(line) => format!(" {line}"),
+                DiffLine::Removed(line) => format!("-{line}"),
+                DiffLine::Added(line) => format!("+{line}"),
+            });
+        }
+    }
+}
+
+/// Diff the lines of `old` and `new`.
+/// The lines in common are matched with the longest common subsequence, once the common prefix and suffix are skipped
+pub fn diff_lines<'a>(old: &[&'a str], new: &[&'a str]) -> Vec<DiffLine<'a>> {
+    let prefix = old
+        .iter()
+        .zip(new.iter())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let old_changed = &old[prefix..old.len() - suffix];
+    let new_changed = &new[prefix..new.len() - suffix];
+    let mut diff: Vec<DiffLine> = old[..prefix].iter().map(|x| DiffLine::Common(x)).collect();
+    if (old_changed.len() + 1) * (new_changed.len() + 1) <= MAX_TABLE_CELLS {
+        diff.extend(lcs_diff(old_changed, new_changed));
+    } else {
+        diff.extend(old_changed.iter().map(|x| DiffLine::Removed(x)));
+        diff.extend(new_changed.iter().map(|x| DiffLine::Added(x)));
+    }
+    diff.extend(
+        old[old.len() - suffix..]
+            .iter()
+            .map(|x| DiffLine::Common(x)),
+    );
+    diff
+}
+
+/// Diff `old` and `new` through the table of the longest common subsequences of their suffixes
+fn lcs_diff<'a>(old: &[&'a str], new: &[&'a str]) -> Vec<DiffLine<'a>> {
+    let width = new.len() + 1;
+    let mut table = vec![0u32; (old.len() + 1) * width];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            table[i * width + j] = match old[i] == new[j] {
+                true => table[(i + 1) * width + j + 1] + 1,
+                false => table[(i + 1) * width + j].max(table[i * width + j + 1]),
+            };
+        }
+    }
+    let mut diff = Vec::with_capacity(old.len() + new.len());
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() && j < new.len() {
+        if old[i] == new[j] {
+            diff.push(DiffLine::Common(old[i]));
+            i += 1;
+            j += 1;
+        } else if table[(i + 1) * width + j] >= table[i * width + j + 1] {
+            diff.push(DiffLine::Removed(old[i]));
+            i += 1;
+        } else {
+            diff.push(DiffLine::Added(new[j]));
+            j += 1;
+        }
+    }
+    diff.extend(old[i..].iter().map(|x| DiffLine::Removed(x)));
+    diff.extend(new[j..].iter().map(|x| DiffLine::Added(x)));
+    diff
+}
+
+/// Get the ranges of `diff` making the hunks: the changes, with their context lines.
+/// Changes whose contexts overlap are joined in the same hunk
+fn hunks(diff: &[DiffLine]) -> Vec<(usize, usize)> {
+    let mut hunks: Vec<(usize, usize)> = Vec::new();
+    for (index, _) in diff.iter().enumerate().filter(|(_, x)| x.is_change()) {
+        let start = index.saturating_sub(CONTEXT_LINES);
+        let end = (index + 1 + CONTEXT_LINES).min(diff.len());
+        match hunks.last_mut() {
+            Some(last) if start <= last.1 => last.1 = end,
+            _ => hunks.push((start, end)),
+        }
+    }
+    hunks
+}
+
+#[cfg(test)]
+mod test {
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn should_diff_lines() {
+        let old = ["a", "b", "c", "d"];
+        let new = ["a", "c", "e", "d"];
+        assert_eq!(
+            diff_lines(&old, &new),
+            vec![
+                DiffLine::Common("a"),
+                DiffLine::Removed("b"),
+                DiffLine::Common("c"),
+                DiffLine::Added("e"),
+                DiffLine::Common("d"),
+            ]
+        );
+        assert_eq!(diff_lines(&old, &old).iter().all(|x| !x.is_change()), true);
+        assert_eq!(diff_lines(&[], &["a"]), vec![DiffLine::Added("a")]);
+    }
+
+    #[test]
+    fn should_make_unified_diff() {
+        let old = "one\ntwo\nthree\nfour\nfive\nsix\nseven\neight\nnine\nten\n";
+        let new = "one\n2\nthree\nfour\nfive\nsix\nseven\neight\nnine\nten\neleven\n";
+        let diff = UnifiedDiff::new(old, new, "a/numbers.txt", "b/numbers.txt");
+        assert_eq!(diff.added(), 2);
+        assert_eq!(diff.removed(), 1);
+        assert_eq!(
+            diff.lines(),
+            &[
+                "--- a/numbers.txt",
+                "+++ b/numbers.txt",
+                "@@ -1,5 +1,5 @@",
+                " one",
+                "-two",
+                "+2",
+                " three",
+                " four",
+                " five",
+                "@@ -8,3 +8,4 @@",
+                " eight",
+                " nine",
+                " ten",
+                "+eleven",
+            ]
+        );
+    }
+
+    #[test]
+    fn should_report_truncated_text() {
+        let old = "[server]\nhost = \"localhost\"\nport = 8080\n";
+        let diff = UnifiedDiff::new(old, "", "a/config.toml", "b/config.toml");
+        assert_eq!(diff.added(), 0);
+        assert_eq!(diff.removed(), 3);
+        assert_eq!(diff.lines()[2].as_str(), "@@ -1,3 +0,0 @@");
+        assert_eq!(diff.lines()[3].as_str(), "-[server]");
+    }
+
+    #[test]
+    fn should_make_empty_diff_for_same_lines() {
+        let diff = UnifiedDiff::new("a\nb\n", "a\nb", "a", "b");
+        assert!(diff.is_empty());
+        assert_eq!(diff.added(), 0);
+        assert_eq!(diff.removed(), 0);
+    }
+}
@@ -8,7 +8,7 @@ use std::time::{Duration, SystemTime};
 use chrono::prelude::*;
 use remotefs::fs::UnixPexClass;
 use tuirealm::ratatui::style::Color;
-use unicode_width::UnicodeWidthStr;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
 /// Convert permissions bytes of permissions value into ls notation (e.g. rwx,-wx,--x)
 pub fn fmt_pex(pex: UnixPexClass) -> String {
@@ -80,6 +80,50 @@ pub fn fmt_path_elide_ex(p: &Path, width: usize, extra_len: usize) -> String {
     }
 }
 
+/// Elide a file name if wider than width, putting the ellipsis in the middle of the name.
+/// The extension is preserved (e.g. `verylongna…me.tar.gz`), unless it takes more than half of the width
+pub fn fmt_name_elide(name: &str, width: usize) -> String {
+    if name.width() <= width || width == 0 {
+        return name.to_string();
+    }
+    // Keep one column for the ellipsis
+    let budget = width - 1;
+    // Try with the full extension (e.g. `.tar.gz`), then with the last one; a leading dot is not an extension
+    let first_len = name.chars().next().map(char::len_utf8).unwrap_or_default();
+    let extension = [name[first_len..].find('.'), name[first_len..].rfind('.')]
+        .into_iter()
+        .flatten()
+        .map(|idx| &name[idx + first_len..])
+        .find(|ext| ext.width() <= budget / 2)
+        .unwrap_or_default();
+    let stem = &name[..name.len() - extension.len()];
+    let stem_budget = budget - extension.width();
+    let tail_budget = stem_budget / 5;
+    let tail = take_width(stem.chars().rev(), tail_budget);
+    let head = take_width(stem.chars(), stem_budget - tail.width());
+    format!(
+        "{head}…{}{extension}",
+        tail.chars().rev().collect::<String>()
+    )
+}
+
+/// Pad `s` with spaces to `width` columns, measuring its display width,
+/// so that the columns following wide chars, such as CJK chars and emoji, stay aligned
+pub fn fmt_pad(s: &str, width: usize) -> String {
+    format!("{s}{}", " ".repeat(width.saturating_sub(s.width())))
+}
+
+/// Take chars from `chars` as long as their width fits in `width`
+fn take_width(chars: impl Iterator<Item = char>, width: usize) -> String {
+    let mut taken = 0;
+    chars
+        .take_while(|c| {
+            taken += c.width().unwrap_or(0);
+            taken <= width
+        })
+        .collect()
+}
+
 /// Format color
 pub fn fmt_color(color: &Color) -> String {
     match color {
@@ -100,7 +144,7 @@ pub fn fmt_color(color: &Color) -> String {
         Color::Reset => "Default".to_string(),
         Color::White => "White".to_string(),
         Color::Yellow => "Yellow".to_string(),
-        Color::Indexed(_) => "Default".to_string(),
+        Color::Indexed(index) => index.to_string(),
         // -- css colors
         Color::Rgb(240, 248, 255) => "aliceblue".to_string(),
         Color::Rgb(250, 235, 215) => "antiquewhite".to_string(),
@@ -319,6 +363,48 @@ mod tests {
         assert_eq!(fmt_path_elide(p, 16), String::from("/develop/…/foo/bar"));
     }
 
+    #[test]
+    fn should_fmt_name_elide() {
+        // Under max size
+        assert_eq!(fmt_name_elide("bar.txt", 16).as_str(), "bar.txt");
+        // Extension is preserved
+        assert_eq!(
+            fmt_name_elide("verylongname-2024-backup.tar.gz", 20).as_str(),
+            "verylongna…up.tar.gz"
+        );
+        assert_eq!(
+            fmt_name_elide("verylongname-2024-backup.tar.gz", 20).width(),
+            20
+        );
+        // Extension too long: only the last one is kept
+        assert_eq!(
+            fmt_name_elide("christian.visintin.txt", 12).as_str(),
+            "christ…n.txt"
+        );
+        // No extension
+        assert_eq!(fmt_name_elide("christian-visintin", 8).as_str(), "christ…n");
+        // Hidden files have no extension
+        assert_eq!(
+            fmt_name_elide(".christian-visintin", 8).as_str(),
+            ".chris…n"
+        );
+        // Wide chars
+        assert_eq!(
+            fmt_name_elide("喵喵喵喵喵喵喵喵喵喵", 9).as_str(),
+            "喵喵喵喵…"
+        );
+        assert!(fmt_name_elide("喵喵喵喵喵喵喵喵喵喵.txt", 12).width() <= 12);
+    }
+
+    #[test]
+    fn should_fmt_pad() {
+        assert_eq!(fmt_pad("bar.txt", 10).as_str(), "bar.txt   ");
+        assert_eq!(fmt_pad("喵喵.txt", 10).as_str(), "喵喵.txt  ");
+        assert_eq!(fmt_pad("🦀.rs", 10).width(), 10);
+        // wider strings are not truncated
+        assert_eq!(fmt_pad("喵喵喵喵", 6).as_str(), "喵喵喵喵");
+    }
+
     #[test]
     fn test_utils_fmt_color() {
         assert_eq!(fmt_color(&Color::Black).as_str(), "Black");
@@ -338,7 +424,7 @@ mod tests {
         assert_eq!(fmt_color(&Color::Reset).as_str(), "Default");
         assert_eq!(fmt_color(&Color::White).as_str(), "White");
         assert_eq!(fmt_color(&Color::Yellow).as_str(), "Yellow");
-        assert_eq!(fmt_color(&Color::Indexed(16)).as_str(), "Default");
+        assert_eq!(fmt_color(&Color::Indexed(16)).as_str(), "16");
         assert_eq!(fmt_color(&Color::Rgb(204, 170, 22)).as_str(), "#ccaa16");
         assert_eq!(fmt_color(&Color::Rgb(204, 170, 0)).as_str(), "#ccaa00");
         // css colors
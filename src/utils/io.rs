@@ -0,0 +1,163 @@
+//! ## Io
+//!
+//! `io` is the module which provides the stream copy shared by all the file transfers
+
+use std::io::{self, Read, Write};
+
+use thiserror::Error;
+
+/// Error of [`copy_with_progress`]
+#[derive(Debug, Error)]
+pub enum CopyError {
+    #[error("read error: {0}")]
+    Read(io::Error),
+    #[error("write error: {0}")]
+    Write(io::Error),
+    #[error("copy aborted")]
+    Aborted,
+}
+
+/// Copy `reader` into `writer` until EOF, using a buffer of `buffer_size` bytes.
+/// After each chunk, `on_chunk` is called with the amount of bytes copied so far; if it returns `false`, the copy
+/// is aborted.
+/// Returns the amount of bytes copied
+pub fn copy_with_progress<R, W, F>(
+    reader: &mut R,
+    writer: &mut W,
+    buffer_size: usize,
+    mut on_chunk: F,
+) -> Result<u64, CopyError>
+where
+    R: Read + ?Sized,
+    W: Write + ?Sized,
+    F: FnMut(u64) -> bool,
+{
+    let mut buffer = vec![0; buffer_size.max(1)];
+    let mut copied: u64 = 0;
+    loop {
+        let bytes = match reader.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(bytes) => bytes,
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+            Err(err) => return Err(CopyError::Read(err)),
+        };
+        writer
+            .write_all(&buffer[..bytes])
+            .map_err(CopyError::Write)?;
+        copied += bytes as u64;
+        if !on_chunk(copied) {
+            return Err(CopyError::Aborted);
+        }
+    }
+    writer.flush().map_err(CopyError::Write)?;
+    Ok(copied)
+}
+
+#[cfg(test)]
+mod test {
+
+    use std::io::Cursor;
+    use std::net::{TcpListener, TcpStream};
+    use std::thread;
+    use std::time::Instant;
+
+    use bytesize::ByteSize;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn should_copy_with_progress() {
+        let data: Vec<u8> = (0..10_000).map(|x| (x % 256) as u8).collect();
+        let mut writer = Vec::new();
+        let mut chunks = Vec::new();
+        assert_eq!(
+            copy_with_progress(&mut Cursor::new(&data), &mut writer, 4096, |x| {
+                chunks.push(x);
+                true
+            })
+            .unwrap(),
+            10_000
+        );
+        assert_eq!(writer, data);
+        assert_eq!(chunks, vec![4096, 8192, 10_000]);
+    }
+
+    #[test]
+    fn should_abort_copy_between_chunks() {
+        let data = vec![0xcd; 10_000];
+        let mut writer = Vec::new();
+        assert!(matches!(
+            copy_with_progress(&mut data.as_slice(), &mut writer, 1024, |x| x < 2048),
+            Err(CopyError::Aborted)
+        ));
+        assert_eq!(writer.len(), 2048);
+    }
+
+    #[test]
+    fn should_report_read_and_write_errors() {
+        struct Broken;
+
+        impl Read for Broken {
+            fn read(&mut self, _buf: &mut [u8]) -> io::Result<usize> {
+                Err(io::Error::other("broken"))
+            }
+        }
+
+        impl Write for Broken {
+            fn write(&mut self, _buf: &[u8]) -> io::Result<usize> {
+                Err(io::Error::other("broken"))
+            }
+
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+
+        assert!(matches!(
+            copy_with_progress(&mut Broken, &mut io::sink(), 1024, |_| true),
+            Err(CopyError::Read(_))
+        ));
+        assert!(matches!(
+            copy_with_progress(&mut [0; 16].as_slice(), &mut Broken, 1024, |_| true),
+            Err(CopyError::Write(_))
+        ));
+    }
+
+    /// Benchmark of the buffer sizes over a loopback socket, which the default `transfer_buffer_kb` is based on.
+    /// Run with `cargo test --release bench_copy_buffer_sizes -- --ignored --nocapture`
+    #[test]
+    #[ignore]
+    fn bench_copy_buffer_sizes() {
+        const SIZE: usize = 512 * 1024 * 1024;
+        const ROUNDS: u32 = 5;
+        for buffer_kb in [64, 256, 1024] {
+            let mut elapsed = Vec::with_capacity(ROUNDS as usize);
+            for _ in 0..ROUNDS {
+                let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+                let addr = listener.local_addr().unwrap();
+                let sender = thread::spawn(move || {
+                    let mut stream = TcpStream::connect(addr).unwrap();
+                    let mut source = io::repeat(0xcd).take(SIZE as u64);
+                    copy_with_progress(&mut source, &mut stream, buffer_kb * 1024, |_| true)
+                        .unwrap();
+                });
+                let (mut stream, _) = listener.accept().unwrap();
+                let started = Instant::now();
+                let copied =
+                    copy_with_progress(&mut stream, &mut io::sink(), buffer_kb * 1024, |_| true)
+                        .unwrap();
+                elapsed.push(started.elapsed());
+                sender.join().unwrap();
+                assert_eq!(copied, SIZE as u64);
+            }
+            elapsed.sort();
+            let median = elapsed[elapsed.len() / 2];
+            println!(
+                "buffer {buffer_kb:>5} KiB: median {:>8.2} ms ({}/s)",
+                median.as_secs_f64() * 1000.0,
+                ByteSize((SIZE as f64 / median.as_secs_f64()) as u64)
+            );
+        }
+    }
+}
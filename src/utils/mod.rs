@@ -3,14 +3,19 @@
 //! `utils` is the module which provides utilities of different kind
 
 // modules
+pub mod archive;
+pub mod checksum;
+pub mod clipboard;
 pub mod crypto;
+pub mod diff;
 pub mod file;
 pub mod fmt;
+pub mod io;
 pub mod parser;
 pub mod path;
 pub mod random;
+pub mod shell;
 pub mod ssh;
-pub mod string;
 pub mod tty;
 pub mod ui;
 
@@ -132,7 +132,7 @@ static BYTESIZE_REGEX: Lazy<Regex> = lazy_regex!(r"(:?([0-9])+)( )*(:?[KMGTP])?B
 ///
 /// For s3:
 ///
-/// s3://<bucket-name>@<region>[:profile][:/wrkdir]
+/// s3://<bucket-name>@<region>[:profile][:/wrkdir][?endpoint=<url>&new_path_style=<bool>]
 ///
 /// For SMB:
 ///
@@ -287,6 +287,11 @@ fn parse_webdav_remote_opt(s: &str, prefix: &str) -> Result<FileTransferParams,
 
 /// Parse remote options for s3 protocol
 fn parse_s3_remote_opt(s: &str) -> Result<FileTransferParams, String> {
+    // the endpoint may contain colons, so the query is split before matching the address
+    let (s, query) = match s.split_once('?') {
+        Some((s, query)) => (s, Some(query)),
+        None => (s, None),
+    };
     match REMOTE_S3_OPT_REGEX.captures(s) {
         Some(groups) => {
             let bucket: String = groups
@@ -300,16 +305,39 @@ fn parse_s3_remote_opt(s: &str) -> Result<FileTransferParams, String> {
             let profile: Option<String> = groups.get(3).map(|x| x.as_str().to_string());
             let remote_path: Option<PathBuf> =
                 groups.get(4).map(|group| PathBuf::from(group.as_str()));
-            Ok(FileTransferParams::new(
-                FileTransferProtocol::AwsS3,
-                ProtocolParams::AwsS3(AwsS3Params::new(bucket, Some(region), profile)),
+            let mut params = AwsS3Params::new(bucket, Some(region), profile);
+            if let Some(query) = query {
+                params = parse_s3_query(params, query)?;
+            }
+            Ok(
+                FileTransferParams::new(FileTransferProtocol::AwsS3, ProtocolParams::AwsS3(params))
+                    .remote_path(remote_path),
             )
-            .remote_path(remote_path))
         }
         None => Err(String::from("Bad remote host syntax!")),
     }
 }
 
+/// Apply the options of the `query` of a s3 address, such as `endpoint=http://localhost:9000&new_path_style=true`,
+/// to `params`
+fn parse_s3_query(mut params: AwsS3Params, query: &str) -> Result<AwsS3Params, String> {
+    for option in query.split('&').filter(|x| !x.is_empty()) {
+        let (key, value) = option
+            .split_once('=')
+            .ok_or_else(|| format!("Missing value for s3 option \"{option}\""))?;
+        match key {
+            "endpoint" => params = params.endpoint(Some(value)),
+            "new_path_style" => {
+                params = params.new_path_style(value.parse::<bool>().map_err(|_| {
+                    format!("Bad value for new_path_style \"{value}\": expected true or false")
+                })?)
+            }
+            key => return Err(format!("Unknown s3 option \"{key}\"")),
+        }
+    }
+    Ok(params)
+}
+
 fn parse_kube_remote_opt(s: &str) -> Result<FileTransferParams, String> {
     match REMOTE_KUBE_OPT_REGEX.captures(s) {
         Some(groups) => {
@@ -354,10 +382,11 @@ fn parse_smb_remote_opts(s: &str) -> Result<FileTransferParams, String> {
                 },
                 None => 445,
             };
-            let share = match groups.get(4) {
-                Some(group) => group.as_str().to_string(),
-                None => return Err(String::from("Missing address")),
-            };
+            // without a share, the shares of the server are browsed
+            let share = groups
+                .get(4)
+                .map(|group| group.as_str().to_string())
+                .unwrap_or_default();
             let remote_path: Option<PathBuf> =
                 groups.get(5).map(|group| PathBuf::from(group.as_str()));
 
@@ -434,8 +463,13 @@ pub fn parse_semver(haystack: &str) -> Option<String> {
 ///     - rgb(255, 64, 32)
 ///     - rgb(255,64,32)
 ///     - 255, 64, 32
+/// 4. 256 colors palette index:
+///     - 208
 pub fn parse_color(color: &str) -> Option<Color> {
-    tuirealm_parser::parse_color(color)
+    match color.trim().parse::<u8>() {
+        Ok(index) => Some(Color::Indexed(index)),
+        Err(_) => tuirealm_parser::parse_color(color),
+    }
 }
 
 #[derive(Debug, PartialEq)]
@@ -487,6 +521,15 @@ pub fn parse_bytesize<S: AsRef<str>>(bytes: S) -> Option<ByteSize> {
     }
 }
 
+/// Parse an octal file mode (e.g. `0664` or `664`) into its permission bits
+pub fn parse_octal_mode<S: AsRef<str>>(mode: S) -> Option<u32> {
+    let mode = mode.as_ref().trim();
+    let mode = mode.strip_prefix("0o").unwrap_or(mode);
+    u32::from_str_radix(mode, 8)
+        .ok()
+        .filter(|mode| *mode <= 0o7777)
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -743,6 +786,43 @@ mod tests {
         assert_eq!(params.region.as_deref().unwrap(), "eu-central-1");
     }
 
+    #[test]
+    fn parse_aws_s3_opt_with_endpoint() {
+        let result: FileTransferParams = parse_remote_opt(&String::from(
+            "s3://mybucket@us-east-1:default:/foobar?endpoint=http://localhost:9000&new_path_style=true",
+        ))
+        .ok()
+        .unwrap();
+        let params = result.params.s3_params().unwrap();
+        assert_eq!(result.protocol, FileTransferProtocol::AwsS3);
+        assert_eq!(result.remote_path, Some(PathBuf::from("/foobar")));
+        assert_eq!(params.bucket_name.as_str(), "mybucket");
+        assert_eq!(params.region.as_deref().unwrap(), "us-east-1");
+        assert_eq!(params.profile.as_deref(), Some("default"));
+        assert_eq!(params.endpoint.as_deref(), Some("http://localhost:9000"));
+        assert_eq!(params.new_path_style, true);
+        // endpoint only
+        let result: FileTransferParams = parse_remote_opt(&String::from(
+            "s3://mybucket@us-east-1?endpoint=minio.local:9000",
+        ))
+        .ok()
+        .unwrap();
+        let params = result.params.s3_params().unwrap();
+        assert_eq!(result.remote_path, None);
+        assert_eq!(params.profile, None);
+        assert_eq!(
+            params.endpoint_url().as_deref(),
+            Some("https://minio.local:9000")
+        );
+        assert_eq!(params.new_path_style, false);
+        // -- bad options
+        assert!(
+            parse_remote_opt(&String::from("s3://mybucket@us-east-1?new_path_style=yes")).is_err()
+        );
+        assert!(parse_remote_opt(&String::from("s3://mybucket@us-east-1?endpoint")).is_err());
+        assert!(parse_remote_opt(&String::from("s3://mybucket@us-east-1?path=/foobar")).is_err());
+    }
+
     #[test]
     fn should_parse_kube_address() {
         let result = parse_remote_opt("kube://my-namespace@http://localhost:1234$/tmp")
@@ -796,6 +876,18 @@ mod tests {
         );
     }
 
+    #[test]
+    #[cfg(smb_unix)]
+    fn should_parse_smb_address_without_share() {
+        let result = parse_remote_opt("smb://omar@myserver:4445").ok().unwrap();
+        let params = result.params.smb_params().unwrap();
+
+        assert_eq!(params.address.as_str(), "myserver");
+        assert_eq!(params.port, 4445);
+        assert!(params.share.is_empty());
+        assert!(result.remote_path.is_none());
+    }
+
     #[test]
     #[cfg(smb_windows)]
     fn should_parse_smb_address() {
@@ -851,6 +943,9 @@ mod tests {
             Color::Rgb(255, 64, 32)
         );
         // bad
+        assert_eq!(parse_color("208"), Some(Color::Indexed(208)));
+        assert_eq!(parse_color("0"), Some(Color::Indexed(0)));
+        assert!(parse_color("256").is_none());
         assert!(parse_color("redd").is_none());
     }
 
@@ -877,4 +972,16 @@ mod tests {
         assert!(parse_bytesize("1 GBaaaaa").is_none());
         assert!(parse_bytesize("1MBaaaaa").is_none());
     }
+
+    #[test]
+    fn should_parse_octal_mode() {
+        assert_eq!(parse_octal_mode("0664"), Some(0o664));
+        assert_eq!(parse_octal_mode("775"), Some(0o775));
+        assert_eq!(parse_octal_mode(" 0o2775 "), Some(0o2775));
+        assert!(parse_octal_mode("").is_none());
+        assert!(parse_octal_mode("0689").is_none());
+        assert_eq!(parse_octal_mode("07777"), Some(0o7777));
+        assert!(parse_octal_mode("17777").is_none());
+        assert!(parse_octal_mode("rw-r--r--").is_none());
+    }
 }
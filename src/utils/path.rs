@@ -82,6 +82,31 @@ pub fn is_child_of<P: AsRef<Path>>(p: P, ancestor: P) -> bool {
     p.as_ref().ancestors().any(|x| x == ancestor.as_ref())
 }
 
+/// Lexically normalize `path`, resolving the `.` and `..` components without accessing the file system.
+/// For example:
+///
+/// ```rust
+/// assert_eq!(normalize(&Path::new("/home/omar/./docs/../readme.txt")).as_path(), Path::new("/home/omar/readme.txt"));
+/// ```
+pub fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => match normalized.components().next_back() {
+                Some(Component::Normal(_)) => {
+                    normalized.pop();
+                }
+                // `..` on root is root itself
+                Some(Component::RootDir | Component::Prefix(_)) => {}
+                _ => normalized.push(component),
+            },
+            component => normalized.push(component),
+        }
+    }
+    normalized
+}
+
 #[cfg(test)]
 mod test {
 
@@ -140,4 +165,17 @@ mod test {
             Path::new("/tmp"),
         ));
     }
+
+    #[test]
+    fn should_normalize_path() {
+        assert_eq!(
+            normalize(Path::new("/home/omar/./docs/../readme.txt")).as_path(),
+            Path::new("/home/omar/readme.txt")
+        );
+        assert_eq!(
+            normalize(Path::new("/tmp/loop/.")).as_path(),
+            Path::new("/tmp/loop")
+        );
+        assert_eq!(normalize(Path::new("/tmp/../..")).as_path(), Path::new("/"));
+    }
 }
@@ -0,0 +1,116 @@
+//! # Shell
+//!
+//! Shell related utilities
+
+/// Shell used by default to run commands on the remote host
+pub const DEFAULT_REMOTE_SHELL: &str = "/bin/sh -c";
+
+/// Quote `s` as a single word for a POSIX shell.
+/// The string is wrapped into single quotes, so nothing is expanded; single quotes are escaped as `'\''`
+pub fn single_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', r"'\''"))
+}
+
+/// Wrap `cmd` so that it is run by `shell` (e.g. `/bin/sh -c`).
+/// The command is passed as a single quoted argument, so expansions are performed by `shell` on the remote host
+pub fn wrap_command(shell: &str, cmd: &str) -> String {
+    let shell = shell.trim();
+    match shell.is_empty() {
+        true => wrap_command(DEFAULT_REMOTE_SHELL, cmd),
+        false => format!("{shell} {}", single_quote(cmd)),
+    }
+}
+
+#[cfg(test)]
+mod test {
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn should_single_quote_plain_strings() {
+        assert_eq!(single_quote(""), "''");
+        assert_eq!(single_quote("ls"), "'ls'");
+        assert_eq!(single_quote("ls -l /tmp"), "'ls -l /tmp'");
+        assert_eq!(single_quote("россия"), "'россия'");
+    }
+
+    #[test]
+    fn should_single_quote_strings_with_single_quotes() {
+        assert_eq!(single_quote("'"), r"''\'''");
+        assert_eq!(single_quote("''"), r"''\'''\'''");
+        assert_eq!(single_quote("it's"), r"'it'\''s'");
+        assert_eq!(single_quote("echo 'a b'"), r"'echo '\''a b'\'''");
+    }
+
+    #[test]
+    fn should_not_escape_other_special_characters() {
+        assert_eq!(single_quote(r#"echo "hi""#), r#"'echo "hi"'"#);
+        assert_eq!(single_quote("echo $HOME"), "'echo $HOME'");
+        assert_eq!(single_quote("echo `id` $(id)"), "'echo `id` $(id)'");
+        assert_eq!(single_quote(r"a\b\\c"), r"'a\b\\c'");
+        assert_eq!(single_quote("a; b && c | d > e"), "'a; b && c | d > e'");
+        assert_eq!(single_quote("*.txt ?[a-z]"), "'*.txt ?[a-z]'");
+        assert_eq!(single_quote("line1\nline2\ttab"), "'line1\nline2\ttab'");
+        assert_eq!(single_quote("!#~%"), "'!#~%'");
+    }
+
+    #[test]
+    fn should_wrap_command() {
+        assert_eq!(
+            wrap_command("/bin/sh -c", "echo $HOME"),
+            "/bin/sh -c 'echo $HOME'"
+        );
+        assert_eq!(
+            wrap_command("  /bin/bash -lc ", "echo 'hi'"),
+            r"/bin/bash -lc 'echo '\''hi'\'''"
+        );
+        assert_eq!(wrap_command("", "ls"), "/bin/sh -c 'ls'");
+        assert_eq!(wrap_command("   ", "ls"), "/bin/sh -c 'ls'");
+    }
+
+    #[test]
+    #[cfg(posix)]
+    fn should_roundtrip_quoted_strings_through_shell() {
+        use std::process::Command;
+
+        for s in [
+            "",
+            "plain",
+            "with spaces  ",
+            "it's",
+            "''",
+            r#"double "quotes""#,
+            "$HOME ${HOME} $(id) `id`",
+            r"back\slash\\",
+            "semi; colon && pipe | redirect > /dev/null",
+            "glob * ? [a-z]",
+            "new\nline\ttab",
+            "unicode: россия ✨",
+        ] {
+            let output = Command::new("/bin/sh")
+                .arg("-c")
+                .arg(format!("printf '%s' {}", single_quote(s)))
+                .output()
+                .unwrap();
+            assert_eq!(String::from_utf8(output.stdout).unwrap(), s);
+        }
+    }
+
+    #[test]
+    #[cfg(posix)]
+    fn should_run_wrapped_command_through_shell() {
+        use std::process::Command;
+
+        let output = Command::new("/bin/sh")
+            .arg("-c")
+            .arg(wrap_command(
+                DEFAULT_REMOTE_SHELL,
+                r#"FOO='a b'; printf '%s|%s' "$FOO" 'it'\''s'"#,
+            ))
+            .output()
+            .unwrap();
+        assert_eq!(String::from_utf8(output.stdout).unwrap(), "a b|it's");
+    }
+}
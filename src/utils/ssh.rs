@@ -1,4 +1,4 @@
-use ssh2_config::{ParseRule, SshConfig};
+use ssh2_config::{Host, HostClause, HostParams, ParseRule, SshConfig};
 
 pub fn parse_ssh2_config(path: &str) -> Result<SshConfig, String> {
     use std::fs::File;
@@ -12,10 +12,47 @@ pub fn parse_ssh2_config(path: &str) -> Result<SshConfig, String> {
         .map_err(|e| format!("Failed to parse ssh2 config: {e}"))
 }
 
+/// Get the value of the `ProxyJump` directive for `host` in the ssh configuration at `path`.
+///
+/// `ProxyJump` is not supported by ssh2-config, so the configuration is scanned for it;
+/// as in ssh, the first value obtained for the host is used
+pub fn proxy_jump(path: &str, host: &str) -> Result<Option<String>, String> {
+    let config =
+        std::fs::read_to_string(path).map_err(|e| format!("failed to open {path}: {e}"))?;
+    // options preceding the first `Host` block apply to all hosts
+    let mut matches = true;
+    for line in config.lines().map(str::trim) {
+        let mut tokens = line.split_whitespace();
+        let Some(field) = tokens.next() else {
+            continue;
+        };
+        match field.to_ascii_lowercase().as_str() {
+            "host" => {
+                let clauses = tokens
+                    .map(|pattern| match pattern.strip_prefix('!') {
+                        Some(pattern) => HostClause::new(pattern.to_string(), true),
+                        None => HostClause::new(pattern.to_string(), false),
+                    })
+                    .collect();
+                matches = Host::new(clauses, HostParams::default()).intersects(host);
+            }
+            // `Match` blocks are not supported
+            "match" => matches = false,
+            "proxyjump" if matches => {
+                return Ok(tokens.next().map(str::to_string));
+            }
+            _ => {}
+        }
+    }
+    Ok(None)
+}
+
 #[cfg(test)]
 mod test {
 
-    use crate::utils::ssh::parse_ssh2_config;
+    use pretty_assertions::assert_eq;
+
+    use crate::utils::ssh::{parse_ssh2_config, proxy_jump};
     use crate::utils::test_helpers;
 
     #[test]
@@ -43,4 +80,36 @@ Host test
         )
         .is_ok());
     }
+
+    #[test]
+    fn should_get_proxy_jump_for_host() {
+        let ssh_config_file = test_helpers::create_sample_file_with_content(
+            r#"
+# ProxyJump of the bastion
+Host bastion
+        HostName 10.0.0.1
+        ProxyJump none
+
+Host internal-* !internal-db
+        ProxyJump omar@bastion:2222,gateway
+
+Host *
+        ProxyJump default
+"#,
+        );
+        let path = ssh_config_file.path().to_string_lossy().to_string();
+        assert_eq!(
+            proxy_jump(&path, "internal-web").unwrap().as_deref(),
+            Some("omar@bastion:2222,gateway")
+        );
+        assert_eq!(
+            proxy_jump(&path, "internal-db").unwrap().as_deref(),
+            Some("default")
+        );
+        assert_eq!(
+            proxy_jump(&path, "bastion").unwrap().as_deref(),
+            Some("none")
+        );
+        assert!(proxy_jump("/this/file/does/not/exist", "bastion").is_err());
+    }
 }
@@ -2,19 +2,28 @@
 //!
 //! `Utils` implements utilities functions to work with layouts
 
+use std::io::Write;
+
+use tuirealm::ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use tuirealm::ratatui::crossterm::terminal;
 use tuirealm::terminal::{TerminalAdapter, TerminalBridge};
 
-/// Read a secret from tty with customisable prompt
+/// Read a secret from tty with customisable prompt.
+/// If `asterisks`, an asterisk is echoed for each typed character, otherwise the input is hidden
 pub fn read_secret_from_tty<T>(
     terminal_bridge: &mut TerminalBridge<T>,
     prompt: impl ToString,
+    asterisks: bool,
 ) -> std::io::Result<Option<String>>
 where
     T: TerminalAdapter,
 {
-    let _ = terminal_bridge.disable_raw_mode();
-    let _ = terminal_bridge.leave_alternate_screen();
-    let res = match rpassword::prompt_password(prompt) {
+    leave_terminal(terminal_bridge);
+    let password = match asterisks {
+        true => prompt_password_with_asterisks(&prompt.to_string()),
+        false => rpassword::prompt_password(prompt),
+    };
+    let res = match password {
         Ok(p) if p.is_empty() => Ok(None),
         Ok(p) => Ok(Some(p)),
         Err(err) => Err(err),
@@ -25,3 +34,91 @@ where
 
     res
 }
+
+/// Prompt for a password, echoing an asterisk for each typed or pasted character
+fn prompt_password_with_asterisks(prompt: &str) -> std::io::Result<String> {
+    let mut stdout = std::io::stdout();
+    write!(stdout, "{prompt}")?;
+    stdout.flush()?;
+    terminal::enable_raw_mode()?;
+    let res = read_masked_line(&mut stdout);
+    let _ = terminal::disable_raw_mode();
+    writeln!(stdout)?;
+    res
+}
+
+/// Read a line from the terminal in raw mode, writing an asterisk to `stdout` for each character
+fn read_masked_line(stdout: &mut impl Write) -> std::io::Result<String> {
+    let mut secret = String::new();
+    loop {
+        match event::read()? {
+            Event::Key(key) if key.kind != KeyEventKind::Release => match key.code {
+                KeyCode::Enter => return Ok(secret),
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    return Err(std::io::Error::new(
+                        std::io::ErrorKind::Interrupted,
+                        "interrupted",
+                    ));
+                }
+                KeyCode::Char(ch) => {
+                    secret.push(ch);
+                    write!(stdout, "*")?;
+                }
+                KeyCode::Backspace if secret.pop().is_some() => {
+                    write!(stdout, "\x08 \x08")?;
+                }
+                _ => {}
+            },
+            Event::Paste(text) => {
+                let text = text.trim_end_matches(['\n', '\r']);
+                secret.push_str(text);
+                write!(stdout, "{}", "*".repeat(text.chars().count()))?;
+            }
+            _ => {}
+        }
+        stdout.flush()?;
+    }
+}
+
+/// Give the terminal back to the shell, disabling raw mode and leaving the alternate screen
+pub fn leave_terminal<T>(terminal_bridge: &mut TerminalBridge<T>)
+where
+    T: TerminalAdapter,
+{
+    if let Err(err) = terminal_bridge.disable_raw_mode() {
+        error!("Failed to disable raw mode: {err}");
+    }
+    if let Err(err) = terminal_bridge.leave_alternate_screen() {
+        error!("Failed to leave alternate screen: {err}");
+    }
+}
+
+/// Take the terminal back from the shell, entering the alternate screen and raw mode.
+/// The screen is cleared, so that the next draw repaints the entire interface
+pub fn enter_terminal<T>(terminal_bridge: &mut TerminalBridge<T>)
+where
+    T: TerminalAdapter,
+{
+    if let Err(err) = terminal_bridge.enter_alternate_screen() {
+        error!("Failed to enter alternate screen: {err}");
+    }
+    if let Err(err) = terminal_bridge.enable_raw_mode() {
+        error!("Failed to enter raw mode: {err}");
+    }
+    if let Err(err) = terminal_bridge.clear_screen() {
+        error!("Failed to clear screen: {err}");
+    }
+}
+
+/// Enable or disable the focus events (`FocusGained` and `FocusLost`) of the terminal, writing the xterm sequence to stdout.
+/// Terminals which don't support them just ignore the sequence
+pub fn set_focus_reporting(enabled: bool) {
+    let sequence: &[u8] = match enabled {
+        true => b"\x1b[?1004h",
+        false => b"\x1b[?1004l",
+    };
+    let mut stdout = std::io::stdout();
+    if let Err(err) = stdout.write_all(sequence).and_then(|_| stdout.flush()) {
+        error!("Failed to set focus reporting: {err}");
+    }
+}